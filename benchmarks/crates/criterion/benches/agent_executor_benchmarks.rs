@@ -4,10 +4,9 @@ use anyhow::Error;
 use criterion::{BenchmarkId, Criterion, Throughput};
 use merlin_agent::RoutingOrchestrator;
 use merlin_core::{RoutingConfig, Task};
-use merlin_routing::{UiChannel, UiEvent};
+use merlin_routing::UiChannel;
 use std::hint::black_box;
 use tokio::runtime::Runtime;
-use tokio::sync::mpsc;
 
 /// Benchmark agent executor with streaming
 ///
@@ -34,8 +33,7 @@ fn bench_agent_executor_streaming(criterion: &mut Criterion) -> Result<(), Error
             |bencher, &request| {
                 bencher.iter(|| {
                     let task = Task::new(black_box(request).to_string());
-                    let (sender, _receiver) = mpsc::channel::<UiEvent>(100);
-                    let ui_channel = UiChannel::from_sender(sender);
+                    let (ui_channel, _receiver) = UiChannel::bounded(100);
                     runtime.block_on(async {
                         let _result = orchestrator.execute_task_streaming(task, ui_channel).await;
                     });
@@ -69,8 +67,7 @@ fn bench_conversation_history(criterion: &mut Criterion) -> Result<(), Error> {
             |bencher, &size| {
                 bencher.iter(|| {
                     let task = Task::new(black_box("Continue the conversation").to_string());
-                    let (sender, _receiver) = mpsc::channel::<UiEvent>(100);
-                    let ui_channel = UiChannel::from_sender(sender);
+                    let (ui_channel, _receiver) = UiChannel::bounded(100);
                     let history: Vec<(String, String)> = (0..size)
                         .map(|idx| ("user".to_string(), format!("Question {idx}")))
                         .collect();
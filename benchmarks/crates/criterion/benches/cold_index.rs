@@ -0,0 +1,125 @@
+//! Cold embedding index benchmarks on the `rust-project` test workspace.
+//!
+//! Every iteration copies the workspace without its cache and indexes it from
+//! scratch. The embedding provider answers each request after a fixed delay,
+//! one chunk per request, standing in for a local Ollama server. Sequential
+//! indexing (one request in flight) is the baseline the concurrent runs are
+//! compared against.
+
+use anyhow::Error;
+use criterion::{BatchSize, BenchmarkId, Criterion};
+use merlin_context::{EmbeddingProvider, VectorSearchManager};
+use merlin_core::CoreResult;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+use tokio::time::sleep;
+
+/// Time the simulated provider takes to answer one request
+const REQUEST_LATENCY: Duration = Duration::from_millis(5);
+
+/// Embedding provider answering every request after [`REQUEST_LATENCY`]
+#[derive(Clone, Copy)]
+struct LatencyEmbeddingProvider;
+
+impl EmbeddingProvider for LatencyEmbeddingProvider {
+    async fn ensure_model_available(&self) -> CoreResult<()> {
+        Ok(())
+    }
+
+    fn supports_batching(&self) -> bool {
+        false
+    }
+
+    async fn embed(&self, text: &str) -> CoreResult<Vec<f32>> {
+        sleep(REQUEST_LATENCY).await;
+        Ok(vec![text.len() as f32; 384])
+    }
+
+    async fn embed_batch(&self, texts: Vec<String>) -> CoreResult<Vec<Vec<f32>>> {
+        sleep(REQUEST_LATENCY).await;
+        Ok(texts
+            .iter()
+            .map(|text| vec![text.len() as f32; 384])
+            .collect())
+    }
+}
+
+/// Root of the `rust-project` test workspace
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../test-workspaces/rust-project")
+}
+
+/// Copies `source` to `target`, leaving out the `.merlin` cache directory
+///
+/// # Errors
+/// Returns an error if a file cannot be read or written.
+fn copy_workspace(source: &Path, target: &Path) -> Result<(), Error> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_name() == ".merlin" {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            copy_workspace(&path, &target.join(entry.file_name()))?;
+        } else {
+            fs::copy(&path, target.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Benchmark cold indexing with increasing numbers of requests in flight
+///
+/// # Errors
+/// Returns an error if the runtime cannot be created.
+fn bench_cold_index(criterion: &mut Criterion) -> Result<(), Error> {
+    let mut group = criterion.benchmark_group("cold_index");
+    group.sample_size(10);
+    let runtime = Runtime::new()?;
+    let fixture = fixture_root();
+
+    for concurrency in [1, 4] {
+        group.bench_with_input(
+            BenchmarkId::new("concurrency", concurrency),
+            &concurrency,
+            |bencher, &concurrency| {
+                bencher.to_async(&runtime).iter_batched(
+                    || {
+                        let workspace = TempDir::new().ok()?;
+                        copy_workspace(&fixture, workspace.path()).ok()?;
+                        Some(workspace)
+                    },
+                    |workspace| async move {
+                        let Some(workspace) = workspace else {
+                            return;
+                        };
+                        let mut manager = VectorSearchManager::with_provider(
+                            workspace.path(),
+                            LatencyEmbeddingProvider,
+                        )
+                        .with_auto_pull(false)
+                        .with_concurrency(concurrency);
+                        let _result = manager.initialize().await;
+                    },
+                    BatchSize::PerIteration,
+                );
+            },
+        );
+    }
+
+    group.finish();
+    Ok(())
+}
+
+/// Executes the cold index benchmarks
+pub fn main() -> Result<(), Error> {
+    let mut criterion = Criterion::default().configure_from_args();
+    bench_cold_index(&mut criterion)?;
+    criterion.final_summary();
+    Ok(())
+}
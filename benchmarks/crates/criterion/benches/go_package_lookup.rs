@@ -0,0 +1,86 @@
+//! Go backend package lookup benchmarks on generated modules of increasing size.
+//!
+//! Every package imports the next one, so `extract_imports` and cross-package
+//! `find_definition` both resolve package members. Lookup time should stay flat
+//! as the module grows, since packages are indexed by directory.
+
+use anyhow::Error;
+use criterion::{BenchmarkId, Criterion, Throughput};
+use merlin_languages::{GoBackend, LanguageProvider as _};
+use std::fs;
+use std::hint::black_box;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Files generated per package
+const FILES_PER_PACKAGE: usize = 40;
+
+/// Writes a module of `packages` packages to `root` and returns the first file of the first package
+///
+/// # Errors
+/// Returns an error if the files cannot be written.
+fn generate_module(root: &Path, packages: usize) -> Result<PathBuf, Error> {
+    fs::write(root.join("go.mod"), "module example.com/app\n\ngo 1.22\n")?;
+    for package in 0..packages {
+        let dir = root.join(format!("p{package}"));
+        fs::create_dir_all(&dir)?;
+        let next = (package + 1) % packages;
+        for file in 0..FILES_PER_PACKAGE {
+            fs::write(
+                dir.join(format!("f{file}.go")),
+                format!(
+                    "package p{package}\n\nimport \"example.com/app/p{next}\"\n\n\
+                     func F{file}() {{\n\tp{next}.F{file}()\n}}\n"
+                ),
+            )?;
+        }
+        fs::write(
+            dir.join("target.go"),
+            format!("package p{package}\n\ntype Target{package} struct{{}}\n"),
+        )?;
+    }
+    Ok(root.join("p0").join("f0.go"))
+}
+
+/// Benchmark package member lookups as the module grows
+///
+/// # Errors
+/// Returns an error if a module cannot be generated or indexed.
+fn bench_package_lookup(criterion: &mut Criterion) -> Result<(), Error> {
+    let mut group = criterion.benchmark_group("go_package_lookup");
+
+    for packages in [25, 100] {
+        let dir = TempDir::new()?;
+        let file = generate_module(dir.path(), packages)?;
+        let mut backend = GoBackend::new();
+        backend.initialize(dir.path())?;
+        let file_count = backend.file_count();
+
+        group.throughput(Throughput::Elements(1));
+        group.bench_with_input(
+            BenchmarkId::new("extract_imports", file_count),
+            &file,
+            |bencher, file| {
+                bencher.iter(|| backend.extract_imports(black_box(file)));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("find_definition", file_count),
+            &file,
+            |bencher, file| {
+                bencher.iter(|| backend.find_definition(black_box("Target1"), black_box(file), 1));
+            },
+        );
+    }
+
+    group.finish();
+    Ok(())
+}
+
+/// Executes the Go package lookup benchmarks
+pub fn main() -> Result<(), Error> {
+    let mut criterion = Criterion::default().configure_from_args();
+    bench_package_lookup(&mut criterion)?;
+    criterion.final_summary();
+    Ok(())
+}
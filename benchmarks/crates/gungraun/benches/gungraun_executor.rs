@@ -4,12 +4,11 @@ use divan::Divan;
 use hint::black_box;
 use merlin_agent::RoutingOrchestrator;
 use merlin_core::{RoutingConfig, Task};
-use merlin_routing::{UiChannel, UiEvent};
+use merlin_routing::UiChannel;
 use mimalloc::MiMalloc;
 use runtime::Runtime;
 use std::hint;
 use tokio::runtime;
-use tokio::sync::mpsc;
 
 #[global_allocator]
 static GLOBAL: MiMalloc = MiMalloc;
@@ -32,8 +31,7 @@ fn simple_task_execution() {
 
     tokio_runtime.block_on(async {
         let task = Task::new(black_box("What is in main.rs?").to_string());
-        let (sender, _receiver) = mpsc::channel::<UiEvent>(100);
-        let ui_channel = UiChannel::from_sender(sender);
+        let (ui_channel, _receiver) = UiChannel::bounded(100);
         let _result = orchestrator.execute_task_streaming(task, ui_channel).await;
     });
 }
@@ -52,8 +50,7 @@ fn task_with_history(history_size: usize) {
 
     tokio_runtime.block_on(async {
         let task = Task::new(black_box("Continue conversation").to_string());
-        let (sender, _receiver) = mpsc::channel::<UiEvent>(100);
-        let ui_channel = UiChannel::from_sender(sender);
+        let (ui_channel, _receiver) = UiChannel::bounded(100);
         let history: Vec<(String, String)> = (0..history_size)
             .map(|idx| ("user".to_string(), format!("Message {idx}")))
             .collect();
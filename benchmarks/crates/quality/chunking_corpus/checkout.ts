@@ -0,0 +1,113 @@
+/** Checkout flow for the storefront. */
+
+import { Cart, LineItem } from './cart';
+import { PaymentGateway } from './payments';
+
+/**
+ * Applies a percentage coupon to the cart subtotal, honouring the coupon's minimum spend.
+ */
+export function applyCoupon(cart: Cart, code: string, percent: number, minimumSpend: number): number {
+  const subtotal = cart.items.reduce((sum, item) => sum + item.price * item.quantity, 0);
+
+  if (subtotal < minimumSpend) {
+    throw new Error(`coupon ${code} requires a minimum spend of ${minimumSpend}`);
+  }
+
+  const discount = Math.round(subtotal * percent) / 100;
+  cart.discounts.push({ code, amount: discount });
+
+  return subtotal - discount;
+}
+
+/**
+ * Calculates sales tax per shipping region, exempting tax-free product categories.
+ */
+export function calculateSalesTax(items: LineItem[], region: string): number {
+  const rates: Record<string, number> = { north: 0.07, south: 0.05, west: 0.0825 };
+  const rate = rates[region] ?? 0.06;
+
+  let taxable = 0;
+  for (const item of items) {
+    if (item.category === 'groceries' || item.category === 'medicine') {
+      continue;
+    }
+
+    taxable += item.price * item.quantity;
+  }
+
+  return Math.round(taxable * rate * 100) / 100;
+}
+
+/**
+ * Charges the customer's card through the payment gateway and retries declined captures.
+ */
+export async function capturePayment(gateway: PaymentGateway, orderId: string, amount: number): Promise<string> {
+  let attempts = 0;
+
+  while (attempts < 3) {
+    attempts += 1;
+    const result = await gateway.capture(orderId, amount);
+
+    if (result.status === 'approved') {
+      return result.transactionId;
+    }
+
+    if (result.status === 'fraud') {
+      throw new Error(`payment for ${orderId} flagged as fraud`);
+    }
+  }
+
+  throw new Error(`payment for ${orderId} declined after ${attempts} attempts`);
+}
+
+/**
+ * Estimates the delivery date from the warehouse dispatch cutoff and carrier transit days.
+ */
+export function estimateDelivery(orderedAt: Date, transitDays: number, cutoffHour: number): Date {
+  const dispatch = new Date(orderedAt);
+
+  if (dispatch.getHours() >= cutoffHour) {
+    dispatch.setDate(dispatch.getDate() + 1);
+  }
+
+  const delivery = new Date(dispatch);
+  let remaining = transitDays;
+  while (remaining > 0) {
+    delivery.setDate(delivery.getDate() + 1);
+    const weekday = delivery.getDay();
+
+    if (weekday !== 0 && weekday !== 6) {
+      remaining -= 1;
+    }
+  }
+
+  return delivery;
+}
+
+/**
+ * Splits an order into separate parcels so that no parcel exceeds the carrier weight limit.
+ */
+export function splitIntoParcels(items: LineItem[], weightLimit: number): LineItem[][] {
+  const parcels: LineItem[][] = [];
+  let current: LineItem[] = [];
+  let weight = 0;
+
+  for (const item of items) {
+    const itemWeight = item.weight * item.quantity;
+
+    if (weight + itemWeight > weightLimit && current.length > 0) {
+      parcels.push(current);
+      current = [];
+      weight = 0;
+    }
+
+    current.push(item);
+    weight += itemWeight;
+  }
+
+  if (current.length > 0) {
+    parcels.push(current);
+  }
+
+  return parcels;
+}
@@ -0,0 +1,99 @@
+"""Warehouse inventory bookkeeping."""
+
+from dataclasses import dataclass, field
+from datetime import datetime, timedelta
+
+
+@dataclass
+class StockItem:
+    sku: str
+    quantity: int
+    reorder_level: int
+    location: str
+    history: list = field(default_factory=list)
+
+
+def reserve_stock(items, sku, amount):
+    """Reserve units of a SKU for a pending order, failing when stock is short."""
+    item = items.get(sku)
+    if item is None:
+        raise KeyError(f"unknown sku {sku}")
+
+    if item.quantity < amount:
+        raise ValueError(f"only {item.quantity} units of {sku} available")
+
+    item.quantity -= amount
+    item.history.append(("reserve", amount, datetime.now()))
+
+    if item.quantity <= item.reorder_level:
+        schedule_reorder(item)
+
+    return item.quantity
+
+
+def schedule_reorder(item):
+    """Queue a supplier purchase order when an item falls below its reorder level."""
+    shortfall = item.reorder_level * 2 - item.quantity
+    if shortfall <= 0:
+        return None
+
+    order = {
+        "sku": item.sku,
+        "units": shortfall,
+        "requested_at": datetime.now(),
+        "deliver_to": item.location,
+    }
+
+    item.history.append(("reorder", shortfall, order["requested_at"]))
+    return order
+
+
+def expire_perishables(items, shelf_life_days):
+    """Write off perishable batches whose shelf life has run out."""
+    cutoff = datetime.now() - timedelta(days=shelf_life_days)
+    expired = []
+
+    for item in items.values():
+        received = [entry for entry in item.history if entry[0] == "receive"]
+        stale = [entry for entry in received if entry[2] < cutoff]
+
+        for _, units, _ in stale:
+            item.quantity = max(0, item.quantity - units)
+            expired.append((item.sku, units))
+
+    return expired
+
+
+def transfer_between_warehouses(items, sku, amount, destination):
+    """Move units of a SKU to another warehouse location and record the transfer."""
+    item = items[sku]
+    if amount > item.quantity:
+        raise ValueError("cannot transfer more units than are on hand")
+
+    item.quantity -= amount
+    item.history.append(("transfer_out", amount, datetime.now()))
+
+    moved = StockItem(
+        sku=sku,
+        quantity=amount,
+        reorder_level=item.reorder_level,
+        location=destination,
+    )
+    moved.history.append(("transfer_in", amount, datetime.now()))
+
+    return moved
+
+
+def audit_discrepancies(items, counted):
+    """Compare a physical stock count against the ledger and report mismatches."""
+    report = []
+
+    for sku, physical in counted.items():
+        item = items.get(sku)
+        expected = item.quantity if item else 0
+
+        if physical != expected:
+            report.append({"sku": sku, "ledger": expected, "counted": physical})
+
+    report.sort(key=lambda row: abs(row["ledger"] - row["counted"]), reverse=True)
+    return report
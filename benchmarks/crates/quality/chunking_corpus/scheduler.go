@@ -0,0 +1,111 @@
+// Package scheduler runs background jobs for the platform.
+package scheduler
+
+import (
+	"errors"
+	"sort"
+	"time"
+)
+
+// Job is a unit of background work.
+type Job struct {
+	ID       string
+	Priority int
+	RunAt    time.Time
+	Attempts int
+	Timeout  time.Duration
+}
+
+// EnqueueJob adds a job to the pending queue ordered by priority and run time.
+func EnqueueJob(queue []Job, job Job) []Job {
+	queue = append(queue, job)
+
+	sort.SliceStable(queue, func(left, right int) bool {
+		if queue[left].Priority != queue[right].Priority {
+			return queue[left].Priority > queue[right].Priority
+		}
+
+		return queue[left].RunAt.Before(queue[right].RunAt)
+	})
+
+	return queue
+}
+
+// RetryWithBackoff reschedules a failed job with exponential backoff until attempts run out.
+func RetryWithBackoff(job Job, maxAttempts int, base time.Duration) (Job, error) {
+	if job.Attempts >= maxAttempts {
+		return job, errors.New("job exhausted its retry attempts")
+	}
+
+	delay := base
+	for attempt := 0; attempt < job.Attempts; attempt++ {
+		delay *= 2
+	}
+
+	job.Attempts++
+	job.RunAt = time.Now().Add(delay)
+
+	return job, nil
+}
+
+// ReapTimedOutJobs cancels running jobs that exceeded their timeout and returns their IDs.
+func ReapTimedOutJobs(running map[string]time.Time, jobs map[string]Job, now time.Time) []string {
+	var reaped []string
+
+	for id, started := range running {
+		job, ok := jobs[id]
+		if !ok {
+			continue
+		}
+
+		if now.Sub(started) > job.Timeout {
+			delete(running, id)
+			reaped = append(reaped, id)
+		}
+	}
+
+	sort.Strings(reaped)
+	return reaped
+}
+
+// DistributeToWorkers assigns queued jobs to workers round robin, skipping workers at capacity.
+func DistributeToWorkers(queue []Job, workers []string, capacity map[string]int) map[string][]Job {
+	assignments := make(map[string][]Job)
+	if len(workers) == 0 {
+		return assignments
+	}
+
+	next := 0
+	for _, job := range queue {
+		for tries := 0; tries < len(workers); tries++ {
+			worker := workers[next%len(workers)]
+			next++
+
+			if len(assignments[worker]) < capacity[worker] {
+				assignments[worker] = append(assignments[worker], job)
+				break
+			}
+		}
+	}
+
+	return assignments
+}
+
+// ParseCronInterval converts a simple cron interval like "every 5m" into a duration.
+func ParseCronInterval(spec string) (time.Duration, error) {
+	const prefix = "every "
+	if len(spec) <= len(prefix) || spec[:len(prefix)] != prefix {
+		return 0, errors.New("interval must start with 'every '")
+	}
+
+	interval, err := time.ParseDuration(spec[len(prefix):])
+	if err != nil {
+		return 0, err
+	}
+
+	if interval < time.Minute {
+		return 0, errors.New("interval must be at least one minute")
+	}
+
+	return interval, nil
+}
@@ -0,0 +1,403 @@
+//! End-to-end agent benchmarks.
+//!
+//! Retrieval benchmarks only show whether the right files are found. These
+//! run the whole agent loop on small fixture repositories and score whether
+//! the task got done: validation, expected file contents, tool calls and
+//! tokens. Each task runs in a fresh temporary workspace, against the
+//! responses scripted in its file or against a local model.
+
+pub mod provider;
+pub mod report;
+pub mod task;
+pub mod tool_calls;
+
+use std::collections::HashSet;
+use std::fs::{create_dir_all, write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context as _, Result, bail};
+use merlin_agent::RoutingOrchestrator;
+use merlin_core::{ModelProvider, RoutingError, Task, TaskResult, UiChannel};
+use merlin_local::LocalModelProvider;
+use merlin_routing::{Model, ModelRegistry, ProviderRegistry, RoutingConfig, StrategyRouter};
+use provider::{ProviderUsage, RecordingProvider, ScriptedProvider};
+use report::{AgentMetrics, AgentResult};
+use task::{AgentTask, TaskSetup};
+use tempfile::TempDir;
+use tokio::process::Command;
+use tokio::spawn;
+use tokio::time::timeout;
+use tool_calls::{ensure_counting, tool_calls};
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+/// Default time a task may take before it counts as failed
+pub const DEFAULT_TASK_TIMEOUT: Duration = Duration::from_mins(5);
+
+/// Capacity of the UI channel whose events are discarded
+const UI_CHANNEL_CAPACITY: usize = 100;
+
+/// Options controlling how agent benchmarks are run
+#[derive(Debug, Clone)]
+pub struct AgentRunOptions {
+    /// Local model answering every task instead of its scripted responses
+    pub model: Option<String>,
+    /// Time a task may take before it counts as failed
+    pub timeout: Duration,
+}
+
+impl Default for AgentRunOptions {
+    fn default() -> Self {
+        Self {
+            model: None,
+            timeout: DEFAULT_TASK_TIMEOUT,
+        }
+    }
+}
+
+/// Loads every task in a directory, ordered by name
+///
+/// # Errors
+/// Returns an error if a task file is invalid or two tasks share a name
+pub fn load_tasks(tasks_dir: &Path) -> Result<Vec<AgentTask>> {
+    let mut tasks = Vec::new();
+    for entry in WalkDir::new(tasks_dir).into_iter().filter_map(Result::ok) {
+        if entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext == "toml")
+        {
+            tasks.push(AgentTask::from_file(entry.path())?);
+        }
+    }
+    tasks.sort_by(|left, right| left.name.cmp(&right.name));
+
+    let mut names = HashSet::new();
+    for task in &tasks {
+        if !names.insert(task.name.as_str()) {
+            bail!("Duplicate agent task name '{}'", task.name);
+        }
+    }
+    Ok(tasks)
+}
+
+/// Runs every task in a directory, one at a time
+///
+/// Tasks without scripted responses are skipped unless a model is given.
+///
+/// # Errors
+/// Returns an error if the tasks cannot be loaded or a workspace cannot be set up
+pub async fn run_agent_benchmarks(
+    tasks_dir: &Path,
+    options: &AgentRunOptions,
+) -> Result<Vec<AgentResult>> {
+    ensure_counting();
+    let mut results = Vec::new();
+    for task in load_tasks(tasks_dir)? {
+        if options.model.is_none() && task.responses.is_empty() {
+            warn!(
+                "Skipping agent task {}: no scripted responses and no model",
+                task.name
+            );
+            continue;
+        }
+        info!("Running agent task {}", task.name);
+        let result = run_task(&task, options)
+            .await
+            .with_context(|| format!("Failed to run agent task {}", task.name))?;
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// What happened while the agent worked on a task
+struct Execution {
+    /// Result of the agent, `None` if it timed out
+    outcome: Option<Result<TaskResult, RoutingError>>,
+    /// Wall time in milliseconds
+    duration_ms: f64,
+    /// Number of tool calls
+    tool_calls: usize,
+    /// Model requests and tokens
+    usage: ProviderUsage,
+}
+
+/// Runs one task in a fresh workspace and scores the outcome
+///
+/// Agent errors, timeouts and failed checks are recorded in the result.
+///
+/// # Errors
+/// Returns an error if the workspace or the orchestrator cannot be set up
+pub async fn run_task(task: &AgentTask, options: &AgentRunOptions) -> Result<AgentResult> {
+    let workspace = TempDir::new().context("Failed to create workspace")?;
+    write_setup(workspace.path(), &task.setup)?;
+
+    let model: Arc<dyn ModelProvider> = match &options.model {
+        Some(model) => Arc::new(LocalModelProvider::new(model.clone())),
+        None => Arc::new(ScriptedProvider::new(
+            task.responses
+                .iter()
+                .map(|response| response.typescript.clone()),
+        )),
+    };
+    let recorder = Arc::new(RecordingProvider::new(model));
+    let orchestrator = create_orchestrator(
+        workspace.path(),
+        Arc::clone(&recorder) as Arc<dyn ModelProvider>,
+    )?;
+
+    let (ui_channel, mut receiver) = UiChannel::bounded(UI_CHANNEL_CAPACITY);
+    let drain = spawn(async move { while receiver.recv().await.is_some() {} });
+    let calls_before = tool_calls();
+    let start = Instant::now();
+    let outcome = timeout(
+        options.timeout,
+        orchestrator.execute_task_streaming(Task::new(task.prompt.clone()), ui_channel),
+    )
+    .await
+    .ok();
+    let execution = Execution {
+        outcome,
+        duration_ms: start.elapsed().as_secs_f64() * 1000.0,
+        tool_calls: tool_calls().saturating_sub(calls_before),
+        usage: recorder.usage(),
+    };
+    drain.abort();
+
+    Ok(score(task, workspace.path(), execution, options.timeout).await)
+}
+
+/// Scores a finished task against its expectations
+async fn score(
+    task: &AgentTask,
+    workspace: &Path,
+    execution: Execution,
+    time_limit: Duration,
+) -> AgentResult {
+    let mut failures = Vec::new();
+    let agent_passed = match execution.outcome {
+        Some(Ok(result)) => agent_validation(&result, &mut failures),
+        Some(Err(err)) => {
+            failures.push(format!("Agent failed: {err}"));
+            false
+        }
+        None => {
+            failures.push(format!("Timed out after {} s", time_limit.as_secs_f64()));
+            false
+        }
+    };
+    let command_passed = if let Some(command) = &task.expect.validate
+        && let Err(reason) = run_validate_command(workspace, command).await
+    {
+        failures.push(reason);
+        false
+    } else {
+        true
+    };
+
+    let mut files_matched = 0;
+    for expectation in &task.expect.files {
+        match expectation.check(workspace) {
+            Ok(()) => files_matched += 1,
+            Err(reason) => failures.push(reason),
+        }
+    }
+
+    let tokens = execution.usage.tokens.total();
+    if let Some(max) = task.expect.max_tool_calls
+        && execution.tool_calls > max
+    {
+        failures.push(format!(
+            "{} tool calls exceed the budget of {max}",
+            execution.tool_calls
+        ));
+    }
+    if let Some(max) = task.expect.max_tokens
+        && tokens > max
+    {
+        failures.push(format!("{tokens} tokens exceed the budget of {max}"));
+    }
+
+    AgentResult {
+        name: task.name.clone(),
+        metrics: AgentMetrics {
+            completed: failures.is_empty(),
+            validation_passed: agent_passed && command_passed,
+            files_matched,
+            files_expected: task.expect.files.len(),
+            tool_calls: execution.tool_calls,
+            model_requests: execution.usage.requests,
+            tokens,
+            duration_ms: execution.duration_ms,
+        },
+        failures,
+    }
+}
+
+/// Writes the fixture repository of a task
+///
+/// # Errors
+/// Returns an error if a file cannot be written
+fn write_setup(workspace: &Path, setup: &TaskSetup) -> Result<()> {
+    for (relative, content) in &setup.files {
+        let path = workspace.join(relative);
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        write(&path, content).with_context(|| format!("Failed to write {relative}"))?;
+    }
+    Ok(())
+}
+
+/// Creates an orchestrator routing every request to `provider`
+///
+/// # Errors
+/// Returns an error if the registries cannot be created
+fn create_orchestrator(
+    workspace: &Path,
+    provider: Arc<dyn ModelProvider>,
+) -> Result<RoutingOrchestrator> {
+    let mut config = RoutingConfig::default();
+    config.tiers.local_enabled = false;
+    config.tiers.groq_enabled = false;
+    config.tiers.premium_enabled = false;
+
+    // Every difficulty routes to one model slot served by the benchmark's provider
+    let mut registry = ProviderRegistry::new(config.clone())?;
+    registry.register_provider(Model::Qwen25Coder32B, provider);
+    let mut model_registry = ModelRegistry::new();
+    for difficulty in 1..=10 {
+        model_registry.register(difficulty, Model::Qwen25Coder32B)?;
+    }
+    let router = Arc::new(StrategyRouter::with_model_registry(
+        model_registry,
+        registry.clone(),
+    ));
+
+    Ok(
+        RoutingOrchestrator::new_with_router(config, router, registry)?
+            .with_workspace(workspace.to_path_buf())
+            .with_embeddings(false)
+            // Title requests would consume scripted responses
+            .with_auto_titles(false),
+    )
+}
+
+/// Returns whether the agent's own validation passed, recording its errors
+fn agent_validation(result: &TaskResult, failures: &mut Vec<String>) -> bool {
+    for error in &result.validation.errors {
+        failures.push(format!("Validation ({:?}): {}", error.stage, error.message));
+    }
+    result.validation.passed
+}
+
+/// Runs a task's validate command in the workspace
+///
+/// # Errors
+/// Returns the reason if the command cannot be started or exits unsuccessfully
+async fn run_validate_command(workspace: &Path, command: &str) -> Result<(), String> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(workspace)
+        .output()
+        .await
+        .map_err(|err| format!("Validate command `{command}` could not run: {err}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let last_line = stderr.lines().rev().find(|line| !line.trim().is_empty());
+    Err(format!(
+        "Validate command `{command}` failed ({}){}",
+        output.status,
+        last_line
+            .map(|line| format!(": {line}"))
+            .unwrap_or_default()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use toml::from_str;
+
+    /// Tests running the bundled tasks end to end with their scripted responses.
+    ///
+    /// # Errors
+    /// Returns an error if the tasks cannot be run.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_bundled_tasks_complete() -> Result<()> {
+        let tasks_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("agent_tasks");
+        let results = run_agent_benchmarks(&tasks_dir, &AgentRunOptions::default()).await?;
+        assert!(!results.is_empty());
+        for result in &results {
+            assert!(
+                result.metrics.completed,
+                "{}: {:?}",
+                result.name, result.failures
+            );
+            assert!(
+                result.metrics.tool_calls > 0,
+                "{}: no tool calls",
+                result.name
+            );
+            assert!(result.metrics.tokens > 0, "{}: no tokens", result.name);
+        }
+        Ok(())
+    }
+
+    /// Tests that wrong output, failed validation and exceeded budgets are scored.
+    ///
+    /// # Errors
+    /// Returns an error if the task cannot be run.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_failed_task_scored() -> Result<()> {
+        ensure_counting();
+        let task: AgentTask = from_str(
+            r#"
+name = "wrong_edit"
+prompt = "Rename one to two"
+
+[setup.files]
+"lib.txt" = "one\n"
+
+[[responses]]
+typescript = """
+async function agent_code(): Promise<string> {
+  await writeFile('lib.txt', 'three\\n');
+  await readFile('lib.txt');
+  return 'Renamed';
+}
+"""
+
+[expect]
+validate = "grep -q two lib.txt"
+max_tool_calls = 1
+max_tokens = 1
+
+[[expect.files]]
+path = "lib.txt"
+matches = ["two"]
+"#,
+        )?;
+        let result = run_task(&task, &AgentRunOptions::default()).await?;
+        let metrics = &result.metrics;
+        assert!(!metrics.completed);
+        assert!(!metrics.validation_passed);
+        assert_eq!((metrics.files_matched, metrics.files_expected), (0, 1));
+        assert_eq!(metrics.model_requests, 1);
+        assert_eq!(result.failures.len(), 4, "{:?}", result.failures);
+        assert!(result.failures[0].starts_with("Validate command `grep -q two lib.txt` failed"));
+        assert_eq!(result.failures[1], "lib.txt does not match `two`");
+        assert!(result.failures[2].contains("tool calls exceed the budget of 1"));
+        assert!(result.failures[3].ends_with("tokens exceed the budget of 1"));
+        Ok(())
+    }
+}
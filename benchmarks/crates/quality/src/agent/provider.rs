@@ -0,0 +1,178 @@
+//! Model providers for agent benchmarks.
+//!
+//! [`ScriptedProvider`] replays the responses of a task file so the agent
+//! loop can be measured without a model. [`RecordingProvider`] wraps either
+//! it or a real model to count requests and tokens over a whole task, which
+//! includes every step of decomposed tasks.
+
+use async_trait::async_trait;
+use merlin_context::context_inclusion::ContextManager;
+use merlin_core::{
+    Context, IgnoreLock as _, ModelProvider, Query, Response, Result, RoutingError, TokenUsage,
+};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Provider answering each request with the next scripted response
+pub struct ScriptedProvider {
+    /// Responses not yet returned, as `TypeScript`
+    responses: Mutex<VecDeque<String>>,
+}
+
+impl ScriptedProvider {
+    /// Creates a provider returning `responses` in order
+    pub fn new(responses: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter().collect()),
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for ScriptedProvider {
+    fn name(&self) -> &'static str {
+        "scripted"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+        let typescript = self
+            .responses
+            .lock_ignore_poison()
+            .pop_front()
+            .ok_or_else(|| {
+                RoutingError::ExecutionFailed(format!(
+                    "No scripted response left for request: {}",
+                    query.text
+                ))
+            })?;
+
+        // Scripted requests are billed like a model would see them
+        let prompt_tokens = ContextManager::estimate_tokens(&context.system_prompt)
+            + ContextManager::estimate_tokens(&query.text)
+            + context
+                .files
+                .iter()
+                .map(|file| ContextManager::estimate_tokens(&file.content))
+                .sum::<usize>();
+        let text = format!("```typescript\n{typescript}\n```");
+        Ok(Response {
+            tokens_used: TokenUsage {
+                input: prompt_tokens as u64,
+                output: ContextManager::estimate_tokens(&text) as u64,
+                cache_read: 0,
+                cache_write: 0,
+            },
+            text,
+            confidence: 1.0,
+            provider: self.name().to_owned(),
+            latency_ms: 0,
+        })
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+/// Requests and tokens recorded by a [`RecordingProvider`]
+#[derive(Debug, Clone, Default)]
+pub struct ProviderUsage {
+    /// Number of model requests
+    pub requests: usize,
+    /// Tokens of every request
+    pub tokens: TokenUsage,
+}
+
+/// Provider recording the requests and tokens of the provider it wraps
+pub struct RecordingProvider {
+    /// Wrapped provider
+    inner: Arc<dyn ModelProvider>,
+    /// Usage so far
+    usage: Mutex<ProviderUsage>,
+}
+
+impl RecordingProvider {
+    /// Wraps `inner`
+    pub fn new(inner: Arc<dyn ModelProvider>) -> Self {
+        Self {
+            inner,
+            usage: Mutex::default(),
+        }
+    }
+
+    /// Returns the usage recorded so far
+    pub fn usage(&self) -> ProviderUsage {
+        self.usage.lock_ignore_poison().clone()
+    }
+}
+
+#[async_trait]
+impl ModelProvider for RecordingProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+        let response = self.inner.generate(query, context).await;
+        let mut usage = self.usage.lock_ignore_poison();
+        usage.requests += 1;
+        if let Ok(response) = &response {
+            usage.tokens.input += response.tokens_used.input;
+            usage.tokens.output += response.tokens_used.output;
+            usage.tokens.cache_read += response.tokens_used.cache_read;
+            usage.tokens.cache_write += response.tokens_used.cache_write;
+        }
+        drop(usage);
+        response
+    }
+
+    fn estimate_cost(&self, context: &Context) -> f64 {
+        self.inner.estimate_cost(context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that scripted responses are returned in order and recorded.
+    ///
+    /// # Errors
+    /// Returns an error if a scripted response is missing.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_scripted_responses_recorded() -> Result<()> {
+        let scripted = Arc::new(ScriptedProvider::new([
+            "return 'first';".to_owned(),
+            "return 'second';".to_owned(),
+        ]));
+        let recorder = RecordingProvider::new(scripted);
+        let context = Context::new("You are a coding agent");
+
+        let first = recorder.generate(&Query::new("task"), &context).await?;
+        assert_eq!(first.text, "```typescript\nreturn 'first';\n```");
+        let second = recorder.generate(&Query::new("task"), &context).await?;
+        assert!(second.text.contains("second"));
+        let exhausted = recorder.generate(&Query::new("task"), &context).await;
+        assert!(exhausted.is_err(), "responses should run out");
+
+        let usage = recorder.usage();
+        assert_eq!(usage.requests, 3);
+        assert_eq!(
+            usage.tokens.total(),
+            first.tokens_used.total() + second.tokens_used.total()
+        );
+        assert!(usage.tokens.input > 0);
+        Ok(())
+    }
+}
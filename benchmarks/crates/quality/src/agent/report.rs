@@ -0,0 +1,314 @@
+//! Scores and reports of agent benchmarks.
+//!
+//! Reports are saved and compared with the baseline machinery of the quality
+//! benchmarks: rates regress when they drop by more than the tolerance, tool
+//! calls and tokens when they rise by more than that fraction.
+
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::baseline::{ComparableReport, MetricSpec, TestMetrics};
+
+/// Metrics compared between runs, in report order
+const AGENT_METRICS: [MetricSpec; 5] = [
+    MetricSpec::percentage("Completion Rate"),
+    MetricSpec::percentage("Validation Pass Rate"),
+    MetricSpec::percentage("File Match Rate"),
+    MetricSpec::count("Tool Calls").lower_is_better(),
+    MetricSpec::count("Tokens").lower_is_better(),
+];
+
+/// Outcome of one task
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentMetrics {
+    /// Whether the agent finished, validation passed, every file matched and
+    /// the task stayed within its budgets
+    pub completed: bool,
+    /// Whether the agent's validation and the task's validate command passed
+    pub validation_passed: bool,
+    /// Number of file expectations met
+    pub files_matched: usize,
+    /// Number of file expectations
+    pub files_expected: usize,
+    /// Number of tool calls made by the agent
+    pub tool_calls: usize,
+    /// Number of model requests
+    pub model_requests: usize,
+    /// Tokens of every model request
+    pub tokens: u64,
+    /// Wall time of the task in milliseconds
+    pub duration_ms: f64,
+}
+
+impl AgentMetrics {
+    /// Percentage of file expectations met, 100 if there are none
+    pub fn file_match_rate(&self) -> f64 {
+        if self.files_expected == 0 {
+            100.0
+        } else {
+            self.files_matched as f64 / self.files_expected as f64 * 100.0
+        }
+    }
+
+    /// Returns the compared metrics in report order
+    fn values(&self) -> Vec<f64> {
+        vec![
+            percent(self.completed),
+            percent(self.validation_passed),
+            self.file_match_rate(),
+            self.tool_calls as f64,
+            self.tokens as f64,
+        ]
+    }
+}
+
+/// Returns 100 for true and 0 for false
+fn percent(flag: bool) -> f64 {
+    if flag { 100.0 } else { 0.0 }
+}
+
+/// Result of running one task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentResult {
+    /// Task name
+    pub name: String,
+    /// Calculated metrics
+    pub metrics: AgentMetrics,
+    /// Why the task did not complete, empty if it did
+    pub failures: Vec<String>,
+}
+
+/// Metrics averaged over every task of a run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AgentAggregate {
+    /// Percentage of completed tasks
+    pub completion_rate: f64,
+    /// Percentage of tasks whose validation passed
+    pub validation_pass_rate: f64,
+    /// Average percentage of file expectations met
+    pub file_match_rate: f64,
+    /// Average number of tool calls
+    pub avg_tool_calls: f64,
+    /// Average number of tokens
+    pub avg_tokens: f64,
+    /// Number of tasks
+    pub test_count: usize,
+}
+
+impl AgentAggregate {
+    /// Averages the metrics of the given tasks
+    pub fn from_results(results: &[AgentResult]) -> Self {
+        if results.is_empty() {
+            return Self::default();
+        }
+        let mut sums = [0.0; AGENT_METRICS.len()];
+        for result in results {
+            for (sum, value) in sums.iter_mut().zip(result.metrics.values()) {
+                *sum += value;
+            }
+        }
+        let count = results.len() as f64;
+        let [completion, validation, files, tool_calls, tokens] = sums.map(|sum| sum / count);
+        Self {
+            completion_rate: completion,
+            validation_pass_rate: validation,
+            file_match_rate: files,
+            avg_tool_calls: tool_calls,
+            avg_tokens: tokens,
+            test_count: results.len(),
+        }
+    }
+}
+
+/// Results of one agent benchmark run in machine-readable form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentReport {
+    /// Metrics averaged over every task of the run
+    pub aggregate: AgentAggregate,
+    /// Results of each task, ordered by name
+    pub tests: Vec<AgentResult>,
+}
+
+impl AgentReport {
+    /// Builds the report of a run
+    pub fn from_results(results: &[AgentResult]) -> Self {
+        let mut tests = results.to_vec();
+        tests.sort_by(|left, right| left.name.cmp(&right.name));
+        Self {
+            aggregate: AgentAggregate::from_results(&tests),
+            tests,
+        }
+    }
+
+    /// Renders the report as markdown
+    pub fn to_markdown(&self) -> String {
+        let aggregate = &self.aggregate;
+        let mut report = String::from("# Agent Benchmark Results\n\n## Summary\n\n");
+        _ = writeln!(report, "**Tasks**: {}\n", aggregate.test_count);
+        report.push_str("| Metric | Value |\n|--------|-------|\n");
+        _ = writeln!(
+            report,
+            "| Completion Rate | {:.1}% |",
+            aggregate.completion_rate
+        );
+        _ = writeln!(
+            report,
+            "| Validation Pass Rate | {:.1}% |",
+            aggregate.validation_pass_rate
+        );
+        _ = writeln!(
+            report,
+            "| File Match Rate | {:.1}% |",
+            aggregate.file_match_rate
+        );
+        _ = writeln!(
+            report,
+            "| Avg Tool Calls | {:.1} |",
+            aggregate.avg_tool_calls
+        );
+        _ = writeln!(report, "| Avg Tokens | {:.0} |", aggregate.avg_tokens);
+
+        report.push_str("\n## Per-Task Results\n\n");
+        report.push_str(
+            "| Task | Completed | Validation | Files | Tool Calls | Requests | Tokens | Time (ms) |\n",
+        );
+        report.push_str(
+            "|------|-----------|------------|-------|------------|----------|--------|-----------|\n",
+        );
+        for test in &self.tests {
+            let metrics = &test.metrics;
+            _ = writeln!(
+                report,
+                "| {} | {} | {} | {}/{} | {} | {} | {} | {:.0} |",
+                test.name,
+                if metrics.completed { "✓" } else { "✗" },
+                if metrics.validation_passed {
+                    "✓"
+                } else {
+                    "✗"
+                },
+                metrics.files_matched,
+                metrics.files_expected,
+                metrics.tool_calls,
+                metrics.model_requests,
+                metrics.tokens,
+                metrics.duration_ms,
+            );
+        }
+
+        let failed: Vec<_> = self
+            .tests
+            .iter()
+            .filter(|test| !test.failures.is_empty())
+            .collect();
+        if !failed.is_empty() {
+            report.push_str("\n## Failures\n");
+            for test in failed {
+                _ = writeln!(report, "\n### {}\n", test.name);
+                for failure in &test.failures {
+                    _ = writeln!(report, "- {failure}");
+                }
+            }
+        }
+        report
+    }
+}
+
+impl ComparableReport for AgentReport {
+    fn metric_specs() -> &'static [MetricSpec] {
+        &AGENT_METRICS
+    }
+
+    fn test_metrics(&self) -> TestMetrics<'_> {
+        self.tests
+            .iter()
+            .map(|test| (test.name.as_str(), test.metrics.values()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::baseline::{DEFAULT_TOLERANCE, compare};
+
+    /// Builds a result with the given outcome
+    fn result(name: &str, completed: bool, tool_calls: usize, tokens: u64) -> AgentResult {
+        AgentResult {
+            name: name.to_owned(),
+            metrics: AgentMetrics {
+                completed,
+                validation_passed: completed,
+                files_matched: usize::from(completed),
+                files_expected: 1,
+                tool_calls,
+                model_requests: 1,
+                tokens,
+                duration_ms: 5.0,
+            },
+            failures: if completed {
+                Vec::new()
+            } else {
+                vec!["lib.rs does not match `fn two`".to_owned()]
+            },
+        }
+    }
+
+    /// Tests aggregation and the markdown report.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_aggregate_and_markdown() {
+        let report = AgentReport::from_results(&[
+            result("fix_bug", false, 4, 300),
+            result("add_function", true, 2, 100),
+        ]);
+        let aggregate = &report.aggregate;
+        assert_eq!(aggregate.test_count, 2);
+        assert!((aggregate.completion_rate - 50.0).abs() < 1e-9);
+        assert!((aggregate.file_match_rate - 50.0).abs() < 1e-9);
+        assert!((aggregate.avg_tool_calls - 3.0).abs() < 1e-9);
+        assert!((aggregate.avg_tokens - 200.0).abs() < 1e-9);
+        assert_eq!(report.tests[0].name, "add_function");
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("| Completion Rate | 50.0% |"));
+        assert!(markdown.contains("| add_function | ✓ | ✓ | 1/1 | 2 | 1 | 100 | 5 |"));
+        assert!(markdown.contains("### fix_bug\n\n- lib.rs does not match `fn two`"));
+    }
+
+    /// Tests that fewer completions and more tool calls are regressions.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_agent_regressions() {
+        let baseline = AgentReport::from_results(&[result("add_function", true, 2, 100)]);
+        let same = AgentReport::from_results(&[result("add_function", true, 2, 101)]);
+        assert!(!compare(&baseline, &same, DEFAULT_TOLERANCE).has_regressions());
+
+        let worse = AgentReport::from_results(&[result("add_function", false, 3, 100)]);
+        let comparison = compare(&baseline, &worse, DEFAULT_TOLERANCE);
+        let regressed: Vec<_> = comparison
+            .regressions
+            .iter()
+            .map(|regression| regression.metric)
+            .collect();
+        assert_eq!(
+            regressed,
+            [
+                "Completion Rate",
+                "Validation Pass Rate",
+                "File Match Rate",
+                "Tool Calls"
+            ]
+        );
+        assert!(
+            comparison
+                .delta_table()
+                .contains("| add_function | -100.0 | -100.0 | -100.0 | +1.0 | +0.0 |")
+        );
+    }
+}
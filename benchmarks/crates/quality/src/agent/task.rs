@@ -0,0 +1,293 @@
+//! Agent task definition and loading.
+//!
+//! See `agent_tasks/README.md` for the TOML schema.
+
+use anyhow::{Context as _, Result, bail};
+use regex::Regex;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+use std::path::Path;
+use toml::from_str;
+
+/// A coding task run end to end by the agent
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentTask {
+    /// Task name
+    pub name: String,
+    /// Description
+    #[serde(default)]
+    pub description: String,
+    /// Request given to the agent
+    pub prompt: String,
+    /// Repository the agent starts from
+    #[serde(default)]
+    pub setup: TaskSetup,
+    /// Scripted model responses, in request order
+    #[serde(default)]
+    pub responses: Vec<ScriptedResponse>,
+    /// How the outcome is scored
+    #[serde(default)]
+    pub expect: Expectations,
+}
+
+/// Fixture repository written to a fresh workspace before the task runs
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSetup {
+    /// File contents by path relative to the workspace
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+/// One scripted model response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScriptedResponse {
+    /// `TypeScript` the model answers with
+    pub typescript: String,
+}
+
+/// Expected outcome of a task
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectations {
+    /// Files checked after the task
+    #[serde(default)]
+    pub files: Vec<FileExpectation>,
+    /// Shell command run in the workspace after the task, which must exit with 0
+    #[serde(default)]
+    pub validate: Option<String>,
+    /// Largest number of tool calls of a completed task
+    #[serde(default)]
+    pub max_tool_calls: Option<usize>,
+    /// Largest number of tokens of a completed task
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+}
+
+/// Expected state of a file after the task
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileExpectation {
+    /// Path relative to the workspace
+    pub path: String,
+    /// Whether the file must exist (`false` expects it to be deleted)
+    #[serde(default = "default_exists")]
+    pub exists: bool,
+    /// Patterns the content must match
+    #[serde(default, deserialize_with = "deserialize_patterns")]
+    pub matches: Vec<Regex>,
+    /// Patterns the content must not match
+    #[serde(default, deserialize_with = "deserialize_patterns")]
+    pub not_matches: Vec<Regex>,
+}
+
+/// Default for [`FileExpectation::exists`]
+const fn default_exists() -> bool {
+    true
+}
+
+/// Compiles the regular expressions of a file expectation
+///
+/// # Errors
+/// Returns an error if a pattern is not a valid regular expression
+fn deserialize_patterns<'de, D>(deserializer: D) -> Result<Vec<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern)
+                .map_err(|err| D::Error::custom(format!("invalid pattern `{pattern}`: {err}")))
+        })
+        .collect()
+}
+
+impl FileExpectation {
+    /// Checks the file in `workspace`, describing the first mismatch
+    ///
+    /// # Errors
+    /// Returns the reason the file does not match the expectation.
+    pub fn check(&self, workspace: &Path) -> Result<(), String> {
+        let path = workspace.join(&self.path);
+        if !self.exists {
+            return if path.exists() {
+                Err(format!("{} should not exist", self.path))
+            } else {
+                Ok(())
+            };
+        }
+        let content =
+            read_to_string(&path).map_err(|err| format!("{} not readable: {err}", self.path))?;
+        if let Some(missing) = self
+            .matches
+            .iter()
+            .find(|pattern| !pattern.is_match(&content))
+        {
+            return Err(format!("{} does not match `{missing}`", self.path));
+        }
+        if let Some(found) = self
+            .not_matches
+            .iter()
+            .find(|pattern| pattern.is_match(&content))
+        {
+            return Err(format!("{} should not match `{found}`", self.path));
+        }
+        Ok(())
+    }
+}
+
+impl AgentTask {
+    /// Load a task from a TOML file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed, or a setup path
+    /// leaves the workspace
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = read_to_string(path)
+            .with_context(|| format!("Failed to read task file: {}", path.display()))?;
+        let task: Self = from_str(&content)
+            .with_context(|| format!("Failed to parse task TOML: {}", path.display()))?;
+        for file in task.setup.files.keys() {
+            if Path::new(file).is_absolute() || file.split(['/', '\\']).any(|part| part == "..") {
+                bail!(
+                    "Setup file '{file}' of task '{}' leaves the workspace",
+                    task.name
+                );
+            }
+        }
+        Ok(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::load_tasks;
+    use std::fs::write;
+    use tempfile::TempDir;
+
+    /// Tests parsing a task with every section.
+    ///
+    /// # Errors
+    /// Returns an error if the task does not parse.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_task() -> Result<()> {
+        let task: AgentTask = from_str(
+            r#"
+name = "add_function"
+prompt = "Add a function"
+
+[setup.files]
+"src/lib.rs" = "pub fn one() {}\n"
+
+[[responses]]
+typescript = "async function agent_code(): Promise<string> { return 'done'; }"
+
+[expect]
+validate = "true"
+max_tool_calls = 3
+
+[[expect.files]]
+path = "src/lib.rs"
+matches = ["pub fn two\\(\\)"]
+not_matches = ["todo!"]
+
+[[expect.files]]
+path = "src/old.rs"
+exists = false
+"#,
+        )?;
+        assert_eq!(task.setup.files.len(), 1);
+        assert_eq!(task.responses.len(), 1);
+        assert_eq!(task.expect.files.len(), 2);
+        assert!(task.expect.files[0].exists);
+        assert!(!task.expect.files[1].exists);
+        assert_eq!(task.expect.max_tool_calls, Some(3));
+        assert_eq!(task.expect.max_tokens, None);
+
+        let invalid = from_str::<AgentTask>(
+            "name = 'x'\nprompt = 'y'\n[[expect.files]]\npath = 'a'\nmatches = ['(']\n",
+        );
+        assert!(invalid.is_err(), "invalid patterns should be rejected");
+        let unknown = from_str::<AgentTask>("name = 'x'\nprompt = 'y'\n[expect]\nmax_calls = 1\n");
+        assert!(unknown.is_err(), "unknown fields should be rejected");
+        Ok(())
+    }
+
+    /// Tests checking files against their expectations.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be written or parsed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_file_expectations() -> Result<()> {
+        let workspace = TempDir::new()?;
+        write(
+            workspace.path().join("lib.rs"),
+            "pub fn two() -> u32 { 2 }\n",
+        )?;
+        let expectations: Expectations = from_str(
+            r#"
+[[files]]
+path = "lib.rs"
+matches = ["fn two"]
+
+[[files]]
+path = "lib.rs"
+matches = ["fn three"]
+
+[[files]]
+path = "lib.rs"
+not_matches = ["u32"]
+
+[[files]]
+path = "gone.rs"
+exists = false
+
+[[files]]
+path = "lib.rs"
+exists = false
+"#,
+        )?;
+        let outcomes: Vec<_> = expectations
+            .files
+            .iter()
+            .map(|file| file.check(workspace.path()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                Ok(()),
+                Err("lib.rs does not match `fn three`".to_owned()),
+                Err("lib.rs should not match `u32`".to_owned()),
+                Ok(()),
+                Err("lib.rs should not exist".to_owned()),
+            ]
+        );
+        Ok(())
+    }
+
+    /// Tests that every bundled task parses.
+    ///
+    /// # Errors
+    /// Returns an error if a bundled task is invalid.
+    ///
+    /// # Panics
+    /// Panics if no bundled task is found.
+    #[test]
+    fn test_bundled_tasks_parse() -> Result<()> {
+        let tasks = load_tasks(&Path::new(env!("CARGO_MANIFEST_DIR")).join("agent_tasks"))?;
+        assert!(!tasks.is_empty(), "no bundled agent tasks found");
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+//! Counting the tool calls of the agent.
+//!
+//! The `TypeScript` runtime opens a `tool_call` span around every tool it
+//! executes, on a thread of its own. [`ToolCallLayer`] counts those spans in a
+//! process-wide counter, so it only sees them when installed in the global
+//! subscriber, and tasks have to run one at a time to be told apart.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tracing::span::{Attributes, Id};
+use tracing::subscriber::set_global_default;
+use tracing::{Subscriber, dispatcher};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::{Context, SubscriberExt as _};
+use tracing_subscriber::registry;
+
+/// Name of the span opened around each tool execution
+const TOOL_CALL_SPAN: &str = "tool_call";
+
+/// Tool calls seen since the process started
+static TOOL_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Layer counting tool calls
+pub struct ToolCallLayer;
+
+impl<S: Subscriber> Layer<S> for ToolCallLayer {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+        if attrs.metadata().name() == TOOL_CALL_SPAN {
+            TOOL_CALLS.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Returns the number of tool calls seen so far
+pub fn tool_calls() -> usize {
+    TOOL_CALLS.load(Ordering::Relaxed)
+}
+
+/// Installs a global subscriber that only counts tool calls, unless one is set
+///
+/// For callers that do not install their own subscriber, such as tests.
+pub fn ensure_counting() {
+    if !dispatcher::has_been_set() {
+        // Another thread may win the race, which is as good
+        _ = set_global_default(registry().with(ToolCallLayer));
+    }
+}
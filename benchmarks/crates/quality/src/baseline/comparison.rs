@@ -0,0 +1,160 @@
+//! Comparison of a run with a baseline.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use super::{ComparableReport, MetricScale, MetricSpec};
+
+/// An aggregate metric that worsened by more than the tolerance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    /// Metric name
+    pub metric: &'static str,
+    /// Baseline value, on the metric's own scale
+    pub baseline: f64,
+    /// Current value, on the metric's own scale
+    pub current: f64,
+}
+
+/// How a test case changed between the baseline and the current run
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestChange {
+    /// Present in both runs, with the change of each metric in report order
+    Compared(Vec<f64>),
+    /// Only in the current run
+    Added,
+    /// Only in the baseline
+    Removed,
+}
+
+/// Outcome of comparing a run with a baseline
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// Aggregate metrics that dropped beyond the tolerance
+    pub regressions: Vec<Regression>,
+    /// Change of every test case of either run, ordered by name
+    pub tests: Vec<(String, TestChange)>,
+    /// Number of test cases present in both runs
+    pub common_count: usize,
+    /// Compared metrics, in report order
+    pub metrics: &'static [MetricSpec],
+}
+
+impl Comparison {
+    /// Returns true if any aggregate metric regressed
+    pub fn has_regressions(&self) -> bool {
+        !self.regressions.is_empty()
+    }
+
+    /// Renders the per-test changes as a markdown table
+    pub fn delta_table(&self) -> String {
+        let mut table = String::from("| Test |");
+        for spec in self.metrics {
+            _ = write!(table, " Δ {} |", spec.name);
+        }
+        table.push_str("\n|------|");
+        table.push_str(&"------|".repeat(self.metrics.len()));
+        table.push('\n');
+
+        for (name, change) in &self.tests {
+            _ = write!(table, "| {name} |");
+            match change {
+                TestChange::Compared(deltas) => {
+                    for (delta, spec) in deltas.iter().zip(self.metrics) {
+                        _ = write!(table, " {} |", format_delta(*delta, spec.scale));
+                    }
+                }
+                TestChange::Added => table.push_str(&" new |".repeat(self.metrics.len())),
+                TestChange::Removed => table.push_str(&" removed |".repeat(self.metrics.len())),
+            }
+            table.push('\n');
+        }
+        table
+    }
+}
+
+/// Compares a run with a baseline
+///
+/// `tolerance` is the largest tolerated worsening of an aggregate metric on
+/// a 0-1 scale, so `0.02` allows percentages to drop by 2 points and MRR or
+/// NDCG by 0.02. Metrics where lower is better (such as the forbidden hit
+/// rate) regress when they rise instead, and counts regress when they
+/// worsen by more than that fraction of their baseline value.
+pub fn compare<R: ComparableReport>(baseline: &R, current: &R, tolerance: f64) -> Comparison {
+    let specs = R::metric_specs();
+    let baseline_tests: HashMap<&str, Vec<f64>> = baseline.test_metrics().into_iter().collect();
+    let current_tests: HashMap<&str, Vec<f64>> = current.test_metrics().into_iter().collect();
+
+    let mut names: Vec<&str> = baseline_tests
+        .keys()
+        .chain(current_tests.keys())
+        .copied()
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+
+    let mut before_sums = vec![0.0; specs.len()];
+    let mut after_sums = vec![0.0; specs.len()];
+    let mut common_count = 0;
+    let mut tests = Vec::with_capacity(names.len());
+    for name in names {
+        let change = match (baseline_tests.get(name), current_tests.get(name)) {
+            (Some(before), Some(after)) => {
+                common_count += 1;
+                for (sum, value) in before_sums.iter_mut().zip(before) {
+                    *sum += value;
+                }
+                for (sum, value) in after_sums.iter_mut().zip(after) {
+                    *sum += value;
+                }
+                TestChange::Compared(
+                    before
+                        .iter()
+                        .zip(after)
+                        .map(|(before_value, after_value)| after_value - before_value)
+                        .collect(),
+                )
+            }
+            (None, _) => TestChange::Added,
+            (_, None) => TestChange::Removed,
+        };
+        tests.push((name.to_owned(), change));
+    }
+
+    // Aggregates are averages over the test cases of both runs
+    let regressions = if common_count == 0 {
+        Vec::new()
+    } else {
+        let count = common_count as f64;
+        specs
+            .iter()
+            .zip(before_sums.into_iter().zip(after_sums))
+            .map(|(spec, (before_sum, after_sum))| (spec, before_sum / count, after_sum / count))
+            .filter(|(spec, before_value, after_value)| {
+                spec.worsening(*before_value, *after_value) > tolerance
+            })
+            .map(|(spec, before_value, after_value)| Regression {
+                metric: spec.name,
+                baseline: before_value,
+                current: after_value,
+            })
+            .collect()
+    };
+
+    Comparison {
+        regressions,
+        tests,
+        common_count,
+        metrics: specs,
+    }
+}
+
+/// Formats a change like the metric itself: three decimals for scores, one otherwise
+fn format_delta(delta: f64, scale: MetricScale) -> String {
+    match scale {
+        MetricScale::Absolute(divisor) if (divisor - 1.0).abs() < f64::EPSILON => {
+            format!("{delta:+.3}")
+        }
+        MetricScale::Absolute(_) | MetricScale::Relative => format!("{delta:+.1}"),
+    }
+}
@@ -0,0 +1,198 @@
+//! Machine-readable results and regression checks against a saved baseline.
+//!
+//! A run is saved as a [`JsonReport`]. [`compare`] matches the test cases of
+//! two reports by name and flags aggregate metrics that dropped by more than
+//! a tolerance. Aggregates are averaged over the test cases present in both
+//! runs, so adding or removing a test case cannot pass or fail the check on
+//! its own. Other reports, such as those of the agent benchmarks, are
+//! compared the same way by implementing [`ComparableReport`].
+
+mod comparison;
+
+use serde::{Deserialize, Serialize};
+
+use crate::BenchmarkResult;
+use crate::metrics::{AggregateMetrics, BenchmarkMetrics, ForbiddenHit};
+use crate::performance::{LatencyStats, PerformanceSummary};
+
+pub use comparison::{Comparison, Regression, TestChange, compare};
+
+/// Default largest tolerated drop of an aggregate metric, on a 0-1 scale
+pub const DEFAULT_TOLERANCE: f64 = 0.02;
+
+/// Retrieval metrics compared between runs, in report order
+const RETRIEVAL_METRICS: [MetricSpec; 7] = [
+    MetricSpec::percentage("Precision@3"),
+    MetricSpec::percentage("Precision@10"),
+    MetricSpec::percentage("Recall@10"),
+    MetricSpec::score("MRR"),
+    MetricSpec::score("NDCG@10"),
+    MetricSpec::percentage("Critical in Top-3"),
+    MetricSpec::percentage("Forbidden Hit Rate").lower_is_better(),
+];
+
+/// How the change of a metric is measured against the tolerance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MetricScale {
+    /// Change divided by this value, bringing the metric to a 0-1 scale
+    Absolute(f64),
+    /// Change relative to the baseline value, for unbounded counts
+    Relative,
+}
+
+/// A metric compared between runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricSpec {
+    /// Metric name
+    pub name: &'static str,
+    /// How changes are measured
+    pub scale: MetricScale,
+    /// Direction of improvement: 1 if higher is better, -1 if lower is
+    pub direction: f64,
+}
+
+impl MetricSpec {
+    /// A percentage stored as 0-100, higher is better
+    pub const fn percentage(name: &'static str) -> Self {
+        Self {
+            name,
+            scale: MetricScale::Absolute(100.0),
+            direction: 1.0,
+        }
+    }
+
+    /// A score stored as 0-1, higher is better
+    pub const fn score(name: &'static str) -> Self {
+        Self {
+            name,
+            scale: MetricScale::Absolute(1.0),
+            direction: 1.0,
+        }
+    }
+
+    /// An unbounded count compared relative to the baseline, higher is better
+    pub const fn count(name: &'static str) -> Self {
+        Self {
+            name,
+            scale: MetricScale::Relative,
+            direction: 1.0,
+        }
+    }
+
+    /// The same metric, where lower is better
+    #[must_use]
+    pub const fn lower_is_better(self) -> Self {
+        Self {
+            direction: -1.0,
+            ..self
+        }
+    }
+
+    /// Returns how much the metric worsened from `before` to `after`, on the tolerance's scale
+    fn worsening(&self, before: f64, after: f64) -> f64 {
+        let change = self.direction * (before - after);
+        match self.scale {
+            MetricScale::Absolute(divisor) => change / divisor,
+            MetricScale::Relative if before.abs() > f64::EPSILON => change / before.abs(),
+            // Any worsening from zero is unbounded in relative terms
+            MetricScale::Relative if change > 0.0 => f64::INFINITY,
+            MetricScale::Relative => 0.0,
+        }
+    }
+}
+
+/// Name and metric values of each test case of a run
+pub type TestMetrics<'report> = Vec<(&'report str, Vec<f64>)>;
+
+/// A run whose test cases can be compared with a baseline
+pub trait ComparableReport {
+    /// Compared metrics, in the order of [`Self::test_metrics`]
+    fn metric_specs() -> &'static [MetricSpec];
+
+    /// Name and metric values of each test case
+    fn test_metrics(&self) -> TestMetrics<'_>;
+}
+
+/// Results of one run in machine-readable form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonReport {
+    /// Metrics averaged over every test case of the run
+    pub aggregate: AggregateMetrics,
+    /// Timings and memory use of the run
+    #[serde(default)]
+    pub performance: PerformanceSummary,
+    /// Results of each test case, ordered by name
+    pub tests: Vec<TestReport>,
+}
+
+/// Result of one test case in machine-readable form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestReport {
+    /// Test case name
+    pub name: String,
+    /// Query used
+    pub query: String,
+    /// Retrieved file paths, best first
+    pub results: Vec<String>,
+    /// Calculated metrics
+    pub metrics: BenchmarkMetrics,
+    /// Forbidden files among the top results
+    #[serde(default)]
+    pub forbidden_hits: Vec<ForbiddenHit>,
+    /// Search latency over the repetitions of the query
+    #[serde(default)]
+    pub latency: LatencyStats,
+}
+
+impl JsonReport {
+    /// Builds the report of a run
+    pub fn from_results(results: &[BenchmarkResult], performance: PerformanceSummary) -> Self {
+        let mut tests: Vec<TestReport> = results
+            .iter()
+            .map(|result| TestReport {
+                name: result.name.clone(),
+                query: result.query.clone(),
+                results: result.results.clone(),
+                metrics: result.metrics.clone(),
+                forbidden_hits: result.forbidden_hits.clone(),
+                latency: result.latency.clone(),
+            })
+            .collect();
+        tests.sort_by(|left, right| left.name.cmp(&right.name));
+        let metrics: Vec<_> = tests.iter().map(|test| test.metrics.clone()).collect();
+        Self {
+            aggregate: AggregateMetrics::from_metrics(&metrics),
+            performance,
+            tests,
+        }
+    }
+}
+
+impl ComparableReport for JsonReport {
+    fn metric_specs() -> &'static [MetricSpec] {
+        &RETRIEVAL_METRICS
+    }
+
+    fn test_metrics(&self) -> TestMetrics<'_> {
+        self.tests
+            .iter()
+            .map(|test| (test.name.as_str(), metric_values(&test.metrics).to_vec()))
+            .collect()
+    }
+}
+
+/// Returns the metrics of a test case in report order
+const fn metric_values(metrics: &BenchmarkMetrics) -> [f64; RETRIEVAL_METRICS.len()] {
+    [
+        metrics.precision_at_3,
+        metrics.precision_at_10,
+        metrics.recall_at_10,
+        metrics.mrr,
+        metrics.ndcg_at_10,
+        metrics.critical_in_top_3,
+        metrics.forbidden_hit_rate,
+    ]
+}
+
+#[cfg(test)]
+mod tests;
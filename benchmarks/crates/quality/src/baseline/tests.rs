@@ -0,0 +1,209 @@
+//! Tests for saving runs and comparing them with a baseline
+
+use super::*;
+use serde_json::{Result as JsonResult, Value, from_str, from_value, to_string, to_value};
+
+/// Builds a test report whose percentages are all `percent` and whose MRR and NDCG are `score`
+fn test_report(name: &str, percent: f64, score: f64) -> TestReport {
+    TestReport {
+        name: name.to_owned(),
+        query: format!("query for {name}"),
+        results: vec!["src/lib.rs".to_owned()],
+        metrics: BenchmarkMetrics {
+            precision_at_3: percent,
+            precision_at_10: percent,
+            recall_at_10: percent,
+            mrr: score,
+            ndcg_at_10: score,
+            critical_in_top_3: percent,
+            forbidden_hit_rate: 0.0,
+        },
+        forbidden_hits: Vec::new(),
+        latency: LatencyStats::default(),
+    }
+}
+
+/// Builds a report from test reports
+fn report(tests: Vec<TestReport>) -> JsonReport {
+    let metrics: Vec<_> = tests.iter().map(|test| test.metrics.clone()).collect();
+    JsonReport {
+        aggregate: AggregateMetrics::from_metrics(&metrics),
+        performance: PerformanceSummary::default(),
+        tests,
+    }
+}
+
+/// Tests that drops within the tolerance pass and larger ones are reported.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_regression_beyond_tolerance() {
+    let baseline = report(vec![test_report("auth", 60.0, 0.5)]);
+
+    let within = report(vec![test_report("auth", 59.0, 0.49)]);
+    assert!(!compare(&baseline, &within, DEFAULT_TOLERANCE).has_regressions());
+
+    let improved = report(vec![test_report("auth", 90.0, 0.9)]);
+    assert!(!compare(&baseline, &improved, DEFAULT_TOLERANCE).has_regressions());
+
+    let mut dropped_test = test_report("auth", 60.0, 0.25);
+    dropped_test.metrics.precision_at_3 = 50.0;
+    let comparison = compare(&baseline, &report(vec![dropped_test]), DEFAULT_TOLERANCE);
+    let regressed: Vec<_> = comparison
+        .regressions
+        .iter()
+        .map(|regression| regression.metric)
+        .collect();
+    assert_eq!(regressed, ["Precision@3", "MRR", "NDCG@10"]);
+
+    // A rising forbidden hit rate is a regression, a falling one is not
+    let mut forbidden_test = test_report("auth", 60.0, 0.5);
+    forbidden_test.metrics.forbidden_hit_rate = 10.0;
+    let worse = compare(
+        &baseline,
+        &report(vec![forbidden_test.clone()]),
+        DEFAULT_TOLERANCE,
+    );
+    assert_eq!(worse.regressions.len(), 1);
+    assert_eq!(worse.regressions[0].metric, "Forbidden Hit Rate");
+    assert!(
+        !compare(&report(vec![forbidden_test]), &baseline, DEFAULT_TOLERANCE).has_regressions()
+    );
+    assert_eq!(
+        comparison.tests,
+        vec![(
+            "auth".to_owned(),
+            TestChange::Compared(vec![-10.0, 0.0, 0.0, -0.25, -0.25, 0.0, 0.0])
+        )]
+    );
+}
+
+/// Tests that added and removed test cases are listed but do not affect the aggregates.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_added_and_removed_test_cases() {
+    let baseline = report(vec![
+        test_report("auth", 60.0, 0.6),
+        test_report("cache", 80.0, 0.8),
+    ]);
+    // `cache` was removed and a much weaker `logging` added; `auth` is unchanged
+    let current = report(vec![
+        test_report("auth", 60.0, 0.6),
+        test_report("logging", 0.0, 0.0),
+    ]);
+
+    let comparison = compare(&baseline, &current, DEFAULT_TOLERANCE);
+    assert!(!comparison.has_regressions());
+    assert_eq!(comparison.common_count, 1);
+    assert_eq!(
+        comparison.tests,
+        vec![
+            (
+                "auth".to_owned(),
+                TestChange::Compared(vec![0.0; RETRIEVAL_METRICS.len()])
+            ),
+            ("cache".to_owned(), TestChange::Removed),
+            ("logging".to_owned(), TestChange::Added),
+        ]
+    );
+
+    let table = comparison.delta_table();
+    assert!(table.contains("| auth | +0.0 | +0.0 | +0.0 | +0.000 | +0.000 | +0.0 | +0.0 |"));
+    assert!(table.contains("| cache | removed |"));
+    assert!(table.contains("| logging | new |"));
+
+    let disjoint = report(vec![test_report("other", 0.0, 0.0)]);
+    let nothing_common = compare(&baseline, &disjoint, DEFAULT_TOLERANCE);
+    assert_eq!(nothing_common.common_count, 0);
+    assert!(!nothing_common.has_regressions());
+}
+
+/// Metrics of [`CountReport`]
+const COUNT_METRICS: [MetricSpec; 1] = [MetricSpec::count("Tool Calls").lower_is_better()];
+
+/// Report of counts where lower is better
+struct CountReport(Vec<(&'static str, f64)>);
+
+impl ComparableReport for CountReport {
+    fn metric_specs() -> &'static [MetricSpec] {
+        &COUNT_METRICS
+    }
+
+    fn test_metrics(&self) -> TestMetrics<'_> {
+        self.0
+            .iter()
+            .map(|(name, value)| (*name, vec![*value]))
+            .collect()
+    }
+}
+
+/// Tests that counts regress relative to their baseline value.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_relative_count_metrics() {
+    let baseline = CountReport(vec![("add", 10.0), ("fix", 20.0)]);
+
+    // 15 to 15.5 calls on average is a 3.3% rise
+    let slightly_more = CountReport(vec![("add", 10.0), ("fix", 21.0)]);
+    assert!(!compare(&baseline, &slightly_more, 0.05).has_regressions());
+    let comparison = compare(&baseline, &slightly_more, DEFAULT_TOLERANCE);
+    assert_eq!(comparison.regressions.len(), 1);
+    assert!((comparison.regressions[0].current - 15.5).abs() < 1e-9);
+
+    let fewer = CountReport(vec![("add", 1.0), ("fix", 1.0)]);
+    assert!(!compare(&baseline, &fewer, 0.0).has_regressions());
+
+    let from_zero = compare(
+        &CountReport(vec![("add", 0.0)]),
+        &CountReport(vec![("add", 1.0)]),
+        10.0,
+    );
+    assert!(from_zero.has_regressions());
+    assert!(
+        compare(&baseline, &baseline, 0.0)
+            .delta_table()
+            .contains("| add | +0.0 |")
+    );
+}
+
+/// Tests that a report survives a JSON round trip.
+///
+/// # Errors
+/// Returns an error if the report cannot be serialized or parsed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_json_round_trip() -> JsonResult<()> {
+    let original = report(vec![test_report("auth", 60.0, 0.6)]);
+    let parsed: JsonReport = from_str(&to_string(&original)?)?;
+    assert_eq!(parsed.tests.len(), 1);
+    assert_eq!(parsed.aggregate.test_count, 1);
+    assert!(!compare(&original, &parsed, 0.0).has_regressions());
+
+    // Baselines saved before performance was measured still load
+    let mut old_format = to_value(&original)?;
+    if let Some(fields) = old_format.as_object_mut() {
+        fields.remove("performance");
+        if let Some(aggregate) = fields.get_mut("aggregate").and_then(Value::as_object_mut) {
+            aggregate.remove("avg_forbidden_hit_rate");
+        }
+    }
+    if let Some(tests) = old_format.get_mut("tests").and_then(Value::as_array_mut) {
+        for test in tests.iter_mut().filter_map(Value::as_object_mut) {
+            test.remove("latency");
+            test.remove("forbidden_hits");
+            if let Some(metrics) = test.get_mut("metrics").and_then(Value::as_object_mut) {
+                metrics.remove("forbidden_hit_rate");
+            }
+        }
+    }
+    let parsed_old: JsonReport = from_value(old_format)?;
+    assert_eq!(parsed_old.performance, PerformanceSummary::default());
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! Chunk-level retrieval benchmark comparing chunking strategies.
+//!
+//! Every file of `chunking_corpus/` is chunked, the chunks are indexed with
+//! BM25 (weighting their headers), and each query asks for one definition. A query counts as recalled if
+//! one of the top results contains the whole definition, so a strategy that
+//! cuts functions in half scores lower even when it finds the right file.
+
+use anyhow::{Result, anyhow};
+use merlin_context::embedding::{BM25Index, FileChunk};
+use merlin_languages::chunker_for;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+/// Chunking strategy under test
+pub type Chunker = fn(&Path, &str) -> Vec<FileChunk>;
+
+/// A query and the definition it should retrieve
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkQuery {
+    /// Corpus file containing the definition
+    pub file: &'static str,
+    /// Identifier of the definition (as reported by the language backend)
+    pub definition: &'static str,
+    /// Natural-language query
+    pub query: &'static str,
+}
+
+/// Queries over `chunking_corpus/`
+pub const CORPUS_QUERIES: &[ChunkQuery] = &[
+    ChunkQuery {
+        file: "inventory.py",
+        definition: "def reserve_stock",
+        query: "reserve units for a pending order when stock is short",
+    },
+    ChunkQuery {
+        file: "inventory.py",
+        definition: "def schedule_reorder",
+        query: "supplier purchase order below reorder level",
+    },
+    ChunkQuery {
+        file: "inventory.py",
+        definition: "def expire_perishables",
+        query: "write off perishable batches shelf life",
+    },
+    ChunkQuery {
+        file: "inventory.py",
+        definition: "def transfer_between_warehouses",
+        query: "move units to another warehouse location transfer",
+    },
+    ChunkQuery {
+        file: "inventory.py",
+        definition: "def audit_discrepancies",
+        query: "physical stock count ledger mismatches",
+    },
+    ChunkQuery {
+        file: "checkout.ts",
+        definition: "function applyCoupon",
+        query: "percentage coupon minimum spend discount",
+    },
+    ChunkQuery {
+        file: "checkout.ts",
+        definition: "function calculateSalesTax",
+        query: "sales tax region exempt categories",
+    },
+    ChunkQuery {
+        file: "checkout.ts",
+        definition: "function capturePayment",
+        query: "charge card payment gateway retry declined",
+    },
+    ChunkQuery {
+        file: "checkout.ts",
+        definition: "function estimateDelivery",
+        query: "estimate delivery date dispatch cutoff transit days",
+    },
+    ChunkQuery {
+        file: "checkout.ts",
+        definition: "function splitIntoParcels",
+        query: "split order into parcels carrier weight limit",
+    },
+    ChunkQuery {
+        file: "scheduler.go",
+        definition: "func EnqueueJob",
+        query: "pending queue ordered by priority and run time",
+    },
+    ChunkQuery {
+        file: "scheduler.go",
+        definition: "func RetryWithBackoff",
+        query: "reschedule failed job exponential backoff attempts",
+    },
+    ChunkQuery {
+        file: "scheduler.go",
+        definition: "func ReapTimedOutJobs",
+        query: "cancel running jobs exceeded timeout",
+    },
+    ChunkQuery {
+        file: "scheduler.go",
+        definition: "func DistributeToWorkers",
+        query: "assign jobs to workers round robin capacity",
+    },
+    ChunkQuery {
+        file: "scheduler.go",
+        definition: "func ParseCronInterval",
+        query: "parse cron interval every duration",
+    },
+];
+
+/// Returns the directory holding the chunking corpus
+pub fn corpus_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("chunking_corpus")
+}
+
+/// Returns the percentage of queries whose whole definition is in the top `cutoff` chunks
+///
+/// # Errors
+/// Returns an error if a corpus file cannot be read or a definition is not found
+pub fn chunk_recall(
+    corpus: &Path,
+    queries: &[ChunkQuery],
+    chunker: Chunker,
+    cutoff: usize,
+) -> Result<f64> {
+    let mut files: Vec<&str> = queries.iter().map(|query| query.file).collect();
+    files.sort_unstable();
+    files.dedup();
+
+    let mut chunks = Vec::new();
+    let mut index = BM25Index::default();
+    for file in files {
+        let content = read_to_string(corpus.join(file))?;
+        for chunk in chunker(Path::new(file), &content) {
+            index.add_chunk(
+                PathBuf::from(chunks.len().to_string()),
+                chunk.header.as_deref(),
+                &chunk.content,
+            );
+            chunks.push(chunk);
+        }
+    }
+    index.finalize();
+
+    let mut recalled = 0usize;
+    for query in queries {
+        let (start_line, end_line) = definition_lines(corpus, query)?;
+        let found = index
+            .search(query.query, cutoff)
+            .iter()
+            .filter_map(|(id, _)| chunks.get(id.to_str()?.parse::<usize>().ok()?))
+            .any(|chunk| {
+                chunk.file_path == query.file
+                    && chunk.start_line <= start_line
+                    && end_line <= chunk.end_line
+            });
+        if found {
+            recalled += 1;
+        }
+    }
+
+    Ok(recalled as f64 / queries.len().max(1) as f64 * 100.0)
+}
+
+/// Returns the line range of a query's definition, including its doc comment
+///
+/// # Errors
+/// Returns an error if the file cannot be read or parsed, or has no such definition
+fn definition_lines(corpus: &Path, query: &ChunkQuery) -> Result<(usize, usize)> {
+    let path = Path::new(query.file);
+    let content = read_to_string(corpus.join(path))?;
+    let chunker = chunker_for(path).ok_or_else(|| anyhow!("No backend for {}", query.file))?;
+    chunker
+        .code_spans(path, &content)?
+        .into_iter()
+        .find(|span| span.identifier == query.definition)
+        .map(|span| (span.start_line, span.end_line))
+        .ok_or_else(|| anyhow!("{} not found in {}", query.definition, query.file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_context::embedding::chunking::chunk_generic_code;
+    use merlin_context::embedding::{chunk_file, chunk_preview};
+
+    /// Line-based chunking, as used before syntax-aware chunking
+    fn chunk_by_lines(path: &Path, content: &str) -> Vec<FileChunk> {
+        chunk_generic_code(path.display().to_string(), content)
+    }
+
+    /// Syntax-aware chunking without chunk headers
+    fn chunk_without_headers(path: &Path, content: &str) -> Vec<FileChunk> {
+        chunk_file(path, content)
+            .into_iter()
+            .map(|chunk| FileChunk {
+                header: None,
+                ..chunk
+            })
+            .collect()
+    }
+
+    /// Tests that syntax-aware chunks recall more whole definitions than line chunks.
+    ///
+    /// # Errors
+    /// Returns an error if the corpus cannot be chunked.
+    ///
+    /// # Panics
+    /// Panics if syntax-aware recall does not beat line-based recall.
+    #[test]
+    fn test_syntax_chunking_beats_line_chunking() -> Result<()> {
+        let corpus = corpus_dir();
+        let syntax_recall = chunk_recall(&corpus, CORPUS_QUERIES, chunk_file, 3)?;
+        let line_recall = chunk_recall(&corpus, CORPUS_QUERIES, chunk_by_lines, 3)?;
+
+        assert!(
+            syntax_recall > line_recall,
+            "syntax-aware R@3 {syntax_recall:.1}% should beat line-based R@3 {line_recall:.1}%"
+        );
+        Ok(())
+    }
+
+    /// Tests that chunk headers keep or improve recall, and previews stay within their limit.
+    ///
+    /// # Errors
+    /// Returns an error if the corpus cannot be read or chunked.
+    ///
+    /// # Panics
+    /// Panics if header recall drops or a preview exceeds its limit.
+    #[test]
+    fn test_chunk_headers_keep_recall() -> Result<()> {
+        let corpus = corpus_dir();
+        for cutoff in [1, 3] {
+            let header_recall = chunk_recall(&corpus, CORPUS_QUERIES, chunk_file, cutoff)?;
+            let plain_recall =
+                chunk_recall(&corpus, CORPUS_QUERIES, chunk_without_headers, cutoff)?;
+            assert!(
+                header_recall >= plain_recall,
+                "R@{cutoff} with headers {header_recall:.1}% should match {plain_recall:.1}% without"
+            );
+        }
+
+        for file in ["checkout.ts", "inventory.py", "scheduler.go"] {
+            let content = read_to_string(corpus.join(file))?;
+            for chunk in chunk_file(Path::new(file), &content) {
+                assert!(
+                    chunk.header.is_some(),
+                    "{file}:{} has no header",
+                    chunk.start_line
+                );
+                let preview = chunk_preview(&chunk, 200);
+                assert!(
+                    preview.chars().count() <= 203,
+                    "preview too long: {preview}"
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,38 +1,137 @@
 //! Quality benchmarking for context retrieval system.
 
+pub mod agent;
+pub mod baseline;
+pub mod chunk_recall;
 pub mod metrics;
+pub mod performance;
+mod report;
+mod repository;
 pub mod test_case;
 
-use anyhow::{Context as _, Result, anyhow, bail};
+use anyhow::{Context as _, Result, anyhow};
 use merlin_context::ContextBuilder;
+use merlin_context::context_inclusion::MAX_CONTEXT_TOKENS;
 use merlin_core::{FileContext, Query};
-use metrics::{AggregateMetrics, BenchmarkMetrics};
+use merlin_tooling::display_path;
+use metrics::{BenchmarkMetrics, ForbiddenHit};
+use performance::{LatencyStats, ProjectTiming, cache_dir, peak_rss_bytes};
+pub use report::generate_report;
+use repository::setup_repository;
 use std::collections::HashMap;
-use std::fs::create_dir_all;
+use std::fs::remove_dir_all;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::sync::Arc;
-use test_case::TestCase;
+use std::time::{Duration, Instant};
+use test_case::{ContextOverrides, TestCase};
 use tokio::sync::Mutex;
 use tokio::task::JoinSet;
 use tracing::warn;
 use walkdir::WalkDir;
 
-/// Run all benchmarks in a directory
+/// Maximum number of files in the context of a test case without an override
+const DEFAULT_MAX_FILES: usize = 20;
+
+/// Options controlling how benchmarks are run
+#[derive(Debug, Clone, Copy)]
+pub struct RunOptions {
+    /// Number of times each query is searched to measure its latency
+    pub repeat: usize,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self { repeat: 1 }
+    }
+}
+
+/// Results and measurements of a benchmark run
+#[derive(Debug, Clone)]
+pub struct BenchmarkRun {
+    /// Result of each test case
+    pub results: Vec<BenchmarkResult>,
+    /// Index initialization time of each project
+    pub projects: Vec<ProjectTiming>,
+    /// Peak resident memory of the run in bytes, if the platform reports it
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// Retrieval benchmarks loaded from a directory of test case files
 ///
-/// # Errors
-/// Returns error if test case files cannot be read or parsed
-pub async fn run_benchmarks_async(test_cases_dir: &Path) -> Result<Vec<BenchmarkResult>> {
-    let mut test_cases = Vec::new();
+/// This is the entry point shared by the `quality-bench` CLI and tests:
+///
+/// ```no_run
+/// # async fn example() -> anyhow::Result<()> {
+/// use merlin_benchmarks_quality::BenchmarkSuite;
+///
+/// let run = BenchmarkSuite::new("benchmarks/crates/quality/test_cases")
+///     .with_filter("CSS")
+///     .run()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BenchmarkSuite {
+    /// Directory searched recursively for `.toml` test cases
+    test_cases_dir: PathBuf,
+    /// Only test cases whose name contains this are run
+    name_filter: Option<String>,
+    /// Options of the run
+    options: RunOptions,
+}
 
-    for entry in WalkDir::new(test_cases_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-    {
-        if entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext == "toml")
+impl BenchmarkSuite {
+    /// Creates a suite of every test case under `test_cases_dir`
+    pub fn new(test_cases_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            test_cases_dir: test_cases_dir.into(),
+            name_filter: None,
+            options: RunOptions::default(),
+        }
+    }
+
+    /// Only runs test cases whose name contains `name`
+    #[must_use]
+    pub fn with_filter(mut self, name: impl Into<String>) -> Self {
+        self.name_filter = Some(name.into());
+        self
+    }
+
+    /// Sets the options of the run
+    #[must_use]
+    pub const fn with_options(mut self, options: RunOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Loads the test cases matching the filter, sorted by name
+    ///
+    /// Repositories of filtered-out test cases are not set up.
+    ///
+    /// # Errors
+    /// Returns an error if a test case file cannot be read or parsed, or its
+    /// repository cannot be cloned
+    pub fn load_test_cases(&self) -> Result<Vec<TestCase>> {
+        let mut test_cases = Vec::new();
+        for entry in WalkDir::new(&self.test_cases_dir)
+            .into_iter()
+            .filter_map(Result::ok)
         {
+            if !entry.file_type().is_file()
+                || entry.path().extension().is_none_or(|ext| ext != "toml")
+            {
+                continue;
+            }
             let test_case = TestCase::from_file(entry.path())
                 .with_context(|| format!("Failed to load test case: {}", entry.path().display()))?;
+            if self
+                .name_filter
+                .as_deref()
+                .is_some_and(|name| !test_case.name.contains(name))
+            {
+                continue;
+            }
 
             // Setup repository if needed
             if let Some(repo_config) = &test_case.repository {
@@ -40,66 +139,118 @@ pub async fn run_benchmarks_async(test_cases_dir: &Path) -> Result<Vec<Benchmark
                     format!("Failed to setup repository for test: {}", test_case.name)
                 })?;
             }
-
             test_cases.push(test_case);
         }
+        test_cases.sort_by(|left, right| left.name.cmp(&right.name));
+        Ok(test_cases)
     }
 
-    // Group test cases by project root to share ContextBuilder/VectorSearchManager instances
-    let mut grouped_cases: HashMap<String, Vec<TestCase>> = HashMap::new();
-    for test_case in test_cases {
-        grouped_cases
-            .entry(test_case.project_root.clone())
-            .or_default()
-            .push(test_case);
-    }
+    /// Runs the matching test cases
+    ///
+    /// Project groups run one after another so their timings do not compete for
+    /// the CPU; the test cases of a group still run in parallel. Each project is
+    /// indexed twice, cold and then warm from the caches the cold pass wrote.
+    ///
+    /// # Errors
+    /// Returns an error if the test cases cannot be loaded
+    pub async fn run(&self) -> Result<BenchmarkRun> {
+        // Group test cases by project root to share ContextBuilder/VectorSearchManager instances
+        let mut grouped_cases: HashMap<String, Vec<TestCase>> = HashMap::new();
+        for test_case in self.load_test_cases()? {
+            grouped_cases
+                .entry(test_case.project_root.clone())
+                .or_default()
+                .push(test_case);
+        }
 
-    // Spawn each project group as a parallel task
-    let mut group_tasks = JoinSet::new();
+        let mut all_results = Vec::new();
+        let mut projects = Vec::new();
+        for (project_root_str, project_cases) in grouped_cases {
+            let (timing, group_results) =
+                run_benchmarks_for_project(&project_root_str, project_cases, self.options.repeat)
+                    .await;
+            projects.push(timing);
+            all_results.extend(group_results);
+        }
+        projects.sort_by(|left, right| left.project_root.cmp(&right.project_root));
 
-    for (project_root_str, project_cases) in grouped_cases {
-        group_tasks.spawn(async move {
-            run_benchmarks_for_project(&project_root_str, project_cases).await
-        });
+        Ok(BenchmarkRun {
+            results: all_results,
+            projects,
+            peak_rss_bytes: peak_rss_bytes(),
+        })
     }
+}
 
-    let mut all_results = Vec::new();
-    while let Some(result) = group_tasks.join_next().await {
-        match result {
-            Ok(group_results) => all_results.extend(group_results),
-            Err(error) => warn!("Project group failed: {error}"),
-        }
-    }
+/// Run all benchmarks in a directory
+///
+/// # Errors
+/// Returns error if test case files cannot be read or parsed
+#[deprecated(note = "use `BenchmarkSuite::new(dir).with_options(options).run()`")]
+pub async fn run_benchmarks_async(
+    test_cases_dir: &Path,
+    options: RunOptions,
+) -> Result<BenchmarkRun> {
+    BenchmarkSuite::new(test_cases_dir)
+        .with_options(options)
+        .run()
+        .await
+}
+
+/// Creates a builder for a project and waits until its index is complete
+///
+/// Returns the builder and how long initialization took.
+async fn initialize_builder(project_root: &Path) -> (ContextBuilder, Duration) {
+    // Create builder with increased max_files for benchmarks
+    let mut builder =
+        ContextBuilder::new(project_root.to_path_buf()).with_max_files(DEFAULT_MAX_FILES);
+    let start = Instant::now();
+
+    // Run a dummy query to initialize all systems, then let indexing finish so
+    // every test case searches the full index
+    let warmup_query = Query::new("initialization");
+    let _ignored = builder.build_context(&warmup_query).await;
+    builder.wait_for_index().await;
 
-    Ok(all_results)
+    (builder, start.elapsed())
 }
 
 /// Run benchmarks for a single project with a shared `ContextBuilder`
+///
+/// The index is initialized twice: cold after clearing the project's caches,
+/// then warm from the caches the cold pass wrote. Test cases run against the
+/// warm builder.
 async fn run_benchmarks_for_project(
     project_root_str: &str,
     test_cases: Vec<TestCase>,
-) -> Vec<BenchmarkResult> {
+    repeat: usize,
+) -> (ProjectTiming, Vec<BenchmarkResult>) {
     let project_root = Path::new(project_root_str).to_path_buf();
 
-    // Create builder with increased max_files for benchmarks
-    let builder = Arc::new(Mutex::new(
-        ContextBuilder::new(project_root.clone()).with_max_files(20),
-    ));
-
-    // Warm up the builder by running a dummy query to initialize all systems
-    // This ensures embeddings are ready before parallel execution
+    let caches = cache_dir(&project_root);
+    if caches.exists()
+        && let Err(error) = remove_dir_all(&caches)
     {
-        let warmup_query = Query::new("initialization");
-        let _ignored = builder.lock().await.build_context(&warmup_query).await;
+        warn!("Failed to clear {}: {error}", caches.display());
     }
+    let (cold_builder, cold_init) = initialize_builder(&project_root).await;
+    // Dropping the builder persists the caches the warm pass starts from
+    drop(cold_builder);
+    let (warm_builder, warm_init) = initialize_builder(&project_root).await;
+    let timing = ProjectTiming {
+        project_root: project_root_str.to_owned(),
+        cold_init_ms: cold_init.as_secs_f64() * 1000.0,
+        warm_init_ms: warm_init.as_secs_f64() * 1000.0,
+    };
+    let builder = Arc::new(Mutex::new(warm_builder));
 
     // Run test cases in parallel, sharing the builder
     let mut tasks = JoinSet::new();
     for test_case in test_cases {
         let builder_clone = Arc::clone(&builder);
-        tasks.spawn(
-            async move { run_single_benchmark_with_builder(&test_case, builder_clone).await },
-        );
+        tasks.spawn(async move {
+            run_single_benchmark_with_builder(&test_case, builder_clone, repeat).await
+        });
     }
 
     let mut results = Vec::new();
@@ -110,13 +261,17 @@ async fn run_benchmarks_for_project(
         }
     }
 
-    results
+    (timing, results)
 }
 
 /// Run a single benchmark test case with a shared `ContextBuilder`
+///
+/// The query is searched `repeat` times to measure its latency; the first
+/// search's results are scored.
 async fn run_single_benchmark_with_builder(
     test_case: &TestCase,
     builder: Arc<Mutex<ContextBuilder>>,
+    repeat: usize,
 ) -> BenchmarkResult {
     let project_path = Path::new(&test_case.project_root);
     let mut logs = Vec::new();
@@ -125,12 +280,22 @@ async fn run_single_benchmark_with_builder(
     logs.push(format!("Query: {}", test_case.query));
     logs.push(format!("Project: {}", project_path.display()));
 
-    let results = perform_search_with_builder(&test_case.query, project_path, builder)
-        .await
-        .unwrap_or_else(|err| {
-            logs.push(format!("❌ Search failed: {err}"));
-            Vec::new()
-        });
+    let mut results = None;
+    let mut samples = Vec::with_capacity(repeat);
+    for _ in 0..repeat.max(1) {
+        match perform_search_with_builder(test_case, Arc::clone(&builder)).await {
+            Ok((paths, elapsed)) => {
+                samples.push(elapsed);
+                results.get_or_insert(paths);
+            }
+            Err(err) => {
+                logs.push(format!("❌ Search failed: {err}"));
+                break;
+            }
+        }
+    }
+    let results = results.unwrap_or_default();
+    let latency = LatencyStats::from_samples(&samples);
 
     let num_results = results.len();
     logs.push(format!("✓ Found {num_results} results"));
@@ -142,13 +307,27 @@ async fn run_single_benchmark_with_builder(
         }
     }
 
-    let metrics = BenchmarkMetrics::calculate(&results, &test_case.expected);
+    logs.push(format!(
+        "Search latency over {} run(s): p50 {:.1} ms, p95 {:.1} ms",
+        latency.runs, latency.p50_ms, latency.p95_ms
+    ));
+
+    let metrics = BenchmarkMetrics::calculate(&results, &test_case.expected, &test_case.forbidden);
+    let forbidden_hits = BenchmarkMetrics::forbidden_hits(&results, &test_case.forbidden);
+    for hit in &forbidden_hits {
+        logs.push(format!(
+            "⚠ Forbidden file at rank {}: {} ({})",
+            hit.rank, hit.path, hit.reason
+        ));
+    }
 
     BenchmarkResult {
         name: test_case.name.clone(),
         query: test_case.query.clone(),
         results,
         metrics,
+        forbidden_hits,
+        latency,
         logs,
     }
 }
@@ -164,216 +343,107 @@ pub struct BenchmarkResult {
     pub results: Vec<String>,
     /// Calculated metrics
     pub metrics: BenchmarkMetrics,
+    /// Forbidden files among the top results
+    pub forbidden_hits: Vec<ForbiddenHit>,
+    /// Search latency over the repetitions of the query
+    pub latency: LatencyStats,
     /// Execution logs
     pub logs: Vec<String>,
 }
 
+/// Applies the context settings of a test case to the shared builder
+///
+/// Settings the test case does not override are reset to the run's defaults,
+/// so overrides of one test case never leak into the next.
+fn apply_overrides(builder: &mut ContextBuilder, overrides: ContextOverrides) {
+    builder.set_max_files(overrides.max_files.unwrap_or(DEFAULT_MAX_FILES));
+    builder.set_token_budget(overrides.token_budget.unwrap_or(MAX_CONTEXT_TOKENS));
+    builder.set_rerank(overrides.rerank.unwrap_or(true));
+}
+
 /// Perform actual context search using merlin-context with a shared builder
 ///
+/// Returns the retrieved paths and how long the search took, excluding the
+/// time spent waiting for the builder.
+///
 /// # Errors
 /// Returns error if context building fails
 async fn perform_search_with_builder(
-    query: &str,
-    project_root: &Path,
+    test_case: &TestCase,
     builder: Arc<Mutex<ContextBuilder>>,
-) -> Result<Vec<String>> {
+) -> Result<(Vec<String>, Duration)> {
+    let project_root = Path::new(&test_case.project_root);
     if !project_root.exists() {
         return Err(anyhow!("Failed to find project {}", project_root.display()));
     }
 
-    let query_obj = Query::new(query);
+    let query_obj = Query::new(&test_case.query);
 
     // Lock the builder for this search operation and build context
-    let context = {
+    let (context, elapsed) = {
         let mut builder_guard = builder.lock().await;
-        builder_guard.build_context(&query_obj).await?
+        apply_overrides(&mut builder_guard, test_case.context);
+        let start = Instant::now();
+        let context = builder_guard.build_context(&query_obj).await?;
+        (context, start.elapsed())
     };
 
     let paths: Vec<String> = context
         .files
         .iter()
         .map(|file: &FileContext| {
-            file.path
-                .strip_prefix(project_root)
-                .unwrap_or(&file.path)
-                .to_string_lossy()
-                .to_string()
-                .replace('\\', "/") // Normalize to forward slashes for cross-platform consistency
+            // Forward slashes keep paths comparable across platforms
+            display_path(file.path.strip_prefix(project_root).unwrap_or(&file.path))
         })
         .collect();
 
-    Ok(paths)
-}
-
-/// Setup repository by cloning if needed and checking out specific commit
-///
-/// # Errors
-/// Returns error if git commands fail
-fn setup_repository(project_root: &str, config: &test_case::RepositoryConfig) -> Result<()> {
-    let repo_path = PathBuf::from(project_root);
-
-    // Clone if repository doesn't exist
-    if !repo_path.exists() {
-        tracing::info!("Cloning repository: {} -> {}", config.url, project_root);
-
-        // Create parent directory if needed
-        if let Some(parent) = repo_path.parent() {
-            create_dir_all(parent).with_context(|| {
-                format!("Failed to create parent directory: {}", parent.display())
-            })?;
-        }
-
-        let clone_output = Command::new("git")
-            .args(["clone", &config.url, project_root])
-            .output()
-            .context("Failed to execute git clone")?;
-
-        if !clone_output.status.success() {
-            let stderr = String::from_utf8_lossy(&clone_output.stderr);
-            bail!("Git clone failed: {stderr}");
-        }
-    }
-
-    // Verify it's a git repository
-    if !repo_path.join(".git").exists() {
-        bail!("Directory exists but is not a git repository: {project_root}");
-    }
-
-    // Stash any local changes
-    let stash_output = Command::new("git")
-        .current_dir(&repo_path)
-        .args(["stash", "push", "-u", "-m", "Quality benchmark auto-stash"])
-        .output()
-        .context("Failed to execute git stash")?;
-
-    if !stash_output.status.success() {
-        let stderr = String::from_utf8_lossy(&stash_output.stderr);
-        warn!("git stash failed (might be nothing to stash): {stderr}");
-    }
-
-    // Checkout the specific commit
-    tracing::info!("Checking out commit: {} in {}", config.commit, project_root);
-    let checkout_output = Command::new("git")
-        .current_dir(&repo_path)
-        .args(["checkout", &config.commit])
-        .output()
-        .context("Failed to execute git checkout")?;
-
-    if !checkout_output.status.success() {
-        let stderr = String::from_utf8_lossy(&checkout_output.stderr);
-        bail!("Git checkout failed: {stderr}");
-    }
-
-    Ok(())
+    Ok((paths, elapsed))
 }
 
-/// Generate summary section of the report
-fn generate_summary_section(aggregate: &AggregateMetrics) -> String {
-    use std::fmt::Write as _;
-
-    let mut section = String::from("## Summary\n\n");
-    _ = writeln!(section, "**Test Cases**: {}\n", aggregate.test_count);
-    section.push_str("| Metric | Value | Target |\n");
-    section.push_str("|--------|-------|--------|\n");
-    _ = writeln!(
-        section,
-        "| Precision@3 | {:.1}% | 60% |",
-        aggregate.avg_precision_at_3
-    );
-    _ = writeln!(
-        section,
-        "| Precision@10 | {:.1}% | 55% |",
-        aggregate.avg_precision_at_10
-    );
-    _ = writeln!(
-        section,
-        "| Recall@10 | {:.1}% | 70% |",
-        aggregate.avg_recall_at_10
-    );
-    _ = writeln!(section, "| MRR | {:.3} | 0.700 |", aggregate.avg_mrr);
-    _ = writeln!(
-        section,
-        "| NDCG@10 | {:.3} | 0.750 |",
-        aggregate.avg_ndcg_at_10
-    );
-    _ = writeln!(
-        section,
-        "| Critical in Top-3 | {:.1}% | 65% |\n",
-        aggregate.avg_critical_in_top_3
-    );
-
-    section
-}
-
-/// Generate individual result section
-fn generate_result_section(result: &BenchmarkResult) -> String {
-    use std::fmt::Write as _;
-
-    let mut section = String::default();
-    _ = writeln!(section, "### {}\n", result.name);
-    _ = writeln!(section, "**Query**: \"{}\"\n", result.query);
-    section.push_str("| Metric | Value |\n");
-    section.push_str("|--------|-------|\n");
-    _ = writeln!(
-        section,
-        "| Precision@3 | {:.1}% |",
-        result.metrics.precision_at_3
-    );
-    _ = writeln!(
-        section,
-        "| Precision@10 | {:.1}% |",
-        result.metrics.precision_at_10
-    );
-    _ = writeln!(
-        section,
-        "| Recall@10 | {:.1}% |",
-        result.metrics.recall_at_10
-    );
-    _ = writeln!(section, "| MRR | {:.3} |", result.metrics.mrr);
-    _ = writeln!(section, "| NDCG@10 | {:.3} |", result.metrics.ndcg_at_10);
-    _ = writeln!(
-        section,
-        "| Critical in Top-3 | {:.1}% |\n",
-        result.metrics.critical_in_top_3
-    );
-
-    section.push_str("**Top 10 Results**:\n");
-    for (index, path) in result.results.iter().take(10).enumerate() {
-        _ = writeln!(section, "{}. `{}`", index + 1, path);
-    }
-    section.push('\n');
-
-    // Add execution logs
-    if !result.logs.is_empty() {
-        section.push_str("<details>\n");
-        section.push_str("<summary>Execution Logs</summary>\n\n");
-        section.push_str("```\n");
-        for log in &result.logs {
-            _ = writeln!(section, "{log}");
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+    use tempfile::TempDir;
+
+    /// Tests that the suite loads only test cases matching its filter, sorted by name.
+    ///
+    /// # Errors
+    /// Returns an error if the test case files cannot be written or loaded.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_load_test_cases_applies_filter() -> Result<()> {
+        let dir = TempDir::new()?;
+        create_dir_all(dir.path().join("nested"))?;
+        for (file, name) in [
+            ("grid.toml", "CSS Grid"),
+            ("nested/flex.toml", "CSS Flexbox"),
+            ("parser.toml", "HTML Parser"),
+        ] {
+            write(
+                dir.path().join(file),
+                format!("name = \"{name}\"\nquery = \"layout\"\nproject_root = \"valor\"\n"),
+            )?;
         }
-        section.push_str("```\n");
-        section.push_str("</details>\n\n");
-    }
-
-    section
-}
-
-/// Generate markdown report from benchmark results
-pub fn generate_report(results: &[BenchmarkResult]) -> String {
-    let mut report = String::from("# Context Quality Benchmark Results\n\n");
-
-    let metrics: Vec<_> = results
-        .iter()
-        .map(|result| result.metrics.clone())
-        .collect();
-    let aggregate = AggregateMetrics::from_metrics(&metrics);
-
-    report.push_str(&generate_summary_section(&aggregate));
-    report.push_str("## Individual Test Results\n\n");
-
-    for result in results {
-        report.push_str(&generate_result_section(result));
+        write(dir.path().join("notes.md"), "not a test case")?;
+
+        let names = |suite: &BenchmarkSuite| -> Result<Vec<String>> {
+            Ok(suite
+                .load_test_cases()?
+                .into_iter()
+                .map(|test_case| test_case.name)
+                .collect())
+        };
+        assert_eq!(
+            names(&BenchmarkSuite::new(dir.path()))?,
+            ["CSS Flexbox", "CSS Grid", "HTML Parser"]
+        );
+        assert_eq!(
+            names(&BenchmarkSuite::new(dir.path()).with_filter("CSS"))?,
+            ["CSS Flexbox", "CSS Grid"]
+        );
+        Ok(())
     }
-
-    report
 }
@@ -1,20 +1,79 @@
 //! Quality benchmark CLI for context retrieval system.
 
-use std::fs::write;
-use std::path::PathBuf;
+use std::fs::{read_to_string, write};
+use std::io::{Write as _, stdout};
+use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::str::FromStr;
 
-use anyhow::{Context as _, Result};
-use merlin_benchmarks_quality::{generate_report, run_benchmarks_async};
+use anyhow::{Context as _, Error, Result, bail};
+use merlin_benchmarks_quality::agent::report::AgentReport;
+use merlin_benchmarks_quality::agent::tool_calls::ToolCallLayer;
+use merlin_benchmarks_quality::agent::{
+    AgentRunOptions, DEFAULT_TASK_TIMEOUT, run_agent_benchmarks,
+};
+use merlin_benchmarks_quality::baseline::{
+    ComparableReport, DEFAULT_TOLERANCE, JsonReport, compare,
+};
+use merlin_benchmarks_quality::performance::{DEFAULT_SLOW_QUERY_MS, PerformanceSummary};
+use merlin_benchmarks_quality::{BenchmarkResult, BenchmarkSuite, RunOptions, generate_report};
 use pico_args::Arguments;
-use tracing::{Level, info};
-use tracing_subscriber::{EnvFilter, fmt};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{from_str, to_string_pretty};
+use std::time::Duration;
+use tracing::{Level, info, warn};
+use tracing_subscriber::layer::SubscriberExt as _;
+use tracing_subscriber::util::SubscriberInitExt as _;
+use tracing_subscriber::{EnvFilter, Layer as _, fmt, registry};
+
+/// Test cases run when `--test-cases` is not given
+const DEFAULT_TEST_CASES: &str = "benchmarks/crates/quality/test_cases";
+
+/// Agent tasks run with `--agent` when `--test-cases` is not given
+const DEFAULT_AGENT_TASKS: &str = "benchmarks/crates/quality/agent_tasks";
+
+/// Baseline written by `--save-baseline` when `--baseline` is not given
+const DEFAULT_BASELINE: &str = "benchmarks/crates/quality/baseline.json";
+
+/// Baseline written by `--agent --save-baseline` when `--baseline` is not given
+const DEFAULT_AGENT_BASELINE: &str = "benchmarks/crates/quality/agent_baseline.json";
+
+/// Format of the report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human-readable markdown tables
+    Markdown,
+    /// Per-test and aggregate metrics as JSON
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        match value {
+            "markdown" | "md" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            other => bail!("Unknown format '{other}', expected 'markdown' or 'json'"),
+        }
+    }
+}
 
 struct Args {
     test_cases: PathBuf,
     output: Option<PathBuf>,
     name: Option<String>,
     verbose: bool,
+    format: OutputFormat,
+    baseline: Option<PathBuf>,
+    save_baseline: bool,
+    tolerance: f64,
+    repeat: usize,
+    slow_query_ms: f64,
+    agent: bool,
+    model: Option<String>,
+    task_timeout: Duration,
 }
 
 impl Args {
@@ -30,18 +89,48 @@ impl Args {
             exit(0);
         }
 
+        let agent = pargs.contains("--agent");
         let args = Self {
             test_cases: pargs
                 .opt_value_from_str(["-t", "--test-cases"])?
-                .unwrap_or_else(|| PathBuf::from("benchmarks/crates/quality/test_cases")),
+                .unwrap_or_else(|| {
+                    PathBuf::from(if agent {
+                        DEFAULT_AGENT_TASKS
+                    } else {
+                        DEFAULT_TEST_CASES
+                    })
+                }),
             output: pargs.opt_value_from_str(["-o", "--output"])?,
             name: pargs.opt_value_from_str(["-n", "--name"])?,
             verbose: pargs.contains(["-v", "--verbose"]),
+            format: pargs
+                .opt_value_from_str(["-f", "--format"])?
+                .unwrap_or(OutputFormat::Markdown),
+            baseline: pargs.opt_value_from_str(["-b", "--baseline"])?,
+            save_baseline: pargs.contains("--save-baseline"),
+            tolerance: pargs
+                .opt_value_from_str("--tolerance")?
+                .unwrap_or(DEFAULT_TOLERANCE),
+            repeat: pargs.opt_value_from_str(["-r", "--repeat"])?.unwrap_or(1),
+            slow_query_ms: pargs
+                .opt_value_from_str("--slow-query-ms")?
+                .unwrap_or(DEFAULT_SLOW_QUERY_MS),
+            agent,
+            model: pargs.opt_value_from_str("--model")?,
+            task_timeout: pargs
+                .opt_value_from_str("--task-timeout")?
+                .map_or(DEFAULT_TASK_TIMEOUT, Duration::from_secs),
         };
+        if args.repeat == 0 {
+            bail!("--repeat must be at least 1");
+        }
+        if args.model.is_some() && !args.agent {
+            bail!("--model requires --agent");
+        }
 
         let remaining = pargs.finish();
         if !remaining.is_empty() {
-            tracing::warn!("Unexpected arguments: {remaining:?}");
+            warn!("Unexpected arguments: {remaining:?}");
         }
 
         Ok(args)
@@ -56,9 +145,30 @@ fn print_help() {
     info!("");
     info!("OPTIONS:");
     info!("    -t, --test-cases <PATH>      Directory containing test case TOML files");
-    info!("                                 [default: benchmarks/crates/quality/test_cases]");
-    info!("    -o, --output <PATH>          Output file for results (markdown format)");
+    info!("                                 [default: {DEFAULT_TEST_CASES}, or");
+    info!("                                 {DEFAULT_AGENT_TASKS} with --agent]");
+    info!("    -o, --output <PATH>          Output file for results");
+    info!("    -f, --format <FORMAT>        Report format: markdown or json [default: markdown]");
     info!("    -n, --name <NAME>            Run specific test case by name");
+    info!("    -b, --baseline <PATH>        Fail if metrics regressed against this JSON baseline");
+    info!("        --tolerance <DROP>       Largest tolerated drop of an aggregate metric, 0-1");
+    info!("                                 [default: {DEFAULT_TOLERANCE}]");
+    info!("        --save-baseline          Save this run as the baseline instead of comparing");
+    info!("                                 [default path: {DEFAULT_BASELINE}, or");
+    info!("                                 {DEFAULT_AGENT_BASELINE} with --agent]");
+    info!(
+        "    -r, --repeat <N>             Search each query N times to measure latency [default: 1]"
+    );
+    info!("        --slow-query-ms <MS>     Flag queries whose p95 latency exceeds MS");
+    info!("                                 [default: {DEFAULT_SLOW_QUERY_MS}]");
+    info!("        --agent                  Run end-to-end agent tasks instead of retrieval cases");
+    info!("        --model <NAME>           Answer agent tasks with this local Ollama model");
+    info!("                                 instead of their scripted responses");
+    info!("        --task-timeout <SECS>    Fail agent tasks running longer than SECS");
+    info!(
+        "                                 [default: {}]",
+        DEFAULT_TASK_TIMEOUT.as_secs()
+    );
     info!("    -v, --verbose                Show verbose output");
     info!("    -h, --help                   Print help information");
 }
@@ -72,66 +182,222 @@ fn print_help() {
 /// Panics if assertions fail during test execution.
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing subscriber to see debug logs
-    fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive(Level::INFO.into()))
+    // Initialize tracing subscriber to see debug logs; tool calls are counted regardless of the filter
+    registry()
+        .with(
+            fmt::layer()
+                .with_filter(EnvFilter::from_default_env().add_directive(Level::INFO.into())),
+        )
+        .with(ToolCallLayer)
         .init();
 
     let args = Args::parse()?;
+    if args.agent {
+        return run_agent_mode(&args).await;
+    }
 
     info!("Running context quality benchmarks...");
     info!("Test cases directory: {}", args.test_cases.display());
     info!("");
 
-    let results = run_benchmarks_async(&args.test_cases)
-        .await
-        .context("Failed to run benchmarks")?;
+    let mut suite = BenchmarkSuite::new(&args.test_cases).with_options(RunOptions {
+        repeat: args.repeat,
+    });
+    if let Some(name_filter) = &args.name {
+        suite = suite.with_filter(name_filter);
+    }
+    let run = suite.run().await.context("Failed to run benchmarks")?;
+    let results = run.results;
 
     if results.is_empty() {
-        info!("No test cases found in {}", args.test_cases.display());
+        if let Some(name_filter) = &args.name {
+            info!("No test cases matched the filter '{name_filter}'");
+        } else {
+            info!("No test cases found in {}", args.test_cases.display());
+        }
         return Ok(());
     }
 
-    let filtered_results: Vec<_> = if let Some(name_filter) = &args.name {
-        results
-            .into_iter()
-            .filter(|result| result.name.contains(name_filter))
-            .collect()
-    } else {
-        results
+    let performance = PerformanceSummary::new(
+        &results,
+        run.projects,
+        run.peak_rss_bytes,
+        args.slow_query_ms,
+    );
+    for name in &performance.slow_queries {
+        warn!("Slow query: {name} (p95 above {} ms)", args.slow_query_ms);
+    }
+    for result in &results {
+        for hit in &result.forbidden_hits {
+            warn!(
+                "Forbidden file: {} retrieved {} at rank {} ({})",
+                result.name, hit.path, hit.rank, hit.reason
+            );
+        }
+    }
+    let json_report = JsonReport::from_results(&results, performance);
+    let report = match args.format {
+        OutputFormat::Markdown => generate_report(&results, &json_report.performance),
+        OutputFormat::Json => to_string_pretty(&json_report)?,
     };
+    write_report(&args, &report)?;
+
+    if args.verbose {
+        log_detailed_results(&results);
+    }
+
+    save_or_check_baseline(&args, &json_report, DEFAULT_BASELINE)
+}
+
+/// Runs the end-to-end agent tasks and reports them like retrieval cases
+///
+/// # Errors
+/// Returns an error if the tasks cannot be run, the report cannot be written
+/// or a metric regressed against the baseline.
+async fn run_agent_mode(args: &Args) -> Result<()> {
+    info!("Running agent benchmarks...");
+    info!("Tasks directory: {}", args.test_cases.display());
+    info!(
+        "Model: {}",
+        args.model.as_deref().unwrap_or("scripted responses")
+    );
+    info!("");
 
-    if filtered_results.is_empty() {
-        info!("No test cases matched the filter");
+    let options = AgentRunOptions {
+        model: args.model.clone(),
+        timeout: args.task_timeout,
+    };
+    let results: Vec<_> = run_agent_benchmarks(&args.test_cases, &options)
+        .await
+        .context("Failed to run agent benchmarks")?
+        .into_iter()
+        .filter(|result| {
+            args.name
+                .as_ref()
+                .is_none_or(|name_filter| result.name.contains(name_filter))
+        })
+        .collect();
+    if results.is_empty() {
+        info!("No agent tasks to run in {}", args.test_cases.display());
         return Ok(());
     }
+    for result in &results {
+        for failure in &result.failures {
+            warn!("Agent task {} failed: {failure}", result.name);
+        }
+    }
+
+    let agent_report = AgentReport::from_results(&results);
+    let report = match args.format {
+        OutputFormat::Markdown => agent_report.to_markdown(),
+        OutputFormat::Json => to_string_pretty(&agent_report)?,
+    };
+    write_report(args, &report)?;
 
-    let report = generate_report(&filtered_results);
+    save_or_check_baseline(args, &agent_report, DEFAULT_AGENT_BASELINE)
+}
+
+/// Saves the run as the baseline, or compares it with the given baseline
+///
+/// # Errors
+/// Returns an error if the baseline cannot be written or read, or a metric
+/// regressed beyond the tolerance.
+fn save_or_check_baseline<R>(args: &Args, report: &R, default_path: &str) -> Result<()>
+where
+    R: ComparableReport + Serialize + DeserializeOwned,
+{
+    if args.save_baseline {
+        let baseline_path = args
+            .baseline
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(default_path));
+        write(&baseline_path, to_string_pretty(report)?)
+            .with_context(|| format!("Failed to write baseline to {}", baseline_path.display()))?;
+        info!("Baseline written to: {}", baseline_path.display());
+    } else if let Some(baseline_path) = &args.baseline {
+        check_baseline(baseline_path, report, args.tolerance)?;
+    }
+    Ok(())
+}
 
+/// Writes a rendered report to the output file, or stdout
+///
+/// # Errors
+/// Returns an error if the report cannot be written.
+fn write_report(args: &Args, report: &str) -> Result<()> {
     if let Some(output_path) = &args.output {
-        write(output_path, &report)
+        write(output_path, report)
             .with_context(|| format!("Failed to write report to {}", output_path.display()))?;
         info!("Report written to: {}", output_path.display());
+    } else if args.format == OutputFormat::Json {
+        // Keep JSON free of log prefixes so it can be piped
+        let mut out = stdout().lock();
+        out.write_all(report.as_bytes())?;
+        out.write_all(b"\n")?;
     } else {
         info!("{report}");
     }
+    Ok(())
+}
 
-    if args.verbose {
-        info!("\nDetailed Results:");
-        for result in &filtered_results {
-            info!("\n{}", "=".repeat(60));
-            info!("Test: {}", result.name);
-            info!("Query: {}", result.query);
-            info!("Results count: {}", result.results.len());
-            info!("Metrics:");
-            info!("  P@3:  {:.1}%", result.metrics.precision_at_3);
-            info!("  P@10: {:.1}%", result.metrics.precision_at_10);
-            info!("  R@10: {:.1}%", result.metrics.recall_at_10);
-            info!("  MRR:  {:.3}", result.metrics.mrr);
-            info!("  NDCG: {:.3}", result.metrics.ndcg_at_10);
-            info!("  Crit: {:.1}%", result.metrics.critical_in_top_3);
-        }
+/// Logs the metrics and latency of every test case
+fn log_detailed_results(results: &[BenchmarkResult]) {
+    info!("\nDetailed Results:");
+    for result in results {
+        info!("\n{}", "=".repeat(60));
+        info!("Test: {}", result.name);
+        info!("Query: {}", result.query);
+        info!("Results count: {}", result.results.len());
+        info!("Metrics:");
+        info!("  P@3:  {:.1}%", result.metrics.precision_at_3);
+        info!("  P@10: {:.1}%", result.metrics.precision_at_10);
+        info!("  R@10: {:.1}%", result.metrics.recall_at_10);
+        info!("  MRR:  {:.3}", result.metrics.mrr);
+        info!("  NDCG: {:.3}", result.metrics.ndcg_at_10);
+        info!("  Crit: {:.1}%", result.metrics.critical_in_top_3);
+        info!("  Forb: {:.1}%", result.metrics.forbidden_hit_rate);
+        info!(
+            "  Latency: p50 {:.1} ms, p95 {:.1} ms over {} run(s)",
+            result.latency.p50_ms, result.latency.p95_ms, result.latency.runs
+        );
     }
+}
 
+/// Compares the run with a saved baseline and fails on regressions
+///
+/// # Errors
+/// Returns an error if the baseline cannot be read or an aggregate metric
+/// worsened by more than `tolerance`.
+fn check_baseline<R>(baseline_path: &Path, current: &R, tolerance: f64) -> Result<()>
+where
+    R: ComparableReport + DeserializeOwned,
+{
+    let source = read_to_string(baseline_path)
+        .with_context(|| format!("Failed to read baseline {}", baseline_path.display()))?;
+    let baseline: R = from_str(&source)
+        .with_context(|| format!("Invalid baseline {}", baseline_path.display()))?;
+    let comparison = compare(&baseline, current, tolerance);
+
+    info!("");
+    info!(
+        "Changes against {} ({} test cases in both runs):",
+        baseline_path.display(),
+        comparison.common_count
+    );
+    info!("\n{}", comparison.delta_table());
+
+    if comparison.has_regressions() {
+        for regression in &comparison.regressions {
+            info!(
+                "Regression: {} went from {:.3} to {:.3}",
+                regression.metric, regression.baseline, regression.current
+            );
+        }
+        bail!(
+            "{} aggregate metric(s) regressed beyond tolerance {tolerance}",
+            comparison.regressions.len()
+        );
+    }
+    info!("No aggregate metric regressed beyond tolerance {tolerance}");
     Ok(())
 }
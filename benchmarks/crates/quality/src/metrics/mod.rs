@@ -3,6 +3,11 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 
+use serde::{Deserialize, Serialize};
+
+/// Number of top results checked for forbidden files
+const FORBIDDEN_CUTOFF: usize = 10;
+
 /// Priority level for expected files
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Priority {
@@ -35,12 +40,57 @@ pub struct ExpectedFile {
     pub path: String,
     /// Priority level
     pub priority: Priority,
+    /// Relevance used for NDCG instead of the priority's score
+    pub weight: Option<f64>,
     /// Reason for relevance
     pub reason: String,
 }
 
+impl ExpectedFile {
+    /// Relevance of the file for NDCG: its weight if set, otherwise its priority's score
+    pub fn relevance(&self) -> f64 {
+        self.weight
+            .unwrap_or_else(|| self.priority.to_relevance_score())
+    }
+}
+
+/// File or directory that should not be retrieved for a query
+#[derive(Debug, Clone, Deserialize)]
+pub struct ForbiddenFile {
+    /// File path, or directory path matching every file under it
+    pub path: String,
+    /// Reason the file is irrelevant
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl ForbiddenFile {
+    /// Returns true if the normalized result path is this file or lies under this directory
+    fn matches(&self, result: &str) -> bool {
+        let forbidden = BenchmarkMetrics::normalize_path(&self.path);
+        let forbidden = forbidden.trim_end_matches('/');
+        result == forbidden
+            || result
+                .strip_prefix(forbidden)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// A forbidden file found among the top results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForbiddenHit {
+    /// 1-based rank of the result
+    pub rank: usize,
+    /// Retrieved path
+    pub path: String,
+    /// Forbidden entry the path matched
+    pub forbidden: String,
+    /// Reason the entry is forbidden
+    pub reason: String,
+}
+
 /// Benchmark metrics for a single test case
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkMetrics {
     /// Precision at 3 (% of top 3 results that are relevant)
     pub precision_at_3: f64,
@@ -54,6 +104,9 @@ pub struct BenchmarkMetrics {
     pub ndcg_at_10: f64,
     /// Percentage of critical files in top 3
     pub critical_in_top_3: f64,
+    /// Percentage of top 10 results that are forbidden (lower is better)
+    #[serde(default)]
+    pub forbidden_hit_rate: f64,
 }
 
 impl BenchmarkMetrics {
@@ -62,8 +115,12 @@ impl BenchmarkMetrics {
         path.replace('\\', "/")
     }
 
-    /// Calculate metrics from results and expected files
-    pub fn calculate(results: &[String], expected: &[ExpectedFile]) -> Self {
+    /// Calculate metrics from results, expected files and forbidden files
+    pub fn calculate(
+        results: &[String],
+        expected: &[ExpectedFile],
+        forbidden: &[ForbiddenFile],
+    ) -> Self {
         // Normalize all paths to use forward slashes for consistent comparison
         let expected_paths: HashSet<_> = expected
             .iter()
@@ -87,6 +144,8 @@ impl BenchmarkMetrics {
         let mrr = Self::mean_reciprocal_rank(&normalized_results, &expected_paths);
         let ndcg_at_10 = Self::ndcg_at_k(&normalized_results, expected, 10);
         let critical_in_top_3 = Self::critical_in_top_k(&normalized_results, &critical_paths, 3);
+        let forbidden_hit_rate =
+            Self::forbidden_hit_rate(&normalized_results, forbidden, FORBIDDEN_CUTOFF);
 
         Self {
             precision_at_3,
@@ -95,7 +154,41 @@ impl BenchmarkMetrics {
             mrr,
             ndcg_at_10,
             critical_in_top_3,
+            forbidden_hit_rate,
+        }
+    }
+
+    /// Returns the forbidden files among the top results, best ranked first
+    pub fn forbidden_hits(results: &[String], forbidden: &[ForbiddenFile]) -> Vec<ForbiddenHit> {
+        results
+            .iter()
+            .take(FORBIDDEN_CUTOFF)
+            .enumerate()
+            .filter_map(|(index, result)| {
+                let path = Self::normalize_path(result);
+                let entry = forbidden.iter().find(|entry| entry.matches(&path))?;
+                Some(ForbiddenHit {
+                    rank: index + 1,
+                    path,
+                    forbidden: entry.path.clone(),
+                    reason: entry.reason.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Calculate percentage of top k results that are forbidden
+    fn forbidden_hit_rate(results: &[String], forbidden: &[ForbiddenFile], cutoff: usize) -> f64 {
+        let checked = cutoff.min(results.len());
+        if checked == 0 || forbidden.is_empty() {
+            return 0.0;
         }
+        let hit_count = results
+            .iter()
+            .take(cutoff)
+            .filter(|res| forbidden.iter().any(|entry| entry.matches(res)))
+            .count();
+        (hit_count as f64 / checked as f64) * 100.0
     }
 
     /// Calculate precision at k
@@ -152,7 +245,7 @@ impl BenchmarkMetrics {
             .map(|(index, result)| {
                 let relevance = expected_map
                     .get(result.as_str())
-                    .map_or(0.0, |exp| exp.priority.to_relevance_score());
+                    .map_or(0.0, |exp| exp.relevance());
                 relevance / ((index + 2) as f64).log2()
             })
             .sum()
@@ -160,10 +253,7 @@ impl BenchmarkMetrics {
 
     /// Calculate ideal DCG at k (best possible ordering)
     fn ideal_dcg_at_k(expected: &[ExpectedFile], cutoff: usize) -> f64 {
-        let mut relevances: Vec<_> = expected
-            .iter()
-            .map(|exp| exp.priority.to_relevance_score())
-            .collect();
+        let mut relevances: Vec<_> = expected.iter().map(ExpectedFile::relevance).collect();
         relevances.sort_by(|left, right| right.partial_cmp(left).unwrap_or(Ordering::Equal));
 
         relevances
@@ -186,7 +276,7 @@ impl BenchmarkMetrics {
 }
 
 /// Aggregate metrics across multiple test cases
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregateMetrics {
     /// Average precision at 3
     pub avg_precision_at_3: f64,
@@ -200,6 +290,9 @@ pub struct AggregateMetrics {
     pub avg_ndcg_at_10: f64,
     /// Average critical in top 3
     pub avg_critical_in_top_3: f64,
+    /// Average forbidden hit rate
+    #[serde(default)]
+    pub avg_forbidden_hit_rate: f64,
     /// Number of test cases
     pub test_count: usize,
 }
@@ -216,6 +309,7 @@ impl AggregateMetrics {
                 avg_mrr: 0.0,
                 avg_ndcg_at_10: 0.0,
                 avg_critical_in_top_3: 0.0,
+                avg_forbidden_hit_rate: 0.0,
                 test_count: 0,
             };
         }
@@ -226,6 +320,7 @@ impl AggregateMetrics {
         let sum_mrr: f64 = metrics.iter().map(|metric| metric.mrr).sum();
         let sum_ndcg: f64 = metrics.iter().map(|metric| metric.ndcg_at_10).sum();
         let sum_critical: f64 = metrics.iter().map(|metric| metric.critical_in_top_3).sum();
+        let sum_forbidden: f64 = metrics.iter().map(|metric| metric.forbidden_hit_rate).sum();
 
         let count = test_count as f64;
 
@@ -236,89 +331,11 @@ impl AggregateMetrics {
             avg_mrr: sum_mrr / count,
             avg_ndcg_at_10: sum_ndcg / count,
             avg_critical_in_top_3: sum_critical / count,
+            avg_forbidden_hit_rate: sum_forbidden / count,
             test_count,
         }
     }
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Tests precision metric calculation.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_precision_calculation() {
-        let results = vec![
-            "file1.rs".to_owned(),
-            "file2.rs".to_owned(),
-            "file3.rs".to_owned(),
-        ];
-        let expected = vec![
-            ExpectedFile {
-                path: "file1.rs".to_owned(),
-                priority: Priority::Critical,
-                reason: "test".to_owned(),
-            },
-            ExpectedFile {
-                path: "file3.rs".to_owned(),
-                priority: Priority::High,
-                reason: "test".to_owned(),
-            },
-        ];
-
-        let metrics = BenchmarkMetrics::calculate(&results, &expected);
-        assert!((metrics.precision_at_3 - 66.67).abs() < 0.1);
-    }
-
-    /// Tests recall metric calculation.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_recall_calculation() {
-        let results = vec![
-            "file1.rs".to_owned(),
-            "file2.rs".to_owned(),
-            "file3.rs".to_owned(),
-        ];
-        let expected = vec![
-            ExpectedFile {
-                path: "file1.rs".to_owned(),
-                priority: Priority::Critical,
-                reason: "test".to_owned(),
-            },
-            ExpectedFile {
-                path: "file4.rs".to_owned(),
-                priority: Priority::High,
-                reason: "test".to_owned(),
-            },
-        ];
-
-        let metrics = BenchmarkMetrics::calculate(&results, &expected);
-        assert!((metrics.recall_at_10 - 50.0).abs() < f64::EPSILON);
-    }
-
-    /// Tests mean reciprocal rank (MRR) calculation.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_mrr_calculation() {
-        let results = vec![
-            "file1.rs".to_owned(),
-            "file2.rs".to_owned(),
-            "file3.rs".to_owned(),
-        ];
-        let expected = vec![ExpectedFile {
-            path: "file2.rs".to_owned(),
-            priority: Priority::Critical,
-            reason: "test".to_owned(),
-        }];
-
-        let metrics = BenchmarkMetrics::calculate(&results, &expected);
-        assert!((metrics.mrr - 0.5).abs() < f64::EPSILON);
-    }
-}
+mod tests;
@@ -0,0 +1,161 @@
+//! Tests for the retrieval metrics
+
+use super::*;
+
+/// Tests precision metric calculation.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_precision_calculation() {
+    let results = vec![
+        "file1.rs".to_owned(),
+        "file2.rs".to_owned(),
+        "file3.rs".to_owned(),
+    ];
+    let expected = vec![
+        ExpectedFile {
+            path: "file1.rs".to_owned(),
+            priority: Priority::Critical,
+            weight: None,
+            reason: "test".to_owned(),
+        },
+        ExpectedFile {
+            path: "file3.rs".to_owned(),
+            priority: Priority::High,
+            weight: None,
+            reason: "test".to_owned(),
+        },
+    ];
+
+    let metrics = BenchmarkMetrics::calculate(&results, &expected, &[]);
+    assert!((metrics.precision_at_3 - 66.67).abs() < 0.1);
+}
+
+/// Tests recall metric calculation.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_recall_calculation() {
+    let results = vec![
+        "file1.rs".to_owned(),
+        "file2.rs".to_owned(),
+        "file3.rs".to_owned(),
+    ];
+    let expected = vec![
+        ExpectedFile {
+            path: "file1.rs".to_owned(),
+            priority: Priority::Critical,
+            weight: None,
+            reason: "test".to_owned(),
+        },
+        ExpectedFile {
+            path: "file4.rs".to_owned(),
+            priority: Priority::High,
+            weight: None,
+            reason: "test".to_owned(),
+        },
+    ];
+
+    let metrics = BenchmarkMetrics::calculate(&results, &expected, &[]);
+    assert!((metrics.recall_at_10 - 50.0).abs() < f64::EPSILON);
+}
+
+/// Tests mean reciprocal rank (MRR) calculation.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_mrr_calculation() {
+    let results = vec![
+        "file1.rs".to_owned(),
+        "file2.rs".to_owned(),
+        "file3.rs".to_owned(),
+    ];
+    let expected = vec![ExpectedFile {
+        path: "file2.rs".to_owned(),
+        priority: Priority::Critical,
+        weight: None,
+        reason: "test".to_owned(),
+    }];
+
+    let metrics = BenchmarkMetrics::calculate(&results, &expected, &[]);
+    assert!((metrics.mrr - 0.5).abs() < f64::EPSILON);
+}
+
+/// Tests that forbidden files and directories among the top results are counted.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_forbidden_hit_rate() {
+    let results = vec![
+        "crates/css/src/parser.rs".to_owned(),
+        "crates/network/src/http.rs".to_owned(),
+        "crates/cssom/src/lib.rs".to_owned(),
+        "tests\\http_test.rs".to_owned(),
+    ];
+    let forbidden = vec![
+        ForbiddenFile {
+            path: "crates/css".to_owned(),
+            reason: "CSS parsing".to_owned(),
+        },
+        ForbiddenFile {
+            path: "tests/".to_owned(),
+            reason: "Test files".to_owned(),
+        },
+    ];
+
+    let metrics = BenchmarkMetrics::calculate(&results, &[], &forbidden);
+    assert!((metrics.forbidden_hit_rate - 50.0).abs() < f64::EPSILON);
+
+    let hits = BenchmarkMetrics::forbidden_hits(&results, &forbidden);
+    let ranks: Vec<_> = hits
+        .iter()
+        .map(|hit| (hit.rank, hit.path.as_str()))
+        .collect();
+    assert_eq!(
+        ranks,
+        [(1, "crates/css/src/parser.rs"), (4, "tests/http_test.rs")]
+    );
+    assert_eq!(hits[1].reason, "Test files");
+
+    let clean = BenchmarkMetrics::calculate(&results[1..3], &[], &forbidden);
+    assert!(clean.forbidden_hit_rate.abs() < f64::EPSILON);
+}
+
+/// Tests that expected file weights override priority relevance in NDCG.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_weighted_ndcg() {
+    let results = vec!["minor.rs".to_owned(), "main.rs".to_owned()];
+    let expected = |main_weight| {
+        vec![
+            ExpectedFile {
+                path: "main.rs".to_owned(),
+                priority: Priority::Medium,
+                weight: main_weight,
+                reason: "test".to_owned(),
+            },
+            ExpectedFile {
+                path: "minor.rs".to_owned(),
+                priority: Priority::Medium,
+                weight: None,
+                reason: "test".to_owned(),
+            },
+        ]
+    };
+
+    // Equal relevance: any order of the two files is ideal
+    let unweighted = BenchmarkMetrics::calculate(&results, &expected(None), &[]);
+    assert!((unweighted.ndcg_at_10 - 1.0).abs() < 1e-9);
+
+    // `main.rs` now matters more, so ranking it second costs NDCG
+    let weighted = BenchmarkMetrics::calculate(&results, &expected(Some(4.0)), &[]);
+    let ideal = 4.0 + 1.0 / 3.0f64.log2();
+    let actual = 1.0 + 4.0 / 3.0f64.log2();
+    assert!((weighted.ndcg_at_10 - actual / ideal).abs() < 1e-9);
+}
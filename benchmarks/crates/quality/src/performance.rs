@@ -0,0 +1,210 @@
+//! Latency and resource measurements for quality benchmarks.
+//!
+//! Retrieval quality alone does not show changes that make indexing or search
+//! slower, so each run also records index initialization time per project,
+//! search latency per test case and the peak memory of the process.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::BenchmarkResult;
+
+/// Default search latency above which a query is reported as slow
+pub const DEFAULT_SLOW_QUERY_MS: f64 = 2000.0;
+
+/// Search latency of a test case over its repetitions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LatencyStats {
+    /// Number of timed searches
+    pub runs: usize,
+    /// Median latency in milliseconds
+    pub p50_ms: f64,
+    /// 95th percentile latency in milliseconds
+    pub p95_ms: f64,
+}
+
+impl LatencyStats {
+    /// Summarizes the durations of repeated searches
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        let mut millis: Vec<f64> = samples
+            .iter()
+            .map(|sample| sample.as_secs_f64() * 1000.0)
+            .collect();
+        millis.sort_by(f64::total_cmp);
+        Self {
+            runs: millis.len(),
+            p50_ms: percentile(&millis, 50),
+            p95_ms: percentile(&millis, 95),
+        }
+    }
+}
+
+/// Returns the nearest-rank percentile of sorted values, or 0 if there are none
+fn percentile(sorted: &[f64], percent: usize) -> f64 {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied().unwrap_or_default()
+}
+
+/// Index initialization time of a project, with and without a cache
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectTiming {
+    /// Project root shared by the project's test cases
+    pub project_root: String,
+    /// Initialization after clearing the cache, in milliseconds
+    pub cold_init_ms: f64,
+    /// Initialization from the cache written by the cold pass, in milliseconds
+    pub warm_init_ms: f64,
+}
+
+/// Performance section of a report
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PerformanceSummary {
+    /// Index initialization time of each project
+    pub projects: Vec<ProjectTiming>,
+    /// Peak resident memory of the run in bytes, if the platform reports it
+    pub peak_rss_bytes: Option<u64>,
+    /// Median of the per-test median search latencies, in milliseconds
+    pub median_p50_ms: f64,
+    /// Largest per-test 95th percentile search latency, in milliseconds
+    pub max_p95_ms: f64,
+    /// Latency above which a query counts as slow, in milliseconds
+    pub slow_query_threshold_ms: f64,
+    /// Test cases whose 95th percentile latency exceeds the threshold
+    pub slow_queries: Vec<String>,
+}
+
+impl PerformanceSummary {
+    /// Summarizes the measurements of a run
+    pub fn new(
+        results: &[BenchmarkResult],
+        projects: Vec<ProjectTiming>,
+        peak_rss_bytes: Option<u64>,
+        slow_query_threshold_ms: f64,
+    ) -> Self {
+        let mut medians: Vec<f64> = results.iter().map(|result| result.latency.p50_ms).collect();
+        medians.sort_by(f64::total_cmp);
+        let mut slow_queries: Vec<String> = results
+            .iter()
+            .filter(|result| result.latency.p95_ms > slow_query_threshold_ms)
+            .map(|result| result.name.clone())
+            .collect();
+        slow_queries.sort();
+        Self {
+            projects,
+            peak_rss_bytes,
+            median_p50_ms: percentile(&medians, 50),
+            max_p95_ms: results
+                .iter()
+                .map(|result| result.latency.p95_ms)
+                .fold(0.0, f64::max),
+            slow_query_threshold_ms,
+            slow_queries,
+        }
+    }
+}
+
+/// Returns the directory holding the caches of a project
+///
+/// Mirrors where `merlin-context` persists embeddings and language indexes,
+/// including the `MERLIN_FOLDER` override.
+pub fn cache_dir(project_root: &Path) -> PathBuf {
+    env::var("MERLIN_FOLDER")
+        .map_or_else(|_| project_root.join(".merlin"), PathBuf::from)
+        .join("cache")
+}
+
+/// Reads the peak resident memory of this process, on Linux
+///
+/// The kernel tracks the high-water mark (`VmHWM`) continuously, so reading
+/// it once after the run captures the peak without sampling.
+pub fn peak_rss_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_hwm(&status)
+}
+
+/// Parses `VmHWM` from the contents of `/proc/self/status`, in bytes
+fn parse_vm_hwm(status: &str) -> Option<u64> {
+    let kilobytes = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    kilobytes.checked_mul(1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::BenchmarkMetrics;
+
+    /// Builds a result with the given latency
+    fn result(name: &str, p50_ms: f64, p95_ms: f64) -> BenchmarkResult {
+        BenchmarkResult {
+            name: name.to_owned(),
+            query: String::new(),
+            results: Vec::new(),
+            metrics: BenchmarkMetrics::calculate(&[], &[], &[]),
+            forbidden_hits: Vec::new(),
+            latency: LatencyStats {
+                runs: 1,
+                p50_ms,
+                p95_ms,
+            },
+            logs: Vec::new(),
+        }
+    }
+
+    /// Tests nearest-rank percentiles over repeated searches.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_latency_percentiles() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_samples(&samples);
+        assert_eq!(stats.runs, 20);
+        assert!((stats.p50_ms - 10.0).abs() < 1e-9, "{stats:?}");
+        assert!((stats.p95_ms - 19.0).abs() < 1e-9, "{stats:?}");
+
+        let single = LatencyStats::from_samples(&[Duration::from_millis(7)]);
+        assert!((single.p50_ms - 7.0).abs() < 1e-9);
+        assert!((single.p95_ms - 7.0).abs() < 1e-9);
+        assert_eq!(LatencyStats::from_samples(&[]), LatencyStats::default());
+    }
+
+    /// Tests that queries slower than the threshold are flagged.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_slow_queries_flagged() {
+        let results = [
+            result("fast", 10.0, 20.0),
+            result("slow", 900.0, 2500.0),
+            result("borderline", 100.0, 1000.0),
+        ];
+        let summary = PerformanceSummary::new(&results, Vec::new(), Some(1024), 1000.0);
+        assert_eq!(summary.slow_queries, ["slow"]);
+        assert!((summary.median_p50_ms - 100.0).abs() < 1e-9);
+        assert!((summary.max_p95_ms - 2500.0).abs() < 1e-9);
+        assert_eq!(summary.peak_rss_bytes, Some(1024));
+    }
+
+    /// Tests parsing `VmHWM` out of `/proc/self/status`.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_vm_hwm() {
+        let status = "Name:\tquality-bench\nVmPeak:\t 2048000 kB\nVmHWM:\t  512000 kB\nVmRSS:\t  256000 kB\n";
+        assert_eq!(parse_vm_hwm(status), Some(512_000 * 1024));
+        assert_eq!(parse_vm_hwm("VmRSS:\t 100 kB\n"), None);
+    }
+}
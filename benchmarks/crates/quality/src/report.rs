@@ -0,0 +1,220 @@
+//! Markdown report of benchmark results.
+
+use crate::BenchmarkResult;
+use crate::metrics::AggregateMetrics;
+use crate::performance::PerformanceSummary;
+
+/// Generate summary section of the report
+fn generate_summary_section(aggregate: &AggregateMetrics) -> String {
+    use std::fmt::Write as _;
+
+    let mut section = String::from("## Summary\n\n");
+    _ = writeln!(section, "**Test Cases**: {}\n", aggregate.test_count);
+    section.push_str("| Metric | Value | Target |\n");
+    section.push_str("|--------|-------|--------|\n");
+    _ = writeln!(
+        section,
+        "| Precision@3 | {:.1}% | 60% |",
+        aggregate.avg_precision_at_3
+    );
+    _ = writeln!(
+        section,
+        "| Precision@10 | {:.1}% | 55% |",
+        aggregate.avg_precision_at_10
+    );
+    _ = writeln!(
+        section,
+        "| Recall@10 | {:.1}% | 70% |",
+        aggregate.avg_recall_at_10
+    );
+    _ = writeln!(section, "| MRR | {:.3} | 0.700 |", aggregate.avg_mrr);
+    _ = writeln!(
+        section,
+        "| NDCG@10 | {:.3} | 0.750 |",
+        aggregate.avg_ndcg_at_10
+    );
+    _ = writeln!(
+        section,
+        "| Critical in Top-3 | {:.1}% | 65% |",
+        aggregate.avg_critical_in_top_3
+    );
+    _ = writeln!(
+        section,
+        "| Forbidden Hit Rate | {:.1}% | 0% |\n",
+        aggregate.avg_forbidden_hit_rate
+    );
+
+    section
+}
+
+/// Generate performance section of the report
+fn generate_performance_section(performance: &PerformanceSummary) -> String {
+    use std::fmt::Write as _;
+
+    let mut section = String::from("## Performance\n\n");
+    section.push_str("| Project | Cold Init | Warm Init |\n");
+    section.push_str("|---------|-----------|-----------|\n");
+    for project in &performance.projects {
+        _ = writeln!(
+            section,
+            "| `{}` | {:.0} ms | {:.0} ms |",
+            project.project_root, project.cold_init_ms, project.warm_init_ms
+        );
+    }
+    section.push('\n');
+
+    if let Some(peak) = performance.peak_rss_bytes {
+        _ = writeln!(
+            section,
+            "**Peak RSS**: {:.1} MB\n",
+            peak as f64 / (1024.0 * 1024.0)
+        );
+    }
+    _ = writeln!(
+        section,
+        "**Search latency**: median p50 {:.1} ms, max p95 {:.1} ms\n",
+        performance.median_p50_ms, performance.max_p95_ms
+    );
+    if performance.slow_queries.is_empty() {
+        _ = writeln!(
+            section,
+            "No queries slower than {:.0} ms.\n",
+            performance.slow_query_threshold_ms
+        );
+    } else {
+        _ = writeln!(
+            section,
+            "**Slow queries** (p95 above {:.0} ms):\n",
+            performance.slow_query_threshold_ms
+        );
+        for name in &performance.slow_queries {
+            _ = writeln!(section, "- {name}");
+        }
+        section.push('\n');
+    }
+
+    section
+}
+
+/// Generate the list of test cases that retrieved forbidden files
+fn generate_violations_section(results: &[BenchmarkResult]) -> String {
+    use std::fmt::Write as _;
+
+    let mut violating: Vec<&BenchmarkResult> = results
+        .iter()
+        .filter(|result| !result.forbidden_hits.is_empty())
+        .collect();
+    if violating.is_empty() {
+        return String::new();
+    }
+    violating.sort_by(|left, right| left.name.cmp(&right.name));
+
+    let mut section = String::from("## Forbidden File Violations\n\n");
+    section.push_str("| Test | Rank | File | Reason |\n");
+    section.push_str("|------|------|------|--------|\n");
+    for result in violating {
+        for hit in &result.forbidden_hits {
+            _ = writeln!(
+                section,
+                "| {} | {} | `{}` | {} |",
+                result.name, hit.rank, hit.path, hit.reason
+            );
+        }
+    }
+    section.push('\n');
+
+    section
+}
+
+/// Generate individual result section
+fn generate_result_section(result: &BenchmarkResult) -> String {
+    use std::fmt::Write as _;
+
+    let mut section = String::default();
+    _ = writeln!(section, "### {}\n", result.name);
+    _ = writeln!(section, "**Query**: \"{}\"\n", result.query);
+    section.push_str("| Metric | Value |\n");
+    section.push_str("|--------|-------|\n");
+    _ = writeln!(
+        section,
+        "| Precision@3 | {:.1}% |",
+        result.metrics.precision_at_3
+    );
+    _ = writeln!(
+        section,
+        "| Precision@10 | {:.1}% |",
+        result.metrics.precision_at_10
+    );
+    _ = writeln!(
+        section,
+        "| Recall@10 | {:.1}% |",
+        result.metrics.recall_at_10
+    );
+    _ = writeln!(section, "| MRR | {:.3} |", result.metrics.mrr);
+    _ = writeln!(section, "| NDCG@10 | {:.3} |", result.metrics.ndcg_at_10);
+    _ = writeln!(
+        section,
+        "| Critical in Top-3 | {:.1}% |",
+        result.metrics.critical_in_top_3
+    );
+    _ = writeln!(
+        section,
+        "| Forbidden Hit Rate | {:.1}% |",
+        result.metrics.forbidden_hit_rate
+    );
+    _ = writeln!(
+        section,
+        "| Search latency (p50 / p95, {} runs) | {:.1} ms / {:.1} ms |\n",
+        result.latency.runs, result.latency.p50_ms, result.latency.p95_ms
+    );
+
+    section.push_str("**Top 10 Results**:\n");
+    for (index, path) in result.results.iter().take(10).enumerate() {
+        _ = writeln!(section, "{}. `{}`", index + 1, path);
+    }
+    section.push('\n');
+
+    if !result.forbidden_hits.is_empty() {
+        section.push_str("**⚠ Forbidden files retrieved**:\n");
+        for hit in &result.forbidden_hits {
+            _ = writeln!(section, "- #{} `{}`: {}", hit.rank, hit.path, hit.reason);
+        }
+        section.push('\n');
+    }
+
+    // Add execution logs
+    if !result.logs.is_empty() {
+        section.push_str("<details>\n");
+        section.push_str("<summary>Execution Logs</summary>\n\n");
+        section.push_str("```\n");
+        for log in &result.logs {
+            _ = writeln!(section, "{log}");
+        }
+        section.push_str("```\n");
+        section.push_str("</details>\n\n");
+    }
+
+    section
+}
+
+/// Generate markdown report from benchmark results
+pub fn generate_report(results: &[BenchmarkResult], performance: &PerformanceSummary) -> String {
+    let mut report = String::from("# Context Quality Benchmark Results\n\n");
+
+    let metrics: Vec<_> = results
+        .iter()
+        .map(|result| result.metrics.clone())
+        .collect();
+    let aggregate = AggregateMetrics::from_metrics(&metrics);
+
+    report.push_str(&generate_summary_section(&aggregate));
+    report.push_str(&generate_violations_section(results));
+    report.push_str(&generate_performance_section(performance));
+    report.push_str("## Individual Test Results\n\n");
+
+    for result in results {
+        report.push_str(&generate_result_section(result));
+    }
+
+    report
+}
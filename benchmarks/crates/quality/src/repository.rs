@@ -0,0 +1,72 @@
+//! Checkout of the repositories test cases run against.
+
+use std::fs::create_dir_all;
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::{Context as _, Result, bail};
+use tracing::warn;
+
+use crate::test_case::RepositoryConfig;
+
+/// Setup repository by cloning if needed and checking out specific commit
+///
+/// # Errors
+/// Returns error if git commands fail
+pub fn setup_repository(project_root: &str, config: &RepositoryConfig) -> Result<()> {
+    let repo_path = PathBuf::from(project_root);
+
+    // Clone if repository doesn't exist
+    if !repo_path.exists() {
+        tracing::info!("Cloning repository: {} -> {}", config.url, project_root);
+
+        // Create parent directory if needed
+        if let Some(parent) = repo_path.parent() {
+            create_dir_all(parent).with_context(|| {
+                format!("Failed to create parent directory: {}", parent.display())
+            })?;
+        }
+
+        let clone_output = Command::new("git")
+            .args(["clone", &config.url, project_root])
+            .output()
+            .context("Failed to execute git clone")?;
+
+        if !clone_output.status.success() {
+            let stderr = String::from_utf8_lossy(&clone_output.stderr);
+            bail!("Git clone failed: {stderr}");
+        }
+    }
+
+    // Verify it's a git repository
+    if !repo_path.join(".git").exists() {
+        bail!("Directory exists but is not a git repository: {project_root}");
+    }
+
+    // Stash any local changes
+    let stash_output = Command::new("git")
+        .current_dir(&repo_path)
+        .args(["stash", "push", "-u", "-m", "Quality benchmark auto-stash"])
+        .output()
+        .context("Failed to execute git stash")?;
+
+    if !stash_output.status.success() {
+        let stderr = String::from_utf8_lossy(&stash_output.stderr);
+        warn!("git stash failed (might be nothing to stash): {stderr}");
+    }
+
+    // Checkout the specific commit
+    tracing::info!("Checking out commit: {} in {}", config.commit, project_root);
+    let checkout_output = Command::new("git")
+        .current_dir(&repo_path)
+        .args(["checkout", &config.commit])
+        .output()
+        .context("Failed to execute git checkout")?;
+
+    if !checkout_output.status.success() {
+        let stderr = String::from_utf8_lossy(&checkout_output.stderr);
+        bail!("Git checkout failed: {stderr}");
+    }
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 //! Test case definition and loading.
 
-use crate::metrics::{ExpectedFile, Priority};
+use crate::metrics::{ExpectedFile, ForbiddenFile, Priority};
 use anyhow::{Context as _, Result};
+use serde::de::Error as _;
 use serde::{Deserialize, Deserializer};
 use std::fs::read_to_string;
 use std::path::Path;
@@ -25,9 +26,26 @@ pub struct TestCase {
     /// Expected relevant files
     #[serde(default)]
     pub expected: Vec<ExpectedFile>,
-    /// Files that should NOT appear
+    /// Files or directories that should NOT appear (`[[excluded]]` is accepted too)
+    #[serde(default, alias = "excluded")]
+    pub forbidden: Vec<ForbiddenFile>,
+    /// Context builder settings for this test case
     #[serde(default)]
-    pub excluded: Vec<ExcludedFile>,
+    pub context: ContextOverrides,
+}
+
+/// Context builder settings overridden by a test case
+///
+/// Unset fields keep the defaults of the benchmark run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContextOverrides {
+    /// Maximum number of files in the context
+    pub max_files: Option<usize>,
+    /// Maximum number of tokens of context gathered by search
+    pub token_budget: Option<usize>,
+    /// Whether search results are reranked by their imports
+    pub rerank: Option<bool>,
 }
 
 /// Repository configuration for test cases
@@ -44,24 +62,25 @@ pub struct RepositoryConfig {
 struct ExpectedFileToml {
     path: String,
     priority: String,
+    #[serde(default)]
+    weight: Option<f64>,
     reason: String,
 }
 
-/// Excluded file entry from TOML
-#[derive(Debug, Clone, Deserialize)]
-pub struct ExcludedFile {
-    /// File path
-    pub path: String,
-    /// Reason for exclusion
-    pub reason: String,
-}
-
 impl<'de> Deserialize<'de> for ExpectedFile {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let toml_file = ExpectedFileToml::deserialize(deserializer)?;
+        if let Some(weight) = toml_file.weight
+            && !(weight.is_finite() && weight >= 0.0)
+        {
+            return Err(D::Error::custom(format!(
+                "weight of {} must be a non-negative number, got {weight}",
+                toml_file.path
+            )));
+        }
         let priority = match toml_file.priority.to_lowercase().as_str() {
             "critical" => Priority::Critical,
             "high" => Priority::High,
@@ -72,6 +91,7 @@ impl<'de> Deserialize<'de> for ExpectedFile {
         Ok(Self {
             path: toml_file.path,
             priority,
+            weight: toml_file.weight,
             reason: toml_file.reason,
         })
     }
@@ -97,6 +117,7 @@ impl TestCase {
 mod tests {
     use super::*;
     use toml::from_str;
+    use walkdir::WalkDir;
 
     /// Tests priority parsing from TOML configuration.
     ///
@@ -130,4 +151,113 @@ mod tests {
 
         Ok(())
     }
+
+    /// Tests parsing forbidden files, weights and context overrides.
+    ///
+    /// # Errors
+    /// Returns an error if TOML parsing fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_forbidden_weights_and_overrides() -> Result<()> {
+        let toml_content = r#"
+            name = "Test"
+            query = "test query"
+            project_root = "test"
+
+            [context]
+            max_files = 5
+            rerank = false
+
+            [[expected]]
+            path = "file1.rs"
+            priority = "high"
+            weight = 2.5
+            reason = "test"
+
+            [[forbidden]]
+            path = "crates/css"
+            reason = "unrelated"
+        "#;
+
+        let test_case: TestCase = from_str(toml_content)?;
+        assert_eq!(test_case.expected[0].weight, Some(2.5));
+        assert!((test_case.expected[0].relevance() - 2.5).abs() < f64::EPSILON);
+        assert_eq!(test_case.forbidden.len(), 1);
+        assert_eq!(test_case.forbidden[0].path, "crates/css");
+        assert_eq!(
+            test_case.context,
+            ContextOverrides {
+                max_files: Some(5),
+                token_budget: None,
+                rerank: Some(false),
+            }
+        );
+
+        let negative_weight = from_str::<TestCase>(&toml_content.replace("2.5", "-1.0"));
+        assert!(negative_weight.is_err(), "negative weights are rejected");
+        let misspelled = from_str::<TestCase>(&toml_content.replace("rerank", "re_rank"));
+        assert!(misspelled.is_err(), "unknown context settings are rejected");
+
+        Ok(())
+    }
+
+    /// Tests that older test cases without the new fields keep their defaults.
+    ///
+    /// # Errors
+    /// Returns an error if TOML parsing fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_backward_compatible_defaults() -> Result<()> {
+        let toml_content = r#"
+            name = "Test"
+            query = "test query"
+            project_root = "test"
+
+            [[expected]]
+            path = "file1.rs"
+            priority = "low"
+            reason = "test"
+
+            [[excluded]]
+            path = "tests/"
+            reason = "Test files"
+        "#;
+
+        let test_case: TestCase = from_str(toml_content)?;
+        assert_eq!(test_case.expected[0].weight, None);
+        assert!((test_case.expected[0].relevance() - 0.5).abs() < f64::EPSILON);
+        assert_eq!(test_case.forbidden[0].path, "tests/");
+        assert_eq!(test_case.context, ContextOverrides::default());
+
+        Ok(())
+    }
+
+    /// Tests that every bundled test case still parses.
+    ///
+    /// # Errors
+    /// Returns an error if a test case cannot be loaded.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_bundled_test_cases_parse() -> Result<()> {
+        let test_cases_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_cases");
+        let mut count = 0;
+        for entry in WalkDir::new(&test_cases_dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "toml"))
+        {
+            let test_case = TestCase::from_file(entry.path())?;
+            assert!(!test_case.expected.is_empty(), "{}", test_case.name);
+            count += 1;
+        }
+        assert!(count > 0, "no test cases in {}", test_cases_dir.display());
+
+        Ok(())
+    }
 }
@@ -5,10 +5,11 @@
 
 use merlin_context::VectorSearchManager;
 use merlin_context::embedding::FakeEmbeddingClient;
+use std::error::Error;
 use std::path::PathBuf;
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() -> Result<(), Box<dyn Error>> {
     let workspace = PathBuf::from("test-workspaces/context-workspace");
 
     tracing::info!("Generating embeddings for {}", workspace.display());
@@ -35,6 +35,27 @@ pub struct FixtureEventController {
 }
 
 impl FixtureEventState {
+    /// Queue a key event for each character of `text`, then Enter if `submit`
+    fn push_text(&mut self, text: &str, submit: bool) {
+        for character in text.chars() {
+            self.current_events.push_back(Event::Key(KeyEvent {
+                code: KeyCode::Char(character),
+                modifiers: KeyModifiers::empty(),
+                kind: KeyEventKind::Press,
+                state: KeyEventState::empty(),
+            }));
+        }
+
+        if submit {
+            self.current_events.push_back(Event::Key(KeyEvent {
+                code: KeyCode::Enter,
+                modifiers: KeyModifiers::empty(),
+                kind: KeyEventKind::Press,
+                state: KeyEventState::empty(),
+            }));
+        }
+    }
+
     /// Load crossterm events for the current fixture event
     fn load_current_event(&mut self) {
         if self.current_index >= self.fixture_events.len() {
@@ -45,25 +66,13 @@ impl FixtureEventState {
         let event = &self.fixture_events[self.current_index];
         match event {
             TestEvent::UserInput(input_event) => {
-                // Add character events for each character in the text
-                for character in input_event.data.text.chars() {
-                    self.current_events.push_back(Event::Key(KeyEvent {
-                        code: KeyCode::Char(character),
-                        modifiers: KeyModifiers::empty(),
-                        kind: KeyEventKind::Press,
-                        state: KeyEventState::empty(),
-                    }));
-                }
-
-                // If submit is true, add an Enter key event
-                if input_event.data.submit {
-                    self.current_events.push_back(Event::Key(KeyEvent {
-                        code: KeyCode::Enter,
-                        modifiers: KeyModifiers::empty(),
-                        kind: KeyEventKind::Press,
-                        state: KeyEventState::empty(),
-                    }));
-                }
+                let (text, submit) = (input_event.data.text.clone(), input_event.data.submit);
+                self.push_text(&text, submit);
+            }
+            // Answers are typed and submitted like input, once the task asks
+            TestEvent::UserAnswer(answer_event) => {
+                let text = answer_event.data.text.clone();
+                self.push_text(&text, true);
             }
             TestEvent::KeyPress(key_event) => {
                 // Convert string key name to KeyCode
@@ -155,6 +164,25 @@ impl FixtureEventController {
             }
         }
     }
+
+    /// Index of the fixture event currently fed to the app
+    #[must_use]
+    pub fn current_index(&self) -> usize {
+        self.state
+            .lock()
+            .map_or(usize::MAX, |state| state.current_index)
+    }
+
+    /// Whether the fixture event currently fed to the app is a `user_answer`
+    #[must_use]
+    pub fn at_user_answer(&self) -> bool {
+        self.state.lock().is_ok_and(|state| {
+            matches!(
+                state.fixture_events.get(state.current_index),
+                Some(TestEvent::UserAnswer(_))
+            )
+        })
+    }
 }
 
 impl InputEventSource for FixtureEventSource {
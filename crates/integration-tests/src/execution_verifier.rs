@@ -1,5 +1,6 @@
 //! Execution verification logic.
 
+use super::execution_tracker::ExecutionRecord;
 use super::mock_provider::MockProvider;
 use super::verification_result::VerificationResult;
 use super::verify::ExecutionVerify;
@@ -435,4 +436,41 @@ impl ExecutionVerifier {
             }
         }
     }
+
+    /// Verify the total tokens a task reported
+    pub fn verify_tokens(
+        result: &mut VerificationResult,
+        record: Option<&ExecutionRecord>,
+        expected: u64,
+    ) {
+        let Some(task_result) = record.and_then(ExecutionRecord::task_result) else {
+            result.add_failure(format!(
+                "Expected {expected} tokens used but no task result was captured"
+            ));
+            return;
+        };
+        let actual = task_result.tokens_used.total();
+        if actual == expected {
+            result.add_success(format!("Task used {actual} tokens"));
+        } else {
+            result.add_failure(format!("Expected {expected} tokens used, got {actual}"));
+        }
+    }
+
+    /// Verify the estimated session cost reached `min_cost`
+    pub fn verify_session_cost(
+        result: &mut VerificationResult,
+        orchestrator: &RoutingOrchestrator,
+        min_cost: f64,
+    ) {
+        match orchestrator.session_cost() {
+            Ok(cost) if cost >= min_cost => {
+                result.add_success(format!("Session cost ${cost:.4} >= ${min_cost:.4}"));
+            }
+            Ok(cost) => result.add_failure(format!(
+                "Expected session cost of at least ${min_cost:.4}, got ${cost:.4}"
+            )),
+            Err(err) => result.add_failure(format!("Failed to get session cost: {err}")),
+        }
+    }
 }
@@ -3,11 +3,12 @@
 //! This module defines the complete fixture format for unified integration tests.
 //! All tests use the same format with optional verification layers.
 
-use crate::mock_provider::{self, ResponseStrategy};
+use crate::mock_provider::{self, ProviderSimulation, ResponseStrategy};
 use crate::verify::{FinalVerify, VerifyConfig};
 use merlin_core::{ContextType, ExecutionResult, PromptType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicUsize;
 
 /// Complete test fixture
@@ -28,6 +29,17 @@ pub struct TestFixture {
     /// Final verification
     #[serde(default)]
     pub final_verify: FinalVerify,
+    /// File the fixture was loaded from, which snapshot files are relative to
+    #[serde(skip)]
+    pub source_path: Option<PathBuf>,
+}
+
+impl TestFixture {
+    /// Directory of the fixture file, which UI snapshot files are relative to
+    #[must_use]
+    pub fn snapshot_dir(&self) -> Option<&Path> {
+        self.source_path.as_deref().and_then(Path::parent)
+    }
 }
 
 /// Setup configuration
@@ -44,8 +56,12 @@ pub struct SetupConfig {
     /// Environment variables to set
     #[serde(default)]
     pub env_vars: HashMap<String, String>,
-    /// Terminal size (width, height)
+    /// Terminal size (width, height) of the test backend, 80x24 by default
     pub terminal_size: Option<(u16, u16)>,
+    /// Run alone after the parallel fixtures, for fixtures needing exclusive
+    /// resources such as a fixed port
+    #[serde(default)]
+    pub serial: bool,
 }
 
 /// Test event
@@ -62,6 +78,8 @@ pub enum TestEvent {
     Wait(WaitEvent),
     /// Verification event (for mid-execution state verification)
     Verify(VerifyEvent),
+    /// Answer to the question the running task asks next
+    UserAnswer(UserAnswerEvent),
 }
 
 /// User input event
@@ -88,6 +106,27 @@ pub struct UserInputData {
     pub submit: bool,
 }
 
+/// Answer typed and submitted once the running task asks a question
+///
+/// Must directly follow the event that started the task, or the previous
+/// answer when the task asks several questions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserAnswerEvent {
+    /// Optional event ID for explicit tracking and verification
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Event data
+    pub data: UserAnswerData,
+}
+
+/// User answer data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UserAnswerData {
+    /// Answer text, or the number of an offered option
+    pub text: String,
+}
+
 /// Key press event
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyPressEvent {
@@ -132,6 +171,13 @@ pub struct LlmResponseEvent {
     /// Retry responses for this event (executed on validation failures)
     #[serde(default)]
     pub retry_responses: Vec<RetryResponse>,
+    /// Simulated provider latency, failures, streaming and token usage
+    #[serde(default)]
+    pub simulation: ProviderSimulation,
+    /// Query text of each request the responses were recorded for, secrets
+    /// redacted (informational, set by `--record-fixture`)
+    #[serde(default)]
+    pub requests: Vec<String>,
 }
 
 /// Retry response configuration
@@ -294,6 +340,8 @@ pub enum EventType {
     Wait,
     /// Verify
     Verify,
+    /// User answer
+    UserAnswer,
 }
 
 impl TestEvent {
@@ -306,6 +354,7 @@ impl TestEvent {
             Self::LlmResponse(_) => EventType::LlmResponse,
             Self::Wait(_) => EventType::Wait,
             Self::Verify(_) => EventType::Verify,
+            Self::UserAnswer(_) => EventType::UserAnswer,
         }
     }
 
@@ -318,6 +367,7 @@ impl TestEvent {
             Self::LlmResponse(event) => event.id.as_deref(),
             Self::Wait(_) => None,
             Self::Verify(event) => event.id.as_deref(),
+            Self::UserAnswer(event) => event.id.as_deref(),
         }
     }
 
@@ -333,14 +383,15 @@ impl TestEvent {
             prompt: None,
             context: None,
             validation: None,
+            tool_calls: None,
         };
 
         match self {
             Self::UserInput(event) => &event.verify,
             Self::KeyPress(event) => &event.verify,
             Self::LlmResponse(event) => &event.verify,
-            Self::Wait(_) => {
-                // Wait events don't have verification
+            Self::Wait(_) | Self::UserAnswer(_) => {
+                // Wait and answer events don't have verification
                 &EMPTY_VERIFY
             }
             Self::Verify(event) => &event.verify,
@@ -1,20 +1,56 @@
 //! Fixture loading and discovery utilities
 
 use merlin_core::{Result, RoutingError};
+use regex::Regex;
 use serde_json::from_str;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use super::fixture::TestFixture;
+use super::fixture::{TestEvent, TestFixture};
 
 /// Load a test fixture from a JSON file
 ///
 /// # Errors
-/// Returns error if file reading or parsing fails
+/// Returns error if file reading or parsing fails, or a tool call pattern is invalid
 pub fn load_fixture(path: &Path) -> Result<TestFixture> {
-    let content = fs::read_to_string(path)
-        .map_err(|err| RoutingError::Other(format!("Failed to read fixture: {err}")))?;
-    from_str(&content).map_err(|err| RoutingError::Other(format!("Failed to parse fixture: {err}")))
+    let content =
+        fs::read_to_string(path).map_err(RoutingError::file_access("read fixture", path))?;
+    let mut fixture: TestFixture = from_str(&content)?;
+    validate_tool_call_patterns(&fixture)?;
+    fixture.source_path = Some(path.to_path_buf());
+    Ok(fixture)
+}
+
+/// Check that every tool call `args_regex` of a fixture compiles
+///
+/// # Errors
+/// Returns error naming the first invalid pattern
+fn validate_tool_call_patterns(fixture: &TestFixture) -> Result<()> {
+    let event_verifies = fixture.events.iter().flat_map(|event| match event {
+        TestEvent::LlmResponse(llm_event) => vec![
+            &llm_event.verify_before,
+            &llm_event.verify_after,
+            &llm_event.verify,
+        ],
+        _ => vec![event.verify_config()],
+    });
+    let tool_call_verifies = event_verifies
+        .filter_map(|verify| verify.tool_calls.as_ref())
+        .chain(fixture.final_verify.tool_calls.as_ref());
+
+    for verify in tool_call_verifies {
+        for expectation in verify.ordered.iter().chain(&verify.unordered) {
+            if let Some(pattern) = &expectation.args_regex {
+                Regex::new(pattern).map_err(|err| {
+                    RoutingError::Other(format!(
+                        "Invalid args_regex for tool {} in fixture {}: {err}",
+                        expectation.tool, fixture.name
+                    ))
+                })?;
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Discover all fixtures in directory
@@ -28,12 +64,11 @@ pub fn discover_fixtures(dir: &Path) -> Result<Vec<PathBuf>> {
         return Ok(fixtures);
     }
 
-    let entries = fs::read_dir(dir)
-        .map_err(|err| RoutingError::Other(format!("Failed to read directory: {err}")))?;
+    let entries =
+        fs::read_dir(dir).map_err(RoutingError::file_access("read fixture directory", dir))?;
 
     for entry in entries {
-        let entry =
-            entry.map_err(|err| RoutingError::Other(format!("Failed to read entry: {err}")))?;
+        let entry = entry.map_err(RoutingError::file_access("read fixture directory", dir))?;
         let path = entry.path();
 
         if path.is_file() && path.extension().is_some_and(|ext| ext == "json") {
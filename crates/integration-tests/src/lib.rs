@@ -1,10 +1,13 @@
 //! Unified integration test framework.
 //!
 //! This crate provides a single unified testing system that can verify:
-//! - TypeScript execution and tool calls
+//! - TypeScript execution and tool calls (including their order and arguments)
 //! - File operations
 //! - UI state and rendering
 //! - Task execution and dependencies
+//! - Provider retries, latency and token usage (simulated per LLM response event)
+//!
+//! Suites of fixtures run concurrently, each in an isolated workspace.
 //!
 //! All tests use the same fixture format with optional verification layers.
 
@@ -16,7 +19,9 @@ mod fixture;
 mod fixture_loader;
 mod mock_provider;
 mod runner;
+mod suite;
 mod timing;
+mod tool_call_verifier;
 mod tui_test_helpers;
 mod ui_verifier;
 mod verification_result;
@@ -25,14 +30,20 @@ mod verify;
 mod workspace_setup;
 
 pub use fixture::{
-    EventType, LlmResponseEvent, SetupConfig, TestEvent, TestFixture, UserInputEvent,
+    EventType, LlmResponseEvent, SetupConfig, TestEvent, TestFixture, UserAnswerEvent,
+    UserInputEvent,
+};
+pub use mock_provider::{
+    MockProvider, ProviderSimulation, RequestStats, ResponseStrategy, SimulatedError,
+    SimulatedTokens,
 };
-pub use mock_provider::MockProvider;
 pub use runner::UnifiedTestRunner;
+pub use suite::{FixtureOutcome, PARALLELISM_ENV, SuiteConfig, SuiteSummary};
 pub use timing::{TimingData, TimingLayer};
+pub use ui_verifier::{BLESS_SNAPSHOTS_ENV, DEFAULT_SNAPSHOT_MASKS, normalize_snapshot};
 pub use verification_result::VerificationResult;
 pub use verifier::UnifiedVerifier;
 pub use verify::{
-    ContextVerify, ExecutionVerify, FileVerify, FinalVerify, PromptVerify, StateVerify, UiVerify,
-    ValidationVerify, VerifyConfig, WorkUnitVerify,
+    ContextVerify, ExecutionVerify, FileVerify, FinalVerify, PromptVerify, StateVerify,
+    ToolCallExpectation, ToolCallVerify, UiVerify, ValidationVerify, VerifyConfig, WorkUnitVerify,
 };
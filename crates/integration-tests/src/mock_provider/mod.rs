@@ -2,10 +2,12 @@
 
 mod provider;
 mod routing_matcher;
+mod simulation;
 mod strategy;
 
-pub use provider::MockProvider;
+pub use provider::{MockProvider, RequestStats};
 pub use routing_matcher::RoutingMatcher;
+pub use simulation::{ProviderSimulation, SimulatedError, SimulatedTokens};
 pub use strategy::ResponseStrategy;
 
 #[cfg(test)]
@@ -15,7 +17,9 @@ mod tests {
     use super::*;
     use merlin_core::{
         Context, ContextType, ModelProvider as _, PromptType, Query, Result, RoutingContext,
+        RoutingError,
     };
+    use std::time::Duration;
 
     /// Test basic pattern matching
     ///
@@ -59,4 +63,117 @@ mod tests {
 
         Ok(())
     }
+
+    /// Provider answering `event` with `simulation`
+    ///
+    /// # Errors
+    /// Returns error if the current event cannot be set
+    fn simulated_provider(simulation: ProviderSimulation) -> Result<MockProvider> {
+        let strategy = ResponseStrategy::Repeating {
+            routing_match: None,
+            typescript: "return 'done';".to_owned(),
+        };
+        let provider = MockProvider::new(
+            "test",
+            HashMap::from([("event".to_owned(), vec![strategy])]),
+        )
+        .with_simulations(HashMap::from([("event".to_owned(), simulation)]));
+        provider.set_current_event(Some("event".to_owned()))?;
+        Ok(provider)
+    }
+
+    /// Test failing the first attempts, then streaming a response with synthetic usage
+    ///
+    /// # Errors
+    /// Returns error if test fails
+    ///
+    /// # Panics
+    /// Panics if assertions fail
+    #[tokio::test]
+    async fn test_simulated_failures_then_streamed_response() -> Result<()> {
+        let provider = simulated_provider(ProviderSimulation {
+            error: Some(SimulatedError::RateLimit),
+            fail_first: Some(2),
+            stream_chunks: Some(4),
+            tokens: Some(SimulatedTokens {
+                input: 50_000,
+                output: 2_000,
+                ..SimulatedTokens::default()
+            }),
+            ..ProviderSimulation::default()
+        })?;
+        let query = Query::new("hello");
+        let context = Context::new("");
+
+        for _ in 0..2 {
+            let failed = provider.generate(&query, &context).await;
+            assert!(
+                matches!(failed, Err(RoutingError::ProviderRateLimit { .. })),
+                "expected a rate limit, got {failed:?}"
+            );
+        }
+        let response = provider.generate(&query, &context).await?;
+        assert_eq!(response.text, "```typescript\nreturn 'done';\n```");
+        assert_eq!(response.tokens_used.total(), 52_000);
+
+        let stats = provider.request_stats("event")?;
+        assert_eq!(
+            stats,
+            RequestStats {
+                requests: 3,
+                failures: 2,
+                chunks: 4,
+            }
+        );
+        Ok(())
+    }
+
+    /// Test the timeout and malformed JSON error modes and reproducible jitter
+    ///
+    /// # Errors
+    /// Returns error if test fails
+    ///
+    /// # Panics
+    /// Panics if assertions fail
+    #[tokio::test]
+    async fn test_simulated_error_modes() -> Result<()> {
+        let query = Query::new("hello");
+        let context = Context::new("");
+
+        let timeout = simulated_provider(ProviderSimulation {
+            latency_ms: 5,
+            error: Some(SimulatedError::Timeout),
+            ..ProviderSimulation::default()
+        })?
+        .generate(&query, &context)
+        .await;
+        assert!(
+            matches!(
+                timeout,
+                Err(RoutingError::ProviderTimeout { timeout_ms: 5, .. })
+            ),
+            "expected a timeout, got {timeout:?}"
+        );
+
+        let malformed = simulated_provider(ProviderSimulation {
+            error: Some(SimulatedError::MalformedJson),
+            ..ProviderSimulation::default()
+        })?
+        .generate(&query, &context)
+        .await;
+        assert!(
+            matches!(malformed, Err(RoutingError::Json(_))),
+            "expected a JSON error, got {malformed:?}"
+        );
+
+        let jittery = ProviderSimulation {
+            latency_ms: 100,
+            jitter_ms: 50,
+            ..ProviderSimulation::default()
+        };
+        let delay = jittery.delay("event", 1);
+        assert_eq!(delay, jittery.delay("event", 1));
+        assert!((Duration::from_millis(100)..=Duration::from_millis(150)).contains(&delay));
+        Ok(())
+    }
 }
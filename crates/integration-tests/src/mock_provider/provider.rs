@@ -1,15 +1,33 @@
 //! Mock provider implementation.
 
+use super::simulation::ProviderSimulation;
 use super::strategy::ResponseStrategy;
 use async_trait::async_trait;
-use merlin_core::{Context, ModelProvider, Query, Response, Result, RoutingError, TokenUsage};
+use merlin_core::{
+    Context, ModelProvider, Query, Response, Result, RoutingError, SharedClock, SystemClock,
+    TokenUsage,
+};
 use std::collections::HashMap;
 use std::fmt::Display;
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 
 /// Type alias for the strategies map
 type StrategyMap = HashMap<String, Vec<ResponseStrategy>>;
 
+/// Type alias for the request stats map
+type StatsMap = HashMap<String, RequestStats>;
+
+/// Requests an event received
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestStats {
+    /// Requests made, including failed ones
+    pub requests: usize,
+    /// Requests that failed with a simulated error
+    pub failures: usize,
+    /// Response chunks streamed
+    pub chunks: usize,
+}
+
 /// Helper to ignore writeln! results without clippy warnings
 fn write_diagnostic<T: Display>(target: &mut String, content: T) {
     use std::fmt::Write as _;
@@ -27,6 +45,12 @@ pub struct MockProvider {
     strategies: Mutex<StrategyMap>,
     /// Current event ID being processed (thread-safe interior mutability)
     current_event: Mutex<Option<String>>,
+    /// Simulated behavior by event ID
+    simulations: HashMap<String, ProviderSimulation>,
+    /// Requests received by event ID
+    stats: Mutex<StatsMap>,
+    /// Clock simulated delays are slept on
+    clock: SharedClock,
 }
 
 impl MockProvider {
@@ -37,9 +61,79 @@ impl MockProvider {
             name,
             strategies: Mutex::new(strategies),
             current_event: Mutex::new(None),
+            simulations: HashMap::new(),
+            stats: Mutex::default(),
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Sleep simulated delays on `clock` instead of the system clock
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Simulate latency, failures, streaming and token usage per event ID
+    #[must_use]
+    pub fn with_simulations(mut self, simulations: HashMap<String, ProviderSimulation>) -> Self {
+        self.simulations = simulations;
+        self
+    }
+
+    /// Get the current event ID
+    ///
+    /// # Errors
+    /// Returns error if lock fails
+    pub fn current_event(&self) -> Result<Option<String>> {
+        self.current_event
+            .lock()
+            .map(|event| event.clone())
+            .map_err(|_| RoutingError::LockPoisoned("Mock provider"))
+    }
+
+    /// Get the requests an event received
+    ///
+    /// # Errors
+    /// Returns error if lock fails
+    pub fn request_stats(&self, event_id: &str) -> Result<RequestStats> {
+        Ok(self
+            .lock_stats()?
+            .get(event_id)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    /// Lock the request stats
+    ///
+    /// # Errors
+    /// Returns error if lock fails
+    fn lock_stats(&self) -> Result<MutexGuard<'_, StatsMap>> {
+        self.stats
+            .lock()
+            .map_err(|_| RoutingError::LockPoisoned("Mock provider"))
+    }
+
+    /// Count a request for an event, returning its 0-based attempt number
+    ///
+    /// # Errors
+    /// Returns error if lock fails
+    fn start_request(&self, event_id: &str) -> Result<usize> {
+        let mut stats = self.lock_stats()?;
+        let event_stats = stats.entry(event_id.to_owned()).or_default();
+        event_stats.requests += 1;
+        Ok(event_stats.requests - 1)
+    }
+
+    /// Update the stats of an event
+    ///
+    /// # Errors
+    /// Returns error if lock fails
+    fn update_stats(&self, event_id: &str, update: impl FnOnce(&mut RequestStats)) -> Result<()> {
+        update(self.lock_stats()?.entry(event_id.to_owned()).or_default());
+        Ok(())
+    }
+
     /// Set the current event ID being processed
     ///
     /// # Errors
@@ -48,7 +142,7 @@ impl MockProvider {
         *self
             .current_event
             .lock()
-            .map_err(|err| RoutingError::Other(format!("Lock poisoned: {err}")))? = event_id;
+            .map_err(|_| RoutingError::LockPoisoned("Mock provider"))? = event_id;
         Ok(())
     }
 
@@ -60,7 +154,7 @@ impl MockProvider {
         let strategies_map = self
             .strategies
             .lock()
-            .map_err(|err| RoutingError::Other(format!("Lock poisoned: {err}")))?;
+            .map_err(|_| RoutingError::LockPoisoned("Mock provider"))?;
 
         let Some(strategies) = strategies_map.get(event_id) else {
             return Err(Self::generate_event_not_found_error(
@@ -149,32 +243,51 @@ impl ModelProvider for MockProvider {
 
     async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
         // Get current event ID (must be set by test runner - no fallback)
-        let current_event = self
-            .current_event
-            .lock()
-            .map_err(|err| RoutingError::Other(format!("Lock poisoned: {err}")))?;
-
-        let event_id = current_event.as_ref().ok_or_else(|| {
+        let event_id = self.current_event()?.ok_or_else(|| {
             RoutingError::ExecutionFailed(
                 "No current event set. Test runner must call set_current_event() before LLM queries.".to_owned()
             )
-        })?.clone();
+        })?;
 
-        drop(current_event);
+        let start = self.clock.now();
+        let simulation = self.simulations.get(&event_id).cloned().unwrap_or_default();
+        let attempt = self.start_request(&event_id)?;
+        let delay = simulation.delay(&event_id, attempt);
+
+        if let Some(error) = simulation.error_for_attempt(attempt) {
+            self.clock.sleep(delay).await;
+            self.update_stats(&event_id, |stats| stats.failures += 1)?;
+            tracing::info!("Simulated {error:?} for event={event_id}, attempt={attempt}");
+            return Err(error.to_routing_error(self.name, delay));
+        }
 
         let typescript_code = self.find_match(&event_id, query, context)?;
 
+        // Stream the response in chunks, spreading the delay between them
+        let chunks = simulation.chunks(&format!("```typescript\n{typescript_code}\n```"));
+        let chunk_delay = delay / u32::try_from(chunks.len()).unwrap_or(u32::MAX);
+        let mut text = String::new();
+        for chunk in &chunks {
+            self.clock.sleep(chunk_delay).await;
+            text.push_str(chunk);
+            self.update_stats(&event_id, |stats| stats.chunks += 1)?;
+        }
+
         Ok(Response {
-            text: format!("```typescript\n{typescript_code}\n```"),
+            text,
             confidence: 1.0,
-            tokens_used: TokenUsage {
-                input: query.text.len() as u64,
-                output: typescript_code.len() as u64,
-                cache_read: 0,
-                cache_write: 0,
-            },
+            tokens_used: simulation.tokens.map_or_else(
+                || TokenUsage {
+                    input: query.text.len() as u64,
+                    output: typescript_code.len() as u64,
+                    cache_read: 0,
+                    cache_write: 0,
+                },
+                TokenUsage::from,
+            ),
             provider: self.name.to_owned(),
-            latency_ms: 0,
+            latency_ms: u64::try_from(self.clock.elapsed_since(start).as_millis())
+                .unwrap_or(u64::MAX),
         })
     }
 
@@ -0,0 +1,125 @@
+//! Simulated provider behavior: latency, failures, streaming and token usage.
+
+use merlin_core::{RoutingError, TokenUsage};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_str};
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+use std::time::Duration;
+
+/// Truncated provider body returned by the `malformed_json` error mode
+const MALFORMED_BODY: &str = r#"{"choices": [{"message": {"content": "#;
+
+/// Per-event provider behavior, configured on `llm_response` fixture events
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProviderSimulation {
+    /// Delay before each response or error, in milliseconds
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Extra delay of up to this many milliseconds, varying per request
+    #[serde(default)]
+    pub jitter_ms: u64,
+    /// Error returned instead of a response
+    pub error: Option<SimulatedError>,
+    /// Number of requests failing with `error` before requests succeed
+    /// (every request fails if unset)
+    pub fail_first: Option<usize>,
+    /// Number of chunks the response is streamed in, with the delay spread between them
+    pub stream_chunks: Option<usize>,
+    /// Token usage reported with each response (sized from the query and
+    /// response text if unset)
+    pub tokens: Option<SimulatedTokens>,
+}
+
+/// Error a simulated provider request fails with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulatedError {
+    /// The request times out after the configured latency
+    Timeout,
+    /// The provider answers HTTP 429
+    RateLimit,
+    /// The provider answers with a truncated JSON body
+    MalformedJson,
+}
+
+/// Token usage reported with each simulated response
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimulatedTokens {
+    /// Input tokens
+    pub input: u64,
+    /// Output tokens
+    pub output: u64,
+    /// Tokens read from cache
+    pub cache_read: u64,
+    /// Tokens written to cache
+    pub cache_write: u64,
+}
+
+impl From<SimulatedTokens> for TokenUsage {
+    fn from(tokens: SimulatedTokens) -> Self {
+        Self {
+            input: tokens.input,
+            output: tokens.output,
+            cache_read: tokens.cache_read,
+            cache_write: tokens.cache_write,
+        }
+    }
+}
+
+impl ProviderSimulation {
+    /// Error the request with 0-based number `attempt` fails with, if any
+    #[must_use]
+    pub fn error_for_attempt(&self, attempt: usize) -> Option<SimulatedError> {
+        self.error
+            .filter(|_| self.fail_first.is_none_or(|failing| attempt < failing))
+    }
+
+    /// Delay of a request: the latency plus jitter derived from the event
+    /// and attempt, so runs are reproducible
+    #[must_use]
+    pub fn delay(&self, event_id: &str, attempt: usize) -> Duration {
+        let jitter = if self.jitter_ms == 0 {
+            0
+        } else {
+            let mut hasher = DefaultHasher::new();
+            event_id.hash(&mut hasher);
+            attempt.hash(&mut hasher);
+            hasher.finish() % (self.jitter_ms + 1)
+        };
+        Duration::from_millis(self.latency_ms + jitter)
+    }
+
+    /// Split a response into the configured number of chunks
+    #[must_use]
+    pub fn chunks(&self, text: &str) -> Vec<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let count = self.stream_chunks.unwrap_or(1).clamp(1, chars.len().max(1));
+        chars
+            .chunks(chars.len().div_ceil(count).max(1))
+            .map(|chunk| chunk.iter().collect())
+            .collect()
+    }
+}
+
+impl SimulatedError {
+    /// The error a real provider reports in this failure mode
+    #[must_use]
+    pub fn to_routing_error(self, provider: &str, delay: Duration) -> RoutingError {
+        match self {
+            Self::Timeout => RoutingError::ProviderTimeout {
+                provider: provider.to_owned(),
+                timeout_ms: u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+            },
+            Self::RateLimit => RoutingError::ProviderRateLimit {
+                provider: provider.to_owned(),
+                retry_after_secs: None,
+            },
+            Self::MalformedJson => from_str::<Value>(MALFORMED_BODY)
+                .map_or_else(RoutingError::from, |_| {
+                    RoutingError::Other(format!("{provider} returned malformed JSON"))
+                }),
+        }
+    }
+}
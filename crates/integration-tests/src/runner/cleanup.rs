@@ -0,0 +1,83 @@
+//! Removal of the threads, tasks and logs a fixture run leaves in its workspace.
+
+use merlin_core::{Result, RoutingError};
+use merlin_tooling::ToolAuditLog;
+use std::fs;
+use std::path::Path;
+
+/// Clean up test artifacts (threads and tasks) after test completion
+///
+/// # Errors
+/// Returns error if cleanup fails
+pub(super) fn cleanup_test_artifacts(workspace_path: &Path) -> Result<()> {
+    // Clean up threads directory
+    let threads_dir = workspace_path.join(".merlin").join("threads");
+    if threads_dir.exists() {
+        fs::remove_dir_all(&threads_dir).map_err(RoutingError::file_access(
+            "clean up threads directory",
+            &threads_dir,
+        ))?;
+    }
+
+    // Clean up tasks directory
+    let tasks_dir = workspace_path.join(".merlin").join("tasks");
+    if tasks_dir.exists() {
+        fs::remove_dir_all(&tasks_dir).map_err(RoutingError::file_access(
+            "clean up tasks directory",
+            &tasks_dir,
+        ))?;
+    }
+
+    // Clean up the tool audit log
+    let audit_log = ToolAuditLog::workspace_path(workspace_path);
+    if audit_log.exists() {
+        fs::remove_file(&audit_log).map_err(RoutingError::file_access(
+            "clean up tool audit log",
+            &audit_log,
+        ))?;
+    }
+
+    // Clean up any stray thread JSON files in workspace root
+    // (from before the path fix - these should no longer be created)
+    cleanup_uuid_json_files(workspace_path);
+
+    Ok(())
+}
+
+/// Check if a filename matches UUID pattern (8-4-4-4-12 hex digits)
+fn is_uuid_filename(stem: &str) -> bool {
+    stem.len() == 36
+        && stem
+            .chars()
+            .enumerate()
+            .all(|(pos_index, char_value)| match pos_index {
+                8 | 13 | 18 | 23 => char_value == '-',
+                _ => char_value.is_ascii_hexdigit(),
+            })
+}
+
+/// Clean up UUID-pattern JSON files in the given directory
+fn cleanup_uuid_json_files(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+
+        let Some("json") = path.extension().and_then(|ext| ext.to_str()) else {
+            continue;
+        };
+
+        let Some(stem) = path.file_stem().and_then(|stem_os| stem_os.to_str()) else {
+            continue;
+        };
+
+        if is_uuid_filename(stem) {
+            drop(fs::remove_file(&path));
+        }
+    }
+}
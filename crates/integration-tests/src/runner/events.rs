@@ -0,0 +1,342 @@
+//! Handling of the fixture events the runner steps through.
+
+use super::UnifiedTestRunner;
+use super::task_completion::{self, PendingTaskResult};
+use crate::execution_tracker::ExecutionResultTracker;
+use crate::fixture::{
+    KeyPressEvent, LlmResponseEvent, TestEvent, UserAnswerEvent, UserInputEvent, VerifyEvent,
+};
+use crate::tui_test_helpers;
+use crate::verifier::{UnifiedVerifier, VerifyEventContext};
+use crate::verify::VerifyConfig;
+use merlin_core::{Clock as _, Result, RoutingError};
+use std::time::{Duration, Instant};
+
+/// Parameters for handling submit input event
+struct SubmitInputParams<'event, 'verifier> {
+    event: &'event TestEvent,
+    input_event: &'event UserInputEvent,
+    event_index: usize,
+    verifier: &'event mut UnifiedVerifier<'verifier>,
+    execution_tracker: &'event ExecutionResultTracker,
+}
+
+/// Parameters for handling LLM response event
+struct LlmResponseParams<'event, 'verifier> {
+    event: &'event TestEvent,
+    llm_event: &'event LlmResponseEvent,
+    event_index: usize,
+    verifier: &'event mut UnifiedVerifier<'verifier>,
+    execution_tracker: &'event ExecutionResultTracker,
+}
+
+/// Runs one fixture event, returning the task a submitted input started
+///
+/// # Errors
+/// Returns error if input processing or verification fails
+pub(super) async fn handle_event(
+    runner: &mut UnifiedTestRunner,
+    event: &TestEvent,
+    event_index: usize,
+    verifier: &mut UnifiedVerifier<'_>,
+    execution_tracker: &ExecutionResultTracker,
+) -> Result<Option<PendingTaskResult>> {
+    match event {
+        TestEvent::UserInput(input_event) if input_event.data.submit => {
+            let params = SubmitInputParams {
+                event,
+                input_event,
+                event_index,
+                verifier,
+                execution_tracker,
+            };
+            return handle_submit_input(runner, params).await.map(Some);
+        }
+        TestEvent::UserInput(UserInputEvent { verify, .. })
+        | TestEvent::KeyPress(KeyPressEvent { verify, .. }) => {
+            handle_user_input(runner, event, verify, verifier, execution_tracker).await?;
+        }
+        TestEvent::LlmResponse(llm_event) => {
+            let params = LlmResponseParams {
+                event,
+                llm_event: llm_event.as_ref(),
+                event_index,
+                verifier,
+                execution_tracker,
+            };
+            handle_llm_response(runner, params).await?;
+        }
+        TestEvent::Wait(wait_event) => {
+            runner
+                .clock
+                .sleep(Duration::from_millis(wait_event.data.duration_ms))
+                .await;
+            runner.event_controller.advance();
+        }
+        TestEvent::Verify(verify_event) => {
+            handle_verify(runner, event, verify_event, verifier, execution_tracker).await?;
+        }
+        TestEvent::UserAnswer(answer_event) => {
+            check_answered(runner, answer_event, event_index)?;
+        }
+    }
+    Ok(None)
+}
+
+/// Process input events from the event source
+///
+/// # Errors
+/// Returns error if input processing fails
+async fn process_input_events(runner: &mut UnifiedTestRunner) -> Result<()> {
+    while let Some(evt) = tui_test_helpers::next_input_event(&mut runner.tui_app).await? {
+        tui_test_helpers::handle_input(&mut runner.tui_app, &evt);
+    }
+    Ok(())
+}
+
+/// Handle submit user input event
+///
+/// # Errors
+/// Returns error if input processing or verification fails
+async fn handle_submit_input(
+    runner: &mut UnifiedTestRunner,
+    params: SubmitInputParams<'_, '_>,
+) -> Result<PendingTaskResult> {
+    let start = Instant::now();
+
+    let process_start = Instant::now();
+    process_input_events(runner).await?;
+    tracing::debug!(
+        "  process_input: {:.3}s",
+        process_start.elapsed().as_secs_f64()
+    );
+
+    let render_start = Instant::now();
+    runner.tui_app.render()?;
+    tracing::debug!("  render: {:.3}s", render_start.elapsed().as_secs_f64());
+
+    let verify_start = Instant::now();
+    params
+        .verifier
+        .verify_event(&VerifyEventContext {
+            event: params.event,
+            verify: &params.input_event.verify,
+            tui_app: Some(&runner.tui_app),
+            execution_tracker: params.execution_tracker,
+            provider: Some(&runner.provider),
+        })
+        .await
+        .map_err(RoutingError::ExecutionFailed)?;
+    tracing::debug!(
+        "  verify_event: {:.3}s",
+        verify_start.elapsed().as_secs_f64()
+    );
+
+    // Look ahead to find the next LlmResponse event and set it as current
+    // This allows LLM queries triggered by submit to find their responses
+    for (idx, evt) in runner
+        .fixture
+        .events
+        .iter()
+        .enumerate()
+        .skip(params.event_index + 1)
+    {
+        if let TestEvent::LlmResponse(llm_event) = evt {
+            let event_id = llm_event
+                .id
+                .clone()
+                .unwrap_or_else(|| format!("event_{idx}"));
+            runner.provider.set_current_event(Some(event_id))?;
+            break;
+        }
+    }
+
+    runner.event_controller.advance();
+
+    let await_start = Instant::now();
+    let mut task_events = tui_test_helpers::get_task_receiver(&mut runner.tui_app)?;
+    let result = task_completion::await_task_completion(
+        &mut runner.tui_app,
+        &mut task_events,
+        &runner.event_controller,
+    )
+    .await;
+    tracing::debug!(
+        "  await_completion: {:.3}s",
+        await_start.elapsed().as_secs_f64()
+    );
+
+    tracing::debug!(
+        "handle_submit_input total: {:.3}s",
+        start.elapsed().as_secs_f64()
+    );
+    result
+}
+
+/// Handle non-submit user input or key press event
+///
+/// # Errors
+/// Returns error if input processing or verification fails
+async fn handle_user_input(
+    runner: &mut UnifiedTestRunner,
+    event: &TestEvent,
+    verify: &VerifyConfig,
+    verifier: &mut UnifiedVerifier<'_>,
+    execution_tracker: &ExecutionResultTracker,
+) -> Result<()> {
+    process_input_events(runner).await?;
+    runner.tui_app.render()?;
+
+    verifier
+        .verify_event(&VerifyEventContext {
+            event,
+            verify,
+            tui_app: Some(&runner.tui_app),
+            execution_tracker,
+            provider: Some(&runner.provider),
+        })
+        .await
+        .map_err(RoutingError::ExecutionFailed)?;
+
+    runner.event_controller.advance();
+    Ok(())
+}
+
+/// Handle mid-execution verification event
+///
+/// # Errors
+/// Returns error if rendering or verification fails
+async fn handle_verify(
+    runner: &mut UnifiedTestRunner,
+    event: &TestEvent,
+    verify_event: &VerifyEvent,
+    verifier: &mut UnifiedVerifier<'_>,
+    execution_tracker: &ExecutionResultTracker,
+) -> Result<()> {
+    tui_test_helpers::process_ui_events(&mut runner.tui_app);
+    runner.tui_app.render()?;
+
+    verifier
+        .verify_event(&VerifyEventContext {
+            event,
+            verify: &verify_event.verify,
+            tui_app: Some(&runner.tui_app),
+            execution_tracker,
+            provider: Some(&runner.provider),
+        })
+        .await
+        .map_err(RoutingError::ExecutionFailed)?;
+
+    runner.event_controller.advance();
+    Ok(())
+}
+
+/// Handle LLM response event
+///
+/// # Errors
+/// Returns error if verification fails
+async fn handle_llm_response(
+    runner: &mut UnifiedTestRunner,
+    params: LlmResponseParams<'_, '_>,
+) -> Result<()> {
+    let start = Instant::now();
+
+    let verify_before_start = Instant::now();
+    params
+        .verifier
+        .verify_event(&VerifyEventContext {
+            event: params.event,
+            verify: &params.llm_event.verify_before,
+            tui_app: Some(&runner.tui_app),
+            execution_tracker: params.execution_tracker,
+            provider: Some(&runner.provider),
+        })
+        .await
+        .map_err(RoutingError::ExecutionFailed)?;
+    tracing::debug!(
+        "  verify_before: {:.3}s",
+        verify_before_start.elapsed().as_secs_f64()
+    );
+
+    // Set current event for mock provider (must match registration ID)
+    let set_event_start = Instant::now();
+    let event_id = params
+        .llm_event
+        .id
+        .clone()
+        .unwrap_or_else(|| format!("event_{}", params.event_index));
+    runner.provider.set_current_event(Some(event_id))?;
+    tracing::debug!(
+        "  set_event: {:.3}s",
+        set_event_start.elapsed().as_secs_f64()
+    );
+
+    let process_ui_start = Instant::now();
+    runner.process_pending_ui_events();
+    tracing::debug!(
+        "  process_ui: {:.3}s",
+        process_ui_start.elapsed().as_secs_f64()
+    );
+
+    let render_start = Instant::now();
+    runner.tui_app.render()?;
+    tracing::debug!("  render: {:.3}s", render_start.elapsed().as_secs_f64());
+
+    let verify_config = if params.llm_event.verify_after.is_empty() {
+        &params.llm_event.verify
+    } else {
+        &params.llm_event.verify_after
+    };
+
+    let verify_after_start = Instant::now();
+    let verify_result = params
+        .verifier
+        .verify_event(&VerifyEventContext {
+            event: params.event,
+            verify: verify_config,
+            tui_app: Some(&runner.tui_app),
+            execution_tracker: params.execution_tracker,
+            provider: Some(&runner.provider),
+        })
+        .await;
+    tracing::debug!(
+        "  verify_after: {:.3}s",
+        verify_after_start.elapsed().as_secs_f64()
+    );
+
+    // Clear current event (even if verification failed)
+    let clear_event_start = Instant::now();
+    runner.provider.set_current_event(None)?;
+    tracing::debug!(
+        "  clear_event: {:.3}s",
+        clear_event_start.elapsed().as_secs_f64()
+    );
+
+    // Now check verification result
+    verify_result.map_err(RoutingError::ExecutionFailed)?;
+
+    runner.event_controller.advance();
+    tracing::debug!(
+        "handle_llm_response total: {:.3}s",
+        start.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
+
+/// Check that the answer was typed while awaiting the task that asked for it
+///
+/// # Errors
+/// Returns error if no question consumed the answer
+fn check_answered(
+    runner: &UnifiedTestRunner,
+    answer_event: &UserAnswerEvent,
+    event_index: usize,
+) -> Result<()> {
+    if runner.event_controller.current_index() <= event_index {
+        return Err(RoutingError::ExecutionFailed(format!(
+            "No question was asked for the user_answer '{}' (event {event_index})",
+            answer_event.data.text
+        )));
+    }
+    Ok(())
+}
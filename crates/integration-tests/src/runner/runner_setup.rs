@@ -2,13 +2,14 @@
 
 use crate::event_source::{FixtureEventController, FixtureEventSource};
 use crate::fixture::{TestEvent, TestFixture};
-use crate::mock_provider::{MockProvider, ResponseStrategy};
+use crate::mock_provider::{MockProvider, ProviderSimulation, ResponseStrategy};
 use crate::tui_test_helpers;
-use crate::workspace_setup::{create_files, get_test_workspace_path};
-use merlin_agent::{RoutingOrchestrator, ThreadStore};
-use merlin_cli::TuiApp;
-use merlin_core::{ModelProvider, Result, RoutingError};
+use crate::workspace_setup::{copy_workspace, create_files, get_test_workspace_path};
+use merlin_agent::{RoutingOrchestrator, SessionRecorder, ThreadStore};
+use merlin_cli::{TaskManager, TuiApp};
+use merlin_core::{ModelProvider, Result, VirtualClock};
 use merlin_routing::{Model, ModelRegistry, ProviderRegistry, RoutingConfig, StrategyRouter};
+use merlin_tooling::ToolCallRecorder;
 use ratatui::backend::TestBackend;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -17,16 +18,22 @@ use tempfile::TempDir;
 
 /// Components needed to construct a test runner
 pub struct RunnerComponents {
-    /// Temporary workspace (for writable tests, auto-cleanup)
-    pub workspace_temp: Option<TempDir>,
+    /// Temporary workspace (auto-cleanup)
+    pub workspace_temp: TempDir,
     /// Workspace path
     pub workspace_path: PathBuf,
     /// Mock provider
     pub provider: Arc<MockProvider>,
+    /// Tool calls made by the agent
+    pub tool_calls: ToolCallRecorder,
+    /// Records the session as a fixture (when requested)
+    pub session_recorder: Option<SessionRecorder>,
     /// TUI application
     pub tui_app: TuiApp<TestBackend>,
     /// Event controller
     pub event_controller: FixtureEventController,
+    /// Clock shared by the provider, orchestrator and UI, advanced instead of sleeping
+    pub clock: VirtualClock,
 }
 
 /// Build strategy map from fixture events
@@ -57,39 +64,73 @@ fn build_strategy_map(fixture: &TestFixture) -> HashMap<String, Vec<ResponseStra
     strategy_map
 }
 
+/// Build the simulated provider behavior of each LLM response event
+fn build_simulation_map(fixture: &TestFixture) -> HashMap<String, ProviderSimulation> {
+    fixture
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(|(event_idx, event)| match event {
+            TestEvent::LlmResponse(llm_event) => Some((
+                llm_event
+                    .id
+                    .clone()
+                    .unwrap_or_else(|| format!("event_{event_idx}")),
+                llm_event.simulation.clone(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Workspace path, with the temporary directory backing it
+type Workspace = (PathBuf, TempDir);
+
+/// Create the private workspace of a fixture
+///
+/// Fixtures with explicit workspace get a copy of a pre-made workspace (e.g.
+/// context tests with cached embeddings), all others a fresh one with their
+/// setup files. Either way nothing written at runtime, `.merlin` state
+/// included, is shared with fixtures running in parallel.
+///
+/// # Errors
+/// Returns error if the workspace is missing or cannot be created
+fn create_workspace(fixture: &TestFixture) -> Result<Workspace> {
+    let workspace = TempDir::new()?;
+    let workspace_path = workspace.path().to_path_buf();
+
+    if let Some(ws_name) = &fixture.setup.workspace {
+        copy_workspace(&get_test_workspace_path(ws_name)?, &workspace_path)?;
+    } else if !fixture.setup.files.is_empty() {
+        create_files(&workspace_path, &fixture.setup.files)?;
+    }
+
+    Ok((workspace_path, workspace))
+}
+
 /// Create test runner components
 ///
-/// Context fixtures use read-only workspaces with pre-generated embeddings.
-/// Other fixtures use temporary writable workspaces (created only when needed).
+/// Each fixture runs in its own temporary workspace, copied from a pre-made
+/// workspace with pre-generated embeddings for context fixtures.
+/// With `record`, the session is recorded as it would be by `--record-fixture`.
+/// Every component reads the time from one virtual clock, so simulated
+/// latency and waits take no real time and displayed durations are reproducible.
 ///
 /// # Errors
 /// Returns error if setup fails
-pub fn create_runner_components(fixture: &TestFixture) -> Result<RunnerComponents> {
+pub fn create_runner_components(fixture: &TestFixture, record: bool) -> Result<RunnerComponents> {
     // Build strategy map from fixture events
     let strategy_map = build_strategy_map(fixture);
+    let clock = VirtualClock::new();
 
     // Create provider with pre-built strategy map
-    let provider = Arc::new(MockProvider::new("test-mock", strategy_map));
-
-    // Determine final workspace path
-    // Fixtures with explicit workspace use read-only pre-made workspaces (e.g. context tests)
-    // All other fixtures get temp workspaces (may write files at runtime)
-    let (final_workspace_path, workspace_temp) = if let Some(ws_name) = &fixture.setup.workspace {
-        // Use pre-made read-only workspace (for context tests with embeddings)
-        (get_test_workspace_path(ws_name)?, None)
-    } else {
-        // Create temp workspace - fixture may write files at runtime
-        let workspace = TempDir::new()
-            .map_err(|err| RoutingError::Other(format!("Failed to create workspace: {err}")))?;
-        let workspace_path = workspace.path().to_path_buf();
-
-        // Create pre-specified files if any
-        if !fixture.setup.files.is_empty() {
-            create_files(&workspace_path, &fixture.setup.files)?;
-        }
+    let provider = Arc::new(
+        MockProvider::new("test-mock", strategy_map)
+            .with_simulations(build_simulation_map(fixture))
+            .with_clock(clock.shared()),
+    );
 
-        (workspace_path, Some(workspace))
-    };
+    let (final_workspace_path, workspace_temp) = create_workspace(fixture)?;
 
     // Create routing config for test orchestrator
     let mut config = RoutingConfig::default();
@@ -122,6 +163,13 @@ pub fn create_runner_components(fixture: &TestFixture) -> Result<RunnerComponent
     // Only enable for fixtures that use pre-made test workspaces (which have cached embeddings)
     let enable_embeddings = fixture.setup.workspace.is_some();
 
+    // Record tool calls for tool call verification, through the session
+    // recorder when recording since it replaces the orchestrator's recorder
+    let session_recorder = record.then(|| SessionRecorder::new(final_workspace_path.clone()));
+    let tool_calls = session_recorder
+        .as_ref()
+        .map_or_else(ToolCallRecorder::new, SessionRecorder::tool_calls);
+
     // Create thread store for conversation management if fixture uses threads
     let needs_threads = fixture.tags.contains(&"threads".to_owned());
     let orchestrator = if needs_threads {
@@ -131,11 +179,22 @@ pub fn create_runner_components(fixture: &TestFixture) -> Result<RunnerComponent
         RoutingOrchestrator::new_with_router(config, router, registry)?
             .with_workspace(final_workspace_path.clone())
             .with_embeddings(enable_embeddings)
+            // Title requests would consume scripted mock responses
+            .with_auto_titles(false)
             .with_thread_store(thread_store)
+            .with_tool_call_recorder(tool_calls.clone())
+            .with_clock(clock.shared())
     } else {
         RoutingOrchestrator::new_with_router(config, router, registry)?
             .with_workspace(final_workspace_path.clone())
             .with_embeddings(enable_embeddings)
+            .with_tool_call_recorder(tool_calls.clone())
+            .with_clock(clock.shared())
+    };
+
+    let orchestrator = match &session_recorder {
+        Some(recorder) => orchestrator.with_session_recorder(recorder.clone())?,
+        None => orchestrator,
     };
 
     // Create fixture-based event source with controller
@@ -146,18 +205,22 @@ pub fn create_runner_components(fixture: &TestFixture) -> Result<RunnerComponent
     let backend = TestBackend::new(terminal_size.0, terminal_size.1);
 
     // Create TUI app with test backend, fixture event source, and orchestrator
-    let tui_app = tui_test_helpers::new_test_app(
+    let mut tui_app = tui_test_helpers::new_test_app(
         backend,
         Box::new(event_source),
         Some(final_workspace_path.clone()),
         Some(Arc::new(orchestrator)),
     )?;
+    tui_app.ui_components.task_manager = TaskManager::with_clock(clock.shared());
 
     Ok(RunnerComponents {
         workspace_temp,
         workspace_path: final_workspace_path,
         provider,
+        tool_calls,
+        session_recorder,
         tui_app,
         event_controller,
+        clock,
     })
 }
@@ -1,16 +1,16 @@
 //! Task completion and result extraction logic.
 
+use crate::event_source::FixtureEventController;
 use crate::execution_tracker::ExecutionResultTracker;
 use crate::tui_test_helpers;
 use merlin_cli::TuiApp;
-use merlin_core::{Result, RoutingError, TaskResult};
+use merlin_core::{Result, RoutingError, TaskId, TaskResult, UiEventReceiver};
 use merlin_routing::UiEvent;
 use merlin_tooling::{ToolError, ToolResult};
 use ratatui::backend::TestBackend;
 use serde_json::{Value as JsonValue, from_str};
 use std::result::Result as StdResult;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
 use tokio::time::{Duration as TokioDuration, timeout};
 
 /// Result type for task completion with captured outputs
@@ -50,8 +50,8 @@ impl CompletionTiming {
 struct EventContext<'ctx> {
     /// Output strings captured so far
     outputs: &'ctx mut Vec<String>,
-    /// Whether task has started
-    task_started: &'ctx mut bool,
+    /// Task the completion is awaited for, once started; subtasks complete before it
+    root_task: &'ctx mut Option<TaskId>,
     /// Timing statistics
     timing: &'ctx CompletionTiming,
     /// Start time
@@ -71,8 +71,17 @@ fn process_task_event(
     ctx: &mut EventContext<'_>,
 ) -> Option<Result<TaskCompletionResult>> {
     match event {
-        UiEvent::TaskStarted { .. } => {
-            *ctx.task_started = true;
+        UiEvent::TaskStarted {
+            task_id, parent_id, ..
+        } => {
+            if ctx.root_task.is_none() && parent_id.is_none() {
+                *ctx.root_task = Some(task_id);
+            }
+            None
+        }
+        UiEvent::TaskCompleted { task_id, .. } | UiEvent::TaskFailed { task_id, .. }
+            if ctx.root_task.is_some_and(|root| root != task_id) =>
+        {
             None
         }
         UiEvent::TaskCompleted { result, .. } => {
@@ -101,20 +110,25 @@ fn process_task_event(
 /// We capture ALL `TaskOutput` events during this wait to ensure we capture outputs
 /// from the main task and any subtasks (like TypeScript tool executions).
 ///
+/// Questions the task asks are answered by the `user_answer` events following
+/// the one that started it, typed into the TUI like user input.
+///
 /// Returns the task result and any captured output from `TaskOutput` events.
 ///
 /// # Errors
-/// Returns error if task completion fails or times out
+/// Returns error if task completion fails or times out, or the task asks a
+/// question without a `user_answer` event to answer it
 pub async fn await_task_completion(
     tui_app: &mut TuiApp<TestBackend>,
-    task_events: &mut mpsc::Receiver<UiEvent>,
+    task_events: &mut UiEventReceiver,
+    event_controller: &FixtureEventController,
 ) -> Result<TaskCompletionResult> {
     let mut outputs = Vec::new();
     let overall_timeout = TokioDuration::from_secs(15);
     let start = Instant::now();
     let mut events_received = 0;
     let mut last_event_time = Instant::now();
-    let mut task_started = false;
+    let mut root_task = None;
     let mut iterations = 0;
     let mut timing = CompletionTiming {
         process_ui: Duration::ZERO,
@@ -129,7 +143,8 @@ pub async fn await_task_completion(
             let idle_time = last_event_time.elapsed().as_millis();
             return Err(RoutingError::ExecutionFailed(format!(
                 "Task completion timed out after 15 seconds - {events_received} events received, \
-                 task_started: {task_started}, idle for {idle_time}ms"
+                 task_started: {}, idle for {idle_time}ms",
+                root_task.is_some()
             )));
         }
 
@@ -147,12 +162,15 @@ pub async fn await_task_completion(
                 timing.try_recv += recv_start.elapsed();
                 events_received += 1;
                 last_event_time = Instant::now();
+                if let UiEvent::UserQuestion { question, .. } = &event {
+                    answer_question(tui_app, event_controller, question).await?;
+                }
 
                 if let Some(result) = process_task_event(
                     event,
                     &mut EventContext {
                         outputs: &mut outputs,
-                        task_started: &mut task_started,
+                        root_task: &mut root_task,
                         timing: &timing,
                         start,
                         iterations,
@@ -165,7 +183,7 @@ pub async fn await_task_completion(
             Ok(None) => {
                 // Channel closed - check if we got completion
                 timing.try_recv += recv_start.elapsed();
-                if task_started {
+                if root_task.is_some() {
                     return Err(RoutingError::ExecutionFailed(format!(
                         "Task event channel closed after {events_received} events \
                          without TaskCompleted/TaskFailed"
@@ -185,6 +203,27 @@ pub async fn await_task_completion(
     }
 }
 
+/// Answers a question of the task with the current `user_answer` fixture event
+///
+/// # Errors
+/// Returns error if the current fixture event is not a `user_answer` or input fails
+async fn answer_question(
+    tui_app: &mut TuiApp<TestBackend>,
+    event_controller: &FixtureEventController,
+    question: &str,
+) -> Result<()> {
+    if !event_controller.at_user_answer() {
+        return Err(RoutingError::ExecutionFailed(format!(
+            "Task asked '{question}' but the next fixture event is not a user_answer"
+        )));
+    }
+    while let Some(event) = tui_test_helpers::next_input_event(tui_app).await? {
+        tui_test_helpers::handle_input(tui_app, &event);
+    }
+    event_controller.advance();
+    Ok(())
+}
+
 /// Complete a pending task by adding its result to the tracker
 pub fn complete_pending_task(
     pending_task: &mut Option<(PendingTaskResult, String)>,
@@ -0,0 +1,115 @@
+//! Tests for recording and replaying sessions
+
+use super::*;
+use serde_json::{Value, from_value, json};
+use tokio::task::LocalSet;
+
+/// Scripted two-task session editing and listing workspace files
+fn scripted_session() -> Value {
+    json!({
+        "name": "Scripted Session",
+        "description": "Session recorded by the round-trip test",
+        "setup": {
+            "files": { "src/lib.rs": "pub fn add(a: i32, b: i32) -> i32 { a + b }\n" }
+        },
+        "events": [
+            { "type": "user_input", "data": { "text": "Rename add to sum", "submit": true } },
+            {
+                "type": "llm_response",
+                "strategy": {
+                    "type": "once",
+                    "response": { "typescript": [
+                        "async function agent_code(): Promise<string> {",
+                        "  const source = await readFile('src/lib.rs');",
+                        "  await writeFile('src/lib.rs', source.replace('fn add', 'fn sum'));",
+                        "  return 'renamed';",
+                        "}"
+                    ] }
+                }
+            },
+            { "type": "user_input", "data": { "text": "List the sources", "submit": true } },
+            {
+                "type": "llm_response",
+                "strategy": {
+                    "type": "once",
+                    "response": { "typescript": [
+                        "async function agent_code(): Promise<string> {",
+                        "  const files = await listFiles('src');",
+                        "  return files.join(', ');",
+                        "}"
+                    ] }
+                }
+            }
+        ]
+    })
+}
+
+/// Runs a fixture to completion, optionally recording it
+///
+/// # Errors
+/// Returns an error if the runner cannot be created, the fixture fails or
+/// the recorded fixture cannot be built.
+async fn run_fixture(
+    fixture: TestFixture,
+    record: bool,
+) -> Result<(VerificationResult, Option<Value>)> {
+    let mut runner = if record {
+        UnifiedTestRunner::new_recording(fixture)?
+    } else {
+        UnifiedTestRunner::new(fixture)?
+    };
+    let result = runner.run().await?;
+    let recorded = match runner.session_recorder() {
+        Some(recorder) => Some(recorder.to_fixture("recorded_session").await?),
+        None => None,
+    };
+    Ok((result, recorded))
+}
+
+/// Tests that a recorded session replays with its generated verification passing.
+///
+/// # Errors
+/// Returns an error if a fixture fails to run or parse.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_recorded_session_replays() -> Result<()> {
+    LocalSet::new()
+        .run_until(async {
+            let scripted: TestFixture = from_value(scripted_session())?;
+            let (result, recorded) = Box::pin(run_fixture(scripted, true)).await?;
+            assert!(result.passed, "recording failed: {:?}", result.failures);
+            let recorded =
+                recorded.ok_or_else(|| RoutingError::Other("nothing recorded".to_owned()))?;
+
+            assert_eq!(
+                recorded["events"][1]["verify"]["execution"]["return_value_matches"],
+                json!("^renamed$")
+            );
+            assert_eq!(
+                recorded["final_verify"]["files"],
+                json!([{
+                    "path": "src/lib.rs",
+                    "exact_content": "pub fn sum(a: i32, b: i32) -> i32 { a + b }\n"
+                }])
+            );
+            assert_eq!(
+                recorded["final_verify"]["tool_calls"]["total_calls"],
+                json!(3)
+            );
+
+            let (replayed, _) = Box::pin(run_fixture(from_value(recorded)?, false)).await?;
+            assert!(replayed.passed, "replay failed: {:?}", replayed.failures);
+            assert!(
+                replayed
+                    .successes
+                    .iter()
+                    .any(|check| check == "Return value matches pattern: ^renamed$"),
+                "replay skipped the generated checks: {:?}",
+                replayed.successes
+            );
+            Ok(())
+        })
+        .await
+}
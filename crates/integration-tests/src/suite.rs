@@ -0,0 +1,381 @@
+//! Concurrent execution of fixture suites.
+//!
+//! Each fixture runs on a blocking thread with its own current-thread runtime
+//! and `LocalSet` (the TypeScript runtime is `!Send`), and its runner owns its
+//! workspace, `.merlin` state, thread store and mock provider, so fixtures
+//! share nothing. Fixtures marked `serial` run one at a time after the rest.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, StreamExt as _};
+use num_cpus::get as get_cpus;
+use tokio::runtime::Builder;
+use tokio::task::{LocalSet, spawn_blocking};
+use tracing::{debug, warn};
+
+use crate::fixture_loader::load_fixture;
+use crate::runner::UnifiedTestRunner;
+use crate::verification_result::VerificationResult;
+
+/// Environment variable overriding the number of fixtures run at once
+pub const PARALLELISM_ENV: &str = "MERLIN_FIXTURE_JOBS";
+
+/// Fixtures taking at least this long are logged as slow
+const SLOW_FIXTURE: Duration = Duration::from_secs(1);
+
+/// How a fixture suite is run
+#[derive(Debug, Clone)]
+pub struct SuiteConfig {
+    /// Number of fixtures run at once
+    pub parallelism: usize,
+    /// Rerun failed fixtures once, marking them flaky if the rerun passes
+    pub retry_failed: bool,
+}
+
+impl SuiteConfig {
+    /// Default configuration, with parallelism taken from `MERLIN_FIXTURE_JOBS` if set
+    #[must_use]
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let parallelism = env::var(PARALLELISM_ENV)
+            .ok()
+            .and_then(|jobs| jobs.parse().ok())
+            .unwrap_or(defaults.parallelism);
+        Self {
+            parallelism,
+            ..defaults
+        }
+    }
+}
+
+impl Default for SuiteConfig {
+    fn default() -> Self {
+        Self {
+            parallelism: get_cpus(),
+            retry_failed: true,
+        }
+    }
+}
+
+/// Result of running one fixture
+#[derive(Debug, Clone)]
+pub struct FixtureOutcome {
+    /// Fixture file
+    pub path: PathBuf,
+    /// Fixture file name
+    pub name: String,
+    /// Directory the fixture is in
+    pub category: String,
+    /// Verification result of the first attempt, or of the retry if it passed
+    pub result: VerificationResult,
+    /// Time spent on all attempts
+    pub duration: Duration,
+    /// Number of times the fixture was run
+    pub attempts: usize,
+}
+
+impl FixtureOutcome {
+    /// Whether the fixture passed, possibly on retry
+    #[must_use]
+    pub const fn passed(&self) -> bool {
+        self.result.passed
+    }
+
+    /// Whether the fixture failed first and passed on retry
+    #[must_use]
+    pub const fn is_flaky(&self) -> bool {
+        self.result.passed && self.attempts > 1
+    }
+}
+
+/// Results of a fixture suite
+#[derive(Debug, Clone, Default)]
+pub struct SuiteSummary {
+    /// Outcome of each fixture, ordered by path
+    pub outcomes: Vec<FixtureOutcome>,
+    /// Wall-clock time of the whole suite
+    pub duration: Duration,
+}
+
+impl SuiteSummary {
+    /// Whether every fixture passed
+    #[must_use]
+    pub fn all_passed(&self) -> bool {
+        self.outcomes.iter().all(FixtureOutcome::passed)
+    }
+
+    /// Fixtures that passed
+    pub fn passed(&self) -> impl Iterator<Item = &FixtureOutcome> {
+        self.outcomes.iter().filter(|outcome| outcome.passed())
+    }
+
+    /// Fixtures that failed
+    pub fn failed(&self) -> impl Iterator<Item = &FixtureOutcome> {
+        self.outcomes.iter().filter(|outcome| !outcome.passed())
+    }
+
+    /// Fixtures that only passed on retry
+    pub fn flaky(&self) -> impl Iterator<Item = &FixtureOutcome> {
+        self.outcomes.iter().filter(|outcome| outcome.is_flaky())
+    }
+
+    /// Number of fixtures and total time per category
+    #[must_use]
+    pub fn category_stats(&self) -> HashMap<String, (usize, Duration)> {
+        let mut stats: HashMap<String, (usize, Duration)> = HashMap::new();
+        for outcome in &self.outcomes {
+            let entry = stats
+                .entry(outcome.category.clone())
+                .or_insert((0, Duration::ZERO));
+            entry.0 += 1;
+            entry.1 += outcome.duration;
+        }
+        stats
+    }
+
+    /// The `count` slowest fixtures with their durations, slowest first
+    #[must_use]
+    pub fn timing_report(&self, count: usize) -> String {
+        let mut outcomes: Vec<_> = self.outcomes.iter().collect();
+        outcomes.sort_by_key(|outcome| Reverse(outcome.duration));
+        let slowest: Vec<String> = outcomes
+            .into_iter()
+            .take(count)
+            .map(|outcome| {
+                format!(
+                    "  {:>8.3}s  {}/{}",
+                    outcome.duration.as_secs_f64(),
+                    outcome.category,
+                    outcome.name
+                )
+            })
+            .collect();
+        format!(
+            "{} fixture(s) in {:.2}s, slowest:\n{}\n",
+            self.outcomes.len(),
+            self.duration.as_secs_f64(),
+            slowest.join("\n")
+        )
+    }
+
+    /// Failed fixtures with their failures
+    #[must_use]
+    pub fn failure_report(&self) -> String {
+        let failed: Vec<_> = self.failed().collect();
+        let mut report = format!("{} fixture(s) failed:\n", failed.len());
+        for outcome in failed {
+            report.push('\n');
+            report.push_str(&outcome.name);
+            report.push_str(":\n");
+            for failure in &outcome.result.failures {
+                report.push_str("  - ");
+                report.push_str(failure);
+                report.push('\n');
+            }
+        }
+        report
+    }
+}
+
+/// Run fixtures concurrently, then the `serial` ones one at a time
+pub async fn run_all(fixtures: Vec<PathBuf>, config: &SuiteConfig) -> SuiteSummary {
+    let start = Instant::now();
+    let (serial, parallel): (Vec<_>, Vec<_>) = fixtures
+        .into_iter()
+        .partition(|path| load_fixture(path).is_ok_and(|fixture| fixture.setup.serial));
+
+    let mut outcomes: Vec<FixtureOutcome> = stream::iter(parallel)
+        .map(|path| run_with_retry(path, config.retry_failed))
+        .buffer_unordered(config.parallelism.max(1))
+        .collect()
+        .await;
+    for path in serial {
+        outcomes.push(run_with_retry(path, config.retry_failed).await);
+    }
+    outcomes.sort_by(|left, right| left.path.cmp(&right.path));
+
+    SuiteSummary {
+        outcomes,
+        duration: start.elapsed(),
+    }
+}
+
+/// Run a fixture, running it once more if it fails and `retry` is set
+async fn run_with_retry(path: PathBuf, retry: bool) -> FixtureOutcome {
+    let name = file_name(&path);
+    let category = path
+        .parent()
+        .map_or_else(|| "unknown".to_owned(), file_name);
+
+    let (mut result, mut duration) = run_isolated(path.clone()).await;
+    let mut attempts = 1;
+    if !result.passed && retry {
+        let (retried, retry_duration) = run_isolated(path.clone()).await;
+        attempts += 1;
+        duration += retry_duration;
+        if retried.passed {
+            warn!(
+                "[FLAKY] {category}/{name} failed, then passed on retry: {:?}",
+                result.failures
+            );
+            result = retried;
+        }
+    }
+    if duration >= SLOW_FIXTURE {
+        debug!("[SLOW] {category}/{name} took {duration:?}");
+    }
+
+    FixtureOutcome {
+        path,
+        name,
+        category,
+        result,
+        duration,
+        attempts,
+    }
+}
+
+/// Run a fixture on its own thread and runtime, turning errors and panics into failures
+async fn run_isolated(path: PathBuf) -> (VerificationResult, Duration) {
+    let start = Instant::now();
+    let joined = spawn_blocking(move || {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|err| format!("Failed to create runtime for fixture: {err}"))?;
+        LocalSet::new().block_on(&runtime, run_fixture(&path))
+    })
+    .await;
+
+    let result = match joined {
+        Ok(Ok(result)) => result,
+        Ok(Err(message)) => failed_result(message),
+        Err(join_err) => failed_result(format!("Fixture task panicked: {join_err}")),
+    };
+    (result, start.elapsed())
+}
+
+/// Load and run a fixture
+///
+/// # Errors
+/// Returns an error if the fixture cannot be loaded, set up or run
+async fn run_fixture(path: &Path) -> Result<VerificationResult, String> {
+    let name = file_name(path);
+    let fixture = UnifiedTestRunner::load_fixture(path)
+        .map_err(|err| format!("Failed to load fixture {name}: {err}"))?;
+    let mut runner = UnifiedTestRunner::new(fixture)
+        .map_err(|err| format!("Failed to create runner for {name}: {err}"))?;
+    runner
+        .run()
+        .await
+        .map_err(|err| format!("Failed to run fixture {name}: {err}"))
+}
+
+/// Verification result with a single failure
+fn failed_result(message: String) -> VerificationResult {
+    let mut result = VerificationResult::new();
+    result.add_failure(message);
+    result
+}
+
+/// Last component of a path, or `unknown`
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map_or_else(|| "unknown".to_owned(), ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::Result;
+    use serde_json::{Value, json, to_string};
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Fixture whose only task returns `returned` and must return `expected`
+    fn fixture(returned: &str, expected: &str, serial: bool) -> Value {
+        json!({
+            "name": format!("returns {returned}"),
+            "description": "Suite runner test fixture",
+            "setup": { "files": { "notes.txt": "notes" }, "serial": serial },
+            "events": [
+                { "type": "user_input", "data": { "text": "Say something", "submit": true } },
+                {
+                    "type": "llm_response",
+                    "verify": { "execution": { "return_value_matches": format!("^{expected}$") } },
+                    "strategy": {
+                        "type": "once",
+                        "response": { "typescript": [
+                            "async function agent_code(): Promise<string> {",
+                            format!("  return '{returned}';"),
+                            "}"
+                        ] }
+                    }
+                }
+            ]
+        })
+    }
+
+    /// Tests that a suite runs parallel and serial fixtures and retries failures.
+    ///
+    /// # Errors
+    /// Returns an error if the fixtures cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_run_all_summarizes_fixtures() -> Result<()> {
+        let fixtures_dir = TempDir::new()?;
+        let category = fixtures_dir.path().join("suite");
+        fs::create_dir_all(&category)?;
+        let fixtures = [
+            ("pass.json", fixture("one", "one", false)),
+            ("serial.json", fixture("two", "two", true)),
+            ("fail.json", fixture("three", "four", false)),
+        ];
+        for (file, content) in &fixtures {
+            fs::write(category.join(file), to_string(content)?)?;
+        }
+
+        let paths = UnifiedTestRunner::discover_fixtures(fixtures_dir.path())?;
+        let config = SuiteConfig {
+            parallelism: 2,
+            retry_failed: true,
+        };
+        let summary = UnifiedTestRunner::run_all(paths, &config).await;
+
+        let names: Vec<_> = summary
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.name.as_str())
+            .collect();
+        assert_eq!(names, ["fail.json", "pass.json", "serial.json"]);
+        assert_eq!(summary.passed().count(), 2);
+        assert_eq!(summary.flaky().count(), 0);
+        assert!(!summary.all_passed());
+        assert!(
+            summary
+                .outcomes
+                .iter()
+                .all(|outcome| outcome.category == "suite")
+        );
+
+        let failed: Vec<_> = summary.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].name, "fail.json");
+        assert_eq!(failed[0].attempts, 2);
+        assert!(
+            summary
+                .failure_report()
+                .starts_with("1 fixture(s) failed:\n\nfail.json:\n  - ")
+        );
+        assert_eq!(summary.category_stats()["suite"].0, 3);
+        assert_eq!(summary.timing_report(2).lines().count(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+//! Tool call verification logic.
+
+use super::verification_result::VerificationResult;
+use super::verify::{ToolCallExpectation, ToolCallVerify};
+use merlin_tooling::RecordedToolCall;
+use regex::Regex;
+use serde_json::{Value, to_string};
+use std::fmt::Write as _;
+
+/// Tool call verifier helper
+pub struct ToolCallVerifier;
+
+impl ToolCallVerifier {
+    /// Verify recorded tool calls, listing every call if any check fails
+    pub fn verify_tool_calls(
+        result: &mut VerificationResult,
+        calls: &[RecordedToolCall],
+        verify: &ToolCallVerify,
+    ) {
+        let mut checks = VerificationResult::new();
+        Self::verify_ordered(&mut checks, calls, &verify.ordered);
+        Self::verify_unordered(&mut checks, calls, &verify.unordered);
+
+        for tool in &verify.not_called {
+            let count = calls.iter().filter(|call| &call.tool == tool).count();
+            if count == 0 {
+                checks.add_success(format!("Tool {tool} was not called"));
+            } else {
+                checks.add_failure(format!("Tool {tool} was called {count} time(s)"));
+            }
+        }
+
+        if let Some(expected) = verify.total_calls {
+            if calls.len() == expected {
+                checks.add_success(format!("{expected} tool call(s) made"));
+            } else {
+                checks.add_failure(format!(
+                    "Expected {expected} tool call(s), got {}",
+                    calls.len()
+                ));
+            }
+        }
+
+        if !checks.passed {
+            checks.add_failure(format!(
+                "Recorded tool calls:\n{}",
+                Self::format_calls(calls)
+            ));
+        }
+        result.merge(checks);
+    }
+
+    /// Verify that the expected calls happened in order
+    fn verify_ordered(
+        result: &mut VerificationResult,
+        calls: &[RecordedToolCall],
+        expectations: &[ToolCallExpectation],
+    ) {
+        if expectations.is_empty() {
+            return;
+        }
+        let mut next = 0;
+        for expectation in expectations {
+            let Some(matcher) = CallMatcher::new(result, expectation) else {
+                return;
+            };
+            for _ in 0..expectation.count.unwrap_or(1) {
+                let Some(position) = calls[next..].iter().position(|call| matcher.matches(call))
+                else {
+                    result.add_failure(format!(
+                        "Expected call {} after call #{next} in order",
+                        matcher.describe()
+                    ));
+                    return;
+                };
+                next += position + 1;
+            }
+        }
+        result.add_success(format!(
+            "{} tool call expectation(s) met in order",
+            expectations.len()
+        ));
+    }
+
+    /// Verify that the expected calls happened in any order
+    fn verify_unordered(
+        result: &mut VerificationResult,
+        calls: &[RecordedToolCall],
+        expectations: &[ToolCallExpectation],
+    ) {
+        for expectation in expectations {
+            let Some(matcher) = CallMatcher::new(result, expectation) else {
+                continue;
+            };
+            let count = calls.iter().filter(|call| matcher.matches(call)).count();
+            match expectation.count {
+                Some(expected) if count != expected => result.add_failure(format!(
+                    "Expected {expected} call(s) {}, got {count}",
+                    matcher.describe()
+                )),
+                None if count == 0 => {
+                    result.add_failure(format!("Expected a call {}", matcher.describe()));
+                }
+                _ => result.add_success(format!("{count} call(s) {}", matcher.describe())),
+            }
+        }
+    }
+
+    /// Format calls as a numbered list
+    fn format_calls(calls: &[RecordedToolCall]) -> String {
+        if calls.is_empty() {
+            return "  (none)".to_owned();
+        }
+        let mut listing = String::new();
+        for (index, call) in calls.iter().enumerate() {
+            _ = writeln!(
+                listing,
+                "  {}. {}({})",
+                index + 1,
+                call.tool,
+                to_string(&call.params).unwrap_or_default()
+            );
+        }
+        listing.trim_end().to_owned()
+    }
+}
+
+/// Expectation with its regex compiled
+struct CallMatcher<'expect> {
+    /// Expectation being matched
+    expectation: &'expect ToolCallExpectation,
+    /// Compiled `args_regex`
+    args_regex: Option<Regex>,
+}
+
+impl<'expect> CallMatcher<'expect> {
+    /// Compile an expectation, recording a failure if its regex is invalid
+    fn new(
+        result: &mut VerificationResult,
+        expectation: &'expect ToolCallExpectation,
+    ) -> Option<Self> {
+        let args_regex = match expectation.args_regex.as_deref().map(Regex::new) {
+            Some(Ok(regex)) => Some(regex),
+            Some(Err(err)) => {
+                result.add_failure(format!(
+                    "Invalid args_regex for tool {}: {err}",
+                    expectation.tool
+                ));
+                return None;
+            }
+            None => None,
+        };
+        Some(Self {
+            expectation,
+            args_regex,
+        })
+    }
+
+    /// Whether a call meets the expectation
+    fn matches(&self, call: &RecordedToolCall) -> bool {
+        call.tool == self.expectation.tool
+            && self.args_regex.as_ref().is_none_or(|regex| {
+                to_string(&call.params).is_ok_and(|params| regex.is_match(&params))
+            })
+            && self
+                .expectation
+                .args
+                .iter()
+                .all(|(path, expected)| json_path(&call.params, path) == Some(expected))
+    }
+
+    /// Describe the expected call for messages
+    fn describe(&self) -> String {
+        let mut description = format!("to {}", self.expectation.tool);
+        if let Some(regex) = &self.args_regex {
+            _ = write!(description, " with args matching /{regex}/");
+        }
+        for (path, expected) in &self.expectation.args {
+            _ = write!(description, " with {path} = {expected}");
+        }
+        description
+    }
+}
+
+/// Look up a value by JSON path (`$`, `$.field.0`, or `field.0`)
+fn json_path<'value>(value: &'value Value, path: &str) -> Option<&'value Value> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => current.get(segment),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Error, from_value, json};
+
+    /// Builds a recorded call
+    fn call(tool: &str, params: Value) -> RecordedToolCall {
+        RecordedToolCall {
+            tool: tool.to_owned(),
+            params,
+        }
+    }
+
+    /// Recorded calls of an agent reading, editing and re-reading a file
+    fn read_edit_read() -> Vec<RecordedToolCall> {
+        vec![
+            call("readFile", json!("src/lib.rs")),
+            call(
+                "editFile",
+                json!({ "path": "src/lib.rs", "old_string": "a + b", "new_string": "a * b" }),
+            ),
+            call("readFile", json!("src/lib.rs")),
+        ]
+    }
+
+    /// Tests that calls in the expected order, count and arguments pass.
+    ///
+    /// # Errors
+    /// Returns an error if the verification config does not parse.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_matching_calls_pass() -> Result<(), Error> {
+        let verify: ToolCallVerify = from_value(json!({
+            "ordered": [
+                { "tool": "readFile", "args": { "$": "src/lib.rs" } },
+                { "tool": "editFile", "args": { "$.path": "src/lib.rs" }, "args_regex": "a \\* b" },
+                { "tool": "readFile" }
+            ],
+            "unordered": [{ "tool": "readFile", "count": 2 }],
+            "not_called": ["deleteFile"],
+            "total_calls": 3
+        }))?;
+        let mut result = VerificationResult::new();
+        ToolCallVerifier::verify_tool_calls(&mut result, &read_edit_read(), &verify);
+        assert!(result.passed, "failures: {:?}", result.failures);
+        Ok(())
+    }
+
+    /// Tests that out-of-order calls fail and list the recorded sequence.
+    ///
+    /// # Errors
+    /// Returns an error if the verification config does not parse.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_mismatches_list_recorded_calls() -> Result<(), Error> {
+        let verify: ToolCallVerify = from_value(json!({
+            "ordered": [
+                { "tool": "editFile" },
+                { "tool": "readFile", "count": 2 }
+            ],
+            "unordered": [{ "tool": "editFile", "args": { "path": "src/main.rs" } }],
+            "not_called": ["readFile"]
+        }))?;
+        let mut result = VerificationResult::new();
+        ToolCallVerifier::verify_tool_calls(&mut result, &read_edit_read(), &verify);
+
+        assert_eq!(
+            result.failures,
+            [
+                "Expected call to readFile after call #3 in order",
+                "Expected a call to editFile with path = \"src/main.rs\"",
+                "Tool readFile was called 2 time(s)",
+                "Recorded tool calls:\n  1. readFile(\"src/lib.rs\")\n  2. editFile({\"path\":\"src/lib.rs\",\"old_string\":\"a + b\",\"new_string\":\"a * b\"})\n  3. readFile(\"src/lib.rs\")",
+            ]
+        );
+        Ok(())
+    }
+}
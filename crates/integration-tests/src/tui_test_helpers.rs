@@ -12,22 +12,23 @@ use merlin_cli::ui::persistence::TaskPersistence;
 use merlin_cli::ui::renderer::{FocusedPane, Renderer};
 use merlin_cli::ui::state::UiState;
 use merlin_cli::ui::task_manager::TaskManager;
-use merlin_routing::{Result, RoutingError, UiEvent};
+use merlin_core::UiEventReceiver;
+use merlin_core::ui::DEFAULT_UI_EVENT_CAPACITY;
+use merlin_routing::{Result, RoutingError, UiChannel};
 use ratatui::Terminal;
 use ratatui::backend::Backend;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Instant;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::broadcast;
 
 /// Shared test config manager (no disk I/O)
 static TEST_CONFIG_MANAGER: OnceLock<ConfigManager> = OnceLock::new();
 
 /// Get or create the shared test config manager
 fn get_test_config_manager() -> &'static ConfigManager {
-    TEST_CONFIG_MANAGER.get_or_init(|| ConfigManager {
-        inner: Arc::new(RwLock::new(Config::default())),
-        config_path: PathBuf::from("/tmp/test-config.toml"),
+    TEST_CONFIG_MANAGER.get_or_init(|| {
+        ConfigManager::from_config(Config::default(), PathBuf::from("/tmp/test-config.toml"))
     })
 }
 
@@ -41,7 +42,7 @@ pub fn new_test_app<B: Backend>(
     tasks_dir: impl Into<Option<PathBuf>>,
     orchestrator: Option<Arc<RoutingOrchestrator>>,
 ) -> Result<TuiApp<B>> {
-    let (sender, receiver) = mpsc::unbounded_channel();
+    let (sender, receiver) = UiChannel::bounded(DEFAULT_UI_EVENT_CAPACITY);
     let (broadcast_sender, _) = broadcast::channel(100);
 
     let mut terminal =
@@ -109,6 +110,7 @@ pub fn new_test_app<B: Backend>(
             orchestrator,
             persistence,
             log_file: None,
+            notifier: None,
         },
         config_manager: config_manager.clone(),
     };
@@ -118,9 +120,10 @@ pub fn new_test_app<B: Backend>(
 
 /// Process pending UI events for testing (non-blocking)
 pub fn process_ui_events<B: Backend>(app: &mut TuiApp<B>) {
-    while let Ok(ui_event) = app.event_system.receiver.try_recv() {
+    while let Some(ui_event) = app.event_system.receiver.try_recv() {
         // Broadcast to observers
         drop(app.event_system.broadcast.send(ui_event.clone()));
+        app.ui_components.state.coalesced_events = app.event_system.receiver.coalesced_events();
 
         // Handle the event
         let persistence = app.runtime_state.persistence.as_ref();
@@ -167,7 +170,7 @@ pub fn handle_input<B: Backend>(app: &mut TuiApp<B>, event: &CrosstermEvent) {
 ///
 /// # Errors
 /// Returns error if no task has been spawned yet
-pub fn get_task_receiver<B: Backend>(app: &mut TuiApp<B>) -> Result<mpsc::Receiver<UiEvent>> {
+pub fn get_task_receiver<B: Backend>(app: &mut TuiApp<B>) -> Result<UiEventReceiver> {
     app.event_system.last_task_receiver.take().ok_or_else(|| {
         RoutingError::Other("No task receiver available - did you spawn a task?".to_owned())
     })
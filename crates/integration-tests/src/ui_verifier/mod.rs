@@ -7,10 +7,12 @@ use merlin_cli::ui::input::InputManager;
 use merlin_cli::ui::renderer::FocusedPane;
 use merlin_cli::ui::task_manager::TaskManager;
 use ratatui::backend::TestBackend;
+use std::path::Path;
 
 mod input;
 mod output;
 mod rendered_buffer;
+mod snapshot;
 mod state;
 mod task_counts;
 mod task_details;
@@ -21,6 +23,8 @@ mod work_unit;
 use input::verify_input_related_fields;
 use output::verify_output_patterns;
 use rendered_buffer::verify_rendered_buffer;
+use snapshot::verify_snapshot;
+pub use snapshot::{BLESS_SNAPSHOTS_ENV, DEFAULT_SNAPSHOT_MASKS, normalize_snapshot};
 use task_counts::verify_task_counts;
 use task_details::verify_task_details;
 use task_selection::verify_selected_task;
@@ -31,11 +35,12 @@ use work_unit::verify_work_unit;
 pub struct UiVerifier;
 
 impl UiVerifier {
-    /// Verify UI
+    /// Verify UI, resolving snapshot files relative to `snapshot_dir`
     pub async fn verify_ui(
         result: &mut VerificationResult,
         tui_app: Option<&TuiApp<TestBackend>>,
         verify: &UiVerify,
+        snapshot_dir: Option<&Path>,
     ) {
         let Some(app) = tui_app else {
             result.add_failure("TUI app not available for verification".to_owned());
@@ -55,6 +60,7 @@ impl UiVerifier {
         Self::verify_ui_states(result, task_manager, input_manager, verify);
         verify_thread_state(result, app, state, verify);
         verify_rendered_buffer(result, app, verify);
+        verify_snapshot(result, app, verify, snapshot_dir);
 
         // Verify WorkUnit if specified
         if let Some(ref work_unit_verify) = verify.work_unit {
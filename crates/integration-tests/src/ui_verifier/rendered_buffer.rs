@@ -18,7 +18,7 @@ pub fn verify_rendered_buffer(
 }
 
 /// Convert buffer to string representation
-fn buffer_to_string(buffer: &Buffer) -> String {
+pub fn buffer_to_string(buffer: &Buffer) -> String {
     let area = buffer.area();
     let mut result = String::new();
 
@@ -121,6 +121,11 @@ fn find_region_by_boundary(buffer_content: &str, region_name: &str) -> Option<St
     let start_idx = start_idx?;
     let end_marker = end_marker?;
 
+    // Side-by-side panes share rows, so prefer cutting the region out of its bordered box
+    if let Some(region) = extract_bordered_box(buffer_content, start_idx, end_marker) {
+        return Some(region);
+    }
+
     // Extract content from after the boundary marker to the next boundary or end
     let content_start = start_idx + end_marker.len();
     let remaining = &buffer_content[content_start..];
@@ -141,6 +146,42 @@ fn find_region_by_boundary(buffer_content: &str, region_name: &str) -> Option<St
     Some(remaining[..end_idx].to_owned())
 }
 
+/// Extract the box whose top border holds the title at `title_idx`
+///
+/// Returns the rest of the title row followed by every row between the box's
+/// side borders, or `None` if the title is not part of a complete box.
+fn extract_bordered_box(buffer_content: &str, title_idx: usize, title: &str) -> Option<String> {
+    let rows: Vec<Vec<char>> = buffer_content
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect();
+    let before_title = &buffer_content[..title_idx];
+    let row = before_title.matches('\n').count();
+    let row_start = before_title.rfind('\n').map_or(0, |idx| idx + 1);
+    let title_column = before_title[row_start..].chars().count();
+
+    let top = rows.get(row)?;
+    let left = top
+        .get(..title_column)?
+        .iter()
+        .rposition(|&symbol| symbol == '┌')?;
+    let right = title_column
+        + top
+            .get(title_column..)?
+            .iter()
+            .position(|&symbol| symbol == '┐')?;
+
+    let title_end = (title_column + title.chars().count()).min(right);
+    let mut region: Vec<String> = vec![top.get(title_end..right)?.iter().collect()];
+    for line in rows.iter().skip(row + 1) {
+        if line.get(left) == Some(&'└') {
+            return Some(region.join("\n"));
+        }
+        region.push(line.get(left + 1..right)?.iter().collect());
+    }
+    None
+}
+
 /// Verify rendered buffer regions
 fn verify_rendered_buffer_regions(
     result: &mut VerificationResult,
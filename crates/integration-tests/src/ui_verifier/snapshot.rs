@@ -0,0 +1,211 @@
+//! Golden snapshot verification of the rendered buffer.
+//!
+//! The rendered buffer is normalized to a text grid (volatile content masked,
+//! trailing whitespace trimmed) and compared with a golden file next to the
+//! fixture. On mismatch the actual grid and a unified diff are written beside
+//! the golden file as `<golden>.actual` and `<golden>.diff`. Running with
+//! `MERLIN_BLESS_SNAPSHOTS=1` writes new or changed golden files instead.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use merlin_cli::TuiApp;
+use ratatui::backend::TestBackend;
+use regex::{Captures, Regex};
+use similar::TextDiff;
+
+use super::rendered_buffer::buffer_to_string;
+use crate::verification_result::VerificationResult;
+use crate::verify::UiVerify;
+
+/// Environment variable that, set to `1`, writes golden snapshots instead of comparing them
+pub const BLESS_SNAPSHOTS_ENV: &str = "MERLIN_BLESS_SNAPSHOTS";
+
+/// Volatile content masked in every snapshot: clock times, durations and UUIDs
+pub const DEFAULT_SNAPSHOT_MASKS: [&str; 3] = [
+    r"\b\d{1,2}:\d{2}(?::\d{2})?\b",
+    r"\b\d+(?:\.\d+)?(?:ms|s)\b",
+    r"\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+];
+
+/// Symbol masked content is replaced with, one per masked character
+const MASK_SYMBOL: &str = "*";
+
+/// Normalize rendered buffer text for snapshots
+///
+/// Each match of `masks` is replaced by as many `*` as it has characters so
+/// the layout is kept, and trailing whitespace is trimmed from every line.
+#[must_use]
+pub fn normalize_snapshot(rendered: &str, masks: &[Regex]) -> String {
+    let mut lines: Vec<String> = rendered.lines().map(ToOwned::to_owned).collect();
+    for line in &mut lines {
+        for mask in masks {
+            *line = mask
+                .replace_all(line, |captures: &Captures<'_>| {
+                    MASK_SYMBOL.repeat(captures[0].chars().count())
+                })
+                .into_owned();
+        }
+        line.truncate(line.trim_end().len());
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Verify the rendered buffer against the golden snapshot, if one is configured
+pub fn verify_snapshot(
+    result: &mut VerificationResult,
+    tui_app: &TuiApp<TestBackend>,
+    verify: &UiVerify,
+    snapshot_dir: Option<&Path>,
+) {
+    let Some(snapshot) = verify.snapshot.as_deref() else {
+        return;
+    };
+    let Some(snapshot_dir) = snapshot_dir else {
+        result.add_failure(format!(
+            "Snapshot '{snapshot}' requires a fixture loaded from a file"
+        ));
+        return;
+    };
+    let masks = match compile_masks(&verify.snapshot_masks) {
+        Ok(masks) => masks,
+        Err(message) => {
+            result.add_failure(message);
+            return;
+        }
+    };
+
+    let actual = normalize_snapshot(
+        &buffer_to_string(tui_app.terminal.backend().buffer()),
+        &masks,
+    );
+    let golden = snapshot_dir.join(snapshot);
+    if env::var_os(BLESS_SNAPSHOTS_ENV).is_some_and(|value| value == "1") {
+        bless_snapshot(result, &golden, &actual);
+    } else {
+        compare_snapshot(result, &golden, &actual);
+    }
+}
+
+/// Compile the default masks followed by the fixture's own
+///
+/// # Errors
+/// Returns a failure message naming the first invalid mask
+fn compile_masks(extra_masks: &[String]) -> Result<Vec<Regex>, String> {
+    DEFAULT_SNAPSHOT_MASKS
+        .iter()
+        .copied()
+        .chain(extra_masks.iter().map(String::as_str))
+        .map(|mask| {
+            Regex::new(mask).map_err(|err| format!("Invalid snapshot mask '{mask}': {err}"))
+        })
+        .collect()
+}
+
+/// Compare the normalized buffer with the golden file, writing the actual output and a diff on mismatch
+fn compare_snapshot(result: &mut VerificationResult, golden: &Path, actual: &str) {
+    let actual_path = sibling(golden, "actual");
+    let diff_path = sibling(golden, "diff");
+    let Ok(expected) = fs::read_to_string(golden) else {
+        let written = write_review_file(&actual_path, actual);
+        result.add_failure(format!(
+            "Snapshot {} is missing; {written}, rerun with {BLESS_SNAPSHOTS_ENV}=1 to create it",
+            golden.display()
+        ));
+        return;
+    };
+
+    if expected == actual {
+        drop(fs::remove_file(&actual_path));
+        drop(fs::remove_file(&diff_path));
+        result.add_success(format!(
+            "Rendered buffer matches snapshot {}",
+            golden.display()
+        ));
+        return;
+    }
+
+    let diff = TextDiff::from_lines(expected.as_str(), actual)
+        .unified_diff()
+        .header("expected", "actual")
+        .to_string();
+    let written = [
+        write_review_file(&actual_path, actual),
+        write_review_file(&diff_path, &diff),
+    ];
+    result.add_failure(format!(
+        "Rendered buffer differs from snapshot {} ({}), rerun with {BLESS_SNAPSHOTS_ENV}=1 to accept:\n{diff}",
+        golden.display(),
+        written.join(", ")
+    ));
+}
+
+/// Write the normalized buffer as the new golden file
+fn bless_snapshot(result: &mut VerificationResult, golden: &Path, actual: &str) {
+    if fs::read_to_string(golden).is_ok_and(|expected| expected == actual) {
+        result.add_success(format!(
+            "Rendered buffer matches snapshot {}",
+            golden.display()
+        ));
+        return;
+    }
+    let written = golden
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(golden, actual));
+    match written {
+        Ok(()) => {
+            drop(fs::remove_file(sibling(golden, "actual")));
+            drop(fs::remove_file(sibling(golden, "diff")));
+            result.add_success(format!("Blessed snapshot {}", golden.display()));
+        }
+        Err(err) => result.add_failure(format!(
+            "Failed to write snapshot {}: {err}",
+            golden.display()
+        )),
+    }
+}
+
+/// Write a file for reviewing a mismatch, describing where it went
+fn write_review_file(path: &Path, content: &str) -> String {
+    match fs::write(path, content) {
+        Ok(()) => format!("wrote {}", path.display()),
+        Err(err) => format!("could not write {}: {err}", path.display()),
+    }
+}
+
+/// Path next to `golden` with `extension` appended to its file name
+fn sibling(golden: &Path, extension: &str) -> PathBuf {
+    let mut path = OsString::from(golden);
+    path.push(".");
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that volatile content is masked without shifting the layout.
+    ///
+    /// # Panics
+    /// Panics if a mask is invalid or assertions fail during test execution.
+    #[test]
+    fn test_normalize_snapshot_masks_volatile_content() {
+        let masks = compile_masks(&["Cost: \\$[0-9.]+".to_owned()]);
+        assert!(matches!(masks, Ok(ref compiled) if compiled.len() == 4));
+        let masks = masks.unwrap_or_default();
+
+        let rendered = "│ Done in 1.25s at 12:04:59 │   \n│ Cost: $0.0012          │\n│ 3f2504e0-4f89-11d3-9a0c-0305e82c3301 │";
+        assert_eq!(
+            normalize_snapshot(rendered, &masks),
+            "│ Done in ***** at ******** │\n│ *************          │\n│ ************************************ │\n"
+        );
+        assert!(
+            matches!(compile_masks(&["(".to_owned()]), Err(message) if message.starts_with("Invalid snapshot mask '('"))
+        );
+    }
+}
@@ -37,6 +37,7 @@ fn verify_status(work: &WorkUnit, expected: &str, result: &mut VerificationResul
         WorkStatus::Failed => "failed",
         WorkStatus::Cancelled => "cancelled",
         WorkStatus::Retrying => "retrying",
+        WorkStatus::Interrupted => "interrupted",
     };
 
     if actual == expected {
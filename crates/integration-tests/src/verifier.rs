@@ -5,11 +5,14 @@ use super::execution_verifier::ExecutionVerifier;
 use super::file_verifier::FileVerifier;
 use super::fixture::TestEvent;
 use super::mock_provider::MockProvider;
+use super::tool_call_verifier::ToolCallVerifier;
 use super::ui_verifier::UiVerifier;
 use super::verification_result::VerificationResult;
-use super::verify::{ExecutionVerify, FinalVerify, VerifyConfig};
+use super::verify::{ExecutionVerify, FinalVerify, PromptVerify, VerifyConfig};
 use merlin_cli::TuiApp;
 use merlin_cli::ui::task_manager::TaskStatus;
+use merlin_core::RoutingError;
+use merlin_tooling::ToolCallRecorder;
 use ratatui::backend::TestBackend;
 use std::path::Path;
 use std::result::Result;
@@ -19,6 +22,10 @@ use std::sync::Arc;
 pub struct UnifiedVerifier<'fixture> {
     /// Workspace root
     workspace_root: &'fixture Path,
+    /// Tool calls made by the agent
+    tool_calls: &'fixture ToolCallRecorder,
+    /// Directory UI snapshot files are relative to (the fixture's directory)
+    snapshot_dir: Option<&'fixture Path>,
     /// Accumulated result
     result: VerificationResult,
 }
@@ -40,13 +47,22 @@ pub struct VerifyEventContext<'ctx> {
 impl<'fixture> UnifiedVerifier<'fixture> {
     /// Create new verifier
     #[must_use]
-    pub fn new(workspace_root: &'fixture Path) -> Self {
+    pub fn new(workspace_root: &'fixture Path, tool_calls: &'fixture ToolCallRecorder) -> Self {
         Self {
             workspace_root,
+            tool_calls,
+            snapshot_dir: None,
             result: VerificationResult::new(),
         }
     }
 
+    /// Resolve UI snapshot files relative to `snapshot_dir`
+    #[must_use]
+    pub const fn with_snapshot_dir(mut self, snapshot_dir: Option<&'fixture Path>) -> Self {
+        self.snapshot_dir = snapshot_dir;
+        self
+    }
+
     /// Verify an event
     ///
     /// # Errors
@@ -62,31 +78,26 @@ impl<'fixture> UnifiedVerifier<'fixture> {
 
         // Verify execution if specified
         if let Some(exec_verify) = &verify.execution {
-            // Get execution result by ID or fall back to last result
-            let execution = exec_verify.execution_id.as_ref().map_or_else(
+            // Get execution record by ID or fall back to last result
+            let record = exec_verify.execution_id.as_ref().map_or_else(
                 || {
                     event.id().map_or_else(
-                        || execution_tracker.last_result().map(ExecutionRecord::result),
-                        |event_id| {
-                            execution_tracker
-                                .get_by_id(event_id)
-                                .map(ExecutionRecord::result)
-                        },
+                        || execution_tracker.last_result(),
+                        |event_id| execution_tracker.get_by_id(event_id),
                     )
                 },
-                |exec_id| {
-                    execution_tracker
-                        .get_by_id(exec_id)
-                        .map(ExecutionRecord::result)
-                },
+                |exec_id| execution_tracker.get_by_id(exec_id),
             );
 
             ExecutionVerifier::verify_execution(
                 &mut self.result,
-                execution,
+                record.map(ExecutionRecord::result),
                 exec_verify,
                 *provider,
             );
+            if let Some(expected_tokens) = exec_verify.tokens_used {
+                ExecutionVerifier::verify_tokens(&mut self.result, record, expected_tokens);
+            }
 
             // Verify routing/cache/metrics if orchestrator is available
             self.verify_routing_cache_metrics(*tui_app, exec_verify);
@@ -101,7 +112,7 @@ impl<'fixture> UnifiedVerifier<'fixture> {
 
         // Verify UI if specified
         if let Some(ui_verify) = &verify.ui {
-            UiVerifier::verify_ui(&mut self.result, *tui_app, ui_verify).await;
+            UiVerifier::verify_ui(&mut self.result, *tui_app, ui_verify, self.snapshot_dir).await;
         }
 
         // Verify state if specified
@@ -109,14 +120,21 @@ impl<'fixture> UnifiedVerifier<'fixture> {
             UiVerifier::verify_state(&mut self.result, *tui_app, state_verify).await;
         }
 
+        // Verify tool calls if specified
+        if let Some(tool_call_verify) = &verify.tool_calls {
+            let calls = self.tool_calls.calls().await;
+            ToolCallVerifier::verify_tool_calls(&mut self.result, &calls, tool_call_verify);
+        }
+
         // Verify prompt if specified
-        if let Some(_prompt_verify) = &verify.prompt {
-            if provider.is_some() {
+        if let Some(prompt_verify) = &verify.prompt {
+            if let Some(provider) = provider {
                 // Prompt verification now happens during execution, not after
                 // The system prompt is verified by checking the actual query sent to the provider
                 // For now, we'll skip prompt verification as it's handled during scope matching
                 self.result
                     .add_success("Prompt verification handled by scope matching system".to_owned());
+                Self::verify_request_counts(&mut self.result, provider, prompt_verify);
             } else {
                 self.result.add_failure(
                     "Prompt verification requested but no provider available".to_owned(),
@@ -140,17 +158,21 @@ impl<'fixture> UnifiedVerifier<'fixture> {
         // Verify final execution state
         if let Some(exec_verify) = &verify.execution {
             // Get execution by ID or use last result
-            let execution = exec_verify.execution_id.as_ref().map_or_else(
-                || execution_tracker.last_result().map(ExecutionRecord::result),
-                |exec_id| {
-                    execution_tracker
-                        .get_by_id(exec_id)
-                        .map(ExecutionRecord::result)
-                },
+            let record = exec_verify.execution_id.as_ref().map_or_else(
+                || execution_tracker.last_result(),
+                |exec_id| execution_tracker.get_by_id(exec_id),
             );
 
             // Success-by-default verification
-            ExecutionVerifier::verify_execution(&mut self.result, execution, exec_verify, None);
+            ExecutionVerifier::verify_execution(
+                &mut self.result,
+                record.map(ExecutionRecord::result),
+                exec_verify,
+                None,
+            );
+            if let Some(expected_tokens) = exec_verify.tokens_used {
+                ExecutionVerifier::verify_tokens(&mut self.result, record, expected_tokens);
+            }
 
             // Verify incomplete/failed tasks if explicitly specified
             if !exec_verify.incomplete_tasks.is_empty() || !exec_verify.failed_tasks.is_empty() {
@@ -175,7 +197,7 @@ impl<'fixture> UnifiedVerifier<'fixture> {
 
         // Verify final UI state if specified
         if let Some(ui_verify) = &verify.ui {
-            UiVerifier::verify_ui(&mut self.result, tui_app, ui_verify).await;
+            UiVerifier::verify_ui(&mut self.result, tui_app, ui_verify, self.snapshot_dir).await;
         }
 
         // Verify final state if specified
@@ -183,6 +205,12 @@ impl<'fixture> UnifiedVerifier<'fixture> {
             UiVerifier::verify_state(&mut self.result, tui_app, state_verify).await;
         }
 
+        // Verify final tool calls if specified
+        if let Some(tool_call_verify) = &verify.tool_calls {
+            let calls = self.tool_calls.calls().await;
+            ToolCallVerifier::verify_tool_calls(&mut self.result, &calls, tool_call_verify);
+        }
+
         Ok(())
     }
 
@@ -219,6 +247,11 @@ impl<'fixture> UnifiedVerifier<'fixture> {
             );
         }
 
+        // Verify session cost
+        if let Some(min_cost) = exec_verify.min_session_cost {
+            ExecutionVerifier::verify_session_cost(&mut self.result, orchestrator, min_cost);
+        }
+
         // Verify metrics collection
         if exec_verify.metrics_recorded.is_some() {
             ExecutionVerifier::verify_metrics(
@@ -229,6 +262,44 @@ impl<'fixture> UnifiedVerifier<'fixture> {
         }
     }
 
+    /// Verify how many requests the current event received
+    fn verify_request_counts(
+        result: &mut VerificationResult,
+        provider: &MockProvider,
+        verify: &PromptVerify,
+    ) {
+        if verify.request_count.is_none() && verify.failed_requests.is_none() {
+            return;
+        }
+        let stats = match provider.current_event().and_then(|event_id| {
+            let event_id = event_id.ok_or_else(|| {
+                RoutingError::Other("no LLM response event is current".to_owned())
+            })?;
+            provider.request_stats(&event_id)
+        }) {
+            Ok(stats) => stats,
+            Err(err) => {
+                result.add_failure(format!("Cannot verify request counts: {err}"));
+                return;
+            }
+        };
+
+        for (label, expected, actual) in [
+            ("request(s)", verify.request_count, stats.requests),
+            ("failed request(s)", verify.failed_requests, stats.failures),
+        ] {
+            match expected {
+                Some(expected) if expected == actual => {
+                    result.add_success(format!("{actual} {label} as expected"));
+                }
+                Some(expected) => {
+                    result.add_failure(format!("Expected {expected} {label}, got {actual}"));
+                }
+                None => {}
+            }
+        }
+    }
+
     /// Verify task states (incomplete/failed tasks)
     fn verify_task_states(
         &mut self,
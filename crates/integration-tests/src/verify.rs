@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 
 /// Verification configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -21,6 +22,8 @@ pub struct VerifyConfig {
     pub context: Option<ContextVerify>,
     /// Validation verification
     pub validation: Option<ValidationVerify>,
+    /// Tool call verification
+    pub tool_calls: Option<ToolCallVerify>,
 }
 
 impl VerifyConfig {
@@ -34,6 +37,7 @@ impl VerifyConfig {
             && self.prompt.is_none()
             && self.context.is_none()
             && self.validation.is_none()
+            && self.tool_calls.is_none()
     }
 }
 
@@ -97,6 +101,12 @@ pub struct ExecutionVerify {
     /// Expected number of escalation attempts
     #[serde(default)]
     pub escalation_attempts: Option<usize>,
+    /// Total tokens the task reported using
+    #[serde(default)]
+    pub tokens_used: Option<u64>,
+    /// Minimum estimated session cost in USD
+    #[serde(default)]
+    pub min_session_cost: Option<f64>,
 }
 
 /// File verification
@@ -121,6 +131,43 @@ pub struct FileVerify {
     pub size_lt: Option<usize>,
 }
 
+/// Tool call verification
+///
+/// Expectations are checked against every tool call recorded since the test
+/// started, in call order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolCallVerify {
+    /// Calls that must happen in this order, with any other calls in between
+    #[serde(default)]
+    pub ordered: Vec<ToolCallExpectation>,
+    /// Calls that must happen in any order
+    #[serde(default)]
+    pub unordered: Vec<ToolCallExpectation>,
+    /// Tools that must not be called
+    #[serde(default)]
+    pub not_called: Vec<String>,
+    /// Exact number of tool calls
+    pub total_calls: Option<usize>,
+}
+
+/// Expected tool call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolCallExpectation {
+    /// Tool name (e.g., `readFile`)
+    pub tool: String,
+    /// Regex the parameters must match, as compact JSON
+    pub args_regex: Option<String>,
+    /// Values the parameters must have, by JSON path (`$` for the whole
+    /// parameters, `$.path` or `path` for a field, `$.items.0` for an element)
+    #[serde(default)]
+    pub args: BTreeMap<String, Value>,
+    /// Number of matching calls: exact when unordered (at least one if
+    /// unset), matched one after another when ordered (one if unset)
+    pub count: Option<usize>,
+}
+
 /// `WorkUnit` verification
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -180,8 +227,11 @@ pub struct UiVerify {
     /// Output does not contain patterns
     #[serde(default)]
     pub output_not_contains: Vec<String>,
-    /// Snapshot file path
+    /// Golden snapshot of the rendered buffer, relative to the fixture's directory
     pub snapshot: Option<String>,
+    /// Regexes masking volatile snapshot content, on top of `DEFAULT_SNAPSHOT_MASKS`
+    #[serde(default)]
+    pub snapshot_masks: Vec<String>,
     /// Final state
     pub final_state: Option<String>,
     /// All tasks completed
@@ -290,6 +340,10 @@ pub struct PromptVerify {
     /// Type definitions that should be present
     #[serde(default)]
     pub has_type_definitions: Vec<String>,
+    /// Number of requests the current event received, including failed attempts
+    pub request_count: Option<usize>,
+    /// Number of requests that failed with the event's simulated error
+    pub failed_requests: Option<usize>,
 }
 
 /// Context verification
@@ -351,4 +405,6 @@ pub struct FinalVerify {
     pub ui: Option<UiVerify>,
     /// State verification
     pub state: Option<StateVerify>,
+    /// Tool call verification
+    pub tool_calls: Option<ToolCallVerify>,
 }
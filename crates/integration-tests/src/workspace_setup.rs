@@ -53,9 +53,30 @@ pub fn create_files(workspace_path: &Path, files: &HashMap<String, String>) -> R
 fn create_file_with_dirs(file_path: &Path, content: &str) -> Result<()> {
     if let Some(parent) = file_path.parent() {
         fs::create_dir_all(parent)
-            .map_err(|err| RoutingError::Other(format!("Failed to create directory: {err}")))?;
+            .map_err(RoutingError::file_access("create directory", parent))?;
+    }
+    fs::write(file_path, content).map_err(RoutingError::file_access("write file", file_path))?;
+    Ok(())
+}
+
+/// Copy a workspace, including hidden files such as its `.merlin` caches
+///
+/// # Errors
+/// Returns error if a directory cannot be read or a file cannot be copied
+pub fn copy_workspace(source: &Path, destination: &Path) -> Result<()> {
+    fs::create_dir_all(destination)
+        .map_err(RoutingError::file_access("create directory", destination))?;
+    let entries =
+        fs::read_dir(source).map_err(RoutingError::file_access("read workspace", source))?;
+    for entry in entries {
+        let entry = entry.map_err(RoutingError::file_access("read workspace", source))?;
+        let target = destination.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_workspace(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .map_err(RoutingError::file_access("copy", &entry.path()))?;
+        }
     }
-    fs::write(file_path, content)
-        .map_err(|err| RoutingError::Other(format!("Failed to write file: {err}")))?;
     Ok(())
 }
@@ -0,0 +1,230 @@
+//! Headless `merlin run` integration tests.
+//!
+//! Runs single tasks against the mock provider and checks the JSON report.
+#![cfg_attr(
+    test,
+    allow(
+        clippy::tests_outside_test_module,
+        reason = "Allow for integration tests"
+    )
+)]
+
+use integration_tests::{MockProvider, ProviderSimulation, ResponseStrategy, SimulatedTokens};
+use merlin_agent::Merlin;
+use merlin_cli::headless::{RunArgs, RunStatus, run_task, scratch_copy, write_report};
+use merlin_core::RoutingConfig;
+use serde_json::{Value, from_slice};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::sink;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::task::LocalSet;
+
+/// Result type of the tests
+type TestResult<T = ()> = Result<T, Box<dyn Error>>;
+
+/// Agent code the mock answers every request with
+const WRITE_NOTES: &str = "await writeFile('notes.txt', 'hi');\nreturn 'done';";
+
+/// Agent code asking which database to use and returning the answer
+const ASK_DATABASE: &str = "return await askUser('Which database?', ['sqlite', 'postgres']);";
+
+/// Opens `workspace` with a mock provider answering every request with `typescript`
+///
+/// # Errors
+/// Returns error if the provider or orchestrator cannot be set up
+fn open_mock(workspace: &Path, typescript: &str) -> TestResult<Merlin> {
+    let event = "run".to_owned();
+    let strategy = ResponseStrategy::Repeating {
+        routing_match: None,
+        typescript: typescript.to_owned(),
+    };
+    // Usage is simulated so the run has a cost to budget against
+    let provider = MockProvider::new(
+        "test-mock",
+        HashMap::from([(event.clone(), vec![strategy])]),
+    )
+    .with_simulations(HashMap::from([(
+        event.clone(),
+        ProviderSimulation {
+            tokens: Some(SimulatedTokens {
+                input: 10_000,
+                output: 1_000,
+                ..SimulatedTokens::default()
+            }),
+            ..ProviderSimulation::default()
+        },
+    )]));
+    provider.set_current_event(Some(event))?;
+
+    let mut config = RoutingConfig::default();
+    config.tiers.local_enabled = false;
+    config.tiers.groq_enabled = false;
+    config.tiers.premium_enabled = false;
+    Ok(Merlin::open_with_provider(
+        workspace,
+        config,
+        Arc::new(provider),
+    )?)
+}
+
+/// Runs `args` on `workspace` with the agent writing notes and parses the JSON report
+///
+/// # Errors
+/// Returns error if the run or the report fails
+async fn run_json(workspace: &Path, args: &RunArgs) -> TestResult<Value> {
+    run_json_with(workspace, args, WRITE_NOTES).await
+}
+
+/// Runs `args` on `workspace` with the agent running `typescript` and parses the JSON report
+///
+/// # Errors
+/// Returns error if the run or the report fails
+async fn run_json_with(workspace: &Path, args: &RunArgs, typescript: &str) -> TestResult<Value> {
+    let merlin = open_mock(workspace, typescript)?;
+    let mut events = Vec::new();
+    let report = run_task(&merlin, args, &mut events).await?;
+
+    // Every streamed event is one JSON document
+    for line in events
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+    {
+        from_slice::<Value>(line)?;
+    }
+
+    let mut out = Vec::new();
+    write_report(&report, true, &mut out, &mut sink())?;
+    Ok(from_slice(&out)?)
+}
+
+/// Arguments of `merlin run --json` with `prompt`
+fn json_args(prompt: &str) -> RunArgs {
+    RunArgs {
+        prompt: prompt.to_owned(),
+        json: true,
+        ..RunArgs::default()
+    }
+}
+
+/// Tests that a run reports its response, file changes and usage as JSON
+///
+/// # Errors
+/// Returns error if the run fails
+///
+/// # Panics
+/// Panics if the report does not describe the run
+#[tokio::test]
+async fn test_run_reports_json() -> TestResult {
+    let project = TempDir::new()?;
+    let report = LocalSet::new()
+        .run_until(run_json(project.path(), &json_args("Write notes")))
+        .await?;
+
+    assert_eq!(report["status"], "succeeded");
+    assert_eq!(RunStatus::Succeeded.exit_code(), 0);
+    // The response is the JSON encoding of the returned value
+    assert_eq!(report["response"], "\"done\"");
+    assert_eq!(
+        report["file_changes"],
+        serde_json::json!([{ "path": "notes.txt", "change": "created" }])
+    );
+    assert_eq!(report["dry_run"], false);
+    assert!(
+        report["tokens"]["input"]
+            .as_u64()
+            .is_some_and(|input| input > 0)
+    );
+    assert_eq!(fs::read_to_string(project.path().join("notes.txt"))?, "hi");
+    Ok(())
+}
+
+/// Tests that a dry run reports changes without making them in the project
+///
+/// # Errors
+/// Returns error if the run fails
+///
+/// # Panics
+/// Panics if the project is modified
+#[tokio::test]
+async fn test_dry_run_leaves_project_unchanged() -> TestResult {
+    let project = TempDir::new()?;
+    fs::write(project.path().join("README.md"), "# Project\n")?;
+    let scratch = scratch_copy(project.path())?;
+    let args = RunArgs {
+        dry_run: true,
+        ..json_args("Write notes")
+    };
+    let report = LocalSet::new()
+        .run_until(run_json(scratch.path(), &args))
+        .await?;
+
+    assert_eq!(report["status"], "succeeded");
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["file_changes"][0]["path"], "notes.txt");
+    assert!(!project.path().join("notes.txt").exists());
+    assert!(scratch.path().join("README.md").exists());
+    Ok(())
+}
+
+/// Tests that going over `--max-cost` is reported as an exceeded budget
+///
+/// # Errors
+/// Returns error if the run fails
+///
+/// # Panics
+/// Panics if the budget is not enforced
+#[tokio::test]
+async fn test_max_cost_exceeded() -> TestResult {
+    let project = TempDir::new()?;
+    let args = RunArgs {
+        max_cost: Some(0.0),
+        ..json_args("Write notes")
+    };
+    let report = LocalSet::new()
+        .run_until(run_json(project.path(), &args))
+        .await?;
+
+    assert_eq!(report["status"], "budget_exceeded");
+    assert!(report["cost_usd"].as_f64().is_some_and(|cost| cost > 0.0));
+    assert_eq!(RunStatus::BudgetExceeded.exit_code(), 3);
+    Ok(())
+}
+
+/// Tests that questions are answered from `--answer` and fail the run without one
+///
+/// # Errors
+/// Returns error if a run fails
+///
+/// # Panics
+/// Panics if a question is not answered or the missing answer is not reported
+#[tokio::test]
+async fn test_answers_questions_from_flags() -> TestResult {
+    let project = TempDir::new()?;
+    let args = RunArgs {
+        answers: vec!["postgres".to_owned()],
+        ..json_args("Set up a database")
+    };
+    let report = LocalSet::new()
+        .run_until(run_json_with(project.path(), &args, ASK_DATABASE))
+        .await?;
+    assert_eq!(report["status"], "succeeded");
+    assert_eq!(report["response"], "\"postgres\"");
+
+    let unanswered = LocalSet::new()
+        .run_until(run_json_with(
+            project.path(),
+            &json_args("Set up a database"),
+            ASK_DATABASE,
+        ))
+        .await?;
+    assert_eq!(unanswered["status"], "failed");
+    assert_eq!(
+        unanswered["error"],
+        "Task asked a question with no --answer left: Which database?"
+    );
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Run one task headlessly through the `Merlin` facade
+//!
+//! This example opens the agent on a temporary project with a mock provider,
+//! submits a prompt, prints the task's UI events and then its response.
+
+use async_trait::async_trait;
+use futures::StreamExt as _;
+use merlin_agent::Merlin;
+use merlin_core::{
+    Context, ModelProvider, Query, Response, Result as MerlinResult, RoutingConfig, TokenUsage,
+    UiEvent,
+};
+use std::error::Error;
+use std::io::{Write as _, stdout};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tokio::task::LocalSet;
+
+/// Provider answering every task with a fixed TypeScript agent reply
+struct MockProvider;
+
+#[async_trait]
+impl ModelProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, _query: &Query, _context: &Context) -> MerlinResult<Response> {
+        Ok(Response {
+            text: "```typescript\nreturn \"Hello from a headless agent\";\n```".to_owned(),
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: self.name().to_owned(),
+            latency_ms: 0,
+        })
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    // The mock stands in for every provider, so none needs an API key
+    let mut config = RoutingConfig::default();
+    config.tiers.local_enabled = false;
+    config.tiers.groq_enabled = false;
+    config.tiers.premium_enabled = false;
+
+    let project = TempDir::new()?;
+    let merlin = Merlin::open_with_provider(project.path(), config, Arc::new(MockProvider))?;
+
+    // Agent code runs on a runtime that is not `Send`, so tasks need a `LocalSet`
+    LocalSet::new()
+        .run_until(async {
+            let mut out = stdout();
+            let mut handle = merlin.submit("Greet the user");
+            let mut events = handle.events();
+            while let Some(event) = events.next().await {
+                match event {
+                    UiEvent::TaskStarted { description, .. } => {
+                        writeln!(out, "started: {description}")
+                    }
+                    UiEvent::TaskCompleted { .. } => writeln!(out, "completed"),
+                    UiEvent::TaskFailed { error, .. } => writeln!(out, "failed: {error}"),
+                    other => writeln!(out, "event: {other:?}"),
+                }?;
+            }
+
+            let result = handle.await_result().await?;
+            writeln!(out, "response: {}", result.response.text)?;
+            Ok::<_, Box<dyn Error>>(())
+        })
+        .await
+}
@@ -1,14 +1,25 @@
 //! Context building for task execution
 
-use merlin_context::ContextFetcher;
+use merlin_context::{
+    ContextExplanation, ContextFetcher, ProgressCallback, ResolvedReference, references,
+};
 use merlin_core::{
-    Context, Query, Result, RoutingError, Task,
-    ui::{TaskProgress, UiChannel, UiEvent},
+    Context, ModelProvider, Query, Result, RoutingError, Task,
+    ui::{MessageLevel, TaskProgress, UiChannel, UiEvent},
 };
 use std::fmt::Write as _;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Role of the entry replacing summarized conversation turns
+const SUMMARY_ROLE: &str = "summary";
+
+/// System prompt for summarizing the oldest turns of a conversation
+const HISTORY_SUMMARY_PROMPT: &str = "You compress conversations between a user and a coding \
+    agent. Summarize the transcript as short bullet points, one per line starting with \"- \", \
+    keeping requests, decisions, file names and open questions. Reply with the bullet points only.";
+
 /// Type alias for conversation history
 pub type ConversationHistory = Vec<(String, String)>;
 
@@ -18,6 +29,12 @@ pub struct ContextBuilder {
     context_fetcher: Arc<ContextFetcher>,
     /// Conversation history for context building (`RwLock` for read-heavy access)
     pub conversation_history: Arc<RwLock<ConversationHistory>>,
+    /// Files pinned to the thread, included ahead of retrieved files
+    pub pinned_files: Vec<PathBuf>,
+    /// Estimated history tokens above which the oldest turns are summarized
+    pub compression_threshold: Option<usize>,
+    /// Cheap provider summarizing the oldest turns, set when the history needs it
+    pub summary_provider: Option<Arc<dyn ModelProvider>>,
 }
 
 impl ContextBuilder {
@@ -30,15 +47,34 @@ impl ContextBuilder {
         Self {
             context_fetcher,
             conversation_history,
+            pinned_files: Vec::new(),
+            compression_threshold: None,
+            summary_provider: None,
         }
     }
 
-    /// Build context for a task
+    /// Refresh the language index for files changed by the agent
+    pub async fn refresh_changed_files(&self, files: &[PathBuf]) {
+        for file in files {
+            self.context_fetcher.apply_file_change(file).await;
+        }
+    }
+
+    /// Build context for a task whose `@` references are already resolved
     ///
     /// # Errors
     /// Returns an error if context building fails
-    pub async fn build_context(&self, task: &Task, ui_channel: &UiChannel) -> Result<Context> {
-        let query = Query::new(task.description.clone());
+    pub async fn build_context(
+        &self,
+        task: &Task,
+        ui_channel: &UiChannel,
+        references: &[ResolvedReference],
+    ) -> Result<Context> {
+        // The annotated description has no `@` tokens left for the fetcher to resolve again
+        let query = references::with_references(
+            &Query::new(task.description.clone()).with_pinned_files(self.pinned_files.clone()),
+            references,
+        );
         let task_id = task.id;
 
         // Always fetch file context (self-assessor will handle simple tasks)
@@ -66,6 +102,26 @@ impl ContextBuilder {
             .set_progress_callback(progress_callback)
             .await;
 
+        // Background indexing outlives this task, so it reports to the status bar instead
+        let index_ui = ui_channel.clone();
+        let index_progress_callback =
+            Arc::new(move |stage: &str, current: u64, total: Option<u64>| {
+                if let Some(total) = total {
+                    index_ui.send(UiEvent::EmbeddingProgress {
+                        current,
+                        total,
+                        stage: stage.to_owned(),
+                    });
+                }
+            });
+        self.context_fetcher
+            .set_index_progress_callback(index_progress_callback)
+            .await;
+
+        self.context_fetcher
+            .set_pull_progress_callback(pull_progress_callback(ui_channel))
+            .await;
+
         // Send substep for file gathering
         ui_channel.send(UiEvent::TaskStepStarted {
             task_id,
@@ -79,15 +135,11 @@ impl ContextBuilder {
             let conv_history = self.conversation_history.read().await;
             if conv_history.is_empty() {
                 drop(conv_history);
-                self.context_fetcher
-                    .build_context_for_query(&query)
-                    .await
-                    .map_err(|err| RoutingError::Other(format!("Failed to build context: {err}")))?
+                self.context_fetcher.build_context_for_query(&query).await?
             } else {
                 self.context_fetcher
                     .build_context_from_conversation(&conv_history, &query)
-                    .await
-                    .map_err(|err| RoutingError::Other(format!("Failed to build context: {err}")))?
+                    .await?
             }
         };
 
@@ -101,6 +153,9 @@ impl ContextBuilder {
 
     /// Build context for TypeScript-based agent execution
     ///
+    /// The context is not capped here: routing picks a model whose window fits
+    /// it, or truncates it to the largest window available.
+    ///
     /// # Errors
     /// Returns an error if context building fails
     pub async fn build_context_for_typescript(
@@ -109,13 +164,32 @@ impl ContextBuilder {
         ui_channel: &UiChannel,
         compiled_prompt: &str,
     ) -> Result<Context> {
+        let references = self
+            .context_fetcher
+            .resolve_references(&task.description)
+            .await;
         // Always get file context (self-assessor handles simple tasks before we get here)
-        let base_context = self.build_context(task, ui_channel).await?;
+        let base_context = self.build_context(task, ui_channel, &references).await?;
+
+        if let Some(threshold) = self.compression_threshold
+            && let Err(err) = self.compress_history_if_needed(threshold).await
+        {
+            tracing::warn!("Keeping the full conversation history: {err}");
+        }
 
         // Combine pre-compiled TypeScript prompt with file context
         let mut context = Context::new(compiled_prompt.to_owned());
         context.files = base_context.files;
 
+        // Spell out what the `@` references in the task point at
+        if !references.is_empty() {
+            let _write_result = write!(
+                context.system_prompt,
+                "\n\n## Referenced in the Task\n\n{}\n",
+                references::annotate_references(&task.description, &references)
+            );
+        }
+
         // Add conversation history if present (read lock)
         let conv_history = self.conversation_history.read().await;
         if !conv_history.is_empty() {
@@ -125,21 +199,89 @@ impl ContextBuilder {
                 let _write_result2 = writeln!(context.system_prompt, "{role}: {content}");
             }
         }
+        drop(conv_history);
 
         Ok(context)
     }
 
+    /// Explain why each file of `context` was included
+    pub async fn explain_context(&self, context: &Context) -> ContextExplanation {
+        self.context_fetcher.explain_context(context).await
+    }
+
     /// Calculate conversation token count
     #[must_use]
     pub async fn calculate_conversation_tokens(&self) -> usize {
-        let char_count: usize = {
+        history_tokens(&self.conversation_history.read().await)
+    }
+
+    /// Summarize the oldest turns once the history is estimated above `threshold_tokens`
+    ///
+    /// The most recent turns are kept as they are, up to half the threshold, and
+    /// the turns before them are replaced by one bullet-point summary written by
+    /// `summary_provider`. Returns whether the history was compressed.
+    ///
+    /// # Errors
+    /// Returns an error if no summary provider is set or the summary request fails,
+    /// leaving the history unchanged
+    pub async fn compress_history_if_needed(&self, threshold_tokens: usize) -> Result<bool> {
+        let oldest = {
             let conv_history = self.conversation_history.read().await;
-            conv_history
-                .iter()
-                .map(|(role, content)| role.len() + content.len() + 10)
-                .sum()
+            if history_tokens(&conv_history) <= threshold_tokens {
+                return Ok(false);
+            }
+            let kept = recent_turns(&conv_history, threshold_tokens / 2);
+            let Some(oldest) = conv_history.get(..conv_history.len() - kept) else {
+                return Ok(false);
+            };
+            // A lone earlier summary cannot be compressed further
+            if oldest.is_empty() || matches!(oldest, [(role, _)] if role == SUMMARY_ROLE) {
+                return Ok(false);
+            }
+            oldest.to_vec()
         };
-        char_count / 4
+        let provider = self.summary_provider.as_ref().ok_or_else(|| {
+            RoutingError::ProviderUnavailable(
+                "No provider to summarize the conversation".to_owned(),
+            )
+        })?;
+
+        let transcript = oldest
+            .iter()
+            .map(|(role, content)| format!("{role}: {content}\n"))
+            .collect::<Vec<_>>()
+            .concat();
+        let response = provider
+            .generate(
+                &Query::new(transcript),
+                &Context::new(HISTORY_SUMMARY_PROMPT),
+            )
+            .await?;
+        let summary = response.text.trim();
+        if summary.is_empty() {
+            return Err(RoutingError::ExecutionFailed(
+                "Conversation summary was empty".to_owned(),
+            ));
+        }
+
+        let mut conv_history = self.conversation_history.write().await;
+        // Turns added while summarizing stay after the summary
+        if conv_history.get(..oldest.len()) != Some(oldest.as_slice()) {
+            return Ok(false);
+        }
+        conv_history.splice(
+            ..oldest.len(),
+            [(
+                SUMMARY_ROLE.to_owned(),
+                format!("Earlier in this conversation:\n{summary}"),
+            )],
+        );
+        tracing::info!(
+            "Summarized {} conversation turns, history now ~{} tokens",
+            oldest.len(),
+            history_tokens(&conv_history)
+        );
+        Ok(true)
     }
 
     /// Log conversation preview
@@ -159,11 +301,10 @@ impl ContextBuilder {
                 .take(preview_count)
                 .enumerate()
                 .map(|(idx, (role, content))| {
-                    let preview = if content.len() > 60 {
-                        format!("{}...", &content[..60])
-                    } else {
-                        content.clone()
-                    };
+                    let preview = content.char_indices().nth(60).map_or_else(
+                        || content.clone(),
+                        |(cut, _)| format!("{}...", &content[..cut]),
+                    );
                     (idx, role.clone(), preview)
                 })
                 .collect();
@@ -206,3 +347,49 @@ impl ContextBuilder {
         }
     }
 }
+
+/// Estimated tokens of `history`
+fn history_tokens(history: &[(String, String)]) -> usize {
+    history.iter().map(turn_chars).sum::<usize>() / 4
+}
+
+/// Characters a turn takes in the prompt, including its role label
+fn turn_chars((role, content): &(String, String)) -> usize {
+    role.len() + content.len() + 10
+}
+
+/// Number of most recent turns fitting in `budget_tokens`, keeping at least the last one
+fn recent_turns(history: &[(String, String)], budget_tokens: usize) -> usize {
+    let mut chars = 0;
+    let fitting = history
+        .iter()
+        .rev()
+        .take_while(|turn| {
+            chars += turn_chars(turn);
+            chars / 4 <= budget_tokens
+        })
+        .count();
+    fitting.max(1).min(history.len())
+}
+
+/// Progress callback reporting an embedding model download as system messages
+///
+/// The download precedes indexing and outlives the task, so it isn't task progress.
+fn pull_progress_callback(ui_channel: &UiChannel) -> ProgressCallback {
+    let ui_channel = ui_channel.clone();
+    Arc::new(move |stage: &str, current: u64, total: Option<u64>| {
+        let message = total.filter(|total| *total > 0).map_or_else(
+            || format!("Downloading embedding model: {stage}"),
+            |total| {
+                format!(
+                    "Downloading embedding model: {stage} {}%",
+                    current * 100 / total
+                )
+            },
+        );
+        ui_channel.send(UiEvent::SystemMessage {
+            level: MessageLevel::Info,
+            message,
+        });
+    })
+}
@@ -1,5 +1,8 @@
 //! Context and execution logging utilities
 
+use std::io::{Write as _, stderr};
+
+use merlin_core::prompts::PromptSource;
 use merlin_core::{Context, Task};
 
 use super::context::ContextBuilder;
@@ -15,6 +18,14 @@ impl ContextLogger {
         char_count / 4
     }
 
+    /// Print why each file of the context was included to stderr, as Markdown
+    pub async fn print_context_explanation(context: &Context, context_builder: &ContextBuilder) {
+        let markdown = context_builder.explain_context(context).await.to_markdown();
+        if let Err(err) = writeln!(stderr(), "{markdown}") {
+            tracing::warn!("Failed to print context explanation: {err}");
+        }
+    }
+
     /// Log context breakdown to debug.log
     pub async fn log_context_breakdown(context: &Context, context_builder: &ContextBuilder) {
         use tracing::info;
@@ -108,20 +119,23 @@ impl ContextLogger {
         // File list is now printed by the context builder
     }
 
-    /// Dump full context to debug.log
+    /// Dump full context to debug.log, including where the agent prompt came from
     pub async fn dump_context_to_log(
         context: &Context,
         task: &Task,
         context_builder: &ContextBuilder,
+        prompt_source: &PromptSource,
     ) {
         use tracing::info;
 
         info!("================== CONTEXT DUMP ==================");
         info!("Task: {}", task.description);
+        info!("Agent prompt: {prompt_source}");
         info!("");
 
         context_builder.log_conversation_history().await;
         Self::log_system_prompt(context);
+        Self::log_files(context);
         Self::log_statistics(context);
 
         info!("================================================");
@@ -136,13 +150,33 @@ impl ContextLogger {
         info!("");
     }
 
+    /// Log files section, pinned files first and labelled
+    fn log_files(context: &Context) {
+        use tracing::info;
+
+        info!("=== FILES ===");
+        for file in &context.files {
+            let label = if file.pinned { " [pinned]" } else { "" };
+            info!(
+                "{}{label} (~{} tokens)",
+                file.path.display(),
+                file.content.len() / 4
+            );
+        }
+        info!("");
+    }
+
     /// Log statistics section
     fn log_statistics(context: &Context) {
         use tracing::info;
 
         info!("=== STATISTICS ===");
         info!("Estimated tokens: {}", context.token_estimate());
-        info!("Files: {}", context.files.len());
+        info!(
+            "Files: {} ({} pinned)",
+            context.files.len(),
+            context.files.iter().filter(|file| file.pinned).count()
+        );
         info!(
             "System prompt length: {} chars",
             context.system_prompt.len()
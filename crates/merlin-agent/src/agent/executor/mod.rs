@@ -3,41 +3,51 @@
 mod context;
 mod logging;
 mod parallel;
+mod preparation;
 mod response_processing;
+mod retry;
 mod step_executor;
 pub(crate) mod typescript;
+mod usage;
 
 #[cfg(test)]
 mod tests;
 
 use context::{ContextBuilder, ConversationHistory};
-use logging::ContextLogger;
 use response_processing::{ResponseProcessingParams, ResponseProcessor};
+pub use retry::{DEFAULT_RETRY_BUDGET, RetryBudget};
+use retry::{RetryPolicy, RetryingProvider};
 pub use step_executor::{
     AgentExecutionParams, StepExecutionParams, StepExecutor, StepResult, TaskListExecutionParams,
 };
+use usage::UsageTrackingProvider;
 
 use std::{
     collections::HashMap,
+    path::{Path, PathBuf},
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
     },
     time::Instant,
 };
+use tokio::select;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
 
 use crate::Validator;
-use merlin_context::ContextFetcher;
+use anyhow::Context as _;
+use merlin_context::{ContextFetcher, RepeatedFiles};
 use merlin_core::AgentResponse;
 use merlin_core::ModelProvider;
+use merlin_core::prompts::{LoadedPrompt, PromptDirs, PromptSource, load_prompt};
 use merlin_core::{
-    Context, Result, RoutingConfig, RoutingError, StepType, Task, TaskId, TaskResult, TaskStep,
-    ui::{UiChannel, UiEvent},
+    Context, PhaseTimings, Result, RoutingConfig, RoutingError, StepType, Task, TaskId, TaskResult,
+    TaskStep, ui::UiChannel,
 };
-use merlin_routing::{ModelRouter, ProviderRegistry};
+use merlin_routing::{ModelRouter, ProviderRegistry, RoutingDecision};
 use merlin_tooling::{PersistentTypeScriptRuntime, ToolRegistry, generate_typescript_signatures};
-use tracing::{Level, span};
+use tracing::{Level, Span, field, span};
 use tracing_futures::Instrument as _;
 
 /// Parameters for executing with step executor
@@ -61,8 +71,8 @@ pub struct AgentExecutorParams {
     pub validator: Arc<dyn Validator>,
     /// Tool registry
     pub tool_registry: ToolRegistry,
-    /// Context fetcher
-    pub context_fetcher: ContextFetcher,
+    /// Context fetcher, shared with the code navigation tools
+    pub context_fetcher: Arc<ContextFetcher>,
     /// Routing configuration
     pub config: RoutingConfig,
     /// Provider registry
@@ -76,12 +86,23 @@ pub struct AgentExecutor {
     tool_registry: ToolRegistry,
     context_builder: ContextBuilder,
     context_dump_enabled: AtomicBool,
+    /// Whether to print why each context file was included to stderr
+    context_explanation_enabled: AtomicBool,
     /// Provider registry for accessing model providers
     provider_registry: ProviderRegistry,
-    /// Persistent TypeScript runtime for agent code execution
+    /// Persistent TypeScript runtime for agent code execution, whose globals
+    /// last as long as the executor
     runtime: PersistentTypeScriptRuntime,
     /// Cached compiled TypeScript agent prompt (computed once at initialization, includes tool signatures)
     compiled_typescript_prompt: String,
+    /// Where the TypeScript agent prompt template was loaded from
+    typescript_prompt_source: PromptSource,
+    /// Files the thread already showed the model, when repeats are condensed
+    repeated_files: Option<RepeatedFiles>,
+    /// How transient provider errors are retried
+    retry_policy: RetryPolicy,
+    /// Retries left across the session
+    retry_budget: Arc<RetryBudget>,
 }
 
 impl AgentExecutor {
@@ -93,13 +114,15 @@ impl AgentExecutor {
         router: Arc<dyn ModelRouter>,
         validator: Arc<dyn Validator>,
         tool_registry: ToolRegistry,
-        context_fetcher: ContextFetcher,
+        context_fetcher: impl Into<Arc<ContextFetcher>>,
         config: &RoutingConfig,
     ) -> Result<Self> {
         let provider_registry = ProviderRegistry::new(config.clone())?;
-        let context_fetcher_arc = Arc::new(context_fetcher);
+        let context_fetcher_arc: Arc<ContextFetcher> = context_fetcher.into();
+        let project_root = context_fetcher_arc.project_root().clone();
         let conversation_history = Arc::new(RwLock::new(Vec::new()));
-        let context_builder = ContextBuilder::new(context_fetcher_arc, conversation_history);
+        let mut context_builder = ContextBuilder::new(context_fetcher_arc, conversation_history);
+        context_builder.compression_threshold = config.conversation_compression_threshold;
 
         // Create persistent runtime with tools
         let tools = tool_registry.list_tools();
@@ -119,7 +142,7 @@ impl AgentExecutor {
         })?;
 
         // Compile TypeScript agent prompt once (load template + inject signatures)
-        let compiled_prompt = Self::compile_typescript_prompt(&signatures)?;
+        let compiled_prompt = Self::compile_typescript_prompt(&signatures, &project_root)?;
 
         Ok(Self {
             router,
@@ -127,9 +150,14 @@ impl AgentExecutor {
             tool_registry,
             context_builder,
             context_dump_enabled: AtomicBool::new(false),
+            context_explanation_enabled: AtomicBool::new(false),
             provider_registry,
             runtime,
-            compiled_typescript_prompt: compiled_prompt,
+            compiled_typescript_prompt: compiled_prompt.text,
+            typescript_prompt_source: compiled_prompt.source,
+            repeated_files: None,
+            retry_policy: RetryPolicy::from_config(config),
+            retry_budget: Arc::default(),
         })
     }
 
@@ -138,9 +166,11 @@ impl AgentExecutor {
     /// # Errors
     /// Returns an error if initialization fails.
     pub fn with_provider_registry(params: AgentExecutorParams) -> Result<Self> {
-        let context_fetcher_arc = Arc::new(params.context_fetcher);
+        let context_fetcher_arc = params.context_fetcher;
+        let project_root = context_fetcher_arc.project_root().clone();
         let conversation_history = Arc::new(RwLock::new(Vec::new()));
-        let context_builder = ContextBuilder::new(context_fetcher_arc, conversation_history);
+        let mut context_builder = ContextBuilder::new(context_fetcher_arc, conversation_history);
+        context_builder.compression_threshold = params.config.conversation_compression_threshold;
 
         // Create persistent runtime with tools
         let tools = params.tool_registry.list_tools();
@@ -160,7 +190,7 @@ impl AgentExecutor {
         })?;
 
         // Compile TypeScript agent prompt once (load template + inject signatures)
-        let compiled_prompt = Self::compile_typescript_prompt(&signatures)?;
+        let compiled_prompt = Self::compile_typescript_prompt(&signatures, &project_root)?;
 
         Ok(Self {
             router: params.router,
@@ -168,28 +198,45 @@ impl AgentExecutor {
             tool_registry: params.tool_registry,
             context_builder,
             context_dump_enabled: AtomicBool::new(false),
+            context_explanation_enabled: AtomicBool::new(false),
             provider_registry: params.provider_registry,
             runtime,
-            compiled_typescript_prompt: compiled_prompt,
+            compiled_typescript_prompt: compiled_prompt.text,
+            typescript_prompt_source: compiled_prompt.source,
+            repeated_files: None,
+            retry_policy: RetryPolicy::from_config(&params.config),
+            retry_budget: Arc::default(),
         })
     }
 
     /// Compile TypeScript agent prompt with tool signatures
     ///
+    /// The template is loaded from the project or user overrides when present,
+    /// and the returned source says which one was used.
+    ///
     /// # Errors
-    /// Returns an error if prompt loading or compilation fails
-    fn compile_typescript_prompt(tool_signatures: &str) -> Result<String> {
-        use merlin_core::prompts::load_prompt;
-
+    /// Returns an error if prompt loading or compilation fails, including an override
+    /// missing the tool signatures placeholder
+    fn compile_typescript_prompt(
+        tool_signatures: &str,
+        project_root: &Path,
+    ) -> Result<LoadedPrompt> {
         const TOOL_SIGNATURES_PLACEHOLDER: &str = "{tool_signatures}";
 
         // Load TypeScript agent prompt template
-        let prompt_template = load_prompt("typescript_agent").map_err(|err| {
-            RoutingError::Other(format!("Failed to load typescript_agent prompt: {err}"))
-        })?;
+        let template = load_prompt("typescript_agent", &PromptDirs::for_project(project_root))
+            .map_err(|err| {
+                RoutingError::Other(format!("Failed to load typescript_agent prompt: {err}"))
+            })?;
+        tracing::info!("Using typescript_agent prompt from {}", template.source);
 
         // Replace placeholder with actual signatures
-        Ok(prompt_template.replace(TOOL_SIGNATURES_PLACEHOLDER, tool_signatures))
+        Ok(LoadedPrompt {
+            text: template
+                .text
+                .replace(TOOL_SIGNATURES_PLACEHOLDER, tool_signatures),
+            source: template.source,
+        })
     }
 
     /// Enable context dumping to debug.log
@@ -200,71 +247,169 @@ impl AgentExecutor {
     pub fn disable_context_dump(&mut self) {
         self.context_dump_enabled.store(false, Ordering::Relaxed);
     }
+    /// Print why each context file was included to stderr after building context
+    pub fn enable_context_explanation(&mut self) {
+        self.context_explanation_enabled
+            .store(true, Ordering::Relaxed);
+    }
 
     /// Set conversation history for context building
     pub async fn set_conversation_history(&mut self, history: ConversationHistory) {
         let mut conv_history = self.context_builder.conversation_history.write().await;
         *conv_history = history;
     }
+    /// Set the files pinned to the thread the task runs in
+    pub fn set_pinned_files(&mut self, files: Vec<PathBuf>) {
+        self.context_builder.pinned_files = files;
+    }
+    /// Condense files the thread the task runs in has already shown the model
+    pub fn set_repeated_files(&mut self, repeated: RepeatedFiles) {
+        self.repeated_files = Some(repeated);
+    }
+    /// Take the files shown so far, including those of the tasks run since set
+    pub const fn take_repeated_files(&mut self) -> Option<RepeatedFiles> {
+        self.repeated_files.take()
+    }
+    /// Spend retries of transient provider errors from a budget shared across the session
+    pub fn set_retry_budget(&mut self, budget: Arc<RetryBudget>) {
+        self.retry_budget = budget;
+    }
     /// Add to conversation history for context building
     pub async fn add_to_conversation(&mut self, role: String, content: String) {
         let mut conv_history = self.context_builder.conversation_history.write().await;
         conv_history.push((role, content));
     }
 
-    /// Execute a task using the task list execution model
+    /// Clear the globals agent code left in the TypeScript runtime
     ///
     /// # Errors
-    /// Returns an error if routing, provider creation, execution, or validation fails
-    pub async fn execute_task(&mut self, task: Task, ui_channel: UiChannel) -> Result<TaskResult> {
-        let span = span!(Level::INFO, "execute_task", task_id = ?task.id);
+    /// Returns an error if the tools cannot be registered in the fresh runtime.
+    pub fn reset_runtime_state(&mut self) -> Result<()> {
+        self.runtime.reset_state().map_err(|err| {
+            RoutingError::ExecutionFailed(format!("Failed to reset TypeScript runtime: {err}"))
+        })
+    }
 
-        async move {
-            let start = Instant::now();
-            let task_id = task.id;
-
-            // Route and get provider
-            let decision = self.router.route(&task).await?;
-            let provider = self
-                .provider_registry
-                .get_provider_for_task(task.difficulty, decision.model)?;
-
-            // Build context with tool signatures
-            let context = self
-                .build_context_and_log(&task, &ui_channel, task_id)
-                .await?;
-
-            // Execute agent - returns String | TaskList
-            let agent_response = self
-                .execute_with_step_executor(ExecutorParams {
-                    task: &task,
-                    context: &context,
-                    provider: &provider,
-                    task_id,
-                    ui_channel: &ui_channel,
-                })
-                .await?;
-
-            let duration_ms = start.elapsed().as_millis() as u64;
-
-            // Handle response type
-            let mut processor =
-                ResponseProcessor::new(&self.validator, &self.tool_registry, &mut self.runtime);
-            processor
-                .process_response(ResponseProcessingParams {
-                    agent_response,
-                    task_id,
-                    task: &task,
-                    decision: &decision,
-                    context: &context,
-                    provider: &provider,
-                    duration_ms,
-                    ui_channel: &ui_channel,
-                })
-                .await
+    /// Execute a task using the task list execution model
+    ///
+    /// The task stops at its next await point once `cancel` is triggered.
+    ///
+    /// # Errors
+    /// Returns an error if routing, provider creation, execution, or validation fails,
+    /// or `RoutingError::Cancelled` if the task is cancelled before it completes
+    pub async fn execute_task(
+        &mut self,
+        task: Task,
+        ui_channel: UiChannel,
+        cancel: CancellationToken,
+    ) -> Result<TaskResult> {
+        let span = span!(
+            Level::INFO,
+            "execute_task",
+            task_id = ?task.id,
+            model_name = field::Empty,
+            token_count = field::Empty
+        );
+        let cancelled_task_id = task.id;
+        let task_description = task.description.clone();
+
+        let execution =
+            async move {
+                let start = Instant::now();
+                let task_id = task.id;
+
+                // Build context with tool signatures
+                let context_start = Instant::now();
+                preparation::prepare_history_summary(self).await;
+                let mut context =
+                    preparation::build_context_and_log(self, &task, &ui_channel, task_id).await?;
+                let context_ms = context_start.elapsed().as_millis() as u64;
+
+                // Route to a model the context fits and get its provider
+                let decision = preparation::route_for_context(self, &task, &mut context).await?;
+                Span::current().record("model_name", field::display(&decision.model));
+                // Sum token usage over every provider call the task makes
+                let tracked_provider = Arc::new(UsageTrackingProvider::new(
+                    self.retrying_provider(&task, &decision, &ui_channel)?,
+                ));
+                let provider: Arc<dyn ModelProvider> = Arc::clone(&tracked_provider) as _;
+                preparation::condense_repeated_files(self, &mut context, provider.as_ref());
+
+                // Execute agent - returns String | TaskList
+                let (agent_response, agent_timings) = self
+                    .execute_with_step_executor(ExecutorParams {
+                        task: &task,
+                        context: &context,
+                        provider: &provider,
+                        task_id,
+                        ui_channel: &ui_channel,
+                    })
+                    .await?;
+                let changed_files = preparation::refresh_changed_files(self).await;
+
+                // Handle response type
+                let mut processor =
+                    ResponseProcessor::new(&self.validator, &self.tool_registry, &mut self.runtime);
+                let mut result = processor
+                    .process_response(ResponseProcessingParams {
+                        agent_response,
+                        task_id,
+                        task: &task,
+                        decision: &decision,
+                        context: &context,
+                        provider: &provider,
+                        duration_ms: start.elapsed().as_millis() as u64,
+                        ui_channel: &ui_channel,
+                        changed_files: &changed_files,
+                    })
+                    .await?;
+                preparation::refresh_changed_files(self).await;
+
+                result.timings.context_ms += context_ms;
+                result.timings.add(&agent_timings);
+
+                let tokens_used = tracked_provider.usage();
+                if let Some(work_unit) = &mut result.work_unit {
+                    work_unit.tokens_used = tokens_used.clone();
+                }
+                result.response.tokens_used = tokens_used.clone();
+                result.tokens_used = tokens_used;
+                Span::current().record("token_count", result.tokens_used.total());
+
+                Ok::<_, RoutingError>(result)
+            }
+            .instrument(span);
+
+        select! {
+            result = execution => result
+                .with_context(|| format!("executing task '{task_description}'"))
+                .map_err(RoutingError::from),
+            () = cancel.cancelled() => {
+                tracing::info!("Task {cancelled_task_id:?} cancelled");
+                Err(RoutingError::Cancelled(cancelled_task_id))
+            }
         }
-        .instrument(span)
-        .await
+    }
+
+    /// Provider of the routed model, retrying its transient errors
+    ///
+    /// # Errors
+    /// Returns an error if the provider of the model is not available
+    fn retrying_provider(
+        &self,
+        task: &Task,
+        decision: &RoutingDecision,
+        ui_channel: &UiChannel,
+    ) -> Result<Arc<dyn ModelProvider>> {
+        let provider = self
+            .provider_registry
+            .get_provider_for_task(task.difficulty, decision.model)?;
+        Ok(Arc::new(RetryingProvider::new(
+            provider,
+            self.retry_policy,
+            Arc::clone(&self.retry_budget),
+            ui_channel.clone(),
+        )))
     }
 
     /// Execute agent with step executor
@@ -274,7 +419,7 @@ impl AgentExecutor {
     async fn execute_with_step_executor(
         &mut self,
         params: ExecutorParams<'_>,
-    ) -> Result<AgentResponse> {
+    ) -> Result<(AgentResponse, PhaseTimings)> {
         let span = span!(Level::INFO, "execute_with_step_executor", task_id = ?params.task_id);
 
         async move {
@@ -287,7 +432,7 @@ impl AgentExecutor {
                 dependencies: Vec::new(),
             };
 
-            let response = StepExecutor::execute_with_agent(AgentExecutionParams {
+            let agent_run = StepExecutor::execute_with_agent(AgentExecutionParams {
                 step: &temp_step,
                 context: params.context,
                 provider: params.provider,
@@ -300,49 +445,7 @@ impl AgentExecutor {
             })
             .await?;
 
-            Ok(response)
-        }
-        .instrument(span)
-        .await
-    }
-
-    /// Build context and log
-    ///
-    /// # Errors
-    /// Returns an error if context building fails
-    async fn build_context_and_log(
-        &self,
-        task: &Task,
-        ui_channel: &UiChannel,
-        task_id: TaskId,
-    ) -> Result<Context> {
-        let span = span!(Level::INFO, "build_context_and_log", task_id = ?task_id);
-
-        async move {
-            ui_channel.send(UiEvent::TaskStepStarted {
-                task_id,
-                step_id: "context_analysis".to_owned(),
-                step_type: "thinking".to_owned(),
-                content: "Analyzing query intent".to_owned(),
-            });
-
-            // Use cached compiled prompt (already has signatures injected)
-            let context = self
-                .context_builder
-                .build_context_for_typescript(task, ui_channel, &self.compiled_typescript_prompt)
-                .await?;
-
-            ui_channel.send(UiEvent::TaskStepCompleted {
-                task_id,
-                step_id: "context_analysis".to_owned(),
-            });
-
-            ContextLogger::log_context_breakdown(&context, &self.context_builder).await;
-            if self.context_dump_enabled.load(Ordering::Relaxed) {
-                ContextLogger::dump_context_to_log(&context, task, &self.context_builder).await;
-            }
-
-            Ok(context)
+            Ok(agent_run)
         }
         .instrument(span)
         .await
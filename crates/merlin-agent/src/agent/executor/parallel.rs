@@ -9,7 +9,9 @@ use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::Mutex;
 
-use merlin_core::{Result, RoutingError, SubtaskStatus, TaskStep, WorkUnit};
+use merlin_core::{
+    PhaseTimings, Result, RoutingError, SubtaskStatus, TIMING_MARKER, TaskStep, WorkUnit,
+};
 
 use super::step_executor::{
     StepExecutionParams, StepExecutor, StepResult, TaskListExecutionParams,
@@ -103,6 +105,9 @@ async fn update_work_unit_on_completion(
                 subtask.status
             );
             work_unit_guard.complete_subtask(subtask_id, Some(step_result.text.clone()));
+            if let Some(completed_subtask) = work_unit_guard.subtasks.get_mut(step_index) {
+                completed_subtask.timings = Some(step_result.timings.clone());
+            }
 
             // Verify it was actually completed
             if let Some(updated_subtask) = work_unit_guard.subtasks.get(step_index) {
@@ -162,6 +167,16 @@ async fn execute_and_track_step(
         step.title
     );
 
+    // Show the step of the top-level plan in the output pane
+    if params.recursion_depth == 0 {
+        params.ui_channel.plan_step_started(
+            params.task_id,
+            completed.len() + 1,
+            params.task_list.steps.len(),
+            step.title.clone(),
+        );
+    }
+
     // Mark subtask as started in WorkUnit if tracking
     if let Some(work_unit) = params.work_unit {
         mark_subtask_started(work_unit, index).await;
@@ -193,6 +208,12 @@ async fn execute_and_track_step(
 
     completed.insert(step.title.clone());
 
+    // Timing line under the completed step
+    params.ui_channel.output(
+        params.task_id,
+        format!("  {TIMING_MARKER} {}: {}", step.title, step_result.timings),
+    );
+
     // Mark subtask as completed in WorkUnit if tracking
     if let Some(work_unit) = params.work_unit {
         update_work_unit_on_completion(work_unit, params, &step.title, &step_result, completed)
@@ -268,9 +289,15 @@ pub(super) async fn execute_task_list_parallel(
         results_by_title.insert(step.title.clone(), step_result);
     }
 
+    let mut timings = PhaseTimings::default();
+    for result in results_by_title.values() {
+        timings.add(&result.timings);
+    }
+
     Ok(StepResult {
         text: build_combined_output(params, &results_by_title),
         duration_ms: start.elapsed().as_millis() as u64,
         success: true,
+        timings,
     })
 }
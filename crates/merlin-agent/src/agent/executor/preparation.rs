@@ -0,0 +1,171 @@
+//! Preparing the context a task is sent with: summarizing a long history,
+//! routing by context size, condensing repeated files and re-indexing the
+//! files tools changed.
+
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+
+use merlin_core::{
+    Context, ModelProvider, Result, RoutingError, Task, TaskId,
+    ui::{UiChannel, UiEvent},
+};
+use merlin_routing::RoutingDecision;
+use tracing::{Level, span};
+use tracing_futures::Instrument as _;
+
+use super::AgentExecutor;
+use super::logging::ContextLogger;
+
+/// Difficulty routed for conversation summaries, picking the cheapest model
+const HISTORY_SUMMARY_DIFFICULTY: u8 = 1;
+
+/// Route the task by the measured size of its context
+///
+/// Files are dropped from the context when the router found no model it fits.
+///
+/// # Errors
+/// Returns an error if routing fails, or if the context still exceeds the
+/// largest window once every unpinned file is dropped
+pub(super) async fn route_for_context(
+    executor: &AgentExecutor,
+    task: &Task,
+    context: &mut Context,
+) -> Result<RoutingDecision> {
+    let decision = executor
+        .router
+        .route_with_context(task, context.token_estimate())
+        .await?;
+    if let Some(budget) = decision.truncate_context_to {
+        let dropped = context.truncate_files(budget);
+        tracing::warn!(
+            "Context of ~{} tokens fits no available model; dropped {dropped} files \
+             to fit {} in {budget} tokens",
+            decision.context_tokens.unwrap_or_default(),
+            decision.model
+        );
+        let token_count = context.token_estimate();
+        if token_count > budget {
+            return Err(RoutingError::ContextTooLarge {
+                token_count,
+                limit: budget,
+            });
+        }
+    }
+    Ok(decision)
+}
+
+/// Route the cheapest model to summarize the history once it grows past the threshold
+pub(super) async fn prepare_history_summary(executor: &mut AgentExecutor) {
+    let Some(threshold) = executor.context_builder.compression_threshold else {
+        return;
+    };
+    if executor
+        .context_builder
+        .calculate_conversation_tokens()
+        .await
+        <= threshold
+    {
+        return;
+    }
+    let task = Task::new("Summarize the conversation".to_owned())
+        .with_difficulty(HISTORY_SUMMARY_DIFFICULTY);
+    let provider = executor.router.route(&task).await.and_then(|decision| {
+        executor
+            .provider_registry
+            .get_provider_for_task(task.difficulty, decision.model)
+    });
+    match provider {
+        Ok(provider) => executor.context_builder.summary_provider = Some(provider),
+        Err(err) => tracing::warn!("No provider to summarize the conversation: {err}"),
+    }
+}
+
+/// Stub or diff the files the thread already showed, unless the provider caches prompts
+pub(super) fn condense_repeated_files(
+    executor: &mut AgentExecutor,
+    context: &mut Context,
+    provider: &dyn ModelProvider,
+) {
+    let Some(repeated) = &mut executor.repeated_files else {
+        return;
+    };
+    if provider.supports_prompt_caching() {
+        repeated.record_all(&context.files);
+        return;
+    }
+    let saved = repeated.condense(&mut context.files);
+    if saved > 0 {
+        tracing::info!(
+            "Condensed files already shown in the thread, saving ~{} tokens",
+            saved / 4
+        );
+    }
+}
+
+/// Re-index the files written, edited or deleted by tools since the last refresh,
+/// returning them
+pub(super) async fn refresh_changed_files(executor: &AgentExecutor) -> Vec<PathBuf> {
+    let changed = executor.tool_registry.file_changes().take().await;
+    if !changed.is_empty() {
+        tracing::debug!(
+            "Refreshing language index for {} changed files",
+            changed.len()
+        );
+        executor
+            .context_builder
+            .refresh_changed_files(&changed)
+            .await;
+    }
+    changed
+}
+
+/// Build context and log
+///
+/// # Errors
+/// Returns an error if context building fails
+pub(super) async fn build_context_and_log(
+    executor: &AgentExecutor,
+    task: &Task,
+    ui_channel: &UiChannel,
+    task_id: TaskId,
+) -> Result<Context> {
+    let span = span!(Level::INFO, "build_context_and_log", task_id = ?task_id);
+
+    async move {
+        ui_channel.send(UiEvent::TaskStepStarted {
+            task_id,
+            step_id: "context_analysis".to_owned(),
+            step_type: "thinking".to_owned(),
+            content: "Analyzing query intent".to_owned(),
+        });
+
+        // Use cached compiled prompt (already has signatures injected)
+        let context = executor
+            .context_builder
+            .build_context_for_typescript(task, ui_channel, &executor.compiled_typescript_prompt)
+            .await?;
+
+        ui_channel.send(UiEvent::TaskStepCompleted {
+            task_id,
+            step_id: "context_analysis".to_owned(),
+        });
+
+        ContextLogger::log_context_breakdown(&context, &executor.context_builder).await;
+        if executor.context_dump_enabled.load(Ordering::Relaxed) {
+            ContextLogger::dump_context_to_log(
+                &context,
+                task,
+                &executor.context_builder,
+                &executor.typescript_prompt_source,
+            )
+            .await;
+        }
+        if executor.context_explanation_enabled.load(Ordering::Relaxed) {
+            ContextLogger::print_context_explanation(&context, &executor.context_builder).await;
+        }
+
+        Ok(context)
+    }
+    .instrument(span)
+    .await
+}
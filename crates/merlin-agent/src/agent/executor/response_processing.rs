@@ -1,10 +1,12 @@
 //! Response processing for agent executor
 
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 use merlin_core::{
-    AgentResponse, Context, ModelProvider, Response, Result, StepType, Task, TaskId, TaskList,
-    TaskResult, TokenUsage, ValidationResult, WorkUnit,
+    AgentResponse, Context, ModelProvider, PhaseTimings, Response, Result, StepType, Task, TaskId,
+    TaskList, TaskResult, TokenUsage, ValidationResult, WorkUnit,
 };
 use merlin_routing::{RoutingDecision, UiChannel};
 use merlin_tooling::{PersistentTypeScriptRuntime, ToolRegistry};
@@ -25,12 +27,14 @@ pub struct ResponseProcessingParams<'resp> {
     pub decision: &'resp RoutingDecision,
     /// Context used
     pub context: &'resp Context,
-    /// Provider used
+    /// Provider used, retrying its transient errors
     pub provider: &'resp Arc<dyn ModelProvider>,
     /// Duration in milliseconds
     pub duration_ms: u64,
     /// UI channel for events
     pub ui_channel: &'resp UiChannel,
+    /// Files the agent changed before responding
+    pub changed_files: &'resp [PathBuf],
 }
 
 /// Response processor for agent responses
@@ -93,7 +97,10 @@ impl<'proc> ResponseProcessor<'proc> {
             latency_ms: params.duration_ms,
         };
 
-        let validation = self.validate_response(&response, params.task).await?;
+        let mut timings = PhaseTimings::default();
+        let validation = self
+            .validate_response(&response, &params, &mut timings)
+            .await?;
 
         Ok(TaskResult {
             task_id: params.task_id,
@@ -103,6 +110,7 @@ impl<'proc> ResponseProcessor<'proc> {
             validation,
             duration_ms: params.duration_ms,
             work_unit: None,
+            timings,
         })
     }
 
@@ -165,7 +173,10 @@ impl<'proc> ResponseProcessor<'proc> {
             latency_ms: step_result.duration_ms,
         };
 
-        let validation = self.validate_response(&response, params.task).await?;
+        let mut timings = step_result.timings;
+        let validation = self
+            .validate_response(&response, &params, &mut timings)
+            .await?;
 
         // Clone work unit from Arc<Mutex<>> (TUI may still hold a reference)
         let final_work_unit = {
@@ -183,6 +194,7 @@ impl<'proc> ResponseProcessor<'proc> {
             validation,
             duration_ms: step_result.duration_ms,
             work_unit: Some(final_work_unit),
+            timings,
         })
     }
 
@@ -197,25 +209,48 @@ impl<'proc> ResponseProcessor<'proc> {
         }
     }
 
-    /// Validate response and log failures
+    /// Validate response and log failures, adding the time it took to `timings`
+    ///
+    /// Validates the files changed before responding and those changed by
+    /// task list steps since, recording files reformatted during validation as
+    /// changed by the task.
     ///
     /// # Errors
     /// Returns an error if validation fails
     async fn validate_response(
         &self,
         response: &Response,
-        task: &Task,
+        params: &ResponseProcessingParams<'_>,
+        timings: &mut PhaseTimings,
     ) -> Result<ValidationResult> {
-        self.validator
-            .validate(response, task)
-            .await
-            .map_err(|validation_error| {
-                tracing::info!(
-                    "Validation failed. Model response was:\n{}\n\nError: {:?}",
-                    response.text,
-                    validation_error
-                );
+        let file_changes = self.tool_registry.file_changes();
+        let mut changed_files = params.changed_files.to_vec();
+        for path in file_changes.changed().await {
+            if !changed_files.contains(&path) {
+                changed_files.push(path);
+            }
+        }
+
+        let start = Instant::now();
+        let validation = self
+            .validator
+            .validate(response, params.task, &changed_files)
+            .await;
+        timings.validation_ms += start.elapsed().as_millis() as u64;
+        let validation = validation.map_err(|validation_error| {
+            tracing::info!(
+                "Validation failed. Model response was:\n{}\n\nError: {:?}",
+                response.text,
                 validation_error
-            })
+            );
+            validation_error
+        })?;
+
+        for stage in &validation.stages {
+            for path in &stage.reformatted {
+                file_changes.record(path.clone()).await;
+            }
+        }
+        Ok(validation)
     }
 }
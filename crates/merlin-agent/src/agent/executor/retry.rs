@@ -0,0 +1,186 @@
+//! Retries of transient provider errors with exponential backoff
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use merlin_core::{
+    Context, MessageLevel, ModelProvider, Query, Response, Result, RoutingConfig, RoutingError,
+    UiChannel, UiEvent,
+};
+use tokio::time::sleep;
+
+/// Retries allowed across a session before provider errors are returned as they are
+pub const DEFAULT_RETRY_BUDGET: usize = 20;
+
+/// Delay before the first retry, doubled for each retry after it
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Retries left across a session
+///
+/// Shared by every task of the session so a persistently failing provider
+/// can't keep running up costs.
+#[derive(Debug)]
+pub struct RetryBudget {
+    /// Retries not yet spent
+    remaining: AtomicUsize,
+}
+
+impl RetryBudget {
+    /// Budget allowing `retries` retries in total
+    pub const fn new(retries: usize) -> Self {
+        Self {
+            remaining: AtomicUsize::new(retries),
+        }
+    }
+
+    /// Takes one retry from the budget, returning false once it is spent
+    pub fn try_take(&self) -> bool {
+        self.remaining
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| {
+                left.checked_sub(1)
+            })
+            .is_ok()
+    }
+
+    /// Retries not yet spent
+    pub fn remaining(&self) -> usize {
+        self.remaining.load(Ordering::SeqCst)
+    }
+}
+
+impl Default for RetryBudget {
+    fn default() -> Self {
+        Self::new(DEFAULT_RETRY_BUDGET)
+    }
+}
+
+/// How often and how long to wait before retrying a provider call
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries of a single call
+    pub max_retries: usize,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Longest delay between retries
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Retries `config.tiers.max_retries` times, waiting 1s, 2s, 4s and so on
+    /// up to `config.max_retry_delay_secs`
+    pub const fn from_config(config: &RoutingConfig) -> Self {
+        Self {
+            max_retries: config.tiers.max_retries,
+            base_delay: BASE_RETRY_DELAY,
+            max_delay: Duration::from_secs(config.max_retry_delay_secs),
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 1) of a call failing with `error`
+    ///
+    /// Rate limits wait as long as the provider asked, still capped at the maximum.
+    pub(super) fn delay(&self, retry: usize, error: &RoutingError) -> Duration {
+        let backoff = u32::try_from(retry.saturating_sub(1))
+            .ok()
+            .and_then(|exponent| 2u32.checked_pow(exponent))
+            .and_then(|factor| self.base_delay.checked_mul(factor))
+            .unwrap_or(self.max_delay);
+        let requested = match error.root() {
+            RoutingError::ProviderRateLimit {
+                retry_after_secs: Some(secs),
+                ..
+            } => Duration::from_secs(*secs),
+            _ => Duration::ZERO,
+        };
+        backoff.max(requested).min(self.max_delay)
+    }
+}
+
+/// Provider retrying the transient errors of the wrapped provider
+///
+/// Timeouts, rate limits and unavailable providers are retried with exponential
+/// backoff, warning in the UI before each retry. Other errors, such as rejected
+/// requests or missing API keys, are returned right away.
+pub struct RetryingProvider {
+    /// Wrapped provider
+    inner: Arc<dyn ModelProvider>,
+    /// Retry counts and delays
+    policy: RetryPolicy,
+    /// Retries left across the session
+    budget: Arc<RetryBudget>,
+    /// Channel the retry warnings are sent to
+    ui_channel: UiChannel,
+}
+
+impl RetryingProvider {
+    /// Wrap `inner`, spending retries from `budget`
+    pub fn new(
+        inner: Arc<dyn ModelProvider>,
+        policy: RetryPolicy,
+        budget: Arc<RetryBudget>,
+        ui_channel: UiChannel,
+    ) -> Self {
+        Self {
+            inner,
+            policy,
+            budget,
+            ui_channel,
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for RetryingProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+        let mut retry = 0;
+        loop {
+            let error = match self.inner.generate(query, context).await {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+            if !error.is_retryable() || retry >= self.policy.max_retries {
+                return Err(error);
+            }
+            if !self.budget.try_take() {
+                tracing::warn!("Retry budget spent, not retrying: {error}");
+                return Err(error);
+            }
+            retry += 1;
+            let delay = self.policy.delay(retry, &error);
+            tracing::warn!(
+                "Retrying {} in {:?} (attempt {retry}/{}): {error}",
+                self.inner.name(),
+                delay,
+                self.policy.max_retries
+            );
+            self.ui_channel.send(UiEvent::SystemMessage {
+                level: MessageLevel::Warning,
+                message: format!(
+                    "Retrying {} in {}s (attempt {retry}/{}): {error}",
+                    self.inner.name(),
+                    delay.as_secs(),
+                    self.policy.max_retries
+                ),
+            });
+            sleep(delay).await;
+        }
+    }
+
+    fn estimate_cost(&self, context: &Context) -> f64 {
+        self.inner.estimate_cost(context)
+    }
+
+    fn supports_prompt_caching(&self) -> bool {
+        self.inner.supports_prompt_caching()
+    }
+}
@@ -2,15 +2,16 @@
 
 use std::{result, sync::Arc, time::Instant};
 
+use anyhow::Context as _;
 use merlin_core::{
     AgentResponse, Context, ContextSpec, ContextType, ExecutionResult, JsValueHandle,
-    ModelProvider, PromptType, Query, Result, RoutingContext, RoutingError, TaskId, TaskList,
-    TaskStep, ValidationErrorType, WorkUnit,
+    ModelProvider, PhaseTimings, PromptType, Query, Result, RoutingContext, RoutingError, TaskId,
+    TaskList, TaskStep, ValidationErrorType, WorkUnit,
 };
 use merlin_routing::UiChannel;
 use merlin_tooling::{PersistentTypeScriptRuntime, ToolRegistry, ToolingJsValueHandle};
 use tokio::sync::Mutex;
-use tracing::{Level, span};
+use tracing::{Level, Span, field, span};
 use tracing_futures::Instrument as _;
 
 use super::typescript::{execute_typescript_code, extract_typescript_code};
@@ -30,6 +31,8 @@ pub struct StepResult {
     pub duration_ms: u64,
     /// Whether this step succeeded
     pub success: bool,
+    /// Time spent in each phase, over all attempts and nested steps
+    pub timings: PhaseTimings,
 }
 
 /// Parameters for step execution
@@ -145,9 +148,9 @@ impl StepExecutor {
         params: &mut StepExecutionParams<'_>,
         context: &Context,
         attempt: &mut usize,
-        start: Instant,
+        (start, timings): (Instant, &mut PhaseTimings),
     ) -> Result<Option<StepResult>> {
-        let response = Self::execute_with_agent(AgentExecutionParams {
+        let (response, agent_timings) = Self::execute_with_agent(AgentExecutionParams {
             step: params.step,
             context,
             provider: params.provider,
@@ -159,6 +162,7 @@ impl StepExecutor {
             previous_result: params.previous_result,
         })
         .await?;
+        timings.add(&agent_timings);
 
         tracing::debug!(
             "Step '{}' returned {}",
@@ -171,17 +175,14 @@ impl StepExecutor {
 
         match response {
             AgentResponse::DirectResult(result) => {
-                let validation = Self::validate_exit_requirement(
-                    params.step.exit_requirement.as_ref(),
-                    params.runtime,
-                )
-                .await;
+                let validation = Self::timed_validation(params, timings).await;
 
                 match Self::handle_validation_error(validation, &params.step.title, attempt) {
                     Ok(()) => Ok(Some(StepResult {
                         text: result,
                         duration_ms: start.elapsed().as_millis() as u64,
                         success: true,
+                        timings: timings.clone(),
                     })),
                     Err(exec_result) => {
                         // Update params for retry
@@ -193,7 +194,7 @@ impl StepExecutor {
             }
 
             AgentResponse::TaskList(task_list) => {
-                let combined_result = Box::pin(super::parallel::execute_task_list_parallel(
+                let mut combined_result = Box::pin(super::parallel::execute_task_list_parallel(
                     &mut TaskListExecutionParams {
                         task_list: &task_list,
                         base_context: context,
@@ -207,18 +208,19 @@ impl StepExecutor {
                     },
                 ))
                 .await?;
-
-                Self::validate_exit_requirement(
-                    params.step.exit_requirement.as_ref(),
-                    params.runtime,
-                )
-                .await
-                .map_err(|err| match err {
-                    ValidationErrorType::Hard(msg) | ValidationErrorType::Soft(msg) => {
-                        RoutingError::Other(format!("Task list result failed validation: {msg}"))
-                    }
-                })?;
-
+                timings.add(&combined_result.timings);
+
+                Self::timed_validation(params, timings)
+                    .await
+                    .map_err(|err| match err {
+                        ValidationErrorType::Hard(msg) | ValidationErrorType::Soft(msg) => {
+                            RoutingError::Other(format!(
+                                "Task list result failed validation: {msg}"
+                            ))
+                        }
+                    })?;
+
+                combined_result.timings = timings.clone();
                 Ok(Some(combined_result))
             }
         }
@@ -239,6 +241,7 @@ impl StepExecutor {
 
         let start = Instant::now();
         let mut attempt = 0;
+        let mut timings = PhaseTimings::default();
 
         // Initialize retry tracking
         params.retry_attempt = 0;
@@ -252,11 +255,13 @@ impl StepExecutor {
                 )));
             }
 
+            let context_start = Instant::now();
             let context = Self::build_context_from_spec(
                 params.base_context,
                 params.step.context.as_ref(),
                 params.previous_results,
             );
+            timings.context_ms += context_start.elapsed().as_millis() as u64;
 
             tracing::debug!(
                 "Executing step '{}' (attempt {})",
@@ -264,8 +269,13 @@ impl StepExecutor {
                 attempt + 1
             );
 
-            if let Some(result) =
-                Self::process_step_attempt(&mut params, &context, &mut attempt, start).await?
+            if let Some(result) = Self::process_step_attempt(
+                &mut params,
+                &context,
+                &mut attempt,
+                (start, &mut timings),
+            )
+            .await?
             {
                 return Ok(result);
             }
@@ -282,17 +292,30 @@ impl StepExecutor {
 
     /// Execute task with agent and parse response
     ///
+    /// Returns the response along with the time spent waiting for the provider
+    /// and in the tools the agent code called.
+    ///
     /// # Errors
     /// Returns an error if execution or parsing fails
     pub(crate) async fn execute_with_agent(
         params: AgentExecutionParams<'_>,
-    ) -> Result<AgentResponse> {
-        let span = span!(Level::INFO, "execute_with_agent", task_id = ?params.task_id);
+    ) -> Result<(AgentResponse, PhaseTimings)> {
+        let span = span!(
+            Level::INFO,
+            "execute_with_agent",
+            task_id = ?params.task_id,
+            model_name = params.provider.name(),
+            token_count = field::Empty,
+            cache_hit = field::Empty
+        );
+        let step = params.step;
+        let step_task_id = params.task_id;
 
         async move {
             let description = params.step.description.as_str();
             let context = params.context;
             let provider = params.provider;
+            let tool_registry = params.tool_registry;
             let runtime = params.runtime;
             let task_id = params.task_id;
             let ui_channel = params.ui_channel;
@@ -320,10 +343,16 @@ impl StepExecutor {
             );
 
             // Execute query with provider
-            let response = provider
-                .generate(&query, context)
-                .await
-                .map_err(|err| RoutingError::Other(format!("Provider error: {err}")))?;
+            // Provider errors propagate unchanged so timeouts and rate limits stay retryable
+            let provider_start = Instant::now();
+            let response = provider.generate(&query, context).await?;
+            let mut timings = PhaseTimings {
+                provider_ms: provider_start.elapsed().as_millis() as u64,
+                ..PhaseTimings::default()
+            };
+            Span::current()
+                .record("token_count", response.tokens_used.total())
+                .record("cache_hit", response.tokens_used.cache_read > 0);
 
             tracing::debug!("Agent response: {}", response.text);
 
@@ -338,11 +367,14 @@ impl StepExecutor {
             // Execute TypeScript code - returns AgentResponse (String | TaskList) directly
             let result =
                 execute_typescript_code(runtime, task_id, &typescript_code, ui_channel).await?;
+            timings.tool_ms = tool_registry.tool_timings().take().await;
 
-            Ok(result)
+            Ok::<_, RoutingError>((result, timings))
         }
         .instrument(span)
         .await
+        .with_context(|| format!("executing step '{}' of task {step_task_id:?}", step.title))
+        .map_err(RoutingError::from)
     }
 
     /// Add previous step results to context
@@ -392,6 +424,26 @@ impl StepExecutor {
         context
     }
 
+    /// Validate the exit requirement of the step, adding the time it took to `timings`
+    ///
+    /// # Errors
+    /// Returns `ValidationErrorType` if validation fails
+    async fn timed_validation(
+        params: &mut StepExecutionParams<'_>,
+        timings: &mut PhaseTimings,
+    ) -> result::Result<(), ValidationErrorType> {
+        let validation_start = Instant::now();
+        let validation =
+            Self::validate_exit_requirement(params.step.exit_requirement.as_ref(), params.runtime)
+                .await;
+        timings.validation_ms += validation_start.elapsed().as_millis() as u64;
+        // Exit requirements may call tools too
+        for (tool, duration_ms) in params.tool_registry.tool_timings().take().await {
+            timings.record_tool(&tool, duration_ms);
+        }
+        validation
+    }
+
     /// Validate exit requirement for a step result
     ///
     /// # Errors
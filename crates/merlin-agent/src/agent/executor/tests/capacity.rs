@@ -0,0 +1,193 @@
+//! Tests for routing contexts past a model's window to a larger one, end to end
+
+use super::*;
+use merlin_routing::{CatalogModel, ModelCatalog, ModelSource};
+use std::time::SystemTime;
+
+/// Bytes of each pinned file, about 50,000 tokens
+const PINNED_FILE_BYTES: usize = 200_000;
+
+/// Provider recording how many files each request's context holds
+#[derive(Default)]
+struct FileCountingProvider {
+    /// File count of each request's context
+    file_counts: Mutex<Vec<usize>>,
+}
+
+#[async_trait]
+impl ModelProvider for FileCountingProvider {
+    fn name(&self) -> &'static str {
+        "file-counting"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, _query: &Query, context: &Context) -> Result<Response> {
+        self.file_counts
+            .lock_ignore_poison()
+            .push(context.files.len());
+        Ok(Response {
+            text: "```typescript\nreturn \"done\";\n```".to_owned(),
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: self.name().to_owned(),
+            latency_ms: 0,
+        })
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+/// Catalog entry of `model` with a `window` token context window
+fn listed(model: Model, window: u64) -> CatalogModel {
+    CatalogModel {
+        id: model.model_id().to_owned(),
+        name: model.to_string(),
+        source: ModelSource::OpenRouter,
+        context_window: Some(window),
+        input_price: Some(0.1),
+        output_price: Some(0.1),
+        features: Vec::new(),
+    }
+}
+
+/// Creates an executor routing every difficulty to Qwen 2.5 Coder 32B, with a
+/// 32k window, and serving Claude 3.5 Haiku with a `haiku_window` token window
+///
+/// # Errors
+/// Returns an error if the registries or the executor cannot be created
+fn capacity_executor(
+    small: Arc<dyn ModelProvider>,
+    large: Arc<dyn ModelProvider>,
+    haiku_window: u64,
+    workspace_root: PathBuf,
+) -> Result<AgentExecutor> {
+    let mut config = RoutingConfig::default();
+    config.tiers.local_enabled = false;
+    config.tiers.groq_enabled = false;
+    config.tiers.premium_enabled = false;
+
+    let mut provider_registry = ProviderRegistry::new(config.clone())?;
+    provider_registry.register_provider(Model::Qwen25Coder32B, small);
+    provider_registry.register_provider(Model::Claude35Haiku, large);
+    let mut model_registry = ModelRegistry::new();
+    for difficulty in 1..=10 {
+        model_registry.register(difficulty, Model::Qwen25Coder32B)?;
+    }
+    let catalog = ModelCatalog::new(
+        vec![
+            listed(Model::Qwen25Coder32B, 32_768),
+            listed(Model::Claude35Haiku, haiku_window),
+        ],
+        SystemTime::UNIX_EPOCH,
+    );
+    let router = Arc::new(
+        StrategyRouter::with_model_registry(model_registry, provider_registry.clone())
+            .with_catalog(Arc::new(catalog)),
+    );
+    AgentExecutor::with_provider_registry(AgentExecutorParams {
+        router,
+        validator: Arc::new(ValidationPipeline::new(Vec::new())),
+        tool_registry: ToolRegistry::with_workspace(workspace_root.clone()),
+        context_fetcher: Arc::new(ContextFetcher::new_with_embeddings(workspace_root, false)),
+        config,
+        provider_registry,
+    })
+}
+
+/// Writes three pinned files of `PINNED_FILE_BYTES` each, about 150,000 tokens together
+///
+/// # Errors
+/// Returns an error if a file cannot be written
+fn pin_large_files(workspace: &TempDir) -> Result<Vec<PathBuf>> {
+    let mut pinned = Vec::new();
+    for name in ["schema.sql", "fixtures.json", "spec.md"] {
+        write(workspace.path().join(name), "x".repeat(PINNED_FILE_BYTES))?;
+        pinned.push(PathBuf::from(name));
+    }
+    Ok(pinned)
+}
+
+/// Tests that a context of over 100,000 tokens goes to the model whose window fits it.
+///
+/// # Errors
+/// Returns an error if the workspace or executor cannot be created, or the task fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_large_context_routes_to_large_window_model() -> Result<()> {
+    let workspace = TempDir::new()?;
+    let small = Arc::new(FileCountingProvider::default());
+    let large = Arc::new(FileCountingProvider::default());
+    let mut executor = capacity_executor(
+        Arc::clone(&small) as Arc<dyn ModelProvider>,
+        Arc::clone(&large) as Arc<dyn ModelProvider>,
+        200_000,
+        workspace.path().to_path_buf(),
+    )?;
+    executor.set_pinned_files(pin_large_files(&workspace)?);
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+
+    executor
+        .execute_task(
+            Task::new("Check the schema against the spec".to_owned()),
+            ui_channel,
+            CancellationToken::new(),
+        )
+        .await?;
+
+    assert!(small.file_counts.lock_ignore_poison().is_empty());
+    let file_counts = large.file_counts.lock_ignore_poison().clone();
+    assert_eq!(file_counts.first(), Some(&3), "{file_counts:?}");
+    Ok(())
+}
+
+/// Tests that a context no window fits fails once truncation cannot shrink it.
+///
+/// Pinned files are never dropped, so the context still exceeds the largest
+/// window and no provider is called.
+///
+/// # Errors
+/// Returns an error if the workspace or executor cannot be created.
+///
+/// # Panics
+/// Panics if the task succeeds or a provider is called.
+#[tokio::test]
+async fn test_context_too_large_for_any_window_fails() -> Result<()> {
+    let workspace = TempDir::new()?;
+    let small = Arc::new(FileCountingProvider::default());
+    let large = Arc::new(FileCountingProvider::default());
+    let mut executor = capacity_executor(
+        Arc::clone(&small) as Arc<dyn ModelProvider>,
+        Arc::clone(&large) as Arc<dyn ModelProvider>,
+        128_000,
+        workspace.path().to_path_buf(),
+    )?;
+    executor.set_pinned_files(pin_large_files(&workspace)?);
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+
+    let result = executor
+        .execute_task(
+            Task::new("Check the schema against the spec".to_owned()),
+            ui_channel,
+            CancellationToken::new(),
+        )
+        .await;
+
+    let error = result.err();
+    assert!(
+        matches!(
+            error.as_ref().map(RoutingError::root),
+            Some(RoutingError::ContextTooLarge { .. })
+        ),
+        "{error:?}"
+    );
+    assert!(small.file_counts.lock_ignore_poison().is_empty());
+    assert!(large.file_counts.lock_ignore_poison().is_empty());
+    Ok(())
+}
@@ -0,0 +1,269 @@
+//! Tests for pinned files, repeated files and history summaries in task context
+
+use super::*;
+
+/// Path and pinned flag of each file in a context
+type ContextFiles = Vec<(PathBuf, bool)>;
+
+/// Provider recording the files of every context it receives
+#[derive(Default)]
+struct ContextRecordingProvider {
+    /// Files of each request's context
+    contexts: Mutex<Vec<ContextFiles>>,
+    /// Token estimate of each request's context
+    token_estimates: Mutex<Vec<usize>>,
+}
+
+#[async_trait]
+impl ModelProvider for ContextRecordingProvider {
+    fn name(&self) -> &'static str {
+        "context-recording"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, _query: &Query, context: &Context) -> Result<Response> {
+        self.token_estimates
+            .lock_ignore_poison()
+            .push(context.token_estimate());
+        self.contexts.lock_ignore_poison().push(
+            context
+                .files
+                .iter()
+                .map(|file| (file.path.clone(), file.pinned))
+                .collect(),
+        );
+        Ok(Response {
+            text: "```typescript\nreturn \"done\";\n```".to_owned(),
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: self.name().to_owned(),
+            latency_ms: 0,
+        })
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+/// Tests that files pinned to a thread reach the context of every task in it.
+///
+/// Pins are read back from the thread store before each task, as the
+/// orchestrator does, so they survive a reload between tasks.
+///
+/// # Errors
+/// Returns an error if the workspace, store or executor cannot be created, or a task fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_pinned_files_included_in_every_task_of_thread() -> Result<()> {
+    let workspace = TempDir::new()?;
+    write(workspace.path().join("spec.md"), "The spec says hello")?;
+    let storage = TempDir::new()?;
+    let mut store = ThreadStore::new(storage.path().to_path_buf())?;
+    let thread = store.create_thread("Pinned".to_owned());
+    store.save_thread(&thread)?;
+    store.pin_file(thread.id, PathBuf::from("spec.md"))?;
+
+    let provider = Arc::new(ContextRecordingProvider::default());
+    let mut executor = executor_with_provider(
+        Arc::clone(&provider) as Arc<dyn ModelProvider>,
+        workspace.path().to_path_buf(),
+    )?;
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+
+    for description in ["Summarize the spec", "Say something unrelated"] {
+        let mut reloaded = ThreadStore::new(storage.path().to_path_buf())?;
+        reloaded.load_all()?;
+        let pinned = reloaded
+            .get_thread(thread.id)
+            .map(|stored| stored.pinned_files.clone())
+            .unwrap_or_default();
+        executor.set_pinned_files(pinned);
+        executor
+            .execute_task(
+                Task::new(description.to_owned()),
+                ui_channel.clone(),
+                CancellationToken::new(),
+            )
+            .await?;
+    }
+
+    let contexts = provider.contexts.lock_ignore_poison().clone();
+    assert_eq!(contexts.len(), 2);
+    for files in contexts {
+        assert_eq!(files.first(), Some(&(PathBuf::from("spec.md"), true)));
+    }
+    Ok(())
+}
+
+/// Tests that asking again in a thread stubs the unchanged files it already showed.
+///
+/// # Errors
+/// Returns an error if the workspace or executor cannot be created, or a task fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_repeated_query_condenses_shown_files() -> Result<()> {
+    let workspace = TempDir::new()?;
+    let invoice_lines = (0..50)
+        .map(|line| format!("    total += invoice.line_{line}; // invoice total\n"))
+        .collect::<Vec<_>>()
+        .concat();
+    write(
+        workspace.path().join("billing.rs"),
+        format!("pub fn invoice_total(invoice: &Invoice) -> u64 {{\n{invoice_lines}}}\n"),
+    )?;
+
+    let provider = Arc::new(ContextRecordingProvider::default());
+    let mut executor = executor_with_provider(
+        Arc::clone(&provider) as Arc<dyn ModelProvider>,
+        workspace.path().to_path_buf(),
+    )?;
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+
+    let mut shown = ShownFiles::new();
+    for message in [1, 3] {
+        executor.set_repeated_files(RepeatedFiles::new(shown, message));
+        executor
+            .execute_task(
+                Task::new("Fix the invoice total".to_owned()),
+                ui_channel.clone(),
+                CancellationToken::new(),
+            )
+            .await?;
+        shown = executor
+            .take_repeated_files()
+            .map(RepeatedFiles::into_shown)
+            .unwrap_or_default();
+    }
+
+    assert!(
+        shown.keys().any(|path| path.ends_with("billing.rs")),
+        "{shown:?}"
+    );
+    let estimates = provider.token_estimates.lock_ignore_poison().clone();
+    assert_eq!(estimates.len(), 2);
+    assert!(estimates.get(1) < estimates.first(), "{estimates:?}");
+    Ok(())
+}
+
+/// Provider summarizing conversations and answering tasks, recording both kinds of request
+#[derive(Default)]
+struct SummarizingProvider {
+    /// Transcripts sent for summarizing
+    summarized: Mutex<Vec<String>>,
+    /// System prompts of the task requests
+    task_prompts: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl ModelProvider for SummarizingProvider {
+    fn name(&self) -> &'static str {
+        "summarizing"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+        let text = if context
+            .system_prompt
+            .starts_with("You compress conversations")
+        {
+            self.summarized
+                .lock_ignore_poison()
+                .push(query.text.clone());
+            "- The user asked for invoice totals".to_owned()
+        } else {
+            self.task_prompts
+                .lock_ignore_poison()
+                .push(context.system_prompt.clone());
+            "```typescript\nreturn \"done\";\n```".to_owned()
+        };
+        Ok(Response {
+            text,
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: self.name().to_owned(),
+            latency_ms: 0,
+        })
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+/// Tests that a history past the threshold has its oldest turns replaced by a summary.
+///
+/// # Errors
+/// Returns an error if the workspace or executor cannot be created, or the task fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_long_history_is_summarized() -> Result<()> {
+    let workspace = TempDir::new()?;
+    let provider = Arc::new(SummarizingProvider::default());
+    let mut executor = executor_with_provider(
+        Arc::clone(&provider) as Arc<dyn ModelProvider>,
+        workspace.path().to_path_buf(),
+    )?;
+    // 24 turns of ~600 tokens each, well past the default 10,000 token threshold
+    let history = (0..24)
+        .map(|turn| {
+            let role = if turn % 2 == 0 { "user" } else { "assistant" };
+            (
+                role.to_owned(),
+                format!("turn {turn}: {}", "invoice ".repeat(300)),
+            )
+        })
+        .collect();
+    executor.set_conversation_history(history).await;
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+
+    executor
+        .execute_task(
+            Task::new("Fix the invoice total".to_owned()),
+            ui_channel,
+            CancellationToken::new(),
+        )
+        .await?;
+
+    let summarized = provider.summarized.lock_ignore_poison().clone();
+    assert_eq!(summarized.len(), 1);
+    assert!(
+        summarized
+            .iter()
+            .all(|transcript| transcript.starts_with("user: turn 0:"))
+    );
+    let prompt = provider
+        .task_prompts
+        .lock_ignore_poison()
+        .first()
+        .cloned()
+        .unwrap_or_default();
+    assert!(
+        prompt.contains(
+            "summary: Earlier in this conversation:\n- The user asked for invoice totals"
+        ),
+        "{prompt}"
+    );
+    assert!(!prompt.contains("turn 0:"));
+    assert!(prompt.contains("assistant: turn 23:"));
+    assert!(
+        executor
+            .context_builder
+            .calculate_conversation_tokens()
+            .await
+            <= 10_000
+    );
+    Ok(())
+}
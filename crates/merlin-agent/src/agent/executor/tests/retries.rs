@@ -0,0 +1,215 @@
+//! Tests for retrying transient provider errors
+
+use super::*;
+
+/// Provider failing with the errors it was given before answering
+struct FlakyProvider {
+    /// Errors returned by the next calls, in order
+    errors: Mutex<Vec<RoutingError>>,
+    /// Calls made so far
+    calls: Mutex<usize>,
+}
+
+impl FlakyProvider {
+    /// Provider failing once with each of `errors`
+    fn new(mut errors: Vec<RoutingError>) -> Self {
+        errors.reverse();
+        Self {
+            errors: Mutex::new(errors),
+            calls: Mutex::new(0),
+        }
+    }
+
+    /// Calls made so far
+    fn calls(&self) -> usize {
+        *self.calls.lock_ignore_poison()
+    }
+}
+
+#[async_trait]
+impl ModelProvider for FlakyProvider {
+    fn name(&self) -> &'static str {
+        "flaky"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, _query: &Query, _context: &Context) -> Result<Response> {
+        *self.calls.lock_ignore_poison() += 1;
+        let error = self.errors.lock_ignore_poison().pop();
+        if let Some(error) = error {
+            return Err(error);
+        }
+        Ok(Response {
+            text: "done".to_owned(),
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: self.name().to_owned(),
+            latency_ms: 0,
+        })
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+/// Policy retrying three times without waiting
+const fn instant_retries() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 3,
+        base_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    }
+}
+
+/// Error of a provider answering 503 Service Unavailable
+fn unavailable() -> RoutingError {
+    RoutingError::ProviderUnavailable("flaky API error 503 Service Unavailable".to_owned())
+}
+
+/// Tests that transient errors are retried, warning in the UI before each retry.
+///
+/// # Errors
+/// Returns an error if the call fails after its retries.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_transient_errors_are_retried() -> Result<()> {
+    let flaky = Arc::new(FlakyProvider::new(vec![
+        unavailable(),
+        RoutingError::ProviderTimeout {
+            provider: "flaky".to_owned(),
+            timeout_ms: 500,
+        },
+    ]));
+    let budget = Arc::new(RetryBudget::new(10));
+    let (ui_channel, mut receiver) = UiChannel::bounded(64);
+    let provider = RetryingProvider::new(
+        Arc::clone(&flaky) as Arc<dyn ModelProvider>,
+        instant_retries(),
+        Arc::clone(&budget),
+        ui_channel,
+    );
+
+    let response = provider
+        .generate(&Query::new("hi".to_owned()), &Context::new(""))
+        .await?;
+
+    assert_eq!(response.text, "done");
+    assert_eq!(flaky.calls(), 3);
+    assert_eq!(budget.remaining(), 8);
+    let mut warnings = Vec::new();
+    while let Some(event) = receiver.try_recv() {
+        if let UiEvent::SystemMessage {
+            level: MessageLevel::Warning,
+            message,
+        } = event
+        {
+            warnings.push(message);
+        }
+    }
+    assert_eq!(warnings.len(), 2);
+    assert!(
+        warnings
+            .first()
+            .is_some_and(|warning| warning.contains("attempt 1/3"))
+    );
+    assert!(
+        warnings
+            .get(1)
+            .is_some_and(|warning| warning.contains("timed out"))
+    );
+    Ok(())
+}
+
+/// Tests that rejected requests are returned without retrying.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_rejected_requests_are_not_retried() {
+    let flaky = Arc::new(FlakyProvider::new(vec![RoutingError::from(
+        CoreError::Provider("flaky API error 401 Unauthorized".to_owned()),
+    )]));
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+    let provider = RetryingProvider::new(
+        Arc::clone(&flaky) as Arc<dyn ModelProvider>,
+        instant_retries(),
+        Arc::new(RetryBudget::new(10)),
+        ui_channel,
+    );
+
+    let result = provider
+        .generate(&Query::new("hi".to_owned()), &Context::new(""))
+        .await;
+
+    assert!(matches!(result, Err(RoutingError::Core(_))));
+    assert_eq!(flaky.calls(), 1);
+}
+
+/// Tests that retries stop once the session's budget is spent.
+///
+/// # Errors
+/// Returns an error if the first call fails after its retry.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_retry_budget_is_shared_and_bounded() -> Result<()> {
+    let budget = Arc::new(RetryBudget::new(2));
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+    let first = Arc::new(FlakyProvider::new(vec![unavailable()]));
+    let second = Arc::new(FlakyProvider::new(vec![unavailable(), unavailable()]));
+
+    RetryingProvider::new(
+        Arc::clone(&first) as Arc<dyn ModelProvider>,
+        instant_retries(),
+        Arc::clone(&budget),
+        ui_channel.clone(),
+    )
+    .generate(&Query::new("hi".to_owned()), &Context::new(""))
+    .await?;
+    let second_result = RetryingProvider::new(
+        Arc::clone(&second) as Arc<dyn ModelProvider>,
+        instant_retries(),
+        Arc::clone(&budget),
+        ui_channel,
+    )
+    .generate(&Query::new("hi".to_owned()), &Context::new(""))
+    .await;
+
+    assert!(matches!(
+        second_result,
+        Err(RoutingError::ProviderUnavailable(_))
+    ));
+    assert_eq!(second.calls(), 2);
+    assert_eq!(budget.remaining(), 0);
+    Ok(())
+}
+
+/// Tests that retry delays double from the base delay up to the maximum.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_retry_delays_back_off_exponentially() {
+    let policy = RetryPolicy {
+        max_retries: 5,
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(5),
+    };
+    let delays: Vec<_> = (1..=4)
+        .map(|retry| policy.delay(retry, &unavailable()).as_secs())
+        .collect();
+    assert_eq!(delays, vec![1, 2, 4, 5]);
+
+    let rate_limit = RoutingError::ProviderRateLimit {
+        provider: "flaky".to_owned(),
+        retry_after_secs: Some(3),
+    };
+    assert_eq!(policy.delay(1, &rate_limit), Duration::from_secs(3));
+}
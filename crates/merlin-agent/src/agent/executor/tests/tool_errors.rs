@@ -0,0 +1,107 @@
+//! Tests for errors raised by tool calls in agent code
+
+use super::*;
+
+/// Provider that always answers with agent code calling the `failing` tool
+struct FailingToolProvider;
+
+#[async_trait]
+impl ModelProvider for FailingToolProvider {
+    fn name(&self) -> &'static str {
+        "failing-tool"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, _query: &Query, _context: &Context) -> Result<Response> {
+        Ok(Response {
+            text: "```typescript\nconst output = await failing();\nreturn output;\n```".to_owned(),
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: self.name().to_owned(),
+            latency_ms: 0,
+        })
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+/// Tool that always fails
+struct FailingTool;
+
+#[async_trait]
+impl Tool for FailingTool {
+    fn name(&self) -> &'static str {
+        "failing"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        "/**\n * Always fails\n */\ndeclare function failing(): Promise<string>;"
+    }
+
+    async fn execute(&self, _input: ToolInput) -> ToolResult<ToolOutput> {
+        Err(ToolError::ExecutionFailed("disk full".to_owned()))
+    }
+}
+
+/// Tests that a failing tool call reports every layer it passed through.
+///
+/// # Errors
+/// Returns an error if the runtime cannot be created or the step unexpectedly succeeds.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_tool_failure_error_chain_depth() -> Result<()> {
+    let tool: Arc<dyn Tool> = Arc::new(FailingTool);
+    let tool_registry =
+        ToolRegistry::with_workspace(PathBuf::from(".")).with_tool(Arc::clone(&tool));
+    let mut runtime =
+        PersistentTypeScriptRuntime::new(&HashMap::from([(tool.name().to_owned(), tool)]))?;
+    let provider: Arc<dyn ModelProvider> = Arc::new(FailingToolProvider);
+    let (ui_channel, _receiver) = UiChannel::bounded(64);
+    let step = TaskStep {
+        title: "Clean up logs".to_owned(),
+        description: "Clean up logs".to_owned(),
+        step_type: StepType::Implementation,
+        exit_requirement: None,
+        context: None,
+        dependencies: Vec::new(),
+    };
+
+    let result = StepExecutor::execute_with_agent(AgentExecutionParams {
+        step: &step,
+        context: &Context::new(""),
+        provider: &provider,
+        tool_registry: &tool_registry,
+        runtime: &mut runtime,
+        task_id: TaskId::default(),
+        ui_channel: &ui_channel,
+        retry_attempt: 0,
+        previous_result: None,
+    })
+    .await;
+    let Err(err) = result else {
+        return Err(RoutingError::Other("Expected the step to fail".to_owned()));
+    };
+
+    // Step -> TypeScript runtime -> tool failure
+    let chain = err.context_chain();
+    assert_eq!(chain.len(), 3, "unexpected chain: {chain:?}");
+    assert!(chain[0].starts_with("executing step 'Clean up logs' of task"));
+    assert_eq!(
+        chain[1],
+        "TypeScript execution failed while running agent code"
+    );
+    assert!(chain[2].contains("calling tool 'failing'"));
+    assert!(chain[2].contains("disk full"));
+    assert!(
+        err.to_string()
+            .starts_with("executing step 'Clean up logs'")
+    );
+    Ok(())
+}
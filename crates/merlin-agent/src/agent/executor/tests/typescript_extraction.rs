@@ -1,61 +1,6 @@
-//! Tests for executor functionality
-
-use super::super::AgentExecutor;
-use super::typescript;
-use crate::ValidationPipeline;
-use merlin_context::ContextFetcher;
-use merlin_core::RoutingConfig;
-use merlin_routing::StrategyRouter;
-use merlin_tooling::{BashTool, ToolRegistry};
-use std::path::PathBuf;
-use std::sync::Arc;
-
-/// Tests that an agent executor can be created successfully.
-///
-/// # Panics
-/// Panics if executor creation succeeds but returns an error when it shouldn't.
-#[tokio::test]
-async fn test_agent_executor_creation() {
-    // Use local-only config to avoid needing API keys
-    let mut config = RoutingConfig::default();
-    config.tiers.groq_enabled = false;
-    config.tiers.premium_enabled = false;
-
-    let router = StrategyRouter::with_default_strategies();
-    if router.is_err() {
-        // Expected when providers can't be initialized
-        return;
-    }
-    let Ok(router) = router else {
-        return;
-    };
-    let router = Arc::new(router);
+//! Tests for extracting TypeScript agent code from responses
 
-    let validator = Arc::new(ValidationPipeline::with_default_stages());
-    let workspace_root = PathBuf::from(".");
-    let tool_registry = ToolRegistry::with_workspace(workspace_root.clone());
-    let context_fetcher = ContextFetcher::new(workspace_root);
-
-    let executor = AgentExecutor::new(router, validator, tool_registry, context_fetcher, &config);
-
-    // Without API keys, executor creation may fail
-    if let Ok(_executor) = executor {
-        // Executor created successfully
-    }
-}
-
-/// Tests that the tool registry can be integrated with tools.
-///
-/// # Panics
-/// Panics if tool registration or retrieval doesn't work as expected.
-#[tokio::test]
-async fn test_tool_registry_integration() {
-    let tool_registry =
-        ToolRegistry::with_workspace(PathBuf::from(".")).with_tool(Arc::new(BashTool));
-
-    assert!(tool_registry.get_tool("bash").is_some());
-    assert!(tool_registry.get_tool("nonexistent").is_none());
-}
+use super::*;
 
 /// Tests extracting a single TypeScript code block from text.
 ///
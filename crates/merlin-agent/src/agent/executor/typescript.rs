@@ -1,5 +1,6 @@
 //! TypeScript code extraction and execution
 
+use anyhow::Context as _;
 use merlin_core::{
     AgentResponse, JsValueHandle as CoreJsValueHandle, Result, RoutingError, StepType, TaskId,
     TaskList, TaskStep, ui::UiEvent,
@@ -74,7 +75,7 @@ pub async fn execute_typescript_code(
         tracing::debug!("Executing TypeScript code:\n{}", code);
         let result_handle = {
             let exec_span = span!(Level::INFO, "typescript_runtime_execute");
-            runtime
+            let execution = runtime
                 .execute(code)
                 .instrument(exec_span)
                 .await
@@ -92,8 +93,11 @@ pub async fn execute_typescript_code(
                         error: err.to_string(),
                     });
 
-                    RoutingError::Other(format!("TypeScript execution failed: {err}"))
-                })?
+                    RoutingError::from(err)
+                });
+            execution
+                .context("TypeScript execution failed while running agent code")
+                .map_err(RoutingError::from)?
         };
 
         // Parse result as String or TaskList by checking JavaScript object properties
@@ -0,0 +1,59 @@
+//! Token usage accounting across the provider calls of a task
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use merlin_core::{Context, ModelProvider, Query, Response, Result, TokenUsage};
+
+/// Provider summing the token usage of every response of the wrapped provider
+pub struct UsageTrackingProvider {
+    /// Wrapped provider
+    inner: Arc<dyn ModelProvider>,
+    /// Usage summed over all responses so far
+    usage: Mutex<TokenUsage>,
+}
+
+impl UsageTrackingProvider {
+    /// Wrap `inner`, starting from zero usage
+    pub fn new(inner: Arc<dyn ModelProvider>) -> Self {
+        Self {
+            inner,
+            usage: Mutex::default(),
+        }
+    }
+
+    /// Token usage summed over all responses so far
+    pub fn usage(&self) -> TokenUsage {
+        self.usage
+            .lock()
+            .map(|usage| usage.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl ModelProvider for UsageTrackingProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+        let response = self.inner.generate(query, context).await?;
+        if let Ok(mut usage) = self.usage.lock() {
+            usage.accumulate(&response.tokens_used);
+        }
+        Ok(response)
+    }
+
+    fn estimate_cost(&self, context: &Context) -> f64 {
+        self.inner.estimate_cost(context)
+    }
+
+    fn supports_prompt_caching(&self) -> bool {
+        self.inner.supports_prompt_caching()
+    }
+}
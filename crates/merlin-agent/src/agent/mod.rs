@@ -9,7 +9,9 @@ pub mod executor;
 pub mod step;
 
 // Re-export context management from merlin-context
-pub use executor::{AgentExecutor, StepExecutionParams, StepExecutor, StepResult};
+pub use executor::{
+    AgentExecutor, DEFAULT_RETRY_BUDGET, RetryBudget, StepExecutionParams, StepExecutor, StepResult,
+};
 pub use merlin_context::ContextFetcher;
 pub use merlin_context::context_inclusion::ContextManager;
 pub use step::StepTracker;
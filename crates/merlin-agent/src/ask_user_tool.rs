@@ -0,0 +1,299 @@
+//! Tool letting the agent ask the user a clarifying question.
+//!
+//! The question is sent as a [`UiEvent::UserQuestion`] and the tool call
+//! blocks until the user answers it, the question is declined or the timeout
+//! passes. Answers of tasks running in a thread are recorded in the thread.
+//!
+//! [`UiEvent::UserQuestion`]: merlin_core::UiEvent::UserQuestion
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use merlin_core::{TaskId, ThreadId, UiChannel};
+use merlin_tooling::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+use serde_json::{Value, json};
+use tokio::time::timeout;
+
+use crate::ThreadStore;
+
+/// Store and thread a question and its answer are recorded in
+type ThreadTarget = (Arc<Mutex<ThreadStore>>, ThreadId);
+
+/// How long a question waits for an answer by default
+pub const ASK_USER_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Tool asking the user a question on behalf of a task
+pub struct AskUserTool {
+    /// Channel the question is sent on
+    ui_channel: UiChannel,
+    /// Task asking the question
+    task_id: TaskId,
+    /// How long to wait for the answer
+    timeout: Duration,
+    /// Store and thread the question and answer are recorded in
+    thread: Option<ThreadTarget>,
+}
+
+impl AskUserTool {
+    /// Creates a tool asking questions for `task_id` on `ui_channel`
+    #[must_use]
+    pub const fn new(ui_channel: UiChannel, task_id: TaskId) -> Self {
+        Self {
+            ui_channel,
+            task_id,
+            timeout: ASK_USER_TIMEOUT,
+            thread: None,
+        }
+    }
+
+    /// Set how long a question waits for an answer
+    #[must_use]
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Record questions and answers as messages of `thread_id`
+    #[must_use]
+    pub fn with_thread(
+        mut self,
+        thread_store: Arc<Mutex<ThreadStore>>,
+        thread_id: ThreadId,
+    ) -> Self {
+        self.thread = Some((thread_store, thread_id));
+        self
+    }
+
+    /// Records the exchange in the thread, logging failures as they shouldn't fail the task
+    fn record(&self, question: &str, answer: &str) {
+        let Some((store, thread_id)) = &self.thread else {
+            return;
+        };
+        let recorded = store
+            .lock()
+            .map_err(|err| err.to_string())
+            .and_then(|mut store| {
+                store
+                    .record_clarification(*thread_id, question, answer)
+                    .map_err(|err| err.to_string())
+            });
+        if let Err(err) = recorded {
+            tracing::warn!("Failed to record answer in thread {thread_id}: {err}");
+        }
+    }
+}
+
+/// Question and suggested answers of an `askUser` call
+struct Question {
+    /// Question to ask
+    text: String,
+    /// Suggested answers
+    options: Vec<String>,
+}
+
+/// Reads the question and options from positional or named parameters
+///
+/// # Errors
+/// Returns an error if the question is missing or empty
+fn parse_params(params: &Value) -> ToolResult<Question> {
+    let question = params
+        .as_str()
+        .or_else(|| params.get("question").and_then(Value::as_str))
+        .map(str::trim)
+        .filter(|question| !question.is_empty())
+        .ok_or_else(|| {
+            ToolError::invalid_argument("question", "askUser requires a 'question' parameter")
+        })?;
+    let options = params
+        .get("options")
+        .and_then(Value::as_array)
+        .map(|options| {
+            options
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Question {
+        text: question.to_owned(),
+        options,
+    })
+}
+
+#[async_trait]
+impl Tool for AskUserTool {
+    fn name(&self) -> &'static str {
+        "askUser"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Asks the user a question and waits for the answer.
+ * Only ask when the request is ambiguous and a wrong guess would waste work.
+ * @param question - Question to ask
+ * @param options - Suggested answers; the user may still answer freely
+ * @returns Promise<string> - The user's answer
+ */
+declare function askUser(question: string, options?: string[]): Promise<string>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "question": { "type": "string" },
+                "options": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["question"]
+        })
+    }
+
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let Question {
+            text: question,
+            options,
+        } = parse_params(&input.params)?;
+        let receiver = self
+            .ui_channel
+            .ask_user(self.task_id, question.clone(), options);
+
+        let answer = match timeout(self.timeout, receiver).await {
+            Ok(Ok(answer)) => answer,
+            Ok(Err(_)) => {
+                return Err(ToolError::ExecutionFailed(format!(
+                    "The user did not answer: {question}"
+                )));
+            }
+            Err(_) => {
+                return Err(ToolError::Timeout {
+                    operation: format!("Waiting for an answer to '{question}'"),
+                    seconds: self.timeout.as_secs(),
+                });
+            }
+        };
+        self.record(&question, &answer);
+        Ok(ToolOutput::success_with_data(
+            answer.clone(),
+            Value::String(answer),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use merlin_core::{Message, Task, UiEvent};
+    use tempfile::TempDir;
+    use tokio::spawn;
+
+    /// Tests that the answer resumes the call and is recorded before the task's message.
+    ///
+    /// # Errors
+    /// Returns an error if the thread store cannot be created or the tool fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_ask_user_returns_and_records_answer() -> Result<()> {
+        let storage = TempDir::new()?;
+        let mut store = ThreadStore::new(storage.path().to_path_buf())?;
+        let mut thread = store.create_thread("Questions".to_owned());
+        thread.add_message(Message::new("Add a cache".to_owned()));
+        store.save_thread(&thread)?;
+        let store = Arc::new(Mutex::new(store));
+
+        let (channel, mut receiver) = UiChannel::bounded(16);
+        let task_id = Task::new("Add a cache".to_owned()).id;
+        let tool = AskUserTool::new(channel, task_id).with_thread(Arc::clone(&store), thread.id);
+        let answerer = spawn(async move {
+            if let Some(UiEvent::UserQuestion {
+                question,
+                options,
+                answer,
+                ..
+            }) = receiver.recv().await
+            {
+                assert_eq!(question, "Which backend?");
+                assert_eq!(options, vec!["redis".to_owned(), "memory".to_owned()]);
+                assert!(answer.answer("redis".to_owned()));
+                assert!(!answer.answer("memory".to_owned()));
+            }
+        });
+
+        let output = tool
+            .execute(ToolInput {
+                params: json!({ "question": "Which backend?", "options": ["redis", "memory"] }),
+            })
+            .await?;
+        answerer.await?;
+        assert_eq!(output.data, Some(json!("redis")));
+
+        let mut reloaded = ThreadStore::new(storage.path().to_path_buf())?;
+        reloaded.load_all()?;
+        let contents: Vec<String> = reloaded
+            .get_thread(thread.id)
+            .map(|reloaded_thread| {
+                reloaded_thread
+                    .messages
+                    .iter()
+                    .map(|message| message.content.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        assert_eq!(
+            contents,
+            vec![
+                "Q: Which backend?\nA: redis".to_owned(),
+                "Add a cache".to_owned()
+            ]
+        );
+        Ok(())
+    }
+
+    /// Tests that declined and unanswered questions fail with the question text.
+    ///
+    /// # Errors
+    /// Returns an error if the answering task panics.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_ask_user_fails_without_answer() -> Result<()> {
+        let (channel, mut receiver) = UiChannel::bounded(16);
+        let task_id = Task::new("Ask".to_owned()).id;
+        let tool = AskUserTool::new(channel, task_id).with_timeout(Duration::from_millis(50));
+
+        let declining = spawn(async move {
+            if let Some(UiEvent::UserQuestion { answer, .. }) = receiver.recv().await {
+                answer.decline();
+            }
+            receiver
+        });
+        let declined = tool
+            .execute(ToolInput {
+                params: json!("Proceed?"),
+            })
+            .await;
+        assert!(
+            matches!(&declined, Err(ToolError::ExecutionFailed(message)) if message.contains("Proceed?"))
+        );
+
+        let _receiver = declining.await?;
+        let timed_out = tool
+            .execute(ToolInput {
+                params: json!({ "question": "Still there?" }),
+            })
+            .await;
+        assert!(
+            matches!(&timed_out, Err(ToolError::Timeout { operation, .. }) if operation.contains("Still there?"))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+//! Deduplication of identical requests submitted in quick succession.
+//!
+//! Submitting the same prompt twice (e.g. by pressing Enter twice) creates two
+//! tasks. While the first one runs, an identical task submitted within
+//! [`DEDUP_WINDOW`] of it waits for its result instead of calling a provider
+//! again. Results are only shared with tasks that arrive while the first one is
+//! still running, so a finished request is never replayed.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::future::Future;
+use std::hash::{Hash, Hasher as _};
+use std::result::Result as StdResult;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use merlin_core::{Result, RoutingError, TaskId, TaskResult, TokenUsage};
+use tokio::sync::oneshot::{Receiver, Sender, channel};
+
+/// How long after an execution starts identical requests may join it
+pub const DEDUP_WINDOW: Duration = Duration::from_secs(5);
+
+/// Failure of a shared execution, as reported to the requests that joined it
+#[derive(Debug, Clone)]
+struct SharedFailure {
+    /// Error message of the executing request
+    message: String,
+    /// Whether the execution was cancelled rather than failed
+    cancelled: bool,
+}
+
+/// Outcome of a shared execution
+type SharedOutcome = StdResult<TaskResult, SharedFailure>;
+
+/// An execution that identical requests can still join
+#[derive(Debug)]
+struct InFlight {
+    /// Task doing the actual work
+    leader: TaskId,
+    /// When the execution started
+    started: Instant,
+    /// Channels of the requests waiting for the outcome
+    waiters: Vec<Sender<SharedOutcome>>,
+}
+
+/// How a submitted request is executed
+enum Role {
+    /// Executes the request and shares the outcome
+    Leader,
+    /// Waits for the outcome of an identical request
+    Follower(TaskId, Receiver<SharedOutcome>),
+    /// Executes the request without sharing it (an identical one is past the window)
+    Independent,
+}
+
+/// Shares one execution between identical requests submitted close together
+#[derive(Debug)]
+pub struct RequestDeduplicator {
+    /// How long after an execution starts identical requests may join it
+    window: Duration,
+    /// Executions keyed by [`request_key`]
+    in_flight: Mutex<HashMap<u64, InFlight>>,
+}
+
+impl Default for RequestDeduplicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestDeduplicator {
+    /// Creates a deduplicator with the default window
+    pub fn new() -> Self {
+        Self {
+            window: DEDUP_WINDOW,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Overrides how long after an execution starts identical requests may join it
+    #[must_use]
+    pub const fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Runs `execute` for `task_id`, unless an identical request is already running
+    ///
+    /// A request joining another one gets a copy of its result under its own
+    /// `task_id`, with no tokens attributed to it since no provider was called.
+    ///
+    /// # Errors
+    /// Returns the error of `execute`, or of the execution the request joined
+    pub async fn run(
+        &self,
+        key: u64,
+        task_id: TaskId,
+        execute: impl Future<Output = Result<TaskResult>>,
+    ) -> Result<TaskResult> {
+        match self.join(key, task_id) {
+            Role::Follower(leader, receiver) => {
+                tracing::info!(
+                    "Task {task_id:?} duplicates running task {leader:?}, sharing its result"
+                );
+                follow(receiver, leader, task_id).await
+            }
+            Role::Independent => execute.await,
+            Role::Leader => {
+                let guard = LeaderGuard {
+                    deduplicator: self,
+                    key,
+                    leader: task_id,
+                };
+                let result = execute.await;
+                let outcome = result
+                    .as_ref()
+                    .map(Clone::clone)
+                    .map_err(|err| SharedFailure {
+                        message: err.to_string(),
+                        cancelled: matches!(err.root(), RoutingError::Cancelled(_)),
+                    });
+                for waiter in guard.finish() {
+                    // A waiter that stopped listening needs no result
+                    let _unused = waiter.send(outcome.clone());
+                }
+                result
+            }
+        }
+    }
+
+    /// Registers a request, deciding whether it executes or joins an identical one
+    fn join(&self, key: u64, task_id: TaskId) -> Role {
+        let Ok(mut in_flight) = self.in_flight.lock() else {
+            return Role::Independent;
+        };
+        match in_flight.get_mut(&key) {
+            Some(running) if running.started.elapsed() <= self.window => {
+                let (sender, receiver) = channel();
+                running.waiters.push(sender);
+                Role::Follower(running.leader, receiver)
+            }
+            Some(_) => Role::Independent,
+            None => {
+                in_flight.insert(
+                    key,
+                    InFlight {
+                        leader: task_id,
+                        started: Instant::now(),
+                        waiters: Vec::new(),
+                    },
+                );
+                Role::Leader
+            }
+        }
+    }
+
+    /// Unregisters an execution, returning the requests waiting for it
+    fn remove(&self, key: u64, leader: TaskId) -> Vec<Sender<SharedOutcome>> {
+        let Ok(mut in_flight) = self.in_flight.lock() else {
+            return Vec::new();
+        };
+        if in_flight
+            .get(&key)
+            .is_some_and(|running| running.leader == leader)
+        {
+            in_flight
+                .remove(&key)
+                .map(|running| running.waiters)
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Unregisters a leader's execution, even if its future is dropped mid-flight
+struct LeaderGuard<'dedup> {
+    /// Deduplicator the execution is registered with
+    deduplicator: &'dedup RequestDeduplicator,
+    /// Request key of the execution
+    key: u64,
+    /// Task doing the actual work
+    leader: TaskId,
+}
+
+impl LeaderGuard<'_> {
+    /// Unregisters the execution, returning the requests waiting for its outcome
+    fn finish(self) -> Vec<Sender<SharedOutcome>> {
+        self.deduplicator.remove(self.key, self.leader)
+    }
+}
+
+impl Drop for LeaderGuard<'_> {
+    fn drop(&mut self) {
+        // Dropping the waiters tells them the execution stopped without an outcome
+        self.deduplicator.remove(self.key, self.leader);
+    }
+}
+
+/// Waits for a shared execution and converts its outcome for `task_id`
+///
+/// # Errors
+/// Returns an error if the shared execution failed, was cancelled or was dropped
+async fn follow(
+    receiver: Receiver<SharedOutcome>,
+    leader: TaskId,
+    task_id: TaskId,
+) -> Result<TaskResult> {
+    match receiver.await {
+        Ok(Ok(mut result)) => {
+            result.task_id = task_id;
+            result.tokens_used = TokenUsage::default();
+            Ok(result)
+        }
+        Ok(Err(failure)) if failure.cancelled => Err(RoutingError::Cancelled(task_id)),
+        Ok(Err(failure)) => Err(RoutingError::DuplicateTaskFailed {
+            leader,
+            reason: format!("failed: {}", failure.message),
+        }),
+        Err(_) => Err(RoutingError::DuplicateTaskFailed {
+            leader,
+            reason: "stopped before finishing".to_owned(),
+        }),
+    }
+}
+
+/// Hashes a request's description with the context it runs in
+///
+/// Requests only share an execution if both the description and the context
+/// (thread or conversation history) are the same.
+pub fn request_key(description: &str, context: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    description.hash(&mut hasher);
+    context.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::{PhaseTimings, Response, ValidationResult};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::join;
+    use tokio::time::sleep;
+
+    /// Builds the result of a task that used 10 tokens
+    fn task_result(task_id: TaskId, text: &str) -> TaskResult {
+        TaskResult {
+            task_id,
+            response: Response {
+                text: text.to_owned(),
+                confidence: 1.0,
+                tokens_used: TokenUsage::default(),
+                provider: "mock".to_owned(),
+                latency_ms: 0,
+            },
+            tier_used: "mock".to_owned(),
+            tokens_used: TokenUsage {
+                input: 10,
+                ..TokenUsage::default()
+            },
+            validation: ValidationResult::default(),
+            duration_ms: 0,
+            work_unit: None,
+            timings: PhaseTimings::default(),
+        }
+    }
+
+    /// Runs a request that takes 50ms, counting executions
+    ///
+    /// # Errors
+    /// Returns the error of the execution the request joined, if any
+    async fn run_counted(
+        deduplicator: &RequestDeduplicator,
+        key: u64,
+        task_id: TaskId,
+        executions: &AtomicUsize,
+    ) -> Result<TaskResult> {
+        deduplicator
+            .run(key, task_id, async {
+                executions.fetch_add(1, Ordering::SeqCst);
+                sleep(Duration::from_millis(50)).await;
+                Ok(task_result(task_id, "done"))
+            })
+            .await
+    }
+
+    /// Tests that identical concurrent requests execute once and both get the result.
+    ///
+    /// # Errors
+    /// Returns an error if a request fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_identical_requests_execute_once() -> Result<()> {
+        let deduplicator = RequestDeduplicator::new();
+        let executions = AtomicUsize::new(0);
+        let key = request_key("fix the bug", &Vec::<(String, String)>::new());
+        let (first_id, second_id) = (TaskId::default(), TaskId::default());
+
+        let (first, second) = join!(
+            run_counted(&deduplicator, key, first_id, &executions),
+            run_counted(&deduplicator, key, second_id, &executions),
+        );
+        let (first, second) = (first?, second?);
+
+        assert_eq!(executions.load(Ordering::SeqCst), 1);
+        assert_eq!(first.task_id, first_id);
+        assert_eq!(second.task_id, second_id);
+        assert_eq!(second.response.text, "done");
+        assert_eq!(first.tokens_used.input, 10);
+        assert_eq!(second.tokens_used.input, 0);
+
+        // Once finished, the same request executes again
+        run_counted(&deduplicator, key, TaskId::default(), &executions).await?;
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+        Ok(())
+    }
+
+    /// Tests that different requests and requests past the window execute separately.
+    ///
+    /// # Errors
+    /// Returns an error if a request fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_distinct_or_late_requests_execute_separately() -> Result<()> {
+        let deduplicator = RequestDeduplicator::new();
+        let executions = AtomicUsize::new(0);
+        let (first, second) = join!(
+            run_counted(
+                &deduplicator,
+                request_key("fix the bug", &1u8),
+                TaskId::default(),
+                &executions
+            ),
+            run_counted(
+                &deduplicator,
+                request_key("fix the bug", &2u8),
+                TaskId::default(),
+                &executions
+            ),
+        );
+        first?;
+        second?;
+        assert_eq!(executions.load(Ordering::SeqCst), 2);
+
+        let expired = RequestDeduplicator::new().with_window(Duration::ZERO);
+        let key = request_key("fix the bug", &1u8);
+        let (early, late) = join!(
+            run_counted(&expired, key, TaskId::default(), &executions),
+            async {
+                sleep(Duration::from_millis(10)).await;
+                run_counted(&expired, key, TaskId::default(), &executions).await
+            },
+        );
+        early?;
+        late?;
+        assert_eq!(executions.load(Ordering::SeqCst), 4);
+        Ok(())
+    }
+
+    /// Tests that a failed or cancelled execution fails the requests that joined it.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_failures_are_shared() {
+        let deduplicator = RequestDeduplicator::new();
+        let leader_id = TaskId::default();
+        let follower_id = TaskId::default();
+        let fail = |error: RoutingError| async move {
+            sleep(Duration::from_millis(50)).await;
+            Err(error)
+        };
+
+        let (_, follower) = join!(
+            deduplicator.run(1, leader_id, fail(RoutingError::NoAvailableTier)),
+            deduplicator.run(1, follower_id, fail(RoutingError::NoAvailableTier)),
+        );
+        assert!(
+            matches!(follower, Err(RoutingError::DuplicateTaskFailed { reason, .. }) if reason.contains("No available tier"))
+        );
+
+        let (_, cancelled) = join!(
+            deduplicator.run(2, leader_id, fail(RoutingError::Cancelled(leader_id))),
+            deduplicator.run(2, follower_id, fail(RoutingError::NoAvailableTier)),
+        );
+        assert!(matches!(cancelled, Err(RoutingError::Cancelled(id)) if id == follower_id));
+    }
+}
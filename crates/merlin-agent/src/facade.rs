@@ -0,0 +1,364 @@
+//! Library entry point for running the agent from other programs.
+//!
+//! [`Merlin`] sets up the orchestrator for a project the way the CLI does, with
+//! the project as workspace and threads stored in its `.merlin` folder, and runs
+//! prompts without a UI. Each submitted prompt gets a [`TaskHandle`] streaming the
+//! task's UI events and resolving to its result.
+//!
+//! Agent code runs in a TypeScript runtime that is not `Send`, so tasks are spawned
+//! with [`spawn_local`] and must be submitted from inside a [`LocalSet`](tokio::task::LocalSet).
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt as _;
+use futures::stream::{BoxStream, unfold};
+use merlin_core::ui::DEFAULT_UI_EVENT_CAPACITY;
+use merlin_core::{
+    ModelProvider, Result, RoutingConfig, RoutingError, Task, TaskId, TaskResult, ThreadId,
+    UiChannel, UiEvent, UiEventReceiver,
+};
+use merlin_routing::{METRICS_FILE, Model, ModelRegistry, ProviderRegistry, StrategyRouter};
+use merlin_tooling::{ToolError, join_error};
+use tokio::select;
+use tokio::task::{JoinHandle, spawn_local};
+use tokio_util::sync::CancellationToken;
+
+use crate::{RoutingOrchestrator, ThreadStore};
+
+/// Folder in a project's root holding Merlin's state
+pub const MERLIN_DIR: &str = ".merlin";
+
+/// Folder in the `.merlin` folder threads are stored in
+pub const THREADS_DIR: &str = "threads";
+
+/// Agent working on one project
+pub struct Merlin {
+    /// Orchestrator running the submitted tasks
+    orchestrator: Arc<RoutingOrchestrator>,
+}
+
+impl Merlin {
+    /// Opens the agent for `project_root`, routing tasks to the providers of `config`
+    ///
+    /// # Errors
+    /// Returns an error if the providers or the thread store cannot be set up
+    pub fn open(project_root: impl Into<PathBuf>, config: RoutingConfig) -> Result<Self> {
+        let project_root = project_root.into();
+        let merlin_dir = project_root.join(MERLIN_DIR);
+        let orchestrator = Self::project_orchestrator(
+            RoutingOrchestrator::new(config)?,
+            project_root,
+            &merlin_dir,
+        )?;
+        Ok(Self::from_orchestrator(orchestrator))
+    }
+
+    /// Opens the agent for `project_root`, routing every task to `provider`
+    ///
+    /// Embeddings are disabled, so context is found by keyword search without Ollama.
+    ///
+    /// # Errors
+    /// Returns an error if the registries or the thread store cannot be set up
+    pub fn open_with_provider(
+        project_root: impl Into<PathBuf>,
+        config: RoutingConfig,
+        provider: Arc<dyn ModelProvider>,
+    ) -> Result<Self> {
+        // Every difficulty routes to one model slot served by `provider`
+        let mut registry = ProviderRegistry::new(config.clone())?;
+        registry.register_provider(Model::Qwen25Coder32B, provider);
+        let mut model_registry = ModelRegistry::new();
+        for difficulty in 1..=10 {
+            model_registry.register(difficulty, Model::Qwen25Coder32B)?;
+        }
+        let router = Arc::new(StrategyRouter::with_model_registry(
+            model_registry,
+            registry.clone(),
+        ));
+
+        let project_root = project_root.into();
+        let merlin_dir = project_root.join(MERLIN_DIR);
+        let orchestrator = RoutingOrchestrator::new_with_router(config, router, registry)?
+            .with_embeddings(false)
+            // Title requests would reach the provider as tasks
+            .with_auto_titles(false);
+        Ok(Self::from_orchestrator(Self::project_orchestrator(
+            orchestrator,
+            project_root,
+            &merlin_dir,
+        )?))
+    }
+
+    /// Wraps an orchestrator set up by the caller
+    #[must_use]
+    pub fn from_orchestrator(orchestrator: RoutingOrchestrator) -> Self {
+        Self {
+            orchestrator: Arc::new(orchestrator),
+        }
+    }
+
+    /// Points `orchestrator` at `project_root`, with threads and request metrics stored in `merlin_dir`
+    ///
+    /// # Errors
+    /// Returns an error if the thread store cannot be created
+    pub fn project_orchestrator(
+        orchestrator: RoutingOrchestrator,
+        project_root: PathBuf,
+        merlin_dir: &Path,
+    ) -> Result<RoutingOrchestrator> {
+        let thread_store = ThreadStore::new(merlin_dir.join(THREADS_DIR))?;
+        Ok(orchestrator
+            .with_workspace(project_root)
+            .with_thread_store(Arc::new(Mutex::new(thread_store)))
+            .with_metrics_log(merlin_dir.join(METRICS_FILE)))
+    }
+
+    /// Orchestrator running the submitted tasks
+    #[must_use]
+    pub const fn orchestrator(&self) -> &Arc<RoutingOrchestrator> {
+        &self.orchestrator
+    }
+
+    /// Starts a task for `prompt` in the background
+    ///
+    /// Must be called inside a [`LocalSet`](tokio::task::LocalSet). The task keeps
+    /// running if its handle is dropped.
+    pub fn submit(&self, prompt: impl Into<String>) -> TaskHandle {
+        self.spawn(Task::new(prompt.into()), None)
+    }
+
+    /// Starts a task for `prompt` in the background, continuing `thread_id`
+    ///
+    /// The task sees the thread's history and pinned files. Must be called inside
+    /// a [`LocalSet`](tokio::task::LocalSet).
+    pub fn submit_in_thread(&self, thread_id: ThreadId, prompt: impl Into<String>) -> TaskHandle {
+        self.spawn(Task::new(prompt.into()), Some(thread_id))
+    }
+
+    /// Spawns the execution of `task`, in `thread_id` if given
+    fn spawn(&self, task: Task, thread_id: Option<ThreadId>) -> TaskHandle {
+        let task_id = task.id;
+        let description = task.description.clone();
+        let (ui_channel, events) = UiChannel::bounded(DEFAULT_UI_EVENT_CAPACITY);
+        let cancel = self.orchestrator.shutdown_coordinator().task_token();
+
+        let orchestrator = Arc::clone(&self.orchestrator);
+        let task_cancel = cancel.clone();
+        let execution = spawn_local(async move {
+            ui_channel.send(UiEvent::TaskStarted {
+                task_id,
+                description: task.description.clone(),
+                parent_id: None,
+                thread_id,
+            });
+            let run = async {
+                match thread_id {
+                    Some(thread_id) => {
+                        orchestrator
+                            .execute_task_in_thread(task, ui_channel.clone(), thread_id)
+                            .await
+                    }
+                    None => {
+                        orchestrator
+                            .execute_task_streaming(task, ui_channel.clone())
+                            .await
+                    }
+                }
+            };
+            let result = select! {
+                result = run => result,
+                () = task_cancel.cancelled() => Err(RoutingError::Cancelled(task_id)),
+            };
+            match &result {
+                Ok(task_result) => ui_channel.completed(task_id, task_result.clone()),
+                Err(err) => {
+                    ui_channel.failed(task_id, ToolError::ExecutionFailed(err.to_string()));
+                }
+            }
+            result
+        });
+
+        TaskHandle {
+            task_id,
+            description,
+            events: Some(events),
+            execution,
+            cancel,
+        }
+    }
+}
+
+/// Task submitted to [`Merlin`]
+pub struct TaskHandle {
+    /// Task being executed
+    task_id: TaskId,
+    /// Prompt the task was submitted with
+    description: String,
+    /// Events of the task, until taken by [`TaskHandle::events`]
+    events: Option<UiEventReceiver>,
+    /// Spawned execution of the task
+    execution: JoinHandle<Result<TaskResult>>,
+    /// Cancels the task
+    cancel: CancellationToken,
+}
+
+impl TaskHandle {
+    /// Task being executed
+    #[must_use]
+    pub const fn task_id(&self) -> TaskId {
+        self.task_id
+    }
+
+    /// UI events of the task, from its start to its completion or failure
+    ///
+    /// The stream ends with the event completing or failing the task. Events can
+    /// only be taken once; later calls return an empty stream.
+    pub fn events(&mut self) -> BoxStream<'static, UiEvent> {
+        let task_id = self.task_id;
+        unfold(self.events.take(), move |receiver| async move {
+            let mut receiver = receiver?;
+            let event = receiver.recv().await?;
+            // Cached context fetchers keep the channel open, so the stream can't wait for it to close
+            let finished = matches!(
+                &event,
+                UiEvent::TaskCompleted { task_id: id, .. } | UiEvent::TaskFailed { task_id: id, .. }
+                    if *id == task_id
+            );
+            Some((event, (!finished).then_some(receiver)))
+        })
+        .boxed()
+    }
+
+    /// Cancels the task at its next await point
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Waits for the task to finish
+    ///
+    /// # Errors
+    /// Returns the error the task failed with, `RoutingError::Cancelled` if it was
+    /// cancelled, or the panic message if it panicked
+    pub async fn await_result(self) -> Result<TaskResult> {
+        self.execution
+            .await
+            .unwrap_or_else(|err| Err(RoutingError::from(join_error(&self.description, err))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use merlin_core::{Context, Query, Response, TokenUsage};
+    use std::future::pending;
+    use tempfile::TempDir;
+    use tokio::task::LocalSet;
+
+    /// Provider answering every task with `done`, or never answering
+    struct ScriptedProvider {
+        /// Whether requests wait forever
+        hang: bool,
+    }
+
+    #[async_trait]
+    impl ModelProvider for ScriptedProvider {
+        fn name(&self) -> &'static str {
+            "scripted"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn generate(&self, _query: &Query, _context: &Context) -> Result<Response> {
+            if self.hang {
+                pending::<()>().await;
+            }
+            Ok(Response {
+                text: "```typescript\nreturn \"done\";\n```".to_owned(),
+                confidence: 1.0,
+                tokens_used: TokenUsage::default(),
+                provider: self.name().to_owned(),
+                latency_ms: 0,
+            })
+        }
+
+        fn estimate_cost(&self, _context: &Context) -> f64 {
+            0.0
+        }
+    }
+
+    /// Agent for a fresh project whose provider answers or hangs
+    ///
+    /// # Errors
+    /// Returns an error if the agent cannot be opened
+    fn open_agent(project: &TempDir, hang: bool) -> Result<Merlin> {
+        let mut config = RoutingConfig::default();
+        config.tiers.local_enabled = false;
+        config.tiers.groq_enabled = false;
+        config.tiers.premium_enabled = false;
+        Merlin::open_with_provider(project.path(), config, Arc::new(ScriptedProvider { hang }))
+    }
+
+    /// Tests that a submitted prompt streams its lifecycle and resolves to its result.
+    ///
+    /// # Errors
+    /// Returns an error if the project or agent cannot be set up, or the task fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_submit_streams_events_and_result() -> Result<()> {
+        let project = TempDir::new()?;
+        let merlin = open_agent(&project, false)?;
+        assert!(project.path().join(MERLIN_DIR).join(THREADS_DIR).is_dir());
+
+        LocalSet::new()
+            .run_until(async {
+                let mut handle = merlin.submit("Say done");
+                let events = handle.events().collect::<Vec<_>>();
+                let (events, result) = tokio::join!(events, handle.await_result());
+
+                // String return values reach the response JSON-encoded
+                assert_eq!(result?.response.text, "\"done\"");
+                assert!(matches!(events.first(), Some(UiEvent::TaskStarted { .. })));
+                assert!(matches!(events.last(), Some(UiEvent::TaskCompleted { .. })));
+                Ok(())
+            })
+            .await
+    }
+
+    /// Tests that cancelling a task fails it as cancelled.
+    ///
+    /// # Errors
+    /// Returns an error if the project or agent cannot be set up.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_cancel_fails_task_as_cancelled() -> Result<()> {
+        let project = TempDir::new()?;
+        let merlin = open_agent(&project, true)?;
+
+        LocalSet::new()
+            .run_until(async {
+                let mut handle = merlin.submit("Wait forever");
+                let task_id = handle.task_id();
+                let mut events = handle.events();
+                assert!(matches!(
+                    events.next().await,
+                    Some(UiEvent::TaskStarted { .. })
+                ));
+
+                handle.cancel();
+                let result = handle.await_result().await;
+                assert!(matches!(result, Err(RoutingError::Cancelled(id)) if id == task_id));
+                let last = events.collect::<Vec<_>>().await.pop();
+                assert!(matches!(last, Some(UiEvent::TaskFailed { .. })));
+                Ok(())
+            })
+            .await
+    }
+}
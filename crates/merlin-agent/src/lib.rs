@@ -13,6 +13,7 @@
 //! - [`agent`]: Agent execution, context management, conversation tracking, and parallel execution
 //! - [`validator`]: Multi-stage validation pipeline for code generation
 //! - [`orchestrator`]: High-level routing orchestration
+//! - [`facade`]: [`Merlin`], running the agent on a project from other programs
 //!
 //! # Example
 //!
@@ -38,19 +39,42 @@
 
 /// Agent execution and self-assessment
 pub mod agent;
+/// Clarifying questions asked of the user by the agent
+pub mod ask_user_tool;
+/// Deduplication of identical requests submitted in quick succession
+pub mod dedup;
+/// Library entry point for running the agent from other programs
+pub mod facade;
 /// High-level orchestration of routing components
 pub mod orchestrator;
+/// Pinning of files to a thread's context by the agent
+pub mod pin_tool;
+/// Recording of sessions as replayable test fixtures
+pub mod recording;
+/// Session journal for restart recovery
+pub mod session;
+/// Graceful shutdown of in-flight tasks
+pub mod shutdown;
 /// Thread persistence and management
 pub mod thread_store;
 /// Validation pipeline and stages
 pub mod validator;
+/// Context fetchers of the directories threads work in
+pub mod workspaces;
 
 pub use agent::{
-    AgentExecutor, ContextFetcher, ContextManager, StepExecutionParams, StepExecutor, StepResult,
-    StepTracker,
+    AgentExecutor, ContextFetcher, ContextManager, DEFAULT_RETRY_BUDGET, RetryBudget,
+    StepExecutionParams, StepExecutor, StepResult, StepTracker,
 };
+pub use dedup::{DEDUP_WINDOW, RequestDeduplicator};
+pub use facade::{MERLIN_DIR, Merlin, THREADS_DIR, TaskHandle};
 pub use orchestrator::RoutingOrchestrator;
-pub use thread_store::ThreadStore;
+pub use recording::{RecordingProvider, SessionRecorder};
+pub use session::{SESSION_FILE_NAME, SessionJournal, SessionTask, SessionTaskStatus};
+pub use shutdown::{SHUTDOWN_GRACE_PERIOD, ShutdownCoordinator};
+pub use thread_store::{ThreadSearchResult, ThreadStore};
 pub use validator::{
-    SyntaxValidationStage, ValidationPipeline, ValidationStage as ValidationStageTrait, Validator,
+    FormatValidationStage, SyntaxValidationStage, ValidationPipeline,
+    ValidationStage as ValidationStageTrait, Validator,
 };
+pub use workspaces::{MAX_WORKSPACE_INDEXES, WorkspaceContexts};
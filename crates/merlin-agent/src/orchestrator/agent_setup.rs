@@ -0,0 +1,145 @@
+//! Agent executors with the tools and context of the workspace a task runs in.
+
+use super::RoutingOrchestrator;
+use super::execution::{ConversationHistory, TaskExecutionParams};
+use crate::ask_user_tool::AskUserTool;
+use crate::pin_tool::PinFileTool;
+use crate::{AgentExecutor, ContextFetcher};
+use merlin_context::{FindCallersTool, FindImplementationsTool, SymbolSearchTool};
+use merlin_core::Result;
+use merlin_tooling::{
+    BashTool, ContextRequestTool, DeleteFileTool, DiffTool, EditFileTool, FindFilesTool, GitTool,
+    JqTool, ListFilesTool, ReadFileTool, ToolAuditLog, ToolRegistry, WriteFileTool,
+};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Adds the `askUser` tool, plus the `pinFile` tool for tasks running in a thread
+pub(super) fn with_conversation_tools(
+    orchestrator: &RoutingOrchestrator,
+    tools: ToolRegistry,
+    params: &TaskExecutionParams,
+    root: &Path,
+) -> ToolRegistry {
+    let ask_user = AskUserTool::new(params.ui_channel.clone(), params.task.id);
+    let (Some(store), Some(thread_id)) = (&orchestrator.thread_store, params.thread_id) else {
+        return tools.with_tool(Arc::new(ask_user));
+    };
+    tools
+        .with_tool(Arc::new(PinFileTool::new(
+            Arc::clone(store),
+            thread_id,
+            root.to_path_buf(),
+        )))
+        .with_tool(Arc::new(ask_user.with_thread(Arc::clone(store), thread_id)))
+}
+
+/// Creates an agent executor with tool registry and context fetcher.
+///
+/// Tools operate in `workspace` and context comes from its cached fetcher.
+/// Tool calls are audited to `.merlin/tool_audit.jsonl` of the project root
+/// under the task's ID. Questions of the `askUser` tool go out on the task's
+/// UI channel. Tasks running in a thread also get the `pinFile` tool, and
+/// their answered questions are recorded in the thread.
+///
+/// # Errors
+/// Returns error if executor creation fails.
+pub(super) fn create_agent_executor(
+    orchestrator: &RoutingOrchestrator,
+    params: &TaskExecutionParams,
+    workspace: &Path,
+) -> Result<AgentExecutor> {
+    let task_id = params.task.id;
+    let context_fetcher = orchestrator
+        .workspace_contexts
+        .fetcher_for(workspace, orchestrator.enable_embeddings);
+    let root = workspace.to_path_buf();
+    let mut tools = ToolRegistry::with_workspace(root.clone()).with_audit_log(
+        ToolAuditLog::for_workspace(&orchestrator.workspace_root).with_task(task_id.to_string()),
+    );
+    if let Some(recorder) = &orchestrator.tool_call_recorder {
+        tools = tools.with_call_recorder(recorder.clone());
+    }
+    tools = with_conversation_tools(orchestrator, tools, params, &root);
+    let changes = tools.file_changes().clone();
+    let tool_registry = tools
+        .with_tool(Arc::new(BashTool::default().with_running_commands(
+            orchestrator.shutdown.running_commands().clone(),
+        )))
+        .with_tool(Arc::new(ReadFileTool::new(root.clone())))
+        .with_tool(Arc::new(
+            WriteFileTool::new(root.clone()).with_change_tracker(changes.clone()),
+        ))
+        .with_tool(Arc::new(
+            EditFileTool::new(root.clone()).with_change_tracker(changes.clone()),
+        ))
+        .with_tool(Arc::new(
+            DeleteFileTool::new(root.clone()).with_change_tracker(changes),
+        ))
+        .with_tool(Arc::new(ListFilesTool::new(root.clone())))
+        .with_tool(Arc::new(FindFilesTool::new(root.clone())))
+        .with_tool(Arc::new(DiffTool::new(root.clone())))
+        .with_tool(Arc::new(JqTool::new(root.clone())))
+        .with_tool(Arc::new(
+            GitTool::new(root.clone())
+                .with_destructive_operations(orchestrator.config.git.allow_destructive),
+        ))
+        .with_tool(Arc::new(
+            ContextRequestTool::new(root)
+                .with_symbol_resolver(Arc::<ContextFetcher>::clone(&context_fetcher)),
+        ))
+        .with_tool(Arc::new(SymbolSearchTool::new(Arc::clone(
+            &context_fetcher,
+        ))))
+        .with_tool(Arc::new(FindCallersTool::new(Arc::clone(&context_fetcher))))
+        .with_tool(Arc::new(FindImplementationsTool::new(Arc::clone(
+            &context_fetcher,
+        ))));
+
+    let mut executor = if let Some(ref registry) = orchestrator.provider_registry {
+        // Use injected provider registry (for testing)
+        use crate::agent::executor::AgentExecutorParams;
+        AgentExecutor::with_provider_registry(AgentExecutorParams {
+            router: Arc::clone(&orchestrator.router),
+            validator: Arc::clone(&orchestrator.validator),
+            tool_registry,
+            context_fetcher,
+            config: orchestrator.config.clone(),
+            provider_registry: registry.clone(),
+        })?
+    } else {
+        // Create new provider registry (production)
+        AgentExecutor::new(
+            Arc::clone(&orchestrator.router),
+            Arc::clone(&orchestrator.validator),
+            tool_registry,
+            context_fetcher,
+            &orchestrator.config,
+        )?
+    };
+
+    executor.set_retry_budget(Arc::clone(&orchestrator.retry_budget));
+    // Context dump is disabled by default
+    if orchestrator.explain_context {
+        executor.enable_context_explanation();
+    }
+    Ok(executor)
+}
+
+/// Sets up conversation history on the executor
+pub(super) async fn setup_conversation_history(
+    executor: &mut AgentExecutor,
+    conversation_history: ConversationHistory,
+) {
+    if conversation_history.is_empty() {
+        tracing::info!("No conversation history to set");
+    } else {
+        tracing::info!(
+            "Setting conversation history with {} messages",
+            conversation_history.len()
+        );
+        executor
+            .set_conversation_history(conversation_history)
+            .await;
+    }
+}
@@ -0,0 +1,69 @@
+//! Commits the changes of completed tasks on their own branch.
+
+use super::RoutingOrchestrator;
+use super::execution::TaskExecutionParams;
+use merlin_core::UiEvent;
+use merlin_tooling::{GitTool, join_error};
+use serde_json::{json, to_value};
+use tokio::task::spawn_blocking;
+
+/// Commits the changes of a completed task on a new `merlin/<task-id>` branch
+///
+/// Changes are committed in the directory the task's thread works in, and the
+/// commit is shown as a `git` tool call in a final step of the task.
+/// Nothing is committed if the task changed nothing, and failures (e.g. a
+/// workspace that is not a repository) are logged without failing the task.
+pub(super) async fn auto_commit(orchestrator: &RoutingOrchestrator, params: &TaskExecutionParams) {
+    let (task, ui_channel) = (&params.task, &params.ui_channel);
+    let git = GitTool::new(orchestrator.thread_workspace(params.thread_id));
+    let branch = format!("merlin/{}", task.id);
+    let message = task.description.clone();
+    let args = json!({ "command": "commit", "branch": branch, "message": message });
+
+    let outcome = spawn_blocking(move || git.commit_all_on_branch(&branch, &message))
+        .await
+        .map_err(|err| join_error("git auto-commit", err))
+        .and_then(|commit| commit);
+    let commit = match outcome {
+        Ok(Some(commit)) => commit,
+        Ok(None) => {
+            tracing::debug!("Task {} changed nothing, skipping auto-commit", task.id);
+            return;
+        }
+        Err(err) => {
+            tracing::warn!("Failed to auto-commit task {}: {err}", task.id);
+            return;
+        }
+    };
+    tracing::info!(
+        "Committed {} file(s) of task {} as {}",
+        commit.files.len(),
+        task.id,
+        commit.sha
+    );
+
+    let step_id = "auto_commit".to_owned();
+    ui_channel.send(UiEvent::TaskStepStarted {
+        task_id: task.id,
+        step_id: step_id.clone(),
+        step_type: "tool_call".to_owned(),
+        content: format!(
+            "Committing changes to {}",
+            commit.branch.as_deref().unwrap_or("HEAD")
+        ),
+    });
+    ui_channel.send(UiEvent::ToolCallStarted {
+        task_id: task.id,
+        tool: "git".to_owned(),
+        args,
+    });
+    ui_channel.send(UiEvent::ToolCallCompleted {
+        task_id: task.id,
+        tool: "git".to_owned(),
+        result: to_value(&commit).unwrap_or_default(),
+    });
+    ui_channel.send(UiEvent::TaskStepCompleted {
+        task_id: task.id,
+        step_id,
+    });
+}
@@ -0,0 +1,439 @@
+//! Task execution: journaling, deduplication, escalation, caching and metrics.
+
+use super::{RoutingOrchestrator, agent_setup, auto_commit, journal, thread_context};
+use crate::dedup::request_key;
+use merlin_core::{
+    ModelProvider, PhaseTimings, Response, Result, RoutingError, Task, TaskResult, ThreadId,
+    TokenUsage, UiChannel, UiEvent, ValidationResult,
+};
+use merlin_routing::{
+    Model, ProviderRegistry, RequestMetrics, RequestMetricsParams, TaskDecomposer,
+};
+use merlin_tooling::ToolError;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{Level, Span, field, span};
+use tracing_futures::Instrument as _;
+
+/// Type alias for conversation history (role, content) tuples
+pub(super) type ConversationHistory = Vec<(String, String)>;
+
+/// Parameters for task execution (internal)
+#[derive(Clone)]
+pub(super) struct TaskExecutionParams {
+    pub(super) task: Task,
+    pub(super) ui_channel: UiChannel,
+    pub(super) conversation_history: ConversationHistory,
+    /// Thread the task runs in, if any
+    pub(super) thread_id: Option<ThreadId>,
+    /// Kind of task, recorded with its request metrics
+    pub(super) task_type: &'static str,
+}
+
+/// Task type of tasks submitted directly
+const TASK: &str = "task";
+
+/// Task type of the subtasks a task was decomposed into
+const SUBTASK: &str = "subtask";
+
+/// Execute a task by splitting it into subtasks run one after another
+///
+/// # Errors
+/// Returns an error if the thread is not found, decomposition fails, or a subtask fails
+pub(super) async fn execute_task_decomposed(
+    orchestrator: &RoutingOrchestrator,
+    task: Task,
+    ui_channel: UiChannel,
+    thread_id: Option<ThreadId>,
+) -> Result<TaskResult> {
+    let mut conversation_history = match thread_id {
+        Some(thread) => thread_context::extract_thread_history(orchestrator, thread)?,
+        None => Vec::new(),
+    };
+    let provider = routed_provider(orchestrator, &task).await?;
+    let subtasks = TaskDecomposer
+        .decompose_with_model(&task, provider.as_ref())
+        .await?;
+    for subtask in &subtasks {
+        ui_channel.send(UiEvent::SubtaskSpawned {
+            parent_id: task.id,
+            child_id: subtask.id,
+            description: subtask.description.clone(),
+        });
+    }
+
+    journal::update_journal(orchestrator, |journal| {
+        journal.record_running(task.id, &task.description, thread_id)
+    });
+    let mut results = Vec::with_capacity(subtasks.len());
+    for subtask in subtasks {
+        ui_channel.task_started_with_parent(subtask.id, subtask.description.clone(), Some(task.id));
+        let outcome = execute_task_with_escalation(
+            orchestrator,
+            TaskExecutionParams {
+                task: subtask.clone(),
+                ui_channel: ui_channel.clone(),
+                conversation_history: conversation_history.clone(),
+                thread_id,
+                task_type: SUBTASK,
+            },
+        )
+        .await;
+        let result = match outcome {
+            Ok(result) => result,
+            Err(err) => {
+                ui_channel.failed(subtask.id, ToolError::ExecutionFailed(err.to_string()));
+                // Cancelled tasks stay journaled so they can be retried after a restart
+                if !matches!(err.root(), RoutingError::Cancelled(_)) {
+                    journal::update_journal(orchestrator, |journal| journal.finish(task.id));
+                }
+                return Err(err);
+            }
+        };
+
+        ui_channel.output(subtask.id, result.response.text.clone());
+        ui_channel.completed(subtask.id, result.clone());
+        conversation_history.push(("user".to_owned(), subtask.description.clone()));
+        conversation_history.push(("assistant".to_owned(), result.response.text.clone()));
+        results.push((subtask, result));
+    }
+    journal::update_journal(orchestrator, |journal| journal.finish(task.id));
+
+    Ok(TaskDecomposer::aggregate(&task, &results))
+}
+
+/// Provider of the model `task` is routed to
+///
+/// # Errors
+/// Returns an error if routing fails or the routed model has no provider
+pub(super) async fn routed_provider(
+    orchestrator: &RoutingOrchestrator,
+    task: &Task,
+) -> Result<Arc<dyn ModelProvider>> {
+    let decision = orchestrator.router.route(task).await?;
+    let registry = orchestrator
+        .provider_registry
+        .clone()
+        .map_or_else(|| ProviderRegistry::new(orchestrator.config.clone()), Ok)?;
+    registry.get_provider_for_task(task.difficulty, decision.model)
+}
+
+/// Records metrics for a task execution attempt
+///
+/// Requests of models listed in the catalog are priced from it.
+pub(super) fn record_metrics(orchestrator: &RoutingOrchestrator, params: RequestMetricsParams) {
+    let mut metrics = RequestMetrics::new(params);
+    if let Some(cost) = Model::from_name(&metrics.tier_used).and_then(|model| {
+        orchestrator
+            .catalog
+            .estimate_cost(model, &metrics.tokens_used)
+    }) {
+        metrics.cost = cost;
+    }
+    if let Ok(mut metrics_guard) = orchestrator.metrics.lock() {
+        metrics_guard.record(metrics);
+    }
+}
+
+/// Records metrics for a failed attempt at `difficulty`
+pub(super) fn record_failure_metrics(
+    orchestrator: &RoutingOrchestrator,
+    params: &TaskExecutionParams,
+    difficulty: u8,
+    latency_ms: u64,
+    escalated: bool,
+) {
+    record_metrics(
+        orchestrator,
+        RequestMetricsParams {
+            query: params.task.description.clone(),
+            task_type: params.task_type.to_owned(),
+            tier_used: format!("Difficulty-{difficulty}"),
+            latency_ms,
+            tokens_used: TokenUsage::default(),
+            success: false,
+            escalated,
+            timings: PhaseTimings::default(),
+        },
+    );
+}
+
+/// Executes a task submitted directly, journaled as running
+///
+/// # Errors
+/// Returns an error if task execution fails
+pub(super) async fn execute_task(
+    orchestrator: &RoutingOrchestrator,
+    task: Task,
+    ui_channel: UiChannel,
+    conversation_history: ConversationHistory,
+    thread_id: Option<ThreadId>,
+) -> Result<TaskResult> {
+    let params = TaskExecutionParams {
+        task,
+        ui_channel,
+        conversation_history,
+        thread_id,
+        task_type: TASK,
+    };
+    execute_journaled(orchestrator, params).await
+}
+
+/// Executes a task while it is journaled as running, so a hard stop can be recovered
+///
+/// A task identical to one started less than `DEDUP_WINDOW` ago and still running
+/// waits for that task's result instead of executing again.
+///
+/// # Errors
+/// Returns an error if task execution fails
+async fn execute_journaled(
+    orchestrator: &RoutingOrchestrator,
+    params: TaskExecutionParams,
+) -> Result<TaskResult> {
+    let task_id = params.task.id;
+    let thread_id = params.thread_id;
+    journal::update_journal(orchestrator, |journal| {
+        journal.record_running(task_id, &params.task.description, thread_id)
+    });
+
+    // Tasks in a thread share its history, so the thread identifies their context
+    let key = thread_id.map_or_else(
+        || request_key(&params.task.description, &params.conversation_history),
+        |thread| request_key(&params.task.description, &thread),
+    );
+    if let Some(recorder) = &orchestrator.session_recorder {
+        recorder.start_task(&params.task.description);
+    }
+    let result = orchestrator
+        .deduplicator
+        .run(
+            key,
+            task_id,
+            Box::pin(execute_task_with_escalation(orchestrator, params)),
+        )
+        .await;
+    if let Some(recorder) = &orchestrator.session_recorder {
+        recorder.finish_task(&result);
+    }
+
+    // Cancelled tasks stay journaled so they can be retried after a restart
+    if !matches!(
+        result.as_ref().map_err(RoutingError::root),
+        Err(RoutingError::Cancelled(_))
+    ) {
+        journal::update_journal(orchestrator, |journal| journal.finish(task_id));
+    }
+    result
+}
+
+/// Execute a task with automatic tier escalation on hard errors (internal method)
+///
+/// Retries up to 3 times total, escalating difficulty by 2 points on each hard error.
+///
+/// # Errors
+/// Returns an error if task execution fails after all retry attempts
+pub(super) async fn execute_task_with_escalation(
+    orchestrator: &RoutingOrchestrator,
+    mut params: TaskExecutionParams,
+) -> Result<TaskResult> {
+    const MAX_ESCALATION_ATTEMPTS: usize = 3;
+    const DIFFICULTY_INCREASE: u8 = 2;
+    const MAX_DIFFICULTY: u8 = 10;
+
+    let original_difficulty = params.task.difficulty;
+    let mut current_difficulty = original_difficulty;
+
+    for attempt in 0..MAX_ESCALATION_ATTEMPTS {
+        params.task.difficulty = current_difficulty;
+
+        if attempt > 0 {
+            tracing::warn!(
+                "Escalating difficulty {original_difficulty}->{current_difficulty} (attempt {}/{})",
+                attempt + 1,
+                MAX_ESCALATION_ATTEMPTS
+            );
+        }
+
+        let start_time = orchestrator.clock.now();
+        match execute_task_streaming_once(orchestrator, params.clone()).await {
+            Ok(result) => {
+                record_success_metrics(
+                    orchestrator,
+                    &params,
+                    &result,
+                    elapsed_ms(orchestrator.clock.elapsed_since(start_time)),
+                    attempt > 0,
+                );
+
+                if attempt > 0 {
+                    tracing::info!(
+                        "Task succeeded after tier escalation (difficulty: {}, attempt: {})",
+                        current_difficulty,
+                        attempt + 1
+                    );
+                }
+                if orchestrator.config.git.auto_commit {
+                    auto_commit::auto_commit(orchestrator, &params).await;
+                }
+                return Ok(result);
+            }
+            Err(err)
+                if matches!(
+                    err.root(),
+                    RoutingError::Cancelled(_) | RoutingError::InternalPanic { .. }
+                ) =>
+            {
+                return Err(err);
+            }
+            Err(err) => {
+                record_failure_metrics(
+                    orchestrator,
+                    &params,
+                    current_difficulty,
+                    elapsed_ms(orchestrator.clock.elapsed_since(start_time)),
+                    attempt > 0,
+                );
+
+                if attempt + 1 >= MAX_ESCALATION_ATTEMPTS {
+                    tracing::error!(
+                        "Task failed after {} escalation attempts (final difficulty: {})",
+                        MAX_ESCALATION_ATTEMPTS,
+                        current_difficulty
+                    );
+                    return Err(err);
+                }
+
+                tracing::info!("Task execution failed: {}. Will escalate and retry.", err);
+                current_difficulty = (current_difficulty + DIFFICULTY_INCREASE).min(MAX_DIFFICULTY);
+            }
+        }
+    }
+
+    Err(RoutingError::Other(format!(
+        "Task failed after {MAX_ESCALATION_ATTEMPTS} attempts"
+    )))
+}
+
+/// Execute a task once without retry logic (internal method)
+///
+/// # Errors
+/// Returns error if task execution or validation fails.
+pub(super) async fn execute_task_streaming_once(
+    orchestrator: &RoutingOrchestrator,
+    params: TaskExecutionParams,
+) -> Result<TaskResult> {
+    let span = span!(
+        Level::INFO,
+        "execute_task_attempt",
+        task_id = ?params.task.id,
+        cache_hit = field::Empty
+    );
+
+    async move {
+        // Check cache before executing, per workspace since threads may work in other repos
+        let workspace = orchestrator.thread_workspace(params.thread_id);
+        let cache_key = format!(
+            "{}:difficulty:{}:{}",
+            params.task.description,
+            params.task.difficulty,
+            workspace.display()
+        );
+
+        if let Ok(cache_guard) = orchestrator.cache.lock()
+            && let Some(cached_response) = cache_guard.get(&cache_key)
+        {
+            tracing::info!(
+                "Cache hit for task: {} (difficulty: {})",
+                params.task.description,
+                params.task.difficulty
+            );
+            Span::current().record("cache_hit", true);
+            return Ok(cached_result(&params, cached_response));
+        }
+
+        Span::current().record("cache_hit", false);
+
+        let mut executor = agent_setup::create_agent_executor(orchestrator, &params, &workspace)?;
+        agent_setup::setup_conversation_history(&mut executor, params.conversation_history).await;
+        executor.set_pinned_files(thread_context::thread_pinned_files(
+            orchestrator,
+            params.thread_id,
+        ));
+        if let Some(repeated) =
+            thread_context::thread_repeated_files(orchestrator, params.thread_id)
+        {
+            executor.set_repeated_files(repeated);
+        }
+
+        // Use orchestrator-determining execution which includes assessment step
+        // For simple tasks, this will skip assessment and execute directly
+        let result = executor
+            .execute_task(
+                params.task.clone(),
+                params.ui_channel.clone(),
+                orchestrator.shutdown.task_token(),
+            )
+            .await?;
+        thread_context::save_shown_files(
+            orchestrator,
+            params.thread_id,
+            executor.take_repeated_files(),
+        );
+
+        // Cache successful result
+        if let Ok(mut cache_guard) = orchestrator.cache.lock() {
+            cache_guard.put(cache_key, result.response.clone());
+            tracing::info!(
+                "Cached response for task: {} (difficulty: {})",
+                params.task.description,
+                params.task.difficulty
+            );
+        }
+
+        Ok(result)
+    }
+    .instrument(span)
+    .await
+}
+
+/// Records request metrics for a successful attempt
+fn record_success_metrics(
+    orchestrator: &RoutingOrchestrator,
+    params: &TaskExecutionParams,
+    result: &TaskResult,
+    latency_ms: u64,
+    escalated: bool,
+) {
+    record_metrics(
+        orchestrator,
+        RequestMetricsParams {
+            query: params.task.description.clone(),
+            task_type: params.task_type.to_owned(),
+            tier_used: result.tier_used.clone(),
+            latency_ms,
+            tokens_used: result.tokens_used.clone(),
+            success: true,
+            escalated,
+            timings: result.timings.clone(),
+        },
+    );
+}
+
+/// Builds the task result served from the response cache
+fn cached_result(params: &TaskExecutionParams, cached_response: Response) -> TaskResult {
+    TaskResult {
+        task_id: params.task.id,
+        response: cached_response,
+        tier_used: format!("cached-difficulty-{}", params.task.difficulty),
+        tokens_used: TokenUsage::default(),
+        validation: ValidationResult::default(),
+        duration_ms: 0,
+        work_unit: None,
+        timings: PhaseTimings::default(),
+    }
+}
+
+/// Whole milliseconds in `elapsed`, saturating at `u64::MAX`
+fn elapsed_ms(elapsed: Duration) -> u64 {
+    elapsed.as_millis().try_into().unwrap_or(u64::MAX)
+}
@@ -0,0 +1,64 @@
+//! Session journal updates and recovery of work interrupted by a previous session.
+
+use super::RoutingOrchestrator;
+use crate::{SessionJournal, SessionTask};
+use merlin_core::{Result, RoutingError};
+
+/// Marks journaled tasks and unfinished thread work as interrupted
+///
+/// # Errors
+/// Returns an error if the journal or thread store cannot be locked or written
+pub(super) fn recover_session(orchestrator: &RoutingOrchestrator) -> Result<Vec<SessionTask>> {
+    if let Some(store) = &orchestrator.thread_store {
+        let recovered = store
+            .lock()
+            .map_err(|_| RoutingError::LockPoisoned("Thread store"))?
+            .recover_interrupted_work()?;
+        if recovered > 0 {
+            tracing::info!("Marked {recovered} unfinished work unit(s) as interrupted");
+        }
+    }
+
+    let Some(journal) = &orchestrator.session_journal else {
+        return Ok(Vec::new());
+    };
+    journal
+        .lock()
+        .map_err(|_| RoutingError::LockPoisoned("Session journal"))?
+        .recover()
+}
+
+/// Removes and returns the most recently interrupted task
+///
+/// # Errors
+/// Returns an error if the journal cannot be locked or written
+pub(super) fn take_interrupted_task(
+    orchestrator: &RoutingOrchestrator,
+) -> Result<Option<SessionTask>> {
+    let Some(journal) = &orchestrator.session_journal else {
+        return Ok(None);
+    };
+    journal
+        .lock()
+        .map_err(|_| RoutingError::LockPoisoned("Session journal"))?
+        .take_interrupted()
+}
+
+/// Applies an update to the session journal, logging failures
+///
+/// Journal errors never fail the task itself; at worst a restart loses the entry.
+pub(super) fn update_journal(
+    orchestrator: &RoutingOrchestrator,
+    update: impl FnOnce(&mut SessionJournal) -> Result<()>,
+) {
+    let Some(journal) = &orchestrator.session_journal else {
+        return;
+    };
+    let outcome = journal
+        .lock()
+        .map_err(|_| RoutingError::LockPoisoned("Session journal"))
+        .and_then(|mut journal| update(&mut journal));
+    if let Err(err) = outcome {
+        tracing::warn!("Failed to update session journal: {err}");
+    }
+}
@@ -0,0 +1,499 @@
+mod agent_setup;
+mod auto_commit;
+mod execution;
+mod journal;
+mod thread_context;
+mod titles;
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::dedup::RequestDeduplicator;
+use crate::{
+    RecordingProvider, RetryBudget, SessionJournal, SessionRecorder, SessionTask,
+    ShutdownCoordinator, ThreadStore, ValidationPipeline, Validator, WorkspaceContexts,
+};
+use execution::ConversationHistory;
+use merlin_context::RepeatedFilePolicy;
+use merlin_core::{
+    Result, RoutingConfig, RoutingError, SharedClock, SystemClock, Task, TaskResult, ThreadId,
+    UiChannel,
+};
+use merlin_routing::{
+    CacheStats, DailyReport, MetricsCollector, MetricsReport, Model, ModelCatalog, ModelRegistry,
+    ModelRouter, ProviderRegistry, ResponseCache, StrategyRouter,
+};
+use merlin_tooling::ToolCallRecorder;
+
+/// High-level orchestrator that coordinates all routing components
+pub struct RoutingOrchestrator {
+    config: RoutingConfig,
+    router: Arc<dyn ModelRouter>,
+    validator: Arc<dyn Validator>,
+    workspace_root: PathBuf,
+    /// Provider registry (for testing, allows injecting mock providers)
+    provider_registry: Option<ProviderRegistry>,
+    /// Thread storage for conversation management
+    thread_store: Option<Arc<Mutex<ThreadStore>>>,
+    /// Journal of queued and running tasks for restart recovery
+    session_journal: Option<Arc<Mutex<SessionJournal>>>,
+    /// Cancels in-flight tasks when the process shuts down
+    shutdown: ShutdownCoordinator,
+    /// Whether to enable embedding/vector search initialization
+    enable_embeddings: bool,
+    /// Context fetchers of the project root and thread working directories
+    workspace_contexts: WorkspaceContexts,
+    /// Whether to generate thread titles after the first completed task
+    enable_auto_titles: bool,
+    /// Whether executors print why each context file was included to stderr
+    explain_context: bool,
+    /// How files a thread already showed the model are included again
+    repeated_files: RepeatedFilePolicy,
+    /// Records the tool calls of every task (for testing)
+    tool_call_recorder: Option<ToolCallRecorder>,
+    /// Records the session as a replayable test fixture
+    session_recorder: Option<SessionRecorder>,
+    /// Response cache for reducing API costs and latency
+    cache: Arc<Mutex<ResponseCache>>,
+    /// Metrics collector for tracking task execution statistics
+    metrics: Arc<Mutex<MetricsCollector>>,
+    /// Prices the recorded requests of the models it lists
+    catalog: Arc<ModelCatalog>,
+    /// Shares one execution between identical tasks submitted close together
+    deduplicator: RequestDeduplicator,
+    /// Clock task latencies and the shutdown grace period are measured on
+    clock: SharedClock,
+    /// Retries of transient provider errors left across the session
+    retry_budget: Arc<RetryBudget>,
+}
+
+impl RoutingOrchestrator {
+    /// Creates a new routing orchestrator with the given configuration.
+    ///
+    /// # Errors
+    /// Returns error if provider registry initialization fails.
+    pub fn new(config: RoutingConfig) -> Result<Self> {
+        // Create provider registry and router
+        let provider_registry = ProviderRegistry::new(config.clone())?;
+        let router = Arc::new(StrategyRouter::new(provider_registry));
+        Ok(Self::from_parts(config, router, None))
+    }
+
+    /// Creates an orchestrator that also routes to the models installed in Ollama.
+    ///
+    /// Difficulty levels whose default model has no enabled provider, e.g. all of
+    /// them with only the local tier enabled, go to the installed local models
+    /// listed by Ollama at startup. Without Ollama this is the same as [`new`](Self::new),
+    /// except that routing and costs use the model catalog (see [`ModelCatalog`]).
+    ///
+    /// # Errors
+    /// Returns error if provider registry initialization fails.
+    pub async fn new_with_local_models(config: RoutingConfig) -> Result<Self> {
+        let provider_registry = ProviderRegistry::new(config.clone())?;
+        let mut model_registry = ModelRegistry::with_defaults();
+        model_registry
+            .discover_local_models(&provider_registry)
+            .await;
+        let catalog = Arc::new(ModelCatalog::load_or_refresh(&config, &provider_registry).await);
+        let router = Arc::new(
+            StrategyRouter::with_model_registry(model_registry, provider_registry.clone())
+                .with_catalog(Arc::clone(&catalog)),
+        );
+        Ok(Self::new_with_router(config, router, provider_registry)?.with_model_catalog(catalog))
+    }
+
+    /// Creates an orchestrator routing every task to `model`, whatever its difficulty.
+    ///
+    /// Difficulty-based provider overrides of `config` are dropped so they can't
+    /// replace the model.
+    ///
+    /// # Errors
+    /// Returns error if provider registry initialization fails or the tier of
+    /// `model` is disabled.
+    pub fn new_with_model(mut config: RoutingConfig, model: Model) -> Result<Self> {
+        config.tiers.provider_low = None;
+        config.tiers.provider_mid = None;
+        config.tiers.provider_high = None;
+        let provider_registry = ProviderRegistry::new(config.clone())?;
+        provider_registry.get_provider(model)?;
+
+        let mut model_registry = ModelRegistry::new();
+        model_registry.register_range(1..=10, model);
+        let router = Arc::new(StrategyRouter::with_model_registry(
+            model_registry,
+            provider_registry.clone(),
+        ));
+        Self::new_with_router(config, router, provider_registry)
+    }
+
+    /// Creates a new orchestrator for testing with a custom router.
+    ///
+    /// # Errors
+    /// Returns error if configuration is invalid.
+    pub fn new_with_router(
+        config: RoutingConfig,
+        router: Arc<dyn ModelRouter>,
+        provider_registry: ProviderRegistry,
+    ) -> Result<Self> {
+        Ok(Self::from_parts(config, router, Some(provider_registry)))
+    }
+
+    /// Creates an orchestrator with default settings around `router`
+    fn from_parts(
+        config: RoutingConfig,
+        router: Arc<dyn ModelRouter>,
+        provider_registry: Option<ProviderRegistry>,
+    ) -> Self {
+        // Validation with the configured stages
+        let validator = Arc::new(ValidationPipeline::from_config(&config.validation));
+        let workspace_contexts = WorkspaceContexts::default();
+        let metrics = Arc::new(Mutex::new(workspace_contexts.metrics_collector()));
+
+        Self {
+            config,
+            router,
+            validator,
+            workspace_root: PathBuf::from("."),
+            provider_registry,
+            thread_store: None,
+            session_journal: None,
+            shutdown: ShutdownCoordinator::new(),
+            enable_embeddings: true,
+            workspace_contexts,
+            enable_auto_titles: true,
+            explain_context: false,
+            repeated_files: RepeatedFilePolicy::default(),
+            tool_call_recorder: None,
+            session_recorder: None,
+            cache: Arc::new(Mutex::new(ResponseCache::new())),
+            metrics,
+            catalog: Arc::new(ModelCatalog::default()),
+            deduplicator: RequestDeduplicator::new(),
+            clock: SystemClock::shared(),
+            retry_budget: Arc::default(),
+        }
+    }
+
+    /// Attaches thread storage for conversation management.
+    #[must_use]
+    pub fn with_thread_store(mut self, thread_store: Arc<Mutex<ThreadStore>>) -> Self {
+        self.thread_store = Some(thread_store);
+        self
+    }
+
+    /// Attaches a session journal so queued and running tasks survive a restart.
+    #[must_use]
+    pub fn with_session_journal(mut self, journal: SessionJournal) -> Self {
+        self.session_journal = Some(Arc::new(Mutex::new(journal)));
+        self
+    }
+
+    /// Appends the metrics of every request to `path`, e.g. for `merlin metrics export`.
+    #[must_use]
+    pub fn with_metrics_log(mut self, path: PathBuf) -> Self {
+        let collector = self.workspace_contexts.metrics_collector().with_log(path);
+        self.metrics = Arc::new(Mutex::new(collector));
+        self
+    }
+
+    /// Prices recorded requests, and so the session cost, from `catalog`.
+    #[must_use]
+    pub fn with_model_catalog(mut self, catalog: Arc<ModelCatalog>) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    /// Measures task latencies and the shutdown grace period on `clock`.
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.shutdown = self.shutdown.with_clock(Arc::clone(&clock));
+        self.clock = clock;
+        self
+    }
+
+    /// Sets the workspace directory for file operations.
+    #[must_use]
+    pub fn with_workspace(mut self, workspace_path: PathBuf) -> Self {
+        self.workspace_root = workspace_path;
+        self
+    }
+
+    /// Sets whether to enable embedding/vector search initialization.
+    #[must_use]
+    pub fn with_embeddings(mut self, enable: bool) -> Self {
+        self.enable_embeddings = enable;
+        self
+    }
+
+    /// Sets whether a missing embedding model is downloaded before indexing.
+    #[must_use]
+    pub fn with_auto_pull(mut self, auto_pull: bool) -> Self {
+        self.workspace_contexts = self.workspace_contexts.with_auto_pull(auto_pull);
+        self
+    }
+
+    /// Sets whether context indexes follow changes made to their workspace outside the agent.
+    #[must_use]
+    pub fn with_watch_mode(mut self, watch_mode: bool) -> Self {
+        self.workspace_contexts = self.workspace_contexts.with_watch_mode(watch_mode);
+        self
+    }
+
+    /// Sets how many workspaces keep their initialized context index.
+    ///
+    /// Defaults to [`MAX_WORKSPACE_INDEXES`](crate::MAX_WORKSPACE_INDEXES).
+    #[must_use]
+    pub fn with_index_capacity(mut self, capacity: usize) -> Self {
+        self.workspace_contexts = self.workspace_contexts.with_capacity(capacity);
+        self
+    }
+
+    /// Sets whether to generate thread titles with a model after the first completed task.
+    #[must_use]
+    pub fn with_auto_titles(mut self, enable: bool) -> Self {
+        self.enable_auto_titles = enable;
+        self
+    }
+
+    /// Sets how files a thread already showed the model are included again.
+    ///
+    /// By default unchanged files are stubbed and changed ones diffed.
+    #[must_use]
+    pub const fn with_repeated_files(mut self, policy: RepeatedFilePolicy) -> Self {
+        self.repeated_files = policy;
+        self
+    }
+
+    /// Sets whether tasks print why each context file was included to stderr.
+    ///
+    /// The explanation is a Markdown table written after context is built.
+    #[must_use]
+    pub fn with_context_explanations(mut self, enable: bool) -> Self {
+        self.explain_context = enable;
+        self
+    }
+
+    /// Records the name and parameters of every tool call made by tasks.
+    #[must_use]
+    pub fn with_tool_call_recorder(mut self, recorder: ToolCallRecorder) -> Self {
+        self.tool_call_recorder = Some(recorder);
+        self
+    }
+
+    /// Records prompts, model exchanges and tool calls so the session can be
+    /// replayed as a test fixture.
+    ///
+    /// Every provider is wrapped in a [`RecordingProvider`], the recorder's tool
+    /// call recorder replaces any set before, and thread titles are no longer
+    /// generated so their requests don't end up in the recording.
+    ///
+    /// # Errors
+    /// Returns error if the provider registry cannot be created.
+    pub fn with_session_recorder(mut self, recorder: SessionRecorder) -> Result<Self> {
+        let registry = match self.provider_registry.take() {
+            Some(registry) => registry,
+            None => ProviderRegistry::new(self.config.clone())?,
+        };
+        self.provider_registry = Some(registry.map_providers(|provider| {
+            Arc::new(RecordingProvider::new(provider, recorder.clone()))
+        }));
+        self.tool_call_recorder = Some(recorder.tool_calls());
+        self.enable_auto_titles = false;
+        self.session_recorder = Some(recorder);
+        Ok(self)
+    }
+
+    /// Gets the thread store if available
+    pub fn thread_store(&self) -> Option<Arc<Mutex<ThreadStore>>> {
+        self.thread_store.clone()
+    }
+
+    /// Gets the coordinator that cancels in-flight tasks on shutdown
+    pub const fn shutdown_coordinator(&self) -> &ShutdownCoordinator {
+        &self.shutdown
+    }
+
+    /// Execute a task with streaming and tool support
+    ///
+    /// # Errors
+    /// Returns error if task execution or validation fails.
+    pub async fn execute_task_streaming(
+        &self,
+        task: Task,
+        ui_channel: UiChannel,
+    ) -> Result<TaskResult> {
+        self.execute_task_streaming_with_history(task, ui_channel, Vec::new())
+            .await
+    }
+
+    /// Execute a task with streaming, tool support, and conversation history.
+    ///
+    /// # Errors
+    /// Returns an error if task execution or validation fails
+    pub async fn execute_task_streaming_with_history(
+        &self,
+        task: Task,
+        ui_channel: UiChannel,
+        conversation_history: ConversationHistory,
+    ) -> Result<TaskResult> {
+        execution::execute_task(self, task, ui_channel, conversation_history, None).await
+    }
+
+    /// Execute a task within a thread context, automatically extracting conversation history.
+    ///
+    /// # Errors
+    /// Returns an error if thread not found, or if task execution or validation fails
+    pub async fn execute_task_in_thread(
+        &self,
+        task: Task,
+        ui_channel: UiChannel,
+        thread_id: ThreadId,
+    ) -> Result<TaskResult> {
+        let conversation_history = thread_context::extract_thread_history(self, thread_id)?;
+
+        execution::execute_task(
+            self,
+            task,
+            ui_channel,
+            conversation_history,
+            Some(thread_id),
+        )
+        .await
+    }
+
+    /// Execute a task by splitting it into subtasks run one after another
+    ///
+    /// The model routed for `task` splits it into subtasks, each executed and reported
+    /// to the UI as a child of `task`. Later subtasks see the responses of earlier ones.
+    /// The result of `task` combines those of its subtasks and is only returned once
+    /// every subtask completed; the first failing subtask fails `task`.
+    ///
+    /// # Errors
+    /// Returns an error if the thread is not found, decomposition fails, or a subtask fails
+    pub async fn execute_task_decomposed(
+        &self,
+        task: Task,
+        ui_channel: UiChannel,
+        thread_id: Option<ThreadId>,
+    ) -> Result<TaskResult> {
+        execution::execute_task_decomposed(self, task, ui_channel, thread_id).await
+    }
+
+    /// Recovers from a previous session that stopped while work was queued or running
+    ///
+    /// Journaled tasks are marked as interrupted and returned so they can be offered
+    /// for a retry, and thread work units left in progress are moved to a terminal state.
+    /// Must be called at startup, before any task is executed.
+    ///
+    /// # Errors
+    /// Returns an error if the journal or thread store cannot be locked or written
+    pub fn recover_session(&self) -> Result<Vec<SessionTask>> {
+        journal::recover_session(self)
+    }
+
+    /// Removes and returns the most recently interrupted task so it can be re-run
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be locked or written
+    pub fn take_interrupted_task(&self) -> Result<Option<SessionTask>> {
+        journal::take_interrupted_task(self)
+    }
+
+    /// Journals a prompt queued behind running work, replacing any previous one
+    pub fn record_queued_prompt(&self, prompt: &str, thread_id: Option<ThreadId>) {
+        journal::update_journal(self, |journal| journal.record_queued(prompt, thread_id));
+    }
+
+    /// Removes the queued prompt from the journal
+    pub fn clear_queued_prompt(&self) {
+        journal::update_journal(self, SessionJournal::clear_queued);
+    }
+
+    /// Generates a short title for a thread after its first completed task
+    ///
+    /// The cheapest model names the thread from the summary of its first
+    /// request and the result of its work (see [`merlin_core::Thread::summarize`]).
+    ///
+    /// Only threads still using their default name are retitled, so manual
+    /// renames are never overwritten. Returns whether a title was applied
+    /// (always `false` when auto-titles are disabled).
+    ///
+    /// # Errors
+    /// Returns an error if the thread store is unavailable, the thread is missing,
+    /// or the title model request fails
+    pub async fn generate_thread_title(&self, thread_id: ThreadId) -> Result<bool> {
+        titles::generate_thread_title(self, thread_id).await
+    }
+
+    /// Directory tasks of the given thread run in
+    ///
+    /// This is the thread's working directory, resolved against the project root
+    /// if relative, or the project root for tasks outside a thread or in threads
+    /// without one.
+    pub fn thread_workspace(&self, thread_id: Option<ThreadId>) -> PathBuf {
+        thread_context::thread_workspace(self, thread_id)
+    }
+
+    /// Gets the routing configuration.
+    pub fn config(&self) -> &RoutingConfig {
+        &self.config
+    }
+
+    /// Gets the workspace root path.
+    #[must_use]
+    pub fn workspace_root(&self) -> &PathBuf {
+        &self.workspace_root
+    }
+
+    /// Gets cache statistics (entries, size).
+    ///
+    /// # Errors
+    /// Returns error if cache lock is poisoned.
+    pub fn cache_stats(&self) -> Result<CacheStats> {
+        self.cache
+            .lock()
+            .map(|cache| cache.stats())
+            .map_err(|_| RoutingError::LockPoisoned("Response cache"))
+    }
+
+    /// Gets daily metrics report (success rate, latency, cost, tier distribution).
+    ///
+    /// # Errors
+    /// Returns error if metrics lock is poisoned.
+    pub fn metrics_report(&self) -> Result<DailyReport> {
+        self.metrics
+            .lock()
+            .map(|metrics| MetricsReport::daily(&metrics))
+            .map_err(|_| RoutingError::LockPoisoned("Metrics"))
+    }
+
+    /// Gets the estimated cost in USD of every request made this session.
+    ///
+    /// # Errors
+    /// Returns error if metrics lock is poisoned.
+    pub fn session_cost(&self) -> Result<f64> {
+        self.metrics
+            .lock()
+            .map(|metrics| metrics.total_cost())
+            .map_err(|_| RoutingError::LockPoisoned("Metrics"))
+    }
+
+    /// Collector of the metrics of every request made this session, e.g. to export them.
+    pub fn metrics_collector(&self) -> Arc<Mutex<MetricsCollector>> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Clears the response cache.
+    ///
+    /// # Errors
+    /// Returns error if cache lock is poisoned.
+    pub fn clear_cache(&self) -> Result<()> {
+        self.cache
+            .lock()
+            .map(|mut cache| cache.clear())
+            .map_err(|_| RoutingError::LockPoisoned("Response cache"))
+    }
+}
+
+#[cfg(test)]
+mod tests;
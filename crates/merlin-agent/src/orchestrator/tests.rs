@@ -0,0 +1,13 @@
+//! Tests for the routing orchestrator
+
+use super::*;
+
+/// # Panics
+/// Test function - panics indicate test failure
+#[tokio::test]
+async fn test_orchestrator_creation() {
+    let config = RoutingConfig::default();
+    if let Ok(orchestrator) = RoutingOrchestrator::new(config) {
+        assert!(orchestrator.config.tiers.local_enabled);
+    }
+}
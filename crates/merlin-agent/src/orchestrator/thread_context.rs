@@ -0,0 +1,139 @@
+//! Conversation history, pinned files and shown files of the thread a task runs in.
+
+use super::RoutingOrchestrator;
+use super::execution::ConversationHistory;
+use merlin_context::{RepeatedFilePolicy, RepeatedFiles};
+use merlin_core::{Message, Result, RoutingError, ThreadId};
+use std::path::PathBuf;
+
+/// Extracts conversation history from a thread
+///
+/// # Errors
+/// Returns an error if thread store is not available or thread not found
+pub(super) fn extract_thread_history(
+    orchestrator: &RoutingOrchestrator,
+    thread_id: ThreadId,
+) -> Result<ConversationHistory> {
+    let thread_store = orchestrator
+        .thread_store
+        .as_ref()
+        .ok_or_else(|| RoutingError::NoThreadStore)?;
+
+    let store = thread_store
+        .lock()
+        .map_err(|_| RoutingError::LockPoisoned("Thread store"))?;
+
+    // Branches include their parent's messages up to the branch point
+    let messages: Vec<Message> = store
+        .conversation(thread_id)?
+        .into_iter()
+        .cloned()
+        .collect();
+
+    // Drop the lock before processing
+    drop(store);
+
+    let mut history = Vec::new();
+
+    for message in &messages {
+        // Add user message
+        history.push(("user".to_string(), message.content.clone()));
+
+        // Add assistant response from work unit if available
+        if let Some(ref work) = message.work {
+            // For now, we don't have the actual response text stored in WorkUnit
+            // This will be enhanced when we integrate with the execution flow
+            // Placeholder: use subtask descriptions as response
+            let response_text = work
+                .subtasks
+                .iter()
+                .map(|subtask| subtask.description.clone())
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            if !response_text.is_empty() {
+                history.push(("assistant".to_string(), response_text));
+            }
+        }
+    }
+
+    Ok(history)
+}
+
+/// Files pinned to the given thread (none outside a thread)
+pub(super) fn thread_pinned_files(
+    orchestrator: &RoutingOrchestrator,
+    thread_id: Option<ThreadId>,
+) -> Vec<PathBuf> {
+    let (Some(store), Some(thread_id)) = (&orchestrator.thread_store, thread_id) else {
+        return Vec::new();
+    };
+    store
+        .lock()
+        .ok()
+        .and_then(|store| {
+            store
+                .get_thread(thread_id)
+                .map(|thread| thread.pinned_files.clone())
+        })
+        .unwrap_or_default()
+}
+
+/// Files the thread already showed the model, unless repeats are included in full
+pub(super) fn thread_repeated_files(
+    orchestrator: &RoutingOrchestrator,
+    thread_id: Option<ThreadId>,
+) -> Option<RepeatedFiles> {
+    if orchestrator.repeated_files == RepeatedFilePolicy::Include {
+        return None;
+    }
+    let store = orchestrator.thread_store.as_ref()?.lock().ok()?;
+    let thread = store.get_thread(thread_id?)?;
+    Some(RepeatedFiles::new(
+        thread.shown_files.clone(),
+        thread.messages.len(),
+    ))
+}
+
+/// Persist the files shown while answering a thread's message
+pub(super) fn save_shown_files(
+    orchestrator: &RoutingOrchestrator,
+    thread_id: Option<ThreadId>,
+    repeated: Option<RepeatedFiles>,
+) {
+    let (Some(store), Some(thread_id), Some(repeated)) =
+        (&orchestrator.thread_store, thread_id, repeated)
+    else {
+        return;
+    };
+    let Ok(mut store) = store.lock() else {
+        return;
+    };
+    if let Err(err) = store.set_shown_files(thread_id, repeated.into_shown()) {
+        tracing::warn!("Failed to save files shown in thread {thread_id}: {err}");
+    }
+}
+
+/// Directory tasks of the given thread run in
+pub(super) fn thread_workspace(
+    orchestrator: &RoutingOrchestrator,
+    thread_id: Option<ThreadId>,
+) -> PathBuf {
+    let working_dir =
+        orchestrator
+            .thread_store
+            .as_ref()
+            .zip(thread_id)
+            .and_then(|(store, thread_id)| {
+                store
+                    .lock()
+                    .ok()?
+                    .get_thread(thread_id)?
+                    .working_dir
+                    .clone()
+            });
+    working_dir.map_or_else(
+        || orchestrator.workspace_root.clone(),
+        |dir| orchestrator.workspace_root.join(dir),
+    )
+}
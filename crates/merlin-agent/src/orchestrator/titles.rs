@@ -0,0 +1,61 @@
+//! Thread titles generated by the cheapest model after a thread's first task.
+
+use super::{RoutingOrchestrator, execution};
+use merlin_core::{Context, Query, Result, RoutingError, Task, ThreadId, TitleSource, WorkStatus};
+
+/// Difficulty used to route title generation to the cheapest available model
+const TITLE_DIFFICULTY: u8 = 1;
+/// System prompt for thread title generation
+const TITLE_SYSTEM_PROMPT: &str = "You name conversation threads. Reply with a descriptive title \
+     of at most 8 words for the conversation summarized by the user. Reply with the title only, \
+     without quotes.";
+
+/// Generates a short title for a thread after its first completed task
+///
+/// # Errors
+/// Returns an error if the thread store is unavailable, the thread is missing,
+/// or the title model request fails
+pub(super) async fn generate_thread_title(
+    orchestrator: &RoutingOrchestrator,
+    thread_id: ThreadId,
+) -> Result<bool> {
+    if !orchestrator.enable_auto_titles {
+        return Ok(false);
+    }
+
+    let thread_store = orchestrator
+        .thread_store
+        .as_ref()
+        .ok_or_else(|| RoutingError::NoThreadStore)?;
+
+    let summary = {
+        let store = thread_store
+            .lock()
+            .map_err(|_| RoutingError::LockPoisoned("Thread store"))?;
+        let thread = store
+            .get_thread(thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?;
+
+        let completed_tasks = thread
+            .messages
+            .iter()
+            .filter_map(|message| message.work.as_ref())
+            .filter(|work| work.status == WorkStatus::Completed)
+            .count();
+        if thread.title_source != TitleSource::Default || completed_tasks != 1 {
+            return Ok(false);
+        }
+        thread.summarize()
+    };
+
+    let task = Task::new(summary.clone()).with_difficulty(TITLE_DIFFICULTY);
+    let provider = execution::routed_provider(orchestrator, &task).await?;
+    let response = provider
+        .generate(&Query::new(summary), &Context::new(TITLE_SYSTEM_PROMPT))
+        .await?;
+
+    thread_store
+        .lock()
+        .map_err(|_| RoutingError::LockPoisoned("Thread store"))?
+        .apply_generated_title(thread_id, &response.text)
+}
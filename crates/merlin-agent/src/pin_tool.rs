@@ -0,0 +1,184 @@
+//! Tool letting the agent pin files to its thread's context.
+//!
+//! Pinned files are included in the context of every later message in the
+//! thread, ahead of retrieved files. The user manages the same set with the
+//! `/pin` and `/unpin` commands.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use merlin_core::ThreadId;
+use merlin_tooling::{Tool, ToolError, ToolInput, ToolOutput, ToolResult, canonicalize};
+use serde_json::{Value, json};
+
+use crate::ThreadStore;
+
+/// Resolves a file to pin, returning its path relative to the workspace
+///
+/// # Errors
+/// Returns an error if the file does not exist or lies outside the workspace
+pub fn resolve_pin_path(workspace_root: &Path, path: &str) -> ToolResult<PathBuf> {
+    let canonical_root = canonicalize(workspace_root)
+        .map_err(|err| ToolError::io("Invalid workspace root", &err))?;
+    let full_path = workspace_root.join(path.trim());
+    if !full_path.is_file() {
+        return Err(ToolError::NotFound(format!("File does not exist: {path}")));
+    }
+
+    let canonical_path = canonicalize(&full_path)
+        .map_err(|err| ToolError::io(format!("Invalid path '{path}'"), &err))?;
+    canonical_path
+        .strip_prefix(&canonical_root)
+        .map(Path::to_path_buf)
+        .map_err(|_| ToolError::SandboxViolation(format!("Path '{path}' is outside the workspace")))
+}
+
+/// Tool pinning a file to the context of the thread a task runs in
+pub struct PinFileTool {
+    /// Store persisting the thread's pinned files
+    thread_store: Arc<Mutex<ThreadStore>>,
+    /// Thread the files are pinned to
+    thread_id: ThreadId,
+    /// Root that pinned paths are relative to
+    workspace_root: PathBuf,
+}
+
+impl PinFileTool {
+    /// Creates a tool pinning files of `workspace_root` to `thread_id`
+    #[must_use]
+    pub fn new(
+        thread_store: Arc<Mutex<ThreadStore>>,
+        thread_id: ThreadId,
+        workspace_root: PathBuf,
+    ) -> Self {
+        Self {
+            thread_store,
+            thread_id,
+            workspace_root,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for PinFileTool {
+    fn name(&self) -> &'static str {
+        "pinFile"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Pins a file to the context of every later message in this conversation.
+ * Use it for files central to the work, such as a spec or a schema.
+ * @param path - Path to the file relative to the workspace root
+ * @returns Promise<{ path: string, pinned: boolean }> - pinned is false if the file was already pinned
+ */
+declare function pinFile(path: string): Promise<{ path: string, pinned: boolean }>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": { "path": { "type": "string" } },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let path = input
+            .params
+            .as_str()
+            .or_else(|| input.params.get("path").and_then(Value::as_str))
+            .ok_or_else(|| {
+                ToolError::invalid_argument("path", "pinFile requires a 'path' parameter")
+            })?;
+        let relative = resolve_pin_path(&self.workspace_root, path)?;
+
+        let pinned = self
+            .thread_store
+            .lock()
+            .map_err(|err| ToolError::ExecutionFailed(format!("Thread store lock error: {err}")))?
+            .pin_file(self.thread_id, relative.clone())
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?;
+
+        let message = if pinned {
+            format!("Pinned {}", relative.display())
+        } else {
+            format!("{} is already pinned", relative.display())
+        };
+        Ok(ToolOutput::success_with_data(
+            message,
+            json!({ "path": relative, "pinned": pinned }),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use std::fs::{create_dir, write};
+    use tempfile::TempDir;
+
+    /// Tests that the tool persists the pin and rejects files outside the workspace.
+    ///
+    /// # Errors
+    /// Returns an error if the workspace or thread store cannot be created.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_pin_file_tool_persists_pins() -> Result<()> {
+        let workspace = TempDir::new()?;
+        create_dir(workspace.path().join("docs"))?;
+        write(workspace.path().join("docs/spec.md"), "The spec")?;
+        let storage = TempDir::new()?;
+        let mut store = ThreadStore::new(storage.path().to_path_buf())?;
+        let thread = store.create_thread("Pins".to_owned());
+        store.save_thread(&thread)?;
+        let store = Arc::new(Mutex::new(store));
+
+        let tool = PinFileTool::new(
+            Arc::clone(&store),
+            thread.id,
+            workspace.path().to_path_buf(),
+        );
+        let first = tool
+            .execute(ToolInput {
+                params: json!("docs/spec.md"),
+            })
+            .await?;
+        let again = tool
+            .execute(ToolInput {
+                params: json!({ "path": "./docs/spec.md" }),
+            })
+            .await?;
+        assert_eq!(
+            first.data,
+            Some(json!({ "path": "docs/spec.md", "pinned": true }))
+        );
+        assert_eq!(
+            again.data,
+            Some(json!({ "path": "docs/spec.md", "pinned": false }))
+        );
+        assert!(matches!(
+            tool.execute(ToolInput {
+                params: json!("../outside.md"),
+            })
+            .await,
+            Err(ToolError::NotFound(_))
+        ));
+        assert!(matches!(
+            tool.execute(ToolInput { params: json!({}) }).await,
+            Err(ToolError::InvalidArguments { field: Some(field), .. }) if field == "path"
+        ));
+
+        let mut reloaded = ThreadStore::new(storage.path().to_path_buf())?;
+        reloaded.load_all()?;
+        let pinned = reloaded
+            .get_thread(thread.id)
+            .map(|reloaded_thread| reloaded_thread.pinned_files.clone());
+        assert_eq!(pinned, Some(vec![PathBuf::from("docs/spec.md")]));
+        Ok(())
+    }
+}
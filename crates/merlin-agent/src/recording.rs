@@ -0,0 +1,439 @@
+//! Recording of sessions as replayable integration-test fixtures.
+//!
+//! A [`SessionRecorder`] attached to the orchestrator captures the prompt of
+//! each task, the model responses it received, the tools the agent called and
+//! the files it changed. [`SessionRecorder::to_fixture`] turns the recording
+//! into the fixture format run by the `integration-tests` crate, with
+//! verification stubs filled in from what was observed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use merlin_core::{Context, ModelProvider, Query, Response, Result, RoutingError, TaskResult};
+use merlin_tooling::{ToolCallRecorder, display_path, redact_secrets};
+use regex::escape;
+use serde_json::{Map, Value, from_str, json, to_string_pretty};
+
+use crate::agent::executor::typescript::extract_typescript_code;
+
+/// Largest file captured in the workspace snapshot
+const MAX_SNAPSHOT_FILE_BYTES: u64 = 64 * 1024;
+/// Most files captured in the workspace snapshot
+const MAX_SNAPSHOT_FILES: usize = 500;
+
+/// Text files of a workspace by relative path
+type FileSnapshot = BTreeMap<String, String>;
+
+/// How a recorded task ended
+#[derive(Debug, Clone)]
+enum RecordedOutcome {
+    /// The task completed with this response text
+    Completed(String),
+    /// The task failed with this message
+    Failed(String),
+}
+
+/// One task of the recorded session
+#[derive(Debug, Clone)]
+struct RecordedTask {
+    /// Prompt the user submitted
+    prompt: String,
+    /// Query text of each model request, secrets redacted
+    requests: Vec<String>,
+    /// Text of each model response, in order
+    responses: Vec<String>,
+    /// How the task ended (`None` while running)
+    outcome: Option<RecordedOutcome>,
+}
+
+/// Records a session for replay as an integration-test fixture
+///
+/// Model requests are attributed to the most recently started task that has
+/// not finished, so sessions should run one task at a time.
+#[derive(Clone)]
+pub struct SessionRecorder {
+    /// Workspace the session runs in
+    workspace_root: PathBuf,
+    /// Workspace files when recording started
+    initial_files: Arc<FileSnapshot>,
+    /// Recorded tasks, in submission order
+    tasks: Arc<Mutex<Vec<RecordedTask>>>,
+    /// Tool calls made by the agent
+    tool_calls: ToolCallRecorder,
+}
+
+impl SessionRecorder {
+    /// Start recording a session in `workspace_root`, snapshotting its text files
+    ///
+    /// Hidden and ignored files, files over 64 KiB and files past the first
+    /// 500 are left out of the snapshot.
+    #[must_use]
+    pub fn new(workspace_root: impl Into<PathBuf>) -> Self {
+        let workspace_root = workspace_root.into();
+        Self {
+            initial_files: Arc::new(snapshot_files(&workspace_root)),
+            workspace_root,
+            tasks: Arc::default(),
+            tool_calls: ToolCallRecorder::new(),
+        }
+    }
+
+    /// Recorder the orchestrator's tools record their calls in
+    #[must_use]
+    pub fn tool_calls(&self) -> ToolCallRecorder {
+        self.tool_calls.clone()
+    }
+
+    /// Record that a task was submitted with `prompt`
+    pub fn start_task(&self, prompt: &str) {
+        self.update_tasks(|tasks| {
+            tasks.push(RecordedTask {
+                prompt: prompt.to_owned(),
+                requests: Vec::new(),
+                responses: Vec::new(),
+                outcome: None,
+            });
+        });
+    }
+
+    /// Record how the running task ended
+    pub fn finish_task(&self, result: &Result<TaskResult>) {
+        let outcome = match result {
+            Ok(task_result) => RecordedOutcome::Completed(task_result.response.text.clone()),
+            Err(err) => RecordedOutcome::Failed(err.to_string()),
+        };
+        self.update_tasks(|tasks| {
+            if let Some(task) = tasks.iter_mut().rev().find(|task| task.outcome.is_none()) {
+                task.outcome = Some(outcome);
+            }
+        });
+    }
+
+    /// Record a model request of the running task and its response
+    fn record_exchange(&self, query: &Query, response: &Response) {
+        self.update_tasks(|tasks| {
+            if let Some(task) = tasks.iter_mut().rev().find(|task| task.outcome.is_none()) {
+                task.requests.push(redact_secrets(&query.text));
+                task.responses.push(response.text.clone());
+            }
+        });
+    }
+
+    /// Apply an update to the recorded tasks, logging a poisoned lock
+    fn update_tasks(&self, update: impl FnOnce(&mut Vec<RecordedTask>)) {
+        match self.tasks.lock() {
+            Ok(mut tasks) => update(&mut tasks),
+            Err(err) => tracing::warn!("Failed to record session: {err}"),
+        }
+    }
+
+    /// Build a fixture replaying the recorded session
+    ///
+    /// Verification stubs expect each task's observed response or failure,
+    /// request count, the tool calls in order and the final content of every
+    /// changed file; tighten or loosen them before committing the fixture.
+    ///
+    /// # Errors
+    /// Returns an error if the recording lock is poisoned
+    pub async fn to_fixture(&self, name: &str) -> Result<Value> {
+        let tasks = self
+            .tasks
+            .lock()
+            .map_err(|_| RoutingError::LockPoisoned("Session recording"))?
+            .clone();
+        let mut events = Vec::new();
+        for task in &tasks {
+            events.push(json!({
+                "type": "user_input",
+                "data": { "text": task.prompt, "submit": true }
+            }));
+            events.push(task_response_event(task));
+        }
+
+        let mut final_verify = Map::new();
+        let file_changes = self.file_changes();
+        if !file_changes.is_empty() {
+            final_verify.insert("files".to_owned(), Value::Array(file_changes));
+        }
+        let calls = self.tool_calls.calls().await;
+        if !calls.is_empty() {
+            let ordered: Vec<Value> = calls
+                .iter()
+                .map(|call| {
+                    // Calls whose arguments held secrets only check the tool
+                    let params = call.params.to_string();
+                    if redact_secrets(&params) == params {
+                        json!({ "tool": call.tool, "args": { "$": call.params } })
+                    } else {
+                        json!({ "tool": call.tool })
+                    }
+                })
+                .collect();
+            final_verify.insert(
+                "tool_calls".to_owned(),
+                json!({ "ordered": ordered, "total_calls": calls.len() }),
+            );
+        }
+
+        Ok(json!({
+            "name": name,
+            "description": format!("Recorded session with {} task(s)", tasks.len()),
+            "tags": ["recorded"],
+            "setup": { "files": self.initial_files.as_ref() },
+            "events": events,
+            "final_verify": final_verify,
+        }))
+    }
+
+    /// Write the fixture replaying the recorded session to `path`, named after its file stem
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be built, serialized or written
+    pub async fn write_fixture(&self, path: &Path) -> Result<()> {
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("recorded_session");
+        let fixture = to_string_pretty(&self.to_fixture(name).await?)?;
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, fixture + "\n")?;
+        Ok(())
+    }
+
+    /// File checks for every file created, modified or deleted since recording started
+    fn file_changes(&self) -> Vec<Value> {
+        let current = snapshot_files(&self.workspace_root);
+        let mut checks: Vec<Value> = current
+            .iter()
+            .filter(|(path, content)| self.initial_files.get(*path) != Some(*content))
+            .map(|(path, content)| json!({ "path": path, "exact_content": content }))
+            .collect();
+        checks.extend(
+            self.initial_files
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .filter(|path| !self.workspace_root.join(path).exists())
+                .map(|path| json!({ "path": path, "exists": false })),
+        );
+        checks
+    }
+}
+
+/// The LLM response event replaying one task, with its verification stubs
+fn task_response_event(task: &RecordedTask) -> Value {
+    let mut responses: Vec<Value> = task
+        .responses
+        .iter()
+        .map(|response| {
+            // The mock provider fences the code itself, so replay only the code
+            let code = extract_typescript_code(response).unwrap_or_else(|| response.clone());
+            json!({ "typescript": code.split('\n').collect::<Vec<_>>() })
+        })
+        .collect();
+    let strategy = if responses.len() == 1 {
+        json!({ "type": "repeating", "response": responses.remove(0) })
+    } else {
+        json!({ "type": "sequence", "responses": responses })
+    };
+
+    let execution = match &task.outcome {
+        Some(RecordedOutcome::Completed(text)) => {
+            json!({ "return_value_matches": format!("^{}$", escape(&return_value_text(text))) })
+        }
+        Some(RecordedOutcome::Failed(message)) => json!({ "expected_failure": message }),
+        None => json!({}),
+    };
+
+    json!({
+        "type": "llm_response",
+        "requests": task.requests,
+        "strategy": strategy,
+        "verify": {
+            "execution": execution,
+            "prompt": { "request_count": task.responses.len() }
+        }
+    })
+}
+
+/// Text the verifier matches a task's return value against: string return
+/// values reach the response JSON-encoded, everything else as-is
+fn return_value_text(response: &str) -> String {
+    match from_str::<Value>(response) {
+        Ok(Value::String(text)) => text,
+        _ => response.to_owned(),
+    }
+}
+
+/// Read the text files of a workspace, skipping hidden, ignored and large files
+fn snapshot_files(workspace_root: &Path) -> FileSnapshot {
+    let mut files = FileSnapshot::new();
+    let entries = WalkBuilder::new(workspace_root)
+        .build()
+        .filter_map(StdResult::ok)
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()));
+    for entry in entries {
+        if files.len() >= MAX_SNAPSHOT_FILES {
+            tracing::warn!(
+                "Workspace snapshot stopped at {MAX_SNAPSHOT_FILES} files, the fixture is incomplete"
+            );
+            break;
+        }
+        if entry
+            .metadata()
+            .is_ok_and(|metadata| metadata.len() > MAX_SNAPSHOT_FILE_BYTES)
+        {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(workspace_root) else {
+            continue;
+        };
+        if let Ok(content) = fs::read_to_string(entry.path()) {
+            files.insert(display_path(relative), content);
+        }
+    }
+    files
+}
+
+/// Provider recording every request and response of the wrapped provider
+pub struct RecordingProvider {
+    /// Wrapped provider
+    inner: Arc<dyn ModelProvider>,
+    /// Recorder the exchanges go to
+    recorder: SessionRecorder,
+}
+
+impl RecordingProvider {
+    /// Wrap `inner` so its exchanges are recorded in `recorder`
+    pub fn new(inner: Arc<dyn ModelProvider>, recorder: SessionRecorder) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for RecordingProvider {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+        let response = self.inner.generate(query, context).await?;
+        self.recorder.record_exchange(query, &response);
+        Ok(response)
+    }
+
+    fn estimate_cost(&self, context: &Context) -> f64 {
+        self.inner.estimate_cost(context)
+    }
+
+    fn supports_prompt_caching(&self) -> bool {
+        self.inner.supports_prompt_caching()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::{PhaseTimings, TaskId, TokenUsage, ValidationResult};
+    use tempfile::TempDir;
+
+    /// Response with the given text
+    fn response(text: &str) -> Response {
+        Response {
+            text: text.to_owned(),
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: "test".to_owned(),
+            latency_ms: 0,
+        }
+    }
+
+    /// Tests that the fixture replays responses and expects the observed outcome.
+    ///
+    /// # Errors
+    /// Returns an error if workspace files cannot be written or the fixture cannot be built.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_fixture_from_recorded_session() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(temp_dir.path().join("notes.txt"), "old")?;
+        fs::write(temp_dir.path().join("gone.txt"), "bye")?;
+        let recorder = SessionRecorder::new(temp_dir.path());
+
+        recorder.start_task("Update notes");
+        recorder.record_exchange(
+            &Query::new("Update notes with GROQ_API_KEY=gsk_abcdefghijklmnop1234"),
+            &response("await writeFile('notes.txt', 'new');\nreturn 'a.b';"),
+        );
+        recorder
+            .tool_calls()
+            .record(
+                "writeFile",
+                json!({ "path": "notes.txt", "content": "new" }),
+            )
+            .await;
+        fs::write(temp_dir.path().join("notes.txt"), "new")?;
+        fs::remove_file(temp_dir.path().join("gone.txt"))?;
+        recorder.finish_task(&Ok(TaskResult {
+            task_id: TaskId::default(),
+            response: response("a.b"),
+            tier_used: "test".to_owned(),
+            tokens_used: TokenUsage::default(),
+            validation: ValidationResult::default(),
+            duration_ms: 0,
+            work_unit: None,
+            timings: PhaseTimings::default(),
+        }));
+        recorder.start_task("Break");
+        recorder.finish_task(&Err(RoutingError::Other("boom".to_owned())));
+
+        let fixture = recorder.to_fixture("session").await?;
+        assert_eq!(fixture["setup"]["files"]["notes.txt"], json!("old"));
+        let events = &fixture["events"];
+        assert_eq!(events[0]["data"]["text"], json!("Update notes"));
+        assert_eq!(
+            events[1]["requests"],
+            json!(["Update notes with GROQ_API_KEY=<redacted>"])
+        );
+        assert_eq!(
+            events[1]["strategy"]["response"]["typescript"],
+            json!(["await writeFile('notes.txt', 'new');", "return 'a.b';"])
+        );
+        assert_eq!(
+            events[1]["verify"]["execution"]["return_value_matches"],
+            json!("^a\\.b$")
+        );
+        assert_eq!(events[1]["verify"]["prompt"]["request_count"], json!(1));
+        assert_eq!(
+            events[3]["verify"]["execution"]["expected_failure"],
+            json!("boom")
+        );
+        assert_eq!(
+            fixture["final_verify"]["files"],
+            json!([
+                { "path": "notes.txt", "exact_content": "new" },
+                { "path": "gone.txt", "exists": false }
+            ])
+        );
+        assert_eq!(
+            fixture["final_verify"]["tool_calls"]["ordered"][0]["args"]["$"]["path"],
+            json!("notes.txt")
+        );
+        Ok(())
+    }
+}
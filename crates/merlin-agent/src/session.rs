@@ -0,0 +1,217 @@
+//! Session journal for recovering work after a restart.
+//!
+//! Queued prompts and in-flight tasks are journaled to `.merlin/session.json`
+//! as they start and finish. If Merlin is killed mid-task the entries survive,
+//! and the next session marks them as interrupted so they can be re-run.
+
+use merlin_core::{Result, RoutingError, TaskId, ThreadId};
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string_pretty};
+use std::fs;
+use std::path::PathBuf;
+
+/// File name of the session journal inside the `.merlin` directory
+pub const SESSION_FILE_NAME: &str = "session.json";
+
+/// Lifecycle state of a journaled task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionTaskStatus {
+    /// Prompt is waiting for running work to finish
+    Queued,
+    /// Task is currently executing
+    Running,
+    /// Task was queued or running when the previous session stopped
+    Interrupted,
+}
+
+/// A task recorded in the session journal
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionTask {
+    /// ID of the task (freshly generated for queued prompts)
+    pub task_id: TaskId,
+    /// Prompt the task was started with
+    pub prompt: String,
+    /// Thread the task belongs to, if any
+    pub thread_id: Option<ThreadId>,
+    /// Current lifecycle state
+    pub status: SessionTaskStatus,
+}
+
+/// Journal of queued and running tasks, persisted on every change
+#[derive(Debug, Clone)]
+pub struct SessionJournal {
+    /// Journaled tasks in the order they were recorded
+    tasks: Vec<SessionTask>,
+    /// Path to the journal file
+    path: PathBuf,
+}
+
+impl SessionJournal {
+    /// Loads the journal at `path`, starting empty if the file doesn't exist
+    ///
+    /// # Errors
+    /// Returns an error if the file exists but cannot be read or parsed
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let tasks = if path.exists() {
+            let contents = fs::read_to_string(&path)
+                .map_err(RoutingError::file_access("read session journal", &path))?;
+            from_str(&contents)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self { tasks, path })
+    }
+
+    /// Records a queued prompt, replacing any previously queued one
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be written
+    pub fn record_queued(&mut self, prompt: &str, thread_id: Option<ThreadId>) -> Result<()> {
+        self.tasks
+            .retain(|task| task.status != SessionTaskStatus::Queued);
+        self.tasks.push(SessionTask {
+            task_id: TaskId::default(),
+            prompt: prompt.to_owned(),
+            thread_id,
+            status: SessionTaskStatus::Queued,
+        });
+        self.save()
+    }
+
+    /// Removes the queued prompt, if any
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be written
+    pub fn clear_queued(&mut self) -> Result<()> {
+        self.tasks
+            .retain(|task| task.status != SessionTaskStatus::Queued);
+        self.save()
+    }
+
+    /// Records a task that has started executing
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be written
+    pub fn record_running(
+        &mut self,
+        task_id: TaskId,
+        prompt: &str,
+        thread_id: Option<ThreadId>,
+    ) -> Result<()> {
+        self.tasks.retain(|task| task.task_id != task_id);
+        self.tasks.push(SessionTask {
+            task_id,
+            prompt: prompt.to_owned(),
+            thread_id,
+            status: SessionTaskStatus::Running,
+        });
+        self.save()
+    }
+
+    /// Removes a task that finished, whether it succeeded or failed
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be written
+    pub fn finish(&mut self, task_id: TaskId) -> Result<()> {
+        self.tasks.retain(|task| task.task_id != task_id);
+        self.save()
+    }
+
+    /// Marks everything left by the previous session as interrupted
+    ///
+    /// Must be called at startup, before any new task is journaled. Returns all
+    /// interrupted tasks, including ones from earlier sessions that were never retried.
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be written
+    pub fn recover(&mut self) -> Result<Vec<SessionTask>> {
+        for task in &mut self.tasks {
+            task.status = SessionTaskStatus::Interrupted;
+        }
+        self.save()?;
+        Ok(self.tasks.clone())
+    }
+
+    /// Removes and returns the most recently interrupted task
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be written
+    pub fn take_interrupted(&mut self) -> Result<Option<SessionTask>> {
+        let Some(index) = self
+            .tasks
+            .iter()
+            .rposition(|task| task.status == SessionTaskStatus::Interrupted)
+        else {
+            return Ok(None);
+        };
+
+        let task = self.tasks.remove(index);
+        self.save()?;
+        Ok(Some(task))
+    }
+
+    /// Returns the number of interrupted tasks awaiting a retry
+    #[must_use]
+    pub fn interrupted_count(&self) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| task.status == SessionTaskStatus::Interrupted)
+            .count()
+    }
+
+    /// Writes the journal to disk
+    ///
+    /// # Errors
+    /// Returns an error if the journal cannot be serialized or written
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(RoutingError::file_access(
+                "create session directory",
+                parent,
+            ))?;
+        }
+
+        let json = to_string_pretty(&self.tasks)?;
+        fs::write(&self.path, json).map_err(RoutingError::file_access(
+            "write session journal",
+            &self.path,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Tests that finished tasks leave the journal and queued prompts are replaced.
+    ///
+    /// # Errors
+    /// Returns an error if journal operations fail.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_journal_tracks_running_and_queued() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join(SESSION_FILE_NAME);
+        let mut journal = SessionJournal::load(path.clone())?;
+
+        let task_id = TaskId::default();
+        journal.record_running(task_id, "Fix the parser", None)?;
+        journal.record_queued("First queued", None)?;
+        journal.record_queued("Second queued", None)?;
+
+        let reloaded = SessionJournal::load(path.clone())?;
+        assert_eq!(reloaded.tasks.len(), 2);
+        assert_eq!(reloaded.tasks[1].prompt, "Second queued");
+
+        journal.finish(task_id)?;
+        journal.clear_queued()?;
+        assert!(SessionJournal::load(path)?.tasks.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+//! Graceful shutdown of in-flight tasks.
+//!
+//! On `SIGINT`/`SIGTERM`, or when the UI asks to quit, the coordinator cancels the
+//! `CancellationToken` handed to every executing task and kills the shell commands
+//! their tools registered with it, leaving those of other coordinators running.
+//! Tasks then have `SHUTDOWN_GRACE_PERIOD` to wind down before the caller exits
+//! and whatever is still running is dropped.
+
+use std::future::pending;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use merlin_core::{SharedClock, SystemClock};
+use merlin_tooling::RunningCommands;
+use tokio::signal::ctrl_c;
+use tokio::spawn;
+use tokio_util::sync::CancellationToken;
+
+/// How long in-flight tasks may keep running after shutdown begins
+pub const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Coordinates cancellation of in-flight tasks when the process is asked to stop
+#[derive(Debug, Clone)]
+pub struct ShutdownCoordinator {
+    /// Parent of every task token; cancelled when shutdown begins
+    token: CancellationToken,
+    /// When shutdown began (unset while running normally)
+    started_at: Arc<OnceLock<Instant>>,
+    /// Time tasks get to finish before they are forcefully cancelled
+    grace_period: Duration,
+    /// Clock the grace period is measured on
+    clock: SharedClock,
+    /// Shell commands the bash tools of this coordinator's tasks are running
+    running_commands: RunningCommands,
+}
+
+impl Default for ShutdownCoordinator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownCoordinator {
+    /// Creates a coordinator with the default grace period
+    pub fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+            started_at: Arc::new(OnceLock::new()),
+            grace_period: SHUTDOWN_GRACE_PERIOD,
+            clock: SystemClock::shared(),
+            running_commands: RunningCommands::default(),
+        }
+    }
+
+    /// Measures the grace period on `clock` instead of the system clock
+    #[must_use]
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Overrides the grace period given to in-flight tasks
+    #[must_use]
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Registry the bash tools of this coordinator's tasks register their commands in
+    pub const fn running_commands(&self) -> &RunningCommands {
+        &self.running_commands
+    }
+
+    /// Returns a token for a new task, cancelled when shutdown begins
+    pub fn task_token(&self) -> CancellationToken {
+        self.token.child_token()
+    }
+
+    /// Begins shutdown by cancelling every task token and killing running shell commands
+    ///
+    /// Calling this again only kills commands started since.
+    pub fn begin_shutdown(&self) {
+        if self.started_at.set(self.clock.now()).is_ok() {
+            tracing::info!(
+                "Shutdown requested, giving in-flight tasks {}s to finish",
+                self.grace_period.as_secs()
+            );
+        }
+        self.token.cancel();
+        self.running_commands.kill_all();
+    }
+
+    /// Returns whether shutdown has begun
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Returns whether the grace period has run out since shutdown began
+    pub fn grace_period_elapsed(&self) -> bool {
+        self.started_at
+            .get()
+            .is_some_and(|started_at| self.clock.elapsed_since(*started_at) >= self.grace_period)
+    }
+
+    /// Waits until shutdown begins
+    pub async fn wait_for_shutdown(&self) {
+        self.token.cancelled().await;
+    }
+
+    /// Begins shutdown when the process receives `SIGINT` or `SIGTERM`
+    pub fn listen_for_signals(&self) {
+        let coordinator = self.clone();
+        spawn(async move {
+            wait_for_termination_signal().await;
+            coordinator.begin_shutdown();
+        });
+    }
+}
+
+/// Waits for `SIGINT` or `SIGTERM`
+#[cfg(unix)]
+async fn wait_for_termination_signal() {
+    use tokio::select;
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(err) => {
+            tracing::warn!("Failed to listen for SIGTERM: {err}");
+            wait_for_interrupt().await;
+            return;
+        }
+    };
+
+    select! {
+        () = wait_for_interrupt() => {}
+        _ = terminate.recv() => tracing::info!("Received SIGTERM"),
+    }
+}
+
+/// Waits for `Ctrl+C`
+#[cfg(not(unix))]
+async fn wait_for_termination_signal() {
+    wait_for_interrupt().await;
+}
+
+/// Waits for `SIGINT` (`Ctrl+C`)
+async fn wait_for_interrupt() {
+    match ctrl_c().await {
+        Ok(()) => tracing::info!("Received SIGINT"),
+        Err(err) => {
+            tracing::warn!("Failed to listen for SIGINT: {err}");
+            // Without a signal handler, never trigger shutdown from here
+            pending::<()>().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::VirtualClock;
+    #[cfg(unix)]
+    use std::io::Result as IoResult;
+
+    /// Tests that beginning shutdown cancels existing and new task tokens.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_begin_shutdown_cancels_tasks() {
+        let coordinator = ShutdownCoordinator::new().with_grace_period(Duration::ZERO);
+        let running_task = coordinator.task_token();
+        assert!(!running_task.is_cancelled());
+        assert!(!coordinator.grace_period_elapsed());
+
+        coordinator.begin_shutdown();
+
+        assert!(coordinator.is_shutting_down());
+        assert!(running_task.is_cancelled());
+        assert!(coordinator.task_token().is_cancelled());
+        assert!(coordinator.grace_period_elapsed());
+    }
+
+    /// Tests that the grace period is measured on the coordinator's clock.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_grace_period_measured_on_clock() {
+        let clock = VirtualClock::new();
+        let coordinator = ShutdownCoordinator::new().with_clock(clock.shared());
+        coordinator.begin_shutdown();
+
+        clock.advance(SHUTDOWN_GRACE_PERIOD.saturating_sub(Duration::from_millis(1)));
+        assert!(!coordinator.grace_period_elapsed());
+        clock.advance(Duration::from_millis(1));
+        assert!(coordinator.grace_period_elapsed());
+    }
+
+    /// Tests that shutting down one coordinator leaves other coordinators' commands running.
+    ///
+    /// # Errors
+    /// Returns an error if `sleep` cannot be spawned or waited on.
+    ///
+    /// # Panics
+    /// Panics if the wrong command is killed.
+    #[cfg(unix)]
+    #[test]
+    fn test_begin_shutdown_kills_only_own_commands() -> IoResult<()> {
+        use std::os::unix::process::CommandExt as _;
+        use std::process::{Child, Command};
+        use std::thread::sleep;
+
+        let spawn_sleep =
+            || -> IoResult<Child> { Command::new("sleep").arg("30").process_group(0).spawn() };
+        let (stopping, running) = (ShutdownCoordinator::new(), ShutdownCoordinator::new());
+        let mut stopped_child = spawn_sleep()?;
+        let mut running_child = spawn_sleep()?;
+        let stopped_command = stopping.running_commands().register(stopped_child.id());
+        let running_command = running.running_commands().register(running_child.id());
+
+        stopping.begin_shutdown();
+
+        let mut stopped_status = None;
+        for _ in 0..100 {
+            stopped_status = stopped_child.try_wait()?;
+            if stopped_status.is_some() {
+                break;
+            }
+            sleep(Duration::from_millis(50));
+        }
+        assert!(stopped_status.is_some(), "sleep outlived its coordinator");
+        assert!(running_child.try_wait()?.is_none());
+        assert_eq!(running.running_commands().len(), 1);
+
+        drop(stopped_command);
+        drop(running_command);
+        running_child.wait()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+//! Branching threads at a message and merging threads.
+
+use super::ThreadStore;
+use merlin_core::{Message, MessageId, Result, RoutingError, Thread, ThreadColor, ThreadId};
+
+/// A thread and its ancestors, each with the message it is cut off after
+type BranchChain<'store> = Vec<(&'store Thread, Option<MessageId>)>;
+/// Creates a new thread branched from another thread
+///
+/// # Errors
+/// Returns an error if the parent thread doesn't exist
+pub(super) fn create_branch(
+    store: &mut ThreadStore,
+    name: String,
+    parent_thread_id: ThreadId,
+    parent_message_id: MessageId,
+) -> Result<Thread> {
+    // Verify parent thread exists
+    let Some(parent) = store.threads.get(&parent_thread_id) else {
+        return Err(RoutingError::ThreadNotFound(parent_thread_id));
+    };
+    let pinned_files = parent.pinned_files.clone();
+    let working_dir = parent.working_dir.clone();
+
+    let color = ThreadColor::from_index(store.next_color_index);
+    store.next_color_index += 1;
+
+    let mut thread = Thread::branched_from(name, color, parent_thread_id, parent_message_id);
+    // Branches keep the files pinned to their parent and work in the same directory
+    thread.pinned_files = pinned_files;
+    thread.working_dir = working_dir;
+    Ok(thread)
+}
+
+/// Branches a thread at one of the messages of its conversation and saves the branch
+///
+/// # Errors
+/// Returns an error if the thread doesn't exist, the message is not part of
+/// its conversation, or the branch cannot be saved
+pub(super) fn branch_thread(
+    store: &mut ThreadStore,
+    thread_id: ThreadId,
+    message_id: MessageId,
+) -> Result<ThreadId> {
+    let chain = branch_chain(store, thread_id)?;
+    let owner = chain
+        .iter()
+        .find(|(thread, until)| {
+            shared_messages(thread, *until)
+                .iter()
+                .any(|message| message.id == message_id)
+        })
+        .map(|(thread, _)| thread.id)
+        .ok_or_else(|| RoutingError::MessageNotFound {
+            thread: thread_id,
+            message: message_id,
+        })?;
+    let name = chain
+        .last()
+        .map(|(thread, _)| format!("Branch of {}", thread.name))
+        .unwrap_or_default();
+
+    let branch = create_branch(store, name, owner, message_id)?;
+    let branch_id = branch.id;
+    store.save_thread(&branch)?;
+    Ok(branch_id)
+}
+
+/// Returns the messages of a thread's conversation, oldest first
+///
+/// # Errors
+/// Returns an error if the thread doesn't exist
+pub(super) fn conversation(store: &ThreadStore, thread_id: ThreadId) -> Result<Vec<&Message>> {
+    Ok(branch_chain(store, thread_id)?
+        .into_iter()
+        .flat_map(|(thread, until)| shared_messages(thread, until))
+        .collect())
+}
+
+/// Returns a thread and its ancestors, root first, with the message each is cut off after
+///
+/// The thread itself is last and not cut off.
+///
+/// # Errors
+/// Returns an error if the thread doesn't exist
+fn branch_chain(store: &ThreadStore, thread_id: ThreadId) -> Result<BranchChain<'_>> {
+    let mut current = store
+        .threads
+        .get(&thread_id)
+        .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?;
+    let mut chain = vec![(current, None)];
+    while let Some(branch_point) = &current.parent_thread {
+        let Some(parent) = store.threads.get(&branch_point.thread_id) else {
+            tracing::warn!(
+                "Parent thread {} of thread {} not found",
+                branch_point.thread_id,
+                current.id
+            );
+            break;
+        };
+        // A corrupted store could link threads in a cycle
+        if chain.iter().any(|(thread, _)| thread.id == parent.id) {
+            break;
+        }
+        chain.push((parent, Some(branch_point.message_id)));
+        current = parent;
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Merges two threads into a new thread
+///
+/// # Errors
+/// Returns an error if either thread doesn't exist, they are the same thread,
+/// `insert_after` is not a message in `target`, or saving fails
+pub(super) fn merge_threads(
+    store: &mut ThreadStore,
+    source: ThreadId,
+    target: ThreadId,
+    insert_after: MessageId,
+) -> Result<ThreadId> {
+    if source == target {
+        return Err(RoutingError::InvalidThreadOperation(format!(
+            "Cannot merge thread {source} into itself"
+        )));
+    }
+
+    let source_thread = store
+        .threads
+        .get(&source)
+        .ok_or_else(|| RoutingError::ThreadNotFound(source))?;
+    let target_thread = store
+        .threads
+        .get(&target)
+        .ok_or_else(|| RoutingError::ThreadNotFound(target))?;
+
+    let insert_index = target_thread
+        .messages
+        .iter()
+        .position(|message| message.id == insert_after)
+        .ok_or_else(|| RoutingError::MessageNotFound {
+            thread: target,
+            message: insert_after,
+        })?
+        + 1;
+
+    let mut merged = Thread::new(target_thread.name.clone(), target_thread.color);
+    merged.title_source = target_thread.title_source;
+    merged
+        .parent_thread
+        .clone_from(&target_thread.parent_thread);
+    merged.created_at = target_thread.created_at.min(source_thread.created_at);
+    merged.messages = target_thread.messages[..insert_index]
+        .iter()
+        .chain(&source_thread.messages)
+        .chain(&target_thread.messages[insert_index..])
+        .cloned()
+        .collect();
+    for tag in target_thread.tags.iter().chain(&source_thread.tags) {
+        if !merged.has_tag(tag) {
+            merged.tags.push(tag.clone());
+        }
+    }
+
+    let merged_id = merged.id;
+    store.save_thread(&merged)?;
+    store.archive_thread(source)?;
+    store.archive_thread(target)?;
+
+    Ok(merged_id)
+}
+
+/// Messages of `thread` up to and including `until`, or all of them without a cut-off
+///
+/// A branch point that is no longer in the thread keeps every message.
+fn shared_messages(thread: &Thread, until: Option<MessageId>) -> &[Message] {
+    let end = until
+        .and_then(|until| {
+            thread
+                .messages
+                .iter()
+                .position(|message| message.id == until)
+        })
+        .map_or(thread.messages.len(), |index| index + 1);
+    &thread.messages[..end]
+}
@@ -0,0 +1,485 @@
+//! Thread persistence and management.
+//!
+//! Handles saving/loading threads to/from disk and managing thread operations.
+//! Branching and merging, tags, titles and search live in submodules.
+
+mod branches;
+mod search;
+mod storage;
+mod tags;
+mod titles;
+
+use merlin_core::{
+    Message, MessageId, Result, RoutingError, ShownFiles, Thread, ThreadColor, ThreadId,
+};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use search::ThreadSearchResult;
+pub use storage::THREAD_SCHEMA;
+pub use tags::{ERROR_TAG, HIGH_COST_TAG};
+pub use titles::GENERATED_TITLE_MAX_WORDS;
+
+use tags::apply_automatic_tags;
+
+/// Storage for conversation threads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadStore {
+    /// All threads indexed by ID
+    threads: HashMap<ThreadId, Thread>,
+    /// Path to the storage directory
+    #[serde(skip)]
+    storage_path: PathBuf,
+    /// Next color index for new threads
+    next_color_index: usize,
+}
+
+impl ThreadStore {
+    /// Creates a new thread store with the given storage path
+    ///
+    /// # Errors
+    /// Returns an error if the storage directory cannot be created
+    pub fn new(storage_path: PathBuf) -> Result<Self> {
+        // Only create directory if it doesn't exist to avoid slow Windows FS operations
+        if !storage_path.exists() {
+            fs::create_dir_all(&storage_path).map_err(RoutingError::file_access(
+                "create thread storage directory",
+                &storage_path,
+            ))?;
+        }
+
+        Ok(Self {
+            threads: HashMap::new(),
+            storage_path,
+            next_color_index: 0,
+        })
+    }
+
+    /// Loads all threads from disk
+    ///
+    /// Files in older formats are upgraded through `THREAD_SCHEMA`. Files that
+    /// cannot be decoded are moved to `.merlin/corrupt/` and their new paths are
+    /// returned; files written by a newer version are skipped and left in place.
+    /// Files are skipped with a warning if they cannot be moved.
+    ///
+    /// # Errors
+    /// Returns an error if the storage directory or a thread file cannot be read
+    pub fn load_all(&mut self) -> Result<Vec<PathBuf>> {
+        let (threads, quarantined) = storage::load_threads(&self.storage_path)?;
+        self.threads
+            .extend(threads.into_iter().map(|thread| (thread.id, thread)));
+        Ok(quarantined)
+    }
+
+    /// Saves a thread to disk
+    ///
+    /// Automatic tags (`ERROR_TAG`, `HIGH_COST_TAG`) are refreshed before saving.
+    ///
+    /// # Errors
+    /// Returns an error if the thread cannot be serialized or written to disk
+    pub fn save_thread(&mut self, thread: &Thread) -> Result<()> {
+        let mut thread = thread.clone();
+        apply_automatic_tags(&mut thread);
+
+        let path = self.thread_path(thread.id);
+        let json = storage::encode_thread(&thread)?;
+
+        self.threads.insert(thread.id, thread);
+
+        fs::write(&path, json).map_err(RoutingError::file_access("write thread file", &path))?;
+
+        Ok(())
+    }
+
+    /// Creates a new thread with automatic color assignment
+    pub fn create_thread(&mut self, name: String) -> Thread {
+        let color = ThreadColor::from_index(self.next_color_index);
+        self.next_color_index += 1;
+        Thread::new(name, color)
+    }
+
+    /// Gets a thread by ID
+    #[must_use]
+    pub fn get_thread(&self, thread_id: ThreadId) -> Option<&Thread> {
+        self.threads.get(&thread_id)
+    }
+
+    /// Gets a mutable reference to a thread by ID
+    pub fn get_thread_mut(&mut self, thread_id: ThreadId) -> Option<&mut Thread> {
+        self.threads.get_mut(&thread_id)
+    }
+
+    /// Deletes a thread
+    ///
+    /// # Errors
+    /// Returns an error if the thread file cannot be deleted
+    pub fn delete_thread(&mut self, thread_id: ThreadId) -> Result<()> {
+        self.threads.remove(&thread_id);
+
+        let path = self.thread_path(thread_id);
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(RoutingError::file_access("delete thread file", &path))?;
+        }
+
+        Ok(())
+    }
+
+    /// Archives a thread (hides from main view but keeps data)
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn archive_thread(&mut self, thread_id: ThreadId) -> Result<()> {
+        // Get the thread and modify it
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        thread.archived = true;
+        self.save_thread(&thread)?;
+
+        Ok(())
+    }
+
+    /// Unarchives a thread
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn unarchive_thread(&mut self, thread_id: ThreadId) -> Result<()> {
+        // Get the thread and modify it
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        thread.archived = false;
+        self.save_thread(&thread)?;
+
+        Ok(())
+    }
+
+    /// Marks work left running by a previous session as interrupted
+    ///
+    /// Work units still `InProgress` or `Retrying` after a restart can never finish,
+    /// so they are moved to the terminal `Interrupted` state. Returns the number of
+    /// work units transitioned.
+    ///
+    /// # Errors
+    /// Returns an error if an updated thread cannot be saved
+    pub fn recover_interrupted_work(&mut self) -> Result<usize> {
+        let mut stale_threads = Vec::new();
+        for thread in self.threads.values() {
+            let mut thread = thread.clone();
+            let mut interrupted = 0;
+            for work in thread
+                .messages
+                .iter_mut()
+                .filter_map(|message| message.work.as_mut())
+                .filter(|work| !work.is_terminal())
+            {
+                work.interrupt();
+                interrupted += 1;
+            }
+            if interrupted > 0 {
+                stale_threads.push((thread, interrupted));
+            }
+        }
+
+        let mut recovered = 0;
+        for (thread, interrupted) in stale_threads {
+            self.save_thread(&thread)?;
+            recovered += interrupted;
+        }
+        Ok(recovered)
+    }
+
+    /// Pins a file to a thread's context and persists it
+    ///
+    /// Returns `false` if the file was already pinned.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn pin_file(&mut self, thread_id: ThreadId, path: PathBuf) -> Result<bool> {
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        let pinned = thread.pin_file(path);
+        if pinned {
+            self.save_thread(&thread)?;
+        }
+        Ok(pinned)
+    }
+
+    /// Unpins a file from a thread's context and persists it
+    ///
+    /// Returns `false` if the file was not pinned.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn unpin_file(&mut self, thread_id: ThreadId, path: &Path) -> Result<bool> {
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        let unpinned = thread.unpin_file(path);
+        if unpinned {
+            self.save_thread(&thread)?;
+        }
+        Ok(unpinned)
+    }
+
+    /// Unpins every file from a thread's context and persists it
+    ///
+    /// Returns the number of files that were pinned.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn clear_pinned_files(&mut self, thread_id: ThreadId) -> Result<usize> {
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        let count = thread.pinned_files.len();
+        if count > 0 {
+            thread.pinned_files.clear();
+            self.save_thread(&thread)?;
+        }
+        Ok(count)
+    }
+
+    /// Records a question a task asked and the user's answer and persists it
+    ///
+    /// The exchange is inserted before the thread's last message, the one the
+    /// asking task runs for, so that message keeps tracking the task's work.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn record_clarification(
+        &mut self,
+        thread_id: ThreadId,
+        question: &str,
+        answer: &str,
+    ) -> Result<()> {
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        let position = thread.messages.len().saturating_sub(1);
+        thread.messages.insert(
+            position,
+            Message::new(format!("Q: {question}\nA: {answer}")),
+        );
+        thread.touch();
+        self.save_thread(&thread)
+    }
+
+    /// Replaces the files a thread has shown the model and persists it
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn set_shown_files(&mut self, thread_id: ThreadId, shown_files: ShownFiles) -> Result<()> {
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        if thread.shown_files != shown_files {
+            thread.shown_files = shown_files;
+            self.save_thread(&thread)?;
+        }
+        Ok(())
+    }
+
+    /// Sets the directory a thread's tasks run in and persists it
+    ///
+    /// `None` returns the thread to the project root given at startup.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn set_working_dir(
+        &mut self,
+        thread_id: ThreadId,
+        working_dir: Option<PathBuf>,
+    ) -> Result<()> {
+        let mut thread = self
+            .threads
+            .get(&thread_id)
+            .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+            .clone();
+
+        if thread.working_dir != working_dir {
+            thread.working_dir = working_dir;
+            self.save_thread(&thread)?;
+        }
+        Ok(())
+    }
+
+    /// Returns all non-archived threads sorted by most recently updated first
+    #[must_use]
+    pub fn active_threads(&self) -> Vec<&Thread> {
+        let mut threads: Vec<&Thread> = self
+            .threads
+            .values()
+            .filter(|thread| !thread.archived)
+            .collect();
+
+        // Sort by updated_at in descending order (most recent first)
+        threads.sort_by_key(|thread| Reverse(thread.updated_at));
+
+        threads
+    }
+
+    /// Returns all archived threads
+    #[must_use]
+    pub fn archived_threads(&self) -> Vec<&Thread> {
+        self.threads
+            .values()
+            .filter(|thread| thread.archived)
+            .collect()
+    }
+
+    /// Returns the total number of threads (including archived)
+    #[must_use]
+    pub fn total_count(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// Creates a new thread branched from another thread
+    ///
+    /// # Errors
+    /// Returns an error if the parent thread doesn't exist
+    pub fn create_branch(
+        &mut self,
+        name: String,
+        parent_thread_id: ThreadId,
+        parent_message_id: MessageId,
+    ) -> Result<Thread> {
+        branches::create_branch(self, name, parent_thread_id, parent_message_id)
+    }
+
+    /// Branches a thread at one of the messages of its conversation and saves the branch
+    ///
+    /// The branch shares the conversation up to and including `message_id` and
+    /// continues on its own from there. Branching at a message the thread
+    /// inherited from its own parent records that parent as the branch point.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist, the message is not part of
+    /// its conversation, or the branch cannot be saved
+    pub fn branch_thread(
+        &mut self,
+        thread_id: ThreadId,
+        message_id: MessageId,
+    ) -> Result<ThreadId> {
+        branches::branch_thread(self, thread_id, message_id)
+    }
+
+    /// Returns the messages of a thread's conversation, oldest first
+    ///
+    /// A branch's conversation is its parent's conversation up to and including
+    /// the branch point, followed by the branch's own messages. Ancestors that no
+    /// longer exist contribute nothing.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist
+    pub fn conversation(&self, thread_id: ThreadId) -> Result<Vec<&Message>> {
+        branches::conversation(self, thread_id)
+    }
+
+    /// Merges two threads into a new thread
+    ///
+    /// The merged thread starts as a copy of `target` with all of `source`'s messages
+    /// inserted after `insert_after`. Messages keep their original IDs and timestamps,
+    /// and tags from both threads are combined. Both original threads are archived.
+    ///
+    /// # Errors
+    /// Returns an error if either thread doesn't exist, they are the same thread,
+    /// `insert_after` is not a message in `target`, or saving fails
+    pub fn merge_threads(
+        &mut self,
+        source: ThreadId,
+        target: ThreadId,
+        insert_after: MessageId,
+    ) -> Result<ThreadId> {
+        branches::merge_threads(self, source, target, insert_after)
+    }
+
+    /// Adds a tag to a thread and persists it
+    ///
+    /// Tags are trimmed and lowercased; adding an existing tag is a no-op.
+    ///
+    /// # Errors
+    /// Returns an error if the tag is empty, the thread doesn't exist, or it cannot be saved
+    pub fn add_tag(&mut self, thread_id: ThreadId, tag: &str) -> Result<()> {
+        tags::add_tag(self, thread_id, tag)
+    }
+
+    /// Removes a tag from a thread and persists it
+    ///
+    /// # Errors
+    /// Returns an error if the tag is empty, the thread doesn't exist, or it cannot be saved
+    pub fn remove_tag(&mut self, thread_id: ThreadId, tag: &str) -> Result<()> {
+        tags::remove_tag(self, thread_id, tag)
+    }
+
+    /// Returns every tag used by any thread, sorted and deduplicated
+    #[must_use]
+    pub fn all_tags(&self) -> Vec<String> {
+        tags::all_tags(self)
+    }
+
+    /// Renames a thread on behalf of the user
+    ///
+    /// Manually renamed threads are never retitled automatically.
+    ///
+    /// # Errors
+    /// Returns an error if the name is empty, the thread doesn't exist, or it cannot be saved
+    pub fn rename_thread(&mut self, thread_id: ThreadId, name: &str) -> Result<()> {
+        titles::rename_thread(self, thread_id, name)
+    }
+
+    /// Applies a model-generated title to a thread that still has its default name
+    ///
+    /// The raw model output is reduced to its first line, stripped of quotes and
+    /// trailing punctuation, and capped at `GENERATED_TITLE_MAX_WORDS` words.
+    /// Returns whether the title was applied.
+    ///
+    /// # Errors
+    /// Returns an error if the thread doesn't exist or cannot be saved
+    pub fn apply_generated_title(&mut self, thread_id: ThreadId, raw_title: &str) -> Result<bool> {
+        titles::apply_generated_title(self, thread_id, raw_title)
+    }
+
+    /// Searches thread names and message content for a query
+    ///
+    /// Matching is a case-insensitive substring search across all stored threads,
+    /// including archived ones. Results are ordered by relevance, then by most
+    /// recently updated. An empty query yields no results.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<ThreadSearchResult> {
+        search::search(self, query)
+    }
+
+    /// Gets the path for a thread file
+    fn thread_path(&self, thread_id: ThreadId) -> PathBuf {
+        self.storage_path.join(format!("{thread_id}.json"))
+    }
+}
+#[cfg(test)]
+mod tests;
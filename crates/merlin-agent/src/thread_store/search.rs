@@ -0,0 +1,102 @@
+//! Relevance-ranked search over thread names and messages.
+
+use super::ThreadStore;
+use merlin_core::{Thread, ThreadId};
+
+/// Score awarded for each occurrence of the query in a thread name
+const NAME_MATCH_SCORE: f32 = 2.0;
+/// Score awarded for each occurrence of the query in a message
+const MESSAGE_MATCH_SCORE: f32 = 1.0;
+/// Number of characters of context kept on each side of a match in excerpts
+const EXCERPT_CONTEXT_CHARS: usize = 40;
+
+/// A thread matching a search query
+#[derive(Debug, Clone)]
+pub struct ThreadSearchResult {
+    /// ID of the matching thread
+    pub thread_id: ThreadId,
+    /// Display name of the matching thread
+    pub thread_name: String,
+    /// Excerpt around the first message match (None if only the name matched)
+    pub message_excerpt: Option<String>,
+    /// Relevance score (higher is better)
+    pub relevance_score: f32,
+}
+
+/// Searches thread names and message content for a query
+pub(super) fn search(store: &ThreadStore, query: &str) -> Vec<ThreadSearchResult> {
+    let needle = query.trim().to_ascii_lowercase();
+    if needle.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(&Thread, ThreadSearchResult)> = store
+        .threads
+        .values()
+        .filter_map(|thread| score_thread(thread, &needle).map(|result| (thread, result)))
+        .collect();
+
+    matches.sort_by(|(first_thread, first), (second_thread, second)| {
+        second
+            .relevance_score
+            .total_cmp(&first.relevance_score)
+            .then_with(|| second_thread.updated_at.cmp(&first_thread.updated_at))
+    });
+
+    matches.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Scores a thread against a lowercase needle, returning None if nothing matches
+fn score_thread(thread: &Thread, needle: &str) -> Option<ThreadSearchResult> {
+    let name_hits = thread.name.to_ascii_lowercase().matches(needle).count();
+    let mut relevance_score = name_hits as f32 * NAME_MATCH_SCORE;
+    let mut message_excerpt = None;
+
+    for message in &thread.messages {
+        let haystack = message.content.to_ascii_lowercase();
+        let hits = haystack.matches(needle).count();
+        if hits == 0 {
+            continue;
+        }
+
+        relevance_score = (hits as f32).mul_add(MESSAGE_MATCH_SCORE, relevance_score);
+        if message_excerpt.is_none()
+            && let Some(position) = haystack.find(needle)
+        {
+            message_excerpt = Some(excerpt_around(&message.content, position, needle.len()));
+        }
+    }
+
+    (relevance_score > 0.0).then(|| ThreadSearchResult {
+        thread_id: thread.id,
+        thread_name: thread.name.clone(),
+        message_excerpt,
+        relevance_score,
+    })
+}
+
+/// Builds a single-line excerpt of `content` around a match at byte `position`
+///
+/// ASCII lowercasing preserves byte offsets, so positions found in the lowercased
+/// haystack are valid in the original content once snapped to char boundaries.
+pub(super) fn excerpt_around(content: &str, position: usize, match_len: usize) -> String {
+    let mut start = position.saturating_sub(EXCERPT_CONTEXT_CHARS);
+    while !content.is_char_boundary(start) {
+        start -= 1;
+    }
+
+    let mut end = (position + match_len + EXCERPT_CONTEXT_CHARS).min(content.len());
+    while !content.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut excerpt = String::new();
+    if start > 0 {
+        excerpt.push_str("...");
+    }
+    excerpt.push_str(&content[start..end].replace('\n', " "));
+    if end < content.len() {
+        excerpt.push_str("...");
+    }
+    excerpt
+}
@@ -0,0 +1,92 @@
+//! On-disk thread format: one versioned JSON file per thread.
+
+use merlin_core::schema::{MigrationRegistry, corrupt_dir_for, quarantine};
+use merlin_core::{Result, RoutingError, Thread};
+use serde_json::{Map, Value, to_string_pretty, to_value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Migrations of the on-disk thread format (current version: 2)
+pub const THREAD_SCHEMA: MigrationRegistry = MigrationRegistry::new(&[add_thread_metadata]);
+
+/// Threads decoded from disk and the paths of quarantined thread files
+type LoadedThreads = (Vec<Thread>, Vec<PathBuf>);
+
+/// Reads every thread file in `storage_path`
+///
+/// Returns the decoded threads and the new paths of files moved to
+/// `.merlin/corrupt/` because they could not be decoded.
+///
+/// # Errors
+/// Returns an error if the storage directory or a thread file cannot be read
+pub(super) fn load_threads(storage_path: &Path) -> Result<LoadedThreads> {
+    let entries = fs::read_dir(storage_path).map_err(RoutingError::file_access(
+        "read thread storage directory",
+        storage_path,
+    ))?;
+
+    let mut threads = Vec::new();
+    let mut quarantined = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(RoutingError::file_access(
+            "read thread storage directory",
+            storage_path,
+        ))?;
+
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let contents = fs::read_to_string(&path)
+            .map_err(RoutingError::file_access("read thread file", &path))?;
+
+        match THREAD_SCHEMA.decode::<Thread>(&contents) {
+            Ok(thread) => {
+                threads.push(thread);
+            }
+            Err(err) if err.is_corrupt() => {
+                match quarantine(&path, &corrupt_dir_for(storage_path)) {
+                    Ok(moved) => {
+                        tracing::warn!(
+                            "Moved unreadable thread file {} to {}: {err}",
+                            path.display(),
+                            moved.display()
+                        );
+                        quarantined.push(moved);
+                    }
+                    Err(move_err) => tracing::warn!(
+                        "Failed to quarantine unreadable thread file {} ({err}): {move_err}",
+                        path.display()
+                    ),
+                }
+            }
+            Err(err) => {
+                tracing::warn!("Skipping thread file {}: {err}", path.display());
+            }
+        }
+    }
+
+    Ok((threads, quarantined))
+}
+
+/// Serializes a thread stamped with the current format version
+///
+/// # Errors
+/// Returns an error if the thread cannot be serialized
+pub(super) fn encode_thread(thread: &Thread) -> Result<String> {
+    let mut document = to_value(thread)?;
+    THREAD_SCHEMA.stamp(&mut document)?;
+    Ok(to_string_pretty(&document)?)
+}
+
+/// Version 1 -> 2: fills in the `title_source` and `tags` fields added after
+/// the first release, so unversioned threads carry every current field
+fn add_thread_metadata(document: &mut Map<String, Value>) {
+    document
+        .entry("title_source")
+        .or_insert_with(|| Value::from("Default"));
+    document
+        .entry("tags")
+        .or_insert_with(|| Value::Array(Vec::new()));
+}
@@ -0,0 +1,105 @@
+//! Manual and automatic thread tags.
+
+use super::ThreadStore;
+use merlin_core::{Result, RoutingError, Thread, ThreadId, WorkStatus};
+use merlin_routing::RequestMetrics;
+
+/// Automatic tag for threads whose most recent work failed
+pub const ERROR_TAG: &str = "error";
+/// Automatic tag for threads whose estimated cost exceeds `HIGH_COST_THRESHOLD_USD`
+pub const HIGH_COST_TAG: &str = "cost:high";
+/// Estimated thread cost (USD) above which `HIGH_COST_TAG` is applied
+const HIGH_COST_THRESHOLD_USD: f64 = 1.0;
+
+/// Adds a tag to a thread and persists it
+///
+/// # Errors
+/// Returns an error if the tag is empty, the thread doesn't exist, or it cannot be saved
+pub(super) fn add_tag(store: &mut ThreadStore, thread_id: ThreadId, tag: &str) -> Result<()> {
+    let tag = normalize_tag(tag)?;
+    let mut thread = store
+        .threads
+        .get(&thread_id)
+        .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+        .clone();
+
+    if !thread.has_tag(&tag) {
+        thread.tags.push(tag);
+        store.save_thread(&thread)?;
+    }
+
+    Ok(())
+}
+
+/// Removes a tag from a thread and persists it
+///
+/// # Errors
+/// Returns an error if the tag is empty, the thread doesn't exist, or it cannot be saved
+pub(super) fn remove_tag(store: &mut ThreadStore, thread_id: ThreadId, tag: &str) -> Result<()> {
+    let tag = normalize_tag(tag)?;
+    let mut thread = store
+        .threads
+        .get(&thread_id)
+        .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+        .clone();
+
+    if thread.has_tag(&tag) {
+        thread.tags.retain(|existing| *existing != tag);
+        store.save_thread(&thread)?;
+    }
+
+    Ok(())
+}
+
+/// Returns every tag used by any thread, sorted and deduplicated
+pub(super) fn all_tags(store: &ThreadStore) -> Vec<String> {
+    let mut tags: Vec<String> = store
+        .threads
+        .values()
+        .flat_map(|thread| thread.tags.iter().cloned())
+        .collect();
+    tags.sort_unstable();
+    tags.dedup();
+    tags
+}
+
+/// Trims and lowercases a tag
+///
+/// # Errors
+/// Returns an error if the tag is empty after trimming
+fn normalize_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err(RoutingError::InvalidThreadOperation(
+            "Thread tag cannot be empty".to_owned(),
+        ));
+    }
+    Ok(tag)
+}
+
+/// Syncs the automatic status tags with the thread's work history
+pub(super) fn apply_automatic_tags(thread: &mut Thread) {
+    let last_work_failed = thread
+        .last_message()
+        .and_then(|message| message.work.as_ref())
+        .is_some_and(|work| work.status == WorkStatus::Failed);
+
+    let total_cost: f64 = thread
+        .messages
+        .iter()
+        .filter_map(|message| message.work.as_ref())
+        .map(|work| RequestMetrics::estimate_cost(&work.tier_used, &work.tokens_used))
+        .sum();
+
+    set_tag(thread, ERROR_TAG, last_work_failed);
+    set_tag(thread, HIGH_COST_TAG, total_cost > HIGH_COST_THRESHOLD_USD);
+}
+
+/// Adds or removes `tag` so that its presence matches `present`
+fn set_tag(thread: &mut Thread, tag: &str, present: bool) {
+    if present && !thread.has_tag(tag) {
+        thread.tags.push(tag.to_owned());
+    } else if !present {
+        thread.tags.retain(|existing| existing != tag);
+    }
+}
@@ -0,0 +1,168 @@
+//! Tests for branching and merging threads
+
+use super::*;
+use merlin_core::BranchPoint;
+
+/// Tests creating a branch from a parent thread.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_create_branch() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let parent = store.create_thread("Parent".to_owned());
+    let parent_id = parent.id;
+    store.save_thread(&parent)?;
+
+    let msg_id = MessageId::default();
+    let branch = store.create_branch("Branch".to_owned(), parent_id, msg_id)?;
+
+    assert!(branch.parent_thread.is_some());
+    if let Some(branch_point) = branch.parent_thread {
+        assert_eq!(branch_point.thread_id, parent_id);
+    }
+    Ok(())
+}
+
+/// Tests that branches share their parent's conversation up to the branch point.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_branch_thread() -> Result<()> {
+    let (mut store, temp) = create_test_store()?;
+    let mut parent = store.create_thread("Parser".to_owned());
+    let messages: Vec<Message> = ["Write a lexer", "Use a table", "Add tests"]
+        .into_iter()
+        .map(|content| Message::new(content.to_owned()))
+        .collect();
+    let ids: Vec<MessageId> = messages.iter().map(|message| message.id).collect();
+    parent.messages = messages;
+    store.save_thread(&parent)?;
+
+    let branch_id = store.branch_thread(parent.id, ids[1])?;
+    let mut branch = store
+        .get_thread(branch_id)
+        .cloned()
+        .ok_or_else(|| RoutingError::Other("branch not saved".to_owned()))?;
+    assert_eq!(branch.name, "Branch of Parser");
+    assert!(branch.messages.is_empty());
+    let alternative = Message::new("Use a match instead".to_owned());
+    let alternative_id = alternative.id;
+    branch.add_message(alternative);
+    store.save_thread(&branch)?;
+
+    let contents = |threads: &ThreadStore, thread_id| -> Result<Vec<String>> {
+        Ok(threads
+            .conversation(thread_id)?
+            .into_iter()
+            .map(|message| message.content.clone())
+            .collect())
+    };
+    assert_eq!(
+        contents(&store, branch_id)?,
+        ["Write a lexer", "Use a table", "Use a match instead"]
+    );
+    assert_eq!(contents(&store, parent.id)?.len(), 3);
+
+    // Branching at an inherited message branches the thread that owns it
+    let nested_id = store.branch_thread(branch_id, ids[0])?;
+    let nested_parent = store
+        .get_thread(nested_id)
+        .and_then(|nested| nested.parent_thread.clone());
+    assert_eq!(
+        nested_parent,
+        Some(BranchPoint {
+            thread_id: parent.id,
+            message_id: ids[0],
+        })
+    );
+    let deep_id = store.branch_thread(branch_id, alternative_id)?;
+    assert_eq!(contents(&store, deep_id)?, contents(&store, branch_id)?);
+    let outside = store.branch_thread(nested_id, ids[2]);
+    assert!(outside.is_err_and(|err| err.to_string().contains("not found")));
+
+    // Branch points survive a reload
+    let mut reloaded = ThreadStore::new(temp.path().to_path_buf())?;
+    reloaded.load_all()?;
+    let reloaded_parent = reloaded
+        .get_thread(branch_id)
+        .and_then(|reloaded_branch| reloaded_branch.parent_thread.clone());
+    assert_eq!(
+        reloaded_parent,
+        Some(BranchPoint {
+            thread_id: parent.id,
+            message_id: ids[1],
+        })
+    );
+    assert_eq!(contents(&reloaded, deep_id)?, contents(&store, deep_id)?);
+    Ok(())
+}
+
+/// Tests merging a thread's messages into another after a given message.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_merge_threads() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let mut target = store.create_thread("Target".to_owned());
+    let first = Message::new("target 1".to_owned());
+    let insert_after = first.id;
+    target.add_message(first);
+    target.add_message(Message::new("target 2".to_owned()));
+    target.tags.push("api".to_owned());
+    store.save_thread(&target)?;
+
+    let mut source = store.create_thread("Source".to_owned());
+    let follow_up = Message::new("follow-up".to_owned());
+    let follow_up_time = follow_up.created_at;
+    source.add_message(follow_up);
+    source.tags.push("bug".to_owned());
+    store.save_thread(&source)?;
+
+    let merged_id = store.merge_threads(source.id, target.id, insert_after)?;
+    let merged = store
+        .get_thread(merged_id)
+        .ok_or_else(|| RoutingError::Other("merged thread missing".to_owned()))?;
+
+    let contents: Vec<&str> = merged
+        .messages
+        .iter()
+        .map(|message| message.content.as_str())
+        .collect();
+    assert_eq!(contents, vec!["target 1", "follow-up", "target 2"]);
+    assert_eq!(merged.messages[1].created_at, follow_up_time);
+    assert_eq!(merged.name, "Target");
+    assert_eq!(merged.tags, vec!["api".to_owned(), "bug".to_owned()]);
+    assert!(!merged.archived);
+
+    assert!(
+        store
+            .get_thread(source.id)
+            .is_some_and(|thread| thread.archived)
+    );
+    assert!(
+        store
+            .get_thread(target.id)
+            .is_some_and(|thread| thread.archived)
+    );
+    assert_eq!(store.active_threads().len(), 1);
+
+    assert!(matches!(
+        store.merge_threads(target.id, target.id, insert_after),
+        Err(RoutingError::InvalidThreadOperation(_))
+    ));
+    Ok(())
+}
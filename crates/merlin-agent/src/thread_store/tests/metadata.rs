@@ -0,0 +1,175 @@
+//! Tests for thread search, tags and titles
+
+use super::super::search::excerpt_around;
+use super::super::titles::clean_generated_title;
+use super::*;
+use merlin_core::TitleSource;
+
+/// Tests searching threads by name and message content.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_search_threads() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let mut parser_thread = store.create_thread("Parser work".to_owned());
+    parser_thread.add_message(Message::new("Add error recovery to the parser".to_owned()));
+    store.save_thread(&parser_thread)?;
+
+    let mut ui_thread = store.create_thread("UI polish".to_owned());
+    ui_thread.add_message(Message::new("Tweak the theme colors".to_owned()));
+    ui_thread.add_message(Message::new("The PARSER output looks odd".to_owned()));
+    store.save_thread(&ui_thread)?;
+
+    let results = store.search("parser");
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].thread_id, parser_thread.id);
+    assert_eq!(results[1].thread_id, ui_thread.id);
+    assert!(results[0].relevance_score > results[1].relevance_score);
+    assert_eq!(
+        results[1].message_excerpt.as_deref(),
+        Some("The PARSER output looks odd")
+    );
+
+    assert!(store.search("nonexistent").is_empty());
+    assert!(store.search("   ").is_empty());
+    Ok(())
+}
+
+/// Tests that excerpts are trimmed around the match on char boundaries.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_excerpt_around_long_message() {
+    let content = format!("{}needle{}", "é".repeat(60), "x".repeat(60));
+    let position = content.find("needle").unwrap_or(0);
+    let excerpt = excerpt_around(&content, position, "needle".len());
+
+    assert!(excerpt.starts_with("..."));
+    assert!(excerpt.ends_with("..."));
+    assert!(excerpt.contains("needle"));
+}
+
+/// Tests adding and removing tags, including persistence across reloads.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_add_and_remove_tags() -> Result<()> {
+    let (mut store, temp) = create_test_store()?;
+    let thread = store.create_thread("Tagged".to_owned());
+    let thread_id = thread.id;
+    store.save_thread(&thread)?;
+
+    store.add_tag(thread_id, " Backend ")?;
+    store.add_tag(thread_id, "backend")?;
+    store.add_tag(thread_id, "api")?;
+    assert!(store.add_tag(thread_id, "  ").is_err());
+    assert_eq!(
+        store.all_tags(),
+        vec!["api".to_owned(), "backend".to_owned()]
+    );
+
+    store.remove_tag(thread_id, "api")?;
+
+    let mut reloaded = ThreadStore::new(temp.path().to_path_buf())?;
+    reloaded.load_all()?;
+    let tags = reloaded
+        .get_thread(thread_id)
+        .map(|reloaded_thread| reloaded_thread.tags.clone());
+    assert_eq!(tags, Some(vec!["backend".to_owned()]));
+    Ok(())
+}
+
+/// Tests that error and cost tags follow the thread's work history.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_automatic_tags() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+    let mut thread = store.create_thread("Expensive".to_owned());
+
+    let mut message = Message::new("Big refactor".to_owned());
+    let mut work = WorkUnit::new(TaskId::default(), "claude".to_owned());
+    work.tokens_used = TokenUsage {
+        input: 100_000,
+        output: 100_000,
+        cache_read: 0,
+        cache_write: 0,
+    };
+    work.fail();
+    message.attach_work(work);
+    thread.add_message(message);
+    store.save_thread(&thread)?;
+
+    let saved = store
+        .get_thread(thread.id)
+        .map(|thread| thread.tags.clone());
+    assert_eq!(
+        saved,
+        Some(vec![ERROR_TAG.to_owned(), HIGH_COST_TAG.to_owned()])
+    );
+
+    // A later successful message clears the error tag
+    let mut retry = Message::new("Try again".to_owned());
+    let mut retry_work = WorkUnit::new(TaskId::default(), "local".to_owned());
+    retry_work.complete();
+    retry.attach_work(retry_work);
+    thread.add_message(retry);
+    store.save_thread(&thread)?;
+
+    let after_retry = store
+        .get_thread(thread.id)
+        .map(|thread| thread.tags.clone());
+    assert_eq!(after_retry, Some(vec![HIGH_COST_TAG.to_owned()]));
+    Ok(())
+}
+
+/// Tests that generated titles apply once and never replace manual names.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_generated_and_manual_titles() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let thread = store.create_thread("fix the login bug in".to_owned());
+    store.save_thread(&thread)?;
+    let generated = store.apply_generated_title(
+        thread.id,
+        "\"Fix Login Session Expiry Handling Bug In Auth Module Today.\"\nextra",
+    )?;
+    assert!(generated);
+    let titled = store
+        .get_thread(thread.id)
+        .ok_or_else(|| RoutingError::Other("thread missing".to_owned()))?;
+    assert_eq!(titled.name, "Fix Login Session Expiry Handling Bug In Auth");
+    assert_eq!(titled.title_source, TitleSource::Generated);
+
+    store.rename_thread(thread.id, "  Auth work  ")?;
+    assert!(!store.apply_generated_title(thread.id, "Something else")?);
+    let renamed = store
+        .get_thread(thread.id)
+        .ok_or_else(|| RoutingError::Other("thread missing".to_owned()))?;
+    assert_eq!(renamed.name, "Auth work");
+    assert_eq!(renamed.title_source, TitleSource::Manual);
+
+    assert!(store.rename_thread(thread.id, "   ").is_err());
+    assert_eq!(clean_generated_title("  \n\"\"\n"), None);
+    Ok(())
+}
@@ -0,0 +1,238 @@
+//! Tests for thread storage
+
+mod branches;
+mod metadata;
+
+use super::*;
+use merlin_core::{MessageId, TaskId, TokenUsage, WorkUnit};
+use tempfile::TempDir;
+
+/// Creates a test thread store with temporary directory.
+///
+/// # Errors
+/// Returns an error if store creation fails.
+fn create_test_store() -> Result<(ThreadStore, TempDir)> {
+    let temp_dir = TempDir::new()?;
+    let store = ThreadStore::new(temp_dir.path().to_path_buf())?;
+    Ok((store, temp_dir))
+}
+
+/// Tests creating threads with unique colors.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_create_thread() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let thread1 = store.create_thread("Thread 1".to_owned());
+    let thread2 = store.create_thread("Thread 2".to_owned());
+
+    assert_eq!(thread1.name, "Thread 1");
+    assert_eq!(thread2.name, "Thread 2");
+    assert_ne!(thread1.color, thread2.color); // Different colors
+    Ok(())
+}
+
+/// Tests saving and loading thread persistence.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_save_and_load_thread() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let thread = store.create_thread("Test".to_owned());
+    let thread_id = thread.id;
+
+    store.save_thread(&thread)?;
+
+    // Create new store to test loading
+    let mut new_store = ThreadStore::new(store.storage_path.clone())?;
+    new_store.load_all()?;
+
+    let loaded = new_store.get_thread(thread_id);
+    assert!(loaded.is_some(), "Expected thread to exist");
+    if let Some(loaded) = loaded {
+        assert_eq!(loaded.name, "Test");
+    }
+    Ok(())
+}
+
+/// Tests deleting a thread.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_delete_thread() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let thread = store.create_thread("Delete Me".to_owned());
+    let thread_id = thread.id;
+
+    store.save_thread(&thread)?;
+    store.delete_thread(thread_id)?;
+
+    assert!(store.get_thread(thread_id).is_none());
+    assert!(!store.thread_path(thread_id).exists());
+    Ok(())
+}
+
+/// Tests archiving a thread.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_archive_thread() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let thread = store.create_thread("Archive Test".to_owned());
+    let thread_id = thread.id;
+
+    store.save_thread(&thread)?;
+    store.archive_thread(thread_id)?;
+
+    let archived = store.get_thread(thread_id);
+    assert!(archived.is_some(), "Thread not found");
+    if let Some(archived) = archived {
+        assert!(archived.archived);
+    }
+
+    assert_eq!(store.active_threads().len(), 0);
+    assert_eq!(store.archived_threads().len(), 1);
+    Ok(())
+}
+
+/// Tests unarchiving a thread.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_unarchive_thread() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let thread = store.create_thread("Unarchive Test".to_owned());
+    let thread_id = thread.id;
+
+    store.save_thread(&thread)?;
+    store.archive_thread(thread_id)?;
+    store.unarchive_thread(thread_id)?;
+
+    let unarchived = store.get_thread(thread_id);
+    assert!(unarchived.is_some(), "Thread not found");
+    if let Some(unarchived) = unarchived {
+        assert!(!unarchived.archived);
+    }
+
+    assert_eq!(store.active_threads().len(), 1);
+    assert_eq!(store.archived_threads().len(), 0);
+    Ok(())
+}
+
+/// Tests thread color cycling behavior.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_color_cycling() -> Result<()> {
+    let (mut store, _temp) = create_test_store()?;
+
+    let colors: Vec<_> = (0..7)
+        .map(|_| store.create_thread("Test".to_owned()).color)
+        .collect();
+
+    // First 6 should be different
+    for first_color in 0..6 {
+        for second_color in (first_color + 1)..6 {
+            assert_ne!(colors[first_color], colors[second_color]);
+        }
+    }
+
+    // 7th should be same as 1st (wraps around)
+    assert_eq!(colors[0], colors[6]);
+    Ok(())
+}
+
+/// Tests pinning files, including persistence across reloads and branches.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_pin_and_unpin_files() -> Result<()> {
+    let (mut store, temp) = create_test_store()?;
+    let mut thread = store.create_thread("Pinned".to_owned());
+    let message = Message::new("First task".to_owned());
+    let message_id = message.id;
+    thread.add_message(message);
+    store.save_thread(&thread)?;
+
+    assert!(store.pin_file(thread.id, PathBuf::from("docs/spec.md"))?);
+    assert!(!store.pin_file(thread.id, PathBuf::from("docs/spec.md"))?);
+    assert!(store.pin_file(thread.id, PathBuf::from("schema.sql"))?);
+    assert!(store.unpin_file(thread.id, Path::new("schema.sql"))?);
+    assert!(!store.unpin_file(thread.id, Path::new("schema.sql"))?);
+
+    let mut reloaded = ThreadStore::new(temp.path().to_path_buf())?;
+    reloaded.load_all()?;
+    let branch = reloaded.create_branch("Branch".to_owned(), thread.id, message_id)?;
+    assert_eq!(branch.pinned_files, vec![PathBuf::from("docs/spec.md")]);
+
+    assert_eq!(reloaded.clear_pinned_files(thread.id)?, 1);
+    let pinned = reloaded
+        .get_thread(thread.id)
+        .map(|reloaded_thread| reloaded_thread.pinned_files.len());
+    assert_eq!(pinned, Some(0));
+    Ok(())
+}
+
+/// Tests that working directories persist and are inherited by branches.
+///
+/// # Errors
+/// Returns an error if store operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_set_working_dir() -> Result<()> {
+    let (mut store, temp) = create_test_store()?;
+    let mut thread = store.create_thread("Infra".to_owned());
+    let message = Message::new("Bump the node pool".to_owned());
+    let message_id = message.id;
+    thread.add_message(message);
+    store.save_thread(&thread)?;
+
+    store.set_working_dir(thread.id, Some(PathBuf::from("/work/infra")))?;
+
+    let mut reloaded = ThreadStore::new(temp.path().to_path_buf())?;
+    reloaded.load_all()?;
+    let branch = reloaded.create_branch("Branch".to_owned(), thread.id, message_id)?;
+    assert_eq!(branch.working_dir, Some(PathBuf::from("/work/infra")));
+
+    reloaded.set_working_dir(thread.id, None)?;
+    let working_dir = reloaded
+        .get_thread(thread.id)
+        .map(|reloaded_thread| reloaded_thread.working_dir.clone());
+    assert_eq!(working_dir, Some(None));
+    Ok(())
+}
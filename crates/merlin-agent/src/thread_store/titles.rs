@@ -0,0 +1,83 @@
+//! Manual and generated thread titles.
+
+use super::ThreadStore;
+use merlin_core::{Result, RoutingError, ThreadId, TitleSource};
+
+/// Maximum number of words kept from a generated thread title
+pub const GENERATED_TITLE_MAX_WORDS: usize = 8;
+
+/// Renames a thread on behalf of the user
+///
+/// # Errors
+/// Returns an error if the name is empty, the thread doesn't exist, or it cannot be saved
+pub(super) fn rename_thread(
+    store: &mut ThreadStore,
+    thread_id: ThreadId,
+    name: &str,
+) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(RoutingError::InvalidThreadOperation(
+            "Thread name cannot be empty".to_owned(),
+        ));
+    }
+
+    let mut thread = store
+        .threads
+        .get(&thread_id)
+        .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+        .clone();
+
+    name.clone_into(&mut thread.name);
+    thread.title_source = TitleSource::Manual;
+    store.save_thread(&thread)
+}
+
+/// Applies a model-generated title to a thread that still has its default name
+///
+/// # Errors
+/// Returns an error if the thread doesn't exist or cannot be saved
+pub(super) fn apply_generated_title(
+    store: &mut ThreadStore,
+    thread_id: ThreadId,
+    raw_title: &str,
+) -> Result<bool> {
+    let mut thread = store
+        .threads
+        .get(&thread_id)
+        .ok_or_else(|| RoutingError::ThreadNotFound(thread_id))?
+        .clone();
+
+    if thread.title_source != TitleSource::Default {
+        return Ok(false);
+    }
+    let Some(title) = clean_generated_title(raw_title) else {
+        return Ok(false);
+    };
+
+    thread.name = title;
+    thread.title_source = TitleSource::Generated;
+    store.save_thread(&thread)?;
+    Ok(true)
+}
+
+/// Reduces raw model output to a short single-line title
+///
+/// Returns `None` if nothing usable remains.
+pub(super) fn clean_generated_title(raw_title: &str) -> Option<String> {
+    let first_line = raw_title.lines().find(|line| !line.trim().is_empty())?;
+    let stripped = first_line
+        .trim()
+        .trim_start_matches(|character: char| character == '#' || character.is_whitespace())
+        .trim_start_matches("Title:")
+        .trim_matches(|character: char| {
+            matches!(character, '"' | '\'' | '`' | '*' | '.' | ':') || character.is_whitespace()
+        });
+
+    let title = stripped
+        .split_whitespace()
+        .take(GENERATED_TITLE_MAX_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then_some(title)
+}
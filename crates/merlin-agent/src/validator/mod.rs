@@ -11,15 +11,21 @@ pub mod stages;
 use async_trait::async_trait;
 use merlin_core::Response;
 use merlin_core::{Result, Task, ValidationResult};
+use std::path::PathBuf;
 
 pub use pipeline::{ValidationPipeline, ValidationStage};
-pub use stages::SyntaxValidationStage;
+pub use stages::{FormatValidationStage, SyntaxValidationStage};
 
 /// Trait for validation strategies
 #[async_trait]
 pub trait Validator: Send + Sync {
-    /// Validate a task response
-    async fn validate(&self, response: &Response, task: &Task) -> Result<ValidationResult>;
+    /// Validate a task response, along with the files the task changed
+    async fn validate(
+        &self,
+        response: &Response,
+        task: &Task,
+        changed_files: &[PathBuf],
+    ) -> Result<ValidationResult>;
 
     /// Quick validation (pre-flight check)
     async fn quick_validate(&self, response: &Response) -> Result<bool>;
@@ -2,20 +2,26 @@ use super::Validator;
 use async_trait::async_trait;
 use merlin_core::Response;
 use merlin_core::{
-    Result, Severity, StageResult as PublicStageResult, Task, ValidationError, ValidationResult,
-    ValidationStageType as StageType,
+    Result, Severity, StageResult as PublicStageResult, Task, ValidationCheckType,
+    ValidationConfig, ValidationError, ValidationResult, ValidationStageType as StageType,
 };
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
 /// Individual validation stage trait.
 #[async_trait]
 pub trait ValidationStage: Send + Sync {
-    /// Validates a response against a task.
+    /// Validates a response against a task that changed `changed_files`.
     ///
     /// # Errors
     /// Returns an error if validation cannot be performed.
-    async fn validate(&self, response: &Response, task: &Task) -> Result<StageResult>;
+    async fn validate(
+        &self,
+        response: &Response,
+        task: &Task,
+        changed_files: &[PathBuf],
+    ) -> Result<StageResult>;
 
     /// Performs a quick pre-flight check of the response.
     ///
@@ -43,6 +49,10 @@ pub struct StageResult {
     pub details: String,
     /// Quality score for this stage (0.0 to 1.0)
     pub score: f64,
+    /// Files the stage reformatted
+    pub reformatted: Vec<PathBuf>,
+    /// Non-blocking problems, such as a formatter that couldn't be run
+    pub warnings: Vec<String>,
 }
 
 /// Multi-stage validation pipeline
@@ -80,11 +90,31 @@ impl ValidationPipeline {
 
         Self::new(stages)
     }
+
+    /// Creates a pipeline with the default stages and the checks enabled in `config`.
+    ///
+    /// Adds formatting of changed files when the format check is enabled.
+    pub fn from_config(config: &ValidationConfig) -> Self {
+        use super::stages::FormatValidationStage;
+
+        let mut pipeline = Self::with_default_stages();
+        if config.checks.is_enabled(ValidationCheckType::Format) {
+            pipeline
+                .stages
+                .push(Arc::new(FormatValidationStage::new(config)));
+        }
+        pipeline
+    }
 }
 
 #[async_trait]
 impl Validator for ValidationPipeline {
-    async fn validate(&self, response: &Response, task: &Task) -> Result<ValidationResult> {
+    async fn validate(
+        &self,
+        response: &Response,
+        task: &Task,
+        changed_files: &[PathBuf],
+    ) -> Result<ValidationResult> {
         let mut result = ValidationResult {
             passed: true,
             score: 1.0,
@@ -95,7 +125,7 @@ impl Validator for ValidationPipeline {
 
         for stage in &self.stages {
             let start = Instant::now();
-            let stage_result = stage.validate(response, task).await?;
+            let stage_result = stage.validate(response, task, changed_files).await?;
 
             result.stages.push(PublicStageResult {
                 stage: stage_result.stage,
@@ -103,7 +133,9 @@ impl Validator for ValidationPipeline {
                 duration_ms: start.elapsed().as_millis() as u64,
                 details: stage_result.details.clone(),
                 score: stage_result.score,
+                reformatted: stage_result.reformatted,
             });
+            result.warnings.extend(stage_result.warnings);
 
             result.score *= stage_result.score;
             result.passed &= stage_result.passed;
@@ -145,13 +177,20 @@ mod tests {
     }
     #[async_trait]
     impl ValidationStage for MockStage {
-        async fn validate(&self, _response: &Response, _task: &Task) -> Result<StageResult> {
+        async fn validate(
+            &self,
+            _response: &Response,
+            _task: &Task,
+            _changed_files: &[PathBuf],
+        ) -> Result<StageResult> {
             Ok(StageResult {
                 stage: StageType::Syntax,
                 passed: self.should_pass,
                 duration_ms: 10,
                 details: format!("{} result", self.name),
                 score: if self.should_pass { 1.0 } else { 0.0 },
+                reformatted: Vec::default(),
+                warnings: Vec::default(),
             })
         }
 
@@ -198,7 +237,7 @@ mod tests {
             latency_ms: 0,
         };
 
-        let result = pipeline.validate(&response, &task).await?;
+        let result = pipeline.validate(&response, &task, &[]).await?;
         assert!(result.passed);
         assert_eq!(result.stages.len(), 2);
         Ok(())
@@ -234,7 +273,7 @@ mod tests {
             latency_ms: 0,
         };
 
-        let result = pipeline.validate(&response, &task).await?;
+        let result = pipeline.validate(&response, &task, &[]).await?;
         assert!(!result.passed);
         assert_eq!(result.stages.len(), 1);
         Ok(())
@@ -0,0 +1,353 @@
+//! Formatting of the files changed by a task.
+//!
+//! Rust files go through rustfmt, other files through the formatters declared
+//! in `ValidationConfig::formatters`. Formatters that are missing or time out
+//! only produce warnings, so an environment without them can still validate.
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::fs;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use super::super::pipeline::{StageResult, ValidationStage};
+use merlin_core::{
+    FormatterConfig, Response, Result, Task, ValidationConfig, ValidationStageType as StageType,
+};
+
+/// Formatter output kept in the failure details
+const MAX_OUTPUT_CHARS: usize = 2_000;
+
+/// Changed files handled by one formatter
+type FormatterFiles<'stage> = (&'stage FormatterConfig, Vec<PathBuf>);
+
+/// Checks, or with `auto_fix` applies, the formatting of changed files
+pub struct FormatValidationStage {
+    /// Format files instead of failing when they aren't formatted
+    auto_fix: bool,
+    /// Timeout for a single formatter run
+    timeout: Duration,
+    /// Formatters, configured ones first so they take precedence over rustfmt
+    formatters: Vec<FormatterConfig>,
+}
+
+/// What running the formatters found
+#[derive(Default)]
+struct FormatOutcome {
+    /// Formatters that reported unformatted or unparsable files
+    failures: Vec<String>,
+    /// Formatters that couldn't be run
+    warnings: Vec<String>,
+    /// Files changed by formatting
+    reformatted: Vec<PathBuf>,
+}
+
+impl FormatValidationStage {
+    /// Create a stage with the formatters and auto-fix setting of `config`
+    pub fn new(config: &ValidationConfig) -> Self {
+        let formatters = config
+            .formatters
+            .iter()
+            .cloned()
+            .chain([FormatterConfig::rustfmt(&config.rust_edition)])
+            .collect();
+        Self {
+            auto_fix: config.auto_fix,
+            timeout: Duration::from_secs(config.format_timeout_seconds),
+            formatters,
+        }
+    }
+
+    /// Set the timeout for a single formatter run
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Groups the existing changed files by the first formatter handling them
+    fn group_files<'stage>(&'stage self, changed_files: &[PathBuf]) -> Vec<FormatterFiles<'stage>> {
+        let mut groups: Vec<FormatterFiles<'_>> = Vec::new();
+        for path in changed_files.iter().filter(|path| path.is_file()) {
+            let Some(formatter) = self
+                .formatters
+                .iter()
+                .find(|formatter| formatter.handles(path))
+            else {
+                continue;
+            };
+            match groups
+                .iter_mut()
+                .find(|(grouped, _)| (*grouped).eq(formatter))
+            {
+                Some((_, files)) => files.push(path.clone()),
+                None => groups.push((formatter, vec![path.clone()])),
+            }
+        }
+        groups
+    }
+
+    /// Runs `formatter` on `files`, adding what it found to `outcome`
+    async fn run_formatter(
+        &self,
+        formatter: &FormatterConfig,
+        files: &[PathBuf],
+        outcome: &mut FormatOutcome,
+    ) {
+        let before = if self.auto_fix {
+            read_all(files).await
+        } else {
+            Vec::new()
+        };
+        let args = if self.auto_fix {
+            &formatter.fix_args
+        } else {
+            &formatter.check_args
+        };
+        let mut command = Command::new(&formatter.command);
+        command.args(args).args(files).kill_on_drop(true);
+        tracing::debug!("Running {} on {} files", formatter.command, files.len());
+
+        match timeout(self.timeout, command.output()).await {
+            Err(_) => outcome.warnings.push(format!(
+                "{} timed out after {}s, skipped formatting {} files",
+                formatter.command,
+                self.timeout.as_secs(),
+                files.len()
+            )),
+            Ok(Err(err)) if err.kind() == ErrorKind::NotFound => outcome.warnings.push(format!(
+                "{} not found, skipped formatting {} files",
+                formatter.command,
+                files.len()
+            )),
+            Ok(Err(err)) => outcome
+                .warnings
+                .push(format!("Failed to run {}: {err}", formatter.command)),
+            Ok(Ok(output)) if output.status.success() => {
+                let after = read_all(files).await;
+                outcome.reformatted.extend(
+                    before
+                        .into_iter()
+                        .zip(after)
+                        .filter(|(old, new)| old.1 != new.1)
+                        .map(|(old, _)| old.0),
+                );
+            }
+            Ok(Ok(output)) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let report: String = format!("{}\n{}", stdout.trim(), stderr.trim())
+                    .trim()
+                    .chars()
+                    .take(MAX_OUTPUT_CHARS)
+                    .collect();
+                outcome.failures.push(format!(
+                    "{} reported unformatted files:\n{report}",
+                    formatter.command
+                ));
+            }
+        }
+    }
+}
+
+/// Reads the contents of `files`, treating unreadable files as empty
+async fn read_all(files: &[PathBuf]) -> Vec<(PathBuf, String)> {
+    let mut contents = Vec::with_capacity(files.len());
+    for path in files {
+        let content = fs::read_to_string(path).await.unwrap_or_default();
+        contents.push((path.clone(), content));
+    }
+    contents
+}
+
+/// Describes a passing run
+fn passed_details(outcome: &FormatOutcome, checked: usize) -> String {
+    if checked == 0 {
+        "No changed files to format".to_owned()
+    } else if outcome.reformatted.is_empty() {
+        format!("{checked} changed files are formatted")
+    } else {
+        let paths: Vec<String> = outcome
+            .reformatted
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        format!("Reformatted {}", paths.join(", "))
+    }
+}
+
+#[async_trait]
+impl ValidationStage for FormatValidationStage {
+    async fn validate(
+        &self,
+        _response: &Response,
+        _task: &Task,
+        changed_files: &[PathBuf],
+    ) -> Result<StageResult> {
+        let groups = self.group_files(changed_files);
+        let checked = groups.iter().map(|(_, files)| files.len()).sum();
+        let mut outcome = FormatOutcome::default();
+        for (formatter, files) in &groups {
+            self.run_formatter(formatter, files, &mut outcome).await;
+        }
+        for warning in &outcome.warnings {
+            tracing::warn!("{warning}");
+        }
+
+        let passed = outcome.failures.is_empty();
+        let details = if passed {
+            passed_details(&outcome, checked)
+        } else {
+            outcome.failures.join("\n")
+        };
+        Ok(StageResult {
+            stage: StageType::Format,
+            passed,
+            duration_ms: 0,
+            details,
+            score: if passed { 1.0 } else { 0.0 },
+            reformatted: outcome.reformatted,
+            warnings: outcome.warnings,
+        })
+    }
+
+    async fn quick_check(&self, _response: &Response) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn name(&self) -> &'static str {
+        "Format"
+    }
+
+    fn stage_type(&self) -> StageType {
+        StageType::Format
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result as TestResult;
+    use merlin_core::TokenUsage;
+    use std::fs as std_fs;
+    use std::slice;
+    use tempfile::TempDir;
+
+    const UNFORMATTED: &str = "fn main( ){let x=1;println!(\"{x}\");}\n";
+
+    fn response() -> Response {
+        Response {
+            text: "done".to_owned(),
+            confidence: 1.0,
+            tokens_used: TokenUsage::default(),
+            provider: "test".to_owned(),
+            latency_ms: 0,
+        }
+    }
+
+    /// Runs `stage` on `files`
+    ///
+    /// # Errors
+    /// Returns an error if the stage fails.
+    async fn run_stage(stage: &FormatValidationStage, files: &[PathBuf]) -> Result<StageResult> {
+        stage
+            .validate(&response(), &Task::new("Format".to_owned()), files)
+            .await
+    }
+
+    /// Tests that check-only mode fails on unformatted Rust and leaves it unchanged.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be written or validation fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_check_only_reports_unformatted_files() -> TestResult<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("main.rs");
+        std_fs::write(&path, UNFORMATTED)?;
+
+        let stage = FormatValidationStage::new(&ValidationConfig::default());
+        let result = run_stage(&stage, slice::from_ref(&path)).await?;
+
+        assert!(!result.passed);
+        assert!(result.details.contains("rustfmt"));
+        assert!(result.reformatted.is_empty());
+        assert_eq!(std_fs::read_to_string(&path)?, UNFORMATTED);
+        Ok(())
+    }
+
+    /// Tests that auto-fix formats unformatted Rust and records the file.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be written or validation fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_auto_fix_reformats_files() -> TestResult<()> {
+        let temp_dir = TempDir::new()?;
+        let unformatted = temp_dir.path().join("main.rs");
+        let formatted = temp_dir.path().join("lib.rs");
+        std_fs::write(&unformatted, UNFORMATTED)?;
+        std_fs::write(&formatted, "pub fn answer() -> u32 {\n    42\n}\n")?;
+
+        let config = ValidationConfig {
+            auto_fix: true,
+            ..ValidationConfig::default()
+        };
+        let stage = FormatValidationStage::new(&config);
+        let result = run_stage(&stage, &[unformatted.clone(), formatted]).await?;
+
+        assert!(result.passed, "{}", result.details);
+        assert_eq!(result.reformatted, vec![unformatted.clone()]);
+        assert!(std_fs::read_to_string(&unformatted)?.contains("let x = 1;"));
+        Ok(())
+    }
+
+    /// Tests that missing formatters and timeouts are warnings, not failures.
+    ///
+    /// # Errors
+    /// Returns an error if the files can't be written or validation fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_unavailable_formatters_only_warn() -> TestResult<()> {
+        let temp_dir = TempDir::new()?;
+        let script = temp_dir.path().join("app.js");
+        let style = temp_dir.path().join("site.css");
+        std_fs::write(&script, "let x=1\n")?;
+        std_fs::write(&style, "a{}\n")?;
+
+        let config = ValidationConfig {
+            formatters: vec![
+                FormatterConfig {
+                    extensions: vec!["js".to_owned()],
+                    command: "merlin-missing-formatter".to_owned(),
+                    check_args: vec!["--check".to_owned()],
+                    fix_args: vec!["--write".to_owned()],
+                },
+                FormatterConfig {
+                    extensions: vec!["css".to_owned()],
+                    command: "sh".to_owned(),
+                    check_args: vec!["-c".to_owned(), "sleep 5".to_owned()],
+                    fix_args: Vec::new(),
+                },
+            ],
+            ..ValidationConfig::default()
+        };
+        let stage = FormatValidationStage::new(&config).with_timeout(Duration::from_millis(200));
+        let result = run_stage(&stage, &[script, style]).await?;
+
+        assert!(result.passed, "{}", result.details);
+        assert_eq!(result.warnings.len(), 2);
+        assert!(result.warnings[0].contains("not found"));
+        assert!(result.warnings[1].contains("timed out"));
+        Ok(())
+    }
+}
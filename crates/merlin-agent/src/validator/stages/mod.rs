@@ -1,4 +1,7 @@
+/// Formatting stage
+pub mod format;
 /// Syntax validation stage
 pub mod syntax;
 
+pub use format::FormatValidationStage;
 pub use syntax::SyntaxValidationStage;
@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use std::path::PathBuf;
 
 use merlin_core::Response;
 
@@ -81,7 +82,12 @@ impl Default for SyntaxValidationStage {
 
 #[async_trait]
 impl ValidationStage for SyntaxValidationStage {
-    async fn validate(&self, response: &Response, _task: &Task) -> Result<StageResult> {
+    async fn validate(
+        &self,
+        response: &Response,
+        _task: &Task,
+        _changed_files: &[PathBuf],
+    ) -> Result<StageResult> {
         let (passed, score, details) = self.check_syntax_errors(&response.text);
 
         Ok(StageResult {
@@ -90,6 +96,8 @@ impl ValidationStage for SyntaxValidationStage {
             duration_ms: 0,
             details,
             score,
+            reformatted: Vec::default(),
+            warnings: Vec::default(),
         })
     }
 
@@ -0,0 +1,167 @@
+//! Context fetchers of the directories threads work in.
+//!
+//! A thread can run in a directory other than the project root given at
+//! startup (see [`Thread::working_dir`](merlin_core::Thread::working_dir)).
+//! Each directory gets its own [`ContextFetcher`], whose index is built on
+//! first use and reused by later tasks in that directory. Only the most
+//! recently used directories keep their fetcher, so switching between many
+//! repositories doesn't keep every index in memory.
+
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use lru::LruCache;
+use merlin_core::sync::IgnoreLock as _;
+use merlin_languages::IndexCacheStats;
+use merlin_routing::MetricsCollector;
+
+use crate::ContextFetcher;
+
+/// Number of directories whose context fetcher is kept when no capacity is configured
+pub const MAX_WORKSPACE_INDEXES: usize = 4;
+
+/// Context fetchers keyed by workspace root
+type Fetchers = LruCache<PathBuf, Arc<ContextFetcher>>;
+
+/// LRU cache of context fetchers keyed by workspace root
+pub struct WorkspaceContexts {
+    /// Initialized fetchers, most recently used first
+    fetchers: Mutex<Fetchers>,
+    /// Whether created fetchers download a missing embedding model before indexing
+    auto_pull: bool,
+    /// Counters of language index cache use shared by every created fetcher
+    index_cache_stats: IndexCacheStats,
+    /// Whether created fetchers watch their directory for changes made outside the agent
+    watch_mode: bool,
+}
+
+impl WorkspaceContexts {
+    /// Create a cache keeping the fetchers of up to `capacity` directories (at least one)
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            fetchers: Mutex::new(LruCache::new(capacity)),
+            auto_pull: true,
+            index_cache_stats: IndexCacheStats::new(),
+            watch_mode: false,
+        }
+    }
+
+    /// Keep the fetchers of up to `capacity` directories (at least one), keeping the other settings
+    ///
+    /// Fetchers already created are dropped.
+    #[must_use]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        self.fetchers = Mutex::new(LruCache::new(capacity));
+        self
+    }
+
+    /// Set whether fetchers created from now on download a missing embedding model
+    #[must_use]
+    pub fn with_auto_pull(mut self, auto_pull: bool) -> Self {
+        self.auto_pull = auto_pull;
+        self
+    }
+
+    /// Set whether fetchers created from now on watch their directory for changes
+    #[must_use]
+    pub fn with_watch_mode(mut self, watch_mode: bool) -> Self {
+        self.watch_mode = watch_mode;
+        self
+    }
+
+    /// Whether created fetchers watch their directory for changes
+    pub const fn watch_mode(&self) -> bool {
+        self.watch_mode
+    }
+
+    /// Metrics collector reporting the language index cache use of created fetchers
+    #[must_use]
+    pub fn metrics_collector(&self) -> MetricsCollector {
+        MetricsCollector::new().with_index_cache(self.index_cache_stats.clone())
+    }
+
+    /// Whether created fetchers download a missing embedding model before indexing
+    pub const fn auto_pull(&self) -> bool {
+        self.auto_pull
+    }
+
+    /// Context fetcher of `root`, created if it is not cached
+    ///
+    /// A created fetcher embeds files if `enable_embeddings` is set.
+    ///
+    /// Creating a fetcher evicts the least recently used one once the cache is full.
+    /// Tasks still holding an evicted fetcher keep using it until they finish.
+    pub fn fetcher_for(&self, root: &Path, enable_embeddings: bool) -> Arc<ContextFetcher> {
+        let mut fetchers = self.fetchers.lock_ignore_poison();
+        if let Some(fetcher) = fetchers.get(root) {
+            return Arc::clone(fetcher);
+        }
+        let fetcher = Arc::new(
+            ContextFetcher::new_with_embeddings(root.to_path_buf(), enable_embeddings)
+                .with_auto_pull(self.auto_pull)
+                .with_index_cache_stats(self.index_cache_stats.clone())
+                .with_watch_mode(self.watch_mode),
+        );
+        if let Some((evicted, _)) = fetchers.push(root.to_path_buf(), Arc::clone(&fetcher)) {
+            tracing::debug!("Dropped context index of {}", evicted.display());
+        }
+        fetcher
+    }
+
+    /// Whether a fetcher for `root` is cached
+    pub fn contains(&self, root: &Path) -> bool {
+        self.fetchers.lock_ignore_poison().contains(root)
+    }
+
+    /// Number of cached fetchers
+    pub fn len(&self) -> usize {
+        self.fetchers.lock_ignore_poison().len()
+    }
+
+    /// Whether no fetchers are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for WorkspaceContexts {
+    fn default() -> Self {
+        Self::new(MAX_WORKSPACE_INDEXES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that fetchers are shared per directory and the least recently used is evicted.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_fetchers_evicted_least_recently_used() {
+        let contexts = WorkspaceContexts::new(2);
+        let backend = contexts.fetcher_for(Path::new("backend"), false);
+        contexts.fetcher_for(Path::new("infra"), false);
+
+        assert!(Arc::ptr_eq(
+            &backend,
+            &contexts.fetcher_for(Path::new("backend"), false)
+        ));
+        contexts.fetcher_for(Path::new("docs"), false);
+
+        assert_eq!(contexts.len(), 2);
+        assert!(contexts.contains(Path::new("backend")));
+        assert!(!contexts.contains(Path::new("infra")));
+        assert_eq!(
+            contexts
+                .fetcher_for(Path::new("docs"), false)
+                .project_root(),
+            Path::new("docs")
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! Integration tests for recovering a session after a hard stop
+//!
+//! Each test journals a submitted task, drops every handle without finishing it
+//! (as if the process were killed), then restarts against the same directory.
+
+#[cfg(test)]
+mod tests {
+    use merlin_agent::{
+        RoutingOrchestrator, SESSION_FILE_NAME, SessionJournal, SessionTaskStatus, ThreadStore,
+    };
+    use merlin_core::{
+        Message, Result, RoutingConfig, RoutingError, TaskId, ThreadId, WorkStatus, WorkUnit,
+    };
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    /// Submits a task to a fresh session and stops before it completes.
+    ///
+    /// Returns the thread and task that were in flight.
+    ///
+    /// # Errors
+    /// Returns an error if the thread store or journal cannot be written.
+    fn submit_and_hard_stop(merlin_dir: &Path) -> Result<(ThreadId, TaskId)> {
+        let mut store = ThreadStore::new(merlin_dir.join("threads"))?;
+        let mut journal = SessionJournal::load(merlin_dir.join(SESSION_FILE_NAME))?;
+
+        let mut thread = store.create_thread("Refactor parser".to_owned());
+        let task_id = TaskId::default();
+        let mut message = Message::new("Refactor the parser".to_owned());
+        message.attach_work(WorkUnit::new(task_id, "local".to_owned()));
+        thread.add_message(message);
+        store.save_thread(&thread)?;
+
+        journal.record_running(task_id, "Refactor the parser", Some(thread.id))?;
+        journal.record_queued("Then add tests", Some(thread.id))?;
+
+        // Hard stop: handles dropped without finishing the task
+        drop(journal);
+        drop(store);
+        Ok((thread.id, task_id))
+    }
+
+    /// Thread store shared between the orchestrator and the test
+    type SharedThreadStore = Arc<Mutex<ThreadStore>>;
+
+    /// Restarts an orchestrator against `merlin_dir` with local providers only.
+    ///
+    /// # Errors
+    /// Returns an error if the thread store, journal, or orchestrator cannot be created.
+    fn restart(merlin_dir: &Path) -> Result<(RoutingOrchestrator, SharedThreadStore)> {
+        let mut store = ThreadStore::new(merlin_dir.join("threads"))?;
+        store.load_all()?;
+        let store = Arc::new(Mutex::new(store));
+
+        let mut config = RoutingConfig::default();
+        config.tiers.groq_enabled = false;
+        config.tiers.premium_enabled = false;
+
+        let orchestrator = RoutingOrchestrator::new(config)?
+            .with_thread_store(Arc::clone(&store))
+            .with_session_journal(SessionJournal::load(merlin_dir.join(SESSION_FILE_NAME))?);
+        Ok((orchestrator, store))
+    }
+
+    /// Tests that a hard stop between submit and completion is recovered on restart.
+    ///
+    /// # Errors
+    /// Returns an error if any session operation fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_hard_stop_marks_tasks_interrupted() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let (thread_id, task_id) = submit_and_hard_stop(temp_dir.path())?;
+
+        let (orchestrator, store) = restart(temp_dir.path())?;
+        let interrupted = orchestrator.recover_session()?;
+
+        assert_eq!(interrupted.len(), 2);
+        assert!(
+            interrupted
+                .iter()
+                .all(|task| task.status == SessionTaskStatus::Interrupted)
+        );
+        assert_eq!(interrupted[0].task_id, task_id);
+        assert_eq!(interrupted[0].thread_id, Some(thread_id));
+
+        let work_status = store
+            .lock()
+            .map_err(|err| RoutingError::Other(err.to_string()))?
+            .get_thread(thread_id)
+            .and_then(|thread| thread.last_message())
+            .and_then(|message| message.work.as_ref())
+            .map(|work| work.status);
+        assert_eq!(work_status, Some(WorkStatus::Interrupted));
+        Ok(())
+    }
+
+    /// Tests that interrupted tasks are retried most recent first and survive another restart.
+    ///
+    /// # Errors
+    /// Returns an error if any session operation fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_interrupted_tasks_retried_across_restarts() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        submit_and_hard_stop(temp_dir.path())?;
+
+        let (first_run, _first_store) = restart(temp_dir.path())?;
+        first_run.recover_session()?;
+        let retried = first_run.take_interrupted_task()?;
+        assert_eq!(
+            retried.map(|task| task.prompt),
+            Some("Then add tests".to_owned())
+        );
+        drop(first_run);
+
+        // The task that was not retried is still offered after another restart
+        let (orchestrator, _store) = restart(temp_dir.path())?;
+        let interrupted = orchestrator.recover_session()?;
+        assert_eq!(interrupted.len(), 1);
+        assert_eq!(interrupted[0].prompt, "Refactor the parser");
+
+        orchestrator.take_interrupted_task()?;
+        assert!(orchestrator.take_interrupted_task()?.is_none());
+        Ok(())
+    }
+}
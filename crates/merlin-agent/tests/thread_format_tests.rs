@@ -0,0 +1,142 @@
+//! Tests pinning the on-disk thread format
+//!
+//! Fixtures under `tests/fixtures/threads/` are real files as written by each
+//! schema version. They must keep loading after any change to `Thread`.
+
+#[cfg(test)]
+mod tests {
+    use merlin_agent::ThreadStore;
+    use merlin_agent::thread_store::THREAD_SCHEMA;
+    use merlin_core::{Result, RoutingError, Thread, TitleSource};
+    use serde_json::{Value, from_str};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    /// Reads a fixture from `tests/fixtures/threads/`.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be read.
+    fn fixture(name: &str) -> Result<String> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("threads")
+            .join(name);
+        Ok(fs::read_to_string(path)?)
+    }
+
+    /// Creates `.merlin/threads/` in `root` holding the given files.
+    ///
+    /// # Errors
+    /// Returns an error if the files cannot be written.
+    fn threads_dir(root: &Path, files: &[(&str, &str)]) -> Result<PathBuf> {
+        let dir = root.join(".merlin").join("threads");
+        fs::create_dir_all(&dir)?;
+        for (name, contents) in files {
+            fs::write(dir.join(name), contents)?;
+        }
+        Ok(dir)
+    }
+
+    /// Loads the only thread in `dir`.
+    ///
+    /// # Errors
+    /// Returns an error if loading fails or the store does not hold exactly one thread.
+    fn load_single(dir: &Path) -> Result<(ThreadStore, Thread)> {
+        let mut store = ThreadStore::new(dir.to_path_buf())?;
+        store.load_all()?;
+        let threads = store.active_threads();
+        let [thread] = threads.as_slice() else {
+            return Err(RoutingError::Other(format!(
+                "expected one thread, found {}",
+                threads.len()
+            )));
+        };
+        let thread = (*thread).clone();
+        Ok((store, thread))
+    }
+
+    /// Tests that a current-version file is written back unchanged.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be loaded or saved.
+    ///
+    /// # Panics
+    /// Panics if the saved file differs from the fixture.
+    #[test]
+    fn test_current_format_round_trips() -> Result<()> {
+        let temp = TempDir::new()?;
+        let original = fixture("thread_v2.json")?;
+        let dir = threads_dir(temp.path(), &[("thread.json", &original)])?;
+
+        let (mut store, thread) = load_single(&dir)?;
+        assert_eq!(thread.title_source, TitleSource::Manual);
+        store.save_thread(&thread)?;
+
+        let saved = fs::read_to_string(dir.join(format!("{}.json", thread.id)))?;
+        assert_eq!(from_str::<Value>(&saved)?, from_str::<Value>(&original)?);
+        assert_eq!(THREAD_SCHEMA.current_version(), 2);
+        Ok(())
+    }
+
+    /// Tests that an unversioned file is upgraded and saved in the current format.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be loaded or saved.
+    ///
+    /// # Panics
+    /// Panics if the migrated thread or saved file is wrong.
+    #[test]
+    fn test_unversioned_format_migrates() -> Result<()> {
+        let temp = TempDir::new()?;
+        let dir = threads_dir(temp.path(), &[("thread.json", &fixture("thread_v1.json")?)])?;
+
+        let (mut store, thread) = load_single(&dir)?;
+        assert_eq!(thread.name, "Fix the login bug");
+        assert_eq!(thread.title_source, TitleSource::Default);
+        assert!(thread.tags.is_empty());
+        assert_eq!(thread.messages.len(), 1);
+        store.save_thread(&thread)?;
+
+        let saved: Value = from_str(&fs::read_to_string(
+            dir.join(format!("{}.json", thread.id)),
+        )?)?;
+        assert_eq!(saved["schema_version"], 2);
+        assert_eq!(saved["title_source"], "Default");
+        assert_eq!(saved["tags"], Value::Array(Vec::new()));
+        Ok(())
+    }
+
+    /// Tests that unreadable files are quarantined and newer ones left alone.
+    ///
+    /// # Errors
+    /// Returns an error if the store cannot be created or loaded.
+    ///
+    /// # Panics
+    /// Panics if files end up in the wrong place.
+    #[test]
+    fn test_unreadable_files_are_quarantined() -> Result<()> {
+        let temp = TempDir::new()?;
+        let newer = r#"{"schema_version": 99, "id": "future"}"#;
+        let dir = threads_dir(
+            temp.path(),
+            &[
+                ("good.json", &fixture("thread_v2.json")?),
+                ("truncated.json", r#"{"id": "6f1c2b3a"#),
+                ("newer.json", newer),
+            ],
+        )?;
+
+        let mut store = ThreadStore::new(dir.clone())?;
+        let quarantined = store.load_all()?;
+
+        let corrupt = temp.path().join(".merlin").join("corrupt");
+        assert_eq!(quarantined, vec![corrupt.join("truncated.json")]);
+        assert!(corrupt.join("truncated.json").exists());
+        assert!(!dir.join("truncated.json").exists());
+        assert_eq!(fs::read_to_string(dir.join("newer.json"))?, newer);
+        assert_eq!(store.active_threads().len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,141 @@
+//! Integration tests for threads working in their own directories
+//!
+//! Two threads of one session run the same agent code, each in a different
+//! temporary project, and every edit must land in its own thread's tree.
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use merlin_agent::{RoutingOrchestrator, ThreadStore};
+    use merlin_core::{
+        Context, ModelProvider, Query, Response, Result, RoutingConfig, RoutingError, Task,
+        TokenUsage, UiChannel,
+    };
+    use merlin_routing::{Model, ModelRegistry, ProviderRegistry, StrategyRouter};
+    use std::fs;
+    use std::sync::{Arc, Mutex};
+    use tempfile::TempDir;
+
+    /// File written by every task
+    const NOTES_FILE: &str = "notes.txt";
+
+    /// Provider answering with agent code that writes [`NOTES_FILE`]
+    struct NotesProvider;
+
+    #[async_trait]
+    impl ModelProvider for NotesProvider {
+        fn name(&self) -> &'static str {
+            "notes"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn generate(&self, _query: &Query, _context: &Context) -> Result<Response> {
+            Ok(Response {
+                text: format!(
+                    "```typescript\nasync function agent_code(): Promise<string> {{\n    \
+                     await writeFile(\"{NOTES_FILE}\", \"Written by the thread\");\n    \
+                     return \"done\";\n}}\n```"
+                ),
+                confidence: 1.0,
+                tokens_used: TokenUsage::default(),
+                provider: self.name().to_owned(),
+                latency_ms: 0,
+            })
+        }
+
+        fn estimate_cost(&self, _context: &Context) -> f64 {
+            0.0
+        }
+    }
+
+    /// Creates an orchestrator for `project_root` routing every task to [`NotesProvider`]
+    ///
+    /// # Errors
+    /// Returns an error if the registries or the orchestrator cannot be created
+    fn notes_orchestrator(
+        project_root: &TempDir,
+        store: Arc<Mutex<ThreadStore>>,
+    ) -> Result<RoutingOrchestrator> {
+        let mut config = RoutingConfig::default();
+        config.tiers.local_enabled = false;
+        config.tiers.groq_enabled = false;
+        config.tiers.premium_enabled = false;
+
+        let mut provider_registry = ProviderRegistry::new(config.clone())?;
+        provider_registry.register_provider(Model::Qwen25Coder32B, Arc::new(NotesProvider));
+        let mut model_registry = ModelRegistry::new();
+        for difficulty in 1..=10 {
+            model_registry.register(difficulty, Model::Qwen25Coder32B)?;
+        }
+        let router = Arc::new(StrategyRouter::with_model_registry(
+            model_registry,
+            provider_registry.clone(),
+        ));
+        Ok(
+            RoutingOrchestrator::new_with_router(config, router, provider_registry)?
+                .with_workspace(project_root.path().to_path_buf())
+                .with_embeddings(false)
+                .with_auto_titles(false)
+                .with_thread_store(store),
+        )
+    }
+
+    /// Tests that edits of each thread land in its working directory.
+    ///
+    /// # Errors
+    /// Returns an error if a project, the thread store or a task fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_thread_edits_land_in_working_dir() -> Result<()> {
+        let backend = TempDir::new()?;
+        let infra = TempDir::new()?;
+        let store = Arc::new(Mutex::new(ThreadStore::new(
+            backend.path().join(".merlin").join("threads"),
+        )?));
+        let orchestrator = notes_orchestrator(&backend, Arc::clone(&store))?;
+
+        let (backend_thread, infra_thread) = {
+            let mut store = store
+                .lock()
+                .map_err(|err| RoutingError::Other(err.to_string()))?;
+            let backend_thread = store.create_thread("Backend".to_owned());
+            let infra_thread = store.create_thread("Infra".to_owned());
+            store.save_thread(&backend_thread)?;
+            store.save_thread(&infra_thread)?;
+            store.set_working_dir(infra_thread.id, Some(infra.path().to_path_buf()))?;
+            (backend_thread.id, infra_thread.id)
+        };
+        assert_eq!(
+            orchestrator.thread_workspace(Some(infra_thread)),
+            infra.path()
+        );
+
+        let (ui_channel, _receiver) = UiChannel::bounded(256);
+        orchestrator
+            .execute_task_in_thread(
+                Task::new("Write infra notes".to_owned()),
+                ui_channel.clone(),
+                infra_thread,
+            )
+            .await?;
+        assert!(infra.path().join(NOTES_FILE).exists());
+        assert!(!backend.path().join(NOTES_FILE).exists());
+
+        fs::remove_file(infra.path().join(NOTES_FILE))?;
+        orchestrator
+            .execute_task_in_thread(
+                Task::new("Write backend notes".to_owned()),
+                ui_channel,
+                backend_thread,
+            )
+            .await?;
+        assert!(backend.path().join(NOTES_FILE).exists());
+        assert!(!infra.path().join(NOTES_FILE).exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,248 @@
+//! `merlin audit tools`: tail and filter the tool call audit log
+
+use anyhow::Result;
+use merlin_tooling::{AuditOutcome, ToolAuditEntry, ToolAuditLog, parse_audit_entries};
+use serde_json::to_string;
+use std::io::{ErrorKind, Write as _, stderr, stdout};
+use std::path::Path;
+use tokio::fs;
+use tokio::time::{Duration, sleep};
+
+/// How often `--follow` checks the log for new entries
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+/// Number of task id characters shown per entry
+const TASK_ID_PREFIX_LEN: usize = 8;
+
+/// Arguments of the `audit tools` subcommand
+#[derive(Debug)]
+pub struct AuditArgs {
+    /// Only show calls of this tool
+    pub tool: Option<String>,
+    /// Only show calls of tasks whose id starts with this prefix
+    pub task: Option<String>,
+    /// Only show failed calls
+    pub errors: bool,
+    /// Number of most recent matching entries to show
+    pub lines: usize,
+    /// Keep printing entries as they are appended
+    pub follow: bool,
+}
+
+impl AuditArgs {
+    /// Whether an entry passes the filters
+    fn matches(&self, entry: &ToolAuditEntry) -> bool {
+        self.tool.as_ref().is_none_or(|tool| &entry.tool == tool)
+            && self.task.as_ref().is_none_or(|prefix| {
+                entry
+                    .task_id
+                    .as_ref()
+                    .is_some_and(|task_id| task_id.starts_with(prefix.as_str()))
+            })
+            && (!self.errors || entry.is_error())
+    }
+}
+
+/// Print the last matching audit entries, then follow the log if requested
+///
+/// # Errors
+/// Returns an error if the log cannot be read or output cannot be written
+pub async fn handle_audit_tools(project: &Path, args: &AuditArgs) -> Result<()> {
+    let path = ToolAuditLog::workspace_path(project);
+    let content = match fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound && !args.follow => {
+            writeln!(stderr(), "No tool calls audited yet ({})", path.display())?;
+            return Ok(());
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let consumed = complete_lines_len(&content);
+    stdout().write_all(tail_entries(&content[..consumed], args).as_bytes())?;
+    if args.follow {
+        follow_log(&path, args, consumed).await?;
+    }
+    Ok(())
+}
+
+/// Print matching entries appended after `offset` until interrupted
+///
+/// # Errors
+/// Returns an error if the log cannot be read or output cannot be written
+async fn follow_log(path: &Path, args: &AuditArgs, mut offset: usize) -> Result<()> {
+    loop {
+        sleep(FOLLOW_INTERVAL).await;
+        let content = match fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+        // The log was truncated or replaced: start over
+        if content.len() < offset {
+            offset = 0;
+        }
+        let appended = &content[offset..];
+        let complete = complete_lines_len(appended);
+        let mut output = String::new();
+        for entry in parse_audit_entries(&appended[..complete]) {
+            if args.matches(&entry) {
+                output.push_str(&format_entry(&entry));
+                output.push('\n');
+            }
+        }
+        stdout().write_all(output.as_bytes())?;
+        offset += complete;
+    }
+}
+
+/// Length of `content` up to and including its last newline
+fn complete_lines_len(content: &str) -> usize {
+    content.rfind('\n').map_or(0, |index| index + 1)
+}
+
+/// Format the last `args.lines` matching entries, one per line
+fn tail_entries(content: &str, args: &AuditArgs) -> String {
+    let matching: Vec<_> = parse_audit_entries(content)
+        .into_iter()
+        .filter(|entry| args.matches(entry))
+        .collect();
+    let skip = matching.len().saturating_sub(args.lines);
+    let mut output = String::new();
+    for entry in &matching[skip..] {
+        output.push_str(&format_entry(entry));
+        output.push('\n');
+    }
+    output
+}
+
+/// Format one entry as `timestamp  task  tool  outcome  duration  input`
+fn format_entry(entry: &ToolAuditEntry) -> String {
+    let task = entry.task_id.as_deref().map_or("-", |task_id| {
+        task_id.get(..TASK_ID_PREFIX_LEN).unwrap_or(task_id)
+    });
+    let outcome = match &entry.outcome {
+        AuditOutcome::Success => "ok".to_owned(),
+        AuditOutcome::Error { message } => format!("error: {}", first_line(message)),
+    };
+    format!(
+        "{}  {task:<8}  {:<16}  {outcome}  {}ms  {}",
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        entry.tool,
+        entry.duration_ms,
+        to_string(&entry.input).unwrap_or_default()
+    )
+}
+
+/// First line of a possibly multi-line message
+fn first_line(message: &str) -> &str {
+    message.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone as _, Utc};
+    use serde_json::{Value, json};
+
+    /// Builds an audit entry line
+    fn line(task_id: &str, tool: &str, input: &Value, outcome: &AuditOutcome) -> String {
+        let entry = ToolAuditEntry {
+            timestamp: Utc
+                .with_ymd_and_hms(2026, 1, 2, 3, 4, 5)
+                .single()
+                .unwrap_or_default(),
+            task_id: Some(task_id.to_owned()),
+            tool: tool.to_owned(),
+            input: input.clone(),
+            outcome: outcome.clone(),
+            duration_ms: 7,
+        };
+        format!("{}\n", to_string(&entry).unwrap_or_default())
+    }
+
+    /// Audit log with calls from two tasks, one of them failing
+    fn sample_log() -> String {
+        let failed = AuditOutcome::Error {
+            message: "Command failed with exit code: 1\nstderr".to_owned(),
+        };
+        [
+            line(
+                "aaaa1111-task",
+                "readFile",
+                &json!("src/lib.rs"),
+                &AuditOutcome::Success,
+            ),
+            line("bbbb2222-task", "bash", &json!("cargo test"), &failed),
+            line(
+                "aaaa1111-task",
+                "readFile",
+                &json!("README.md"),
+                &AuditOutcome::Success,
+            ),
+            "not an entry\n".to_owned(),
+            line(
+                "bbbb2222-task",
+                "readFile",
+                &json!("Cargo.toml"),
+                &AuditOutcome::Success,
+            ),
+        ]
+        .concat()
+    }
+
+    /// Arguments showing up to `lines` entries without filters
+    const fn unfiltered(lines: usize) -> AuditArgs {
+        AuditArgs {
+            tool: None,
+            task: None,
+            errors: false,
+            lines,
+            follow: false,
+        }
+    }
+
+    /// Tests that only the last matching entries are shown.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_tail_filters_entries() {
+        let log = sample_log();
+
+        let last_two = tail_entries(&log, &unfiltered(2));
+        assert_eq!(
+            last_two,
+            "2026-01-02 03:04:05  aaaa1111  readFile          ok  7ms  \"README.md\"\n\
+             2026-01-02 03:04:05  bbbb2222  readFile          ok  7ms  \"Cargo.toml\"\n"
+        );
+
+        let task_reads = AuditArgs {
+            tool: Some("readFile".to_owned()),
+            task: Some("aaaa".to_owned()),
+            ..unfiltered(20)
+        };
+        assert_eq!(tail_entries(&log, &task_reads).lines().count(), 2);
+
+        let errors = AuditArgs {
+            errors: true,
+            ..unfiltered(20)
+        };
+        assert_eq!(
+            tail_entries(&log, &errors),
+            "2026-01-02 03:04:05  bbbb2222  bash              error: Command failed with exit code: 1  7ms  \"cargo test\"\n"
+        );
+    }
+
+    /// Tests that a partially written last line is left for the next read.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_complete_lines_len() {
+        assert_eq!(complete_lines_len(""), 0);
+        assert_eq!(complete_lines_len("{\"tool\""), 0);
+        assert_eq!(complete_lines_len("{}\n{\"tool\""), 3);
+        assert_eq!(complete_lines_len("{}\n{}\n"), 6);
+    }
+}
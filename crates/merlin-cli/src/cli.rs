@@ -3,6 +3,11 @@ use std::process::exit;
 
 use pico_args::{Arguments, Error};
 
+use crate::audit::AuditArgs;
+use crate::headless::RunArgs;
+use crate::prompts::PromptsCommand;
+use crate::setup::SetupArgs;
+
 /// Validation mode for task execution
 #[derive(Clone, Copy, Debug)]
 pub enum Validation {
@@ -12,6 +17,66 @@ pub enum Validation {
     Disabled,
 }
 
+/// Whether context retrieval embeds files for vector search
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Embeddings {
+    /// Embeddings as configured
+    Configured,
+    /// Embeddings disabled (BM25 keyword search only)
+    Disabled,
+}
+
+/// Whether a missing embedding model is downloaded from Ollama before indexing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModelPull {
+    /// Download the model automatically
+    Automatic,
+    /// Leave pulling the model to the user
+    Manual,
+}
+
+/// Subcommand to run instead of the interactive session
+#[derive(Debug)]
+pub enum Command {
+    /// List stored threads
+    ThreadList {
+        /// Only list threads carrying this tag
+        tag: Option<String>,
+    },
+    /// Run the setup wizard for provider keys and models
+    Setup(SetupArgs),
+    /// Show where configuration is loaded from
+    Config {
+        /// Print the merged global and project config instead of the file locations
+        effective: bool,
+    },
+    /// Show audited tool calls
+    AuditTools(AuditArgs),
+    /// List, show or edit prompt template overrides
+    Prompts(PromptsCommand),
+    /// Run one task without the TUI
+    Run(RunArgs),
+    /// Export recorded request metrics as CSV
+    MetricsExport {
+        /// File to write, stdout if unset
+        output: Option<PathBuf>,
+        /// One row per day and model instead of one per request
+        daily: bool,
+    },
+    /// List the models Merlin routes to
+    ModelsList {
+        /// List the models installed in Ollama instead
+        local: bool,
+    },
+    /// Save the most recent task as an SVG screenshot of the TUI
+    Screenshot {
+        /// Terminal width in cells
+        width: u16,
+        /// Terminal height in cells
+        height: u16,
+    },
+}
+
 /// Command-line arguments for Merlin CLI
 #[derive(Debug)]
 pub struct Cli {
@@ -26,6 +91,24 @@ pub struct Cli {
 
     /// Dump full context to debug.log before each model call
     pub context_dump: bool,
+
+    /// Print why each context file was included to stderr after building context
+    pub debug_context: bool,
+
+    /// Whether `--no-embed` disabled vector embeddings (BM25 keyword search only)
+    pub embeddings: Embeddings,
+
+    /// Whether `--no-auto-pull` disabled downloading a missing embedding model
+    pub model_pull: ModelPull,
+
+    /// OTLP/HTTP endpoint to export tracing spans to (requires the `otlp` feature)
+    pub otlp_endpoint: Option<String>,
+
+    /// Record the session as an integration-test fixture written to this path
+    pub record_fixture: Option<PathBuf>,
+
+    /// Subcommand to run (None starts the interactive session)
+    pub command: Option<Command>,
 }
 
 impl Cli {
@@ -43,7 +126,9 @@ impl Cli {
             exit(0);
         }
 
-        let cli = Self {
+        let subcommand: Option<String> = pargs.subcommand()?;
+
+        let mut cli = Self {
             project: pargs
                 .opt_value_from_str(["-p", "--project"])?
                 .unwrap_or_else(|| PathBuf::from(".")),
@@ -61,6 +146,43 @@ impl Cli {
                 }
             },
             context_dump: pargs.contains("--context-dump"),
+            debug_context: pargs.contains("--debug-context"),
+            embeddings: if pargs.contains("--no-embed") {
+                Embeddings::Disabled
+            } else {
+                Embeddings::Configured
+            },
+            model_pull: if pargs.contains("--no-auto-pull") {
+                ModelPull::Manual
+            } else {
+                ModelPull::Automatic
+            },
+            otlp_endpoint: pargs.opt_value_from_str("--otlp-endpoint")?,
+            record_fixture: pargs.opt_value_from_str("--record-fixture")?,
+            command: None,
+        };
+
+        cli.command = match subcommand.as_deref() {
+            None => None,
+            Some("thread") => Some(parse_thread_command(&mut pargs)?),
+            Some("setup") => Some(parse_setup_command(&mut pargs)?),
+            Some("config") => Some(Command::Config {
+                effective: pargs.contains("--effective"),
+            }),
+            Some("audit") => Some(parse_audit_command(&mut pargs)?),
+            Some("prompts") => Some(parse_prompts_command(&mut pargs)?),
+            Some("run") => Some(parse_run_command(&mut pargs)?),
+            Some("metrics") => Some(parse_metrics_command(&mut pargs)?),
+            Some("models") => Some(parse_models_command(&mut pargs, cli.local)?),
+            Some("screenshot") => Some(Command::Screenshot {
+                width: pargs.opt_value_from_str("--width")?.unwrap_or(120),
+                height: pargs.opt_value_from_str("--height")?.unwrap_or(40),
+            }),
+            Some(other) => {
+                return Err(Error::ArgumentParsingFailed {
+                    cause: format!("unknown command: {other}"),
+                });
+            }
         };
 
         // Check for any remaining arguments
@@ -73,20 +195,254 @@ impl Cli {
     }
 }
 
-fn print_help() {
-    const HELP_TEXT: &str = "\
+/// Parses the arguments of the `thread` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the thread action is missing or unknown
+fn parse_thread_command(pargs: &mut Arguments) -> Result<Command, Error> {
+    let tag: Option<String> = pargs.opt_value_from_str("--tag")?;
+    let action: Option<String> = pargs.opt_free_from_str()?;
+    match action.as_deref() {
+        Some("list") => Ok(Command::ThreadList { tag }),
+        Some(other) => Err(Error::ArgumentParsingFailed {
+            cause: format!("unknown thread command: {other}"),
+        }),
+        None => Err(Error::ArgumentParsingFailed {
+            cause: "missing thread command (expected: list)".to_owned(),
+        }),
+    }
+}
+
+/// Parses the arguments of the `metrics` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the metrics action is missing or unknown, or a flag value is invalid
+fn parse_metrics_command(pargs: &mut Arguments) -> Result<Command, Error> {
+    let output: Option<PathBuf> = pargs.opt_value_from_str(["-o", "--output"])?;
+    let daily = pargs.contains("--daily");
+    let action: Option<String> = pargs.opt_free_from_str()?;
+    match action.as_deref() {
+        Some("export") => Ok(Command::MetricsExport { output, daily }),
+        Some(other) => Err(Error::ArgumentParsingFailed {
+            cause: format!("unknown metrics command: {other}"),
+        }),
+        None => Err(Error::ArgumentParsingFailed {
+            cause: "missing metrics command (expected: export)".to_owned(),
+        }),
+    }
+}
+
+/// Parses the arguments of the `models` subcommand, `local` being the `--local` flag
+///
+/// # Errors
+///
+/// Returns an error if the models action is missing or unknown
+fn parse_models_command(pargs: &mut Arguments, local: bool) -> Result<Command, Error> {
+    let action: Option<String> = pargs.opt_free_from_str()?;
+    match action.as_deref() {
+        Some("list") => Ok(Command::ModelsList { local }),
+        Some(other) => Err(Error::ArgumentParsingFailed {
+            cause: format!("unknown models command: {other}"),
+        }),
+        None => Err(Error::ArgumentParsingFailed {
+            cause: "missing models command (expected: list)".to_owned(),
+        }),
+    }
+}
+
+/// Parses the arguments of the `audit` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the audit target is missing or unknown, or a flag value is invalid
+fn parse_audit_command(pargs: &mut Arguments) -> Result<Command, Error> {
+    let args = AuditArgs {
+        tool: pargs.opt_value_from_str("--tool")?,
+        task: pargs.opt_value_from_str("--task")?,
+        errors: pargs.contains("--errors"),
+        lines: pargs.opt_value_from_str(["-n", "--lines"])?.unwrap_or(20),
+        follow: pargs.contains(["-f", "--follow"]),
+    };
+    let target: Option<String> = pargs.opt_free_from_str()?;
+    match target.as_deref() {
+        Some("tools") => Ok(Command::AuditTools(args)),
+        Some(other) => Err(Error::ArgumentParsingFailed {
+            cause: format!("unknown audit command: {other}"),
+        }),
+        None => Err(Error::ArgumentParsingFailed {
+            cause: "missing audit command (expected: tools)".to_owned(),
+        }),
+    }
+}
+
+/// Parses the arguments of the `prompts` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the prompts action or prompt name is missing, or the action is unknown
+fn parse_prompts_command(pargs: &mut Arguments) -> Result<Command, Error> {
+    let action: Option<String> = pargs.opt_free_from_str()?;
+    let command = match action.as_deref() {
+        Some("list") => PromptsCommand::List,
+        Some("show") => PromptsCommand::Show {
+            name: pargs.free_from_str()?,
+        },
+        Some("edit") => PromptsCommand::Edit {
+            name: pargs.free_from_str()?,
+        },
+        Some(other) => {
+            return Err(Error::ArgumentParsingFailed {
+                cause: format!("unknown prompts command: {other}"),
+            });
+        }
+        None => {
+            return Err(Error::ArgumentParsingFailed {
+                cause: "missing prompts command (expected: list, show, edit)".to_owned(),
+            });
+        }
+    };
+    Ok(Command::Prompts(command))
+}
+
+/// Parses the arguments of the `run` subcommand
+///
+/// # Errors
+///
+/// Returns an error if the prompt is missing or a flag value is invalid
+fn parse_run_command(pargs: &mut Arguments) -> Result<Command, Error> {
+    let mut args = RunArgs {
+        json: pargs.contains("--json"),
+        model: pargs.opt_value_from_str("--model")?,
+        max_cost: pargs.opt_value_from_str("--max-cost")?,
+        dry_run: pargs.contains("--dry-run"),
+        context: pargs.values_from_str("--context")?,
+        answers: pargs.values_from_str("--answer")?,
+        ..RunArgs::default()
+    };
+    args.prompt = pargs
+        .opt_free_from_str()?
+        .ok_or_else(|| Error::ArgumentParsingFailed {
+            cause: "missing prompt (expected: merlin run \"<PROMPT>\")".to_owned(),
+        })?;
+    Ok(Command::Run(args))
+}
+
+/// Parses the flags of the `setup` subcommand
+///
+/// # Errors
+///
+/// Returns an error if a flag value is missing
+fn parse_setup_command(pargs: &mut Arguments) -> Result<Command, Error> {
+    Ok(Command::Setup(SetupArgs {
+        non_interactive: pargs.contains("--non-interactive"),
+        openrouter_key: pargs.opt_value_from_str("--openrouter-key")?,
+        groq_key: pargs.opt_value_from_str("--groq-key")?,
+        high_model: pargs.opt_value_from_str("--high-model")?,
+        medium_model: pargs.opt_value_from_str("--medium-model")?,
+        ollama_model: pargs.opt_value_from_str("--ollama-model")?,
+        skip_checks: pargs.contains("--skip-checks"),
+    }))
+}
+
+/// Usage printed by `--help`
+const HELP_TEXT: &str = "\
 merlin - Intelligent AI coding assistant with multi-model routing
 
 USAGE:
     merlin [OPTIONS]
+    merlin thread list [--tag <TAG>]
+    merlin setup [--non-interactive] [SETUP OPTIONS]
+    merlin config [--effective]
+    merlin audit tools [AUDIT OPTIONS]
+    merlin prompts <list|show|edit> [<NAME>]
+    merlin run <PROMPT> [RUN OPTIONS]
+    merlin metrics export [--output <PATH>] [--daily]
+    merlin models list [--local]
+    merlin screenshot [--width <N>] [--height <N>]
 
 OPTIONS:
     -p, --project <PATH>         Project root directory [default: .]
     --local                      Use only local models (Ollama), disable remote tiers
     --validation <MODE>          Validation mode (enabled/disabled) [default: enabled]
     --context-dump               Dump full context to debug.log before each model call
+    --debug-context              Print why each context file was included (scores, source,
+                                 matched chunks) to stderr as Markdown; redirect it, e.g.
+                                 2> context.md, to keep the TUI intact
+    --no-embed                   Disable vector embeddings and search context by keywords only
+                                 (no Ollama needed; same as [context] embedding_enabled = false)
+    --no-auto-pull               Don't download a missing embedding model from Ollama; search
+                                 uses keywords only until it is pulled by hand
+    --otlp-endpoint <URL>        Export traces over OTLP/HTTP (e.g. http://localhost:4318/v1/traces)
+    --record-fixture <PATH>      Record the session as a replayable integration-test fixture
+                                 (written on exit, secrets in prompts redacted)
     -h, --help                   Print help information
+
+COMMANDS:
+    thread list                  List threads
+        --tag <TAG>              Only list threads carrying this tag
+    setup                        Configure provider keys and default models
+                                 (runs automatically when ~/.merlin/config.toml is missing)
+    config                       Show the global and project config file locations
+        --effective              Print the merged config (project <project>/.merlin/config.toml
+                                 overrides ~/.merlin/config.toml, API keys redacted)
+    audit tools                  Show tool calls from <project>/.merlin/tool_audit.jsonl
+                                 (file contents and secrets are redacted)
+    prompts list                 List prompts and where each is loaded from: the project
+                                 override <project>/.merlin/prompts/<NAME>.md, the user
+                                 override ~/.merlin/prompts/<NAME>.md, or the default
+    prompts show <NAME>          Print a prompt as it will be used
+    prompts edit <NAME>          Create the project override from the default if missing
+                                 and open it in $VISUAL or $EDITOR
+    run <PROMPT>                 Run one task without the TUI and print its response;
+                                 exits with 0 on success, 1 on failure, 2 on failed
+                                 validation and 3 when over --max-cost
+    metrics export               Export the metrics of every request made in the project
+                                 as CSV, one row per request
+        -o, --output <PATH>      File to write [default: stdout]
+        --daily                  One row per day and model, with totals and means
+    models list                  List the models tasks are routed to, with their tier and
+                                 difficulty levels, then the rest of the model catalog, with
+                                 context windows and USD prices per million tokens (catalog
+                                 cached in ~/.merlin/models.json, see [catalog] in the config)
+        --local                  List the models installed in Ollama with their size and
+                                 modification date instead
+    screenshot                   Save the most recent task as rendered by the TUI to
+                                 <project>/.merlin/screenshots/<timestamp>.svg
+        --width <N>              Terminal width in cells [default: 120]
+        --height <N>             Terminal height in cells [default: 40]
+
+SETUP OPTIONS:
+    --non-interactive            Take all answers from flags (implied without a terminal)
+    --openrouter-key <KEY>       OpenRouter API key [env: OPENROUTER_API_KEY]
+    --groq-key <KEY>             Groq API key [env: GROQ_API_KEY]
+    --high-model <MODEL>         Default high-complexity OpenRouter model
+    --medium-model <MODEL>       Default medium-complexity OpenRouter model
+    --ollama-model <MODEL>       Enable local models through Ollama with this model
+    --skip-checks                Skip live key, model and Ollama checks
+
+RUN OPTIONS:
+    --json                       Stream task events to stderr as NDJSON and write the
+                                 result (response, file changes, validation, tokens,
+                                 cost) to stdout as JSON
+    --model <ID>                 Send every request to this model (e.g. qwen2.5-coder:7b)
+    --max-cost <USD>             Cancel the task once its estimated cost exceeds this
+    --dry-run                    Work on a scratch copy of the project and only report
+                                 the changes the task would make
+    --context <PATH>             Pin a file to the task's context (repeatable)
+    --answer <TEXT>              Answer the task's next question (repeatable, in order);
+                                 a question with no answer left fails the run
+
+AUDIT OPTIONS:
+    --tool <NAME>                Only show calls of this tool (e.g. bash, writeFile)
+    --task <ID>                  Only show calls of tasks whose id starts with ID
+    --errors                     Only show failed calls
+    -n, --lines <N>              Number of most recent calls to show [default: 20]
+    -f, --follow                 Keep printing calls as they are made
 ";
+
+fn print_help() {
     // Help text is printed to stdout by convention for CLI tools
     {
         use std::io::{Write as _, stdout};
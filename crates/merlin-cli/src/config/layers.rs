@@ -0,0 +1,227 @@
+//! Layering of the global config file and a per-project overlay
+//!
+//! Both files are kept as TOML tables. The project table is merged over the
+//! global one key by key (project wins), and when the effective config is saved
+//! every value is written back to the file that supplied it.
+
+use std::path::PathBuf;
+use toml::{Table, Value};
+
+/// Global-only key that lets project files set API keys
+pub const ALLOW_PROJECT_SECRETS: &str = "allow_project_secrets";
+
+/// Keys holding API keys, which only the global file may set unless it allows project secrets
+pub const SECRET_KEYS: &[&[&str]] = &[
+    &["providers", "openrouter_key"],
+    &["api_keys", "groq_api_key"],
+    &["api_keys", "openrouter_api_key"],
+];
+
+/// Placeholder shown instead of secret values
+const REDACTED: &str = "<redacted>";
+
+/// Config file of a single project (`<project>/.merlin/config.toml`)
+#[derive(Debug, Clone)]
+pub struct ProjectLayer {
+    /// Path of the project config file
+    pub path: PathBuf,
+    /// Contents of the file, including keys that are ignored in the overlay
+    pub table: Table,
+    /// Whether the global config allows this file to set API keys
+    pub allow_secrets: bool,
+}
+
+impl ProjectLayer {
+    /// Returns the keys of the file that are ignored when merging, as dotted paths
+    pub fn ignored_keys(&self) -> Vec<String> {
+        let mut ignored = Vec::new();
+        if self.table.contains_key(ALLOW_PROJECT_SECRETS) {
+            ignored.push(ALLOW_PROJECT_SECRETS.to_owned());
+        }
+        if !self.allow_secrets {
+            ignored.extend(
+                SECRET_KEYS
+                    .iter()
+                    .filter(|path| get_path(&self.table, path).is_some())
+                    .map(|path| path.join(".")),
+            );
+        }
+        ignored
+    }
+
+    /// Returns the values this file contributes to the effective config
+    fn overlay(&self) -> Table {
+        let mut overlay = self.table.clone();
+        overlay.remove(ALLOW_PROJECT_SECRETS);
+        if !self.allow_secrets {
+            for path in SECRET_KEYS {
+                remove_path(&mut overlay, path);
+            }
+        }
+        overlay
+    }
+}
+
+/// Which config files changed while absorbing an edit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChangedLayers {
+    /// The global config file needs to be written
+    pub global: bool,
+    /// The project config file needs to be written
+    pub project: bool,
+}
+
+/// Global config and optional project overlay behind the effective config
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    /// Contents of the global config file
+    pub global: Table,
+    /// Project overlay, if the project has a config file
+    pub project: Option<ProjectLayer>,
+    /// Effective config as of the last load or save
+    pub effective: Table,
+}
+
+impl ConfigLayers {
+    /// Returns the global table with the project overlay merged over it
+    pub fn merged(&self) -> Table {
+        let mut merged = self.global.clone();
+        if let Some(project) = &self.project {
+            merge_into(&mut merged, project.overlay());
+        }
+        merged
+    }
+
+    /// Routes the values of an edited effective config back to their layers
+    ///
+    /// Values the project overlay sets are updated in the project file, all other
+    /// values in the global file. Values that disappeared since the last save
+    /// (options set back to `None`) are removed from both files. Keys the config
+    /// does not know about are left untouched.
+    pub fn absorb(&mut self, effective: Table) -> ChangedLayers {
+        let overlay = self.project.as_ref().map(ProjectLayer::overlay);
+        let mut global = self.global.clone();
+        let mut project = self.project.as_ref().map(|layer| layer.table.clone());
+
+        let mut removed = Vec::new();
+        collect_leaves(&self.effective, &mut Vec::new(), &mut |path, _| {
+            if get_path(&effective, path).is_none() {
+                removed.push(path.to_vec());
+            }
+        });
+        for path in &removed {
+            remove_path(&mut global, path.as_slice());
+            if let Some(table) = project.as_mut() {
+                remove_path(table, path.as_slice());
+            }
+        }
+        drop(removed);
+
+        collect_leaves(&effective, &mut Vec::new(), &mut |path, value| {
+            let owned_by_project = overlay
+                .as_ref()
+                .is_some_and(|overlay| get_path(overlay, path).is_some());
+            match project.as_mut() {
+                Some(table) if owned_by_project => set_path(table, path, value.clone()),
+                _ => set_path(&mut global, path, value.clone()),
+            }
+        });
+
+        let changed = ChangedLayers {
+            global: global != self.global,
+            project: project.as_ref() != self.project.as_ref().map(|layer| &layer.table),
+        };
+        self.global = global;
+        if let (Some(layer), Some(table)) = (self.project.as_mut(), project) {
+            layer.table = table;
+        }
+        self.effective = effective;
+        changed
+    }
+}
+
+/// Merges `overlay` into `base`, recursing into tables present in both
+pub fn merge_into(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base_table)), Value::Table(overlay_table)) => {
+                merge_into(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Replaces every secret value present in `table` with a placeholder
+pub fn redact_secrets(table: &mut Table) {
+    for path in SECRET_KEYS {
+        if get_path(table, path).is_some() {
+            set_path(table, path, Value::String(REDACTED.to_owned()));
+        }
+    }
+}
+
+/// Calls `visit` with the path and value of every non-table value in `table`
+fn collect_leaves<'table>(
+    table: &'table Table,
+    prefix: &mut Vec<&'table str>,
+    visit: &mut impl FnMut(&[&'table str], &'table Value),
+) {
+    for (key, value) in table {
+        prefix.push(key);
+        if let Value::Table(nested) = value {
+            collect_leaves(nested, prefix, visit);
+        } else {
+            visit(prefix, value);
+        }
+        prefix.pop();
+    }
+}
+
+/// Returns the value at a dotted path
+fn get_path<'table>(table: &'table Table, path: &[impl AsRef<str>]) -> Option<&'table Value> {
+    let (last, parents) = path.split_last()?;
+    let mut current = table;
+    for key in parents {
+        current = current.get(key.as_ref())?.as_table()?;
+    }
+    current.get(last.as_ref())
+}
+
+/// Sets the value at a dotted path, creating intermediate tables
+fn set_path(table: &mut Table, path: &[impl AsRef<str>], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for key in parents {
+        let entry = current
+            .entry(key.as_ref())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        let Value::Table(nested) = entry else {
+            return;
+        };
+        current = nested;
+    }
+    current.insert(last.as_ref().to_owned(), value);
+}
+
+/// Removes the value at a dotted path, if present
+fn remove_path(table: &mut Table, path: &[impl AsRef<str>]) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for key in parents {
+        let Some(Value::Table(nested)) = current.get_mut(key.as_ref()) else {
+            return;
+        };
+        current = nested;
+    }
+    current.remove(last.as_ref());
+}
@@ -1,26 +1,40 @@
 //! Configuration management for Merlin CLI
 //!
-//! Handles loading and auto-saving configuration to `~/.merlin/config.toml`.
+//! Handles loading and auto-saving configuration to `~/.merlin/config.toml`, with an
+//! optional per-project overlay in `<project>/.merlin/config.toml` merged over it.
 //! Uses a `Drop`-based auto-save mechanism: when you get a mutable reference and drop it,
-//! the config is automatically persisted to disk.
+//! the config is automatically persisted to disk, each value to the file it came from.
 
+mod layers;
+mod storage;
+
+pub use layers::{ALLOW_PROJECT_SECRETS, ConfigLayers, ProjectLayer};
+
+use crate::ui::notifications::NotificationConfig;
+use crate::ui::output_buffer::OutputLimits;
 use crate::ui::theme::Theme;
 use dirs::home_dir;
-use merlin_core::config::{ApiKeys, TierConfig};
+use layers::redact_secrets;
+use merlin_core::config::{
+    ApiKeys, CatalogConfig, GitConfig, RoutingConfig, TierConfig, ValidationConfig,
+    default_compression_threshold, default_max_retry_delay_secs,
+};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::io;
 use std::ops::{Deref, DerefMut};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
-use tokio::fs as async_fs;
-use toml::{from_str, to_string_pretty};
+use storage::{to_table, to_toml};
 
 const ENV_OPENROUTER_API_KEY: &str = "OPENROUTER_API_KEY";
 
 /// Main configuration for the agentic optimizer
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Config {
+    /// Allow project config files to set API keys (only read from the global config)
+    #[serde(default)]
+    pub allow_project_secrets: bool,
     /// Provider configuration (API keys and models)
     #[serde(default)]
     pub providers: ProvidersConfig,
@@ -33,6 +47,39 @@ pub struct Config {
     /// API keys for model providers (for `RoutingConfig` compatibility)
     #[serde(default)]
     pub api_keys: ApiKeys,
+    /// Long-running task notifications
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    /// Bounds on the task output kept in memory and on disk
+    #[serde(default)]
+    pub output: OutputLimits,
+    /// Git operations available to agents
+    #[serde(default)]
+    pub git: GitConfig,
+    /// Context retrieval settings
+    #[serde(default)]
+    pub context: ContextConfig,
+    /// Model catalog refresh settings
+    #[serde(default)]
+    pub catalog: CatalogConfig,
+    /// Validation of task results, including formatters for changed files
+    #[serde(default)]
+    pub validation: ValidationConfig,
+}
+
+impl Config {
+    /// Returns the routing settings of this config
+    pub fn routing_config(&self) -> RoutingConfig {
+        RoutingConfig {
+            tiers: self.tiers.clone(),
+            api_keys: self.api_keys.clone(),
+            git: self.git.clone(),
+            conversation_compression_threshold: self.context.conversation_compression_threshold,
+            catalog: self.catalog.clone(),
+            max_retry_delay_secs: default_max_retry_delay_secs(),
+            validation: self.validation.clone(),
+        }
+    }
 }
 
 /// Configuration for remote model providers
@@ -59,35 +106,89 @@ impl Default for ProvidersConfig {
     }
 }
 
+/// Context retrieval settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextConfig {
+    /// Embed files for semantic search (needs Ollama); keyword search is used otherwise
+    #[serde(default = "default_enabled")]
+    pub embedding_enabled: bool,
+    /// Stub files a thread already showed the model, or diff them if changed
+    #[serde(default = "default_enabled")]
+    pub dedupe_repeated_files: bool,
+    /// Estimated tokens of conversation history above which the oldest turns are summarized
+    #[serde(default = "default_compression_threshold")]
+    pub conversation_compression_threshold: Option<usize>,
+    /// Follow changes made to the project outside Merlin, reloading the index when a manifest changes
+    #[serde(default)]
+    pub watch_mode: bool,
+}
+
+/// Embeddings and deduplication are used unless disabled
+const fn default_enabled() -> bool {
+    true
+}
+
+impl Default for ContextConfig {
+    fn default() -> Self {
+        Self {
+            embedding_enabled: default_enabled(),
+            dedupe_repeated_files: default_enabled(),
+            conversation_compression_threshold: default_compression_threshold(),
+            watch_mode: false,
+        }
+    }
+}
+
 /// Shared configuration manager with auto-save on mutable drop
 #[derive(Clone)]
 pub struct ConfigManager {
     /// Shared config state
     pub inner: Arc<RwLock<Config>>,
-    /// Path to config file
+    /// Path to the global config file
     pub config_path: PathBuf,
+    /// Global and project layers the config was merged from
+    pub layers: Arc<RwLock<ConfigLayers>>,
 }
 
 impl ConfigManager {
-    /// Creates a new config manager
+    /// Creates a config manager for the global config only
     ///
     /// # Errors
     /// Returns an error if the home directory cannot be determined or config directory cannot be created
     pub async fn new() -> io::Result<Self> {
-        let config_path = Self::get_config_path()?;
+        Self::load(Self::get_config_path()?, None).await
+    }
 
-        // Ensure ~/.merlin directory exists
-        if let Some(parent) = config_path.parent() {
-            async_fs::create_dir_all(parent).await?;
-        }
+    /// Creates a config manager that merges `<project_root>/.merlin/config.toml` over the global config
+    ///
+    /// # Errors
+    /// Returns an error if the home directory cannot be determined or either config file is invalid
+    pub async fn for_project(project_root: &Path) -> io::Result<Self> {
+        Self::load(Self::get_config_path()?, Some(project_root)).await
+    }
 
-        // Load or create config
-        let config = Self::load_from_disk(&config_path).await?;
+    /// Loads the config at `config_path`, merging the project overlay if a project root is given
+    ///
+    /// Project files may not set API keys unless the global config sets
+    /// `allow_project_secrets`; such keys are ignored with a warning.
+    ///
+    /// # Errors
+    /// Returns an error if the config directory cannot be created or a config file cannot be read or parsed
+    pub async fn load(config_path: PathBuf, project_root: Option<&Path>) -> io::Result<Self> {
+        storage::load(config_path, project_root).await
+    }
 
-        Ok(Self {
+    /// Creates a manager for an in-memory config that is saved to `config_path` as a whole
+    pub fn from_config(config: Config, config_path: PathBuf) -> Self {
+        let layers = ConfigLayers {
+            effective: to_table(&config).unwrap_or_default(),
+            ..ConfigLayers::default()
+        };
+        Self {
             inner: Arc::new(RwLock::new(config)),
             config_path,
-        })
+            layers: Arc::new(RwLock::new(layers)),
+        }
     }
 
     /// Gets the config file path (~/.merlin/config.toml)
@@ -104,36 +205,69 @@ impl ConfigManager {
         Ok(home.join(".merlin").join("config.toml"))
     }
 
-    /// Loads config from disk, returns default if file doesn't exist
+    /// Returns the path of a project's config file (`<project>/.merlin/config.toml`)
+    pub fn project_config_path(project_root: &Path) -> PathBuf {
+        project_root.join(".merlin").join("config.toml")
+    }
+
+    /// Returns the path of the loaded project config file, if the project has one
+    pub fn project_path(&self) -> Option<PathBuf> {
+        self.layers
+            .read()
+            .ok()?
+            .project
+            .as_ref()
+            .map(|layer| layer.path.clone())
+    }
+
+    /// Returns the keys of the project config file that are ignored when merging
+    pub fn ignored_project_keys(&self) -> Vec<String> {
+        self.layers
+            .read()
+            .ok()
+            .and_then(|layers| layers.project.as_ref().map(ProjectLayer::ignored_keys))
+            .unwrap_or_default()
+    }
+
+    /// Saves config to disk (async), writing each value to the file it came from
     ///
     /// # Errors
-    /// Returns error if file cannot be read or parsed
-    async fn load_from_disk(path: &PathBuf) -> io::Result<Config> {
-        if !async_fs::try_exists(path).await.unwrap_or(false) {
-            return Ok(Config::default());
-        }
+    /// Returns error if serialization fails or a file cannot be written
+    async fn save_to_disk(&self) -> io::Result<()> {
+        storage::save_to_disk(self).await
+    }
 
-        let contents = async_fs::read_to_string(path).await?;
-        from_str(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    /// Renders the effective config as TOML with API keys redacted
+    ///
+    /// # Errors
+    /// Returns error if lock is poisoned or the config cannot be serialized
+    pub fn effective_toml(&self) -> io::Result<String> {
+        let mut table = to_table(&*self.get()?)?;
+        redact_secrets(&mut table);
+        to_toml(&table)
     }
 
-    /// Saves config to disk (async)
+    /// Returns true if the config file has been written before
+    pub fn exists(&self) -> bool {
+        self.config_path.exists()
+    }
+
+    /// Applies `edit` to the config and waits until it is saved to disk
+    ///
+    /// Unlike `get_mut`, the write has finished when this returns, so it is safe
+    /// to call right before the process exits.
     ///
     /// # Errors
-    /// Returns error if serialization fails or file cannot be written
-    async fn save_to_disk(&self) -> io::Result<()> {
-        let toml_str = {
-            let config = self
+    /// Returns error if lock is poisoned or the config cannot be written
+    pub async fn update(&self, edit: impl FnOnce(&mut Config)) -> io::Result<()> {
+        {
+            let mut config = self
                 .inner
-                .read()
+                .write()
                 .map_err(|err| io::Error::other(format!("Lock poisoned: {err}")))?;
-
-            to_string_pretty(&*config)
-                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
-        };
-
-        async_fs::write(&self.config_path, toml_str).await?;
-        Ok(())
+            edit(&mut config);
+        }
+        self.save_to_disk().await
     }
 
     /// Gets immutable reference to config
@@ -210,87 +344,4 @@ impl Drop for ConfigGuard<'_> {
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-    use std::error::Error as StdError;
-    use tokio::time::{Duration, sleep};
-    use toml::to_string_pretty;
-
-    /// Tests default configuration values.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_config_default() {
-        let config = Config::default();
-        assert!(config.providers.high_model.is_some());
-        assert!(config.providers.medium_model.is_some());
-        assert_eq!(config.theme, Theme::default());
-    }
-
-    /// Tests providers configuration defaults.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_providers_config_default() {
-        let config = ProvidersConfig::default();
-        assert!(config.high_model.is_some());
-        assert!(config.medium_model.is_some());
-    }
-
-    /// Test configuration serialization
-    ///
-    /// # Errors
-    /// Returns error if serialization fails
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_config_serialization() -> Result<(), Box<dyn StdError>> {
-        let config = Config::default();
-        let toml_str = to_string_pretty(&config)?;
-        assert!(toml_str.contains("providers"));
-        assert!(toml_str.contains("theme"));
-        Ok(())
-    }
-
-    /// Test configuration manager creation
-    ///
-    /// # Errors
-    /// Returns error if manager creation or config access fails
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[tokio::test]
-    async fn test_config_manager_new() -> Result<(), Box<dyn StdError>> {
-        let manager = ConfigManager::new().await?;
-        let config = manager.get()?;
-        assert!(config.providers.high_model.is_some());
-        Ok(())
-    }
-
-    /// Test configuration manager mutable access
-    ///
-    /// # Errors
-    /// Returns error if manager creation or config modification fails
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[tokio::test]
-    async fn test_config_manager_get_mut() -> Result<(), Box<dyn StdError>> {
-        let manager = ConfigManager::new().await?;
-
-        {
-            let mut config_guard = manager.get_mut()?;
-            config_guard.theme = Theme::Nord;
-        } // Drop happens here, triggering auto-save
-
-        // Give async save a moment to complete
-        sleep(Duration::from_millis(100)).await;
-
-        let config = manager.get()?;
-        assert_eq!(config.theme, Theme::Nord);
-        Ok(())
-    }
-}
+mod tests;
@@ -0,0 +1,148 @@
+//! Reading, merging and writing the global and project config files
+
+use super::layers::merge_into;
+use super::{ALLOW_PROJECT_SECRETS, Config, ConfigLayers, ConfigManager, ProjectLayer};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::fs as async_fs;
+use toml::{Table, Value, from_str, to_string_pretty};
+
+/// Loads the config at `config_path`, merging the project overlay if a project root is given
+///
+/// # Errors
+/// Returns an error if the config directory cannot be created or a config file cannot be read or parsed
+pub(super) async fn load(
+    config_path: PathBuf,
+    project_root: Option<&Path>,
+) -> io::Result<ConfigManager> {
+    // Ensure ~/.merlin directory exists
+    if let Some(parent) = config_path.parent() {
+        async_fs::create_dir_all(parent).await?;
+    }
+
+    let global = load_table(&config_path).await?.unwrap_or_default();
+    let allow_secrets = global
+        .get(ALLOW_PROJECT_SECRETS)
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    let mut project = None;
+    if let Some(root) = project_root {
+        let path = ConfigManager::project_config_path(root);
+        let is_global = async_fs::canonicalize(&path).await.ok()
+            == async_fs::canonicalize(&config_path).await.ok();
+        if !is_global && let Some(table) = load_table(&path).await? {
+            let layer = ProjectLayer {
+                path,
+                table,
+                allow_secrets,
+            };
+            for key in layer.ignored_keys() {
+                tracing::warn!(
+                    "Ignoring `{key}` in {}: API keys belong in the global config \
+                         unless it sets `{ALLOW_PROJECT_SECRETS} = true`",
+                    layer.path.display()
+                );
+            }
+            project = Some(layer);
+        }
+    }
+
+    let mut layers = ConfigLayers {
+        global,
+        project,
+        effective: Table::new(),
+    };
+    // Fields neither file sets keep their defaults, so partial tables are valid
+    let mut merged = to_table(&Config::default())?;
+    merge_into(&mut merged, layers.merged());
+    let config: Config = Value::Table(merged)
+        .try_into()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    layers.effective = to_table(&config)?;
+
+    Ok(ConfigManager {
+        layers: Arc::new(RwLock::new(layers)),
+        ..ConfigManager::from_config(config, config_path)
+    })
+}
+
+/// Reads a config file as a TOML table, returns `None` if the file doesn't exist
+///
+/// # Errors
+/// Returns error if file cannot be read or parsed
+async fn load_table(path: &Path) -> io::Result<Option<Table>> {
+    if !async_fs::try_exists(path).await.unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let contents = async_fs::read_to_string(path).await?;
+    from_str(&contents).map(Some).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {err}", path.display()),
+        )
+    })
+}
+
+/// Saves config to disk, writing each value to the file it came from
+///
+/// # Errors
+/// Returns error if serialization fails or a file cannot be written
+pub(super) async fn save_to_disk(manager: &ConfigManager) -> io::Result<()> {
+    let effective = {
+        let config = manager
+            .inner
+            .read()
+            .map_err(|err| io::Error::other(format!("Lock poisoned: {err}")))?;
+        to_table(&config)?
+    };
+
+    let writes = {
+        let mut layers = manager
+            .layers
+            .write()
+            .map_err(|err| io::Error::other(format!("Lock poisoned: {err}")))?;
+        let changed = layers.absorb(effective);
+
+        let mut writes = Vec::new();
+        if changed.global || !manager.exists() {
+            writes.push((manager.config_path.clone(), to_toml(&layers.global)?));
+        }
+        if changed.project
+            && let Some(project) = &layers.project
+        {
+            writes.push((project.path.clone(), to_toml(&project.table)?));
+        }
+        writes
+    };
+
+    for (path, contents) in writes {
+        async_fs::write(path, contents).await?;
+    }
+    Ok(())
+}
+
+/// Serializes a config into a TOML table
+///
+/// # Errors
+/// Returns error if the config cannot be represented as a TOML table
+pub(super) fn to_table(config: &Config) -> io::Result<Table> {
+    match Value::try_from(config) {
+        Ok(Value::Table(table)) => Ok(table),
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Config did not serialize to a table",
+        )),
+        Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+    }
+}
+
+/// Renders a TOML table as file contents
+///
+/// # Errors
+/// Returns error if the table cannot be serialized
+pub(super) fn to_toml(table: &Table) -> io::Result<String> {
+    to_string_pretty(table).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
@@ -0,0 +1,271 @@
+//! Tests for config loading, project overlays and saving
+
+use super::*;
+use merlin_core::ProviderType;
+use std::error::Error as StdError;
+use std::fs;
+use tempfile::TempDir;
+use tokio::time::{Duration, sleep};
+use toml::{Table, from_str, to_string_pretty};
+
+const GLOBAL_CONFIG: &str = r#"
+theme = "Nord"
+
+[providers]
+openrouter_key = "global-key"
+high_model = "global/high"
+medium_model = "global/medium"
+
+[tiers]
+local_model = "global-local"
+provider_high = "groq"
+
+[api_keys]
+groq_api_key = "global-groq"
+"#;
+
+const PROJECT_CONFIG: &str = r#"
+allow_project_secrets = true
+build_timeout_seconds = 120
+
+[providers]
+openrouter_key = "project-key"
+high_model = "project/high"
+
+[tiers]
+provider_low = "local"
+
+[api_keys]
+groq_api_key = "project-groq"
+"#;
+
+/// Returns the global config path inside a temporary directory.
+fn global_config_path(dir: &TempDir) -> PathBuf {
+    dir.path().join("home").join(".merlin").join("config.toml")
+}
+
+/// Writes the global and project config files into a temporary home and project.
+///
+/// # Errors
+/// Returns error if the files cannot be written.
+fn write_configs(global: &str, project: &str) -> io::Result<TempDir> {
+    let dir = TempDir::new()?;
+    let project_path = ConfigManager::project_config_path(&dir.path().join("project"));
+    for (path, contents) in [(global_config_path(&dir), global), (project_path, project)] {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, contents)?;
+    }
+    Ok(dir)
+}
+
+/// Tests default configuration values.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_config_default() {
+    let config = Config::default();
+    assert!(config.providers.high_model.is_some());
+    assert!(config.providers.medium_model.is_some());
+    assert_eq!(config.theme, Theme::default());
+}
+
+/// Tests providers configuration defaults.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_providers_config_default() {
+    let config = ProvidersConfig::default();
+    assert!(config.high_model.is_some());
+    assert!(config.medium_model.is_some());
+}
+
+/// Test configuration serialization
+///
+/// # Errors
+/// Returns error if serialization fails
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_config_serialization() -> Result<(), Box<dyn StdError>> {
+    let config = Config::default();
+    let toml_str = to_string_pretty(&config)?;
+    assert!(toml_str.contains("providers"));
+    assert!(toml_str.contains("theme"));
+    Ok(())
+}
+
+/// Test configuration manager creation
+///
+/// # Errors
+/// Returns error if manager creation or config access fails
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_config_manager_new() -> Result<(), Box<dyn StdError>> {
+    let manager = ConfigManager::new().await?;
+    let config = manager.get()?;
+    assert!(config.providers.high_model.is_some());
+    Ok(())
+}
+
+/// Test configuration manager mutable access
+///
+/// # Errors
+/// Returns error if manager creation or config modification fails
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_config_manager_get_mut() -> Result<(), Box<dyn StdError>> {
+    let manager = ConfigManager::new().await?;
+
+    {
+        let mut config_guard = manager.get_mut()?;
+        config_guard.theme = Theme::Nord;
+    } // Drop happens here, triggering auto-save
+
+    // Give async save a moment to complete
+    sleep(Duration::from_millis(100)).await;
+
+    let config = manager.get()?;
+    assert_eq!(config.theme, Theme::Nord);
+    Ok(())
+}
+
+/// Tests that project values win field by field, including nested optional fields,
+/// and that project API keys are ignored unless the global config allows them.
+///
+/// # Errors
+/// Returns error if the configs cannot be written or loaded
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_project_overlay_precedence() -> Result<(), Box<dyn StdError>> {
+    let dir = write_configs(GLOBAL_CONFIG, PROJECT_CONFIG)?;
+    let project_root = dir.path().join("project");
+    let manager = ConfigManager::load(global_config_path(&dir), Some(&project_root)).await?;
+    let config = manager.get()?;
+
+    // Project wins where it sets a value, the global file fills the rest
+    assert_eq!(config.providers.high_model.as_deref(), Some("project/high"));
+    assert_eq!(
+        config.providers.medium_model.as_deref(),
+        Some("global/medium")
+    );
+    assert_eq!(config.tiers.provider_low, Some(ProviderType::Local));
+    assert_eq!(config.tiers.provider_high, Some(ProviderType::Groq));
+    assert_eq!(config.tiers.provider_mid, None);
+    assert_eq!(config.tiers.local_model, "global-local");
+    assert_eq!(config.theme, Theme::Nord);
+    // Fields neither file sets keep their defaults
+    assert_eq!(config.tiers.max_retries, TierConfig::default().max_retries);
+
+    // Secrets and the permission itself only count in the global file
+    assert!(!config.allow_project_secrets);
+    assert_eq!(
+        config.providers.openrouter_key.as_deref(),
+        Some("global-key")
+    );
+    assert_eq!(config.api_keys.groq_api_key.as_deref(), Some("global-groq"));
+    assert_eq!(
+        manager.project_path(),
+        Some(ConfigManager::project_config_path(&project_root))
+    );
+    Ok(())
+}
+
+/// Tests that the global config can allow project files to set API keys.
+///
+/// # Errors
+/// Returns error if the configs cannot be written or loaded
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_project_secrets_allowed_by_global() -> Result<(), Box<dyn StdError>> {
+    let global = format!("allow_project_secrets = true\n{GLOBAL_CONFIG}");
+    let dir = write_configs(&global, PROJECT_CONFIG)?;
+    let manager =
+        ConfigManager::load(global_config_path(&dir), Some(&dir.path().join("project"))).await?;
+    let config = manager.get()?;
+
+    assert_eq!(
+        config.providers.openrouter_key.as_deref(),
+        Some("project-key")
+    );
+    assert_eq!(
+        config.api_keys.groq_api_key.as_deref(),
+        Some("project-groq")
+    );
+
+    let effective = manager.effective_toml()?;
+    assert!(effective.contains("<redacted>"));
+    assert!(!effective.contains("project-key"));
+    Ok(())
+}
+
+/// Tests that saving writes each value back to the file it came from.
+///
+/// # Errors
+/// Returns error if the configs cannot be written, loaded or saved
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_save_writes_values_to_their_source() -> Result<(), Box<dyn StdError>> {
+    let dir = write_configs(GLOBAL_CONFIG, PROJECT_CONFIG)?;
+    let project_root = dir.path().join("project");
+    let global_path = global_config_path(&dir);
+    let manager = ConfigManager::load(global_path.clone(), Some(&project_root)).await?;
+
+    manager
+        .update(|config| {
+            config.providers.high_model = Some("edited/high".to_owned());
+            config.providers.medium_model = Some("edited/medium".to_owned());
+            config.tiers.provider_low = None;
+            config.theme = Theme::Gruvbox;
+        })
+        .await?;
+
+    let global: Table = from_str(&fs::read_to_string(&global_path)?)?;
+    let project: Table = from_str(&fs::read_to_string(ConfigManager::project_config_path(
+        &project_root,
+    ))?)?;
+
+    assert_eq!(
+        project["providers"]["high_model"].as_str(),
+        Some("edited/high")
+    );
+    assert!(project["tiers"].get("provider_low").is_none());
+    assert_eq!(
+        global["providers"]["high_model"].as_str(),
+        Some("global/high")
+    );
+    assert_eq!(
+        global["providers"]["medium_model"].as_str(),
+        Some("edited/medium")
+    );
+    assert_eq!(global["theme"].as_str(), Some("Gruvbox"));
+    assert!(project.get("theme").is_none());
+
+    // Ignored secrets and keys the CLI config doesn't know are preserved
+    assert_eq!(
+        project["providers"]["openrouter_key"].as_str(),
+        Some("project-key")
+    );
+    assert_eq!(project["build_timeout_seconds"].as_integer(), Some(120));
+
+    let reloaded = ConfigManager::load(global_config_path(&dir), Some(&project_root)).await?;
+    let config = reloaded.get()?;
+    assert_eq!(config.providers.high_model.as_deref(), Some("edited/high"));
+    assert_eq!(config.tiers.provider_low, None);
+    assert_eq!(config.theme, Theme::Gruvbox);
+    Ok(())
+}
@@ -0,0 +1,373 @@
+//! Command handlers for CLI operations
+
+mod models;
+
+pub use models::handle_models_list;
+
+use anyhow::{Context as _, Result, bail};
+use chrono::Local;
+use merlin_agent::{
+    MERLIN_DIR, Merlin, RoutingOrchestrator, SESSION_FILE_NAME, SessionJournal, SessionRecorder,
+    THREADS_DIR, ThreadStore,
+};
+use merlin_context::RepeatedFilePolicy;
+use merlin_core::schema::CORRUPT_DIR;
+use merlin_routing::{METRICS_FILE, MetricsCollector, MetricsReport, Model};
+use ratatui::layout::Size;
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Write as _, stderr, stdout};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::fs as async_fs;
+
+use crate::cli::{Cli, Embeddings, ModelPull};
+use crate::config::{ALLOW_PROJECT_SECRETS, ConfigManager};
+use crate::headless::{RunArgs, RunStatus, run_task, scratch_copy, write_report};
+use crate::interactive::{FixtureRecording, run_tui_interactive};
+use crate::telemetry::{init_tracing, start_metrics_endpoint};
+use crate::ui::input::InputManager;
+use crate::ui::persistence::TaskPersistence;
+use crate::ui::renderer::{FocusedPane, RenderCtx, Renderer, UiCtx};
+use crate::ui::screenshot::{buffer_to_svg, render_to_buffer, save_screenshot};
+use crate::ui::state::UiState;
+use crate::ui::task_manager::TaskManager;
+use crate::utils::get_merlin_folder;
+
+/// Handle interactive agent session with routing
+///
+/// # Errors
+/// Returns an error if the orchestrator fails to initialize or process requests
+pub async fn handle_interactive(cli: Cli) -> Result<()> {
+    let Cli {
+        project,
+        local,
+        validation,
+        context_dump,
+        debug_context,
+        embeddings,
+        model_pull,
+        otlp_endpoint,
+        record_fixture,
+        ..
+    } = cli;
+    // Initialize tracing - TUI mode logs to file
+    let merlin_dir = get_merlin_folder(&project)?;
+    let _telemetry = init_tracing(open_debug_log(&merlin_dir).await?, otlp_endpoint.as_deref())?;
+    // Validation and context dumps are not configurable per session yet
+    tracing::debug!(
+        "Ignoring session options: validation {validation:?}, context dump {context_dump}"
+    );
+
+    // Load ~/.merlin/config.toml with the project's .merlin/config.toml merged over it
+    let config_manager = ConfigManager::for_project(&project)
+        .await
+        .context("Failed to load configuration")?;
+    let (mut config, context_config) = {
+        let loaded = config_manager.get()?;
+        (loaded.routing_config(), loaded.context.clone())
+    };
+
+    if local {
+        config.tiers.groq_enabled = false;
+        config.tiers.premium_enabled = false;
+    }
+
+    // Create orchestrator working on the project, with its thread store
+    let mut orchestrator = Merlin::project_orchestrator(
+        RoutingOrchestrator::new_with_local_models(config).await?,
+        project.clone(),
+        &merlin_dir,
+    )?
+    .with_context_explanations(debug_context)
+    .with_embeddings(context_config.embedding_enabled && embeddings == Embeddings::Configured)
+    .with_auto_pull(model_pull == ModelPull::Automatic)
+    .with_watch_mode(context_config.watch_mode)
+    .with_repeated_files(if context_config.dedupe_repeated_files {
+        RepeatedFilePolicy::Condense
+    } else {
+        RepeatedFilePolicy::Include
+    });
+
+    start_metrics_endpoint(orchestrator.metrics_collector())?;
+
+    // Journal queued and running tasks so they can be recovered after a hard stop
+    match SessionJournal::load(merlin_dir.join(SESSION_FILE_NAME)) {
+        Ok(journal) => orchestrator = orchestrator.with_session_journal(journal),
+        Err(error) => tracing::warn!("Session recovery disabled: {error}"),
+    }
+
+    // Snapshot the workspace before anything runs, so the fixture starts from it
+    let recording = match record_fixture {
+        Some(path) => {
+            let recorder = SessionRecorder::new(project.clone());
+            orchestrator = orchestrator.with_session_recorder(recorder.clone())?;
+            Some(FixtureRecording { recorder, path })
+        }
+        None => None,
+    };
+
+    let merlin = Merlin::from_orchestrator(orchestrator);
+    run_tui_interactive(merlin, config_manager, project, true, recording).await
+}
+
+/// Run one task without the TUI, printing its result
+///
+/// Returns how the run ended, which decides the process exit code.
+///
+/// # Errors
+/// Returns an error if the orchestrator fails to initialize or the output cannot be written
+pub async fn handle_run(cli: Cli, args: RunArgs) -> Result<RunStatus> {
+    let Cli {
+        project,
+        local,
+        embeddings,
+        model_pull,
+        otlp_endpoint,
+        ..
+    } = cli;
+    // Stdout and stderr carry the result and the events, so logs go to the file
+    let merlin_dir = get_merlin_folder(&project)?;
+    let _telemetry = init_tracing(open_debug_log(&merlin_dir).await?, otlp_endpoint.as_deref())?;
+
+    let config_manager = ConfigManager::for_project(&project)
+        .await
+        .context("Failed to load configuration")?;
+    let (mut config, context_config) = {
+        let loaded = config_manager.get()?;
+        (loaded.routing_config(), loaded.context.clone())
+    };
+
+    if local {
+        config.tiers.groq_enabled = false;
+        config.tiers.premium_enabled = false;
+    }
+
+    let orchestrator = match &args.model {
+        Some(id) => {
+            let Some(model) = Model::from_id(id) else {
+                let known: Vec<_> = Model::all().iter().map(Model::model_id).collect();
+                bail!(
+                    "Unknown model '{id}', expected one of: {}",
+                    known.join(", ")
+                );
+            };
+            RoutingOrchestrator::new_with_model(config, model)?
+        }
+        None => RoutingOrchestrator::new_with_local_models(config).await?,
+    };
+
+    // A dry run works on a scratch copy, dropped once the changes are reported
+    let scratch = if args.dry_run {
+        Some(scratch_copy(&project)?)
+    } else {
+        None
+    };
+    let (workspace, workspace_merlin_dir) = scratch.as_ref().map_or_else(
+        || (project.clone(), merlin_dir),
+        |dir| (dir.path().to_path_buf(), dir.path().join(MERLIN_DIR)),
+    );
+
+    let orchestrator =
+        Merlin::project_orchestrator(orchestrator, workspace, &workspace_merlin_dir)?
+            .with_embeddings(
+                context_config.embedding_enabled && embeddings == Embeddings::Configured,
+            )
+            .with_auto_pull(model_pull == ModelPull::Automatic)
+            .with_repeated_files(if context_config.dedupe_repeated_files {
+                RepeatedFilePolicy::Condense
+            } else {
+                RepeatedFilePolicy::Include
+            });
+    let merlin = Merlin::from_orchestrator(orchestrator);
+
+    let report = run_task(&merlin, &args, &mut stderr()).await?;
+    write_report(&report, args.json, &mut stdout(), &mut stderr())?;
+    Ok(report.status)
+}
+
+/// Open a fresh `debug.log` in the merlin folder for the tracing writer
+///
+/// # Errors
+/// Returns an error if the folder cannot be created or the log cannot be opened
+async fn open_debug_log(merlin_dir: &Path) -> Result<File> {
+    async_fs::create_dir_all(merlin_dir).await?;
+
+    let debug_log = merlin_dir.join("debug.log");
+    if async_fs::try_exists(&debug_log).await.unwrap_or(false) {
+        async_fs::remove_file(&debug_log).await?;
+    }
+
+    // Open log file synchronously for tracing writer (needs sync File)
+    Ok(OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&debug_log)?)
+}
+
+/// List stored threads, most recently updated first
+///
+/// # Errors
+/// Returns an error if the thread store cannot be loaded or output cannot be written
+pub fn handle_thread_list(project: &Path, tag: Option<&str>) -> Result<()> {
+    let merlin_dir = get_merlin_folder(project)?;
+    let mut store = ThreadStore::new(merlin_dir.join(THREADS_DIR))?;
+    let quarantined = store.load_all()?;
+    if !quarantined.is_empty() {
+        writeln!(
+            stderr(),
+            "Warning: moved {} unreadable thread file(s) to {}",
+            quarantined.len(),
+            merlin_dir.join(CORRUPT_DIR).display()
+        )?;
+    }
+
+    let tag = tag.map(|tag| tag.trim().to_lowercase());
+    let mut output = String::new();
+    for thread in store.active_threads() {
+        if tag.as_deref().is_some_and(|wanted| !thread.has_tag(wanted)) {
+            continue;
+        }
+
+        write!(
+            output,
+            "{}  {} ({} messages)",
+            thread.id,
+            thread.name,
+            thread.messages.len()
+        )?;
+        for thread_tag in &thread.tags {
+            write!(output, " #{thread_tag}")?;
+        }
+        output.push('\n');
+    }
+
+    stdout().write_all(output.as_bytes())?;
+    Ok(())
+}
+
+/// Show the config file locations, or the merged config with `effective`
+///
+/// # Errors
+/// Returns an error if a config file cannot be loaded or output cannot be written
+pub async fn handle_config(project: &Path, effective: bool) -> Result<()> {
+    let manager = ConfigManager::for_project(project).await?;
+    let project_path = manager.project_path();
+
+    let mut output = String::new();
+    if effective {
+        writeln!(output, "# Global: {}", manager.config_path.display())?;
+        if let Some(path) = &project_path {
+            writeln!(output, "# Project: {}", path.display())?;
+        }
+        output.push('\n');
+        output.push_str(&manager.effective_toml()?);
+    } else {
+        writeln!(output, "Global config:  {}", manager.config_path.display())?;
+        let project_line = project_path.map_or_else(
+            || {
+                format!(
+                    "{} (not found)",
+                    ConfigManager::project_config_path(project).display()
+                )
+            },
+            |path| path.display().to_string(),
+        );
+        writeln!(output, "Project config: {project_line}")?;
+    }
+    for key in manager.ignored_project_keys() {
+        writeln!(
+            output,
+            "# Ignored in project config: {key} (global config only, see `{ALLOW_PROJECT_SECRETS}`)"
+        )?;
+    }
+
+    stdout().write_all(output.as_bytes())?;
+    Ok(())
+}
+
+/// Export the request metrics logged in the project as CSV to `output`, or stdout
+///
+/// # Errors
+/// Returns an error if the metrics log cannot be loaded or the CSV cannot be written
+pub fn handle_metrics_export(project: &Path, output: Option<&Path>, daily: bool) -> Result<()> {
+    let log = get_merlin_folder(project)?.join(METRICS_FILE);
+    let collector = MetricsCollector::load(&log)
+        .with_context(|| format!("Failed to load metrics from {}", log.display()))?;
+    let csv = if daily {
+        MetricsReport::daily_csv(&collector)?
+    } else {
+        MetricsReport::to_csv(&collector)?
+    };
+
+    match output {
+        Some(path) => {
+            fs::write(path, csv).with_context(|| format!("Failed to write {}", path.display()))?;
+            writeln!(
+                stderr(),
+                "Exported {} requests to {}",
+                collector.len(),
+                path.display()
+            )?;
+        }
+        None => stdout().write_all(csv.as_bytes())?,
+    }
+    Ok(())
+}
+
+/// Render the most recently created task as the TUI would show it and save it as an SVG
+///
+/// # Errors
+/// Returns an error if tasks, threads or config cannot be loaded, no task exists, or the
+/// screenshot cannot be written
+pub async fn handle_screenshot(project: &Path, size: Size) -> Result<()> {
+    let merlin_dir = get_merlin_folder(project)?;
+    let loaded = TaskPersistence::new(merlin_dir.join("tasks"))
+        .load_all_tasks()
+        .await?;
+    let mut task_manager = TaskManager::default();
+    for (task_id, task) in loaded.tasks {
+        task_manager.insert_task_for_load(task_id, task);
+    }
+    task_manager.rebuild_order();
+    let Some((task_id, thread_id)) = task_manager
+        .task_order()
+        .iter()
+        .filter_map(|&task_id| task_manager.get_task(task_id).map(|task| (task_id, task)))
+        .max_by_key(|(_, task)| task.created_at)
+        .map(|(task_id, task)| (task_id, task.thread_id))
+    else {
+        bail!("No tasks in {}", merlin_dir.join("tasks").display());
+    };
+
+    let mut store = ThreadStore::new(merlin_dir.join(THREADS_DIR))?;
+    // Unreadable thread files are quarantined; the screenshot still renders without them
+    store.load_all()?;
+    let thread_store = Arc::new(Mutex::new(store));
+    let state = UiState {
+        active_task_id: Some(task_id),
+        active_thread_id: thread_id,
+        ..UiState::default()
+    };
+    let theme = ConfigManager::for_project(project)
+        .await?
+        .get()?
+        .theme
+        .with_custom_colors();
+    let renderer = Renderer::new(theme);
+    let input = InputManager::default();
+    let ctx = RenderCtx {
+        ui_ctx: UiCtx {
+            task_manager: &task_manager,
+            state: &state,
+        },
+        input: &input,
+        focused: FocusedPane::Output,
+        thread_store: &thread_store,
+    };
+
+    let buffer = render_to_buffer(&renderer, &ctx, size)?;
+    let path = save_screenshot(&merlin_dir, &buffer_to_svg(&buffer, theme), Local::now())?;
+    writeln!(stdout(), "{}", path.display())?;
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! `merlin models list`: the routed models and the model catalog, or the models installed in Ollama
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Local};
+use merlin_core::RoutingConfig;
+use merlin_local::LocalModelProvider;
+use merlin_routing::{Model, ModelCatalog, ModelRegistry, ProviderRegistry};
+use std::fmt::Write as _;
+use std::io::{Write as _, stderr, stdout};
+use std::path::Path;
+
+use crate::config::ConfigManager;
+
+/// List the models tasks are routed to and the model catalog, or with `local`
+/// the models installed in Ollama
+///
+/// # Errors
+/// Returns an error if the config cannot be loaded, Ollama cannot be reached for
+/// `local`, or output cannot be written
+pub async fn handle_models_list(project: &Path, local: bool) -> Result<()> {
+    let config = ConfigManager::for_project(project)
+        .await?
+        .get()?
+        .routing_config();
+    let mut output = String::new();
+    if local {
+        let models = LocalModelProvider::new(config.tiers.local_model)
+            .list_available_models()
+            .await
+            .context("Failed to list Ollama models (is `ollama serve` running?)")?;
+        let width = models
+            .iter()
+            .map(|model| model.name.len())
+            .max()
+            .unwrap_or(0);
+        writeln!(output, "{:<width$}  {:>9}  MODIFIED", "NAME", "SIZE")?;
+        for model in &models {
+            writeln!(
+                output,
+                "{:<width$}  {:>9}  {}",
+                model.name,
+                format_size(model.size_bytes),
+                format_modified(&model.modified_at)
+            )?;
+        }
+    } else {
+        let (catalog, registry) = routed_models(&config).await?;
+        write_models_table(&mut output, &catalog, &registry)?;
+    }
+    stdout().write_all(output.as_bytes())?;
+    Ok(())
+}
+
+/// The model catalog and the difficulty levels each model is routed at
+///
+/// Without the providers configured (e.g. a missing API key), the cached
+/// catalog and the default levels are used.
+///
+/// # Errors
+/// Returns an error if the home directory cannot be determined
+async fn routed_models(config: &RoutingConfig) -> Result<(ModelCatalog, ModelRegistry)> {
+    let mut registry = ModelRegistry::with_defaults();
+    match ProviderRegistry::new(config.clone()) {
+        Ok(providers) => {
+            registry.discover_local_models(&providers).await;
+            let catalog = ModelCatalog::load_or_refresh(config, &providers).await;
+            Ok((catalog, registry))
+        }
+        Err(error) => {
+            writeln!(stderr(), "Listing the cached model catalog: {error}")?;
+            let catalog = ModelCatalog::load(&ModelCatalog::cache_path()?).unwrap_or_default();
+            Ok((catalog, registry))
+        }
+    }
+}
+
+/// Writes the routed models, then the rest of the catalog, with their prices and tiers
+///
+/// # Errors
+/// Returns an error if writing to `output` fails
+fn write_models_table(
+    output: &mut String,
+    catalog: &ModelCatalog,
+    registry: &ModelRegistry,
+) -> Result<()> {
+    let routed = Model::all().into_iter().map(|model| {
+        let levels = format_levels(&registry.levels_of(model));
+        (
+            model.model_id(),
+            model.tier_category().to_string(),
+            levels,
+            catalog.model(model),
+        )
+    });
+    let unrouted = catalog
+        .models
+        .iter()
+        .filter(|entry| Model::from_id(&entry.id).is_none())
+        .map(|entry| {
+            (
+                entry.id.as_str(),
+                "-".to_owned(),
+                "-".to_owned(),
+                Some(entry),
+            )
+        });
+    let rows: Vec<_> = routed.chain(unrouted).collect();
+
+    let width = rows.iter().map(|(id, ..)| id.len()).max().unwrap_or(0);
+    writeln!(
+        output,
+        "{:<width$}  {:<7}  {:<6}  {:>9}  {:>9}  {:>9}",
+        "ID", "TIER", "LEVELS", "CONTEXT", "INPUT/M", "OUTPUT/M"
+    )?;
+    for (id, tier, levels, entry) in rows {
+        let context = entry
+            .and_then(|entry| entry.context_window)
+            .map_or_else(|| "-".to_owned(), |tokens| tokens.to_string());
+        writeln!(
+            output,
+            "{id:<width$}  {tier:<7}  {levels:<6}  {context:>9}  {:>9}  {:>9}",
+            format_price(entry.and_then(|entry| entry.input_price)),
+            format_price(entry.and_then(|entry| entry.output_price))
+        )?;
+    }
+    Ok(())
+}
+
+/// Formats difficulty levels as ranges, e.g. `1-2,5`
+fn format_levels(levels: &[u8]) -> String {
+    let mut ranges: Vec<(u8, u8)> = Vec::new();
+    for &level in levels {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == level => *end = level,
+            _ => ranges.push((level, level)),
+        }
+    }
+    if ranges.is_empty() {
+        return "-".to_owned();
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Formats a price in USD per million tokens, `-` if unknown
+fn format_price(price: Option<f64>) -> String {
+    price.map_or_else(|| "-".to_owned(), |price| format!("${price:.2}"))
+}
+
+/// Formats a size in bytes with a decimal unit, like `ollama list`
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = "B";
+    for next in UNITS {
+        if size < 1000.0 {
+            break;
+        }
+        size /= 1000.0;
+        unit = next;
+    }
+    if unit == "B" {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {unit}")
+    }
+}
+
+/// Formats an RFC 3339 timestamp from Ollama in local time, or returns it as-is
+fn format_modified(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp).map_or_else(
+        |_| timestamp.to_owned(),
+        |time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        },
+    )
+}
@@ -0,0 +1,50 @@
+//! Answering the questions a headless task asks with the `--answer` values.
+
+use merlin_core::UiEvent;
+use std::slice::Iter;
+
+/// The `--answer` values not used yet, and the first question none was left for
+pub(super) struct Answers<'args> {
+    /// Answers for the next questions, in order
+    remaining: Iter<'args, String>,
+    /// First question asked after the answers ran out
+    unanswered: Option<String>,
+}
+
+impl<'args> Answers<'args> {
+    /// Answers questions with `answers`, in order
+    pub(super) fn new(answers: &'args [String]) -> Self {
+        Self {
+            remaining: answers.iter(),
+            unanswered: None,
+        }
+    }
+
+    /// Answers `event` if it is a question, returning whether the task must be
+    /// cancelled as no answer was left for it
+    pub(super) fn respond(&mut self, event: &UiEvent) -> bool {
+        let UiEvent::UserQuestion {
+            question, answer, ..
+        } = event
+        else {
+            return false;
+        };
+        if let Some(text) = self.remaining.next() {
+            answer.answer(text.clone());
+            return false;
+        }
+        answer.decline();
+        self.unanswered.get_or_insert_with(|| question.clone());
+        true
+    }
+
+    /// The first question asked after the answers ran out
+    pub(super) fn into_unanswered(self) -> Option<String> {
+        self.unanswered
+    }
+}
+
+/// Error reported for a task cancelled because `question` had no answer left
+pub(super) fn unanswered_error(question: &str) -> String {
+    format!("Task asked a question with no --answer left: {question}")
+}
@@ -0,0 +1,427 @@
+//! `merlin run`: execute one task headlessly, without the terminal UI.
+//!
+//! With `--json`, the task's UI events are streamed as NDJSON while it runs and
+//! a [`RunReport`] is written once it finished; otherwise only the response is
+//! printed. The exit code tells success apart from failed validation and an
+//! exceeded budget, see [`RunStatus::exit_code`].
+//!
+//! With `--dry-run` the task works on a [`scratch_copy`] of the project, so
+//! the report lists the changes it would make without touching the project.
+
+mod answers;
+#[cfg(test)]
+mod tests;
+
+use answers::{Answers, unanswered_error};
+use anyhow::{Context as _, Result, anyhow};
+use futures::StreamExt as _;
+use ignore::{Walk, WalkBuilder};
+use merlin_agent::pin_tool::resolve_pin_path;
+use merlin_agent::{MERLIN_DIR, Merlin, TaskHandle};
+use merlin_core::{
+    RoutingError, TaskId, TaskResult, ThreadId, TokenUsage, UiEvent, ValidationResult,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{to_writer, to_writer_pretty};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+use tempfile::TempDir;
+
+/// Arguments of the `run` subcommand
+#[derive(Debug, Clone, Default)]
+pub struct RunArgs {
+    /// Prompt of the task
+    pub prompt: String,
+    /// Stream events as NDJSON and write the result as JSON
+    pub json: bool,
+    /// Identifier of the model every request goes to, instead of routing by difficulty
+    pub model: Option<String>,
+    /// Estimated cost in USD after which the task is cancelled
+    pub max_cost: Option<f64>,
+    /// Work on a scratch copy of the project, leaving the project unchanged
+    pub dry_run: bool,
+    /// Files pinned to the task's context, relative to the project root
+    pub context: Vec<String>,
+    /// Answers to the questions the task asks, in order
+    pub answers: Vec<String>,
+}
+
+/// What happened while a task's events were streamed
+struct StreamOutcome {
+    /// Whether the task was cancelled for exceeding `--max-cost`
+    budget_exceeded: bool,
+    /// Question the task was cancelled for, as no `--answer` was left for it
+    unanswered: Option<String>,
+}
+
+/// How a headless run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// The task completed and passed validation
+    Succeeded,
+    /// The task failed
+    Failed,
+    /// The task's output failed validation
+    ValidationFailed,
+    /// The task cost more than `--max-cost`
+    BudgetExceeded,
+}
+
+impl RunStatus {
+    /// Process exit code reporting the status
+    #[must_use]
+    pub const fn exit_code(self) -> i32 {
+        match self {
+            Self::Succeeded => 0,
+            Self::Failed => 1,
+            Self::ValidationFailed => 2,
+            Self::BudgetExceeded => 3,
+        }
+    }
+}
+
+/// How a file was changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The file did not exist before
+    Created,
+    /// The file was written to
+    Modified,
+    /// The file was removed
+    Deleted,
+}
+
+/// File changed while the task ran
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedFile {
+    /// Path relative to the workspace
+    pub path: PathBuf,
+    /// How the file was changed
+    pub change: ChangeKind,
+}
+
+/// Outcome of a headless run, written as the final JSON document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunReport {
+    /// How the run ended
+    pub status: RunStatus,
+    /// Task that was executed
+    pub task_id: TaskId,
+    /// Response text, if the task completed
+    pub response: Option<String>,
+    /// Error message, if the task failed
+    pub error: Option<String>,
+    /// Files created, modified or deleted by the task
+    pub file_changes: Vec<ChangedFile>,
+    /// Validation of the task's output, if it got that far
+    pub validation: Option<ValidationResult>,
+    /// Tokens used by the task
+    pub tokens: TokenUsage,
+    /// Estimated cost in USD of every request the task made
+    pub cost_usd: f64,
+    /// Wall-clock duration of the run in milliseconds
+    pub duration_ms: u64,
+    /// Whether the changes were made to a scratch copy of the project
+    pub dry_run: bool,
+}
+
+/// Runs the task of `args` to completion, writing its events to `events` as NDJSON with `--json`
+///
+/// File changes are found by comparing the workspace of `merlin` before and after
+/// the task. Once the session cost exceeds `--max-cost`, the task is cancelled.
+///
+/// # Errors
+/// Returns an error if a context file cannot be pinned or an event cannot be written;
+/// failures of the task itself are reported in the returned [`RunReport`]
+pub async fn run_task(
+    merlin: &Merlin,
+    args: &RunArgs,
+    events: &mut impl Write,
+) -> Result<RunReport> {
+    let orchestrator = merlin.orchestrator();
+    let workspace = orchestrator.workspace_root().clone();
+    let before = WorkspaceSnapshot::capture(&workspace);
+    let started = Instant::now();
+
+    let mut handle = if args.context.is_empty() {
+        merlin.submit(args.prompt.as_str())
+    } else {
+        let thread_id = pin_context(merlin, &args.prompt, &args.context)?;
+        merlin.submit_in_thread(thread_id, args.prompt.as_str())
+    };
+    let task_id = handle.task_id();
+
+    let streamed = stream_events(merlin, &mut handle, args, events).await?;
+    let result = handle.await_result().await;
+    let budget_exceeded = streamed.budget_exceeded || over_budget(merlin, args.max_cost)?;
+
+    let status = if streamed.unanswered.is_some() {
+        RunStatus::Failed
+    } else {
+        run_status(&result, budget_exceeded)
+    };
+    let (response, error, validation, tokens) = match result {
+        Ok(task_result) => (
+            Some(task_result.response.text),
+            None,
+            Some(task_result.validation),
+            task_result.tokens_used,
+        ),
+        Err(err) => (
+            None,
+            Some(err.to_string()),
+            failed_validation(&err),
+            TokenUsage::default(),
+        ),
+    };
+
+    Ok(RunReport {
+        status,
+        task_id,
+        response,
+        error: streamed
+            .unanswered
+            .as_deref()
+            .map(unanswered_error)
+            .or(error),
+        file_changes: before.changes(&WorkspaceSnapshot::capture(&workspace)),
+        validation,
+        tokens,
+        cost_usd: orchestrator.session_cost()?,
+        duration_ms: started.elapsed().as_millis().try_into().unwrap_or(u64::MAX),
+        dry_run: args.dry_run,
+    })
+}
+
+/// Streams the events of `handle` until it finished, answering its questions
+///
+/// Questions are answered with the `--answer` values in order. A question with
+/// none left, or exceeding `--max-cost`, cancels the task.
+///
+/// # Errors
+/// Returns an error if an event cannot be written or the session cost cannot be read
+async fn stream_events(
+    merlin: &Merlin,
+    handle: &mut TaskHandle,
+    args: &RunArgs,
+    events: &mut impl Write,
+) -> Result<StreamOutcome> {
+    let mut outcome = StreamOutcome {
+        budget_exceeded: false,
+        unanswered: None,
+    };
+    let mut answers = Answers::new(&args.answers);
+    let mut stream = handle.events();
+    while let Some(event) = stream.next().await {
+        if args.json {
+            write_event(events, &event)?;
+        }
+        if answers.respond(&event) {
+            handle.cancel();
+        }
+        if !outcome.budget_exceeded && over_budget(merlin, args.max_cost)? {
+            outcome.budget_exceeded = true;
+            handle.cancel();
+        }
+    }
+    outcome.unanswered = answers.into_unanswered();
+    Ok(outcome)
+}
+
+/// Writes the outcome of a run: the report as JSON with `--json`, else the response
+///
+/// Without `--json`, failures are described on `errors` instead.
+///
+/// # Errors
+/// Returns an error if the output cannot be written
+pub fn write_report(
+    report: &RunReport,
+    json: bool,
+    out: &mut impl Write,
+    errors: &mut impl Write,
+) -> Result<()> {
+    if json {
+        to_writer_pretty(&mut *out, report)?;
+        writeln!(out)?;
+        return Ok(());
+    }
+
+    if let Some(response) = &report.response {
+        writeln!(out, "{response}")?;
+    }
+    if let Some(error) = &report.error {
+        writeln!(errors, "Error: {error}")?;
+    }
+    match report.status {
+        RunStatus::ValidationFailed => writeln!(errors, "Validation failed")?,
+        RunStatus::BudgetExceeded => {
+            writeln!(errors, "Budget exceeded: ${:.4} spent", report.cost_usd)?;
+        }
+        RunStatus::Succeeded | RunStatus::Failed => {}
+    }
+    Ok(())
+}
+
+/// Copies the project into a temporary directory for `--dry-run`
+///
+/// Ignored files, `.git` and the `.merlin` state folder are left out.
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or a file cannot be copied
+pub fn scratch_copy(project: &Path) -> Result<TempDir> {
+    let scratch = TempDir::new().context("Failed to create scratch directory")?;
+    for entry in project_files(project) {
+        let entry = entry?;
+        let Ok(relative) = entry.path().strip_prefix(project) else {
+            continue;
+        };
+        let target = scratch.path().join(relative);
+        if entry.file_type().is_some_and(|kind| kind.is_dir()) {
+            fs::create_dir_all(&target)?;
+        } else if entry.file_type().is_some_and(|kind| kind.is_file()) {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(scratch)
+}
+
+/// Walks the files of a project, skipping ignored files, `.git` and `.merlin`
+fn project_files(root: &Path) -> Walk {
+    WalkBuilder::new(root)
+        .hidden(false)
+        // Scratch copies have no repository, but their `.gitignore` still applies
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git" && entry.file_name() != MERLIN_DIR)
+        .build()
+}
+
+/// Creates a thread for the run with `files` pinned to its context
+///
+/// # Errors
+/// Returns an error if a file is missing or outside the workspace, or the thread
+/// cannot be saved
+fn pin_context(merlin: &Merlin, prompt: &str, files: &[String]) -> Result<ThreadId> {
+    let orchestrator = merlin.orchestrator();
+    let pinned = files
+        .iter()
+        .map(|file| resolve_pin_path(orchestrator.workspace_root(), file))
+        .collect::<Result<Vec<_>, _>>()?;
+    let store = orchestrator
+        .thread_store()
+        .context("No thread store to pin context files in")?;
+    let mut store = store
+        .lock()
+        .map_err(|err| anyhow!("Thread store lock error: {err}"))?;
+
+    let thread = store.create_thread(prompt.to_owned());
+    store.save_thread(&thread)?;
+    for path in pinned {
+        store.pin_file(thread.id, path)?;
+    }
+    Ok(thread.id)
+}
+
+/// Writes `event` as one line of JSON
+///
+/// # Errors
+/// Returns an error if the event cannot be serialized or written
+fn write_event(events: &mut impl Write, event: &UiEvent) -> Result<()> {
+    to_writer(&mut *events, event)?;
+    writeln!(events)?;
+    events.flush()?;
+    Ok(())
+}
+
+/// Whether the session cost more than `max_cost`
+///
+/// # Errors
+/// Returns an error if the metrics lock is poisoned
+fn over_budget(merlin: &Merlin, max_cost: Option<f64>) -> Result<bool> {
+    let Some(max_cost) = max_cost else {
+        return Ok(false);
+    };
+    Ok(merlin.orchestrator().session_cost()? > max_cost)
+}
+
+/// Status of a run that ended with `result`
+fn run_status(result: &Result<TaskResult, RoutingError>, budget_exceeded: bool) -> RunStatus {
+    if budget_exceeded {
+        return RunStatus::BudgetExceeded;
+    }
+    match result {
+        Ok(task_result) if task_result.validation.passed => RunStatus::Succeeded,
+        Ok(_) => RunStatus::ValidationFailed,
+        Err(err) if failed_validation(err).is_some() => RunStatus::ValidationFailed,
+        Err(_) => RunStatus::Failed,
+    }
+}
+
+/// Validation that made the task fail, if it failed validation
+fn failed_validation(err: &RoutingError) -> Option<ValidationResult> {
+    match err.root() {
+        RoutingError::ValidationFailed(validation)
+        | RoutingError::MaxRetriesExceeded { validation, .. } => Some(validation.clone()),
+        _ => None,
+    }
+}
+
+/// Size and modification time of a file, which change when it is written
+type FileStamp = (u64, Option<SystemTime>);
+
+/// Size and modification time of every file of a workspace
+struct WorkspaceSnapshot {
+    /// Size and modification time by path relative to the workspace
+    files: BTreeMap<PathBuf, FileStamp>,
+}
+
+impl WorkspaceSnapshot {
+    /// Records the files of `root`, skipping ignored files, `.git` and `.merlin`
+    fn capture(root: &Path) -> Self {
+        let files = project_files(root)
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let relative = entry.path().strip_prefix(root).ok()?.to_path_buf();
+                Some((relative, (metadata.len(), metadata.modified().ok())))
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Files created, modified or deleted between this snapshot and `after`
+    fn changes(&self, after: &Self) -> Vec<ChangedFile> {
+        let mut changes: Vec<ChangedFile> = after
+            .files
+            .iter()
+            .filter_map(|(path, state)| {
+                let change = match self.files.get(path) {
+                    None => ChangeKind::Created,
+                    Some(before) if before != state => ChangeKind::Modified,
+                    Some(_) => return None,
+                };
+                Some(ChangedFile {
+                    path: path.clone(),
+                    change,
+                })
+            })
+            .collect();
+        changes.extend(
+            self.files
+                .keys()
+                .filter(|path| !after.files.contains_key(*path))
+                .map(|path| ChangedFile {
+                    path: path.clone(),
+                    change: ChangeKind::Deleted,
+                }),
+        );
+        changes.sort_by(|left, right| left.path.cmp(&right.path));
+        changes
+    }
+}
@@ -0,0 +1,100 @@
+//! Tests for headless runs
+
+use super::*;
+use merlin_core::{Severity, ValidationError, ValidationStageType};
+
+/// Tests that snapshots report created, modified and deleted files, ignoring `.merlin`.
+///
+/// # Errors
+/// Returns an error if the workspace cannot be written.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_snapshot_changes() -> Result<()> {
+    let workspace = TempDir::new()?;
+    fs::write(workspace.path().join("kept.rs"), "fn kept() {}\n")?;
+    fs::write(workspace.path().join("edited.rs"), "fn edited() {}\n")?;
+    fs::write(workspace.path().join("removed.rs"), "fn removed() {}\n")?;
+    let before = WorkspaceSnapshot::capture(workspace.path());
+
+    fs::write(
+        workspace.path().join("edited.rs"),
+        "fn edited() { todo!() }\n",
+    )?;
+    fs::remove_file(workspace.path().join("removed.rs"))?;
+    fs::write(workspace.path().join("created.rs"), "fn created() {}\n")?;
+    fs::create_dir_all(workspace.path().join(MERLIN_DIR))?;
+    fs::write(workspace.path().join(MERLIN_DIR).join("debug.log"), "log")?;
+
+    let changes = before.changes(&WorkspaceSnapshot::capture(workspace.path()));
+    let expected = [
+        ("created.rs", ChangeKind::Created),
+        ("edited.rs", ChangeKind::Modified),
+        ("removed.rs", ChangeKind::Deleted),
+    ]
+    .map(|(path, change)| ChangedFile {
+        path: PathBuf::from(path),
+        change,
+    });
+    assert_eq!(changes, expected);
+    Ok(())
+}
+
+/// Tests that scratch copies keep the project's files but not its `.git` and `.merlin`.
+///
+/// # Errors
+/// Returns an error if the project cannot be written or copied.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_scratch_copy_skips_state() -> Result<()> {
+    let project = TempDir::new()?;
+    fs::create_dir_all(project.path().join("src"))?;
+    fs::create_dir_all(project.path().join(".git"))?;
+    fs::create_dir_all(project.path().join(MERLIN_DIR))?;
+    fs::write(
+        project.path().join("src").join("lib.rs"),
+        "pub fn lib() {}\n",
+    )?;
+    fs::write(project.path().join(".gitignore"), "target/\n")?;
+    fs::write(project.path().join(".git").join("HEAD"), "ref: main\n")?;
+    fs::write(project.path().join(MERLIN_DIR).join("debug.log"), "log")?;
+
+    let scratch = scratch_copy(project.path())?;
+    assert!(scratch.path().join("src").join("lib.rs").is_file());
+    assert!(scratch.path().join(".gitignore").is_file());
+    assert!(!scratch.path().join(".git").exists());
+    assert!(!scratch.path().join(MERLIN_DIR).exists());
+    Ok(())
+}
+
+/// Tests that failed validation and an exceeded budget get their own status.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_run_status() {
+    let failed_validation = ValidationResult {
+        passed: false,
+        errors: vec![ValidationError {
+            stage: ValidationStageType::Syntax,
+            message: "unbalanced braces".to_owned(),
+            severity: Severity::Error,
+        }],
+        ..ValidationResult::default()
+    };
+    let cancelled = Err(RoutingError::Cancelled(TaskId::default()));
+
+    assert_eq!(
+        run_status(
+            &Err(RoutingError::ValidationFailed(failed_validation)),
+            false
+        ),
+        RunStatus::ValidationFailed
+    );
+    assert_eq!(run_status(&cancelled, true), RunStatus::BudgetExceeded);
+    assert_eq!(run_status(&cancelled, false), RunStatus::Failed);
+    assert_eq!(RunStatus::BudgetExceeded.exit_code(), 3);
+}
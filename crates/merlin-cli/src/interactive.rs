@@ -1,11 +1,14 @@
 //! Interactive mode functionality - TUI mode
 
 use anyhow::Result;
-use merlin_agent::RoutingOrchestrator;
+use merlin_agent::{Merlin, SessionRecorder};
 
+use crate::config::ConfigManager;
 use crate::ui::TuiApp;
-use std::io::Write as _;
+use crate::ui::terminal_guard::{CRASH_LOG, TerminalGuard};
+use std::io::{Write as _, stderr};
 use std::path::{Path, PathBuf};
+use std::process::exit;
 use std::sync::Arc;
 use std::time::SystemTime;
 use tokio::fs as async_fs;
@@ -43,14 +46,24 @@ fn init_tui_logging(merlin_dir: &Path, project: &Path, local_only: bool) -> Resu
     Ok(log_file)
 }
 
+/// Session recording written as a test fixture when the session ends
+pub struct FixtureRecording {
+    /// Recorder attached to the orchestrator
+    pub recorder: SessionRecorder,
+    /// Path the fixture is written to
+    pub path: PathBuf,
+}
+
 /// Run fully self-contained TUI interactive session
 ///
 /// # Errors
 /// Returns an error if filesystem, TUI, or async operations fail.
 pub async fn run_tui_interactive(
-    orchestrator: RoutingOrchestrator,
+    merlin: Merlin,
+    config_manager: ConfigManager,
     project: PathBuf,
     local_only: bool,
+    recording: Option<FixtureRecording>,
 ) -> Result<()> {
     let merlin_dir = get_merlin_folder(&project)?;
     async_fs::create_dir_all(&merlin_dir).await?;
@@ -65,12 +78,13 @@ pub async fn run_tui_interactive(
     let log_clone = log_file.try_clone()?;
     let mut tui_app = TuiApp::new_with_storage(
         tasks_dir.clone(),
-        Some(Arc::new(orchestrator)),
+        Some(Arc::clone(merlin.orchestrator())),
+        config_manager,
         Some(log_clone),
-    )
-    .await?;
+    )?;
 
-    TuiApp::enable_raw_mode()?;
+    // Restores the terminal however the session ends, including panics
+    let terminal_guard = TerminalGuard::enter(merlin_dir.join(CRASH_LOG))?;
 
     // Render the UI immediately before loading tasks
     tui_app.render()?;
@@ -79,6 +93,7 @@ pub async fn run_tui_interactive(
     if let Err(err) = tui_app.load_threads() {
         tracing::warn!("Failed to load threads: {err}");
     }
+    tui_app.recover_session();
 
     // Count .gz files asynchronously
     let disk_task_files = async {
@@ -113,7 +128,31 @@ pub async fn run_tui_interactive(
     // Run the event loop until quit
     tui_app.run_event_loop().await?;
 
-    tui_app.disable_raw_mode()?;
+    terminal_guard.restore()?;
+    tui_app.clear_screen()?;
+
+    if let Some(recording) = recording {
+        match recording.recorder.write_fixture(&recording.path).await {
+            Ok(()) => writeln!(
+                stderr(),
+                "Recorded session fixture to {}",
+                recording.path.display()
+            )?,
+            Err(err) => writeln!(stderr(), "Failed to write session fixture: {err}")?,
+        }
+    }
+
+    let remaining = tui_app.remaining_task_count();
+    if remaining > 0 {
+        // Blocking tool calls would otherwise keep the runtime alive on drop
+        writeln!(
+            log_file,
+            "=== Session ended, forcefully cancelled {remaining} task(s) ==="
+        )?;
+        let shutdown = merlin.orchestrator().shutdown_coordinator();
+        shutdown.running_commands().kill_all();
+        exit(0);
+    }
     writeln!(log_file, "=== Session ended ===")?;
 
     Ok(())
@@ -5,6 +5,12 @@
 // Modules needed by the UI and integration tests
 pub mod config;
 
+// Headless `merlin run`, driven by integration tests against a mock provider
+pub mod headless;
+
+// First-run setup wizard
+pub mod setup;
+
 // UI module is public for integration testing
 pub mod ui;
 
@@ -1,13 +1,20 @@
 //! Merlin CLI - Interactive AI coding assistant command-line interface
 
 use anyhow::{Context as _, Result};
-use cli::Cli;
+use cli::{Cli, Command};
+use ratatui::layout::Size;
+use std::process::exit;
 use tokio::task::LocalSet;
 
+mod audit;
 mod cli;
 mod config;
 mod handlers;
+mod headless;
 mod interactive;
+mod prompts;
+mod setup;
+mod telemetry;
 mod ui;
 mod utils;
 
@@ -20,14 +27,43 @@ mod utils;
 /// Panics if tokio runtime initialization fails
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse().context("Failed to parse command-line arguments")?;
+    let mut cli = Cli::parse().context("Failed to parse command-line arguments")?;
+
+    match cli.command.take() {
+        Some(Command::ThreadList { tag }) => {
+            return handlers::handle_thread_list(&cli.project, tag.as_deref());
+        }
+        Some(Command::Setup(args)) => return setup::run_setup(args).await,
+        Some(Command::Config { effective }) => {
+            return handlers::handle_config(&cli.project, effective).await;
+        }
+        Some(Command::AuditTools(args)) => {
+            return audit::handle_audit_tools(&cli.project, &args).await;
+        }
+        Some(Command::Prompts(command)) => {
+            return prompts::handle_prompts(&cli.project, &command).await;
+        }
+        Some(Command::Run(args)) => {
+            let status = LocalSet::new()
+                .run_until(handlers::handle_run(cli, args))
+                .await?;
+            exit(status.exit_code());
+        }
+        Some(Command::MetricsExport { output, daily }) => {
+            return handlers::handle_metrics_export(&cli.project, output.as_deref(), daily);
+        }
+        Some(Command::ModelsList { local }) => {
+            return handlers::handle_models_list(&cli.project, local).await;
+        }
+        Some(Command::Screenshot { width, height }) => {
+            return handlers::handle_screenshot(&cli.project, Size::new(width, height)).await;
+        }
+        None => setup::run_first_time_setup().await?,
+    }
 
     // Wrap entire execution in LocalSet to support !Send TypeScript runtime
     LocalSet::new()
-        .run_until(async {
-            handlers::handle_interactive(cli.project, cli.validation, cli.local, cli.context_dump)
-                .await
-        })
+        .run_until(async { handlers::handle_interactive(cli).await })
         .await?;
 
     Ok(())
@@ -197,7 +233,7 @@ mod tests {
 
         let remaining_result = fs::read_dir(&tasks_dir);
         assert!(remaining_result.is_ok(), "Failed to read tasks dir");
-        let remaining = remaining_result.map(Iterator::count).unwrap_or(0);
+        let remaining = remaining_result.map_or(0, Iterator::count);
         assert_eq!(
             remaining, NUM_TASKS,
             "All tasks should remain when under limit"
@@ -245,7 +281,7 @@ mod tests {
 
         let remaining_result = fs::read_dir(&tasks_dir);
         assert!(remaining_result.is_ok(), "Failed to read tasks dir");
-        let remaining = remaining_result.map(Iterator::count).unwrap_or(0);
+        let remaining = remaining_result.map_or(0, Iterator::count);
         assert_eq!(remaining, MAX_TASKS, "Should keep exactly MAX_TASKS tasks");
         Ok(())
     }
@@ -291,7 +327,7 @@ mod tests {
 
         let total_files_result = fs::read_dir(&tasks_dir);
         assert!(total_files_result.is_ok(), "Failed to read tasks dir");
-        let total_files = total_files_result.map(Iterator::count).unwrap_or(0);
+        let total_files = total_files_result.map_or(0, Iterator::count);
         assert_eq!(total_files, EXPECTED_TOTAL, "Should preserve non-gz files");
         Ok(())
     }
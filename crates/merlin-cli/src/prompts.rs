@@ -0,0 +1,104 @@
+//! `merlin prompts`: list, show and edit prompt template overrides
+
+use anyhow::{Result, anyhow, bail};
+use merlin_core::prompts::{PROMPT_NAMES, PromptDirs, embedded_prompt, load_prompt};
+use merlin_tooling::ShellFlavor;
+use std::env;
+use std::fmt::Write as _;
+use std::io::{Write as _, stderr, stdout};
+use std::path::Path;
+use tokio::fs;
+use tokio::process::Command;
+
+/// Action of the `prompts` subcommand
+#[derive(Debug)]
+pub enum PromptsCommand {
+    /// List every prompt and where it is loaded from
+    List,
+    /// Print the prompt as it will be used
+    Show {
+        /// Prompt name
+        name: String,
+    },
+    /// Create the project override if missing and open it in `$VISUAL` or `$EDITOR`
+    Edit {
+        /// Prompt name
+        name: String,
+    },
+}
+
+/// Run a `prompts` subcommand for `project`
+///
+/// # Errors
+/// Returns an error if the prompt is unknown or invalid, the override cannot be
+/// written, the editor fails, or output cannot be written
+pub async fn handle_prompts(project: &Path, command: &PromptsCommand) -> Result<()> {
+    let dirs = PromptDirs::for_project(project);
+    match command {
+        PromptsCommand::List => list_prompts(&dirs),
+        PromptsCommand::Show { name } => {
+            let prompt = load_prompt(name, &dirs).map_err(|err| anyhow!(err))?;
+            writeln!(stderr(), "# {name}: {}", prompt.source)?;
+            writeln!(stdout(), "{}", prompt.text)?;
+            Ok(())
+        }
+        PromptsCommand::Edit { name } => edit_prompt(&dirs, name).await,
+    }
+}
+
+/// Print each prompt with its source, or why its override is rejected
+///
+/// # Errors
+/// Returns an error if output cannot be written
+fn list_prompts(dirs: &PromptDirs) -> Result<()> {
+    let mut output = String::new();
+    for name in PROMPT_NAMES {
+        match load_prompt(name, dirs) {
+            Ok(prompt) => writeln!(output, "{name:<20} {}", prompt.source)?,
+            Err(err) => writeln!(output, "{name:<20} invalid: {err}")?,
+        }
+    }
+    stdout().write_all(output.as_bytes())?;
+    Ok(())
+}
+
+/// Scaffold the project override of `name` from the default and open it for editing
+///
+/// # Errors
+/// Returns an error if the prompt is unknown, the override cannot be written, the
+/// editor fails, or the edited override is invalid
+async fn edit_prompt(dirs: &PromptDirs, name: &str) -> Result<()> {
+    let default = embedded_prompt(name).ok_or_else(|| anyhow!("Unknown prompt: {name}"))?;
+    let path = dirs
+        .project_path(name)
+        .ok_or_else(|| anyhow!("No project prompt directory"))?;
+
+    if !fs::try_exists(&path).await? {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&path, default).await?;
+        writeln!(stderr(), "Created {} from the default", path.display())?;
+    }
+
+    let editor = env::var("VISUAL").or_else(|_| env::var("EDITOR")).ok();
+    let Some(editor) = editor.filter(|editor| !editor.trim().is_empty()) else {
+        writeln!(
+            stderr(),
+            "Set $EDITOR to open overrides, or edit {}",
+            path.display()
+        )?;
+        return Ok(());
+    };
+
+    // Run through the shell like git does, so editors with quoted paths or arguments work
+    let shell = ShellFlavor::from_env();
+    let script = format!("{editor} {}", shell.quote(&path.to_string_lossy()));
+    let status = Command::from(shell.command(&script)).status().await?;
+    if !status.success() {
+        bail!("{editor} exited with {status}");
+    }
+
+    load_prompt(name, dirs).map_err(|err| anyhow!(err))?;
+    Ok(())
+}
@@ -0,0 +1,261 @@
+//! First-run setup wizard
+//!
+//! Walks new users through choosing providers, entering API keys, picking the
+//! default high and medium models, and enabling local models through Ollama.
+//! Runs before the first interactive session when `~/.merlin/config.toml` does
+//! not exist yet, or explicitly with `merlin setup`. With `--non-interactive`
+//! every answer comes from flags, so CI can provision a config without a terminal.
+
+mod prompt;
+mod steps;
+
+use anyhow::{Context as _, Result, bail};
+use merlin_local::OllamaManager;
+use merlin_providers::{GroqProvider, OpenRouterProvider};
+use std::env;
+use std::io::{IsTerminal as _, Write as _, stdin, stdout};
+
+use crate::config::{Config, ConfigManager};
+use prompt::Prompter;
+use steps::run_interactive;
+
+/// Env var consulted for the `OpenRouter` key in non-interactive mode
+const ENV_OPENROUTER_API_KEY: &str = "OPENROUTER_API_KEY";
+/// Env var consulted for the Groq key in non-interactive mode
+const ENV_GROQ_API_KEY: &str = "GROQ_API_KEY";
+
+/// Options for `merlin setup`
+#[derive(Debug, Default)]
+pub struct SetupArgs {
+    /// Take every answer from flags instead of prompting (for CI provisioning)
+    pub non_interactive: bool,
+    /// `OpenRouter` API key (falls back to `OPENROUTER_API_KEY`)
+    pub openrouter_key: Option<String>,
+    /// Groq API key (falls back to `GROQ_API_KEY`)
+    pub groq_key: Option<String>,
+    /// Default high-complexity model
+    pub high_model: Option<String>,
+    /// Default medium-complexity model
+    pub medium_model: Option<String>,
+    /// Enable local models through Ollama with this model
+    pub ollama_model: Option<String>,
+    /// Skip the live key, model and Ollama checks
+    pub skip_checks: bool,
+}
+
+/// Provider settings chosen during setup
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SetupChoices {
+    /// `OpenRouter` API key (None disables remote premium models)
+    pub openrouter_key: Option<String>,
+    /// Groq API key (None disables the Groq tier)
+    pub groq_key: Option<String>,
+    /// Default high-complexity model (None keeps the current model)
+    pub high_model: Option<String>,
+    /// Default medium-complexity model (None keeps the current model)
+    pub medium_model: Option<String>,
+    /// Ollama model for the local tier (None disables local models)
+    pub local_model: Option<String>,
+}
+
+impl SetupChoices {
+    /// Takes the choices from `merlin setup` flags
+    ///
+    /// # Errors
+    /// Returns an error if a remote model is given without an `OpenRouter` key
+    pub fn from_args(args: SetupArgs) -> Result<Self> {
+        if args.openrouter_key.is_none()
+            && (args.high_model.is_some() || args.medium_model.is_some())
+        {
+            bail!(
+                "--high-model and --medium-model need an OpenRouter key \
+                 (--openrouter-key or {ENV_OPENROUTER_API_KEY})"
+            );
+        }
+
+        Ok(Self {
+            openrouter_key: args.openrouter_key,
+            groq_key: args.groq_key,
+            high_model: args.high_model,
+            medium_model: args.medium_model,
+            local_model: args.ollama_model,
+        })
+    }
+
+    /// Writes the choices into `config`
+    ///
+    /// Tiers without a key are disabled, so routing does not fail on startup
+    /// looking for a key that was never configured.
+    pub fn apply(self, config: &mut Config) {
+        config.tiers.premium_enabled = self.openrouter_key.is_some();
+        config
+            .api_keys
+            .openrouter_api_key
+            .clone_from(&self.openrouter_key);
+        config.providers.openrouter_key = self.openrouter_key;
+        if let Some(model) = self.high_model {
+            config.providers.high_model = Some(model);
+        }
+        if let Some(model) = self.medium_model {
+            config.providers.medium_model = Some(model);
+        }
+
+        config.tiers.groq_enabled = self.groq_key.is_some();
+        config.api_keys.groq_api_key = self.groq_key;
+
+        config.tiers.local_enabled = self.local_model.is_some();
+        if let Some(model) = self.local_model {
+            config.tiers.local_model = model;
+        }
+    }
+
+    /// Checks keys, models and the Ollama installation with live requests
+    ///
+    /// # Errors
+    /// Returns an error describing the first check that failed
+    async fn verify(&self) -> Result<()> {
+        if let Some(ref key) = self.openrouter_key {
+            KeyedProvider::OpenRouter
+                .check_key(key)
+                .await
+                .context("OpenRouter key check failed")?;
+            let models = OpenRouterProvider::new(key.clone())?
+                .list_models()
+                .await
+                .context("Failed to fetch the OpenRouter model list")?;
+            for model in [&self.high_model, &self.medium_model].into_iter().flatten() {
+                if !models.contains(model) {
+                    bail!("Unknown OpenRouter model: {model}");
+                }
+            }
+        }
+
+        if let Some(ref key) = self.groq_key {
+            KeyedProvider::Groq
+                .check_key(key)
+                .await
+                .context("Groq key check failed")?;
+        }
+
+        if let Some(ref model) = self.local_model {
+            let installed = OllamaManager::default()
+                .list_models()
+                .await
+                .context("Ollama is not reachable")?;
+            if find_installed(&installed, model).is_none() {
+                bail!("Ollama model {model} is not installed (run `ollama pull {model}`)");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remote provider that needs an API key
+#[derive(Debug, Clone, Copy)]
+enum KeyedProvider {
+    /// `OpenRouter`, serving the premium models
+    OpenRouter,
+    /// Groq, serving fast open models
+    Groq,
+}
+
+impl KeyedProvider {
+    /// Display name of the provider
+    const fn name(self) -> &'static str {
+        match self {
+            Self::OpenRouter => "OpenRouter",
+            Self::Groq => "Groq",
+        }
+    }
+
+    /// Question asked before configuring the provider
+    const fn question(self) -> &'static str {
+        match self {
+            Self::OpenRouter => "Use OpenRouter for remote models (Claude, GPT, ...)?",
+            Self::Groq => "Use Groq for fast open models?",
+        }
+    }
+
+    /// Sends a live request to check that the provider accepts `key`
+    ///
+    /// # Errors
+    /// Returns an error if the request fails or the key is rejected
+    async fn check_key(self, key: &str) -> Result<()> {
+        match self {
+            Self::OpenRouter => {
+                OpenRouterProvider::new(key.to_owned())?
+                    .validate_key()
+                    .await?;
+            }
+            Self::Groq => {
+                GroqProvider::with_api_key_direct(key.to_owned())?
+                    .validate_key()
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs `merlin setup` and saves the result through `ConfigManager`
+///
+/// Prompts on the terminal unless `--non-interactive` is set or stdin is not a
+/// terminal, in which case the answers come from flags and environment variables.
+///
+/// # Errors
+/// Returns an error if a check fails, the wizard is cancelled, or the config cannot be saved
+pub async fn run_setup(mut args: SetupArgs) -> Result<()> {
+    let manager = ConfigManager::new().await?;
+
+    let choices = if args.non_interactive || !stdin().is_terminal() {
+        args.openrouter_key = args
+            .openrouter_key
+            .or_else(|| env::var(ENV_OPENROUTER_API_KEY).ok());
+        args.groq_key = args.groq_key.or_else(|| env::var(ENV_GROQ_API_KEY).ok());
+        let skip_checks = args.skip_checks;
+
+        let choices = SetupChoices::from_args(args)?;
+        if !skip_checks {
+            choices.verify().await?;
+        }
+        choices
+    } else {
+        let current = Config::clone(&*manager.get()?);
+        run_interactive(&mut Prompter::terminal(), &current).await?
+    };
+
+    manager.update(|config| choices.apply(config)).await?;
+    stdout().write_all(
+        format!("Saved configuration to {}\n", manager.config_path.display()).as_bytes(),
+    )?;
+    Ok(())
+}
+
+/// Runs the wizard before the first interactive session if no config exists yet
+///
+/// Does nothing when stdin is not a terminal, so scripted runs keep relying on
+/// environment variables.
+///
+/// # Errors
+/// Returns an error if the wizard is cancelled or fails
+pub async fn run_first_time_setup() -> Result<()> {
+    let manager = ConfigManager::new().await?;
+    if manager.exists() || !stdin().is_terminal() {
+        return Ok(());
+    }
+
+    run_setup(SetupArgs::default())
+        .await
+        .context("Setup did not finish; run `merlin setup` to try again")
+}
+
+/// Finds `model` among installed Ollama models, treating a missing tag as `:latest`
+fn find_installed<'models>(installed: &'models [String], model: &str) -> Option<&'models String> {
+    let latest = format!("{model}:latest");
+    installed
+        .iter()
+        .find(|name| **name == model || **name == latest)
+}
+
+#[cfg(test)]
+mod tests;
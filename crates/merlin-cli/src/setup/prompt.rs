@@ -0,0 +1,193 @@
+//! Line-based prompts used by the setup wizard
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, read};
+use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+use std::io::{self, BufRead, StdinLock, Stdout, Write, stdin, stdout};
+
+/// Asks questions on a reader/writer pair
+pub struct Prompter<R, W> {
+    /// Where answers are read from
+    input: R,
+    /// Where questions are written to
+    output: W,
+    /// Read secrets from the terminal without echoing them
+    mask_secrets: bool,
+}
+
+impl Prompter<StdinLock<'static>, Stdout> {
+    /// Creates a prompter on the process's terminal, masking secret input
+    pub fn terminal() -> Self {
+        Self {
+            input: stdin().lock(),
+            output: stdout(),
+            mask_secrets: true,
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Creates a prompter that reads every answer, including secrets, as plain lines
+    #[cfg(test)]
+    pub const fn new(input: R, output: W) -> Self {
+        Self {
+            input,
+            output,
+            mask_secrets: false,
+        }
+    }
+
+    /// Writes a line of text
+    ///
+    /// # Errors
+    /// Returns an error if the output cannot be written
+    pub fn say(&mut self, message: &str) -> io::Result<()> {
+        writeln!(self.output, "{message}")
+    }
+
+    /// Asks a question and returns the trimmed answer
+    ///
+    /// # Errors
+    /// Returns an error if the input is closed or cannot be read
+    pub fn ask(&mut self, question: &str) -> io::Result<String> {
+        write!(self.output, "{question}")?;
+        self.output.flush()?;
+
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Input closed before setup finished",
+            ));
+        }
+        Ok(answer.trim().to_owned())
+    }
+
+    /// Asks a yes/no question, returning `default` for an empty answer
+    ///
+    /// # Errors
+    /// Returns an error if the input is closed or cannot be read
+    pub fn confirm(&mut self, question: &str, default: bool) -> io::Result<bool> {
+        let hint = if default { "[Y/n]" } else { "[y/N]" };
+        loop {
+            match self
+                .ask(&format!("{question} {hint} "))?
+                .to_lowercase()
+                .as_str()
+            {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => self.say("Please answer y or n")?,
+            }
+        }
+    }
+
+    /// Asks for a secret such as an API key without echoing it
+    ///
+    /// # Errors
+    /// Returns an error if the input is closed, cannot be read, or is cancelled
+    pub fn secret(&mut self, question: &str) -> io::Result<String> {
+        if !self.mask_secrets {
+            return self.ask(question);
+        }
+
+        write!(self.output, "{question}")?;
+        self.output.flush()?;
+        let secret = read_masked(&mut self.output)?;
+        write!(self.output, "\r\n")?;
+        Ok(secret.trim().to_owned())
+    }
+}
+
+/// Restores cooked terminal mode when dropped
+struct RawModeGuard;
+
+impl RawModeGuard {
+    /// Switches the terminal to raw mode
+    ///
+    /// # Errors
+    /// Returns an error if raw mode cannot be enabled
+    fn enable() -> io::Result<Self> {
+        enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        if let Err(err) = disable_raw_mode() {
+            tracing::warn!("Failed to restore terminal mode: {err}");
+        }
+    }
+}
+
+/// Reads a line from the terminal, echoing `*` for each character
+///
+/// # Errors
+/// Returns an error if terminal events cannot be read or input is cancelled with Esc or Ctrl+C
+fn read_masked(output: &mut impl Write) -> io::Result<String> {
+    let _raw_mode = RawModeGuard::enable()?;
+    let mut secret = String::new();
+
+    loop {
+        let Event::Key(key) = read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Enter => return Ok(secret),
+            KeyCode::Esc => return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled")),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"));
+            }
+            KeyCode::Backspace => {
+                if secret.pop().is_some() {
+                    write!(output, "\x08 \x08")?;
+                }
+            }
+            KeyCode::Char(character) => {
+                secret.push(character);
+                write!(output, "*")?;
+            }
+            _ => continue,
+        }
+        output.flush()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Tests yes/no answers, defaults and re-asking on invalid input.
+    ///
+    /// # Errors
+    /// Returns an error if reading answers fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_confirm() -> io::Result<()> {
+        let mut output = Vec::new();
+        let mut prompter = Prompter::new(Cursor::new("\nmaybe\nno\nYES\n"), &mut output);
+
+        assert!(prompter.confirm("Continue?", true)?);
+        assert!(!prompter.confirm("Continue?", true)?);
+        assert!(prompter.confirm("Continue?", false)?);
+        assert_eq!(
+            prompter
+                .confirm("Continue?", false)
+                .map_err(|err| err.kind()),
+            Err(io::ErrorKind::UnexpectedEof)
+        );
+
+        let transcript = String::from_utf8_lossy(&output);
+        assert!(transcript.contains("Continue? [Y/n] "));
+        assert!(transcript.contains("Please answer y or n"));
+        Ok(())
+    }
+}
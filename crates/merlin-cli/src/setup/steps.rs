@@ -0,0 +1,240 @@
+//! Interactive wizard steps: provider keys, model choice and local models
+
+use anyhow::Result;
+use merlin_local::OllamaManager;
+use merlin_providers::OpenRouterProvider;
+use std::io::{self, BufRead, Write};
+
+use super::prompt::Prompter;
+use super::{KeyedProvider, SetupChoices, find_installed};
+use crate::config::{Config, ProvidersConfig};
+
+/// Maximum number of models listed for a search while picking a model
+const MODEL_SEARCH_LIMIT: usize = 10;
+
+/// Asks through every step of the wizard, starting from the current `config`
+///
+/// # Errors
+/// Returns an error if the input is closed or cancelled
+pub(super) async fn run_interactive<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    config: &Config,
+) -> Result<SetupChoices> {
+    prompter.say("Welcome to Merlin! Let's set up the models it can use.")?;
+    let mut choices = SetupChoices {
+        openrouter_key: ask_api_key(
+            prompter,
+            KeyedProvider::OpenRouter,
+            config.providers.openrouter_key.as_deref(),
+            true,
+        )
+        .await?,
+        ..SetupChoices::default()
+    };
+
+    if let Some(ref key) = choices.openrouter_key {
+        let models = fetch_models(prompter, key).await?;
+        let defaults = ProvidersConfig::default();
+        let high_default = config
+            .providers
+            .high_model
+            .clone()
+            .or(defaults.high_model)
+            .unwrap_or_default();
+        let medium_default = config
+            .providers
+            .medium_model
+            .clone()
+            .or(defaults.medium_model)
+            .unwrap_or_default();
+
+        prompter.say("Type part of a model name to search, or press Enter for the default.")?;
+        choices.high_model = Some(choose_model(
+            prompter,
+            "High-complexity",
+            &models,
+            &high_default,
+        )?);
+        choices.medium_model = Some(choose_model(
+            prompter,
+            "Medium-complexity",
+            &models,
+            &medium_default,
+        )?);
+    }
+
+    choices.groq_key = ask_api_key(
+        prompter,
+        KeyedProvider::Groq,
+        config.api_keys.groq_api_key.as_deref(),
+        false,
+    )
+    .await?;
+    choices.local_model = ask_ollama_model(prompter, &config.tiers.local_model).await?;
+    Ok(choices)
+}
+
+/// Asks whether to use `provider` and for its API key, checking the key with a live request
+///
+/// Returns `None` if the provider is skipped.
+///
+/// # Errors
+/// Returns an error if the input is closed or cancelled
+async fn ask_api_key<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    provider: KeyedProvider,
+    current: Option<&str>,
+    use_by_default: bool,
+) -> Result<Option<String>> {
+    if !prompter.confirm(provider.question(), use_by_default)? {
+        return Ok(None);
+    }
+
+    let name = provider.name();
+    loop {
+        let question = if current.is_some() {
+            format!("{name} API key (Enter keeps the current key): ")
+        } else {
+            format!("{name} API key: ")
+        };
+        let answer = prompter.secret(&question)?;
+        let key = match (answer.is_empty(), current) {
+            (false, _) => answer,
+            (true, Some(current)) => current.to_owned(),
+            (true, None) => continue,
+        };
+
+        prompter.say(&format!("Checking the key with {name}..."))?;
+        match provider.check_key(&key).await {
+            Ok(()) => {
+                prompter.say("Key accepted")?;
+                return Ok(Some(key));
+            }
+            Err(err) => {
+                prompter.say(&format!("Key check failed: {err:#}"))?;
+                if !prompter.confirm("Try a different key?", true)? {
+                    return Ok(prompter
+                        .confirm("Save this key anyway?", false)?
+                        .then_some(key));
+                }
+            }
+        }
+    }
+}
+
+/// Fetches the `OpenRouter` model list, returning an empty list if it is unavailable
+///
+/// # Errors
+/// Returns an error if the output cannot be written
+async fn fetch_models<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    key: &str,
+) -> Result<Vec<String>> {
+    let models = match OpenRouterProvider::new(key.to_owned()) {
+        Ok(provider) => provider.list_models().await,
+        Err(err) => Err(err.into()),
+    };
+    match models {
+        Ok(models) => {
+            prompter.say(&format!("Found {} models on OpenRouter.", models.len()))?;
+            Ok(models)
+        }
+        Err(err) => {
+            prompter.say(&format!(
+                "Could not fetch the model list ({err}); any model id will be accepted."
+            ))?;
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Asks whether to enable local models and which installed Ollama model to use
+///
+/// Returns `None` if local models are skipped.
+///
+/// # Errors
+/// Returns an error if the input is closed or cancelled
+async fn ask_ollama_model<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    suggested: &str,
+) -> Result<Option<String>> {
+    if !prompter.confirm("Enable local models through Ollama?", false)? {
+        return Ok(None);
+    }
+
+    let manager = OllamaManager::default();
+    loop {
+        match manager.list_models().await {
+            Ok(installed) if !installed.is_empty() => {
+                prompter.say(&format!("Installed models: {}", installed.join(", ")))?;
+                let default = find_installed(&installed, suggested)
+                    .or_else(|| installed.first())
+                    .cloned()
+                    .unwrap_or_default();
+                return Ok(Some(choose_model(prompter, "Local", &installed, &default)?));
+            }
+            Ok(_) => prompter.say(&format!(
+                "Ollama has no models installed. Install one with `ollama pull {suggested}`."
+            ))?,
+            Err(err) => prompter.say(&format!(
+                "Ollama is not reachable ({err}). Start it with `ollama serve`."
+            ))?,
+        }
+
+        if !prompter.confirm("Check again?", true)? {
+            return Ok(None);
+        }
+    }
+}
+
+/// Asks for a model from `models`, accepting a list number, an exact id, or search text
+///
+/// Any id is accepted when `models` is empty because the list could not be fetched.
+///
+/// # Errors
+/// Returns an error if the input is closed or cannot be read
+pub(super) fn choose_model<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    label: &str,
+    models: &[String],
+    default: &str,
+) -> io::Result<String> {
+    let mut matches: Vec<&String> = Vec::new();
+    loop {
+        let answer = prompter.ask(&format!("{label} model [{default}]: "))?;
+        if answer.is_empty() {
+            return Ok(default.to_owned());
+        }
+        if let Some(model) = answer
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| number.checked_sub(1))
+            .and_then(|index| matches.get(index))
+        {
+            return Ok((*model).clone());
+        }
+        if models.is_empty() || models.contains(&answer) {
+            return Ok(answer);
+        }
+
+        matches = search_models(models, &answer);
+        if matches.is_empty() {
+            prompter.say(&format!("No models match '{answer}'"))?;
+            continue;
+        }
+        for (index, model) in matches.iter().enumerate() {
+            prompter.say(&format!("  {}. {model}", index + 1))?;
+        }
+        prompter.say("Enter a number to pick a model, or search again")?;
+    }
+}
+
+/// Returns up to `MODEL_SEARCH_LIMIT` models whose id contains `query` (case-insensitive)
+fn search_models<'models>(models: &'models [String], query: &str) -> Vec<&'models String> {
+    let query = query.to_lowercase();
+    models
+        .iter()
+        .filter(|model| model.to_lowercase().contains(&query))
+        .take(MODEL_SEARCH_LIMIT)
+        .collect()
+}
@@ -0,0 +1,147 @@
+//! Tests for the setup wizard
+
+use super::steps::{choose_model, run_interactive};
+use super::*;
+use crate::config::ProvidersConfig;
+use std::io::{self, Cursor};
+
+/// Builds a list of model ids.
+fn models(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| (*id).to_owned()).collect()
+}
+
+/// Tests that remote models are rejected without an `OpenRouter` key.
+///
+/// # Errors
+/// Returns an error if valid flags are rejected.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_from_args_requires_key_for_models() -> Result<()> {
+    let missing_key = SetupChoices::from_args(SetupArgs {
+        high_model: Some("openai/gpt-4o".to_owned()),
+        ..SetupArgs::default()
+    });
+    assert!(missing_key.is_err_and(|err| err.to_string().contains("need an OpenRouter key")));
+
+    let choices = SetupChoices::from_args(SetupArgs {
+        openrouter_key: Some("sk-or-test".to_owned()),
+        high_model: Some("openai/gpt-4o".to_owned()),
+        ollama_model: Some("llama3".to_owned()),
+        ..SetupArgs::default()
+    })?;
+    assert_eq!(choices.high_model.as_deref(), Some("openai/gpt-4o"));
+    assert_eq!(choices.local_model.as_deref(), Some("llama3"));
+    Ok(())
+}
+
+/// Tests that applying choices writes keys and models and disables unconfigured tiers.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_apply_choices() {
+    let mut config = Config::default();
+    SetupChoices {
+        openrouter_key: Some("sk-or-test".to_owned()),
+        high_model: Some("openai/gpt-4o".to_owned()),
+        ..SetupChoices::default()
+    }
+    .apply(&mut config);
+
+    assert!(config.tiers.premium_enabled);
+    assert_eq!(
+        config.providers.openrouter_key.as_deref(),
+        Some("sk-or-test")
+    );
+    assert_eq!(
+        config.api_keys.openrouter_api_key.as_deref(),
+        Some("sk-or-test")
+    );
+    assert_eq!(
+        config.providers.high_model.as_deref(),
+        Some("openai/gpt-4o")
+    );
+    assert_eq!(
+        config.providers.medium_model,
+        ProvidersConfig::default().medium_model
+    );
+    assert!(!config.tiers.groq_enabled);
+    assert!(!config.tiers.local_enabled);
+}
+
+/// Tests picking models by default, search result number and exact id.
+///
+/// # Errors
+/// Returns an error if reading answers fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_choose_model() -> io::Result<()> {
+    let catalog = models(&[
+        "anthropic/claude-3.5-sonnet",
+        "anthropic/claude-sonnet-4",
+        "openai/gpt-4o",
+    ]);
+    let mut output = Vec::new();
+    let mut prompter = Prompter::new(
+        Cursor::new("\nSONNET\n2\nmistral\nopenai/gpt-4o\n"),
+        &mut output,
+    );
+
+    assert_eq!(
+        choose_model(&mut prompter, "High", &catalog, "openai/gpt-4o")?,
+        "openai/gpt-4o"
+    );
+    assert_eq!(
+        choose_model(&mut prompter, "High", &catalog, "openai/gpt-4o")?,
+        "anthropic/claude-sonnet-4"
+    );
+    assert_eq!(
+        choose_model(&mut prompter, "High", &catalog, "openai/gpt-4o")?,
+        "openai/gpt-4o"
+    );
+
+    let transcript = String::from_utf8_lossy(&output);
+    assert!(transcript.contains("  1. anthropic/claude-3.5-sonnet"));
+    assert!(transcript.contains("No models match 'mistral'"));
+    Ok(())
+}
+
+/// Tests that skipping every provider disables all tiers without network access.
+///
+/// # Errors
+/// Returns an error if the wizard fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_interactive_skip_all_providers() -> Result<()> {
+    let mut output = Vec::new();
+    let mut prompter = Prompter::new(Cursor::new("n\n\n\n"), &mut output);
+
+    let choices = run_interactive(&mut prompter, &Config::default()).await?;
+    assert_eq!(choices, SetupChoices::default());
+
+    let mut config = Config::default();
+    choices.apply(&mut config);
+    assert!(!config.tiers.premium_enabled);
+    assert!(!config.tiers.groq_enabled);
+    assert!(!config.tiers.local_enabled);
+    Ok(())
+}
+
+/// Tests matching Ollama model names with and without tags.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_find_installed() {
+    let installed = models(&["llama3:latest", "qwen2.5-coder:7b"]);
+
+    assert!(find_installed(&installed, "llama3").is_some());
+    assert!(find_installed(&installed, "qwen2.5-coder:7b").is_some());
+    assert!(find_installed(&installed, "qwen2.5-coder").is_none());
+}
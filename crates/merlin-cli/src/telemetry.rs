@@ -0,0 +1,156 @@
+//! Tracing subscriber setup with optional OpenTelemetry export
+//!
+//! Spans are always written to the debug log. When built with the `otlp` feature
+//! and started with `--otlp-endpoint`, they are also exported over OTLP/HTTP
+//! (e.g. to Jaeger, Tempo or Honeycomb). When built with the `metrics` feature
+//! and started with `MERLIN_METRICS_PORT` set, request metrics are served for
+//! Prometheus on `/metrics`.
+
+use anyhow::Result;
+use merlin_routing::MetricsCollector;
+use std::env;
+use std::fs::File;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::{
+    EnvFilter, Registry, fmt, layer::SubscriberExt as _, util::SubscriberInitExt as _,
+};
+
+#[cfg(feature = "metrics")]
+use anyhow::Context as _;
+#[cfg(any(not(feature = "otlp"), not(feature = "metrics")))]
+use anyhow::bail;
+#[cfg(feature = "metrics")]
+use merlin_routing::metrics::prometheus::{METRICS_PATH, serve};
+#[cfg(feature = "otlp")]
+use opentelemetry::trace::TracerProvider as _;
+#[cfg(feature = "otlp")]
+use opentelemetry_otlp::{SpanExporter, WithExportConfig as _};
+#[cfg(feature = "otlp")]
+use opentelemetry_sdk::{Resource, trace::SdkTracerProvider};
+#[cfg(feature = "metrics")]
+use std::net::{Ipv4Addr, TcpListener as StdTcpListener};
+#[cfg(feature = "metrics")]
+use tokio::net::TcpListener;
+#[cfg(feature = "metrics")]
+use tokio::spawn;
+#[cfg(feature = "otlp")]
+use tracing_opentelemetry::layer as otel_layer;
+
+/// Environment variable holding the port of the Prometheus metrics endpoint
+pub const METRICS_PORT_ENV: &str = "MERLIN_METRICS_PORT";
+
+/// Service name attached to exported spans
+#[cfg(feature = "otlp")]
+const SERVICE_NAME: &str = "merlin";
+
+/// Keeps trace export running; pending spans are flushed when dropped
+#[must_use = "dropping the guard stops trace export"]
+pub struct TelemetryGuard {
+    /// Tracer provider backing the OTLP exporter, if one was configured
+    #[cfg(feature = "otlp")]
+    provider: Option<SdkTracerProvider>,
+}
+
+#[cfg(feature = "otlp")]
+impl Drop for TelemetryGuard {
+    fn drop(&mut self) {
+        if let Some(provider) = self.provider.take()
+            && let Err(error) = provider.shutdown()
+        {
+            tracing::warn!("Failed to flush OTLP traces: {error}");
+        }
+    }
+}
+
+/// Installs the global tracing subscriber, logging to `log_file`
+///
+/// # Errors
+/// Returns an error if `otlp_endpoint` is set but the exporter cannot be created,
+/// or if merlin was built without the `otlp` feature
+pub fn init_tracing(log_file: File, otlp_endpoint: Option<&str>) -> Result<TelemetryGuard> {
+    let registry = Registry::default()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+            "merlin_context=info,merlin_routing=info,agentic_optimizer=info".into()
+        }))
+        .with(
+            fmt::layer()
+                .with_writer(Arc::new(log_file))
+                .with_ansi(false)
+                .with_target(true)
+                .with_level(true),
+        );
+
+    #[cfg(feature = "otlp")]
+    {
+        let provider = otlp_endpoint.map(build_tracer_provider).transpose()?;
+        let otlp = provider
+            .as_ref()
+            .map(|provider| otel_layer().with_tracer(provider.tracer(SERVICE_NAME)));
+        registry.with(otlp).init();
+        Ok(TelemetryGuard { provider })
+    }
+
+    #[cfg(not(feature = "otlp"))]
+    {
+        if otlp_endpoint.is_some() {
+            bail!("--otlp-endpoint requires merlin to be built with the `otlp` feature");
+        }
+        registry.init();
+        Ok(TelemetryGuard {})
+    }
+}
+
+/// Serves `collector` for Prometheus on all interfaces when `MERLIN_METRICS_PORT` is set
+///
+/// The endpoint runs on a background task for the rest of the process.
+///
+/// # Errors
+/// Returns an error if the port is invalid or cannot be bound, or if merlin was
+/// built without the `metrics` feature
+pub fn start_metrics_endpoint(collector: Arc<Mutex<MetricsCollector>>) -> Result<()> {
+    let Ok(port) = env::var(METRICS_PORT_ENV) else {
+        return Ok(());
+    };
+
+    #[cfg(feature = "metrics")]
+    {
+        let port: u16 = port
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid {METRICS_PORT_ENV}: {port}"))?;
+        let listener = StdTcpListener::bind((Ipv4Addr::UNSPECIFIED, port))
+            .with_context(|| format!("Failed to bind the metrics endpoint to port {port}"))?;
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        tracing::info!("Serving Prometheus metrics on port {port} at {METRICS_PATH}");
+        spawn(async move {
+            if let Err(err) = serve(listener, collector).await {
+                tracing::warn!("Metrics endpoint stopped: {err}");
+            }
+        });
+        Ok(())
+    }
+
+    #[cfg(not(feature = "metrics"))]
+    {
+        drop(collector);
+        bail!("{METRICS_PORT_ENV}={port} requires merlin to be built with the `metrics` feature");
+    }
+}
+
+/// Creates a tracer provider that batches spans to the OTLP/HTTP `endpoint`
+///
+/// # Errors
+/// Returns an error if the exporter cannot be built
+#[cfg(feature = "otlp")]
+fn build_tracer_provider(endpoint: &str) -> Result<SdkTracerProvider> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name(SERVICE_NAME).build())
+        .build())
+}
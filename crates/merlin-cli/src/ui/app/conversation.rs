@@ -3,6 +3,7 @@
 //! Handles conversation history extraction from tasks in a thread,
 //! formatting for context building, and thread context management.
 
+use merlin_core::TIMING_MARKER;
 use merlin_routing::TaskId;
 
 use crate::ui::task_manager::TaskManager;
@@ -27,12 +28,18 @@ pub fn get_conversation_history_from_task(
             history.push(("user".to_string(), task.description.clone()));
         }
 
-        // Add assistant response from output
-        if !task.output.is_empty()
-            && !task.output.contains("Saving task")
-            && !task.output.contains("Loading task")
+        // Add assistant response from output, without the timing lines
+        let response = task
+            .output
+            .lines()
+            .filter(|line| !line.trim_start().starts_with(TIMING_MARKER))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !response.is_empty()
+            && !response.contains("Saving task")
+            && !response.contains("Loading task")
         {
-            history.push(("assistant".to_string(), task.output.clone()));
+            history.push(("assistant".to_string(), response));
         }
     }
 
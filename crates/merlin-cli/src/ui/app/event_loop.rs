@@ -4,16 +4,26 @@ use crossterm::event::{Event, KeyEventKind};
 use merlin_routing::{Result, RoutingError, Task, UiEvent};
 use ratatui::backend::Backend;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use tokio::time::{MissedTickBehavior, interval};
 
 use super::navigation;
+use super::session_recovery::RETRY_COMMAND;
+use super::shutdown::shutdown_tick;
 use super::task_execution::TaskExecutionParams;
+use super::theme_reload::ThemeReloadSignal;
 use super::tui_app::TuiApp;
 use crate::ui::app::navigation::ScrollContext;
-use crate::ui::event_handler::EventHandler;
+use crate::ui::event_handler::{ANSWER_MARKER, EventHandler};
 use crate::ui::renderer::{FocusedPane, RenderCtx, UiCtx};
+use crate::ui::spinner::SPINNER_INTERVAL;
 use crate::ui::state::{ConversationEntry, ConversationRole};
-use crate::ui::task_manager::TaskDisplay;
+use crate::ui::task_manager::{TaskDisplay, TaskStatus};
+
+/// How often session statistics are refreshed for the status bar
+const SESSION_STATS_INTERVAL: Duration = Duration::from_secs(2);
+/// Input command running a prompt as subtasks the model splits it into
+pub const SPLIT_COMMAND: &str = "/split";
 
 impl<B: Backend> TuiApp<B> {
     /// Run the main event loop until quit
@@ -24,13 +34,21 @@ impl<B: Backend> TuiApp<B> {
     /// # Errors
     /// Returns an error if event processing or rendering fails.
     pub async fn run_event_loop(&mut self) -> Result<()> {
+        self.listen_for_shutdown_signals();
+
+        let mut stats_interval = interval(SESSION_STATS_INTERVAL);
+        stats_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut spinner_interval = interval(SPINNER_INTERVAL);
+        spinner_interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut theme_reload = ThemeReloadSignal::listen();
+
         loop {
             tokio::select! {
                 // Wait for input event from async stream
                 event_result = self.event_system.source.next_event() => {
                     match event_result {
                         Ok(Some(event)) => {
-                            if self.handle_input(&event) {
+                            if self.handle_input(&event) && self.request_shutdown() {
                                 break; // Quit requested
                             }
                         }
@@ -49,11 +67,29 @@ impl<B: Backend> TuiApp<B> {
                 Some(ui_event) = self.event_system.receiver.recv() => {
                     self.handle_ui_event(ui_event);
                 }
+
+                // Periodically refresh session-wide statistics
+                _ = stats_interval.tick() => self.refresh_session_stats(),
+
+                // Animate the spinner, only redrawing for it while tasks run
+                _ = spinner_interval.tick(), if !self.ui_components.state.active_running_tasks.is_empty() => self.tick(),
+
+                // Reload the custom theme on SIGHUP
+                () = theme_reload.recv() => self.reload_custom_theme(),
+
+                // Wake up to track shutdown started by a signal or quit request
+                () = shutdown_tick(self.runtime_state.orchestrator.as_deref()) => {}
             }
 
+            let shutdown_complete = self.update_shutdown_progress();
+
             // Render after processing any event
             self.render()?;
             self.ui_components.last_render_time = Instant::now();
+
+            if shutdown_complete {
+                break;
+            }
         }
 
         Ok(())
@@ -61,12 +97,18 @@ impl<B: Backend> TuiApp<B> {
 
     /// Handle an input event and return true if the app should quit
     fn handle_input(&mut self, event: &Event) -> bool {
-        if let Event::Key(key) = event
-            && matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat)
-        {
-            return self.handle_key_event(key);
+        match event {
+            Event::Key(key) if matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat) => {
+                self.handle_key_event(key)
+            }
+            Event::FocusGained | Event::FocusLost => {
+                if let Some(ref mut notifier) = self.runtime_state.notifier {
+                    notifier.set_terminal_focused(matches!(event, Event::FocusGained));
+                }
+                false
+            }
+            _ => false,
         }
-        false
     }
 
     /// Handle a UI event from the orchestrator
@@ -74,6 +116,9 @@ impl<B: Backend> TuiApp<B> {
         // Broadcast to observers
         drop(self.event_system.broadcast.send(ui_event.clone()));
 
+        self.notify_if_task_finished(&ui_event);
+        self.ui_components.state.coalesced_events = self.event_system.receiver.coalesced_events();
+
         // Handle the event
         let persistence = self.runtime_state.persistence.as_ref();
         let mut handler = EventHandler::new(
@@ -86,6 +131,40 @@ impl<B: Backend> TuiApp<B> {
         self.adjust_task_list_scroll();
     }
 
+    /// Notifies the user when a long-running task completes or fails
+    fn notify_if_task_finished(&mut self, ui_event: &UiEvent) {
+        let (task_id, status) = match *ui_event {
+            UiEvent::TaskCompleted { task_id, .. } => (task_id, TaskStatus::Completed),
+            UiEvent::TaskFailed { task_id, .. } => (task_id, TaskStatus::Failed),
+            _ => return,
+        };
+        let (Some(notifier), Some(task)) = (
+            self.runtime_state.notifier.as_mut(),
+            self.ui_components.task_manager.get_task(task_id),
+        ) else {
+            return;
+        };
+        let elapsed = self.ui_components.task_manager.elapsed(task);
+        notifier.task_finished(&task.description, status, elapsed);
+    }
+
+    /// Publishes a `SessionStats` event with the orchestrator's latest statistics
+    /// Advances the running task spinner to its next frame
+    pub const fn tick(&mut self) {
+        self.ui_components.state.spinner_tick =
+            self.ui_components.state.spinner_tick.wrapping_add(1);
+    }
+
+    fn refresh_session_stats(&mut self) {
+        let Some(ref orchestrator) = self.runtime_state.orchestrator else {
+            return;
+        };
+        match orchestrator.session_cost() {
+            Ok(session_cost) => self.handle_ui_event(UiEvent::SessionStats { session_cost }),
+            Err(err) => tracing::warn!("Failed to read session stats: {err}"),
+        }
+    }
+
     /// Submits the current input if non-empty and returns true if it indicates quitting
     pub(super) fn submit_input(&mut self) -> bool {
         let input = self.ui_components.input_manager.input_area().lines()[0]
@@ -96,15 +175,43 @@ impl<B: Backend> TuiApp<B> {
             return false;
         }
 
+        if self.answer_question(&input) {
+            self.ui_components.input_manager.clear();
+            return false;
+        }
+
         if input.eq_ignore_ascii_case("exit") || input.eq_ignore_ascii_case("quit") {
             return true;
         }
 
+        if input.eq_ignore_ascii_case(RETRY_COMMAND) {
+            self.retry_interrupted_task();
+            self.ui_components.input_manager.clear();
+            return false;
+        }
+
+        if self.handle_pin_command(&input) || self.handle_cd_command(&input) {
+            self.ui_components.input_manager.clear();
+            return false;
+        }
+
+        let split_prompt = split_command_prompt(&input);
+        if split_prompt == Some("") {
+            self.ui_components
+                .show_notice(format!("Usage: {SPLIT_COMMAND} <prompt>"));
+            self.ui_components.input_manager.clear();
+            return false;
+        }
+
         // Check if there's already work running
         let has_running_work = !self.ui_components.state.active_running_tasks.is_empty();
 
         if has_running_work {
-            // Queue the input for later processing
+            // Queue the input for later processing, journaled so it survives a restart
+            if let Some(ref orchestrator) = self.runtime_state.orchestrator {
+                orchestrator
+                    .record_queued_prompt(&input, self.ui_components.state.active_thread_id);
+            }
             self.ui_components.state.queued_input = Some(input);
             self.ui_components.state.processing_status =
                 Some("[Work in progress. Press 'c' to cancel, 'a' to queue]".to_string());
@@ -112,6 +219,38 @@ impl<B: Backend> TuiApp<B> {
             return false;
         }
 
+        match split_prompt {
+            Some(prompt) => self.start_task_with(prompt.to_owned(), true),
+            None => self.start_task(input),
+        }
+        self.ui_components.input_manager.clear();
+        false
+    }
+
+    /// Answers the oldest question a task asked with `input`, if one is pending
+    ///
+    /// Questions whose task stopped waiting are skipped.
+    fn answer_question(&mut self, input: &str) -> bool {
+        while let Some(question) = self.ui_components.state.pending_questions.pop_front() {
+            let answer = question.resolve(input);
+            if question.answer.answer(answer.clone()) {
+                self.handle_ui_event(UiEvent::TaskOutput {
+                    task_id: question.task_id,
+                    output: format!("{ANSWER_MARKER} {answer}"),
+                });
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Starts a task for `input` in the active thread, creating a thread if none is active
+    pub(super) fn start_task(&mut self, input: String) {
+        self.start_task_with(input, false);
+    }
+
+    /// Starts a task for `input`, split into child tasks by the model if `decompose` is set
+    fn start_task_with(&mut self, input: String, decompose: bool) {
         // If a task is selected, we're continuing that conversation
         if self.ui_components.state.active_task_id.is_some() {
             self.ui_components.state.continuing_conversation_from =
@@ -129,17 +268,7 @@ impl<B: Backend> TuiApp<B> {
         self.ui_components.state.processing_status = Some("[Processing...]".to_string());
 
         // Create thread immediately if none exists, so UI shows it right away
-        if self.ui_components.state.active_thread_id.is_none()
-            && let Ok(mut store) = self.runtime_state.thread_store.lock()
-        {
-            let thread_name = input.chars().take(30).collect::<String>();
-            let thread = store.create_thread(thread_name);
-            let tid = thread.id;
-            if let Err(save_err) = store.save_thread(&thread) {
-                tracing::warn!("Failed to create thread: {save_err}");
-            }
-            self.ui_components.state.active_thread_id = Some(tid);
-        }
+        self.ensure_active_thread(&input);
 
         // Create task ID immediately so it shows in the UI before async execution starts
         let task = Task::new(input.clone());
@@ -171,19 +300,19 @@ impl<B: Backend> TuiApp<B> {
                 parent_task_id,
                 conversation_history,
                 thread_id: self.ui_components.state.active_thread_id,
+                decompose,
             });
         } else {
             self.ui_components.pending_input = Some(input);
         }
-
-        self.ui_components.input_manager.clear();
-        false
     }
 
     /// Cycles to the next theme and auto-saves via `ConfigManager`
     pub(super) fn cycle_theme(&mut self) {
         let new_theme = self.ui_components.renderer.theme().next();
         self.ui_components.renderer.set_theme(new_theme);
+        self.ui_components
+            .show_notice(format!("Theme: {}", new_theme.name()));
 
         // Update config (auto-saves when guard is dropped)
         if let Ok(mut config) = self.config_manager.get_mut() {
@@ -193,7 +322,7 @@ impl<B: Backend> TuiApp<B> {
 
     /// Adjusts task list scroll to keep the selected task visible
     pub(super) fn adjust_task_list_scroll(&mut self) {
-        let terminal_height = self.terminal.size().map(|size| size.height).unwrap_or(30);
+        let terminal_height = self.terminal.size().map_or(30, |size| size.height);
         navigation::adjust_task_list_scroll(&mut ScrollContext {
             active_task_id: self.ui_components.state.active_task_id.as_ref(),
             expanded_conversations: &self.ui_components.state.expanded_conversations,
@@ -212,12 +341,6 @@ impl<B: Backend> TuiApp<B> {
         // Ensure scroll is correct before rendering (handles initial state)
         self.adjust_task_list_scroll();
 
-        // Auto-scroll output to bottom if flag is set
-        if self.ui_components.state.auto_scroll_output_to_bottom {
-            self.auto_scroll_output_to_bottom();
-            self.ui_components.state.auto_scroll_output_to_bottom = false;
-        }
-
         let renderer = &self.ui_components.renderer;
         let task_manager = &self.ui_components.task_manager;
         let state = &self.ui_components.state;
@@ -243,3 +366,13 @@ impl<B: Backend> TuiApp<B> {
         Ok(())
     }
 }
+
+/// Prompt of a `/split` command, `None` if `input` is not one
+fn split_command_prompt(input: &str) -> Option<&str> {
+    let (command, prompt) = input
+        .split_once(char::is_whitespace)
+        .map_or((input, ""), |(command, prompt)| (command, prompt.trim()));
+    command
+        .eq_ignore_ascii_case(SPLIT_COMMAND)
+        .then_some(prompt)
+}
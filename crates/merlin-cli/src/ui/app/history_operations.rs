@@ -0,0 +1,86 @@
+//! `Ctrl+R` history search operations for TUI
+
+use super::tui_app::TuiApp;
+use crate::ui::history_search::{HistorySearch, history_entries, matching_entries};
+use crate::ui::renderer::FocusedPane;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::backend::Backend;
+
+impl<B: Backend> TuiApp<B> {
+    /// Opens the history search over the input pane
+    pub(super) fn open_history_search(&mut self) {
+        self.ui_components.focused_pane = FocusedPane::Input;
+        self.ui_components
+            .state
+            .history_search
+            .get_or_insert_with(HistorySearch::default);
+    }
+
+    /// Handles a key press while the history search is open
+    ///
+    /// Typing filters the past messages, Up/Down move the selection, Enter
+    /// pastes the selected message into the input without submitting it, and
+    /// Esc cancels.
+    pub(super) fn handle_history_search_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.ui_components.state.history_search = None;
+                return;
+            }
+            KeyCode::Enter => {
+                self.paste_history_selection();
+                return;
+            }
+            _ => {}
+        }
+
+        let match_count = self.history_matches().len();
+        let Some(search) = self.ui_components.state.history_search.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Up => search.selected = search.selected.saturating_sub(1),
+            KeyCode::Down => {
+                search.selected = (search.selected + 1).min(match_count.saturating_sub(1));
+            }
+            KeyCode::Backspace => {
+                search.query.pop();
+                search.selected = 0;
+            }
+            KeyCode::Char(character) => {
+                search.query.push(character);
+                search.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
+    /// Past user messages matching the open search, in display order
+    pub(super) fn history_matches(&self) -> Vec<String> {
+        let state = &self.ui_components.state;
+        let Some(search) = state.history_search.as_ref() else {
+            return Vec::new();
+        };
+        let store = self.runtime_state.thread_store.lock().ok();
+        let entries = history_entries(state, store.as_deref());
+        matching_entries(&entries, &search.query)
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    /// Closes the search, replacing the input with the selected message if any
+    fn paste_history_selection(&mut self) {
+        let selected = self
+            .ui_components
+            .state
+            .history_search
+            .as_ref()
+            .map_or(0, |search| search.selected);
+        let selection = self.history_matches().into_iter().nth(selected);
+        self.ui_components.state.history_search = None;
+        if let Some(text) = selection {
+            self.ui_components.input_manager.set_text(&text);
+        }
+    }
+}
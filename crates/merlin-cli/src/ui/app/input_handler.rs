@@ -9,6 +9,7 @@ use std::hash::BuildHasher;
 
 use crate::ui::input::InputManager;
 use crate::ui::renderer::FocusedPane;
+use crate::ui::scroll::OutputViewport;
 use crate::ui::task_manager::TaskManager;
 
 /// Handles key events when the input pane is focused
@@ -20,38 +21,17 @@ pub fn handle_input_key(key: &KeyEvent, input_manager: &mut InputManager, termin
 }
 
 /// Handles key events when the output pane is focused
-pub fn handle_output_key(
-    key: &KeyEvent,
-    active_task_id: Option<TaskId>,
-    output_scroll_offset: &mut u16,
-    max_scroll: u16,
-) {
-    if active_task_id.is_none() {
-        return;
-    }
-
+pub fn handle_output_key(key: &KeyEvent, viewport: &mut OutputViewport, max_scroll: u16) {
     match key.code {
         // Arrow keys and vim-style navigation scroll the text output
-        KeyCode::Up | KeyCode::Char('k') => {
-            *output_scroll_offset = output_scroll_offset.saturating_sub(1);
-        }
-        KeyCode::Down | KeyCode::Char('j') => {
-            *output_scroll_offset = (*output_scroll_offset).saturating_add(1).min(max_scroll);
-        }
-        // Home/End scroll to top/bottom of output
-        KeyCode::Home => {
-            *output_scroll_offset = 0;
-        }
-        KeyCode::End => {
-            *output_scroll_offset = max_scroll;
-        }
+        KeyCode::Up | KeyCode::Char('k') => viewport.scroll_up(1, max_scroll),
+        KeyCode::Down | KeyCode::Char('j') => viewport.scroll_down(1, max_scroll),
+        // Home/End scroll to top/bottom of output (End resumes follow mode)
+        KeyCode::Home => viewport.scroll_to_top(max_scroll),
+        KeyCode::End => viewport.scroll_to_bottom(),
         // PageUp/PageDown for faster scrolling
-        KeyCode::PageUp => {
-            *output_scroll_offset = output_scroll_offset.saturating_sub(10);
-        }
-        KeyCode::PageDown => {
-            *output_scroll_offset = (*output_scroll_offset).saturating_add(10).min(max_scroll);
-        }
+        KeyCode::PageUp => viewport.scroll_up(10, max_scroll),
+        KeyCode::PageDown => viewport.scroll_down(10, max_scroll),
         _ => {}
     }
 }
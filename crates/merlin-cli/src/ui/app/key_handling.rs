@@ -4,11 +4,15 @@ use super::input_handler;
 use super::tui_app::TuiApp;
 use crate::ui::app::navigation::{NavigationContext, navigate_tasks_down, navigate_tasks_up};
 use crate::ui::renderer::FocusedPane;
+use crate::ui::state::{InfoPanel, OutputWrap};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::backend::Backend;
 use std::collections::HashSet;
 use std::hash::Hash;
 
+/// Columns scrolled per Left/Right key press while word wrap is off
+const HORIZONTAL_SCROLL_STEP: u16 = 8;
+
 /// Toggle a value in a `HashSet` (remove if present, insert if absent)
 fn toggle_set<T: Eq + Hash>(set: &mut HashSet<T>, value: T) {
     if !set.remove(&value) {
@@ -21,34 +25,30 @@ impl<B: Backend> TuiApp<B> {
     pub fn handle_key_event(&mut self, key: &KeyEvent) -> bool {
         // Handle cancel/queue prompt keys if queued input exists
         if self.ui_components.state.queued_input.is_some() {
-            return match key.code {
-                KeyCode::Char('c') => {
-                    // Cancel current work and submit queued input
-                    self.ui_components.state.cancel_requested = true;
-                    if let Some(queued) = self.ui_components.state.queued_input.take() {
-                        self.ui_components.state.processing_status =
-                            Some("[Cancelling work...]".to_string());
-                        self.ui_components.pending_input = Some(queued);
-                    }
-                    false
-                }
-                KeyCode::Char('a') => {
-                    // Accept queue - just keep the queued input
-                    self.ui_components.state.processing_status =
-                        Some("[Input queued, will run after current work]".to_string());
-                    false
-                }
-                KeyCode::Esc => {
-                    // Discard queued input
-                    self.ui_components.state.queued_input = None;
-                    self.ui_components.state.processing_status = None;
-                    false
-                }
-                _ => {
-                    // Ignore other keys when prompt is showing
-                    false
-                }
-            };
+            self.handle_queued_input_key(key);
+            return false;
+        }
+
+        // Any key dismisses the statistics panel
+        if self.ui_components.state.info_panel != InfoPanel::Hidden {
+            self.ui_components.state.info_panel = InfoPanel::Hidden;
+            return false;
+        }
+
+        // Route plain keys to the history search while open
+        if self.ui_components.state.history_search.is_some()
+            && !key.modifiers.contains(KeyModifiers::CONTROL)
+        {
+            self.handle_history_search_key(key);
+            return false;
+        }
+
+        // Route plain keys to the rename input, merge or branch picker, tag selector or search bar while open
+        if self.ui_components.focused_pane == FocusedPane::Threads
+            && !key.modifiers.contains(KeyModifiers::CONTROL)
+            && self.handle_thread_overlay_key(key)
+        {
+            return false;
         }
 
         match key.code {
@@ -57,6 +57,26 @@ impl<B: Backend> TuiApp<B> {
                 self.cycle_theme();
                 false
             }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_history_search();
+                false
+            }
+            KeyCode::Char('i') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.ui_components.state.info_panel = InfoPanel::TaskStats;
+                false
+            }
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.take_screenshot();
+                false
+            }
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_thread_search();
+                false
+            }
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_tag_selector();
+                false
+            }
             KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 if key.modifiers.contains(KeyModifiers::SHIFT) {
                     // Ctrl+Shift+T: Toggle thread pane focus
@@ -89,6 +109,55 @@ impl<B: Backend> TuiApp<B> {
         }
     }
 
+    /// Handles the cancel/queue prompt shown while input is queued behind running work
+    fn handle_queued_input_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Char('c') => {
+                // Cancel current work and submit queued input
+                self.ui_components.state.cancel_requested = true;
+                if let Some(queued) = self.ui_components.state.queued_input.take() {
+                    self.ui_components.state.processing_status =
+                        Some("[Cancelling work...]".to_string());
+                    self.ui_components.pending_input = Some(queued);
+                }
+                self.clear_journaled_queue();
+            }
+            KeyCode::Char('a') => {
+                // Accept queue - just keep the queued input
+                self.ui_components.state.processing_status =
+                    Some("[Input queued, will run after current work]".to_string());
+            }
+            KeyCode::Esc => {
+                // Discard queued input
+                self.ui_components.state.queued_input = None;
+                self.ui_components.state.processing_status = None;
+                self.clear_journaled_queue();
+            }
+            _ => {
+                // Ignore other keys when prompt is showing
+            }
+        }
+    }
+
+    /// Routes a key to the open thread overlay, returning false if none is open
+    fn handle_thread_overlay_key(&mut self, key: &KeyEvent) -> bool {
+        let state = &self.ui_components.state;
+        if state.thread_rename_input.is_some() {
+            self.handle_thread_rename_key(key);
+        } else if state.thread_merge_source.is_some() {
+            self.handle_merge_picker_key(key);
+        } else if state.thread_branch_picker.is_some() {
+            self.handle_branch_picker_key(key);
+        } else if state.thread_tag_input.is_some() {
+            self.handle_tag_selector_key(key);
+        } else if state.thread_search_query.is_some() {
+            self.handle_thread_search_key(key);
+        } else {
+            return false;
+        }
+        true
+    }
+
     /// Handles the Enter key press
     pub(super) fn handle_enter_key(&mut self, shift_pressed: bool) -> bool {
         match self.ui_components.focused_pane {
@@ -149,18 +218,67 @@ impl<B: Backend> TuiApp<B> {
     }
 
     fn handle_input_pane_key(&mut self, key: &KeyEvent) {
-        let terminal_width = self.terminal.size().map(|size| size.width).unwrap_or(80);
+        let terminal_width = self.terminal.size().map_or(80, |size| size.width);
         input_handler::handle_input_key(key, &mut self.ui_components.input_manager, terminal_width);
     }
 
     fn handle_output_pane_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Char('y') => self.copy_task_output(),
+            KeyCode::Char('Y') => self.copy_visible_output(),
+            KeyCode::Char(']') => self.select_next_code_block(),
+            KeyCode::Char('[') => self.select_previous_code_block(),
+            KeyCode::Char('m') => self.toggle_markdown_rendering(),
+            KeyCode::Char('w') => self.toggle_word_wrap(),
+            KeyCode::Left | KeyCode::Right
+                if self.ui_components.state.output_wrap == OutputWrap::NoWrap =>
+            {
+                self.scroll_output_horizontally(key.code == KeyCode::Right);
+            }
+            _ => self.scroll_output_pane(key),
+        }
+    }
+
+    fn scroll_output_pane(&mut self, key: &KeyEvent) {
         let max_scroll = self.calculate_output_max_scroll();
-        input_handler::handle_output_key(
-            key,
-            self.ui_components.state.active_task_id,
-            &mut self.ui_components.state.output_scroll_offset,
-            max_scroll,
-        );
+        let Some(task) = self
+            .ui_components
+            .state
+            .active_task_id
+            .and_then(|task_id| self.ui_components.task_manager.get_task_mut(task_id))
+        else {
+            return;
+        };
+        input_handler::handle_output_key(key, &mut task.viewport, max_scroll);
+
+        // Reaching the top of what is loaded brings spilled lines back from disk
+        if task.viewport.offset(max_scroll) == 0 && task.spill.spilled_lines > 0 {
+            let batch = self
+                .ui_components
+                .task_manager
+                .output_limits()
+                .batch_lines();
+            self.load_spilled_output(batch);
+        }
+    }
+
+    fn scroll_output_horizontally(&mut self, right: bool) {
+        let max_column = self.calculate_output_max_column();
+        let Some(task) = self
+            .ui_components
+            .state
+            .active_task_id
+            .and_then(|task_id| self.ui_components.task_manager.get_task_mut(task_id))
+        else {
+            return;
+        };
+        if right {
+            task.viewport
+                .scroll_right(HORIZONTAL_SCROLL_STEP, max_column);
+        } else {
+            task.viewport
+                .scroll_left(HORIZONTAL_SCROLL_STEP, max_column);
+        }
     }
 
     fn handle_tasks_pane_key(&mut self, key: &KeyEvent) {
@@ -192,22 +310,22 @@ impl<B: Backend> TuiApp<B> {
             KeyCode::Up | KeyCode::Char('k') => self.navigate_threads_up(),
             KeyCode::Down | KeyCode::Char('j') => self.navigate_threads_down(),
             KeyCode::Char('n') => self.create_new_thread(),
-            KeyCode::Char('b') => self.branch_from_current(),
+            KeyCode::Char('b') => self.start_thread_branch(),
+            KeyCode::Char('m') => self.start_thread_merge(),
+            KeyCode::Char('r') => self.start_thread_rename(),
             KeyCode::Delete | KeyCode::Char('d') => self.archive_selected_thread(),
             _ => {}
         }
     }
 
     fn navigate_tasks_up_handler(&mut self) {
-        let terminal_height = self.terminal.size().map(|size| size.height).unwrap_or(30);
+        let terminal_height = self.terminal.size().map_or(30, |size| size.height);
         navigate_tasks_up(
             &self.ui_components.task_manager,
             &mut NavigationContext {
                 active_task_id: &mut self.ui_components.state.active_task_id,
                 expanded_conversations: &self.ui_components.state.expanded_conversations,
                 task_list_scroll_offset: &mut self.ui_components.state.task_list_scroll_offset,
-                task_output_scroll: &mut self.ui_components.state.task_output_scroll,
-                output_scroll_offset: &mut self.ui_components.state.output_scroll_offset,
             },
             terminal_height,
             self.ui_components.focused_pane == FocusedPane::Tasks,
@@ -215,15 +333,13 @@ impl<B: Backend> TuiApp<B> {
     }
 
     fn navigate_tasks_down_handler(&mut self) {
-        let terminal_height = self.terminal.size().map(|size| size.height).unwrap_or(30);
+        let terminal_height = self.terminal.size().map_or(30, |size| size.height);
         navigate_tasks_down(
             &self.ui_components.task_manager,
             &mut NavigationContext {
                 active_task_id: &mut self.ui_components.state.active_task_id,
                 expanded_conversations: &self.ui_components.state.expanded_conversations,
                 task_list_scroll_offset: &mut self.ui_components.state.task_list_scroll_offset,
-                task_output_scroll: &mut self.ui_components.state.task_output_scroll,
-                output_scroll_offset: &mut self.ui_components.state.output_scroll_offset,
             },
             terminal_height,
             self.ui_components.focused_pane == FocusedPane::Tasks,
@@ -1,6 +1,5 @@
-//! Application lifecycle operations (constructors, initialization, raw mode)
+//! Application lifecycle operations (constructors, initialization, clearing the screen)
 
-use crossterm::terminal;
 use ratatui::Terminal;
 use ratatui::backend::{Backend, CrosstermBackend};
 use std::fs;
@@ -8,39 +7,39 @@ use std::io;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::broadcast;
 
 use super::tui_app::{EventSystem, RuntimeState, TuiApp, UiComponents};
 use crate::config::ConfigManager;
 use crate::ui::event_source::CrosstermEventSource;
 use crate::ui::input::InputManager;
 use crate::ui::layout;
+use crate::ui::notifications::Notifier;
 use crate::ui::persistence::TaskPersistence;
 use crate::ui::renderer::{FocusedPane, Renderer};
 use crate::ui::state::UiState;
 use crate::ui::task_manager::TaskManager;
 use merlin_agent::{RoutingOrchestrator, ThreadStore};
-use merlin_routing::{Result, RoutingError};
+use merlin_core::schema::CORRUPT_DIR;
+use merlin_core::ui::DEFAULT_UI_EVENT_CAPACITY;
+use merlin_routing::{Result, RoutingError, UiChannel};
 
 impl TuiApp<CrosstermBackend<io::Stdout>> {
-    /// Creates a new `TuiApp` with task storage and orchestrator
+    /// Creates a new `TuiApp` with task storage, orchestrator and loaded config
     ///
     /// # Errors
     /// Returns an error if terminal initialization or clearing fails.
-    pub async fn new_with_storage(
+    pub fn new_with_storage(
         tasks_dir: impl Into<Option<PathBuf>>,
         orchestrator: Option<Arc<RoutingOrchestrator>>,
+        config_manager: ConfigManager,
         log_file: Option<fs::File>,
     ) -> Result<Self> {
-        let (sender, receiver) = mpsc::unbounded_channel();
+        let (sender, receiver) = UiChannel::bounded(DEFAULT_UI_EVENT_CAPACITY);
         let (broadcast_sender, _) = broadcast::channel(100);
 
-        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))
-            .map_err(|err| RoutingError::Other(err.to_string()))?;
-
-        terminal
-            .clear()
-            .map_err(|err| RoutingError::Other(err.to_string()))?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        terminal.clear()?;
 
         let tasks_dir = tasks_dir.into();
         let mut state = UiState::default();
@@ -49,15 +48,18 @@ impl TuiApp<CrosstermBackend<io::Stdout>> {
             state.loading_tasks = true;
         }
 
-        // Initialize config manager and load theme from ~/.merlin/config.toml
-        let config_manager = ConfigManager::new().await.map_err(|err| {
-            RoutingError::Other(format!("Failed to create config manager: {err}"))
-        })?;
-
-        let theme = config_manager
-            .get()
-            .map_err(|err| RoutingError::Other(format!("Failed to read config: {err}")))?
-            .theme;
+        let (theme, notification_config, output_limits) = {
+            let config = config_manager
+                .get()
+                .map_err(|err| RoutingError::Other(format!("Failed to read config: {err}")))?;
+            (
+                config.theme.with_custom_colors(),
+                config.notifications.clone(),
+                config.output,
+            )
+        };
+        let mut task_manager = TaskManager::default();
+        task_manager.set_output_limits(output_limits);
 
         let persistence = tasks_dir
             .as_ref()
@@ -65,18 +67,14 @@ impl TuiApp<CrosstermBackend<io::Stdout>> {
 
         // Get thread store from orchestrator if available, otherwise create a new one
         let thread_store = if let Some(ref orch) = orchestrator {
-            orch.thread_store().ok_or_else(|| {
-                RoutingError::Other("Orchestrator does not have a thread store".to_string())
-            })?
+            orch.thread_store().ok_or(RoutingError::NoThreadStore)?
         } else {
             let thread_storage_path = tasks_dir.as_ref().map_or_else(
                 || PathBuf::from(".merlin/threads"),
                 |dir| dir.join("threads"),
             );
 
-            let store = ThreadStore::new(thread_storage_path).map_err(|err| {
-                RoutingError::Other(format!("Failed to create thread store: {err}"))
-            })?;
+            let store = ThreadStore::new(thread_storage_path)?;
             Arc::new(Mutex::new(store))
         };
 
@@ -90,7 +88,7 @@ impl TuiApp<CrosstermBackend<io::Stdout>> {
                 last_task_receiver: None,
             },
             ui_components: UiComponents {
-                task_manager: TaskManager::default(),
+                task_manager,
                 state,
                 input_manager: InputManager::default(),
                 renderer: Renderer::new(theme),
@@ -104,43 +102,35 @@ impl TuiApp<CrosstermBackend<io::Stdout>> {
                 orchestrator,
                 persistence,
                 log_file,
+                notifier: Some(Notifier::new(notification_config)),
             },
             config_manager,
         };
 
         Ok(app)
     }
+}
 
-    /// Enables raw mode
-    ///
-    /// # Errors
-    /// Returns an error if enabling raw mode fails.
-    pub fn enable_raw_mode() -> Result<()> {
-        terminal::enable_raw_mode().map_err(|err| RoutingError::Other(err.to_string()))
-    }
+// Test utilities (new_for_test) moved to app/test_util.rs, only compiled with test-util feature
 
-    /// Disables raw mode
+impl<B: Backend> TuiApp<B> {
+    /// Clears the screen once the session ends
     ///
     /// # Errors
-    /// Returns an error if disabling raw mode or clearing the terminal fails.
-    pub fn disable_raw_mode(&mut self) -> Result<()> {
-        terminal::disable_raw_mode().map_err(|err| RoutingError::Other(err.to_string()))?;
-        self.terminal
-            .clear()
-            .map_err(|err| RoutingError::Other(err.to_string()))
+    /// Returns an error if clearing the terminal fails.
+    pub fn clear_screen(&mut self) -> Result<()> {
+        Ok(self.terminal.clear()?)
     }
-}
-
-// Test utilities (new_for_test) moved to app/test_util.rs, only compiled with test-util feature
 
-impl<B: Backend> TuiApp<B> {
     /// Loads tasks asynchronously
     pub async fn load_tasks_async(&mut self) {
         if let Some(persistence) = &self.runtime_state.persistence {
             let mut loaded_count = 0usize;
-            if let Ok(tasks) = persistence.load_all_tasks().await {
-                loaded_count = tasks.len();
-                for (task_id, task_display) in tasks {
+            let mut quarantined = Vec::new();
+            if let Ok(loaded) = persistence.load_all_tasks().await {
+                loaded_count = loaded.tasks.len();
+                quarantined = loaded.quarantined;
+                for (task_id, task_display) in loaded.tasks {
                     self.ui_components
                         .task_manager
                         .insert_task_for_load(task_id, task_display);
@@ -155,6 +145,7 @@ impl<B: Backend> TuiApp<B> {
 
             tracing::info!("Loaded {} tasks from persistence", loaded_count);
             self.ui_components.state.loading_tasks = false;
+            self.report_quarantined(quarantined);
         }
     }
 
@@ -162,14 +153,14 @@ impl<B: Backend> TuiApp<B> {
     ///
     /// # Errors
     /// Returns an error if thread loading fails
-    pub fn load_threads(&self) -> Result<()> {
+    pub fn load_threads(&mut self) -> Result<()> {
         let mut store = self
             .runtime_state
             .thread_store
             .lock()
-            .map_err(|err| RoutingError::Other(format!("Thread store lock error: {err}")))?;
+            .map_err(|_| RoutingError::LockPoisoned("Thread store"))?;
         let loaded_count = store.active_threads().len();
-        store.load_all()?;
+        let quarantined = store.load_all()?;
         let new_count = store.active_threads().len();
         drop(store);
         tracing::info!(
@@ -177,6 +168,21 @@ impl<B: Backend> TuiApp<B> {
             new_count,
             new_count.saturating_sub(loaded_count)
         );
+        self.report_quarantined(quarantined);
         Ok(())
     }
+
+    /// Shows a single notice for all files moved to `.merlin/corrupt/` so far
+    fn report_quarantined(&mut self, quarantined: Vec<PathBuf>) {
+        if quarantined.is_empty() {
+            return;
+        }
+        let state = &mut self.ui_components.state;
+        state.quarantined_files.extend(quarantined);
+        let message = format!(
+            "Moved {} unreadable file(s) to .merlin/{CORRUPT_DIR}/",
+            state.quarantined_files.len()
+        );
+        self.ui_components.show_notice(message);
+    }
 }
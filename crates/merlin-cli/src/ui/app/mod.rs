@@ -8,12 +8,20 @@ pub mod navigation;
 
 // TUI application implementation modules
 mod event_loop;
+mod history_operations;
 mod key_handling;
 mod lifecycle;
+mod output_operations;
+mod pin_operations;
+mod screenshot_operations;
+mod session_recovery;
+mod shutdown;
 mod task_execution;
 mod task_operations;
+mod theme_reload;
 
 mod thread_operations;
 pub mod tui_app;
+mod working_dir_operations;
 
 pub use tui_app::TuiApp;
@@ -0,0 +1,145 @@
+//! Output pane clipboard copying, code block selection, display toggles and spilled output
+
+use ratatui::backend::Backend;
+
+use super::tui_app::TuiApp;
+use crate::ui::clipboard::copy_to_clipboard;
+use crate::ui::code_blocks::find_code_blocks;
+use crate::ui::output_buffer;
+use crate::ui::state::{OutputFormat, OutputWrap};
+use crate::ui::task_manager::TaskDisplay;
+
+impl<B: Backend> TuiApp<B> {
+    /// Loads up to `count` spilled lines of the active task back above its output
+    ///
+    /// The viewport moves down with the inserted lines, so the text in view stays put.
+    pub(super) fn load_spilled_output(&mut self, count: usize) {
+        let Some(task) = self
+            .ui_components
+            .state
+            .active_task_id
+            .and_then(|task_id| self.ui_components.task_manager.get_task_mut(task_id))
+        else {
+            return;
+        };
+        match output_buffer::load_spilled(task, count) {
+            Ok(loaded) => task.viewport.insert_lines_above(loaded),
+            Err(err) => {
+                tracing::warn!("Failed to load spilled output: {err}");
+                self.ui_components
+                    .show_notice(format!("Failed to load earlier output: {err}"));
+            }
+        }
+    }
+
+    /// Copies the selected code block, or the whole task output if none is selected
+    pub(super) fn copy_task_output(&mut self) {
+        let Some(task) = self.active_task() else {
+            return;
+        };
+
+        let text = task
+            .selected_code_block
+            .and_then(|index| find_code_blocks(&task.output).into_iter().nth(index))
+            .map_or_else(|| task.output.clone(), |block| block.content);
+
+        self.copy_with_notice(&text);
+    }
+
+    /// Copies the output lines currently visible in the output pane
+    pub(super) fn copy_visible_output(&mut self) {
+        let max_scroll = self.calculate_output_max_scroll();
+        let viewport_height = self.ui_components.layout_cache.output_viewport_height();
+        let Some(task) = self.active_task() else {
+            return;
+        };
+
+        let offset = task.viewport.offset(max_scroll);
+        let text = task
+            .output
+            .lines()
+            .skip(usize::from(offset))
+            .take(usize::from(viewport_height))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        self.copy_with_notice(&text);
+    }
+
+    /// Switches the output pane between rendered Markdown and raw text
+    pub(super) fn toggle_markdown_rendering(&mut self) {
+        let state = &mut self.ui_components.state;
+        state.output_format = state.output_format.toggled();
+        let message = match state.output_format {
+            OutputFormat::Markdown => "Markdown rendering on",
+            OutputFormat::Raw => "Markdown rendering off",
+        };
+        self.ui_components.show_notice(message);
+    }
+
+    /// Switches the output pane between wrapping long lines and scrolling horizontally
+    pub(super) fn toggle_word_wrap(&mut self) {
+        let state = &mut self.ui_components.state;
+        state.output_wrap = state.output_wrap.toggled();
+        let message = match state.output_wrap {
+            OutputWrap::Wrap => "Word wrap on",
+            OutputWrap::NoWrap => "Word wrap off",
+        };
+        self.ui_components.show_notice(message);
+    }
+
+    /// Selects the next fenced code block, clearing the selection past the last one
+    pub(super) fn select_next_code_block(&mut self) {
+        self.move_code_block_selection(true);
+    }
+
+    /// Selects the previous fenced code block, clearing the selection before the first one
+    pub(super) fn select_previous_code_block(&mut self) {
+        self.move_code_block_selection(false);
+    }
+
+    /// Moves the code block selection and scrolls the selected block into view
+    fn move_code_block_selection(&mut self, forward: bool) {
+        let max_scroll = self.calculate_output_max_scroll();
+        let Some(task) = self
+            .ui_components
+            .state
+            .active_task_id
+            .and_then(|task_id| self.ui_components.task_manager.get_task_mut(task_id))
+        else {
+            return;
+        };
+
+        let blocks = find_code_blocks(&task.output);
+        let selected = match (task.selected_code_block, forward) {
+            (None, true) => (!blocks.is_empty()).then_some(0),
+            (None, false) => blocks.len().checked_sub(1),
+            (Some(index), true) => (index + 1 < blocks.len()).then_some(index + 1),
+            (Some(index), false) => index.checked_sub(1),
+        };
+
+        task.selected_code_block = selected;
+        if let Some(block) = selected.and_then(|index| blocks.get(index)) {
+            let line = u16::try_from(block.start_line).unwrap_or(u16::MAX);
+            task.viewport.scroll_to_line(line, max_scroll);
+        }
+    }
+
+    /// Returns the task shown in the output pane
+    pub(super) fn active_task(&self) -> Option<&TaskDisplay> {
+        let task_id = self.ui_components.state.active_task_id?;
+        self.ui_components.task_manager.get_task(task_id)
+    }
+
+    /// Copies text to the clipboard and reports the outcome as a status notice
+    fn copy_with_notice(&mut self, text: &str) {
+        let message = match copy_to_clipboard(text) {
+            Ok(()) => format!("Copied {} lines", text.lines().count()),
+            Err(err) => {
+                tracing::warn!("Failed to copy to clipboard: {err}");
+                format!("Copy failed: {err}")
+            }
+        };
+        self.ui_components.show_notice(message);
+    }
+}
@@ -0,0 +1,118 @@
+//! `/pin` and `/unpin` commands managing the files pinned to the active thread
+
+use std::path::Path;
+
+use merlin_agent::pin_tool::resolve_pin_path;
+use merlin_core::ThreadId;
+use ratatui::backend::Backend;
+
+use super::tui_app::TuiApp;
+
+/// Input command pinning a file to the active thread's context
+pub const PIN_COMMAND: &str = "/pin";
+/// Input command unpinning one file, or every file when no path is given
+pub const UNPIN_COMMAND: &str = "/unpin";
+
+impl<B: Backend> TuiApp<B> {
+    /// Runs `input` if it is a `/pin` or `/unpin` command, returning whether it was one
+    pub(super) fn handle_pin_command(&mut self, input: &str) -> bool {
+        let (command, argument) = input
+            .split_once(char::is_whitespace)
+            .map_or((input, ""), |(command, argument)| {
+                (command, argument.trim())
+            });
+
+        if command.eq_ignore_ascii_case(PIN_COMMAND) {
+            self.pin_file(argument);
+        } else if command.eq_ignore_ascii_case(UNPIN_COMMAND) {
+            self.unpin_file(argument);
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Pins a file to the active thread, starting a thread if none is active
+    fn pin_file(&mut self, path: &str) {
+        if path.is_empty() {
+            self.ui_components
+                .show_notice(format!("Usage: {PIN_COMMAND} <path>"));
+            return;
+        }
+        let relative = match resolve_pin_path(&self.active_workspace(), path) {
+            Ok(relative) => relative,
+            Err(err) => {
+                self.ui_components
+                    .show_notice(format!("Cannot pin {path}: {}", err.user_message()));
+                return;
+            }
+        };
+        let Some(thread_id) = self.ensure_active_thread(&format!("Pinned {path}")) else {
+            return;
+        };
+
+        let outcome = self
+            .runtime_state
+            .thread_store
+            .lock()
+            .map_err(|err| err.to_string())
+            .and_then(|mut store| {
+                store
+                    .pin_file(thread_id, relative.clone())
+                    .map_err(|err| err.to_string())
+            });
+        let notice = match outcome {
+            Ok(true) => format!("Pinned {}", relative.display()),
+            Ok(false) => format!("{} is already pinned", relative.display()),
+            Err(err) => format!("Failed to pin {}: {err}", relative.display()),
+        };
+        self.ui_components.show_notice(notice);
+    }
+
+    /// Unpins a file from the active thread, or every file if `path` is empty
+    fn unpin_file(&mut self, path: &str) {
+        let Some(thread_id) = self.ui_components.state.active_thread_id else {
+            self.ui_components.show_notice("No active thread");
+            return;
+        };
+
+        // Files deleted since they were pinned can still be unpinned by their path
+        let relative = resolve_pin_path(&self.active_workspace(), path)
+            .unwrap_or_else(|_| Path::new(path.trim_start_matches("./")).to_path_buf());
+        let outcome = self
+            .runtime_state
+            .thread_store
+            .lock()
+            .map_err(|err| err.to_string())
+            .and_then(|mut store| {
+                if path.is_empty() {
+                    store.clear_pinned_files(thread_id).map(|count| count > 0)
+                } else {
+                    store.unpin_file(thread_id, &relative)
+                }
+                .map_err(|err| err.to_string())
+            });
+        let notice = match outcome {
+            Ok(true) if path.is_empty() => "Unpinned all files".to_owned(),
+            Ok(true) => format!("Unpinned {}", relative.display()),
+            Ok(false) if path.is_empty() => "No pinned files".to_owned(),
+            Ok(false) => format!("{} is not pinned", relative.display()),
+            Err(err) => format!("Failed to unpin: {err}"),
+        };
+        self.ui_components.show_notice(notice);
+    }
+
+    /// Returns the active thread, creating and selecting one named `name` if none is active
+    pub(super) fn ensure_active_thread(&mut self, name: &str) -> Option<ThreadId> {
+        if self.ui_components.state.active_thread_id.is_none()
+            && let Ok(mut store) = self.runtime_state.thread_store.lock()
+        {
+            let thread = store.create_thread(name.chars().take(30).collect());
+            if let Err(save_err) = store.save_thread(&thread) {
+                tracing::warn!("Failed to create thread: {save_err}");
+            }
+            self.ui_components.state.active_thread_id = Some(thread.id);
+        }
+        self.ui_components.state.active_thread_id
+    }
+}
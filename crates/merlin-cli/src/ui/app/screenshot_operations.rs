@@ -0,0 +1,53 @@
+//! `Ctrl+E` export of the current frame as an SVG screenshot
+
+use std::path::PathBuf;
+
+use chrono::Local;
+use ratatui::backend::Backend;
+use ratatui::layout::Size;
+
+use super::tui_app::TuiApp;
+use crate::ui::renderer::{RenderCtx, UiCtx};
+use crate::ui::screenshot::{buffer_to_svg, render_to_buffer, save_screenshot};
+
+impl<B: Backend> TuiApp<B> {
+    /// Renders the current frame off-screen and saves it to `.merlin/screenshots/`
+    pub(super) fn take_screenshot(&mut self) {
+        let size = self.terminal.size().map_or_else(
+            |_| Size::new(120, 40),
+            |size| Size::new(size.width, size.height),
+        );
+        let renderer = &self.ui_components.renderer;
+        let ctx = RenderCtx {
+            ui_ctx: UiCtx {
+                task_manager: &self.ui_components.task_manager,
+                state: &self.ui_components.state,
+            },
+            input: &self.ui_components.input_manager,
+            focused: self.ui_components.focused_pane,
+            thread_store: &self.runtime_state.thread_store,
+        };
+
+        let outcome = render_to_buffer(renderer, &ctx, size)
+            .map_err(|err| err.to_string())
+            .and_then(|buffer| {
+                let svg = buffer_to_svg(&buffer, renderer.theme());
+                save_screenshot(&self.merlin_dir(), &svg, Local::now())
+                    .map_err(|err| err.to_string())
+            });
+        let notice = match outcome {
+            Ok(path) => format!("Saved screenshot to {}", path.display()),
+            Err(err) => format!("Failed to save screenshot: {err}"),
+        };
+        self.ui_components.show_notice(notice);
+    }
+
+    /// The `.merlin` directory task files are stored in
+    fn merlin_dir(&self) -> PathBuf {
+        self.runtime_state
+            .persistence
+            .as_ref()
+            .and_then(|persistence| persistence.tasks_dir().parent())
+            .map_or_else(|| PathBuf::from(".merlin"), PathBuf::from)
+    }
+}
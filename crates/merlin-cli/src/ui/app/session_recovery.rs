@@ -0,0 +1,78 @@
+//! Recovery of tasks interrupted by a previous session
+
+use ratatui::backend::Backend;
+
+use super::tui_app::TuiApp;
+
+/// Input command that re-runs the most recently interrupted task
+pub const RETRY_COMMAND: &str = "/retry";
+
+impl<B: Backend> TuiApp<B> {
+    /// Recovers work left unfinished by the previous session
+    ///
+    /// Interrupted tasks are reported in the status line with an offer to `/retry` them.
+    /// Must be called after threads are loaded and before any task is submitted.
+    pub fn recover_session(&mut self) {
+        let Some(ref orchestrator) = self.runtime_state.orchestrator else {
+            return;
+        };
+
+        match orchestrator.recover_session() {
+            Ok(interrupted) if !interrupted.is_empty() => {
+                self.ui_components.state.processing_status = Some(format!(
+                    "[{} task(s) interrupted by restart. Type {RETRY_COMMAND} to re-run]",
+                    interrupted.len()
+                ));
+            }
+            Ok(_) => {}
+            Err(err) => tracing::warn!("Failed to recover previous session: {err}"),
+        }
+    }
+
+    /// Re-runs the most recently interrupted task with its original prompt and thread
+    pub(super) fn retry_interrupted_task(&mut self) {
+        if !self.ui_components.state.active_running_tasks.is_empty() {
+            self.ui_components
+                .show_notice("Wait for running work to finish before retrying");
+            return;
+        }
+
+        let Some(ref orchestrator) = self.runtime_state.orchestrator else {
+            return;
+        };
+
+        let interrupted = match orchestrator.take_interrupted_task() {
+            Ok(Some(interrupted)) => interrupted,
+            Ok(None) => {
+                self.ui_components
+                    .show_notice("No interrupted tasks to retry");
+                return;
+            }
+            Err(err) => {
+                tracing::warn!("Failed to read interrupted task: {err}");
+                self.ui_components
+                    .show_notice(format!("Retry failed: {err}"));
+                return;
+            }
+        };
+
+        // Resume in the original thread if it still exists
+        let thread_exists = interrupted.thread_id.is_some_and(|thread_id| {
+            self.runtime_state
+                .thread_store
+                .lock()
+                .is_ok_and(|store| store.get_thread(thread_id).is_some())
+        });
+        self.ui_components.state.active_thread_id = interrupted.thread_id.filter(|_| thread_exists);
+        self.ui_components.state.active_task_id = None;
+
+        self.start_task(interrupted.prompt);
+    }
+
+    /// Removes the queued prompt from the session journal
+    pub(super) fn clear_journaled_queue(&self) {
+        if let Some(ref orchestrator) = self.runtime_state.orchestrator {
+            orchestrator.clear_queued_prompt();
+        }
+    }
+}
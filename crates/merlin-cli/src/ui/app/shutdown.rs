@@ -0,0 +1,84 @@
+//! Graceful shutdown while tasks are still running
+
+use merlin_agent::RoutingOrchestrator;
+use ratatui::backend::Backend;
+use std::future::pending;
+use std::time::Duration;
+use tokio::time::sleep;
+
+use super::tui_app::TuiApp;
+
+/// How often shutdown progress is re-checked while tasks wind down
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+impl<B: Backend> TuiApp<B> {
+    /// Begins shutdown when the process receives `SIGINT` or `SIGTERM`
+    pub(super) fn listen_for_shutdown_signals(&self) {
+        if let Some(ref orchestrator) = self.runtime_state.orchestrator {
+            orchestrator.shutdown_coordinator().listen_for_signals();
+        }
+    }
+
+    /// Handles a quit request and returns true if the app should exit now
+    ///
+    /// With tasks running, the first request cancels them and waits out the grace
+    /// period; a second request exits immediately.
+    pub(super) fn request_shutdown(&mut self) -> bool {
+        let Some(ref orchestrator) = self.runtime_state.orchestrator else {
+            return true;
+        };
+        let coordinator = orchestrator.shutdown_coordinator();
+        if coordinator.is_shutting_down()
+            || self.ui_components.state.active_running_tasks.is_empty()
+        {
+            return true;
+        }
+
+        coordinator.begin_shutdown();
+        self.update_shutdown_progress()
+    }
+
+    /// Updates the shutdown status line and returns true once the app should exit
+    ///
+    /// Exits when every task has stopped or the grace period has run out.
+    pub(super) fn update_shutdown_progress(&mut self) -> bool {
+        let Some(ref orchestrator) = self.runtime_state.orchestrator else {
+            return false;
+        };
+        let coordinator = orchestrator.shutdown_coordinator();
+        if !coordinator.is_shutting_down() {
+            return false;
+        }
+
+        let remaining = self.remaining_task_count();
+        if remaining == 0 {
+            return true;
+        }
+        if coordinator.grace_period_elapsed() {
+            tracing::warn!("Grace period elapsed, forcefully cancelling {remaining} task(s)");
+            return true;
+        }
+
+        self.ui_components.state.processing_status =
+            Some(format!("Shutting down... ({remaining} tasks remaining)"));
+        false
+    }
+
+    /// Returns the number of tasks that are still running
+    pub fn remaining_task_count(&self) -> usize {
+        self.ui_components.state.active_running_tasks.len()
+    }
+}
+
+/// Resolves when shutdown begins, then periodically while it is in progress
+pub(super) async fn shutdown_tick(orchestrator: Option<&RoutingOrchestrator>) {
+    let Some(orchestrator) = orchestrator else {
+        return pending().await;
+    };
+    let coordinator = orchestrator.shutdown_coordinator();
+    if coordinator.is_shutting_down() {
+        sleep(SHUTDOWN_POLL_INTERVAL).await;
+    } else {
+        coordinator.wait_for_shutdown().await;
+    }
+}
@@ -2,14 +2,19 @@
 
 use std::fs::File;
 use std::io::Write as _;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
 
+use futures::FutureExt as _;
 use merlin_agent::RoutingOrchestrator;
-use merlin_core::{Message, MessageId, TaskResult, ThreadId, TokenUsage, WorkUnit};
+use merlin_core::ui::DEFAULT_UI_EVENT_CAPACITY;
+use merlin_core::{
+    Message, MessageId, TaskResult, ThreadId, TokenUsage, UiEventReceiver, WorkUnit,
+};
 use merlin_routing::{RoutingError, Task, TaskId, UiChannel, UiEvent};
-use merlin_tooling::ToolError;
+use merlin_tooling::{ToolError, recover_panic};
 use ratatui::backend::Backend;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::oneshot;
 use tokio::task::spawn_local;
 
 use super::tui_app::TuiApp;
@@ -50,6 +55,8 @@ pub struct TaskExecutionParams {
     pub conversation_history: Vec<(String, String)>,
     /// Thread ID for multi-turn conversations
     pub thread_id: Option<ThreadId>,
+    /// Whether to split the task into subtasks run as child tasks
+    pub decompose: bool,
 }
 
 /// Internal parameters for task execution including runtime state
@@ -60,6 +67,7 @@ struct InternalExecutionParams {
     parent_task_id: Option<TaskId>,
     conversation_history: Vec<(String, String)>,
     thread_id: Option<ThreadId>,
+    decompose: bool,
     ui_channel: UiChannel,
     log_file: Option<File>,
     forwarder_done_rx: oneshot::Receiver<()>,
@@ -74,6 +82,7 @@ async fn execute_and_handle_task(params: InternalExecutionParams) {
         parent_task_id,
         conversation_history,
         thread_id,
+        decompose,
         ui_channel,
         mut log_file,
         forwarder_done_rx,
@@ -99,16 +108,29 @@ async fn execute_and_handle_task(params: InternalExecutionParams) {
         output: format!("Prompt: {user_input}\n"),
     });
 
-    let result = if let Some(tid) = actual_thread_id {
-        orchestrator
-            .execute_task_in_thread(task, ui_channel.clone(), tid)
-            .await
-    } else {
-        orchestrator
-            .execute_task_streaming_with_history(task, ui_channel.clone(), conversation_history)
-            .await
+    let execution = async {
+        if decompose {
+            orchestrator
+                .execute_task_decomposed(task, ui_channel.clone(), actual_thread_id)
+                .await
+        } else if let Some(tid) = actual_thread_id {
+            orchestrator
+                .execute_task_in_thread(task, ui_channel.clone(), tid)
+                .await
+        } else {
+            orchestrator
+                .execute_task_streaming_with_history(task, ui_channel.clone(), conversation_history)
+                .await
+        }
     };
 
+    // A panic inside the task fails it instead of silently killing this spawned future,
+    // which would leave the TUI showing the task as running forever
+    let result = AssertUnwindSafe(execution)
+        .catch_unwind()
+        .await
+        .unwrap_or_else(|payload| Err(RoutingError::from(recover_panic(&user_input, payload))));
+
     // Handle result in a scope to ensure ctx is dropped before we drop ui_channel
     {
         let mut ctx = TaskResultContext {
@@ -133,10 +155,34 @@ async fn execute_and_handle_task(params: InternalExecutionParams) {
     // Drop ui_channel to close internal_tx, signaling forwarder to finish
     drop(ui_channel);
 
+    finish_task_execution(
+        &orchestrator,
+        result.is_ok(),
+        actual_thread_id,
+        forwarder_done_rx,
+    )
+    .await;
+}
+
+/// Waits for the task's events to be forwarded, then titles its thread if it succeeded
+async fn finish_task_execution(
+    orchestrator: &RoutingOrchestrator,
+    succeeded: bool,
+    thread_id: Option<ThreadId>,
+    forwarder_done_rx: oneshot::Receiver<()>,
+) {
     // Wait for forwarder to finish processing all events
     if forwarder_done_rx.await.is_err() {
         tracing::warn!("Forwarder completion signal sender was dropped before signaling");
     }
+
+    // Replace the placeholder thread name once the first task completes
+    if succeeded
+        && let Some(tid) = thread_id
+        && let Err(title_err) = orchestrator.generate_thread_title(tid).await
+    {
+        tracing::debug!("Thread title generation failed: {title_err}");
+    }
 }
 
 /// Handle successful task completion
@@ -159,6 +205,7 @@ fn handle_task_success(result_data: &TaskResult, ctx: &mut TaskResultContext<'_>
             result_data.duration_ms,
             result_data.response.tokens_used.total()
         );
+        let _timings_write = writeln!(log, "Timings: {}", result_data.timings);
     }
 
     if let (Some(tid), Some(msg_id)) = (ctx.actual_thread_id, ctx.message_id) {
@@ -317,22 +364,20 @@ fn update_thread_work_failed(
 impl<B: Backend> TuiApp<B> {
     /// Spawns an event forwarder that duplicates events to both task-specific and global channels
     fn spawn_event_forwarder(
-        mut internal_rx: mpsc::Receiver<UiEvent>,
-        task_event_tx: mpsc::Sender<UiEvent>,
-        global_ui_sender: mpsc::UnboundedSender<UiEvent>,
+        mut internal_rx: UiEventReceiver,
+        task_event_tx: UiChannel,
+        global_ui_sender: UiChannel,
         forwarder_done_tx: oneshot::Sender<()>,
     ) {
         spawn_local(async move {
             while let Some(event) = internal_rx.recv().await {
-                // Send to task-specific channel (test waits on this) - now bounded
-                if task_event_tx.send(event.clone()).await.is_err() {
-                    // Task channel closed, continue draining to global channel
-                    tracing::debug!("Task event channel closed, draining to global");
-                }
-                // Send to global UI channel (UI updates from this) - still unbounded
-                if global_ui_sender.send(event).is_err() {
+                // Send to task-specific channel (test waits on this), ignored once it is dropped
+                task_event_tx.send(event.clone());
+                // Send to global UI channel (UI updates from this, coalesced when behind)
+                if global_ui_sender.is_closed() {
                     break; // Global channel closed, stop forwarding
                 }
+                global_ui_sender.send(event);
             }
             // Signal that forwarding is complete AFTER all events processed
             if forwarder_done_tx.send(()).is_err() {
@@ -352,16 +397,17 @@ impl<B: Backend> TuiApp<B> {
             parent_task_id,
             conversation_history,
             thread_id,
+            decompose,
         } = params;
 
-        // Create per-task event channel for isolated event delivery (bounded with backpressure)
-        let (task_event_tx, task_event_rx) = mpsc::channel(128);
+        // Create per-task event channel for isolated event delivery (bounded, coalescing)
+        let (task_event_tx, task_event_rx) = UiChannel::bounded(DEFAULT_UI_EVENT_CAPACITY);
 
         // Clone global UI channel for broadcasting to UI
         let global_ui_sender = self.event_system.sender.clone();
 
-        // Create internal channel for task execution (bounded with backpressure)
-        let (internal_tx, internal_rx) = mpsc::channel::<UiEvent>(128);
+        // Create internal channel for task execution (bounded, coalescing when behind)
+        let (ui_channel, internal_rx) = UiChannel::bounded(DEFAULT_UI_EVENT_CAPACITY);
 
         // Create oneshot channel to signal when forwarder is done
         let (forwarder_done_tx, forwarder_done_rx) = oneshot::channel();
@@ -374,7 +420,6 @@ impl<B: Backend> TuiApp<B> {
             forwarder_done_tx,
         );
 
-        let ui_channel = UiChannel::from_sender(internal_tx);
         let log_file = self
             .runtime_state
             .log_file
@@ -388,6 +433,7 @@ impl<B: Backend> TuiApp<B> {
             parent_task_id,
             conversation_history,
             thread_id,
+            decompose,
             ui_channel,
             log_file,
             forwarder_done_rx,
@@ -6,6 +6,7 @@ use tracing::warn;
 use super::conversation;
 use super::tui_app::TuiApp;
 use crate::ui::renderer::Renderer;
+use crate::ui::scroll::max_line_width;
 use crate::ui::state::ConversationRole;
 use merlin_routing::TaskId;
 
@@ -105,16 +106,16 @@ impl<B: Backend> TuiApp<B> {
             return 0;
         }
 
-        let terminal_width = self.terminal.size().map(|size| size.width).unwrap_or(80);
+        let terminal_width = self.terminal.size().map_or(80, |size| size.width);
         let text_lines = Renderer::calculate_output_line_count(task, terminal_width);
         text_lines.saturating_sub(viewport_height)
     }
 
-    /// Auto-scrolls the output pane to the bottom if already at or near bottom
-    pub(super) fn auto_scroll_output_to_bottom(&mut self) {
-        if self.ui_components.state.active_task_id.is_some() {
-            let max_scroll = self.calculate_output_max_scroll();
-            self.ui_components.state.output_scroll_offset = max_scroll;
-        }
+    /// Calculates how far the output pane can scroll right with word wrap off
+    pub(super) fn calculate_output_max_column(&self) -> u16 {
+        let viewport_width = self.ui_components.layout_cache.output_viewport_width();
+        self.active_task().map_or(0, |task| {
+            max_line_width(&task.output).saturating_sub(viewport_width)
+        })
     }
 }
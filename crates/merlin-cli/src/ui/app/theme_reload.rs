@@ -0,0 +1,70 @@
+//! Hot-reloading of the custom theme on `SIGHUP`
+
+use ratatui::backend::Backend;
+use std::future::pending;
+#[cfg(unix)]
+use tokio::signal::unix::{Signal, SignalKind, signal};
+
+use super::tui_app::TuiApp;
+use crate::ui::theme::{CustomTheme, Theme};
+
+/// Listener for the signal asking to reload the custom theme
+pub(super) struct ThemeReloadSignal {
+    /// `SIGHUP` stream (None if it could not be registered)
+    #[cfg(unix)]
+    hangup: Option<Signal>,
+}
+
+impl ThemeReloadSignal {
+    /// Starts listening for `SIGHUP`
+    #[cfg(unix)]
+    pub(super) fn listen() -> Self {
+        let hangup = signal(SignalKind::hangup())
+            .inspect_err(|err| tracing::warn!("Failed to listen for SIGHUP: {err}"))
+            .ok();
+        Self { hangup }
+    }
+
+    /// Signals are not supported on this platform, so the theme is never reloaded
+    #[cfg(not(unix))]
+    pub(super) const fn listen() -> Self {
+        Self {}
+    }
+
+    /// Resolves when a reload is requested
+    #[cfg(unix)]
+    pub(super) async fn recv(&mut self) {
+        let Some(hangup) = self.hangup.as_mut() else {
+            return pending().await;
+        };
+        if hangup.recv().await.is_none() {
+            self.hangup = None;
+            pending::<()>().await;
+        }
+    }
+
+    /// Never resolves
+    #[cfg(not(unix))]
+    pub(super) async fn recv(&mut self) {
+        pending::<()>().await;
+    }
+}
+
+impl<B: Backend> TuiApp<B> {
+    /// Reloads `~/.merlin/theme.toml` if the custom theme is selected
+    pub(super) fn reload_custom_theme(&mut self) {
+        if !matches!(self.ui_components.renderer.theme(), Theme::Custom(_)) {
+            tracing::debug!("Ignoring theme reload, the custom theme is not selected");
+            return;
+        }
+
+        let message = match CustomTheme::load() {
+            Ok(custom) => {
+                self.ui_components.renderer.set_theme(Theme::Custom(custom));
+                "Reloaded custom theme".to_owned()
+            }
+            Err(err) => format!("Failed to reload custom theme: {err}"),
+        };
+        self.ui_components.show_notice(message);
+    }
+}
@@ -1,6 +1,10 @@
 //! Thread management operations for TUI
 
 use super::tui_app::TuiApp;
+use crate::ui::renderer::FocusedPane;
+use crate::ui::state::BranchPicker;
+use crate::ui::thread_filter::{tag_suggestions, visible_threads};
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::backend::Backend;
 
 impl<B: Backend> TuiApp<B> {
@@ -9,7 +13,7 @@ impl<B: Backend> TuiApp<B> {
         let Ok(store) = self.runtime_state.thread_store.lock() else {
             return;
         };
-        let active_threads = store.active_threads();
+        let active_threads = visible_threads(&store, &self.ui_components.state);
         if active_threads.is_empty() {
             return;
         }
@@ -36,7 +40,7 @@ impl<B: Backend> TuiApp<B> {
         let Ok(store) = self.runtime_state.thread_store.lock() else {
             return;
         };
-        let active_threads = store.active_threads();
+        let active_threads = visible_threads(&store, &self.ui_components.state);
         if active_threads.is_empty() {
             return;
         }
@@ -58,6 +62,107 @@ impl<B: Backend> TuiApp<B> {
         }
     }
 
+    /// Opens the inline thread search bar and focuses the thread list
+    pub(super) fn open_thread_search(&mut self) {
+        self.ui_components.focused_pane = FocusedPane::Threads;
+        self.ui_components
+            .state
+            .thread_search_query
+            .get_or_insert_with(String::new);
+    }
+
+    /// Handles a key press while the thread search bar is open
+    pub(super) fn handle_thread_search_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Enter => {
+                self.ui_components.state.thread_search_query = None;
+            }
+            KeyCode::Up => self.navigate_threads_up(),
+            KeyCode::Down => self.navigate_threads_down(),
+            KeyCode::Backspace => {
+                if let Some(query) = &mut self.ui_components.state.thread_search_query {
+                    query.pop();
+                }
+                self.select_first_visible_thread();
+            }
+            KeyCode::Char(character) => {
+                if let Some(query) = &mut self.ui_components.state.thread_search_query {
+                    query.push(character);
+                }
+                self.select_first_visible_thread();
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens the tag selector and focuses the thread list
+    pub(super) fn open_tag_selector(&mut self) {
+        self.ui_components.focused_pane = FocusedPane::Threads;
+        self.ui_components.state.thread_search_query = None;
+        self.ui_components
+            .state
+            .thread_tag_input
+            .get_or_insert_with(String::new);
+    }
+
+    /// Handles a key press while the tag selector is open
+    ///
+    /// Enter applies the typed tag (an empty tag clears the filter), Tab completes
+    /// to the first existing tag matching the input, and Esc cancels.
+    pub(super) fn handle_tag_selector_key(&mut self, key: &KeyEvent) {
+        let state = &mut self.ui_components.state;
+        match key.code {
+            KeyCode::Esc => state.thread_tag_input = None,
+            KeyCode::Enter => {
+                let tag = state
+                    .thread_tag_input
+                    .take()
+                    .map(|input| input.trim().to_lowercase())
+                    .filter(|tag| !tag.is_empty());
+                state.thread_tag_filter = tag;
+                self.select_first_visible_thread();
+            }
+            KeyCode::Tab => {
+                let Ok(store) = self.runtime_state.thread_store.lock() else {
+                    return;
+                };
+                let prefix = state.thread_tag_input.as_deref().unwrap_or_default();
+                let completion = tag_suggestions(&store, prefix).into_iter().next();
+                drop(store);
+
+                if let Some(tag) = completion {
+                    state.thread_tag_input = Some(tag);
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(input) = &mut state.thread_tag_input {
+                    input.pop();
+                }
+            }
+            KeyCode::Char(character) => {
+                if let Some(input) = &mut state.thread_tag_input {
+                    input.push(character);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Selects the best match in the (possibly filtered) thread list
+    fn select_first_visible_thread(&mut self) {
+        let Ok(store) = self.runtime_state.thread_store.lock() else {
+            return;
+        };
+        let first_thread = visible_threads(&store, &self.ui_components.state)
+            .first()
+            .map(|thread| thread.id);
+        drop(store);
+
+        if first_thread.is_some() {
+            self.ui_components.state.active_thread_id = first_thread;
+        }
+    }
+
     /// Creates a new thread
     pub(super) fn create_new_thread(&mut self) {
         let Ok(mut store) = self.runtime_state.thread_store.lock() else {
@@ -67,9 +172,17 @@ impl<B: Backend> TuiApp<B> {
         // Get user input for thread name
         // For now, use a default name based on count
         let count = store.total_count() + 1;
-        let thread = store.create_thread(format!("Thread {count}"));
+        let mut thread = store.create_thread(format!("Thread {count}"));
         let thread_id = thread.id;
 
+        // New threads work in the directory of the selected thread
+        thread.working_dir = self
+            .ui_components
+            .state
+            .active_thread_id
+            .and_then(|active| store.get_thread(active))
+            .and_then(|active| active.working_dir.clone());
+
         // Save the thread
         if let Err(err) = store.save_thread(&thread) {
             tracing::error!("Failed to save new thread: {err}");
@@ -81,51 +194,89 @@ impl<B: Backend> TuiApp<B> {
         tracing::info!("Created new thread {thread_id}");
     }
 
-    /// Branches from the current message
-    pub(super) fn branch_from_current(&mut self) {
-        // Get the current thread and message
+    /// Opens the message picker for branching the selected thread, starting at its last message
+    pub(super) fn start_thread_branch(&mut self) {
         let Some(thread_id) = self.ui_components.state.active_thread_id else {
             tracing::warn!("No thread selected for branching");
             return;
         };
-
-        let Ok(mut store) = self.runtime_state.thread_store.lock() else {
+        let Ok(store) = self.runtime_state.thread_store.lock() else {
             return;
         };
+        let message_count = store
+            .conversation(thread_id)
+            .map_or(0, |messages| messages.len());
+        drop(store);
 
-        let Some(thread) = store.get_thread(thread_id) else {
-            tracing::warn!("Selected thread {thread_id} not found");
+        if message_count == 0 {
+            self.ui_components
+                .show_notice("Thread has no messages to branch from");
             return;
-        };
+        }
+        self.ui_components.state.thread_search_query = None;
+        self.ui_components.state.thread_tag_input = None;
+        self.ui_components.state.thread_branch_picker = Some(BranchPicker {
+            thread_id,
+            selected: message_count - 1,
+        });
+    }
 
-        // Get the last message from the thread
-        let Some(last_message) = thread.messages.last() else {
-            tracing::warn!("Thread {thread_id} has no messages to branch from");
+    /// Handles a key press while picking the message to branch from
+    pub(super) fn handle_branch_picker_key(&mut self, key: &KeyEvent) {
+        let Some(picker) = self.ui_components.state.thread_branch_picker.as_mut() else {
             return;
         };
+        match key.code {
+            KeyCode::Esc => self.ui_components.state.thread_branch_picker = None,
+            KeyCode::Up | KeyCode::Char('k') => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let Ok(store) = self.runtime_state.thread_store.lock() else {
+                    return;
+                };
+                let last = store
+                    .conversation(picker.thread_id)
+                    .map_or(0, |messages| messages.len().saturating_sub(1));
+                picker.selected = (picker.selected + 1).min(last);
+            }
+            KeyCode::Enter => self.complete_thread_branch(),
+            _ => {}
+        }
+    }
 
-        let message_id = last_message.id;
-
-        // Create branch
-        let count = store.total_count() + 1;
-        match store.create_branch(format!("Branch {count}"), thread_id, message_id) {
-            Ok(branch) => {
-                let branch_id = branch.id;
+    /// Branches the picked thread at the selected message and selects the branch
+    fn complete_thread_branch(&mut self) {
+        let Some(picker) = self.ui_components.state.thread_branch_picker.take() else {
+            return;
+        };
+        let Ok(mut store) = self.runtime_state.thread_store.lock() else {
+            return;
+        };
+        let message_id = store
+            .conversation(picker.thread_id)
+            .ok()
+            .and_then(|messages| messages.get(picker.selected).map(|message| message.id));
+        let Some(message_id) = message_id else {
+            return;
+        };
 
-                // Save the branch
-                if let Err(err) = store.save_thread(&branch) {
-                    tracing::error!("Failed to save branch: {err}");
-                    return;
-                }
+        let result = store.branch_thread(picker.thread_id, message_id);
+        drop(store);
 
-                // Select the new branch
+        let message = match result {
+            Ok(branch_id) => {
                 self.ui_components.state.active_thread_id = Some(branch_id);
-                tracing::info!("Created branch {branch_id} from thread {thread_id}");
+                tracing::info!(
+                    "Created branch {branch_id} from thread {} at message {message_id}",
+                    picker.thread_id
+                );
+                format!("Branched at message {}", picker.selected + 1)
             }
             Err(err) => {
                 tracing::error!("Failed to create branch: {err}");
+                format!("Branch failed: {err}")
             }
-        }
+        };
+        self.ui_components.show_notice(message);
     }
 
     /// Archives the currently selected thread
@@ -150,4 +301,134 @@ impl<B: Backend> TuiApp<B> {
 
         tracing::info!("Archived thread {thread_id}");
     }
+
+    /// Starts merging the selected thread, opening the merge target picker
+    pub(super) fn start_thread_merge(&mut self) {
+        let Some(thread_id) = self.ui_components.state.active_thread_id else {
+            tracing::warn!("No thread selected for merging");
+            return;
+        };
+
+        self.ui_components.state.thread_search_query = None;
+        self.ui_components.state.thread_tag_input = None;
+        self.ui_components.state.thread_merge_source = Some(thread_id);
+    }
+
+    /// Handles a key press while picking the thread to merge into
+    pub(super) fn handle_merge_picker_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.ui_components.state.thread_merge_source = None,
+            KeyCode::Up | KeyCode::Char('k') => self.navigate_threads_up(),
+            KeyCode::Down | KeyCode::Char('j') => self.navigate_threads_down(),
+            KeyCode::Enter => self.complete_thread_merge(),
+            _ => {}
+        }
+    }
+
+    /// Merges the merge source into the selected thread after its last message
+    fn complete_thread_merge(&mut self) {
+        let Some(source_id) = self.ui_components.state.thread_merge_source else {
+            return;
+        };
+        let Some(target_id) = self
+            .ui_components
+            .state
+            .active_thread_id
+            .filter(|target_id| *target_id != source_id)
+        else {
+            return;
+        };
+
+        let Ok(mut store) = self.runtime_state.thread_store.lock() else {
+            return;
+        };
+
+        let Some(insert_after) = store
+            .get_thread(target_id)
+            .and_then(|thread| thread.last_message())
+            .map(|message| message.id)
+        else {
+            drop(store);
+            self.ui_components
+                .show_notice("Target thread has no messages");
+            return;
+        };
+
+        let result = store.merge_threads(source_id, target_id, insert_after);
+        drop(store);
+
+        self.ui_components.state.thread_merge_source = None;
+        let message = match result {
+            Ok(merged_id) => {
+                self.ui_components.state.active_thread_id = Some(merged_id);
+                tracing::info!("Merged thread {source_id} into {target_id} as {merged_id}");
+                "Merged threads".to_owned()
+            }
+            Err(err) => {
+                tracing::error!("Failed to merge threads: {err}");
+                format!("Merge failed: {err}")
+            }
+        };
+        self.ui_components.show_notice(message);
+    }
+
+    /// Opens the inline rename input for the selected thread, prefilled with its name
+    pub(super) fn start_thread_rename(&mut self) {
+        let Some(thread_id) = self.ui_components.state.active_thread_id else {
+            tracing::warn!("No thread selected for renaming");
+            return;
+        };
+        let Ok(store) = self.runtime_state.thread_store.lock() else {
+            return;
+        };
+        let current_name = store
+            .get_thread(thread_id)
+            .map(|thread| thread.name.clone());
+        drop(store);
+
+        self.ui_components.state.thread_search_query = None;
+        self.ui_components.state.thread_tag_input = None;
+        self.ui_components.state.thread_rename_input = current_name;
+    }
+
+    /// Handles a key press while renaming the selected thread
+    pub(super) fn handle_thread_rename_key(&mut self, key: &KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.ui_components.state.thread_rename_input = None,
+            KeyCode::Enter => self.complete_thread_rename(),
+            KeyCode::Backspace => {
+                if let Some(input) = &mut self.ui_components.state.thread_rename_input {
+                    input.pop();
+                }
+            }
+            KeyCode::Char(character) => {
+                if let Some(input) = &mut self.ui_components.state.thread_rename_input {
+                    input.push(character);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Saves the rename input as the selected thread's name
+    fn complete_thread_rename(&mut self) {
+        let Some(name) = self.ui_components.state.thread_rename_input.take() else {
+            return;
+        };
+        let Some(thread_id) = self.ui_components.state.active_thread_id else {
+            return;
+        };
+        let Ok(mut store) = self.runtime_state.thread_store.lock() else {
+            return;
+        };
+
+        let result = store.rename_thread(thread_id, &name);
+        drop(store);
+
+        if let Err(err) = result {
+            tracing::error!("Failed to rename thread: {err}");
+            self.ui_components
+                .show_notice(format!("Rename failed: {err}"));
+        }
+    }
 }
@@ -5,31 +5,33 @@ use ratatui::backend::Backend;
 use std::fs;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use tokio::sync::{broadcast, mpsc};
+use tokio::sync::broadcast;
 
 use crate::config::ConfigManager;
 use crate::ui::event_source::InputEventSource;
 use crate::ui::input::InputManager;
 use crate::ui::layout;
+use crate::ui::notifications::Notifier;
 use crate::ui::persistence::TaskPersistence;
 use crate::ui::renderer::{FocusedPane, Renderer};
-use crate::ui::state::UiState;
+use crate::ui::state::{StatusNotice, UiState};
 use crate::ui::task_manager::TaskManager;
 use merlin_agent::{RoutingOrchestrator, ThreadStore};
-use merlin_routing::UiEvent;
+use merlin_core::UiEventReceiver;
+use merlin_routing::{UiChannel, UiEvent};
 
 /// Event handling and communication channels
 pub struct EventSystem {
     /// Channel receiving UI events from background tasks
-    pub receiver: mpsc::UnboundedReceiver<UiEvent>,
+    pub receiver: UiEventReceiver,
     /// Channel for sending UI events (kept internal)
-    pub sender: mpsc::UnboundedSender<UiEvent>,
+    pub sender: UiChannel,
     /// Broadcast channel for UI events (observers can subscribe)
     pub broadcast: broadcast::Sender<UiEvent>,
     /// Source of input events (abstracted for testing)
     pub source: Box<dyn InputEventSource + Send>,
     /// Latest task-specific event receiver for testing
-    pub last_task_receiver: Option<mpsc::Receiver<UiEvent>>,
+    pub last_task_receiver: Option<UiEventReceiver>,
 }
 
 /// UI component management and rendering state
@@ -52,6 +54,14 @@ pub struct UiComponents {
     pub last_render_time: Instant,
 }
 
+impl UiComponents {
+    /// Shows a short-lived status notice, starting now on the task manager's clock
+    pub fn show_notice(&mut self, message: impl Into<String>) {
+        let shown_at = self.task_manager.clock().now();
+        self.state.status_notice = Some(StatusNotice::new(message.into(), shown_at));
+    }
+}
+
 /// Runtime state and orchestration
 pub struct RuntimeState {
     /// Thread storage and management (shared with orchestrator)
@@ -62,6 +72,8 @@ pub struct RuntimeState {
     pub persistence: Option<TaskPersistence>,
     /// Log file for task execution
     pub log_file: Option<fs::File>,
+    /// Notifies about finished long-running tasks (`None` disables notifications)
+    pub notifier: Option<Notifier>,
 }
 
 /// Main TUI application
@@ -0,0 +1,182 @@
+//! `/cd` command setting the directory the active thread works in
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dirs::home_dir;
+use ratatui::backend::Backend;
+
+use super::tui_app::TuiApp;
+
+/// Input command changing the active thread's working directory, or resetting it when no path is given
+pub const CD_COMMAND: &str = "/cd";
+
+impl<B: Backend> TuiApp<B> {
+    /// Runs `input` if it is a `/cd` command, returning whether it was one
+    pub(super) fn handle_cd_command(&mut self, input: &str) -> bool {
+        let (command, argument) = input
+            .split_once(char::is_whitespace)
+            .map_or((input, ""), |(command, argument)| {
+                (command, argument.trim())
+            });
+        if !command.eq_ignore_ascii_case(CD_COMMAND) {
+            return false;
+        }
+
+        if argument.is_empty() {
+            self.reset_working_dir();
+        } else {
+            self.change_working_dir(argument);
+        }
+        true
+    }
+
+    /// Moves the active thread to `path`, starting a thread if none is active
+    ///
+    /// Relative paths are resolved against the directory the thread currently works in.
+    fn change_working_dir(&mut self, path: &str) {
+        let project_root = self.project_root();
+        let target = match resolve_working_dir(&self.active_workspace(), path) {
+            Ok(target) => target,
+            Err(err) => {
+                self.ui_components
+                    .show_notice(format!("Cannot cd to {path}: {err}"));
+                return;
+            }
+        };
+        let name = directory_name(&target);
+        let Some(thread_id) = self.ensure_active_thread(&format!("Working in {name}")) else {
+            return;
+        };
+
+        // The project root is stored as no working directory, so it follows the startup path
+        let working_dir =
+            (fs::canonicalize(&project_root).ok().as_ref() != Some(&target)).then_some(target);
+        let outcome = self
+            .runtime_state
+            .thread_store
+            .lock()
+            .map_err(|err| err.to_string())
+            .and_then(|mut store| {
+                store
+                    .set_working_dir(thread_id, working_dir)
+                    .map_err(|err| err.to_string())
+            });
+        let notice = match outcome {
+            Ok(()) => format!("Working in {name}"),
+            Err(err) => format!("Failed to change directory: {err}"),
+        };
+        self.ui_components.show_notice(notice);
+    }
+
+    /// Returns the active thread to the project root
+    fn reset_working_dir(&mut self) {
+        let Some(thread_id) = self.ui_components.state.active_thread_id else {
+            self.ui_components.show_notice("No active thread");
+            return;
+        };
+        let outcome = self
+            .runtime_state
+            .thread_store
+            .lock()
+            .map_err(|err| err.to_string())
+            .and_then(|mut store| {
+                store
+                    .set_working_dir(thread_id, None)
+                    .map_err(|err| err.to_string())
+            });
+        let notice = match outcome {
+            Ok(()) => format!("Working in {}", directory_name(&self.project_root())),
+            Err(err) => format!("Failed to change directory: {err}"),
+        };
+        self.ui_components.show_notice(notice);
+    }
+
+    /// Project root given at startup
+    fn project_root(&self) -> PathBuf {
+        self.runtime_state.orchestrator.as_ref().map_or_else(
+            || PathBuf::from("."),
+            |orchestrator| orchestrator.workspace_root().clone(),
+        )
+    }
+
+    /// Directory the active thread works in (the project root outside a thread)
+    pub(super) fn active_workspace(&self) -> PathBuf {
+        self.runtime_state.orchestrator.as_ref().map_or_else(
+            || PathBuf::from("."),
+            |orchestrator| orchestrator.thread_workspace(self.ui_components.state.active_thread_id),
+        )
+    }
+}
+
+/// Resolves `path` against `base` to an existing directory
+///
+/// A leading `~` refers to the home directory.
+///
+/// # Errors
+/// Returns a message if the path doesn't exist or is not a directory
+fn resolve_working_dir(base: &Path, path: &str) -> Result<PathBuf, String> {
+    let expanded = match path.strip_prefix('~') {
+        Some(rest) => home_dir()
+            .ok_or_else(|| "home directory not found".to_owned())?
+            .join(rest.trim_start_matches('/')),
+        None => PathBuf::from(path),
+    };
+    let resolved = fs::canonicalize(base.join(expanded)).map_err(|err| err.to_string())?;
+    if resolved.is_dir() {
+        Ok(resolved)
+    } else {
+        Err("not a directory".to_owned())
+    }
+}
+
+/// Last component of `dir`, or the whole path if it has none
+fn directory_name(dir: &Path) -> String {
+    fs::canonicalize(dir)
+        .unwrap_or_else(|_| dir.to_path_buf())
+        .file_name()
+        .map_or_else(
+            || dir.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Result;
+    use tempfile::TempDir;
+
+    /// Tests resolving relative directories and rejecting files and missing paths.
+    ///
+    /// # Errors
+    /// Returns an error if the temporary project cannot be created.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_resolve_working_dir() -> Result<()> {
+        let temp = TempDir::new()?;
+        fs::create_dir(temp.path().join("infra"))?;
+        fs::write(temp.path().join("README.md"), "# Projects")?;
+
+        assert_eq!(
+            resolve_working_dir(temp.path(), "infra"),
+            Ok(fs::canonicalize(temp.path().join("infra"))?)
+        );
+        assert_eq!(
+            resolve_working_dir(&temp.path().join("infra"), ".."),
+            Ok(fs::canonicalize(temp.path())?)
+        );
+        assert_eq!(
+            resolve_working_dir(temp.path(), "README.md"),
+            Err("not a directory".to_owned())
+        );
+        assert!(matches!(
+            resolve_working_dir(temp.path(), "backend"),
+            Err(message) if !message.is_empty()
+        ));
+        assert_eq!(directory_name(&temp.path().join("infra")), "infra");
+        Ok(())
+    }
+}
@@ -0,0 +1,132 @@
+//! System clipboard access
+//!
+//! Copies text using the platform clipboard tool (`pbcopy` on macOS, `clip` on
+//! Windows, `wl-copy`/`xclip`/`xsel` on Linux). Over SSH, or when no tool is
+//! available, falls back to an OSC 52 escape sequence so the local terminal
+//! emulator performs the copy.
+
+use std::env;
+use std::io::{self, Write as _, stdout};
+use std::process::{Command, Stdio};
+
+/// Base64 alphabet used by OSC 52 payloads
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Copies `text` to the system clipboard
+///
+/// # Errors
+/// Returns an error if neither a clipboard tool nor the OSC 52 fallback succeeds
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let over_ssh = env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some();
+    if !over_ssh
+        && clipboard_commands()
+            .iter()
+            .any(|(program, args)| pipe_to_command(program, args, text).is_ok())
+    {
+        return Ok(());
+    }
+
+    let mut out = stdout();
+    out.write_all(osc52_sequence(text).as_bytes())?;
+    out.flush()
+}
+
+/// Clipboard tool invocation: program name and arguments
+type ClipboardCommand = (&'static str, &'static [&'static str]);
+
+/// Returns the clipboard tools to try on this platform, in order
+fn clipboard_commands() -> Vec<ClipboardCommand> {
+    const NO_ARGS: &[&str] = &[];
+
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", NO_ARGS)]
+    } else if cfg!(windows) {
+        vec![("clip", NO_ARGS)]
+    } else {
+        let mut commands = Vec::<ClipboardCommand>::with_capacity(3);
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", NO_ARGS));
+        }
+        commands.push(("xclip", &["-selection", "clipboard"]));
+        commands.push(("xsel", &["--clipboard", "--input"]));
+        commands
+    }
+}
+
+/// Runs `program` with `text` written to its stdin
+///
+/// # Errors
+/// Returns an error if the program cannot be spawned or exits unsuccessfully
+fn pipe_to_command(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{program} exited with {status}")))
+    }
+}
+
+/// Builds the OSC 52 escape sequence that sets the terminal clipboard
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", encode_base64(text.as_bytes()))
+}
+
+/// Encodes bytes as padded standard base64
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let first = chunk.first().copied().unwrap_or(0);
+        let second = chunk.get(1).copied().unwrap_or(0);
+        let third = chunk.get(2).copied().unwrap_or(0);
+        let combined = (u32::from(first) << 16) | (u32::from(second) << 8) | u32::from(third);
+
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (combined >> (18 - 6 * index)) & 0x3F;
+                encoded.push(char::from(BASE64_ALPHABET[sextet as usize]));
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests base64 encoding including padding.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_encode_base64() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"f"), "Zg==");
+        assert_eq!(encode_base64(b"fo"), "Zm8=");
+        assert_eq!(encode_base64(b"foo"), "Zm9v");
+        assert_eq!(encode_base64(b"hello world"), "aGVsbG8gd29ybGQ=");
+    }
+
+    /// Tests the OSC 52 escape sequence format.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
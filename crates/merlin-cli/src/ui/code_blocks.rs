@@ -0,0 +1,89 @@
+//! Fenced code block detection in task output
+//!
+//! Locates Markdown-style ``` fences so individual code blocks can be
+//! highlighted and copied from the output pane.
+
+/// A fenced code block within task output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    /// Line index of the opening fence
+    pub start_line: usize,
+    /// Line index of the closing fence (last line of output if unterminated)
+    pub end_line: usize,
+    /// Code between the fences, without the fence lines
+    pub content: String,
+}
+
+/// A code block whose closing fence has not been reached: start line and code lines
+type OpenBlock<'output> = (usize, Vec<&'output str>);
+
+/// Finds all fenced code blocks in `output`
+///
+/// An unterminated fence extends to the end of the output.
+pub fn find_code_blocks(output: &str) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open_block: Option<OpenBlock<'_>> = None;
+    let mut last_line = 0;
+
+    for (index, line) in output.lines().enumerate() {
+        last_line = index;
+        let is_fence = line.trim_start().starts_with("```");
+
+        match (&mut open_block, is_fence) {
+            (None, true) => open_block = Some((index, Vec::new())),
+            (Some((start_line, code_lines)), true) => {
+                blocks.push(CodeBlock {
+                    start_line: *start_line,
+                    end_line: index,
+                    content: code_lines.join("\n"),
+                });
+                open_block = None;
+            }
+            (Some((_, code_lines)), false) => code_lines.push(line),
+            (None, false) => {}
+        }
+    }
+
+    if let Some((start_line, code_lines)) = open_block {
+        blocks.push(CodeBlock {
+            start_line,
+            end_line: last_line,
+            content: code_lines.join("\n"),
+        });
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests finding terminated and unterminated fenced blocks.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_find_code_blocks() {
+        let output = "Intro\n```rust\nfn main() {}\n```\nBetween\n```\nlet x = 1;\nlet y = 2;";
+        let blocks = find_code_blocks(output);
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].start_line, 1);
+        assert_eq!(blocks[0].end_line, 3);
+        assert_eq!(blocks[0].content, "fn main() {}");
+        assert_eq!(blocks[1].start_line, 5);
+        assert_eq!(blocks[1].end_line, 7);
+        assert_eq!(blocks[1].content, "let x = 1;\nlet y = 2;");
+    }
+
+    /// Tests that output without fences has no blocks.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_no_code_blocks() {
+        assert!(find_code_blocks("plain output\nno fences").is_empty());
+        assert!(find_code_blocks("").is_empty());
+    }
+}
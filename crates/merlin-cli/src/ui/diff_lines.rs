@@ -0,0 +1,137 @@
+//! Unified diff detection in task output
+//!
+//! Classifies the lines of unified diffs so the output pane can color added
+//! and removed lines. Diffs are recognized inside ```diff (or ```patch) fences
+//! and, outside fences, from a diff header (`diff --git`, `--- `, `+++ ` or
+//! `@@ `) up to the first line that is not part of a hunk.
+
+/// Role of a line within a unified diff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine {
+    /// File or hunk header
+    Header,
+    /// Line only in the new version (`+`)
+    Added,
+    /// Line only in the old version (`-`)
+    Removed,
+    /// Unchanged line shown for context
+    Context,
+}
+
+/// Classifies each line of `output`, `None` for lines outside diffs
+///
+/// Code fence lines are never part of a diff.
+pub fn classify_diff_lines(output: &str) -> Vec<Option<DiffLine>> {
+    // Inside a fence: whether the fence is a diff
+    let mut fence: Option<bool> = None;
+    let mut in_diff = false;
+
+    output
+        .lines()
+        .map(|line| {
+            if let Some(info) = line.trim_start().strip_prefix("```") {
+                fence = match fence {
+                    None => Some(is_diff_language(info)),
+                    Some(_) => None,
+                };
+                in_diff = false;
+                return None;
+            }
+            match fence {
+                Some(true) => Some(classify_line(line).unwrap_or(DiffLine::Context)),
+                None if is_header(line) => {
+                    in_diff = true;
+                    Some(DiffLine::Header)
+                }
+                None if in_diff => {
+                    let kind = classify_line(line);
+                    in_diff = kind.is_some();
+                    kind
+                }
+                Some(false) | None => None,
+            }
+        })
+        .collect()
+}
+
+/// Returns true if a fence's info string names a diff
+fn is_diff_language(info: &str) -> bool {
+    matches!(
+        info.split_whitespace().next(),
+        Some("diff" | "patch" | "udiff")
+    )
+}
+
+/// Returns true if `line` starts a diff outside of fences
+fn is_header(line: &str) -> bool {
+    ["diff --git ", "--- ", "+++ ", "@@ "]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+/// Classifies a line by its prefix, `None` if it cannot belong to a hunk
+fn classify_line(line: &str) -> Option<DiffLine> {
+    if is_header(line) {
+        return Some(DiffLine::Header);
+    }
+    match line.chars().next() {
+        Some('+') => Some(DiffLine::Added),
+        Some('-') => Some(DiffLine::Removed),
+        // `\ No newline at end of file` is shown like context
+        Some(' ' | '\\') => Some(DiffLine::Context),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that lines of a fenced diff are classified by their prefix.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_fenced_diff() {
+        let output = "Changes:\n```diff\n--- a/lib.rs\n+++ b/lib.rs\n@@ -1,2 +1,2 @@\n fn main() {\n-    old();\n+    new();\n```\n- not a diff";
+        assert_eq!(
+            classify_diff_lines(output),
+            vec![
+                None,
+                None,
+                Some(DiffLine::Header),
+                Some(DiffLine::Header),
+                Some(DiffLine::Header),
+                Some(DiffLine::Context),
+                Some(DiffLine::Removed),
+                Some(DiffLine::Added),
+                None,
+                None,
+            ]
+        );
+    }
+
+    /// Tests that an unfenced diff ends at the first line outside a hunk.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_unfenced_diff() {
+        let output =
+            "@@ -3 +3 @@\n-a\n+b\n\\ No newline at end of file\n\n- list item\n```rust\n+x\n```";
+        assert_eq!(
+            classify_diff_lines(output),
+            vec![
+                Some(DiffLine::Header),
+                Some(DiffLine::Removed),
+                Some(DiffLine::Added),
+                Some(DiffLine::Context),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+}
@@ -0,0 +1,432 @@
+mod steps;
+
+use super::output_buffer;
+use super::persistence::TaskPersistence;
+use super::state::{ConversationEntry, ConversationRole, PendingQuestion, UiState};
+use super::task_manager::{TaskDisplay, TaskManager, TaskStatus};
+use merlin_core::{TIMING_MARKER, ThreadId, WorkUnit};
+use merlin_routing::{MessageLevel, TaskId, TaskProgress, TaskResult, UiEvent};
+use merlin_tooling::ToolError;
+use std::io;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+/// Marks a subtask the parent was split into, in the parent's output
+const SUBTASK_PLANNED: &str = "○";
+/// Marks a subtask starting, in the parent's output
+const SUBTASK_STARTED: &str = "▸";
+/// Marks a subtask completing, in the parent's output
+const SUBTASK_COMPLETED: &str = "✓";
+/// Marks a subtask failing, in the parent's output
+const SUBTASK_FAILED: &str = "✗";
+/// Marks a question asked of the user, in the task's output
+pub const QUESTION_MARKER: &str = "[?]";
+/// Marks the user's answer to a question, in the task's output
+pub const ANSWER_MARKER: &str = ">";
+/// Indents the output of a subtask nested under its parent
+const SUBTASK_INDENT: &str = "  │ ";
+
+/// Handles UI events and updates task manager and state
+pub struct EventHandler<'handler> {
+    task_manager: &'handler mut TaskManager,
+    state: &'handler mut UiState,
+    persistence: Option<&'handler TaskPersistence>,
+}
+
+impl<'handler> EventHandler<'handler> {
+    /// Creates a new `EventHandler`
+    pub fn new(
+        task_manager: &'handler mut TaskManager,
+        state: &'handler mut UiState,
+        persistence: Option<&'handler TaskPersistence>,
+    ) -> Self {
+        Self {
+            task_manager,
+            state,
+            persistence,
+        }
+    }
+
+    /// Handles a UI event
+    pub fn handle_event(&mut self, event: UiEvent) {
+        match event {
+            UiEvent::TaskStarted {
+                task_id,
+                description,
+                parent_id,
+                thread_id,
+            } => self.handle_task_started(task_id, description, parent_id, thread_id),
+
+            UiEvent::TaskProgress { task_id, progress } => {
+                self.handle_task_progress(task_id, progress);
+            }
+
+            UiEvent::WorkUnitStarted { task_id, work_unit } => {
+                self.handle_work_unit_started(task_id, work_unit);
+            }
+
+            UiEvent::TaskOutput { task_id, output } => self.handle_task_output(task_id, &output),
+
+            UiEvent::TaskCompleted { task_id, result } => {
+                self.handle_task_completed(task_id, result);
+            }
+
+            UiEvent::TaskFailed { task_id, error } => self.handle_task_failed(task_id, &error),
+
+            UiEvent::TaskRetrying {
+                task_id,
+                retry_count,
+                error,
+            } => self.handle_task_retrying(task_id, retry_count, &error),
+
+            UiEvent::SystemMessage { level, message } => {
+                self.handle_system_message(level, message);
+            }
+
+            UiEvent::TaskStepStarted {
+                task_id,
+                step_id,
+                step_type,
+                content,
+            } => self.handle_task_step_started(task_id, step_id, &step_type, content),
+
+            UiEvent::TaskStepCompleted { task_id, step_id } => {
+                self.handle_task_step_completed(task_id, &step_id);
+            }
+
+            UiEvent::TaskStepFailed {
+                task_id,
+                step_id,
+                error,
+            } => self.handle_task_step_failed(task_id, &step_id, &error),
+
+            UiEvent::PlanStepStarted {
+                task_id,
+                step,
+                total,
+                title,
+            } => self.handle_plan_step_started(task_id, step, total, title),
+
+            question @ UiEvent::UserQuestion { .. } => self.handle_user_question(question),
+
+            UiEvent::ToolCallStarted {
+                task_id,
+                tool,
+                args,
+            } => self.handle_tool_call_started(task_id, tool, args),
+
+            UiEvent::ToolCallCompleted {
+                task_id,
+                tool,
+                result,
+            } => self.handle_tool_call_completed(task_id, &tool, result),
+
+            UiEvent::SubtaskSpawned {
+                parent_id,
+                child_id,
+                description,
+            } => self.handle_subtask_spawned(parent_id, child_id, &description),
+
+            UiEvent::WorkUnitProgress { .. } | UiEvent::ThinkingUpdate { .. } => {
+                // WorkUnitProgress: the WorkUnit is already updated by the executor via
+                // Arc<Mutex<>>, so this event is just a signal to re-render the UI
+                // ThinkingUpdate: deprecated, functionality now handled by
+                // TaskStepStarted; kept for backward compatibility with existing tests
+            }
+
+            UiEvent::EmbeddingProgress { current, total, .. } => {
+                // Clear progress when complete (current == total)
+                if current >= total {
+                    self.state.embedding_progress = None;
+                    self.state.index_ready = true;
+                } else {
+                    self.state.embedding_progress = Some((current, total));
+                }
+            }
+
+            UiEvent::SessionStats { session_cost } => self.state.session_cost = session_cost,
+        }
+    }
+
+    // Private event handlers
+
+    fn handle_task_started(
+        &mut self,
+        task_id: TaskId,
+        description: String,
+        parent_id: Option<TaskId>,
+        thread_id: Option<ThreadId>,
+    ) {
+        let started_line = format!("{SUBTASK_STARTED} {description}");
+        let subtask_of = self.state.spawned_subtasks.remove(&task_id);
+
+        // Task may already exist if it was created immediately on input submit
+        // If so, just update the thread and parent if provided
+        if let Some(existing_task) = self.task_manager.get_task_mut(task_id) {
+            if let Some(tid) = thread_id {
+                existing_task.thread_id = Some(tid);
+            }
+            if parent_id.is_some() {
+                existing_task.parent_id = parent_id;
+            }
+        } else {
+            // Task doesn't exist yet, create it
+            let task_display = TaskDisplay {
+                description,
+                thread_id,
+                parent_id,
+                subtask_of,
+                ..Default::default()
+            };
+            self.task_manager.add_task(task_id, task_display);
+        }
+
+        self.state.active_running_tasks.insert(task_id);
+
+        // Update active_thread_id when a task with a thread starts
+        if let Some(tid) = thread_id {
+            self.state.active_thread_id = Some(tid);
+        }
+
+        self.state.processing_status = None;
+
+        // Subtasks are followed in their parent's output rather than taking the focus
+        if let Some(parent) = subtask_of {
+            self.handle_task_output(parent, &started_line);
+        } else {
+            self.select_task(task_id);
+        }
+    }
+
+    fn handle_subtask_spawned(&mut self, parent_id: TaskId, child_id: TaskId, description: &str) {
+        self.state.spawned_subtasks.insert(child_id, parent_id);
+        self.handle_task_output(parent_id, &format!("{SUBTASK_PLANNED} {description}"));
+    }
+
+    fn handle_task_progress(&mut self, task_id: TaskId, progress: TaskProgress) {
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            task.progress = Some(progress);
+        }
+    }
+
+    fn handle_work_unit_started(&mut self, task_id: TaskId, work_unit: Arc<Mutex<WorkUnit>>) {
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            task.work_unit = Some(work_unit);
+        }
+    }
+
+    fn handle_task_output(&mut self, task_id: TaskId, output: &str) {
+        let limits = *self.task_manager.output_limits();
+        let Some(task) = self.task_manager.get_task_mut(task_id) else {
+            return;
+        };
+        let subtask_of = task.subtask_of;
+
+        task.output_lines.push(output.to_string());
+
+        // Filter out "Prompt:" lines and append to output
+        let mut appended_lines = 0;
+        for line in output.lines() {
+            if line.trim_start().starts_with("Prompt:") {
+                continue;
+            }
+
+            if !task.output.is_empty() {
+                task.output.push('\n');
+            }
+            task.output.push_str(line);
+            appended_lines += 1;
+        }
+
+        // Following viewports stay at the bottom; pinned ones count what they missed
+        task.viewport.record_new_lines(appended_lines);
+
+        let spill_file = self
+            .persistence
+            .map(|persistence| persistence.spill_path(task_id));
+        if let Err(err) =
+            output_buffer::record_appended(task, appended_lines, &limits, spill_file.as_deref())
+        {
+            warn!("Failed to spill output of task {:?}: {}", task_id, err);
+        }
+
+        // Subtasks are followed in their parent's output, nested under the subtask
+        if let Some(parent) = subtask_of {
+            let nested = output
+                .lines()
+                .filter(|line| !line.trim_start().starts_with("Prompt:"))
+                .map(|line| format!("{SUBTASK_INDENT}{line}"))
+                .collect::<Vec<_>>();
+            if !nested.is_empty() {
+                self.handle_task_output(parent, &nested.join("\n"));
+            }
+        }
+    }
+
+    /// Reports how `task_id` ended in its parent's output if it is a subtask, returning whether it is
+    fn report_to_parent(&mut self, task_id: TaskId, marker: &str, detail: &str) -> bool {
+        let Some((parent, description)) = self.task_manager.get_task(task_id).and_then(|task| {
+            task.subtask_of
+                .map(|parent| (parent, task.description.clone()))
+        }) else {
+            return false;
+        };
+        self.handle_task_output(parent, &format!("{marker} {description}{detail}"));
+        true
+    }
+
+    /// Queues the question for the input area and shows it in the task's output
+    fn handle_user_question(&mut self, event: UiEvent) {
+        let UiEvent::UserQuestion {
+            task_id,
+            question,
+            options,
+            answer,
+        } = event
+        else {
+            return;
+        };
+        let question = PendingQuestion {
+            task_id,
+            question,
+            options,
+            answer,
+        };
+        self.handle_task_output(
+            question.task_id,
+            &format!("{QUESTION_MARKER} {}", question.prompt()),
+        );
+        self.state.pending_questions.push_back(question);
+    }
+
+    /// Declines the questions of a finished task that were never answered
+    fn drop_questions(&mut self, task_id: TaskId) {
+        self.state.pending_questions.retain(|question| {
+            let asked_by_task = question.task_id == task_id;
+            if asked_by_task {
+                question.answer.decline();
+            }
+            !asked_by_task
+        });
+    }
+
+    fn handle_task_completed(&mut self, task_id: TaskId, result: Box<TaskResult>) {
+        self.state.active_running_tasks.remove(&task_id);
+        self.drop_questions(task_id);
+        self.state.last_task_model = Some(result.tier_used.clone());
+
+        if !result.timings.is_empty() {
+            self.handle_task_output(task_id, &format!("{TIMING_MARKER} {}", result.timings));
+        }
+
+        self.task_manager.finish_task(task_id);
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            task.status = TaskStatus::Completed;
+            // Clear progress indicators when task completes
+            task.progress = None;
+            task.plan_step = None;
+            task.tokens_used = Some(result.tokens_used.clone());
+            task.validation_passed = Some(result.validation.passed);
+        }
+
+        if let Err(save_err) = self.save_task(task_id) {
+            warn!("Failed to save completed task {:?}: {}", task_id, save_err);
+        }
+
+        // A subtask's response is part of its parent's, which joins the conversation
+        if !self.report_to_parent(task_id, SUBTASK_COMPLETED, "") {
+            self.state.add_conversation_entry(ConversationEntry {
+                role: ConversationRole::Assistant,
+                text: result.response.text,
+            });
+        }
+    }
+
+    fn handle_task_failed(&mut self, task_id: TaskId, error: &ToolError) {
+        self.state.active_running_tasks.remove(&task_id);
+        self.drop_questions(task_id);
+
+        self.task_manager.finish_task(task_id);
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            task.status = TaskStatus::Failed;
+            task.plan_step = None;
+
+            let error_msg = format!("Error: {}", error.user_message());
+            if !task.output.is_empty() {
+                task.output.push('\n');
+            }
+            task.output.push_str(&error_msg);
+        }
+
+        if let Err(save_err) = self.save_task(task_id) {
+            warn!("Failed to save failed task {:?}: {}", task_id, save_err);
+        }
+        self.report_to_parent(
+            task_id,
+            SUBTASK_FAILED,
+            &format!(": {}", error.user_message()),
+        );
+    }
+
+    fn handle_task_retrying(&mut self, task_id: TaskId, retry_count: u32, _error: &ToolError) {
+        // Update retry count in task display
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            task.retry_count = retry_count;
+        }
+    }
+
+    fn handle_system_message(&mut self, level: MessageLevel, message: String) {
+        let prefix = match level {
+            MessageLevel::Info => "[i]",
+            MessageLevel::Warning => "[!]",
+            MessageLevel::Error => "[X]",
+            MessageLevel::Success => "[+]",
+        };
+
+        // Send to active task
+        if let Some(task_id) = self.state.active_task_id
+            && let Some(task) = self.task_manager.get_task_mut(task_id)
+        {
+            if !task.output.is_empty() {
+                task.output.push('\n');
+            }
+            task.output.push_str(prefix);
+            task.output.push(' ');
+            task.output.push_str(&message);
+        }
+
+        self.state.add_conversation_entry(ConversationEntry {
+            role: ConversationRole::System,
+            text: message,
+        });
+    }
+
+    /// Saves the task, spilling the head of a large output so the file keeps only the tail
+    ///
+    /// # Errors
+    /// Returns an error if the spill file or the task file cannot be written
+    fn save_task(&mut self, task_id: TaskId) -> io::Result<()> {
+        let max_bytes = self.task_manager.output_limits().persisted_bytes;
+        let (Some(persistence), Some(task)) =
+            (self.persistence, self.task_manager.get_task_mut(task_id))
+        else {
+            return Ok(());
+        };
+        output_buffer::fit_for_save(task, max_bytes, Some(&persistence.spill_path(task_id)))?;
+        persistence.save_task(task_id, task)
+    }
+
+    /// Prunes completed steps of the task once it exceeds the node budget
+    fn prune_steps(&mut self, task_id: TaskId) {
+        let max_nodes = self.task_manager.output_limits().step_nodes;
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            output_buffer::prune_steps(task, max_nodes);
+        }
+    }
+
+    fn select_task(&mut self, task_id: TaskId) {
+        // Scroll position lives on the task, so it survives switching away and back
+        self.state.active_task_id = Some(task_id);
+    }
+}
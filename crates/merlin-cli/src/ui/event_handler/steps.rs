@@ -0,0 +1,128 @@
+//! Handling of task steps: the plan step pinned above the output, streamed
+//! steps and the tool calls made while they run.
+
+use super::EventHandler;
+use crate::ui::task_manager::{TaskStepInfo, TaskStepStatus, ToolCallInfo};
+use merlin_routing::TaskId;
+use serde_json::Value;
+use std::time::SystemTime;
+
+impl EventHandler<'_> {
+    pub(super) fn handle_task_step_started(
+        &mut self,
+        task_id: TaskId,
+        step_id: String,
+        step_type: &str,
+        content: String,
+    ) {
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            let step_info = TaskStepInfo {
+                step_id,
+                step_type: step_type.to_owned(),
+                content,
+                ..Default::default()
+            };
+
+            // Set as current step (replaces previous step)
+            task.current_step = Some(step_info.clone());
+
+            // Also keep in history
+            task.steps.push(step_info);
+        }
+        self.prune_steps(task_id);
+    }
+
+    /// Replaces the plan step pinned above the task's output
+    pub(super) fn handle_plan_step_started(
+        &mut self,
+        task_id: TaskId,
+        step: usize,
+        total: usize,
+        title: String,
+    ) {
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            task.plan_step = Some((step, total, title));
+        }
+    }
+
+    pub(super) fn handle_task_step_completed(&mut self, task_id: TaskId, step_id: &str) {
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            // Mark step as completed in history
+            if let Some(step) = task.steps.iter_mut().find(|step| step.step_id == step_id) {
+                step.status = TaskStepStatus::Completed;
+                step.completed_at = Some(SystemTime::now());
+            }
+
+            // Clear current step if it matches
+            if task
+                .current_step
+                .as_ref()
+                .is_some_and(|step| step.step_id == step_id)
+            {
+                task.current_step = None;
+            }
+        }
+    }
+
+    pub(super) fn handle_task_step_failed(&mut self, task_id: TaskId, step_id: &str, _error: &str) {
+        if let Some(task) = self.task_manager.get_task_mut(task_id) {
+            // Mark step as failed in history
+            if let Some(step) = task.steps.iter_mut().find(|step| step.step_id == step_id) {
+                step.status = TaskStepStatus::Failed;
+                step.completed_at = Some(SystemTime::now());
+            }
+
+            // Update current step status if it matches
+            if let Some(current_step) = &mut task.current_step
+                && current_step.step_id == step_id
+            {
+                current_step.status = TaskStepStatus::Failed;
+            }
+        }
+    }
+
+    /// Records a tool call under the running step of the task
+    ///
+    /// Calls made while no step is running are not recorded.
+    pub(super) fn handle_tool_call_started(&mut self, task_id: TaskId, tool: String, args: Value) {
+        if let Some(step) = self.running_step_mut(task_id) {
+            step.tool_calls.push(ToolCallInfo {
+                tool,
+                args,
+                result: None,
+                started_at: SystemTime::now(),
+                completed_at: None,
+            });
+        }
+        self.prune_steps(task_id);
+    }
+
+    /// Stores the result on the latest unfinished call of `tool` in the running step
+    pub(super) fn handle_tool_call_completed(
+        &mut self,
+        task_id: TaskId,
+        tool: &str,
+        result: Value,
+    ) {
+        let call = self.running_step_mut(task_id).and_then(|step| {
+            step.tool_calls
+                .iter_mut()
+                .rev()
+                .find(|call| call.tool == tool && call.result.is_none())
+        });
+        if let Some(call) = call {
+            call.result = Some(result);
+            call.completed_at = Some(SystemTime::now());
+        }
+    }
+
+    /// Returns the step history entry of the task's running step
+    fn running_step_mut(&mut self, task_id: TaskId) -> Option<&mut TaskStepInfo> {
+        let task = self.task_manager.get_task_mut(task_id)?;
+        let step_id = task.current_step.as_ref()?.step_id.clone();
+        task.steps
+            .iter_mut()
+            .rev()
+            .find(|step| step.step_id == step_id)
+    }
+}
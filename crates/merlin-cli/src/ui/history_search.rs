@@ -0,0 +1,156 @@
+//! Search through previously submitted messages
+//!
+//! `Ctrl+R` opens a search over the user messages of this session and of every
+//! stored thread. Rendering and key handling share these functions so the
+//! selection always refers to the list on screen.
+
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+use merlin_agent::ThreadStore;
+
+use super::state::{ConversationRole, UiState};
+
+/// State of the open history search
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HistorySearch {
+    /// Text typed into the search
+    pub query: String,
+    /// Index of the selected entry in the matching entries
+    pub selected: usize,
+}
+
+/// Returns past user messages, newest first and without duplicates
+///
+/// Messages from this session come first, followed by those of stored threads
+/// (archived ones included) from newest to oldest.
+pub fn history_entries(state: &UiState, store: Option<&ThreadStore>) -> Vec<String> {
+    let session = state
+        .conversation_history
+        .iter()
+        .rev()
+        .filter(|entry| entry.role == ConversationRole::User)
+        .map(|entry| entry.text.as_str());
+
+    let mut stored: Vec<_> = store
+        .map(|store| {
+            store
+                .active_threads()
+                .into_iter()
+                .chain(store.archived_threads())
+                .flat_map(|thread| &thread.messages)
+                .collect()
+        })
+        .unwrap_or_default();
+    stored.sort_by_key(|message| Reverse(message.created_at));
+
+    let mut seen = HashSet::new();
+    session
+        .chain(stored.into_iter().map(|message| message.content.as_str()))
+        .map(str::trim)
+        .filter(|text| !text.is_empty() && seen.insert(*text))
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+/// Returns the entries matching `query`, keeping their order
+///
+/// Entries containing the query (ignoring case) match. If none do, entries
+/// where every query word fuzzily matches one of their words are returned instead.
+/// An empty query matches every entry.
+pub fn matching_entries<'entries>(entries: &'entries [String], query: &str) -> Vec<&'entries str> {
+    let query = query.trim().to_lowercase();
+    let substring_matches: Vec<&str> = entries
+        .iter()
+        .map(String::as_str)
+        .filter(|entry| entry.to_lowercase().contains(&query))
+        .collect();
+    if !substring_matches.is_empty() {
+        return substring_matches;
+    }
+
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+    entries
+        .iter()
+        .map(String::as_str)
+        .filter(|entry| {
+            let entry = entry.to_lowercase();
+            query_words.iter().all(|query_word| {
+                entry
+                    .split_whitespace()
+                    .any(|word| is_subsequence(query_word, word))
+            })
+        })
+        .collect()
+}
+
+/// Whether the characters of `needle` appear in `haystack` in order
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle
+        .chars()
+        .all(|character| haystack.any(|candidate| candidate == character))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::state::ConversationEntry;
+
+    fn entries(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| (*text).to_owned()).collect()
+    }
+
+    /// Tests that substring matches win and word-level fuzzy matching is the fallback.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_matching_entries_prefers_substring_matches() {
+        let history = entries(&[
+            "Refactor the parser module",
+            "Add tests for the lexer",
+            "Fix the PARSER error messages",
+        ]);
+
+        assert_eq!(
+            matching_entries(&history, "parser"),
+            vec![
+                "Refactor the parser module",
+                "Fix the PARSER error messages"
+            ]
+        );
+        assert_eq!(matching_entries(&history, "").len(), 3);
+        // No entry contains "tst lxr", but both words fuzzily match words of one entry
+        assert_eq!(
+            matching_entries(&history, "tst lxr"),
+            vec!["Add tests for the lexer"]
+        );
+        assert!(matching_entries(&history, "deploy").is_empty());
+    }
+
+    /// Tests that session history comes first, newest first and without duplicates.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_history_entries_skips_assistant_messages_and_duplicates() {
+        let mut state = UiState::default();
+        for (role, text) in [
+            (ConversationRole::User, "First question"),
+            (ConversationRole::Assistant, "An answer"),
+            (ConversationRole::User, "Second question"),
+            (ConversationRole::User, " First question "),
+        ] {
+            state.add_conversation_entry(ConversationEntry {
+                role,
+                text: text.to_owned(),
+            });
+        }
+
+        assert_eq!(
+            history_entries(&state, None),
+            vec!["First question", "Second question"]
+        );
+    }
+}
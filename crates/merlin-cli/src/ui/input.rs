@@ -100,6 +100,18 @@ impl InputManager {
         }
     }
 
+    /// Replaces the input with `text`, placing the cursor at its end
+    pub fn set_text(&mut self, text: &str) {
+        self.clear();
+        for (index, line) in text.lines().enumerate() {
+            if index > 0 {
+                self.input_area.insert_newline();
+                self.record_manual_newline();
+            }
+            self.input_area.insert_str(line);
+        }
+    }
+
     /// Clears the input area
     pub fn clear(&mut self) {
         self.input_area = TextArea::default();
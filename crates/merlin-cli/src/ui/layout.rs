@@ -27,8 +27,17 @@ impl LayoutCache {
         self.output_area
             .map_or(0, |(_, height)| height.saturating_sub(2))
     }
+
+    /// Gets the output viewport width (excluding borders and horizontal padding)
+    pub fn output_viewport_width(&self) -> u16 {
+        self.output_area
+            .map_or(0, |(width, _)| width.saturating_sub(4))
+    }
 }
 
+/// Height of the `Ctrl+R` history search shown in place of the input area (including borders)
+pub const HISTORY_SEARCH_HEIGHT: u16 = 10;
+
 /// Calculates input area height based on content
 ///
 /// # Arguments
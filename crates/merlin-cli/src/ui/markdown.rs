@@ -0,0 +1,366 @@
+//! Lightweight Markdown rendering for task output
+//!
+//! Renders each output line as exactly one styled line, so line indices (scroll
+//! offsets, code block selection) match the raw text. Fenced code keeps its
+//! indentation and is clipped to the pane width instead of being re-wrapped.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use std::mem::take;
+use unicode_width::{UnicodeWidthChar as _, UnicodeWidthStr as _};
+
+use super::diff_lines::{DiffLine, classify_diff_lines};
+
+/// Bullet glyphs by list nesting level
+const BULLETS: [&str; 3] = ["•", "◦", "▪"];
+
+/// Columns of source indentation per list nesting level
+const LIST_INDENT: usize = 2;
+
+/// Styles applied to Markdown elements
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownStyles {
+    /// Plain prose
+    pub text: Style,
+    /// Header lines
+    pub heading: Style,
+    /// Inline code and fenced code block contents
+    pub code: Style,
+    /// Code fence lines
+    pub fence: Style,
+    /// Link URLs
+    pub link: Style,
+    /// Added lines of diffs
+    pub added: Style,
+    /// Removed lines of diffs
+    pub removed: Style,
+}
+
+/// Renders Markdown `text` into one styled line per source line
+///
+/// Code lines wider than `code_width` columns are clipped with an ellipsis.
+/// An unterminated fence extends to the end of the text. Lines of unified
+/// diffs are rendered as code, with added and removed lines colored.
+pub fn render_markdown(
+    text: &str,
+    styles: &MarkdownStyles,
+    code_width: usize,
+) -> Vec<Line<'static>> {
+    let mut in_code_block = false;
+
+    text.lines()
+        .zip(classify_diff_lines(text))
+        .map(|(line, diff_line)| {
+            if line.trim_start().starts_with("```") {
+                in_code_block = !in_code_block;
+                Line::styled(line.to_owned(), styles.fence)
+            } else if let Some(kind) = diff_line {
+                let style = match kind {
+                    DiffLine::Header => styles.heading,
+                    DiffLine::Added => styles.added,
+                    DiffLine::Removed => styles.removed,
+                    DiffLine::Context if in_code_block => styles.code,
+                    DiffLine::Context => styles.text,
+                };
+                Line::styled(clip_to_width(line, code_width), style)
+            } else if in_code_block {
+                Line::styled(clip_to_width(line, code_width), styles.code)
+            } else {
+                render_prose_line(line, styles)
+            }
+        })
+        .collect()
+}
+
+/// Renders a line outside of code blocks (header, list item, or paragraph text)
+fn render_prose_line(line: &str, styles: &MarkdownStyles) -> Line<'static> {
+    let content = line.trim_start();
+
+    if let Some(heading) = strip_heading_marker(content) {
+        return Line::from(inline_spans(heading, styles.heading, styles));
+    }
+
+    let Some((marker, item)) = strip_list_marker(content) else {
+        return Line::from(inline_spans(line, styles.text, styles));
+    };
+
+    let level = (line.len() - content.len()) / LIST_INDENT;
+    let marker = marker.unwrap_or(BULLETS[level % BULLETS.len()]);
+    let mut spans = vec![Span::styled(
+        format!("{}{marker} ", " ".repeat(level * LIST_INDENT)),
+        styles.text,
+    )];
+    spans.extend(inline_spans(item, styles.text, styles));
+    Line::from(spans)
+}
+
+/// Returns the header text if `content` starts with 1-6 `#` and a space
+fn strip_heading_marker(content: &str) -> Option<&str> {
+    let level = content
+        .chars()
+        .take_while(|&character| character == '#')
+        .count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    content[level..].strip_prefix(' ')
+}
+
+/// A list item's marker (`None` for bullets) and text
+type ListItem<'content> = (Option<&'content str>, &'content str);
+
+/// Splits a list item into its marker and text
+///
+/// Bullet items (`-`, `*`, `+`) return `None` as the marker so a glyph is chosen
+/// by nesting level; numbered items keep their number (e.g. `2.`).
+fn strip_list_marker(content: &str) -> Option<ListItem<'_>> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(item) = content.strip_prefix(bullet) {
+            return Some((None, item));
+        }
+    }
+
+    let digits = content.chars().take_while(char::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    content[digits..]
+        .strip_prefix(". ")
+        .map(|item| (Some(&content[..=digits]), item))
+}
+
+/// Splits inline Markdown into styled spans
+///
+/// Handles `**bold**`, `` `code` `` and `[label](url)`; unmatched markers are kept as text.
+fn inline_spans(text: &str, base: Style, styles: &MarkdownStyles) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut bold = false;
+    let mut rest = text;
+
+    while let Some(character) = rest.chars().next() {
+        let style = if bold {
+            base.add_modifier(Modifier::BOLD)
+        } else {
+            base
+        };
+
+        if let Some(after) = rest.strip_prefix("**") {
+            flush_plain(&mut spans, &mut plain, style);
+            bold = !bold;
+            rest = after;
+            continue;
+        }
+
+        if character == '`'
+            && let Some(end) = rest[1..].find('`')
+        {
+            flush_plain(&mut spans, &mut plain, style);
+            spans.push(Span::styled(rest[1..=end].to_owned(), styles.code));
+            rest = &rest[end + 2..];
+            continue;
+        }
+
+        if character == '['
+            && let Some((label, url, after)) = split_link(rest)
+        {
+            flush_plain(&mut spans, &mut plain, style);
+            spans.push(Span::styled(label.to_owned(), style));
+            spans.push(Span::styled(format!(" ({url})"), styles.link));
+            rest = after;
+            continue;
+        }
+
+        plain.push(character);
+        rest = &rest[character.len_utf8()..];
+    }
+
+    let style = if bold {
+        base.add_modifier(Modifier::BOLD)
+    } else {
+        base
+    };
+    flush_plain(&mut spans, &mut plain, style);
+    spans
+}
+
+/// Moves accumulated plain text into a span
+fn flush_plain(spans: &mut Vec<Span<'static>>, plain: &mut String, style: Style) {
+    if !plain.is_empty() {
+        spans.push(Span::styled(take(plain), style));
+    }
+}
+
+/// Splits `[label](url)rest` into its label, URL and the remaining text
+fn split_link(text: &str) -> Option<(&str, &str, &str)> {
+    let label_end = text.find("](")?;
+    let after_label = &text[label_end + 2..];
+    let url_end = after_label.find(')')?;
+    Some((
+        &text[1..label_end],
+        &after_label[..url_end],
+        &after_label[url_end + 1..],
+    ))
+}
+
+/// Clips `line` to `width` columns, marking clipped lines with an ellipsis
+fn clip_to_width(line: &str, width: usize) -> String {
+    if width == 0 || line.width() <= width {
+        return line.to_owned();
+    }
+
+    let mut clipped = String::new();
+    let mut used = 0;
+    for character in line.chars() {
+        let char_width = character.width().unwrap_or(0);
+        if used + char_width >= width {
+            break;
+        }
+        clipped.push(character);
+        used += char_width;
+    }
+    clipped.push('…');
+    clipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    /// Styles with distinct colors so tests can tell elements apart.
+    fn test_styles() -> MarkdownStyles {
+        MarkdownStyles {
+            text: Style::default(),
+            heading: Style::default()
+                .fg(Color::Blue)
+                .add_modifier(Modifier::BOLD),
+            code: Style::default().fg(Color::Green),
+            fence: Style::default().add_modifier(Modifier::DIM),
+            link: Style::default().fg(Color::Cyan),
+            added: Style::default().fg(Color::LightGreen),
+            removed: Style::default().fg(Color::Red),
+        }
+    }
+
+    /// Renders `text` at an 80 column code width and returns the plain text of each line.
+    fn render_text(text: &str) -> Vec<String> {
+        render_markdown(text, &test_styles(), 80)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Tests that nested bullet and numbered lists are indented by level.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_nested_lists() {
+        let rendered = render_text(
+            "- Parser\n  - Lexer\n    - Tokens with **bold**\n  2. Second step\n- Renderer",
+        );
+
+        assert_eq!(
+            rendered,
+            vec![
+                "• Parser",
+                "  ◦ Lexer",
+                "    ▪ Tokens with bold",
+                "  2. Second step",
+                "• Renderer",
+            ]
+        );
+    }
+
+    /// Tests that an unterminated fence renders the rest of the text as code.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_unterminated_code_fence() {
+        let lines = render_markdown(
+            "Fix:\n```rust\n    let **x** = 1;\n# not a header",
+            &test_styles(),
+            80,
+        );
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2].to_string(), "    let **x** = 1;");
+        assert_eq!(lines[3].to_string(), "# not a header");
+        assert!(
+            lines[2..]
+                .iter()
+                .all(|line| line.style == test_styles().code)
+        );
+    }
+
+    /// Tests that code lines are clipped rather than wrapped, while prose is left whole.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_code_lines_clipped_to_width() {
+        let lines = render_markdown(
+            "A long sentence of prose that is wider than the pane\n```\nlet value = compute();\n```",
+            &test_styles(),
+            12,
+        );
+
+        assert_eq!(
+            lines[0].to_string(),
+            "A long sentence of prose that is wider than the pane"
+        );
+        assert_eq!(lines[2].to_string(), "let value =…");
+        assert_eq!(lines[2].width(), 12);
+    }
+
+    /// Tests headers, inline code and links.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_headers_inline_code_and_links() {
+        let styles = test_styles();
+        let lines = render_markdown(
+            "## Summary\nRun `cargo test` and see [the docs](https://docs.rs).\n#hashtag",
+            &styles,
+            80,
+        );
+
+        assert_eq!(lines[0].to_string(), "Summary");
+        assert_eq!(lines[0].spans[0].style, styles.heading);
+
+        assert_eq!(
+            lines[1].to_string(),
+            "Run cargo test and see the docs (https://docs.rs)."
+        );
+        assert_eq!(lines[1].spans[1].style, styles.code);
+        assert_eq!(lines[1].spans[4].style, styles.link);
+
+        // Not a header without the space after the marker
+        assert_eq!(lines[2].to_string(), "#hashtag");
+    }
+
+    /// Tests that diff lines are colored and not rendered as Markdown.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_diff_lines_colored() {
+        let styles = test_styles();
+        let lines = render_markdown(
+            "```diff\n@@ -1 +1 @@\n-# old\n+# new\n```\n- item",
+            &styles,
+            80,
+        );
+
+        assert_eq!(lines[1].style, styles.heading);
+        assert_eq!(lines[2].to_string(), "-# old");
+        assert_eq!(lines[2].style, styles.removed);
+        assert_eq!(lines[3].to_string(), "+# new");
+        assert_eq!(lines[3].style, styles.added);
+        // Prose after the fence is Markdown again
+        assert_eq!(lines[5].to_string(), "• item");
+    }
+}
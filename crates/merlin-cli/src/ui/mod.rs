@@ -18,14 +18,38 @@ pub mod theme;
 // Internal modules (visible for testing)
 /// TUI application and main event loop (contains sub-modules)
 pub mod app;
+/// System clipboard access
+pub mod clipboard;
+/// Fenced code block detection in task output
+pub mod code_blocks;
+/// Unified diff detection in task output
+pub mod diff_lines;
 /// Event handler for UI events
 pub mod event_handler;
+/// Search through previously submitted messages
+pub mod history_search;
 /// Layout calculation utilities
 pub mod layout;
+/// Markdown rendering for task output
+pub mod markdown;
+/// Notifications for long-running tasks
+pub mod notifications;
+/// Bounded task output spilled to disk
+pub mod output_buffer;
 /// Task persistence
 pub mod persistence;
+/// SVG screenshots of the rendered TUI
+pub mod screenshot;
 /// Scrolling utilities
 pub mod scroll;
+/// Animated indicator for running tasks
+pub mod spinner;
+/// Session statistics of finished tasks
+pub mod task_stats;
+/// Terminal restore on exit, errors and panics
+pub mod terminal_guard;
+/// Thread list filtering
+pub mod thread_filter;
 
 // Re-exports
 pub use app::TuiApp;
@@ -0,0 +1,342 @@
+//! Notifications for long-running tasks
+//!
+//! When a task that ran longer than the configured threshold completes or fails,
+//! the terminal bell rings, a desktop notification is requested through an OSC 9
+//! or OSC 777 escape sequence, and an optional hook command runs. Notifications
+//! are rate limited, suppressed during quiet hours, and skipped while the
+//! terminal reports that the TUI window has focus.
+
+use chrono::{Local, NaiveTime};
+use merlin_tooling::ShellFlavor;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write as _, stdout};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+use super::task_manager::TaskStatus;
+
+/// Title shown on desktop notifications
+const NOTIFICATION_TITLE: &str = "Merlin";
+
+/// Longest task description included in a notification
+const MAX_DESCRIPTION_CHARS: usize = 100;
+
+/// Format of quiet hour boundaries in the config (e.g. `22:30`)
+const QUIET_HOURS_FORMAT: &str = "%H:%M";
+
+/// Escape sequence used for desktop notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DesktopProtocol {
+    /// `OSC 9` (iTerm2, `WezTerm`, Windows Terminal, Ghostty)
+    Osc9,
+    /// `OSC 777` (urxvt, foot, `WezTerm`, Ghostty)
+    Osc777,
+}
+
+/// Daily window during which notifications are suppressed
+///
+/// Boundaries are local `HH:MM` times; the window may wrap past midnight.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// Start of the quiet window (inclusive)
+    pub start: String,
+    /// End of the quiet window (exclusive)
+    pub end: String,
+}
+
+impl QuietHours {
+    /// Returns true if `time` falls inside the quiet window
+    ///
+    /// Invalid boundaries disable the window.
+    fn contains(&self, time: NaiveTime) -> bool {
+        let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(&self.start, QUIET_HOURS_FORMAT),
+            NaiveTime::parse_from_str(&self.end, QUIET_HOURS_FORMAT),
+        ) else {
+            return false;
+        };
+
+        if start <= end {
+            start <= time && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+/// Notification settings (`[notifications]` in `~/.merlin/config.toml`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// Whether completion notifications are sent at all
+    pub enabled: bool,
+    /// Minimum task duration in seconds before a notification is sent
+    pub min_task_secs: u64,
+    /// Ring the terminal bell
+    pub bell: bool,
+    /// Desktop notification escape sequence (`None` disables desktop notifications)
+    pub desktop: Option<DesktopProtocol>,
+    /// Shell command run for each notification
+    ///
+    /// Receives `MERLIN_TASK_DESCRIPTION`, `MERLIN_TASK_STATUS` and
+    /// `MERLIN_TASK_DURATION_SECS` as environment variables.
+    pub hook: Option<String>,
+    /// Minimum number of seconds between two notifications
+    pub min_interval_secs: u64,
+    /// Daily window during which no notifications are sent
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_task_secs: 30,
+            bell: true,
+            desktop: Some(DesktopProtocol::Osc9),
+            hook: None,
+            min_interval_secs: 10,
+            quiet_hours: None,
+        }
+    }
+}
+
+/// Sends notifications when long-running tasks finish
+pub struct Notifier {
+    /// Notification settings
+    config: NotificationConfig,
+    /// When the last notification was sent
+    last_sent: Option<Instant>,
+    /// Whether the terminal window has focus (`None` until the terminal reports it)
+    terminal_focused: Option<bool>,
+}
+
+impl Notifier {
+    /// Creates a notifier with the given settings
+    pub const fn new(config: NotificationConfig) -> Self {
+        Self {
+            config,
+            last_sent: None,
+            terminal_focused: None,
+        }
+    }
+
+    /// Records a focus change reported by the terminal
+    pub const fn set_terminal_focused(&mut self, focused: bool) {
+        self.terminal_focused = Some(focused);
+    }
+
+    /// Notifies that a task finished with `status` after running for `elapsed`
+    ///
+    /// Does nothing for short tasks, while the terminal has focus, during quiet
+    /// hours, or if a notification was sent too recently.
+    pub fn task_finished(&mut self, description: &str, status: TaskStatus, elapsed: Duration) {
+        let now = Instant::now();
+        if !self.should_notify(elapsed, now, Local::now().time()) {
+            return;
+        }
+        self.last_sent = Some(now);
+
+        let message = notification_message(description, status);
+        let sequences = self.escape_sequences(&message);
+        if !sequences.is_empty()
+            && let Err(err) = write_to_terminal(&sequences)
+        {
+            tracing::warn!("Failed to write notification: {err}");
+        }
+
+        if let Some(ref hook) = self.config.hook
+            && let Err(err) = run_hook(hook, description, status, elapsed)
+        {
+            tracing::warn!("Failed to run notification hook '{hook}': {err}");
+        }
+    }
+
+    /// Returns true if a task that ran for `elapsed` should trigger a notification
+    fn should_notify(&self, elapsed: Duration, now: Instant, local_time: NaiveTime) -> bool {
+        let min_interval = Duration::from_secs(self.config.min_interval_secs);
+
+        self.config.enabled
+            && elapsed >= Duration::from_secs(self.config.min_task_secs)
+            && self.terminal_focused != Some(true)
+            && self
+                .last_sent
+                .is_none_or(|sent| now.duration_since(sent) >= min_interval)
+            && !self
+                .config
+                .quiet_hours
+                .as_ref()
+                .is_some_and(|quiet| quiet.contains(local_time))
+    }
+
+    /// Builds the bell and desktop notification escape sequences for `message`
+    fn escape_sequences(&self, message: &str) -> String {
+        let bell = if self.config.bell { "\x07" } else { "" };
+        let desktop = match self.config.desktop {
+            Some(DesktopProtocol::Osc9) => format!("\x1b]9;{message}\x07"),
+            Some(DesktopProtocol::Osc777) => {
+                format!("\x1b]777;notify;{NOTIFICATION_TITLE};{message}\x07")
+            }
+            None => String::new(),
+        };
+        format!("{bell}{desktop}")
+    }
+}
+
+/// Returns the label used for a finished task's status
+const fn status_label(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Completed => "completed",
+        TaskStatus::Failed => "failed",
+        TaskStatus::Running => "running",
+    }
+}
+
+/// Builds the notification text, stripping control characters from the description
+fn notification_message(description: &str, status: TaskStatus) -> String {
+    let mut description: String = description
+        .chars()
+        .filter(|character| !character.is_control())
+        .collect();
+    if description.chars().count() > MAX_DESCRIPTION_CHARS {
+        description = description.chars().take(MAX_DESCRIPTION_CHARS).collect();
+        description.push('…');
+    }
+    format!("Task {}: {description}", status_label(status))
+}
+
+/// Writes escape sequences directly to the terminal
+///
+/// # Errors
+/// Returns an error if writing to stdout fails
+fn write_to_terminal(sequences: &str) -> io::Result<()> {
+    let mut out = stdout();
+    out.write_all(sequences.as_bytes())?;
+    out.flush()
+}
+
+/// Starts the user's notification hook without waiting for it
+///
+/// # Errors
+/// Returns an error if the hook process cannot be spawned
+fn run_hook(
+    hook: &str,
+    description: &str,
+    status: TaskStatus,
+    elapsed: Duration,
+) -> io::Result<()> {
+    Command::from(ShellFlavor::from_env().command(hook))
+        .env("MERLIN_TASK_DESCRIPTION", description)
+        .env("MERLIN_TASK_STATUS", status_label(status))
+        .env("MERLIN_TASK_DURATION_SECS", elapsed.as_secs().to_string())
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses an `HH:MM` test time (midnight if invalid).
+    fn time(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, QUIET_HOURS_FORMAT).unwrap_or_default()
+    }
+
+    /// Tests that only tasks longer than the threshold trigger notifications.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_threshold() {
+        let notifier = Notifier::new(NotificationConfig::default());
+        let now = Instant::now();
+
+        assert!(!notifier.should_notify(Duration::from_secs(29), now, time("12:00")));
+        assert!(notifier.should_notify(Duration::from_secs(30), now, time("12:00")));
+    }
+
+    /// Tests that notifications are rate limited.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_rate_limit() {
+        let mut notifier = Notifier::new(NotificationConfig::default());
+        let sent = Instant::now();
+        notifier.last_sent = Some(sent);
+        let elapsed = Duration::from_mins(1);
+
+        assert!(!notifier.should_notify(elapsed, sent + Duration::from_secs(5), time("12:00")));
+        assert!(notifier.should_notify(elapsed, sent + Duration::from_secs(10), time("12:00")));
+    }
+
+    /// Tests that notifications are skipped only while the terminal reports focus.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_focus_suppresses_notifications() {
+        let mut notifier = Notifier::new(NotificationConfig::default());
+        let now = Instant::now();
+        let elapsed = Duration::from_mins(1);
+
+        notifier.set_terminal_focused(true);
+        assert!(!notifier.should_notify(elapsed, now, time("12:00")));
+
+        notifier.set_terminal_focused(false);
+        assert!(notifier.should_notify(elapsed, now, time("12:00")));
+    }
+
+    /// Tests quiet hours that wrap past midnight.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_quiet_hours() {
+        let notifier = Notifier::new(NotificationConfig {
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_owned(),
+                end: "07:30".to_owned(),
+            }),
+            ..NotificationConfig::default()
+        });
+        let now = Instant::now();
+        let elapsed = Duration::from_mins(1);
+
+        assert!(!notifier.should_notify(elapsed, now, time("23:15")));
+        assert!(!notifier.should_notify(elapsed, now, time("03:00")));
+        assert!(notifier.should_notify(elapsed, now, time("07:30")));
+        assert!(notifier.should_notify(elapsed, now, time("21:59")));
+    }
+
+    /// Tests the bell and desktop notification escape sequences.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_escape_sequences() {
+        let message = notification_message("Fix \x1b[31mparser", TaskStatus::Failed);
+        assert_eq!(message, "Task failed: Fix [31mparser");
+
+        let osc9 = Notifier::new(NotificationConfig::default());
+        assert_eq!(
+            osc9.escape_sequences(&message),
+            "\x07\x1b]9;Task failed: Fix [31mparser\x07"
+        );
+
+        let osc777 = Notifier::new(NotificationConfig {
+            bell: false,
+            desktop: Some(DesktopProtocol::Osc777),
+            ..NotificationConfig::default()
+        });
+        assert_eq!(
+            osc777.escape_sequences("Task completed: Add tests"),
+            "\x1b]777;notify;Merlin;Task completed: Add tests\x07"
+        );
+    }
+}
@@ -0,0 +1,413 @@
+//! Bounded task output
+//!
+//! Long-running tasks can stream far more output than is worth keeping in
+//! memory. Each task keeps the newest [`OutputLimits::memory_lines`] lines; older
+//! lines are appended to a per-task spill file next to the task file and read
+//! back when the user scrolls to the top of what is loaded. Completed steps
+//! lose their tool call details once a task exceeds its node budget, and saved
+//! tasks keep only the tail of large outputs, the rest living in the spill file.
+
+use super::task_manager::{TaskDisplay, TaskStepStatus};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead as _, BufReader, ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
+
+/// Output limits (`[output]` in `~/.merlin/config.toml`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutputLimits {
+    /// Output lines of a task kept in memory; older lines are spilled to disk
+    pub memory_lines: usize,
+    /// Steps and tool calls kept per task before completed ones are pruned
+    pub step_nodes: usize,
+    /// Output size in bytes above which a saved task keeps only the tail
+    pub persisted_bytes: usize,
+}
+
+impl Default for OutputLimits {
+    fn default() -> Self {
+        Self {
+            memory_lines: 10_000,
+            step_nodes: 2_000,
+            persisted_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl OutputLimits {
+    /// Lines appended before the output is trimmed again, and loaded back per scroll
+    ///
+    /// Trimming counts every line, so it runs once per batch rather than on
+    /// every append; the output grows to at most `memory_lines` plus one batch.
+    pub fn batch_lines(&self) -> usize {
+        (self.memory_lines / 10).max(1)
+    }
+}
+
+/// Where the lines trimmed from a task's output went
+///
+/// The spill file holds the first `file_lines` lines of the output. The
+/// in-memory output starts at line `spilled_lines`, which is before the end of
+/// the file once spilled lines have been loaded back.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputSpill {
+    /// Spill file (`None` until lines are first spilled)
+    pub file: Option<PathBuf>,
+    /// Lines preceding the in-memory output
+    pub spilled_lines: usize,
+    /// Lines written to the spill file
+    pub file_lines: usize,
+    /// Lines appended since the output was last trimmed
+    pub pending_lines: usize,
+}
+
+/// Records `appended` new output lines, trimming the output once a batch has accumulated
+///
+/// Trimmed lines are spilled to `spill_file`, or dropped without one.
+///
+/// # Errors
+/// Returns an error if the spill file cannot be written
+pub fn record_appended(
+    task: &mut TaskDisplay,
+    appended: usize,
+    limits: &OutputLimits,
+    spill_file: Option<&Path>,
+) -> io::Result<()> {
+    task.spill.pending_lines += appended;
+    if task.spill.pending_lines < limits.batch_lines() {
+        return Ok(());
+    }
+    task.spill.pending_lines = 0;
+    trim_output(task, limits.memory_lines, spill_file)?;
+    if task.output_lines.len() > limits.memory_lines {
+        let excess = task.output_lines.len() - limits.memory_lines;
+        task.output_lines.drain(..excess);
+    }
+    Ok(())
+}
+
+/// Keeps the newest `keep_lines` lines of the output, spilling the rest
+///
+/// A pinned viewport moves up with the removed lines, so the same text stays
+/// in view. Returns the number of lines removed.
+///
+/// # Errors
+/// Returns an error if the spill file cannot be written
+pub fn trim_output(
+    task: &mut TaskDisplay,
+    keep_lines: usize,
+    spill_file: Option<&Path>,
+) -> io::Result<usize> {
+    let line_count = task.output.lines().count();
+    if line_count <= keep_lines {
+        return Ok(0);
+    }
+    let excess = line_count - keep_lines;
+    let Some((split, _)) = task.output.match_indices('\n').nth(excess - 1) else {
+        return Ok(0);
+    };
+
+    // Lines loaded back from the spill file are already in it
+    let already_spilled = task
+        .spill
+        .file_lines
+        .saturating_sub(task.spill.spilled_lines)
+        .min(excess);
+    if task.spill.file.is_none() {
+        task.spill.file = spill_file.map(Path::to_path_buf);
+    }
+    if let Some(path) = &task.spill.file
+        && already_spilled < excess
+    {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        for line in task.output[..split].lines().skip(already_spilled) {
+            writeln!(file, "{line}")?;
+        }
+    }
+
+    task.output.drain(..=split);
+    task.spill.spilled_lines += excess;
+    task.spill.file_lines = task.spill.file_lines.max(task.spill.spilled_lines);
+    task.viewport.remove_lines_above(excess);
+    // Code block indices count from the top of the output
+    task.selected_code_block = None;
+    Ok(excess)
+}
+
+/// Loads up to `count` spilled lines back in front of the output
+///
+/// Returns the number of lines loaded, zero when nothing was spilled or the
+/// spill file is gone.
+///
+/// # Errors
+/// Returns an error if the spill file cannot be read
+pub fn load_spilled(task: &mut TaskDisplay, count: usize) -> io::Result<usize> {
+    let Some(path) = &task.spill.file else {
+        return Ok(0);
+    };
+    let end = task.spill.spilled_lines;
+    let start = end.saturating_sub(count);
+    if start == end {
+        return Ok(0);
+    }
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+
+    let mut loaded = String::new();
+    let mut loaded_lines = 0;
+    for line in BufReader::new(file).lines().skip(start).take(end - start) {
+        loaded.push_str(&line?);
+        loaded.push('\n');
+        loaded_lines += 1;
+    }
+    if task.output.is_empty() {
+        loaded.pop();
+    }
+    task.output.insert_str(0, &loaded);
+    task.spill.spilled_lines = end - loaded_lines;
+    task.selected_code_block = None;
+    Ok(loaded_lines)
+}
+
+/// Spills the head of the output until it fits in `max_bytes`
+///
+/// Called before saving, so the saved file holds the tail of the output and
+/// the spill file the rest.
+///
+/// # Errors
+/// Returns an error if the spill file cannot be written
+pub fn fit_for_save(
+    task: &mut TaskDisplay,
+    max_bytes: usize,
+    spill_file: Option<&Path>,
+) -> io::Result<()> {
+    if task.output.len() <= max_bytes {
+        return Ok(());
+    }
+    let mut kept_bytes = 0;
+    let keep_lines = task
+        .output
+        .lines()
+        .rev()
+        .take_while(|line| {
+            kept_bytes += line.len() + 1;
+            kept_bytes <= max_bytes
+        })
+        .count();
+    trim_output(task, keep_lines, spill_file)?;
+    Ok(())
+}
+
+/// Drops the tool calls of completed steps, then completed steps, until the task fits `max_nodes`
+///
+/// Each step and each tool call counts as one node. Running steps are never
+/// pruned. Returns the number of nodes removed.
+pub fn prune_steps(task: &mut TaskDisplay, max_nodes: usize) -> usize {
+    let node_count = |display: &TaskDisplay| {
+        display
+            .steps
+            .iter()
+            .map(|step| 1 + step.tool_calls.len())
+            .sum::<usize>()
+    };
+    let before = node_count(task);
+    let mut excess = before.saturating_sub(max_nodes);
+    if excess == 0 {
+        return 0;
+    }
+
+    for step in &mut task.steps {
+        if excess == 0 {
+            break;
+        }
+        if step.status != TaskStepStatus::Running && !step.tool_calls.is_empty() {
+            excess = excess.saturating_sub(step.tool_calls.len());
+            step.tool_calls.clear();
+        }
+    }
+    task.steps.retain(|step| {
+        if excess == 0 || step.status == TaskStepStatus::Running {
+            return true;
+        }
+        excess -= 1;
+        false
+    });
+
+    before - node_count(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::task_manager::{TaskStepInfo, ToolCallInfo};
+    use serde_json::Value;
+    use std::fmt::Write as _;
+    use std::fs::read_to_string;
+    use std::ops::Range;
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    /// Appends the numbered lines in `range` to the task as output events would
+    ///
+    /// # Errors
+    /// Returns an error if the spill file cannot be written
+    fn stream_lines(
+        task: &mut TaskDisplay,
+        range: Range<usize>,
+        limits: &OutputLimits,
+        spill_file: &Path,
+    ) -> io::Result<()> {
+        for number in range {
+            if !task.output.is_empty() {
+                task.output.push('\n');
+            }
+            _ = write!(task.output, "line {number}");
+            task.output_lines.push(format!("line {number}"));
+            task.viewport.record_new_lines(1);
+            record_appended(task, 1, limits, Some(spill_file))?;
+        }
+        Ok(())
+    }
+
+    /// Tests that massive output stays bounded and spilled lines can be loaded back in order.
+    ///
+    /// # Errors
+    /// Returns an error if the spill file cannot be written or read.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_massive_output_is_bounded_and_reloadable() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let spill_file = dir.path().join("task.spill.log");
+        let limits = OutputLimits {
+            memory_lines: 1_000,
+            ..OutputLimits::default()
+        };
+        let mut task = TaskDisplay::default();
+        stream_lines(&mut task, 0..200_000, &limits, &spill_file)?;
+
+        let retained = task.output.lines().count();
+        assert!(retained <= limits.memory_lines + limits.batch_lines());
+        assert!(task.output_lines.len() <= limits.memory_lines + limits.batch_lines());
+        assert_eq!(task.spill.spilled_lines + retained, 200_000);
+        assert_eq!(task.output.lines().last(), Some("line 199999"));
+
+        // Scrolling far up loads the preceding lines, which are not spilled again
+        let first_retained = task.spill.spilled_lines;
+        assert_eq!(load_spilled(&mut task, 500)?, 500);
+        assert_eq!(
+            task.output.lines().next(),
+            Some(format!("line {}", first_retained - 500).as_str())
+        );
+        stream_lines(&mut task, 200_000..201_000, &limits, &spill_file)?;
+
+        let spilled = read_to_string(&spill_file)?;
+        assert_eq!(spilled.lines().count(), task.spill.file_lines);
+        assert!(
+            spilled
+                .lines()
+                .enumerate()
+                .all(|(index, line)| line == format!("line {index}"))
+        );
+        Ok(())
+    }
+
+    /// Tests that a pinned viewport keeps showing the same line while output is trimmed.
+    ///
+    /// # Errors
+    /// Returns an error if the spill file cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_trim_keeps_pinned_viewport_on_same_line() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let spill_file = dir.path().join("task.spill.log");
+        let mut task = TaskDisplay {
+            output: (0..100)
+                .map(|number| format!("line {number}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            ..TaskDisplay::default()
+        };
+        task.viewport.scroll_to_line(60, 90);
+
+        assert_eq!(trim_output(&mut task, 50, Some(&spill_file))?, 50);
+        let max_scroll = 40;
+        let offset = usize::from(task.viewport.offset(max_scroll));
+        assert_eq!(task.output.lines().nth(offset), Some("line 60"));
+        Ok(())
+    }
+
+    /// Tests that saving keeps only the tail of large outputs.
+    ///
+    /// # Errors
+    /// Returns an error if the spill file cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_fit_for_save_keeps_tail() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let spill_file = dir.path().join("task.spill.log");
+        let mut task = TaskDisplay {
+            output: "0123456789\n".repeat(1_000) + "last",
+            ..TaskDisplay::default()
+        };
+
+        fit_for_save(&mut task, 1_024, Some(&spill_file))?;
+        assert!(task.output.len() <= 1_024);
+        assert!(task.output.ends_with("\nlast"));
+        assert_eq!(
+            read_to_string(&spill_file)?.lines().count(),
+            task.spill.spilled_lines
+        );
+        Ok(())
+    }
+
+    /// Tests that completed steps lose their tool calls before steps are dropped.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_prune_steps_within_node_budget() {
+        let step = |step_id: &str, status, calls: usize| TaskStepInfo {
+            step_id: step_id.to_owned(),
+            status,
+            tool_calls: (0..calls)
+                .map(|_| ToolCallInfo {
+                    tool: "bash".to_owned(),
+                    args: Value::Null,
+                    result: None,
+                    started_at: SystemTime::now(),
+                    completed_at: None,
+                })
+                .collect(),
+            ..TaskStepInfo::default()
+        };
+        let mut task = TaskDisplay {
+            steps: vec![
+                step("old", TaskStepStatus::Completed, 5),
+                step("failed", TaskStepStatus::Failed, 5),
+                step("running", TaskStepStatus::Running, 5),
+            ],
+            ..TaskDisplay::default()
+        };
+
+        // 18 nodes: clearing the first step's calls is enough
+        assert_eq!(prune_steps(&mut task, 13), 5);
+        assert!(task.steps[0].tool_calls.is_empty());
+        assert_eq!(task.steps[1].tool_calls.len(), 5);
+
+        // Running steps survive even an impossible budget
+        prune_steps(&mut task, 0);
+        assert_eq!(task.steps.len(), 1);
+        assert_eq!(task.steps[0].step_id, "running");
+        assert_eq!(task.steps[0].tool_calls.len(), 5);
+    }
+}
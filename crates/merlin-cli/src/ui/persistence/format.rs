@@ -0,0 +1,293 @@
+//! On-disk task format: serializable records, schema migrations and gzip encoding
+
+use crate::ui::output_buffer::OutputSpill;
+use crate::ui::task_manager::{
+    TaskDisplay, TaskStatus, TaskStepInfo, TaskStepStatus, ToolCallInfo,
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use merlin_core::ThreadId;
+use merlin_core::schema::{MigrationRegistry, SchemaError};
+use merlin_routing::TaskId;
+use merlin_tooling::{display_path, native_path};
+use serde::{Deserialize, Serialize};
+use serde_json::{Error as JsonError, Map, Value, to_string};
+use std::fs::File;
+use std::io::{self, Read as _, Write as _};
+use std::path::Path;
+use std::time::{Instant, SystemTime};
+
+/// Migrations of the on-disk task format (current version: 4)
+pub(super) const TASK_SCHEMA: MigrationRegistry =
+    MigrationRegistry::new(&[add_parent_and_steps, add_step_details, add_output_spill]);
+
+/// Serializable task representation for disk storage
+#[derive(Serialize, Deserialize)]
+pub(super) struct SerializableTask {
+    schema_version: u32,
+    id: TaskId,
+    description: String,
+    status: String,
+    output_text: String,
+    output_lines: Vec<String>,
+    /// Spill file holding the output before `output_text`, relative to the tasks
+    /// directory and with forward slashes
+    spill_file: Option<String>,
+    spilled_lines: usize,
+    spill_file_lines: usize,
+    created_at: SystemTime,
+    timestamp: SystemTime,
+    thread_id: Option<ThreadId>,
+    parent_id: Option<TaskId>,
+    steps: Vec<SerializableStep>,
+}
+
+/// Serializable task step
+#[derive(Serialize, Deserialize)]
+struct SerializableStep {
+    step_id: String,
+    step_type: String,
+    content: String,
+    status: String,
+    started_at: SystemTime,
+    completed_at: Option<SystemTime>,
+    tool_calls: Vec<SerializableToolCall>,
+}
+
+/// Serializable tool call, nested under the step that made it
+#[derive(Serialize, Deserialize)]
+struct SerializableToolCall {
+    tool: String,
+    args: Value,
+    result: Option<Value>,
+    started_at: SystemTime,
+    completed_at: Option<SystemTime>,
+}
+
+/// Converts a task to its serializable form, storing its spill file relative to `tasks_dir`
+pub(super) fn serialize_task(
+    task_id: TaskId,
+    task: &TaskDisplay,
+    tasks_dir: &Path,
+) -> SerializableTask {
+    let status_str = task_status_to_string(task.status);
+
+    // Convert Instant to SystemTime by calculating elapsed time from task start
+    let now_instant = Instant::now();
+    let now_system = SystemTime::now();
+    let elapsed = now_instant.duration_since(task.timestamp);
+    let timestamp = now_system - elapsed;
+
+    SerializableTask {
+        schema_version: TASK_SCHEMA.current_version(),
+        id: task_id,
+        description: task.description.clone(),
+        status: status_str.to_string(),
+        output_text: task.output.clone(),
+        output_lines: task.output_lines.clone(),
+        spill_file: task
+            .spill
+            .file
+            .as_ref()
+            .and_then(|file| file.strip_prefix(tasks_dir).ok())
+            .map(display_path),
+        spilled_lines: task.spill.spilled_lines,
+        spill_file_lines: task.spill.file_lines,
+        created_at: task.created_at,
+        timestamp,
+        thread_id: task.thread_id,
+        parent_id: task.parent_id,
+        steps: task.steps.iter().map(serialize_step).collect(),
+    }
+}
+
+/// Decompresses, upgrades and deserializes a single task file
+///
+/// # Errors
+/// Returns an error if the gzip decoding fails, the JSON is malformed or its
+/// schema version is unsupported
+pub(super) fn decode_task(
+    compressed_data: &[u8],
+    tasks_dir: &Path,
+) -> Result<(TaskId, TaskDisplay), SchemaError> {
+    // Decompress (this is CPU-bound but fast, so keep it sync)
+    let mut decoder = GzDecoder::new(compressed_data);
+    let mut json_str = String::default();
+    decoder
+        .read_to_string(&mut json_str)
+        .map_err(JsonError::io)?;
+
+    let serializable: SerializableTask = TASK_SCHEMA.decode(&json_str)?;
+    Ok(deserialize_task(serializable, tasks_dir))
+}
+
+/// Version 1 -> 2: adds the parent task and the list of executed steps
+fn add_parent_and_steps(document: &mut Map<String, Value>) {
+    document.entry("parent_id").or_insert(Value::Null);
+    document
+        .entry("steps")
+        .or_insert_with(|| Value::Array(Vec::new()));
+}
+
+/// Version 2 -> 3: adds step types, content, timestamps and tool calls
+///
+/// Older files did not record when steps ran, so they are dated to the
+/// creation of their task.
+fn add_step_details(document: &mut Map<String, Value>) {
+    let created_at = document.get("created_at").cloned().unwrap_or(Value::Null);
+    let Some(Value::Array(steps)) = document.get_mut("steps") else {
+        return;
+    };
+    for step in steps.iter_mut().filter_map(Value::as_object_mut) {
+        for key in ["step_type", "content"] {
+            step.entry(key)
+                .or_insert_with(|| Value::String(String::new()));
+        }
+        step.entry("started_at")
+            .or_insert_with(|| created_at.clone());
+        step.entry("completed_at").or_insert(Value::Null);
+        step.entry("tool_calls")
+            .or_insert_with(|| Value::Array(Vec::new()));
+    }
+}
+
+/// Version 3 -> 4: adds the spill file holding output that outgrew memory
+fn add_output_spill(document: &mut Map<String, Value>) {
+    document.entry("spill_file").or_insert(Value::Null);
+    for key in ["spilled_lines", "spill_file_lines"] {
+        document.entry(key).or_insert_with(|| Value::from(0));
+    }
+}
+
+/// Deserializes a task from its serializable form, resolving its spill file in `tasks_dir`
+fn deserialize_task(serializable: SerializableTask, tasks_dir: &Path) -> (TaskId, TaskDisplay) {
+    let status = match serializable.status.as_str() {
+        "Completed" => TaskStatus::Completed,
+        "Failed" => TaskStatus::Failed,
+        _ => TaskStatus::Running,
+    };
+
+    // Convert SystemTime to Instant by calculating offset from now
+    let now_instant = Instant::now();
+    let now_system = SystemTime::now();
+
+    let timestamp = now_system
+        .duration_since(serializable.timestamp)
+        .map_or(now_instant, |elapsed| {
+            now_instant.checked_sub(elapsed).unwrap_or(now_instant)
+        });
+
+    let task_display = TaskDisplay {
+        description: serializable.description,
+        status,
+        output_lines: serializable.output_lines,
+        spill: OutputSpill {
+            file: serializable
+                .spill_file
+                .map(|file| tasks_dir.join(native_path(&file))),
+            spilled_lines: serializable.spilled_lines,
+            file_lines: serializable.spill_file_lines,
+            pending_lines: 0,
+        },
+        created_at: serializable.created_at,
+        timestamp,
+        thread_id: serializable.thread_id,
+        parent_id: serializable.parent_id,
+        output: serializable.output_text,
+        steps: serializable
+            .steps
+            .into_iter()
+            .map(deserialize_step)
+            .collect(),
+        ..Default::default()
+    };
+
+    (serializable.id, task_display)
+}
+
+/// Converts a step and its tool calls to their serializable form
+fn serialize_step(step: &TaskStepInfo) -> SerializableStep {
+    SerializableStep {
+        step_id: step.step_id.clone(),
+        step_type: step.step_type.clone(),
+        content: step.content.clone(),
+        status: step_status_to_string(step.status).to_owned(),
+        started_at: step.started_at,
+        completed_at: step.completed_at,
+        tool_calls: step
+            .tool_calls
+            .iter()
+            .map(|call| SerializableToolCall {
+                tool: call.tool.clone(),
+                args: call.args.clone(),
+                result: call.result.clone(),
+                started_at: call.started_at,
+                completed_at: call.completed_at,
+            })
+            .collect(),
+    }
+}
+
+/// Rebuilds a step and its tool calls from their serializable form
+fn deserialize_step(step: SerializableStep) -> TaskStepInfo {
+    TaskStepInfo {
+        step_id: step.step_id,
+        step_type: step.step_type,
+        content: step.content,
+        status: match step.status.as_str() {
+            "Completed" => TaskStepStatus::Completed,
+            "Failed" => TaskStepStatus::Failed,
+            _ => TaskStepStatus::Running,
+        },
+        started_at: step.started_at,
+        completed_at: step.completed_at,
+        tool_calls: step
+            .tool_calls
+            .into_iter()
+            .map(|call| ToolCallInfo {
+                tool: call.tool,
+                args: call.args,
+                result: call.result,
+                started_at: call.started_at,
+                completed_at: call.completed_at,
+            })
+            .collect(),
+    }
+}
+
+/// Writes a compressed task to disk
+///
+/// # Errors
+///
+/// Returns an error if the JSON serialization fails or the file cannot be written
+pub(super) fn write_compressed_task(
+    path: &Path,
+    serializable: &SerializableTask,
+) -> io::Result<()> {
+    let json =
+        to_string(serializable).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let file = File::create(path)?;
+    let mut encoder = GzEncoder::new(file, Compression::fast());
+    encoder.write_all(json.as_bytes())?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+/// Converts task status to string
+fn task_status_to_string(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Running => "Running",
+        TaskStatus::Completed => "Completed",
+        TaskStatus::Failed => "Failed",
+    }
+}
+
+/// Converts step status to string
+fn step_status_to_string(status: TaskStepStatus) -> &'static str {
+    match status {
+        TaskStepStatus::Running => "Running",
+        TaskStepStatus::Completed => "Completed",
+        TaskStepStatus::Failed => "Failed",
+    }
+}
@@ -0,0 +1,166 @@
+mod format;
+
+use super::task_manager::TaskDisplay;
+use format::{decode_task, write_compressed_task};
+use merlin_core::schema::{corrupt_dir_for, quarantine};
+use merlin_routing::TaskId;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs as filesystem;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::fs as async_fs;
+
+/// Extension of the files holding output spilled out of memory
+const SPILL_EXTENSION: &str = "spill.log";
+
+/// Tasks read from disk, plus the unreadable files that were moved aside
+#[derive(Default)]
+pub struct LoadedTasks {
+    /// Successfully loaded tasks
+    pub tasks: HashMap<TaskId, TaskDisplay>,
+    /// New paths (under `.merlin/corrupt/`) of task files that could not be decoded
+    pub quarantined: Vec<PathBuf>,
+}
+
+/// Handles task persistence to disk
+pub struct TaskPersistence {
+    tasks_dir: PathBuf,
+}
+
+impl TaskPersistence {
+    /// Creates a new `TaskPersistence` instance
+    pub fn new(tasks_dir: PathBuf) -> Self {
+        Self { tasks_dir }
+    }
+
+    /// Directory task files are stored in
+    pub fn tasks_dir(&self) -> &Path {
+        &self.tasks_dir
+    }
+
+    /// File the task's output is spilled to once it outgrows memory
+    pub fn spill_path(&self, task_id: TaskId) -> PathBuf {
+        self.tasks_dir.join(format!(
+            "{}.{SPILL_EXTENSION}",
+            extract_task_id_string(task_id)
+        ))
+    }
+
+    /// Loads all tasks from disk
+    ///
+    /// Files in older formats are upgraded through `TASK_SCHEMA`. Files that
+    /// cannot be decoded are moved to `.merlin/corrupt/`; files written by a
+    /// newer version are skipped and left in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task directory cannot be read
+    pub async fn load_all_tasks(&self) -> io::Result<LoadedTasks> {
+        let mut loaded = LoadedTasks::default();
+
+        // Check if directory exists using tokio async fs
+        if !async_fs::try_exists(&self.tasks_dir).await.unwrap_or(false) {
+            return Ok(loaded);
+        }
+
+        let mut entries = async_fs::read_dir(&self.tasks_dir).await?;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            if !is_compressed_task_file(&path) {
+                continue;
+            }
+
+            let compressed_data = match async_fs::read(&path).await {
+                Ok(data) => data,
+                Err(error) => {
+                    tracing::warn!("Failed to read task file {:?}: {}", path, error);
+                    continue;
+                }
+            };
+
+            match decode_task(&compressed_data, &self.tasks_dir) {
+                Ok((task_id, task_display)) => {
+                    loaded.tasks.insert(task_id, task_display);
+                }
+                Err(error) if error.is_corrupt() => {
+                    match quarantine(&path, &corrupt_dir_for(&self.tasks_dir)) {
+                        Ok(moved) => {
+                            tracing::warn!(
+                                "Moved unreadable task file {:?} to {:?}: {}",
+                                path,
+                                moved,
+                                error
+                            );
+                            loaded.quarantined.push(moved);
+                        }
+                        Err(move_error) => tracing::warn!(
+                            "Failed to quarantine unreadable task file {:?} ({}): {}",
+                            path,
+                            error,
+                            move_error
+                        ),
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!("Skipping task file {:?}: {}", path, error);
+                }
+            }
+        }
+
+        Ok(loaded)
+    }
+
+    /// Saves a task to disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task directory cannot be created or the task file cannot be written
+    pub fn save_task(&self, task_id: TaskId, task: &TaskDisplay) -> io::Result<()> {
+        // Ensure the tasks directory exists (only if not already present)
+        if !self.tasks_dir.exists() {
+            filesystem::create_dir_all(&self.tasks_dir)?;
+        }
+
+        let serializable = format::serialize_task(task_id, task, &self.tasks_dir);
+        let filename = format!("{}.json.gz", extract_task_id_string(task_id));
+        let path = self.tasks_dir.join(filename);
+
+        write_compressed_task(&path, &serializable)
+    }
+
+    /// Deletes a task file, and its spill file if there is one, from disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task file or spill file cannot be removed
+    pub fn delete_task_file(&self, task_id: TaskId) -> io::Result<()> {
+        let filename = format!("{}.json.gz", extract_task_id_string(task_id));
+        let task_file = self.tasks_dir.join(filename);
+        filesystem::remove_file(task_file)?;
+        match filesystem::remove_file(self.spill_path(task_id)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Helper functions
+/// Checks if a path is a compressed task file
+fn is_compressed_task_file(path: &Path) -> bool {
+    path.extension().and_then(OsStr::to_str) == Some("gz")
+}
+
+/// Extracts clean task ID string from `TaskId` debug format
+fn extract_task_id_string(task_id: TaskId) -> String {
+    let task_id_str = format!("{task_id:?}");
+    let Some(stripped) = task_id_str.strip_prefix("TaskId(") else {
+        return task_id_str;
+    };
+    stripped.strip_suffix(")").unwrap_or(stripped).to_owned()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,400 @@
+//! Tests for task persistence and on-disk format migrations
+
+use super::format::{SerializableTask, TASK_SCHEMA};
+use super::*;
+use crate::ui::event_handler::EventHandler;
+use crate::ui::output_buffer::{OutputLimits, OutputSpill, load_spilled};
+use crate::ui::state::UiState;
+use crate::ui::task_manager::{TaskManager, TaskStatus, TaskStepStatus};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use merlin_core::schema::SchemaError;
+use merlin_routing::UiEvent;
+use merlin_tooling::ToolError;
+use serde_json::{Error as JsonError, Value, from_str, json, to_value};
+use std::fs::File;
+use std::io::{Read as _, Write as _};
+use tempfile::TempDir;
+
+/// Reads a fixture from `tests/fixtures/tasks/`
+///
+/// # Errors
+/// Returns an error if the fixture cannot be read
+fn fixture(name: &str) -> io::Result<String> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("tasks")
+        .join(name);
+    filesystem::read_to_string(path)
+}
+
+/// Writes `json` gzip-compressed to `path`
+///
+/// # Errors
+/// Returns an error if the file cannot be written
+fn write_gz(path: &Path, json: &str) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(File::create(path)?, Compression::fast());
+    encoder.write_all(json.as_bytes())?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// Reads a gzip-compressed JSON file
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is not JSON
+fn read_gz(path: &Path) -> io::Result<Value> {
+    let mut json = String::new();
+    GzDecoder::new(File::open(path)?).read_to_string(&mut json)?;
+    from_str(&json).map_err(io::Error::other)
+}
+
+/// Creates `.merlin/tasks/` in `root` holding the given compressed files
+///
+/// # Errors
+/// Returns an error if the files cannot be written
+fn tasks_dir(root: &Path, files: &[(&str, &str)]) -> io::Result<PathBuf> {
+    let dir = root.join(".merlin").join("tasks");
+    filesystem::create_dir_all(&dir)?;
+    for (name, json) in files {
+        write_gz(&dir.join(name), json)?;
+    }
+    Ok(dir)
+}
+
+/// Tests that the current format deserializes and serializes unchanged
+///
+/// # Errors
+/// Returns an error if a fixture cannot be read, written or decoded
+///
+/// # Panics
+/// Panics if assertions fail
+#[test]
+fn test_current_format_serializes_unchanged() -> Result<(), SchemaError> {
+    let original: Value = from_str(&fixture("task_v4.json").map_err(JsonError::io)?)?;
+    let task: SerializableTask = TASK_SCHEMA.decode(&original.to_string())?;
+    assert_eq!(to_value(&task)?, original);
+    Ok(())
+}
+
+/// Tests that a current-version task file is saved back unchanged
+///
+/// # Errors
+/// Returns an error if a fixture cannot be read, written or decoded
+///
+/// # Panics
+/// Panics if assertions fail
+#[tokio::test]
+async fn test_current_format_round_trips() -> io::Result<()> {
+    let temp = TempDir::new()?;
+    let original = fixture("task_v4.json")?;
+    let dir = tasks_dir(temp.path(), &[("task.json.gz", &original)])?;
+    let persistence = TaskPersistence::new(dir.clone());
+
+    let loaded = persistence.load_all_tasks().await?;
+    assert!(loaded.quarantined.is_empty());
+    let [(task_id, task)] = loaded.tasks.iter().collect::<Vec<_>>()[..] else {
+        return Err(io::Error::other("expected one task"));
+    };
+    assert!(task.parent_id.is_none());
+    assert_eq!(task.status, TaskStatus::Completed);
+    let tools: Vec<Vec<&str>> = task
+        .steps
+        .iter()
+        .map(|step| {
+            step.tool_calls
+                .iter()
+                .map(|call| call.tool.as_str())
+                .collect()
+        })
+        .collect();
+    assert_eq!(tools, [vec!["search", "read"], vec![]]);
+    assert_eq!(task.spill.spilled_lines, 120);
+    assert_eq!(
+        task.spill.file,
+        Some(dir.join("d41e9c27-6a58-4b3f-8e02-7c9b1a4f5e63.spill.log"))
+    );
+
+    persistence.save_task(*task_id, task)?;
+    let path = dir.join(format!("{}.json.gz", extract_task_id_string(*task_id)));
+    let mut saved = read_gz(&path)?;
+    let mut expected: Value = from_str(&original)?;
+    // `timestamp` goes through a monotonic `Instant` in memory, so it may drift
+    for document in [&mut saved, &mut expected] {
+        if let Some(object) = document.as_object_mut() {
+            object.remove("timestamp");
+        }
+    }
+    assert_eq!(saved, expected);
+    Ok(())
+}
+
+/// Tests that an unversioned task file is upgraded on load and save
+///
+/// # Errors
+/// Returns an error if a fixture cannot be read, written or decoded
+///
+/// # Panics
+/// Panics if assertions fail
+#[tokio::test]
+async fn test_unversioned_format_migrates() -> io::Result<()> {
+    let temp = TempDir::new()?;
+    let dir = tasks_dir(temp.path(), &[("task.json.gz", &fixture("task_v1.json")?)])?;
+    let persistence = TaskPersistence::new(dir.clone());
+
+    let loaded = persistence.load_all_tasks().await?;
+    let [(task_id, task)] = loaded.tasks.iter().collect::<Vec<_>>()[..] else {
+        return Err(io::Error::other("expected one task"));
+    };
+    assert_eq!(task.description, "Explain the config loader");
+    assert!(task.thread_id.is_some());
+    assert!(task.parent_id.is_none());
+    assert!(task.steps.is_empty());
+
+    persistence.save_task(*task_id, task)?;
+    let saved = read_gz(&dir.join(format!("{}.json.gz", extract_task_id_string(*task_id))))?;
+    assert_eq!(saved["schema_version"], 4);
+    assert_eq!(saved["parent_id"], Value::Null);
+    assert_eq!(saved["steps"], Value::Array(Vec::new()));
+    assert_eq!(saved["spill_file"], Value::Null);
+    assert_eq!(saved["spilled_lines"], 0);
+    Ok(())
+}
+
+/// Tests that steps saved before step details were recorded gain defaults
+///
+/// # Errors
+/// Returns an error if a fixture cannot be read, written or decoded
+///
+/// # Panics
+/// Panics if assertions fail
+#[tokio::test]
+async fn test_steps_without_details_migrate() -> io::Result<()> {
+    let temp = TempDir::new()?;
+    let dir = tasks_dir(temp.path(), &[("task.json.gz", &fixture("task_v2.json")?)])?;
+
+    let loaded = TaskPersistence::new(dir).load_all_tasks().await?;
+    let [task] = loaded.tasks.values().collect::<Vec<_>>()[..] else {
+        return Err(io::Error::other("expected one task"));
+    };
+    let step_statuses: Vec<_> = task.steps.iter().map(|step| step.status).collect();
+    assert_eq!(
+        step_statuses,
+        [TaskStepStatus::Completed, TaskStepStatus::Failed]
+    );
+    for step in &task.steps {
+        assert!(step.step_type.is_empty());
+        assert_eq!(step.started_at, task.created_at);
+        assert!(step.completed_at.is_none());
+        assert!(step.tool_calls.is_empty());
+    }
+    Ok(())
+}
+
+/// Tests that steps with nested tool calls survive a save and load unchanged
+///
+/// # Errors
+/// Returns an error if the task cannot be saved or loaded
+///
+/// # Panics
+/// Panics if assertions fail
+#[tokio::test]
+async fn test_step_tree_round_trips() -> io::Result<()> {
+    let task_id = TaskId::default();
+    let mut task_manager = TaskManager::default();
+    let mut state = UiState::default();
+    let mut handler = EventHandler::new(&mut task_manager, &mut state, None);
+    let events = [
+        UiEvent::TaskStarted {
+            task_id,
+            description: "Rename the config loader".to_owned(),
+            parent_id: None,
+            thread_id: None,
+        },
+        UiEvent::TaskStepStarted {
+            task_id,
+            step_id: "find-usages".to_owned(),
+            step_type: "tool_call".to_owned(),
+            content: "Searching for callers".to_owned(),
+        },
+        UiEvent::ToolCallStarted {
+            task_id,
+            tool: "search".to_owned(),
+            args: json!({ "pattern": "load(" }),
+        },
+        UiEvent::ToolCallStarted {
+            task_id,
+            tool: "read".to_owned(),
+            args: json!({ "path": "src/config.rs" }),
+        },
+        UiEvent::ToolCallCompleted {
+            task_id,
+            tool: "search".to_owned(),
+            result: json!({ "matches": 2 }),
+        },
+        UiEvent::TaskOutput {
+            task_id,
+            output: "Found 2 callers".to_owned(),
+        },
+        UiEvent::TaskStepCompleted {
+            task_id,
+            step_id: "find-usages".to_owned(),
+        },
+        UiEvent::TaskStepStarted {
+            task_id,
+            step_id: "apply-edits".to_owned(),
+            step_type: "thinking".to_owned(),
+            content: String::new(),
+        },
+    ];
+    for event in events {
+        handler.handle_event(event);
+    }
+    let Some(task) = task_manager.get_task(task_id) else {
+        return Err(io::Error::other("task was not started"));
+    };
+    let calls: Vec<(&str, bool)> = task
+        .steps
+        .iter()
+        .flat_map(|step| &step.tool_calls)
+        .map(|call| (call.tool.as_str(), call.result.is_some()))
+        .collect();
+    assert_eq!(calls, [("search", true), ("read", false)]);
+
+    let temp = TempDir::new()?;
+    let persistence = TaskPersistence::new(temp.path().join("tasks"));
+    persistence.save_task(task_id, task)?;
+    let loaded = persistence.load_all_tasks().await?;
+    let Some(restored) = loaded.tasks.get(&task_id) else {
+        return Err(io::Error::other("task was not restored"));
+    };
+
+    assert_eq!(restored.steps, task.steps);
+    assert_eq!(restored.output, task.output);
+    assert_eq!(restored.output_lines, task.output_lines);
+    Ok(())
+}
+
+/// Tests that a task with massive output saves only its tail and reloads the rest on demand
+///
+/// # Errors
+/// Returns an error if the task cannot be saved, loaded or its spill file read
+///
+/// # Panics
+/// Panics if assertions fail
+#[tokio::test]
+async fn test_massive_output_saves_tail_and_spill_pointer() -> io::Result<()> {
+    let temp = TempDir::new()?;
+    let persistence = TaskPersistence::new(temp.path().join("tasks"));
+    filesystem::create_dir_all(persistence.tasks_dir())?;
+    let task_id = TaskId::default();
+    let mut task_manager = TaskManager::default();
+    task_manager.set_output_limits(OutputLimits {
+        memory_lines: 2_000,
+        step_nodes: 100,
+        persisted_bytes: 16 * 1024,
+    });
+    let mut state = UiState::default();
+    let mut handler = EventHandler::new(&mut task_manager, &mut state, Some(&persistence));
+    handler.handle_event(UiEvent::TaskStarted {
+        task_id,
+        description: "Run the full test suite".to_owned(),
+        parent_id: None,
+        thread_id: None,
+    });
+    for number in 0..100_000 {
+        handler.handle_event(UiEvent::TaskOutput {
+            task_id,
+            output: format!("test case {number} ... ok"),
+        });
+    }
+    handler.handle_event(UiEvent::TaskFailed {
+        task_id,
+        error: ToolError::ExecutionFailed("3 tests failed".to_owned()),
+    });
+
+    let saved = read_gz(
+        &persistence
+            .tasks_dir()
+            .join(format!("{}.json.gz", extract_task_id_string(task_id))),
+    )?;
+    assert!(
+        saved["output_text"]
+            .as_str()
+            .is_some_and(|text| text.len() <= 16 * 1024)
+    );
+    assert!(saved["spill_file"].is_string());
+
+    let mut loaded = persistence.load_all_tasks().await?;
+    let Some(restored) = loaded.tasks.get_mut(&task_id) else {
+        return Err(io::Error::other("task was not restored"));
+    };
+    assert!(restored.output.ends_with("3 tests failed"));
+    let first_line = restored.spill.spilled_lines;
+    assert_eq!(load_spilled(restored, 10)?, 10);
+    assert_eq!(
+        restored.output.lines().next(),
+        Some(format!("test case {} ... ok", first_line - 10).as_str())
+    );
+    Ok(())
+}
+
+/// Tests that tasks saved before output spilling load with nothing spilled
+///
+/// # Errors
+/// Returns an error if a fixture cannot be read, written or decoded
+///
+/// # Panics
+/// Panics if assertions fail
+#[tokio::test]
+async fn test_format_without_spill_migrates() -> io::Result<()> {
+    let temp = TempDir::new()?;
+    let dir = tasks_dir(temp.path(), &[("task.json.gz", &fixture("task_v3.json")?)])?;
+
+    let loaded = TaskPersistence::new(dir).load_all_tasks().await?;
+    let [task] = loaded.tasks.values().collect::<Vec<_>>()[..] else {
+        return Err(io::Error::other("expected one task"));
+    };
+    assert_eq!(task.spill, OutputSpill::default());
+    Ok(())
+}
+
+/// Tests that unreadable task files are quarantined and newer ones left alone
+///
+/// # Errors
+/// Returns an error if a fixture cannot be read, written or decoded
+///
+/// # Panics
+/// Panics if assertions fail
+#[tokio::test]
+async fn test_unreadable_files_are_quarantined() -> io::Result<()> {
+    let temp = TempDir::new()?;
+    let newer = r#"{"schema_version": 99}"#;
+    let dir = tasks_dir(
+        temp.path(),
+        &[
+            ("good.json.gz", &fixture("task_v2.json")?),
+            ("truncated.json.gz", r#"{"id": "8b2e"#),
+            ("newer.json.gz", newer),
+        ],
+    )?;
+    filesystem::write(dir.join("plain.json.gz"), "not gzip")?;
+
+    let loaded = TaskPersistence::new(dir.clone()).load_all_tasks().await?;
+
+    let corrupt = temp.path().join(".merlin").join("corrupt");
+    let mut quarantined = loaded.quarantined;
+    quarantined.sort();
+    assert_eq!(
+        quarantined,
+        [
+            corrupt.join("plain.json.gz"),
+            corrupt.join("truncated.json.gz")
+        ]
+    );
+    assert!(!dir.join("truncated.json.gz").exists());
+    assert!(!dir.join("plain.json.gz").exists());
+    assert!(dir.join("newer.json.gz").exists());
+    assert_eq!(loaded.tasks.len(), 1);
+    Ok(())
+}
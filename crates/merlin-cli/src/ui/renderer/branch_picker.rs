@@ -0,0 +1,96 @@
+//! Branch point picker
+//!
+//! Replaces the output pane while a thread is being branched: the messages of
+//! the thread's conversation, numbered, with the one to branch at marked.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph},
+};
+
+use super::{RenderCtx, truncate_text};
+use crate::ui::state::BranchPicker;
+use crate::ui::theme::Theme;
+
+/// Renders the branch point picker in place of the output pane
+pub(super) fn render_branch_picker(
+    theme: &Theme,
+    frame: &mut Frame,
+    area: Rect,
+    picker: BranchPicker,
+    ctx: &RenderCtx<'_>,
+) {
+    let store = ctx.thread_store.lock().ok();
+    let messages: Vec<String> = store
+        .as_ref()
+        .and_then(|store| store.conversation(picker.thread_id).ok())
+        .map(|messages| {
+            messages
+                .into_iter()
+                .map(|message| message.content.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    drop(store);
+
+    let line_width = usize::from(area.width.saturating_sub(4));
+    let mut lines = vec![Line::from(Span::styled(
+        "The branch keeps every message up to the selected one",
+        Style::default()
+            .fg(theme.text())
+            .add_modifier(Modifier::DIM),
+    ))];
+
+    // Scroll the list so the selection stays visible below the hint line
+    let visible_rows = usize::from(area.height.saturating_sub(3)).max(1);
+    let first_row = picker.selected.saturating_sub(visible_rows - 1);
+    for (index, content) in messages
+        .iter()
+        .enumerate()
+        .skip(first_row)
+        .take(visible_rows)
+    {
+        // Multi-line messages are shown by their first line
+        let text = format!(
+            "{}. {}",
+            index + 1,
+            content.trim().lines().next().unwrap_or_default()
+        );
+        let line = if index == picker.selected {
+            Line::from(vec![
+                Span::styled(
+                    "> ",
+                    Style::default()
+                        .fg(theme.highlight())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    truncate_text(&text, line_width.saturating_sub(2)),
+                    Style::default()
+                        .fg(theme.text())
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ])
+        } else {
+            Line::from(format!(
+                "  {}",
+                truncate_text(&text, line_width.saturating_sub(2))
+            ))
+        };
+        lines.push(line);
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("─── Branch from message ")
+                .border_style(Style::default().fg(theme.focused_border()))
+                .padding(Padding::horizontal(1)),
+        );
+    frame.render_widget(paragraph, area);
+}
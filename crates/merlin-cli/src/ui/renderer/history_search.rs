@@ -0,0 +1,91 @@
+//! `Ctrl+R` history search overlay
+//!
+//! Replaces the input area while the search is open: the query on the first
+//! line, then the matching past messages with the selected one marked.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Padding, Paragraph},
+};
+
+use super::{RenderCtx, truncate_text};
+use crate::ui::history_search::{HistorySearch, history_entries, matching_entries};
+use crate::ui::theme::Theme;
+
+/// Renders the history search in place of the input area
+pub(super) fn render_history_search(
+    theme: &Theme,
+    frame: &mut Frame,
+    area: Rect,
+    search: &HistorySearch,
+    ctx: &RenderCtx<'_>,
+) {
+    let store = ctx.thread_store.lock().ok();
+    let entries = history_entries(ctx.ui_ctx.state, store.as_deref());
+    drop(store);
+    let matches = matching_entries(&entries, &search.query);
+
+    let line_width = usize::from(area.width.saturating_sub(4));
+    let mut lines = vec![Line::from(Span::styled(
+        format!("Search: {}_", search.query),
+        Style::default().fg(theme.focused_border()),
+    ))];
+
+    if matches.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No matching messages",
+            Style::default()
+                .fg(theme.text())
+                .add_modifier(Modifier::DIM),
+        )));
+    }
+
+    // Scroll the list so the selection stays visible below the query line
+    let visible_rows = usize::from(area.height.saturating_sub(3)).max(1);
+    let first_row = search.selected.saturating_sub(visible_rows - 1);
+    for (index, entry) in matches
+        .iter()
+        .enumerate()
+        .skip(first_row)
+        .take(visible_rows)
+    {
+        // Multi-line messages are shown by their first line
+        let text = entry.lines().next().unwrap_or_default();
+        let line = if index == search.selected {
+            Line::from(vec![
+                Span::styled(
+                    "> ",
+                    Style::default()
+                        .fg(theme.highlight())
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    truncate_text(text, line_width.saturating_sub(2)),
+                    Style::default()
+                        .fg(theme.text())
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ])
+        } else {
+            Line::from(format!(
+                "  {}",
+                truncate_text(text, line_width.saturating_sub(2))
+            ))
+        };
+        lines.push(line);
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("─── Input · History search ")
+                .border_style(Style::default().fg(theme.focused_border()))
+                .padding(Padding::horizontal(1)),
+        );
+    frame.render_widget(paragraph, area);
+}
@@ -1,29 +1,37 @@
 //! UI rendering module
 //!
-//! Handles rendering of the thread-based UI layout.
+//! Handles rendering of the thread-based UI layout. Each pane lives in a
+//! submodule as free functions taking the [`Theme`], so `Renderer` keeps the
+//! single `impl` block the `multiple_inherent_impl` lint allows.
+
+mod branch_picker;
+mod history_search;
+mod output_pane;
+mod status_bar;
+mod task_stats;
+mod thread_list;
 
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
-    widgets::{Block, Borders, Padding, Paragraph, Wrap},
+    widgets::{Block, Borders, Padding},
 };
 
 use std::sync::{Arc, Mutex};
 
 use merlin_agent::ThreadStore;
-use merlin_core::{Thread, ThreadId};
-use ratatui::text::Line;
 
 use super::input::InputManager;
 use super::layout;
-use super::scroll;
-use super::state::UiState;
+use super::state::{InfoPanel, UiState};
 use super::task_manager::{TaskDisplay, TaskManager};
 use super::theme::Theme;
 
 // Layout constants
 const MIN_REMAINING_HEIGHT: u16 = 10;
+/// Height of the session status bar below the main layout
+const STATUS_BAR_HEIGHT: u16 = 1;
 
 /// Handles rendering of the TUI
 pub struct Renderer {
@@ -68,16 +76,39 @@ impl Renderer {
 
     /// Renders the entire UI
     pub fn render(&self, frame: &mut Frame, ctx: &RenderCtx<'_>) {
-        let main_area = frame.area();
+        let vertical_split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(STATUS_BAR_HEIGHT)])
+            .split(frame.area());
 
         // Always use thread mode (side-by-side layout with threads | work + input)
-        self.render_thread_mode(frame, main_area, ctx);
+        self.render_thread_mode(frame, vertical_split[0], ctx);
+
+        let thread_name = ctx.ui_ctx.state.active_thread_id.and_then(|thread_id| {
+            let store = ctx.thread_store.lock().ok()?;
+            let thread = store.get_thread(thread_id)?;
+            Some(thread.working_dir_name().map_or_else(
+                || thread.name.clone(),
+                |repo| format!("{} @{repo}", thread.name),
+            ))
+        });
+        status_bar::render_status_bar(
+            &self.theme,
+            frame,
+            vertical_split[1],
+            ctx.ui_ctx.state,
+            thread_name.as_deref(),
+        );
     }
 
     /// Renders the thread-based side-by-side layout
     fn render_thread_mode(&self, frame: &mut Frame, main_area: Rect, ctx: &RenderCtx<'_>) {
+        let history_search = ctx.ui_ctx.state.history_search.as_ref();
         let input_content_lines = ctx.input.input_area().lines().len() as u16;
-        let input_height = layout::calculate_input_area_height(input_content_lines);
+        let mut input_height = layout::calculate_input_area_height(input_content_lines);
+        if history_search.is_some() {
+            input_height = input_height.max(layout::HISTORY_SEARCH_HEIGHT);
+        }
 
         // Split horizontally: threads (30%) | work details (70%)
         let horizontal_split = Layout::default()
@@ -95,70 +126,46 @@ impl Renderer {
             .split(horizontal_split[1]);
 
         // Render thread list on left
-        self.render_thread_list(frame, horizontal_split[0], ctx);
-
-        // Render work details on top right
-        self.render_focused_detail_section(frame, right_side_split[0], &ctx.ui_ctx, ctx.focused);
-
-        // Render input on bottom right
-        self.render_input_area(frame, right_side_split[1], ctx.input, ctx);
-    }
-
-    // Rendering methods
-
-    /// Renders the focused task output section
-    fn render_focused_detail_section(
-        &self,
-        frame: &mut Frame,
-        area: Rect,
-        ui_ctx: &UiCtx<'_>,
-        focused: FocusedPane,
-    ) {
-        let border_color = if focused == FocusedPane::Output {
-            self.theme.focused_border()
+        thread_list::render_thread_list(&self.theme, frame, horizontal_split[0], ctx);
+
+        // Render work details on top right, replaced by the branch picker while it is open
+        if let Some(picker) = ctx.ui_ctx.state.thread_branch_picker {
+            branch_picker::render_branch_picker(
+                &self.theme,
+                frame,
+                right_side_split[0],
+                picker,
+                ctx,
+            );
         } else {
-            self.theme.unfocused_border()
-        };
-
-        // Get task output if a task is selected
-        let (text, title) = if let Some(active_task_id) = ui_ctx.state.active_task_id
-            && let Some(task) = ui_ctx.task_manager.get_task(active_task_id)
-        {
-            // Get plain text output from task
-            let text = task.output.clone();
-
-            // Build title without embedding progress (moved to input box)
-            let base_title = format!("─── Focused - {} ", task.description);
-            let title = truncate_text(&base_title, area.width.saturating_sub(2) as usize);
+            output_pane::render_focused_detail_section(
+                &self.theme,
+                frame,
+                right_side_split[0],
+                &ctx.ui_ctx,
+                ctx.focused,
+            );
+        }
+        if ctx.ui_ctx.state.info_panel == InfoPanel::TaskStats {
+            task_stats::render_task_stats(&self.theme, frame, right_side_split[0], &ctx.ui_ctx);
+        }
 
-            (text, title)
+        // Render input on bottom right, replaced by the history search while it is open
+        if let Some(search) = history_search {
+            history_search::render_history_search(
+                &self.theme,
+                frame,
+                right_side_split[1],
+                search,
+                ctx,
+            );
         } else {
-            // No task selected - show empty output pane with generic title
-            (String::new(), "─── Focused ".to_owned())
-        };
-
-        // Calculate content height and clamp scroll offset
-        // Account for borders only (2) - horizontal padding doesn't affect height
-        let viewport_height = area.height.saturating_sub(2);
-        let text_lines = scroll::count_text_lines(&text);
-        let max_scroll = text_lines.saturating_sub(viewport_height);
-        let clamped_scroll = ui_ctx.state.output_scroll_offset.min(max_scroll);
-
-        let paragraph = Paragraph::new(text)
-            .style(Style::default().fg(self.theme.text()))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(title)
-                    .border_style(Style::default().fg(border_color))
-                    .padding(Padding::horizontal(1)),
-            )
-            .wrap(Wrap { trim: false })
-            .scroll((clamped_scroll, 0));
-
-        frame.render_widget(paragraph, area);
+            self.render_input_area(frame, right_side_split[1], ctx.input, ctx);
+        }
     }
 
+    // Rendering methods
+
     fn render_input_area(
         &self,
         frame: &mut Frame,
@@ -168,26 +175,33 @@ impl Renderer {
     ) {
         let mut input_area = input_manager.input_area().clone();
 
-        let border_color = if ctx.focused == FocusedPane::Input {
-            self.theme.focused_border()
-        } else {
-            self.theme.unfocused_border()
-        };
-
         let cursor_style = if ctx.focused == FocusedPane::Input {
             Style::default().add_modifier(Modifier::REVERSED)
         } else {
             Style::default()
         };
 
-        // Build title with optional embedding progress indicator
-        let title = if let Some((current, total)) = ctx.ui_ctx.state.embedding_progress {
-            let percent = (current as f64 / total as f64 * 100.0) as u16;
-            format!("─── Input  [Indexing: {percent}%] ")
+        let mut border_color = if ctx.focused == FocusedPane::Input {
+            self.theme.focused_border()
         } else {
-            "─── Input ".to_owned()
+            self.theme.unfocused_border()
         };
 
+        // A pending question replaces the title, which otherwise shows the
+        // status notice (indexing progress is in the status bar)
+        let state = ctx.ui_ctx.state;
+        let mut title = "─── Input ".to_owned();
+        if let Some(question) = state.pending_questions.front() {
+            border_color = self.theme.warning();
+            title = format!("─── Question: {} ", question.prompt());
+        } else if let Some(notice) = state
+            .status_notice
+            .as_ref()
+            .filter(|notice| notice.is_visible(ctx.ui_ctx.task_manager.clock().now()))
+        {
+            title = format!("{title} [{}] ", notice.message);
+        }
+
         input_area.set_block(
             Block::default()
                 .borders(Borders::ALL)
@@ -201,168 +215,6 @@ impl Renderer {
         frame.render_widget(&input_area, area);
     }
 
-    fn render_thread_list(&self, frame: &mut Frame, area: Rect, ctx: &RenderCtx<'_>) {
-        let focused = ctx.focused;
-        let selected_thread_id = ctx.ui_ctx.state.active_thread_id;
-        let thread_store = ctx.thread_store;
-
-        let border_color = if focused == FocusedPane::Threads {
-            self.theme.focused_border()
-        } else {
-            self.theme.unfocused_border()
-        };
-
-        let lines = thread_store.lock().ok().map_or_else(Vec::new, |store| {
-            let threads = store.active_threads();
-            self.build_thread_list_lines(&threads, selected_thread_id, focused)
-        });
-
-        let paragraph = Paragraph::new(lines)
-            .style(Style::default().fg(self.theme.text()))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("─── Threads ")
-                    .border_style(Style::default().fg(border_color))
-                    .padding(Padding::horizontal(1)),
-            )
-            .wrap(Wrap { trim: false });
-
-        frame.render_widget(paragraph, area);
-    }
-
-    /// Builds the lines for thread list display
-    fn build_thread_list_lines(
-        &self,
-        threads: &[&Thread],
-        selected_thread_id: Option<ThreadId>,
-        focused: FocusedPane,
-    ) -> Vec<Line<'static>> {
-        use ratatui::text::Span;
-
-        let mut lines = Vec::new();
-
-        if threads.is_empty() {
-            lines.push(Line::from(Span::styled(
-                "No threads yet",
-                Style::default().fg(self.theme.text()),
-            )));
-            lines.push(Line::from(""));
-            lines.push(Line::from(Span::styled(
-                "Press 'n' to create a new thread",
-                Style::default().fg(self.theme.text()),
-            )));
-        } else {
-            for (index, thread) in threads.iter().enumerate() {
-                lines.push(self.build_thread_line(thread, selected_thread_id, index + 1));
-            }
-        }
-
-        // Add help text at bottom
-        if focused == FocusedPane::Threads {
-            lines.push(Line::from(""));
-            lines.push(Line::from(Span::styled(
-                "n:new b:branch d:delete ↑↓:navigate",
-                Style::default()
-                    .fg(self.theme.text())
-                    .add_modifier(Modifier::DIM),
-            )));
-        }
-
-        lines
-    }
-
-    /// Builds a single thread line with selection, number, name, and status
-    fn build_thread_line(
-        &self,
-        thread: &Thread,
-        selected_thread_id: Option<ThreadId>,
-        thread_number: usize,
-    ) -> Line<'static> {
-        use ratatui::text::Span;
-
-        let is_selected = selected_thread_id == Some(thread.id);
-        let mut spans = Vec::new();
-
-        // Selection indicator
-        if is_selected {
-            spans.push(Span::styled(
-                "> ",
-                Style::default()
-                    .fg(self.theme.focused_border())
-                    .add_modifier(Modifier::BOLD),
-            ));
-        } else {
-            spans.push(Span::raw("  "));
-        }
-
-        // Thread number in brackets
-        spans.push(Span::styled(
-            format!("[{thread_number}] "),
-            Style::default()
-                .fg(self.theme.text())
-                .add_modifier(Modifier::DIM),
-        ));
-
-        // Thread name
-        let name_style = if is_selected {
-            Style::default()
-                .fg(self.theme.text())
-                .add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(self.theme.text())
-        };
-        spans.push(Span::styled(thread.name.clone(), name_style));
-
-        // Check if thread has in-progress work and show running indicator
-        let is_running = thread
-            .last_message()
-            .and_then(|msg| msg.work.as_ref())
-            .is_some_and(|work| {
-                use merlin_core::WorkStatus;
-                matches!(work.status, WorkStatus::InProgress | WorkStatus::Retrying)
-            });
-
-        if is_running {
-            spans.push(Span::styled(
-                " [...]",
-                Style::default()
-                    .fg(self.theme.warning())
-                    .add_modifier(Modifier::DIM),
-            ));
-        }
-
-        // Show message count with status color if work exists
-        let msg_count = thread.messages.len();
-        if msg_count > 0 {
-            let count_text = format!(" ({msg_count})");
-
-            // Apply status color if there's work
-            let count_style = if let Some(last_msg) = thread.last_message()
-                && let Some(ref work) = last_msg.work
-            {
-                use merlin_core::WorkStatus;
-                let status_color = match work.status {
-                    WorkStatus::Completed => self.theme.success(),
-                    WorkStatus::Failed => self.theme.error(),
-                    WorkStatus::InProgress | WorkStatus::Retrying => self.theme.warning(),
-                    WorkStatus::Cancelled => self.theme.text(),
-                };
-                Style::default()
-                    .fg(status_color)
-                    .add_modifier(Modifier::DIM)
-            } else {
-                Style::default()
-                    .fg(self.theme.text())
-                    .add_modifier(Modifier::DIM)
-            };
-
-            spans.push(Span::styled(count_text, count_style));
-        }
-
-        Line::from(spans)
-    }
-
     // Helper methods
 
     /// Calculate the number of lines that will be rendered for a task's output
@@ -410,3 +262,6 @@ pub enum FocusedPane {
     /// Threads list pane (side-by-side mode)
     Threads,
 }
+
+#[cfg(test)]
+mod tests;
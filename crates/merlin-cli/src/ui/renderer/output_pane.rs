@@ -0,0 +1,244 @@
+//! Focused task output pane
+//!
+//! Renders the active task's output at its viewport: following the end of the
+//! output or pinned at a scroll offset, with a marker for lines that arrived
+//! below a pinned viewport, the current plan step pinned above the output and
+//! horizontal scrolling when long lines are not wrapped.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Text},
+    widgets::{Block, Borders, Padding, Paragraph, Wrap},
+};
+
+use super::{FocusedPane, UiCtx, truncate_text};
+use crate::ui::code_blocks::find_code_blocks;
+use crate::ui::diff_lines::{DiffLine, classify_diff_lines};
+use crate::ui::markdown::{MarkdownStyles, render_markdown};
+use crate::ui::scroll::{self, OutputViewport};
+use crate::ui::spinner::Spinner;
+use crate::ui::state::{OutputFormat, OutputWrap};
+use crate::ui::task_manager::{TaskDisplay, TaskStatus};
+use crate::ui::theme::Theme;
+
+/// Renders the focused task output section
+pub(super) fn render_focused_detail_section(
+    theme: &Theme,
+    frame: &mut Frame,
+    area: Rect,
+    ui_ctx: &UiCtx<'_>,
+    focused: FocusedPane,
+) {
+    let border_color = if focused == FocusedPane::Output {
+        theme.focused_border()
+    } else {
+        theme.unfocused_border()
+    };
+
+    // The wrap mode is shown right-aligned in the title, after the task description
+    let wrap_indicator = format!(" {} ", ui_ctx.state.output_wrap.indicator());
+    let title_width =
+        usize::from(area.width.saturating_sub(2)).saturating_sub(wrap_indicator.len());
+
+    // Get task output if a task is selected
+    let (text, title, viewport, selected_block, plan_step) = if let Some(active_task_id) =
+        ui_ctx.state.active_task_id
+        && let Some(task) = ui_ctx.task_manager.get_task(active_task_id)
+    {
+        (
+            task.output.clone(),
+            focused_title(theme, task, ui_ctx, title_width),
+            task.viewport,
+            task.selected_code_block,
+            task.plan_step_indicator(),
+        )
+    } else {
+        // No task selected - show empty output pane with generic title
+        (
+            String::new(),
+            "─── Focused ".to_owned(),
+            OutputViewport::default(),
+            None,
+            None,
+        )
+    };
+
+    // Calculate content height and clamp scroll offset
+    // Account for borders (2) and the pinned plan step - horizontal padding doesn't affect height
+    let pinned_rows = u16::from(plan_step.is_some());
+    let viewport_height = area.height.saturating_sub(2 + pinned_rows);
+    let text_lines = scroll::count_text_lines(&text);
+    let max_scroll = text_lines.saturating_sub(viewport_height);
+    let clamped_scroll = viewport.offset(max_scroll);
+
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title(Line::from(wrap_indicator).right_aligned())
+        .border_style(Style::default().fg(border_color))
+        .padding(Padding::horizontal(1));
+
+    // Pinned viewports show how much output arrived below them
+    let unseen_lines = viewport.unseen_lines(max_scroll);
+    if unseen_lines > 0 {
+        block = block.title_bottom(
+            Line::from(format!(" {unseen_lines} new lines ↓ "))
+                .style(Style::default().fg(theme.warning()))
+                .right_aligned(),
+        );
+    }
+
+    // Code lines are clipped to the content width (borders and padding) so they never wrap
+    let code_width = usize::from(area.width.saturating_sub(4));
+    let output_text = match ui_ctx.state.output_format {
+        OutputFormat::Markdown => build_markdown_text(theme, &text, selected_block, code_width),
+        OutputFormat::Raw => build_output_text(theme, &text, selected_block),
+    };
+
+    let paragraph = Paragraph::new(output_text).style(Style::default().fg(theme.text()));
+    // Without word wrap, long lines are cut at the pane edge and scrolled horizontally
+    let paragraph = match ui_ctx.state.output_wrap {
+        OutputWrap::Wrap => paragraph
+            .wrap(Wrap { trim: false })
+            .scroll((clamped_scroll, 0)),
+        OutputWrap::NoWrap => {
+            let max_column =
+                scroll::max_line_width(&text).saturating_sub(area.width.saturating_sub(4));
+            paragraph.scroll((clamped_scroll, viewport.column(max_column)))
+        }
+    };
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    render_pinned_output(theme, frame, inner, plan_step, paragraph);
+}
+
+/// Builds the focused pane title of `task`, truncated to `title_width`
+///
+/// The title leaves out progress (shown in the input box) and includes the
+/// running time of unfinished tasks.
+fn focused_title(
+    theme: &Theme,
+    task: &TaskDisplay,
+    ui_ctx: &UiCtx<'_>,
+    title_width: usize,
+) -> String {
+    let indicator =
+        Spinner::for_theme(*theme).status_indicator(task.status, ui_ctx.state.spinner_tick);
+    let base_title = if task.status == TaskStatus::Running {
+        let elapsed = ui_ctx.task_manager.elapsed(task).as_secs();
+        format!(
+            "─── Focused - {indicator} {} ({elapsed}s) ",
+            task.description
+        )
+    } else {
+        format!("─── Focused - {indicator} {} ", task.description)
+    };
+    truncate_text(&base_title, title_width)
+}
+
+/// Renders the output into `area` below the pinned plan step, if any
+fn render_pinned_output(
+    theme: &Theme,
+    frame: &mut Frame,
+    area: Rect,
+    plan_step: Option<String>,
+    output: Paragraph<'_>,
+) {
+    let pinned_rows = u16::from(plan_step.is_some());
+    let split = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(pinned_rows), Constraint::Min(0)])
+        .split(area);
+    if let Some(plan_step) = plan_step {
+        let line = Line::from(truncate_text(&plan_step, usize::from(area.width))).style(
+            Style::default()
+                .fg(theme.highlight())
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_widget(Paragraph::new(line), split[0]);
+    }
+    frame.render_widget(output, split[1]);
+}
+
+/// Builds the output pane text, coloring diffs and highlighting the selected code block
+fn build_output_text(theme: &Theme, output: &str, selected_block: Option<usize>) -> Text<'static> {
+    let block = selected_block.and_then(|index| find_code_blocks(output).into_iter().nth(index));
+
+    let highlight = Style::default()
+        .fg(theme.highlight())
+        .add_modifier(Modifier::BOLD);
+    output
+        .lines()
+        .zip(classify_diff_lines(output))
+        .enumerate()
+        .map(|(index, (line, diff_line))| {
+            if block
+                .as_ref()
+                .is_some_and(|block| (block.start_line..=block.end_line).contains(&index))
+            {
+                return Line::styled(line.to_owned(), highlight);
+            }
+            match diff_line {
+                Some(DiffLine::Added) => {
+                    Line::styled(line.to_owned(), Style::default().fg(theme.success()))
+                }
+                Some(DiffLine::Removed) => {
+                    Line::styled(line.to_owned(), Style::default().fg(theme.error()))
+                }
+                _ => Line::raw(line.to_owned()),
+            }
+        })
+        .collect()
+}
+
+/// Builds the output pane text as rendered Markdown, highlighting the selected code block
+fn build_markdown_text(
+    theme: &Theme,
+    output: &str,
+    selected_block: Option<usize>,
+    code_width: usize,
+) -> Text<'static> {
+    let styles = MarkdownStyles {
+        text: Style::default(),
+        heading: Style::default()
+            .fg(theme.focused_border())
+            .add_modifier(Modifier::BOLD),
+        code: Style::default().fg(theme.success()),
+        fence: Style::default().add_modifier(Modifier::DIM),
+        link: Style::default()
+            .fg(theme.focused_border())
+            .add_modifier(Modifier::UNDERLINED),
+        added: Style::default().fg(theme.success()),
+        removed: Style::default().fg(theme.error()),
+    };
+    let lines = render_markdown(output, &styles, code_width);
+
+    let Some(block) =
+        selected_block.and_then(|index| find_code_blocks(output).into_iter().nth(index))
+    else {
+        return Text::from(lines);
+    };
+
+    let highlight = Style::default()
+        .fg(theme.highlight())
+        .add_modifier(Modifier::BOLD);
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(index, line)| {
+            if (block.start_line..=block.end_line).contains(&index) {
+                Line::from(
+                    line.spans
+                        .into_iter()
+                        .map(|span| span.patch_style(highlight))
+                        .collect::<Vec<_>>(),
+                )
+            } else {
+                line
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,279 @@
+//! One-line session status bar
+//!
+//! Shows the active thread, the model that handled the last task, the session cost,
+//! the embedding index state, task counts and, once rendering has fallen behind,
+//! how many UI events were coalesced. On narrow terminals the least
+//! important segments are dropped first.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+use unicode_width::UnicodeWidthStr as _;
+
+use super::truncate_text;
+use crate::ui::state::UiState;
+use crate::ui::theme::Theme;
+
+/// Separator drawn between status bar segments
+const SEGMENT_SEPARATOR: &str = " │ ";
+
+/// A piece of session information shown in the status bar
+struct StatusSegment {
+    /// Text shown for the segment
+    text: String,
+    /// Importance of the segment (lower values are dropped last)
+    priority: u8,
+}
+
+/// Renders the status bar into `area`
+pub(super) fn render_status_bar(
+    theme: &Theme,
+    frame: &mut Frame,
+    area: Rect,
+    state: &UiState,
+    thread_name: Option<&str>,
+) {
+    let content_width = usize::from(area.width.saturating_sub(2));
+    let text = fit_segments(status_segments(state, thread_name), content_width);
+
+    let paragraph = Paragraph::new(Line::from(Span::styled(
+        format!(" {text}"),
+        Style::default()
+            .fg(theme.text())
+            .add_modifier(Modifier::DIM),
+    )));
+    frame.render_widget(paragraph, area);
+}
+
+/// Builds the status bar segments in display order
+fn status_segments(state: &UiState, thread_name: Option<&str>) -> Vec<StatusSegment> {
+    let mut segments = vec![
+        StatusSegment {
+            text: format!("Thread: {}", thread_name.unwrap_or("none")),
+            priority: 5,
+        },
+        StatusSegment {
+            text: format!("Model: {}", state.last_task_model.as_deref().unwrap_or("-")),
+            priority: 1,
+        },
+        StatusSegment {
+            text: format!("Cost: ${:.4}", state.session_cost),
+            priority: 2,
+        },
+    ];
+
+    // Nothing is shown while embeddings are disabled or indexing hasn't reported yet
+    let index_text = match state.embedding_progress {
+        Some((current, total)) => Some(format!(
+            "Indexing {}%",
+            current.saturating_mul(100) / total.max(1)
+        )),
+        None if state.index_ready => Some("Index ready".to_owned()),
+        None => None,
+    };
+    if let Some(text) = index_text {
+        segments.push(StatusSegment { text, priority: 3 });
+    }
+
+    segments.push(StatusSegment {
+        text: format!(
+            "Tasks: {} active, {} queued",
+            state.active_running_tasks.len(),
+            usize::from(state.queued_input.is_some())
+        ),
+        priority: 4,
+    });
+
+    if state.coalesced_events > 0 {
+        segments.push(StatusSegment {
+            text: format!("{} events coalesced", state.coalesced_events),
+            priority: 6,
+        });
+    }
+
+    segments
+}
+
+/// Joins segments into a line of at most `width` columns
+///
+/// Drops the least important segments until the rest fit, then truncates the
+/// last remaining segment if it is still too wide.
+fn fit_segments(mut segments: Vec<StatusSegment>, width: usize) -> String {
+    loop {
+        let line = segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(SEGMENT_SEPARATOR);
+        if line.width() <= width || segments.len() <= 1 {
+            return truncate_text(&line, width);
+        }
+
+        let least_important = segments
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, segment)| segment.priority)
+            .map(|(index, _)| index);
+        if let Some(index) = least_important {
+            segments.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::Result;
+    use merlin_routing::TaskId;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::buffer::Cell;
+
+    /// Builds a state with every status bar segment populated.
+    fn busy_state() -> UiState {
+        let mut state = UiState {
+            embedding_progress: Some((40, 100)),
+            last_task_model: Some("llama-3.1-8b".to_owned()),
+            session_cost: 0.0123,
+            queued_input: Some("Then add tests".to_owned()),
+            ..Default::default()
+        };
+        state.active_running_tasks.insert(TaskId::default());
+        state
+    }
+
+    /// Renders the status bar for `state` at `width` columns and returns the row text.
+    ///
+    /// # Errors
+    /// Returns an error if drawing to the test terminal fails.
+    fn render_status_bar(state: &UiState, width: u16) -> Result<String> {
+        let theme = Theme::default();
+        let mut terminal = Terminal::new(TestBackend::new(width, 1))?;
+        terminal.draw(|frame| {
+            super::render_status_bar(&theme, frame, frame.area(), state, Some("Refactor parser"));
+        })?;
+
+        Ok(terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(Cell::symbol)
+            .collect::<String>()
+            .trim_end()
+            .to_owned())
+    }
+
+    /// Tests that a wide terminal shows every segment.
+    ///
+    /// # Errors
+    /// Returns an error if rendering fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_status_bar_wide_shows_all_segments() -> Result<()> {
+        assert_eq!(
+            render_status_bar(&busy_state(), 120)?,
+            " Thread: Refactor parser │ Model: llama-3.1-8b │ Cost: $0.0123 │ Indexing 40% │ \
+             Tasks: 1 active, 1 queued"
+        );
+        Ok(())
+    }
+
+    /// Tests that the thread segment is dropped first as the terminal narrows.
+    ///
+    /// # Errors
+    /// Returns an error if rendering fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_status_bar_medium_drops_thread() -> Result<()> {
+        assert_eq!(
+            render_status_bar(&busy_state(), 80)?,
+            " Model: llama-3.1-8b │ Cost: $0.0123 │ Indexing 40% │ Tasks: 1 active, 1 queued"
+        );
+        Ok(())
+    }
+
+    /// Tests that narrow terminals keep the model and cost.
+    ///
+    /// # Errors
+    /// Returns an error if rendering fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_status_bar_narrow_keeps_model_and_cost() -> Result<()> {
+        assert_eq!(
+            render_status_bar(&busy_state(), 50)?,
+            " Model: llama-3.1-8b │ Cost: $0.0123"
+        );
+        Ok(())
+    }
+
+    /// Tests that a very narrow terminal truncates the last remaining segment.
+    ///
+    /// # Errors
+    /// Returns an error if rendering fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_status_bar_very_narrow_truncates_model() -> Result<()> {
+        assert_eq!(render_status_bar(&busy_state(), 16)?, " Model: llam...");
+        Ok(())
+    }
+
+    /// Tests that coalesced events are reported and dropped first when narrow.
+    ///
+    /// # Errors
+    /// Returns an error if rendering fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_status_bar_shows_coalesced_events() -> Result<()> {
+        let state = UiState {
+            coalesced_events: 1234,
+            ..busy_state()
+        };
+        assert_eq!(
+            render_status_bar(&state, 150)?,
+            " Thread: Refactor parser │ Model: llama-3.1-8b │ Cost: $0.0123 │ Indexing 40% │ \
+             Tasks: 1 active, 1 queued │ 1234 events coalesced"
+        );
+        assert_eq!(
+            render_status_bar(&state, 120)?,
+            " Thread: Refactor parser │ Model: llama-3.1-8b │ Cost: $0.0123 │ Indexing 40% │ \
+             Tasks: 1 active, 1 queued"
+        );
+        Ok(())
+    }
+
+    /// Tests the idle state before any task has run and after indexing finished.
+    ///
+    /// # Errors
+    /// Returns an error if rendering fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_status_bar_idle_with_ready_index() -> Result<()> {
+        let state = UiState {
+            index_ready: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_status_bar(&state, 100)?,
+            " Thread: Refactor parser │ Model: - │ Cost: $0.0000 │ Index ready │ \
+             Tasks: 0 active, 0 queued"
+        );
+        Ok(())
+    }
+}
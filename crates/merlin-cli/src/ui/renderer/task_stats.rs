@@ -0,0 +1,91 @@
+//! `Ctrl+I` task statistics panel
+//!
+//! Drawn over the output pane until the next key press: a bar chart of the
+//! running time of each task finished this session, followed by the token,
+//! cost, duration and validation totals.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+
+use super::UiCtx;
+use crate::ui::task_stats::{TaskStats, duration_chart, format_duration};
+use crate::ui::theme::Theme;
+
+/// Rows of the duration chart
+const CHART_HEIGHT: usize = 4;
+
+/// Renders the statistics panel over `area`
+pub(super) fn render_task_stats(theme: &Theme, frame: &mut Frame, area: Rect, ui_ctx: &UiCtx<'_>) {
+    let stats = TaskStats::collect(ui_ctx.task_manager, ui_ctx.state.session_cost);
+    let dim = Style::default()
+        .fg(theme.text())
+        .add_modifier(Modifier::DIM);
+
+    // Each bar takes a column plus a separating space, so only the latest tasks may fit
+    let bar_capacity = usize::from(area.width.saturating_sub(4)).div_ceil(2);
+    let shown = &stats.durations[stats.durations.len().saturating_sub(bar_capacity)..];
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "Task durations ({} of {})",
+            shown.len(),
+            stats.durations.len()
+        ),
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    if shown.is_empty() {
+        lines.push(Line::from(Span::styled("No finished tasks yet", dim)));
+    } else {
+        lines.extend(
+            duration_chart(shown, CHART_HEIGHT)
+                .into_iter()
+                .map(|row| Line::from(Span::styled(row, Style::default().fg(theme.highlight())))),
+        );
+    }
+    lines.push(Line::default());
+
+    let average = stats
+        .average_duration()
+        .map_or_else(|| "-".to_owned(), format_duration);
+    let longest = stats
+        .durations
+        .iter()
+        .max()
+        .map_or_else(|| "-".to_owned(), |longest| format_duration(*longest));
+    let validation = match stats.pass_rate() {
+        Some(rate) => format!(
+            "{} passed, {} failed ({:.0}%)",
+            stats.validation_passed,
+            stats.validation_failed,
+            rate * 100.0
+        ),
+        None => "-".to_owned(),
+    };
+    lines.extend(
+        [
+            format!("Average duration: {average} (longest {longest})"),
+            format!("Tokens used: {}", stats.total_tokens),
+            format!("Estimated cost: ${:.4}", stats.session_cost),
+            format!("Validation: {validation}"),
+        ]
+        .into_iter()
+        .map(Line::from),
+    );
+
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("─── Session statistics ")
+                .title_bottom(Line::from(Span::styled(" Press any key to close ", dim)))
+                .border_style(Style::default().fg(theme.focused_border()))
+                .padding(Padding::horizontal(1)),
+        );
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
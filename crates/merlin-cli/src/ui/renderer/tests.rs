@@ -0,0 +1,403 @@
+//! Tests for the renderer
+
+use super::thread_list::{ListPosition, ThreadListView};
+use super::*;
+use crate::ui::spinner::Spinner;
+use crate::ui::state::OutputWrap;
+use crate::ui::task_manager::TaskStatus;
+use merlin_core::{Result, Thread, VirtualClock};
+use merlin_routing::TaskId;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Cell;
+use std::time::Duration;
+
+/// Builds a task whose output is `line_count` numbered lines.
+fn task_with_lines(line_count: usize) -> TaskDisplay {
+    let output = (1..=line_count)
+        .map(|number| format!("Line {number}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    TaskDisplay {
+        description: "Scroll test".to_owned(),
+        output,
+        ..Default::default()
+    }
+}
+
+/// Renders the focused output pane for `task` into a 60x10 buffer and returns its text.
+///
+/// # Errors
+/// Returns an error if drawing to the test terminal fails.
+fn render_output(task: TaskDisplay) -> Result<String> {
+    render_output_after(task, Duration::ZERO)
+}
+
+/// Renders the focused output pane for `task` once it has run for `elapsed`.
+///
+/// # Errors
+/// Returns an error if drawing to the test terminal fails.
+fn render_output_after(task: TaskDisplay, elapsed: Duration) -> Result<String> {
+    render_output_with(task, elapsed, OutputWrap::Wrap)
+}
+
+/// Renders the focused output pane for `task` with long lines shown as `output_wrap`.
+///
+/// # Errors
+/// Returns an error if drawing to the test terminal fails.
+fn render_output_with(
+    task: TaskDisplay,
+    elapsed: Duration,
+    output_wrap: OutputWrap,
+) -> Result<String> {
+    let task_id = TaskId::default();
+    let clock = VirtualClock::new();
+    let mut task_manager = TaskManager::with_clock(clock.shared());
+    task_manager.add_task(task_id, task);
+    clock.advance(elapsed);
+    let state = UiState {
+        active_task_id: Some(task_id),
+        output_wrap,
+        ..Default::default()
+    };
+
+    let renderer = Renderer::new(Theme::default());
+    let mut terminal = Terminal::new(TestBackend::new(60, 10))?;
+    terminal.draw(|frame| {
+        let ui_ctx = UiCtx {
+            task_manager: &task_manager,
+            state: &state,
+        };
+        output_pane::render_focused_detail_section(
+            &renderer.theme,
+            frame,
+            frame.area(),
+            &ui_ctx,
+            FocusedPane::Output,
+        );
+    })?;
+
+    let buffer = terminal.backend().buffer();
+    Ok(buffer
+        .content()
+        .chunks(usize::from(buffer.area.width))
+        .map(|row| row.iter().map(Cell::symbol).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Tests that a following viewport shows the end of the output.
+///
+/// # Errors
+/// Returns an error if rendering fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_following_viewport_shows_bottom() -> Result<()> {
+    let rendered = render_output(task_with_lines(30))?;
+
+    assert!(rendered.contains("Line 30"));
+    assert!(!rendered.contains("Line 1 "));
+    Ok(())
+}
+
+/// Tests that the focused title shows how long a running task has run on the clock.
+///
+/// # Errors
+/// Returns an error if rendering fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_running_task_title_shows_elapsed_time() -> Result<()> {
+    let running = render_output_after(task_with_lines(1), Duration::from_millis(42_500))?;
+    let spinner = Spinner::for_theme(Theme::default()).frame(0);
+    assert!(running.contains(&format!("Focused - {spinner} Scroll test (42s)")));
+
+    let completed = TaskDisplay {
+        status: TaskStatus::Completed,
+        ..task_with_lines(1)
+    };
+    let finished = render_output_after(completed, Duration::from_secs(42))?;
+    assert!(finished.contains("Focused - [+] Scroll test "));
+    assert!(!finished.contains("(42s)"));
+    Ok(())
+}
+
+/// Tests that the running plan step is pinned above the output, keeping the
+/// latest output visible, and hidden once the task finished.
+///
+/// # Errors
+/// Returns an error if rendering fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_plan_step_pinned_above_output() -> Result<()> {
+    let running = TaskDisplay {
+        plan_step: Some((2, 4, "Implement the auth handler".to_owned())),
+        ..task_with_lines(30)
+    };
+    let rendered = render_output(running.clone())?;
+    let rows: Vec<&str> = rendered.lines().collect();
+    assert!(rows[1].contains("Step 2/4: Implement the auth handler"));
+    assert!(rendered.contains("Line 30"));
+    assert!(rendered.contains("Line 24"));
+    assert!(!rendered.contains("Line 23"));
+
+    let completed = TaskDisplay {
+        status: TaskStatus::Completed,
+        ..running
+    };
+    assert!(!render_output(completed)?.contains("Step 2/4"));
+    Ok(())
+}
+
+/// Tests that the statistics panel shows the duration chart and session totals.
+///
+/// # Errors
+/// Returns an error if rendering fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_task_stats_panel() -> Result<()> {
+    let clock = VirtualClock::new();
+    let mut task_manager = TaskManager::with_clock(clock.shared());
+    for seconds in [4, 2] {
+        let task_id = TaskId::default();
+        task_manager.add_task(task_id, TaskDisplay::default());
+        clock.advance(Duration::from_secs(seconds));
+        task_manager.finish_task(task_id);
+        if let Some(task) = task_manager.get_task_mut(task_id) {
+            task.validation_passed = Some(seconds == 4);
+        }
+    }
+    let state = UiState {
+        session_cost: 0.5,
+        info_panel: InfoPanel::TaskStats,
+        ..Default::default()
+    };
+
+    let renderer = Renderer::new(Theme::default());
+    let mut terminal = Terminal::new(TestBackend::new(60, 14))?;
+    terminal.draw(|frame| {
+        let ui_ctx = UiCtx {
+            task_manager: &task_manager,
+            state: &state,
+        };
+        task_stats::render_task_stats(&renderer.theme, frame, frame.area(), &ui_ctx);
+    })?;
+    let buffer = terminal.backend().buffer();
+    let panel = buffer
+        .content()
+        .chunks(usize::from(buffer.area.width))
+        .map(|row| row.iter().map(Cell::symbol).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    assert!(panel.contains("Task durations (2 of 2)"));
+    assert_eq!(panel.matches("█ █").count(), 2);
+    assert!(panel.contains("Average duration: 3.0s (longest 4.0s)"));
+    assert!(panel.contains("Estimated cost: $0.5000"));
+    assert!(panel.contains("Validation: 1 passed, 1 failed (50%)"));
+    Ok(())
+}
+
+/// Tests that without word wrap long lines are cut and scroll horizontally.
+///
+/// # Errors
+/// Returns an error if rendering fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_no_wrap_scrolls_long_lines_horizontally() -> Result<()> {
+    let task = TaskDisplay {
+        output: "| name | value | description of the column shown in the table | unit |".to_owned(),
+        ..task_with_lines(0)
+    };
+
+    let wrapped = render_output_with(task.clone(), Duration::ZERO, OutputWrap::Wrap)?;
+    assert!(wrapped.contains("[WRAP]"));
+    assert!(wrapped.contains("unit |"));
+
+    let cut = render_output_with(task.clone(), Duration::ZERO, OutputWrap::NoWrap)?;
+    assert!(cut.contains("[NO-WRAP]"));
+    assert!(cut.contains("| name | value"));
+    assert!(!cut.contains("unit |"));
+
+    let mut scrolled = task;
+    scrolled.viewport.scroll_right(100, 100);
+    let shifted = render_output_with(scrolled, Duration::ZERO, OutputWrap::NoWrap)?;
+    assert!(shifted.contains("unit |"));
+    assert!(!shifted.contains("| name"));
+    Ok(())
+}
+
+/// Tests that a pinned offset is clamped when the task's output shrinks.
+///
+/// # Errors
+/// Returns an error if rendering fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_pinned_offset_clamped_when_output_shrinks() -> Result<()> {
+    let mut task = task_with_lines(30);
+    // 30 lines in an 8-line viewport: pin 20 lines down
+    task.viewport.scroll_to_top(22);
+    task.viewport.scroll_down(20, 22);
+    // Pinned: the offset stays put however far the bottom moves
+    assert_eq!(task.viewport.offset(100), 20);
+
+    // Output reloaded with fewer lines than the pinned offset
+    task.output = task_with_lines(12).output;
+    let rendered = render_output(task)?;
+
+    assert!(rendered.contains("Line 5"));
+    assert!(rendered.contains("Line 12"));
+    assert!(!rendered.contains("new lines"));
+    Ok(())
+}
+
+/// Tests that a pinned viewport reports output added below it.
+///
+/// # Errors
+/// Returns an error if rendering fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_pinned_viewport_shows_new_line_indicator() -> Result<()> {
+    let mut task = task_with_lines(20);
+    task.viewport.scroll_up(10, 12);
+    task.output = task_with_lines(25).output;
+    task.viewport.record_new_lines(5);
+
+    let rendered = render_output(task)?;
+
+    assert!(rendered.contains("Line 3"));
+    assert!(rendered.contains("5 new lines ↓"));
+    Ok(())
+}
+
+/// Tests that long thread names are truncated to fit the pane width.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_thread_line_truncates_long_names() {
+    use merlin_core::ThreadColor;
+
+    let renderer = Renderer::new(Theme::default());
+    let view = ThreadListView {
+        line_width: 30,
+        ..ThreadListView::default()
+    };
+    let thread = Thread::new(
+        "Investigate flaky integration tests in the routing crate".to_owned(),
+        ThreadColor::Blue,
+    );
+
+    let line = thread_list::build_thread_line(
+        &renderer.theme,
+        &thread,
+        None,
+        ListPosition {
+            number: 1,
+            depth: 0,
+        },
+        &view,
+    );
+    let text = line.to_string();
+    assert_eq!(line.width(), 30);
+    assert!(text.ends_with("..."));
+    assert!(text.starts_with("  [1] Investigate"));
+
+    let short = Thread::new("Short".to_owned(), ThreadColor::Blue);
+    assert_eq!(
+        thread_list::build_thread_line(
+            &renderer.theme,
+            &short,
+            None,
+            ListPosition {
+                number: 1,
+                depth: 0
+            },
+            &view
+        )
+        .to_string(),
+        "  [1] Short"
+    );
+}
+
+/// Tests that threads working outside the project root show their directory.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_thread_line_shows_working_dir() {
+    use merlin_core::ThreadColor;
+    use std::path::PathBuf;
+
+    let renderer = Renderer::new(Theme::default());
+    let view = ThreadListView {
+        line_width: 30,
+        ..ThreadListView::default()
+    };
+    let mut thread = Thread::new("Node pools".to_owned(), ThreadColor::Blue);
+    thread.working_dir = Some(PathBuf::from("/work/infra"));
+
+    assert_eq!(
+        thread_list::build_thread_line(
+            &renderer.theme,
+            &thread,
+            None,
+            ListPosition {
+                number: 2,
+                depth: 0
+            },
+            &view
+        )
+        .to_string(),
+        "  [2] Node pools @infra"
+    );
+}
+
+/// Tests that branches are marked and indented under their listed ancestors.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_thread_line_marks_branches() {
+    use merlin_core::{MessageId, ThreadColor};
+
+    let renderer = Renderer::new(Theme::default());
+    let view = ThreadListView {
+        line_width: 40,
+        ..ThreadListView::default()
+    };
+    let parent = Thread::new("Parser".to_owned(), ThreadColor::Blue);
+    let branch = Thread::branched_from(
+        "Match instead".to_owned(),
+        ThreadColor::Green,
+        parent.id,
+        MessageId::default(),
+    );
+
+    let line_text = |depth| {
+        thread_list::build_thread_line(
+            &renderer.theme,
+            &branch,
+            None,
+            ListPosition { number: 3, depth },
+            &view,
+        )
+        .to_string()
+    };
+    assert_eq!(line_text(0), "  [3] ↳ Match instead");
+    assert_eq!(line_text(1), "  [3] ↳ Match instead");
+    assert_eq!(line_text(2), "  [3]   ↳ Match instead");
+}
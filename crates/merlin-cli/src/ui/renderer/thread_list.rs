@@ -0,0 +1,388 @@
+//! Thread list pane
+//!
+//! Lists the threads with their status, directory and tags, below the search,
+//! tag, rename, merge and branch prompts while one of them is open.
+
+use merlin_core::{Thread, ThreadId};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Padding, Paragraph, Wrap},
+};
+
+use super::{FocusedPane, RenderCtx, truncate_text};
+use crate::ui::spinner::Spinner;
+use crate::ui::theme::Theme;
+use crate::ui::thread_filter::{branch_depths, tag_suggestions, visible_threads};
+
+/// Minimum columns kept for a thread name before truncating it
+const MIN_THREAD_NAME_WIDTH: usize = 8;
+
+/// Thread list inputs, filters, and layout shown in the threads pane
+#[derive(Default)]
+pub(super) struct ThreadListView<'view> {
+    /// Search query while the search bar is open
+    pub(super) search_query: Option<&'view str>,
+    /// Tag selector input while the tag selector is open
+    pub(super) tag_input: Option<&'view str>,
+    /// Tag the thread list is filtered by
+    pub(super) tag_filter: Option<&'view str>,
+    /// Existing tags matching the tag selector input
+    pub(super) tag_suggestions: Vec<String>,
+    /// Name of the thread being merged while picking the merge target
+    pub(super) merge_source_name: Option<String>,
+    /// Rename input while renaming the selected thread
+    pub(super) rename_input: Option<&'view str>,
+    /// Name of the thread being branched while picking the branch point
+    pub(super) branch_source_name: Option<String>,
+    /// Usable width of a thread line inside the pane borders
+    pub(super) line_width: usize,
+    /// Spinner frame shown next to threads with running work
+    pub(super) spinner_frame: &'static str,
+}
+
+/// Where a thread appears in the thread list
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ListPosition {
+    /// Number shown in brackets (1-based)
+    pub(super) number: usize,
+    /// Number of the thread's ancestors listed above it
+    pub(super) depth: usize,
+}
+
+impl ThreadListView<'_> {
+    /// Returns whether any search or tag filter narrows the thread list
+    fn is_filtering(&self) -> bool {
+        self.search_query.is_some() || self.tag_filter.is_some()
+    }
+}
+
+/// Renders the thread list pane
+pub(super) fn render_thread_list(
+    theme: &Theme,
+    frame: &mut Frame,
+    area: Rect,
+    ctx: &RenderCtx<'_>,
+) {
+    let focused = ctx.focused;
+    let selected_thread_id = ctx.ui_ctx.state.active_thread_id;
+    let thread_store = ctx.thread_store;
+
+    let border_color = if focused == FocusedPane::Threads {
+        theme.focused_border()
+    } else {
+        theme.unfocused_border()
+    };
+
+    let state = ctx.ui_ctx.state;
+    let lines = thread_store.lock().ok().map_or_else(Vec::new, |store| {
+        let threads = visible_threads(&store, state);
+        let view = ThreadListView {
+            search_query: state.thread_search_query.as_deref(),
+            tag_input: state.thread_tag_input.as_deref(),
+            tag_filter: state.thread_tag_filter.as_deref(),
+            tag_suggestions: state
+                .thread_tag_input
+                .as_deref()
+                .map(|input| tag_suggestions(&store, input))
+                .unwrap_or_default(),
+            merge_source_name: state
+                .thread_merge_source
+                .and_then(|thread_id| store.get_thread(thread_id))
+                .map(|thread| thread.name.clone()),
+            rename_input: state.thread_rename_input.as_deref(),
+            branch_source_name: state
+                .thread_branch_picker
+                .and_then(|picker| store.get_thread(picker.thread_id))
+                .map(|thread| thread.name.clone()),
+            line_width: usize::from(area.width.saturating_sub(4)),
+            spinner_frame: Spinner::for_theme(*theme).frame(state.spinner_tick),
+        };
+        build_thread_list_lines(theme, &threads, selected_thread_id, focused, &view)
+    });
+
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.text()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("─── Threads ")
+                .border_style(Style::default().fg(border_color))
+                .padding(Padding::horizontal(1)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(paragraph, area);
+}
+
+/// Builds the lines for thread list display
+fn build_thread_list_lines(
+    theme: &Theme,
+    threads: &[&Thread],
+    selected_thread_id: Option<ThreadId>,
+    focused: FocusedPane,
+    view: &ThreadListView<'_>,
+) -> Vec<Line<'static>> {
+    use ratatui::text::Span;
+
+    let mut lines = build_thread_filter_lines(theme, view);
+
+    if threads.is_empty() && view.is_filtering() {
+        lines.push(Line::from(Span::styled(
+            "No matching threads",
+            Style::default().fg(theme.text()),
+        )));
+    } else if threads.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No threads yet",
+            Style::default().fg(theme.text()),
+        )));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Press 'n' to create a new thread",
+            Style::default().fg(theme.text()),
+        )));
+    } else {
+        let depths = branch_depths(threads);
+        for (index, (thread, depth)) in threads.iter().zip(depths).enumerate() {
+            let position = ListPosition {
+                number: index + 1,
+                depth,
+            };
+            lines.push(build_thread_line(
+                theme,
+                thread,
+                selected_thread_id,
+                position,
+                view,
+            ));
+        }
+    }
+
+    // Add help text at bottom
+    if focused == FocusedPane::Threads {
+        let help_text = if view.rename_input.is_some() {
+            "Enter:save Esc:cancel"
+        } else if view.merge_source_name.is_some() {
+            "↑↓:select target Enter:merge Esc:cancel"
+        } else if view.branch_source_name.is_some() {
+            "↑↓:select message Enter:branch Esc:cancel"
+        } else if view.tag_input.is_some() {
+            "Tab:complete Enter:apply Esc:cancel"
+        } else if view.search_query.is_some() {
+            "type to filter ↑↓:navigate Enter/Esc:close"
+        } else {
+            "n:new r:rename b:branch m:merge d:delete ↑↓:navigate ^S:search ^G:tags"
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            help_text,
+            Style::default()
+                .fg(theme.text())
+                .add_modifier(Modifier::DIM),
+        )));
+    }
+
+    lines
+}
+
+/// Builds the search bar and tag filter lines shown above the thread list
+fn build_thread_filter_lines(theme: &Theme, view: &ThreadListView<'_>) -> Vec<Line<'static>> {
+    use ratatui::text::Span;
+
+    let input_style = Style::default().fg(theme.focused_border());
+    let mut lines = Vec::new();
+
+    if let Some(input) = view.rename_input {
+        lines.push(Line::from(Span::styled(
+            format!("Rename: {input}_"),
+            input_style,
+        )));
+    }
+
+    if let Some(name) = &view.merge_source_name {
+        lines.push(Line::from(Span::styled(
+            format!("Merge '{name}' into:"),
+            input_style,
+        )));
+    }
+
+    if let Some(name) = &view.branch_source_name {
+        lines.push(Line::from(Span::styled(
+            format!("Branch '{name}' at the selected message"),
+            input_style,
+        )));
+    }
+
+    if let Some(query) = view.search_query {
+        lines.push(Line::from(Span::styled(
+            format!("Search: {query}_"),
+            input_style,
+        )));
+    }
+
+    if let Some(input) = view.tag_input {
+        lines.push(Line::from(Span::styled(
+            format!("Tag: {input}_"),
+            input_style,
+        )));
+        if !view.tag_suggestions.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", view.tag_suggestions.join(" ")),
+                Style::default()
+                    .fg(theme.text())
+                    .add_modifier(Modifier::DIM),
+            )));
+        }
+    } else if let Some(tag) = view.tag_filter {
+        lines.push(Line::from(Span::styled(
+            format!("Tag: #{tag}"),
+            input_style,
+        )));
+    }
+
+    if !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+
+    lines
+}
+
+/// Builds a single thread line with selection, number, name, status, repository and tags
+///
+/// Branches are marked with `↳`, indented one level per ancestor listed above
+/// them. Threads working outside the project root show their directory as `@name`.
+/// Long names are truncated with an ellipsis so the status and tags still fit
+/// within the view's line width. Threads with running work show the view's spinner frame.
+pub(super) fn build_thread_line(
+    theme: &Theme,
+    thread: &Thread,
+    selected_thread_id: Option<ThreadId>,
+    position: ListPosition,
+    view: &ThreadListView<'_>,
+) -> Line<'static> {
+    use ratatui::text::Span;
+
+    let is_selected = selected_thread_id == Some(thread.id);
+    let mut spans = Vec::new();
+
+    // Selection indicator
+    if is_selected {
+        spans.push(Span::styled(
+            "> ",
+            Style::default()
+                .fg(theme.highlight())
+                .add_modifier(Modifier::BOLD),
+        ));
+    } else {
+        spans.push(Span::raw("  "));
+    }
+
+    // Thread number in brackets
+    spans.push(Span::styled(
+        format!("[{}] ", position.number),
+        Style::default()
+            .fg(theme.text())
+            .add_modifier(Modifier::DIM),
+    ));
+
+    // Branch marker, indented under the listed ancestors
+    if thread.parent_thread.is_some() {
+        spans.push(Span::styled(
+            format!("{}↳ ", "  ".repeat(position.depth.saturating_sub(1))),
+            Style::default().fg(theme.highlight()),
+        ));
+    }
+
+    let mut status_spans = build_thread_status_line(theme, thread, view.spinner_frame).spans;
+    if let Some(repo) = thread.working_dir_name() {
+        status_spans.push(Span::styled(
+            format!(" @{repo}"),
+            Style::default().fg(theme.highlight()),
+        ));
+    }
+    status_spans.extend(build_tag_line(theme, thread).spans);
+
+    // Thread name, truncated to the width left over by the other spans
+    let used_width: usize = spans.iter().chain(&status_spans).map(Span::width).sum();
+    let name_width = view
+        .line_width
+        .saturating_sub(used_width)
+        .max(MIN_THREAD_NAME_WIDTH);
+    let name_style = if is_selected {
+        Style::default()
+            .fg(theme.text())
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(theme.text())
+    };
+    spans.push(Span::styled(
+        truncate_text(&thread.name, name_width),
+        name_style,
+    ));
+
+    spans.extend(status_spans);
+    Line::from(spans)
+}
+
+/// Builds the running spinner and status-colored message count for a thread
+fn build_thread_status_line(theme: &Theme, thread: &Thread, spinner_frame: &str) -> Line<'static> {
+    use merlin_core::WorkStatus;
+    use ratatui::text::Span;
+
+    let mut spans = Vec::new();
+    let last_work = thread.last_message().and_then(|msg| msg.work.as_ref());
+
+    // Check if thread has in-progress work and show running indicator
+    let is_running = last_work
+        .is_some_and(|work| matches!(work.status, WorkStatus::InProgress | WorkStatus::Retrying));
+
+    if is_running {
+        spans.push(Span::styled(
+            format!(" {spinner_frame}"),
+            Style::default().fg(theme.warning()),
+        ));
+    }
+
+    // Show message count with status color if work exists
+    let msg_count = thread.messages.len();
+    if msg_count > 0 {
+        let count_text = format!(" ({msg_count})");
+
+        // Apply status color if there's work
+        let count_color = last_work.map_or_else(
+            || theme.text(),
+            |work| match work.status {
+                WorkStatus::Completed => theme.success(),
+                WorkStatus::Failed | WorkStatus::Interrupted => theme.error(),
+                WorkStatus::InProgress | WorkStatus::Retrying => theme.warning(),
+                WorkStatus::Cancelled => theme.text(),
+            },
+        );
+
+        spans.push(Span::styled(
+            count_text,
+            Style::default().fg(count_color).add_modifier(Modifier::DIM),
+        ));
+    }
+
+    Line::from(spans)
+}
+
+/// Builds dimmed `#tag` spans for a thread's tags
+fn build_tag_line(theme: &Theme, thread: &Thread) -> Line<'static> {
+    use ratatui::text::Span;
+
+    let tag_style = Style::default()
+        .fg(theme.text())
+        .add_modifier(Modifier::DIM);
+    Line::from(
+        thread
+            .tags
+            .iter()
+            .map(|tag| Span::styled(format!(" #{tag}"), tag_style))
+            .collect::<Vec<_>>(),
+    )
+}
@@ -0,0 +1,351 @@
+//! SVG screenshots of the rendered TUI
+//!
+//! A frame is drawn into an off-screen buffer through ratatui's `TestBackend`,
+//! then every run of equally styled cells becomes an SVG `<text>` element
+//! filled with its theme color, over a `<rect>` where the run has a background.
+//! Screenshots are saved as `.merlin/screenshots/<timestamp>.svg`.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use merlin_core::Result;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::layout::{Position, Size};
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr as _;
+
+use super::renderer::{RenderCtx, Renderer};
+use super::theme::Theme;
+
+/// Directory under `.merlin/` screenshots are saved to
+pub const SCREENSHOT_DIR: &str = "screenshots";
+
+/// Width of a terminal cell in pixels
+const CELL_WIDTH: u32 = 9;
+/// Height of a terminal cell in pixels
+const CELL_HEIGHT: u32 = 18;
+/// Font size in pixels
+const FONT_SIZE: u32 = 15;
+/// Distance from the top of a cell to the text baseline in pixels
+const BASELINE_OFFSET: u32 = 14;
+/// Opacity of text drawn with the `DIM` modifier
+const DIM_OPACITY: &str = "0.6";
+
+/// Colors of the 16 named terminal colors (xterm defaults)
+const ANSI_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Cells of one row sharing a style
+struct StyledRun {
+    /// Column of the first cell
+    column: u16,
+    /// Number of cells covered
+    width: u16,
+    /// Symbols of the cells
+    text: String,
+    /// Foreground color
+    foreground: Color,
+    /// Background color
+    background: Color,
+    /// Text modifiers
+    modifier: Modifier,
+}
+
+/// Draws the full UI into an off-screen buffer of `size` cells
+///
+/// # Errors
+/// Returns an error if drawing to the buffer fails
+pub fn render_to_buffer(renderer: &Renderer, ctx: &RenderCtx<'_>, size: Size) -> Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(size.width, size.height))?;
+    terminal.draw(|frame| renderer.render(frame, ctx))?;
+    Ok(terminal.backend().buffer().clone())
+}
+
+/// Converts a rendered buffer to an SVG document
+///
+/// Unstyled cells use the theme's text and background colors.
+pub fn buffer_to_svg(buffer: &Buffer, theme: Theme) -> String {
+    let width = u32::from(buffer.area.width) * CELL_WIDTH;
+    let height = u32::from(buffer.area.height) * CELL_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\" font-family=\"monospace\" font-size=\"{FONT_SIZE}\" \
+         xml:space=\"preserve\">\n<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        hex_color(theme.background(), theme.background())
+    );
+
+    for row in 0..buffer.area.height {
+        let y = u32::from(row) * CELL_HEIGHT;
+        for run in style_runs(buffer, row) {
+            let reversed = run.modifier.contains(Modifier::REVERSED);
+            let mut fill = hex_color(run.foreground, theme.text());
+            let mut background = hex_color(run.background, theme.background());
+            if reversed {
+                (fill, background) = (background, fill);
+            }
+            let x = u32::from(run.column) * CELL_WIDTH;
+            if run.background != Color::Reset || reversed {
+                _ = writeln!(
+                    svg,
+                    "<rect x=\"{x}\" y=\"{y}\" width=\"{}\" height=\"{CELL_HEIGHT}\" \
+                     fill=\"{background}\"/>",
+                    u32::from(run.width) * CELL_WIDTH,
+                );
+            }
+            if run.text.trim().is_empty() {
+                continue;
+            }
+            _ = writeln!(
+                svg,
+                "<text x=\"{x}\" y=\"{}\" fill=\"{fill}\"{}>{}</text>",
+                y + BASELINE_OFFSET,
+                text_attributes(run.modifier),
+                escape_xml(&run.text)
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Saves an SVG screenshot as `<merlin_dir>/screenshots/<timestamp>.svg`
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or the file cannot be written
+pub fn save_screenshot(
+    merlin_dir: &Path,
+    svg: &str,
+    taken_at: DateTime<Local>,
+) -> io::Result<PathBuf> {
+    let dir = merlin_dir.join(SCREENSHOT_DIR);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.svg", taken_at.format("%Y%m%d-%H%M%S%.3f")));
+    fs::write(&path, svg)?;
+    Ok(path)
+}
+
+/// Splits a buffer row into runs of cells with the same style
+///
+/// Cells covered by the second half of a wide character are skipped.
+fn style_runs(buffer: &Buffer, row: u16) -> Vec<StyledRun> {
+    let mut runs: Vec<StyledRun> = Vec::new();
+    let mut column = 0;
+    while column < buffer.area.width {
+        let Some(cell) = buffer.cell(Position::new(buffer.area.x + column, buffer.area.y + row))
+        else {
+            break;
+        };
+        let cell_width = u16::try_from(cell.symbol().width()).unwrap_or(1).max(1);
+        match runs.last_mut() {
+            Some(run) if same_style(run, cell) => {
+                run.text.push_str(cell.symbol());
+                run.width += cell_width;
+            }
+            _ => runs.push(StyledRun {
+                column,
+                width: cell_width,
+                text: cell.symbol().to_owned(),
+                foreground: cell.fg,
+                background: cell.bg,
+                modifier: cell.modifier,
+            }),
+        }
+        column += cell_width;
+    }
+    runs
+}
+
+/// Whether `cell` continues `run`
+fn same_style(run: &StyledRun, cell: &Cell) -> bool {
+    run.foreground == cell.fg && run.background == cell.bg && run.modifier == cell.modifier
+}
+
+/// SVG attributes for the text modifiers
+fn text_attributes(modifier: Modifier) -> String {
+    let mut attributes = String::new();
+    if modifier.contains(Modifier::BOLD) {
+        attributes.push_str(" font-weight=\"bold\"");
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        attributes.push_str(" font-style=\"italic\"");
+    }
+    if modifier.contains(Modifier::DIM) {
+        _ = write!(attributes, " fill-opacity=\"{DIM_OPACITY}\"");
+    }
+    if modifier.contains(Modifier::UNDERLINED) {
+        attributes.push_str(" text-decoration=\"underline\"");
+    }
+    if modifier.contains(Modifier::CROSSED_OUT) {
+        attributes.push_str(" text-decoration=\"line-through\"");
+    }
+    attributes
+}
+
+/// Hex notation of a terminal color, with `Reset` standing for `default`
+fn hex_color(color: Color, default: Color) -> String {
+    let (red, green, blue) = match color {
+        Color::Reset if default == Color::Reset => ANSI_COLORS[15],
+        Color::Reset => return hex_color(default, Color::Reset),
+        Color::Rgb(red, green, blue) => (red, green, blue),
+        Color::Indexed(index) => indexed_rgb(index),
+        Color::Black => ANSI_COLORS[0],
+        Color::Red => ANSI_COLORS[1],
+        Color::Green => ANSI_COLORS[2],
+        Color::Yellow => ANSI_COLORS[3],
+        Color::Blue => ANSI_COLORS[4],
+        Color::Magenta => ANSI_COLORS[5],
+        Color::Cyan => ANSI_COLORS[6],
+        Color::Gray => ANSI_COLORS[7],
+        Color::DarkGray => ANSI_COLORS[8],
+        Color::LightRed => ANSI_COLORS[9],
+        Color::LightGreen => ANSI_COLORS[10],
+        Color::LightYellow => ANSI_COLORS[11],
+        Color::LightBlue => ANSI_COLORS[12],
+        Color::LightMagenta => ANSI_COLORS[13],
+        Color::LightCyan => ANSI_COLORS[14],
+        Color::White => ANSI_COLORS[15],
+    };
+    format!("#{red:02x}{green:02x}{blue:02x}")
+}
+
+/// Color of an entry of the 256-color palette
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_COLORS[usize::from(index)],
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let cube = index - 16;
+            (level(cube / 36), level(cube / 6 % 6), level(cube % 6))
+        }
+        232..=255 => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// Escapes the characters with a meaning in XML
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+    use tempfile::TempDir;
+
+    /// Tests that styled spans become text elements with their colors and weight.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_buffer_to_svg_styles_spans() {
+        let theme = Theme::Nord;
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 20, 2));
+        buffer.set_string(0, 0, "fn <main>", Style::default());
+        buffer.set_string(
+            10,
+            0,
+            "ok",
+            Style::default()
+                .fg(theme.success())
+                .add_modifier(Modifier::BOLD),
+        );
+        buffer.set_string(0, 1, "sel", Style::default().bg(theme.highlight()));
+
+        let svg = buffer_to_svg(&buffer, theme);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"180\""));
+        assert!(svg.contains("fill=\"#2e3440\"/>"));
+        assert!(svg.contains("<text x=\"0\" y=\"14\" fill=\"#eceff4\">fn &lt;main&gt; </text>"));
+        assert!(
+            svg.contains("<text x=\"90\" y=\"14\" fill=\"#a3be8c\" font-weight=\"bold\">ok</text>")
+        );
+        assert!(
+            svg.contains("<rect x=\"0\" y=\"18\" width=\"27\" height=\"18\" fill=\"#88c0d0\"/>")
+        );
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    /// Tests that a wide character keeps later runs in their columns.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_wide_characters_keep_columns() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 1));
+        buffer.set_string(0, 0, "日x", Style::default());
+        buffer.set_string(3, 0, "y", Style::default().fg(Color::Red));
+
+        let runs = style_runs(&buffer, 0);
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0].text, "日x");
+        assert_eq!((runs[1].column, runs[1].text.as_str()), (3, "y"));
+    }
+
+    /// Tests that screenshots are saved under the screenshots directory.
+    ///
+    /// # Errors
+    /// Returns an error if the screenshot cannot be written or read back.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_save_screenshot() -> io::Result<()> {
+        let merlin_dir = TempDir::new()?;
+        let path = save_screenshot(merlin_dir.path(), "<svg/>", Local::now())?;
+
+        assert_eq!(
+            path.parent(),
+            Some(merlin_dir.path().join(SCREENSHOT_DIR).as_path())
+        );
+        assert_eq!(fs::read_to_string(path)?, "<svg/>");
+        Ok(())
+    }
+
+    /// Tests hex conversion of palette and reset colors.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_hex_color() {
+        assert_eq!(hex_color(Color::Reset, Color::Rgb(1, 2, 3)), "#010203");
+        assert_eq!(hex_color(Color::Indexed(196), Color::Reset), "#ff0000");
+        assert_eq!(hex_color(Color::Indexed(244), Color::Reset), "#808080");
+        assert_eq!(hex_color(Color::DarkGray, Color::Reset), "#7f7f7f");
+    }
+}
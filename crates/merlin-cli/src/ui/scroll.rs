@@ -1,7 +1,10 @@
 //! Scrolling utilities for UI components
 //!
 //! This module provides helper functions for consistent text line counting
-//! to avoid off-by-one errors in scroll calculations.
+//! to avoid off-by-one errors in scroll calculations, and the per-task output
+//! viewport used to preserve scroll position when switching tasks.
+
+use unicode_width::UnicodeWidthStr as _;
 
 /// Counts the number of lines in text content
 ///
@@ -20,6 +23,144 @@ pub fn count_text_lines(text: &str) -> u16 {
     text.lines().count() as u16
 }
 
+/// Returns the display width of the widest line in `text`
+pub fn max_line_width(text: &str) -> u16 {
+    let widest = text.lines().map(str::width).max().unwrap_or(0);
+    u16::try_from(widest).unwrap_or(u16::MAX)
+}
+
+/// Scroll state for a single task's output pane
+///
+/// While following, the viewport sticks to the bottom as new output arrives.
+/// Scrolling up pins the viewport and counts the lines added below it until
+/// the user scrolls back to the bottom. With word wrap off, the viewport also
+/// scrolls horizontally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputViewport {
+    /// Scroll offset from the top (ignored while following)
+    offset: u16,
+    /// Whether the viewport sticks to the bottom of the output
+    follow: bool,
+    /// Lines appended since the viewport was pinned
+    unseen_lines: usize,
+    /// Columns scrolled to the right (only used while word wrap is off)
+    column: u16,
+}
+
+impl Default for OutputViewport {
+    /// Creates a viewport in follow mode
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            follow: true,
+            unseen_lines: 0,
+            column: 0,
+        }
+    }
+}
+
+impl OutputViewport {
+    /// Returns the scroll offset to render, clamped to `max_scroll`
+    pub fn offset(&self, max_scroll: u16) -> u16 {
+        if self.follow {
+            max_scroll
+        } else {
+            self.offset.min(max_scroll)
+        }
+    }
+
+    /// Returns the number of lines appended below a pinned viewport
+    ///
+    /// Zero while following or when the pinned viewport already shows the bottom.
+    pub fn unseen_lines(&self, max_scroll: u16) -> usize {
+        if self.offset(max_scroll) < max_scroll {
+            self.unseen_lines
+        } else {
+            0
+        }
+    }
+
+    /// Returns the horizontal scroll offset to render, clamped to `max_column`
+    pub fn column(&self, max_column: u16) -> u16 {
+        self.column.min(max_column)
+    }
+
+    /// Scrolls left by `columns`
+    pub fn scroll_left(&mut self, columns: u16, max_column: u16) {
+        self.column = self.column(max_column).saturating_sub(columns);
+    }
+
+    /// Scrolls right by `columns`, stopping once the widest line ends at the right edge
+    pub fn scroll_right(&mut self, columns: u16, max_column: u16) {
+        self.column = self
+            .column(max_column)
+            .saturating_add(columns)
+            .min(max_column);
+    }
+
+    /// Scrolls up by `lines`, pinning the viewport
+    pub fn scroll_up(&mut self, lines: u16, max_scroll: u16) {
+        let target = self.offset(max_scroll).saturating_sub(lines);
+        self.set_offset(target, max_scroll);
+    }
+
+    /// Scrolls down by `lines`, resuming follow mode once the bottom is reached
+    pub fn scroll_down(&mut self, lines: u16, max_scroll: u16) {
+        let target = self.offset(max_scroll).saturating_add(lines);
+        self.set_offset(target, max_scroll);
+    }
+
+    /// Scrolls to the top of the output
+    pub fn scroll_to_top(&mut self, max_scroll: u16) {
+        self.set_offset(0, max_scroll);
+    }
+
+    /// Scrolls so that `line` is at the top of the viewport (or as close as possible)
+    pub fn scroll_to_line(&mut self, line: u16, max_scroll: u16) {
+        self.set_offset(line, max_scroll);
+    }
+
+    /// Scrolls to the bottom of the output and resumes follow mode
+    pub fn scroll_to_bottom(&mut self) {
+        self.follow = true;
+        self.offset = 0;
+        self.unseen_lines = 0;
+    }
+
+    /// Records lines appended to the output
+    pub fn record_new_lines(&mut self, count: usize) {
+        if !self.follow {
+            self.unseen_lines = self.unseen_lines.saturating_add(count);
+        }
+    }
+
+    /// Keeps a pinned viewport on the same text after `count` lines were removed above it
+    pub fn remove_lines_above(&mut self, count: usize) {
+        if !self.follow {
+            let count = u16::try_from(count).unwrap_or(u16::MAX);
+            self.offset = self.offset.saturating_sub(count);
+        }
+    }
+
+    /// Keeps a pinned viewport on the same text after `count` lines were inserted above it
+    pub fn insert_lines_above(&mut self, count: usize) {
+        if !self.follow {
+            let count = u16::try_from(count).unwrap_or(u16::MAX);
+            self.offset = self.offset.saturating_add(count);
+        }
+    }
+
+    /// Moves to `target`, following if it is at or past the bottom
+    fn set_offset(&mut self, target: u16, max_scroll: u16) {
+        if target >= max_scroll {
+            self.scroll_to_bottom();
+        } else {
+            self.offset = target;
+            self.follow = false;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -44,4 +185,66 @@ mod tests {
         assert_eq!(count_text_lines("hello\nworld"), 2);
         assert_eq!(count_text_lines("a\nb\nc\n"), 3);
     }
+
+    /// Tests that scrolling up pins the viewport and scrolling back down resumes follow mode.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_output_viewport_follow_mode() {
+        let mut viewport = OutputViewport::default();
+        assert_eq!(viewport.offset(20), 20);
+        // Following sticks to the bottom however far it moves
+        assert_eq!(viewport.offset(40), 40);
+
+        viewport.scroll_up(5, 20);
+        assert_eq!(viewport.offset(20), 15);
+        assert_eq!(viewport.offset(40), 15);
+
+        // New output does not move a pinned viewport
+        viewport.record_new_lines(3);
+        assert_eq!(viewport.offset(23), 15);
+        assert_eq!(viewport.unseen_lines(23), 3);
+
+        viewport.scroll_down(10, 23);
+        assert_eq!(viewport.offset(23), 23);
+        assert_eq!(viewport.offset(40), 40);
+        assert_eq!(viewport.unseen_lines(23), 0);
+    }
+
+    /// Tests that horizontal scrolling stays within the widest line.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_output_viewport_horizontal_scroll() {
+        assert_eq!(max_line_width("short\na much longer line\n"), 18);
+
+        let mut viewport = OutputViewport::default();
+        viewport.scroll_right(8, 10);
+        viewport.scroll_right(8, 10);
+        assert_eq!(viewport.column(10), 10);
+
+        // Narrower output clamps the offset without losing it
+        assert_eq!(viewport.column(4), 4);
+        viewport.scroll_left(8, 10);
+        assert_eq!(viewport.column(10), 2);
+        viewport.scroll_left(8, 10);
+        assert_eq!(viewport.column(10), 0);
+    }
+
+    /// Tests that scrolling within output too short to scroll keeps follow mode.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_output_viewport_without_overflow() {
+        let mut viewport = OutputViewport::default();
+        viewport.scroll_up(1, 0);
+        // Still following: the viewport moves with output that starts to overflow
+        assert_eq!(viewport.offset(5), 5);
+
+        viewport.record_new_lines(2);
+        assert_eq!(viewport.unseen_lines(0), 0);
+    }
 }
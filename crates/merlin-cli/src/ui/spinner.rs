@@ -0,0 +1,81 @@
+//! Animated indicator for running tasks
+//!
+//! The event loop ticks every [`SPINNER_INTERVAL`] while tasks are running and
+//! renders the frame of the current theme's spinner selected by the tick count.
+//! Finished tasks show a static marker instead.
+
+use std::time::Duration;
+
+use super::task_manager::TaskStatus;
+use super::theme::Theme;
+
+/// How often the spinner advances to its next frame
+pub const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Marker shown for completed tasks
+pub const COMPLETED_MARKER: &str = "[+]";
+
+/// Marker shown for failed tasks
+pub const FAILED_MARKER: &str = "[X]";
+
+/// Animation cycling through a fixed set of frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Spinner {
+    /// Frames in display order
+    frames: &'static [&'static str],
+}
+
+impl Spinner {
+    /// Creates a spinner cycling through `frames`
+    pub const fn new(frames: &'static [&'static str]) -> Self {
+        Self { frames }
+    }
+
+    /// Creates the spinner of `theme`
+    pub const fn for_theme(theme: Theme) -> Self {
+        Self::new(theme.spinner_frames())
+    }
+
+    /// Frame shown at `tick`, wrapping around after the last frame
+    pub fn frame(&self, tick: u64) -> &'static str {
+        let frame_count = self.frames.len() as u64;
+        if frame_count == 0 {
+            return "";
+        }
+        self.frames
+            .get((tick % frame_count) as usize)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Indicator for a task with `status`: the frame at `tick` while running, a static marker once finished
+    pub fn status_indicator(&self, status: TaskStatus, tick: u64) -> &'static str {
+        match status {
+            TaskStatus::Running => self.frame(tick),
+            TaskStatus::Completed => COMPLETED_MARKER,
+            TaskStatus::Failed => FAILED_MARKER,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that frames cycle with the tick and finished tasks show static markers.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_spinner_frames_cycle() {
+        let spinner = Spinner::for_theme(Theme::Monochrome);
+        let frames: Vec<_> = (0..5).map(|tick| spinner.frame(tick)).collect();
+        assert_eq!(frames, vec!["-", "\\", "|", "/", "-"]);
+        assert_eq!(Spinner::for_theme(Theme::Nord).frame(1), "⣽");
+
+        assert_eq!(spinner.status_indicator(TaskStatus::Running, 2), "|");
+        assert_eq!(spinner.status_indicator(TaskStatus::Completed, 2), "[+]");
+        assert_eq!(spinner.status_indicator(TaskStatus::Failed, 2), "[X]");
+        assert_eq!(Spinner::new(&[]).frame(3), "");
+    }
+}
@@ -1,10 +1,128 @@
-use merlin_core::ThreadId;
+use super::history_search::HistorySearch;
+use merlin_core::{AnswerSender, ThreadId};
 use merlin_routing::TaskId;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 /// Maximum number of conversation entries to retain
 const MAX_CONVERSATION_HISTORY: usize = 50;
 
+/// How long transient status notices stay visible
+const STATUS_NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+/// How task output is shown in the output pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Markdown rendered with headings, lists and code styling
+    #[default]
+    Markdown,
+    /// Output exactly as the agent produced it
+    Raw,
+}
+
+impl OutputFormat {
+    /// Returns the other format
+    #[must_use]
+    pub const fn toggled(self) -> Self {
+        match self {
+            Self::Markdown => Self::Raw,
+            Self::Raw => Self::Markdown,
+        }
+    }
+}
+
+/// How long lines are shown in the output pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputWrap {
+    /// Lines wrap at the pane edge
+    #[default]
+    Wrap,
+    /// Lines are cut at the pane edge and the pane scrolls horizontally
+    NoWrap,
+}
+
+impl OutputWrap {
+    /// Returns the other mode
+    #[must_use]
+    pub const fn toggled(self) -> Self {
+        match self {
+            Self::Wrap => Self::NoWrap,
+            Self::NoWrap => Self::Wrap,
+        }
+    }
+
+    /// Indicator shown in the output pane title
+    #[must_use]
+    pub const fn indicator(self) -> &'static str {
+        match self {
+            Self::Wrap => "[WRAP]",
+            Self::NoWrap => "[NO-WRAP]",
+        }
+    }
+}
+
+/// Panel drawn over the output pane
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InfoPanel {
+    /// No panel, the output pane is visible
+    #[default]
+    Hidden,
+    /// Session task statistics (`Ctrl+I`)
+    TaskStats,
+}
+
+/// Message picker for branching a thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchPicker {
+    /// Thread being branched
+    pub thread_id: ThreadId,
+    /// Index of the selected message in the thread's conversation
+    pub selected: usize,
+}
+
+/// Question a task asked the user, answered through the input area
+#[derive(Debug, Clone)]
+pub struct PendingQuestion {
+    /// Task waiting for the answer
+    pub task_id: TaskId,
+    /// Question text
+    pub question: String,
+    /// Suggested answers, picked by their number
+    pub options: Vec<String>,
+    /// Resumes the task with the answer
+    pub answer: AnswerSender,
+}
+
+impl PendingQuestion {
+    /// Answer for `input`: the option it numbers, else the input itself
+    #[must_use]
+    pub fn resolve(&self, input: &str) -> String {
+        input
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| number.checked_sub(1))
+            .and_then(|index| self.options.get(index))
+            .map_or_else(|| input.to_owned(), Clone::clone)
+    }
+
+    /// Question with its numbered options, as shown in the input title
+    #[must_use]
+    pub fn prompt(&self) -> String {
+        let options: Vec<String> = self
+            .options
+            .iter()
+            .enumerate()
+            .map(|(index, option)| format!("{}) {option}", index + 1))
+            .collect();
+        if options.is_empty() {
+            self.question.clone()
+        } else {
+            format!("{} [{}]", self.question, options.join(" "))
+        }
+    }
+}
+
 /// Main UI state
 #[derive(Default)]
 pub struct UiState {
@@ -12,6 +130,8 @@ pub struct UiState {
     pub active_task_id: Option<TaskId>,
     /// Set of currently running tasks
     pub active_running_tasks: HashSet<TaskId>,
+    /// Subtasks spawned but not started yet, with the task each was split from
+    pub spawned_subtasks: HashMap<TaskId, TaskId>,
     /// Task pending deletion
     pub pending_delete_task_id: Option<TaskId>,
     /// Whether tasks are currently loading
@@ -20,10 +140,16 @@ pub struct UiState {
     pub conversation_history: Vec<ConversationEntry>,
     /// Status message to display when processing input
     pub processing_status: Option<String>,
-    /// Vertical scroll offset for the output pane
-    pub output_scroll_offset: u16,
     /// Background embedding index progress (current, total)
     pub embedding_progress: Option<(u64, u64)>,
+    /// Whether background embedding indexing has finished
+    pub index_ready: bool,
+    /// Model that handled the most recently completed task
+    pub last_task_model: Option<String>,
+    /// Estimated cost in USD of every request made this session
+    pub session_cost: f64,
+    /// UI events merged or dropped because rendering fell behind this session
+    pub coalesced_events: u64,
     /// Task ID to continue conversation from (when submitting with a task selected)
     pub continuing_conversation_from: Option<TaskId>,
     /// Scroll offset for the task list (0 = bottom/newest, higher = scroll up to older)
@@ -32,16 +158,40 @@ pub struct UiState {
     pub expanded_conversations: HashSet<TaskId>,
     /// Set of task IDs with expanded steps (showing step details)
     pub expanded_steps: HashSet<TaskId>,
-    /// Per-task output scroll positions for preserving scroll when switching tasks
-    pub task_output_scroll: HashMap<TaskId, u16>,
-    /// Flag to auto-scroll output to bottom on next render
-    pub auto_scroll_output_to_bottom: bool,
     /// Currently active thread
     pub active_thread_id: Option<ThreadId>,
+    /// Thread search query (Some while the thread search bar is open)
+    pub thread_search_query: Option<String>,
+    /// Tag the thread list is filtered by
+    pub thread_tag_filter: Option<String>,
+    /// Tag selector input (Some while the tag selector is open)
+    pub thread_tag_input: Option<String>,
+    /// Thread being merged (Some while picking the merge target)
+    pub thread_merge_source: Option<ThreadId>,
+    /// New name for the selected thread (Some while renaming inline)
+    pub thread_rename_input: Option<String>,
+    /// Message picker shown in the output pane (Some while choosing where to branch)
+    pub thread_branch_picker: Option<BranchPicker>,
+    /// Search through past user messages (Some while the `Ctrl+R` search is open)
+    pub history_search: Option<HistorySearch>,
+    /// Panel drawn over the output pane until the next key press
+    pub info_panel: InfoPanel,
+    /// Spinner ticks elapsed, selecting the frame shown for running tasks
+    pub spinner_tick: u64,
     /// Pending user input waiting for running work to finish
     pub queued_input: Option<String>,
     /// Flag to cancel currently running work
     pub cancel_requested: bool,
+    /// How task output is shown in the output pane
+    pub output_format: OutputFormat,
+    /// Whether long lines wrap in the output pane
+    pub output_wrap: OutputWrap,
+    /// Transient notice shown in the input title (e.g. clipboard confirmation)
+    pub status_notice: Option<StatusNotice>,
+    /// Unreadable task and thread files moved to `.merlin/corrupt/` this session
+    pub quarantined_files: Vec<PathBuf>,
+    /// Questions asked by tasks, the first one answered by the next submitted input
+    pub pending_questions: VecDeque<PendingQuestion>,
 }
 
 impl UiState {
@@ -100,3 +250,23 @@ pub enum ConversationRole {
     /// System message
     System,
 }
+
+/// Short-lived status message
+pub struct StatusNotice {
+    /// Message text
+    pub message: String,
+    /// When the notice was raised
+    pub shown_at: Instant,
+}
+
+impl StatusNotice {
+    /// Creates a notice that is visible from `shown_at`
+    pub const fn new(message: String, shown_at: Instant) -> Self {
+        Self { message, shown_at }
+    }
+
+    /// Returns whether the notice should still be displayed at `now`
+    pub fn is_visible(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.shown_at) < STATUS_NOTICE_DURATION
+    }
+}
@@ -1,10 +1,13 @@
-use merlin_core::{ThreadId, WorkUnit};
+use super::output_buffer::{OutputLimits, OutputSpill};
+use super::scroll::OutputViewport;
+use merlin_core::{SharedClock, SystemClock, ThreadId, TokenUsage, WorkUnit};
 use merlin_routing::TaskId;
 use merlin_routing::TaskProgress;
+use serde_json::Value;
 use std::{
     collections::HashMap,
     sync::Arc,
-    time::{Instant, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
 use tokio::sync::Mutex;
 
@@ -28,24 +31,43 @@ pub struct TaskDisplay {
     pub status: TaskStatus,
     /// Optional progress information
     pub progress: Option<TaskProgress>,
-    /// Output lines from the task
+    /// Output lines from the task (the newest ones once the output is bounded)
     pub output_lines: Vec<String>,
+    /// Output lines moved out of memory into the task's spill file
+    pub spill: OutputSpill,
     /// When the task was created (persists across program runs)
     pub created_at: SystemTime,
-    /// When the task was created/started (monotonic clock, used for sorting within a program run)
+    /// When the task was created/started (read from the task manager's clock when added)
     pub timestamp: Instant,
+    /// When the task completed or failed this session (read from the task manager's clock)
+    pub end_time: Option<Instant>,
+    /// Tokens used by the task, once completed this session
+    pub tokens_used: Option<TokenUsage>,
+    /// Whether the task's result passed validation, once completed this session
+    pub validation_passed: Option<bool>,
     /// Thread this task belongs to
     pub thread_id: Option<ThreadId>,
+    /// Task this one continues from, if it is a follow-up
+    pub parent_id: Option<TaskId>,
+    /// Task this one was split from, if it is a subtask
+    pub subtask_of: Option<TaskId>,
     /// Plain text output
     pub output: String,
     /// List of task steps
     pub steps: Vec<TaskStepInfo>,
     /// Currently active step (shown as visual subtask in UI)
     pub current_step: Option<TaskStepInfo>,
+    /// Running step of the task's plan as (step number, step count, title),
+    /// pinned above the output while the task runs
+    pub plan_step: Option<(usize, usize, String)>,
     /// Retry count (0 = first attempt, increments on each retry)
     pub retry_count: u32,
     /// Live `WorkUnit` reference during execution (for mid-execution verification)
     pub work_unit: Option<Arc<Mutex<WorkUnit>>>,
+    /// Output pane scroll state, preserved while other tasks are selected
+    pub viewport: OutputViewport,
+    /// Index of the fenced code block selected in the output pane
+    pub selected_code_block: Option<usize>,
 }
 
 impl Default for TaskDisplay {
@@ -55,27 +77,56 @@ impl Default for TaskDisplay {
     /// - Empty description
     /// - Status: Running
     /// - No progress
-    /// - Empty output
-    /// - Current timestamp
+    /// - Empty output, nothing spilled
+    /// - Current timestamp, not finished
+    /// - No token usage or validation result
     /// - Zero retry count
+    /// - Output viewport following new output
+    /// - No code block selected
     fn default() -> Self {
         Self {
             description: String::new(),
             status: TaskStatus::Running,
             progress: None,
             output_lines: Vec::new(),
+            spill: OutputSpill::default(),
             created_at: SystemTime::now(),
             timestamp: Instant::now(),
+            end_time: None,
+            tokens_used: None,
+            validation_passed: None,
             thread_id: None,
+            parent_id: None,
+            subtask_of: None,
             output: String::new(),
             steps: Vec::new(),
             current_step: None,
+            plan_step: None,
             retry_count: 0,
             work_unit: None,
+            viewport: OutputViewport::default(),
+            selected_code_block: None,
         }
     }
 }
 
+impl TaskDisplay {
+    /// Step of the plan the task is running, as "Step 2/4: title"
+    pub fn plan_step_indicator(&self) -> Option<String> {
+        if self.status != TaskStatus::Running {
+            return None;
+        }
+        let (step, total, title) = self.plan_step.as_ref()?;
+        Some(format!("Step {step}/{total}: {title}"))
+    }
+
+    /// How long the task ran, if it finished this session
+    pub fn duration(&self) -> Option<Duration> {
+        self.end_time
+            .map(|end_time| end_time.saturating_duration_since(self.timestamp))
+    }
+}
+
 /// Status of a task step
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TaskStepStatus {
@@ -88,12 +139,22 @@ pub enum TaskStepStatus {
 }
 
 /// Task step information
-#[derive(Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TaskStepInfo {
     /// Unique identifier for this step
     pub step_id: String,
+    /// Type of step (e.g., `thinking`, `tool_call`)
+    pub step_type: String,
+    /// Step content
+    pub content: String,
     /// Status of the step
     pub status: TaskStepStatus,
+    /// When the step started
+    pub started_at: SystemTime,
+    /// When the step completed or failed
+    pub completed_at: Option<SystemTime>,
+    /// Tool calls made while this step was running, in call order
+    pub tool_calls: Vec<ToolCallInfo>,
 }
 
 impl Default for TaskStepInfo {
@@ -101,25 +162,93 @@ impl Default for TaskStepInfo {
     fn default() -> Self {
         Self {
             step_id: String::new(),
+            step_type: String::new(),
+            content: String::new(),
             status: TaskStepStatus::Running,
+            started_at: SystemTime::now(),
+            completed_at: None,
+            tool_calls: Vec::new(),
         }
     }
 }
 
+/// Tool call made during a task step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolCallInfo {
+    /// Name of the tool
+    pub tool: String,
+    /// Arguments passed to the tool
+    pub args: Value,
+    /// Result returned by the tool, once completed
+    pub result: Option<Value>,
+    /// When the call started
+    pub started_at: SystemTime,
+    /// When the call completed
+    pub completed_at: Option<SystemTime>,
+}
+
 /// Manages task storage and ordering
-#[derive(Default)]
 pub struct TaskManager {
     tasks: HashMap<TaskId, TaskDisplay>,
     task_order: Vec<TaskId>,
+    /// Clock task start times and running times are read from
+    clock: SharedClock,
+    /// Bounds on the output and steps kept per task
+    output_limits: OutputLimits,
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        Self::with_clock(SystemClock::shared())
+    }
 }
 
 impl TaskManager {
-    /// Adds a task to the manager
-    pub fn add_task(&mut self, task_id: TaskId, task: TaskDisplay) {
+    /// Creates an empty manager reading the time from `clock`
+    pub fn with_clock(clock: SharedClock) -> Self {
+        Self {
+            tasks: HashMap::new(),
+            task_order: Vec::new(),
+            clock,
+            output_limits: OutputLimits::default(),
+        }
+    }
+
+    /// Bounds on the output and steps kept per task
+    pub const fn output_limits(&self) -> &OutputLimits {
+        &self.output_limits
+    }
+
+    /// Sets the bounds on the output and steps kept per task
+    pub const fn set_output_limits(&mut self, limits: OutputLimits) {
+        self.output_limits = limits;
+    }
+
+    /// Clock task times are read from
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Adds a task to the manager, starting it now
+    pub fn add_task(&mut self, task_id: TaskId, mut task: TaskDisplay) {
+        task.timestamp = self.clock.now();
         self.tasks.insert(task_id, task);
         self.task_order.push(task_id);
     }
 
+    /// Time since the task started
+    pub fn elapsed(&self, task: &TaskDisplay) -> Duration {
+        self.clock.elapsed_since(task.timestamp)
+    }
+
+    /// Marks the task as finished now
+    pub fn finish_task(&mut self, task_id: TaskId) {
+        let now = self.clock.now();
+        if let Some(task) = self.tasks.get_mut(&task_id) {
+            task.end_time = Some(now);
+        }
+    }
+
     /// Inserts a task into the `HashMap` only, without updating `task_order`
     /// Used during bulk loading - call `rebuild_order()` after all tasks are inserted
     pub fn insert_task_for_load(&mut self, task_id: TaskId, task: TaskDisplay) {
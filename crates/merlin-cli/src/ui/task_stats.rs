@@ -0,0 +1,189 @@
+//! Session statistics shown in the `Ctrl+I` info panel
+//!
+//! Computed from the tasks finished this session: how long each ran, the
+//! tokens they used and whether their results passed validation. Tasks loaded
+//! from earlier sessions have no end time or token usage and are left out.
+
+use std::time::Duration;
+
+use super::task_manager::TaskManager;
+
+/// Bar characters from one eighth of a cell to a full cell
+pub const BAR_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Statistics of the tasks finished this session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskStats {
+    /// Running time of each finished task, oldest first
+    pub durations: Vec<Duration>,
+    /// Tokens used by all completed tasks
+    pub total_tokens: u64,
+    /// Estimated cost in USD of every request made this session
+    pub session_cost: f64,
+    /// Completed tasks whose result passed validation
+    pub validation_passed: usize,
+    /// Completed tasks whose result failed validation
+    pub validation_failed: usize,
+}
+
+impl TaskStats {
+    /// Collects the statistics of the tasks in `task_manager`
+    pub fn collect(task_manager: &TaskManager, session_cost: f64) -> Self {
+        let mut stats = Self {
+            session_cost,
+            ..Self::default()
+        };
+        for task in task_manager
+            .task_order()
+            .iter()
+            .filter_map(|task_id| task_manager.get_task(*task_id))
+        {
+            if let Some(duration) = task.duration() {
+                stats.durations.push(duration);
+            }
+            if let Some(tokens) = &task.tokens_used {
+                stats.total_tokens += tokens.total();
+            }
+            match task.validation_passed {
+                Some(true) => stats.validation_passed += 1,
+                Some(false) => stats.validation_failed += 1,
+                None => {}
+            }
+        }
+        stats
+    }
+
+    /// Mean running time of the finished tasks
+    pub fn average_duration(&self) -> Option<Duration> {
+        let count = u32::try_from(self.durations.len()).ok()?;
+        (count > 0).then(|| self.durations.iter().sum::<Duration>() / count)
+    }
+
+    /// Fraction of validated results that passed
+    pub fn pass_rate(&self) -> Option<f64> {
+        let validated = self.validation_passed + self.validation_failed;
+        (validated > 0).then(|| self.validation_passed as f64 / validated as f64)
+    }
+}
+
+/// Draws one bar per duration, `height` rows tall and scaled to the longest
+///
+/// Rows are returned top first. Each cell is filled in eighths using
+/// [`BAR_BLOCKS`], and every task gets at least the lowest block so that
+/// instant tasks remain visible. Bars are separated by a space.
+pub fn duration_chart(durations: &[Duration], height: usize) -> Vec<String> {
+    let longest = durations.iter().max().copied().unwrap_or_default();
+    let eighths: Vec<usize> = durations
+        .iter()
+        .map(|duration| {
+            let scale = if longest.is_zero() {
+                0.0
+            } else {
+                duration.as_secs_f64() / longest.as_secs_f64()
+            };
+            ((scale * (height * 8) as f64).round() as usize).max(1)
+        })
+        .collect();
+
+    (0..height)
+        .rev()
+        .map(|row| {
+            eighths
+                .iter()
+                .map(|filled| match filled.saturating_sub(row * 8).min(8) {
+                    0 => ' ',
+                    cell => BAR_BLOCKS[cell - 1],
+                })
+                .flat_map(|bar| [bar, ' '])
+                .collect::<String>()
+                .trim_end()
+                .to_owned()
+        })
+        .collect()
+}
+
+/// Formats a duration as seconds below a minute and minutes and seconds above
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::task_manager::{TaskDisplay, TaskStatus};
+    use merlin_core::{TokenUsage, VirtualClock};
+    use merlin_routing::TaskId;
+
+    /// Tests that statistics cover only tasks finished this session.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_collect_task_stats() {
+        let clock = VirtualClock::new();
+        let mut manager = TaskManager::with_clock(clock.shared());
+        let (passed, failed, running) = (TaskId::default(), TaskId::default(), TaskId::default());
+        for task_id in [passed, failed, running] {
+            manager.add_task(task_id, TaskDisplay::default());
+        }
+
+        clock.advance(Duration::from_secs(2));
+        manager.finish_task(passed);
+        clock.advance(Duration::from_secs(4));
+        manager.finish_task(failed);
+        if let Some(task) = manager.get_task_mut(passed) {
+            task.status = TaskStatus::Completed;
+            task.validation_passed = Some(true);
+            task.tokens_used = Some(TokenUsage {
+                input: 100,
+                output: 20,
+                ..TokenUsage::default()
+            });
+        }
+        if let Some(task) = manager.get_task_mut(failed) {
+            task.validation_passed = Some(false);
+        }
+
+        let stats = TaskStats::collect(&manager, 0.25);
+        assert_eq!(
+            stats.durations,
+            vec![Duration::from_secs(2), Duration::from_secs(6)]
+        );
+        assert_eq!(stats.total_tokens, 120);
+        assert_eq!(stats.average_duration(), Some(Duration::from_secs(4)));
+        assert_eq!(stats.pass_rate(), Some(0.5));
+        assert_eq!(TaskStats::default().average_duration(), None);
+        assert_eq!(TaskStats::default().pass_rate(), None);
+    }
+
+    /// Tests bar heights in eighths of a row, scaled to the longest duration.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_duration_chart() {
+        let durations = [
+            Duration::from_secs(8),
+            Duration::from_secs(3),
+            Duration::ZERO,
+        ];
+        assert_eq!(duration_chart(&durations, 1), vec!["█ ▃ ▁"]);
+        assert_eq!(duration_chart(&durations, 2), vec!["█", "█ ▆ ▁"]);
+        assert!(duration_chart(&[], 3).iter().all(String::is_empty));
+    }
+
+    /// Tests duration formatting below and above a minute.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(2_500)), "2.5s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 05s");
+    }
+}
@@ -0,0 +1,261 @@
+//! Terminal setup that is undone on every exit path
+//!
+//! [`TerminalGuard::enter`] puts the terminal into raw mode for the TUI. The
+//! terminal is restored when the guard is dropped, so errors bubbling out of
+//! the event loop leave a usable shell, and a panic hook restores it before
+//! the panic is reported. Panic messages and backtraces are appended to
+//! `.merlin/crash.log`.
+
+use std::backtrace::Backtrace;
+use std::fs::OpenOptions;
+use std::io::{self, Write, stderr, stdout};
+use std::panic::{self, PanicHookInfo};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, Once};
+use std::thread::{self, ThreadId};
+
+use chrono::{DateTime, Local};
+use crossterm::cursor::Show;
+use crossterm::event::{
+    DisableFocusChange, EnableFocusChange, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
+use crossterm::terminal::{self, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use merlin_core::sync::IgnoreLock as _;
+
+/// File under `.merlin/` panics of the TUI are written to
+pub const CRASH_LOG: &str = "crash.log";
+
+/// Terminal currently owned by a guard
+#[derive(Clone)]
+struct ActiveTerminal {
+    /// Thread running the TUI, whose panics end the session
+    thread: ThreadId,
+    /// Crash log panics are appended to
+    crash_log: PathBuf,
+}
+
+/// Terminal owned by the live guard, read by the panic hook
+static ACTIVE_TERMINAL: Mutex<Option<ActiveTerminal>> = Mutex::new(None);
+
+/// Installs the panic hook once per process
+static PANIC_HOOK: Once = Once::new();
+
+/// Restores the terminal when dropped
+///
+/// Created on the thread running the TUI. Panics on other threads are logged
+/// but leave the terminal alone, since the TUI keeps running after them.
+pub struct TerminalGuard {
+    /// Whether the terminal was already restored
+    restored: bool,
+}
+
+impl TerminalGuard {
+    /// Enables raw mode, focus change reporting and, where supported, unambiguous key codes
+    ///
+    /// Panics from here on restore the terminal and are appended to `crash_log`.
+    ///
+    /// # Errors
+    /// Returns an error if the terminal cannot be configured; whatever was
+    /// already enabled is restored
+    pub fn enter(crash_log: PathBuf) -> io::Result<Self> {
+        PANIC_HOOK.call_once(install_panic_hook);
+        *ACTIVE_TERMINAL.lock_ignore_poison() = Some(ActiveTerminal {
+            thread: thread::current().id(),
+            crash_log,
+        });
+        let guard = Self { restored: false };
+
+        terminal::enable_raw_mode()?;
+        // Focus events let notifications stay quiet while the TUI is in front
+        execute!(stdout(), EnableFocusChange)?;
+        // Without disambiguation terminals send Ctrl+I as Tab
+        if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            execute!(
+                stdout(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+            )?;
+        }
+        Ok(guard)
+    }
+
+    /// Restores the terminal, reporting failures the drop would ignore
+    ///
+    /// # Errors
+    /// Returns an error if the restore sequence cannot be written or raw mode cannot be disabled
+    pub fn restore(mut self) -> io::Result<()> {
+        self.restored = true;
+        release_terminal()
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if !self.restored {
+            drop(release_terminal());
+        }
+    }
+}
+
+/// Writes the escape sequences undoing the TUI's terminal setup to `out`
+///
+/// Pops the keyboard enhancement flags, disables focus reporting, leaves the
+/// alternate screen and shows the cursor. Tests can pass a buffer to check
+/// the sequence.
+///
+/// # Errors
+/// Returns an error if writing to `out` fails
+pub fn write_restore_sequence(out: &mut impl Write) -> io::Result<()> {
+    queue!(
+        out,
+        PopKeyboardEnhancementFlags,
+        DisableFocusChange,
+        LeaveAlternateScreen,
+        Show
+    )?;
+    out.flush()
+}
+
+/// Formats a crash log entry
+pub fn crash_report(message: &str, backtrace: &Backtrace, crashed_at: DateTime<Local>) -> String {
+    format!(
+        "=== Crash at {} ===\n{message}\n\nBacktrace:\n{backtrace}\n",
+        crashed_at.format("%Y-%m-%d %H:%M:%S%.3f")
+    )
+}
+
+/// Appends `report` to the crash log at `path`
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or written
+pub fn append_crash_log(path: &Path, report: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{report}")
+}
+
+/// Releases the terminal from the active guard and restores it
+///
+/// # Errors
+/// Returns an error if the restore sequence cannot be written or raw mode cannot be disabled
+fn release_terminal() -> io::Result<()> {
+    ACTIVE_TERMINAL.lock_ignore_poison().take();
+    let written = write_restore_sequence(&mut stdout());
+    terminal::disable_raw_mode()?;
+    written
+}
+
+/// Chains a hook restoring the terminal and logging the panic before the previous hook
+fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let active = ACTIVE_TERMINAL.lock_ignore_poison().clone();
+        let Some(active) = active else {
+            previous(info);
+            return;
+        };
+        let report = crash_report(
+            &panic_message(info),
+            &Backtrace::force_capture(),
+            Local::now(),
+        );
+        let logged = append_crash_log(&active.crash_log, &report);
+        if thread::current().id() != active.thread {
+            previous(info);
+            return;
+        }
+
+        drop(release_terminal());
+        let mut err = stderr();
+        drop(err.write_all(report.as_bytes()));
+        drop(match logged {
+            Ok(()) => writeln!(err, "Crash report saved to {}", active.crash_log.display()),
+            Err(log_err) => writeln!(err, "Failed to write crash report: {log_err}"),
+        });
+    }));
+}
+
+/// Panic payload and location, like the default hook prints them
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_owned())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Box<dyn Any>".to_owned());
+    let thread = thread::current();
+    let location = info
+        .location()
+        .map_or_else(String::new, |location| format!(" at {location}"));
+    format!(
+        "thread '{}' panicked{location}:\n{payload}",
+        thread.name().unwrap_or("<unnamed>")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::read_to_string;
+    use tempfile::TempDir;
+
+    /// Tests that the restore sequence shows the cursor and leaves the alternate screen.
+    ///
+    /// # Errors
+    /// Returns an error if the sequence cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_write_restore_sequence() -> io::Result<()> {
+        let mut out = Vec::new();
+        write_restore_sequence(&mut out)?;
+        let sequence = String::from_utf8_lossy(&out);
+
+        assert!(
+            sequence.contains("\x1b[<1u"),
+            "pops keyboard flags: {sequence:?}"
+        );
+        assert!(
+            sequence.contains("\x1b[?1004l"),
+            "disables focus: {sequence:?}"
+        );
+        assert!(
+            sequence.contains("\x1b[?1049l"),
+            "leaves alternate screen: {sequence:?}"
+        );
+        assert!(
+            sequence.ends_with("\x1b[?25h"),
+            "shows cursor last: {sequence:?}"
+        );
+        Ok(())
+    }
+
+    /// Tests that crash reports are appended with the message and backtrace.
+    ///
+    /// # Errors
+    /// Returns an error if the crash log cannot be written or read.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_append_crash_log() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join(CRASH_LOG);
+        let backtrace = Backtrace::disabled();
+        append_crash_log(
+            &path,
+            &crash_report("first panic", &backtrace, Local::now()),
+        )?;
+        append_crash_log(
+            &path,
+            &crash_report("second panic", &backtrace, Local::now()),
+        )?;
+
+        let log = read_to_string(&path)?;
+        assert_eq!(log.matches("=== Crash at ").count(), 2);
+        assert!(log.contains("first panic\n\nBacktrace:\n"));
+        assert!(log.find("first panic") < log.find("second panic"));
+        Ok(())
+    }
+}
@@ -1,8 +1,17 @@
+use dirs::home_dir;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml::from_str;
 
 /// UI theme configuration
+///
+/// Built-in themes are recorded in the config by name; the custom theme is
+/// recorded as `"custom"` and its colors are loaded from `~/.merlin/theme.toml`.
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ThemeName", into = "ThemeName")]
 pub enum Theme {
     /// Nord color palette
     Nord,
@@ -17,24 +26,185 @@ pub enum Theme {
     Catppuccin,
     /// Monochrome color palette
     Monochrome,
+    /// User-defined palette from `~/.merlin/theme.toml`
+    Custom(CustomTheme),
+}
+
+/// Name a theme is recorded under in the config
+#[derive(Serialize, Deserialize)]
+enum ThemeName {
+    Nord,
+    Dracula,
+    Gruvbox,
+    TokyoNight,
+    Catppuccin,
+    Monochrome,
+    #[serde(rename = "custom", alias = "Custom")]
+    Custom,
+}
+
+impl From<ThemeName> for Theme {
+    fn from(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Nord => Self::Nord,
+            ThemeName::Dracula => Self::Dracula,
+            ThemeName::Gruvbox => Self::Gruvbox,
+            ThemeName::TokyoNight => Self::TokyoNight,
+            ThemeName::Catppuccin => Self::Catppuccin,
+            ThemeName::Monochrome => Self::Monochrome,
+            // Colors are loaded separately, see `Theme::with_custom_colors`
+            ThemeName::Custom => Self::Custom(CustomTheme::default()),
+        }
+    }
+}
+
+impl From<Theme> for ThemeName {
+    fn from(theme: Theme) -> Self {
+        match theme {
+            Theme::Nord => Self::Nord,
+            Theme::Dracula => Self::Dracula,
+            Theme::Gruvbox => Self::Gruvbox,
+            Theme::TokyoNight => Self::TokyoNight,
+            Theme::Catppuccin => Self::Catppuccin,
+            Theme::Monochrome => Self::Monochrome,
+            Theme::Custom(_) => Self::Custom,
+        }
+    }
+}
+
+/// Colors of the custom theme as RGB triples
+///
+/// Colors missing from the theme file keep their Tokyo Night value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomTheme {
+    /// Border of the focused pane
+    pub focused_border: [u8; 3],
+    /// Border of unfocused panes
+    pub unfocused_border: [u8; 3],
+    /// Regular text
+    pub text: [u8; 3],
+    /// Completed work
+    pub success: [u8; 3],
+    /// Failed work
+    pub error: [u8; 3],
+    /// In-progress or retrying work
+    pub warning: [u8; 3],
+    /// Selected items and code blocks
+    pub highlight: [u8; 3],
+}
+
+impl Default for CustomTheme {
+    fn default() -> Self {
+        Self {
+            focused_border: [122, 162, 247],
+            unfocused_border: [86, 95, 137],
+            text: [192, 202, 245],
+            success: [158, 206, 106],
+            error: [247, 118, 142],
+            warning: [224, 175, 104],
+            highlight: [122, 162, 247],
+        }
+    }
+}
+
+impl CustomTheme {
+    /// Gets the custom theme file path (~/.merlin/theme.toml)
+    pub fn path() -> Option<PathBuf> {
+        home_dir().map(|home| home.join(".merlin").join("theme.toml"))
+    }
+
+    /// Loads the custom theme from `~/.merlin/theme.toml`
+    ///
+    /// # Errors
+    /// Returns an error if the home directory is unknown or the file cannot be read or parsed
+    pub fn load() -> io::Result<Self> {
+        let path = Self::path().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "Could not determine home directory",
+            )
+        })?;
+        Self::load_from(&path)
+    }
+
+    /// Loads a custom theme file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid theme
+    pub fn load_from(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path)?;
+        from_str(&content).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid theme file {}: {err}", path.display()),
+            )
+        })
+    }
+}
+
+/// Converts an RGB triple to a color
+const fn rgb([red, green, blue]: [u8; 3]) -> Color {
+    Color::Rgb(red, green, blue)
 }
 
 impl Theme {
+    /// Gets the name the theme is recorded under in the config
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Nord => "Nord",
+            Self::Dracula => "Dracula",
+            Self::Gruvbox => "Gruvbox",
+            Self::TokyoNight => "TokyoNight",
+            Self::Catppuccin => "Catppuccin",
+            Self::Monochrome => "Monochrome",
+            Self::Custom(_) => "custom",
+        }
+    }
+
     /// Gets the next theme in sequence
+    ///
+    /// The custom theme follows Monochrome when `~/.merlin/theme.toml` can be loaded.
     #[must_use]
     pub fn next(self) -> Self {
+        self.next_with(CustomTheme::load().ok())
+    }
+
+    /// Gets the next theme in sequence, with `custom` as the custom theme if available
+    #[must_use]
+    pub fn next_with(self, custom: Option<CustomTheme>) -> Self {
         match self {
             Self::Nord => Self::Dracula,
             Self::Dracula => Self::Gruvbox,
             Self::Gruvbox => Self::TokyoNight,
             Self::TokyoNight => Self::Catppuccin,
             Self::Catppuccin => Self::Monochrome,
-            Self::Monochrome => Self::Nord,
+            Self::Monochrome => custom.map_or(Self::Nord, Self::Custom),
+            Self::Custom(_) => Self::Nord,
+        }
+    }
+
+    /// Loads the colors of the custom theme from `~/.merlin/theme.toml`
+    ///
+    /// Built-in themes are returned unchanged. If the file cannot be loaded,
+    /// the default theme is used instead.
+    #[must_use]
+    pub fn with_custom_colors(self) -> Self {
+        match self {
+            Self::Custom(_) => CustomTheme::load().map_or_else(
+                |err| {
+                    tracing::warn!("Failed to load custom theme, using the default theme: {err}");
+                    Self::default()
+                },
+                Self::Custom,
+            ),
+            builtin => builtin,
         }
     }
 
     /// Gets the focused border color
-    pub fn focused_border(self) -> Color {
+    pub const fn focused_border(self) -> Color {
         match self {
             Self::Nord => Color::Rgb(136, 192, 208),
             Self::Dracula => Color::Rgb(189, 147, 249),
@@ -42,11 +212,12 @@ impl Theme {
             Self::TokyoNight => Color::Rgb(122, 162, 247),
             Self::Catppuccin => Color::Rgb(137, 180, 250),
             Self::Monochrome => Color::Rgb(100, 200, 255),
+            Self::Custom(custom) => rgb(custom.focused_border),
         }
     }
 
     /// Gets the unfocused border color
-    pub fn unfocused_border(self) -> Color {
+    pub const fn unfocused_border(self) -> Color {
         match self {
             Self::Nord => Color::Rgb(216, 222, 233),
             Self::Dracula => Color::Rgb(98, 114, 164),
@@ -54,11 +225,12 @@ impl Theme {
             Self::TokyoNight => Color::Rgb(86, 95, 137),
             Self::Catppuccin => Color::Rgb(108, 112, 134),
             Self::Monochrome => Color::Rgb(128, 128, 128),
+            Self::Custom(custom) => rgb(custom.unfocused_border),
         }
     }
 
     /// Gets the text color
-    pub fn text(self) -> Color {
+    pub const fn text(self) -> Color {
         match self {
             Self::Nord => Color::Rgb(236, 239, 244),
             Self::Dracula => Color::Rgb(248, 248, 242),
@@ -66,11 +238,12 @@ impl Theme {
             Self::TokyoNight => Color::Rgb(192, 202, 245),
             Self::Catppuccin => Color::Rgb(205, 214, 244),
             Self::Monochrome => Color::Rgb(255, 255, 255),
+            Self::Custom(custom) => rgb(custom.text),
         }
     }
 
     /// Gets the success color (for completed work)
-    pub fn success(self) -> Color {
+    pub const fn success(self) -> Color {
         match self {
             Self::Nord => Color::Rgb(163, 190, 140),
             Self::Dracula => Color::Rgb(80, 250, 123),
@@ -78,11 +251,12 @@ impl Theme {
             Self::TokyoNight => Color::Rgb(158, 206, 106),
             Self::Catppuccin => Color::Rgb(166, 227, 161),
             Self::Monochrome => Color::Rgb(150, 150, 150),
+            Self::Custom(custom) => rgb(custom.success),
         }
     }
 
     /// Gets the error color (for failed work)
-    pub fn error(self) -> Color {
+    pub const fn error(self) -> Color {
         match self {
             Self::Nord => Color::Rgb(191, 97, 106),
             Self::Dracula => Color::Rgb(255, 85, 85),
@@ -90,11 +264,12 @@ impl Theme {
             Self::TokyoNight => Color::Rgb(247, 118, 142),
             Self::Catppuccin => Color::Rgb(243, 139, 168),
             Self::Monochrome => Color::Rgb(200, 200, 200),
+            Self::Custom(custom) => rgb(custom.error),
         }
     }
 
     /// Gets the warning color (for in-progress/retrying work)
-    pub fn warning(self) -> Color {
+    pub const fn warning(self) -> Color {
         match self {
             Self::Nord => Color::Rgb(235, 203, 139),
             Self::Dracula => Color::Rgb(241, 250, 140),
@@ -102,6 +277,118 @@ impl Theme {
             Self::TokyoNight => Color::Rgb(224, 175, 104),
             Self::Catppuccin => Color::Rgb(249, 226, 175),
             Self::Monochrome => Color::Rgb(180, 180, 180),
+            Self::Custom(custom) => rgb(custom.warning),
+        }
+    }
+
+    /// Gets the highlight color (for selected items and code blocks)
+    pub const fn highlight(self) -> Color {
+        match self {
+            Self::Custom(custom) => rgb(custom.highlight),
+            builtin => builtin.focused_border(),
+        }
+    }
+
+    /// Gets the background color, used where the terminal's own background is unknown (screenshots)
+    pub const fn background(self) -> Color {
+        match self {
+            Self::Nord => Color::Rgb(46, 52, 64),
+            Self::Dracula => Color::Rgb(40, 42, 54),
+            Self::Gruvbox => Color::Rgb(40, 40, 40),
+            Self::TokyoNight => Color::Rgb(26, 27, 38),
+            Self::Catppuccin => Color::Rgb(30, 30, 46),
+            Self::Monochrome => Color::Rgb(0, 0, 0),
+            Self::Custom(_) => Color::Rgb(30, 30, 30),
         }
     }
+
+    /// Gets the animation frames of the running task spinner
+    pub const fn spinner_frames(self) -> &'static [&'static str] {
+        match self {
+            Self::Nord | Self::Custom(_) => &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"],
+            Self::Dracula => &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            Self::Gruvbox => &["◐", "◓", "◑", "◒"],
+            Self::TokyoNight => &["▁", "▃", "▄", "▅", "▆", "▇", "█", "▇", "▆", "▅", "▄", "▃"],
+            Self::Catppuccin => &["◜", "◠", "◝", "◞", "◡", "◟"],
+            Self::Monochrome => &["-", "\\", "|", "/"],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as StdError;
+    use tempfile::TempDir;
+    use toml::to_string;
+
+    /// Tests loading a partial custom theme file, with defaults for missing colors.
+    ///
+    /// # Errors
+    /// Returns an error if the theme file cannot be written or loaded.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_load_custom_theme() -> Result<(), Box<dyn StdError>> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("theme.toml");
+        fs::write(&path, "text = [1, 2, 3]\nhighlight = [250, 0, 128]\n")?;
+
+        let theme = Theme::Custom(CustomTheme::load_from(&path)?);
+        assert_eq!(theme.text(), Color::Rgb(1, 2, 3));
+        assert_eq!(theme.highlight(), Color::Rgb(250, 0, 128));
+        assert_eq!(theme.error(), Theme::TokyoNight.error());
+
+        fs::write(&path, "txt = [1, 2, 3]\n")?;
+        let invalid = CustomTheme::load_from(&path);
+        assert!(matches!(invalid, Err(err) if err.kind() == io::ErrorKind::InvalidData));
+        Ok(())
+    }
+
+    /// Tests that the cycle includes the custom theme only when it is available.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_cycle_includes_available_custom_theme() {
+        let custom = CustomTheme::default();
+        assert_eq!(Theme::Monochrome.next_with(None), Theme::Nord);
+        assert_eq!(
+            Theme::Monochrome.next_with(Some(custom)),
+            Theme::Custom(custom)
+        );
+        assert_eq!(Theme::Custom(custom).next_with(Some(custom)), Theme::Nord);
+        assert_eq!(Theme::Custom(custom).name(), "custom");
+    }
+
+    /// Tests that the custom theme is recorded by name rather than by its colors.
+    ///
+    /// # Errors
+    /// Returns an error if serialization fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_custom_theme_serializes_as_name() -> Result<(), Box<dyn StdError>> {
+        #[derive(Serialize, Deserialize)]
+        struct Wrapper {
+            theme: Theme,
+        }
+
+        let custom = CustomTheme {
+            text: [1, 2, 3],
+            ..CustomTheme::default()
+        };
+        let serialized = to_string(&Wrapper {
+            theme: Theme::Custom(custom),
+        })?;
+        assert_eq!(serialized.trim(), r#"theme = "custom""#);
+
+        let reloaded: Wrapper = from_str(&serialized)?;
+        assert_eq!(reloaded.theme, Theme::Custom(CustomTheme::default()));
+        let builtin: Wrapper = from_str(r#"theme = "Nord""#)?;
+        assert_eq!(builtin.theme, Theme::Nord);
+        Ok(())
+    }
 }
@@ -0,0 +1,129 @@
+//! Thread list filtering
+//!
+//! Applies the inline thread search and tag filter to the active threads so that
+//! rendering and keyboard navigation always operate on the same list.
+
+use std::collections::HashMap;
+
+use merlin_agent::ThreadStore;
+use merlin_core::{Thread, ThreadId};
+
+use super::state::UiState;
+
+/// Returns the threads currently shown in the thread list
+///
+/// Without a search query this is every non-archived thread, most recent first,
+/// with branches listed right below the thread they were branched from.
+/// With a query, only non-archived threads matching it are returned, best match first.
+/// Either way, threads lacking the selected tag are hidden.
+pub fn visible_threads<'store>(store: &'store ThreadStore, state: &UiState) -> Vec<&'store Thread> {
+    let tag_filter = state.thread_tag_filter.as_deref();
+    let has_tag = |thread: &&Thread| tag_filter.is_none_or(|tag| thread.has_tag(tag));
+
+    let Some(query) = state
+        .thread_search_query
+        .as_deref()
+        .filter(|query| !query.trim().is_empty())
+    else {
+        return nest_branches(store.active_threads().into_iter().filter(has_tag).collect());
+    };
+
+    store
+        .search(query)
+        .iter()
+        .filter_map(|result| store.get_thread(result.thread_id))
+        .filter(|thread| !thread.archived)
+        .filter(has_tag)
+        .collect()
+}
+
+/// Returns how many listed ancestors each listed thread has, in list order
+///
+/// Only ancestors listed before a thread count, so branches are indented under
+/// their parent in the nested list and not at all in search results.
+pub fn branch_depths(threads: &[&Thread]) -> Vec<usize> {
+    let mut depths: HashMap<ThreadId, usize> = HashMap::new();
+    threads
+        .iter()
+        .map(|thread| {
+            let depth = thread
+                .parent_thread
+                .as_ref()
+                .and_then(|branch_point| depths.get(&branch_point.thread_id))
+                .map_or(0, |parent_depth| parent_depth + 1);
+            depths.insert(thread.id, depth);
+            depth
+        })
+        .collect()
+}
+
+/// Moves every branch right below its parent, keeping the order among siblings
+///
+/// Branches whose parent is not listed stay where they are.
+fn nest_branches(threads: Vec<&Thread>) -> Vec<&Thread> {
+    let listed: Vec<ThreadId> = threads.iter().map(|thread| thread.id).collect();
+    let mut children: HashMap<ThreadId, Vec<&Thread>> = HashMap::new();
+    let mut roots = Vec::new();
+    for thread in threads {
+        match &thread.parent_thread {
+            Some(branch_point) if listed.contains(&branch_point.thread_id) => children
+                .entry(branch_point.thread_id)
+                .or_default()
+                .push(thread),
+            _ => roots.push(thread),
+        }
+    }
+
+    let mut nested = Vec::with_capacity(listed.len());
+    let mut pending: Vec<&Thread> = roots.into_iter().rev().collect();
+    while let Some(thread) = pending.pop() {
+        nested.push(thread);
+        if let Some(branches) = children.remove(&thread.id) {
+            pending.extend(branches.into_iter().rev());
+        }
+    }
+    nested
+}
+
+/// Returns existing tags starting with the typed prefix, for autocompletion
+pub fn tag_suggestions(store: &ThreadStore, prefix: &str) -> Vec<String> {
+    let prefix = prefix.trim().to_lowercase();
+    store
+        .all_tags()
+        .into_iter()
+        .filter(|tag| tag.starts_with(&prefix))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::{MessageId, ThreadColor};
+
+    /// Tests that branches follow their parent, indented one level per listed ancestor.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_branches_nest_under_parents() {
+        let branch_of = |name: &str, parent: &Thread| {
+            Thread::branched_from(
+                name.to_owned(),
+                ThreadColor::Blue,
+                parent.id,
+                MessageId::default(),
+            )
+        };
+        let root = Thread::new("Root".to_owned(), ThreadColor::Blue);
+        let other = Thread::new("Other".to_owned(), ThreadColor::Green);
+        let branch = branch_of("Branch", &root);
+        let nested = branch_of("Nested", &branch);
+        let orphan = branch_of("Orphan", &Thread::new("Gone".to_owned(), ThreadColor::Red));
+
+        // Most recent first: the nested branch was updated last
+        let listed = nest_branches(vec![&nested, &other, &branch, &orphan, &root]);
+        let names: Vec<&str> = listed.iter().map(|thread| thread.name.as_str()).collect();
+        assert_eq!(names, ["Other", "Orphan", "Root", "Branch", "Nested"]);
+        assert_eq!(branch_depths(&listed), [0, 0, 0, 1, 2]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Utility functions for CLI operations
 
 use anyhow::{Context as _, Result};
+use std::cmp::Reverse;
 use std::env;
 use std::fs;
 use std::fs::canonicalize;
@@ -58,7 +59,7 @@ pub fn cleanup_old_tasks(merlin_dir: &Path) -> Result<()> {
         .collect();
 
     // Sort by modification time (newest first)
-    task_files.sort_by(|left, right| right.1.cmp(&left.1));
+    task_files.sort_by_key(|(_, modified)| Reverse(*modified));
 
     // Keep only the 50 most recent, delete the rest
     for (path, _) in task_files.iter().skip(MAX_TASKS) {
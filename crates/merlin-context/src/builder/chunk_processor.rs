@@ -14,6 +14,17 @@ pub type FileChunksMap = HashMap<PathBuf, Vec<(usize, usize, f32)>>;
 /// Type alias for file score information
 pub type FileScoreInfo = (PathBuf, f32, Option<f32>, Option<f32>);
 
+/// Splits a chunk result path (`file:start-end`) into the absolute file path and line range
+pub fn chunk_location(project_root: &Path, chunk_path: &Path) -> Option<(PathBuf, usize, usize)> {
+    let (file_part, range_part) = chunk_path.to_str()?.rsplit_once(':')?;
+    let (start, end) = range_part.split_once('-')?;
+    Some((
+        project_root.join(file_part),
+        start.parse().ok()?,
+        end.parse().ok()?,
+    ))
+}
+
 /// Merge overlapping chunks considering context expansion
 pub fn merge_overlapping_chunks(chunks: Vec<(usize, usize, f32)>) -> Vec<(usize, usize, f32)> {
     const CONTEXT_LINES: usize = 50;
@@ -107,10 +118,7 @@ pub fn extract_chunk_with_context(
         format!("--- Lines {context_start}-{context_end} ---\n{chunk_content}")
     };
 
-    Ok(FileContext {
-        path: file_path.clone(),
-        content: final_content,
-    })
+    Ok(FileContext::new(file_path.clone(), final_content))
 }
 
 /// Check if a chunk should be included based on size and score
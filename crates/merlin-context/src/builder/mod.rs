@@ -4,12 +4,21 @@ mod chunk_processor;
 mod file_scanner;
 mod search;
 mod system_init;
+mod watcher;
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use merlin_core::{Context, CoreResult as Result, FileContext, Query};
+use merlin_core::{Context, CoreResult as Result, Error, FileContext, Query};
+use merlin_languages::{
+    CacheStats, CrossLanguageResolver, IndexCacheStats, LanguageProvider, SearchQuery, SymbolInfo,
+    WorkspaceCache,
+};
 
-use crate::embedding::{ProgressCallback, VectorSearchManager};
+use crate::context_inclusion::MAX_CONTEXT_TOKENS;
+use crate::embedding::{NoOpEmbeddingProvider, ProgressCallback, VectorSearchManager};
+use crate::explanation::{ContextExplanation, FileExplanation, InclusionSource};
+use crate::pinned;
 use crate::query::{QueryAnalyzer, QueryIntent};
 
 /// Builds a `Context` by scanning files under a project root.
@@ -20,21 +29,60 @@ pub struct ContextBuilder {
     max_files: usize,
     /// Maximum file size in bytes to include
     max_file_size: usize,
+    /// Maximum number of tokens of context gathered by search
+    token_budget: usize,
+    /// Whether search results are reranked by their imports
+    rerank: bool,
+    /// Whether search embeds files for semantic search, or uses BM25 keywords only
+    embeddings_enabled: bool,
     /// Vector search manager for semantic search
     vector_manager: Option<VectorSearchManager>,
+    /// BM25 index searched instead of the vector manager while embeddings are disabled
+    keyword_index: Option<VectorSearchManager<NoOpEmbeddingProvider>>,
+    /// Where the language backend's parse results are cached between runs
+    index_cache: WorkspaceCache,
+    /// Language backend for symbol lookup, activated when the project contains supported files
+    language_backend: Option<Box<dyn LanguageProvider>>,
+    /// Links imports of the backend's language to native extension modules, if the project has any
+    cross_language: Option<CrossLanguageResolver>,
     /// Optional progress callback for embedding operations
     progress_callback: Option<ProgressCallback>,
+    /// Optional progress callback for background indexing, which outlives the task starting it
+    index_progress_callback: Option<ProgressCallback>,
+    /// Optional progress callback for downloading a missing embedding model
+    pull_progress_callback: Option<ProgressCallback>,
+    /// Whether a missing embedding model is downloaded before indexing
+    auto_pull: bool,
+    /// Watcher of changes made outside the agent and backends being reloaded
+    watch: watcher::WatchState,
+    /// Full vector index being built while queries use the partial one
+    background_index: Option<system_init::BackgroundIndex>,
+    /// Why each file of the last built context was included, keyed by its path
+    explanations: HashMap<PathBuf, FileExplanation>,
 }
 
 impl ContextBuilder {
     /// Create a new builder with defaults.
     pub fn new(project_root: PathBuf) -> Self {
         Self {
+            index_cache: WorkspaceCache::new(system_init::index_cache_dir(&project_root)),
             project_root,
             max_files: 50,
             max_file_size: 100_000,
+            token_budget: MAX_CONTEXT_TOKENS,
+            rerank: true,
+            embeddings_enabled: true,
             vector_manager: None,
+            keyword_index: None,
+            language_backend: None,
+            cross_language: None,
             progress_callback: None,
+            index_progress_callback: None,
+            pull_progress_callback: None,
+            auto_pull: true,
+            watch: watcher::WatchState::default(),
+            background_index: None,
+            explanations: HashMap::new(),
         }
     }
 
@@ -45,6 +93,65 @@ impl ContextBuilder {
         self
     }
 
+    /// Set the maximum number of files included in context (mutable update)
+    pub const fn set_max_files(&mut self, max_files: usize) {
+        self.max_files = max_files;
+    }
+
+    /// Set the maximum number of tokens of context gathered by search
+    ///
+    /// Defaults to [`MAX_CONTEXT_TOKENS`].
+    pub const fn set_token_budget(&mut self, token_budget: usize) {
+        self.token_budget = token_budget;
+    }
+
+    /// Enable or disable reranking search results by their imports
+    ///
+    /// Enabled by default. Without it, results keep the order of BM25 and
+    /// vector rank fusion.
+    pub const fn set_rerank(&mut self, rerank: bool) {
+        self.rerank = rerank;
+    }
+
+    /// Enable or disable embeddings
+    ///
+    /// Enabled by default. Without them, the embedding model is never
+    /// contacted and search ranks files by BM25 keywords alone, so context
+    /// can be built without Ollama.
+    #[must_use]
+    pub const fn with_embeddings(mut self, enabled: bool) -> Self {
+        self.embeddings_enabled = enabled;
+        self
+    }
+
+    /// Count the language index cache's hits, misses and evictions on `stats`
+    ///
+    /// Builders given clones of the same counters report their cache use together.
+    #[must_use]
+    pub fn with_index_cache_stats(mut self, stats: IndexCacheStats) -> Self {
+        self.index_cache = self.index_cache.with_stats(stats);
+        self
+    }
+
+    /// Returns the language index cache's hits, misses and evictions
+    pub fn index_cache_stats(&self) -> CacheStats {
+        self.index_cache.stats()
+    }
+
+    /// Use `backend` for symbol lookup instead of detecting the project's languages.
+    ///
+    /// The backend must already be initialized for the project root. Any
+    /// `LanguageProvider` works, including a `PolyglotBackend` combining
+    /// languages. Native extension modules are still indexed for cross-language
+    /// imports.
+    #[must_use]
+    pub fn with_language_backend(mut self, backend: Box<dyn LanguageProvider>) -> Self {
+        self.language_backend = Some(backend);
+        self.cross_language = Some(CrossLanguageResolver::new(&self.project_root))
+            .filter(|resolver| !resolver.is_empty());
+        self
+    }
+
     /// Set a progress callback for embedding operations (builder pattern)
     #[must_use]
     pub fn with_progress_callback(mut self, callback: ProgressCallback) -> Self {
@@ -57,11 +164,154 @@ impl ContextBuilder {
         self.progress_callback = Some(callback);
     }
 
+    /// Set the progress callback of background indexing
+    ///
+    /// Indexing starts with the first query and may finish after it, so this
+    /// callback should report to the UI rather than to a task.
+    pub fn set_index_progress_callback(&mut self, callback: ProgressCallback) {
+        self.index_progress_callback = Some(callback);
+    }
+
+    /// Set the progress callback of downloading a missing embedding model
+    pub fn set_pull_progress_callback(&mut self, callback: ProgressCallback) {
+        self.pull_progress_callback = Some(callback);
+    }
+
+    /// Enable or disable downloading a missing embedding model before indexing
+    ///
+    /// Enabled by default. Without it, background indexing fails until the
+    /// model is pulled by hand and search ranks files by BM25 keywords alone.
+    pub const fn set_auto_pull(&mut self, auto_pull: bool) {
+        self.auto_pull = auto_pull;
+    }
+
+    /// Enable or disable watching the project for changes made outside the agent
+    ///
+    /// Disabled by default. When enabled, the project is watched from the
+    /// first query on: changed source files are re-parsed before the next
+    /// query, and a changed manifest starts a [`reload`](Self::reload).
+    #[must_use]
+    pub fn with_watch_mode(mut self, enabled: bool) -> Self {
+        self.watch = watcher::WatchState::new(enabled);
+        self
+    }
+
+    /// Starts rebuilding the language backends from the project's current files
+    ///
+    /// The backends are rebuilt on a blocking thread. Queries keep using the
+    /// current backends until a query after the rebuild finished, which swaps
+    /// them in. Must be called within a Tokio runtime.
+    pub fn reload(&mut self) {
+        self.watch
+            .start_reload(&self.project_root, &self.index_cache);
+    }
+
+    /// Whether the language backends are being rebuilt
+    ///
+    /// While this is true, symbol queries are answered from the backends as
+    /// they were before the reload and may miss the latest changes.
+    pub fn reload_in_progress(&self) -> bool {
+        self.watch.reload_in_progress()
+    }
+
+    /// Applies the changes seen by the watcher and swaps in reloaded backends
+    async fn sync_watched_changes(&mut self) {
+        let language = system_init::LanguageState {
+            backend: &mut self.language_backend,
+            cross_language: &mut self.cross_language,
+            index_cache: &self.index_cache,
+        };
+        self.watch.sync(language, &self.project_root).await;
+    }
+
+    /// Applies a file created, edited or deleted by the agent to the language backend.
+    ///
+    /// `new_text` is the new content, or `None` if the file was deleted. Does
+    /// nothing before the backend is initialized, since initialization reads the
+    /// current files anyway.
+    pub fn apply_file_change(&mut self, path: &Path, new_text: Option<&str>) {
+        let mut language = system_init::LanguageState {
+            backend: &mut self.language_backend,
+            cross_language: &mut self.cross_language,
+            index_cache: &self.index_cache,
+        };
+        system_init::apply_file_change(&mut language, &self.project_root, path, new_text);
+    }
+
+    /// Returns the functions and methods calling the symbol defined at `file`:`line`.
+    ///
+    /// # Errors
+    /// Returns an error if the project has no supported source files or the lookup fails.
+    pub async fn find_callers(
+        &mut self,
+        symbol_name: &str,
+        file: &Path,
+        line: u32,
+    ) -> Result<Vec<SymbolInfo>> {
+        self.indexed_backend()
+            .await?
+            .find_callers(symbol_name, file, line)
+    }
+
+    /// Returns the types implementing, extending or satisfying `type_name`.
+    ///
+    /// # Errors
+    /// Returns an error if the project has no supported source files or the lookup fails.
+    pub async fn find_implementations(&mut self, type_name: &str) -> Result<Vec<SymbolInfo>> {
+        self.indexed_backend()
+            .await?
+            .find_implementations(type_name)
+    }
+
+    /// Returns the symbols whose name contains the query's, exact matches first.
+    ///
+    /// # Errors
+    /// Returns an error if the project has no supported source files or the search fails.
+    pub async fn search_symbols(&mut self, query: &SearchQuery) -> Result<Vec<SymbolInfo>> {
+        Ok(self.indexed_backend().await?.search_symbols(query)?.symbols)
+    }
+
+    /// Returns the lines of the project's source files mentioning `symbol_name`.
+    ///
+    /// # Errors
+    /// Returns an error if the project has no supported source files or the lookup fails.
+    pub async fn find_references(&mut self, symbol_name: &str) -> Result<Vec<SymbolInfo>> {
+        self.indexed_backend().await?.find_references(symbol_name)
+    }
+
+    /// Returns the language backend, indexing the project first if no query has yet.
+    ///
+    /// # Errors
+    /// Returns an error if the project has no supported source files.
+    async fn indexed_backend(&mut self) -> Result<&dyn LanguageProvider> {
+        self.sync_watched_changes().await;
+        let language = system_init::LanguageState {
+            backend: &mut self.language_backend,
+            cross_language: &mut self.cross_language,
+            index_cache: &self.index_cache,
+        };
+        system_init::initialize_language_backend(language, &self.project_root).await;
+        self.language_backend
+            .as_deref()
+            .ok_or_else(|| Error::Other("No supported source files found to navigate".to_owned()))
+    }
+
+    /// Wait for background indexing to finish and switch to the full index
+    ///
+    /// Queries are otherwise served from the partial index loaded from the
+    /// cache until indexing completes. Does nothing if no indexing is running.
+    pub async fn wait_for_index(&mut self) {
+        system_init::await_background_index(&mut self.vector_manager, &mut self.background_index)
+            .await;
+    }
+
     /// Build a `Context` for the provided query.
     ///
     /// # Errors
     /// Returns an error if file scanning or reading fails.
     pub async fn build_context(&mut self, query: &Query) -> Result<Context> {
+        self.sync_watched_changes().await;
+
         // Step 1: Analyze the query to extract intent
         let analyzer = QueryAnalyzer;
         let intent = analyzer.analyze(&query.text);
@@ -78,42 +328,87 @@ impl ContextBuilder {
             intent.entities
         );
 
-        let mut files = if query.files_context.is_empty() {
+        // Pinned files take their share of the token budget before retrieval
+        let pinned = pinned::load_pinned_files(&self.project_root, &query.pinned_files).await;
+        let token_budget = self
+            .token_budget
+            .saturating_sub(pinned::token_count(&pinned));
+
+        let (retrieved, explanations) = self.retrieve_files(&intent, query, token_budget).await?;
+        self.explanations =
+            explanations
+                .into_iter()
+                .chain(pinned.iter().map(|file| {
+                    FileExplanation::new(file.path.clone(), Some(InclusionSource::Pinned))
+                }))
+                .map(|explanation| (explanation.path.clone(), explanation))
+                .collect();
+
+        let files = pinned::merge_pinned(&self.project_root, pinned, retrieved, self.max_files);
+        tracing::info!(
+            "Final context: {} files ({} pinned, max: {})",
+            files.len(),
+            query.pinned_files.len(),
+            self.max_files
+        );
+
+        Ok(Context::new(String::new()).with_files(files))
+    }
+
+    /// Explains why each file of `context` was included
+    ///
+    /// Reflects the last context this builder built; files it did not include
+    /// are listed without a source.
+    pub fn explain_context(&self, context: &Context) -> ContextExplanation {
+        ContextExplanation::for_context(context, &self.explanations)
+    }
+
+    /// Gathers the files of the query that are not pinned, and why each was included
+    ///
+    /// Uses the query's files if any are readable, hybrid search otherwise.
+    ///
+    /// # Errors
+    /// Returns an error if initializing search or searching fails
+    async fn retrieve_files(
+        &mut self,
+        intent: &QueryIntent,
+        query: &Query,
+        token_budget: usize,
+    ) -> Result<(Vec<FileContext>, Vec<FileExplanation>)> {
+        if query.files_context.is_empty() {
             // Step 2: Initialize backend and vector search IN PARALLEL
             self.initialize_systems_parallel().await?;
 
             // Step 3: Use hybrid search for context (vector search works without backend)
-            let agent_files = self.use_subagent_for_context(&intent, &query.text).await?;
+            let (agent_files, explanations) = self
+                .use_subagent_for_context(intent, &query.text, token_budget)
+                .await?;
             tracing::info!(
                 "Intelligent context fetching found {} files",
                 agent_files.len()
             );
-            agent_files
-        } else {
-            // User provided specific files
-            let mut collected = Vec::new();
-            for file_path in &query.files_context {
-                if let Ok(file_context) = FileContext::from_path(file_path) {
-                    collected.push(file_context);
-                }
-            }
-            if collected.is_empty() {
-                let all_files = self.collect_all_files();
-                tracing::info!("Collected {} files from project scan", all_files.len());
-                all_files
-            } else {
-                collected
+            return Ok((agent_files, explanations));
+        }
+
+        // User provided specific files
+        let mut collected = Vec::new();
+        for file_path in &query.files_context {
+            if let Ok(file_context) = FileContext::from_path(file_path) {
+                collected.push(file_context);
             }
+        }
+        let source = if collected.is_empty() {
+            collected = self.collect_all_files();
+            tracing::info!("Collected {} files from project scan", collected.len());
+            InclusionSource::ProjectScan
+        } else {
+            InclusionSource::Requested
         };
-
-        files.truncate(self.max_files);
-        tracing::info!(
-            "Final context: {} files (max: {})",
-            files.len(),
-            self.max_files
-        );
-
-        Ok(Context::new(String::new()).with_files(files))
+        let explanations = collected
+            .iter()
+            .map(|file| FileExplanation::new(file.path.clone(), Some(source)))
+            .collect();
+        Ok((collected, explanations))
     }
 
     /// Use hybrid search to intelligently gather context
@@ -124,9 +419,28 @@ impl ContextBuilder {
         &self,
         intent: &QueryIntent,
         query_text: &str,
-    ) -> Result<Vec<FileContext>> {
+        token_budget: usize,
+    ) -> Result<(Vec<FileContext>, Vec<FileExplanation>)> {
         search::use_subagent_for_context(
-            self.vector_manager.as_ref(),
+            search::SearchSettings {
+                index: self
+                    .keyword_index
+                    .as_ref()
+                    .map(search::SearchIndex::Keyword)
+                    .or_else(|| {
+                        self.vector_manager
+                            .as_ref()
+                            .map(search::SearchIndex::Hybrid)
+                    }),
+                token_budget,
+                rerank: self.rerank,
+            },
+            self.language_backend
+                .as_deref()
+                .map(|backend| search::SymbolLookup {
+                    backend,
+                    cross_language: self.cross_language.as_ref(),
+                }),
             &self.project_root,
             intent,
             query_text,
@@ -139,16 +453,47 @@ impl ContextBuilder {
         file_scanner::collect_all_files(&self.project_root, self.max_files, self.max_file_size)
     }
 
-    /// Initializes vector search system.
+    /// Initializes the language backend and vector search in parallel.
+    ///
+    /// With embeddings disabled, a keyword index is built instead of vector search.
     ///
     /// # Errors
     /// Returns an error if critical initialization fails.
     async fn initialize_systems_parallel(&mut self) -> Result<()> {
-        system_init::initialize_systems_parallel(
+        if !self.embeddings_enabled {
+            system_init::initialize_keyword_search(
+                &mut self.keyword_index,
+                system_init::LanguageState {
+                    backend: &mut self.language_backend,
+                    cross_language: &mut self.cross_language,
+                    index_cache: &self.index_cache,
+                },
+                self.project_root.as_path(),
+            )
+            .await;
+            return Ok(());
+        }
+        let background_index = system_init::initialize_systems_parallel(
             &mut self.vector_manager,
+            system_init::LanguageState {
+                backend: &mut self.language_backend,
+                cross_language: &mut self.cross_language,
+                index_cache: &self.index_cache,
+            },
             self.project_root.as_path(),
-            self.progress_callback.as_ref(),
+            system_init::VectorProgress {
+                task: self.progress_callback.as_ref(),
+                index: self.index_progress_callback.as_ref(),
+                pull: self.pull_progress_callback.as_ref(),
+                auto_pull: self.auto_pull,
+            },
         )
-        .await
+        .await?;
+        if background_index.is_some() {
+            self.background_index = background_index;
+        }
+        system_init::adopt_background_index(&mut self.vector_manager, &mut self.background_index)
+            .await;
+        Ok(())
     }
 }
@@ -1,37 +1,79 @@
 //! Search and context building functionality.
 
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use merlin_core::{CoreResult as Result, FileContext};
+use merlin_languages::provider::DIRECT_WEIGHT;
+use merlin_languages::{CrossLanguageResolver, LanguageProvider, SearchQuery};
 
-use crate::context_inclusion::{ContextManager, MAX_CONTEXT_TOKENS, add_prioritized_files};
-use crate::embedding::{SearchResult, VectorSearchManager};
+use crate::context_inclusion::{
+    ContextManager, FilePriority, PrioritizedFile, add_prioritized_files,
+};
+use crate::embedding::{NoOpEmbeddingProvider, SearchResult, VectorSearchManager, chunk_file};
+use crate::explanation::{FileExplanation, InclusionSource};
 use crate::query::QueryIntent;
 
-use super::chunk_processor::{FileScoreInfo, process_search_results};
+use super::chunk_processor::{
+    FileScoreInfo, chunk_location, extract_chunk_with_context, process_search_results,
+};
+
+/// Maximum number of definitions included per entity named in the query
+const MAX_DEFINITIONS_PER_ENTITY: usize = 3;
+
+/// Language backend and cross-language links used to look up symbols
+pub struct SymbolLookup<'lookup> {
+    /// Backend of the project's primary language
+    pub backend: &'lookup dyn LanguageProvider,
+    /// Links to native extension modules, if the project has any
+    pub cross_language: Option<&'lookup CrossLanguageResolver>,
+}
+
+/// Index searched for context
+#[derive(Clone, Copy)]
+pub enum SearchIndex<'index> {
+    /// BM25 and vector search
+    Hybrid(&'index VectorSearchManager),
+    /// BM25 keyword search only, while embeddings are disabled
+    Keyword(&'index VectorSearchManager<NoOpEmbeddingProvider>),
+}
+
+/// Search index and limits used to search for context
+pub struct SearchSettings<'search> {
+    /// Index to search, if one is initialized
+    pub index: Option<SearchIndex<'search>>,
+    /// Maximum number of tokens of context gathered
+    pub token_budget: usize,
+    /// Whether search results are reranked by their imports
+    pub rerank: bool,
+}
 
 /// Performs hybrid search (BM25 + vector) for relevant code chunks.
 ///
+/// A keyword index is searched with BM25 alone.
+///
 /// # Errors
 /// Returns an error if hybrid search fails
 pub async fn perform_hybrid_search(
-    vector_manager: Option<&VectorSearchManager>,
+    index: Option<SearchIndex<'_>>,
     query_text: &str,
+    rerank: bool,
 ) -> Result<Vec<SearchResult>> {
-    tracing::info!("Running hybrid search (BM25 + Vector)...");
-    tracing::info!("Using hybrid BM25 + Vector search for context");
-
-    let semantic_matches = if let Some(manager) = &vector_manager {
-        match manager.search(query_text, 50).await {
-            Ok(results) => results,
-            Err(search_error) => {
-                tracing::warn!("Hybrid search failed: {search_error}");
-                Vec::new()
-            }
+    let search_result = match index {
+        Some(SearchIndex::Hybrid(manager)) => {
+            tracing::info!("Using hybrid BM25 + Vector search for context");
+            manager.search_with_rerank(query_text, 50, rerank).await
         }
-    } else {
-        Vec::new()
+        Some(SearchIndex::Keyword(manager)) => {
+            tracing::info!("Using BM25 keyword search for context (embeddings disabled)");
+            manager.search_with_rerank(query_text, 50, rerank).await
+        }
+        None => Ok(Vec::new()),
     };
+    let semantic_matches = search_result.unwrap_or_else(|search_error| {
+        tracing::warn!("Hybrid search failed: {search_error}");
+        Vec::new()
+    });
 
     if semantic_matches.is_empty() {
         tracing::info!("Hybrid search: no results (store may be empty)");
@@ -56,22 +98,47 @@ pub async fn perform_hybrid_search(
 
 /// Use hybrid search to intelligently gather context
 ///
+/// Returns the files along with why each was included.
+///
 /// # Errors
 /// Returns an error if hybrid search fails
 pub async fn use_subagent_for_context(
-    vector_manager: Option<&VectorSearchManager>,
+    settings: SearchSettings<'_>,
+    symbol_lookup: Option<SymbolLookup<'_>>,
     project_root: &Path,
-    _intent: &QueryIntent,
+    intent: &QueryIntent,
     query_text: &str,
-) -> Result<Vec<FileContext>> {
+) -> Result<(Vec<FileContext>, Vec<FileExplanation>)> {
     // Perform hybrid search
-    let semantic_matches = perform_hybrid_search(vector_manager, query_text).await?;
+    let semantic_matches =
+        perform_hybrid_search(settings.index, query_text, settings.rerank).await?;
 
     // Process search results into prioritized chunks
-    let (search_prioritized, file_scores) = process_search_results(project_root, &semantic_matches);
+    let (mut search_prioritized, file_scores) =
+        process_search_results(project_root, &semantic_matches);
+
+    // Add the definitions of symbols named in the query
+    let mut definition_sources = Vec::new();
+    if let Some(lookup) = symbol_lookup {
+        let definitions = find_symbol_definitions(&lookup, intent, &search_prioritized);
+        tracing::info!(
+            "Language backend found {} symbol definitions",
+            definitions.len()
+        );
+        // Definitions are high priority, files they import across languages medium
+        definition_sources.extend(definitions.iter().map(|definition| {
+            let source = if definition.priority == FilePriority::High {
+                InclusionSource::SymbolDefinition
+            } else {
+                InclusionSource::ImportExpansion
+            };
+            (definition.file.path.clone(), source)
+        }));
+        search_prioritized.extend(definitions);
+    }
 
     // Use context manager to add hybrid search results
-    let mut context_mgr = ContextManager::new(MAX_CONTEXT_TOKENS);
+    let mut context_mgr = ContextManager::new(settings.token_budget);
 
     let added = add_prioritized_files(&mut context_mgr, search_prioritized);
     tracing::info!(
@@ -90,8 +157,157 @@ pub async fn use_subagent_for_context(
     log_context_files(&context_mgr, &file_scores);
 
     let files = context_mgr.into_files();
+    let explanations = explain_files(
+        project_root,
+        &files,
+        &semantic_matches,
+        &file_scores,
+        &definition_sources,
+    );
+
+    Ok((files, explanations))
+}
+
+/// Records why each of the distinct `files` was included
+///
+/// Files found through a symbol are listed in `definition_sources`; the rest
+/// came from search and carry the scores of their best chunk.
+fn explain_files(
+    project_root: &Path,
+    files: &[FileContext],
+    semantic_matches: &[SearchResult],
+    file_scores: &[FileScoreInfo],
+    definition_sources: &[(PathBuf, InclusionSource)],
+) -> Vec<FileExplanation> {
+    let mut explanations: Vec<FileExplanation> = Vec::new();
+    for file in files {
+        if explanations
+            .iter()
+            .any(|explanation| explanation.path == file.path)
+        {
+            continue;
+        }
+        if let Some((_, source)) = definition_sources
+            .iter()
+            .find(|(path, _)| path == &file.path)
+        {
+            explanations.push(FileExplanation::new(file.path.clone(), Some(*source)));
+            continue;
+        }
+
+        let mut explanation =
+            FileExplanation::new(file.path.clone(), Some(InclusionSource::Search));
+        let best = file_scores
+            .iter()
+            .filter(|(path, _, _, _)| path == &file.path)
+            .max_by(|(_, score_a, _, _), (_, score_b, _, _)| score_a.total_cmp(score_b));
+        if let Some((_, combined, bm25, vector)) = best {
+            explanation.combined_score = Some(*combined);
+            explanation.bm25_score = *bm25;
+            explanation.vector_score = *vector;
+        }
+        explanation.matched_chunks = semantic_matches
+            .iter()
+            .filter_map(|result| chunk_location(project_root, &result.file_path))
+            .filter(|(path, _, _)| path == &file.path)
+            .map(|(_, start, end)| (start, end))
+            .collect();
+        explanations.push(explanation);
+    }
+    explanations
+}
+
+/// Finds the definitions of entities named in the query
+///
+/// Each definition is included as the chunk that contains it. Files in other
+/// languages that a definition's file imports are included whole at a lower
+/// priority. Files already covered by search results are skipped.
+fn find_symbol_definitions(
+    lookup: &SymbolLookup<'_>,
+    intent: &QueryIntent,
+    search_prioritized: &[PrioritizedFile],
+) -> Vec<PrioritizedFile> {
+    let mut definitions: Vec<PrioritizedFile> = Vec::new();
+
+    for entity in &intent.entities {
+        // `Type::method` and `module.function` name their last segment
+        let Some(name) = entity
+            .rsplit([':', '.'])
+            .next()
+            .filter(|name| !name.is_empty())
+        else {
+            continue;
+        };
+        let query = SearchQuery {
+            symbol_name: Some(name.to_owned()),
+            ..SearchQuery::default()
+        };
+        let mut result = match lookup.backend.search_symbols(&query) {
+            Ok(result) => result,
+            Err(search_error) => {
+                tracing::warn!("Symbol search for '{name}' failed: {search_error}");
+                continue;
+            }
+        };
+        result.symbols.retain(|symbol| symbol.name == name);
+        result.symbols.truncate(MAX_DEFINITIONS_PER_ENTITY);
+        if let Some(resolver) = lookup.cross_language {
+            resolver.extend_related(lookup.backend, &mut result);
+        }
+
+        for symbol in &result.symbols {
+            let already_included = search_prioritized
+                .iter()
+                .chain(&definitions)
+                .any(|prioritized| prioritized.file.path == symbol.file_path);
+            if already_included {
+                continue;
+            }
+            let Ok(content) = fs::read_to_string(&symbol.file_path) else {
+                continue;
+            };
+
+            let line = symbol.line as usize;
+            let Some(chunk) = chunk_file(&symbol.file_path, &content)
+                .into_iter()
+                .find(|chunk| chunk.start_line <= line && line <= chunk.end_line)
+            else {
+                continue;
+            };
+            match extract_chunk_with_context(
+                &symbol.file_path,
+                chunk.start_line,
+                chunk.end_line,
+                false,
+            ) {
+                Ok(file) => definitions.push(PrioritizedFile::new(file, FilePriority::High)),
+                Err(extract_error) => tracing::warn!(
+                    "Failed to extract definition of '{name}' from {}: {extract_error}",
+                    symbol.file_path.display()
+                ),
+            }
+        }
+
+        for related in result
+            .related_files
+            .into_iter()
+            .filter(|related| related.weight < DIRECT_WEIGHT)
+        {
+            let already_included = search_prioritized
+                .iter()
+                .chain(&definitions)
+                .any(|prioritized| prioritized.file.path == related.file.path);
+            if !already_included {
+                tracing::debug!(
+                    "Linked {} to '{name}' across languages",
+                    related.file.path.display()
+                );
+                definitions.push(PrioritizedFile::new(related.file, FilePriority::Medium));
+            }
+        }
+    }
 
-    Ok(files)
+    definitions
 }
 
 /// Log detailed information about context files
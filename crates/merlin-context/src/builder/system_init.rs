@@ -1,43 +1,324 @@
-//! System initialization for vector search.
+//! System initialization for the language backend and vector search.
 
+use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::spawn;
+use tokio::task::{JoinHandle, spawn_blocking};
+use tokio::{join, spawn};
 
 use merlin_core::CoreResult as Result;
+use merlin_languages::cross_language::is_native_source;
+use merlin_languages::{
+    CrossLanguageResolver, LanguageProvider, WorkspaceCache, compose_backends, detect_languages,
+};
+use merlin_tooling::join_error;
 
-use crate::embedding::{ProgressCallback, VectorSearchManager};
+use crate::embedding::{NoOpEmbeddingProvider, ProgressCallback, VectorSearchManager};
+
+/// Language backend selected for a project
+type BoxedProvider = Box<dyn LanguageProvider>;
+
+/// Full embedding initialization running in the background
+pub type BackgroundIndex = JoinHandle<Result<VectorSearchManager>>;
+
+/// Progress callbacks of vector search initialization
+#[derive(Clone, Copy)]
+pub struct VectorProgress<'callback> {
+    /// Reports loading the cached index on behalf of the current task
+    pub task: Option<&'callback ProgressCallback>,
+    /// Reports background indexing, which outlives the current task
+    pub index: Option<&'callback ProgressCallback>,
+    /// Reports downloading a missing embedding model, if it is downloaded
+    pub pull: Option<&'callback ProgressCallback>,
+    /// Whether a missing embedding model is downloaded before indexing
+    pub auto_pull: bool,
+}
+
+/// Language backend state of a builder, filled in by initialization
+pub struct LanguageState<'state> {
+    /// Backend for symbol lookup, kept if already set
+    pub backend: &'state mut Option<BoxedProvider>,
+    /// Resolver of imports of native extension modules
+    pub cross_language: &'state mut Option<CrossLanguageResolver>,
+    /// Where the backends' parse results are cached between runs
+    pub index_cache: &'state WorkspaceCache,
+}
 
 /// Spawn background task for full embedding initialization
 ///
-/// Note: Does not use progress callback to avoid UI blocking
-pub fn spawn_background_embedding(project_root: PathBuf) {
+/// The index callback of `progress` outlives the task that started indexing,
+/// so it should report to the UI rather than to a task. It is called a last
+/// time with equal progress and total once the index is complete. A missing
+/// embedding model is downloaded first, unless `progress` disables it.
+pub fn spawn_background_embedding(
+    project_root: &Path,
+    progress: VectorProgress<'_>,
+) -> BackgroundIndex {
+    let progress_callback = progress.index.cloned();
+    let mut bg_manager = VectorSearchManager::new(project_root).with_auto_pull(progress.auto_pull);
+    if let Some(callback) = progress.pull {
+        bg_manager = bg_manager.with_pull_progress_callback(Arc::clone(callback));
+    }
+    if let Some(callback) = &progress_callback {
+        bg_manager = bg_manager.with_progress_callback(Arc::clone(callback));
+    }
     spawn(async move {
-        let mut bg_manager = VectorSearchManager::new(&project_root);
-        // Don't set progress callback - background task shouldn't update UI
-
         tracing::info!("Background: Starting full embedding initialization...");
-        if let Err(bg_error) = bg_manager.initialize().await {
+        bg_manager.initialize().await?;
+        tracing::info!(
+            "Background: Embedding generation completed ({} files indexed)",
+            bg_manager.len()
+        );
+        if let Some(callback) = progress_callback {
+            let indexed = bg_manager.len() as u64;
+            callback("Index ready", indexed, Some(indexed));
+        }
+        Ok(bg_manager)
+    })
+}
+
+/// Switches queries to the full index once background initialization finished
+///
+/// Until then, the partial index loaded from the cache keeps serving queries.
+/// If the background initialization failed, the partial index stays in use.
+pub async fn adopt_background_index(
+    vector_manager: &mut Option<VectorSearchManager>,
+    background_index: &mut Option<BackgroundIndex>,
+) {
+    if background_index
+        .as_ref()
+        .is_some_and(JoinHandle::is_finished)
+    {
+        await_background_index(vector_manager, background_index).await;
+    }
+}
+
+/// Waits for background initialization and switches queries to the full index
+///
+/// If the background initialization failed, the partial index stays in use.
+pub async fn await_background_index(
+    vector_manager: &mut Option<VectorSearchManager>,
+    background_index: &mut Option<BackgroundIndex>,
+) {
+    let Some(handle) = background_index.take() else {
+        return;
+    };
+    match handle.await {
+        Ok(Ok(manager)) => {
+            tracing::info!("Full embedding index ready, replacing the partial index");
+            *vector_manager = Some(manager);
+        }
+        Ok(Err(bg_error)) => {
             tracing::warn!("Background embedding generation failed: {bg_error}");
-        } else {
-            tracing::info!("Background: Embedding generation completed successfully");
         }
-    });
+        Err(join_err) => {
+            let error = join_error("Background embedding", join_err);
+            tracing::warn!("Background embedding generation stopped: {error}");
+        }
+    }
 }
 
-/// Initializes vector search system.
+/// Initializes the language backend and vector search in parallel.
+///
+/// Returns the background task completing the vector index, if one was started.
 ///
 /// # Errors
 /// Returns an error if critical initialization fails.
 pub async fn initialize_systems_parallel(
+    vector_manager: &mut Option<VectorSearchManager>,
+    language: LanguageState<'_>,
+    project_root: &Path,
+    progress: VectorProgress<'_>,
+) -> Result<Option<BackgroundIndex>> {
+    let (vector_result, ()) = join!(
+        initialize_vector_search(vector_manager, project_root, progress),
+        initialize_language_backend(language, project_root),
+    );
+    vector_result
+}
+
+/// Initializes the language backend and a BM25 keyword index in parallel.
+///
+/// Used while embeddings are disabled: the embedding model is never contacted.
+/// The keyword index is built on a blocking thread, once.
+pub async fn initialize_keyword_search(
+    keyword_index: &mut Option<VectorSearchManager<NoOpEmbeddingProvider>>,
+    language: LanguageState<'_>,
+    project_root: &Path,
+) {
+    let root = project_root.to_path_buf();
+    let needs_index = keyword_index.is_none();
+    let build_index = async move {
+        if !needs_index {
+            return None;
+        }
+        tracing::info!("Embeddings disabled, building keyword index...");
+        let built = spawn_blocking(move || {
+            let mut manager = VectorSearchManager::keyword_only(&root);
+            manager.index_keywords();
+            manager
+        })
+        .await;
+        match built {
+            Ok(manager) => Some(manager),
+            Err(join_err) => {
+                let error = join_error("Keyword indexing", join_err);
+                tracing::warn!("Keyword indexing stopped: {error}");
+                None
+            }
+        }
+    };
+    let (built, ()) = join!(
+        build_index,
+        initialize_language_backend(language, project_root),
+    );
+    if built.is_some() {
+        *keyword_index = built;
+    }
+}
+
+/// Activates the language backends for the project's languages.
+///
+/// The languages come from `detect_languages`, which looks at the manifests
+/// and source files of the project; several backends are combined when the
+/// project mixes languages. Native extension modules are indexed alongside for
+/// cross-language imports. Parsing runs on a blocking thread. Failures are
+/// logged and leave the backend disabled, since context building works without
+/// it. A backend set by the caller is kept as is.
+pub(super) async fn initialize_language_backend(language: LanguageState<'_>, project_root: &Path) {
+    if language.backend.is_some() {
+        return;
+    }
+
+    let root = project_root.to_path_buf();
+    let index_cache = language.index_cache.clone();
+    let init_result = spawn_blocking(move || load_language_backend(&root, &index_cache)).await;
+
+    match init_result {
+        Ok(Ok((Some(backend), resolver))) => {
+            if resolver.is_some() {
+                tracing::info!("Native extension modules found, linking cross-language imports");
+            }
+            *language.backend = Some(backend);
+            *language.cross_language = resolver;
+        }
+        Ok(Ok((None, _))) => {
+            tracing::debug!("No supported source files found, language backend disabled");
+        }
+        Ok(Err(init_error)) => {
+            tracing::warn!("Language backend initialization failed: {init_error}");
+        }
+        Err(join_err) => {
+            let error = join_error("Language backend initialization", join_err);
+            tracing::warn!("Language backend initialization stopped: {error}");
+        }
+    }
+}
+
+/// Applies a file created, edited or deleted to the language backend
+///
+/// `new_text` is the new content, or `None` if the file was deleted. Does
+/// nothing before the backend is initialized, since initialization reads the
+/// current files anyway.
+pub(super) fn apply_file_change(
+    language: &mut LanguageState<'_>,
+    project_root: &Path,
+    path: &Path,
+    new_text: Option<&str>,
+) {
+    let Some(backend) = language.backend.as_mut() else {
+        return;
+    };
+    if let Err(error) = backend.apply_file_change(path, new_text) {
+        tracing::debug!(
+            "Dropped {} from the language index: {error}",
+            path.display()
+        );
+    }
+    if is_native_source(path) {
+        *language.cross_language =
+            Some(CrossLanguageResolver::new(project_root)).filter(|resolver| !resolver.is_empty());
+    }
+}
+
+/// Backend selected for a project and its cross-language resolver, if any
+pub(super) type LoadedBackend = (Option<BoxedProvider>, Option<CrossLanguageResolver>);
+
+/// Creates and initializes the backends of `root` and links native extension modules
+///
+/// Blocks while parsing, so callers run it on a blocking thread.
+///
+/// # Errors
+/// Returns an error if a selected backend fails to initialize
+pub(super) fn load_language_backend(
+    root: &Path,
+    index_cache: &WorkspaceCache,
+) -> Result<LoadedBackend> {
+    let backend = select_language_backend(root, index_cache)?;
+    let resolver = backend
+        .is_some()
+        .then(|| CrossLanguageResolver::new(root))
+        .filter(|resolver| !resolver.is_empty());
+    Ok((backend, resolver))
+}
+
+/// Returns the directory a project's parse results are cached in between runs
+///
+/// Like the embedding cache, this lives under `MERLIN_FOLDER` if it is set and
+/// under the project's `.merlin/` directory otherwise. Projects sharing a
+/// `MERLIN_FOLDER` each get their own cache file in it.
+pub(super) fn index_cache_dir(project_root: &Path) -> PathBuf {
+    env::var("MERLIN_FOLDER")
+        .map_or_else(|_| project_root.join(".merlin"), PathBuf::from)
+        .join("cache")
+        .join("index")
+}
+
+/// Creates and initializes the backends for the languages of the project
+///
+/// A project using one language gets that language's backend; a project
+/// mixing languages gets a `PolyglotBackend` routing each file to the
+/// backend of its extension, with the primary language first.
+///
+/// # Errors
+/// Returns an error if a selected backend fails to initialize
+fn select_language_backend(
+    root: &Path,
+    index_cache: &WorkspaceCache,
+) -> Result<Option<BoxedProvider>> {
+    let detected = detect_languages(root);
+    let names: Vec<&str> = detected.iter().map(|found| found.language.name()).collect();
+    let backends = detected
+        .iter()
+        .map(|found| {
+            let cache = Some(index_cache.clone());
+            (found.language, found.language.create_backend(cache))
+        })
+        .collect();
+    let Some(mut backend) = compose_backends(backends) else {
+        return Ok(None);
+    };
+    tracing::info!("Language backends active: {}", names.join(", "));
+    backend.initialize(root)?;
+    Ok(Some(backend))
+}
+
+/// Initializes vector search system.
+///
+/// The cached index is loaded for immediate use, and a background task
+/// validates it and embeds new or modified files.
+///
+/// # Errors
+/// Returns an error if critical initialization fails.
+async fn initialize_vector_search(
     vector_manager: &mut Option<VectorSearchManager>,
     project_root: &Path,
-    progress_callback: Option<&ProgressCallback>,
-) -> Result<()> {
+    progress: VectorProgress<'_>,
+) -> Result<Option<BackgroundIndex>> {
     let needs_vector_init = vector_manager.is_none();
 
     if !needs_vector_init {
-        return Ok(());
+        return Ok(None);
     }
 
     tracing::info!("Initializing vector search...");
@@ -47,25 +328,81 @@ pub async fn initialize_systems_parallel(
     tracing::info!("Loading embedding cache (non-blocking)...");
     let mut manager = VectorSearchManager::new(project_root);
 
-    if let Some(callback) = progress_callback {
+    if let Some(callback) = progress.task {
         manager = manager.with_progress_callback(Arc::clone(callback));
     }
 
     // Try partial init first (fast, uses cache only)
     match manager.initialize_partial().await {
         Ok(()) => {
-            tracing::info!("Using cached embeddings immediately");
-            *vector_manager = Some(manager);
+            tracing::info!("Using cached embeddings immediately, validating them in background");
         }
         Err(error) => {
+            // The manager is stored anyway (empty but ready for BM25 fallback)
             tracing::warn!("No cache available, spawning background embedding generation: {error}");
-            spawn_background_embedding(project_root.to_path_buf());
+        }
+    }
+    *vector_manager = Some(manager);
+    let background_index = spawn_background_embedding(project_root, progress);
 
-            // Store the manager anyway (empty but ready for BM25 fallback)
-            *vector_manager = Some(manager);
+    tracing::info!("Vector search initialized (embeddings continue in background)");
+    Ok(Some(background_index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::Error;
+    use tempfile::TempDir;
+    use tokio::task::yield_now;
+
+    /// Waits until a background index has finished
+    async fn wait_for(background_index: &BackgroundIndex) {
+        while !background_index.is_finished() {
+            yield_now().await;
         }
     }
 
-    tracing::info!("Vector search initialized (embeddings may continue in background)");
-    Ok(())
+    /// Tests that the full index replaces the partial one only once it is ready.
+    ///
+    /// # Errors
+    /// Returns an error if the temporary project cannot be created.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_adopt_background_index() -> Result<()> {
+        let dir = TempDir::new()?;
+        let mut vector_manager = None;
+
+        let root = dir.path().to_path_buf();
+        let mut background_index = Some(spawn(async move {
+            yield_now().await;
+            Ok(VectorSearchManager::new(&root))
+        }));
+        adopt_background_index(&mut vector_manager, &mut background_index).await;
+        assert!(vector_manager.is_none(), "unfinished index must not block");
+        assert!(background_index.is_some());
+
+        if let Some(handle) = &background_index {
+            wait_for(handle).await;
+        }
+        adopt_background_index(&mut vector_manager, &mut background_index).await;
+        assert!(vector_manager.is_some());
+        assert!(background_index.is_none());
+
+        let mut failed_index: Option<BackgroundIndex> = Some(spawn(async {
+            Err(Error::Other("embedding model unavailable".to_owned()))
+        }));
+        if let Some(handle) = &failed_index {
+            wait_for(handle).await;
+        }
+        adopt_background_index(&mut vector_manager, &mut failed_index).await;
+        assert!(
+            vector_manager.is_some(),
+            "a failed index keeps the partial one"
+        );
+        assert!(failed_index.is_none());
+        Ok(())
+    }
 }
@@ -0,0 +1,242 @@
+//! Watch mode: picking up changes made to the project outside the agent.
+//!
+//! A `notify` watcher reports the files changed under the project root.
+//! Changed source files are re-parsed through `apply_file_change`, like the
+//! agent's own edits. A changed manifest can change how the whole project
+//! resolves (module paths, source roots, path mappings), so it starts a
+//! reload: the backends are rebuilt on a blocking thread while queries keep
+//! using the old ones, which are replaced once the reload finished. Changes
+//! are picked up before the next query.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::mpsc::{Receiver, channel};
+
+use merlin_core::CoreResult as Result;
+use merlin_core::sync::IgnoreLock as _;
+use merlin_languages::{Language, WorkspaceCache};
+use merlin_tooling::join_error;
+use notify::{
+    Event, RecommendedWatcher, RecursiveMode, Result as NotifyResult, Watcher as _,
+    recommended_watcher,
+};
+use tokio::fs::read_to_string;
+use tokio::task::{JoinHandle, spawn_blocking};
+
+use super::system_init::{LanguageState, LoadedBackend, apply_file_change, load_language_backend};
+
+/// Watch mode state of a context builder
+#[derive(Default)]
+pub(super) struct WatchState {
+    /// Whether the project is watched once the builder is first queried
+    enabled: bool,
+    /// Watcher of the project root, started by the first query
+    watcher: Option<ProjectWatcher>,
+    /// Backends being rebuilt, replacing the current ones once done
+    reload: Option<Reload>,
+}
+
+/// Backends being rebuilt in the background
+struct Reload {
+    /// Blocking task loading the backends
+    handle: JoinHandle<Result<LoadedBackend>>,
+    /// Source files changed while loading, applied again to the new backends
+    changed: BTreeSet<PathBuf>,
+}
+
+impl WatchState {
+    /// Creates the state, watching the project if `enabled`
+    pub(super) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Self::default()
+        }
+    }
+
+    /// Whether backends are being rebuilt while queries use the old ones
+    pub(super) fn reload_in_progress(&self) -> bool {
+        self.reload
+            .as_ref()
+            .is_some_and(|reload| !reload.handle.is_finished())
+    }
+
+    /// Starts rebuilding the backends of `project_root` in the background
+    ///
+    /// A reload already running is superseded, since it may have read the
+    /// files before the latest change.
+    pub(super) fn start_reload(&mut self, project_root: &Path, index_cache: &WorkspaceCache) {
+        let root = project_root.to_path_buf();
+        let index_cache = index_cache.clone();
+        let handle = spawn_blocking(move || load_language_backend(&root, &index_cache));
+        let changed = self
+            .reload
+            .take()
+            .map(|reload| reload.changed)
+            .unwrap_or_default();
+        tracing::info!("Reloading language backends of {}", project_root.display());
+        self.reload = Some(Reload { handle, changed });
+    }
+
+    /// Adopts a finished reload and applies the changes reported by the watcher
+    ///
+    /// Starts the watcher on first use and a reload when a manifest changed.
+    pub(super) async fn sync(&mut self, mut language: LanguageState<'_>, project_root: &Path) {
+        let mut sources = BTreeSet::new();
+        if self.enabled && self.watcher.is_none() {
+            match ProjectWatcher::start(project_root) {
+                Ok(watcher) => self.watcher = Some(watcher),
+                Err(error) => {
+                    tracing::warn!("Watch mode disabled: {error}");
+                    self.enabled = false;
+                }
+            }
+        }
+
+        if self
+            .reload
+            .as_ref()
+            .is_some_and(|reload| reload.handle.is_finished())
+            && let Some(reload) = self.reload.take()
+        {
+            adopt(reload.handle, &mut language).await;
+            sources.extend(reload.changed);
+        }
+
+        if let Some(watcher) = &self.watcher {
+            let changes = watcher.take_changes();
+            if let Some(reload) = &mut self.reload {
+                reload.changed.extend(changes.sources.iter().cloned());
+            }
+            if changes.manifest_changed {
+                self.start_reload(project_root, language.index_cache);
+            }
+            sources.extend(changes.sources);
+        }
+
+        for path in sources {
+            let text = read_to_string(&path).await.ok();
+            apply_file_change(&mut language, project_root, &path, text.as_deref());
+        }
+    }
+}
+
+/// Replaces the backends with the ones loaded by `handle`, keeping them on failure
+async fn adopt(handle: JoinHandle<Result<LoadedBackend>>, language: &mut LanguageState<'_>) {
+    match handle.await {
+        Ok(Ok((backend, resolver))) => {
+            tracing::info!("Language backends reloaded");
+            *language.backend = backend;
+            *language.cross_language = resolver;
+        }
+        Ok(Err(error)) => tracing::warn!("Language backend reload failed: {error}"),
+        Err(join_err) => {
+            let error = join_error("Language backend reload", join_err);
+            tracing::warn!("Language backend reload stopped: {error}");
+        }
+    }
+}
+
+/// Watcher reporting the files changed under a project root
+struct ProjectWatcher {
+    /// Root of the watched project
+    project_root: PathBuf,
+    /// Keeps watching until dropped
+    _watcher: RecommendedWatcher,
+    /// Events reported since the changes were last taken (locked to keep the builder `Sync`)
+    events: Mutex<Receiver<NotifyResult<Event>>>,
+}
+
+impl ProjectWatcher {
+    /// Starts watching `project_root` recursively
+    ///
+    /// # Errors
+    /// Returns an error if the platform watcher cannot be created or watch the root
+    fn start(project_root: &Path) -> NotifyResult<Self> {
+        let (sender, events) = channel();
+        let mut watcher = recommended_watcher(sender)?;
+        watcher.watch(project_root, RecursiveMode::Recursive)?;
+        tracing::debug!("Watching {} for changes", project_root.display());
+        Ok(Self {
+            project_root: project_root.to_path_buf(),
+            _watcher: watcher,
+            events: Mutex::new(events),
+        })
+    }
+
+    /// Takes the changes reported since the last call
+    fn take_changes(&self) -> WatchedChanges {
+        let mut changes = WatchedChanges::default();
+        for event in self.events.lock_ignore_poison().try_iter() {
+            match event {
+                Ok(event) if !event.kind.is_access() => {
+                    for path in event.paths {
+                        changes.add(&self.project_root, path);
+                    }
+                }
+                Ok(_) => {}
+                Err(error) => tracing::debug!("File watcher error: {error}"),
+            }
+        }
+        changes
+    }
+}
+
+/// Changes to the project relevant to the language backends
+#[derive(Debug, Default, PartialEq, Eq)]
+struct WatchedChanges {
+    /// Whether a manifest at the project root changed
+    manifest_changed: bool,
+    /// Changed source files of a supported language
+    sources: BTreeSet<PathBuf>,
+}
+
+impl WatchedChanges {
+    /// Records a change to `path` in the project at `project_root`
+    ///
+    /// Only manifests at the root count, since nested ones are usually
+    /// dependencies (e.g. `node_modules/*/package.json`).
+    fn add(&mut self, project_root: &Path, path: PathBuf) {
+        let is_manifest = path.parent() == Some(project_root)
+            && path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    Language::ALL
+                        .iter()
+                        .any(|language| language.manifests().contains(&name))
+                });
+        if is_manifest {
+            self.manifest_changed = true;
+        } else if Language::ALL
+            .iter()
+            .any(|language| language.file_filter()(&path))
+        {
+            self.sources.insert(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that root manifests, nested manifests and source files are told apart.
+    ///
+    /// # Panics
+    /// Panics if a path is classified wrongly.
+    #[test]
+    fn test_changes_classified() {
+        let root = Path::new("/project");
+        let mut changes = WatchedChanges::default();
+        changes.add(root, root.join("node_modules/left-pad/package.json"));
+        changes.add(root, root.join("src/app.ts"));
+        changes.add(root, root.join("README.md"));
+        assert!(!changes.manifest_changed);
+        assert_eq!(changes.sources, BTreeSet::from([root.join("src/app.ts")]));
+
+        changes.add(root, root.join("go.mod"));
+        assert!(changes.manifest_changed);
+        assert_eq!(changes.sources.len(), 1);
+    }
+}
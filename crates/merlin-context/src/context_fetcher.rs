@@ -1,5 +1,5 @@
 use regex::Regex;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
@@ -7,9 +7,12 @@ use tokio::fs::read_to_string;
 use tokio::sync::Mutex;
 use tracing::{debug, info};
 
-use crate::{ContextBuilder, ProgressCallback};
+use crate::pinned;
+use crate::references::{self, ResolvedReference};
+use crate::{ContextBuilder, ContextExplanation, ProgressCallback};
 use merlin_core::{Context, FileContext, Query};
 use merlin_core::{Result, RoutingError};
+use merlin_languages::{IndexCacheStats, SearchQuery, SymbolInfo};
 
 /// Extracts file references and builds contextual information for tasks
 pub struct ContextFetcher {
@@ -19,6 +22,10 @@ pub struct ContextFetcher {
     context_builder: Mutex<Option<ContextBuilder>>,
     /// Optional progress callback for embedding operations (uses async Mutex for thread-safety)
     progress_callback: Mutex<Option<ProgressCallback>>,
+    /// Optional progress callback for background indexing
+    index_progress_callback: Mutex<Option<ProgressCallback>>,
+    /// Optional progress callback for downloading the embedding model
+    pull_progress_callback: Mutex<Option<ProgressCallback>>,
 }
 
 impl ContextFetcher {
@@ -29,6 +36,9 @@ impl ContextFetcher {
 
     /// Create a new context fetcher with optional embedding initialization
     ///
+    /// Without embeddings, context is retrieved with BM25 keyword search only
+    /// and the embedding model is never contacted.
+    ///
     /// # Arguments
     /// * `project_root` - Project root directory
     /// * `enable_embeddings` - Whether to initialize embedding/vector search system
@@ -36,16 +46,18 @@ impl ContextFetcher {
         // Check if we should skip expensive operations (for tests)
         let skip_embeddings = env::var("MERLIN_SKIP_EMBEDDINGS").is_ok();
 
-        // Create context builder unless explicitly disabled or env var set
+        // Create context builder unless the env var is set
         let context_builder = if skip_embeddings {
             debug!("Skipping context builder initialization (MERLIN_SKIP_EMBEDDINGS set)");
             None
-        } else if !enable_embeddings {
-            debug!("Skipping context builder initialization (embeddings disabled)");
-            None
         } else {
-            let builder = ContextBuilder::new(project_root.clone());
-            debug!("Context builder initialized for context fetcher");
+            let builder =
+                ContextBuilder::new(project_root.clone()).with_embeddings(enable_embeddings);
+            if enable_embeddings {
+                debug!("Context builder initialized for context fetcher");
+            } else {
+                debug!("Context builder initialized for keyword search (embeddings disabled)");
+            }
             Some(builder)
         };
 
@@ -53,6 +65,8 @@ impl ContextFetcher {
             project_root,
             context_builder: Mutex::new(context_builder),
             progress_callback: Mutex::new(None),
+            index_progress_callback: Mutex::new(None),
+            pull_progress_callback: Mutex::new(None),
         }
     }
 
@@ -79,6 +93,74 @@ impl ContextFetcher {
         *self.progress_callback.lock().await = Some(callback);
     }
 
+    /// Set the progress callback of background indexing (async update)
+    ///
+    /// Indexing continues after the query that started it, so the callback
+    /// should report to the UI rather than to a task.
+    pub async fn set_index_progress_callback(&self, callback: ProgressCallback) {
+        *self.index_progress_callback.lock().await = Some(callback);
+    }
+
+    /// Set the progress callback of downloading a missing embedding model (async update)
+    ///
+    /// The download runs before background indexing, so like that callback it
+    /// should report to the UI rather than to a task.
+    pub async fn set_pull_progress_callback(&self, callback: ProgressCallback) {
+        *self.pull_progress_callback.lock().await = Some(callback);
+    }
+
+    /// Download a missing embedding model before indexing (enabled by default)
+    ///
+    /// Without it, embeddings stay unavailable until the model is pulled by hand
+    /// and search falls back to BM25 keywords.
+    #[must_use]
+    pub fn with_auto_pull(self, auto_pull: bool) -> Self {
+        if let Ok(mut guard) = self.context_builder.try_lock()
+            && let Some(builder) = guard.as_mut()
+        {
+            builder.set_auto_pull(auto_pull);
+        }
+        self
+    }
+
+    /// Count the language index cache's hits, misses and evictions on `stats`
+    ///
+    /// Fetchers given clones of the same counters report their cache use together.
+    #[must_use]
+    pub fn with_index_cache_stats(self, stats: IndexCacheStats) -> Self {
+        if let Ok(mut guard) = self.context_builder.try_lock()
+            && let Some(builder) = guard.take()
+        {
+            *guard = Some(builder.with_index_cache_stats(stats));
+        }
+        self
+    }
+
+    /// Watch the project for changes made outside the agent (disabled by default)
+    ///
+    /// See [`ContextBuilder::with_watch_mode`].
+    #[must_use]
+    pub fn with_watch_mode(self, enabled: bool) -> Self {
+        if let Ok(mut guard) = self.context_builder.try_lock()
+            && let Some(builder) = guard.take()
+        {
+            *guard = Some(builder.with_watch_mode(enabled));
+        }
+        self
+    }
+
+    /// Whether the language backends are being rebuilt after a manifest changed
+    ///
+    /// Symbol lookups answered meanwhile come from the backends as they were
+    /// before the change.
+    pub async fn reload_in_progress(&self) -> bool {
+        self.context_builder
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(ContextBuilder::reload_in_progress)
+    }
+
     /// Extract file references from text
     ///
     /// Supports multiple formats:
@@ -170,7 +252,52 @@ impl ContextFetcher {
         None
     }
 
+    /// Resolves the `@file` and `@symbol` references in `text`
+    ///
+    /// Paths are resolved relative to the project root and must name a file.
+    /// Other tokens are looked up as symbols by exact name, which needs the
+    /// symbol index; tokens that resolve to nothing are skipped.
+    pub async fn resolve_references(&self, text: &str) -> Vec<ResolvedReference> {
+        let mut resolved = Vec::new();
+        for token in references::extract_references(text) {
+            let relative = PathBuf::from(&token);
+            if relative.is_relative() && self.project_root.join(&relative).is_file() {
+                resolved.push(ResolvedReference {
+                    token,
+                    file: relative,
+                    symbol: None,
+                });
+                continue;
+            }
+            if !references::is_symbol_token(&token) {
+                continue;
+            }
+            let name = token.rsplit("::").next().unwrap_or(&token).to_owned();
+            let query = SearchQuery {
+                symbol_name: Some(name.clone()),
+                ..SearchQuery::default()
+            };
+            let Ok(symbols) = self.search_symbols(&query).await else {
+                continue;
+            };
+            if let Some(symbol) = symbols.into_iter().find(|symbol| symbol.name == name) {
+                let file = symbol
+                    .file_path
+                    .strip_prefix(&self.project_root)
+                    .map_or_else(|_| symbol.file_path.clone(), Path::to_path_buf);
+                resolved.push(ResolvedReference {
+                    token,
+                    file,
+                    symbol: Some(symbol),
+                });
+            }
+        }
+        debug!("Resolved {} @ references", resolved.len());
+        resolved
+    }
+
     /// Build comprehensive context for a query, including:
+    /// - Files and symbols referenced with `@`, pinned ahead of everything else
     /// - Extracted file references
     /// - Vector/semantic search results
     /// - Relevant project files
@@ -180,6 +307,9 @@ impl ContextFetcher {
     pub async fn build_context_for_query(&self, query: &Query) -> Result<Context> {
         info!("Building context for query: {}", query.text);
 
+        let resolved = self.resolve_references(&query.text).await;
+        let query = &references::with_references(query, &resolved);
+
         // Extract explicitly mentioned files
         let explicit_files = self.extract_file_references(&query.text);
         debug!(
@@ -194,6 +324,14 @@ impl ContextFetcher {
             if let Some(callback) = callback_opt {
                 builder.set_progress_callback(callback);
             }
+            let index_callback = self.index_progress_callback.lock().await.clone();
+            if let Some(callback) = index_callback {
+                builder.set_index_progress_callback(callback);
+            }
+            let pull_callback = self.pull_progress_callback.lock().await.clone();
+            if let Some(callback) = pull_callback {
+                builder.set_pull_progress_callback(callback);
+            }
 
             let context = builder
                 .build_context(query)
@@ -204,19 +342,99 @@ impl ContextFetcher {
             return Ok(context);
         }
 
-        // Fallback: create basic context with explicit files only
-        let mut context = Context::new(&query.text);
+        // Fallback: create basic context with pinned and explicit files only
+        let mut explicit_contexts = Vec::new();
         for file_path in explicit_files {
             if let Ok(content) = read_to_string(&file_path).await {
-                let file_context = FileContext {
-                    path: file_path.clone(),
-                    content,
-                };
-                context.files.push(file_context);
+                explicit_contexts.push(FileContext::new(file_path, content));
             }
         }
+        let pinned = pinned::load_pinned_files(&self.project_root, &query.pinned_files).await;
 
-        Ok(context)
+        Ok(Context::new(&query.text).with_files(pinned::merge_pinned(
+            &self.project_root,
+            pinned,
+            explicit_contexts,
+            usize::MAX,
+        )))
+    }
+
+    /// Explains why each file of `context` was included
+    ///
+    /// Without a context builder only pinned files can be told apart, since
+    /// nothing else is recorded.
+    pub async fn explain_context(&self, context: &Context) -> ContextExplanation {
+        self.context_builder.lock().await.as_ref().map_or_else(
+            || ContextExplanation::for_context(context, &HashMap::new()),
+            |builder| builder.explain_context(context),
+        )
+    }
+
+    /// Updates the language index after the agent created, edited or deleted a file
+    ///
+    /// The file is read again, so a missing file counts as deleted.
+    pub async fn apply_file_change(&self, path: &Path) {
+        let new_text = read_to_string(path).await.ok();
+        if let Some(builder) = &mut *self.context_builder.lock().await {
+            builder.apply_file_change(path, new_text.as_deref());
+        }
+    }
+
+    /// Returns the functions and methods calling the symbol defined at `file`:`line`
+    ///
+    /// # Errors
+    /// Returns an error if the context builder is disabled or the lookup fails
+    pub async fn find_callers(
+        &self,
+        symbol_name: &str,
+        file: &Path,
+        line: u32,
+    ) -> Result<Vec<SymbolInfo>> {
+        let mut guard = self.context_builder.lock().await;
+        let builder = guard.as_mut().ok_or_else(navigation_unavailable)?;
+        builder
+            .find_callers(symbol_name, file, line)
+            .await
+            .map_err(|err| RoutingError::Other(format!("Finding callers failed: {err}")))
+    }
+
+    /// Returns the types implementing, extending or satisfying `type_name`
+    ///
+    /// # Errors
+    /// Returns an error if the context builder is disabled or the lookup fails
+    pub async fn find_implementations(&self, type_name: &str) -> Result<Vec<SymbolInfo>> {
+        let mut guard = self.context_builder.lock().await;
+        let builder = guard.as_mut().ok_or_else(navigation_unavailable)?;
+        builder
+            .find_implementations(type_name)
+            .await
+            .map_err(|err| RoutingError::Other(format!("Finding implementations failed: {err}")))
+    }
+
+    /// Returns the symbols matching `query`, exact name matches first
+    ///
+    /// # Errors
+    /// Returns an error if the context builder is disabled or the search fails
+    pub async fn search_symbols(&self, query: &SearchQuery) -> Result<Vec<SymbolInfo>> {
+        let mut guard = self.context_builder.lock().await;
+        let builder = guard.as_mut().ok_or_else(navigation_unavailable)?;
+        builder
+            .search_symbols(query)
+            .await
+            .map_err(|err| RoutingError::Other(format!("Searching symbols failed: {err}")))
+    }
+
+    /// Returns the source lines mentioning `symbol_name`
+    ///
+    /// # Errors
+    /// Returns an error if the context builder is disabled or the lookup fails
+    pub async fn find_references(&self, symbol_name: &str) -> Result<Vec<SymbolInfo>> {
+        let mut guard = self.context_builder.lock().await;
+        let builder = guard.as_mut().ok_or_else(navigation_unavailable)?;
+        builder
+            .find_references(symbol_name)
+            .await
+            .map_err(|err| RoutingError::Other(format!("Finding references failed: {err}")))
     }
 
     /// Build context from conversation history
@@ -268,3 +486,8 @@ impl ContextFetcher {
         Ok(context)
     }
 }
+
+/// Error returned by code navigation when the context builder is disabled
+fn navigation_unavailable() -> RoutingError {
+    RoutingError::Other("Code navigation is unavailable because indexing is disabled".to_owned())
+}
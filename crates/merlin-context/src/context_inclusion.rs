@@ -157,10 +157,7 @@ mod tests {
     use std::path::PathBuf;
 
     fn create_test_file(path: &str, content: &str) -> FileContext {
-        FileContext {
-            path: PathBuf::from(path),
-            content: content.to_owned(),
-        }
+        FileContext::new(PathBuf::from(path), content.to_owned())
     }
 
     /// Tests context manager initialization.
@@ -1,16 +1,21 @@
 //! BM25 keyword search implementation for file ranking.
 
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::iter::repeat_n;
 use std::path::PathBuf;
 
 /// BM25 parameters
 const TERM_SATURATION_K1: f32 = 1.5; // Term frequency saturation parameter
 const LENGTH_NORM_B: f32 = 0.75; // Length normalization parameter
+/// How many times a chunk header term counts, as headers name what the chunk defines
+const HEADER_TERM_WEIGHT: usize = 3;
 
 /// Document in the BM25 index
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
 struct Document {
     path: PathBuf,
     terms: HashMap<String, usize>, // term -> frequency
@@ -18,7 +23,10 @@ struct Document {
 }
 
 /// BM25 search index
-#[derive(Default)]
+///
+/// The finalized index can be persisted, so it does not have to be rebuilt
+/// from the embedding cache on every startup.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct BM25Index {
     documents: Vec<Document>,
     avg_doc_length: f32,
@@ -90,13 +98,24 @@ impl BM25Index {
 
     /// Add a document to the index
     pub fn add_document(&mut self, path: PathBuf, content: &str) {
-        let terms = Self::tokenize(content);
-        let term_freq = Self::count_terms(&terms);
-        let length = terms.len();
+        self.add_chunk(path, None, content);
+    }
+
+    /// Add a chunk to the index, weighting the terms of its header
+    ///
+    /// Header terms count [`HEADER_TERM_WEIGHT`] times towards term frequency
+    /// but once towards the document length.
+    pub fn add_chunk(&mut self, path: PathBuf, header: Option<&str>, content: &str) {
+        let mut terms = Self::tokenize(content);
+        let header_terms = header.map(Self::tokenize).unwrap_or_default();
+        let length = terms.len() + header_terms.len();
+        for term in header_terms {
+            terms.extend(repeat_n(term, HEADER_TERM_WEIGHT));
+        }
 
         self.documents.push(Document {
             path,
-            terms: term_freq,
+            terms: Self::count_terms(&terms),
             length,
         });
 
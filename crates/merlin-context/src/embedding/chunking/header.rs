@@ -0,0 +1,191 @@
+//! Chunk headers - name the item a chunk belongs to above its preview.
+//!
+//! A header reads `@@ impl Config > pub fn load(path: &Path) -> Result<Self> @@ Loads the config`:
+//! the enclosing items and the signature of the chunk's item, then the first
+//! line of the item's doc comment. The `@@` delimiters (as in diff hunk
+//! headers) keep it from being mistaken for code.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use super::FileChunk;
+
+/// Delimiter around the item path of a header
+pub const HEADER_MARKER: &str = "@@";
+
+/// Longest signature kept in a header, in characters
+const MAX_SIGNATURE_CHARS: usize = 100;
+/// Longest doc comment line kept in a header, in characters
+const MAX_DOC_CHARS: usize = 80;
+/// Most lines a signature is joined from
+const MAX_SIGNATURE_LINES: usize = 4;
+
+/// Start of a definition, after any visibility and modifier keywords
+static ITEM_START: LazyLock<Option<Regex>> = LazyLock::new(|| {
+    Regex::new(
+        r#"^(?:(?:pub(?:\([^)]*\))?|export|default|async|unsafe|const|extern(?:\s+"[^"]*")?|abstract|public|private|protected|static|override)\s+)*(fn|struct|enum|union|trait|impl|mod|type|class|interface|def|function|func)[\s<]"#,
+    )
+    .ok()
+});
+
+/// Item kinds that contain other items
+const CONTAINER_KINDS: &[&str] = &["impl", "mod", "trait", "class", "interface"];
+
+/// Builds the header of a chunk of the file with `lines`
+///
+/// The chunk's item is the first definition in the chunk, or the definition
+/// enclosing it when the chunk starts inside one. Returns `None` if neither exists.
+pub fn chunk_header(lines: &[&str], chunk: &FileChunk) -> Option<String> {
+    let item_index = item_line(lines, chunk)?;
+    let mut path = enclosing_items(lines, item_index);
+    path.push(signature(lines, item_index));
+    let path = path.join(" > ");
+    let mut header = format!("{HEADER_MARKER} {path} {HEADER_MARKER}");
+    if let Some(doc) = doc_line(lines, item_index) {
+        header.push(' ');
+        header.push_str(&doc);
+    }
+    Some(header)
+}
+
+/// Returns the item kind if `line` starts a definition
+fn item_kind(line: &str) -> Option<&str> {
+    ITEM_START
+        .as_ref()?
+        .captures(line.trim())
+        .and_then(|captures| captures.get(1))
+        .map(|kind| kind.as_str())
+}
+
+/// Width of the leading whitespace of `line`
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Index of the line declaring the chunk's item
+fn item_line(lines: &[&str], chunk: &FileChunk) -> Option<usize> {
+    let start = chunk.start_line.saturating_sub(1);
+    let end = chunk.end_line.min(lines.len());
+    if let Some(index) = (start..end).find(|&index| item_kind(lines[index]).is_some()) {
+        return Some(index);
+    }
+
+    // The chunk starts inside a definition, so name the one enclosing it
+    let first_indent = (start..end)
+        .map(|index| lines[index])
+        .find(|line| !line.trim().is_empty())
+        .map(indentation)?;
+    (0..start.min(lines.len())).rev().find(|&index| {
+        indentation(lines[index]) < first_indent && item_kind(lines[index]).is_some()
+    })
+}
+
+/// Short names of the containers (impls, classes, modules) enclosing the item, outermost first
+fn enclosing_items(lines: &[&str], item_index: usize) -> Vec<String> {
+    let mut containers = Vec::new();
+    let mut indent = indentation(lines[item_index]);
+    for index in (0..item_index).rev() {
+        if indent == 0 {
+            break;
+        }
+        let line = lines[index];
+        if line.trim().is_empty() || indentation(line) >= indent {
+            continue;
+        }
+        indent = indentation(line);
+        if item_kind(line).is_some_and(|kind| CONTAINER_KINDS.contains(&kind)) {
+            containers.push(container_name(line));
+        }
+    }
+    containers.reverse();
+    containers
+}
+
+/// Declaration of a container up to its body, without modifiers (e.g. `impl Config`)
+fn container_name(line: &str) -> String {
+    let declaration = line.trim();
+    let declaration = declaration
+        .find(['{', '(', ':'])
+        .map_or(declaration, |end| &declaration[..end]);
+    let start = ITEM_START
+        .as_ref()
+        .and_then(|item_start| item_start.captures(declaration))
+        .and_then(|captures| captures.get(1))
+        .map_or(0, |kind| kind.start());
+    declaration[start..].trim().to_owned()
+}
+
+/// Signature of the item, joined across lines until its parameters close
+fn signature(lines: &[&str], item_index: usize) -> String {
+    let mut signature = String::new();
+    let mut opened = 0;
+    let mut closed = 0;
+    for line in lines.iter().skip(item_index).take(MAX_SIGNATURE_LINES) {
+        let line = line.trim();
+        let body_start = line.find('{').unwrap_or(line.len());
+        let part = &line[..body_start];
+        if !signature.is_empty() && !signature.ends_with('(') {
+            signature.push(' ');
+        }
+        signature.push_str(part.trim());
+        opened += part.matches(['(', '[']).count();
+        closed += part.matches([')', ']']).count();
+        if body_start < line.len() || closed >= opened {
+            break;
+        }
+    }
+    let signature = signature.trim_end_matches([':', ' ']).replace(", )", ")");
+    truncate(&signature, MAX_SIGNATURE_CHARS)
+}
+
+/// First line of the item's doc comment, above it or (for Python) as its docstring
+fn doc_line(lines: &[&str], item_index: usize) -> Option<String> {
+    let mut comment_start = item_index;
+    while comment_start > 0 {
+        let line = lines[comment_start - 1].trim();
+        let is_comment = ["//", "/*", "*", "#"]
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+            && !line.starts_with("#[")
+            && !line.starts_with("#!");
+        let is_attribute = line.starts_with("#[") || line.starts_with('@');
+        if !is_comment && !is_attribute {
+            break;
+        }
+        comment_start -= 1;
+    }
+    let above = lines[comment_start..item_index]
+        .iter()
+        .filter(|line| !line.trim().starts_with("#[") && !line.trim().starts_with('@'));
+    let docstring = lines
+        .iter()
+        .skip(item_index + 1)
+        .take(MAX_SIGNATURE_LINES)
+        .skip_while(|line| !line.trim().starts_with("\"\"\"") && !line.trim().starts_with("'''"))
+        .take(2);
+    above
+        .chain(docstring)
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(['/', '*', '!', '#'])
+                .trim_start_matches("\"\"\"")
+                .trim_start_matches("'''")
+                .trim_end_matches("*/")
+                .trim_end_matches("\"\"\"")
+                .trim_end_matches("'''")
+                .trim()
+        })
+        .find(|text| !text.is_empty())
+        .map(|text| truncate(text, MAX_DOC_CHARS))
+}
+
+/// Cuts `text` to `max_chars` characters, marking the cut with `...`
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        let truncated: String = text.chars().take(max_chars).collect();
+        format!("{}...", truncated.trim_end())
+    } else {
+        text.to_owned()
+    }
+}
@@ -0,0 +1,134 @@
+//! Lockfile summaries - index dependency lockfiles by the names they lock.
+//!
+//! Lockfiles are large, generated and mostly versions and checksums, so they
+//! are replaced by one chunk naming the project's top-level dependencies.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde_json::{Value, from_str};
+
+use super::FileChunk;
+
+/// Lockfiles summarized instead of chunked
+const LOCKFILE_NAMES: &[&str] = &["Cargo.lock", "package-lock.json", "npm-shrinkwrap.json"];
+
+/// Most dependency names listed in a summary
+const MAX_SUMMARY_NAMES: usize = 200;
+
+/// Returns whether `path` is a lockfile that is summarized instead of chunked
+pub fn is_lockfile(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| LOCKFILE_NAMES.contains(&name))
+}
+
+/// Summarizes a lockfile as a single chunk listing its top-level dependency names
+///
+/// The chunk spans the whole file, so results point at the lockfile itself.
+pub fn chunk_lockfile(file_path: String, content: &str) -> Vec<FileChunk> {
+    let (locked, names) = if file_path.ends_with("Cargo.lock") {
+        cargo_dependencies(content)
+    } else {
+        npm_dependencies(content)
+    };
+    let file_name = Path::new(&file_path).file_name().map_or_else(
+        || file_path.clone(),
+        |name| name.to_string_lossy().into_owned(),
+    );
+
+    let mut names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let omitted = names.len().saturating_sub(MAX_SUMMARY_NAMES);
+    names.truncate(MAX_SUMMARY_NAMES);
+    let mut summary = format!(
+        "{file_name}: lockfile with {locked} locked packages\nTop-level dependencies: {}",
+        names.join(", ")
+    );
+    if omitted > 0 {
+        let _write_result = write!(summary, " and {omitted} more");
+    }
+
+    let end_line = content.lines().count().max(1);
+    vec![FileChunk::new(
+        file_path,
+        summary,
+        "dependencies".to_owned(),
+        1,
+        end_line,
+    )]
+}
+
+/// Locked package count and dependencies of the workspace's own packages in a `Cargo.lock`
+///
+/// Workspace packages are the ones without a `source`; if there are none,
+/// every locked package is listed.
+fn cargo_dependencies(content: &str) -> (usize, BTreeSet<String>) {
+    let mut locked = 0;
+    let mut local = BTreeSet::new();
+    let mut all = BTreeSet::new();
+    let mut top_level = BTreeSet::new();
+
+    for package in content.split("[[package]]").skip(1) {
+        locked += 1;
+        let Some(name) = package.lines().find_map(|line| toml_string(line, "name")) else {
+            continue;
+        };
+        all.insert(name.clone());
+        if package
+            .lines()
+            .any(|line| line.trim_start().starts_with("source"))
+        {
+            continue;
+        }
+        local.insert(name);
+        if let Some(list) = package.split("dependencies = [").nth(1) {
+            let list = list.split(']').next().unwrap_or_default();
+            top_level.extend(list.split(',').filter_map(|entry| {
+                let entry = entry.trim().trim_matches('"');
+                entry.split_whitespace().next().map(str::to_owned)
+            }));
+        }
+    }
+
+    if local.is_empty() {
+        return (locked, all);
+    }
+    (locked, &top_level - &local)
+}
+
+/// Value of a `key = "value"` line
+fn toml_string(line: &str, key: &str) -> Option<String> {
+    let (line_key, value) = line.split_once('=')?;
+    (line_key.trim() == key).then(|| value.trim().trim_matches('"').to_owned())
+}
+
+/// Locked package count and root dependencies of a `package-lock.json`
+fn npm_dependencies(content: &str) -> (usize, BTreeSet<String>) {
+    let Ok(lock) = from_str::<Value>(content) else {
+        return (0, BTreeSet::new());
+    };
+    // Version 2+ lists the root package under `packages[""]`, version 1 only has `dependencies`
+    let root = lock
+        .get("packages")
+        .and_then(|packages| packages.get(""))
+        .unwrap_or(&lock);
+    let names = [
+        "dependencies",
+        "devDependencies",
+        "optionalDependencies",
+        "peerDependencies",
+    ]
+    .iter()
+    .filter_map(|section| root.get(section).and_then(Value::as_object))
+    .flat_map(|dependencies| dependencies.keys().cloned())
+    .collect();
+    let locked = lock
+        .get("packages")
+        .or_else(|| lock.get("dependencies"))
+        .and_then(Value::as_object)
+        .map_or(0, |packages| {
+            packages.keys().filter(|key| !key.is_empty()).count()
+        });
+    (locked, names)
+}
@@ -2,16 +2,23 @@
 
 mod config;
 mod generic;
+mod header;
+mod lockfile;
 mod markdown;
 mod rust;
+mod syntax;
 mod text;
 
+use merlin_languages::chunker_for;
 use std::path::Path;
 
 pub use config::chunk_config;
 pub use generic::chunk_generic_code;
+pub use header::{HEADER_MARKER, chunk_header};
+pub use lockfile::{chunk_lockfile, is_lockfile};
 pub use markdown::chunk_markdown;
 pub use rust::chunk_rust;
+pub use syntax::chunk_syntax;
 pub use text::chunk_text;
 
 /// Optimal token range for chunks
@@ -46,6 +53,8 @@ pub struct FileChunk {
     pub start_line: usize,
     /// End line number (1-indexed)
     pub end_line: usize,
+    /// Enclosing item signature and doc line (see [`chunk_header`]), for code chunks
+    pub header: Option<String>,
 }
 
 impl FileChunk {
@@ -63,26 +72,45 @@ impl FileChunk {
             identifier,
             start_line,
             end_line,
+            header: None,
         }
     }
 }
 
 /// Chunk a file based on its extension
+///
+/// Languages with a backend in `merlin-languages` are chunked on definition
+/// boundaries; other code falls back to line-based chunking. Code chunks are
+/// given a header naming the item they belong to, and lockfiles are reduced to
+/// a summary of their dependencies.
 pub fn chunk_file(file_path: &Path, content: &str) -> Vec<FileChunk> {
     let path_str = file_path.display().to_string();
+    if is_lockfile(file_path) {
+        return chunk_lockfile(path_str, content);
+    }
 
-    let Some(extension) = file_path.extension() else {
-        return chunk_generic_code(path_str, content);
+    let ext = file_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    let chunks = match ext {
+        "md" | "markdown" => return chunk_markdown(&path_str, content),
+        "txt" | "log" => return chunk_text(path_str, content),
+        "toml" | "yaml" | "yml" | "json" => return chunk_config(path_str, content),
+        "rs" => chunk_rust(path_str, content),
+        _ => match chunker_for(file_path) {
+            Some(chunker) => chunk_syntax(path_str, file_path, content, chunker.as_ref()),
+            None => chunk_generic_code(path_str, content),
+        },
     };
-    if let Some(ext) = extension.to_str() {
-        match ext {
-            "rs" => chunk_rust(path_str, content),
-            "md" | "markdown" => chunk_markdown(&path_str, content),
-            "txt" | "log" => chunk_text(path_str, content),
-            "toml" | "yaml" | "yml" | "json" => chunk_config(path_str, content),
-            _ => chunk_generic_code(path_str, content),
-        }
-    } else {
-        chunk_generic_code(path_str, content)
+    with_headers(chunks, content)
+}
+
+/// Fills in the header of each code chunk of `content`
+fn with_headers(mut chunks: Vec<FileChunk>, content: &str) -> Vec<FileChunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    for chunk in &mut chunks {
+        chunk.header = chunk_header(&lines, chunk);
     }
+    chunks
 }
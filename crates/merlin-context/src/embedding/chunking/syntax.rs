@@ -0,0 +1,182 @@
+//! Syntax-aware code chunking - uses definition boundaries from the language backends.
+
+use super::{FileChunk, MAX_CHUNK_TOKENS, MIN_CHUNK_TOKENS, chunk_generic_code, estimate_tokens};
+use merlin_languages::{CodeChunker, CodeSpan};
+use std::path::Path;
+
+/// Chunk code on the definition boundaries reported by a language backend
+///
+/// Every top-level definition becomes a chunk, including its leading doc
+/// comment. Code between definitions (imports, statements) is kept with the
+/// definition that follows it. Definitions that exceed `MAX_CHUNK_TOKENS` are
+/// split at their nested definitions (methods, fields), and definitions below
+/// `MIN_CHUNK_TOKENS` are merged with their neighbours. Files that cannot be
+/// parsed or have no definitions fall back to generic chunking.
+pub fn chunk_syntax(
+    file_path: String,
+    path: &Path,
+    content: &str,
+    chunker: &dyn CodeChunker,
+) -> Vec<FileChunk> {
+    let spans = match chunker.code_spans(path, content) {
+        Ok(spans) if !spans.is_empty() => spans,
+        Ok(_) => return chunk_generic_code(file_path, content),
+        Err(error) => {
+            tracing::debug!("Falling back to generic chunking for {file_path}: {error}");
+            return chunk_generic_code(file_path, content);
+        }
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut segments = Vec::default();
+    let mut next_line = 1;
+
+    for span in &spans {
+        let start_line = next_line.min(span.start_line);
+        push_span(&lines, span, start_line, &mut segments);
+        next_line = next_line.max(span.end_line + 1);
+    }
+
+    if next_line <= lines.len() {
+        segments.push(Segment {
+            identifier: "module".to_owned(),
+            start_line: next_line,
+            end_line: lines.len(),
+        });
+    }
+
+    let mut chunks = Vec::default();
+    for segment in merge_small_segments(&lines, segments) {
+        push_segment(&file_path, &lines, &segment, &mut chunks);
+    }
+    chunks
+}
+
+/// A range of lines that becomes one chunk
+struct Segment {
+    identifier: String,
+    start_line: usize,
+    end_line: usize,
+}
+
+/// Adds a definition starting at `start_line` as one segment, or split at its
+/// nested definitions if it exceeds `MAX_CHUNK_TOKENS`
+fn push_span(lines: &[&str], span: &CodeSpan, start_line: usize, segments: &mut Vec<Segment>) {
+    let tokens = estimate_tokens(&join_lines(lines, start_line, span.end_line));
+    let Some(first) = span.children.first().filter(|_| tokens > MAX_CHUNK_TOKENS) else {
+        segments.push(Segment {
+            identifier: span.identifier.clone(),
+            start_line,
+            end_line: span.end_line,
+        });
+        return;
+    };
+
+    // The header (signature, docs, leading fields) up to the first nested definition
+    if first.start_line > start_line {
+        segments.push(Segment {
+            identifier: span.identifier.clone(),
+            start_line,
+            end_line: first.start_line - 1,
+        });
+    }
+
+    // Code between nested definitions stays with the one that follows it
+    let mut next_line = first.start_line.max(start_line);
+    for child in &span.children {
+        push_span(lines, child, next_line.min(child.start_line), segments);
+        next_line = next_line.max(child.end_line + 1);
+    }
+
+    // Code after the last nested definition (e.g. a closing brace) stays with it
+    if let Some(last) = segments.last_mut() {
+        last.end_line = last.end_line.max(span.end_line);
+    }
+}
+
+/// Merges segments below `MIN_CHUNK_TOKENS` into the following segment
+///
+/// A small trailing segment is merged into the previous one instead. Segments
+/// are only merged while the result stays within `MAX_CHUNK_TOKENS`, and the
+/// merged chunk keeps the identifier of its larger part.
+fn merge_small_segments(lines: &[&str], segments: Vec<Segment>) -> Vec<Segment> {
+    let tokens = |start_line, end_line| estimate_tokens(&join_lines(lines, start_line, end_line));
+    let mut merged: Vec<Segment> = Vec::default();
+
+    for segment in segments {
+        match merged.last_mut() {
+            Some(previous)
+                if tokens(previous.start_line, previous.end_line) < MIN_CHUNK_TOKENS
+                    && tokens(previous.start_line, segment.end_line) <= MAX_CHUNK_TOKENS =>
+            {
+                // The merged chunk is named after its larger part
+                if tokens(segment.start_line, segment.end_line)
+                    > tokens(previous.start_line, previous.end_line)
+                {
+                    previous.identifier = segment.identifier;
+                }
+                previous.end_line = segment.end_line;
+            }
+            _ => merged.push(segment),
+        }
+    }
+
+    if merged.len() > 1
+        && let Some(last) = merged.pop()
+    {
+        let fits = merged.last().is_some_and(|previous| {
+            tokens(last.start_line, last.end_line) < MIN_CHUNK_TOKENS
+                && tokens(previous.start_line, last.end_line) <= MAX_CHUNK_TOKENS
+        });
+        match merged.last_mut() {
+            Some(previous) if fits => previous.end_line = last.end_line,
+            _ => merged.push(last),
+        }
+    }
+    merged
+}
+
+/// Adds a segment as a chunk, splitting it with the generic chunker if it is too large
+fn push_segment(file_path: &str, lines: &[&str], segment: &Segment, chunks: &mut Vec<FileChunk>) {
+    let content = join_lines(lines, segment.start_line, segment.end_line);
+    if content.trim().is_empty() {
+        return;
+    }
+    let identifier = &segment.identifier;
+    if estimate_tokens(&content) <= MAX_CHUNK_TOKENS {
+        chunks.push(FileChunk::new(
+            file_path.to_owned(),
+            content,
+            identifier.clone(),
+            segment.start_line,
+            segment.end_line.min(lines.len()),
+        ));
+        return;
+    }
+
+    tracing::warn!("Splitting large chunk {identifier} by line count");
+    // Generic chunk line numbers are relative to the segment
+    let offset = segment.start_line - 1;
+    for (part, chunk) in chunk_generic_code(file_path.to_owned(), &content)
+        .into_iter()
+        .enumerate()
+    {
+        chunks.push(FileChunk::new(
+            chunk.file_path,
+            chunk.content,
+            format!("{identifier} (part {})", part + 1),
+            chunk.start_line + offset,
+            chunk.end_line + offset,
+        ));
+    }
+}
+
+/// Joins the lines `start_line..=end_line` (1-indexed)
+fn join_lines(lines: &[&str], start_line: usize, end_line: usize) -> String {
+    lines
+        .iter()
+        .take(end_line)
+        .skip(start_line.saturating_sub(1))
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n")
+}
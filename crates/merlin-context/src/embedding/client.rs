@@ -1,7 +1,10 @@
 //! Embedding and vector search functionality using Ollama.
 
+use crate::embedding::ProgressCallback;
+use crate::embedding::chunking::{FileChunk, HEADER_MARKER};
 use crate::models::ModelConfig;
 use merlin_core::{CoreResult as Result, Error};
+use merlin_local::OllamaManager;
 use ollama_rs::Ollama;
 use ollama_rs::generation::embeddings::request::GenerateEmbeddingsRequest;
 use std::cmp::Ordering;
@@ -9,7 +12,6 @@ use std::collections::HashMap;
 use std::env;
 use std::future::Future;
 use std::path::PathBuf;
-use std::process::Command;
 
 /// A single embedding vector
 type Embedding = Vec<f32>;
@@ -22,12 +24,32 @@ pub trait EmbeddingProvider: Send + Sync {
     /// Returns an error if the model is not available or cannot be loaded
     fn ensure_model_available(&self) -> impl Future<Output = Result<()>> + Send;
 
+    /// Download the embedding model if it is missing, reporting to `progress`
+    ///
+    /// Providers without a model to download do nothing.
+    ///
+    /// # Errors
+    /// Returns an error if the model cannot be downloaded
+    fn pull_model(
+        &self,
+        _progress: Option<&ProgressCallback>,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
     /// Generate embedding for text
     ///
     /// # Errors
     /// Returns an error if embedding generation fails
     fn embed(&self, text: &str) -> impl Future<Output = Result<Embedding>> + Send;
 
+    /// Whether [`embed_batch`](Self::embed_batch) embeds several texts in one request
+    ///
+    /// Providers without a batch endpoint are sent one text per request.
+    fn supports_batching(&self) -> bool {
+        true
+    }
+
     /// Embed multiple texts in batch (sends all at once for better performance)
     ///
     /// # Errors
@@ -134,6 +156,8 @@ impl VectorStore {
 #[derive(Clone)]
 pub struct OllamaEmbeddingClient {
     ollama: Ollama,
+    /// Downloads the model when it is missing
+    manager: OllamaManager,
     model: String,
 }
 
@@ -150,37 +174,28 @@ impl EmbeddingProvider for OllamaEmbeddingClient {
         };
 
         // Check if our embedding model is available
-        let model_available = models.iter().any(|model| model.name.contains(&self.model));
-
-        if !model_available {
-            tracing::info!("⚙️  Embedding model '{}' not found", self.model);
-            tracing::info!("⬇️  Pulling model from Ollama (this may take a few minutes)...");
-            tracing::info!("    Running: ollama pull {}", self.model);
-
-            // Pull the model using Ollama CLI with inherited stdio for progress
-            let status = Command::new("ollama")
-                .args(["pull", &self.model])
-                .status()
-                .map_err(|error| {
-                    Error::Other(format!(
-                        "Failed to run 'ollama pull {}': {}. Is Ollama installed?",
-                        self.model, error
-                    ))
-                })?;
-
-            if !status.success() {
-                return Err(Error::Other(format!(
-                    "Failed to pull model '{}'. Check Ollama is running.",
-                    self.model
-                )));
-            }
-
-            tracing::info!("✓ Successfully pulled embedding model '{}'", self.model);
+        if !models.iter().any(|model| model.name.contains(&self.model)) {
+            return Err(Error::Other(format!(
+                "Embedding model '{}' not found. Run: ollama pull {}",
+                self.model, self.model
+            )));
         }
 
         Ok(())
     }
 
+    async fn pull_model(&self, progress: Option<&ProgressCallback>) -> Result<()> {
+        self.manager
+            .ensure_model_pulled(&self.model, progress)
+            .await
+            .map_err(|error| {
+                Error::Other(format!(
+                    "Failed to pull embedding model '{}': {error}",
+                    self.model
+                ))
+            })
+    }
+
     async fn embed(&self, text: &str) -> Result<Embedding> {
         let request = GenerateEmbeddingsRequest::new(self.model.clone(), text.to_string().into());
 
@@ -247,12 +262,41 @@ impl Default for OllamaEmbeddingClient {
         let host = env::var("OLLAMA_HOST").unwrap_or_else(|_| "http://localhost:11434".to_string());
         let config = ModelConfig::from_env();
         Self {
+            manager: OllamaManager::default().with_url(host.clone()),
             ollama: Ollama::new(host, 11434),
             model: config.embedding,
         }
     }
 }
 
+/// Embedding provider used while embeddings are disabled
+///
+/// Every call fails, so a `VectorSearchManager` built on it never embeds and
+/// serves BM25 keyword search only. Lets Merlin run where Ollama is unavailable.
+#[derive(Clone, Copy, Default)]
+pub struct NoOpEmbeddingProvider;
+
+impl NoOpEmbeddingProvider {
+    /// Error returned by every call
+    fn disabled() -> Error {
+        Error::Other("Embeddings are disabled".to_owned())
+    }
+}
+
+impl EmbeddingProvider for NoOpEmbeddingProvider {
+    async fn ensure_model_available(&self) -> Result<()> {
+        Err(Self::disabled())
+    }
+
+    async fn embed(&self, _text: &str) -> Result<Embedding> {
+        Err(Self::disabled())
+    }
+
+    async fn embed_batch(&self, _texts: Vec<String>) -> Result<Vec<Embedding>> {
+        Err(Self::disabled())
+    }
+}
+
 /// Test-only fake embedding provider (deterministic, hash-based)
 ///
 /// Available in test builds for fast, deterministic embeddings.
@@ -327,6 +371,27 @@ fn cosine_similarity(vector_a: &[f32], vector_b: &[f32]) -> f32 {
     dot_product / (magnitude_a * magnitude_b)
 }
 
+/// Generate a preview of a chunk, led by its header when it has one
+///
+/// The header takes at most half of `max_chars` and the content the rest, so
+/// the preview stays within the same limit as [`generate_preview`].
+pub fn chunk_preview(chunk: &FileChunk, max_chars: usize) -> String {
+    let Some(header) = &chunk.header else {
+        return generate_preview(&chunk.content, max_chars);
+    };
+    let header = generate_preview(header, max_chars / 2);
+    let body_chars = max_chars.saturating_sub(header.chars().count() + 1);
+    format!("{header}\n{}", generate_preview(&chunk.content, body_chars))
+}
+
+/// Split a preview into its chunk header (see [`chunk_preview`]) and content
+pub fn split_preview_header(preview: &str) -> (Option<&str>, &str) {
+    match preview.split_once('\n') {
+        Some((header, content)) if header.starts_with(HEADER_MARKER) => (Some(header), content),
+        _ => (None, preview),
+    }
+}
+
 /// Generate a preview from file content (first few lines or summary)
 pub fn generate_preview(content: &str, max_chars: usize) -> String {
     let lines: Vec<&str> = content.lines().take(10).collect();
@@ -10,6 +10,7 @@ pub use chunking::{FileChunk, chunk_file};
 #[cfg(any(test, feature = "test-helpers"))]
 pub use client::FakeEmbeddingClient;
 pub use client::{
-    EmbeddingClient, EmbeddingProvider, SearchResult, VectorEntry, VectorStore, generate_preview,
+    EmbeddingClient, EmbeddingProvider, NoOpEmbeddingProvider, SearchResult, VectorEntry,
+    VectorStore, chunk_preview, generate_preview, split_preview_header,
 };
 pub use vector_search::{ProgressCallback, VectorSearchManager};
@@ -0,0 +1,176 @@
+//! Embedding batch size derived from available memory.
+//!
+//! Every chunk of a batch is held in memory by the embedding model at once, so
+//! a large batch on a small machine can run out of memory. The batch size is
+//! derived from the memory available when embedding starts, clamped to
+//! [`MIN_BATCH_SIZE`]..=[`MAX_BATCH_SIZE`], unless [`BATCH_SIZE_ENV`] sets it.
+
+use std::env;
+use std::fs;
+
+use tracing::{info, warn};
+
+/// Environment variable overriding the batch size
+pub const BATCH_SIZE_ENV: &str = "MERLIN_EMBED_BATCH_SIZE";
+
+/// Smallest batch size used
+pub const MIN_BATCH_SIZE: usize = 1;
+
+/// Largest batch size used
+pub const MAX_BATCH_SIZE: usize = 128;
+
+/// Batch size used when the available memory is unknown
+const DEFAULT_BATCH_SIZE: usize = 50;
+
+/// Estimated memory needed to embed one chunk of a batch
+///
+/// Covers the chunk text, its request and response, and the activations of a
+/// local embedding model, so 8 GB of available memory allows 64 chunks.
+const ESTIMATED_BYTES_PER_CHUNK: u64 = 128 * 1024 * 1024;
+
+/// Where the batch size came from, for logging
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BatchSizeSource {
+    /// Set by [`BATCH_SIZE_ENV`]
+    Environment,
+    /// Derived from this many bytes of available memory
+    Memory(u64),
+    /// Available memory unknown
+    Default,
+}
+
+/// Returns the number of chunks to embed per request, logging how it was chosen
+pub fn embedding_batch_size() -> usize {
+    let override_value = env::var(BATCH_SIZE_ENV).ok();
+    let (batch_size, source) = resolve_batch_size(override_value.as_deref(), available_memory);
+    match source {
+        BatchSizeSource::Environment => {
+            info!("Embedding batch size: {batch_size} (set by {BATCH_SIZE_ENV})");
+        }
+        BatchSizeSource::Memory(available) => info!(
+            "Embedding batch size: {batch_size} ({} MB available memory)",
+            available / (1024 * 1024)
+        ),
+        BatchSizeSource::Default => info!(
+            "Embedding batch size: {batch_size} (available memory unknown, set {BATCH_SIZE_ENV} to override)"
+        ),
+    }
+    batch_size
+}
+
+/// Chooses the batch size from an override or the available memory
+///
+/// `available_memory` is only queried without a valid override.
+fn resolve_batch_size(
+    override_value: Option<&str>,
+    available_memory: impl FnOnce() -> Option<u64>,
+) -> (usize, BatchSizeSource) {
+    if let Some(value) = override_value {
+        match value.trim().parse::<usize>() {
+            Ok(size) => {
+                return (
+                    size.clamp(MIN_BATCH_SIZE, MAX_BATCH_SIZE),
+                    BatchSizeSource::Environment,
+                );
+            }
+            Err(error) => warn!("Ignoring {BATCH_SIZE_ENV}={value:?}: {error}"),
+        }
+    }
+    available_memory().map_or(
+        (DEFAULT_BATCH_SIZE, BatchSizeSource::Default),
+        |available| {
+            (
+                batch_size_for_memory(available),
+                BatchSizeSource::Memory(available),
+            )
+        },
+    )
+}
+
+/// Returns the batch size fitting in `available` bytes of memory
+pub fn batch_size_for_memory(available: u64) -> usize {
+    usize::try_from(available / ESTIMATED_BYTES_PER_CHUNK)
+        .unwrap_or(MAX_BATCH_SIZE)
+        .clamp(MIN_BATCH_SIZE, MAX_BATCH_SIZE)
+}
+
+/// Reads the memory available to new processes, on Linux
+fn available_memory() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_available(&meminfo)
+}
+
+/// Parses `MemAvailable` from the contents of `/proc/meminfo`, in bytes
+fn parse_mem_available(meminfo: &str) -> Option<u64> {
+    let kilobytes = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    kilobytes.checked_mul(1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gibibytes in bytes
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    /// Tests that the batch size scales with memory within its bounds.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_batch_size_for_memory() {
+        assert_eq!(batch_size_for_memory(8 * GIB), 64);
+        assert_eq!(batch_size_for_memory(2 * GIB), 16);
+        assert_eq!(batch_size_for_memory(64 * 1024 * 1024), MIN_BATCH_SIZE);
+        assert_eq!(batch_size_for_memory(0), MIN_BATCH_SIZE);
+        assert_eq!(batch_size_for_memory(u64::MAX), MAX_BATCH_SIZE);
+    }
+
+    /// Tests that a valid override wins and an invalid one falls back to memory.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_resolve_batch_size() {
+        let no_memory = || None;
+        assert_eq!(
+            resolve_batch_size(Some(" 32 "), || Some(GIB)),
+            (32, BatchSizeSource::Environment)
+        );
+        assert_eq!(
+            resolve_batch_size(Some("1000"), no_memory),
+            (MAX_BATCH_SIZE, BatchSizeSource::Environment)
+        );
+        assert_eq!(
+            resolve_batch_size(Some("0"), no_memory),
+            (MIN_BATCH_SIZE, BatchSizeSource::Environment)
+        );
+        assert_eq!(
+            resolve_batch_size(Some("lots"), || Some(4 * GIB)),
+            (32, BatchSizeSource::Memory(4 * GIB))
+        );
+        assert_eq!(
+            resolve_batch_size(None, no_memory),
+            (DEFAULT_BATCH_SIZE, BatchSizeSource::Default)
+        );
+    }
+
+    /// Tests parsing `MemAvailable` out of `/proc/meminfo`.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_mem_available() {
+        let meminfo = "MemTotal:       16318480 kB\nMemFree:         1203344 kB\nMemAvailable:    8388608 kB\n";
+        assert_eq!(parse_mem_available(meminfo), Some(8 * GIB));
+        assert_eq!(parse_mem_available("MemTotal: 100 kB\n"), None);
+        assert_eq!(parse_mem_available("MemAvailable: many kB\n"), None);
+    }
+}
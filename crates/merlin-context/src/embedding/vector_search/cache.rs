@@ -3,14 +3,21 @@
 use bincode::config::standard as bincode_config;
 use bincode::{Decode, Encode, decode_from_slice, encode_to_vec};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::hash::{Hash as _, Hasher as _};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
+use std::{fs, io};
 use tokio::task::spawn_blocking;
-use tracing::info;
+use tracing::{debug, info};
 
+use crate::embedding::BM25Index;
 use merlin_core::{CoreResult as Result, Error};
+use merlin_tooling::join_error;
+
+/// Suffix appended to the embedding cache path for the persisted BM25 index
+const BM25_CACHE_SUFFIX: &str = ".bm25.bin";
 
 /// Cache entry for a chunk embedding
 #[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
@@ -53,7 +60,7 @@ impl Default for VectorCache {
 
 impl VectorCache {
     /// Cache version identifier
-    pub const VERSION: u32 = 5; // Bumped for content_hash field
+    pub const VERSION: u32 = 7; // Bumped for skipping binary, huge and lock files
 
     /// Check if cache version is valid
     pub fn is_valid(&self) -> bool {
@@ -61,6 +68,28 @@ impl VectorCache {
     }
 }
 
+/// Persisted BM25 index, stored next to the embedding cache
+#[derive(Debug, Serialize, Deserialize, Encode, Decode)]
+pub struct Bm25Cache {
+    /// Version identifier for cache invalidation
+    pub version: u32,
+    /// Hash of the cached embeddings the index was built from (see [`CacheOperations::index_hash`])
+    pub index_hash: u64,
+    /// Finalized index
+    pub index: BM25Index,
+}
+
+impl Bm25Cache {
+    /// Cache version identifier (bump when tokenization or scoring changes)
+    pub const VERSION: u32 = 2;
+
+    /// Returns the index if it was built from exactly these embeddings
+    pub fn into_index_for(self, entries: &[CachedEmbedding]) -> Option<BM25Index> {
+        (self.version == Self::VERSION && self.index_hash == CacheOperations::index_hash(entries))
+            .then_some(self.index)
+    }
+}
+
 /// Cache operations
 pub struct CacheOperations {
     /// Cache file path
@@ -81,17 +110,22 @@ impl CacheOperations {
         }
     }
 
-    /// Load cache from disk
+    /// Path of the persisted BM25 index (`{cache_path}.bm25.bin`)
+    fn bm25_path(&self) -> PathBuf {
+        let mut path = self.cache_path.clone().into_os_string();
+        path.push(BM25_CACHE_SUFFIX);
+        PathBuf::from(path)
+    }
+
+    /// Load cache from disk, along with the persisted BM25 index if present
     ///
     /// # Errors
     /// Returns an error if the cache file cannot be read or deserialized
-    pub async fn load_cache(&self) -> Result<VectorCache> {
+    pub async fn load_cache(&self) -> Result<(VectorCache, Option<Bm25Cache>)> {
         use tokio::fs as async_fs;
 
         // Read cache file asynchronously to avoid blocking
-        let data = async_fs::read(&self.cache_path)
-            .await
-            .map_err(|error| Error::Other(format!("Failed to read cache: {error}")))?;
+        let data = async_fs::read(&self.cache_path).await?;
 
         // Deserialize in blocking task (CPU-bound operation)
         let cache = spawn_blocking(move || {
@@ -100,17 +134,47 @@ impl CacheOperations {
                 .map(|(cache, _)| cache)
         })
         .await
-        .map_err(|error| Error::Other(format!("Task join error: {error}")))??;
+        .map_err(|error| {
+            Error::Other(join_error("Embedding cache decoding", error).to_string())
+        })??;
 
-        Ok(cache)
+        Ok((cache, self.load_bm25().await))
+    }
+
+    /// Load the persisted BM25 index, if present and readable
+    async fn load_bm25(&self) -> Option<Bm25Cache> {
+        use tokio::fs as async_fs;
+
+        let data = async_fs::read(self.bm25_path()).await.ok()?;
+        let decoded = spawn_blocking(move || decode_from_slice(&data, bincode_config())).await;
+        match decoded {
+            Ok(Ok((bm25, _))) => Some(bm25),
+            Ok(Err(error)) => {
+                debug!("Ignoring unreadable BM25 cache: {error}");
+                None
+            }
+            Err(error) => {
+                debug!("{}", join_error("BM25 cache decoding", error));
+                None
+            }
+        }
     }
 
     /// Save cache to disk (async version)
     ///
     /// # Errors
     /// Returns an error if the cache directory cannot be created or serialization fails
-    pub async fn save_cache_async(&self, embeddings: Vec<CachedEmbedding>) -> Result<()> {
+    pub async fn save_cache_async(
+        &self,
+        embeddings: Vec<CachedEmbedding>,
+        bm25: &BM25Index,
+    ) -> Result<()> {
         self.ensure_cache_dir()?;
+        let bm25_cache = Bm25Cache {
+            version: Bm25Cache::VERSION,
+            index_hash: Self::index_hash(&embeddings),
+            index: bm25.clone(),
+        };
         let cache = VectorCache {
             version: VectorCache::VERSION,
             embeddings,
@@ -121,15 +185,26 @@ impl CacheOperations {
             self.cache_path.display()
         );
 
-        let bytes = spawn_blocking(move || {
-            encode_to_vec(&cache, bincode_config())
-                .map_err(|error| Error::Other(format!("Failed to serialize cache: {error}")))
+        let (bytes, bm25_bytes) = spawn_blocking(move || {
+            let bytes = encode_to_vec(&cache, bincode_config())
+                .map_err(|error| Error::Other(format!("Failed to serialize cache: {error}")))?;
+            let bm25_bytes = encode_to_vec(&bm25_cache, bincode_config()).map_err(|error| {
+                Error::Other(format!("Failed to serialize BM25 cache: {error}"))
+            })?;
+            Ok::<_, Error>((bytes, bm25_bytes))
         })
         .await
-        .map_err(|error| Error::Other(format!("Task join error: {error}")))??;
+        .map_err(|error| {
+            Error::Other(join_error("Embedding cache encoding", error).to_string())
+        })??;
 
-        self.write_cache_bytes_async(&bytes).await?;
-        info!("  ✓ Cache saved successfully ({} bytes)", bytes.len());
+        write_cache_bytes_async(&self.cache_path, &bytes).await?;
+        write_cache_bytes_async(&self.bm25_path(), &bm25_bytes).await?;
+        info!(
+            "  ✓ Cache saved successfully ({} bytes, BM25 index {} bytes)",
+            bytes.len(),
+            bm25_bytes.len()
+        );
         Ok(())
     }
 
@@ -137,8 +212,17 @@ impl CacheOperations {
     ///
     /// # Errors
     /// Returns an error if the cache directory cannot be created or serialization fails
-    pub fn save_cache_sync(&self, embeddings: Vec<CachedEmbedding>) -> Result<()> {
+    pub fn save_cache_sync(
+        &self,
+        embeddings: Vec<CachedEmbedding>,
+        bm25: &BM25Index,
+    ) -> Result<()> {
         self.ensure_cache_dir()?;
+        let bm25_cache = Bm25Cache {
+            version: Bm25Cache::VERSION,
+            index_hash: Self::index_hash(&embeddings),
+            index: bm25.clone(),
+        };
         let cache = VectorCache {
             version: VectorCache::VERSION,
             embeddings,
@@ -150,8 +234,15 @@ impl CacheOperations {
         );
         let bytes = encode_to_vec(&cache, bincode_config())
             .map_err(|error| Error::Other(format!("Failed to serialize cache: {error}")))?;
-        self.write_cache_bytes_sync(&bytes)?;
-        info!("  ✓ Cache saved successfully ({} bytes)", bytes.len());
+        write_cache_bytes_sync(&self.cache_path, &bytes)?;
+        let bm25_bytes = encode_to_vec(&bm25_cache, bincode_config())
+            .map_err(|error| Error::Other(format!("Failed to serialize BM25 cache: {error}")))?;
+        write_cache_bytes_sync(&self.bm25_path(), &bm25_bytes)?;
+        info!(
+            "  ✓ Cache saved successfully ({} bytes, BM25 index {} bytes)",
+            bytes.len(),
+            bm25_bytes.len()
+        );
         Ok(())
     }
 
@@ -195,11 +286,33 @@ impl CacheOperations {
         (valid, invalid)
     }
 
+    /// Hash identifying a set of cached embeddings, independent of their order
+    ///
+    /// Covers every chunk's location and its file's content hash, so the BM25
+    /// index saved with one set of embeddings is not reused once a file changes.
+    pub fn index_hash(entries: &[CachedEmbedding]) -> u64 {
+        let mut chunks: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    &entry.path,
+                    entry.start_line,
+                    entry.end_line,
+                    entry.content_hash,
+                )
+            })
+            .collect();
+        chunks.sort_unstable();
+
+        let mut hasher = DefaultHasher::new();
+        for chunk in &chunks {
+            chunk.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
     /// Compute hash of file content for cache validation
     pub fn compute_file_hash(content: &str) -> u64 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash as _, Hasher as _};
-
         let mut hasher = DefaultHasher::new();
         content.hash(&mut hasher);
         hasher.finish()
@@ -211,59 +324,52 @@ impl CacheOperations {
     /// Returns an error if the cache directory cannot be created
     fn ensure_cache_dir(&self) -> Result<()> {
         if let Some(parent) = self.cache_path.parent() {
-            fs::create_dir_all(parent).map_err(|error| {
-                Error::Other(format!("Failed to create cache directory: {error}"))
-            })?;
+            fs::create_dir_all(parent)?;
         }
         Ok(())
     }
+}
 
-    /// Write cache bytes to current `cache_path` (async version)
-    ///
-    /// # Errors
-    /// Returns an error if the write fails even after ensuring parent dir exists
-    async fn write_cache_bytes_async(&self, data: &[u8]) -> Result<()> {
-        use tokio::fs as async_fs;
-
-        let cache_path = self.cache_path.clone();
-        let data_vec = data.to_vec();
+/// Write cache bytes to `cache_path` (async version)
+///
+/// # Errors
+/// Returns an error if the write fails even after ensuring parent dir exists
+async fn write_cache_bytes_async(cache_path: &Path, data: &[u8]) -> Result<()> {
+    use tokio::fs as async_fs;
 
-        if let Err(write_error) = async_fs::write(&cache_path, &data_vec).await {
-            if let Some(parent) = cache_path.parent() {
-                async_fs::create_dir_all(parent).await.map_err(|error| {
-                    Error::Other(format!("Failed to create cache directory: {error}"))
-                })?;
-            }
-            async_fs::write(&cache_path, &data_vec)
-                .await
-                .map_err(|error| {
-                    Error::Other(format!(
-                        "Failed to write cache to {}: {error}. Prior error: {write_error}",
-                        cache_path.display()
-                    ))
-                })?;
+    if let Err(write_error) = async_fs::write(cache_path, data).await {
+        if let Some(parent) = cache_path.parent() {
+            async_fs::create_dir_all(parent).await?;
         }
-        Ok(())
+        async_fs::write(cache_path, data)
+            .await
+            .map_err(|error| cache_write_error(cache_path, &error, &write_error))?;
     }
+    Ok(())
+}
 
-    /// Write cache bytes to current `cache_path` (sync version for Drop)
-    ///
-    /// # Errors
-    /// Returns an error if the write fails even after ensuring parent dir exists
-    fn write_cache_bytes_sync(&self, data: &[u8]) -> Result<()> {
-        if let Err(write_error) = fs::write(&self.cache_path, data) {
-            if let Some(parent) = self.cache_path.parent() {
-                fs::create_dir_all(parent).map_err(|error| {
-                    Error::Other(format!("Failed to create cache directory: {error}"))
-                })?;
-            }
-            fs::write(&self.cache_path, data).map_err(|error| {
-                Error::Other(format!(
-                    "Failed to write cache to {}: {error}. Prior error: {write_error}",
-                    self.cache_path.display()
-                ))
-            })?;
+/// Write cache bytes to `cache_path` (sync version for Drop)
+///
+/// # Errors
+/// Returns an error if the write fails even after ensuring parent dir exists
+fn write_cache_bytes_sync(cache_path: &Path, data: &[u8]) -> Result<()> {
+    if let Err(write_error) = fs::write(cache_path, data) {
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent)?;
         }
-        Ok(())
+        fs::write(cache_path, data)
+            .map_err(|error| cache_write_error(cache_path, &error, &write_error))?;
     }
+    Ok(())
+}
+
+/// Wraps a failed cache write retry, keeping the IO error kind and both failure reasons
+fn cache_write_error(cache_path: &Path, error: &io::Error, first_error: &io::Error) -> Error {
+    Error::Io(io::Error::new(
+        error.kind(),
+        format!(
+            "Failed to write cache to {}: {error}. Prior error: {first_error}",
+            cache_path.display()
+        ),
+    ))
 }
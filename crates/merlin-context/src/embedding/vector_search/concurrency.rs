@@ -0,0 +1,79 @@
+//! Number of embedding requests in flight at once.
+//!
+//! Ollama serves several requests in parallel, so a cold index finishes much
+//! sooner when requests overlap. The concurrency defaults to half the available
+//! CPU cores, clamped to [`MIN_CONCURRENCY`]..=[`DEFAULT_MAX_CONCURRENCY`],
+//! unless [`CONCURRENCY_ENV`] sets it (up to [`MAX_CONCURRENCY`]).
+
+use std::env;
+use std::num::NonZeroUsize;
+use std::thread;
+
+use tracing::{info, warn};
+
+/// Environment variable overriding the number of concurrent embedding requests
+pub const CONCURRENCY_ENV: &str = "MERLIN_EMBED_CONCURRENCY";
+
+/// Fewest concurrent embedding requests
+pub const MIN_CONCURRENCY: usize = 1;
+
+/// Most concurrent embedding requests, even when set by [`CONCURRENCY_ENV`]
+pub const MAX_CONCURRENCY: usize = 32;
+
+/// Most concurrent embedding requests chosen from the CPU count
+///
+/// Ollama queues requests beyond its `OLLAMA_NUM_PARALLEL` (4 by default),
+/// so more in flight only adds memory.
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Returns the number of embedding requests to keep in flight, logging how it was chosen
+pub fn embedding_concurrency() -> usize {
+    let override_value = env::var(CONCURRENCY_ENV).ok();
+    let concurrency = resolve_concurrency(override_value.as_deref(), || {
+        thread::available_parallelism().ok()
+    });
+    info!("Embedding concurrency: {concurrency} (set {CONCURRENCY_ENV} to override)");
+    concurrency
+}
+
+/// Chooses the concurrency from an override or the number of CPU cores
+///
+/// `cores` is only queried without a valid override.
+fn resolve_concurrency(
+    override_value: Option<&str>,
+    cores: impl FnOnce() -> Option<NonZeroUsize>,
+) -> usize {
+    if let Some(value) = override_value {
+        match value.trim().parse::<usize>() {
+            Ok(concurrency) => return concurrency.clamp(MIN_CONCURRENCY, MAX_CONCURRENCY),
+            Err(error) => warn!("Ignoring {CONCURRENCY_ENV}={value:?}: {error}"),
+        }
+    }
+    cores().map_or(MIN_CONCURRENCY, |cores| {
+        (cores.get() / 2).clamp(MIN_CONCURRENCY, DEFAULT_MAX_CONCURRENCY)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that a valid override wins and the default follows the CPU count.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_resolve_concurrency() {
+        let cores = |count| move || NonZeroUsize::new(count);
+        assert_eq!(resolve_concurrency(Some(" 6 "), cores(2)), 6);
+        assert_eq!(resolve_concurrency(Some("0"), cores(2)), MIN_CONCURRENCY);
+        assert_eq!(resolve_concurrency(Some("1000"), cores(2)), MAX_CONCURRENCY);
+        assert_eq!(resolve_concurrency(Some("many"), cores(4)), 2);
+        assert_eq!(resolve_concurrency(None, cores(1)), MIN_CONCURRENCY);
+        assert_eq!(
+            resolve_concurrency(None, cores(64)),
+            DEFAULT_MAX_CONCURRENCY
+        );
+        assert_eq!(resolve_concurrency(None, || None), MIN_CONCURRENCY);
+    }
+}
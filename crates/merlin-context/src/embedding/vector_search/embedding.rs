@@ -1,22 +1,35 @@
 //! Embedding operations for files and chunks.
 
+use futures::future::join;
 use futures::stream::{FuturesUnordered, StreamExt as _};
 use std::collections::HashMap;
-use std::fs;
+use std::mem;
 use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
 use std::sync::Arc;
 use std::time::SystemTime;
-use tokio::task::spawn_blocking;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::task::{JoinError, JoinSet, spawn_blocking};
 use tracing::{info, warn};
 
-use crate::embedding::chunking::{FileChunk, chunk_file};
-use crate::embedding::vector_search::cache::{CacheOperations, CachedEmbedding};
-use crate::embedding::{EmbeddingProvider, generate_preview};
+use crate::embedding::chunking::FileChunk;
+use crate::embedding::vector_search::batch_size::embedding_batch_size;
+use crate::embedding::vector_search::cache::CachedEmbedding;
+use crate::embedding::vector_search::concurrency::embedding_concurrency;
+use crate::embedding::vector_search::sections::{DEFAULT_STREAMING_THRESHOLD, read_and_chunk};
+use crate::embedding::{EmbeddingProvider, chunk_preview};
 use merlin_core::CoreResult as Result;
+use merlin_tooling::join_error;
 
 type ChunkResult = (PathBuf, FileChunk, Vec<f32>, String, u64);
-type FileChunksData = (PathBuf, String, Vec<FileChunk>, u64);
-type FileChunkMap = HashMap<PathBuf, Vec<(usize, FileChunk, u64)>>;
+type FileChunksData = (PathBuf, Vec<FileChunk>, u64);
+/// Chunk waiting to be embedded, with its file and the file's content hash
+type ChunkJob = (PathBuf, FileChunk, u64);
+/// Outcome of an embedding request, with the chunks it embedded
+type RequestOutcome = (Vec<ChunkJob>, Result<Vec<Vec<f32>>>);
+
+/// Files read and chunked at once
+const MAX_CONCURRENT_READS: usize = 20;
 
 /// Progress callback for embedding operations
 pub type ProgressCallback = Arc<dyn Fn(&str, u64, Option<u64>) + Send + Sync>;
@@ -29,9 +42,13 @@ pub struct EmbeddingOperations<E: EmbeddingProvider + Clone> {
     project_root: PathBuf,
     /// Optional progress callback
     progress_callback: Option<ProgressCallback>,
+    /// Embedding requests in flight at once
+    concurrency: usize,
+    /// Size in bytes from which files are read and chunked a section at a time
+    streaming_threshold: u64,
 }
 
-impl<E: EmbeddingProvider + Clone> EmbeddingOperations<E> {
+impl<E: EmbeddingProvider + Clone + 'static> EmbeddingOperations<E> {
     /// Create new embedding operations
     pub fn new(
         client: E,
@@ -42,9 +59,25 @@ impl<E: EmbeddingProvider + Clone> EmbeddingOperations<E> {
             client,
             project_root,
             progress_callback,
+            concurrency: embedding_concurrency(),
+            streaming_threshold: DEFAULT_STREAMING_THRESHOLD,
         }
     }
 
+    /// Keep up to `concurrency` embedding requests in flight (at least one)
+    #[must_use]
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Read files of at least `bytes` bytes a section at a time (default 64 KB)
+    #[must_use]
+    pub const fn with_streaming_threshold(mut self, bytes: u64) -> Self {
+        self.streaming_threshold = bytes;
+        self
+    }
+
     /// Report progress if callback is set
     fn report_progress(&self, stage: &str, current: u64, total: Option<u64>) {
         if let Some(callback) = &self.progress_callback {
@@ -52,118 +85,121 @@ impl<E: EmbeddingProvider + Clone> EmbeddingOperations<E> {
         }
     }
 
-    /// Embed a batch of files (chunked) - optimized version
+    /// Embed a batch of files (chunked)
+    ///
+    /// Files are read and chunked on blocking threads while earlier chunks are
+    /// embedded, with up to the configured number of requests in flight. Chunks
+    /// of a failed request are retried one at a time once everything else is
+    /// embedded; chunks failing again are skipped.
     ///
     /// # Errors
     /// Returns an error if any embedding task fails
     pub async fn embed_files(&self, files: Vec<PathBuf>) -> Result<Vec<ChunkResult>> {
         let total_files = files.len();
         info!(
-            "Starting optimized embedding pipeline for {} files",
-            total_files
+            "Starting embedding pipeline for {total_files} files ({} concurrent requests)",
+            self.concurrency
         );
-
-        // Phase 1: Parallel file reading and chunking (CPU-bound)
-        tracing::info!("Reading and chunking files...");
         self.report_progress("Reading files", 0, Some(total_files as u64));
-        let file_chunks_data = Self::parallel_read_and_chunk(files, &self.project_root).await;
 
-        info!("Chunked {} files into chunks", file_chunks_data.len());
-        self.report_progress(
-            "Chunking complete",
-            file_chunks_data.len() as u64,
-            Some(total_files as u64),
+        let (sender, receiver) = mpsc::channel(MAX_CONCURRENT_READS);
+        let reading = Self::parallel_read_and_chunk(
+            files,
+            &self.project_root,
+            self.streaming_threshold,
+            sender,
+        );
+        let (chunked_files, mut chunk_results) = join(reading, self.embed_chunks(receiver)).await;
+        info!(
+            "Embedded {} chunks of {chunked_files} files",
+            chunk_results.len()
         );
 
-        // Phase 2: Cross-file chunk batching and embedding (I/O-bound)
-        let (all_chunk_results, _file_chunk_map) = self.embed_chunk_batches(file_chunks_data).await;
-
-        Ok(all_chunk_results)
+        chunk_results.sort_by(|first, second| {
+            (&first.0, first.1.start_line).cmp(&(&second.0, second.1.start_line))
+        });
+        Ok(chunk_results)
     }
 
-    /// Process chunk batches and generate embeddings
-    ///
-    /// Returns vector of chunk results and file-chunk mapping
-    async fn embed_chunk_batches(
-        &self,
-        file_chunks_data: Vec<FileChunksData>,
-    ) -> (Vec<ChunkResult>, FileChunkMap) {
-        const CHUNK_BATCH_SIZE: usize = 50;
-        let mut all_chunk_results = Vec::new();
-        let mut chunk_queue = Vec::new();
-        let mut file_chunk_map: FileChunkMap = HashMap::new();
-
-        // Collect all chunks with their file association
-        for (relative_path, _content, chunks, content_hash) in file_chunks_data {
-            for (idx, chunk) in chunks.into_iter().enumerate() {
-                file_chunk_map
-                    .entry(relative_path.clone())
-                    .or_default()
-                    .push((idx, chunk.clone(), content_hash));
-                chunk_queue.push((relative_path.clone(), chunk, content_hash));
+    /// Embed the chunks of files received from `receiver` as they arrive
+    async fn embed_chunks(&self, mut receiver: Receiver<FileChunksData>) -> Vec<ChunkResult> {
+        let batch_size = if self.client.supports_batching() {
+            (embedding_batch_size() / self.concurrency).max(1)
+        } else {
+            1
+        };
+        // Chunks waiting for a request, bounded so reading can't run far ahead
+        let max_pending = batch_size * self.concurrency;
+        let mut pending = Vec::new();
+        let mut requests = EmbeddingRequests::new(self);
+        let mut reading = true;
+
+        while reading || !pending.is_empty() || !requests.in_flight.is_empty() {
+            while requests.in_flight.len() < self.concurrency
+                && (pending.len() >= batch_size || (!reading && !pending.is_empty()))
+            {
+                let batch: Vec<_> = pending.drain(..batch_size.min(pending.len())).collect();
+                requests.spawn(batch);
             }
-        }
-
-        let total_chunks = chunk_queue.len();
-        info!("Total chunks to embed: {}", total_chunks);
-        self.report_progress("Embedding chunks", 0, Some(total_chunks as u64));
 
-        // Embed chunks in mega-batches
-        for batch_start in (0..chunk_queue.len()).step_by(CHUNK_BATCH_SIZE) {
-            let batch_end = (batch_start + CHUNK_BATCH_SIZE).min(chunk_queue.len());
-            let batch = &chunk_queue[batch_start..batch_end];
-
-            let chunk_texts: Vec<String> = batch
-                .iter()
-                .map(|(_, chunk, _)| chunk.content.clone())
-                .collect();
-
-            let embeddings = match self.client.embed_batch(chunk_texts).await {
-                Ok(embs) => embs,
-                Err(error) => {
-                    warn!("Failed to embed batch: {error}");
-                    continue;
+            tokio::select! {
+                next_file = receiver.recv(), if reading && pending.len() < max_pending => {
+                    match next_file {
+                        Some((relative_path, chunks, content_hash)) => {
+                            requests.discovered += chunks.len() as u64;
+                            pending.extend(chunks.into_iter().map(|chunk| {
+                                (relative_path.clone(), chunk, content_hash)
+                            }));
+                        }
+                        None => reading = false,
+                    }
                 }
-            };
-
-            // Store results
-            for ((relative_path, chunk, content_hash), embedding) in
-                batch.iter().zip(embeddings.into_iter())
-            {
-                let preview = generate_preview(&chunk.content, 200);
-                all_chunk_results.push((
-                    relative_path.clone(),
-                    chunk.clone(),
-                    embedding,
-                    preview,
-                    *content_hash,
-                ));
+                Some(joined) = requests.in_flight.join_next() => requests.finish(joined),
+                else => break,
             }
+        }
 
-            self.report_progress(
-                "Embedding chunks",
-                all_chunk_results.len() as u64,
-                Some(total_chunks as u64),
+        // Retry the chunks of failed requests one at a time
+        let failed = mem::take(&mut requests.failed);
+        if !failed.is_empty() {
+            warn!(
+                "Retrying {} chunks of failed embedding requests",
+                failed.len()
             );
+            for job in failed {
+                if requests.in_flight.len() >= self.concurrency
+                    && let Some(joined) = requests.in_flight.join_next().await
+                {
+                    requests.finish(joined);
+                }
+                requests.spawn(vec![job]);
+            }
+            while let Some(joined) = requests.in_flight.join_next().await {
+                requests.finish(joined);
+            }
+            if !requests.failed.is_empty() {
+                warn!(
+                    "Skipped {} chunks that failed to embed twice",
+                    requests.failed.len()
+                );
+            }
         }
 
-        info!(
-            "Successfully embedded {} chunks (received from model)",
-            all_chunk_results.len()
-        );
-
-        (all_chunk_results, file_chunk_map)
+        requests.results
     }
 
     /// Parallel file reading and chunking using blocking tasks
+    ///
+    /// Chunked files are sent to `sender` as soon as they are read. Returns the
+    /// number of files sent.
     async fn parallel_read_and_chunk(
         files: Vec<PathBuf>,
         project_root: &Path,
-    ) -> Vec<FileChunksData> {
-        const MAX_CONCURRENT_READS: usize = 20;
-
+        streaming_threshold: u64,
+        sender: Sender<FileChunksData>,
+    ) -> usize {
         let mut tasks = FuturesUnordered::new();
-        let mut results = Vec::new();
+        let mut sent = 0;
         let mut file_iter = files.into_iter();
 
         // Start initial batch
@@ -173,15 +209,22 @@ impl<E: EmbeddingProvider + Clone> EmbeddingOperations<E> {
                 let relative_clone = relative_path.clone();
 
                 tasks.push(spawn_blocking(move || {
-                    Self::read_and_chunk_file(relative_clone, &absolute_path)
+                    Self::read_and_chunk_file(relative_clone, &absolute_path, streaming_threshold)
                 }));
             }
         }
 
         // Process results and spawn new tasks
         while let Some(result) = tasks.next().await {
-            if let Ok(Some(file_data)) = result {
-                results.push(file_data);
+            match result {
+                Ok(Some(file_data)) => {
+                    if sender.send(file_data).await.is_err() {
+                        break;
+                    }
+                    sent += 1;
+                }
+                Ok(None) => {}
+                Err(join_err) => warn!("Skipping file: {}", join_error("File chunking", join_err)),
             }
 
             // Spawn next task to maintain concurrency
@@ -190,36 +233,28 @@ impl<E: EmbeddingProvider + Clone> EmbeddingOperations<E> {
                 let relative_clone = relative_path.clone();
 
                 tasks.push(spawn_blocking(move || {
-                    Self::read_and_chunk_file(relative_clone, &absolute_path)
+                    Self::read_and_chunk_file(relative_clone, &absolute_path, streaming_threshold)
                 }));
             }
         }
 
-        results
+        sent
     }
 
     /// Read and chunk a single file (CPU-bound, runs in blocking task)
-    fn read_and_chunk_file(relative_path: PathBuf, absolute_path: &Path) -> Option<FileChunksData> {
-        let content = match fs::read_to_string(absolute_path) {
-            Ok(content) => content,
+    fn read_and_chunk_file(
+        relative_path: PathBuf,
+        absolute_path: &Path,
+        streaming_threshold: u64,
+    ) -> Option<FileChunksData> {
+        match read_and_chunk(&relative_path, absolute_path, streaming_threshold) {
+            Ok((chunks, _)) if chunks.is_empty() => None,
+            Ok((chunks, content_hash)) => Some((relative_path, chunks, content_hash)),
             Err(error) => {
                 warn!("Failed to read {}: {error}", relative_path.display());
-                return None;
+                None
             }
-        };
-
-        if content.trim().is_empty() {
-            return None;
         }
-
-        let content_hash = CacheOperations::compute_file_hash(&content);
-        let chunks = chunk_file(&relative_path, &content);
-
-        if chunks.is_empty() {
-            return None;
-        }
-
-        Some((relative_path, content, chunks, content_hash))
     }
 
     /// Prepare embeddings for caching
@@ -263,3 +298,76 @@ impl<E: EmbeddingProvider + Clone> EmbeddingOperations<E> {
         result
     }
 }
+
+/// Embedding requests in flight and what they produced so far
+struct EmbeddingRequests<'ops, E: EmbeddingProvider + Clone> {
+    /// Operations whose client embeds and whose callback gets progress
+    ops: &'ops EmbeddingOperations<E>,
+    /// Requests in flight
+    in_flight: JoinSet<RequestOutcome>,
+    /// Embedded chunks
+    results: Vec<ChunkResult>,
+    /// Chunks of failed requests
+    failed: Vec<ChunkJob>,
+    /// Chunks read so far, the total progress is reported against
+    discovered: u64,
+}
+
+impl<'ops, E: EmbeddingProvider + Clone + 'static> EmbeddingRequests<'ops, E> {
+    /// Create an empty set of requests
+    fn new(ops: &'ops EmbeddingOperations<E>) -> Self {
+        Self {
+            ops,
+            in_flight: JoinSet::new(),
+            results: Vec::new(),
+            failed: Vec::new(),
+            discovered: 0,
+        }
+    }
+
+    /// Start embedding `batch` in one request
+    fn spawn(&mut self, batch: Vec<ChunkJob>) {
+        let client = self.ops.client.clone();
+        self.in_flight.spawn(async move {
+            let texts = batch
+                .iter()
+                .map(|(_, chunk, _)| chunk.content.clone())
+                .collect();
+            let embeddings = client.embed_batch(texts).await;
+            (batch, embeddings)
+        });
+    }
+
+    /// Record the outcome of a finished request and report progress
+    fn finish(&mut self, joined: StdResult<RequestOutcome, JoinError>) {
+        match joined {
+            Ok((batch, Ok(embeddings))) if embeddings.len() == batch.len() => {
+                for ((relative_path, chunk, content_hash), embedding) in
+                    batch.into_iter().zip(embeddings)
+                {
+                    let preview = chunk_preview(&chunk, 200);
+                    self.results
+                        .push((relative_path, chunk, embedding, preview, content_hash));
+                }
+            }
+            Ok((batch, Ok(embeddings))) => {
+                warn!(
+                    "Embedding request returned {} embeddings for {} chunks",
+                    embeddings.len(),
+                    batch.len()
+                );
+                self.failed.extend(batch);
+            }
+            Ok((batch, Err(error))) => {
+                warn!("Failed to embed {} chunks: {error}", batch.len());
+                self.failed.extend(batch);
+            }
+            Err(join_err) => warn!("{}", join_error("Embedding request", join_err)),
+        }
+        self.ops.report_progress(
+            "Embedding chunks",
+            self.results.len() as u64,
+            Some(self.discovered),
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Content sniffing that keeps binary, huge and minified files out of the index.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{File, metadata};
+use std::io::{self, Read as _};
+use std::path::Path;
+
+use tracing::info;
+
+/// Files larger than this many bytes are not embedded by default (1 MB)
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// How many leading bytes of a file are sniffed
+const SNIFF_BYTES: u64 = 8 * 1024;
+
+/// Lines longer than this many bytes mark a file as minified or generated
+const MAX_LINE_BYTES: usize = 2000;
+
+/// Why a source file was left out of the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipReason {
+    /// The file has a NUL byte in its first bytes
+    Binary,
+    /// The file is larger than the size cap
+    TooLarge,
+    /// The file has a line longer than [`MAX_LINE_BYTES`]
+    Minified,
+    /// The file could not be read
+    Unreadable,
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Binary => "binary",
+            Self::TooLarge => "too large",
+            Self::Minified => "minified",
+            Self::Unreadable => "unreadable",
+        })
+    }
+}
+
+/// Checks whether the file at `path` is worth indexing
+///
+/// # Errors
+/// Returns why the file should be skipped
+pub fn sniff_file(path: &Path, max_file_size: u64) -> Result<(), SkipReason> {
+    let is_minified_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.contains(".min."));
+    if is_minified_name {
+        return Err(SkipReason::Minified);
+    }
+    if metadata(path).map_err(|_| SkipReason::Unreadable)?.len() > max_file_size {
+        return Err(SkipReason::TooLarge);
+    }
+    let head = read_head(path).map_err(|_| SkipReason::Unreadable)?;
+    if head.contains(&0) {
+        return Err(SkipReason::Binary);
+    }
+    if head
+        .split(|&byte| byte == b'\n')
+        .any(|line| line.len() > MAX_LINE_BYTES)
+    {
+        return Err(SkipReason::Minified);
+    }
+    Ok(())
+}
+
+/// Reads the first [`SNIFF_BYTES`] of a file
+///
+/// # Errors
+/// Returns an error if the file cannot be read
+fn read_head(path: &Path) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    File::open(path)?.take(SNIFF_BYTES).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+/// Number of skipped files per reason
+#[derive(Debug, Default)]
+pub struct SkippedFiles(BTreeMap<SkipReason, usize>);
+
+impl SkippedFiles {
+    /// Counts a skipped file
+    pub fn record(&mut self, reason: SkipReason) {
+        *self.0.entry(reason).or_default() += 1;
+    }
+
+    /// Logs how many files were skipped for each reason
+    pub fn log(&self) {
+        if self.0.is_empty() {
+            return;
+        }
+        let total: usize = self.0.values().sum();
+        let reasons: Vec<String> = self
+            .0
+            .iter()
+            .map(|(reason, count)| format!("{count} {reason}"))
+            .collect();
+        info!("  Skipped {total} files ({})", reasons.join(", "));
+    }
+}
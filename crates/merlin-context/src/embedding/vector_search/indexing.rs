@@ -0,0 +1,241 @@
+//! Building the index from the embedding cache or from scratch, and saving the cache.
+
+use std::fs;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use super::VectorSearchManager;
+use super::cache::{Bm25Cache, CacheOperations, CachedEmbedding, VectorCache};
+use super::embedding::EmbeddingOperations;
+use super::initialization::InitializationHelper;
+use crate::embedding::client::EmbeddingProvider;
+use merlin_core::CoreResult as Result;
+
+impl<E: EmbeddingProvider + Clone + 'static> VectorSearchManager<E> {
+    /// Try to initialize from cached embeddings
+    ///
+    /// # Errors
+    /// Returns an error if embedding operations fail
+    pub(super) async fn try_initialize_from_cache(
+        &mut self,
+        cache: VectorCache,
+        bm25_cache: Option<Bm25Cache>,
+    ) -> Result<bool> {
+        info!(
+            "  Cache file found with {} embeddings (version: {})",
+            cache.embeddings.len(),
+            cache.version
+        );
+
+        if cache.embeddings.is_empty() {
+            warn!("  Cache is empty - will rebuild index");
+            return Ok(false);
+        }
+
+        if !cache.is_valid() {
+            return Ok(false);
+        }
+
+        tracing::info!("Validating {} cached embeddings...", cache.embeddings.len());
+
+        self.process_cached_embeddings(&cache, bm25_cache).await?;
+
+        Ok(true)
+    }
+
+    /// Process and validate cached embeddings
+    ///
+    /// # Errors
+    /// Returns an error if embedding operations fail
+    async fn process_cached_embeddings(
+        &mut self,
+        cache: &VectorCache,
+        bm25_cache: Option<Bm25Cache>,
+    ) -> Result<()> {
+        let (valid, invalid) =
+            CacheOperations::validate_cache_entries(&cache.embeddings, &self.project_root);
+
+        // Add valid entries to store and BM25 index
+        self.load_entries(&valid, bm25_cache);
+        info!("  Total embeddings in store: {}", self.store.len());
+
+        // Handle new and invalid files
+        let (new_files, new_count) =
+            InitializationHelper::identify_new_files(cache, &self.project_root, self.max_file_size);
+
+        self.update_cache_with_changes(new_files, invalid, new_count)
+            .await?;
+
+        self.save_cache_async().await?;
+        Ok(())
+    }
+
+    /// Load cache entries into the store, restoring or rebuilding the BM25 index
+    pub(super) fn load_entries(
+        &mut self,
+        entries: &[CachedEmbedding],
+        bm25_cache: Option<Bm25Cache>,
+    ) {
+        let restored = InitializationHelper::restore_bm25(bm25_cache, entries);
+        let rebuild = restored.is_none();
+        if let Some(index) = restored {
+            self.bm25 = index;
+        }
+
+        InitializationHelper::load_valid_entries(
+            entries,
+            &mut self.store,
+            rebuild.then_some(&mut self.bm25),
+            &mut self.cache_ops.file_times,
+            &mut self.cache_ops.file_hashes,
+        );
+
+        if rebuild {
+            self.bm25.finalize();
+            info!("  BM25 index built with {} documents", self.bm25.len());
+        } else {
+            info!("  BM25 index loaded with {} documents", self.bm25.len());
+        }
+    }
+
+    /// Update cache with new and modified files
+    ///
+    /// # Errors
+    /// Returns an error if embedding operations fail
+    async fn update_cache_with_changes(
+        &mut self,
+        new_files: Vec<PathBuf>,
+        invalid: Vec<PathBuf>,
+        new_count: usize,
+    ) -> Result<()> {
+        // Modified files may have become binary or too large since they were embedded
+        let invalid =
+            InitializationHelper::indexable_files(&self.project_root, invalid, self.max_file_size);
+        let invalid_count = invalid.len();
+
+        if !new_files.is_empty() {
+            info!("  Found {new_count} new files to embed");
+            tracing::info!("Embedding {new_count} new files...");
+            self.report_progress("Embedding new files", 0, Some(new_count as u64));
+            self.embed_files(new_files).await?;
+        }
+
+        if !invalid.is_empty() {
+            tracing::info!("Re-embedding {invalid_count} modified files...");
+            self.report_progress("Re-embedding modified files", 0, Some(invalid_count as u64));
+            self.embed_files(invalid).await?;
+            tracing::info!(
+                "✓ Loaded cache + updated {} files",
+                invalid_count + new_count
+            );
+        } else if new_count > 0 {
+            tracing::info!("✓ Loaded cache + added {new_count} new files");
+        } else {
+            tracing::info!("✓ Loaded embeddings from cache");
+        }
+
+        Ok(())
+    }
+
+    /// Initialize from scratch by embedding entire codebase
+    ///
+    /// # Errors
+    /// Returns an error if embedding operations fail
+    pub(super) async fn initialize_from_scratch(&mut self) -> Result<()> {
+        info!("  No valid cache found - building from scratch");
+        tracing::info!("Building embedding index for codebase...");
+        let files =
+            InitializationHelper::collect_source_files(&self.project_root, self.max_file_size);
+
+        info!("  Found {} source files to embed", files.len());
+        tracing::info!("Embedding {} source files...", files.len());
+        self.report_progress("Embedding", 0, Some(files.len() as u64));
+        self.embed_files(files).await?;
+
+        info!("  Embedded {} files total", self.store.len());
+        tracing::info!("✓ Indexed {} files with embeddings", self.store.len());
+
+        info!("  Saving cache to disk...");
+        self.report_progress("Saving cache", 0, None);
+        self.save_cache_async().await?;
+        info!("  ✓ Cache saved");
+
+        Ok(())
+    }
+
+    /// Embed a batch of files
+    ///
+    /// # Errors
+    /// Returns an error if embedding fails
+    async fn embed_files(&mut self, files: Vec<PathBuf>) -> Result<()> {
+        let mut embedding_ops = EmbeddingOperations::new(
+            self.client.clone(),
+            self.project_root.clone(),
+            self.progress_callback.clone(),
+        )
+        .with_streaming_threshold(self.streaming_threshold);
+        if let Some(concurrency) = self.concurrency {
+            embedding_ops = embedding_ops.with_concurrency(concurrency);
+        }
+
+        let chunk_results = embedding_ops.embed_files(files).await?;
+
+        // Process results and update indices
+        for (path, chunk, embedding, preview, content_hash) in chunk_results {
+            let chunk_path: String =
+                format!("{}:{}-{}", path.display(), chunk.start_line, chunk.end_line);
+
+            // Track file metadata
+            if let Ok(metadata) = fs::metadata(self.project_root.join(&path))
+                && let Ok(modified) = metadata.modified()
+            {
+                self.cache_ops.file_times.insert(path.clone(), modified);
+                self.cache_ops.file_hashes.insert(path, content_hash);
+            }
+
+            self.store
+                .add(PathBuf::from(&chunk_path), embedding, preview.clone());
+            self.bm25.add_chunk(
+                PathBuf::from(chunk_path.clone()),
+                chunk.header.as_deref(),
+                &chunk.content,
+            );
+        }
+
+        self.bm25.finalize();
+
+        Ok(())
+    }
+
+    /// Save cache to disk
+    ///
+    /// # Errors
+    /// Returns an error if cache save fails
+    async fn save_cache_async(&self) -> Result<()> {
+        let embeddings = EmbeddingOperations::<E>::prepare_embeddings(
+            self.store
+                .iter()
+                .map(|entry| (entry.path, entry.embedding, entry.preview)),
+            &self.cache_ops.file_times,
+            &self.cache_ops.file_hashes,
+        );
+        self.cache_ops
+            .save_cache_async(embeddings, &self.bm25)
+            .await
+    }
+
+    /// Save cache to disk (sync version for Drop)
+    ///
+    /// # Errors
+    /// Returns an error if cache save fails
+    pub(super) fn save_cache_sync(&self) -> Result<()> {
+        let embeddings = EmbeddingOperations::<E>::prepare_embeddings(
+            self.store
+                .iter()
+                .map(|entry| (entry.path, entry.embedding, entry.preview)),
+            &self.cache_ops.file_times,
+            &self.cache_ops.file_hashes,
+        );
+        self.cache_ops.save_cache_sync(embeddings, &self.bm25)
+    }
+}
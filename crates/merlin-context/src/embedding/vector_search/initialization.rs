@@ -7,8 +7,10 @@ use std::result::Result as StdResult;
 use std::time::SystemTime;
 use tracing::info;
 
-use crate::embedding::vector_search::cache::{CachedEmbedding, VectorCache};
-use crate::embedding::{BM25Index, VectorStore};
+use crate::embedding::chunking::is_lockfile;
+use crate::embedding::vector_search::cache::{Bm25Cache, CachedEmbedding, VectorCache};
+use crate::embedding::vector_search::file_filter::{SkippedFiles, sniff_file};
+use crate::embedding::{BM25Index, VectorStore, split_preview_header};
 use crate::fs_utils::is_source_file;
 
 /// Initialization helper
@@ -36,8 +38,11 @@ impl InitializationHelper {
             .join("embeddings.bin")
     }
 
-    /// Collect all source files in the project
-    pub fn collect_source_files(project_root: &Path) -> Vec<PathBuf> {
+    /// Collect all source files in the project worth indexing
+    ///
+    /// Binary, minified and files over `max_file_size` bytes are skipped (see
+    /// [`sniff_file`]); lockfiles are kept to be summarized.
+    pub fn collect_source_files(project_root: &Path, max_file_size: u64) -> Vec<PathBuf> {
         use ignore::WalkBuilder;
 
         let mut files = Vec::default();
@@ -56,7 +61,7 @@ impl InitializationHelper {
             if entry
                 .file_type()
                 .is_some_and(|file_type| file_type.is_file())
-                && is_source_file(path)
+                && (is_source_file(path) || is_lockfile(path))
             {
                 let normalized_path = path
                     .strip_prefix(project_root)
@@ -65,14 +70,51 @@ impl InitializationHelper {
             }
         }
 
+        Self::indexable_files(project_root, files, max_file_size)
+    }
+
+    /// Drop the files that should not be indexed, logging how many were skipped and why
+    pub fn indexable_files(
+        project_root: &Path,
+        files: Vec<PathBuf>,
+        max_file_size: u64,
+    ) -> Vec<PathBuf> {
+        let mut skipped = SkippedFiles::default();
+        let files = files
+            .into_iter()
+            .filter(|path| {
+                if is_lockfile(path) {
+                    return true;
+                }
+                sniff_file(&project_root.join(path), max_file_size)
+                    .map_err(|reason| skipped.record(reason))
+                    .is_ok()
+            })
+            .collect();
+        skipped.log();
         files
     }
 
+    /// Restore the persisted BM25 index if it was built from exactly `valid`
+    ///
+    /// Returns `None` if there is no persisted index, it has an old version, or
+    /// any file changed since it was saved, in which case the index has to be
+    /// rebuilt from the cache entries.
+    pub fn restore_bm25(cached: Option<Bm25Cache>, valid: &[CachedEmbedding]) -> Option<BM25Index> {
+        let index = cached?.into_index_for(valid);
+        if index.is_none() {
+            info!("  Persisted BM25 index is stale - rebuilding");
+        }
+        index
+    }
+
     /// Load valid cache entries into the store
+    ///
+    /// Entries are also added to `bm25` unless it was restored from disk.
     pub fn load_valid_entries(
         valid: &[CachedEmbedding],
         store: &mut VectorStore,
-        bm25: &mut BM25Index,
+        mut bm25: Option<&mut BM25Index>,
         file_times: &mut HashMap<PathBuf, SystemTime>,
         file_hashes: &mut HashMap<PathBuf, u64>,
     ) {
@@ -92,13 +134,20 @@ impl InitializationHelper {
             );
 
             // Rebuild BM25 index from preview (approximation)
-            bm25.add_document(PathBuf::from(chunk_path), &entry.preview);
+            if let Some(index) = bm25.as_deref_mut() {
+                let (header, content) = split_preview_header(&entry.preview);
+                index.add_chunk(PathBuf::from(chunk_path), header, content);
+            }
         }
     }
 
     /// Identify new files that need embedding
-    pub fn identify_new_files(cache: &VectorCache, project_root: &Path) -> (Vec<PathBuf>, usize) {
-        let all_files = Self::collect_source_files(project_root);
+    pub fn identify_new_files(
+        cache: &VectorCache,
+        project_root: &Path,
+        max_file_size: u64,
+    ) -> (Vec<PathBuf>, usize) {
+        let all_files = Self::collect_source_files(project_root, max_file_size);
         let cached_paths: HashSet<_> = cache.embeddings.iter().map(|entry| &entry.path).collect();
         let new_files: Vec<_> = all_files
             .into_iter()
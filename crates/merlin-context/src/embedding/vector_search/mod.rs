@@ -1,28 +1,37 @@
 //! Vector search manager with persistent caching.
 
+mod batch_size;
 mod cache;
+mod concurrency;
 mod embedding;
+mod file_filter;
+mod indexing;
 mod initialization;
 mod scoring;
+mod search;
+mod sections;
 
-pub use cache::{CachedEmbedding, VectorCache};
+pub use batch_size::{BATCH_SIZE_ENV, MAX_BATCH_SIZE, MIN_BATCH_SIZE, batch_size_for_memory};
+pub use cache::{Bm25Cache, CachedEmbedding, VectorCache};
+pub use concurrency::{CONCURRENCY_ENV, MAX_CONCURRENCY, MIN_CONCURRENCY};
 pub use embedding::ProgressCallback;
+pub use file_filter::{DEFAULT_MAX_FILE_SIZE, SkipReason, sniff_file};
+pub use sections::DEFAULT_STREAMING_THRESHOLD;
 
-use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tracing::{info, warn};
 
 use crate::embedding::client::EmbeddingProvider;
-use crate::embedding::{BM25Index, EmbeddingClient, SearchResult, VectorStore};
+use crate::embedding::client::NoOpEmbeddingProvider;
+use crate::embedding::{BM25Index, EmbeddingClient, VectorStore, chunk_file, chunk_preview};
 use cache::CacheOperations;
-use embedding::EmbeddingOperations;
 use initialization::InitializationHelper;
 use merlin_core::{CoreResult as Result, Error};
-use scoring::ScoringUtils;
 
 /// Vector search manager with caching and BM25 keyword search
-pub struct VectorSearchManager<E: EmbeddingProvider + Clone = EmbeddingClient> {
+pub struct VectorSearchManager<E: EmbeddingProvider + Clone + 'static = EmbeddingClient> {
     /// In-memory vector store
     store: VectorStore,
     /// BM25 keyword search index
@@ -35,9 +44,21 @@ pub struct VectorSearchManager<E: EmbeddingProvider + Clone = EmbeddingClient> {
     cache_ops: CacheOperations,
     /// Optional progress callback
     progress_callback: Option<ProgressCallback>,
+    /// Whether a missing embedding model is downloaded before initializing
+    auto_pull: bool,
+    /// Optional progress callback for downloading the embedding model
+    pull_progress_callback: Option<ProgressCallback>,
+    /// Size in bytes above which files are not indexed
+    max_file_size: u64,
+    /// Embedding requests in flight at once (chosen from the hardware if unset)
+    concurrency: Option<usize>,
+    /// Size in bytes from which files are read and chunked a section at a time
+    streaming_threshold: u64,
+    /// Chunk previews of a keyword-only index, which has no vector store holding them
+    keyword_previews: HashMap<PathBuf, String>,
 }
 
-impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
+impl<E: EmbeddingProvider + Clone + 'static> VectorSearchManager<E> {
     /// Create a new vector search manager with a custom embedding provider
     pub fn with_provider(project_root: &Path, client: E) -> Self {
         let cache_path = InitializationHelper::resolve_cache_path(project_root);
@@ -49,18 +70,15 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
             project_root: project_root.to_path_buf(),
             cache_ops: CacheOperations::new(cache_path),
             progress_callback: None,
+            auto_pull: true,
+            pull_progress_callback: None,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            concurrency: None,
+            streaming_threshold: DEFAULT_STREAMING_THRESHOLD,
+            keyword_previews: HashMap::new(),
         }
     }
-}
 
-impl VectorSearchManager<EmbeddingClient> {
-    /// Create a new vector search manager with default Ollama client
-    pub fn new(project_root: &Path) -> Self {
-        Self::with_provider(project_root, EmbeddingClient::default())
-    }
-}
-
-impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
     /// Set a progress callback for embedding operations
     #[must_use]
     pub fn with_progress_callback(mut self, callback: ProgressCallback) -> Self {
@@ -68,6 +86,50 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
         self
     }
 
+    /// Download a missing embedding model before initializing (enabled by default)
+    ///
+    /// Without it, initialization fails until the model is pulled by hand.
+    #[must_use]
+    pub const fn with_auto_pull(mut self, auto_pull: bool) -> Self {
+        self.auto_pull = auto_pull;
+        self
+    }
+
+    /// Set a progress callback for downloading the embedding model
+    #[must_use]
+    pub fn with_pull_progress_callback(mut self, callback: ProgressCallback) -> Self {
+        self.pull_progress_callback = Some(callback);
+        self
+    }
+
+    /// Leave files larger than `bytes` bytes out of the index (default 1 MB)
+    ///
+    /// Lockfiles are summarized whatever their size.
+    #[must_use]
+    pub const fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// Keep up to `concurrency` embedding requests in flight while embedding
+    ///
+    /// Defaults to [`CONCURRENCY_ENV`] or half the CPU cores, up to 4.
+    #[must_use]
+    pub const fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = Some(concurrency);
+        self
+    }
+
+    /// Read files of at least `bytes` bytes in sections of about that size while embedding
+    ///
+    /// Defaults to [`DEFAULT_STREAMING_THRESHOLD`]. A large file is then never
+    /// held whole, only one section and the chunks made from it so far.
+    #[must_use]
+    pub const fn with_streaming_threshold(mut self, bytes: u64) -> Self {
+        self.streaming_threshold = bytes;
+        self
+    }
+
     /// Report progress if callback is set
     fn report_progress(&self, stage: &str, current: u64, total: Option<u64>) {
         if let Some(callback) = &self.progress_callback {
@@ -80,6 +142,12 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
     /// # Errors
     /// Returns an error if embedding model is unavailable or embedding/cache IO fails
     pub async fn initialize(&mut self) -> Result<()> {
+        if self.auto_pull {
+            self.client
+                .pull_model(self.pull_progress_callback.as_ref())
+                .await?;
+        }
+
         // Check if embedding model is available
         tracing::info!("Checking embedding model availability...");
         self.client.ensure_model_available().await?;
@@ -91,8 +159,8 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
         );
 
         // Try to load from cache first
-        if let Ok(cache) = self.cache_ops.load_cache().await
-            && self.try_initialize_from_cache(cache).await?
+        if let Ok((cache, bm25_cache)) = self.cache_ops.load_cache().await
+            && self.try_initialize_from_cache(cache, bm25_cache).await?
         {
             return Ok(());
         }
@@ -120,7 +188,7 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
         );
 
         // Try to load from cache - if it exists and is valid, use it immediately
-        if let Ok(cache) = self.cache_ops.load_cache().await
+        if let Ok((cache, bm25_cache)) = self.cache_ops.load_cache().await
             && cache.is_valid()
             && !cache.embeddings.is_empty()
         {
@@ -130,14 +198,7 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
             );
 
             // Load all entries without validation (trust the cache)
-            InitializationHelper::load_valid_entries(
-                &cache.embeddings,
-                &mut self.store,
-                &mut self.bm25,
-                &mut self.cache_ops.file_times,
-                &mut self.cache_ops.file_hashes,
-            );
-            self.bm25.finalize();
+            self.load_entries(&cache.embeddings, bm25_cache);
 
             info!(
                 "  Partial index ready: {} embeddings, {} BM25 docs",
@@ -153,271 +214,32 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
         ))
     }
 
-    /// Try to initialize from cached embeddings
-    ///
-    /// # Errors
-    /// Returns an error if embedding operations fail
-    async fn try_initialize_from_cache(&mut self, cache: VectorCache) -> Result<bool> {
-        info!(
-            "  Cache file found with {} embeddings (version: {})",
-            cache.embeddings.len(),
-            cache.version
-        );
-
-        if cache.embeddings.is_empty() {
-            warn!("  Cache is empty - will rebuild index");
-            return Ok(false);
-        }
-
-        if !cache.is_valid() {
-            return Ok(false);
-        }
-
-        tracing::info!("Validating {} cached embeddings...", cache.embeddings.len());
-
-        self.process_cached_embeddings(&cache).await?;
-
-        Ok(true)
-    }
-
-    /// Process and validate cached embeddings
-    ///
-    /// # Errors
-    /// Returns an error if embedding operations fail
-    async fn process_cached_embeddings(&mut self, cache: &VectorCache) -> Result<()> {
-        let (valid, invalid) =
-            CacheOperations::validate_cache_entries(&cache.embeddings, &self.project_root);
-
-        // Add valid entries to store and BM25 index
-        InitializationHelper::load_valid_entries(
-            &valid,
-            &mut self.store,
-            &mut self.bm25,
-            &mut self.cache_ops.file_times,
-            &mut self.cache_ops.file_hashes,
-        );
-
-        // Finalize BM25 index
-        self.bm25.finalize();
-        info!("  BM25 index built with {} documents", self.bm25.len());
-        info!("  Total embeddings in store: {}", self.store.len());
-
-        // Handle new and invalid files
-        let (new_files, new_count) =
-            InitializationHelper::identify_new_files(cache, &self.project_root);
-
-        self.update_cache_with_changes(new_files, invalid, new_count)
-            .await?;
-
-        self.save_cache_async().await?;
-        Ok(())
-    }
-
-    /// Update cache with new and modified files
+    /// Index the project's source files for BM25 keyword search only
     ///
-    /// # Errors
-    /// Returns an error if embedding operations fail
-    async fn update_cache_with_changes(
-        &mut self,
-        new_files: Vec<PathBuf>,
-        invalid: Vec<PathBuf>,
-        new_count: usize,
-    ) -> Result<()> {
-        let invalid_count = invalid.len();
-
-        if !new_files.is_empty() {
-            info!("  Found {new_count} new files to embed");
-            tracing::info!("Embedding {new_count} new files...");
-            self.report_progress("Embedding new files", 0, Some(new_count as u64));
-            self.embed_files(new_files).await?;
-        }
-
-        if !invalid.is_empty() {
-            tracing::info!("Re-embedding {invalid_count} modified files...");
-            self.report_progress("Re-embedding modified files", 0, Some(invalid_count as u64));
-            self.embed_files(invalid).await?;
-            tracing::info!(
-                "✓ Loaded cache + updated {} files",
-                invalid_count + new_count
-            );
-        } else if new_count > 0 {
-            tracing::info!("✓ Loaded cache + added {new_count} new files");
-        } else {
-            tracing::info!("✓ Loaded embeddings from cache");
-        }
-
-        Ok(())
-    }
-
-    /// Initialize from scratch by embedding entire codebase
-    ///
-    /// # Errors
-    /// Returns an error if embedding operations fail
-    async fn initialize_from_scratch(&mut self) -> Result<()> {
-        info!("  No valid cache found - building from scratch");
-        tracing::info!("Building embedding index for codebase...");
-        let files = InitializationHelper::collect_source_files(&self.project_root);
-
-        info!("  Found {} source files to embed", files.len());
-        tracing::info!("Embedding {} source files...", files.len());
-        self.report_progress("Embedding", 0, Some(files.len() as u64));
-        self.embed_files(files).await?;
-
-        info!("  Embedded {} files total", self.store.len());
-        tracing::info!("✓ Indexed {} files with embeddings", self.store.len());
-
-        info!("  Saving cache to disk...");
-        self.report_progress("Saving cache", 0, None);
-        self.save_cache_async().await?;
-        info!("  ✓ Cache saved");
-
-        Ok(())
-    }
-
-    /// Embed a batch of files
-    ///
-    /// # Errors
-    /// Returns an error if embedding fails
-    async fn embed_files(&mut self, files: Vec<PathBuf>) -> Result<()> {
-        let embedding_ops = EmbeddingOperations::new(
-            self.client.clone(),
-            self.project_root.clone(),
-            self.progress_callback.clone(),
-        );
-
-        let chunk_results = embedding_ops.embed_files(files).await?;
-
-        // Process results and update indices
-        for (path, chunk, embedding, preview, content_hash) in chunk_results {
-            let chunk_path: String =
-                format!("{}:{}-{}", path.display(), chunk.start_line, chunk.end_line);
-
-            // Track file metadata
-            if let Ok(metadata) = fs::metadata(self.project_root.join(&path))
-                && let Ok(modified) = metadata.modified()
-            {
-                self.cache_ops.file_times.insert(path.clone(), modified);
-                self.cache_ops.file_hashes.insert(path, content_hash);
-            }
-
-            self.store
-                .add(PathBuf::from(&chunk_path), embedding, preview.clone());
-            self.bm25
-                .add_document(PathBuf::from(chunk_path.clone()), &chunk.content);
-        }
-
-        self.bm25.finalize();
-
-        Ok(())
-    }
-
-    /// Hybrid search combining BM25 keyword search and vector semantic search
-    ///
-    /// # Errors
-    /// Returns an error if embedding the query fails
-    pub async fn search(&self, query: &str, top_k: usize) -> Result<Vec<SearchResult>> {
-        info!(
-            "  Hybrid search: {} embeddings, {} BM25 docs",
-            self.store.len(),
-            self.bm25.len()
-        );
-
-        if self.store.is_empty() {
-            warn!("  Vector store is empty - no results");
-            return Ok(Vec::default());
-        }
-
-        // Run BM25 keyword search
-        let bm25_results = self.bm25.search(query, top_k * 2);
-        info!("  BM25 found {} keyword matches", bm25_results.len());
-
-        // Run vector semantic search
-        let query_embedding = self.client.embed(query).await?;
-        let vector_results = self.store.search(&query_embedding, top_k * 2);
-        info!("  Vector found {} semantic matches", vector_results.len());
-
-        // Combine results using adaptive weighted fusion
-        let mut combined =
-            ScoringUtils::reciprocal_rank_fusion(query, &bm25_results, &vector_results, top_k);
-
-        // Build import graph for graph-based ranking
-        let all_files: Vec<PathBuf> = combined
-            .iter()
-            .map(|result| result.file_path.clone())
-            .collect();
-        let import_graph = ScoringUtils::build_import_graph(&all_files);
-
-        // Apply graph-based boost
-        ScoringUtils::apply_graph_boost(&mut combined, &import_graph);
-
-        // Apply import-based boosting using preview content
-        for result in &mut combined {
-            let import_boost = ScoringUtils::boost_by_imports(&result.preview, query);
-            result.score *= import_boost;
-        }
-
-        // Re-sort after boosting
-        combined.sort_by(|result_a, result_b| {
-            result_b
-                .score
-                .partial_cmp(&result_a.score)
-                .unwrap_or(Ordering::Equal)
-        });
-
-        // Re-normalize after boosting
-        if let Some(max_score) = combined.first().map(|result| result.score)
-            && max_score > 0.0
+    /// Nothing is embedded or cached, so searches rank chunks by keywords alone.
+    /// Used instead of [`initialize`](Self::initialize) while embeddings are disabled.
+    pub fn index_keywords(&mut self) {
+        for relative_path in
+            InitializationHelper::collect_source_files(&self.project_root, self.max_file_size)
         {
-            for result in &mut combined {
-                result.score /= max_score;
+            let Ok(content) = fs::read_to_string(self.project_root.join(&relative_path)) else {
+                continue;
+            };
+            for chunk in chunk_file(&relative_path, &content) {
+                let chunk_path = PathBuf::from(format!(
+                    "{}:{}-{}",
+                    relative_path.display(),
+                    chunk.start_line,
+                    chunk.end_line
+                ));
+                self.bm25
+                    .add_chunk(chunk_path.clone(), chunk.header.as_deref(), &chunk.content);
+                self.keyword_previews
+                    .insert(chunk_path, chunk_preview(&chunk, 200));
             }
         }
-
-        info!(
-            "  Combined {} results using RRF + import boost",
-            combined.len()
-        );
-        if !combined.is_empty() {
-            let top_scores: Vec<f32> = combined.iter().take(5).map(|result| result.score).collect();
-            info!("  Top scores: {:?}", top_scores);
-        }
-
-        // Filter by minimum similarity score
-        let filtered = ScoringUtils::filter_by_min_score(combined);
-
-        info!("  After filtering: {} results", filtered.len());
-
-        Ok(filtered)
-    }
-
-    /// Save cache to disk
-    ///
-    /// # Errors
-    /// Returns an error if cache save fails
-    async fn save_cache_async(&self) -> Result<()> {
-        let embeddings = EmbeddingOperations::<E>::prepare_embeddings(
-            self.store
-                .iter()
-                .map(|entry| (entry.path, entry.embedding, entry.preview)),
-            &self.cache_ops.file_times,
-            &self.cache_ops.file_hashes,
-        );
-        self.cache_ops.save_cache_async(embeddings).await
-    }
-
-    /// Save cache to disk (sync version for Drop)
-    ///
-    /// # Errors
-    /// Returns an error if cache save fails
-    fn save_cache_sync(&self) -> Result<()> {
-        let embeddings = EmbeddingOperations::<E>::prepare_embeddings(
-            self.store
-                .iter()
-                .map(|entry| (entry.path, entry.embedding, entry.preview)),
-            &self.cache_ops.file_times,
-            &self.cache_ops.file_hashes,
-        );
-        self.cache_ops.save_cache_sync(embeddings)
+        self.bm25.finalize();
+        info!("  Keyword index built with {} BM25 docs", self.bm25.len());
     }
 
     /// Get the number of indexed files
@@ -431,7 +253,23 @@ impl<E: EmbeddingProvider + Clone> VectorSearchManager<E> {
     }
 }
 
-impl<E: EmbeddingProvider + Clone> Drop for VectorSearchManager<E> {
+impl VectorSearchManager<EmbeddingClient> {
+    /// Create a new vector search manager with default Ollama client
+    pub fn new(project_root: &Path) -> Self {
+        Self::with_provider(project_root, EmbeddingClient::default())
+    }
+}
+
+impl VectorSearchManager<NoOpEmbeddingProvider> {
+    /// Create a manager serving BM25 keyword search only, for when embeddings are disabled
+    ///
+    /// Build its index with [`index_keywords`](Self::index_keywords).
+    pub fn keyword_only(project_root: &Path) -> Self {
+        Self::with_provider(project_root, NoOpEmbeddingProvider)
+    }
+}
+
+impl<E: EmbeddingProvider + Clone + 'static> Drop for VectorSearchManager<E> {
     fn drop(&mut self) {
         if !self.store.is_empty() {
             if let Err(error) = self.save_cache_sync() {
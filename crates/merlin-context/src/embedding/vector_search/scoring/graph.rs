@@ -1,7 +1,9 @@
 //! Graph-based scoring and filtering utilities.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use merlin_languages::import_graph;
 
 use crate::context_inclusion::MIN_SIMILARITY_SCORE;
 use crate::embedding::SearchResult;
@@ -53,9 +55,27 @@ pub fn filter_by_min_score(results: Vec<SearchResult>) -> Vec<SearchResult> {
         .collect()
 }
 
-/// Build import graph from Rust source files.
-/// Currently returns an empty graph when rust-analyzer backend is not available.
-pub fn build_import_graph(_files: &[PathBuf]) -> HashMap<PathBuf, Vec<PathBuf>> {
-    // Graph ranking is an enhancement; safe to return empty graph.
-    HashMap::default()
+/// Build the import graph of ranked files, with paths relative to `project_root`
+///
+/// Imports are resolved by the language backends (Python and TypeScript); files
+/// of other languages have no edges.
+pub fn build_import_graph(
+    project_root: &Path,
+    files: &[PathBuf],
+) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut absolute: Vec<PathBuf> = files.iter().map(|file| project_root.join(file)).collect();
+    absolute.sort();
+    absolute.dedup();
+
+    let relative = |path: &Path| {
+        path.strip_prefix(project_root)
+            .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
+    };
+    import_graph(project_root, &absolute)
+        .into_iter()
+        .map(|(file, imports)| {
+            let imports = imports.iter().map(|import| relative(import)).collect();
+            (relative(&file), imports)
+        })
+        .collect()
 }
@@ -7,7 +7,7 @@ mod graph;
 mod query_analysis;
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::embedding::SearchResult;
 
@@ -40,9 +40,11 @@ impl ScoringUtils {
         graph::filter_by_min_score(results)
     }
 
-    /// Build import graph from Rust source files.
-    /// Currently returns an empty graph when rust-analyzer backend is not available.
-    pub fn build_import_graph(files: &[PathBuf]) -> HashMap<PathBuf, Vec<PathBuf>> {
-        graph::build_import_graph(files)
+    /// Build the import graph of ranked files, with paths relative to `project_root`
+    pub fn build_import_graph(
+        project_root: &Path,
+        files: &[PathBuf],
+    ) -> HashMap<PathBuf, Vec<PathBuf>> {
+        graph::build_import_graph(project_root, files)
     }
 }
@@ -0,0 +1,186 @@
+//! Hybrid BM25 and vector search over the index.
+
+use std::cmp::Ordering;
+use std::path::PathBuf;
+use std::time::Instant;
+use tracing::{Instrument as _, Level, Span, field, info, span, warn};
+
+use super::VectorSearchManager;
+use super::scoring::ScoringUtils;
+use crate::embedding::SearchResult;
+use crate::embedding::client::EmbeddingProvider;
+use merlin_core::CoreResult as Result;
+
+impl<E: EmbeddingProvider + Clone + 'static> VectorSearchManager<E> {
+    /// Hybrid search combining BM25 keyword search and vector semantic search
+    ///
+    /// Query embedding time and hybrid ranking time are recorded separately on the
+    /// `vector_search` span.
+    ///
+    /// # Errors
+    /// Returns an error if embedding the query fails
+    pub async fn search(&self, query: &str, top_k: usize) -> Result<Vec<SearchResult>> {
+        self.search_with_rerank(query, top_k, true).await
+    }
+
+    /// Search with hybrid BM25 + vector approach, optionally skipping reranking
+    ///
+    /// With `rerank` off, results keep their fused BM25 and vector order
+    /// instead of being boosted by the import graph and query imports.
+    ///
+    /// # Errors
+    /// Returns an error if embedding the query fails
+    pub async fn search_with_rerank(
+        &self,
+        query: &str,
+        top_k: usize,
+        rerank: bool,
+    ) -> Result<Vec<SearchResult>> {
+        let span = span!(
+            Level::INFO,
+            "vector_search",
+            top_k,
+            query_embedding_ms = field::Empty,
+            hybrid_search_ms = field::Empty
+        );
+
+        async move {
+            info!(
+                "  Hybrid search: {} embeddings, {} BM25 docs",
+                self.store.len(),
+                self.bm25.len()
+            );
+
+            if self.store.is_empty() && self.keyword_previews.is_empty() {
+                warn!("  Vector store is empty - no results");
+                return Ok(Vec::default());
+            }
+
+            // A keyword-only index has no vectors to compare the query with
+            let query_embedding = if self.store.is_empty() {
+                None
+            } else {
+                let embedding_start = Instant::now();
+                let embedding = self
+                    .client
+                    .embed(query)
+                    .instrument(span!(Level::INFO, "query_embedding"))
+                    .await?;
+                Span::current().record(
+                    "query_embedding_ms",
+                    embedding_start.elapsed().as_millis() as u64,
+                );
+                Some(embedding)
+            };
+
+            let hybrid_start = Instant::now();
+            let results = span!(Level::INFO, "hybrid_search")
+                .in_scope(|| self.hybrid_search(query, query_embedding.as_deref(), top_k, rerank));
+            Span::current().record(
+                "hybrid_search_ms",
+                hybrid_start.elapsed().as_millis() as u64,
+            );
+
+            Ok(results)
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Ranks BM25 and vector matches for an already embedded query
+    ///
+    /// Without a query embedding, only BM25 matches are ranked.
+    fn hybrid_search(
+        &self,
+        query: &str,
+        query_embedding: Option<&[f32]>,
+        top_k: usize,
+        rerank: bool,
+    ) -> Vec<SearchResult> {
+        // Run BM25 keyword search
+        let bm25_results = self.bm25.search(query, top_k * 2);
+        info!("  BM25 found {} keyword matches", bm25_results.len());
+
+        // Run vector semantic search, or pass the keyword matches' previews on unscored
+        let vector_results = query_embedding.map_or_else(
+            || self.keyword_previews(&bm25_results),
+            |embedding| {
+                let results = self.store.search(embedding, top_k * 2);
+                info!("  Vector found {} semantic matches", results.len());
+                results
+            },
+        );
+
+        // Combine results using adaptive weighted fusion
+        let mut combined =
+            ScoringUtils::reciprocal_rank_fusion(query, &bm25_results, &vector_results, top_k);
+
+        if rerank {
+            // Build import graph for graph-based ranking
+            let all_files: Vec<PathBuf> = combined
+                .iter()
+                .map(|result| result.file_path.clone())
+                .collect();
+            let import_graph = ScoringUtils::build_import_graph(&self.project_root, &all_files);
+
+            // Apply graph-based boost
+            ScoringUtils::apply_graph_boost(&mut combined, &import_graph);
+
+            // Apply import-based boosting using preview content
+            for result in &mut combined {
+                let import_boost = ScoringUtils::boost_by_imports(&result.preview, query);
+                result.score *= import_boost;
+            }
+
+            // Re-sort after boosting
+            combined.sort_by(|result_a, result_b| {
+                result_b
+                    .score
+                    .partial_cmp(&result_a.score)
+                    .unwrap_or(Ordering::Equal)
+            });
+
+            // Re-normalize after boosting
+            if let Some(max_score) = combined.first().map(|result| result.score)
+                && max_score > 0.0
+            {
+                for result in &mut combined {
+                    result.score /= max_score;
+                }
+            }
+        }
+
+        info!(
+            "  Combined {} results using RRF{}",
+            combined.len(),
+            if rerank { " + import boost" } else { "" }
+        );
+        if !combined.is_empty() {
+            let top_scores: Vec<f32> = combined.iter().take(5).map(|result| result.score).collect();
+            info!("  Top scores: {:?}", top_scores);
+        }
+
+        // Filter by minimum similarity score
+        let filtered = ScoringUtils::filter_by_min_score(combined);
+
+        info!("  After filtering: {} results", filtered.len());
+
+        filtered
+    }
+
+    /// Unscored results carrying the previews of BM25 matches in a keyword-only index
+    fn keyword_previews(&self, bm25_results: &[(PathBuf, f32)]) -> Vec<SearchResult> {
+        bm25_results
+            .iter()
+            .filter_map(|(path, _)| {
+                self.keyword_previews.get(path).map(|preview| SearchResult {
+                    file_path: path.clone(),
+                    score: 0.0,
+                    preview: preview.clone(),
+                    bm25_score: None,
+                    vector_score: None,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,185 @@
+//! Reading files a section at a time while chunking them.
+//!
+//! Files of at least the streaming threshold are never read whole: they are
+//! read in sections of about the threshold's size, cut at line ends, and each
+//! section is chunked on its own. The content hash is built up section by
+//! section and equals [`CacheOperations::compute_file_hash`] of the whole file,
+//! so cached embeddings stay valid whichever way a file was read.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::Hasher as _;
+use std::io::{self, BufRead, BufReader, Read as _};
+use std::path::Path;
+
+use crate::embedding::chunking::{FileChunk, chunk_file};
+#[cfg(doc)]
+use crate::embedding::vector_search::cache::CacheOperations;
+
+/// Size in bytes from which files are read and chunked a section at a time
+pub const DEFAULT_STREAMING_THRESHOLD: u64 = 64 * 1024;
+
+/// Chunks of a file and the hash of its content
+type ChunkedFile = (Vec<FileChunk>, u64);
+
+/// Byte `str` hashing appends after the content, keeping hashes prefix-free
+const STR_HASH_TERMINATOR: u8 = 0xff;
+
+/// Reads and chunks the file at `absolute_path`, returning its chunks and content hash
+///
+/// Files smaller than `threshold` bytes are read whole; larger ones are read
+/// and chunked in sections of about `threshold` bytes. Chunk lines are lines
+/// of the whole file either way. Returns no chunks for a blank file.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is not valid UTF-8
+pub fn read_and_chunk(
+    relative_path: &Path,
+    absolute_path: &Path,
+    threshold: u64,
+) -> io::Result<ChunkedFile> {
+    let file = File::open(absolute_path)?;
+    let len = file.metadata().map_or(0, |metadata| metadata.len());
+    if len >= threshold {
+        return chunk_sections(relative_path, BufReader::new(file), threshold);
+    }
+
+    let mut content = String::with_capacity(usize::try_from(len).unwrap_or_default());
+    BufReader::new(file).read_to_string(&mut content)?;
+    if content.trim().is_empty() {
+        return Ok((Vec::new(), 0));
+    }
+    let mut hasher = DefaultHasher::new();
+    hasher.write(content.as_bytes());
+    hasher.write_u8(STR_HASH_TERMINATOR);
+    Ok((chunk_file(relative_path, &content), hasher.finish()))
+}
+
+/// Chunks `reader` a section of about `section_bytes` bytes at a time
+///
+/// # Errors
+/// Returns an error if reading fails or the content is not valid UTF-8
+fn chunk_sections(
+    relative_path: &Path,
+    mut reader: impl BufRead,
+    section_bytes: u64,
+) -> io::Result<ChunkedFile> {
+    let section_bytes = usize::try_from(section_bytes).unwrap_or(usize::MAX).max(1);
+    let mut hasher = DefaultHasher::new();
+    let mut chunks = Vec::new();
+    let mut lines_before = 0;
+    let mut blank = true;
+    let mut section = String::new();
+
+    loop {
+        section.clear();
+        while section.len() < section_bytes && reader.read_line(&mut section)? > 0 {}
+        if section.is_empty() {
+            break;
+        }
+        hasher.write(section.as_bytes());
+        if !section.trim().is_empty() {
+            blank = false;
+            chunks.extend(
+                chunk_file(relative_path, &section)
+                    .into_iter()
+                    .map(|mut chunk| {
+                        chunk.start_line += lines_before;
+                        chunk.end_line += lines_before;
+                        chunk
+                    }),
+            );
+        }
+        lines_before += section.lines().count();
+    }
+
+    if blank {
+        return Ok((Vec::new(), 0));
+    }
+    hasher.write_u8(STR_HASH_TERMINATOR);
+    Ok((chunks, hasher.finish()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedding::vector_search::cache::CacheOperations;
+    use std::io::Cursor;
+    use std::path::PathBuf;
+
+    /// Markdown with `count` sections of a heading and a paragraph
+    fn markdown(count: usize) -> String {
+        "## Section\n\nA paragraph of text.\n\n".repeat(count)
+    }
+
+    /// Tests that the sectioned hash equals the hash of the whole content.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails.
+    ///
+    /// # Panics
+    /// Panics if the hashes differ.
+    #[test]
+    fn test_section_hash_matches_whole_file_hash() -> io::Result<()> {
+        let content = markdown(50);
+        for section_bytes in [1, 7, 100, 4096] {
+            let (_, hash) = chunk_sections(
+                &PathBuf::from("notes.md"),
+                Cursor::new(content.as_bytes()),
+                section_bytes,
+            )?;
+            assert_eq!(hash, CacheOperations::compute_file_hash(&content));
+        }
+        Ok(())
+    }
+
+    /// Tests that chunk lines of later sections are lines of the whole file.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails.
+    ///
+    /// # Panics
+    /// Panics if a chunk's first line is not the file line it claims to start at.
+    #[test]
+    fn test_section_chunks_keep_file_line_numbers() -> io::Result<()> {
+        let content = markdown(50);
+        let lines: Vec<_> = content.lines().collect();
+        let (chunks, _) = chunk_sections(
+            &PathBuf::from("notes.md"),
+            Cursor::new(content.as_bytes()),
+            256,
+        )?;
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert_eq!(
+                chunk.content.lines().next(),
+                lines.get(chunk.start_line - 1).copied(),
+                "{chunk:?}"
+            );
+        }
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.end_line).max(),
+            Some(lines.len())
+        );
+        Ok(())
+    }
+
+    /// Tests that a file of only whitespace yields no chunks.
+    ///
+    /// # Errors
+    /// Returns an error if reading fails.
+    ///
+    /// # Panics
+    /// Panics if chunks are produced.
+    #[test]
+    fn test_blank_sections_yield_no_chunks() -> io::Result<()> {
+        let (chunks, _) = chunk_sections(
+            &PathBuf::from("blank.md"),
+            Cursor::new("\n   \n\n".repeat(100).into_bytes()),
+            16,
+        )?;
+        assert!(chunks.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+//! Explanations of why files were included in a context.
+//!
+//! The [`ContextBuilder`] records how each file of its last context was found:
+//! pinned or requested by the user, matched by hybrid search, or pulled in as
+//! a symbol definition or a cross-language import. [`ContextExplanation`]
+//! lists that per file and renders it as a Markdown table for debugging.
+//!
+//! [`ContextBuilder`]: crate::ContextBuilder
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use merlin_core::Context;
+
+/// How a file came to be included in a context
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InclusionSource {
+    /// Pinned to the conversation or referenced with `@`
+    Pinned,
+    /// Listed in the query's files
+    Requested,
+    /// Matched by hybrid BM25 and vector search
+    Search,
+    /// Defines a symbol named in the query
+    SymbolDefinition,
+    /// Imported across languages by a symbol definition's file
+    ImportExpansion,
+    /// Collected by scanning the project because requested files were unreadable
+    ProjectScan,
+}
+
+impl InclusionSource {
+    /// Short label used in the Markdown table
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Pinned => "pinned",
+            Self::Requested => "requested",
+            Self::Search => "search",
+            Self::SymbolDefinition => "symbol definition",
+            Self::ImportExpansion => "import expansion",
+            Self::ProjectScan => "project scan",
+        }
+    }
+}
+
+/// Why one file was included
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileExplanation {
+    /// Path of the file as it appears in the context
+    pub path: PathBuf,
+    /// How the file was found, if the builder recorded it
+    pub source: Option<InclusionSource>,
+    /// Best BM25 score among the file's matching chunks
+    pub bm25_score: Option<f32>,
+    /// Best vector similarity score among the file's matching chunks
+    pub vector_score: Option<f32>,
+    /// Best reciprocal rank fusion score among the file's matching chunks
+    pub combined_score: Option<f32>,
+    /// Line ranges of the chunks that matched the query, in rank order
+    pub matched_chunks: Vec<(usize, usize)>,
+}
+
+impl FileExplanation {
+    /// Creates an explanation without scores
+    pub const fn new(path: PathBuf, source: Option<InclusionSource>) -> Self {
+        Self {
+            path,
+            source,
+            bm25_score: None,
+            vector_score: None,
+            combined_score: None,
+            matched_chunks: Vec::new(),
+        }
+    }
+
+    /// Whether the file was added by following an import
+    pub fn import_expansion(&self) -> bool {
+        self.source == Some(InclusionSource::ImportExpansion)
+    }
+
+    /// Whether the user asked for the file rather than it being retrieved
+    pub fn requested(&self) -> bool {
+        matches!(
+            self.source,
+            Some(InclusionSource::Pinned | InclusionSource::Requested)
+        )
+    }
+}
+
+/// Why each file of a context was included
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContextExplanation {
+    /// One entry per distinct file, in context order
+    pub files: Vec<FileExplanation>,
+}
+
+impl ContextExplanation {
+    /// Explains the files of `context` from what was recorded while building it
+    ///
+    /// Files missing from `recorded` are listed without a source, except pinned
+    /// files, which say so themselves.
+    pub fn for_context(context: &Context, recorded: &HashMap<PathBuf, FileExplanation>) -> Self {
+        let mut files: Vec<FileExplanation> = Vec::new();
+        for file in &context.files {
+            if files.iter().any(|explained| explained.path == file.path) {
+                continue;
+            }
+            let explanation = recorded.get(&file.path).cloned().unwrap_or_else(|| {
+                let source = file.pinned.then_some(InclusionSource::Pinned);
+                FileExplanation::new(file.path.clone(), source)
+            });
+            files.push(explanation);
+        }
+        Self { files }
+    }
+
+    /// Explanation of the file at `path`, if it is in the context
+    pub fn file(&self, path: &Path) -> Option<&FileExplanation> {
+        self.files.iter().find(|file| file.path == path)
+    }
+
+    /// Formats the explanation as a Markdown table, one row per file
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("## Context explanation ({} files)\n\n", self.files.len());
+        if self.files.is_empty() {
+            markdown.push_str("No files were included.\n");
+            return markdown;
+        }
+        markdown.push_str(
+            "| File | Source | BM25 | Vector | RRF | Import expansion | Requested | Matched chunks |\n\
+             |------|--------|------|--------|-----|------------------|-----------|----------------|\n",
+        );
+        for file in &self.files {
+            let chunks = if file.matched_chunks.is_empty() {
+                "-".to_owned()
+            } else {
+                file.matched_chunks
+                    .iter()
+                    .map(|(start, end)| format!("{start}-{end}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            _ = writeln!(
+                markdown,
+                "| `{}` | {} | {} | {} | {} | {} | {} | {chunks} |",
+                file.path.display(),
+                file.source.map_or("unknown", InclusionSource::label),
+                format_score(file.bm25_score),
+                format_score(file.vector_score),
+                format_score(file.combined_score),
+                yes_no(file.import_expansion()),
+                yes_no(file.requested()),
+            );
+        }
+        markdown
+    }
+}
+
+/// Formats a score to three decimals, or `-` when absent
+fn format_score(score: Option<f32>) -> String {
+    score.map_or_else(|| "-".to_owned(), |value| format!("{value:.3}"))
+}
+
+/// Formats a flag for the table
+const fn yes_no(flag: bool) -> &'static str {
+    if flag { "yes" } else { "no" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::FileContext;
+
+    /// Tests that recorded files keep their scores and unrecorded ones fall back.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_explanation_to_markdown() {
+        let searched = FileExplanation {
+            bm25_score: Some(0.5),
+            vector_score: None,
+            combined_score: Some(0.825),
+            matched_chunks: vec![(10, 40), (90, 120)],
+            ..FileExplanation::new(
+                PathBuf::from("/p/src/auth.rs"),
+                Some(InclusionSource::Search),
+            )
+        };
+        let expanded = FileExplanation::new(
+            PathBuf::from("/p/native/auth.c"),
+            Some(InclusionSource::ImportExpansion),
+        );
+        let recorded: HashMap<_, _> = [searched.clone(), expanded]
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect();
+        let context = Context::new(String::new()).with_files(vec![
+            FileContext::new(PathBuf::from("spec.md"), "spec".to_owned()).into_pinned(),
+            FileContext::new(PathBuf::from("/p/src/auth.rs"), "a".to_owned()),
+            FileContext::new(PathBuf::from("/p/src/auth.rs"), "b".to_owned()),
+            FileContext::new(PathBuf::from("/p/native/auth.c"), "c".to_owned()),
+            FileContext::new(PathBuf::from("/p/other.rs"), "d".to_owned()),
+        ]);
+
+        let explanation = ContextExplanation::for_context(&context, &recorded);
+        assert_eq!(explanation.files.len(), 4);
+        assert_eq!(
+            explanation.file(Path::new("/p/src/auth.rs")),
+            Some(&searched)
+        );
+        assert!(explanation.files[0].requested());
+        assert_eq!(explanation.files[3].source, None);
+
+        let markdown = explanation.to_markdown();
+        assert!(markdown.starts_with("## Context explanation (4 files)\n"));
+        assert!(markdown.contains("| `spec.md` | pinned | - | - | - | no | yes | - |\n"));
+        assert!(markdown.contains(
+            "| `/p/src/auth.rs` | search | 0.500 | - | 0.825 | no | no | 10-40, 90-120 |\n"
+        ));
+        assert!(
+            markdown.contains("| `/p/native/auth.c` | import expansion | - | - | - | yes | no |")
+        );
+        assert!(markdown.contains("| `/p/other.rs` | unknown |"));
+    }
+}
@@ -5,15 +5,24 @@ mod builder;
 pub mod context_fetcher;
 pub mod context_inclusion;
 pub mod embedding;
+pub mod explanation;
 mod fs_utils;
 pub mod models;
+pub mod navigation;
+mod pinned;
 pub mod query;
+pub mod references;
+pub mod repeated_files;
 
 pub use builder::ContextBuilder;
 pub use context_fetcher::ContextFetcher;
 #[cfg(any(test, feature = "test-helpers"))]
 pub use embedding::FakeEmbeddingClient;
 pub use embedding::{
-    EmbeddingClient, EmbeddingProvider, ProgressCallback, SearchResult, VectorSearchManager,
-    VectorStore,
+    EmbeddingClient, EmbeddingProvider, NoOpEmbeddingProvider, ProgressCallback, SearchResult,
+    VectorSearchManager, VectorStore,
 };
+pub use explanation::{ContextExplanation, FileExplanation, InclusionSource};
+pub use navigation::{FindCallersTool, FindImplementationsTool, SymbolSearchTool};
+pub use references::ResolvedReference;
+pub use repeated_files::{RepeatedFilePolicy, RepeatedFiles};
@@ -0,0 +1,206 @@
+//! Code navigation tools backed by the language index.
+//!
+//! `searchSymbols`, `findCallers` and `findImplementations` answer definition,
+//! call hierarchy and type hierarchy questions from the same index the context
+//! builder uses, so agents can follow code without grepping for names. The
+//! same index resolves the symbols named in `requestContext` calls.
+
+mod resolver;
+mod symbol_search;
+#[cfg(test)]
+mod tests;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Value, json, to_value};
+use std::path::Path;
+use std::sync::Arc;
+
+use merlin_languages::SymbolInfo;
+use merlin_tooling::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+
+use crate::ContextFetcher;
+
+pub use symbol_search::SymbolSearchTool;
+
+/// A symbol returned to the agent, with its path relative to the project root
+#[derive(Debug, Clone, Serialize)]
+struct SymbolLocation {
+    /// Symbol name (`<module>` for calls made outside any function)
+    name: String,
+    /// Symbol kind, e.g. `Function` or `Struct`
+    kind: String,
+    /// File containing the symbol
+    file: String,
+    /// Line of the call or definition (1-indexed)
+    line: u32,
+}
+
+/// Converts symbols to the JSON array returned to the agent
+///
+/// # Errors
+/// Returns an error if serialization fails
+fn symbol_locations(project_root: &Path, symbols: Vec<SymbolInfo>) -> ToolResult<Value> {
+    let locations: Vec<SymbolLocation> = symbols
+        .into_iter()
+        .map(|symbol| SymbolLocation {
+            name: symbol.name,
+            kind: format!("{:?}", symbol.kind),
+            file: symbol
+                .file_path
+                .strip_prefix(project_root)
+                .unwrap_or(&symbol.file_path)
+                .display()
+                .to_string(),
+            line: symbol.line,
+        })
+        .collect();
+    Ok(to_value(locations)?)
+}
+
+/// Reads a required string parameter
+///
+/// # Errors
+/// Returns an error if the parameter is missing or not a string
+fn string_param<'input>(
+    input: &'input ToolInput,
+    tool: &str,
+    name: &str,
+) -> ToolResult<&'input str> {
+    input
+        .params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            ToolError::invalid_argument(name, format!("{tool} requires a '{name}' parameter"))
+        })
+}
+
+/// Tool listing the functions and methods that call a symbol
+pub struct FindCallersTool {
+    /// Fetcher owning the language index
+    context_fetcher: Arc<ContextFetcher>,
+}
+
+impl FindCallersTool {
+    /// Create a new `FindCallersTool` querying `context_fetcher`'s index
+    #[must_use]
+    pub const fn new(context_fetcher: Arc<ContextFetcher>) -> Self {
+        Self { context_fetcher }
+    }
+}
+
+#[async_trait]
+impl Tool for FindCallersTool {
+    fn name(&self) -> &'static str {
+        "findCallers"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Finds the functions and methods that call a symbol.
+ * Calls outside any function are reported with the name '<module>'.
+ * @param symbol - Name of the called function, method or class
+ * @param file - File where the symbol is used or defined, relative to the workspace root
+ * @param line - Line of that use or definition (1-indexed), used to resolve the symbol
+ * @returns Callers with the line of each call
+ */
+declare function findCallers(symbol: string, file: string, line: number): Promise<{ name: string, kind: string, file: string, line: number }[]>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "symbol": { "type": "string" },
+                "file": { "type": "string" },
+                "line": { "type": "integer" }
+            },
+            "required": ["symbol", "file", "line"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let symbol = string_param(&input, "findCallers", "symbol")?;
+        let file = string_param(&input, "findCallers", "file")?;
+        let line = input
+            .params
+            .get("line")
+            .and_then(Value::as_u64)
+            .and_then(|line| u32::try_from(line).ok())
+            .ok_or_else(|| {
+                ToolError::invalid_argument("line", "findCallers requires a 'line' parameter")
+            })?;
+
+        let project_root = self.context_fetcher.project_root();
+        let callers = self
+            .context_fetcher
+            .find_callers(symbol, &project_root.join(file), line)
+            .await
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?;
+
+        let message = format!("Found {} calls of {symbol}", callers.len());
+        Ok(ToolOutput::success_with_data(
+            message,
+            symbol_locations(project_root, callers)?,
+        ))
+    }
+}
+
+/// Tool listing the types that implement, extend or satisfy a type
+pub struct FindImplementationsTool {
+    /// Fetcher owning the language index
+    context_fetcher: Arc<ContextFetcher>,
+}
+
+impl FindImplementationsTool {
+    /// Create a new `FindImplementationsTool` querying `context_fetcher`'s index
+    #[must_use]
+    pub const fn new(context_fetcher: Arc<ContextFetcher>) -> Self {
+        Self { context_fetcher }
+    }
+}
+
+#[async_trait]
+impl Tool for FindImplementationsTool {
+    fn name(&self) -> &'static str {
+        "findImplementations"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Finds the types implementing an interface or extending a class.
+ * Go types are matched by method set, since interfaces are satisfied implicitly.
+ * @param name - Name of the interface, class or base type
+ * @returns The implementing types with the line of their definition
+ */
+declare function findImplementations(name: string): Promise<{ name: string, kind: string, file: string, line: number }[]>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": { "name": { "type": "string" } },
+            "required": ["name"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let name = match input.params.as_str() {
+            Some(name) => name,
+            None => string_param(&input, "findImplementations", "name")?,
+        };
+
+        let implementations = self
+            .context_fetcher
+            .find_implementations(name)
+            .await
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?;
+
+        let message = format!("Found {} implementations of {name}", implementations.len());
+        Ok(ToolOutput::success_with_data(
+            message,
+            symbol_locations(self.context_fetcher.project_root(), implementations)?,
+        ))
+    }
+}
@@ -0,0 +1,62 @@
+//! Resolution of `requestContext` symbol requests from the language index.
+
+use async_trait::async_trait;
+use tokio::fs::read_to_string;
+
+use merlin_languages::{CodeSpan, SearchQuery, chunker_for};
+use merlin_tooling::{SymbolDefinition, SymbolName, SymbolResolver, ToolError, ToolResult};
+
+use crate::ContextFetcher;
+
+/// Innermost span containing `line` that defines `symbol`
+fn definition_span<'spans>(
+    spans: &'spans [CodeSpan],
+    line: usize,
+    symbol: &SymbolName,
+) -> Option<&'spans CodeSpan> {
+    spans
+        .iter()
+        .filter(|span| span.start_line <= line && line <= span.end_line)
+        .find_map(|span| {
+            definition_span(&span.children, line, symbol)
+                .or_else(|| symbol.matches_identifier(&span.identifier).then_some(span))
+        })
+}
+
+#[async_trait]
+impl SymbolResolver for ContextFetcher {
+    async fn resolve_symbol(&self, symbol: &SymbolName) -> ToolResult<Vec<SymbolDefinition>> {
+        let query = SearchQuery {
+            symbol_name: Some(symbol.name.clone()),
+            ..SearchQuery::default()
+        };
+        let symbols = self
+            .search_symbols(&query)
+            .await
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?;
+
+        let mut definitions = Vec::new();
+        for info in symbols.into_iter().filter(|info| info.name == symbol.name) {
+            let path = self.project_root().join(&info.file_path);
+            let line = info.line as usize;
+            let source = read_to_string(&path).await.unwrap_or_default();
+            let spans = chunker_for(&path)
+                .and_then(|chunker| chunker.code_spans(&path, &source).ok())
+                .unwrap_or_default();
+
+            // Without a span the parent cannot be checked, so only unqualified names fall back to the line
+            let span = definition_span(&spans, line, symbol)
+                .map(|span| (span.start_line, span.end_line))
+                .or_else(|| symbol.parent.is_none().then_some((line, line)));
+            if let Some((start_line, end_line)) = span {
+                definitions.push(SymbolDefinition {
+                    kind: format!("{:?}", info.kind),
+                    file: path,
+                    start_line,
+                    end_line,
+                });
+            }
+        }
+        Ok(definitions)
+    }
+}
@@ -0,0 +1,202 @@
+//! The `searchSymbols` tool, listing definitions by name or kind.
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Value, json, to_value};
+use std::sync::Arc;
+
+use merlin_languages::{SearchQuery, SymbolInfo, SymbolKind};
+use merlin_tooling::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+
+use super::string_param;
+use crate::ContextFetcher;
+
+/// A definition or reference returned by `searchSymbols`
+#[derive(Debug, Clone, Serialize)]
+struct SymbolMatch {
+    /// Symbol name
+    name: String,
+    /// Symbol kind, or `Reference` for a line mentioning the symbol
+    kind: String,
+    /// File containing the definition or reference
+    file: String,
+    /// Line of the definition or reference (1-indexed)
+    line: u32,
+    /// Doc comment of the definition, if any
+    documentation: Option<String>,
+}
+
+/// Symbol kinds accepted by `kind_filter`
+const SYMBOL_KINDS: [SymbolKind; 10] = [
+    SymbolKind::Function,
+    SymbolKind::Struct,
+    SymbolKind::Enum,
+    SymbolKind::Trait,
+    SymbolKind::Module,
+    SymbolKind::Constant,
+    SymbolKind::Variable,
+    SymbolKind::Field,
+    SymbolKind::Method,
+    SymbolKind::Type,
+];
+
+/// Parses a `kind_filter` entry, ignoring case
+///
+/// # Errors
+/// Returns an error naming the accepted kinds if `name` is not one of them
+fn parse_kind(name: &str) -> ToolResult<SymbolKind> {
+    SYMBOL_KINDS
+        .into_iter()
+        .find(|kind| format!("{kind:?}").eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let accepted: Vec<String> = SYMBOL_KINDS
+                .iter()
+                .map(|kind| format!("{kind:?}"))
+                .collect();
+            ToolError::invalid_argument(
+                "kind_filter",
+                format!(
+                    "Unknown symbol kind '{name}', expected one of: {}",
+                    accepted.join(", ")
+                ),
+            )
+        })
+}
+
+/// Tool searching the language index for definitions and, optionally, references
+pub struct SymbolSearchTool {
+    /// Fetcher owning the language index
+    context_fetcher: Arc<ContextFetcher>,
+}
+
+impl SymbolSearchTool {
+    /// Create a new `SymbolSearchTool` querying `context_fetcher`'s index
+    #[must_use]
+    pub const fn new(context_fetcher: Arc<ContextFetcher>) -> Self {
+        Self { context_fetcher }
+    }
+
+    /// Converts a symbol to the entry returned to the agent
+    fn symbol_match(&self, symbol: SymbolInfo, kind: String) -> SymbolMatch {
+        SymbolMatch {
+            name: symbol.name,
+            kind,
+            file: symbol
+                .file_path
+                .strip_prefix(self.context_fetcher.project_root())
+                .unwrap_or(&symbol.file_path)
+                .display()
+                .to_string(),
+            line: symbol.line,
+            documentation: symbol.documentation,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for SymbolSearchTool {
+    fn name(&self) -> &'static str {
+        "searchSymbols"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Finds the definitions of symbols whose name contains the given one, exact matches first.
+ * @param symbol_name - Name of the function, type, method or constant
+ * @param options - Optional settings: kind_filter (e.g. ['Function', 'Method']) keeps only
+ *   definitions of those kinds; include_references also returns every line mentioning the
+ *   symbol, with kind 'Reference'
+ * @returns Definitions, then references, with paths relative to the workspace root
+ */
+declare function searchSymbols(symbol_name: string, options?: { kind_filter?: string[], include_references?: boolean }): Promise<{ name: string, kind: string, file: string, line: number, documentation: string | null }[]>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "symbol_name": { "type": "string" },
+                "kind_filter": { "type": ["array", "null"], "items": { "type": "string" } },
+                "include_references": { "type": ["boolean", "null"] }
+            },
+            "required": ["symbol_name"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let symbol_name = match input.params.as_str() {
+            Some(name) => name,
+            None => string_param(&input, "searchSymbols", "symbol_name")?,
+        };
+        let kind_filter = input
+            .params
+            .get("kind_filter")
+            .and_then(Value::as_array)
+            .map(|kinds| {
+                kinds
+                    .iter()
+                    .map(|kind| {
+                        kind.as_str().map_or_else(
+                            || {
+                                Err(ToolError::invalid_argument(
+                                    "kind_filter",
+                                    "kind_filter entries must be strings",
+                                ))
+                            },
+                            parse_kind,
+                        )
+                    })
+                    .collect::<ToolResult<Vec<_>>>()
+            })
+            .transpose()?;
+        let include_references = input
+            .params
+            .get("include_references")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let query = SearchQuery {
+            symbol_name: Some(symbol_name.to_owned()),
+            ..SearchQuery::default()
+        };
+        let definitions: Vec<SymbolInfo> = self
+            .context_fetcher
+            .search_symbols(&query)
+            .await
+            .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?
+            .into_iter()
+            .filter(|symbol| {
+                kind_filter
+                    .as_ref()
+                    .is_none_or(|kinds| kinds.contains(&symbol.kind))
+            })
+            .collect();
+        let references = if include_references {
+            self.context_fetcher
+                .find_references(symbol_name)
+                .await
+                .map_err(|err| ToolError::ExecutionFailed(err.to_string()))?
+        } else {
+            Vec::new()
+        };
+
+        let message = format!(
+            "Found {} definitions and {} references of {symbol_name}",
+            definitions.len(),
+            references.len()
+        );
+        let matches: Vec<SymbolMatch> = definitions
+            .into_iter()
+            .map(|symbol| {
+                let kind = format!("{:?}", symbol.kind);
+                self.symbol_match(symbol, kind)
+            })
+            .chain(
+                references
+                    .into_iter()
+                    .map(|symbol| self.symbol_match(symbol, "Reference".to_owned())),
+            )
+            .collect();
+        Ok(ToolOutput::success_with_data(message, to_value(matches)?))
+    }
+}
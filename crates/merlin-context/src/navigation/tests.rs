@@ -0,0 +1,180 @@
+//! Tests for the code navigation tools
+
+use super::*;
+use merlin_tooling::{SymbolDefinition, SymbolName, SymbolResolver as _};
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+/// Tests that both tools answer from the project's language index.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or a tool fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_navigation_tools() -> ToolResult<()> {
+    let dir = TempDir::new()?;
+    fs::write(
+        dir.path().join("shapes.py"),
+        "class Shape:\n    def area(self):\n        return 0\n\n\nclass Square(Shape):\n    pass\n\n\ndef total(shapes):\n    return sum(shape.area() for shape in shapes)\n",
+    )?;
+    let fetcher = Arc::new(ContextFetcher::new(dir.path().to_path_buf()));
+
+    let callers = FindCallersTool::new(Arc::clone(&fetcher))
+        .execute(ToolInput {
+            params: json!({ "symbol": "area", "file": "shapes.py", "line": 2 }),
+        })
+        .await?;
+    assert_eq!(
+        callers.data,
+        Some(json!([{ "name": "total", "kind": "Function", "file": "shapes.py", "line": 11 }]))
+    );
+
+    let implementations = FindImplementationsTool::new(fetcher)
+        .execute(ToolInput {
+            params: json!("Shape"),
+        })
+        .await?;
+    assert_eq!(
+        implementations.data,
+        Some(json!([{ "name": "Square", "kind": "Struct", "file": "shapes.py", "line": 6 }]))
+    );
+    Ok(())
+}
+
+/// Tests searching definitions by kind, with and without references.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or the tool fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_symbol_search_tool() -> ToolResult<()> {
+    let dir = TempDir::new()?;
+    fs::write(
+        dir.path().join("billing.py"),
+        "def charge(amount):\n    \"\"\"Charges the card.\"\"\"\n    return amount\n\n\nclass ChargeError(Exception):\n    pass\n",
+    )?;
+    fs::write(
+        dir.path().join("checkout.py"),
+        "from billing import charge\n\n\ndef pay():\n    return charge(10)\n",
+    )?;
+    let tool = SymbolSearchTool::new(Arc::new(ContextFetcher::new(dir.path().to_path_buf())));
+
+    let functions = tool
+        .execute(ToolInput {
+            params: json!({ "symbol_name": "charge", "kind_filter": ["function"] }),
+        })
+        .await?;
+    assert_eq!(
+        functions.data,
+        Some(json!([{
+            "name": "charge",
+            "kind": "Function",
+            "file": "billing.py",
+            "line": 1,
+            "documentation": "Charges the card."
+        }]))
+    );
+
+    let with_references = tool
+        .execute(ToolInput {
+            params: json!({ "symbol_name": "charge", "include_references": true }),
+        })
+        .await?;
+    let entries: Vec<(String, String, u64)> = with_references
+        .data
+        .as_ref()
+        .and_then(Value::as_array)
+        .ok_or_else(|| ToolError::ExecutionFailed("expected matches".to_owned()))?
+        .iter()
+        .map(|entry| {
+            (
+                entry["kind"].as_str().unwrap_or_default().to_owned(),
+                entry["file"].as_str().unwrap_or_default().to_owned(),
+                entry["line"].as_u64().unwrap_or_default(),
+            )
+        })
+        .collect();
+    let expected = [
+        ("Function", "billing.py", 1),
+        ("Struct", "billing.py", 6),
+        ("Reference", "billing.py", 1),
+        ("Reference", "checkout.py", 1),
+        ("Reference", "checkout.py", 5),
+    ]
+    .map(|(kind, file, line)| (kind.to_owned(), file.to_owned(), line));
+    assert_eq!(entries, expected);
+
+    let unknown_kind = tool
+        .execute(ToolInput {
+            params: json!({ "symbol_name": "charge", "kind_filter": ["Widget"] }),
+        })
+        .await;
+    assert!(matches!(
+        &unknown_kind,
+        Err(err) if err.field() == Some("kind_filter")
+    ));
+    Ok(())
+}
+
+/// Tests resolving methods and classes to their spans through the index.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or resolution fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_resolve_symbol_spans() -> ToolResult<()> {
+    let dir = TempDir::new()?;
+    fs::write(
+        dir.path().join("accounts.py"),
+        "class Account:\n    # Current balance\n    def balance(self):\n        return 0\n\n\nclass Ledger:\n    def balance(self):\n        return 1\n",
+    )?;
+    let fetcher = ContextFetcher::new(dir.path().to_path_buf());
+
+    let method = fetcher
+        .resolve_symbol(&SymbolName::parse("Ledger.balance"))
+        .await?;
+    assert_eq!(
+        method,
+        vec![SymbolDefinition {
+            kind: "Method".to_owned(),
+            file: dir.path().join("accounts.py"),
+            start_line: 8,
+            end_line: 9,
+        }]
+    );
+
+    let unqualified = fetcher
+        .resolve_symbol(&SymbolName::parse("balance"))
+        .await?;
+    assert_eq!(unqualified.len(), 2);
+    assert!(
+        unqualified
+            .iter()
+            .any(|definition| (definition.start_line, definition.end_line) == (2, 4))
+    );
+
+    let class = fetcher
+        .resolve_symbol(&SymbolName::parse("Account"))
+        .await?;
+    assert_eq!(
+        class
+            .iter()
+            .map(|definition| (definition.start_line, definition.end_line))
+            .collect::<Vec<_>>(),
+        vec![(1, 4)]
+    );
+    assert!(
+        fetcher
+            .resolve_symbol(&SymbolName::parse("Missing.balance"))
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
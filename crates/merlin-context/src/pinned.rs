@@ -0,0 +1,113 @@
+//! Files pinned to a conversation's context.
+//!
+//! Pinned files are included in full and ahead of retrieved files. They count
+//! against the token budget first, so retrieval only fills what is left.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use merlin_core::FileContext;
+use tokio::fs::read_to_string;
+use tracing::warn;
+
+use crate::context_inclusion::ContextManager;
+
+/// Resolves a pinned path, relative paths being relative to the project root
+fn resolve(project_root: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        project_root.join(path)
+    }
+}
+
+/// Reads the pinned files, skipping those that cannot be read
+pub async fn load_pinned_files(project_root: &Path, paths: &[PathBuf]) -> Vec<FileContext> {
+    let mut files = Vec::with_capacity(paths.len());
+    for path in paths {
+        match read_to_string(resolve(project_root, path)).await {
+            Ok(content) => files.push(FileContext::new(path.clone(), content).into_pinned()),
+            Err(err) => warn!("Skipping pinned file {}: {err}", path.display()),
+        }
+    }
+    files
+}
+
+/// Estimated number of tokens taken by the files
+pub fn token_count(files: &[FileContext]) -> usize {
+    files
+        .iter()
+        .map(|file| ContextManager::estimate_tokens(&file.content))
+        .sum()
+}
+
+/// Places the pinned files ahead of the retrieved ones, keeping at most `max_files`
+///
+/// Pinned files are never dropped. Retrieved chunks of a pinned file are, since
+/// the whole file is already included.
+pub fn merge_pinned(
+    project_root: &Path,
+    pinned: Vec<FileContext>,
+    retrieved: Vec<FileContext>,
+    max_files: usize,
+) -> Vec<FileContext> {
+    let pinned_paths: HashSet<PathBuf> = pinned
+        .iter()
+        .map(|file| resolve(project_root, &file.path))
+        .collect();
+    let room = max_files.saturating_sub(pinned.len());
+
+    let mut files = pinned;
+    files.extend(
+        retrieved
+            .into_iter()
+            .filter(|file| !pinned_paths.contains(&resolve(project_root, &file.path)))
+            .take(room),
+    );
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::write;
+    use std::io::Result;
+    use tempfile::TempDir;
+
+    /// Tests that pinned files come first, replace their chunks and survive truncation.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be created.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_merge_pinned_keeps_pinned_files_first() -> Result<()> {
+        let dir = TempDir::new()?;
+        write(dir.path().join("spec.md"), "The spec")?;
+        let pinned = load_pinned_files(
+            dir.path(),
+            &[PathBuf::from("spec.md"), PathBuf::from("missing.md")],
+        )
+        .await;
+        assert_eq!(pinned.len(), 1);
+        assert!(pinned[0].pinned);
+        assert!(token_count(&pinned) > 0);
+
+        let retrieved = vec![
+            FileContext::new(dir.path().join("spec.md"), "chunk".to_owned()),
+            FileContext::new(PathBuf::from("src/lib.rs"), "lib".to_owned()),
+            FileContext::new(PathBuf::from("src/main.rs"), "main".to_owned()),
+        ];
+        let files = merge_pinned(dir.path(), pinned, retrieved, 2);
+
+        let paths: Vec<_> = files.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("spec.md"), PathBuf::from("src/lib.rs")]
+        );
+        assert_eq!(files[0].content, "The spec");
+        assert!(!files[1].pinned);
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! `@file` and `@symbol` references in queries.
+//!
+//! Users point at code with tokens like `@src/auth/mod.rs` or `@authenticate`.
+//! [`extract_references`] finds the tokens, the [`ContextFetcher`] resolves
+//! them to files, and [`annotate_references`] replaces each resolved token
+//! with a short summary of what it refers to.
+//!
+//! [`ContextFetcher`]: crate::ContextFetcher
+
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use merlin_core::Query;
+use merlin_languages::SymbolInfo;
+use regex::Regex;
+
+/// An `@` token not preceded by a word character, so email addresses are skipped
+static REFERENCE: LazyLock<Option<Regex>> =
+    LazyLock::new(|| Regex::new(r"(?:^|[^\w@])@([\w./:\-]+)").ok());
+
+/// Characters ending a sentence rather than a reference
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ':', ';', '?', '!', ')', '/', '-'];
+
+/// A reference resolved to the file it points at
+#[derive(Debug, Clone)]
+pub struct ResolvedReference {
+    /// Token as written after the `@`
+    pub token: String,
+    /// Referenced file, relative to the project root
+    pub file: PathBuf,
+    /// Symbol the token named, when it was not a path
+    pub symbol: Option<SymbolInfo>,
+}
+
+impl ResolvedReference {
+    /// Inline summary replacing the `@` token
+    pub fn summary(&self) -> String {
+        self.symbol.as_ref().map_or_else(
+            || format!("`{}` (file, included in context)", self.file.display()),
+            |symbol| {
+                format!(
+                    "`{}` ({:?} defined in {}:{}, included in context)",
+                    self.token,
+                    symbol.kind,
+                    self.file.display(),
+                    symbol.line
+                )
+            },
+        )
+    }
+}
+
+/// Returns the distinct `@` references in `text`, in order of appearance
+pub fn extract_references(text: &str) -> Vec<String> {
+    let Some(regex) = REFERENCE.as_ref() else {
+        return Vec::new();
+    };
+    let mut tokens: Vec<String> = Vec::new();
+    for capture in regex.captures_iter(text) {
+        let Some(matched) = capture.get(1) else {
+            continue;
+        };
+        let token = matched.as_str().trim_end_matches(TRAILING_PUNCTUATION);
+        if !token.is_empty() && !tokens.iter().any(|existing| existing == token) {
+            tokens.push(token.to_owned());
+        }
+    }
+    tokens
+}
+
+/// Whether `token` can name a symbol (`name` or `module::name`)
+pub fn is_symbol_token(token: &str) -> bool {
+    token.split("::").all(|segment| {
+        segment
+            .chars()
+            .next()
+            .is_some_and(|first| first.is_alphabetic() || first == '_')
+            && segment
+                .chars()
+                .all(|character| character.is_alphanumeric() || character == '_')
+    })
+}
+
+/// Replaces the `@` token of each resolved reference in `text` with its summary
+///
+/// Unresolved tokens are left as written.
+pub fn annotate_references(text: &str, references: &[ResolvedReference]) -> String {
+    let Some(regex) = REFERENCE.as_ref() else {
+        return text.to_owned();
+    };
+    let mut annotated = String::with_capacity(text.len());
+    let mut copied = 0;
+    for capture in regex.captures_iter(text) {
+        let Some(matched) = capture.get(1) else {
+            continue;
+        };
+        let token = matched.as_str().trim_end_matches(TRAILING_PUNCTUATION);
+        let Some(reference) = references.iter().find(|reference| reference.token == token) else {
+            continue;
+        };
+        // The `@` sits right before the captured token
+        annotated.push_str(&text[copied..matched.start() - 1]);
+        annotated.push_str(&reference.summary());
+        copied = matched.start() + token.len();
+    }
+    annotated.push_str(&text[copied..]);
+    annotated
+}
+
+/// Pins the referenced files to a copy of `query` and replaces the `@` tokens with summaries
+///
+/// Pinned files are included in full ahead of retrieved files and are never
+/// dropped for the token budget.
+pub fn with_references(query: &Query, resolved: &[ResolvedReference]) -> Query {
+    let mut query = query.clone();
+    for reference in resolved {
+        if !query.pinned_files.contains(&reference.file) {
+            query.pinned_files.push(reference.file.clone());
+        }
+    }
+    query.text = annotate_references(&query.text, resolved);
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_languages::SymbolKind;
+
+    /// Tests that paths and symbols are extracted while emails and punctuation are not.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_extract_references() {
+        let tokens = extract_references(
+            "Explain how @src/auth/mod.rs handles sessions. What does @authenticate do? \
+             Mail me@example.com about @auth::Session, and @src/auth/mod.rs again.",
+        );
+        assert_eq!(
+            tokens,
+            vec!["src/auth/mod.rs", "authenticate", "auth::Session"]
+        );
+    }
+
+    /// Tests that resolved tokens are replaced by summaries and others kept.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_annotate_references() {
+        let references = [
+            ResolvedReference {
+                token: "main.rs".to_owned(),
+                file: PathBuf::from("main.rs"),
+                symbol: None,
+            },
+            ResolvedReference {
+                token: "run".to_owned(),
+                file: PathBuf::from("src/lib.rs"),
+                symbol: Some(SymbolInfo {
+                    name: "run".to_owned(),
+                    kind: SymbolKind::Function,
+                    file_path: PathBuf::from("src/lib.rs"),
+                    line: 12,
+                    documentation: None,
+                }),
+            },
+        ];
+        let annotated = annotate_references("Does @main.rs call @run? Ask @nobody.", &references);
+        assert_eq!(
+            annotated,
+            "Does `main.rs` (file, included in context) call `run` (Function defined in \
+             src/lib.rs:12, included in context)? Ask @nobody."
+        );
+        assert!(is_symbol_token("auth::Session"));
+        assert!(!is_symbol_token("main.rs"));
+    }
+}
@@ -0,0 +1,259 @@
+//! Condensing files a thread has already shown to the model.
+//!
+//! Retrieval often returns the same files turn after turn. A file whose
+//! content has not changed since an earlier prompt of the thread is replaced
+//! by a short stub, and a changed file by its diff against the version shown
+//! when the diff is smaller. Pinned files are always included in full.
+
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+
+use merlin_core::{FileContext, ShownFile, ShownFiles};
+use merlin_tooling::DiffSummary;
+
+/// Unchanged lines shown around each change of a condensed file
+const DIFF_CONTEXT_LINES: usize = 2;
+
+/// Start of the first line of content that is an excerpt rather than the whole file
+const EXCERPT_PREFIX: &str = "--- Context: lines";
+
+/// How files already shown in a thread are included again
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RepeatedFilePolicy {
+    /// Include every file in full
+    Include,
+    /// Stub unchanged files and diff changed ones
+    #[default]
+    Condense,
+}
+
+/// Files shown in a thread's prompts, updated with each prompt of a message
+#[derive(Debug, Clone, Default)]
+pub struct RepeatedFiles {
+    /// Latest version of each file shown so far
+    shown: ShownFiles,
+    /// Number of the message being answered (1-based)
+    message: usize,
+}
+
+impl RepeatedFiles {
+    /// Track the files of `shown` while answering message number `message`
+    #[must_use]
+    pub const fn new(shown: ShownFiles, message: usize) -> Self {
+        Self { shown, message }
+    }
+
+    /// Replace files shown before with a stub or a diff, and remember the rest
+    ///
+    /// Returns the number of bytes of file content saved.
+    pub fn condense(&mut self, files: &mut [FileContext]) -> usize {
+        let mut saved = 0;
+        for file in files.iter_mut().filter(|file| !file.pinned) {
+            let hash = content_hash(&file.content);
+            let shown = self.shown.get(&file.path);
+            let condensed = shown.and_then(|shown| condensed_content(file, shown, hash));
+            if shown.is_none_or(|shown| shown.hash != hash) {
+                self.record(file, hash);
+            }
+            if let Some(condensed) = condensed {
+                saved += file.content.len().saturating_sub(condensed.len());
+                file.content = condensed;
+            }
+        }
+        saved
+    }
+
+    /// Remember the files as shown in full, without condensing them
+    pub fn record_all(&mut self, files: &[FileContext]) {
+        for file in files.iter().filter(|file| !file.pinned) {
+            self.record(file, content_hash(&file.content));
+        }
+    }
+
+    /// Files shown so far, including those of this message
+    #[must_use]
+    pub fn into_shown(self) -> ShownFiles {
+        self.shown
+    }
+
+    /// Remember `file` as shown in this message
+    fn record(&mut self, file: &FileContext, hash: u64) {
+        self.shown.insert(
+            file.path.clone(),
+            ShownFile {
+                hash,
+                content: file.content.clone(),
+                message: self.message,
+            },
+        );
+    }
+}
+
+/// Stub for `file` if it is unchanged since `shown`, or its diff if smaller than the file
+fn condensed_content(file: &FileContext, shown: &ShownFile, hash: u64) -> Option<String> {
+    if shown.hash == hash {
+        return Some(format!(
+            "[unchanged since message #{}, request if needed]",
+            shown.message
+        ));
+    }
+    // A different excerpt of the file is new content rather than a change
+    if excerpt_range(&shown.content) != excerpt_range(&file.content) {
+        return None;
+    }
+    let label = file.path.display().to_string();
+    let diff = DiffSummary::between(
+        &label,
+        &label,
+        &shown.content,
+        &file.content,
+        DIFF_CONTEXT_LINES,
+    )
+    .diff;
+    let condensed = format!(
+        "[changed since message #{}, diff against that version]\n{diff}",
+        shown.message
+    );
+    (condensed.len() < file.content.len()).then_some(condensed)
+}
+
+/// Header line naming the lines of an excerpt, `None` for whole files
+fn excerpt_range(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .next()
+        .filter(|line| line.starts_with(EXCERPT_PREFIX))
+}
+
+/// Hash telling file versions apart
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::Context;
+    use std::path::PathBuf;
+
+    /// Source of a file long enough that a one-line change diffs smaller than it
+    fn long_source(answer: u32) -> String {
+        (0..40)
+            .map(|line| format!("pub fn helper_{line}() -> u32 {{ {line} }}\n"))
+            .chain([format!("pub fn answer() -> u32 {{ {answer} }}\n")])
+            .collect::<Vec<_>>()
+            .concat()
+    }
+
+    /// Context with `lib.rs` holding `source` and a pinned spec
+    fn context_with(source: String) -> Context {
+        Context::new("system").with_files(vec![
+            FileContext::new(PathBuf::from("spec.md"), "Spec".repeat(50)).into_pinned(),
+            FileContext::new(PathBuf::from("lib.rs"), source),
+        ])
+    }
+
+    /// Tests that repeating a query stubs the unchanged file and lowers the token estimate.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_unchanged_file_is_stubbed() {
+        let mut first = context_with(long_source(1));
+        let mut repeated = RepeatedFiles::new(ShownFiles::new(), 2);
+        assert_eq!(repeated.condense(&mut first.files), 0);
+        let full_estimate = first.token_estimate();
+
+        let mut second = context_with(long_source(1));
+        let mut next = RepeatedFiles::new(repeated.into_shown(), 4);
+        assert!(next.condense(&mut second.files) > 0);
+
+        assert!(second.token_estimate() < full_estimate);
+        assert_eq!(
+            second.files.get(1).map(|file| file.content.as_str()),
+            Some("[unchanged since message #2, request if needed]")
+        );
+        assert_eq!(
+            second.files.first().map(|file| file.content.len()),
+            Some(200),
+            "pinned files stay in full"
+        );
+    }
+
+    /// Tests that a changed file is replaced by its diff when the diff is smaller.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_changed_file_is_diffed() {
+        let mut repeated = RepeatedFiles::new(ShownFiles::new(), 1);
+        repeated.condense(&mut context_with(long_source(1)).files);
+
+        let mut changed = context_with(long_source(2));
+        let mut next = RepeatedFiles::new(repeated.into_shown(), 3);
+        next.condense(&mut changed.files);
+        let content = changed
+            .files
+            .get(1)
+            .map(|file| file.content.clone())
+            .unwrap_or_default();
+        assert!(
+            content.starts_with("[changed since message #1, diff against that version]"),
+            "{content}"
+        );
+        assert!(
+            content.contains("-pub fn answer() -> u32 { 1 }"),
+            "{content}"
+        );
+        assert!(
+            content.contains("+pub fn answer() -> u32 { 2 }"),
+            "{content}"
+        );
+
+        // The changed version is the one later messages are compared with
+        let mut again = context_with(long_source(2));
+        RepeatedFiles::new(next.into_shown(), 5).condense(&mut again.files);
+        assert_eq!(
+            again.files.get(1).map(|file| file.content.as_str()),
+            Some("[unchanged since message #3, request if needed]")
+        );
+    }
+
+    /// Tests that a small file that changed entirely is included in full.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_rewritten_small_file_is_included_in_full() {
+        let path = PathBuf::from("main.rs");
+        let mut repeated = RepeatedFiles::new(ShownFiles::new(), 1);
+        repeated.condense(&mut [FileContext::new(path.clone(), "fn main() {}".to_owned())]);
+
+        let mut files = [FileContext::new(path, "fn start() {}".to_owned())];
+        RepeatedFiles::new(repeated.into_shown(), 2).condense(&mut files);
+        assert_eq!(
+            files.first().map(|file| file.content.as_str()),
+            Some("fn start() {}")
+        );
+    }
+
+    /// Tests that another excerpt of a file shown before is included in full.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_other_excerpt_is_included_in_full() {
+        let path = PathBuf::from("lib.rs");
+        let excerpt = |range: &str| format!("--- Context: lines {range} ---\n{}", long_source(1));
+        let mut repeated = RepeatedFiles::new(ShownFiles::new(), 1);
+        repeated.condense(&mut [FileContext::new(path.clone(), excerpt("1-41"))]);
+
+        let mut files = [FileContext::new(path, excerpt("60-100"))];
+        RepeatedFiles::new(repeated.into_shown(), 2).condense(&mut files);
+        assert_eq!(
+            files.first().map(|file| file.content.clone()),
+            Some(excerpt("60-100"))
+        );
+    }
+}
@@ -15,3 +15,18 @@ mod chunking_validation;
 
 #[path = "modules/embedding_cache.rs"]
 mod embedding_cache;
+
+#[path = "modules/language_backend.rs"]
+mod language_backend;
+
+#[path = "modules/query_references.rs"]
+mod query_references;
+
+#[path = "modules/context_explanation.rs"]
+mod context_explanation;
+
+#[path = "modules/keyword_search.rs"]
+mod keyword_search;
+
+#[path = "modules/source_filtering.rs"]
+mod source_filtering;
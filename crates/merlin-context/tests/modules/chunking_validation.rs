@@ -3,7 +3,7 @@
 #[cfg(test)]
 mod tests {
     use merlin_context::embedding::chunking::{
-        FileChunk, MAX_CHUNK_TOKENS, MIN_CHUNK_TOKENS, chunk_file, estimate_tokens,
+        FileChunk, MAX_CHUNK_TOKENS, MIN_CHUNK_TOKENS, chunk_file, chunk_header, estimate_tokens,
     };
     use std::env::current_dir;
     use std::fs;
@@ -247,4 +247,237 @@ mod tests {
             ));
         }
     }
+
+    /// Builds a Python module with imports, a small helper, a large class and a function
+    fn python_source() -> String {
+        let body = "        total = total + value * 2  # accumulate the running total\n".repeat(12);
+        let methods = (0..10)
+            .map(|index| {
+                format!(
+                    "    def method_{index}(self, value):\n        total = 0\n{body}        return total\n\n"
+                )
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        format!(
+            "import os\nfrom .models import User\n\n\ndef helper():\n    return 1\n\n\n\
+             class Store:\n    \"\"\"Stores values.\"\"\"\n\n{methods}\n\
+             def summarize(values):\n    total = 0\n{body}    return total\n"
+        )
+    }
+
+    /// Tests that Python files are chunked on definition boundaries.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_python_chunk_boundaries() {
+        let source = python_source();
+        let chunks = chunk_file(Path::new("app/store.py"), &source);
+        let identifiers: Vec<&str> = chunks
+            .iter()
+            .map(|chunk| chunk.identifier.as_str())
+            .collect();
+
+        assert_eq!(identifiers.first(), Some(&"def Store.method_0"));
+        assert!(identifiers.contains(&"def Store.method_5"));
+        assert_eq!(identifiers.last(), Some(&"def summarize"));
+
+        for chunk in &chunks {
+            let tokens = estimate_tokens(&chunk.content);
+            assert!(
+                (MIN_CHUNK_TOKENS..=MAX_CHUNK_TOKENS).contains(&tokens),
+                "{} has {tokens} tokens",
+                chunk.identifier
+            );
+        }
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end_line + 1, pair[1].start_line);
+        }
+        assert_eq!(chunks.first().map(|chunk| chunk.start_line), Some(1));
+        assert_eq!(
+            chunks.last().map(|chunk| chunk.end_line),
+            Some(source.lines().count())
+        );
+    }
+
+    /// Builds a TypeScript module with a large documented class and a function
+    fn typescript_source() -> String {
+        let body = "    total = total + value * 2; // accumulate the running total\n".repeat(12);
+        let methods = (0..10)
+            .map(|index| {
+                format!(
+                    "  /**\n   * Handles step {index} of the order pipeline.\n   */\n  \
+                     step{index}(value: number): number {{\n    let total = 0;\n{body}    \
+                     return total;\n  }}\n\n"
+                )
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        format!(
+            "import {{ Order }} from './order';\n\n/** Runs orders through the pipeline. */\n\
+             export class OrderPipeline {{\n  private readonly name = 'orders';\n\n{methods}}}\n\n\
+             /** Sums order values. */\nexport function summarize(values: number[]): number {{\n  \
+             let total = 0;\n{body}  return total;\n}}\n"
+        )
+    }
+
+    /// Tests that TypeScript classes are split at methods, keeping their `JSDoc`.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_typescript_chunk_boundaries() {
+        let source = typescript_source();
+        let chunks = chunk_file(Path::new("src/pipeline.ts"), &source);
+        let identifiers: Vec<&str> = chunks
+            .iter()
+            .map(|chunk| chunk.identifier.as_str())
+            .collect();
+
+        assert_eq!(identifiers.first(), Some(&"OrderPipeline.step0"));
+        assert_eq!(identifiers.last(), Some(&"function summarize"));
+        let step_five = chunks
+            .iter()
+            .find(|chunk| chunk.identifier == "OrderPipeline.step5");
+        assert!(step_five.is_some_and(|chunk| {
+            chunk.content.contains("Handles step 5 of") && chunk.content.contains("step5(value")
+        }));
+
+        for chunk in &chunks {
+            let tokens = estimate_tokens(&chunk.content);
+            assert!(
+                (MIN_CHUNK_TOKENS..=MAX_CHUNK_TOKENS).contains(&tokens),
+                "{} has {tokens} tokens",
+                chunk.identifier
+            );
+            // Chunks end on a closing brace, never inside a method body
+            let last_line = chunk
+                .content
+                .lines()
+                .rev()
+                .find(|line| !line.trim().is_empty());
+            assert!(
+                last_line.is_some_and(|line| line.trim() == "}"),
+                "{} ends mid-definition",
+                chunk.identifier
+            );
+        }
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].end_line + 1, pair[1].start_line);
+        }
+    }
+
+    /// Tests that Go functions are chunked whole, together with their doc comments.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_go_chunk_boundaries() {
+        let body = "\ttotal += value * 2 // accumulate the running total\n\n".repeat(10);
+        let functions = (0..6)
+            .map(|index| {
+                format!(
+                    "// Handle{index} processes batch {index} of the queue.\n\
+                     func Handle{index}(value int) int {{\n\ttotal := 0\n{body}\treturn total\n}}\n\n"
+                )
+            })
+            .collect::<Vec<_>>()
+            .concat();
+        let source = format!("package queue\n\nimport \"fmt\"\n\n{functions}");
+        let chunks = chunk_file(Path::new("queue/handlers.go"), &source);
+
+        let identifiers: Vec<&str> = chunks
+            .iter()
+            .map(|chunk| chunk.identifier.as_str())
+            .collect();
+        assert_eq!(
+            identifiers,
+            [
+                "func Handle0",
+                "func Handle1",
+                "func Handle2",
+                "func Handle3",
+                "func Handle4",
+                "func Handle5"
+            ]
+        );
+        for (index, chunk) in chunks.iter().enumerate() {
+            assert!(
+                chunk
+                    .content
+                    .contains(&format!("// Handle{index} processes"))
+            );
+            assert!(chunk.content.trim_end().ends_with('}'));
+        }
+    }
+
+    /// Builds the header of the chunk spanning `start_line..=end_line` of `content`
+    fn header_of(content: &str, start_line: usize, end_line: usize) -> Option<String> {
+        let lines: Vec<&str> = content.lines().collect();
+        let chunk = FileChunk::new(
+            "file".to_owned(),
+            lines[start_line - 1..end_line].join("\n"),
+            "item".to_owned(),
+            start_line,
+            end_line,
+        );
+        chunk_header(&lines, &chunk)
+    }
+
+    /// Tests that Rust methods are named by their impl, signature and doc comment.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_rust_method_header() {
+        let content = "impl Config {\n    /// Loads the configuration from disk.\n    ///\n    /// Missing files are created.\n    #[must_use]\n    pub fn load(\n        path: &Path,\n    ) -> Result<Self> {\n        read(path)\n    }\n}";
+
+        assert_eq!(
+            header_of(content, 2, 10).as_deref(),
+            Some(
+                "@@ impl Config > pub fn load(path: &Path) -> Result<Self> @@ Loads the configuration from disk."
+            )
+        );
+    }
+
+    /// Tests that chunks starting inside a definition are named after it.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_split_chunk_header_names_enclosing_item() {
+        let content = "class StockItem:\n    def reserve(self, amount):\n        \"\"\"Reserves units for an order.\"\"\"\n        self.reserved += amount\n        return self.reserved";
+
+        assert_eq!(
+            header_of(content, 4, 5).as_deref(),
+            Some("@@ class StockItem > def reserve(self, amount) @@ Reserves units for an order.")
+        );
+    }
+
+    /// Tests that only code chunks containing or inside a definition get headers.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_chunk_headers_only_for_code() {
+        assert_eq!(
+            header_of("let total = 1;\nprintln!(\"{total}\");", 1, 2),
+            None
+        );
+
+        let code = chunk_file(
+            Path::new("src/lib.rs"),
+            "/// Adds one.\npub fn add_one(value: u32) -> u32 {\n    value + 1\n}\n",
+        );
+        assert!(!code.is_empty());
+        assert!(code.iter().all(|chunk| chunk.header.as_deref()
+            == Some("@@ pub fn add_one(value: u32) -> u32 @@ Adds one.")));
+
+        let docs = chunk_file(
+            Path::new("README.md"),
+            "# Title\n\nfn mentioned_in_prose() {}\n",
+        );
+        assert!(docs.iter().all(|chunk| chunk.header.is_none()));
+    }
 }
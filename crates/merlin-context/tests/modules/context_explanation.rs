@@ -0,0 +1,51 @@
+//! Tests for explaining why files were included in a context.
+
+#[cfg(test)]
+mod tests {
+    use merlin_context::{ContextBuilder, InclusionSource};
+    use merlin_core::{Query, Result};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use tempfile::TempDir;
+
+    /// Ensures requested and pinned files are explained by how the user asked for them.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written or the context cannot be built.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_explain_requested_and_pinned_files() -> Result<()> {
+        let dir = TempDir::new()?;
+        let lib = dir.path().join("lib.rs");
+        fs::write(&lib, "pub fn run() {}\n")?;
+        fs::write(dir.path().join("notes.md"), "Keep run fast\n")?;
+
+        let mut builder = ContextBuilder::new(dir.path().to_path_buf());
+        let query = Query::new("Speed up run")
+            .with_files(vec![lib.clone()])
+            .with_pinned_files(vec![PathBuf::from("notes.md")]);
+        let context = builder.build_context(&query).await?;
+        let explanation = builder.explain_context(&context);
+
+        assert_eq!(explanation.files.len(), 2);
+        let notes = explanation.file(Path::new("notes.md"));
+        assert_eq!(
+            notes.and_then(|file| file.source),
+            Some(InclusionSource::Pinned)
+        );
+        let requested = explanation.file(&lib);
+        assert!(
+            requested.is_some_and(|file| file.source == Some(InclusionSource::Requested)
+                && file.requested()
+                && !file.import_expansion()
+                && file.combined_score.is_none())
+        );
+
+        let markdown = explanation.to_markdown();
+        assert!(markdown.contains("| `notes.md` | pinned |"));
+        assert!(markdown.contains("| requested | - | - | - | no | yes | - |"));
+        Ok(())
+    }
+}
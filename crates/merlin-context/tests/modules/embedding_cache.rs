@@ -4,13 +4,20 @@
 //! They use minimal test files (2 tiny files) to reduce I/O time.
 //! Embeddings are deterministic (content hash-based) using `FakeEmbeddingClient`.
 
+use bincode::config::standard as bincode_config;
+use bincode::decode_from_slice;
+use merlin_context::embedding::vector_search::{Bm25Cache, VectorCache};
 use merlin_context::{EmbeddingProvider, VectorSearchManager};
-use merlin_core::CoreResult as Result;
+use merlin_core::{CoreResult as Result, Error};
 use std::collections::hash_map::DefaultHasher;
 use std::env;
+use std::fmt::{self, Write as _};
 use std::fs;
 use std::hash::{Hash as _, Hasher as _};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use tempfile::TempDir;
 
 /// Fake embedding client for testing (deterministic, hash-based)
@@ -49,6 +56,30 @@ impl FakeEmbeddingClient {
     }
 }
 
+/// Fake embedding client whose first request fails
+#[derive(Clone, Default)]
+struct FlakyEmbeddingClient {
+    /// Requests received so far
+    requests: Arc<AtomicUsize>,
+}
+
+impl EmbeddingProvider for FlakyEmbeddingClient {
+    async fn ensure_model_available(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        FakeEmbeddingClient.embed(text).await
+    }
+
+    async fn embed_batch(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if self.requests.fetch_add(1, Ordering::SeqCst) == 0 {
+            return Err(Error::Other("connection reset".to_owned()));
+        }
+        FakeEmbeddingClient.embed_batch(texts).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,4 +250,166 @@ mod tests {
 
         Ok(())
     }
+
+    /// Reads and decodes the persisted BM25 index of a project
+    ///
+    /// # Errors
+    /// Returns an error if the file is missing or cannot be decoded.
+    fn read_bm25_cache(project_root: &Path) -> Result<Bm25Cache> {
+        let folder =
+            env::var("MERLIN_FOLDER").map_or_else(|_| project_root.join(".merlin"), PathBuf::from);
+        let path = folder
+            .join("cache")
+            .join("vector")
+            .join("embeddings.bin.bm25.bin");
+        let data = fs::read(path).map_err(CoreError::Io)?;
+        decode_from_slice(&data, bincode_config())
+            .map(|(cache, _)| cache)
+            .map_err(|error| CoreError::Other(error.to_string()))
+    }
+
+    /// Tests that the BM25 index is persisted and invalidated when files change.
+    ///
+    /// # Errors
+    /// Returns an error if file operations or cache operations fail.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_bm25_index_persistence() -> Result<()> {
+        let temp_dir = create_minimal_project()?;
+        let project_root = temp_dir.path().to_path_buf();
+
+        let mut manager = VectorSearchManager::with_provider(&project_root, FakeEmbeddingClient);
+        manager.initialize().await?;
+        let saved = read_bm25_cache(&project_root)?;
+        assert_eq!(saved.version, Bm25Cache::VERSION);
+        assert_eq!(saved.index.len(), manager.len());
+
+        // Unchanged files - the persisted index is reused and saved again as is
+        let mut reloaded = VectorSearchManager::with_provider(&project_root, FakeEmbeddingClient);
+        reloaded.initialize().await?;
+        assert_eq!(reloaded.len(), manager.len());
+        assert_eq!(read_bm25_cache(&project_root)?.index_hash, saved.index_hash);
+
+        // Modified file - the index is rebuilt under a new hash
+        fs::write(project_root.join("src").join("lib.rs"), "pub fn b() { }")
+            .map_err(CoreError::Io)?;
+        let mut rebuilt = VectorSearchManager::with_provider(&project_root, FakeEmbeddingClient);
+        rebuilt.initialize().await?;
+        assert_eq!(rebuilt.len(), manager.len());
+        assert_ne!(read_bm25_cache(&project_root)?.index_hash, saved.index_hash);
+
+        Ok(())
+    }
+
+    /// Writes `count` functions, each large enough to be chunked on its own
+    ///
+    /// # Errors
+    /// Returns an error if formatting fails.
+    fn large_rust_source(count: usize) -> StdResult<String, fmt::Error> {
+        let mut content = String::new();
+        for index in 0..count {
+            writeln!(content, "pub fn function{index}() -> usize {{")?;
+            for line in 0..25 {
+                writeln!(content, "    let value{line} = {index} + {line};")?;
+            }
+            writeln!(content, "    value0\n}}")?;
+        }
+        Ok(content)
+    }
+
+    /// Tests that a file read in sections is embedded with whole-file lines and hash.
+    ///
+    /// # Errors
+    /// Returns an error if file operations or embedding fail.
+    ///
+    /// # Panics
+    /// Panics if the cached chunks do not cover the whole file.
+    #[tokio::test]
+    async fn test_large_files_embed_in_sections() -> Result<()> {
+        let temp_dir = create_minimal_project()?;
+        let project_root = temp_dir.path().to_path_buf();
+        let content =
+            large_rust_source(200).map_err(|error| CoreError::Other(error.to_string()))?;
+        let line_count = content.lines().count();
+        assert!(content.len() > 128 * 1024);
+        fs::write(project_root.join("src").join("large.rs"), &content).map_err(CoreError::Io)?;
+
+        // Read in sections of about 16 KB
+        let mut manager = VectorSearchManager::with_provider(&project_root, FakeEmbeddingClient)
+            .with_streaming_threshold(16 * 1024);
+        manager.initialize().await?;
+
+        let folder =
+            env::var("MERLIN_FOLDER").map_or_else(|_| project_root.join(".merlin"), PathBuf::from);
+        let data = fs::read(folder.join("cache").join("vector").join("embeddings.bin"))
+            .map_err(CoreError::Io)?;
+        let cache: VectorCache = decode_from_slice(&data, bincode_config())
+            .map(|(cache, _)| cache)
+            .map_err(|error| CoreError::Other(error.to_string()))?;
+        let large: Vec<_> = cache
+            .embeddings
+            .iter()
+            .filter(|entry| entry.path == Path::new("src").join("large.rs"))
+            .collect();
+
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        let content_hash = hasher.finish();
+        assert!(large.len() > 1);
+        assert!(large.iter().all(|entry| entry.content_hash == content_hash));
+        assert_eq!(
+            large.iter().map(|entry| entry.end_line).max(),
+            Some(line_count)
+        );
+        Ok(())
+    }
+
+    /// Tests that chunks of a failed request are retried and progress ends complete.
+    ///
+    /// # Errors
+    /// Returns an error if file operations or embedding fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_concurrent_embedding_retries_failed_chunks() -> Result<()> {
+        let temp_dir = TempDir::new().map_err(CoreError::Io)?;
+        let src_dir = temp_dir.path().join("src");
+        fs::create_dir_all(&src_dir).map_err(CoreError::Io)?;
+        for index in 0..12 {
+            fs::write(
+                src_dir.join(format!("module{index}.rs")),
+                format!("pub fn function{index}() {{ }}"),
+            )
+            .map_err(CoreError::Io)?;
+        }
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&reports);
+
+        let mut manager =
+            VectorSearchManager::with_provider(temp_dir.path(), FlakyEmbeddingClient::default())
+                .with_concurrency(3)
+                .with_progress_callback(Arc::new(move |stage, current, total| {
+                    if stage == "Embedding chunks"
+                        && let Ok(mut received) = recorded.lock()
+                    {
+                        received.push((current, total));
+                    }
+                }));
+        manager.initialize().await?;
+
+        assert_eq!(manager.len(), 12);
+        let reports = reports
+            .lock()
+            .map_err(|error| CoreError::Other(error.to_string()))?;
+        assert!(
+            reports
+                .iter()
+                .all(|(current, total)| Some(*current) <= *total)
+        );
+        assert_eq!(reports.last(), Some(&(12, Some(12))));
+        Ok(())
+    }
 }
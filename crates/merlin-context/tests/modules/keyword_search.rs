@@ -0,0 +1,112 @@
+//! Tests for context retrieval with embeddings disabled.
+
+#[cfg(test)]
+mod tests {
+    use merlin_context::{ContextBuilder, VectorSearchManager};
+    use merlin_core::{Query, Result};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Writes a small project where only `billing.rs` mentions invoices.
+    ///
+    /// # Errors
+    /// Returns an error if a file cannot be written.
+    fn write_project(root: &Path) -> Result<()> {
+        fs::write(
+            root.join("billing.rs"),
+            "/// Totals an invoice\n\
+             pub fn invoice_total(invoice: &Invoice) -> u64 {\n\
+             \x20   invoice.lines.iter().map(|line| line.amount).sum()\n\
+             }\n\n\
+             /// Invoice with its billed lines\n\
+             pub struct Invoice {\n\
+             \x20   pub lines: Vec<InvoiceLine>,\n\
+             }\n",
+        )?;
+        fs::write(
+            root.join("parser.rs"),
+            "/// Parses tokens\npub fn parse(tokens: &[String]) -> usize {\n    tokens.len()\n}\n",
+        )?;
+        fs::write(
+            root.join("render.rs"),
+            "/// Renders a frame\npub fn render(width: u16) -> String {\n    \" \".repeat(usize::from(width))\n}\n",
+        )?;
+        Ok(())
+    }
+
+    /// Ensures a keyword-only index ranks files by BM25 without embedding anything.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written or searching fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_keyword_only_manager_searches_without_embeddings() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_project(dir.path())?;
+
+        let mut manager = VectorSearchManager::keyword_only(dir.path());
+        assert!(
+            manager.initialize().await.is_err(),
+            "the no-op provider must refuse to embed"
+        );
+
+        manager.index_keywords();
+        assert!(manager.len() >= 3, "every file must be indexed");
+        let results = manager.search("invoice total", 10).await?;
+
+        let top = results
+            .first()
+            .map(|result| result.file_path.display().to_string());
+        assert!(
+            top.as_deref()
+                .is_some_and(|path| path.starts_with("billing.rs")),
+            "unexpected results: {results:?}"
+        );
+        assert!(results.iter().all(|result| result.vector_score.is_none()));
+        assert!(
+            results
+                .iter()
+                .any(|result| result.preview.contains("invoice_total"))
+        );
+        Ok(())
+    }
+
+    /// Ensures a builder with embeddings disabled still retrieves context by keywords.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written or the context cannot be built.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_builder_without_embeddings_uses_keyword_search() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_project(dir.path())?;
+
+        let mut builder = ContextBuilder::new(dir.path().to_path_buf()).with_embeddings(false);
+        let context = builder
+            .build_context(&Query::new("Fix the invoice total"))
+            .await?;
+
+        assert!(
+            context
+                .files
+                .iter()
+                .any(|file| file.path.ends_with("billing.rs")),
+            "unexpected files: {:?}",
+            context
+                .files
+                .iter()
+                .map(|file| &file.path)
+                .collect::<Vec<_>>()
+        );
+        assert!(
+            !dir.path().join(".merlin/cache/vector").exists(),
+            "nothing may be embedded or cached"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+//! Tests for the language backend of the context builder: handing it a
+//! combined backend, and following changes made outside the agent.
+
+#[cfg(test)]
+mod tests {
+    use merlin_context::ContextBuilder;
+    use merlin_core::{CoreResult as Result, Error};
+    use merlin_languages::{SearchQuery, compose_backends, detect_languages};
+    use std::fs;
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use tokio::time::sleep;
+
+    /// Searches `builder` for `name` until it is found or about five seconds passed
+    ///
+    /// # Errors
+    /// Returns an error if a search fails.
+    async fn eventually_found(builder: &mut ContextBuilder, name: &str) -> Result<bool> {
+        let query = SearchQuery {
+            symbol_name: Some(name.to_owned()),
+            ..SearchQuery::default()
+        };
+        for _ in 0..100 {
+            if builder
+                .search_symbols(&query)
+                .await?
+                .iter()
+                .any(|symbol| symbol.name == name)
+            {
+                return Ok(true);
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        Ok(false)
+    }
+
+    /// Ensures a backend composed from the detected languages answers for both of them.
+    ///
+    /// # Errors
+    /// Returns an error if the workspace cannot be written or indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_with_composed_language_backend() -> Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+        fs::write(
+            root.join("models.py"),
+            "class Model:\n    pass\n\n\nclass User(Model):\n    pass\n",
+        )?;
+        fs::write(
+            root.join("models.ts"),
+            "export class Model {}\n\nexport class Order extends Model {}\n",
+        )?;
+
+        let backends = detect_languages(root)
+            .into_iter()
+            .map(|found| (found.language, found.language.create_backend(None)))
+            .collect();
+        let mut backend = compose_backends(backends)
+            .ok_or_else(|| Error::Other("no language detected".to_owned()))?;
+        backend.initialize(root)?;
+
+        let mut builder = ContextBuilder::new(root.to_path_buf()).with_language_backend(backend);
+        let mut implementations: Vec<String> = builder
+            .find_implementations("Model")
+            .await?
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        implementations.sort();
+        assert_eq!(implementations, ["Order", "User"]);
+        Ok(())
+    }
+
+    /// Ensures watch mode re-parses edited files and reloads when a manifest appears.
+    ///
+    /// # Errors
+    /// Returns an error if the workspace cannot be written or indexed.
+    ///
+    /// # Panics
+    /// Panics if a change made outside the builder is not picked up.
+    #[tokio::test]
+    async fn test_watch_mode_follows_outside_changes() -> Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path().canonicalize()?;
+        fs::write(root.join("app.py"), "def first():\n    pass\n")?;
+        let mut builder = ContextBuilder::new(root.clone()).with_watch_mode(true);
+        assert!(eventually_found(&mut builder, "first").await?);
+
+        fs::write(root.join("app.py"), "def second():\n    pass\n")?;
+        assert!(
+            eventually_found(&mut builder, "second").await?,
+            "edited source file should be re-parsed"
+        );
+
+        fs::write(root.join("main.go"), "package main\n\nfunc Serve() {}\n")?;
+        fs::write(root.join("go.mod"), "module example.com/app\n")?;
+        assert!(
+            eventually_found(&mut builder, "Serve").await?,
+            "new manifest should reload the backends with Go"
+        );
+
+        builder.reload();
+        for _ in 0..100 {
+            if !builder.reload_in_progress() {
+                break;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        assert!(!builder.reload_in_progress());
+        assert!(eventually_found(&mut builder, "second").await?);
+        Ok(())
+    }
+}
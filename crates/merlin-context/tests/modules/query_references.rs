@@ -0,0 +1,42 @@
+//! Tests for resolving `@` references in queries.
+
+#[cfg(test)]
+mod tests {
+    use merlin_context::ContextFetcher;
+    use merlin_core::{Query, Result};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Ensures a file referenced with `@` is pinned into the context and summarized.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written or the context cannot be built.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_file_reference_is_added_to_context() -> Result<()> {
+        let dir = TempDir::new()?;
+        fs::write(dir.path().join("main.rs"), "fn main() {}\n")?;
+        fs::write(dir.path().join("other.rs"), "fn other() {}\n")?;
+
+        let fetcher = ContextFetcher::new_with_embeddings(dir.path().to_path_buf(), false);
+        let context = fetcher
+            .build_context_for_query(&Query::new("Explain what @main.rs does"))
+            .await?;
+
+        let main = context
+            .files
+            .iter()
+            .find(|file| file.path == Path::new("main.rs"));
+        assert!(main.is_some_and(|file| file.pinned && file.content == "fn main() {}\n"));
+        assert!(
+            context
+                .files
+                .iter()
+                .all(|file| !file.path.ends_with("other.rs"))
+        );
+        Ok(())
+    }
+}
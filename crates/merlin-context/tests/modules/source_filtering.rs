@@ -0,0 +1,152 @@
+//! Tests for skipping binary, huge and minified files and summarizing lockfiles.
+
+#[cfg(test)]
+mod tests {
+    use merlin_context::VectorSearchManager;
+    use merlin_context::embedding::vector_search::{SkipReason, sniff_file};
+    use merlin_core::Result;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Size cap used by the tests, small enough for a crafted file to exceed it
+    const MAX_FILE_SIZE: u64 = 4096;
+
+    /// Writes a project with one file of each kind, all mentioning `zebrafish`.
+    ///
+    /// # Errors
+    /// Returns an error if a file cannot be written.
+    fn write_project(root: &Path) -> Result<()> {
+        fs::write(
+            root.join("fish.rs"),
+            "/// Feeds the zebrafish\npub fn feed_zebrafish() {}\n",
+        )?;
+        // A PNG committed under a source extension, still valid UTF-8
+        fs::write(
+            root.join("logo.ts"),
+            "PNG\r\n\u{1a}\n\0\0\0\rIHDR zebrafish\0\0",
+        )?;
+        fs::write(root.join("bundle.js"), "var zebrafish=1;".repeat(150))?;
+        fs::write(root.join("notes.txt"), "zebrafish tank notes\n".repeat(200))?;
+        fs::write(
+            root.join("Cargo.lock"),
+            "version = 4\n\n\
+             [[package]]\nname = \"aquarium\"\nversion = \"0.1.0\"\n\
+             dependencies = [\n \"serde\",\n \"zebrafish-core 0.2.0\",\n]\n\n\
+             [[package]]\nname = \"serde\"\nversion = \"1.0.0\"\n\
+             source = \"registry+https://github.com/rust-lang/crates.io-index\"\n\
+             checksum = \"0123456789abcdef\"\n\n\
+             [[package]]\nname = \"zebrafish-core\"\nversion = \"0.2.0\"\n\
+             source = \"registry+https://github.com/rust-lang/crates.io-index\"\n\
+             checksum = \"fedcba9876543210\"\n",
+        )?;
+        fs::write(
+            root.join("package-lock.json"),
+            r#"{
+  "name": "aquarium-web",
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "dependencies": { "lodash": "^4.17.21" }, "devDependencies": { "jest": "^29.0.0" } },
+    "node_modules/lodash": { "version": "4.17.21", "integrity": "sha512-abc" },
+    "node_modules/jest": { "version": "29.7.0", "integrity": "sha512-def" },
+    "node_modules/jest-cli": { "version": "29.7.0", "integrity": "sha512-ghi" }
+  }
+}"#,
+        )?;
+        Ok(())
+    }
+
+    /// Ensures sniffing names the reason each crafted file is skipped.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_sniff_file_reasons() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_project(dir.path())?;
+        let sniff = |name: &str| sniff_file(&dir.path().join(name), MAX_FILE_SIZE);
+
+        assert_eq!(sniff("fish.rs"), Ok(()));
+        assert_eq!(sniff("logo.ts"), Err(SkipReason::Binary));
+        assert_eq!(sniff("bundle.js"), Err(SkipReason::Minified));
+        assert_eq!(sniff("notes.txt"), Err(SkipReason::TooLarge));
+        assert_eq!(sniff("missing.rs"), Err(SkipReason::Unreadable));
+        Ok(())
+    }
+
+    /// Ensures skipped files are not indexed and lockfiles are indexed as summaries.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written or searching fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_index_skips_unindexable_files_and_summarizes_lockfiles() -> Result<()> {
+        let dir = TempDir::new()?;
+        write_project(dir.path())?;
+
+        let mut manager =
+            VectorSearchManager::keyword_only(dir.path()).with_max_file_size(MAX_FILE_SIZE);
+        manager.index_keywords();
+        assert_eq!(
+            manager.len(),
+            3,
+            "only fish.rs and the two lockfiles are indexed"
+        );
+
+        let results = manager.search("zebrafish", 10).await?;
+        let paths: Vec<String> = results
+            .iter()
+            .map(|result| result.file_path.display().to_string())
+            .collect();
+        assert!(
+            paths.iter().any(|path| path.starts_with("fish.rs")),
+            "unexpected results: {paths:?}"
+        );
+        for skipped in ["logo.ts", "bundle.js", "notes.txt"] {
+            assert!(
+                !paths.iter().any(|path| path.starts_with(skipped)),
+                "{skipped} must not be indexed: {paths:?}"
+            );
+        }
+
+        let cargo = manager.search("serde", 10).await?;
+        let cargo_preview = cargo
+            .iter()
+            .find(|result| {
+                result
+                    .file_path
+                    .to_string_lossy()
+                    .starts_with("Cargo.lock:1-")
+            })
+            .map(|result| result.preview.as_str());
+        assert_eq!(
+            cargo_preview,
+            Some(
+                "Cargo.lock: lockfile with 3 locked packages\nTop-level dependencies: serde, zebrafish-core"
+            )
+        );
+
+        let npm = manager.search("lodash", 10).await?;
+        let npm_preview = npm
+            .iter()
+            .find(|result| {
+                result
+                    .file_path
+                    .to_string_lossy()
+                    .starts_with("package-lock.json:1-")
+            })
+            .map(|result| result.preview.as_str());
+        assert_eq!(
+            npm_preview,
+            Some(
+                "package-lock.json: lockfile with 3 locked packages\nTop-level dependencies: jest, lodash"
+            )
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! Clock abstraction for time-dependent code.
+//!
+//! Code that measures elapsed time, waits, or times out reads the time from a
+//! [`Clock`] instead of calling `Instant::now` directly. The application uses
+//! [`SystemClock`]; tests use a [`VirtualClock`], whose sleeps advance its time
+//! instantly, so timing-dependent behavior and output are deterministic and
+//! waits cost no wall-clock time.
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::task::yield_now;
+use tokio::time::sleep;
+
+use crate::sync::IgnoreLock as _;
+
+/// Clock shared by the components of an application
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Source of the current time and of delays
+#[async_trait]
+pub trait Clock: Send + Sync + Debug {
+    /// Current instant
+    fn now(&self) -> Instant;
+
+    /// Waits until `duration` has passed on this clock
+    async fn sleep(&self, duration: Duration);
+
+    /// Time passed on this clock since `earlier` (zero if `earlier` is in the future)
+    fn elapsed_since(&self, earlier: Instant) -> Duration {
+        self.now().saturating_duration_since(earlier)
+    }
+}
+
+/// Clock reading the system's monotonic time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// System clock as a [`SharedClock`]
+    #[must_use]
+    pub fn shared() -> SharedClock {
+        Arc::new(Self)
+    }
+}
+
+#[async_trait]
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        sleep(duration).await;
+    }
+}
+
+/// Clock whose time only moves when it is advanced
+///
+/// Sleeping advances the clock by the requested duration and returns at once.
+/// Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct VirtualClock {
+    /// Instant the clock started at
+    origin: Instant,
+    /// Time the clock has been advanced by
+    advanced: Arc<Mutex<Duration>>,
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VirtualClock {
+    /// Creates a clock starting at the current instant
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            advanced: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Moves the clock forward by `duration`
+    pub fn advance(&self, duration: Duration) {
+        let mut advanced = self.advanced.lock_ignore_poison();
+        *advanced = advanced.saturating_add(duration);
+    }
+
+    /// Total time the clock has been advanced by
+    #[must_use]
+    pub fn advanced(&self) -> Duration {
+        *self.advanced.lock_ignore_poison()
+    }
+
+    /// This clock as a [`SharedClock`]
+    #[must_use]
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait]
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        self.origin + self.advanced()
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+        // Still let other tasks run, as a real sleep would
+        yield_now().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that virtual sleeps advance the shared time without waiting.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_virtual_clock_sleep_advances_instantly() {
+        let clock = VirtualClock::new();
+        let shared = clock.shared();
+        let started = shared.now();
+        let wall_start = Instant::now();
+
+        shared.sleep(Duration::from_hours(1)).await;
+        clock.advance(Duration::from_secs(42));
+
+        assert_eq!(
+            shared.elapsed_since(started),
+            Duration::from_secs(3600 + 42)
+        );
+        assert_eq!(clock.advanced(), Duration::from_secs(3600 + 42));
+        assert!(wall_start.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            shared.elapsed_since(started + Duration::from_hours(2)),
+            Duration::ZERO
+        );
+    }
+}
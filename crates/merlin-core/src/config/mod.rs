@@ -1,13 +1,27 @@
 //! Configuration types for routing, validation, execution, and workspace settings.
 
+#[cfg(test)]
+mod tests;
+mod validation;
+
+pub use validation::{
+    FormatterConfig, ProjectConfig, ValidationCheckType, ValidationChecks, ValidationConfig,
+};
+
 use crate::routing_error::{Result, RoutingError};
 use serde::{Deserialize, Serialize};
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Estimated tokens of conversation history above which the oldest turns are summarized
+pub const DEFAULT_CONVERSATION_COMPRESSION_THRESHOLD: usize = 10_000;
+
+/// Longest delay between retries of a transient provider error, in seconds
+pub const DEFAULT_MAX_RETRY_DELAY_SECS: u64 = 30;
+
 /// Complete routing configuration (global, stored in `~/.merlin/config.toml`).
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoutingConfig {
     /// Model tier configuration
     #[serde(default)]
@@ -15,6 +29,85 @@ pub struct RoutingConfig {
     /// API keys for model providers
     #[serde(default)]
     pub api_keys: ApiKeys,
+    /// Git operations available to agents
+    #[serde(default)]
+    pub git: GitConfig,
+    /// Estimated tokens of conversation history above which the oldest turns are
+    /// summarized by the cheapest model (`None` keeps the full history)
+    #[serde(default = "default_compression_threshold")]
+    pub conversation_compression_threshold: Option<usize>,
+    /// Model catalog refresh settings
+    #[serde(default)]
+    pub catalog: CatalogConfig,
+    /// Longest delay in seconds between retries of a transient provider error,
+    /// which otherwise doubles from one second after each attempt
+    #[serde(default = "default_max_retry_delay_secs")]
+    pub max_retry_delay_secs: u64,
+    /// Validation of task results
+    #[serde(default)]
+    pub validation: ValidationConfig,
+}
+
+/// Retry delays are capped at the default unless configured
+#[must_use]
+pub const fn default_max_retry_delay_secs() -> u64 {
+    DEFAULT_MAX_RETRY_DELAY_SECS
+}
+
+/// Conversation history is compressed past the default threshold unless configured
+#[must_use]
+pub const fn default_compression_threshold() -> Option<usize> {
+    Some(DEFAULT_CONVERSATION_COMPRESSION_THRESHOLD)
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            tiers: TierConfig::default(),
+            api_keys: ApiKeys::default(),
+            git: GitConfig::default(),
+            conversation_compression_threshold: default_compression_threshold(),
+            catalog: CatalogConfig::default(),
+            max_retry_delay_secs: default_max_retry_delay_secs(),
+            validation: ValidationConfig::default(),
+        }
+    }
+}
+
+/// Model catalog settings (prices, context windows and features of the available models).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogConfig {
+    /// Hours the cached catalog in `~/.merlin/models.json` is used before it is refreshed
+    #[serde(default = "default_catalog_ttl_hours")]
+    pub ttl_hours: u64,
+    /// Only use the cached catalog, never refreshing it (for offline use)
+    #[serde(default)]
+    pub offline: bool,
+}
+
+/// The cached catalog is refreshed once a day unless configured
+const fn default_catalog_ttl_hours() -> u64 {
+    24
+}
+
+impl Default for CatalogConfig {
+    fn default() -> Self {
+        Self {
+            ttl_hours: default_catalog_ttl_hours(),
+            offline: false,
+        }
+    }
+}
+
+/// Git integration settings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct GitConfig {
+    /// Allow agents to push, hard reset and clean
+    #[serde(default)]
+    pub allow_destructive: bool,
+    /// Commit the changes of each completed task on a `merlin/<task-id>` branch
+    #[serde(default)]
+    pub auto_commit: bool,
 }
 
 /// API keys for model providers.
@@ -98,130 +191,6 @@ impl TierConfig {
     }
 }
 
-/// Types of validation checks that can be performed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum ValidationCheckType {
-    /// Syntax validation
-    Syntax,
-    /// Build validation
-    Build,
-    /// Test validation
-    Test,
-    /// Lint validation
-    Lint,
-}
-
-/// Validation checks to perform.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ValidationChecks {
-    /// Set of checks to perform
-    pub enabled_checks: Vec<ValidationCheckType>,
-}
-
-impl ValidationChecks {
-    /// Check if a specific validation type is enabled.
-    pub fn is_enabled(&self, check_type: ValidationCheckType) -> bool {
-        self.enabled_checks.contains(&check_type)
-    }
-
-    /// Enable all validation checks.
-    pub fn all() -> Self {
-        Self {
-            enabled_checks: vec![
-                ValidationCheckType::Syntax,
-                ValidationCheckType::Build,
-                ValidationCheckType::Test,
-                ValidationCheckType::Lint,
-            ],
-        }
-    }
-
-    /// Disable all validation checks.
-    pub fn none() -> Self {
-        Self {
-            enabled_checks: vec![],
-        }
-    }
-}
-
-impl Default for ValidationChecks {
-    fn default() -> Self {
-        Self::all()
-    }
-}
-
-/// Validation configuration (always enabled, never early-exit).
-/// Timeouts are now per-project in `ProjectConfig`.
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
-pub struct ValidationConfig {
-    /// Checks to perform during validation
-    #[serde(default)]
-    pub checks: ValidationChecks,
-}
-
-/// Per-project configuration (stored in `<project>/.merlin/config.toml`).
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProjectConfig {
-    /// Checks to perform during validation
-    #[serde(default)]
-    pub validation_checks: ValidationChecks,
-    /// Timeout in seconds for build operations (since last output line, capped at 1000 lines)
-    #[serde(default = "default_build_timeout")]
-    pub build_timeout_seconds: u64,
-    /// Timeout in seconds for test operations (since last output line, capped at 1000 lines)
-    #[serde(default = "default_test_timeout")]
-    pub test_timeout_seconds: u64,
-    /// Whether the workspace is read-only (prevents file modifications)
-    #[serde(default)]
-    pub read_only: bool,
-}
-
-const fn default_build_timeout() -> u64 {
-    60
-}
-
-const fn default_test_timeout() -> u64 {
-    300
-}
-
-impl Default for ProjectConfig {
-    fn default() -> Self {
-        Self {
-            validation_checks: ValidationChecks::default(),
-            build_timeout_seconds: default_build_timeout(),
-            test_timeout_seconds: default_test_timeout(),
-            read_only: false,
-        }
-    }
-}
-
-impl ProjectConfig {
-    /// Load project config from `.merlin/config.toml` in the given directory.
-    ///
-    /// # Errors
-    /// Returns an error if the config file cannot be read or parsed
-    pub fn load_from_dir(project_root: &Path) -> Result<Self> {
-        let config_path = project_root.join(".merlin").join("config.toml");
-        if !config_path.exists() {
-            return Ok(Self::default());
-        }
-        Self::load_from_file(&config_path)
-    }
-
-    /// Load project config from a specific file.
-    ///
-    /// # Errors
-    /// Returns an error if the config file cannot be read or parsed
-    pub fn load_from_file(path: &Path) -> Result<Self> {
-        use toml::from_str;
-
-        let contents = fs::read_to_string(path)
-            .map_err(|err| RoutingError::Other(format!("Failed to read config: {err}")))?;
-        from_str(&contents)
-            .map_err(|err| RoutingError::Other(format!("Failed to parse config: {err}")))
-    }
-}
-
 impl RoutingConfig {
     /// Get the default config directory path (`~/.merlin`)
     ///
@@ -334,103 +303,3 @@ impl RoutingConfig {
         }
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use anyhow::Result;
-
-    /// Tests API key loading from TOML configuration file.
-    ///
-    /// # Errors
-    /// Returns an error if file creation or config loading fails.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_api_key_loading_from_toml() -> Result<()> {
-        use std::io::Write as _;
-        use tempfile::NamedTempFile;
-
-        // Create a temporary config file with API keys
-        let toml_content = r#"
-[tiers]
-local_enabled = true
-local_model = "qwen2.5-coder:7b"
-groq_enabled = true
-groq_model = "llama-3.1-70b-versatile"
-premium_enabled = true
-max_retries = 3
-timeout_seconds = 300
-
-[api_keys]
-groq_api_key = "test_groq_key_123"
-openrouter_api_key = "test_openrouter_key_456"
-"#;
-
-        let mut temp_file = NamedTempFile::new()?;
-        temp_file.write_all(toml_content.as_bytes())?;
-
-        // Load config from the temp file
-        let config = RoutingConfig::load_from_file(temp_file.path())?;
-
-        // Verify API keys were loaded
-        assert_eq!(
-            config.api_keys.groq_api_key,
-            Some("test_groq_key_123".to_owned())
-        );
-        assert_eq!(
-            config.api_keys.openrouter_api_key,
-            Some("test_openrouter_key_456".to_owned())
-        );
-
-        // Verify get_api_key method works
-        assert_eq!(
-            config.get_api_key("groq"),
-            Some("test_groq_key_123".to_owned())
-        );
-        assert_eq!(
-            config.get_api_key("openrouter"),
-            Some("test_openrouter_key_456".to_owned())
-        );
-        Ok(())
-    }
-
-    /// Tests loading actual user configuration file if it exists.
-    ///
-    /// # Errors
-    /// Returns an error if config loading fails unexpectedly.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_load_actual_config_if_exists() -> Result<()> {
-        // This test checks if the actual ~/.merlin/config.toml can be loaded
-        // It's optional - passes if the file doesn't exist
-        if let Ok(config_path) = RoutingConfig::config_path()
-            && config_path.exists()
-        {
-            let config = RoutingConfig::load_from_file(&config_path)?;
-
-            // Just verify it loaded without crashing
-            tracing::debug!("Loaded config from {config_path:?}");
-            tracing::debug!(
-                "  groq_api_key present: {}",
-                config.api_keys.groq_api_key.is_some()
-            );
-            tracing::debug!(
-                "  openrouter_api_key present: {}",
-                config.api_keys.openrouter_api_key.is_some()
-            );
-
-            // Verify get_api_key returns the keys
-            if config.api_keys.groq_api_key.is_some() {
-                assert!(
-                    config.get_api_key("groq").is_some(),
-                    "groq_api_key is set in file but get_api_key returns None"
-                );
-            }
-        }
-        Ok(())
-    }
-}
@@ -0,0 +1,98 @@
+//! Tests for loading the routing configuration
+
+use super::*;
+use anyhow::Result;
+
+/// Tests API key loading from TOML configuration file.
+///
+/// # Errors
+/// Returns an error if file creation or config loading fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_api_key_loading_from_toml() -> Result<()> {
+    use std::io::Write as _;
+    use tempfile::NamedTempFile;
+
+    // Create a temporary config file with API keys
+    let toml_content = r#"
+[tiers]
+local_enabled = true
+local_model = "qwen2.5-coder:7b"
+groq_enabled = true
+groq_model = "llama-3.1-70b-versatile"
+premium_enabled = true
+max_retries = 3
+timeout_seconds = 300
+
+[api_keys]
+groq_api_key = "test_groq_key_123"
+openrouter_api_key = "test_openrouter_key_456"
+"#;
+
+    let mut temp_file = NamedTempFile::new()?;
+    temp_file.write_all(toml_content.as_bytes())?;
+
+    // Load config from the temp file
+    let config = RoutingConfig::load_from_file(temp_file.path())?;
+
+    // Verify API keys were loaded
+    assert_eq!(
+        config.api_keys.groq_api_key,
+        Some("test_groq_key_123".to_owned())
+    );
+    assert_eq!(
+        config.api_keys.openrouter_api_key,
+        Some("test_openrouter_key_456".to_owned())
+    );
+
+    // Verify get_api_key method works
+    assert_eq!(
+        config.get_api_key("groq"),
+        Some("test_groq_key_123".to_owned())
+    );
+    assert_eq!(
+        config.get_api_key("openrouter"),
+        Some("test_openrouter_key_456".to_owned())
+    );
+    Ok(())
+}
+
+/// Tests loading actual user configuration file if it exists.
+///
+/// # Errors
+/// Returns an error if config loading fails unexpectedly.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_load_actual_config_if_exists() -> Result<()> {
+    // This test checks if the actual ~/.merlin/config.toml can be loaded
+    // It's optional - passes if the file doesn't exist
+    if let Ok(config_path) = RoutingConfig::config_path()
+        && config_path.exists()
+    {
+        let config = RoutingConfig::load_from_file(&config_path)?;
+
+        // Just verify it loaded without crashing
+        tracing::debug!("Loaded config from {config_path:?}");
+        tracing::debug!(
+            "  groq_api_key present: {}",
+            config.api_keys.groq_api_key.is_some()
+        );
+        tracing::debug!(
+            "  openrouter_api_key present: {}",
+            config.api_keys.openrouter_api_key.is_some()
+        );
+
+        // Verify get_api_key returns the keys
+        if config.api_keys.groq_api_key.is_some() {
+            assert!(
+                config.get_api_key("groq").is_some(),
+                "groq_api_key is set in file but get_api_key returns None"
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,205 @@
+//! Validation, formatter and per-project configuration.
+
+use crate::Error as CoreError;
+use crate::routing_error::{Result, RoutingError};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Types of validation checks that can be performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ValidationCheckType {
+    /// Syntax validation
+    Syntax,
+    /// Build validation
+    Build,
+    /// Test validation
+    Test,
+    /// Lint validation
+    Lint,
+    /// Formatting of changed files
+    Format,
+}
+
+/// Validation checks to perform.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationChecks {
+    /// Set of checks to perform
+    pub enabled_checks: Vec<ValidationCheckType>,
+}
+
+impl ValidationChecks {
+    /// Check if a specific validation type is enabled.
+    pub fn is_enabled(&self, check_type: ValidationCheckType) -> bool {
+        self.enabled_checks.contains(&check_type)
+    }
+
+    /// Enable all validation checks.
+    pub fn all() -> Self {
+        Self {
+            enabled_checks: vec![
+                ValidationCheckType::Syntax,
+                ValidationCheckType::Build,
+                ValidationCheckType::Test,
+                ValidationCheckType::Lint,
+                ValidationCheckType::Format,
+            ],
+        }
+    }
+
+    /// Disable all validation checks.
+    pub fn none() -> Self {
+        Self {
+            enabled_checks: vec![],
+        }
+    }
+}
+
+impl Default for ValidationChecks {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// Validation configuration (always enabled, never early-exit).
+/// Build and test timeouts are per-project in `ProjectConfig`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationConfig {
+    /// Checks to perform during validation
+    #[serde(default)]
+    pub checks: ValidationChecks,
+    /// Format changed files instead of failing validation when they aren't formatted
+    #[serde(default)]
+    pub auto_fix: bool,
+    /// Timeout in seconds for a single formatter run
+    #[serde(default = "default_format_timeout")]
+    pub format_timeout_seconds: u64,
+    /// Rust edition passed to rustfmt for changed `.rs` files
+    #[serde(default = "default_rust_edition")]
+    pub rust_edition: String,
+    /// Formatters for other file types, taking precedence over rustfmt for
+    /// the extensions they list
+    #[serde(default)]
+    pub formatters: Vec<FormatterConfig>,
+}
+
+const fn default_format_timeout() -> u64 {
+    30
+}
+
+fn default_rust_edition() -> String {
+    "2021".to_owned()
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            checks: ValidationChecks::default(),
+            auto_fix: false,
+            format_timeout_seconds: default_format_timeout(),
+            rust_edition: default_rust_edition(),
+            formatters: Vec::default(),
+        }
+    }
+}
+
+/// External formatter run on changed files, e.g. prettier
+///
+/// The changed files are appended to the arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FormatterConfig {
+    /// File extensions the formatter handles, without the leading dot
+    pub extensions: Vec<String>,
+    /// Formatter executable
+    pub command: String,
+    /// Arguments checking formatting without changing files, e.g. `["--check"]`
+    #[serde(default)]
+    pub check_args: Vec<String>,
+    /// Arguments formatting files in place, e.g. `["--write"]`
+    #[serde(default)]
+    pub fix_args: Vec<String>,
+}
+
+impl FormatterConfig {
+    /// rustfmt for `edition`
+    pub fn rustfmt(edition: &str) -> Self {
+        let fix_args = vec!["--edition".to_owned(), edition.to_owned()];
+        let mut check_args = vec!["--check".to_owned()];
+        check_args.extend(fix_args.iter().cloned());
+        Self {
+            extensions: vec!["rs".to_owned()],
+            command: "rustfmt".to_owned(),
+            check_args,
+            fix_args,
+        }
+    }
+
+    /// Whether the formatter handles `path`
+    pub fn handles(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| self.extensions.iter().any(|handled| handled == extension))
+    }
+}
+
+/// Per-project configuration (stored in `<project>/.merlin/config.toml`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectConfig {
+    /// Checks to perform during validation
+    #[serde(default)]
+    pub validation_checks: ValidationChecks,
+    /// Timeout in seconds for build operations (since last output line, capped at 1000 lines)
+    #[serde(default = "default_build_timeout")]
+    pub build_timeout_seconds: u64,
+    /// Timeout in seconds for test operations (since last output line, capped at 1000 lines)
+    #[serde(default = "default_test_timeout")]
+    pub test_timeout_seconds: u64,
+    /// Whether the workspace is read-only (prevents file modifications)
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+const fn default_build_timeout() -> u64 {
+    60
+}
+
+const fn default_test_timeout() -> u64 {
+    300
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        Self {
+            validation_checks: ValidationChecks::default(),
+            build_timeout_seconds: default_build_timeout(),
+            test_timeout_seconds: default_test_timeout(),
+            read_only: false,
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Load project config from `.merlin/config.toml` in the given directory.
+    ///
+    /// # Errors
+    /// Returns an error if the config file cannot be read or parsed
+    pub fn load_from_dir(project_root: &Path) -> Result<Self> {
+        let config_path = project_root.join(".merlin").join("config.toml");
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load_from_file(&config_path)
+    }
+
+    /// Load project config from a specific file.
+    ///
+    /// # Errors
+    /// Returns an error if the config file cannot be read or parsed
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        use toml::from_str;
+
+        let contents =
+            fs::read_to_string(path).map_err(RoutingError::file_access("read config", path))?;
+        from_str(&contents).map_err(|err| RoutingError::Core(CoreError::Toml(err)))
+    }
+}
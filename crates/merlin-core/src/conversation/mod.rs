@@ -13,7 +13,7 @@ mod work;
 
 // Re-export all public types
 pub use ids::{MessageId, SubtaskId, ThreadId, WorkUnitId};
-pub use types::{BranchPoint, Message, Thread};
+pub use types::{BranchPoint, Message, ShownFile, ShownFiles, Thread, TitleSource};
 pub use work::{Subtask, SubtaskStatus, VerificationStep, WorkStatus, WorkUnit};
 
 /// Thread colors for visual identification in the UI
@@ -1,5 +1,8 @@
 //! Thread, message, and conversation types.
 
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +10,9 @@ use super::ThreadColor;
 use super::ids::{MessageId, ThreadId};
 use super::work::WorkUnit;
 
+/// Most characters kept from each side of the exchange in a thread summary
+const SUMMARY_PART_MAX_CHARS: usize = 160;
+
 /// A conversation thread containing messages and their associated work
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Thread {
@@ -14,6 +20,9 @@ pub struct Thread {
     pub id: ThreadId,
     /// Display name for this thread (user-editable)
     pub name: String,
+    /// Where the current name came from (auto-titles never replace manual names)
+    #[serde(default)]
+    pub title_source: TitleSource,
     /// Color for visual identification
     pub color: ThreadColor,
     /// Messages in this thread (ordered chronologically)
@@ -22,12 +31,38 @@ pub struct Thread {
     pub parent_thread: Option<BranchPoint>,
     /// Whether this thread is archived (hidden from main view)
     pub archived: bool,
+    /// Tags for organizing threads by topic, project, or status
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Files included in the context of every message in this thread
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_files: Vec<PathBuf>,
+    /// Directory this thread's tasks run in (None for the project root given at startup)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<PathBuf>,
+    /// Latest version of each file shown to the model in this thread's prompts
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub shown_files: ShownFiles,
     /// When this thread was created
     pub created_at: DateTime<Utc>,
     /// When this thread was last updated (message added or modified)
     pub updated_at: DateTime<Utc>,
 }
 
+/// Files shown to the model in a thread's prompts, by path
+pub type ShownFiles = BTreeMap<PathBuf, ShownFile>;
+
+/// Version of a file that was shown to the model
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShownFile {
+    /// Hash of the content, to tell unchanged files apart cheaply
+    pub hash: u64,
+    /// Content as shown, to diff later versions against
+    pub content: String,
+    /// Number of the message whose prompt showed it (1-based)
+    pub message: usize,
+}
+
 impl Thread {
     /// Creates a new thread with the given name and color
     pub fn new(name: String, color: ThreadColor) -> Self {
@@ -35,10 +70,15 @@ impl Thread {
         Self {
             id: ThreadId::new(),
             name,
+            title_source: TitleSource::Default,
             color,
             messages: Vec::new(),
             parent_thread: None,
             archived: false,
+            tags: Vec::new(),
+            pinned_files: Vec::new(),
+            working_dir: None,
+            shown_files: ShownFiles::new(),
             created_at: now,
             updated_at: now,
         }
@@ -55,6 +95,7 @@ impl Thread {
         Self {
             id: ThreadId::new(),
             name,
+            title_source: TitleSource::Default,
             color,
             messages: Vec::new(),
             parent_thread: Some(BranchPoint {
@@ -62,6 +103,10 @@ impl Thread {
                 message_id: parent_message_id,
             }),
             archived: false,
+            tags: Vec::new(),
+            pinned_files: Vec::new(),
+            working_dir: None,
+            shown_files: ShownFiles::new(),
             created_at: now,
             updated_at: now,
         }
@@ -83,10 +128,88 @@ impl Thread {
     pub fn last_message(&self) -> Option<&Message> {
         self.messages.last()
     }
+
+    /// Returns whether this thread carries the given tag
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|existing| existing == tag)
+    }
+
+    /// Pins a file to this thread's context
+    ///
+    /// Returns `false` if the file was already pinned.
+    pub fn pin_file(&mut self, path: PathBuf) -> bool {
+        if self.is_pinned(&path) {
+            return false;
+        }
+        self.pinned_files.push(path);
+        true
+    }
+
+    /// Unpins a file from this thread's context
+    ///
+    /// Returns `false` if the file was not pinned.
+    pub fn unpin_file(&mut self, path: &Path) -> bool {
+        let pinned_count = self.pinned_files.len();
+        self.pinned_files.retain(|pinned| pinned != path);
+        self.pinned_files.len() != pinned_count
+    }
+
+    /// Returns whether the given file is pinned to this thread
+    #[must_use]
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        self.pinned_files.iter().any(|pinned| pinned == path)
+    }
+
+    /// One-sentence summary of the thread's first exchange
+    ///
+    /// The first sentence of the first request, followed by the first sentence
+    /// of what its work produced once it has results. Empty for threads without
+    /// messages.
+    #[must_use]
+    pub fn summarize(&self) -> String {
+        let Some(first) = self.messages.first() else {
+            return String::new();
+        };
+        let request = first_sentence(&first.content);
+        let answer = first
+            .work
+            .iter()
+            .flat_map(|work| &work.subtasks)
+            .find_map(|subtask| subtask.result.as_deref())
+            .map(first_sentence)
+            .filter(|answer| !answer.is_empty());
+        match answer {
+            Some(answer) => format!("{request} — {answer}"),
+            None => request,
+        }
+    }
+
+    /// Name of the directory this thread works in, if it is not the project root
+    #[must_use]
+    pub fn working_dir_name(&self) -> Option<String> {
+        let dir = self.working_dir.as_ref()?;
+        Some(dir.file_name().map_or_else(
+            || dir.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        ))
+    }
+}
+
+/// Origin of a thread's display name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TitleSource {
+    /// Placeholder name derived from the first prompt
+    #[default]
+    Default,
+    /// Title generated by a model after the first completed task
+    Generated,
+    /// Name set explicitly by the user
+    Manual,
 }
 
 /// Reference to a parent thread and message where a branch occurred
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BranchPoint {
     /// ID of the parent thread
     pub thread_id: ThreadId,
@@ -103,6 +226,9 @@ pub struct Message {
     pub content: String,
     /// Work unit spawned by this message (None if cancelled before work started)
     pub work: Option<WorkUnit>,
+    /// When this message was sent (preserved when threads are merged)
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
 }
 
 impl Message {
@@ -112,6 +238,7 @@ impl Message {
             id: MessageId::new(),
             content,
             work: None,
+            created_at: Utc::now(),
         }
     }
 
@@ -121,10 +248,30 @@ impl Message {
     }
 }
 
+/// First sentence or line of `text`, cut to `SUMMARY_PART_MAX_CHARS` characters
+fn first_sentence(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or_default();
+    let sentence = line
+        .match_indices(['.', '?', '!'])
+        .find(|(index, _)| {
+            line.get(index + 1..)
+                .is_none_or(|rest| rest.is_empty() || rest.starts_with(' '))
+        })
+        .map_or(line, |(index, _)| line.get(..index).unwrap_or(line));
+    let sentence = sentence.trim();
+    if sentence.chars().count() <= SUMMARY_PART_MAX_CHARS {
+        return sentence.to_owned();
+    }
+    let cut: String = sentence.chars().take(SUMMARY_PART_MAX_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::TaskId;
     use anyhow::Result;
+    use serde_json::to_string;
 
     /// Tests basic thread creation and initialization.
     ///
@@ -138,6 +285,24 @@ mod tests {
         assert!(thread.messages.is_empty());
         assert!(thread.parent_thread.is_none());
         assert!(!thread.archived);
+        assert_eq!(thread.working_dir_name(), None);
+    }
+
+    /// Tests that the working directory is named after its last component.
+    ///
+    /// # Errors
+    /// Returns an error if the thread cannot be serialized.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_working_dir_name() -> Result<()> {
+        let mut thread = Thread::new("Infra".to_owned(), ThreadColor::Blue);
+        assert!(!to_string(&thread)?.contains("working_dir"));
+
+        thread.working_dir = Some(PathBuf::from("/home/dev/infra"));
+        assert_eq!(thread.working_dir_name(), Some("infra".to_owned()));
+        Ok(())
     }
 
     /// Tests thread branching functionality and parent thread tracking.
@@ -176,4 +341,37 @@ mod tests {
         assert_eq!(message.content, "Hello");
         assert!(message.work.is_none());
     }
+
+    /// Tests summarizing a thread's first request and the result of its work.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_thread_summarize() {
+        let mut thread = Thread::new("Thread".to_owned(), ThreadColor::Blue);
+        assert_eq!(thread.summarize(), "");
+
+        let mut message = Message::new(
+            "Fix the login bug in session.rs. It logs users out after 5.5 minutes.".to_owned(),
+        );
+        thread.add_message(message.clone());
+        assert_eq!(thread.summarize(), "Fix the login bug in session.rs");
+
+        let mut work = WorkUnit::new(TaskId::default(), "local".to_owned());
+        let subtask = work.add_subtask("Fix expiry".to_owned(), 3);
+        work.complete_subtask(
+            subtask,
+            Some("Sessions now expire after 30 minutes!\nDone".to_owned()),
+        );
+        message.attach_work(work);
+        thread.messages = vec![message];
+        assert_eq!(
+            thread.summarize(),
+            "Fix the login bug in session.rs — Sessions now expire after 30 minutes"
+        );
+
+        let long = first_sentence(&"word ".repeat(100));
+        assert!(long.chars().count() <= SUMMARY_PART_MAX_CHARS + 1);
+        assert!(long.ends_with('…'));
+    }
 }
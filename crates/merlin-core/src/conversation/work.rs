@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 
 use super::ids::{SubtaskId, WorkUnitId};
-use crate::{TaskId, TokenUsage};
+use crate::{PhaseTimings, TaskId, TokenUsage};
 
 /// Status of a work unit
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,6 +19,8 @@ pub enum WorkStatus {
     Cancelled,
     /// Work is being retried after failure (🔄)
     Retrying,
+    /// Work was cut short because Merlin stopped while it was running (⚠️)
+    Interrupted,
 }
 
 impl WorkStatus {
@@ -31,6 +33,7 @@ impl WorkStatus {
             Self::Failed => "❌",
             Self::Cancelled => "⏸️",
             Self::Retrying => "🔄",
+            Self::Interrupted => "⚠️",
         }
     }
 }
@@ -87,6 +90,7 @@ impl WorkUnit {
             verification: None,
             error: None,
             result: None,
+            timings: None,
         };
         let id = subtask.id;
         self.subtasks.push(subtask);
@@ -147,7 +151,7 @@ impl WorkUnit {
             return match self.status {
                 WorkStatus::Completed => 100,
                 WorkStatus::InProgress | WorkStatus::Retrying => 50,
-                WorkStatus::Failed | WorkStatus::Cancelled => 0,
+                WorkStatus::Failed | WorkStatus::Cancelled | WorkStatus::Interrupted => 0,
             };
         }
 
@@ -194,6 +198,17 @@ impl WorkUnit {
         self.status = WorkStatus::Cancelled;
     }
 
+    /// Marks the work as interrupted by a restart, failing any subtask that was running
+    pub fn interrupt(&mut self) {
+        for subtask in &mut self.subtasks {
+            if subtask.status == SubtaskStatus::InProgress {
+                subtask.status = SubtaskStatus::Failed;
+                subtask.error = Some("Interrupted before completion".to_owned());
+            }
+        }
+        self.status = WorkStatus::Interrupted;
+    }
+
     /// Increments retry count and marks as retrying
     pub fn retry(&mut self) {
         self.retry_count += 1;
@@ -205,7 +220,10 @@ impl WorkUnit {
     pub const fn is_terminal(&self) -> bool {
         matches!(
             self.status,
-            WorkStatus::Completed | WorkStatus::Failed | WorkStatus::Cancelled
+            WorkStatus::Completed
+                | WorkStatus::Failed
+                | WorkStatus::Cancelled
+                | WorkStatus::Interrupted
         )
     }
 }
@@ -265,6 +283,9 @@ pub struct Subtask {
     pub error: Option<String>,
     /// Optional result/output from completing this subtask
     pub result: Option<String>,
+    /// Time spent in each phase of the subtask, once completed
+    #[serde(default)]
+    pub timings: Option<PhaseTimings>,
 }
 
 /// Verification step for a subtask (optional)
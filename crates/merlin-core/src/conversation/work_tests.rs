@@ -172,6 +172,7 @@ fn test_status_emojis() {
     assert_eq!(WorkStatus::Failed.emoji(), "❌");
     assert_eq!(WorkStatus::Cancelled.emoji(), "⏸️");
     assert_eq!(WorkStatus::Retrying.emoji(), "🔄");
+    assert_eq!(WorkStatus::Interrupted.emoji(), "⚠️");
 
     assert_eq!(SubtaskStatus::Pending.emoji(), "⏳");
     assert_eq!(SubtaskStatus::InProgress.emoji(), "🔄");
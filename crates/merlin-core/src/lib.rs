@@ -3,6 +3,8 @@
 //! This crate provides fundamental types, error handling, and trait definitions
 //! used across the agentic optimizer system.
 
+/// Clock abstraction for time-dependent code.
+pub mod clock;
 /// Error types and result definitions.
 pub mod error;
 /// Prompt loading utilities.
@@ -21,6 +23,8 @@ pub mod config;
 pub mod conversation;
 /// Routing error types
 pub mod routing_error;
+/// Versioning and migration of persisted formats
+pub mod schema;
 /// Streaming events and step tracking
 pub mod streaming;
 /// Task types and execution context
@@ -29,6 +33,7 @@ pub mod task;
 pub mod ui;
 
 // Original merlin-core exports
+pub use clock::{Clock, SharedClock, SystemClock, VirtualClock};
 pub use error::Error;
 pub use error::Result as CoreResult; // Renamed to avoid conflict
 pub use sync::IgnoreLock;
@@ -40,12 +45,12 @@ pub use types::{
 
 // Re-export types from merged modules (formerly merlin-types)
 pub use config::{
-    ProjectConfig, ProviderType, RoutingConfig, TierConfig, ValidationCheckType, ValidationChecks,
-    ValidationConfig,
+    CatalogConfig, FormatterConfig, GitConfig, ProjectConfig, ProviderType, RoutingConfig,
+    TierConfig, ValidationCheckType, ValidationChecks, ValidationConfig,
 };
 pub use conversation::{
-    BranchPoint, Message, MessageId, Subtask, SubtaskId, SubtaskStatus, Thread, ThreadColor,
-    ThreadId, VerificationStep, WorkStatus, WorkUnit, WorkUnitId,
+    BranchPoint, Message, MessageId, ShownFile, ShownFiles, Subtask, SubtaskId, SubtaskStatus,
+    Thread, ThreadColor, ThreadId, TitleSource, VerificationStep, WorkStatus, WorkUnit, WorkUnitId,
 };
 pub use routing_error::RoutingError;
 // Re-export Result from routing_error as the main Result (for backward compatibility with merlin-types)
@@ -64,10 +69,12 @@ pub use task::{
     FileChange,
     FilePattern,
     JsValueHandle,
+    PhaseTimings,
     Priority,
     Severity,
     StageResult,
     StepType,
+    TIMING_MARKER,
     Task,
     TaskAction,
     TaskAnalysis,
@@ -82,4 +89,4 @@ pub use task::{
     ValidationResult,
     ValidationStage as ValidationStageType,
 };
-pub use ui::{MessageLevel, TaskProgress, UiChannel, UiEvent};
+pub use ui::{AnswerSender, MessageLevel, TaskProgress, UiChannel, UiEvent, UiEventReceiver};
@@ -3,23 +3,160 @@
 //! This module provides functions to load prompts from the central prompts directory.
 //! Each prompt file is a markdown document with Usage and Prompt sections.
 //! Prompts are embedded at compile time using `include_str!`.
+//!
+//! A prompt can be overridden per project with `.merlin/prompts/<name>.md`, or for
+//! every project with `~/.merlin/prompts/<name>.md`. Overrides are checked in that
+//! order before the embedded default, and must keep the placeholders the prompt is
+//! compiled with.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 
 // Embed prompt files at compile time
 const CONTEXT_PLANNING_MD: &str = include_str!("../../../../prompts/context_planning.md");
 const TYPESCRIPT_AGENT_MD: &str = include_str!("../../../../prompts/typescript_agent.md");
 
-/// Loads a prompt by name
+/// Names of all prompts that can be loaded or overridden
+pub const PROMPT_NAMES: &[&str] = &["context_planning", "typescript_agent"];
+
+/// Where a loaded prompt came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptSource {
+    /// Project override (`<project>/.merlin/prompts/<name>.md`)
+    Project(PathBuf),
+    /// User override (`~/.merlin/prompts/<name>.md`)
+    User(PathBuf),
+    /// Default prompt embedded in the binary
+    Embedded,
+}
+
+impl Display for PromptSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Project(path) => write!(f, "project override ({})", path.display()),
+            Self::User(path) => write!(f, "user override ({})", path.display()),
+            Self::Embedded => write!(f, "embedded default"),
+        }
+    }
+}
+
+/// A prompt together with the source it was loaded from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedPrompt {
+    /// Prompt text (the Prompt section of the markdown file)
+    pub text: String,
+    /// Where the prompt was loaded from
+    pub source: PromptSource,
+}
+
+/// Directories searched for prompt overrides
+#[derive(Debug, Clone, Default)]
+pub struct PromptDirs {
+    /// Project override directory, checked first
+    pub project: Option<PathBuf>,
+    /// User override directory, checked before the embedded default
+    pub user: Option<PathBuf>,
+}
+
+impl PromptDirs {
+    /// Override directories of `project_root` and of the current user
+    #[must_use]
+    pub fn for_project(project_root: &Path) -> Self {
+        use dirs::home_dir;
+        Self {
+            project: Some(project_root.join(".merlin").join("prompts")),
+            user: home_dir().map(|home| home.join(".merlin").join("prompts")),
+        }
+    }
+
+    /// Path of the project override of `name`, whether or not it exists
+    #[must_use]
+    pub fn project_path(&self, name: &str) -> Option<PathBuf> {
+        self.project
+            .as_ref()
+            .map(|dir| dir.join(format!("{name}.md")))
+    }
+}
+
+/// Loads a prompt by name, preferring project and user overrides to the embedded default
 ///
 /// # Errors
-/// Returns an error if the prompt name is unknown or the prompt section cannot be extracted
-pub fn load_prompt(name: &str) -> Result<String, String> {
-    let content = match name {
-        "context_planning" => CONTEXT_PLANNING_MD,
-        "typescript_agent" => TYPESCRIPT_AGENT_MD,
-        _ => return Err(format!("Unknown prompt: {name}")),
-    };
-
-    extract_prompt_section(content)
+/// Returns an error if the prompt name is unknown, an override cannot be read, the
+/// prompt section cannot be extracted, or an override is missing a required placeholder
+pub fn load_prompt(name: &str, dirs: &PromptDirs) -> Result<LoadedPrompt, String> {
+    let embedded = embedded_prompt(name).ok_or_else(|| format!("Unknown prompt: {name}"))?;
+
+    let overrides = [
+        (
+            dirs.project.as_ref(),
+            PromptSource::Project as fn(PathBuf) -> PromptSource,
+        ),
+        (dirs.user.as_ref(), PromptSource::User),
+    ];
+    for (dir, source) in overrides {
+        let Some(dir) = dir else {
+            continue;
+        };
+        let path = dir.join(format!("{name}.md"));
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(format!("Failed to read {}: {err}", path.display())),
+        };
+        let text = extract_override(&content);
+        let source = source(path);
+        validate_placeholders(name, &text).map_err(|err| format!("{source}: {err}"))?;
+        return Ok(LoadedPrompt { text, source });
+    }
+
+    Ok(LoadedPrompt {
+        text: extract_prompt_section(embedded)?,
+        source: PromptSource::Embedded,
+    })
+}
+
+/// Returns the embedded markdown file of a prompt, including its Usage section
+#[must_use]
+pub fn embedded_prompt(name: &str) -> Option<&'static str> {
+    match name {
+        "context_planning" => Some(CONTEXT_PLANNING_MD),
+        "typescript_agent" => Some(TYPESCRIPT_AGENT_MD),
+        _ => None,
+    }
+}
+
+/// Placeholders a prompt must contain to be compiled
+#[must_use]
+pub fn required_placeholders(name: &str) -> &'static [&'static str] {
+    match name {
+        "typescript_agent" => &["{tool_signatures}"],
+        _ => &[],
+    }
+}
+
+/// Checks that `text` keeps every placeholder required by prompt `name`
+///
+/// # Errors
+/// Returns an error naming the first missing placeholder
+pub fn validate_placeholders(name: &str, text: &str) -> Result<(), String> {
+    required_placeholders(name)
+        .iter()
+        .find(|placeholder| !text.contains(*placeholder))
+        .map_or(Ok(()), |placeholder| {
+            Err(format!(
+                "prompt `{name}` is missing required placeholder {placeholder}"
+            ))
+        })
+}
+
+/// Extracts the prompt of an override file
+///
+/// Overrides copied from the defaults keep their Usage section, so the Prompt
+/// section is used when there is one and the whole file otherwise.
+fn extract_override(content: &str) -> String {
+    extract_prompt_section(content).unwrap_or_else(|_| content.trim().to_owned())
 }
 
 /// Extracts the Prompt section from a markdown file
@@ -48,6 +185,7 @@ fn extract_prompt_section(content: &str) -> Result<String, String> {
 mod tests {
     use super::*;
     use anyhow::Result;
+    use tempfile::TempDir;
 
     /// Tests extraction of prompt section from markdown.
     ///
@@ -88,7 +226,9 @@ It can have multiple lines.
     /// Panics if assertions fail during test execution.
     #[test]
     fn test_load_context_planning_prompt() -> Result<()> {
-        let prompt = load_prompt("context_planning").map_err(|err| anyhow::anyhow!("{err}"))?;
+        let prompt = load_prompt("context_planning", &PromptDirs::default())
+            .map_err(|err| anyhow::anyhow!("{err}"))?
+            .text;
         // Ensure Usage section is not included in the extracted prompt
         assert!(!prompt.contains("## Usage"));
         assert!(!prompt.contains("When used:"));
@@ -104,10 +244,115 @@ It can have multiple lines.
     /// Panics if assertions fail during test execution.
     #[test]
     fn test_load_typescript_agent_prompt() -> Result<()> {
-        let prompt = load_prompt("typescript_agent").map_err(|err| anyhow::anyhow!("{err}"))?;
+        let prompt = load_prompt("typescript_agent", &PromptDirs::default())
+            .map_err(|err| anyhow::anyhow!("{err}"))?
+            .text;
         // Ensure Usage section is not included in the extracted prompt
         assert!(!prompt.contains("## Usage"));
         assert!(!prompt.contains("When used:"));
+        // Tool error codes are documented so agent code can branch on them
+        assert!(prompt.contains("# TOOL ERRORS"));
+        assert!(prompt.contains("`sandbox_violation`"));
+        Ok(())
+    }
+
+    /// Tests that project overrides win over user overrides, which win over the default.
+    ///
+    /// # Errors
+    /// Returns an error if override files cannot be written or prompt loading fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_override_fallback_chain() -> Result<()> {
+        let project = TempDir::new()?;
+        let user = TempDir::new()?;
+        let dirs = PromptDirs {
+            project: Some(project.path().to_path_buf()),
+            user: Some(user.path().to_path_buf()),
+        };
+        let load =
+            || load_prompt("typescript_agent", &dirs).map_err(|err| anyhow::anyhow!("{err}"));
+
+        assert_eq!(load()?.source, PromptSource::Embedded);
+
+        let user_path = user.path().join("typescript_agent.md");
+        fs::write(&user_path, "User prompt\n\n{tool_signatures}\n")?;
+        let user_prompt = load()?;
+        assert_eq!(user_prompt.source, PromptSource::User(user_path));
+        assert_eq!(user_prompt.text, "User prompt\n\n{tool_signatures}");
+
+        let project_path = project.path().join("typescript_agent.md");
+        fs::write(
+            &project_path,
+            "# Team prompt\n\n## Usage\n\nNotes\n\n## Prompt\n\nProject prompt {tool_signatures}\n",
+        )?;
+        let project_prompt = load()?;
+        assert_eq!(project_prompt.source, PromptSource::Project(project_path));
+        assert_eq!(project_prompt.text, "Project prompt {tool_signatures}");
+        Ok(())
+    }
+
+    /// Tests that an override missing a required placeholder is rejected.
+    ///
+    /// # Errors
+    /// Returns an error if the override file cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_override_missing_placeholder_is_rejected() -> Result<()> {
+        let project = TempDir::new()?;
+        fs::write(
+            project.path().join("typescript_agent.md"),
+            "Write TypeScript, the tools are up to you.\n",
+        )?;
+        fs::write(
+            project.path().join("context_planning.md"),
+            "Plan context.\n",
+        )?;
+        let dirs = PromptDirs {
+            project: Some(project.path().to_path_buf()),
+            user: None,
+        };
+
+        let result = load_prompt("typescript_agent", &dirs);
+        assert!(
+            result.as_ref().is_err_and(
+                |err| err.contains("{tool_signatures}") && err.contains("project override")
+            ),
+            "unexpected result: {result:?}"
+        );
+
+        // Prompts without required placeholders accept any override
+        let planning =
+            load_prompt("context_planning", &dirs).map_err(|err| anyhow::anyhow!("{err}"))?;
+        assert_eq!(planning.text, "Plan context.");
+        Ok(())
+    }
+
+    /// Tests that unknown prompt names are rejected even if an override exists.
+    ///
+    /// # Errors
+    /// Returns an error if the override file cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_unknown_prompt_is_rejected() -> Result<()> {
+        let project = TempDir::new()?;
+        fs::write(project.path().join("made_up.md"), "Anything\n")?;
+        let dirs = PromptDirs {
+            project: Some(project.path().to_path_buf()),
+            user: None,
+        };
+        let result = load_prompt("made_up", &dirs);
+        assert!(
+            result
+                .as_ref()
+                .is_err_and(|err| err.contains("Unknown prompt")),
+            "unexpected result: {result:?}"
+        );
         Ok(())
     }
 }
@@ -1,9 +1,13 @@
 //! Error types for the routing system.
 
 use crate::Error as CoreError;
+use crate::conversation::{MessageId, ThreadId};
+use crate::schema::SchemaError;
 use crate::task::{TaskId, ValidationResult};
+use anyhow::Error as AnyhowError;
+use merlin_tooling::ToolError;
 use serde_json::Error as JsonError;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::result::Result as StdResult;
 use std::{fmt, io};
 use thiserror::Error;
@@ -22,6 +26,18 @@ pub enum RoutingError {
     #[error("IO error: {0}")]
     Io(#[from] io::Error),
 
+    /// A file or directory could not be read, written or removed
+    #[error("Failed to {action} {}: {source}", path.display())]
+    FileAccess {
+        /// What was being done, e.g. "write thread file"
+        action: &'static str,
+        /// Path of the file or directory
+        path: PathBuf,
+        /// Underlying IO error
+        #[source]
+        source: io::Error,
+    },
+
     /// Formatting error
     #[error("Format error: {0}")]
     Format(#[from] fmt::Error),
@@ -30,17 +46,64 @@ pub enum RoutingError {
     #[error("JSON error: {0}")]
     Json(#[from] JsonError),
 
+    /// Stored document could not be encoded or decoded with its schema
+    #[error("Schema error: {0}")]
+    Schema(#[from] SchemaError),
+
     /// Provider is temporarily unavailable
     #[error("Provider temporarily unavailable: {0}")]
     ProviderUnavailable(String),
 
-    /// Rate limit has been exceeded
-    #[error("Rate limit exceeded: {0}")]
-    RateLimitExceeded(String),
+    /// Provider request timed out
+    #[error("Provider {provider} timed out after {timeout_ms}ms")]
+    ProviderTimeout {
+        /// Name of the provider that timed out
+        provider: String,
+        /// Timeout that elapsed, in milliseconds
+        timeout_ms: u64,
+    },
+
+    /// Provider rejected the request because of rate limiting
+    #[error("Provider {provider} rate limited the request{}", format_retry_after(*.retry_after_secs))]
+    ProviderRateLimit {
+        /// Name of the rate-limiting provider
+        provider: String,
+        /// Seconds to wait before retrying, if the provider reported it
+        retry_after_secs: Option<u64>,
+    },
+
+    /// Prompt context exceeds the model's token budget
+    #[error("Context too large: ~{token_count} tokens exceeds limit of {limit}")]
+    ContextTooLarge {
+        /// Estimated token count of the context
+        token_count: usize,
+        /// Maximum allowed token count
+        limit: usize,
+    },
+
+    /// A tool's command could not run or exited unsuccessfully
+    #[error("Tool {tool_name} failed (exit code {}): {stderr}", format_exit_code(*.exit_code))]
+    ToolExecutionFailed {
+        /// Name of the failing tool
+        tool_name: String,
+        /// Process exit code (None if the process never ran or was killed)
+        exit_code: Option<i32>,
+        /// Captured standard error or failure reason
+        stderr: String,
+    },
 
-    /// Operation timed out
-    #[error("Timeout after {0}ms")]
-    Timeout(u64),
+    /// Task was cancelled before it finished (e.g. during shutdown)
+    #[error("Task {0:?} was cancelled")]
+    Cancelled(TaskId),
+
+    /// Code run for a task panicked and the panic was caught
+    #[error("Internal panic in {task_description}: {message}")]
+    InternalPanic {
+        /// What was running when the panic occurred
+        task_description: String,
+        /// Panic message
+        message: String,
+    },
 
     /// Cyclic dependency detected in task graph
     #[error("Cyclic dependency detected in task graph")]
@@ -102,6 +165,40 @@ pub enum RoutingError {
     #[error("Validation failed: {0:?}")]
     ValidationFailed(ValidationResult),
 
+    /// Identical task this one waited on failed instead of it
+    #[error("Identical task {leader:?} {reason}")]
+    DuplicateTaskFailed {
+        /// ID of the task that ran
+        leader: TaskId,
+        /// How it failed
+        reason: String,
+    },
+
+    /// No thread store is configured
+    #[error("Thread store not initialized")]
+    NoThreadStore,
+
+    /// No stored thread has the ID
+    #[error("Thread {0} not found")]
+    ThreadNotFound(ThreadId),
+
+    /// Thread has no message with the ID
+    #[error("Message {message} not found in thread {thread}")]
+    MessageNotFound {
+        /// Thread searched
+        thread: ThreadId,
+        /// Message looked for
+        message: MessageId,
+    },
+
+    /// Thread change that cannot be made (e.g. an empty name, a self-merge)
+    #[error("Invalid thread operation: {0}")]
+    InvalidThreadOperation(String),
+
+    /// Shared state cannot be locked because a holder panicked
+    #[error("{0} lock poisoned")]
+    LockPoisoned(&'static str),
+
     /// Task execution failed
     #[error("Task execution failed: {0}")]
     ExecutionFailed(String),
@@ -110,6 +207,12 @@ pub enum RoutingError {
     #[error("Analysis failed: {0}")]
     AnalysisFailed(String),
 
+    /// Error wrapped in domain context layers (e.g. "executing step X of task Y")
+    ///
+    /// Displays the full chain, outermost context first.
+    #[error("{0:#}")]
+    WithContext(AnyhowError),
+
     /// Other error
     #[error("{0}")]
     Other(String),
@@ -119,14 +222,93 @@ impl RoutingError {
     /// Checks if this error is retryable (transient failure).
     pub fn is_retryable(&self) -> bool {
         matches!(
-            self,
-            Self::ProviderUnavailable(_) | Self::RateLimitExceeded(_) | Self::Timeout(_)
+            self.root(),
+            Self::ProviderUnavailable(_)
+                | Self::ProviderRateLimit { .. }
+                | Self::ProviderTimeout { .. }
         )
     }
 
     /// Checks if this error condition allows escalation to a higher tier.
     pub fn can_escalate(&self) -> bool {
-        matches!(self, Self::MaxRetriesExceeded { .. })
+        matches!(self.root(), Self::MaxRetriesExceeded { .. })
+    }
+
+    /// Returns a `map_err` closure wrapping an IO error in [`Self::FileAccess`].
+    pub fn file_access(action: &'static str, path: &Path) -> impl FnOnce(io::Error) -> Self {
+        let path = path.to_path_buf();
+        move |source| Self::FileAccess {
+            action,
+            path,
+            source,
+        }
+    }
+
+    /// Returns the innermost error beneath any context layers.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::WithContext(err) => err.downcast_ref::<Self>().map_or(self, Self::root),
+            _ => self,
+        }
+    }
+
+    /// Returns every context layer, outermost first, ending with the root error.
+    pub fn context_chain(&self) -> Vec<String> {
+        let Self::WithContext(err) = self else {
+            return vec![self.to_string()];
+        };
+
+        let mut chain = Vec::new();
+        for cause in err.chain() {
+            if let Some(inner) = cause.downcast_ref::<Self>() {
+                chain.extend(inner.context_chain());
+                break;
+            }
+            chain.push(cause.to_string());
+        }
+        chain
+    }
+}
+
+/// Formats the optional retry delay of a rate limit error
+fn format_retry_after(retry_after_secs: Option<u64>) -> String {
+    retry_after_secs.map_or_else(String::new, |secs| format!(" (retry after {secs}s)"))
+}
+
+/// Formats an optional process exit code
+fn format_exit_code(exit_code: Option<i32>) -> String {
+    exit_code.map_or_else(|| "none".to_owned(), |code| code.to_string())
+}
+
+/// Preserves the full context chain built with `anyhow::Context`
+impl From<AnyhowError> for RoutingError {
+    fn from(err: AnyhowError) -> Self {
+        Self::WithContext(err)
+    }
+}
+
+impl From<ToolError> for RoutingError {
+    fn from(err: ToolError) -> Self {
+        match err {
+            ToolError::CommandFailed {
+                tool_name,
+                exit_code,
+                stderr,
+            } => Self::ToolExecutionFailed {
+                tool_name,
+                exit_code,
+                stderr,
+            },
+            ToolError::Panicked {
+                task_description,
+                message,
+            } => Self::InternalPanic {
+                task_description,
+                message,
+            },
+            ToolError::Io(message) => Self::Io(io::Error::other(message)),
+            other => Self::ExecutionFailed(other.user_message()),
+        }
     }
 }
 
@@ -147,3 +329,134 @@ pub struct FileConflict {
     /// Current hash of the file
     pub current_hash: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests retry classification of structured provider errors.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_structured_errors_retryable() {
+        let timeout = RoutingError::ProviderTimeout {
+            provider: "groq".to_owned(),
+            timeout_ms: 30_000,
+        };
+        let rate_limit = RoutingError::ProviderRateLimit {
+            provider: "openrouter".to_owned(),
+            retry_after_secs: Some(20),
+        };
+        let too_large = RoutingError::ContextTooLarge {
+            token_count: 150_000,
+            limit: 100_000,
+        };
+
+        assert!(timeout.is_retryable());
+        assert!(rate_limit.is_retryable());
+        assert!(!too_large.is_retryable());
+        assert_eq!(
+            rate_limit.to_string(),
+            "Provider openrouter rate limited the request (retry after 20s)"
+        );
+    }
+
+    /// Tests converting tool errors into routing errors.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_tool_error_conversion() {
+        let command_failed = RoutingError::from(ToolError::CommandFailed {
+            tool_name: "bash".to_owned(),
+            exit_code: None,
+            stderr: "sh: not found".to_owned(),
+        });
+        assert!(matches!(
+            command_failed,
+            RoutingError::ToolExecutionFailed {
+                exit_code: None,
+                ..
+            }
+        ));
+        assert_eq!(
+            command_failed.to_string(),
+            "Tool bash failed (exit code none): sh: not found"
+        );
+
+        let io_error = RoutingError::from(ToolError::Io("disk full".to_owned()));
+        assert!(matches!(io_error, RoutingError::Io(_)));
+
+        let panicked = RoutingError::from(ToolError::Panicked {
+            task_description: "bash command".to_owned(),
+            message: "index out of bounds".to_owned(),
+        });
+        assert!(!panicked.is_retryable());
+        assert_eq!(
+            panicked.to_string(),
+            "Internal panic in bash command: index out of bounds"
+        );
+    }
+
+    /// Tests that nested context layers keep the full chain and the root variant.
+    ///
+    /// # Errors
+    /// Returns an error if the wrapped result unexpectedly succeeds.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_context_chain_preserved() -> Result<()> {
+        use anyhow::Context as _;
+
+        let leaf: Result<()> = Err(RoutingError::ProviderTimeout {
+            provider: "groq".to_owned(),
+            timeout_ms: 500,
+        });
+        let step: Result<()> = leaf
+            .context("executing step 'Plan' of task 'Fix parser'")
+            .map_err(RoutingError::from);
+        let task = step
+            .context("executing task 'Fix parser'")
+            .map_err(RoutingError::from);
+        let Err(err) = task else {
+            return Err(RoutingError::Other("Expected the task to fail".to_owned()));
+        };
+
+        assert_eq!(
+            err.context_chain(),
+            vec![
+                "executing task 'Fix parser'".to_owned(),
+                "executing step 'Plan' of task 'Fix parser'".to_owned(),
+                "Provider groq timed out after 500ms".to_owned(),
+            ]
+        );
+        assert_eq!(
+            err.to_string(),
+            "executing task 'Fix parser': executing step 'Plan' of task 'Fix parser': \
+             Provider groq timed out after 500ms"
+        );
+        assert!(matches!(err.root(), RoutingError::ProviderTimeout { .. }));
+        assert!(err.is_retryable());
+        Ok(())
+    }
+
+    /// Tests that file access errors name the action, path and cause.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_file_access_error() {
+        let path = Path::new("threads").join("thread.json");
+        let err =
+            RoutingError::file_access("write thread file", &path)(io::Error::other("disk full"));
+
+        assert!(matches!(&err, RoutingError::FileAccess { path: failed, .. } if *failed == path));
+        assert_eq!(
+            err.to_string(),
+            format!("Failed to write thread file {}: disk full", path.display())
+        );
+        assert!(!err.is_retryable());
+    }
+}
@@ -0,0 +1,289 @@
+//! Versioning of the JSON formats persisted under `.merlin/`
+//!
+//! Every persisted document carries a `schema_version` field. Documents written
+//! before versioning was introduced have no such field and are treated as
+//! version 1. On load, a [`MigrationRegistry`] upgrades older documents one
+//! version at a time before they are deserialized into the current structs,
+//! and files that cannot be decoded at all are moved aside with [`quarantine`].
+
+use serde::de::DeserializeOwned;
+use serde_json::{Error as JsonError, Map, Value, from_str, from_value};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Field holding the schema version of a persisted document
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Version assumed for documents without a `schema_version` field
+pub const UNVERSIONED: u32 = 1;
+
+/// Directory (next to `tasks/` and `threads/`) that unreadable files are moved to
+pub const CORRUPT_DIR: &str = "corrupt";
+
+/// Upgrades a document by exactly one schema version, in place
+pub type Migration = fn(&mut Map<String, Value>);
+
+/// Errors raised while decoding a persisted document
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    /// The document is not valid JSON or does not match the current structs
+    #[error("invalid document: {0}")]
+    Json(#[from] JsonError),
+
+    /// The document is not a JSON object
+    #[error("document is not a JSON object")]
+    NotAnObject,
+
+    /// The `schema_version` field is not a positive integer
+    #[error("invalid schema version: {0}")]
+    InvalidVersion(Value),
+
+    /// The document was written by a newer version of Merlin
+    #[error("schema version {found} is newer than the supported version {supported}")]
+    Unsupported {
+        /// Version found in the document
+        found: u32,
+        /// Newest version this build understands
+        supported: u32,
+    },
+}
+
+impl SchemaError {
+    /// Returns whether the file is unreadable rather than just too new
+    ///
+    /// Files written by a newer Merlin are left in place so that version can
+    /// still read them; everything else is quarantined.
+    #[must_use]
+    pub const fn is_corrupt(&self) -> bool {
+        !matches!(self, Self::Unsupported { .. })
+    }
+}
+
+/// Ordered list of migrations for one persisted format
+///
+/// The migration at index `i` upgrades version `i + 1` to `i + 2`, so the
+/// current version is one more than the number of migrations.
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationRegistry {
+    migrations: &'static [Migration],
+}
+
+impl MigrationRegistry {
+    /// Creates a registry from migrations ordered oldest first
+    #[must_use]
+    pub const fn new(migrations: &'static [Migration]) -> Self {
+        Self { migrations }
+    }
+
+    /// Returns the version documents are written with
+    #[must_use]
+    pub fn current_version(&self) -> u32 {
+        u32::try_from(self.migrations.len())
+            .map_or(u32::MAX, |count| UNVERSIONED.saturating_add(count))
+    }
+
+    /// Upgrades a document to the current version and stamps it with that version
+    ///
+    /// # Errors
+    /// Returns an error if the document is not an object, has an invalid version,
+    /// or was written by a newer version than this registry knows about
+    pub fn upgrade(&self, mut document: Value) -> Result<Value, SchemaError> {
+        let supported = self.current_version();
+        let object = document.as_object_mut().ok_or(SchemaError::NotAnObject)?;
+        let found = match object.get(SCHEMA_VERSION_KEY) {
+            None => UNVERSIONED,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .filter(|version| *version >= UNVERSIONED)
+                .ok_or_else(|| SchemaError::InvalidVersion(version.clone()))?,
+        };
+        if found > supported {
+            return Err(SchemaError::Unsupported { found, supported });
+        }
+
+        let pending = usize::try_from(found - UNVERSIONED).unwrap_or(usize::MAX);
+        for migration in self.migrations.iter().skip(pending) {
+            migration(object);
+        }
+        object.insert(SCHEMA_VERSION_KEY.to_owned(), Value::from(supported));
+        Ok(document)
+    }
+
+    /// Parses, upgrades and deserializes a persisted JSON document
+    ///
+    /// # Errors
+    /// Returns an error if the JSON is malformed, the version is unsupported, or
+    /// the upgraded document does not match `T`
+    pub fn decode<T: DeserializeOwned>(&self, json: &str) -> Result<T, SchemaError> {
+        let document = self.upgrade(from_str(json)?)?;
+        Ok(from_value(document)?)
+    }
+
+    /// Stamps a freshly serialized document with the current version
+    ///
+    /// # Errors
+    /// Returns an error if the document is not a JSON object
+    pub fn stamp(&self, document: &mut Value) -> Result<(), SchemaError> {
+        let object = document.as_object_mut().ok_or(SchemaError::NotAnObject)?;
+        object.insert(
+            SCHEMA_VERSION_KEY.to_owned(),
+            Value::from(self.current_version()),
+        );
+        Ok(())
+    }
+}
+
+/// Moves an unreadable file into `corrupt_dir`, returning its new path
+///
+/// Existing files in `corrupt_dir` are never overwritten; a numeric suffix is
+/// appended to the file name instead.
+///
+/// # Errors
+/// Returns an error if the directory cannot be created or the file cannot be moved
+pub fn quarantine(path: &Path, corrupt_dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(corrupt_dir)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+
+    let mut target = corrupt_dir.join(file_name);
+    let mut suffix = 1u32;
+    while target.exists() {
+        let mut name = file_name.to_os_string();
+        name.push(format!(".{suffix}"));
+        target = corrupt_dir.join(name);
+        suffix += 1;
+    }
+
+    fs::rename(path, &target)?;
+    Ok(target)
+}
+
+/// Returns the quarantine directory for files stored in `storage_dir`
+///
+/// Storage directories live directly under `.merlin/`, so this is
+/// `.merlin/corrupt/`.
+#[must_use]
+pub fn corrupt_dir_for(storage_dir: &Path) -> PathBuf {
+    storage_dir.parent().map_or_else(
+        || storage_dir.join(CORRUPT_DIR),
+        |merlin_dir| merlin_dir.join(CORRUPT_DIR),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Adds a `tags` array to version 1 documents
+    fn add_tags(document: &mut Map<String, Value>) {
+        document.entry("tags").or_insert_with(|| json!([]));
+    }
+
+    /// Renames `name` to `title` in version 2 documents
+    fn rename_name(document: &mut Map<String, Value>) {
+        if let Some(name) = document.remove("name") {
+            document.insert("title".to_owned(), name);
+        }
+    }
+
+    const REGISTRY: MigrationRegistry = MigrationRegistry::new(&[add_tags, rename_name]);
+
+    /// Tests that unversioned documents go through every migration
+    ///
+    /// # Errors
+    /// Returns an error if a document cannot be upgraded
+    ///
+    /// # Panics
+    /// Panics if assertions fail
+    #[test]
+    fn test_unversioned_document_runs_every_migration() -> Result<(), SchemaError> {
+        let upgraded = REGISTRY.upgrade(json!({ "name": "old" }))?;
+        assert_eq!(
+            upgraded,
+            json!({ "title": "old", "tags": [], "schema_version": 3 })
+        );
+        Ok(())
+    }
+
+    /// Tests that versioned documents only run the pending migrations
+    ///
+    /// # Errors
+    /// Returns an error if a document cannot be upgraded
+    ///
+    /// # Panics
+    /// Panics if assertions fail
+    #[test]
+    fn test_versioned_document_skips_applied_migrations() -> Result<(), SchemaError> {
+        let upgraded = REGISTRY.upgrade(json!({ "schema_version": 2, "name": "mid" }))?;
+        assert_eq!(upgraded, json!({ "title": "mid", "schema_version": 3 }));
+        Ok(())
+    }
+
+    /// Tests that newer and malformed versions are rejected
+    ///
+    /// # Panics
+    /// Panics if assertions fail
+    #[test]
+    fn test_newer_and_invalid_versions_are_rejected() {
+        assert!(matches!(
+            REGISTRY.upgrade(json!({ "schema_version": 4 })),
+            Err(SchemaError::Unsupported {
+                found: 4,
+                supported: 3
+            })
+        ));
+        assert!(matches!(
+            REGISTRY.upgrade(json!({ "schema_version": "two" })),
+            Err(SchemaError::InvalidVersion(_))
+        ));
+        assert!(matches!(
+            REGISTRY.upgrade(json!([])),
+            Err(SchemaError::NotAnObject)
+        ));
+    }
+
+    /// Tests that only unreadable documents are treated as corrupt
+    ///
+    /// # Panics
+    /// Panics if assertions fail
+    #[test]
+    fn test_only_unreadable_documents_are_corrupt() {
+        let newer = REGISTRY.decode::<Value>(r#"{"schema_version": 9}"#);
+        let truncated = REGISTRY.decode::<Value>(r#"{"name": "#);
+        assert!(newer.is_err_and(|err| !err.is_corrupt()));
+        assert!(truncated.is_err_and(|err| err.is_corrupt()));
+    }
+
+    /// Tests that quarantining never overwrites earlier files
+    ///
+    /// # Errors
+    /// Returns an error if the files cannot be written or moved
+    ///
+    /// # Panics
+    /// Panics if assertions fail
+    #[test]
+    fn test_quarantine_never_overwrites() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let storage = dir.path().join("tasks");
+        fs::create_dir_all(&storage)?;
+        let corrupt = corrupt_dir_for(&storage);
+
+        fs::write(storage.join("bad.json"), "first")?;
+        let first = quarantine(&storage.join("bad.json"), &corrupt)?;
+        fs::write(storage.join("bad.json"), "second")?;
+        let second = quarantine(&storage.join("bad.json"), &corrupt)?;
+
+        assert_eq!(first, dir.path().join("corrupt").join("bad.json"));
+        assert_eq!(second, dir.path().join("corrupt").join("bad.json.1"));
+        assert_eq!(fs::read_to_string(first)?, "first");
+        assert_eq!(fs::read_to_string(second)?, "second");
+        assert!(!storage.join("bad.json").exists());
+        Ok(())
+    }
+}
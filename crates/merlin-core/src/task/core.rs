@@ -1,6 +1,7 @@
 //! Core task types and basic structures
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::path::PathBuf;
 use uuid::Uuid;
 
@@ -16,6 +17,12 @@ impl Default for TaskId {
     }
 }
 
+impl fmt::Display for TaskId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// Immutable task representation with metadata and execution state.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Task {
@@ -9,6 +9,7 @@ use crate::conversation::WorkUnit;
 use crate::{Response, TokenUsage};
 
 use super::core::TaskId;
+use super::timings::PhaseTimings;
 use super::validation::ValidationResult;
 
 /// Result of executing a task.
@@ -29,6 +30,9 @@ pub struct TaskResult {
     /// Optional `WorkUnit` containing the work performed for this task
     #[serde(skip_serializing_if = "Option::is_none")]
     pub work_unit: Option<WorkUnit>,
+    /// Time spent in each phase of the task
+    #[serde(default)]
+    pub timings: PhaseTimings,
 }
 
 /// File change operation.
@@ -8,6 +8,7 @@ mod analysis;
 mod core;
 mod decomposition;
 mod execution;
+mod timings;
 mod validation;
 
 // Re-export all public types
@@ -15,4 +16,5 @@ pub use analysis::*;
 pub use core::*;
 pub use decomposition::*;
 pub use execution::*;
+pub use timings::{PhaseTimings, TIMING_MARKER};
 pub use validation::*;
@@ -0,0 +1,154 @@
+//! Time spent in each phase of executing a step or task
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+
+/// Marks output lines reporting timings, so they can be told apart from responses
+pub const TIMING_MARKER: &str = "⏱";
+
+/// Milliseconds spent in each phase of a step, a task, or many tasks added together
+///
+/// Tool time is summed over calls, so tools running concurrently can add up to
+/// more than the wall-clock time of the step.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTimings {
+    /// Building the context (retrieval and prompt assembly)
+    pub context_ms: u64,
+    /// Waiting for model providers
+    pub provider_ms: u64,
+    /// Running tools called by agent code, by tool name
+    pub tool_ms: BTreeMap<String, u64>,
+    /// Validating results and exit requirements
+    pub validation_ms: u64,
+}
+
+impl PhaseTimings {
+    /// Total time spent in tools
+    #[must_use]
+    pub fn tools_ms(&self) -> u64 {
+        self.tool_ms.values().sum()
+    }
+
+    /// Total time over all phases
+    #[must_use]
+    pub fn total_ms(&self) -> u64 {
+        self.context_ms + self.provider_ms + self.tools_ms() + self.validation_ms
+    }
+
+    /// Whether no time was recorded in any phase
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.total_ms() == 0
+    }
+
+    /// Adds `duration_ms` spent in `tool`
+    pub fn record_tool(&mut self, tool: &str, duration_ms: u64) {
+        *self.tool_ms.entry(tool.to_owned()).or_default() += duration_ms;
+    }
+
+    /// Adds the time of every phase of `other`
+    pub fn add(&mut self, other: &Self) {
+        self.context_ms += other.context_ms;
+        self.provider_ms += other.provider_ms;
+        for (tool, duration_ms) in &other.tool_ms {
+            self.record_tool(tool, *duration_ms);
+        }
+        self.validation_ms += other.validation_ms;
+    }
+}
+
+/// Formats milliseconds as `850ms` below a second and `2.4s` above
+fn format_ms(duration_ms: u64) -> String {
+    if duration_ms < 1000 {
+        format!("{duration_ms}ms")
+    } else {
+        format!("{:.1}s", duration_ms as f64 / 1000.0)
+    }
+}
+
+impl Display for PhaseTimings {
+    /// Compact one-line breakdown, e.g.
+    /// `context 120ms · provider 2.4s · tools 80ms (readFile 50ms, bash 30ms) · validation 5ms`
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "context {} · provider {} · tools {}",
+            format_ms(self.context_ms),
+            format_ms(self.provider_ms),
+            format_ms(self.tools_ms())
+        )?;
+        if !self.tool_ms.is_empty() {
+            let tools = self
+                .tool_ms
+                .iter()
+                .map(|(tool, duration_ms)| format!("{tool} {}", format_ms(*duration_ms)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(f, " ({tools})")?;
+        }
+        write!(f, " · validation {}", format_ms(self.validation_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that adding timings sums each phase and each tool.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_add_sums_phases_and_tools() {
+        let mut total = PhaseTimings {
+            context_ms: 10,
+            provider_ms: 100,
+            validation_ms: 1,
+            ..PhaseTimings::default()
+        };
+        total.record_tool("readFile", 5);
+
+        let mut step = PhaseTimings {
+            provider_ms: 200,
+            validation_ms: 2,
+            ..PhaseTimings::default()
+        };
+        step.record_tool("readFile", 7);
+        step.record_tool("bash", 30);
+        total.add(&step);
+
+        assert_eq!(total.context_ms, 10);
+        assert_eq!(total.provider_ms, 300);
+        assert_eq!(total.validation_ms, 3);
+        assert_eq!(total.tool_ms.get("readFile"), Some(&12));
+        assert_eq!(total.tool_ms.get("bash"), Some(&30));
+        assert_eq!(total.tools_ms(), 42);
+        assert_eq!(total.total_ms(), 355);
+    }
+
+    /// Tests the compact timing line.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_display_is_compact() {
+        let mut timings = PhaseTimings {
+            context_ms: 120,
+            provider_ms: 2_400,
+            validation_ms: 5,
+            ..PhaseTimings::default()
+        };
+        assert_eq!(
+            timings.to_string(),
+            "context 120ms · provider 2.4s · tools 0ms · validation 5ms"
+        );
+
+        timings.record_tool("readFile", 50);
+        timings.record_tool("bash", 30);
+        assert_eq!(
+            timings.to_string(),
+            "context 120ms · provider 2.4s · tools 80ms (bash 30ms, readFile 50ms) · validation 5ms"
+        );
+    }
+}
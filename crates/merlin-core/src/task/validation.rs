@@ -1,6 +1,7 @@
 //! Validation types and results
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 /// Validation result with pass/fail status and detailed feedback.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +52,8 @@ pub enum ValidationStage {
     Test,
     /// Linting checks
     Lint,
+    /// Formatting of changed files
+    Format,
 }
 
 /// Error severity level.
@@ -79,4 +82,7 @@ pub struct StageResult {
     pub details: String,
     /// Quality score for this stage (0.0 to 1.0)
     pub score: f64,
+    /// Files the stage reformatted
+    #[serde(default)]
+    pub reformatted: Vec<PathBuf>,
 }
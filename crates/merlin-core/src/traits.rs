@@ -21,4 +21,10 @@ pub trait ModelProvider: Send + Sync {
 
     /// Estimates the cost in USD for processing the given context.
     fn estimate_cost(&self, context: &Context) -> f64;
+
+    /// Whether repeated context is served from a prompt cache, making it cheaper
+    /// to send again than to condense.
+    fn supports_prompt_caching(&self) -> bool {
+        false
+    }
 }
@@ -14,6 +14,9 @@ pub struct Query {
     pub conversation_id: Option<String>,
     /// Paths to files that provide additional context for the query.
     pub files_context: Vec<PathBuf>,
+    /// Files pinned to the conversation, always included ahead of retrieved files.
+    #[serde(default)]
+    pub pinned_files: Vec<PathBuf>,
     /// Routing context for test infrastructure and debugging.
     #[serde(default)]
     pub routing_context: RoutingContext,
@@ -26,6 +29,7 @@ impl Query {
             text: text.into(),
             conversation_id: None,
             files_context: Vec::default(),
+            pinned_files: Vec::default(),
             routing_context: RoutingContext::default(),
         }
     }
@@ -37,6 +41,13 @@ impl Query {
         self
     }
 
+    /// Sets the files pinned to the conversation this query belongs to.
+    #[must_use]
+    pub fn with_pinned_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.pinned_files = files;
+        self
+    }
+
     /// Sets the routing context for this query.
     #[must_use]
     pub fn with_routing_context(mut self, routing_context: RoutingContext) -> Self {
@@ -78,6 +89,14 @@ impl TokenUsage {
     pub fn total(&self) -> u64 {
         self.input + self.output + self.cache_read + self.cache_write
     }
+
+    /// Adds the counts of `other` to this usage.
+    pub const fn accumulate(&mut self, other: &Self) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_read += other.cache_read;
+        self.cache_write += other.cache_write;
+    }
 }
 
 /// Context provided to a model for generating responses.
@@ -121,7 +140,14 @@ impl Context {
     pub fn files_to_string(&self) -> String {
         self.files
             .iter()
-            .map(|file| format!("// File: {}\n{}\n", file.path.display(), file.content))
+            .map(|file| {
+                let label = if file.pinned { " (pinned)" } else { "" };
+                format!(
+                    "// File: {}{label}\n{}\n",
+                    file.path.display(),
+                    file.content
+                )
+            })
             .collect::<Vec<_>>()
             .join("\n")
     }
@@ -131,6 +157,21 @@ impl Context {
         let files_len: usize = self.files.iter().map(|file| file.content.len()).sum();
         (self.system_prompt.len() + files_len) / 4
     }
+
+    /// Drops files, last first, until the estimate is at most `max_tokens`.
+    ///
+    /// Pinned files are kept. Returns how many files were dropped.
+    pub fn truncate_files(&mut self, max_tokens: usize) -> usize {
+        let mut dropped = 0;
+        while self.token_estimate() > max_tokens {
+            let Some(index) = self.files.iter().rposition(|file| !file.pinned) else {
+                break;
+            };
+            self.files.remove(index);
+            dropped += 1;
+        }
+        dropped
+    }
 }
 
 /// A file and its content provided as context to a model.
@@ -140,6 +181,9 @@ pub struct FileContext {
     pub path: PathBuf,
     /// Text content of the file.
     pub content: String,
+    /// Whether the file is pinned to the conversation rather than retrieved.
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 impl FileContext {
@@ -149,15 +193,23 @@ impl FileContext {
         let content =
             read_to_string(path).map_err(|_| Error::FileNotFound(path.display().to_string()))?;
 
-        Ok(Self {
-            path: path.clone(),
-            content,
-        })
+        Ok(Self::new(path.clone(), content))
     }
 
     /// Creates a new file context with the given path and content.
     pub fn new(path: PathBuf, content: String) -> Self {
-        Self { path, content }
+        Self {
+            path,
+            content,
+            pinned: false,
+        }
+    }
+
+    /// Marks this file as pinned to the conversation.
+    #[must_use]
+    pub fn into_pinned(mut self) -> Self {
+        self.pinned = true;
+        self
     }
 }
 
@@ -242,10 +294,12 @@ mod tests {
         let files = vec![
             FileContext::new(PathBuf::from("file1.rs"), "content1".to_owned()),
             FileContext::new(PathBuf::from("file2.rs"), "content2".to_owned()),
+            FileContext::new(PathBuf::from("spec.md"), "spec".to_owned()).into_pinned(),
         ];
         let context = Context::new("prompt").with_files(files);
         let result = context.files_to_string();
-        assert!(result.contains("// File: file1.rs"));
+        assert!(result.contains("// File: spec.md (pinned)\nspec"));
+        assert!(result.contains("// File: file1.rs\n"));
         assert!(result.contains("content1"));
         assert!(result.contains("// File: file2.rs"));
         assert!(result.contains("content2"));
@@ -263,6 +317,28 @@ mod tests {
         assert_eq!(context.token_estimate(), 26);
     }
 
+    /// Tests dropping the last unpinned files to fit a token budget.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_context_truncate_files() {
+        let mut pinned = FileContext::new(PathBuf::from("spec.md"), "a".repeat(400));
+        pinned.pinned = true;
+        let files = vec![
+            FileContext::new(PathBuf::from("first.rs"), "b".repeat(400)),
+            pinned,
+            FileContext::new(PathBuf::from("last.rs"), "c".repeat(400)),
+        ];
+        let mut context = Context::new("").with_files(files);
+
+        assert_eq!(context.truncate_files(200), 1);
+        assert_eq!(context.files.len(), 2);
+        assert_eq!(context.truncate_files(0), 1);
+        assert_eq!(context.files.len(), 1);
+        assert!(context.files.iter().all(|file| file.pinned));
+    }
+
     // REMOVED: test_file_context_new - Constructor test
 
     /// Tests loading file context from filesystem path.
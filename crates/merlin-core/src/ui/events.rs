@@ -1,10 +1,13 @@
 use crate::conversation::{ThreadId, WorkUnit};
+use crate::sync::IgnoreLock as _;
 use crate::task::{TaskId, TaskResult};
 use merlin_tooling::ToolError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::Arc;
+use std::fmt::{Debug, Formatter, Result as FmtResult};
+use std::sync::{Arc, Mutex as StdMutex};
 use tokio::sync::Mutex;
+use tokio::sync::oneshot::{self, Receiver, Sender};
 
 /// UI event that tasks send to update display.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,6 +114,29 @@ pub enum UiEvent {
         /// Error message
         error: String,
     },
+    /// Step of the task's plan has started
+    PlanStepStarted {
+        /// ID of the task
+        task_id: TaskId,
+        /// Number of the step, starting at 1
+        step: usize,
+        /// Number of steps in the plan
+        total: usize,
+        /// Title of the step
+        title: String,
+    },
+    /// Task asked the user a question and waits for the answer
+    UserQuestion {
+        /// ID of the task asking
+        task_id: TaskId,
+        /// Question text
+        question: String,
+        /// Suggested answers, empty for a free-form answer
+        options: Vec<String>,
+        /// Resumes the task with the answer, not serialized
+        #[serde(skip)]
+        answer: AnswerSender,
+    },
     /// Tool call started
     ToolCallStarted {
         /// ID of the task making the tool call
@@ -154,6 +180,67 @@ pub enum UiEvent {
         /// Stage description
         stage: String,
     },
+    /// Periodic snapshot of session-wide statistics
+    SessionStats {
+        /// Estimated cost in USD of every request made this session
+        session_cost: f64,
+    },
+}
+
+/// Slot holding the sender until the question is answered or declined
+type AnswerSlot = Arc<StdMutex<Option<Sender<String>>>>;
+
+/// Answers a [`UiEvent::UserQuestion`], shared by every clone of the event.
+///
+/// Only the first answer reaches the task; later ones are ignored.
+#[derive(Clone, Default)]
+pub struct AnswerSender {
+    /// Sender of the answer, taken by the first answer
+    sender: AnswerSlot,
+}
+
+impl AnswerSender {
+    /// Creates a sender and the receiver the asking task awaits
+    #[must_use]
+    pub fn new() -> (Self, Receiver<String>) {
+        let (sender, receiver) = oneshot::channel();
+        (
+            Self {
+                sender: Arc::new(StdMutex::new(Some(sender))),
+            },
+            receiver,
+        )
+    }
+
+    /// Sends `answer` to the task, returning whether it was still waiting
+    pub fn answer(&self, answer: String) -> bool {
+        self.sender
+            .lock_ignore_poison()
+            .take()
+            .is_some_and(|sender| sender.send(answer).is_ok())
+    }
+
+    /// Declines to answer, failing the task's question
+    pub fn decline(&self) {
+        drop(self.sender.lock_ignore_poison().take());
+    }
+
+    /// Whether the question was already answered or declined
+    #[must_use]
+    pub fn is_settled(&self) -> bool {
+        self.sender
+            .lock_ignore_poison()
+            .as_ref()
+            .is_none_or(Sender::is_closed)
+    }
+}
+
+impl Debug for AnswerSender {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("AnswerSender")
+            .field("settled", &self.is_settled())
+            .finish()
+    }
 }
 
 /// Progress information for a task.
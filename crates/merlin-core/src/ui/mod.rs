@@ -4,38 +4,47 @@
 use crate::conversation::{ThreadId, WorkUnit};
 use crate::task::{TaskId, TaskResult};
 use merlin_tooling::ToolError;
+use queue::EventQueue;
 use std::sync::Arc;
-use tokio::spawn;
-use tokio::sync::{Mutex, mpsc};
-use tracing::warn;
+use tokio::sync::{Mutex, oneshot};
 
 /// Event types for UI updates
 pub mod events;
+mod queue;
 
 // Re-exports
-pub use events::{MessageLevel, TaskProgress, UiEvent};
+pub use events::{AnswerSender, MessageLevel, TaskProgress, UiEvent};
+pub use queue::UiEventReceiver;
+
+/// Default number of queued events beyond which a UI channel coalesces
+pub const DEFAULT_UI_EVENT_CAPACITY: usize = 256;
 
 /// UI update channel - REQUIRED for all task execution
-#[derive(Clone)]
+///
+/// Bounded: events queue up to a capacity while the receiver is busy, and
+/// output and progress updates are coalesced rather than queued beyond it.
+/// Task lifecycle, step and tool call events are never dropped.
 pub struct UiChannel {
-    /// Sender used to deliver `UiEvent`s to the UI thread (bounded for backpressure)
-    sender: mpsc::Sender<UiEvent>,
+    /// Queue shared with the receiver
+    queue: Arc<EventQueue>,
 }
 
 impl UiChannel {
-    /// Creates a UI channel from an existing bounded sender
-    pub fn from_sender(sender: mpsc::Sender<UiEvent>) -> Self {
-        Self { sender }
+    /// Creates a channel that coalesces once `capacity` events are queued
+    pub fn bounded(capacity: usize) -> (Self, UiEventReceiver) {
+        let queue = Arc::new(EventQueue::new(capacity));
+        let receiver = UiEventReceiver::new(Arc::clone(&queue));
+        (Self { queue }, receiver)
     }
 
-    /// Sends a UI event (spawns a task to avoid blocking the caller)
+    /// Sends a UI event without blocking, coalescing it if the receiver is behind
     pub fn send(&self, event: UiEvent) {
-        let sender = self.sender.clone();
-        spawn(async move {
-            if let Err(error) = sender.send(event).await {
-                warn!("Failed to send UI event: {}", error);
-            }
-        });
+        self.queue.push(event);
+    }
+
+    /// Whether the receiver was dropped, so events are discarded
+    pub fn is_closed(&self) -> bool {
+        self.queue.is_closed()
     }
 
     /// Sends a task started event
@@ -113,6 +122,35 @@ impl UiChannel {
         });
     }
 
+    /// Sends plan step started event for step `step` of `total`
+    pub fn plan_step_started(&self, task_id: TaskId, step: usize, total: usize, title: String) {
+        self.send(UiEvent::PlanStepStarted {
+            task_id,
+            step,
+            total,
+            title,
+        });
+    }
+
+    /// Sends a question to the user, returning the receiver of the answer
+    ///
+    /// The receiver fails if the question is declined or nobody is listening.
+    pub fn ask_user(
+        &self,
+        task_id: TaskId,
+        question: String,
+        options: Vec<String>,
+    ) -> oneshot::Receiver<String> {
+        let (answer, receiver) = AnswerSender::new();
+        self.send(UiEvent::UserQuestion {
+            task_id,
+            question,
+            options,
+            answer,
+        });
+        receiver
+    }
+
     /// Sends task output
     pub fn output(&self, task_id: TaskId, output: String) {
         self.send(UiEvent::TaskOutput { task_id, output });
@@ -131,3 +169,18 @@ impl UiChannel {
         self.send(UiEvent::TaskFailed { task_id, error });
     }
 }
+
+impl Clone for UiChannel {
+    fn clone(&self) -> Self {
+        self.queue.add_sender();
+        Self {
+            queue: Arc::clone(&self.queue),
+        }
+    }
+}
+
+impl Drop for UiChannel {
+    fn drop(&mut self) {
+        self.queue.remove_sender();
+    }
+}
@@ -0,0 +1,417 @@
+//! Bounded queue of UI events that coalesces bursts.
+//!
+//! Fast tool output would otherwise flood the render loop, which draws after
+//! every event. Events wait in a queue of fixed capacity and are merged while
+//! the UI is busy:
+//! - consecutive `TaskOutput` events of a task become one, lines joined
+//! - progress snapshots (`TaskProgress`, `WorkUnitProgress`,
+//!   `EmbeddingProgress`, `SessionStats`) replace the queued snapshot they
+//!   supersede
+//!
+//! Once the queue is full, output is appended to the task's last queued
+//! output and new progress snapshots are dropped. Every other event (task
+//! lifecycle, steps, tool calls) is always queued, even over capacity.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::Notify;
+
+use super::events::UiEvent;
+use crate::sync::IgnoreLock as _;
+use crate::task::TaskId;
+
+/// Queue shared by the senders and the receiver of a UI channel
+pub struct EventQueue {
+    /// Queued events and bookkeeping
+    state: Mutex<QueueState>,
+    /// Wakes the receiver when an event is queued or the last sender leaves
+    ready: Notify,
+}
+
+/// Contents of an [`EventQueue`]
+struct QueueState {
+    /// Events in delivery order
+    events: VecDeque<UiEvent>,
+    /// Number of queued events beyond which output is merged and snapshots dropped
+    capacity: usize,
+    /// Events merged into queued ones or dropped
+    coalesced: u64,
+    /// Number of live senders
+    senders: usize,
+    /// Whether the receiver was dropped
+    receiver_closed: bool,
+}
+
+impl EventQueue {
+    /// Creates a queue with one sender
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                events: VecDeque::new(),
+                capacity: capacity.max(1),
+                coalesced: 0,
+                senders: 1,
+                receiver_closed: false,
+            }),
+            ready: Notify::new(),
+        }
+    }
+
+    /// Queues `event`, merging it into a queued event where possible
+    ///
+    /// Events sent after the receiver was dropped are discarded.
+    pub fn push(&self, event: UiEvent) {
+        let mut state = self.state.lock_ignore_poison();
+        if state.receiver_closed {
+            return;
+        }
+        state.push(event);
+        drop(state);
+        self.ready.notify_one();
+    }
+
+    /// Registers a cloned sender
+    pub fn add_sender(&self) {
+        self.state.lock_ignore_poison().senders += 1;
+    }
+
+    /// Unregisters a dropped sender, waking the receiver after the last one
+    pub fn remove_sender(&self) {
+        let mut state = self.state.lock_ignore_poison();
+        state.senders = state.senders.saturating_sub(1);
+        let last = state.senders == 0;
+        drop(state);
+        if last {
+            self.ready.notify_one();
+        }
+    }
+
+    /// Whether the receiver was dropped
+    pub fn is_closed(&self) -> bool {
+        self.state.lock_ignore_poison().receiver_closed
+    }
+}
+
+impl QueueState {
+    /// Queues `event` under the coalescing policy
+    fn push(&mut self, event: UiEvent) {
+        let full = self.events.len() >= self.capacity;
+        match event {
+            UiEvent::TaskOutput { task_id, output } => {
+                if self.merge_output(task_id, &output, full) {
+                    self.coalesced += 1;
+                } else {
+                    self.events
+                        .push_back(UiEvent::TaskOutput { task_id, output });
+                }
+            }
+            snapshot if is_snapshot(&snapshot) => {
+                if let Some(queued) = self
+                    .events
+                    .iter_mut()
+                    .find(|queued| supersedes(&snapshot, queued))
+                {
+                    *queued = snapshot;
+                    self.coalesced += 1;
+                } else if full {
+                    self.coalesced += 1;
+                } else {
+                    self.events.push_back(snapshot);
+                }
+            }
+            other => self.events.push_back(other),
+        }
+    }
+
+    /// Appends `output` to a queued output of `task_id`, returning whether it was merged
+    ///
+    /// Only the last queued event is considered unless the queue is `full`, in
+    /// which case the task's last queued event is, so output never jumps ahead
+    /// of the task's own steps.
+    fn merge_output(&mut self, task_id: TaskId, output: &str, full: bool) -> bool {
+        let target = if full {
+            self.events
+                .iter_mut()
+                .rev()
+                .find(|queued| event_task(queued) == Some(task_id))
+        } else {
+            self.events.back_mut()
+        };
+        match target {
+            Some(UiEvent::TaskOutput {
+                task_id: queued_task,
+                output: queued,
+            }) if *queued_task == task_id => {
+                queued.push('\n');
+                queued.push_str(output);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Whether `event` is a progress snapshot a later one makes obsolete
+const fn is_snapshot(event: &UiEvent) -> bool {
+    matches!(
+        event,
+        UiEvent::TaskProgress { .. }
+            | UiEvent::WorkUnitProgress { .. }
+            | UiEvent::EmbeddingProgress { .. }
+            | UiEvent::SessionStats { .. }
+    )
+}
+
+/// Whether the snapshot `newer` replaces the queued `older`
+fn supersedes(newer: &UiEvent, older: &UiEvent) -> bool {
+    match (newer, older) {
+        (
+            UiEvent::TaskProgress { task_id: newer, .. },
+            UiEvent::TaskProgress { task_id: older, .. },
+        )
+        | (
+            UiEvent::WorkUnitProgress { task_id: newer, .. },
+            UiEvent::WorkUnitProgress { task_id: older, .. },
+        ) => newer == older,
+        (UiEvent::EmbeddingProgress { .. }, UiEvent::EmbeddingProgress { .. })
+        | (UiEvent::SessionStats { .. }, UiEvent::SessionStats { .. }) => true,
+        _ => false,
+    }
+}
+
+/// Task an event belongs to, if any
+const fn event_task(event: &UiEvent) -> Option<TaskId> {
+    match *event {
+        UiEvent::TaskStarted { task_id, .. }
+        | UiEvent::TaskProgress { task_id, .. }
+        | UiEvent::WorkUnitStarted { task_id, .. }
+        | UiEvent::WorkUnitProgress { task_id, .. }
+        | UiEvent::TaskOutput { task_id, .. }
+        | UiEvent::TaskCompleted { task_id, .. }
+        | UiEvent::TaskFailed { task_id, .. }
+        | UiEvent::TaskRetrying { task_id, .. }
+        | UiEvent::TaskStepStarted { task_id, .. }
+        | UiEvent::TaskStepCompleted { task_id, .. }
+        | UiEvent::TaskStepFailed { task_id, .. }
+        | UiEvent::PlanStepStarted { task_id, .. }
+        | UiEvent::UserQuestion { task_id, .. }
+        | UiEvent::ToolCallStarted { task_id, .. }
+        | UiEvent::ToolCallCompleted { task_id, .. }
+        | UiEvent::ThinkingUpdate { task_id, .. } => Some(task_id),
+        UiEvent::SubtaskSpawned { parent_id, .. } => Some(parent_id),
+        UiEvent::SystemMessage { .. }
+        | UiEvent::EmbeddingProgress { .. }
+        | UiEvent::SessionStats { .. } => None,
+    }
+}
+
+/// Receiving end of a [`UiChannel`](super::UiChannel)
+pub struct UiEventReceiver {
+    /// Queue shared with the senders
+    queue: Arc<EventQueue>,
+}
+
+impl UiEventReceiver {
+    /// Creates the receiver of `queue`
+    pub const fn new(queue: Arc<EventQueue>) -> Self {
+        Self { queue }
+    }
+
+    /// Waits for the next event, or `None` once every sender is dropped and the queue is empty
+    ///
+    /// Cancel safe: an event is only taken from the queue when it is returned.
+    pub async fn recv(&mut self) -> Option<UiEvent> {
+        loop {
+            {
+                let mut state = self.queue.state.lock_ignore_poison();
+                if let Some(event) = state.events.pop_front() {
+                    return Some(event);
+                }
+                if state.senders == 0 {
+                    return None;
+                }
+            }
+            self.queue.ready.notified().await;
+        }
+    }
+
+    /// Takes the next event if one is queued
+    pub fn try_recv(&mut self) -> Option<UiEvent> {
+        self.queue.state.lock_ignore_poison().events.pop_front()
+    }
+
+    /// Number of events merged into queued ones or dropped so far
+    pub fn coalesced_events(&self) -> u64 {
+        self.queue.state.lock_ignore_poison().coalesced
+    }
+}
+
+impl Drop for UiEventReceiver {
+    fn drop(&mut self) {
+        let mut state = self.queue.state.lock_ignore_poison();
+        state.receiver_closed = true;
+        state.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{TaskProgress, UiChannel};
+    use super::*;
+    use tokio::spawn;
+    use tokio::task::{JoinError, yield_now};
+
+    /// Number of output events pushed by the flood tests
+    const FLOOD: usize = 100_000;
+
+    /// Builds a progress update of `task_id` at `current`
+    fn progress(task_id: TaskId, current: u64) -> UiEvent {
+        UiEvent::TaskProgress {
+            task_id,
+            progress: TaskProgress {
+                stage: "executing".to_owned(),
+                current,
+                total: None,
+                message: String::new(),
+            },
+        }
+    }
+
+    /// Builds a step started event of `task_id`
+    fn step(task_id: TaskId, step_id: usize) -> UiEvent {
+        UiEvent::TaskStepStarted {
+            task_id,
+            step_id: step_id.to_string(),
+            step_type: "tool_call".to_owned(),
+            content: String::new(),
+        }
+    }
+
+    /// Sends [`FLOOD`] output lines, yielding now and then so the receiver runs
+    async fn flood_output(channel: UiChannel, task_id: TaskId) {
+        for line in 0..FLOOD {
+            channel.output(task_id, line.to_string());
+            if line % 1000 == 0 {
+                yield_now().await;
+            }
+        }
+    }
+
+    /// Tests that 100k interleaved output events arrive as a bounded number of
+    /// messages, with each task's output complete and in order.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_output_flood_is_coalesced_in_order() {
+        let (channel, mut receiver) = UiChannel::bounded(64);
+        let tasks = [TaskId::default(), TaskId::default()];
+        for line in 0..FLOOD {
+            let task_id = tasks[line % 2];
+            channel.output(task_id, format!("line {line}"));
+            if line % 10_000 == 0 {
+                channel.send(step(task_id, line));
+                channel.send(progress(task_id, line as u64));
+            }
+        }
+        channel.send(UiEvent::TaskStepCompleted {
+            task_id: tasks[0],
+            step_id: "done".to_owned(),
+        });
+
+        let mut messages = Vec::new();
+        while let Some(event) = receiver.try_recv() {
+            messages.push(event);
+        }
+
+        assert!(
+            messages.len() <= 64 + 3 * 10 + 1,
+            "{} messages",
+            messages.len()
+        );
+        assert_eq!(
+            receiver.coalesced_events(),
+            (FLOOD + 20 - messages.len() + 1) as u64
+        );
+        for (index, task_id) in tasks.into_iter().enumerate() {
+            let lines: Vec<String> = messages
+                .iter()
+                .filter_map(|event| match event {
+                    UiEvent::TaskOutput {
+                        task_id: output_task,
+                        output,
+                    } if *output_task == task_id => Some(output.lines()),
+                    _ => None,
+                })
+                .flatten()
+                .map(str::to_owned)
+                .collect();
+            let expected: Vec<String> = (index..FLOOD)
+                .step_by(2)
+                .map(|line| format!("line {line}"))
+                .collect();
+            assert_eq!(lines, expected);
+        }
+        let steps = messages
+            .iter()
+            .filter(|event| matches!(event, UiEvent::TaskStepStarted { .. }))
+            .count();
+        assert_eq!(steps, 10);
+        assert!(matches!(
+            messages.last(),
+            Some(UiEvent::TaskStepCompleted { .. })
+        ));
+    }
+
+    /// Tests that a newer progress update replaces the queued one in place.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_progress_replaces_queued_update() {
+        let (channel, mut receiver) = UiChannel::bounded(8);
+        let task_id = TaskId::default();
+        channel.send(progress(task_id, 1));
+        channel.output(task_id, "first".to_owned());
+        channel.send(progress(task_id, 2));
+
+        assert!(matches!(
+            receiver.try_recv(),
+            Some(UiEvent::TaskProgress { progress, .. }) if progress.current == 2
+        ));
+        assert!(matches!(
+            receiver.try_recv(),
+            Some(UiEvent::TaskOutput { .. })
+        ));
+        assert!(receiver.try_recv().is_none());
+        assert_eq!(receiver.coalesced_events(), 1);
+    }
+
+    /// Tests that the receiver drains a concurrent flood and ends once senders are gone.
+    ///
+    /// # Errors
+    /// Returns an error if the producer task panics.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_receiver_ends_after_senders_drop() -> Result<(), JoinError> {
+        let (channel, mut receiver) = UiChannel::bounded(16);
+        let task_id = TaskId::default();
+        let producer = spawn(flood_output(channel, task_id));
+
+        let mut messages = 0;
+        let mut lines = 0;
+        while let Some(event) = receiver.recv().await {
+            messages += 1;
+            if let UiEvent::TaskOutput { output, .. } = event {
+                lines += output.lines().count();
+            }
+        }
+        producer.await?;
+        assert_eq!(lines, FLOOD);
+        assert!(messages < FLOOD, "{messages} messages");
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+//! Helpers shared by the language backends.
+//!
+//! Backends index a project by parsing every source file once; these helpers
+//! cover the parts that do not depend on the language: scanning the project,
+//! updating the index after a file changes, ranking symbol search results and
+//! finding textual references.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::result::Result as StdResult;
+
+use merlin_core::{CoreResult as Result, FileContext};
+use walkdir::{DirEntry, WalkDir};
+
+use crate::provider::{RelatedFile, SearchQuery, SearchResult, SymbolInfo, SymbolKind};
+
+/// Iterates over the source files under `project_root` accepted by `is_source`
+///
+/// Hidden entries and directories named in `ignored_dirs` are skipped.
+pub fn source_files(
+    project_root: &Path,
+    ignored_dirs: &'static [&'static str],
+    is_source: fn(&Path) -> bool,
+) -> impl Iterator<Item = PathBuf> {
+    WalkDir::new(project_root)
+        .into_iter()
+        .filter_entry(move |entry| !is_ignored(entry, ignored_dirs))
+        .filter_map(StdResult::ok)
+        .filter(move |entry| entry.file_type().is_file() && is_source(entry.path()))
+        .map(DirEntry::into_path)
+}
+
+/// Checks if a directory entry should be skipped during a project scan
+fn is_ignored(entry: &DirEntry, ignored_dirs: &[&str]) -> bool {
+    if entry.depth() == 0 {
+        return false;
+    }
+    let name = entry.file_name().to_string_lossy();
+    name.starts_with('.') || (entry.file_type().is_dir() && ignored_dirs.contains(&name.as_ref()))
+}
+
+/// Returns true if a scan of `project_root` would index `path`
+///
+/// Mirrors [`source_files`]: the path must be inside the project, accepted by
+/// `is_source`, and not below a hidden directory or one named in `ignored_dirs`.
+pub fn is_indexed_path(
+    project_root: &Path,
+    path: &Path,
+    ignored_dirs: &[&str],
+    is_source: fn(&Path) -> bool,
+) -> bool {
+    let Ok(relative) = path.strip_prefix(project_root) else {
+        return false;
+    };
+    let mut components = relative.components().rev();
+    let file_name = components.next();
+    is_source(path)
+        && file_name.is_some_and(|name| !name.as_os_str().to_string_lossy().starts_with('.'))
+        && components.all(|dir| {
+            let name = dir.as_os_str().to_string_lossy();
+            !name.starts_with('.') && !ignored_dirs.contains(&name.as_ref())
+        })
+}
+
+/// Re-parses one changed file of an index keyed by path
+///
+/// `new_text` of `None` means the file was deleted. If the new content does not
+/// parse, the stale entry is dropped and the parse error returned.
+///
+/// # Errors
+/// Returns the error of `parse`
+pub fn reindex_file<Parsed>(
+    index: &mut HashMap<PathBuf, Parsed>,
+    file: &Path,
+    new_text: Option<&str>,
+    parse: impl FnOnce(&str) -> Result<Parsed>,
+) -> Result<()> {
+    let Some(text) = new_text else {
+        index.remove(file);
+        return Ok(());
+    };
+    match parse(text) {
+        Ok(parsed) => {
+            index.insert(file.to_path_buf(), parsed);
+            Ok(())
+        }
+        Err(error) => {
+            index.remove(file);
+            Err(error)
+        }
+    }
+}
+
+/// Filters `symbols` by the query's name and ranks them into a search result
+///
+/// Names match case-insensitively by substring. Exact matches come first, then
+/// symbols are ordered by location, and the files of the kept symbols are
+/// returned as related context.
+pub fn rank_symbols(
+    symbols: impl Iterator<Item = SymbolInfo>,
+    query: &SearchQuery,
+) -> SearchResult {
+    let needle = query.symbol_name.as_deref().map(str::to_lowercase);
+    let mut symbols: Vec<SymbolInfo> = symbols
+        .filter(|symbol| {
+            needle
+                .as_deref()
+                .is_none_or(|needle| symbol.name.to_lowercase().contains(needle))
+        })
+        .collect();
+
+    // Exact matches first, then stable by location
+    symbols.sort_by(|left, right| {
+        let exact =
+            |symbol: &SymbolInfo| query.symbol_name.as_deref() != Some(symbol.name.as_str());
+        exact(left)
+            .cmp(&exact(right))
+            .then_with(|| left.file_path.cmp(&right.file_path))
+            .then_with(|| left.line.cmp(&right.line))
+    });
+    symbols.truncate(query.max_results);
+
+    let mut related_files: Vec<RelatedFile> = Vec::new();
+    for symbol in &symbols {
+        if related_files
+            .iter()
+            .all(|related| related.file.path != symbol.file_path)
+            && let Ok(file) = FileContext::from_path(&symbol.file_path)
+        {
+            related_files.push(RelatedFile::direct(file));
+        }
+    }
+
+    SearchResult {
+        symbols,
+        related_files,
+    }
+}
+
+/// Finds every line of `files` that mentions `symbol_name` as a whole word
+///
+/// References are reported as `SymbolKind::Variable`, ordered by file and line.
+pub fn find_word_references<'files>(
+    files: impl Iterator<Item = &'files PathBuf>,
+    symbol_name: &str,
+) -> Vec<SymbolInfo> {
+    let mut references = Vec::new();
+    for path in files {
+        let Ok(source) = fs::read_to_string(path) else {
+            continue;
+        };
+        for (index, line) in source.lines().enumerate() {
+            if contains_identifier(line, symbol_name) {
+                references.push(SymbolInfo {
+                    name: symbol_name.to_owned(),
+                    kind: SymbolKind::Variable,
+                    file_path: path.clone(),
+                    line: u32::try_from(index + 1).unwrap_or(u32::MAX),
+                    documentation: None,
+                });
+            }
+        }
+    }
+    references.sort_by(|left, right| {
+        left.file_path
+            .cmp(&right.file_path)
+            .then_with(|| left.line.cmp(&right.line))
+    });
+    references
+}
+
+/// Returns true if `line` contains `identifier` as a whole word
+fn contains_identifier(line: &str, identifier: &str) -> bool {
+    let is_identifier_char =
+        |character: char| character.is_alphanumeric() || character == '_' || character == '$';
+    line.match_indices(identifier).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + identifier.len()..].chars().next();
+        !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests which changed paths a project scan would have indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_is_indexed_path() {
+        let root = Path::new("/project");
+        let is_py = |path: &Path| path.extension().is_some_and(|ext| ext == "py");
+        let indexed = |path: &str| is_indexed_path(root, Path::new(path), &["venv"], is_py);
+
+        assert!(indexed("/project/app/models.py"));
+        assert!(!indexed("/project/app/models.txt"));
+        assert!(!indexed("/project/venv/lib/site.py"));
+        assert!(!indexed("/project/.cache/tmp.py"));
+        assert!(!indexed("/project/app/.hidden.py"));
+        assert!(!indexed("/elsewhere/models.py"));
+    }
+
+    /// Tests whole-word identifier matching.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_contains_identifier() {
+        assert!(contains_identifier("return User()", "User"));
+        assert!(contains_identifier("User", "User"));
+        assert!(!contains_identifier("find_user(UserId)", "User"));
+        assert!(!contains_identifier("const $User = 1", "User"));
+    }
+}
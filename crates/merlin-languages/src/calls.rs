@@ -0,0 +1,208 @@
+//! Call sites and call hierarchy queries shared by the backends.
+//!
+//! Parsers record every call and constructor expression as a [`CallSite`].
+//! A caller is the innermost function or method whose lines contain the call,
+//! so callers can be found from the parsed definitions without a type checker.
+
+use std::path::Path;
+
+use bincode::{Decode, Encode};
+
+use crate::provider::{SearchQuery, SearchResult, SymbolInfo, SymbolKind};
+use crate::{LanguageProvider, RelatedFile};
+use merlin_core::{CoreResult as Result, FileContext};
+
+/// Name reported for calls made outside any function
+pub const MODULE_CALLER: &str = "<module>";
+
+/// A call or constructor expression found while parsing a file
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct CallSite {
+    /// Name of the called function, method or type (last segment of `a.b.name(...)`)
+    pub callee: String,
+    /// Whether the callee was reached through a value, module or package (`a.name(...)`)
+    pub qualified: bool,
+    /// Line of the call (1-indexed)
+    pub line: usize,
+}
+
+/// Line range of a definition that calls can be attributed to
+#[derive(Debug, Clone, Copy)]
+pub struct CallerSpan<'name> {
+    /// Name of the function or method
+    pub name: &'name str,
+    /// `Function` or `Method`; other kinds are never callers
+    pub kind: SymbolKind,
+    /// First line (1-indexed)
+    pub start_line: usize,
+    /// Last line (1-indexed)
+    pub end_line: usize,
+}
+
+/// Returns true if only calls through a receiver can reach `definition`
+///
+/// Methods are called as `value.method()`, while functions and types may be
+/// called directly or through their module. Unknown definitions match any call.
+pub fn receiver_only(definition: Option<&SymbolInfo>) -> bool {
+    definition.is_some_and(|symbol| symbol.kind == SymbolKind::Method)
+}
+
+/// Returns the callers of `callee` across indexed files, ordered by location
+///
+/// Each file is given with its calls and the spans of its definitions. Each
+/// caller is reported at the line of the call, so a function calling `callee`
+/// twice is listed twice.
+pub fn collect_callers<'index>(
+    files: impl Iterator<Item = (&'index Path, &'index [CallSite], Vec<CallerSpan<'index>>)>,
+    callee: &str,
+    receiver_only: bool,
+) -> Vec<SymbolInfo> {
+    let mut callers: Vec<SymbolInfo> = files
+        .flat_map(|(file, calls, spans)| {
+            callers_in_file(file, calls, &spans, callee, receiver_only)
+        })
+        .collect();
+    sort_by_location(&mut callers);
+    callers
+}
+
+/// Returns the callers of `callee` among the calls of one file
+fn callers_in_file(
+    file: &Path,
+    calls: &[CallSite],
+    spans: &[CallerSpan<'_>],
+    callee: &str,
+    receiver_only: bool,
+) -> Vec<SymbolInfo> {
+    calls
+        .iter()
+        .filter(|call| call.callee == callee && (call.qualified || !receiver_only))
+        .map(|call| {
+            let enclosing = spans
+                .iter()
+                .filter(|span| {
+                    matches!(span.kind, SymbolKind::Function | SymbolKind::Method)
+                        && span.start_line <= call.line
+                        && call.line <= span.end_line
+                })
+                .min_by_key(|span| span.end_line - span.start_line);
+            SymbolInfo {
+                name: enclosing.map_or(MODULE_CALLER, |span| span.name).to_owned(),
+                kind: enclosing.map_or(SymbolKind::Module, |span| span.kind),
+                file_path: file.to_path_buf(),
+                line: u32::try_from(call.line).unwrap_or(u32::MAX),
+                documentation: None,
+            }
+        })
+        .collect()
+}
+
+/// Orders symbols by file and line
+pub fn sort_by_location(symbols: &mut [SymbolInfo]) {
+    symbols.sort_by(|left, right| {
+        left.file_path
+            .cmp(&right.file_path)
+            .then_with(|| left.line.cmp(&right.line))
+    });
+}
+
+/// Adds the implementations of the queried type if the query asks for them
+///
+/// Implementations are added after the ranked matches, skipping symbols that
+/// already matched by name, and their files become related context.
+///
+/// # Errors
+/// Returns the error of the backend's `find_implementations`
+pub fn add_implementations(
+    result: &mut SearchResult,
+    query: &SearchQuery,
+    backend: &impl LanguageProvider,
+) -> Result<()> {
+    let Some(name) = query.symbol_name.as_deref() else {
+        return Ok(());
+    };
+    if !query.include_implementations {
+        return Ok(());
+    }
+
+    for implementation in backend.find_implementations(name)? {
+        if result.symbols.iter().any(|symbol| {
+            symbol.file_path == implementation.file_path && symbol.line == implementation.line
+        }) {
+            continue;
+        }
+        if result
+            .related_files
+            .iter()
+            .all(|related| related.file.path != implementation.file_path)
+            && let Ok(file) = FileContext::from_path(&implementation.file_path)
+        {
+            result.related_files.push(RelatedFile::direct(file));
+        }
+        result.symbols.push(implementation);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that calls are attributed to the innermost enclosing function.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_callers_in_file() {
+        let call = |callee: &str, qualified: bool, line: usize| CallSite {
+            callee: callee.to_owned(),
+            qualified,
+            line,
+        };
+        let calls = [
+            call("save", true, 3),
+            call("save", false, 8),
+            call("load", false, 9),
+            call("save", false, 12),
+        ];
+        let spans = [
+            CallerSpan {
+                name: "Repo",
+                kind: SymbolKind::Struct,
+                start_line: 1,
+                end_line: 5,
+            },
+            CallerSpan {
+                name: "flush",
+                kind: SymbolKind::Method,
+                start_line: 2,
+                end_line: 4,
+            },
+            CallerSpan {
+                name: "main",
+                kind: SymbolKind::Function,
+                start_line: 7,
+                end_line: 10,
+            },
+        ];
+        let callers = |receiver_only: bool| -> Vec<(String, SymbolKind, u32)> {
+            callers_in_file(Path::new("a.py"), &calls, &spans, "save", receiver_only)
+                .into_iter()
+                .map(|caller| (caller.name, caller.kind, caller.line))
+                .collect()
+        };
+
+        assert_eq!(
+            callers(false),
+            vec![
+                ("flush".to_owned(), SymbolKind::Method, 3),
+                ("main".to_owned(), SymbolKind::Function, 8),
+                (MODULE_CALLER.to_owned(), SymbolKind::Module, 12),
+            ]
+        );
+        assert_eq!(
+            callers(true),
+            vec![("flush".to_owned(), SymbolKind::Method, 3)]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Syntax-aligned chunk boundaries for embedding.
+//!
+//! Each backend reports the definitions of a file as nested [`CodeSpan`]s so
+//! that files can be chunked at function, type and module boundaries instead
+//! of at arbitrary line counts.
+
+use std::path::Path;
+
+use merlin_core::CoreResult as Result;
+
+use crate::golang::is_go_file;
+use crate::python::is_python_file;
+use crate::typescript::is_typescript_file;
+use crate::{GoBackend, PythonBackend, TypeScriptBackend};
+
+/// Line range of a definition, used as a chunk boundary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeSpan {
+    /// Chunk identifier (e.g. `class User`, `func Handler.Serve`)
+    pub identifier: String,
+    /// First line, including leading doc comments and decorators (1-indexed)
+    pub start_line: usize,
+    /// Last line (1-indexed)
+    pub end_line: usize,
+    /// Definitions nested inside this one, where an oversized span may be split
+    pub children: Vec<Self>,
+}
+
+impl CodeSpan {
+    /// Creates a span without children
+    pub fn new(identifier: String, start_line: usize, end_line: usize) -> Self {
+        Self {
+            identifier,
+            start_line,
+            end_line,
+            children: Vec::new(),
+        }
+    }
+
+    /// Returns true if both spans cover exactly the same lines
+    fn same_range(&self, other: &Self) -> bool {
+        self.start_line == other.start_line && self.end_line == other.end_line
+    }
+
+    /// Returns true if `other` lies inside this span without covering all of it
+    fn strictly_contains(&self, other: &Self) -> bool {
+        self.start_line <= other.start_line
+            && other.end_line <= self.end_line
+            && !self.same_range(other)
+    }
+}
+
+/// Produces chunk boundaries aligned with syntax nodes
+pub trait CodeChunker {
+    /// Returns the top-level definitions of a file as nested spans in source order
+    ///
+    /// # Errors
+    /// Returns an error if the source cannot be parsed
+    fn code_spans(&self, file: &Path, source: &str) -> Result<Vec<CodeSpan>>;
+}
+
+/// Returns the chunker of the backend handling `file`, if any
+pub fn chunker_for(file: &Path) -> Option<Box<dyn CodeChunker>> {
+    if is_python_file(file) {
+        Some(Box::new(PythonBackend::new()))
+    } else if is_typescript_file(file) {
+        Some(Box::new(TypeScriptBackend::new()))
+    } else if is_go_file(file) {
+        Some(Box::new(GoBackend::new()))
+    } else {
+        None
+    }
+}
+
+/// Nests flat spans by line containment
+///
+/// Spans sharing the exact range of an earlier span (e.g. several variables
+/// in one declaration) are dropped so no lines are chunked twice.
+pub fn nest_spans(mut spans: Vec<CodeSpan>) -> Vec<CodeSpan> {
+    spans.sort_by(|left, right| {
+        left.start_line
+            .cmp(&right.start_line)
+            .then(right.end_line.cmp(&left.end_line))
+    });
+    let mut roots = Vec::new();
+    for span in spans {
+        insert_span(&mut roots, span);
+    }
+    roots
+}
+
+/// Inserts a span under the last sibling containing it
+fn insert_span(siblings: &mut Vec<CodeSpan>, span: CodeSpan) {
+    match siblings.last_mut() {
+        Some(last) if last.strictly_contains(&span) => insert_span(&mut last.children, span),
+        Some(last) if last.same_range(&span) => {}
+        _ => siblings.push(span),
+    }
+}
+
+/// Moves `start_line` up over the comment lines directly above it
+///
+/// Stops at the first blank or non-comment line, so only a comment attached
+/// to the definition is included.
+pub fn with_leading_comments(
+    lines: &[&str],
+    start_line: usize,
+    is_comment: fn(&str) -> bool,
+) -> usize {
+    let mut start = start_line;
+    while start > 1
+        && lines
+            .get(start - 2)
+            .is_some_and(|line| is_comment(line.trim()))
+    {
+        start -= 1;
+    }
+    start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that spans are nested by containment and duplicates are dropped.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_nest_spans() {
+        let nested = nest_spans(vec![
+            CodeSpan::new("method".to_owned(), 3, 5),
+            CodeSpan::new("class".to_owned(), 1, 10),
+            CodeSpan::new("first".to_owned(), 12, 12),
+            CodeSpan::new("second".to_owned(), 12, 12),
+        ]);
+
+        let names: Vec<&str> = nested.iter().map(|span| span.identifier.as_str()).collect();
+        assert_eq!(names, ["class", "first"]);
+        assert_eq!(
+            nested.first().map(|class| class.children.clone()),
+            Some(vec![CodeSpan::new("method".to_owned(), 3, 5)])
+        );
+    }
+
+    /// Tests that only comment lines directly above a definition are included.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_with_leading_comments() {
+        let lines = [
+            "// unrelated",
+            "",
+            "// Doc line one",
+            "// Doc line two",
+            "func Run() {}",
+        ];
+        let is_comment = |line: &str| line.starts_with("//");
+        assert_eq!(with_leading_comments(&lines, 5, is_comment), 3);
+        assert_eq!(with_leading_comments(&lines, 1, is_comment), 1);
+    }
+}
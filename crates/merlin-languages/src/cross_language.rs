@@ -0,0 +1,267 @@
+//! Links between files in different languages, such as Python imports of `PyO3` extension modules
+
+use merlin_core::{CoreResult as Result, FileContext};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Table;
+
+use crate::backend::source_files;
+use crate::provider::{CROSS_LANGUAGE_WEIGHT, LanguageProvider, RelatedFile, SearchResult};
+
+/// Directories skipped while looking for native extension modules
+const NATIVE_IGNORED_DIRS: &[&str] = &[
+    "target",
+    "node_modules",
+    "__pycache__",
+    "venv",
+    "site-packages",
+    "build",
+    "dist",
+];
+
+/// Links imports that cross a language boundary to the files implementing them
+///
+/// Covers Python imports of Rust extension modules built with `PyO3`: a module
+/// name matches the file declaring a `#[pymodule]` of that name, or the library
+/// root of a crate whose `Cargo.toml` names the library that way.
+#[derive(Debug, Default)]
+pub struct CrossLanguageResolver {
+    /// Implementing files keyed by the module name other languages import
+    modules: HashMap<String, Vec<PathBuf>>,
+}
+
+impl CrossLanguageResolver {
+    /// Indexes the native extension modules under `project_root`
+    pub fn new(project_root: &Path) -> Self {
+        let mut resolver = Self::default();
+        for path in source_files(project_root, NATIVE_IGNORED_DIRS, is_native_source) {
+            let Ok(source) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if path.file_name().is_some_and(|name| name == "Cargo.toml") {
+                if let Some((name, lib_root)) = cargo_library(&path, &source) {
+                    resolver.insert(name, lib_root);
+                }
+            } else {
+                for name in pymodule_names(&source) {
+                    resolver.insert(name, path.clone());
+                }
+            }
+        }
+        resolver
+    }
+
+    /// Returns true if the project has no native extension modules
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    /// Returns the files implementing a dotted module name
+    ///
+    /// Extension modules are top-level, so only the first segment is matched.
+    pub fn resolve(&self, module: &str) -> &[PathBuf] {
+        module
+            .split('.')
+            .next()
+            .and_then(|name| self.modules.get(name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the files in other languages that `file` imports
+    ///
+    /// # Errors
+    /// Returns an error if the backend cannot parse `file`
+    pub fn link(&self, backend: &dyn LanguageProvider, file: &Path) -> Result<Vec<PathBuf>> {
+        let mut linked: Vec<PathBuf> = Vec::new();
+        for module in backend.unresolved_imports(file)? {
+            for path in self.resolve(&module) {
+                if !linked.contains(path) {
+                    linked.push(path.clone());
+                }
+            }
+        }
+        Ok(linked)
+    }
+
+    /// Adds the files linked from each symbol's file to `result` with [`CROSS_LANGUAGE_WEIGHT`]
+    pub fn extend_related(&self, backend: &dyn LanguageProvider, result: &mut SearchResult) {
+        let mut files: Vec<&Path> = result
+            .symbols
+            .iter()
+            .map(|symbol| symbol.file_path.as_path())
+            .collect();
+        files.dedup();
+
+        for file in files {
+            let linked = match self.link(backend, file) {
+                Ok(linked) => linked,
+                Err(error) => {
+                    tracing::debug!("Skipping imports of {}: {error}", file.display());
+                    continue;
+                }
+            };
+            for path in linked {
+                if result
+                    .related_files
+                    .iter()
+                    .any(|related| related.file.path == path)
+                {
+                    continue;
+                }
+                if let Ok(context) = FileContext::from_path(&path) {
+                    result.related_files.push(RelatedFile {
+                        file: context,
+                        weight: CROSS_LANGUAGE_WEIGHT,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Records `path` as an implementation of `name`
+    fn insert(&mut self, name: String, path: PathBuf) {
+        let paths = self.modules.entry(name).or_default();
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+}
+
+/// Returns true for Rust sources and Cargo manifests, the files [`CrossLanguageResolver`] indexes
+pub fn is_native_source(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "rs")
+        || path.file_name().is_some_and(|name| name == "Cargo.toml")
+}
+
+/// Returns the library name and root file of a Cargo manifest
+///
+/// The name is `[lib] name`, or the package name with dashes replaced by
+/// underscores, which is also what `maturin` names the Python module.
+fn cargo_library(manifest: &Path, source: &str) -> Option<(String, PathBuf)> {
+    let table: Table = source.parse().ok()?;
+    let lib = table.get("lib").and_then(|lib| lib.as_table());
+    let name = lib
+        .and_then(|lib| lib.get("name"))
+        .or_else(|| table.get("package")?.get("name"))?
+        .as_str()?
+        .replace('-', "_");
+    let root = manifest.parent()?.join(
+        lib.and_then(|lib| lib.get("path")?.as_str())
+            .unwrap_or("src/lib.rs"),
+    );
+    root.is_file().then_some((name, root))
+}
+
+/// Returns the Python module names declared by `#[pymodule]` functions in a Rust file
+///
+/// A `name = "..."` argument to `#[pymodule]` or `#[pyo3]` overrides the function name.
+fn pymodule_names(source: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut pending = false;
+    let mut renamed = None;
+    for line in source.lines().map(str::trim) {
+        if line.starts_with("#[pymodule") {
+            pending = true;
+        }
+        if !pending {
+            continue;
+        }
+        if line.starts_with("#[") {
+            renamed = renamed.or_else(|| attribute_name(line));
+        } else if let Some((_, rest)) = line.split_once("fn ") {
+            let function: String = rest
+                .chars()
+                .take_while(|character| character.is_alphanumeric() || *character == '_')
+                .collect();
+            names.extend(
+                renamed
+                    .take()
+                    .or(Some(function))
+                    .filter(|name| !name.is_empty()),
+            );
+            pending = false;
+        }
+    }
+    names
+}
+
+/// Returns the value of a `name = "..."` argument in an attribute line
+fn attribute_name(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("name")?;
+    let (_, rest) = rest.trim_start().strip_prefix('=')?.split_once('"')?;
+    let (name, _) = rest.split_once('"')?;
+    Some(name.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PythonBackend;
+    use crate::provider::{DIRECT_WEIGHT, SearchQuery};
+    use std::slice;
+    use tempfile::TempDir;
+
+    /// Tests `#[pymodule]` detection, including renamed modules.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_pymodule_names() {
+        let source = "use pyo3::prelude::*;\n\n#[pymodule]\nfn fastmath(m: &Bound<'_, PyModule>) -> PyResult<()> {\n    Ok(())\n}\n\n#[pymodule]\n#[pyo3(name = \"_core\")]\npub fn core_module(m: &Bound<'_, PyModule>) -> PyResult<()> {\n    Ok(())\n}\n\nfn helper() {}\n";
+        assert_eq!(pymodule_names(source), ["fastmath", "_core"]);
+    }
+
+    /// Tests that Python imports of a `PyO3` module are linked to the Rust crate.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be created or indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_cross_language_links() -> Result<()> {
+        let dir = TempDir::new()?;
+        let native = dir.path().join("native");
+        fs::create_dir_all(native.join("src"))?;
+        fs::write(
+            native.join("Cargo.toml"),
+            "[package]\nname = \"vector-ops\"\n\n[lib]\ncrate-type = [\"cdylib\"]\n",
+        )?;
+        let lib = native.join("src").join("lib.rs");
+        fs::write(
+            &lib,
+            "#[pymodule]\nfn vector_ops(m: &Bound<'_, PyModule>) -> PyResult<()> {\n    Ok(())\n}\n",
+        )?;
+        let app = dir.path().join("app.py");
+        fs::write(
+            &app,
+            "import os\nfrom vector_ops.linalg import dot\n\n\ndef norm(v):\n    return dot(v, v)\n",
+        )?;
+
+        let mut backend = PythonBackend::new();
+        backend.initialize(dir.path())?;
+        let resolver = CrossLanguageResolver::new(dir.path());
+        assert_eq!(resolver.resolve("vector_ops.linalg"), slice::from_ref(&lib));
+        assert!(resolver.resolve("os").is_empty());
+
+        let mut result = backend.search_symbols(&SearchQuery {
+            symbol_name: Some("norm".to_owned()),
+            ..SearchQuery::default()
+        })?;
+        resolver.extend_related(&backend, &mut result);
+        let related: Vec<(&Path, f32)> = result
+            .related_files
+            .iter()
+            .map(|related| (related.file.path.as_path(), related.weight))
+            .collect();
+        assert_eq!(
+            related,
+            [
+                (app.as_path(), DIRECT_WEIGHT),
+                (lib.as_path(), CROSS_LANGUAGE_WEIGHT)
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+//! Detection of the languages used by a project.
+//!
+//! [`detect_languages`] looks for the manifests of each supported language at
+//! the project root and counts its source files, so callers can create the
+//! right backends without knowing the project. The primary language comes
+//! first, which is the precedence a [`PolyglotBackend`] gives it.
+
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+use crate::golang::{GO_MOD, go_files, is_go_file};
+use crate::index_cache::WorkspaceCache;
+use crate::python::{is_python_file, python_files};
+use crate::typescript::{is_typescript_file, typescript_files};
+use crate::{GoBackend, LanguageProvider, PolyglotBackend, PythonBackend, TypeScriptBackend};
+
+/// A language with a backend in this crate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// Go, indexed by [`GoBackend`]
+    Golang,
+    /// Python, indexed by [`PythonBackend`]
+    Python,
+    /// TypeScript and JavaScript, indexed by [`TypeScriptBackend`]
+    TypeScript,
+}
+
+impl Language {
+    /// Every supported language
+    pub const ALL: [Self; 3] = [Self::Golang, Self::Python, Self::TypeScript];
+
+    /// Returns the display name of the language
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Golang => "Go",
+            Self::Python => "Python",
+            Self::TypeScript => "TypeScript",
+        }
+    }
+
+    /// Returns the manifests marking a project root of this language
+    pub const fn manifests(self) -> &'static [&'static str] {
+        match self {
+            Self::Golang => &[GO_MOD],
+            Self::Python => &[
+                "pyproject.toml",
+                "setup.py",
+                "setup.cfg",
+                "requirements.txt",
+            ],
+            Self::TypeScript => &["package.json", "tsconfig.json", "jsconfig.json"],
+        }
+    }
+
+    /// Returns the filter accepting the source files of this language
+    pub const fn file_filter(self) -> fn(&Path) -> bool {
+        match self {
+            Self::Golang => is_go_file,
+            Self::Python => is_python_file,
+            Self::TypeScript => is_typescript_file,
+        }
+    }
+
+    /// Creates an uninitialized backend, persisting its parse results in `index_cache` if given
+    pub fn create_backend(self, index_cache: Option<WorkspaceCache>) -> Box<dyn LanguageProvider> {
+        match (self, index_cache) {
+            (Self::Golang, Some(cache)) => Box::new(GoBackend::new().with_index_cache(cache)),
+            (Self::Golang, None) => Box::new(GoBackend::new()),
+            (Self::Python, Some(cache)) => Box::new(PythonBackend::new().with_index_cache(cache)),
+            (Self::Python, None) => Box::new(PythonBackend::new()),
+            (Self::TypeScript, Some(cache)) => {
+                Box::new(TypeScriptBackend::new().with_index_cache(cache))
+            }
+            (Self::TypeScript, None) => Box::new(TypeScriptBackend::new()),
+        }
+    }
+
+    /// Counts the source files the backend of this language would index
+    fn count_files(self, project_root: &Path) -> usize {
+        match self {
+            Self::Golang => go_files(project_root).count(),
+            Self::Python => python_files(project_root).count(),
+            Self::TypeScript => typescript_files(project_root).count(),
+        }
+    }
+}
+
+/// Backends paired with the language each one indexes
+pub type LanguageBackends = Vec<(Language, Box<dyn LanguageProvider>)>;
+
+/// A language found in a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedLanguage {
+    /// The language
+    pub language: Language,
+    /// First manifest of the language found at the project root, if any
+    pub manifest: Option<PathBuf>,
+    /// Number of source files of the language
+    pub file_count: usize,
+}
+
+/// Detects the languages of a project, primary language first
+///
+/// A language is detected when the project has source files of it; Go also
+/// needs a `go.mod`, since imports resolve against its module path. Languages
+/// with a manifest at the root rank before languages without one, then by
+/// number of files, so a few build scripts do not outrank the project itself.
+pub fn detect_languages(project_root: &Path) -> Vec<DetectedLanguage> {
+    let mut detected: Vec<DetectedLanguage> = Language::ALL
+        .into_iter()
+        .filter_map(|language| {
+            let manifest = language
+                .manifests()
+                .iter()
+                .map(|name| project_root.join(name))
+                .find(|path| path.is_file());
+            if language == Language::Golang && manifest.is_none() {
+                return None;
+            }
+            let file_count = language.count_files(project_root);
+            (file_count > 0).then_some(DetectedLanguage {
+                language,
+                manifest,
+                file_count,
+            })
+        })
+        .collect();
+    detected.sort_by_key(|found| (found.manifest.is_none(), Reverse(found.file_count)));
+    detected
+}
+
+/// Combines the backends of several languages, in order of precedence
+///
+/// One backend is returned as is, several are wrapped in a [`PolyglotBackend`]
+/// routing each file to the backend of its language, and none gives `None`.
+pub fn compose_backends(backends: LanguageBackends) -> Option<Box<dyn LanguageProvider>> {
+    if backends.len() > 1 {
+        let polyglot =
+            backends
+                .into_iter()
+                .fold(PolyglotBackend::new(), |polyglot, (language, backend)| {
+                    polyglot.with_backend(language.name(), language.file_filter(), backend)
+                });
+        Some(Box::new(polyglot))
+    } else {
+        backends.into_iter().next().map(|(_, backend)| backend)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use merlin_core::{CoreResult as Result, Error};
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Tests that a manifest outranks a larger number of files.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_manifest_decides_primary_language() -> Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+        fs::write(root.join("pyproject.toml"), "[project]\nname = \"app\"\n")?;
+        fs::write(root.join("app.py"), "def main():\n    pass\n")?;
+        fs::create_dir(root.join("scripts"))?;
+        for name in ["build.js", "lint.js", "deploy.js"] {
+            fs::write(root.join("scripts").join(name), "console.log(1);\n")?;
+        }
+        fs::create_dir(root.join("node_modules"))?;
+        fs::write(root.join("node_modules").join("dep.js"), "")?;
+        // Go files without `go.mod` cannot be resolved, so Go is not detected
+        fs::write(root.join("tool.go"), "package main\n")?;
+
+        let detected = detect_languages(root);
+        assert_eq!(
+            detected,
+            vec![
+                DetectedLanguage {
+                    language: Language::Python,
+                    manifest: Some(root.join("pyproject.toml")),
+                    file_count: 1,
+                },
+                DetectedLanguage {
+                    language: Language::TypeScript,
+                    manifest: None,
+                    file_count: 3,
+                },
+            ]
+        );
+        assert!(
+            detect_languages(&root.join("scripts")).iter().all(|found| {
+                found.language == Language::TypeScript && found.manifest.is_none()
+            })
+        );
+        Ok(())
+    }
+
+    /// Tests that combined backends each index the files of their language.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written or indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_compose_backends() -> Result<()> {
+        assert!(compose_backends(Vec::new()).is_none());
+
+        let dir = TempDir::new()?;
+        let root = dir.path();
+        fs::write(
+            root.join("api.py"),
+            "def handler():
+    pass
+",
+        )?;
+        fs::write(
+            root.join("view.ts"),
+            "export function render() {}
+",
+        )?;
+        let mut composed = compose_backends(
+            detect_languages(root)
+                .into_iter()
+                .map(|found| (found.language, found.language.create_backend(None)))
+                .collect(),
+        )
+        .ok_or_else(|| Error::Other("no backend composed".to_owned()))?;
+        composed.initialize(root)?;
+
+        let names = |file: &str| -> Result<Vec<String>> {
+            Ok(composed
+                .list_symbols_in_file(&root.join(file))?
+                .into_iter()
+                .map(|symbol| symbol.name)
+                .collect())
+        };
+        assert_eq!(names("api.py")?, ["handler"]);
+        assert_eq!(names("view.ts")?, ["render"]);
+        Ok(())
+    }
+}
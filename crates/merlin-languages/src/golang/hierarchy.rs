@@ -0,0 +1,106 @@
+//! Caller and interface implementation queries for Go.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use merlin_core::CoreResult as Result;
+
+use super::{GoBackend, GoSymbol, symbol_info};
+use crate::calls::{CallerSpan, collect_callers, receiver_only, sort_by_location};
+use crate::provider::{LanguageProvider as _, SymbolInfo, SymbolKind};
+
+/// Method names of each receiver type, keyed by package directory and type name
+type MethodSets<'index> = HashMap<(&'index Path, &'index str), Vec<&'index str>>;
+
+/// Finds the functions and methods whose bodies call `symbol_name`
+///
+/// # Errors
+/// Returns an error if the definition of `symbol_name` cannot be looked up
+pub(super) fn find_callers(
+    backend: &GoBackend,
+    symbol_name: &str,
+    file: &Path,
+    line: u32,
+) -> Result<Vec<SymbolInfo>> {
+    let receiver_only = receiver_only(backend.find_definition(symbol_name, file, line)?.as_ref());
+    let files = backend.files.iter().map(|(path, parsed)| {
+        let spans = parsed
+            .symbols
+            .iter()
+            .map(|symbol| CallerSpan {
+                name: &symbol.name,
+                kind: symbol.kind,
+                start_line: symbol.start_line,
+                end_line: symbol.end_line,
+            })
+            .collect();
+        (path.as_path(), parsed.calls.as_slice(), spans)
+    });
+    Ok(collect_callers(files, symbol_name, receiver_only))
+}
+
+/// Finds the types whose method set in their package covers every method of the interface `type_name`
+pub(super) fn find_implementations(backend: &GoBackend, type_name: &str) -> Vec<SymbolInfo> {
+    // Go interfaces are satisfied implicitly, so compare method sets per package
+    let mut required: Vec<&str> = Vec::new();
+    let mut method_sets: MethodSets<'_> = HashMap::new();
+    for (path, parsed) in &backend.files {
+        let interfaces: Vec<&GoSymbol> = parsed
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.kind == SymbolKind::Trait)
+            .collect();
+        for method in parsed
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.kind == SymbolKind::Method)
+        {
+            let Some(parent) = method.parent.as_deref() else {
+                continue;
+            };
+            let enclosing = interfaces.iter().find(|interface| {
+                interface.start_line <= method.start_line && method.end_line <= interface.end_line
+            });
+            match enclosing {
+                Some(interface) if interface.name == type_name => {
+                    required.push(&method.name);
+                }
+                Some(_) => {}
+                None => {
+                    let dir = path.parent().unwrap_or(path);
+                    method_sets
+                        .entry((dir, parent))
+                        .or_default()
+                        .push(&method.name);
+                }
+            }
+        }
+    }
+    // Every type satisfies an empty interface, which says nothing useful
+    if required.is_empty() {
+        return Vec::new();
+    }
+
+    let mut implementations: Vec<SymbolInfo> =
+        backend
+            .files
+            .iter()
+            .flat_map(|(path, parsed)| {
+                let dir = path.parent().unwrap_or(path);
+                let method_sets = &method_sets;
+                let required = &required;
+                parsed
+                    .symbols
+                    .iter()
+                    .filter(move |symbol| {
+                        matches!(symbol.kind, SymbolKind::Struct | SymbolKind::Type)
+                            && method_sets.get(&(dir, symbol.name.as_str())).is_some_and(
+                                |methods| required.iter().all(|name| methods.contains(name)),
+                            )
+                    })
+                    .map(move |symbol| symbol_info(path, symbol))
+            })
+            .collect();
+    sort_by_location(&mut implementations);
+    implementations
+}
@@ -0,0 +1,362 @@
+//! Go language backend built on `tree-sitter-go`.
+//!
+//! Parses every `.go` file under the project root once during initialization and
+//! answers symbol, reference and import queries from the parsed files. Imports are
+//! resolved to package directories through the module path declared in `go.mod`.
+
+mod hierarchy;
+mod parser;
+
+pub use parser::{GoFile, GoImport, GoSymbol, parse_go};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use merlin_core::{CoreResult as Result, Error, FileContext};
+
+use crate::backend::{
+    find_word_references, is_indexed_path, rank_symbols, reindex_file, source_files,
+};
+use crate::calls::add_implementations;
+use crate::chunking::{CodeChunker, CodeSpan, nest_spans, with_leading_comments};
+use crate::index_cache::{WorkspaceCache, index_workspace};
+use crate::provider::{LanguageProvider, SearchQuery, SearchResult, SymbolInfo, SymbolKind};
+
+/// Directories skipped while scanning for Go files
+const IGNORED_DIRS: &[&str] = &["vendor", "testdata", "node_modules", "target"];
+
+/// Name of the module definition file at the project root
+pub const GO_MOD: &str = "go.mod";
+
+/// Returns true if `path` is a Go source file
+pub fn is_go_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "go")
+}
+
+/// Returns true if the project root has a `go.mod` and the project contains `.go` files
+pub fn contains_go_module(project_root: &Path) -> bool {
+    project_root.join(GO_MOD).is_file() && go_files(project_root).next().is_some()
+}
+
+/// Iterates over the Go files under `project_root`, skipping hidden, vendored and test data directories
+pub(crate) fn go_files(project_root: &Path) -> impl Iterator<Item = PathBuf> {
+    source_files(project_root, IGNORED_DIRS, is_go_file)
+}
+
+/// Returns the module path declared by the `module` directive of a `go.mod` file
+pub fn module_path(go_mod: &str) -> Option<String> {
+    go_mod.lines().find_map(|line| {
+        let path = line.trim().strip_prefix("module")?;
+        // `module` must be followed by whitespace, not be a prefix of another word
+        if !path.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let path = path.split("//").next()?.trim().trim_matches(['"', '`']);
+        (!path.is_empty()).then(|| path.to_owned())
+    })
+}
+
+/// Go backend that indexes the declarations and imports of a module
+#[derive(Debug, Default)]
+pub struct GoBackend {
+    /// Project root the backend was initialized with
+    project_root: PathBuf,
+    /// Module path from `go.mod`, used to map import paths to directories
+    module_path: Option<String>,
+    /// Parsed files keyed by file path
+    files: HashMap<PathBuf, GoFile>,
+    /// Indexed non-test files of each package, keyed by directory and sorted by path
+    packages: HashMap<PathBuf, Vec<PathBuf>>,
+    /// Where parse results are persisted between runs, if anywhere
+    index_cache: Option<WorkspaceCache>,
+}
+
+impl GoBackend {
+    /// Creates an uninitialized backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persists parse results in `cache` so unchanged files are not parsed on the next start
+    #[must_use]
+    pub fn with_index_cache(mut self, cache: WorkspaceCache) -> Self {
+        self.index_cache = Some(cache);
+        self
+    }
+
+    /// Returns the number of parsed Go files
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns the parsed file for `file`, parsing it if it was not indexed
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed
+    fn file(&self, file: &Path) -> Result<GoFile> {
+        if let Some(parsed) = self.files.get(file) {
+            return Ok(parsed.clone());
+        }
+        let source = fs::read_to_string(file)
+            .map_err(|_| Error::FileNotFound(file.display().to_string()))?;
+        parse_go(&source)
+    }
+
+    /// Resolves an import path of this module to its package directory
+    ///
+    /// Standard library and third-party imports resolve to `None`.
+    fn resolve_import(&self, import: &GoImport) -> Option<PathBuf> {
+        let module = self.module_path.as_deref()?;
+        let relative = if import.path == module {
+            ""
+        } else {
+            import.path.strip_prefix(module)?.strip_prefix('/')?
+        };
+        let dir = relative
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .fold(self.project_root.clone(), |path, part| path.join(part));
+        dir.is_dir().then_some(dir)
+    }
+
+    /// Returns the indexed non-test files of the package in `dir`, sorted by path
+    fn package_files(&self, dir: &Path) -> &[PathBuf] {
+        self.packages.get(dir).map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds `file` to or removes it from its package after the file index changed
+    fn sync_package(&mut self, file: &Path) {
+        let Some(dir) = file.parent() else {
+            return;
+        };
+        let indexed = self.files.contains_key(file) && is_package_file(file);
+        let members = self.packages.entry(dir.to_path_buf()).or_default();
+        match (
+            members.binary_search_by(|member| member.as_path().cmp(file)),
+            indexed,
+        ) {
+            (Err(position), true) => members.insert(position, file.to_path_buf()),
+            (Ok(position), false) => {
+                members.remove(position);
+            }
+            _ => {}
+        }
+        if members.is_empty() {
+            self.packages.remove(dir);
+        }
+    }
+
+    /// Returns the declaration of `symbol_name` in the package in `dir`
+    fn find_in_package(&self, dir: &Path, symbol_name: &str, skip: &Path) -> Option<SymbolInfo> {
+        self.package_files(dir)
+            .iter()
+            .filter(|path| path.as_path() != skip)
+            .find_map(|path| {
+                self.files
+                    .get(path)?
+                    .symbols
+                    .iter()
+                    .find(|symbol| symbol.name == symbol_name)
+                    .map(|symbol| symbol_info(path, symbol))
+            })
+    }
+}
+
+/// Returns true if `path` belongs to its package's build, i.e. is not a `_test.go` file
+fn is_package_file(path: &Path) -> bool {
+    !path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with("_test.go"))
+}
+
+/// Groups the package files of an index by directory, sorted by path
+fn package_index<'files>(
+    files: impl Iterator<Item = &'files PathBuf>,
+) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut packages: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for path in files.filter(|path| is_package_file(path)) {
+        if let Some(dir) = path.parent() {
+            packages
+                .entry(dir.to_path_buf())
+                .or_default()
+                .push(path.clone());
+        }
+    }
+    for members in packages.values_mut() {
+        members.sort();
+    }
+    packages
+}
+
+/// Converts a parsed symbol into the provider's symbol type
+fn symbol_info(file: &Path, symbol: &GoSymbol) -> SymbolInfo {
+    SymbolInfo {
+        name: symbol.name.clone(),
+        kind: symbol.kind,
+        file_path: file.to_path_buf(),
+        line: u32::try_from(symbol.start_line).unwrap_or(u32::MAX),
+        documentation: symbol.documentation.clone(),
+    }
+}
+
+impl LanguageProvider for GoBackend {
+    fn initialize(&mut self, project_root: &Path) -> Result<()> {
+        self.project_root = project_root.to_path_buf();
+        self.module_path = fs::read_to_string(project_root.join(GO_MOD))
+            .ok()
+            .and_then(|go_mod| module_path(&go_mod));
+        self.files = index_workspace(
+            self.index_cache.as_ref(),
+            "go",
+            project_root,
+            go_files(project_root),
+            |_, source| parse_go(source),
+        );
+        self.packages = package_index(self.files.keys());
+
+        tracing::info!(
+            "Go backend indexed {} files of module {}",
+            self.files.len(),
+            self.module_path.as_deref().unwrap_or("<unknown>")
+        );
+        Ok(())
+    }
+
+    fn search_symbols(&self, query: &SearchQuery) -> Result<SearchResult> {
+        let symbols = self.files.iter().flat_map(|(path, parsed)| {
+            parsed
+                .symbols
+                .iter()
+                .map(move |symbol| symbol_info(path, symbol))
+        });
+        let mut result = rank_symbols(symbols, query);
+        add_implementations(&mut result, query, self)?;
+        Ok(result)
+    }
+
+    fn find_definition(
+        &self,
+        symbol_name: &str,
+        file: &Path,
+        _line: u32,
+    ) -> Result<Option<SymbolInfo>> {
+        // Prefer the same file, then the rest of its package, then imported packages
+        let local = self.file(file)?;
+        if let Some(symbol) = local
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == symbol_name)
+        {
+            return Ok(Some(symbol_info(file, symbol)));
+        }
+
+        if let Some(symbol) = file
+            .parent()
+            .and_then(|dir| self.find_in_package(dir, symbol_name, file))
+        {
+            return Ok(Some(symbol));
+        }
+
+        Ok(local
+            .imports
+            .iter()
+            .filter_map(|import| self.resolve_import(import))
+            .find_map(|dir| self.find_in_package(&dir, symbol_name, file)))
+    }
+
+    fn find_references(&self, symbol_name: &str) -> Result<Vec<SymbolInfo>> {
+        Ok(find_word_references(self.files.keys(), symbol_name))
+    }
+
+    fn find_callers(&self, symbol_name: &str, file: &Path, line: u32) -> Result<Vec<SymbolInfo>> {
+        hierarchy::find_callers(self, symbol_name, file, line)
+    }
+
+    fn find_implementations(&self, type_name: &str) -> Result<Vec<SymbolInfo>> {
+        Ok(hierarchy::find_implementations(self, type_name))
+    }
+
+    fn get_related_context(&self, file: &Path) -> Result<Vec<FileContext>> {
+        Ok(self
+            .extract_imports(file)?
+            .iter()
+            .filter_map(|path| FileContext::from_path(path).ok())
+            .collect())
+    }
+
+    fn extract_imports(&self, file: &Path) -> Result<Vec<PathBuf>> {
+        let parsed = self.file(file)?;
+        let mut imports: Vec<PathBuf> = Vec::new();
+        for dir in parsed
+            .imports
+            .iter()
+            .filter_map(|import| self.resolve_import(import))
+        {
+            for path in self.package_files(&dir) {
+                if path != file && !imports.contains(path) {
+                    imports.push(path.clone());
+                }
+            }
+        }
+        Ok(imports)
+    }
+
+    fn list_symbols_in_file(&self, file: &Path) -> Result<Vec<SymbolInfo>> {
+        Ok(self
+            .file(file)?
+            .symbols
+            .iter()
+            .map(|symbol| symbol_info(file, symbol))
+            .collect())
+    }
+
+    fn apply_file_change(&mut self, file: &Path, new_text: Option<&str>) -> Result<()> {
+        if file == self.project_root.join(GO_MOD) {
+            self.module_path = new_text.and_then(module_path);
+            return Ok(());
+        }
+        if !is_indexed_path(&self.project_root, file, IGNORED_DIRS, is_go_file) {
+            return Ok(());
+        }
+        let reindexed = reindex_file(&mut self.files, file, new_text, parse_go);
+        self.sync_package(file);
+        reindexed
+    }
+}
+
+impl CodeChunker for GoBackend {
+    fn code_spans(&self, _file: &Path, source: &str) -> Result<Vec<CodeSpan>> {
+        let file = parse_go(source)?;
+        let lines: Vec<&str> = source.lines().collect();
+        let spans = file
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let name = &symbol.name;
+                let identifier = match (symbol.kind, &symbol.parent) {
+                    (SymbolKind::Function, _) => format!("func {name}"),
+                    // Methods with a receiver, as opposed to interface method elements
+                    (SymbolKind::Method, Some(receiver))
+                        if lines
+                            .get(symbol.start_line - 1)
+                            .is_some_and(|line| line.trim_start().starts_with("func")) =>
+                    {
+                        format!("func {receiver}.{name}")
+                    }
+                    (_, Some(parent)) => format!("{parent}.{name}"),
+                    (SymbolKind::Constant, None) => format!("const {name}"),
+                    (SymbolKind::Variable, None) => format!("var {name}"),
+                    (_, None) => format!("type {name}"),
+                };
+                let start_line =
+                    with_leading_comments(&lines, symbol.start_line, |line| line.starts_with("//"));
+                CodeSpan::new(identifier, start_line, symbol.end_line)
+            })
+            .collect();
+        Ok(nest_spans(spans))
+    }
+}
+
+#[cfg(test)]
+mod tests;
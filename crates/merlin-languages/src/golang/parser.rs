@@ -0,0 +1,454 @@
+//! Go source parsing with `tree-sitter-go`.
+
+use bincode::{Decode, Encode};
+use merlin_core::{CoreResult as Result, Error};
+use tree_sitter::{Language, Node, Parser};
+use tree_sitter_go::LANGUAGE;
+
+use crate::calls::CallSite;
+use crate::provider::SymbolKind;
+
+/// A declaration found in a Go source file
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct GoSymbol {
+    /// Name of the declaration
+    pub name: String,
+    /// Kind of declaration (structs map to `Struct`, interfaces to `Trait`)
+    pub kind: SymbolKind,
+    /// First line of the declaration (1-indexed)
+    pub start_line: usize,
+    /// Last line of the declaration (1-indexed)
+    pub end_line: usize,
+    /// Receiver type of methods, or the struct or interface a field or method belongs to
+    pub parent: Option<String>,
+    /// Doc comment directly above the declaration, without `//` markers
+    pub documentation: Option<String>,
+}
+
+/// An import spec of a Go source file
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct GoImport {
+    /// Import path (e.g. `example.com/app/models` or `fmt`)
+    pub path: String,
+    /// Package name the import is bound to, if renamed (`_` and `.` included)
+    pub alias: Option<String>,
+}
+
+/// Package name, declarations and imports of a parsed Go source file
+#[derive(Debug, Clone, Default, Encode, Decode)]
+pub struct GoFile {
+    /// Name from the `package` clause
+    pub package: String,
+    /// Functions, methods, types, fields, constants and variables in source order
+    pub symbols: Vec<GoSymbol>,
+    /// Import specs in source order
+    pub imports: Vec<GoImport>,
+    /// Function and method calls in source order
+    pub calls: Vec<CallSite>,
+}
+
+/// Parses Go source into its declarations and imports
+///
+/// # Errors
+/// Returns an error if the grammar cannot be loaded or the parser gives up
+pub fn parse_go(source: &str) -> Result<GoFile> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&Language::from(LANGUAGE))
+        .map_err(|err| Error::Other(format!("Failed to load Go grammar: {err}")))?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| Error::Other("Failed to parse Go source".to_owned()))?;
+
+    let mut file = GoFile::default();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    for declaration in root.named_children(&mut cursor) {
+        match declaration.kind() {
+            "package_clause" => {
+                if let Some(name) = declaration.named_child(0) {
+                    text(name, source).clone_into(&mut file.package);
+                }
+            }
+            "import_declaration" => collect_imports(declaration, source, &mut file),
+            "function_declaration" => {
+                push_named(&mut file, declaration, source, SymbolKind::Function, None);
+            }
+            "method_declaration" => {
+                let receiver = declaration
+                    .child_by_field_name("receiver")
+                    .and_then(|receiver| receiver.named_child(0))
+                    .and_then(|parameter| parameter.child_by_field_name("type"))
+                    .and_then(|receiver_type| type_name(receiver_type, source));
+                push_named(&mut file, declaration, source, SymbolKind::Method, receiver);
+            }
+            "type_declaration" => collect_types(declaration, source, &mut file),
+            "const_declaration" | "var_declaration" => {
+                collect_values(declaration, source, &mut file);
+            }
+            _ => {}
+        }
+    }
+    collect_calls(root, source, &mut file.calls);
+    Ok(file)
+}
+
+/// Records every call below `node` in source order
+fn collect_calls(node: Node<'_>, source: &str, calls: &mut Vec<CallSite>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "call_expression"
+            && let Some(function) = child.child_by_field_name("function")
+        {
+            let callee = match function.kind() {
+                "identifier" => Some((function, false)),
+                "selector_expression" => function
+                    .child_by_field_name("field")
+                    .map(|field| (field, true)),
+                _ => None,
+            };
+            if let Some((name, qualified)) = callee {
+                calls.push(CallSite {
+                    callee: text(name, source).to_owned(),
+                    qualified,
+                    line: child.start_position().row + 1,
+                });
+            }
+        }
+        collect_calls(child, source, calls);
+    }
+}
+
+/// Records the specs of an `import` declaration, grouped or not
+fn collect_imports(declaration: Node<'_>, source: &str, file: &mut GoFile) {
+    let mut cursor = declaration.walk();
+    for child in declaration.named_children(&mut cursor) {
+        let specs: Vec<Node<'_>> = if child.kind() == "import_spec_list" {
+            let mut list_cursor = child.walk();
+            child.named_children(&mut list_cursor).collect()
+        } else {
+            vec![child]
+        };
+        for spec in specs
+            .into_iter()
+            .filter(|spec| spec.kind() == "import_spec")
+        {
+            let Some(path) = spec.child_by_field_name("path") else {
+                continue;
+            };
+            file.imports.push(GoImport {
+                path: text(path, source).trim_matches(['"', '`']).to_owned(),
+                alias: spec
+                    .child_by_field_name("name")
+                    .map(|name| text(name, source).to_owned()),
+            });
+        }
+    }
+}
+
+/// Records the type specs of a `type` declaration with their fields and interface methods
+fn collect_types(declaration: Node<'_>, source: &str, file: &mut GoFile) {
+    let mut cursor = declaration.walk();
+    let specs: Vec<Node<'_>> = declaration
+        .named_children(&mut cursor)
+        .filter(|spec| matches!(spec.kind(), "type_spec" | "type_alias"))
+        .collect();
+    // A single spec spans the whole declaration, including the `type` keyword
+    let single = specs.len() == 1;
+
+    for spec in specs {
+        let Some(name) = spec
+            .child_by_field_name("name")
+            .map(|name| text(name, source))
+        else {
+            continue;
+        };
+        let body = spec.child_by_field_name("type");
+        let kind = match body.map(|body| body.kind()) {
+            Some("struct_type") if spec.kind() == "type_spec" => SymbolKind::Struct,
+            Some("interface_type") if spec.kind() == "type_spec" => SymbolKind::Trait,
+            _ => SymbolKind::Type,
+        };
+        let outer = if single { declaration } else { spec };
+        file.symbols
+            .push(symbol(name, kind, outer, None, doc_comment(outer, source)));
+
+        match (kind, body) {
+            (SymbolKind::Struct, Some(body)) => collect_fields(body, name, source, file),
+            (SymbolKind::Trait, Some(body)) => {
+                let mut body_cursor = body.walk();
+                for element in body.named_children(&mut body_cursor) {
+                    if element.kind() == "method_elem" {
+                        push_named(file, element, source, SymbolKind::Method, Some(name));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Records the named fields of a struct type
+fn collect_fields(struct_type: Node<'_>, struct_name: &str, source: &str, file: &mut GoFile) {
+    let Some(fields) = struct_type.named_child(0) else {
+        return;
+    };
+    let mut cursor = fields.walk();
+    for field in fields.named_children(&mut cursor) {
+        if field.kind() != "field_declaration" {
+            continue;
+        }
+        let mut name_cursor = field.walk();
+        for name in field.children_by_field_name("name", &mut name_cursor) {
+            file.symbols.push(symbol(
+                text(name, source),
+                SymbolKind::Field,
+                field,
+                Some(struct_name),
+                doc_comment(field, source),
+            ));
+        }
+    }
+}
+
+/// Records the names of a `const` or `var` declaration, grouped or not
+fn collect_values(declaration: Node<'_>, source: &str, file: &mut GoFile) {
+    let kind = if declaration.kind() == "const_declaration" {
+        SymbolKind::Constant
+    } else {
+        SymbolKind::Variable
+    };
+
+    let mut cursor = declaration.walk();
+    let mut specs = Vec::new();
+    for child in declaration.named_children(&mut cursor) {
+        if child.kind() == "var_spec_list" {
+            let mut list_cursor = child.walk();
+            specs.extend(child.named_children(&mut list_cursor));
+        } else {
+            specs.push(child);
+        }
+    }
+    let specs: Vec<Node<'_>> = specs
+        .into_iter()
+        .filter(|spec| matches!(spec.kind(), "const_spec" | "var_spec"))
+        .collect();
+    let single = specs.len() == 1;
+
+    for spec in specs {
+        let outer = if single { declaration } else { spec };
+        let mut name_cursor = spec.walk();
+        for name in spec.children_by_field_name("name", &mut name_cursor) {
+            if name.kind() == "identifier" {
+                file.symbols.push(symbol(
+                    text(name, source),
+                    kind,
+                    outer,
+                    None,
+                    doc_comment(outer, source),
+                ));
+            }
+        }
+    }
+}
+
+/// Records a declaration named by its `name` field
+fn push_named(
+    file: &mut GoFile,
+    node: Node<'_>,
+    source: &str,
+    kind: SymbolKind,
+    parent: Option<&str>,
+) {
+    if let Some(name) = node.child_by_field_name("name") {
+        file.symbols.push(symbol(
+            text(name, source),
+            kind,
+            node,
+            parent,
+            doc_comment(node, source),
+        ));
+    }
+}
+
+/// Builds a symbol spanning `node`
+fn symbol(
+    name: &str,
+    kind: SymbolKind,
+    node: Node<'_>,
+    parent: Option<&str>,
+    documentation: Option<String>,
+) -> GoSymbol {
+    GoSymbol {
+        name: name.to_owned(),
+        kind,
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        parent: parent.map(str::to_owned),
+        documentation,
+    }
+}
+
+/// Returns the name of a receiver type, without pointer or type arguments
+fn type_name<'source>(node: Node<'_>, source: &'source str) -> Option<&'source str> {
+    match node.kind() {
+        "type_identifier" => Some(text(node, source)),
+        "pointer_type" | "parenthesized_type" => type_name(node.named_child(0)?, source),
+        "generic_type" => type_name(node.child_by_field_name("type")?, source),
+        _ => None,
+    }
+}
+
+/// Returns the `//` comment lines directly above `node`, joined and without markers
+fn doc_comment(node: Node<'_>, source: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    let mut next_row = node.start_position().row;
+    let mut sibling = node.prev_sibling();
+    while let Some(comment) = sibling {
+        if comment.kind() != "comment" || comment.end_position().row + 1 != next_row {
+            break;
+        }
+        let Some(line) = text(comment, source).strip_prefix("//") else {
+            break;
+        };
+        lines.push(line.trim());
+        next_row = comment.start_position().row;
+        sibling = comment.prev_sibling();
+    }
+
+    lines.reverse();
+    let documentation = lines.join("\n");
+    (!documentation.is_empty()).then_some(documentation)
+}
+
+/// Returns the source text of a node
+fn text<'source>(node: Node<'_>, source: &'source str) -> &'source str {
+    node.utf8_text(source.as_bytes()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"package models
+
+import (
+	"fmt"
+	str "strings"
+	_ "embed"
+)
+
+import "example.com/app/internal/db"
+
+// MaxUsers limits the number of users.
+const MaxUsers = 10
+
+var (
+	registry = map[string]*User{}
+	count    int
+)
+
+// User is a registered account.
+type User struct {
+	Name, Email string
+	db.Model
+}
+
+type (
+	// Store persists users.
+	Store interface {
+		Save(user *User) error
+	}
+	ID = string
+)
+
+// NewUser creates a user.
+func NewUser(name string) *User {
+	return &User{Name: str.TrimSpace(name)}
+}
+
+// Greet says hello.
+func (u *User) Greet() string {
+	return fmt.Sprintf("hi %s", u.Name)
+}
+"#;
+
+    /// Tests that functions, methods, types, fields and values are mapped to symbol kinds.
+    ///
+    /// # Errors
+    /// Returns an error if parsing fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_symbols() -> Result<()> {
+        let file = parse_go(SOURCE)?;
+        assert_eq!(file.package, "models");
+
+        let symbols: Vec<_> = file
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            symbols,
+            vec![
+                ("MaxUsers", SymbolKind::Constant, None),
+                ("registry", SymbolKind::Variable, None),
+                ("count", SymbolKind::Variable, None),
+                ("User", SymbolKind::Struct, None),
+                ("Name", SymbolKind::Field, Some("User")),
+                ("Email", SymbolKind::Field, Some("User")),
+                ("Store", SymbolKind::Trait, None),
+                ("Save", SymbolKind::Method, Some("Store")),
+                ("ID", SymbolKind::Type, None),
+                ("NewUser", SymbolKind::Function, None),
+                ("Greet", SymbolKind::Method, Some("User")),
+            ]
+        );
+
+        let user = &file.symbols[3];
+        assert_eq!((user.start_line, user.end_line), (20, 23));
+        assert_eq!(
+            user.documentation.as_deref(),
+            Some("User is a registered account.")
+        );
+        assert_eq!(
+            file.symbols[6].documentation.as_deref(),
+            Some("Store persists users.")
+        );
+        assert_eq!(
+            file.symbols[0].documentation.as_deref(),
+            Some("MaxUsers limits the number of users.")
+        );
+        Ok(())
+    }
+
+    /// Tests grouped, renamed and single import extraction.
+    ///
+    /// # Errors
+    /// Returns an error if parsing fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_imports() -> Result<()> {
+        let file = parse_go(SOURCE)?;
+        let imports: Vec<_> = file
+            .imports
+            .iter()
+            .map(|import| (import.path.as_str(), import.alias.as_deref()))
+            .collect();
+
+        assert_eq!(
+            imports,
+            vec![
+                ("fmt", None),
+                ("strings", Some("str")),
+                ("embed", Some("_")),
+                ("example.com/app/internal/db", None),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,229 @@
+//! Tests for the Go backend
+
+use super::*;
+use crate::provider::SymbolKind;
+use tempfile::TempDir;
+
+/// Creates a small Go module with a package split across two files.
+///
+/// # Errors
+/// Returns an error if the files cannot be written.
+fn create_project() -> Result<TempDir> {
+    let dir = TempDir::new()?;
+    let models = dir.path().join("models");
+    let service = dir.path().join("service");
+    fs::create_dir_all(&models)?;
+    fs::create_dir_all(&service)?;
+    fs::create_dir_all(dir.path().join("vendor").join("lib"))?;
+    fs::write(
+        dir.path().join("go.mod"),
+        "module example.com/app\n\ngo 1.22\n",
+    )?;
+    fs::write(
+        models.join("user.go"),
+        "package models\n\n// User is an account.\ntype User struct {\n\tName string\n}\n",
+    )?;
+    fs::write(
+        models.join("store.go"),
+        "package models\n\ntype Store interface {\n\tFind(name string) *User\n}\n",
+    )?;
+    fs::write(
+        service.join("service.go"),
+        "package service\n\nimport (\n\t\"fmt\"\n\n\t\"example.com/app/models\"\n)\n\nfunc FindUser(name string) *models.User {\n\tfmt.Println(name)\n\treturn &models.User{Name: name}\n}\n",
+    )?;
+    fs::write(
+        dir.path().join("vendor").join("lib").join("lib.go"),
+        "package lib\n\nfunc Hidden() {}\n",
+    )?;
+    Ok(dir)
+}
+
+/// Tests `go.mod` module directive parsing.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_module_path() {
+    assert_eq!(
+        module_path("// comment\nmodule example.com/app // main module\n\ngo 1.22\n"),
+        Some("example.com/app".to_owned())
+    );
+    assert_eq!(module_path("modules x\n"), None);
+}
+
+/// Tests symbol search and project detection.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_search_symbols() -> Result<()> {
+    let dir = create_project()?;
+    assert!(contains_go_module(dir.path()));
+
+    let mut backend = GoBackend::new();
+    backend.initialize(dir.path())?;
+    assert_eq!(backend.file_count(), 3);
+
+    let result = backend.search_symbols(&SearchQuery {
+        symbol_name: Some("user".to_owned()),
+        ..SearchQuery::default()
+    })?;
+    let names: Vec<(&str, SymbolKind)> = result
+        .symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("User", SymbolKind::Struct),
+            ("FindUser", SymbolKind::Function)
+        ]
+    );
+
+    let hidden = backend.search_symbols(&SearchQuery {
+        symbol_name: Some("Hidden".to_owned()),
+        ..SearchQuery::default()
+    })?;
+    assert!(hidden.symbols.is_empty());
+    Ok(())
+}
+
+/// Tests module import resolution, package-wide definitions and references.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_imports_and_definitions() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = GoBackend::new();
+    backend.initialize(dir.path())?;
+
+    let models = dir.path().join("models");
+    let service = dir.path().join("service").join("service.go");
+    assert_eq!(
+        backend.extract_imports(&service)?,
+        vec![models.join("store.go"), models.join("user.go")]
+    );
+
+    // Imported package
+    let definition = backend.find_definition("User", &service, 9)?;
+    assert_eq!(
+        definition.map(|symbol| (symbol.file_path, symbol.line, symbol.documentation)),
+        Some((
+            models.join("user.go"),
+            4,
+            Some("User is an account.".to_owned())
+        ))
+    );
+
+    // Another file of the same package
+    let sibling = backend.find_definition("User", &models.join("store.go"), 4)?;
+    assert_eq!(
+        sibling.map(|symbol| symbol.file_path),
+        Some(models.join("user.go"))
+    );
+
+    let references: Vec<u32> = backend
+        .find_references("User")?
+        .iter()
+        .map(|symbol| symbol.line)
+        .collect();
+    assert_eq!(references, vec![4, 3, 4, 9, 11]);
+    Ok(())
+}
+
+/// Tests that file and `go.mod` changes update the index.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_apply_file_change() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = GoBackend::new();
+    backend.initialize(dir.path())?;
+    let service = dir.path().join("service").join("service.go");
+    let models = dir.path().join("models");
+
+    backend.apply_file_change(
+        &models.join("team.go"),
+        Some("package models\n\ntype Team struct{}\n"),
+    )?;
+    let team = backend.find_definition("Team", &service, 1)?;
+    assert_eq!(
+        team.map(|symbol| symbol.file_path),
+        Some(models.join("team.go"))
+    );
+
+    // Test files stay out of the package's import set
+    backend.apply_file_change(
+        &models.join("team_test.go"),
+        Some("package models\n\nfunc TestTeam() {}\n"),
+    )?;
+    assert_eq!(
+        backend.extract_imports(&service)?,
+        vec![
+            models.join("store.go"),
+            models.join("team.go"),
+            models.join("user.go")
+        ]
+    );
+
+    backend.apply_file_change(&models.join("user.go"), None)?;
+    assert!(backend.find_definition("User", &service, 1)?.is_none());
+    assert_eq!(
+        backend.extract_imports(&service)?,
+        vec![models.join("store.go"), models.join("team.go")]
+    );
+
+    backend.apply_file_change(
+        &dir.path().join("go.mod"),
+        Some("module example.com/renamed\n"),
+    )?;
+    assert!(backend.extract_imports(&service)?.is_empty());
+    Ok(())
+}
+
+/// Tests caller lookup and structural interface implementations.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_callers_and_implementations() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = GoBackend::new();
+    backend.initialize(dir.path())?;
+    let memory = dir.path().join("models").join("memory.go");
+    backend.apply_file_change(
+        &memory,
+        Some("package models\n\ntype MemoryStore struct{}\n\nfunc (s *MemoryStore) Find(name string) *User {\n\treturn s.lookup(name)\n}\n\nfunc (s *MemoryStore) lookup(name string) *User {\n\treturn nil\n}\n"),
+    )?;
+
+    let callers: Vec<(String, u32)> = backend
+        .find_callers("lookup", &memory, 6)?
+        .into_iter()
+        .map(|caller| (caller.name, caller.line))
+        .collect();
+    assert_eq!(callers, vec![("Find".to_owned(), 6)]);
+
+    // `User` has no `Find` method, so only `MemoryStore` satisfies `Store`
+    let implementations: Vec<(String, PathBuf, u32)> = backend
+        .find_implementations("Store")?
+        .into_iter()
+        .map(|symbol| (symbol.name, symbol.file_path, symbol.line))
+        .collect();
+    assert_eq!(implementations, vec![("MemoryStore".to_owned(), memory, 3)]);
+    Ok(())
+}
@@ -0,0 +1,313 @@
+//! Parse results persisted between runs.
+//!
+//! Parsing every file dominates backend start-up on large projects. The parsed
+//! files are stored in a bincode cache along with the size and modification
+//! time of each file, so a restart only parses the files that changed. A cache
+//! written in another format or by another Merlin version is ignored and every
+//! file is parsed again. [`WorkspaceCache`] keeps a cache per workspace for
+//! the most recently indexed workspaces and counts its hits and misses.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, SystemTime};
+
+use bincode::config::standard as bincode_config;
+use bincode::{Decode, Encode, decode_from_slice, encode_to_vec};
+use merlin_core::CoreResult as Result;
+
+/// Per-workspace caches bounded to the most recently used.
+mod workspaces;
+
+pub use workspaces::{
+    CacheStats, DEFAULT_MAX_WORKSPACES, IndexCacheStats, WorkspaceCache, index_workspace,
+};
+
+/// Cache format version (bump when a parsed type changes)
+pub const INDEX_CACHE_VERSION: u32 = 1;
+
+/// Merlin version writing the cache, since parsers may change between releases
+const MERLIN_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Parsed files of one backend as stored on disk
+#[derive(Debug, Encode, Decode)]
+struct IndexCache<Parsed> {
+    /// Format version, compared with [`INDEX_CACHE_VERSION`]
+    version: u32,
+    /// Version of the Merlin build that wrote the cache
+    merlin_version: String,
+    /// Parsed files with the state they were parsed in
+    files: Vec<CachedFile<Parsed>>,
+}
+
+/// A parsed file and the state of the file when it was parsed
+#[derive(Debug, Encode, Decode)]
+struct CachedFile<Parsed> {
+    /// Path of the file
+    path: PathBuf,
+    /// Size and modification time when parsed
+    stamp: FileStamp,
+    /// Parse result
+    parsed: Parsed,
+}
+
+/// Size and modification time used to detect changed files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+struct FileStamp {
+    /// File size in bytes
+    len: u64,
+    /// Last modification time
+    modified: SystemTime,
+}
+
+impl FileStamp {
+    /// Reads the stamp of `path`, or `None` if its metadata is unavailable
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+}
+
+/// Parses `files`, reusing the results cached at `cache_path` for unchanged files
+///
+/// Files that cannot be read or parsed are skipped. Without a `cache_path`
+/// every file is parsed and nothing is written. The cache is rewritten only
+/// if a file was parsed or removed since it was last written.
+pub fn index_files<Parsed>(
+    files: impl Iterator<Item = PathBuf>,
+    cache_path: Option<&Path>,
+    parse: impl FnMut(&Path, &str) -> Result<Parsed>,
+) -> HashMap<PathBuf, Parsed>
+where
+    Parsed: Encode + Decode<()>,
+{
+    index_counting_reuse(files, cache_path, parse).0
+}
+
+/// Parses `files` like [`index_files`], also returning how many came from the cache
+fn index_counting_reuse<Parsed>(
+    files: impl Iterator<Item = PathBuf>,
+    cache_path: Option<&Path>,
+    mut parse: impl FnMut(&Path, &str) -> Result<Parsed>,
+) -> (HashMap<PathBuf, Parsed>, usize)
+where
+    Parsed: Encode + Decode<()>,
+{
+    let started = Instant::now();
+    let mut cached = cache_path.map(load_cache::<Parsed>).unwrap_or_default();
+    let cached_count = cached.len();
+
+    let mut index = HashMap::new();
+    let mut stamps = Vec::new();
+    let mut reused = 0usize;
+    for path in files {
+        let Some(stamp) = FileStamp::of(&path) else {
+            continue;
+        };
+        if let Some((cached_stamp, parsed)) = cached.remove(&path)
+            && cached_stamp == stamp
+        {
+            reused += 1;
+            stamps.push((path.clone(), stamp));
+            index.insert(path, parsed);
+            continue;
+        }
+
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+        match parse(&path, &source) {
+            Ok(parsed) => {
+                stamps.push((path.clone(), stamp));
+                index.insert(path, parsed);
+            }
+            Err(error) => tracing::debug!("Skipping {}: {error}", path.display()),
+        }
+    }
+
+    if let Some(cache_path) = cache_path {
+        tracing::info!(
+            "Index cache {}: reused {reused} of {} files in {:.2?}",
+            cache_path.display(),
+            index.len(),
+            started.elapsed()
+        );
+        if reused != cached_count || reused != index.len() {
+            save_cache(cache_path, &index, stamps);
+        }
+    }
+    (index, reused)
+}
+
+/// Loads the cached files keyed by path, or nothing if the cache is missing or stale
+fn load_cache<Parsed: Decode<()>>(cache_path: &Path) -> HashMap<PathBuf, (FileStamp, Parsed)> {
+    let Ok(data) = fs::read(cache_path) else {
+        tracing::debug!("Index cache miss: {} not found", cache_path.display());
+        return HashMap::new();
+    };
+    let cache: IndexCache<Parsed> = match decode_from_slice(&data, bincode_config()) {
+        Ok((cache, _)) => cache,
+        Err(error) => {
+            tracing::warn!(
+                "Index cache {} is unreadable, parsing all files: {error}",
+                cache_path.display()
+            );
+            return HashMap::new();
+        }
+    };
+    if cache.version != INDEX_CACHE_VERSION || cache.merlin_version != MERLIN_VERSION {
+        tracing::info!(
+            "Index cache miss: {} was written by Merlin {} (format {}), parsing all files",
+            cache_path.display(),
+            cache.merlin_version,
+            cache.version
+        );
+        return HashMap::new();
+    }
+    cache
+        .files
+        .into_iter()
+        .map(|file| (file.path, (file.stamp, file.parsed)))
+        .collect()
+}
+
+/// Writes the parsed files to the cache, logging failures
+///
+/// A cache that cannot be written only costs a full parse on the next start.
+fn save_cache<Parsed: Encode>(
+    cache_path: &Path,
+    index: &HashMap<PathBuf, Parsed>,
+    stamps: Vec<(PathBuf, FileStamp)>,
+) {
+    let cache = IndexCache {
+        version: INDEX_CACHE_VERSION,
+        merlin_version: MERLIN_VERSION.to_owned(),
+        files: stamps
+            .into_iter()
+            .filter_map(|(path, stamp)| {
+                let parsed = index.get(&path)?;
+                Some(CachedFile {
+                    path,
+                    stamp,
+                    parsed,
+                })
+            })
+            .collect(),
+    };
+    let written = encode_to_vec(&cache, bincode_config())
+        .map_err(|error| error.to_string())
+        .and_then(|bytes| {
+            if let Some(parent) = cache_path.parent() {
+                fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+            }
+            fs::write(cache_path, bytes).map_err(|error| error.to_string())
+        });
+    if let Err(error) = written {
+        tracing::warn!(
+            "Failed to write index cache {}: {error}",
+            cache_path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::io;
+    use tempfile::TempDir;
+
+    /// Indexes the files of `dir` by content length, counting calls to the parser
+    fn index_lengths(dir: &Path, cache: &Path, parses: &Cell<usize>) -> HashMap<PathBuf, usize> {
+        let mut files: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|name| dir.join(name))
+            .filter(|path| path.exists())
+            .collect();
+        files.sort();
+        index_files(files.into_iter(), Some(cache), |_, source| {
+            parses.set(parses.get() + 1);
+            Ok(source.len())
+        })
+    }
+
+    /// Tests that a second index only parses files that changed.
+    ///
+    /// # Errors
+    /// Returns an error if the test files cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_unchanged_files_are_not_reparsed() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let cache = dir.path().join(".merlin").join("index.bin");
+        fs::write(dir.path().join("a.txt"), "one")?;
+        fs::write(dir.path().join("b.txt"), "three")?;
+        let parses = Cell::new(0);
+
+        let first = index_lengths(dir.path(), &cache, &parses);
+        assert_eq!(parses.get(), 2);
+        assert!(cache.exists());
+
+        let second = index_lengths(dir.path(), &cache, &parses);
+        assert_eq!(
+            parses.get(),
+            2,
+            "unchanged files should come from the cache"
+        );
+        assert_eq!(first, second);
+
+        fs::write(dir.path().join("b.txt"), "changed")?;
+        fs::write(dir.path().join("c.txt"), "new")?;
+        fs::remove_file(dir.path().join("a.txt"))?;
+        let third = index_lengths(dir.path(), &cache, &parses);
+        assert_eq!(parses.get(), 4);
+        assert_eq!(third.get(&dir.path().join("b.txt")), Some(&7));
+        assert_eq!(third.len(), 2);
+
+        index_lengths(dir.path(), &cache, &parses);
+        assert_eq!(parses.get(), 4);
+        Ok(())
+    }
+
+    /// Tests that unreadable or outdated caches fall back to parsing every file.
+    ///
+    /// # Errors
+    /// Returns an error if the test files cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_stale_cache_parses_all_files() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let cache = dir.path().join("index.bin");
+        fs::write(dir.path().join("a.txt"), "one")?;
+        fs::write(dir.path().join("b.txt"), "three")?;
+        let parses = Cell::new(0);
+
+        fs::write(&cache, b"not a cache")?;
+        assert_eq!(index_lengths(dir.path(), &cache, &parses).len(), 2);
+        assert_eq!(parses.get(), 2);
+
+        let outdated = IndexCache::<usize> {
+            version: INDEX_CACHE_VERSION,
+            merlin_version: "0.0.0-old".to_owned(),
+            files: vec![CachedFile {
+                path: dir.path().join("a.txt"),
+                stamp: FileStamp::of(&dir.path().join("a.txt"))
+                    .ok_or_else(|| io::Error::other("missing stamp"))?,
+                parsed: 100,
+            }],
+        };
+        let bytes = encode_to_vec(&outdated, bincode_config()).map_err(io::Error::other)?;
+        fs::write(&cache, bytes)?;
+        let reparsed = index_lengths(dir.path(), &cache, &parses);
+        assert_eq!(parses.get(), 4);
+        assert_eq!(reparsed.get(&dir.path().join("a.txt")), Some(&3));
+        Ok(())
+    }
+}
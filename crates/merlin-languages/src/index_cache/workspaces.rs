@@ -0,0 +1,322 @@
+//! Index caches of several workspaces, bounded to the most recently used.
+//!
+//! Every workspace root gets its own cache file in the language's directory,
+//! so projects sharing a `MERLIN_FOLDER` no longer overwrite each other's
+//! cache. Indexing a workspace refreshes the modification time of its file,
+//! and the least recently indexed files beyond the limit are deleted.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::{Hash as _, Hasher as _};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use bincode::{Decode, Encode};
+use merlin_core::CoreResult as Result;
+
+use super::index_counting_reuse;
+
+/// Number of workspaces whose index is cached per language by default
+pub const DEFAULT_MAX_WORKSPACES: usize = 3;
+
+/// Index cache use counted since the counters were created
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Files taken from the cache
+    pub hits: u64,
+    /// Files parsed because they were not cached or changed
+    pub misses: u64,
+    /// Workspace caches deleted to stay within the limit
+    pub evictions: u64,
+}
+
+/// Counters of index cache use, shared by every clone
+#[derive(Debug, Clone, Default)]
+pub struct IndexCacheStats {
+    /// Counters updated by the caches holding a clone
+    counters: Arc<Counters>,
+}
+
+/// Atomic counters behind [`IndexCacheStats`]
+#[derive(Debug, Default)]
+struct Counters {
+    /// Files taken from the cache
+    hits: AtomicU64,
+    /// Files parsed
+    misses: AtomicU64,
+    /// Workspace caches deleted
+    evictions: AtomicU64,
+}
+
+impl IndexCacheStats {
+    /// Creates counters starting at zero
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the current counts
+    pub fn snapshot(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Adds `hits`, `misses` and `evictions` to the counts
+    fn add(&self, hits: usize, misses: usize, evictions: usize) {
+        let count = |value: usize| u64::try_from(value).unwrap_or(u64::MAX);
+        self.counters.hits.fetch_add(count(hits), Ordering::Relaxed);
+        self.counters
+            .misses
+            .fetch_add(count(misses), Ordering::Relaxed);
+        self.counters
+            .evictions
+            .fetch_add(count(evictions), Ordering::Relaxed);
+    }
+}
+
+/// Parse results of the most recently indexed workspaces, one file each
+#[derive(Debug, Clone)]
+pub struct WorkspaceCache {
+    /// Directory holding a subdirectory of workspace caches per language
+    dir: PathBuf,
+    /// Number of workspace caches kept per language
+    max_entries: usize,
+    /// Counters of cache use
+    stats: IndexCacheStats,
+}
+
+impl WorkspaceCache {
+    /// Creates a cache in `dir` keeping [`DEFAULT_MAX_WORKSPACES`] workspaces per language
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_entries: DEFAULT_MAX_WORKSPACES,
+            stats: IndexCacheStats::new(),
+        }
+    }
+
+    /// Keeps the caches of up to `max_entries` workspaces per language (at least one)
+    #[must_use]
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Counts cache use on `stats`, e.g. to share counters between caches
+    #[must_use]
+    pub fn with_stats(mut self, stats: IndexCacheStats) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Returns the hits, misses and evictions counted so far
+    pub fn stats(&self) -> CacheStats {
+        self.stats.snapshot()
+    }
+
+    /// Parses the `files` of the workspace at `root`, reusing its cached results
+    ///
+    /// Works like [`index_files`](super::index_files) with the cache file of
+    /// `root`, then evicts the least recently indexed workspaces of `language`
+    /// beyond the limit.
+    pub fn index_files<Parsed>(
+        &self,
+        language: &str,
+        root: &Path,
+        files: impl Iterator<Item = PathBuf>,
+        parse: impl FnMut(&Path, &str) -> Result<Parsed>,
+    ) -> HashMap<PathBuf, Parsed>
+    where
+        Parsed: Encode + Decode<()>,
+    {
+        let language_dir = self.dir.join(language);
+        let cache_path = language_dir.join(workspace_file_name(root));
+        let (index, reused) = index_counting_reuse(files, Some(&cache_path), parse);
+        touch(&cache_path);
+        let evicted = self.evict(&language_dir, &cache_path);
+        self.stats.add(reused, index.len() - reused, evicted);
+        index
+    }
+
+    /// Deletes the least recently used caches in `language_dir` beyond the limit
+    ///
+    /// `current` is kept whatever its modification time. Returns the number of
+    /// caches deleted.
+    fn evict(&self, language_dir: &Path, current: &Path) -> usize {
+        let Ok(entries) = fs::read_dir(language_dir) else {
+            return 0;
+        };
+        let mut others: Vec<(SystemTime, PathBuf)> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let modified = fs::metadata(&path).ok()?.modified().ok()?;
+                (path != current && path.extension().is_some_and(|ext| ext == "bin"))
+                    .then_some((modified, path))
+            })
+            .collect();
+        others.sort_by(|left, right| right.0.cmp(&left.0));
+
+        let kept = self
+            .max_entries
+            .saturating_sub(usize::from(current.exists()));
+        others
+            .into_iter()
+            .skip(kept)
+            .filter(|(_, path)| match fs::remove_file(path) {
+                Ok(()) => {
+                    tracing::debug!("Evicted index cache {}", path.display());
+                    true
+                }
+                Err(error) => {
+                    tracing::warn!("Failed to evict index cache {}: {error}", path.display());
+                    false
+                }
+            })
+            .count()
+    }
+}
+
+/// Parses `files` with the cache of `root` in `cache`, or without caching if there is none
+pub fn index_workspace<Parsed>(
+    cache: Option<&WorkspaceCache>,
+    language: &str,
+    root: &Path,
+    files: impl Iterator<Item = PathBuf>,
+    parse: impl FnMut(&Path, &str) -> Result<Parsed>,
+) -> HashMap<PathBuf, Parsed>
+where
+    Parsed: Encode + Decode<()>,
+{
+    match cache {
+        Some(cache) => cache.index_files(language, root, files, parse),
+        None => index_counting_reuse(files, None, parse).0,
+    }
+}
+
+/// Name of the cache file of the workspace at `root`
+fn workspace_file_name(root: &Path) -> String {
+    let mut hasher = DefaultHasher::new();
+    root.hash(&mut hasher);
+    format!("{:016x}.bin", hasher.finish())
+}
+
+/// Marks the cache at `path` as just used, if it exists
+fn touch(path: &Path) {
+    let touched = File::options()
+        .append(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+    if let Err(error) = touched {
+        tracing::debug!("Index cache {} not touched: {error}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io, iter};
+    use tempfile::TempDir;
+
+    /// Indexes the single file of `root` with `cache`
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be written.
+    fn index(cache: &WorkspaceCache, root: &Path) -> io::Result<HashMap<PathBuf, usize>> {
+        fs::create_dir_all(root)?;
+        let file = root.join("main.txt");
+        if !file.exists() {
+            fs::write(&file, "source")?;
+        }
+        Ok(cache.index_files("text", root, iter::once(file), |_, source| Ok(source.len())))
+    }
+
+    /// Tests that hits and misses count reused and parsed files.
+    ///
+    /// # Errors
+    /// Returns an error if the test files cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_stats_count_hits_and_misses() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let cache = WorkspaceCache::new(dir.path().join("cache"));
+        let root = dir.path().join("project");
+
+        index(&cache, &root)?;
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 0,
+                misses: 1,
+                evictions: 0
+            }
+        );
+        index(&cache, &root)?;
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 1,
+                evictions: 0
+            }
+        );
+
+        let shared = WorkspaceCache::new(dir.path().join("cache")).with_stats(cache.stats.clone());
+        index(&shared, &root)?;
+        assert_eq!(cache.stats().hits, 2);
+        Ok(())
+    }
+
+    /// Tests that the least recently indexed workspaces are evicted beyond the limit.
+    ///
+    /// # Errors
+    /// Returns an error if the test files cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_least_recently_indexed_workspace_evicted() -> io::Result<()> {
+        let dir = TempDir::new()?;
+        let cache = WorkspaceCache::new(dir.path().join("cache")).with_max_entries(2);
+        let [first, second, third] = ["first", "second", "third"].map(|name| dir.path().join(name));
+        let cached = |root: &Path| {
+            dir.path()
+                .join("cache")
+                .join("text")
+                .join(workspace_file_name(root))
+                .exists()
+        };
+
+        index(&cache, &first)?;
+        index(&cache, &second)?;
+        File::options()
+            .append(true)
+            .open(
+                dir.path()
+                    .join("cache")
+                    .join("text")
+                    .join(workspace_file_name(&second)),
+            )?
+            .set_modified(SystemTime::UNIX_EPOCH)?;
+        index(&cache, &first)?;
+        index(&cache, &third)?;
+
+        assert!(cached(&first));
+        assert!(
+            !cached(&second),
+            "least recently indexed workspace should be evicted"
+        );
+        assert!(cached(&third));
+        assert_eq!(cache.stats().evictions, 1);
+
+        index(&cache, &second)?;
+        assert_eq!(cache.stats().misses, 4);
+        Ok(())
+    }
+}
@@ -1,9 +1,42 @@
 //! Language-specific code analysis and context building.
 //!
-//! This crate provides language provider abstractions for semantic code analysis.
-//! Language backends have been removed as they are not currently in use.
+//! This crate provides language provider abstractions for semantic code analysis,
+//! plus tree-sitter backends for Python and Go projects, an SWC backend for
+//! TypeScript and JavaScript projects, and a backend combining them for
+//! projects mixing languages.
 
+/// Helpers shared by the language backends.
+mod backend;
+/// Call sites and call hierarchy queries shared by the backends.
+pub mod calls;
+/// Syntax-aligned chunk boundaries for embedding.
+pub mod chunking;
+/// Links between files in different languages.
+pub mod cross_language;
+/// Detection of the languages used by a project.
+pub mod detect;
+/// Go backend built on tree-sitter.
+pub mod golang;
+/// Parse results persisted between runs.
+pub mod index_cache;
+/// Projects mixing several languages.
+pub mod polyglot;
 /// Language provider trait and types.
 pub mod provider;
+/// Python backend built on tree-sitter.
+pub mod python;
+/// TypeScript and JavaScript backend built on SWC.
+pub mod typescript;
 
-pub use provider::{LanguageProvider, SearchQuery, SearchResult, SymbolInfo, SymbolKind};
+pub use calls::CallSite;
+pub use chunking::{CodeChunker, CodeSpan, chunker_for};
+pub use cross_language::CrossLanguageResolver;
+pub use detect::{DetectedLanguage, Language, compose_backends, detect_languages};
+pub use golang::GoBackend;
+pub use index_cache::{CacheStats, IndexCacheStats, WorkspaceCache};
+pub use polyglot::{PolyglotBackend, import_graph};
+pub use provider::{
+    LanguageProvider, RelatedFile, SearchQuery, SearchResult, SymbolInfo, SymbolKind,
+};
+pub use python::PythonBackend;
+pub use typescript::TypeScriptBackend;
@@ -0,0 +1,275 @@
+//! Projects mixing several languages.
+//!
+//! [`PolyglotBackend`] combines one backend per language and routes every
+//! per-file query to the backend handling the file's extension, so a Python
+//! service and its TypeScript frontend are both indexed. [`import_graph`]
+//! resolves the imports of individual files for retrieval ranking without
+//! indexing the project.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use merlin_core::{CoreResult as Result, FileContext};
+
+use crate::provider::{LanguageProvider, SearchQuery, SearchResult, SymbolInfo};
+use crate::python::is_python_file;
+use crate::typescript::is_typescript_file;
+use crate::{PythonBackend, TypeScriptBackend};
+
+/// A language backend and the files it handles
+struct LanguageBackend {
+    /// Language name, for logging
+    language: &'static str,
+    /// Returns true for the files this backend parses
+    handles: fn(&Path) -> bool,
+    /// The backend itself
+    provider: Box<dyn LanguageProvider>,
+}
+
+/// Backend combining the backends of every language in a project
+#[derive(Default)]
+pub struct PolyglotBackend {
+    /// Backends in order of precedence
+    backends: Vec<LanguageBackend>,
+}
+
+impl PolyglotBackend {
+    /// Creates a backend without languages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the backend of a language, handling the files accepted by `handles`
+    #[must_use]
+    pub fn with_backend(
+        mut self,
+        language: &'static str,
+        handles: fn(&Path) -> bool,
+        provider: Box<dyn LanguageProvider>,
+    ) -> Self {
+        self.backends.push(LanguageBackend {
+            language,
+            handles,
+            provider,
+        });
+        self
+    }
+
+    /// Returns the names of the combined languages, in order of precedence
+    pub fn languages(&self) -> Vec<&'static str> {
+        self.backends
+            .iter()
+            .map(|backend| backend.language)
+            .collect()
+    }
+
+    /// Returns the backend handling `file`, if any
+    fn backend_for(&self, file: &Path) -> Option<&dyn LanguageProvider> {
+        self.backends
+            .iter()
+            .find(|backend| (backend.handles)(file))
+            .map(|backend| backend.provider.as_ref())
+    }
+
+    /// Concatenates the results of a query run against every backend
+    ///
+    /// # Errors
+    /// Returns the first error of a backend
+    fn collect_all(
+        &self,
+        query: impl Fn(&dyn LanguageProvider) -> Result<Vec<SymbolInfo>>,
+    ) -> Result<Vec<SymbolInfo>> {
+        let mut symbols = Vec::new();
+        for backend in &self.backends {
+            symbols.extend(query(backend.provider.as_ref())?);
+        }
+        Ok(symbols)
+    }
+}
+
+impl LanguageProvider for PolyglotBackend {
+    fn initialize(&mut self, project_root: &Path) -> Result<()> {
+        for backend in &mut self.backends {
+            backend.provider.initialize(project_root)?;
+        }
+        tracing::info!(
+            "Polyglot backend initialized for {}",
+            self.languages().join(", ")
+        );
+        Ok(())
+    }
+
+    fn search_symbols(&self, query: &SearchQuery) -> Result<SearchResult> {
+        let mut combined = SearchResult {
+            symbols: Vec::new(),
+            related_files: Vec::new(),
+        };
+        for backend in &self.backends {
+            let result = backend.provider.search_symbols(query)?;
+            combined.symbols.extend(result.symbols);
+            for related in result.related_files {
+                if combined
+                    .related_files
+                    .iter()
+                    .all(|existing| existing.file.path != related.file.path)
+                {
+                    combined.related_files.push(related);
+                }
+            }
+        }
+
+        // Exact matches of any language come first; the sort is stable, keeping each backend's order
+        combined
+            .symbols
+            .sort_by_key(|symbol| query.symbol_name.as_deref() != Some(symbol.name.as_str()));
+        combined.symbols.truncate(query.max_results);
+        Ok(combined)
+    }
+
+    fn find_definition(
+        &self,
+        symbol_name: &str,
+        file: &Path,
+        line: u32,
+    ) -> Result<Option<SymbolInfo>> {
+        self.backend_for(file).map_or(Ok(None), |backend| {
+            backend.find_definition(symbol_name, file, line)
+        })
+    }
+
+    fn find_references(&self, symbol_name: &str) -> Result<Vec<SymbolInfo>> {
+        self.collect_all(|backend| backend.find_references(symbol_name))
+    }
+
+    fn find_callers(&self, symbol_name: &str, file: &Path, line: u32) -> Result<Vec<SymbolInfo>> {
+        // Calls are resolved by name within one language
+        self.backend_for(file).map_or(Ok(Vec::new()), |backend| {
+            backend.find_callers(symbol_name, file, line)
+        })
+    }
+
+    fn find_implementations(&self, type_name: &str) -> Result<Vec<SymbolInfo>> {
+        self.collect_all(|backend| backend.find_implementations(type_name))
+    }
+
+    fn get_related_context(&self, file: &Path) -> Result<Vec<FileContext>> {
+        self.backend_for(file)
+            .map_or(Ok(Vec::new()), |backend| backend.get_related_context(file))
+    }
+
+    fn extract_imports(&self, file: &Path) -> Result<Vec<PathBuf>> {
+        self.backend_for(file)
+            .map_or(Ok(Vec::new()), |backend| backend.extract_imports(file))
+    }
+
+    fn unresolved_imports(&self, file: &Path) -> Result<Vec<String>> {
+        self.backend_for(file)
+            .map_or(Ok(Vec::new()), |backend| backend.unresolved_imports(file))
+    }
+
+    fn list_symbols_in_file(&self, file: &Path) -> Result<Vec<SymbolInfo>> {
+        self.backend_for(file)
+            .map_or(Ok(Vec::new()), |backend| backend.list_symbols_in_file(file))
+    }
+
+    fn apply_file_change(&mut self, file: &Path, new_text: Option<&str>) -> Result<()> {
+        // Every backend sees the change, since config files like `go.mod` or
+        // `tsconfig.json` matter to a backend without being its source files
+        for backend in &mut self.backends {
+            backend.provider.apply_file_change(file, new_text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the project files imported by each of `files`
+///
+/// Files are parsed on demand rather than by indexing the project, so this is
+/// meant for the handful of files ranked for one query. Python and TypeScript
+/// files are supported; Go imports name packages, which need the module index,
+/// so Go files and files of other languages get no edges.
+pub fn import_graph(project_root: &Path, files: &[PathBuf]) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut python = None;
+    let mut typescript = None;
+    let mut graph = HashMap::new();
+    for file in files {
+        let backend: &dyn LanguageProvider = if is_python_file(file) {
+            python.get_or_insert_with(|| PythonBackend::unindexed(project_root))
+        } else if is_typescript_file(file) {
+            typescript.get_or_insert_with(|| TypeScriptBackend::unindexed(project_root))
+        } else {
+            continue;
+        };
+        match backend.extract_imports(file) {
+            Ok(imports) if !imports.is_empty() => {
+                graph.insert(file.clone(), imports);
+            }
+            Ok(_) => {}
+            Err(error) => tracing::debug!("No imports for {}: {error}", file.display()),
+        }
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Tests that files are routed to the backend of their language.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be written or indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_routes_files_by_extension() -> Result<()> {
+        let dir = TempDir::new()?;
+        let root = dir.path();
+        fs::write(
+            root.join("server.py"),
+            "def render(page):\n    return page\n",
+        )?;
+        fs::write(
+            root.join("client.ts"),
+            "export function render(page: string) {\n  return page;\n}\n",
+        )?;
+        let mut backend = PolyglotBackend::new()
+            .with_backend("Python", is_python_file, Box::new(PythonBackend::new()))
+            .with_backend(
+                "TypeScript",
+                is_typescript_file,
+                Box::new(TypeScriptBackend::new()),
+            );
+        backend.initialize(root)?;
+
+        let query = SearchQuery {
+            symbol_name: Some("render".to_owned()),
+            ..SearchQuery::default()
+        };
+        let files: Vec<PathBuf> = backend
+            .search_symbols(&query)?
+            .symbols
+            .into_iter()
+            .map(|symbol| symbol.file_path)
+            .collect();
+        assert_eq!(files, [root.join("server.py"), root.join("client.ts")]);
+
+        let definition = backend.find_definition("render", &root.join("client.ts"), 1)?;
+        assert_eq!(
+            definition.map(|symbol| symbol.file_path),
+            Some(root.join("client.ts"))
+        );
+        assert!(
+            backend
+                .list_symbols_in_file(&root.join("README.md"))?
+                .is_empty()
+        );
+
+        backend.apply_file_change(&root.join("client.ts"), None)?;
+        assert_eq!(backend.find_references("render")?.len(), 1);
+        Ok(())
+    }
+}
@@ -1,6 +1,13 @@
+use bincode::{Decode, Encode};
 use merlin_core::{CoreResult as Result, FileContext};
 use std::path::{Path, PathBuf};
 
+/// Weight of files related through a resolved import or a matching symbol
+pub const DIRECT_WEIGHT: f32 = 1.0;
+
+/// Weight of files linked across a language boundary by [`crate::CrossLanguageResolver`]
+pub const CROSS_LANGUAGE_WEIGHT: f32 = 0.5;
+
 /// Information about a code symbol (function, struct, etc.)
 #[derive(Debug, Clone)]
 pub struct SymbolInfo {
@@ -17,7 +24,7 @@ pub struct SymbolInfo {
 }
 
 /// The kind of code symbol
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
 pub enum SymbolKind {
     /// A function
     Function,
@@ -48,7 +55,7 @@ pub struct SearchQuery {
     pub symbol_name: Option<String>,
     /// Whether to include references to the symbol
     pub include_references: bool,
-    /// Whether to include trait implementations
+    /// Whether to add the types implementing or extending the named type
     pub include_implementations: bool,
     /// Maximum number of results to return
     pub max_results: usize,
@@ -65,13 +72,32 @@ impl Default for SearchQuery {
     }
 }
 
+/// File related to a search result
+#[derive(Debug, Clone)]
+pub struct RelatedFile {
+    /// The file context
+    pub file: FileContext,
+    /// Relevance relative to the symbols' own files ([`DIRECT_WEIGHT`])
+    pub weight: f32,
+}
+
+impl RelatedFile {
+    /// Creates a related file with [`DIRECT_WEIGHT`]
+    pub const fn direct(file: FileContext) -> Self {
+        Self {
+            file,
+            weight: DIRECT_WEIGHT,
+        }
+    }
+}
+
 /// Results from a symbol search
 #[derive(Debug, Clone)]
 pub struct SearchResult {
     /// The symbols found
     pub symbols: Vec<SymbolInfo>,
     /// Related file contexts
-    pub related_files: Vec<FileContext>,
+    pub related_files: Vec<RelatedFile>,
 }
 
 /// Language-specific code analysis provider
@@ -105,6 +131,23 @@ pub trait LanguageProvider: Send + Sync {
     /// Returns an error if the search fails
     fn find_references(&self, symbol_name: &str) -> Result<Vec<SymbolInfo>>;
 
+    /// Find the functions and methods calling a function, method or constructor
+    ///
+    /// `file` and `line` locate the definition of `symbol_name`; if it is a
+    /// method, only calls through a receiver count. Each caller is reported at
+    /// the line of the call, and calls outside any function are reported as
+    /// [`MODULE_CALLER`](crate::calls::MODULE_CALLER).
+    ///
+    /// # Errors
+    /// Returns an error if the definition's file cannot be analyzed
+    fn find_callers(&self, symbol_name: &str, file: &Path, line: u32) -> Result<Vec<SymbolInfo>>;
+
+    /// Find the types implementing an interface or extending a class
+    ///
+    /// # Errors
+    /// Returns an error if the search fails
+    fn find_implementations(&self, type_name: &str) -> Result<Vec<SymbolInfo>>;
+
     /// Get files related to a given file through imports/dependencies
     ///
     /// # Errors
@@ -117,11 +160,33 @@ pub trait LanguageProvider: Send + Sync {
     /// Returns an error if the file cannot be parsed
     fn extract_imports(&self, file: &Path) -> Result<Vec<PathBuf>>;
 
+    /// Extract the module names of imports that `extract_imports` could not resolve
+    ///
+    /// These may name modules implemented in another language, see
+    /// [`CrossLanguageResolver`]. Backends that do not track them return nothing.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be parsed
+    fn unresolved_imports(&self, _file: &Path) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
     /// List all symbols defined in a file
     ///
     /// # Errors
     /// Returns an error if the file cannot be analyzed
     fn list_symbols_in_file(&self, file: &Path) -> Result<Vec<SymbolInfo>>;
+
+    /// Update the index after a file was created, edited or deleted
+    ///
+    /// `new_text` is the new content of the file, or `None` if it was deleted.
+    /// Only that file is re-parsed, so this is much cheaper than `initialize`.
+    /// Files the project scan would skip are ignored.
+    ///
+    /// # Errors
+    /// Returns an error if the new content cannot be parsed; the file is then
+    /// dropped from the index
+    fn apply_file_change(&mut self, file: &Path, new_text: Option<&str>) -> Result<()>;
 }
 
 #[cfg(test)]
@@ -0,0 +1,323 @@
+//! Python language backend built on `tree-sitter-python`.
+//!
+//! Parses every `.py` file under the project root once during initialization and
+//! answers symbol, reference and import queries from the parsed modules.
+
+mod hierarchy;
+mod parser;
+
+pub use parser::{PythonImport, PythonModule, PythonSymbol, parse_python};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use merlin_core::{CoreResult as Result, Error, FileContext};
+
+use crate::backend::{
+    find_word_references, is_indexed_path, rank_symbols, reindex_file, source_files,
+};
+use crate::calls::add_implementations;
+use crate::chunking::{CodeChunker, CodeSpan, nest_spans, with_leading_comments};
+use crate::index_cache::{WorkspaceCache, index_workspace};
+use crate::provider::{LanguageProvider, SearchQuery, SearchResult, SymbolInfo, SymbolKind};
+
+/// Directories skipped while scanning for Python files
+const IGNORED_DIRS: &[&str] = &[
+    "__pycache__",
+    "node_modules",
+    "target",
+    "build",
+    "dist",
+    "venv",
+    "site-packages",
+];
+
+/// Source roots that absolute imports are resolved against, relative to the project root
+const SOURCE_ROOTS: &[&str] = &["", "src"];
+
+/// Returns true if `path` is a Python source file
+pub fn is_python_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "py")
+}
+
+/// Returns true if a project contains at least one Python source file
+pub fn contains_python_files(project_root: &Path) -> bool {
+    python_files(project_root).next().is_some()
+}
+
+/// Iterates over the Python files under `project_root`, skipping hidden and build directories
+pub(crate) fn python_files(project_root: &Path) -> impl Iterator<Item = PathBuf> {
+    source_files(project_root, IGNORED_DIRS, is_python_file)
+}
+
+/// Python backend that indexes the definitions and imports of a project
+#[derive(Debug, Default)]
+pub struct PythonBackend {
+    /// Project root the backend was initialized with
+    project_root: PathBuf,
+    /// Parsed modules keyed by file path
+    modules: HashMap<PathBuf, PythonModule>,
+    /// Where parse results are persisted between runs, if anywhere
+    index_cache: Option<WorkspaceCache>,
+}
+
+impl PythonBackend {
+    /// Creates an uninitialized backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persists parse results in `cache` so unchanged files are not parsed on the next start
+    #[must_use]
+    pub fn with_index_cache(mut self, cache: WorkspaceCache) -> Self {
+        self.index_cache = Some(cache);
+        self
+    }
+
+    /// Creates a backend for resolving imports of `project_root` without indexing it
+    ///
+    /// Files are parsed when queried, so this suits a few lookups on demand.
+    pub(crate) fn unindexed(project_root: &Path) -> Self {
+        Self {
+            project_root: project_root.to_path_buf(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns the number of parsed Python files
+    pub fn file_count(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Returns the parsed module for `file`, parsing it if it was not indexed
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed
+    fn module(&self, file: &Path) -> Result<PythonModule> {
+        if let Some(module) = self.modules.get(file) {
+            return Ok(module.clone());
+        }
+        let source = fs::read_to_string(file)
+            .map_err(|_| Error::FileNotFound(file.display().to_string()))?;
+        parse_python(&source)
+    }
+
+    /// Resolves an import to the project files it refers to
+    fn resolve_import(&self, file: &Path, import: &PythonImport) -> Vec<PathBuf> {
+        let bases: Vec<PathBuf> = if import.level == 0 {
+            SOURCE_ROOTS
+                .iter()
+                .map(|root| self.project_root.join(root))
+                .collect()
+        } else {
+            // One dot is the importing file's package, each further dot goes up a level
+            file.ancestors()
+                .nth(import.level)
+                .map(Path::to_path_buf)
+                .into_iter()
+                .collect()
+        };
+
+        let mut resolved = Vec::new();
+        for base in bases {
+            let module_dir = import
+                .module
+                .split('.')
+                .filter(|part| !part.is_empty())
+                .fold(base, |path, part| path.join(part));
+
+            // `from . import name` names modules of the package itself
+            let mut candidates = if import.module.is_empty() {
+                Vec::new()
+            } else {
+                vec![
+                    module_dir.with_extension("py"),
+                    module_dir.join("__init__.py"),
+                ]
+            };
+            // `from package import module` imports submodules
+            candidates.extend(
+                import
+                    .names
+                    .iter()
+                    .map(|name| module_dir.join(name).with_extension("py")),
+            );
+
+            for candidate in candidates {
+                if candidate.is_file() && candidate != file && !resolved.contains(&candidate) {
+                    resolved.push(candidate);
+                }
+            }
+        }
+        resolved
+    }
+}
+
+/// Converts a parsed symbol into the provider's symbol type
+fn symbol_info(file: &Path, symbol: &PythonSymbol) -> SymbolInfo {
+    SymbolInfo {
+        name: symbol.name.clone(),
+        kind: symbol.kind,
+        file_path: file.to_path_buf(),
+        line: u32::try_from(symbol.start_line).unwrap_or(u32::MAX),
+        documentation: symbol.documentation.clone(),
+    }
+}
+
+impl LanguageProvider for PythonBackend {
+    fn initialize(&mut self, project_root: &Path) -> Result<()> {
+        self.project_root = project_root.to_path_buf();
+        self.modules = index_workspace(
+            self.index_cache.as_ref(),
+            "python",
+            project_root,
+            python_files(project_root),
+            |_, source| parse_python(source),
+        );
+
+        tracing::info!("Python backend indexed {} files", self.modules.len());
+        Ok(())
+    }
+
+    fn search_symbols(&self, query: &SearchQuery) -> Result<SearchResult> {
+        let symbols = self.modules.iter().flat_map(|(path, module)| {
+            module
+                .symbols
+                .iter()
+                .map(move |symbol| symbol_info(path, symbol))
+        });
+        let mut result = rank_symbols(symbols, query);
+        add_implementations(&mut result, query, self)?;
+        Ok(result)
+    }
+
+    fn find_definition(
+        &self,
+        symbol_name: &str,
+        file: &Path,
+        _line: u32,
+    ) -> Result<Option<SymbolInfo>> {
+        // Prefer a definition in the same file, then one in a file it imports
+        let local = self.module(file)?;
+        if let Some(symbol) = local
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == symbol_name)
+        {
+            return Ok(Some(symbol_info(file, symbol)));
+        }
+
+        for import in &local.imports {
+            for path in self.resolve_import(file, import) {
+                if let Some(symbol) = self.modules.get(&path).and_then(|module| {
+                    module
+                        .symbols
+                        .iter()
+                        .find(|symbol| symbol.name == symbol_name)
+                }) {
+                    return Ok(Some(symbol_info(&path, symbol)));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn find_references(&self, symbol_name: &str) -> Result<Vec<SymbolInfo>> {
+        Ok(find_word_references(self.modules.keys(), symbol_name))
+    }
+
+    fn find_callers(&self, symbol_name: &str, file: &Path, line: u32) -> Result<Vec<SymbolInfo>> {
+        hierarchy::find_callers(self, symbol_name, file, line)
+    }
+
+    fn find_implementations(&self, type_name: &str) -> Result<Vec<SymbolInfo>> {
+        Ok(hierarchy::find_implementations(self, type_name))
+    }
+
+    fn get_related_context(&self, file: &Path) -> Result<Vec<FileContext>> {
+        Ok(self
+            .extract_imports(file)?
+            .iter()
+            .filter_map(|path| FileContext::from_path(path).ok())
+            .collect())
+    }
+
+    fn extract_imports(&self, file: &Path) -> Result<Vec<PathBuf>> {
+        let module = self.module(file)?;
+        let mut imports: Vec<PathBuf> = Vec::new();
+        for import in &module.imports {
+            for path in self.resolve_import(file, import) {
+                if !imports.contains(&path) {
+                    imports.push(path);
+                }
+            }
+        }
+        Ok(imports)
+    }
+
+    fn unresolved_imports(&self, file: &Path) -> Result<Vec<String>> {
+        let module = self.module(file)?;
+        let mut unresolved: Vec<String> = Vec::new();
+        for import in &module.imports {
+            if !self.resolve_import(file, import).is_empty() {
+                continue;
+            }
+            // `from . import _native` names the module in the imported names
+            let modules = if import.module.is_empty() {
+                import.names.clone()
+            } else {
+                vec![import.module.clone()]
+            };
+            for name in modules {
+                if !unresolved.contains(&name) {
+                    unresolved.push(name);
+                }
+            }
+        }
+        Ok(unresolved)
+    }
+
+    fn list_symbols_in_file(&self, file: &Path) -> Result<Vec<SymbolInfo>> {
+        Ok(self
+            .module(file)?
+            .symbols
+            .iter()
+            .map(|symbol| symbol_info(file, symbol))
+            .collect())
+    }
+
+    fn apply_file_change(&mut self, file: &Path, new_text: Option<&str>) -> Result<()> {
+        if !is_indexed_path(&self.project_root, file, IGNORED_DIRS, is_python_file) {
+            return Ok(());
+        }
+        reindex_file(&mut self.modules, file, new_text, parse_python)
+    }
+}
+
+impl CodeChunker for PythonBackend {
+    fn code_spans(&self, _file: &Path, source: &str) -> Result<Vec<CodeSpan>> {
+        let module = parse_python(source)?;
+        let lines: Vec<&str> = source.lines().collect();
+        let spans = module
+            .symbols
+            .iter()
+            .filter_map(|symbol| {
+                let identifier = match (symbol.kind, &symbol.parent) {
+                    (SymbolKind::Struct, _) => format!("class {}", symbol.name),
+                    (SymbolKind::Function, None) => format!("def {}", symbol.name),
+                    (SymbolKind::Method, Some(class)) => format!("def {class}.{}", symbol.name),
+                    _ => return None,
+                };
+                let start_line =
+                    with_leading_comments(&lines, symbol.start_line, |line| line.starts_with('#'));
+                Some(CodeSpan::new(identifier, start_line, symbol.end_line))
+            })
+            .collect();
+        Ok(nest_spans(spans))
+    }
+}
+
+#[cfg(test)]
+mod tests;
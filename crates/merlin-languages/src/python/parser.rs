@@ -0,0 +1,415 @@
+//! Python source parsing with `tree-sitter-python`.
+
+use bincode::{Decode, Encode};
+use merlin_core::{CoreResult as Result, Error};
+use tree_sitter::{Language, Node, Parser};
+use tree_sitter_python::LANGUAGE;
+
+use crate::calls::CallSite;
+use crate::provider::SymbolKind;
+
+/// A definition found in a Python module
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct PythonSymbol {
+    /// Name of the definition
+    pub name: String,
+    /// Kind of definition (classes map to `Struct`)
+    pub kind: SymbolKind,
+    /// First line of the definition, including decorators (1-indexed)
+    pub start_line: usize,
+    /// Last line of the definition (1-indexed)
+    pub end_line: usize,
+    /// Name of the enclosing class for methods and fields
+    pub parent: Option<String>,
+    /// Docstring of the class or function
+    pub documentation: Option<String>,
+    /// Base classes of a class, without module prefixes
+    pub bases: Vec<String>,
+}
+
+/// An `import` or `from ... import` statement
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct PythonImport {
+    /// Dotted module path (empty for `from . import name`)
+    pub module: String,
+    /// Number of leading dots in a relative import (0 for absolute imports)
+    pub level: usize,
+    /// Names imported with `from ... import` (empty for plain `import`)
+    pub names: Vec<String>,
+}
+
+/// Definitions and imports of a parsed Python module
+#[derive(Debug, Clone, Default, Encode, Decode)]
+pub struct PythonModule {
+    /// Classes, functions, methods, fields and module-level variables in source order
+    pub symbols: Vec<PythonSymbol>,
+    /// Import statements at module level
+    pub imports: Vec<PythonImport>,
+    /// Calls anywhere in the module, including class instantiations
+    pub calls: Vec<CallSite>,
+}
+
+/// Parses Python source into its definitions and imports
+///
+/// # Errors
+/// Returns an error if the grammar cannot be loaded or the parser gives up
+pub fn parse_python(source: &str) -> Result<PythonModule> {
+    let mut parser = Parser::new();
+    parser
+        .set_language(&Language::from(LANGUAGE))
+        .map_err(|err| Error::Other(format!("Failed to load Python grammar: {err}")))?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| Error::Other("Failed to parse Python source".to_owned()))?;
+
+    let mut module = PythonModule::default();
+    collect_definitions(tree.root_node(), source, None, &mut module);
+    collect_calls(tree.root_node(), source, &mut module.calls);
+    Ok(module)
+}
+
+/// Collects definitions and imports from the statements of a module or class body
+fn collect_definitions(
+    node: Node<'_>,
+    source: &str,
+    class_name: Option<&str>,
+    module: &mut PythonModule,
+) {
+    let mut cursor = node.walk();
+    for statement in node.named_children(&mut cursor) {
+        // Decorators belong to the definition they wrap
+        let definition = if statement.kind() == "decorated_definition" {
+            statement
+                .child_by_field_name("definition")
+                .unwrap_or(statement)
+        } else {
+            statement
+        };
+
+        match definition.kind() {
+            "class_definition" => {
+                let Some(name) = field_text(definition, "name", source) else {
+                    continue;
+                };
+                module.symbols.push(PythonSymbol {
+                    name: name.to_owned(),
+                    kind: SymbolKind::Struct,
+                    start_line: statement.start_position().row + 1,
+                    end_line: definition.end_position().row + 1,
+                    parent: class_name.map(str::to_owned),
+                    documentation: docstring(definition, source),
+                    bases: base_classes(definition, source),
+                });
+                if let Some(body) = definition.child_by_field_name("body") {
+                    collect_definitions(body, source, Some(name), module);
+                }
+            }
+            "function_definition" => {
+                let Some(name) = field_text(definition, "name", source) else {
+                    continue;
+                };
+                module.symbols.push(PythonSymbol {
+                    name: name.to_owned(),
+                    kind: if class_name.is_some() {
+                        SymbolKind::Method
+                    } else {
+                        SymbolKind::Function
+                    },
+                    start_line: statement.start_position().row + 1,
+                    end_line: definition.end_position().row + 1,
+                    parent: class_name.map(str::to_owned),
+                    documentation: docstring(definition, source),
+                    bases: Vec::new(),
+                });
+            }
+            "expression_statement" => {
+                collect_assignment(definition, source, class_name, module);
+            }
+            "import_statement" | "import_from_statement" if class_name.is_none() => {
+                module.imports.extend(parse_import(definition, source));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Records `NAME = value` assignments as fields, constants or variables
+fn collect_assignment(
+    statement: Node<'_>,
+    source: &str,
+    class_name: Option<&str>,
+    module: &mut PythonModule,
+) {
+    let Some(assignment) = statement.named_child(0) else {
+        return;
+    };
+    if assignment.kind() != "assignment" {
+        return;
+    }
+    let Some(target) = assignment.child_by_field_name("left") else {
+        return;
+    };
+    if target.kind() != "identifier" {
+        return;
+    }
+    let Ok(name) = target.utf8_text(source.as_bytes()) else {
+        return;
+    };
+
+    let kind = if class_name.is_some() {
+        SymbolKind::Field
+    } else if is_constant_name(name) {
+        SymbolKind::Constant
+    } else {
+        SymbolKind::Variable
+    };
+    module.symbols.push(PythonSymbol {
+        name: name.to_owned(),
+        kind,
+        start_line: statement.start_position().row + 1,
+        end_line: statement.end_position().row + 1,
+        parent: class_name.map(str::to_owned),
+        documentation: None,
+        bases: Vec::new(),
+    });
+}
+
+/// Records every call below `node` in source order
+fn collect_calls(node: Node<'_>, source: &str, calls: &mut Vec<CallSite>) {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.kind() == "call"
+            && let Some(function) = child.child_by_field_name("function")
+        {
+            let target = match function.kind() {
+                "identifier" => Some((function, false)),
+                "attribute" => function
+                    .child_by_field_name("attribute")
+                    .map(|attribute| (attribute, true)),
+                _ => None,
+            };
+            if let Some((name, qualified)) = target
+                && let Ok(callee) = name.utf8_text(source.as_bytes())
+            {
+                calls.push(CallSite {
+                    callee: callee.to_owned(),
+                    qualified,
+                    line: child.start_position().row + 1,
+                });
+            }
+        }
+        collect_calls(child, source, calls);
+    }
+}
+
+/// Returns the base classes of a class definition, without module prefixes
+fn base_classes(class: Node<'_>, source: &str) -> Vec<String> {
+    let Some(superclasses) = class.child_by_field_name("superclasses") else {
+        return Vec::new();
+    };
+    let mut cursor = superclasses.walk();
+    superclasses
+        .named_children(&mut cursor)
+        .filter(|base| matches!(base.kind(), "identifier" | "attribute"))
+        .filter_map(|base| base.utf8_text(source.as_bytes()).ok())
+        .filter_map(|base| base.rsplit('.').next())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Parses an `import` or `from ... import` statement
+fn parse_import(statement: Node<'_>, source: &str) -> Vec<PythonImport> {
+    let mut cursor = statement.walk();
+    let names: Vec<String> = statement
+        .children_by_field_name("name", &mut cursor)
+        .filter_map(|name| imported_name(name, source))
+        .collect();
+
+    if statement.kind() == "import_statement" {
+        return names
+            .into_iter()
+            .map(|module| PythonImport {
+                module,
+                level: 0,
+                names: Vec::new(),
+            })
+            .collect();
+    }
+
+    let Some(module_name) = statement.child_by_field_name("module_name") else {
+        return Vec::new();
+    };
+    let (module, level) = if module_name.kind() == "relative_import" {
+        let text = module_name.utf8_text(source.as_bytes()).unwrap_or_default();
+        let level = text
+            .chars()
+            .take_while(|&character| character == '.')
+            .count();
+        (text[level..].to_owned(), level)
+    } else {
+        (
+            module_name
+                .utf8_text(source.as_bytes())
+                .unwrap_or_default()
+                .to_owned(),
+            0,
+        )
+    };
+
+    vec![PythonImport {
+        module,
+        level,
+        names,
+    }]
+}
+
+/// Returns the dotted name of an import target, ignoring any `as` alias
+fn imported_name(node: Node<'_>, source: &str) -> Option<String> {
+    let name = if node.kind() == "aliased_import" {
+        node.child_by_field_name("name")?
+    } else {
+        node
+    };
+    name.utf8_text(source.as_bytes()).ok().map(str::to_owned)
+}
+
+/// Returns the text of a node's field
+fn field_text<'source>(node: Node<'_>, field: &str, source: &'source str) -> Option<&'source str> {
+    node.child_by_field_name(field)?
+        .utf8_text(source.as_bytes())
+        .ok()
+}
+
+/// Returns the docstring of a class or function, without quotes
+fn docstring(definition: Node<'_>, source: &str) -> Option<String> {
+    let body = definition.child_by_field_name("body")?;
+    let first = body.named_child(0)?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+    let string = first.named_child(0)?;
+    if string.kind() != "string" {
+        return None;
+    }
+
+    let text = string.utf8_text(source.as_bytes()).ok()?;
+    let unquoted = text
+        .trim_start_matches(|character: char| character.is_ascii_alphabetic())
+        .trim_matches(|character| character == '"' || character == '\'');
+    Some(unquoted.trim().to_owned())
+}
+
+/// Returns true for `UPPER_CASE` names, which Python uses for constants
+fn is_constant_name(name: &str) -> bool {
+    name.chars().any(|character| character.is_ascii_uppercase())
+        && name.chars().all(|character| {
+            character.is_ascii_uppercase() || character.is_ascii_digit() || character == '_'
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+import os
+import numpy as np
+from .models import User, Group as G
+from ..utils.text import slugify
+from pathlib import Path
+
+MAX_USERS = 10
+registry = {}
+
+
+@dataclass
+class Account:
+    """A user account."""
+
+    owner: str
+    limit = 5
+
+    def deposit(self, amount):
+        """Add money."""
+        return amount
+
+    @property
+    def balance(self):
+        return 0
+
+
+async def fetch(url):
+    pass
+"#;
+
+    /// Tests that classes, functions, methods, fields and constants are mapped to symbol kinds.
+    ///
+    /// # Errors
+    /// Returns an error if parsing fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_symbols() -> Result<()> {
+        let module = parse_python(SOURCE)?;
+        let symbols: Vec<_> = module
+            .symbols
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.parent.as_deref()))
+            .collect();
+
+        assert_eq!(
+            symbols,
+            vec![
+                ("MAX_USERS", SymbolKind::Constant, None),
+                ("registry", SymbolKind::Variable, None),
+                ("Account", SymbolKind::Struct, None),
+                ("owner", SymbolKind::Field, Some("Account")),
+                ("limit", SymbolKind::Field, Some("Account")),
+                ("deposit", SymbolKind::Method, Some("Account")),
+                ("balance", SymbolKind::Method, Some("Account")),
+                ("fetch", SymbolKind::Function, None),
+            ]
+        );
+
+        let account = &module.symbols[2];
+        assert_eq!((account.start_line, account.end_line), (12, 25));
+        assert_eq!(account.documentation.as_deref(), Some("A user account."));
+        Ok(())
+    }
+
+    /// Tests absolute, aliased and relative import extraction.
+    ///
+    /// # Errors
+    /// Returns an error if parsing fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_imports() -> Result<()> {
+        let module = parse_python(SOURCE)?;
+        let imports: Vec<_> = module
+            .imports
+            .iter()
+            .map(|import| {
+                (
+                    import.module.as_str(),
+                    import.level,
+                    import.names.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            imports,
+            vec![
+                ("os", 0, vec![]),
+                ("numpy", 0, vec![]),
+                ("models", 1, vec!["User", "Group"]),
+                ("utils.text", 2, vec!["slugify"]),
+                ("pathlib", 0, vec!["Path"]),
+            ]
+        );
+        Ok(())
+    }
+}
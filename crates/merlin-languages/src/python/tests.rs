@@ -0,0 +1,240 @@
+//! Tests for the Python backend
+
+use super::*;
+use crate::provider::SymbolKind;
+use std::time::Instant;
+use tempfile::TempDir;
+
+/// Caller names with the line of each call
+type Callers = Vec<(String, u32)>;
+
+/// Creates a small Python package with a relative and an absolute import.
+///
+/// # Errors
+/// Returns an error if the files cannot be written.
+fn create_project() -> Result<TempDir> {
+    let dir = TempDir::new()?;
+    let package = dir.path().join("app");
+    fs::create_dir_all(&package)?;
+    fs::create_dir_all(dir.path().join(".venv"))?;
+    fs::write(package.join("__init__.py"), "")?;
+    fs::write(
+        package.join("models.py"),
+        "class User:\n    \"\"\"A user.\"\"\"\n\n    def greet(self):\n        return 'hi'\n",
+    )?;
+    fs::write(
+        package.join("service.py"),
+        "from .models import User\nimport app.models\n\n\ndef find_user(name):\n    return User()\n",
+    )?;
+    fs::write(
+        dir.path().join(".venv").join("ignored.py"),
+        "def hidden():\n    pass\n",
+    )?;
+    Ok(dir)
+}
+
+/// Tests symbol search across indexed files.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_search_symbols() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = PythonBackend::new();
+    backend.initialize(dir.path())?;
+    assert_eq!(backend.file_count(), 3);
+
+    let result = backend.search_symbols(&SearchQuery {
+        symbol_name: Some("user".to_owned()),
+        ..SearchQuery::default()
+    })?;
+    let names: Vec<(&str, SymbolKind)> = result
+        .symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("User", SymbolKind::Struct),
+            ("find_user", SymbolKind::Function)
+        ]
+    );
+    assert_eq!(result.related_files.len(), 2);
+
+    let hidden = backend.search_symbols(&SearchQuery {
+        symbol_name: Some("hidden".to_owned()),
+        ..SearchQuery::default()
+    })?;
+    assert!(hidden.symbols.is_empty());
+    Ok(())
+}
+
+/// Tests import resolution, definition lookup and references.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_imports_and_definitions() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = PythonBackend::new();
+    backend.initialize(dir.path())?;
+
+    let service = dir.path().join("app").join("service.py");
+    let models = dir.path().join("app").join("models.py");
+    assert_eq!(backend.extract_imports(&service)?, vec![models.clone()]);
+
+    let definition = backend.find_definition("User", &service, 6)?;
+    assert_eq!(
+        definition.map(|symbol| (symbol.file_path, symbol.line)),
+        Some((models, 1))
+    );
+
+    let references: Vec<u32> = backend
+        .find_references("User")?
+        .iter()
+        .map(|symbol| symbol.line)
+        .collect();
+    assert_eq!(references, vec![1, 1, 6]);
+    Ok(())
+}
+
+/// Searches `backend` for symbols named exactly `name`.
+///
+/// # Errors
+/// Returns an error if the search fails.
+fn find_exact(backend: &PythonBackend, name: &str) -> Result<Vec<PathBuf>> {
+    Ok(backend
+        .search_symbols(&SearchQuery {
+            symbol_name: Some(name.to_owned()),
+            ..SearchQuery::default()
+        })?
+        .symbols
+        .into_iter()
+        .filter(|symbol| symbol.name == name)
+        .map(|symbol| symbol.file_path)
+        .collect())
+}
+
+/// Tests that edited, created and deleted files update the index.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_apply_file_change() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = PythonBackend::new();
+    backend.initialize(dir.path())?;
+    let models = dir.path().join("app").join("models.py");
+    let audit = dir.path().join("app").join("audit.py");
+
+    backend.apply_file_change(
+        &models,
+        Some("class User:\n    pass\n\n\nclass Team:\n    pass\n"),
+    )?;
+    assert_eq!(find_exact(&backend, "Team")?, vec![models.clone()]);
+
+    backend.apply_file_change(&audit, Some("def record(event):\n    pass\n"))?;
+    assert_eq!(find_exact(&backend, "record")?, vec![audit]);
+    assert_eq!(backend.file_count(), 4);
+
+    backend.apply_file_change(&models, None)?;
+    assert!(find_exact(&backend, "User")?.is_empty());
+
+    let ignored = dir.path().join(".venv").join("tool.py");
+    backend.apply_file_change(&ignored, Some("def hidden():\n    pass\n"))?;
+    assert!(find_exact(&backend, "hidden")?.is_empty());
+    Ok(())
+}
+
+/// Tests that applying one change is much faster than re-indexing the project.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if the incremental update is not at least five times faster.
+#[test]
+fn test_incremental_update_faster_than_initialize() -> Result<()> {
+    let dir = TempDir::new()?;
+    for index in 0..200 {
+        fs::write(
+            dir.path().join(format!("module_{index}.py")),
+            format!(
+                "class Model{index}:\n    def save(self):\n        return {index}\n\n\ndef load_{index}():\n    return Model{index}()\n"
+            ),
+        )?;
+    }
+    let mut backend = PythonBackend::new();
+
+    let full_start = Instant::now();
+    backend.initialize(dir.path())?;
+    let full = full_start.elapsed();
+
+    let changed = dir.path().join("module_0.py");
+    let incremental_start = Instant::now();
+    backend.apply_file_change(&changed, Some("def added():\n    pass\n"))?;
+    let incremental = incremental_start.elapsed();
+
+    assert_eq!(find_exact(&backend, "added")?, vec![changed]);
+    assert!(
+        incremental * 5 < full,
+        "incremental update took {incremental:?}, full initialize {full:?}"
+    );
+    Ok(())
+}
+
+/// Tests caller lookup and subclass discovery.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_callers_and_implementations() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = PythonBackend::new();
+    backend.initialize(dir.path())?;
+    let models = dir.path().join("app").join("models.py");
+    let service = dir.path().join("app").join("service.py");
+    let admin = dir.path().join("app").join("admin.py");
+    backend.apply_file_change(
+        &admin,
+        Some("from .models import User\n\n\nclass Admin(User):\n    def promote(self):\n        self.greet()\n\n\ndef run():\n    Admin().greet()\n    greet()\n"),
+    )?;
+
+    let callers = |name: &str, file: &Path, line: u32| -> Result<Callers> {
+        Ok(backend
+            .find_callers(name, file, line)?
+            .into_iter()
+            .map(|caller| (caller.name, caller.line))
+            .collect())
+    };
+    // A method is only reached through a receiver, so the bare `greet()` is skipped
+    assert_eq!(
+        callers("greet", &models, 4)?,
+        vec![("promote".to_owned(), 6), ("run".to_owned(), 10)]
+    );
+    assert_eq!(
+        callers("User", &service, 6)?,
+        vec![("find_user".to_owned(), 6)]
+    );
+
+    let implementations: Vec<(String, PathBuf, u32)> = backend
+        .find_implementations("User")?
+        .into_iter()
+        .map(|symbol| (symbol.name, symbol.file_path, symbol.line))
+        .collect();
+    assert_eq!(implementations, vec![("Admin".to_owned(), admin, 4)]);
+    Ok(())
+}
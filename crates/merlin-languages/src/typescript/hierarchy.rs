@@ -0,0 +1,56 @@
+//! Caller and implementation queries for TypeScript and JavaScript.
+
+use std::path::Path;
+
+use merlin_core::CoreResult as Result;
+
+use super::{TypeScriptBackend, symbol_info};
+use crate::calls::{CallerSpan, collect_callers, receiver_only, sort_by_location};
+use crate::provider::{LanguageProvider as _, SymbolInfo};
+
+/// Finds the functions and methods whose bodies call `symbol_name`
+///
+/// # Errors
+/// Returns an error if the definition of `symbol_name` cannot be looked up
+pub(super) fn find_callers(
+    backend: &TypeScriptBackend,
+    symbol_name: &str,
+    file: &Path,
+    line: u32,
+) -> Result<Vec<SymbolInfo>> {
+    let receiver_only = receiver_only(backend.find_definition(symbol_name, file, line)?.as_ref());
+    let files = backend.modules.iter().map(|(path, module)| {
+        let spans = module
+            .symbols
+            .iter()
+            .map(|symbol| CallerSpan {
+                name: &symbol.name,
+                kind: symbol.kind,
+                start_line: symbol.start_line,
+                end_line: symbol.end_line,
+            })
+            .collect();
+        (path.as_path(), module.calls.as_slice(), spans)
+    });
+    Ok(collect_callers(files, symbol_name, receiver_only))
+}
+
+/// Finds the classes and interfaces that extend or implement `type_name`
+pub(super) fn find_implementations(
+    backend: &TypeScriptBackend,
+    type_name: &str,
+) -> Vec<SymbolInfo> {
+    let mut implementations: Vec<SymbolInfo> = backend
+        .modules
+        .iter()
+        .flat_map(|(path, module)| {
+            module
+                .symbols
+                .iter()
+                .filter(|symbol| symbol.supertypes.iter().any(|parent| parent == type_name))
+                .map(move |symbol| symbol_info(path, symbol))
+        })
+        .collect();
+    sort_by_location(&mut implementations);
+    implementations
+}
@@ -0,0 +1,372 @@
+//! TypeScript and JavaScript language backend built on SWC.
+//!
+//! Parses every TypeScript and JavaScript file under the project root once during
+//! initialization and answers symbol, reference and import queries from the
+//! parsed modules.
+
+mod hierarchy;
+mod parser;
+mod tsconfig;
+
+pub use parser::{TypeScriptImport, TypeScriptModule, TypeScriptSymbol, parse_typescript};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use merlin_core::{CoreResult as Result, Error, FileContext};
+
+use crate::backend::{
+    find_word_references, is_indexed_path, rank_symbols, reindex_file, source_files,
+};
+use crate::calls::add_implementations;
+use crate::chunking::{CodeChunker, CodeSpan, nest_spans, with_leading_comments};
+use crate::index_cache::{WorkspaceCache, index_workspace};
+use tsconfig::{CONFIG_FILES, PathMappings};
+
+use crate::provider::{LanguageProvider, SearchQuery, SearchResult, SymbolInfo, SymbolKind};
+
+/// Directories skipped while scanning for TypeScript files
+const IGNORED_DIRS: &[&str] = &["node_modules", "dist", "build", "out", "target", "coverage"];
+
+/// File extensions handled by the backend
+const EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/// Extensions tried, in order, for an import specifier without one
+const RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "d.ts", "js", "jsx"];
+
+/// Maximum number of re-export hops followed when looking up an imported definition
+const MAX_REEXPORT_DEPTH: usize = 3;
+
+/// Returns true if `path` is a TypeScript or JavaScript source file
+pub fn is_typescript_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| EXTENSIONS.contains(&ext))
+}
+
+/// Returns true if a project contains at least one TypeScript or JavaScript source file
+pub fn contains_typescript_files(project_root: &Path) -> bool {
+    typescript_files(project_root).next().is_some()
+}
+
+/// Iterates over the source files under `project_root`, skipping hidden and build directories
+pub(crate) fn typescript_files(project_root: &Path) -> impl Iterator<Item = PathBuf> {
+    source_files(project_root, IGNORED_DIRS, is_typescript_file)
+}
+
+/// Parses a file's source, enabling JSX for everything but plain TypeScript files
+///
+/// # Errors
+/// Returns an error if the source cannot be parsed
+fn parse_file(path: &Path, source: &str) -> Result<TypeScriptModule> {
+    let jsx = !path
+        .extension()
+        .is_some_and(|ext| ext == "ts" || ext == "mts" || ext == "cts");
+    parse_typescript(source, jsx)
+}
+
+/// TypeScript backend that indexes the declarations and imports of a project
+#[derive(Debug, Default)]
+pub struct TypeScriptBackend {
+    /// Project root the backend was initialized with
+    project_root: PathBuf,
+    /// Parsed modules keyed by file path
+    modules: HashMap<PathBuf, TypeScriptModule>,
+    /// Where parse results are persisted between runs, if anywhere
+    index_cache: Option<WorkspaceCache>,
+    /// `baseUrl` and `paths` of the project's `tsconfig.json`
+    path_mappings: PathMappings,
+}
+
+impl TypeScriptBackend {
+    /// Creates an uninitialized backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Persists parse results in `cache` so unchanged files are not parsed on the next start
+    #[must_use]
+    pub fn with_index_cache(mut self, cache: WorkspaceCache) -> Self {
+        self.index_cache = Some(cache);
+        self
+    }
+
+    /// Returns the number of parsed source files
+    pub fn file_count(&self) -> usize {
+        self.modules.len()
+    }
+
+    /// Returns the parsed module for `file`, parsing it if it was not indexed
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or parsed
+    fn module(&self, file: &Path) -> Result<TypeScriptModule> {
+        if let Some(module) = self.modules.get(file) {
+            return Ok(module.clone());
+        }
+        let source = fs::read_to_string(file)
+            .map_err(|_| Error::FileNotFound(file.display().to_string()))?;
+        parse_file(file, &source)
+    }
+
+    /// Creates a backend for resolving imports of `project_root` without indexing it
+    ///
+    /// Files are parsed when queried, so this suits a few lookups on demand.
+    pub(crate) fn unindexed(project_root: &Path) -> Self {
+        Self {
+            project_root: project_root.to_path_buf(),
+            path_mappings: PathMappings::load(project_root),
+            ..Self::default()
+        }
+    }
+
+    /// Resolves an import specifier to the project file it refers to
+    ///
+    /// Relative specifiers (`./`, `../`) resolve against the importing file's
+    /// directory. Other specifiers go through the `paths` and `baseUrl` of
+    /// `tsconfig.json`, then resolve against the project root. Package imports
+    /// that match no project file are ignored.
+    fn resolve_import(&self, file: &Path, import: &TypeScriptImport) -> Option<PathBuf> {
+        let specifier = import.source.as_str();
+        if specifier.starts_with("./") || specifier.starts_with("../") {
+            return resolve_base(&file.parent()?.join(specifier), file);
+        }
+        self.path_mappings
+            .candidates(specifier)
+            .into_iter()
+            .chain([self.project_root.join(specifier)])
+            .find_map(|base| resolve_base(&base, file))
+    }
+
+    /// Returns the declaration of `symbol_name` in an indexed module or the modules it re-exports
+    ///
+    /// `depth` bounds how many re-export hops (such as `index.ts` barrel files) are followed.
+    fn find_exported(&self, path: &Path, symbol_name: &str, depth: usize) -> Option<SymbolInfo> {
+        let module = self.modules.get(path)?;
+        if let Some(symbol) = module
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == symbol_name)
+        {
+            return Some(symbol_info(path, symbol));
+        }
+        if depth == 0 {
+            return None;
+        }
+        module
+            .imports
+            .iter()
+            .filter(|import| import.names.is_empty())
+            .filter_map(|import| self.resolve_import(path, import))
+            .find_map(|reexported| self.find_exported(&reexported, symbol_name, depth - 1))
+    }
+}
+
+/// Resolves an import base path to a file, trying extensions and `index` files
+///
+/// `importer` is never returned, so a module cannot import itself.
+fn resolve_base(base: &Path, importer: &Path) -> Option<PathBuf> {
+    let base = normalize(base);
+
+    let mut candidates = vec![base.clone()];
+    // Node-style ESM imports name the emitted `.js` file of a `.ts` source
+    if base.extension().is_some_and(|ext| ext == "js") {
+        candidates.push(base.with_extension("ts"));
+        candidates.push(base.with_extension("tsx"));
+    }
+    let file_name = base.file_name()?.to_string_lossy().into_owned();
+    candidates.extend(
+        RESOLVE_EXTENSIONS
+            .iter()
+            .map(|ext| base.with_file_name(format!("{file_name}.{ext}"))),
+    );
+    candidates.extend(
+        RESOLVE_EXTENSIONS
+            .iter()
+            .map(|ext| base.join(format!("index.{ext}"))),
+    );
+
+    candidates
+        .into_iter()
+        .find(|candidate| candidate.is_file() && candidate != importer)
+}
+
+/// Removes `.` and `..` components from a path without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Converts a parsed symbol into the provider's symbol type
+fn symbol_info(file: &Path, symbol: &TypeScriptSymbol) -> SymbolInfo {
+    SymbolInfo {
+        name: symbol.name.clone(),
+        kind: symbol.kind,
+        file_path: file.to_path_buf(),
+        line: u32::try_from(symbol.start_line).unwrap_or(u32::MAX),
+        documentation: symbol.documentation.clone(),
+    }
+}
+
+impl LanguageProvider for TypeScriptBackend {
+    fn initialize(&mut self, project_root: &Path) -> Result<()> {
+        self.project_root = project_root.to_path_buf();
+        self.path_mappings = PathMappings::load(project_root);
+        self.modules = index_workspace(
+            self.index_cache.as_ref(),
+            "typescript",
+            project_root,
+            typescript_files(project_root),
+            parse_file,
+        );
+
+        tracing::info!("TypeScript backend indexed {} files", self.modules.len());
+        Ok(())
+    }
+
+    fn search_symbols(&self, query: &SearchQuery) -> Result<SearchResult> {
+        let symbols = self.modules.iter().flat_map(|(path, module)| {
+            module
+                .symbols
+                .iter()
+                .map(move |symbol| symbol_info(path, symbol))
+        });
+        let mut result = rank_symbols(symbols, query);
+        add_implementations(&mut result, query, self)?;
+        Ok(result)
+    }
+
+    fn find_definition(
+        &self,
+        symbol_name: &str,
+        file: &Path,
+        _line: u32,
+    ) -> Result<Option<SymbolInfo>> {
+        // Prefer a declaration in the same file, then one in the module that imports the name
+        let local = self.module(file)?;
+        if let Some(symbol) = local
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == symbol_name)
+        {
+            return Ok(Some(symbol_info(file, symbol)));
+        }
+
+        let (named, others): (Vec<_>, Vec<_>) = local
+            .imports
+            .iter()
+            .partition(|import| import.names.iter().any(|name| name == symbol_name));
+        Ok(named
+            .into_iter()
+            .chain(others)
+            .filter_map(|import| self.resolve_import(file, import))
+            .find_map(|path| self.find_exported(&path, symbol_name, MAX_REEXPORT_DEPTH)))
+    }
+
+    fn find_references(&self, symbol_name: &str) -> Result<Vec<SymbolInfo>> {
+        Ok(find_word_references(self.modules.keys(), symbol_name))
+    }
+
+    fn find_callers(&self, symbol_name: &str, file: &Path, line: u32) -> Result<Vec<SymbolInfo>> {
+        hierarchy::find_callers(self, symbol_name, file, line)
+    }
+
+    fn find_implementations(&self, type_name: &str) -> Result<Vec<SymbolInfo>> {
+        Ok(hierarchy::find_implementations(self, type_name))
+    }
+
+    fn get_related_context(&self, file: &Path) -> Result<Vec<FileContext>> {
+        Ok(self
+            .extract_imports(file)?
+            .iter()
+            .filter_map(|path| FileContext::from_path(path).ok())
+            .collect())
+    }
+
+    fn extract_imports(&self, file: &Path) -> Result<Vec<PathBuf>> {
+        let module = self.module(file)?;
+        let mut imports: Vec<PathBuf> = Vec::new();
+        for import in &module.imports {
+            if let Some(path) = self.resolve_import(file, import)
+                && !imports.contains(&path)
+            {
+                imports.push(path);
+            }
+        }
+        Ok(imports)
+    }
+
+    fn list_symbols_in_file(&self, file: &Path) -> Result<Vec<SymbolInfo>> {
+        Ok(self
+            .module(file)?
+            .symbols
+            .iter()
+            .map(|symbol| symbol_info(file, symbol))
+            .collect())
+    }
+
+    fn apply_file_change(&mut self, file: &Path, new_text: Option<&str>) -> Result<()> {
+        if CONFIG_FILES
+            .iter()
+            .any(|name| file == self.project_root.join(name))
+        {
+            self.path_mappings = PathMappings::load(&self.project_root);
+            return Ok(());
+        }
+        if !is_indexed_path(&self.project_root, file, IGNORED_DIRS, is_typescript_file) {
+            return Ok(());
+        }
+        reindex_file(&mut self.modules, file, new_text, |source| {
+            parse_file(file, source)
+        })
+    }
+}
+
+impl CodeChunker for TypeScriptBackend {
+    fn code_spans(&self, file: &Path, source: &str) -> Result<Vec<CodeSpan>> {
+        let module = parse_file(file, source)?;
+        let lines: Vec<&str> = source.lines().collect();
+        let spans = module
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let identifier = match (&symbol.parent, symbol.kind) {
+                    (Some(parent), _) => format!("{parent}.{}", symbol.name),
+                    (None, SymbolKind::Struct) => format!("class {}", symbol.name),
+                    (None, SymbolKind::Trait) => format!("interface {}", symbol.name),
+                    (None, SymbolKind::Type) => format!("type {}", symbol.name),
+                    (None, SymbolKind::Enum) => format!("enum {}", symbol.name),
+                    (None, SymbolKind::Module) => format!("namespace {}", symbol.name),
+                    (None, SymbolKind::Function) => format!("function {}", symbol.name),
+                    (None, SymbolKind::Constant) => format!("const {}", symbol.name),
+                    (None, _) => format!("let {}", symbol.name),
+                };
+                let start_line =
+                    with_leading_comments(&lines, symbol.start_line, is_comment_or_decorator);
+                CodeSpan::new(identifier, start_line, symbol.end_line)
+            })
+            .collect();
+        Ok(nest_spans(spans))
+    }
+}
+
+/// Returns true for `//` and block comment lines and `@decorator` lines
+fn is_comment_or_decorator(line: &str) -> bool {
+    line.starts_with("//")
+        || line.starts_with("/*")
+        || line.starts_with('*')
+        || line.starts_with('@')
+}
+
+#[cfg(test)]
+mod tests;
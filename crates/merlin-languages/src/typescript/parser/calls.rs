@@ -0,0 +1,55 @@
+//! Call sites and type references collected while walking a module.
+
+use swc_common::Span;
+use swc_ecma_ast::{CallExpr, Callee, Expr, MemberProp, NewExpr};
+use swc_ecma_visit::{Visit, VisitWith as _};
+
+use super::{Collector, LineIndex};
+use crate::calls::CallSite;
+
+impl Visit for Collector<'_> {
+    fn visit_call_expr(&mut self, node: &CallExpr) {
+        if let Callee::Expr(ref callee) = node.callee {
+            self.module
+                .calls
+                .extend(call_site(&self.lines, callee, node.span));
+        }
+        node.visit_children_with(self);
+    }
+
+    fn visit_new_expr(&mut self, node: &NewExpr) {
+        self.module
+            .calls
+            .extend(call_site(&self.lines, &node.callee, node.span));
+        node.visit_children_with(self);
+    }
+}
+
+/// Returns the call of `callee` at `span`, if the callee is a plain or member name
+fn call_site(lines: &LineIndex, callee: &Expr, span: Span) -> Option<CallSite> {
+    let (name, qualified) = match callee {
+        Expr::Ident(ident) => (ident.sym.to_string(), false),
+        Expr::Member(member) => match member.prop {
+            MemberProp::Ident(ref prop) => (prop.sym.to_string(), true),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(CallSite {
+        callee: name,
+        qualified,
+        line: lines.line(span.lo),
+    })
+}
+
+/// Returns the last segment of a type reference such as `Base` or `models.Base`
+pub(super) fn expr_name(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::Member(member) => match member.prop {
+            MemberProp::Ident(ref prop) => Some(prop.sym.to_string()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
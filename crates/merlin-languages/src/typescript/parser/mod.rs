@@ -0,0 +1,363 @@
+//! TypeScript and JavaScript source parsing with SWC.
+
+mod calls;
+
+use std::iter;
+
+use bincode::{Decode, Encode};
+use merlin_core::{CoreResult as Result, Error};
+use swc_common::comments::{CommentKind, Comments as _, SingleThreadedComments};
+use swc_common::{BytePos, FileName, SourceMap, Span, sync::Lrc};
+use swc_ecma_ast::{
+    Class, ClassMember, Decl, DefaultDecl, EsVersion, Expr, ModuleDecl, ModuleItem, Pat, PropName,
+    Stmt, TsExprWithTypeArgs, TsModuleName, TsTypeElement, VarDeclKind,
+};
+use swc_ecma_parser::{Syntax, TsSyntax, parse_file_as_module};
+use swc_ecma_visit::VisitWith as _;
+
+use crate::calls::CallSite;
+use crate::provider::SymbolKind;
+use calls::expr_name;
+
+/// A declaration found in a TypeScript module
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TypeScriptSymbol {
+    /// Name of the declaration
+    pub name: String,
+    /// Kind of declaration (classes map to `Struct`, interfaces to `Trait`)
+    pub kind: SymbolKind,
+    /// First line of the declaration, including `export` (1-indexed)
+    pub start_line: usize,
+    /// Last line of the declaration (1-indexed)
+    pub end_line: usize,
+    /// Name of the enclosing class or interface for members
+    pub parent: Option<String>,
+    /// `JSDoc` comment of the declaration, without comment markers
+    pub documentation: Option<String>,
+    /// Classes and interfaces a class extends or implements, or an interface extends
+    pub supertypes: Vec<String>,
+}
+
+/// An `import` statement or a re-export with a source module
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TypeScriptImport {
+    /// Module specifier as written (e.g. `./models` or `react`)
+    pub source: String,
+    /// Local names bound by the import (empty for side-effect imports and re-exports)
+    pub names: Vec<String>,
+}
+
+/// Declarations and imports of a parsed TypeScript module
+#[derive(Debug, Clone, Default, Encode, Decode)]
+pub struct TypeScriptModule {
+    /// Functions, classes, interfaces, type aliases, enums and variables in source order
+    pub symbols: Vec<TypeScriptSymbol>,
+    /// Import statements and re-exports at module level
+    pub imports: Vec<TypeScriptImport>,
+    /// Function, method and constructor calls anywhere in the module
+    pub calls: Vec<CallSite>,
+}
+
+/// Parses TypeScript or JavaScript source into its declarations and imports
+///
+/// `jsx` enables JSX syntax (`.tsx` and `.jsx` files), which disables
+/// `<Type>value` casts.
+///
+/// # Errors
+/// Returns an error if the source has syntax errors the parser cannot recover from
+pub fn parse_typescript(source: &str, jsx: bool) -> Result<TypeScriptModule> {
+    let source_map: Lrc<SourceMap> = Lrc::default();
+    let source_file = source_map.new_source_file(Lrc::new(FileName::Anon), source.to_owned());
+    let comments = SingleThreadedComments::default();
+    let syntax = Syntax::Typescript(TsSyntax {
+        tsx: jsx,
+        decorators: true,
+        dts: false,
+        no_early_errors: true,
+        disallow_ambiguous_jsx_like: false,
+    });
+
+    let module = parse_file_as_module(
+        &source_file,
+        syntax,
+        EsVersion::latest(),
+        Some(&comments),
+        &mut Vec::new(),
+    )
+    .map_err(|err| Error::Other(format!("Failed to parse TypeScript source: {err:?}")))?;
+
+    let mut collector = Collector {
+        lines: LineIndex::new(source, source_file.start_pos),
+        comments: &comments,
+        module: TypeScriptModule::default(),
+    };
+    for item in &module.body {
+        collector.collect_item(item);
+    }
+    module.visit_with(&mut collector);
+    Ok(collector.module)
+}
+
+/// Maps byte positions to 1-indexed line numbers
+struct LineIndex {
+    /// Position of the first byte of the source
+    start_pos: BytePos,
+    /// Byte offset at which each line starts
+    line_starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Indexes the line starts of `source`
+    fn new(source: &str, start_pos: BytePos) -> Self {
+        let line_starts = iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            start_pos,
+            line_starts,
+        }
+    }
+
+    /// Returns the line containing `pos`
+    fn line(&self, pos: BytePos) -> usize {
+        let offset = pos.0.saturating_sub(self.start_pos.0) as usize;
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+}
+
+/// Walks module items, recording declarations and imports
+struct Collector<'comments> {
+    /// Line lookup for spans
+    lines: LineIndex,
+    /// Comments gathered while parsing, used for `JSDoc`
+    comments: &'comments SingleThreadedComments,
+    /// Module being built
+    module: TypeScriptModule,
+}
+
+impl Collector<'_> {
+    /// Records the declarations and imports of a top-level item
+    fn collect_item(&mut self, item: &ModuleItem) {
+        match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => self.collect_decl(decl, None),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                self.collect_decl(&export.decl, Some(export.span));
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
+                self.collect_default_decl(&export.decl, export.span);
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                let names = import
+                    .specifiers
+                    .iter()
+                    .map(|specifier| specifier.local().sym.to_string())
+                    .collect();
+                self.module.imports.push(TypeScriptImport {
+                    source: import.src.value.to_string(),
+                    names,
+                });
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => {
+                if let Some(ref src) = export.src {
+                    self.push_reexport(src.value.to_string());
+                }
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => {
+                self.push_reexport(export.src.value.to_string());
+            }
+            ModuleItem::Stmt(_) | ModuleItem::ModuleDecl(_) => {}
+        }
+    }
+
+    /// Records a re-export (`export ... from`) as an import of its source
+    fn push_reexport(&mut self, source: String) {
+        self.module.imports.push(TypeScriptImport {
+            source,
+            names: Vec::new(),
+        });
+    }
+
+    /// Records a declaration; `export_span` covers the `export` keyword if present
+    fn collect_decl(&mut self, decl: &Decl, export_span: Option<Span>) {
+        match decl {
+            Decl::Fn(function) => {
+                let span = export_span.unwrap_or(function.function.span);
+                self.push(&function.ident.sym, SymbolKind::Function, span, None);
+            }
+            Decl::Class(class) => {
+                let span = export_span.unwrap_or(class.class.span);
+                self.push_class(&class.ident.sym, &class.class, span);
+            }
+            Decl::TsInterface(interface) => {
+                let span = export_span.unwrap_or(interface.span);
+                let name = &interface.id.sym;
+                self.push(name, SymbolKind::Trait, span, None);
+                self.set_supertypes(&interface.extends);
+                for member in &interface.body.body {
+                    self.push_interface_member(name, member);
+                }
+            }
+            Decl::TsTypeAlias(alias) => {
+                let span = export_span.unwrap_or(alias.span);
+                self.push(&alias.id.sym, SymbolKind::Type, span, None);
+            }
+            Decl::TsEnum(enumeration) => {
+                let span = export_span.unwrap_or(enumeration.span);
+                self.push(&enumeration.id.sym, SymbolKind::Enum, span, None);
+            }
+            Decl::TsModule(namespace) => {
+                if let TsModuleName::Ident(ref ident) = namespace.id {
+                    let span = export_span.unwrap_or(namespace.span);
+                    self.push(&ident.sym, SymbolKind::Module, span, None);
+                }
+            }
+            Decl::Var(var) => {
+                for declarator in &var.decls {
+                    let Pat::Ident(ref binding) = declarator.name else {
+                        continue;
+                    };
+                    let kind = match declarator.init.as_deref() {
+                        Some(Expr::Arrow(_) | Expr::Fn(_)) => SymbolKind::Function,
+                        Some(Expr::Class(_)) => SymbolKind::Struct,
+                        _ if var.kind == VarDeclKind::Const => SymbolKind::Constant,
+                        _ => SymbolKind::Variable,
+                    };
+                    let span = export_span.unwrap_or(var.span);
+                    self.push(&binding.id.sym, kind, span, None);
+                }
+            }
+            Decl::Using(_) => {}
+        }
+    }
+
+    /// Records a named `export default` class, function or interface
+    fn collect_default_decl(&mut self, decl: &DefaultDecl, span: Span) {
+        match decl {
+            DefaultDecl::Class(class) => {
+                if let Some(ref ident) = class.ident {
+                    self.push_class(&ident.sym, &class.class, span);
+                }
+            }
+            DefaultDecl::Fn(function) => {
+                if let Some(ref ident) = function.ident {
+                    self.push(&ident.sym, SymbolKind::Function, span, None);
+                }
+            }
+            DefaultDecl::TsInterfaceDecl(interface) => {
+                self.push(&interface.id.sym, SymbolKind::Trait, span, None);
+                self.set_supertypes(&interface.extends);
+            }
+        }
+    }
+
+    /// Records a class and its methods and properties
+    fn push_class(&mut self, name: &str, class: &Class, span: Span) {
+        self.push(name, SymbolKind::Struct, span, None);
+        if let Some(symbol) = self.module.symbols.last_mut() {
+            symbol.supertypes = class
+                .super_class
+                .iter()
+                .filter_map(|super_class| expr_name(super_class))
+                .chain(
+                    class
+                        .implements
+                        .iter()
+                        .filter_map(|parent| expr_name(&parent.expr)),
+                )
+                .collect();
+        }
+        for member in &class.body {
+            let (member_name, kind, member_span) = match member {
+                ClassMember::Method(method) => {
+                    (prop_name(&method.key), SymbolKind::Method, method.span)
+                }
+                ClassMember::PrivateMethod(method) => (
+                    Some(format!("#{}", method.key.name)),
+                    SymbolKind::Method,
+                    method.span,
+                ),
+                ClassMember::ClassProp(prop) => {
+                    (prop_name(&prop.key), SymbolKind::Field, prop.span)
+                }
+                ClassMember::PrivateProp(prop) => (
+                    Some(format!("#{}", prop.key.name)),
+                    SymbolKind::Field,
+                    prop.span,
+                ),
+                _ => continue,
+            };
+            if let Some(member_name) = member_name {
+                self.push(&member_name, kind, member_span, Some(name));
+            }
+        }
+    }
+
+    /// Records an interface property or method signature
+    fn push_interface_member(&mut self, interface: &str, member: &TsTypeElement) {
+        let (key, kind, span) = match member {
+            TsTypeElement::TsPropertySignature(property) => {
+                (&property.key, SymbolKind::Field, property.span)
+            }
+            TsTypeElement::TsMethodSignature(method) => {
+                (&method.key, SymbolKind::Method, method.span)
+            }
+            _ => return,
+        };
+        if let Expr::Ident(ref ident) = **key {
+            self.push(&ident.sym, kind, span, Some(interface));
+        }
+    }
+
+    /// Sets the interfaces extended by the interface recorded last
+    fn set_supertypes(&mut self, extends: &[TsExprWithTypeArgs]) {
+        if let Some(symbol) = self.module.symbols.last_mut() {
+            symbol.supertypes = extends
+                .iter()
+                .filter_map(|parent| expr_name(&parent.expr))
+                .collect();
+        }
+    }
+
+    /// Records a symbol spanning `span`
+    fn push(&mut self, name: &str, kind: SymbolKind, span: Span, parent: Option<&str>) {
+        self.module.symbols.push(TypeScriptSymbol {
+            name: name.to_owned(),
+            kind,
+            start_line: self.lines.line(span.lo),
+            end_line: self.lines.line(span.hi),
+            parent: parent.map(str::to_owned),
+            documentation: self.jsdoc(span.lo),
+            supertypes: Vec::new(),
+        });
+    }
+
+    /// Returns the `JSDoc` block (`/** ... */`) directly before `pos`, without markers
+    fn jsdoc(&self, pos: BytePos) -> Option<String> {
+        let comment = self
+            .comments
+            .get_leading(pos)?
+            .into_iter()
+            .rfind(|comment| comment.kind == CommentKind::Block && comment.text.starts_with('*'))?;
+
+        let text = comment
+            .text
+            .lines()
+            .map(|line| line.trim().trim_start_matches('*').trim())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+/// Returns the name of a class member key, if it is statically known
+fn prop_name(key: &PropName) -> Option<String> {
+    match key {
+        PropName::Ident(ident) => Some(ident.sym.to_string()),
+        PropName::Str(string) => Some(string.value.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests;
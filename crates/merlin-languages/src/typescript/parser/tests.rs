@@ -0,0 +1,146 @@
+//! Tests for TypeScript and JavaScript parsing
+
+use super::*;
+
+const SOURCE: &str = r"
+import React, { useState as useLocal } from 'react';
+import * as api from './api';
+import type { User } from '../models/user';
+import './styles.css';
+export * from './helpers';
+export { format } from './format';
+
+/** Maximum number of retries. */
+export const MAX_RETRIES = 3;
+let counter = 0;
+
+/**
+ * Loads a user.
+ */
+export async function loadUser(id: string): Promise<User> {
+return api.get(id);
+}
+
+export const render = (user: User) => user.name;
+
+export interface Repository<T> {
+name: string;
+find(id: string): T;
+}
+
+export type UserId = string;
+
+enum Color { Red, Green }
+
+export default class UserStore {
+private cache = new Map();
+#secret = 1;
+
+get(id: string) {
+    return this.cache.get(id);
+}
+}
+";
+
+/// Tests that declarations are mapped to symbol kinds with members and `JSDoc`.
+///
+/// # Errors
+/// Returns an error if parsing fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_parse_symbols() -> Result<()> {
+    let module = parse_typescript(SOURCE, false)?;
+    let symbols: Vec<_> = module
+        .symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.parent.as_deref()))
+        .collect();
+
+    assert_eq!(
+        symbols,
+        vec![
+            ("MAX_RETRIES", SymbolKind::Constant, None),
+            ("counter", SymbolKind::Variable, None),
+            ("loadUser", SymbolKind::Function, None),
+            ("render", SymbolKind::Function, None),
+            ("Repository", SymbolKind::Trait, None),
+            ("name", SymbolKind::Field, Some("Repository")),
+            ("find", SymbolKind::Method, Some("Repository")),
+            ("UserId", SymbolKind::Type, None),
+            ("Color", SymbolKind::Enum, None),
+            ("UserStore", SymbolKind::Struct, None),
+            ("cache", SymbolKind::Field, Some("UserStore")),
+            ("#secret", SymbolKind::Field, Some("UserStore")),
+            ("get", SymbolKind::Method, Some("UserStore")),
+        ]
+    );
+
+    let load_user = &module.symbols[2];
+    assert_eq!((load_user.start_line, load_user.end_line), (16, 18));
+    assert_eq!(load_user.documentation.as_deref(), Some("Loads a user."));
+    assert_eq!(
+        module.symbols[0].documentation.as_deref(),
+        Some("Maximum number of retries.")
+    );
+    Ok(())
+}
+
+/// Tests import and re-export extraction.
+///
+/// # Errors
+/// Returns an error if parsing fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_parse_imports() -> Result<()> {
+    let module = parse_typescript(SOURCE, false)?;
+    let imports: Vec<_> = module
+        .imports
+        .iter()
+        .map(|import| {
+            (
+                import.source.as_str(),
+                import.names.iter().map(String::as_str).collect(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        imports,
+        vec![
+            ("react", vec!["React", "useLocal"]),
+            ("./api", vec!["api"]),
+            ("../models/user", vec!["User"]),
+            ("./styles.css", vec![]),
+            ("./helpers", vec![]),
+            ("./format", vec![]),
+        ]
+    );
+    Ok(())
+}
+
+/// Tests JSX parsing and rejection of invalid syntax.
+///
+/// # Errors
+/// Returns an error if valid JSX fails to parse.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_parse_jsx() -> Result<()> {
+    let module = parse_typescript(
+        "export function App() {\n    return <div className=\"app\" />;\n}\n",
+        true,
+    )?;
+    assert_eq!(module.symbols.len(), 1);
+    assert!(
+        parse_typescript("function broken( {", false).is_err_and(|err| {
+            err.to_string()
+                .contains("Failed to parse TypeScript source")
+        })
+    );
+    Ok(())
+}
@@ -0,0 +1,165 @@
+//! Tests for the TypeScript and JavaScript backend
+
+use super::*;
+use crate::provider::SymbolKind;
+use tempfile::TempDir;
+
+/// Caller names with the line of each call
+type Callers = Vec<(String, u32)>;
+
+/// Creates a small TypeScript project with relative, index and package imports.
+///
+/// # Errors
+/// Returns an error if the files cannot be written.
+fn create_project() -> Result<TempDir> {
+    let dir = TempDir::new()?;
+    let src = dir.path().join("src");
+    fs::create_dir_all(src.join("models"))?;
+    fs::create_dir_all(dir.path().join("node_modules").join("react"))?;
+    fs::write(
+        src.join("models").join("user.ts"),
+        "/** A user. */\nexport class User {\n    greet(): string {\n        return 'hi';\n    }\n}\n",
+    )?;
+    fs::write(
+        src.join("models").join("index.ts"),
+        "export * from './user';\n",
+    )?;
+    fs::write(
+        src.join("service.tsx"),
+        "import React from 'react';\nimport { User } from './models';\n\nexport function findUser(name: string) {\n    return new User();\n}\n",
+    )?;
+    fs::write(
+        dir.path()
+            .join("node_modules")
+            .join("react")
+            .join("index.js"),
+        "export function hidden() {}\n",
+    )?;
+    Ok(dir)
+}
+
+/// Tests symbol search across indexed files.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_search_symbols() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = TypeScriptBackend::new();
+    backend.initialize(dir.path())?;
+    assert_eq!(backend.file_count(), 3);
+
+    let result = backend.search_symbols(&SearchQuery {
+        symbol_name: Some("user".to_owned()),
+        ..SearchQuery::default()
+    })?;
+    let names: Vec<(&str, SymbolKind)> = result
+        .symbols
+        .iter()
+        .map(|symbol| (symbol.name.as_str(), symbol.kind))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("User", SymbolKind::Struct),
+            ("findUser", SymbolKind::Function)
+        ]
+    );
+    assert_eq!(result.related_files.len(), 2);
+
+    let hidden = backend.search_symbols(&SearchQuery {
+        symbol_name: Some("hidden".to_owned()),
+        ..SearchQuery::default()
+    })?;
+    assert!(hidden.symbols.is_empty());
+    Ok(())
+}
+
+/// Tests import resolution through an index file, definition lookup and references.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_imports_and_definitions() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = TypeScriptBackend::new();
+    backend.initialize(dir.path())?;
+
+    let src = dir.path().join("src");
+    let service = src.join("service.tsx");
+    let index = src.join("models").join("index.ts");
+    let user = src.join("models").join("user.ts");
+    assert_eq!(backend.extract_imports(&service)?, vec![index.clone()]);
+    assert_eq!(backend.extract_imports(&index)?, vec![user.clone()]);
+
+    let definition = backend.find_definition("User", &service, 5)?;
+    assert_eq!(
+        definition.map(|symbol| (symbol.file_path, symbol.line, symbol.documentation)),
+        Some((user, 2, Some("A user.".to_owned())))
+    );
+
+    let references: Vec<u32> = backend
+        .find_references("User")?
+        .iter()
+        .map(|symbol| symbol.line)
+        .collect();
+    assert_eq!(references, vec![2, 2, 5]);
+    Ok(())
+}
+
+/// Tests caller lookup and `extends`/`implements` discovery.
+///
+/// # Errors
+/// Returns an error if the project cannot be created or indexed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_callers_and_implementations() -> Result<()> {
+    let dir = create_project()?;
+    let mut backend = TypeScriptBackend::new();
+    backend.initialize(dir.path())?;
+    let models = dir.path().join("src").join("models");
+    let service = dir.path().join("src").join("service.tsx");
+    let admin = models.join("admin.ts");
+    backend.apply_file_change(
+        &admin,
+        Some("import { User } from './user';\n\nexport interface Named {\n    name(): string;\n}\n\nexport class Admin extends User implements Named {\n    name(): string {\n        return this.greet();\n    }\n}\n"),
+    )?;
+
+    let callers = |name: &str, file: &Path, line: u32| -> Result<Callers> {
+        Ok(backend
+            .find_callers(name, file, line)?
+            .into_iter()
+            .map(|caller| (caller.name, caller.line))
+            .collect())
+    };
+    assert_eq!(
+        callers("greet", &models.join("user.ts"), 3)?,
+        vec![("name".to_owned(), 9)]
+    );
+    // Constructor calls count as calls of the class
+    assert_eq!(
+        callers("User", &service, 5)?,
+        vec![("findUser".to_owned(), 5)]
+    );
+
+    for supertype in ["User", "Named"] {
+        let implementations: Vec<(String, PathBuf, u32)> = backend
+            .find_implementations(supertype)?
+            .into_iter()
+            .map(|symbol| (symbol.name, symbol.file_path, symbol.line))
+            .collect();
+        assert_eq!(
+            implementations,
+            vec![("Admin".to_owned(), admin.clone(), 7)]
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,249 @@
+//! Import path mappings from `tsconfig.json`.
+//!
+//! Non-relative specifiers are resolved through `compilerOptions.paths` and
+//! `compilerOptions.baseUrl` the way the TypeScript compiler does, so aliases
+//! such as `@app/*` lead to project files. `extends` is not followed.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use merlin_core::{CoreResult as Result, Error};
+use serde_json::{Value, from_str};
+
+/// Config files read for path mappings, in order of preference
+pub const CONFIG_FILES: &[&str] = &["tsconfig.json", "jsconfig.json"];
+
+/// A `paths` pattern and the targets it maps to
+type PathPattern = (String, Vec<String>);
+
+/// `baseUrl` and `paths` of a project's `tsconfig.json`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathMappings {
+    /// Directory `paths` targets and bare specifiers resolve against
+    base_dir: Option<PathBuf>,
+    /// `paths` patterns and their targets
+    paths: Vec<PathPattern>,
+}
+
+impl PathMappings {
+    /// Reads the mappings of the first config file in `project_root`
+    ///
+    /// A missing config means no mappings; an unreadable one is logged and ignored.
+    pub fn load(project_root: &Path) -> Self {
+        let Some((path, source)) = CONFIG_FILES.iter().find_map(|name| {
+            let path = project_root.join(name);
+            fs::read_to_string(&path).ok().map(|source| (path, source))
+        }) else {
+            return Self::default();
+        };
+        Self::parse(project_root, &source).unwrap_or_else(|error| {
+            tracing::warn!("Ignoring path mappings of {}: {error}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Parses the mappings of a config file located in `config_dir`
+    ///
+    /// Comments and trailing commas are accepted, as in `tsc`.
+    ///
+    /// # Errors
+    /// Returns an error if the config is not valid JSON
+    pub fn parse(config_dir: &Path, source: &str) -> Result<Self> {
+        let config: Value = from_str(&strip_jsonc(source))
+            .map_err(|error| Error::Other(format!("Invalid tsconfig: {error}")))?;
+        let options = config.get("compilerOptions");
+        let base_url = options
+            .and_then(|options| options.get("baseUrl"))
+            .and_then(Value::as_str)
+            .map(|base_url| config_dir.join(base_url));
+        let paths: Vec<PathPattern> = options
+            .and_then(|options| options.get("paths"))
+            .and_then(Value::as_object)
+            .map(|paths| {
+                paths
+                    .iter()
+                    .map(|(pattern, targets)| {
+                        let targets = targets
+                            .as_array()
+                            .map(|targets| {
+                                targets
+                                    .iter()
+                                    .filter_map(Value::as_str)
+                                    .map(str::to_owned)
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        (pattern.clone(), targets)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Since TypeScript 4.1, `paths` without `baseUrl` resolve against the config's directory
+        let base_dir = base_url.or_else(|| (!paths.is_empty()).then(|| config_dir.to_path_buf()));
+        Ok(Self { base_dir, paths })
+    }
+
+    /// Returns the paths a bare specifier may refer to, most specific first
+    ///
+    /// The `paths` pattern with the longest prefix before its `*` wins, as in
+    /// `tsc`; its targets are followed by the specifier under `baseUrl`. The
+    /// candidates still need an extension or `index` file to be resolved.
+    pub fn candidates(&self, specifier: &str) -> Vec<PathBuf> {
+        let Some(base_dir) = &self.base_dir else {
+            return Vec::new();
+        };
+        let best = self
+            .paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                match_pattern(pattern, specifier)
+                    .map(|(prefix_len, star)| (prefix_len, star, targets))
+            })
+            .max_by_key(|(prefix_len, _, _)| *prefix_len);
+
+        let mut candidates: Vec<PathBuf> = best
+            .map(|(_, star, targets)| {
+                targets
+                    .iter()
+                    .map(|target| base_dir.join(target.replacen('*', star, 1)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        candidates.push(base_dir.join(specifier));
+        candidates
+    }
+}
+
+/// Matches a specifier against a `paths` pattern with at most one `*`
+///
+/// Returns the length of the pattern's prefix and the text matched by `*`.
+/// Patterns without `*` match only the identical specifier, ranking above
+/// every wildcard pattern.
+fn match_pattern<'spec>(pattern: &str, specifier: &'spec str) -> Option<(usize, &'spec str)> {
+    let Some((prefix, suffix)) = pattern.split_once('*') else {
+        return (pattern == specifier).then_some((usize::MAX, ""));
+    };
+    let star = specifier.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    Some((prefix.len(), star))
+}
+
+/// Removes comments and trailing commas from JSON with comments
+fn strip_jsonc(source: &str) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+    while let Some(current) = chars.next() {
+        if in_string {
+            output.push(current);
+            match current {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (current, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(current);
+            }
+            ('/', Some('/')) => while chars.next_if(|next| *next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (']' | '}', _) => {
+                let trimmed = output.trim_end().len();
+                if output[..trimmed].ends_with(',') {
+                    output.truncate(trimmed - 1);
+                }
+                output.push(current);
+            }
+            _ => output.push(current),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that comments and trailing commas are stripped outside strings.
+    ///
+    /// # Errors
+    /// Returns an error if the stripped config is not valid JSON.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_config_with_comments() -> Result<()> {
+        let source = r#"{
+            // Path aliases
+            "compilerOptions": {
+                /* resolved against the config */
+                "baseUrl": "./src",
+                "paths": { "@app/*": ["app/*",], "//": ["not/a/comment"], },
+            },
+        }"#;
+        let mappings = PathMappings::parse(Path::new("/web"), source)?;
+        assert_eq!(mappings.base_dir, Some(PathBuf::from("/web/./src")));
+        // Key order depends on whether `serde_json` preserves it
+        let mut paths = mappings.paths;
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                ("//".to_owned(), vec!["not/a/comment".to_owned()]),
+                ("@app/*".to_owned(), vec!["app/*".to_owned()]),
+            ]
+        );
+        Ok(())
+    }
+
+    /// Tests that the pattern with the longest prefix wins and `baseUrl` comes last.
+    ///
+    /// # Errors
+    /// Returns an error if the config cannot be parsed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_candidates() -> Result<()> {
+        let source = r#"{ "compilerOptions": { "paths": {
+            "@app/*": ["src/*", "generated/*"],
+            "@app/ui/*": ["ui/*"],
+            "config": ["config/index.ts"]
+        } } }"#;
+        let mappings = PathMappings::parse(Path::new("/web"), source)?;
+
+        assert_eq!(
+            mappings.candidates("@app/models/user"),
+            vec![
+                PathBuf::from("/web/src/models/user"),
+                PathBuf::from("/web/generated/models/user"),
+                PathBuf::from("/web/@app/models/user"),
+            ]
+        );
+        assert_eq!(
+            mappings.candidates("@app/ui/button").first(),
+            Some(&PathBuf::from("/web/ui/button"))
+        );
+        assert_eq!(
+            mappings.candidates("config").first(),
+            Some(&PathBuf::from("/web/config/index.ts"))
+        );
+        assert_eq!(
+            PathMappings::default().candidates("@app/models"),
+            Vec::<PathBuf>::new()
+        );
+        Ok(())
+    }
+}
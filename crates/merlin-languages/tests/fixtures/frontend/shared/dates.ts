@@ -0,0 +1,4 @@
+/** Formats a date as YYYY-MM-DD */
+export function formatDate(date: Date): string {
+  return date.toISOString().slice(0, 10);
+}
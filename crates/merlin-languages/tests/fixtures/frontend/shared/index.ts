@@ -0,0 +1 @@
+export { formatDate } from "./dates";
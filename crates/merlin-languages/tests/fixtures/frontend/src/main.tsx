@@ -0,0 +1,13 @@
+import { UserService } from "@app/services/users";
+import { formatDate } from "@shared";
+import { Button } from "./components/Button";
+import { createRoot } from "react-dom/client";
+
+const service = new UserService();
+
+export function App() {
+  const user = service.current();
+  return <Button label={formatDate(user.joined)} />;
+}
+
+createRoot(document.body).render(<App />);
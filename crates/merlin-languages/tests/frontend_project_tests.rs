@@ -0,0 +1,192 @@
+//! Integration tests over a small frontend project with a Python API
+//!
+//! The fixture in `tests/fixtures/frontend` imports through `tsconfig.json`
+//! path aliases, a barrel `index.ts` and relative paths, next to a Python file
+//! that a polyglot backend indexes alongside the TypeScript sources. Its
+//! `package.json` makes TypeScript the primary language.
+
+#[cfg(test)]
+mod tests {
+    use merlin_core::{CoreResult as Result, Error};
+    use merlin_languages::python::is_python_file;
+    use merlin_languages::typescript::is_typescript_file;
+    use merlin_languages::{
+        DetectedLanguage, Language, LanguageProvider as _, PolyglotBackend, PythonBackend,
+        SearchQuery, TypeScriptBackend, compose_backends, detect_languages, import_graph,
+    };
+    use std::path::{Path, PathBuf};
+
+    /// Returns the root of the fixture project
+    fn fixture_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("frontend")
+    }
+
+    /// Tests that aliased, barrel and relative imports resolve to project files.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_imports_resolve_through_tsconfig_paths() -> Result<()> {
+        let root = fixture_root();
+        let mut backend = TypeScriptBackend::new();
+        backend.initialize(&root)?;
+        let main = root.join("src/main.tsx");
+
+        assert_eq!(
+            backend.extract_imports(&main)?,
+            vec![
+                root.join("src/services/users.ts"),
+                root.join("shared/index.ts"),
+                root.join("src/components/Button.tsx"),
+            ]
+        );
+        let service = backend.find_definition("UserService", &main, 6)?;
+        assert_eq!(
+            service.map(|symbol| symbol.file_path),
+            Some(root.join("src/services/users.ts"))
+        );
+        let format_date = backend.find_definition("formatDate", &main, 10)?;
+        assert_eq!(
+            format_date.map(|symbol| symbol.file_path),
+            Some(root.join("shared/dates.ts"))
+        );
+        Ok(())
+    }
+
+    /// Tests that one backend serves both the frontend and the Python API.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_polyglot_backend_indexes_both_languages() -> Result<()> {
+        let root = fixture_root();
+        let mut backend = PolyglotBackend::new()
+            .with_backend("Python", is_python_file, Box::new(PythonBackend::new()))
+            .with_backend(
+                "TypeScript",
+                is_typescript_file,
+                Box::new(TypeScriptBackend::new()),
+            );
+        backend.initialize(&root)?;
+        assert_eq!(backend.languages(), ["Python", "TypeScript"]);
+
+        let search = |name: &str| -> Result<Vec<PathBuf>> {
+            let query = SearchQuery {
+                symbol_name: Some(name.to_owned()),
+                ..SearchQuery::default()
+            };
+            Ok(backend
+                .search_symbols(&query)?
+                .symbols
+                .into_iter()
+                .map(|symbol| symbol.file_path)
+                .collect())
+        };
+        assert_eq!(
+            search("list_users")?.first(),
+            Some(&root.join("api/server.py"))
+        );
+        assert_eq!(
+            search("Button")?.first(),
+            Some(&root.join("src/components/Button.tsx"))
+        );
+
+        let implementations: Vec<String> = backend
+            .find_implementations("Repository")?
+            .into_iter()
+            .map(|symbol| symbol.name)
+            .collect();
+        assert_eq!(implementations, ["UserService"]);
+        assert!(
+            backend
+                .extract_imports(&root.join("api/server.py"))?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    /// Tests that both languages are detected, the one with a manifest first.
+    ///
+    /// # Errors
+    /// Returns an error if the detected backends cannot be indexed.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_detects_and_composes_languages() -> Result<()> {
+        let root = fixture_root();
+        let detected = detect_languages(&root);
+        assert_eq!(
+            detected,
+            vec![
+                DetectedLanguage {
+                    language: Language::TypeScript,
+                    manifest: Some(root.join("package.json")),
+                    file_count: 5,
+                },
+                DetectedLanguage {
+                    language: Language::Python,
+                    manifest: None,
+                    file_count: 1,
+                },
+            ]
+        );
+
+        let backends = detected
+            .iter()
+            .map(|found| (found.language, found.language.create_backend(None)))
+            .collect();
+        let mut backend = compose_backends(backends)
+            .ok_or_else(|| Error::Other("two languages were detected".to_owned()))?;
+        backend.initialize(&root)?;
+        let symbols = |file: &str| -> Result<Vec<String>> {
+            Ok(backend
+                .list_symbols_in_file(&root.join(file))?
+                .into_iter()
+                .map(|symbol| symbol.name)
+                .collect())
+        };
+        assert_eq!(symbols("api/server.py")?, ["list_users"]);
+        assert_eq!(symbols("shared/dates.ts")?, ["formatDate"]);
+        assert_eq!(
+            backend.extract_imports(&root.join("shared/index.ts"))?,
+            vec![root.join("shared/dates.ts")]
+        );
+        Ok(())
+    }
+
+    /// Tests that the import graph links the entry point to the files it imports.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_import_graph_without_indexing() {
+        let root = fixture_root();
+        let files = [
+            root.join("src/main.tsx"),
+            root.join("shared/index.ts"),
+            root.join("api/server.py"),
+        ];
+        let graph = import_graph(&root, &files);
+
+        assert_eq!(
+            graph.get(&files[0]).map(Vec::len),
+            Some(3),
+            "main.tsx imports users.ts, shared/index.ts and Button.tsx"
+        );
+        assert_eq!(
+            graph.get(&files[1]),
+            Some(&vec![root.join("shared/dates.ts")])
+        );
+        assert!(!graph.contains_key(&files[2]));
+    }
+}
@@ -1,9 +1,13 @@
 use crate::OllamaManager;
-use crate::models::{OllamaGenerateRequest, OllamaGenerateResponse};
+use crate::models::{ModelInfo, OllamaGenerateRequest, OllamaGenerateResponse, OllamaListResponse};
 use async_trait::async_trait;
-use merlin_core::{Context, CoreResult, Error, ModelProvider, Query, Response, Result, TokenUsage};
+use merlin_core::{
+    Context, CoreResult, Error, ModelProvider, Query, Response, Result, TaskId, TokenUsage,
+    UiChannel,
+};
 use reqwest::Client;
-use std::time::Instant;
+use serde_json::from_slice;
+use std::time::{Duration, Instant};
 
 /// Local model provider using `Ollama`.
 pub struct LocalModelProvider {
@@ -28,6 +32,14 @@ impl LocalModelProvider {
         }
     }
 
+    /// Sends requests through `client`, sharing its connection pool.
+    #[must_use]
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.manager = self.manager.with_client(client.clone());
+        self.client = client;
+        self
+    }
+
     /// Sets a custom URL for the Ollama service.
     #[must_use]
     pub fn with_url(mut self, url: String) -> Self {
@@ -36,30 +48,17 @@ impl LocalModelProvider {
         self
     }
 
-    /// Send a completion request to the Ollama runtime.
+    /// Lists the models installed in Ollama (`GET /api/tags`).
     ///
     /// # Errors
     ///
-    /// Returns an [`Error`] if the request fails, the service reports an error,
-    /// or the response payload cannot be parsed.
-    async fn generate_completion(
-        &self,
-        prompt: &str,
-        system: Option<&str>,
-    ) -> CoreResult<OllamaGenerateResponse> {
-        let request = OllamaGenerateRequest {
-            model: self.model_name.clone(),
-            prompt: prompt.to_owned(),
-            system: system.map(String::from),
-            temperature: Some(0.7),
-            max_tokens: None,
-            stream: false,
-        };
-
+    /// Returns an [`Error`] if Ollama is unreachable, reports an error, or the
+    /// model list cannot be parsed.
+    pub async fn list_available_models(&self) -> CoreResult<Vec<ModelInfo>> {
         let response = self
             .client
-            .post(format!("{}/api/generate", self.base_url))
-            .json(&request)
+            .get(format!("{}/api/tags", self.base_url))
+            .timeout(Duration::from_secs(5))
             .send()
             .await
             .map_err(|err| Error::Other(format!("Ollama request failed: {err}")))?;
@@ -71,26 +70,29 @@ impl LocalModelProvider {
             )));
         }
 
-        let ollama_response: OllamaGenerateResponse = response
+        let list: OllamaListResponse = response
             .json()
             .await
-            .map_err(|err| Error::Other(format!("Failed to parse Ollama response: {err}")))?;
-
-        Ok(ollama_response)
-    }
-}
-
-#[async_trait]
-impl ModelProvider for LocalModelProvider {
-    fn name(&self) -> &'static str {
-        "Ollama"
-    }
+            .map_err(|err| Error::Other(format!("Failed to parse Ollama model list: {err}")))?;
 
-    async fn is_available(&self) -> bool {
-        self.manager.is_available().await
+        Ok(list.models.into_iter().map(ModelInfo::from).collect())
     }
 
-    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+    /// Generates a response, sending the text to `output` while it is streamed.
+    ///
+    /// Each line becomes a [`TaskOutput`](merlin_core::UiEvent::TaskOutput) of the given task as soon as
+    /// Ollama finishes it, so long answers show up before generation ends.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prompt cannot be built, the request fails, the
+    /// service reports an error, or the stream cannot be parsed.
+    pub async fn generate_streaming(
+        &self,
+        query: &Query,
+        context: &Context,
+        output: Option<(&UiChannel, TaskId)>,
+    ) -> Result<Response> {
         let start = Instant::now();
 
         // Use provided system prompt or default
@@ -121,7 +123,7 @@ impl ModelProvider for LocalModelProvider {
         }
 
         let ollama_response = self
-            .generate_completion(&prompt, Some(system_prompt))
+            .generate_completion(&prompt, Some(system_prompt), output)
             .await?;
 
         let latency_ms = start.elapsed().as_millis() as u64;
@@ -142,6 +144,148 @@ impl ModelProvider for LocalModelProvider {
         })
     }
 
+    /// Streams a completion from the Ollama runtime, passing finished lines to `output`.
+    ///
+    /// The returned response holds the whole generated text and the token
+    /// counts of the final update.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the request fails, the service reports an error,
+    /// or a streamed update cannot be parsed.
+    async fn generate_completion(
+        &self,
+        prompt: &str,
+        system: Option<&str>,
+        output: Option<(&UiChannel, TaskId)>,
+    ) -> CoreResult<OllamaGenerateResponse> {
+        let request = OllamaGenerateRequest {
+            model: self.model_name.clone(),
+            prompt: prompt.to_owned(),
+            system: system.map(String::from),
+            temperature: Some(0.7),
+            max_tokens: None,
+            stream: true,
+        };
+
+        let mut response = self
+            .client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|err| Error::Other(format!("Ollama request failed: {err}")))?;
+
+        if !response.status().is_success() {
+            return Err(Error::Other(format!(
+                "Ollama returned error: {}",
+                response.status()
+            )));
+        }
+
+        let send = |lines: Option<String>| {
+            if let (Some((channel, task_id)), Some(lines)) = (output, lines) {
+                channel.output(task_id, lines);
+            }
+        };
+        let mut completion = StreamedCompletion::default();
+        let mut pending = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|err| Error::Other(format!("Ollama stream failed: {err}")))?
+        {
+            pending.extend_from_slice(&chunk);
+            while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                send(completion.receive(&line)?);
+            }
+        }
+        send(completion.receive(&pending)?);
+
+        let (ollama_response, rest) = completion.finish()?;
+        send(rest);
+        Ok(ollama_response)
+    }
+}
+
+/// Full response of a finished stream and the output not handed out yet
+type FinishedCompletion = (OllamaGenerateResponse, Option<String>);
+
+/// Text accumulated from the updates Ollama streams while generating
+#[derive(Default)]
+struct StreamedCompletion {
+    /// Text generated so far
+    text: String,
+    /// Length of `text` already handed out as output
+    emitted: usize,
+    /// Final update, carrying the token counts
+    last: Option<OllamaGenerateResponse>,
+}
+
+impl StreamedCompletion {
+    /// Adds one streamed update, returning the output lines it finished
+    ///
+    /// Output is handed out a line at a time because the UI shows every
+    /// output event on lines of its own.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the update cannot be parsed or reports an error.
+    fn receive(&mut self, line: &[u8]) -> CoreResult<Option<String>> {
+        if line.trim_ascii().is_empty() {
+            return Ok(None);
+        }
+        let update: OllamaGenerateResponse = from_slice(line)
+            .map_err(|err| Error::Other(format!("Failed to parse Ollama response: {err}")))?;
+        if let Some(error) = update.error {
+            return Err(Error::Other(format!("Ollama returned error: {error}")));
+        }
+        self.text.push_str(&update.response);
+        if update.done {
+            self.last = Some(update);
+        }
+
+        let Some(end) = self.text[self.emitted..].rfind('\n') else {
+            return Ok(None);
+        };
+        let end = self.emitted + end;
+        let lines = self.text[self.emitted..end].to_owned();
+        self.emitted = end + 1;
+        Ok(Some(lines))
+    }
+
+    /// Ends the stream, returning the full response and the output not handed out yet
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the stream ended before its final update.
+    fn finish(self) -> CoreResult<FinishedCompletion> {
+        let Some(mut response) = self.last else {
+            return Err(Error::Other(
+                "Ollama stopped before the response was complete".to_owned(),
+            ));
+        };
+        let rest = Some(self.text[self.emitted..].to_owned()).filter(|rest| !rest.is_empty());
+        response.response = self.text;
+        Ok((response, rest))
+    }
+}
+
+#[async_trait]
+impl ModelProvider for LocalModelProvider {
+    fn name(&self) -> &'static str {
+        "Ollama"
+    }
+
+    async fn is_available(&self) -> bool {
+        self.manager.is_available().await
+    }
+
+    async fn generate(&self, query: &Query, context: &Context) -> Result<Response> {
+        self.generate_streaming(query, context, None).await
+    }
+
     fn estimate_cost(&self, _context: &Context) -> f64 {
         0.0
     }
@@ -150,6 +294,8 @@ impl ModelProvider for LocalModelProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_server::serve;
+    use merlin_core::UiEvent;
 
     /// Tests local model provider initialization.
     ///
@@ -162,6 +308,113 @@ mod tests {
         assert_eq!(provider.model_name, "qwen2.5-coder:7b");
     }
 
+    /// Tests that installed models are listed with their size, details and date.
+    ///
+    /// # Errors
+    /// Returns an error if the fake server cannot start or listing fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn list_available_models() -> CoreResult<()> {
+        let tags = r#"{"models":[
+            {"name":"qwen2.5-coder:7b","size":4683087332,"digest":"2b0496514337","modified_at":"2025-01-02T10:00:00Z",
+             "details":{"family":"qwen2","parameter_size":"7.6B","quantization_level":"Q4_K_M"}},
+            {"name":"nomic-embed-text:latest","size":274302450,"digest":"0a109f422b47","modified_at":"2025-01-01T09:00:00Z"}
+        ]}"#;
+        let url = serve(vec![tags.to_owned()])?;
+
+        let models = LocalModelProvider::new("qwen2.5-coder:7b".to_owned())
+            .with_url(url)
+            .list_available_models()
+            .await?;
+
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].name, "qwen2.5-coder:7b");
+        assert_eq!(models[0].size_bytes, 4_683_087_332);
+        assert_eq!(models[0].parameter_count, "7.6B");
+        assert_eq!(models[0].quantization, "Q4_K_M");
+        assert_eq!(models[0].modified_at, "2025-01-02T10:00:00Z");
+        assert_eq!(models[1].family, "");
+        Ok(())
+    }
+
+    /// Tests that a streamed response is accumulated and sent to the UI line by line.
+    ///
+    /// # Errors
+    /// Returns an error if the fake server cannot start or generation fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn generate_streaming_sends_lines() -> Result<()> {
+        let stream = [
+            r#"{"model":"qwen2.5-coder:7b","response":"fn main() {","done":false}"#,
+            r#"{"model":"qwen2.5-coder:7b","response":"\n    run();\n","done":false}"#,
+            r#"{"model":"qwen2.5-coder:7b","response":"}","done":false}"#,
+            r#"{"model":"qwen2.5-coder:7b","response":"","done":true,"prompt_eval_count":12,"eval_count":7}"#,
+        ]
+        .join("\n");
+        let url = serve(vec![stream]).map_err(|err| Error::Other(err.to_string()))?;
+        let (channel, mut receiver) = UiChannel::bounded(16);
+        let task_id = TaskId::default();
+
+        let response = LocalModelProvider::new("qwen2.5-coder:7b".to_owned())
+            .with_url(url)
+            .generate_streaming(
+                &Query::new("Write main"),
+                &Context::new(""),
+                Some((&channel, task_id)),
+            )
+            .await?;
+
+        assert_eq!(response.text, "fn main() {\n    run();\n}");
+        assert_eq!(response.tokens_used.input, 12);
+        assert_eq!(response.tokens_used.output, 7);
+
+        let mut lines = Vec::new();
+        while let Some(event) = receiver.try_recv() {
+            if let UiEvent::TaskOutput {
+                task_id: id,
+                output,
+            } = event
+            {
+                assert_eq!(id, task_id);
+                lines.extend(output.lines().map(str::to_owned));
+            }
+        }
+        assert_eq!(lines, ["fn main() {", "    run();", "}"]);
+        Ok(())
+    }
+
+    /// Tests that an error in the stream or a stream without a final update fails generation.
+    ///
+    /// # Errors
+    /// Returns an error if the fake server cannot start.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn generate_streaming_errors() -> Result<()> {
+        let url = serve(vec![
+            format!(
+                "{}\n{}\n",
+                r#"{"response":"partial","done":false}"#, r#"{"error":"model crashed"}"#
+            ),
+            r#"{"response":"cut off","done":false}"#.to_owned(),
+        ])
+        .map_err(|err| Error::Other(err.to_string()))?;
+        let provider = LocalModelProvider::new("qwen2.5-coder:7b".to_owned()).with_url(url);
+        let query = Query::new("Write main");
+        let context = Context::new("");
+
+        let crashed = provider.generate(&query, &context).await;
+        assert!(crashed.is_err_and(|err| err.to_string().contains("model crashed")));
+        let cut_off = provider.generate(&query, &context).await;
+        assert!(cut_off.is_err_and(|err| err.to_string().contains("before the response")));
+        Ok(())
+    }
+
     /// Tests cost estimation for local models (should be zero).
     ///
     /// # Panics
@@ -12,8 +12,13 @@ pub mod inference;
 pub mod manager;
 /// Data types for Ollama models and API interactions.
 pub mod models;
+#[cfg(test)]
+mod test_server;
 
 pub use error::{LocalError, Result};
 pub use inference::LocalModelProvider;
-pub use manager::OllamaManager;
-pub use models::{ModelInfo, OllamaGenerateRequest, OllamaGenerateResponse, OllamaModel};
+pub use manager::{OllamaManager, PullProgress};
+pub use models::{
+    ModelInfo, OllamaGenerateRequest, OllamaGenerateResponse, OllamaModel, OllamaModelDetails,
+    OllamaPullRequest, OllamaPullStatus,
+};
@@ -1,7 +1,19 @@
 use reqwest::Client;
+use serde_json::from_slice;
+use std::sync::Arc;
 use std::time::Duration;
 
+use crate::error::{LocalError, Result};
+use crate::models::{OllamaListResponse, OllamaPullRequest, OllamaPullStatus};
+
+/// Receives pull progress as (status, bytes downloaded, layer size if known)
+pub type PullProgress = Arc<dyn Fn(&str, u64, Option<u64>) + Send + Sync>;
+
+/// Percentage steps at which the download of a layer is reported
+const PULL_PROGRESS_STEP: u64 = 10;
+
 /// Manages Ollama installation and models
+#[derive(Clone)]
 pub struct OllamaManager {
     /// HTTP client used to interact with the Ollama service.
     client: Client,
@@ -10,6 +22,13 @@ pub struct OllamaManager {
 }
 
 impl OllamaManager {
+    /// Sends requests through `client`, sharing its connection pool.
+    #[must_use]
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Sets a custom URL for the Ollama service.
     #[must_use]
     pub fn with_url(mut self, url: String) -> Self {
@@ -26,6 +45,115 @@ impl OllamaManager {
             .await
             .is_ok()
     }
+
+    /// Lists the names of the models installed in Ollama
+    ///
+    /// # Errors
+    /// Returns an error if Ollama is unreachable or the response cannot be parsed
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let response = self
+            .client
+            .get(format!("{}/api/tags", self.base_url))
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+            .map_err(|err| LocalError::OllamaUnavailable(err.to_string()))?;
+        let list: OllamaListResponse = response.error_for_status()?.json().await?;
+        Ok(list.models.into_iter().map(|model| model.name).collect())
+    }
+
+    /// Downloads `model` unless it is already installed, like `ollama pull <model>`
+    ///
+    /// Download progress goes to `progress` whenever the status changes and at
+    /// every 10% of a layer. A model without a tag refers to its `latest` tag.
+    ///
+    /// # Errors
+    /// Returns an error if Ollama is unreachable or the pull fails
+    pub async fn ensure_model_pulled(
+        &self,
+        model: &str,
+        progress: Option<&PullProgress>,
+    ) -> Result<()> {
+        if has_model(&self.list_models().await?, model) {
+            return Ok(());
+        }
+        tracing::info!("Pulling model '{model}' from Ollama");
+
+        let mut response = self
+            .client
+            .post(format!("{}/api/pull", self.base_url))
+            .json(&OllamaPullRequest {
+                model: model.to_owned(),
+                stream: true,
+            })
+            .send()
+            .await
+            .map_err(|err| LocalError::OllamaUnavailable(err.to_string()))?
+            .error_for_status()?;
+
+        let mut reporter = PullReporter::default();
+        let mut pending = Vec::new();
+        let mut succeeded = false;
+        while let Some(chunk) = response.chunk().await? {
+            pending.extend_from_slice(&chunk);
+            while let Some(end) = pending.iter().position(|byte| *byte == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                let status: OllamaPullStatus = from_slice(&line)?;
+                if let Some(error) = status.error {
+                    return Err(LocalError::ModelPullFailed(format!("{model}: {error}")));
+                }
+                succeeded |= status.status == "success";
+                if let Some(callback) = progress {
+                    reporter.report(callback, &status);
+                }
+            }
+        }
+
+        if !succeeded {
+            return Err(LocalError::ModelPullFailed(format!(
+                "{model}: Ollama stopped before the download finished"
+            )));
+        }
+        tracing::info!("Pulled model '{model}'");
+        Ok(())
+    }
+}
+
+/// Whether `model` is among the `installed` model names
+fn has_model(installed: &[String], model: &str) -> bool {
+    installed.iter().any(|name| {
+        name == model || (!model.contains(':') && name.strip_suffix(":latest") == Some(model))
+    })
+}
+
+/// Status of a pull update and the progress step it reached, if it has a size
+type PullStep = (String, Option<u64>);
+
+/// Drops pull updates that would repeat the last reported status and progress step
+#[derive(Default)]
+struct PullReporter {
+    /// Last reported status and progress step
+    last: Option<PullStep>,
+}
+
+impl PullReporter {
+    /// Passes `status` on to `progress` if its status or progress step is new
+    fn report(&mut self, progress: &PullProgress, status: &OllamaPullStatus) {
+        let completed = status.completed.unwrap_or(0);
+        let step = status
+            .total
+            .filter(|total| *total > 0)
+            .map(|total| completed * 100 / total / PULL_PROGRESS_STEP);
+        let current = (status.status.clone(), step);
+        if self.last.as_ref() == Some(&current) {
+            return;
+        }
+        self.last = Some(current);
+        progress(&status.status, completed, status.total);
+    }
 }
 
 impl Default for OllamaManager {
@@ -40,6 +168,8 @@ impl Default for OllamaManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_server::serve;
+    use std::sync::Mutex;
 
     /// Tests Ollama manager creation with default URL.
     ///
@@ -51,6 +181,110 @@ mod tests {
         assert_eq!(manager.base_url, "http://localhost:11434");
     }
 
+    /// Tests that installed models match with or without their `latest` tag.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_has_model() {
+        let installed = vec![
+            "nomic-embed-text:latest".to_owned(),
+            "qwen2.5-coder:7b".to_owned(),
+        ];
+        assert!(has_model(&installed, "nomic-embed-text"));
+        assert!(has_model(&installed, "nomic-embed-text:latest"));
+        assert!(has_model(&installed, "qwen2.5-coder:7b"));
+        assert!(!has_model(&installed, "qwen2.5-coder"));
+        assert!(!has_model(&installed, "qwen2.5-coder:32b"));
+    }
+
+    /// Tests that a missing model is pulled, reporting its progress in 10% steps.
+    ///
+    /// # Errors
+    /// Returns an error if the fake server cannot start or the pull fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_ensure_model_pulled() -> Result<()> {
+        let pull = [
+            r#"{"status":"pulling manifest"}"#,
+            r#"{"status":"pulling abc","total":1000,"completed":0}"#,
+            r#"{"status":"pulling abc","total":1000,"completed":50}"#,
+            r#"{"status":"pulling abc","total":1000,"completed":500}"#,
+            r#"{"status":"pulling abc","total":1000,"completed":1000}"#,
+            r#"{"status":"success"}"#,
+        ]
+        .join("\n");
+        let url = serve(vec![r#"{"models":[]}"#.to_owned(), format!("{pull}\n")])
+            .map_err(|err| LocalError::Other(err.to_string()))?;
+
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&updates);
+        let progress: PullProgress = Arc::new(move |status, completed, total| {
+            if let Ok(mut received) = sink.lock() {
+                received.push((status.to_owned(), completed, total));
+            }
+        });
+        OllamaManager::default()
+            .with_url(url)
+            .ensure_model_pulled("nomic-embed-text", Some(&progress))
+            .await?;
+
+        let received = updates
+            .lock()
+            .map_err(|err| LocalError::Other(err.to_string()))?
+            .clone();
+        let steps: Vec<_> = received
+            .iter()
+            .map(|(status, completed, _)| (status.as_str(), *completed))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                ("pulling manifest", 0),
+                ("pulling abc", 0),
+                ("pulling abc", 500),
+                ("pulling abc", 1000),
+                ("success", 0),
+            ]
+        );
+        Ok(())
+    }
+
+    /// Tests that an installed model is not pulled again and a failed pull is an error.
+    ///
+    /// # Errors
+    /// Returns an error if the fake server cannot start or the check fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_ensure_model_pulled_skips_and_fails() -> Result<()> {
+        let installed = r#"{"models":[{"name":"nomic-embed-text:latest","size":1,"digest":"d","modified_at":"m"}]}"#;
+        let installed_url =
+            serve(vec![installed.to_owned()]).map_err(|err| LocalError::Other(err.to_string()))?;
+        OllamaManager::default()
+            .with_url(installed_url)
+            .ensure_model_pulled("nomic-embed-text", None)
+            .await?;
+
+        let failing_url = serve(vec![
+            r#"{"models":[]}"#.to_owned(),
+            "{\"error\":\"pull model manifest: file does not exist\"}\n".to_owned(),
+        ])
+        .map_err(|err| LocalError::Other(err.to_string()))?;
+        let failed = OllamaManager::default()
+            .with_url(failing_url)
+            .ensure_model_pulled("no-such-model", None)
+            .await;
+        assert!(
+            matches!(failed, Err(LocalError::ModelPullFailed(ref message)) if message.contains("file does not exist")),
+            "expected a failed pull, got {failed:?}"
+        );
+        Ok(())
+    }
+
     /// Tests Ollama manager with custom URL configuration.
     ///
     /// # Panics
@@ -13,6 +13,9 @@ pub struct ModelInfo {
     pub quantization: String,
     /// Model family (e.g., "qwen", "llama").
     pub family: String,
+    /// When the model was last pulled or changed, as reported by Ollama (empty if unknown).
+    #[serde(default)]
+    pub modified_at: String,
 }
 
 impl ModelInfo {
@@ -24,6 +27,7 @@ impl ModelInfo {
             parameter_count: "7B".to_owned(),
             quantization: "Q4_0".to_owned(),
             family: "qwen".to_owned(),
+            modified_at: String::new(),
         }
     }
 
@@ -35,6 +39,7 @@ impl ModelInfo {
             parameter_count: "6.7B".to_owned(),
             quantization: "Q4_0".to_owned(),
             family: "deepseek".to_owned(),
+            modified_at: String::new(),
         }
     }
 
@@ -46,6 +51,7 @@ impl ModelInfo {
             parameter_count: "7B".to_owned(),
             quantization: "Q4_0".to_owned(),
             family: "llama".to_owned(),
+            modified_at: String::new(),
         }
     }
 }
@@ -68,6 +74,36 @@ pub struct OllamaModel {
     pub digest: String,
     /// Timestamp of last modification.
     pub modified_at: String,
+    /// Parameter count, quantization and family of the model.
+    #[serde(default)]
+    pub details: OllamaModelDetails,
+}
+
+/// Details of an Ollama model returned from the API.
+#[derive(Debug, Default, Deserialize)]
+pub struct OllamaModelDetails {
+    /// Model family (e.g., "qwen2", "llama").
+    #[serde(default)]
+    pub family: String,
+    /// Human-readable parameter count (e.g., "7.6B").
+    #[serde(default)]
+    pub parameter_size: String,
+    /// Quantization format (e.g., "`Q4_K_M`").
+    #[serde(default)]
+    pub quantization_level: String,
+}
+
+impl From<OllamaModel> for ModelInfo {
+    fn from(model: OllamaModel) -> Self {
+        Self {
+            name: model.name,
+            size_bytes: model.size,
+            parameter_count: model.details.parameter_size,
+            quantization: model.details.quantization_level,
+            family: model.details.family,
+            modified_at: model.modified_at,
+        }
+    }
 }
 
 /// Ollama API request for generation
@@ -91,13 +127,19 @@ pub struct OllamaGenerateRequest {
 }
 
 /// Ollama API response for generation
+///
+/// When streaming, Ollama sends one of these per line: each carries the next
+/// piece of text and the last one (`done`) the token counts.
 #[derive(Debug, Deserialize)]
 pub struct OllamaGenerateResponse {
     /// Model that generated the response.
+    #[serde(default)]
     pub model: String,
     /// Generated text content.
+    #[serde(default)]
     pub response: String,
     /// Whether generation is complete.
+    #[serde(default)]
     pub done: bool,
     /// Total time taken in nanoseconds.
     #[serde(default)]
@@ -108,4 +150,30 @@ pub struct OllamaGenerateResponse {
     /// Number of tokens generated.
     #[serde(default)]
     pub eval_count: usize,
+    /// Why generation failed, if it did.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// Ollama API request for pulling a model
+#[derive(Debug, Serialize)]
+pub struct OllamaPullRequest {
+    /// Model to download.
+    pub model: String,
+    /// Whether to stream progress updates.
+    pub stream: bool,
+}
+
+/// Progress update streamed by the Ollama pull API, one per line
+#[derive(Debug, Deserialize)]
+pub struct OllamaPullStatus {
+    /// Current step (e.g. `pulling manifest`, `pulling <digest>`, `success`).
+    #[serde(default)]
+    pub status: String,
+    /// Size in bytes of the layer being downloaded.
+    pub total: Option<u64>,
+    /// Bytes of the layer downloaded so far.
+    pub completed: Option<u64>,
+    /// Why the pull failed, if it did.
+    pub error: Option<String>,
 }
@@ -0,0 +1,29 @@
+//! Fake Ollama server for tests.
+
+use std::io::{Read as _, Result as IoResult, Write as _};
+use std::net::TcpListener;
+use std::thread::spawn;
+
+/// Serves one canned HTTP response per body to the connections made to it
+///
+/// # Errors
+/// Returns an error if no local port can be bound
+pub fn serve(bodies: Vec<String>) -> IoResult<String> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let url = format!("http://{}", listener.local_addr()?);
+    spawn(move || {
+        for body in bodies {
+            let Ok((mut stream, _)) = listener.accept() else {
+                return;
+            };
+            let mut request = [0u8; 4096];
+            drop(stream.read(&mut request));
+            drop(write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            ));
+        }
+    });
+    Ok(url)
+}
@@ -5,8 +5,12 @@ use serde::{Deserialize, Serialize};
 use std::env;
 use std::time::Instant;
 
+use crate::http_errors::{REQUEST_TIMEOUT, request_error, status_error};
+
 /// Groq API endpoint URL.
 const GROQ_API_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
+/// Groq model list endpoint, used to validate API keys.
+const GROQ_MODELS_URL: &str = "https://api.groq.com/openai/v1/models";
 /// Default model for Groq.
 const DEFAULT_MODEL: &str = "llama-3.1-70b-versatile";
 /// Env var key for Groq API key.
@@ -56,6 +60,13 @@ impl GroqProvider {
         })
     }
 
+    /// Sends requests through `client`, sharing its connection pool.
+    #[must_use]
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Sets the model to use for generation.
     #[must_use]
     pub fn with_model(mut self, model: String) -> Self {
@@ -69,6 +80,27 @@ impl GroqProvider {
         self.api_key = api_key;
         self
     }
+
+    /// Checks that Groq accepts the API key without running a completion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails or the key is rejected.
+    pub async fn validate_key(&self) -> Result<()> {
+        let response = self
+            .client
+            .get(GROQ_MODELS_URL)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|err| request_error("Groq", &err))?;
+
+        if !response.status().is_success() {
+            return Err(status_error("Groq", response).await);
+        }
+        Ok(())
+    }
 }
 
 /// Request payload sent to the Groq chat completion API.
@@ -180,18 +212,14 @@ impl ModelProvider for GroqProvider {
             .post(GROQ_API_URL)
             .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
+            .timeout(REQUEST_TIMEOUT)
             .json(&request)
             .send()
             .await
-            .map_err(|err| Error::Other(format!("Groq API request failed: {err}")))?;
+            .map_err(|err| request_error("Groq", &err))?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_owned());
-            return Err(Error::Other(format!("Groq API error {status}: {error_text}")).into());
+            return Err(status_error("Groq", response).await);
         }
 
         let groq_response: GroqResponse = response
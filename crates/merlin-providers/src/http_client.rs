@@ -0,0 +1,27 @@
+//! HTTP client shared by the providers.
+
+use std::time::Duration;
+
+use merlin_core::CoreResult;
+use reqwest::Client;
+
+/// Idle connections kept open per host.
+pub const POOL_MAX_IDLE_PER_HOST: usize = 10;
+
+/// Interval of TCP keepalive probes on pooled connections.
+pub const TCP_KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// Builds the HTTP client shared by every provider of a registry.
+///
+/// Clones of a `Client` share its connection pool, so injecting clones of this
+/// client lets repeated requests to the same endpoint reuse open connections
+/// instead of paying for a new TCP and TLS handshake each time.
+///
+/// # Errors
+/// Returns an error if the TLS backend cannot be initialized.
+pub fn pooled_client() -> CoreResult<Client> {
+    Ok(Client::builder()
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .tcp_keepalive(TCP_KEEPALIVE)
+        .build()?)
+}
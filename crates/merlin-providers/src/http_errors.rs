@@ -0,0 +1,101 @@
+//! Structured error mapping for provider HTTP requests.
+
+use std::time::Duration;
+
+use merlin_core::{Error, RoutingError};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Error as ReqwestError, Response as HttpResponse, StatusCode};
+
+/// Maximum time a single provider request may take before it is abandoned.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_mins(2);
+
+/// Converts a failed request into a routing error, tagging timeouts with the provider.
+///
+/// Connection failures become `RoutingError::ProviderUnavailable` so they are retried.
+pub fn request_error(provider: &str, err: &ReqwestError) -> RoutingError {
+    if err.is_timeout() {
+        RoutingError::ProviderTimeout {
+            provider: provider.to_owned(),
+            timeout_ms: u64::try_from(REQUEST_TIMEOUT.as_millis()).unwrap_or(u64::MAX),
+        }
+    } else if err.is_connect() {
+        RoutingError::ProviderUnavailable(format!("{provider} could not be reached: {err}"))
+    } else {
+        Error::Provider(format!("{provider} request failed: {err}")).into()
+    }
+}
+
+/// Converts an unsuccessful response into a routing error.
+///
+/// HTTP 429 responses become `RoutingError::ProviderRateLimit`, carrying the
+/// `Retry-After` delay when the provider sends one in seconds. HTTP 502, 503
+/// and 504 responses become `RoutingError::ProviderUnavailable`; other statuses,
+/// such as 400 and 401, are not worth retrying and stay provider errors.
+pub async fn status_error(provider: &str, response: HttpResponse) -> RoutingError {
+    let status = response.status();
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        let retry_after_secs = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        return RoutingError::ProviderRateLimit {
+            provider: provider.to_owned(),
+            retry_after_secs,
+        };
+    }
+
+    let error_text = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "Unknown error".to_owned());
+    if is_unavailable_status(status) {
+        return RoutingError::ProviderUnavailable(format!(
+            "{provider} API error {status}: {error_text}"
+        ));
+    }
+    Error::Provider(format!("{provider} API error {status}: {error_text}")).into()
+}
+
+/// Whether the status means the provider is temporarily unable to serve requests.
+fn is_unavailable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parses a `Retry-After` header given in whole seconds.
+///
+/// HTTP-date values are not supported and yield `None`.
+fn parse_retry_after(value: &str) -> Option<u64> {
+    value.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing `Retry-After` header values.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("30"), Some(30));
+        assert_eq!(parse_retry_after(" 5 "), Some(5));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    /// Tests which statuses are treated as temporary unavailability.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_unavailable_status() {
+        assert!(is_unavailable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_unavailable_status(StatusCode::GATEWAY_TIMEOUT));
+        assert!(!is_unavailable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_unavailable_status(StatusCode::UNAUTHORIZED));
+    }
+}
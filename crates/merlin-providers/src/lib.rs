@@ -4,9 +4,14 @@
 pub mod claude_code;
 /// Groq provider implementation.
 pub mod groq;
+/// HTTP client shared by the providers.
+mod http_client;
+/// Structured error mapping for provider HTTP requests.
+mod http_errors;
 /// `OpenRouter` multi-provider implementation.
 pub mod openrouter;
 
 pub use claude_code::ClaudeCodeProvider;
 pub use groq::GroqProvider;
-pub use openrouter::OpenRouterProvider;
+pub use http_client::{POOL_MAX_IDLE_PER_HOST, TCP_KEEPALIVE, pooled_client};
+pub use openrouter::{OpenRouterModel, OpenRouterPricing, OpenRouterProvider, parse_model_catalog};
@@ -0,0 +1,80 @@
+//! The `OpenRouter` model catalog and its prices.
+
+use serde::Deserialize;
+use serde_json::from_str;
+
+use merlin_core::{Error, Result};
+
+/// Model catalog returned by the `OpenRouter` models endpoint.
+#[derive(Deserialize)]
+struct OpenRouterModelList {
+    /// Available models.
+    data: Vec<OpenRouterModel>,
+}
+
+/// A single entry of the `OpenRouter` model catalog.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenRouterModel {
+    /// Model id used in requests (e.g. `anthropic/claude-3.5-sonnet`).
+    pub id: String,
+    /// Human-readable model name.
+    #[serde(default)]
+    pub name: String,
+    /// Maximum tokens of prompt and completion, if published.
+    #[serde(default)]
+    pub context_length: Option<u64>,
+    /// Price of the tokens sent and received.
+    #[serde(default)]
+    pub pricing: OpenRouterPricing,
+    /// Request parameters the model supports (e.g. `tools`, `reasoning`).
+    #[serde(default)]
+    pub supported_parameters: Vec<String>,
+}
+
+/// Prices of an `OpenRouter` model, in USD per token as decimal strings.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OpenRouterPricing {
+    /// Price of one prompt token.
+    #[serde(default)]
+    pub prompt: String,
+    /// Price of one completion token.
+    #[serde(default)]
+    pub completion: String,
+}
+
+impl OpenRouterPricing {
+    /// Price of a million prompt tokens in USD, if published.
+    #[must_use]
+    pub fn input_per_million(&self) -> Option<f64> {
+        per_million(&self.prompt)
+    }
+
+    /// Price of a million completion tokens in USD, if published.
+    #[must_use]
+    pub fn output_per_million(&self) -> Option<f64> {
+        per_million(&self.completion)
+    }
+}
+
+/// Converts a per-token price string to USD per million tokens.
+///
+/// `OpenRouter` marks variable-price routers with negative prices, which are
+/// treated as unknown.
+fn per_million(per_token: &str) -> Option<f64> {
+    per_token
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|price| *price >= 0.0)
+        .map(|price| price * 1_000_000.0)
+}
+
+/// Parses a response of the `OpenRouter` models endpoint.
+///
+/// # Errors
+/// Returns an error if `body` is not a model catalog.
+pub fn parse_model_catalog(body: &str) -> Result<Vec<OpenRouterModel>> {
+    let catalog: OpenRouterModelList = from_str(body)
+        .map_err(|err| Error::Provider(format!("Failed to parse model list: {err}")))?;
+    Ok(catalog.data)
+}
@@ -1,3 +1,9 @@
+mod catalog;
+#[cfg(test)]
+mod tests;
+
+pub use catalog::{OpenRouterModel, OpenRouterPricing, parse_model_catalog};
+
 use std::env;
 use std::time::Instant;
 
@@ -8,8 +14,14 @@ use serde_json::{Value, json};
 
 use merlin_core::{Context, CoreResult, Error, ModelProvider, Query, Response, Result, TokenUsage};
 
+use crate::http_errors::{REQUEST_TIMEOUT, request_error, status_error};
+
 /// `OpenRouter` API endpoint URL.
 const OPENROUTER_API_URL: &str = "https://openrouter.ai/api/v1/chat/completions";
+/// `OpenRouter` endpoint describing the current API key.
+const OPENROUTER_KEY_URL: &str = "https://openrouter.ai/api/v1/key";
+/// `OpenRouter` model catalog endpoint.
+const OPENROUTER_MODELS_URL: &str = "https://openrouter.ai/api/v1/models";
 /// Default model for `OpenRouter`.
 const DEFAULT_MODEL: &str = "anthropic/claude-sonnet-4-20250514";
 /// Env var key for `OpenRouter` API key.
@@ -67,6 +79,13 @@ impl OpenRouterProvider {
         Self::new(api_key)
     }
 
+    /// Sends requests through `client`, sharing its connection pool.
+    #[must_use]
+    pub fn with_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
     /// Sets the model to use for generation.
     #[must_use]
     pub fn with_model(mut self, model: String) -> Self {
@@ -74,6 +93,66 @@ impl OpenRouterProvider {
         self
     }
 
+    /// Checks that `OpenRouter` accepts the API key without running a completion.
+    ///
+    /// # Errors
+    /// Returns an error if the request fails or the key is rejected.
+    pub async fn validate_key(&self) -> Result<()> {
+        let response = self
+            .client
+            .get(OPENROUTER_KEY_URL)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|err| request_error("OpenRouter", &err))?;
+
+        if !response.status().is_success() {
+            return Err(status_error("OpenRouter", response).await);
+        }
+        Ok(())
+    }
+
+    /// Lists the ids of all models available through `OpenRouter`, sorted by name.
+    ///
+    /// # Errors
+    /// Returns an error if the request fails or the catalog cannot be parsed.
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let mut models: Vec<String> = self
+            .model_catalog()
+            .await?
+            .into_iter()
+            .map(|model| model.id)
+            .collect();
+        models.sort_unstable();
+        Ok(models)
+    }
+
+    /// Fetches the `OpenRouter` model catalog with context windows, prices and features.
+    ///
+    /// # Errors
+    /// Returns an error if the request fails or the catalog cannot be parsed.
+    pub async fn model_catalog(&self) -> Result<Vec<OpenRouterModel>> {
+        let response = self
+            .client
+            .get(OPENROUTER_MODELS_URL)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|err| request_error("OpenRouter", &err))?;
+
+        if !response.status().is_success() {
+            return Err(status_error("OpenRouter", response).await);
+        }
+
+        let body = response
+            .text()
+            .await
+            .map_err(|err| request_error("OpenRouter", &err))?;
+        parse_model_catalog(&body)
+    }
+
     /// Builds messages from context and query for the `OpenRouter` API.
     fn build_messages(context: &Context, query: &Query) -> Vec<Value> {
         let mut messages = vec![json!({
@@ -175,18 +254,14 @@ impl ModelProvider for OpenRouterProvider {
                 "https://github.com/BigBadE/agentic_optimizer",
             )
             .header("X-Title", "Agentic Optimizer")
+            .timeout(REQUEST_TIMEOUT)
             .json(&request_body)
             .send()
             .await
-            .map_err(|err| Error::Provider(format!("Request failed: {err}")))?;
+            .map_err(|err| request_error("OpenRouter", &err))?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(Error::Provider(format!(
-                "OpenRouter API request failed with status {status}: {error_text}"
-            ))
-            .into());
+            return Err(status_error("OpenRouter", response).await);
         }
 
         let api_response: OpenRouterResponse = response
@@ -231,159 +306,9 @@ impl ModelProvider for OpenRouterProvider {
         let tokens = context.token_estimate() as f64;
         tokens * 3.0 / 1_000_000.0
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Tests that creating a provider with an empty API key returns an error.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_new_with_empty_api_key() {
-        let result = OpenRouterProvider::new(String::new());
-        assert!(result.is_err(), "Empty API key should return an error");
-
-        if let Err(err) = result {
-            assert!(
-                matches!(err, Error::MissingApiKey(_)),
-                "Should be a MissingApiKey error"
-            );
-        }
-    }
 
-    /// Tests that creating a provider with a valid API key succeeds.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_new_with_valid_api_key() {
-        let result = OpenRouterProvider::new("valid_key".to_owned());
-        assert!(result.is_ok(), "Valid API key should succeed");
-
-        if let Ok(provider) = result {
-            assert_eq!(provider.api_key, "valid_key");
-            assert_eq!(provider.model, DEFAULT_MODEL);
-        }
-    }
-
-    /// Tests that `with_model` correctly sets the model.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_with_model() {
-        let result = OpenRouterProvider::new("test_key".to_owned());
-        assert!(result.is_ok());
-        if let Ok(provider) = result {
-            let provider = provider.with_model("custom-model".to_owned());
-            assert_eq!(provider.model, "custom-model");
-        }
-    }
-
-    /// Tests provider name returns correct identifier.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_provider_name() {
-        let result = OpenRouterProvider::new("test_key".to_owned());
-        assert!(result.is_ok());
-        if let Ok(provider) = result {
-            assert_eq!(provider.name(), "openrouter");
-        }
-    }
-
-    /// Tests cost estimation for non-empty context.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_cost_estimation() {
-        let result = OpenRouterProvider::new("test_key".to_owned());
-        assert!(result.is_ok());
-        if let Ok(provider) = result {
-            let context = Context::new("test query");
-            let cost = provider.estimate_cost(&context);
-
-            // Cost should be positive for non-empty context
-            assert!(cost > 0.0, "Cost should be positive for non-empty context");
-        }
-    }
-
-    /// Tests that cost scales with context size.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_cost_estimation_scaling() {
-        let result = OpenRouterProvider::new("test_key".to_owned());
-        assert!(result.is_ok());
-        if let Ok(provider) = result {
-            let small_context = Context::new("small");
-            let large_context = Context::new("large ".repeat(100));
-
-            let small_cost = provider.estimate_cost(&small_context);
-            let large_cost = provider.estimate_cost(&large_context);
-
-            // Larger context should cost more
-            assert!(
-                large_cost > small_cost,
-                "Larger context should have higher cost"
-            );
-        }
-    }
-
-    /// Tests message building with context and query.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_build_messages_with_context() {
-        let context = Context::new("test query");
-        let query = Query::new("user question");
-
-        let messages = OpenRouterProvider::build_messages(&context, &query);
-
-        // Should have at least 2 messages (system + user)
-        assert!(messages.len() >= 2, "Should have at least 2 messages");
-
-        // First message should be system
-        assert_eq!(
-            messages[0]["role"].as_str(),
-            Some("system"),
-            "First message should be system role"
-        );
-
-        // Last message should be user with query text
-        assert!(!messages.is_empty(), "Messages should not be empty");
-        let last = &messages[messages.len() - 1];
-        assert_eq!(
-            last["role"].as_str(),
-            Some("user"),
-            "Last message should be user role"
-        );
-        assert_eq!(
-            last["content"].as_str(),
-            Some("user question"),
-            "Last message should contain query text"
-        );
-    }
-
-    /// Tests that methods can be chained.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_model_chaining() {
-        let result = OpenRouterProvider::new("test_key".to_owned());
-        assert!(result.is_ok());
-        if let Ok(base_provider) = result {
-            let provider = base_provider.with_model("custom-model".to_owned());
-            assert_eq!(provider.model, "custom-model");
-            assert_eq!(provider.api_key, "test_key");
-        }
+    /// The context message is marked for caching, so resending files is cheap
+    fn supports_prompt_caching(&self) -> bool {
+        true
     }
 }
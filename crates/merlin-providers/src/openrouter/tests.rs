@@ -0,0 +1,153 @@
+//! Tests for the `OpenRouter` provider
+
+use super::*;
+
+/// Tests that creating a provider with an empty API key returns an error.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_new_with_empty_api_key() {
+    let result = OpenRouterProvider::new(String::new());
+    assert!(result.is_err(), "Empty API key should return an error");
+
+    if let Err(err) = result {
+        assert!(
+            matches!(err, Error::MissingApiKey(_)),
+            "Should be a MissingApiKey error"
+        );
+    }
+}
+
+/// Tests that creating a provider with a valid API key succeeds.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_new_with_valid_api_key() {
+    let result = OpenRouterProvider::new("valid_key".to_owned());
+    assert!(result.is_ok(), "Valid API key should succeed");
+
+    if let Ok(provider) = result {
+        assert_eq!(provider.api_key, "valid_key");
+        assert_eq!(provider.model, DEFAULT_MODEL);
+    }
+}
+
+/// Tests that `with_model` correctly sets the model.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_with_model() {
+    let result = OpenRouterProvider::new("test_key".to_owned());
+    assert!(result.is_ok());
+    if let Ok(provider) = result {
+        let provider = provider.with_model("custom-model".to_owned());
+        assert_eq!(provider.model, "custom-model");
+    }
+}
+
+/// Tests provider name returns correct identifier.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_provider_name() {
+    let result = OpenRouterProvider::new("test_key".to_owned());
+    assert!(result.is_ok());
+    if let Ok(provider) = result {
+        assert_eq!(provider.name(), "openrouter");
+    }
+}
+
+/// Tests cost estimation for non-empty context.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_cost_estimation() {
+    let result = OpenRouterProvider::new("test_key".to_owned());
+    assert!(result.is_ok());
+    if let Ok(provider) = result {
+        let context = Context::new("test query");
+        let cost = provider.estimate_cost(&context);
+
+        // Cost should be positive for non-empty context
+        assert!(cost > 0.0, "Cost should be positive for non-empty context");
+    }
+}
+
+/// Tests that cost scales with context size.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_cost_estimation_scaling() {
+    let result = OpenRouterProvider::new("test_key".to_owned());
+    assert!(result.is_ok());
+    if let Ok(provider) = result {
+        let small_context = Context::new("small");
+        let large_context = Context::new("large ".repeat(100));
+
+        let small_cost = provider.estimate_cost(&small_context);
+        let large_cost = provider.estimate_cost(&large_context);
+
+        // Larger context should cost more
+        assert!(
+            large_cost > small_cost,
+            "Larger context should have higher cost"
+        );
+    }
+}
+
+/// Tests message building with context and query.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_build_messages_with_context() {
+    let context = Context::new("test query");
+    let query = Query::new("user question");
+
+    let messages = OpenRouterProvider::build_messages(&context, &query);
+
+    // Should have at least 2 messages (system + user)
+    assert!(messages.len() >= 2, "Should have at least 2 messages");
+
+    // First message should be system
+    assert_eq!(
+        messages[0]["role"].as_str(),
+        Some("system"),
+        "First message should be system role"
+    );
+
+    // Last message should be user with query text
+    assert!(!messages.is_empty(), "Messages should not be empty");
+    let last = &messages[messages.len() - 1];
+    assert_eq!(
+        last["role"].as_str(),
+        Some("user"),
+        "Last message should be user role"
+    );
+    assert_eq!(
+        last["content"].as_str(),
+        Some("user question"),
+        "Last message should contain query text"
+    );
+}
+
+/// Tests that methods can be chained.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_model_chaining() {
+    let result = OpenRouterProvider::new("test_key".to_owned());
+    assert!(result.is_ok());
+    if let Ok(base_provider) = result {
+        let provider = base_provider.with_model("custom-model".to_owned());
+        assert_eq!(provider.model, "custom-model");
+        assert_eq!(provider.api_key, "test_key");
+    }
+}
@@ -1,5 +1,15 @@
 use super::intent::{Action, Intent};
-use crate::Task;
+use crate::{Result, RoutingError, Task, TaskResult, ValidationResult};
+use merlin_core::{Context, ModelProvider, PhaseTimings, Query, Response, TokenUsage};
+use serde_json::from_str;
+
+/// Most subtasks a model decomposition is allowed to produce
+const MAX_MODEL_SUBTASKS: usize = 8;
+/// System prompt asking the model to split a task into subtasks
+const DECOMPOSITION_SYSTEM_PROMPT: &str = "You split programming tasks into smaller subtasks \
+     that are done one after another. Reply with a JSON array of subtask descriptions only, \
+     for example [\"Add the config field\", \"Read it in the loader\"]. Each description must \
+     make sense on its own. Use at most 8 subtasks.";
 
 /// Decomposes complex requests into smaller tasks
 pub struct TaskDecomposer;
@@ -17,6 +27,101 @@ impl TaskDecomposer {
         }
     }
 
+    /// Ask `provider` to split `task` into subtasks done one after another
+    ///
+    /// Each subtask becomes a child task inheriting the difficulty and priority of
+    /// `task` and depending on the subtask before it.
+    ///
+    /// # Errors
+    /// Returns an error if the model request fails or its reply holds no subtasks
+    pub async fn decompose_with_model(
+        &self,
+        task: &Task,
+        provider: &dyn ModelProvider,
+    ) -> Result<Vec<Task>> {
+        let response = provider
+            .generate(
+                &Query::new(task.description.clone()),
+                &Context::new(DECOMPOSITION_SYSTEM_PROMPT),
+            )
+            .await?;
+
+        let mut subtasks: Vec<Task> = Vec::default();
+        for description in parse_subtask_descriptions(&response.text)? {
+            let dependencies = subtasks.last().map(|previous| vec![previous.id]);
+            subtasks.push(
+                Task::new(description)
+                    .with_difficulty(task.difficulty)
+                    .with_priority(task.priority)
+                    .with_dependencies(dependencies.unwrap_or_default()),
+            );
+        }
+        Ok(subtasks)
+    }
+
+    /// Combine the results of the subtasks of `parent` into its result
+    ///
+    /// The response holds each subtask's response under its description, usage and
+    /// timings are summed, and validation passes only if every subtask's passed.
+    #[must_use]
+    pub fn aggregate(parent: &Task, results: &[(Task, TaskResult)]) -> TaskResult {
+        let mut tiers: Vec<&str> = Vec::default();
+        let mut sections = Vec::default();
+        let mut tokens_used = TokenUsage::default();
+        let mut timings = PhaseTimings::default();
+        let mut validation = ValidationResult::default();
+        let mut duration_ms = 0;
+        let mut latency_ms = 0;
+        let mut confidence: f64 = 1.0;
+
+        for (index, (subtask, result)) in results.iter().enumerate() {
+            if !tiers.contains(&result.tier_used.as_str()) {
+                tiers.push(&result.tier_used);
+            }
+            sections.push(format!(
+                "## {}. {}\n\n{}",
+                index + 1,
+                subtask.description,
+                result.response.text
+            ));
+            tokens_used.accumulate(&result.tokens_used);
+            timings.add(&result.timings);
+            duration_ms += result.duration_ms;
+            latency_ms += result.response.latency_ms;
+            confidence = confidence.min(result.response.confidence);
+
+            validation.passed &= result.validation.passed;
+            validation.score = validation.score.min(result.validation.score);
+            validation
+                .errors
+                .extend(result.validation.errors.iter().cloned());
+            validation
+                .warnings
+                .extend(result.validation.warnings.iter().cloned());
+            validation
+                .stages
+                .extend(result.validation.stages.iter().cloned());
+        }
+
+        let tier_used = tiers.join(", ");
+        TaskResult {
+            task_id: parent.id,
+            response: Response {
+                text: sections.join("\n\n"),
+                confidence,
+                tokens_used: tokens_used.clone(),
+                provider: tier_used.clone(),
+                latency_ms,
+            },
+            tier_used,
+            tokens_used,
+            validation,
+            duration_ms,
+            work_unit: None,
+            timings,
+        }
+    }
+
     fn decompose_refactor(intent: &Intent, request: &str) -> Vec<Task> {
         let mut tasks = Vec::default();
 
@@ -111,6 +216,34 @@ impl TaskDecomposer {
     }
 }
 
+/// Subtask descriptions in a model reply holding a JSON array of strings
+///
+/// Text around the array, such as a code fence, is ignored.
+///
+/// # Errors
+/// Returns an error if the reply holds no array of strings or the array is empty
+fn parse_subtask_descriptions(reply: &str) -> Result<Vec<String>> {
+    let array = reply
+        .find('[')
+        .zip(reply.rfind(']'))
+        .and_then(|(start, end)| reply.get(start..=end))
+        .ok_or_else(|| {
+            RoutingError::AnalysisFailed(format!("Decomposition reply has no JSON array: {reply}"))
+        })?;
+    let descriptions = from_str::<Vec<String>>(array)?
+        .into_iter()
+        .map(|description| description.trim().to_owned())
+        .filter(|description| !description.is_empty())
+        .take(MAX_MODEL_SUBTASKS)
+        .collect::<Vec<_>>();
+    if descriptions.is_empty() {
+        return Err(RoutingError::AnalysisFailed(
+            "Decomposition reply has no subtasks".to_owned(),
+        ));
+    }
+    Ok(descriptions)
+}
+
 impl Default for TaskDecomposer {
     fn default() -> Self {
         Self
@@ -121,6 +254,139 @@ impl Default for TaskDecomposer {
 mod tests {
     use super::super::intent::IntentExtractor;
     use super::*;
+    use async_trait::async_trait;
+
+    /// Provider replying with a fixed text
+    struct ReplyProvider(&'static str);
+
+    #[async_trait]
+    impl ModelProvider for ReplyProvider {
+        fn name(&self) -> &'static str {
+            "reply"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn generate(&self, _query: &Query, _context: &Context) -> Result<Response> {
+            Ok(Response {
+                text: self.0.to_owned(),
+                confidence: 1.0,
+                tokens_used: TokenUsage::default(),
+                provider: "reply".to_owned(),
+                latency_ms: 0,
+            })
+        }
+
+        fn estimate_cost(&self, _context: &Context) -> f64 {
+            0.0
+        }
+    }
+
+    /// Result of `task` answered with `text` by `tier`
+    fn result_of(task: &Task, text: &str, tier: &str, passed: bool) -> TaskResult {
+        let tokens_used = TokenUsage {
+            input: 10,
+            output: 5,
+            ..TokenUsage::default()
+        };
+        TaskResult {
+            task_id: task.id,
+            response: Response {
+                text: text.to_owned(),
+                confidence: 0.9,
+                tokens_used: tokens_used.clone(),
+                provider: tier.to_owned(),
+                latency_ms: 100,
+            },
+            tier_used: tier.to_owned(),
+            tokens_used,
+            validation: ValidationResult {
+                passed,
+                ..ValidationResult::default()
+            },
+            duration_ms: 200,
+            work_unit: None,
+            timings: PhaseTimings {
+                provider_ms: 100,
+                ..PhaseTimings::default()
+            },
+        }
+    }
+
+    /// Tests that a model reply becomes child tasks run one after another.
+    ///
+    /// # Errors
+    /// Returns an error if decomposition fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_decompose_with_model_chains_subtasks() -> Result<()> {
+        let provider = ReplyProvider(
+            "Here is the plan:\n```json\n[\"Add the field\", \" \", \"Read it in the loader\"]\n```",
+        );
+        let task = Task::new("Make the loader configurable".to_owned()).with_difficulty(7);
+        let subtasks = TaskDecomposer
+            .decompose_with_model(&task, &provider)
+            .await?;
+
+        let descriptions = subtasks
+            .iter()
+            .map(|subtask| subtask.description.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(descriptions, vec!["Add the field", "Read it in the loader"]);
+        assert!(subtasks.iter().all(|subtask| subtask.difficulty == 7));
+        assert!(subtasks[0].dependencies.is_empty());
+        assert_eq!(subtasks[1].dependencies, vec![subtasks[0].id]);
+        Ok(())
+    }
+
+    /// Tests that a reply without subtasks fails the decomposition.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_decompose_with_model_rejects_reply_without_subtasks() {
+        let task = Task::new("Make the loader configurable".to_owned());
+        for reply in ["I cannot split this task", "[]"] {
+            let outcome = TaskDecomposer
+                .decompose_with_model(&task, &ReplyProvider(reply))
+                .await;
+            assert!(
+                matches!(outcome, Err(RoutingError::AnalysisFailed(_))),
+                "{reply}"
+            );
+        }
+    }
+
+    /// Tests that subtask results are combined into the parent's result.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_aggregate_combines_subtask_results() {
+        let parent = Task::new("Make the loader configurable".to_owned());
+        let first = Task::new("Add the field".to_owned());
+        let second = Task::new("Read it in the loader".to_owned());
+        let results = vec![
+            (first.clone(), result_of(&first, "Added", "local", true)),
+            (second.clone(), result_of(&second, "Read", "groq", false)),
+        ];
+
+        let result = TaskDecomposer::aggregate(&parent, &results);
+        assert_eq!(result.task_id, parent.id);
+        assert_eq!(
+            result.response.text,
+            "## 1. Add the field\n\nAdded\n\n## 2. Read it in the loader\n\nRead"
+        );
+        assert_eq!(result.tier_used, "local, groq");
+        assert_eq!(result.tokens_used.total(), 30);
+        assert_eq!(result.duration_ms, 400);
+        assert_eq!(result.timings.provider_ms, 200);
+        assert!(!result.validation.passed);
+    }
 
     /// Tests that simple tasks are not decomposed into subtasks.
     ///
@@ -59,12 +59,12 @@ pub use analyzer::{
 };
 pub use cache::{CacheStats, CachedResponse, ResponseCache};
 pub use metrics::{
-    DailyReport, MetricsCollector, MetricsReport, RequestMetrics, RequestMetricsParams,
-    TierBreakdown,
+    DailyReport, METRICS_FILE, MetricsCollector, MetricsReport, RequestMetrics,
+    RequestMetricsParams, TierBreakdown,
 };
 pub use router::{
-    AvailabilityChecker, Model, ModelRegistry, ModelRouter, ProviderRegistry, RoutingDecision,
-    StrategyRouter, TierCategory,
+    AvailabilityChecker, CATALOG_FILE, CatalogModel, Model, ModelCatalog, ModelRegistry,
+    ModelRouter, ModelSource, ProviderRegistry, RoutingDecision, StrategyRouter, TierCategory,
 };
 // Re-export tools from merlin-tools and merlin-typescript crates
 pub use merlin_tooling::{
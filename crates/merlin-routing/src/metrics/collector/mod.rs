@@ -1,9 +1,17 @@
 //! Metrics collection for tracking task execution statistics.
 
-use merlin_core::TokenUsage;
+use merlin_core::{PhaseTimings, Result, TokenUsage};
+use merlin_languages::{CacheStats, IndexCacheStats};
 use serde::{Deserialize, Serialize};
+use serde_json::{Result as JsonResult, from_str, to_string};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write as _};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+/// File in `.merlin` that every recorded request is appended to, one JSON line each
+pub const METRICS_FILE: &str = "metrics.jsonl";
+
 /// Metrics for a single request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestMetrics {
@@ -11,6 +19,9 @@ pub struct RequestMetrics {
     pub timestamp: SystemTime,
     /// The query text
     pub query: String,
+    /// Kind of task the request was made for (e.g. `task` or `subtask`)
+    #[serde(default)]
+    pub task_type: String,
     /// Model tier used
     pub tier_used: String,
     /// Latency in milliseconds
@@ -23,16 +34,21 @@ pub struct RequestMetrics {
     pub success: bool,
     /// Whether the request was escalated to a higher tier
     pub escalated: bool,
+    /// Time spent in each phase of the task
+    #[serde(default)]
+    pub timings: PhaseTimings,
 }
 
 /// Builder for creating request metrics
 pub struct RequestMetricsBuilder {
     query: String,
+    task_type: String,
     tier_used: String,
     latency_ms: u64,
     tokens_used: TokenUsage,
     success: bool,
     escalated: bool,
+    timings: PhaseTimings,
 }
 
 impl RequestMetricsBuilder {
@@ -40,14 +56,23 @@ impl RequestMetricsBuilder {
     pub fn new(query: String, tier_used: String) -> Self {
         Self {
             query,
+            task_type: String::new(),
             tier_used,
             latency_ms: 0,
             tokens_used: TokenUsage::default(),
             success: true,
             escalated: false,
+            timings: PhaseTimings::default(),
         }
     }
 
+    /// Sets the kind of task
+    #[must_use]
+    pub fn task_type(mut self, task_type: String) -> Self {
+        self.task_type = task_type;
+        self
+    }
+
     /// Sets the latency
     #[must_use]
     pub fn latency_ms(mut self, latency_ms: u64) -> Self {
@@ -76,6 +101,13 @@ impl RequestMetricsBuilder {
         self
     }
 
+    /// Sets the time spent in each phase
+    #[must_use]
+    pub fn timings(mut self, timings: PhaseTimings) -> Self {
+        self.timings = timings;
+        self
+    }
+
     /// Builds the request metrics
     pub fn build(self) -> RequestMetrics {
         let cost = RequestMetrics::estimate_cost(&self.tier_used, &self.tokens_used);
@@ -83,12 +115,14 @@ impl RequestMetricsBuilder {
         RequestMetrics {
             timestamp: SystemTime::now(),
             query: self.query,
+            task_type: self.task_type,
             tier_used: self.tier_used,
             latency_ms: self.latency_ms,
             tokens_used: self.tokens_used,
             cost,
             success: self.success,
             escalated: self.escalated,
+            timings: self.timings,
         }
     }
 }
@@ -97,6 +131,8 @@ impl RequestMetricsBuilder {
 pub struct RequestMetricsParams {
     /// The query text
     pub query: String,
+    /// Kind of task the request was made for
+    pub task_type: String,
     /// Model tier used
     pub tier_used: String,
     /// Latency in milliseconds
@@ -107,21 +143,26 @@ pub struct RequestMetricsParams {
     pub success: bool,
     /// Whether the request was escalated to a higher tier
     pub escalated: bool,
+    /// Time spent in each phase of the task
+    pub timings: PhaseTimings,
 }
 
 impl RequestMetrics {
     /// Creates new request metrics
     pub fn new(params: RequestMetricsParams) -> Self {
         RequestMetricsBuilder::new(params.query, params.tier_used)
+            .task_type(params.task_type)
             .latency_ms(params.latency_ms)
             .tokens_used(params.tokens_used)
             .success(params.success)
             .escalated(params.escalated)
+            .timings(params.timings)
             .build()
     }
 
-    /// Estimates cost based on tier and token usage
-    fn estimate_cost(tier: &str, tokens: &TokenUsage) -> f64 {
+    /// Estimates cost in USD based on tier and token usage
+    #[must_use]
+    pub fn estimate_cost(tier: &str, tokens: &TokenUsage) -> f64 {
         // Cost estimates per 1M tokens (input/output)
         let (input_cost, output_cost) = match tier {
             tier if tier.contains("local") => (0.0, 0.0), // Local models are free
@@ -142,6 +183,10 @@ impl RequestMetrics {
 /// Collects and stores metrics for analysis
 pub struct MetricsCollector {
     requests: Vec<RequestMetrics>,
+    /// File every recorded request is appended to, if any
+    log: Option<PathBuf>,
+    /// Counters of the language index caches, if they are reported
+    index_cache: Option<IndexCacheStats>,
 }
 
 impl MetricsCollector {
@@ -149,14 +194,80 @@ impl MetricsCollector {
     pub fn new() -> Self {
         Self {
             requests: Vec::new(),
+            log: None,
+            index_cache: None,
         }
     }
 
+    /// Also appends every recorded request to `path` as a JSON line
+    ///
+    /// Requests already in the file are not loaded, so the collector only
+    /// covers the current session.
+    #[must_use]
+    pub fn with_log(mut self, path: PathBuf) -> Self {
+        self.log = Some(path);
+        self
+    }
+
+    /// Also reports the language index cache use counted on `stats`
+    #[must_use]
+    pub fn with_index_cache(mut self, stats: IndexCacheStats) -> Self {
+        self.index_cache = Some(stats);
+        self
+    }
+
+    /// Returns the language index caches' hits, misses and evictions, if reported
+    pub fn index_cache_stats(&self) -> Option<CacheStats> {
+        self.index_cache.as_ref().map(IndexCacheStats::snapshot)
+    }
+
+    /// Loads the requests appended to `path` by earlier sessions
+    ///
+    /// A missing file yields an empty collector.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or a line is not a request
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Self::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let requests = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(from_str)
+            .collect::<JsonResult<_>>()?;
+        Ok(Self {
+            requests,
+            log: None,
+            index_cache: None,
+        })
+    }
+
     /// Records a request
     pub fn record(&mut self, metrics: RequestMetrics) {
+        if let Some(log) = &self.log
+            && let Err(err) = Self::append(log, &metrics)
+        {
+            tracing::warn!("Failed to log request metrics to {}: {err}", log.display());
+        }
         self.requests.push(metrics);
     }
 
+    /// Appends `metrics` to `log` as one JSON line
+    ///
+    /// # Errors
+    /// Returns an error if the metrics cannot be serialized or written
+    fn append(log: &Path, metrics: &RequestMetrics) -> Result<()> {
+        if let Some(parent) = log.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(log)?;
+        writeln!(file, "{}", to_string(metrics)?)?;
+        Ok(())
+    }
+
     /// Gets all recorded requests
     pub fn requests(&self) -> &[RequestMetrics] {
         &self.requests
@@ -165,14 +276,13 @@ impl MetricsCollector {
     /// Gets requests from today
     pub fn requests_today(&self) -> Vec<&RequestMetrics> {
         let now = SystemTime::now();
-        let day_start = now
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|duration| {
-                let secs = duration.as_secs();
-                let day_secs = secs - (secs % 86400);
-                SystemTime::UNIX_EPOCH + Duration::from_secs(day_secs)
-            })
-            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let day_start =
+            now.duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(SystemTime::UNIX_EPOCH, |duration| {
+                    let secs = duration.as_secs();
+                    let day_secs = secs - (secs % 86400);
+                    SystemTime::UNIX_EPOCH + Duration::from_secs(day_secs)
+                });
 
         self.requests
             .iter()
@@ -185,8 +295,9 @@ impl MetricsCollector {
         let now = SystemTime::now();
         let week_ago = now
             .duration_since(SystemTime::UNIX_EPOCH)
-            .map(|duration| SystemTime::UNIX_EPOCH + duration - Duration::from_secs(7 * 86400))
-            .unwrap_or(SystemTime::UNIX_EPOCH);
+            .map_or(SystemTime::UNIX_EPOCH, |duration| {
+                SystemTime::UNIX_EPOCH + duration - Duration::from_hours(7 * 24)
+            });
 
         self.requests
             .iter()
@@ -194,6 +305,16 @@ impl MetricsCollector {
             .collect()
     }
 
+    /// Returns the time spent in each phase, added up over every recorded request
+    pub fn phase_totals(&self) -> PhaseTimings {
+        phase_totals(self.requests.iter())
+    }
+
+    /// Returns the estimated cost in USD of every recorded request
+    pub fn total_cost(&self) -> f64 {
+        self.requests.iter().map(|req| req.cost).sum()
+    }
+
     /// Clears all metrics
     pub fn clear(&mut self) {
         self.requests.clear();
@@ -210,6 +331,17 @@ impl MetricsCollector {
     }
 }
 
+/// Adds up the time spent in each phase by `requests`
+pub fn phase_totals<'req>(
+    requests: impl IntoIterator<Item = &'req RequestMetrics>,
+) -> PhaseTimings {
+    let mut totals = PhaseTimings::default();
+    for request in requests {
+        totals.add(&request.timings);
+    }
+    totals
+}
+
 impl Default for MetricsCollector {
     fn default() -> Self {
         Self::new()
@@ -217,70 +349,4 @@ impl Default for MetricsCollector {
 }
 
 #[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Tests basic metrics collection functionality.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_metrics_collector() {
-        let mut collector = MetricsCollector::new();
-
-        let metrics = RequestMetrics::new(RequestMetricsParams {
-            query: "test query".to_owned(),
-            tier_used: "local".to_owned(),
-            latency_ms: 100,
-            tokens_used: TokenUsage::default(),
-            success: true,
-            escalated: false,
-        });
-
-        collector.record(metrics);
-        assert_eq!(collector.len(), 1);
-    }
-
-    /// Tests cost estimation for different model tiers.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_cost_estimation() {
-        let tokens = TokenUsage {
-            input: 1000,
-            output: 500,
-            cache_read: 0,
-            cache_write: 0,
-        };
-
-        let local_cost = RequestMetrics::estimate_cost("local", &tokens);
-        assert!((local_cost - 0.0).abs() < f64::EPSILON);
-
-        let claude_cost = RequestMetrics::estimate_cost("claude", &tokens);
-        assert!(claude_cost > f64::EPSILON);
-    }
-
-    /// Tests filtering requests from today.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_requests_today() {
-        let mut collector = MetricsCollector::new();
-
-        let metrics = RequestMetrics::new(RequestMetricsParams {
-            query: "test".to_owned(),
-            tier_used: "local".to_owned(),
-            latency_ms: 100,
-            tokens_used: TokenUsage::default(),
-            success: true,
-            escalated: false,
-        });
-
-        collector.record(metrics);
-
-        let today = collector.requests_today();
-        assert_eq!(today.len(), 1);
-    }
-}
+mod tests;
@@ -0,0 +1,181 @@
+//! Tests for the metrics collector
+
+use super::*;
+use tempfile::TempDir;
+
+/// Tests basic metrics collection functionality.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_metrics_collector() {
+    let mut collector = MetricsCollector::new();
+
+    let metrics = RequestMetrics::new(RequestMetricsParams {
+        query: "test query".to_owned(),
+        task_type: "task".to_owned(),
+        tier_used: "local".to_owned(),
+        latency_ms: 100,
+        tokens_used: TokenUsage::default(),
+        success: true,
+        escalated: false,
+        timings: PhaseTimings::default(),
+    });
+
+    collector.record(metrics);
+    assert_eq!(collector.len(), 1);
+}
+
+/// Tests that total cost sums the cost of every recorded request.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_total_cost() {
+    let mut collector = MetricsCollector::new();
+    assert!(collector.total_cost().abs() < f64::EPSILON);
+
+    let tokens = TokenUsage {
+        input: 1_000_000,
+        output: 0,
+        cache_read: 0,
+        cache_write: 0,
+    };
+    for tier in ["local", "claude"] {
+        collector.record(RequestMetrics::new(RequestMetricsParams {
+            query: "test".to_owned(),
+            task_type: "task".to_owned(),
+            tier_used: tier.to_owned(),
+            latency_ms: 100,
+            tokens_used: tokens.clone(),
+            success: true,
+            escalated: false,
+            timings: PhaseTimings::default(),
+        }));
+    }
+
+    assert!((collector.total_cost() - 3.0).abs() < f64::EPSILON);
+}
+
+/// Tests cost estimation for different model tiers.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_cost_estimation() {
+    let tokens = TokenUsage {
+        input: 1000,
+        output: 500,
+        cache_read: 0,
+        cache_write: 0,
+    };
+
+    let local_cost = RequestMetrics::estimate_cost("local", &tokens);
+    assert!((local_cost - 0.0).abs() < f64::EPSILON);
+
+    let claude_cost = RequestMetrics::estimate_cost("claude", &tokens);
+    assert!(claude_cost > f64::EPSILON);
+}
+
+/// Tests filtering requests from today.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_requests_today() {
+    let mut collector = MetricsCollector::new();
+
+    let metrics = RequestMetrics::new(RequestMetricsParams {
+        query: "test".to_owned(),
+        task_type: "task".to_owned(),
+        tier_used: "local".to_owned(),
+        latency_ms: 100,
+        tokens_used: TokenUsage::default(),
+        success: true,
+        escalated: false,
+        timings: PhaseTimings::default(),
+    });
+
+    collector.record(metrics);
+
+    let today = collector.requests_today();
+    assert_eq!(today.len(), 1);
+}
+
+/// Tests that logged requests are loaded back, without the earlier ones in the collector.
+///
+/// # Errors
+/// Returns an error if the log cannot be written or loaded.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_log_round_trip() -> Result<()> {
+    let dir = TempDir::new()?;
+    let log = dir.path().join(".merlin").join(METRICS_FILE);
+    assert!(MetricsCollector::load(&log)?.is_empty());
+
+    for (session, query) in ["first", "second"].into_iter().enumerate() {
+        let mut collector = MetricsCollector::new().with_log(log.clone());
+        collector.record(RequestMetrics::new(RequestMetricsParams {
+            query: query.to_owned(),
+            task_type: "subtask".to_owned(),
+            tier_used: "local".to_owned(),
+            latency_ms: 100,
+            tokens_used: TokenUsage::default(),
+            success: session == 0,
+            escalated: false,
+            timings: PhaseTimings::default(),
+        }));
+        assert_eq!(collector.len(), 1);
+    }
+
+    let loaded = MetricsCollector::load(&log)?;
+    let queries: Vec<_> = loaded
+        .requests()
+        .iter()
+        .map(|req| req.query.as_str())
+        .collect();
+    assert_eq!(queries, ["first", "second"]);
+    assert_eq!(loaded.requests()[0].task_type, "subtask");
+    assert!(!loaded.requests()[1].success);
+    Ok(())
+}
+
+/// Tests that the phase breakdowns of requests are added up.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_phase_totals() {
+    let mut collector = MetricsCollector::new();
+    assert!(collector.phase_totals().is_empty());
+
+    for (provider_ms, tool_ms) in [(1_000, 40), (3_000, 60)] {
+        let mut timings = PhaseTimings {
+            context_ms: 100,
+            provider_ms,
+            validation_ms: 5,
+            ..PhaseTimings::default()
+        };
+        timings.record_tool("bash", tool_ms);
+        timings.record_tool("readFile", 10);
+        collector.record(RequestMetrics::new(RequestMetricsParams {
+            query: "test".to_owned(),
+            task_type: "task".to_owned(),
+            tier_used: "local".to_owned(),
+            latency_ms: provider_ms,
+            tokens_used: TokenUsage::default(),
+            success: true,
+            escalated: false,
+            timings,
+        }));
+    }
+
+    let totals = collector.phase_totals();
+    assert_eq!(totals.context_ms, 200);
+    assert_eq!(totals.provider_ms, 4_000);
+    assert_eq!(totals.tool_ms.get("bash"), Some(&100));
+    assert_eq!(totals.tool_ms.get("readFile"), Some(&20));
+    assert_eq!(totals.validation_ms, 10);
+}
@@ -5,8 +5,11 @@
 
 /// Metrics collection
 pub mod collector;
+/// Prometheus export
+#[cfg(feature = "metrics")]
+pub mod prometheus;
 /// Report generation
 pub mod reporter;
 
-pub use collector::{MetricsCollector, RequestMetrics, RequestMetricsParams};
+pub use collector::{METRICS_FILE, MetricsCollector, RequestMetrics, RequestMetricsParams};
 pub use reporter::{DailyReport, MetricsReport, TierBreakdown};
@@ -0,0 +1,492 @@
+//! Prometheus export of collected request metrics.
+//!
+//! [`render`] turns recorded requests into the Prometheus text exposition
+//! format, and [`serve`] answers `GET /metrics` with it over HTTP/1.1 using
+//! hyper, followed by the language index cache counters when the collector
+//! reports them. Requests whose head exceeds [`MAX_REQUEST_BYTES`] are
+//! answered with 431 and malformed ones with 400 by hyper itself.
+
+use super::collector::{MetricsCollector, RequestMetrics};
+use http_body_util::Full;
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{CONTENT_TYPE as CONTENT_TYPE_HEADER, HeaderValue};
+use hyper::server::conn::http1::Builder;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use merlin_core::TokenUsage;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::{Error as FmtError, Write as _};
+use std::io;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use tokio::spawn;
+
+/// Path the metrics are served on
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Quantiles reported for task durations
+const QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Largest request head accepted (hyper's minimum buffer size)
+pub const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Content type of the text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Totals of the requests handled by one model
+#[derive(Default)]
+struct ModelTotals {
+    /// Number of successful requests
+    succeeded: u64,
+    /// Number of failed requests
+    failed: u64,
+    /// Tokens used
+    tokens: TokenUsage,
+    /// Estimated cost in USD
+    cost: f64,
+    /// Durations in seconds
+    durations: Vec<f64>,
+}
+
+/// Renders `requests` in the Prometheus text exposition format
+///
+/// Requests are grouped by the model (tier) that handled them.
+///
+/// # Errors
+/// Returns an error if formatting fails
+pub fn render(requests: &[RequestMetrics]) -> Result<String, FmtError> {
+    let models = totals_by_model(requests);
+    let mut output = String::new();
+    write_counters(&mut output, &models)?;
+    write_durations(&mut output, &models)?;
+    Ok(output)
+}
+
+/// Renders the requests of `collector`, then its language index cache counters if reported
+///
+/// # Errors
+/// Returns an error if formatting fails
+pub fn render_collector(collector: &MetricsCollector) -> Result<String, FmtError> {
+    let mut output = render(collector.requests())?;
+    if let Some(stats) = collector.index_cache_stats() {
+        for (name, help, count) in [
+            (
+                "hits",
+                "Source files taken from the index cache",
+                stats.hits,
+            ),
+            ("misses", "Source files parsed for the index", stats.misses),
+            (
+                "evictions",
+                "Workspace index caches evicted",
+                stats.evictions,
+            ),
+        ] {
+            let metric = format!("merlin_index_cache_{name}_total");
+            write_header(&mut output, &metric, help, "counter")?;
+            writeln!(output, "{metric} {count}")?;
+        }
+    }
+    Ok(output)
+}
+
+/// Totals of `requests` keyed by their escaped model label, durations sorted
+fn totals_by_model(requests: &[RequestMetrics]) -> BTreeMap<String, ModelTotals> {
+    let mut models: BTreeMap<String, ModelTotals> = BTreeMap::new();
+    for request in requests {
+        let totals = models.entry(escape_label(&request.tier_used)).or_default();
+        if request.success {
+            totals.succeeded += 1;
+        } else {
+            totals.failed += 1;
+        }
+        totals.tokens.input += request.tokens_used.input;
+        totals.tokens.output += request.tokens_used.output;
+        totals.tokens.cache_read += request.tokens_used.cache_read;
+        totals.tokens.cache_write += request.tokens_used.cache_write;
+        totals.cost += request.cost;
+        totals.durations.push(request.latency_ms as f64 / 1000.0);
+    }
+    for totals in models.values_mut() {
+        totals.durations.sort_by(f64::total_cmp);
+    }
+    models
+}
+
+/// Writes the request, token and cost counters
+///
+/// # Errors
+/// Returns an error if formatting fails
+fn write_counters(
+    output: &mut String,
+    models: &BTreeMap<String, ModelTotals>,
+) -> Result<(), FmtError> {
+    write_header(
+        output,
+        "merlin_requests_total",
+        "Requests handled by model and outcome",
+        "counter",
+    )?;
+    for (model, totals) in models {
+        writeln!(
+            output,
+            "merlin_requests_total{{model=\"{model}\",status=\"success\"}} {}",
+            totals.succeeded
+        )?;
+        writeln!(
+            output,
+            "merlin_requests_total{{model=\"{model}\",status=\"failure\"}} {}",
+            totals.failed
+        )?;
+    }
+
+    write_header(
+        output,
+        "merlin_tokens_total",
+        "Tokens used by type and model",
+        "counter",
+    )?;
+    for (model, totals) in models {
+        for (kind, count) in [
+            ("input", totals.tokens.input),
+            ("output", totals.tokens.output),
+            ("cache_read", totals.tokens.cache_read),
+            ("cache_write", totals.tokens.cache_write),
+        ] {
+            writeln!(
+                output,
+                "merlin_tokens_total{{type=\"{kind}\",model=\"{model}\"}} {count}"
+            )?;
+        }
+    }
+
+    write_header(
+        output,
+        "merlin_cost_usd_total",
+        "Estimated cost in USD by model",
+        "counter",
+    )?;
+    for (model, totals) in models {
+        writeln!(
+            output,
+            "merlin_cost_usd_total{{model=\"{model}\"}} {}",
+            totals.cost
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the task duration summary
+///
+/// # Errors
+/// Returns an error if formatting fails
+fn write_durations(
+    output: &mut String,
+    models: &BTreeMap<String, ModelTotals>,
+) -> Result<(), FmtError> {
+    write_header(
+        output,
+        "merlin_task_duration_seconds",
+        "Task duration by model",
+        "summary",
+    )?;
+    for (model, totals) in models {
+        for quantile in QUANTILES {
+            writeln!(
+                output,
+                "merlin_task_duration_seconds{{model=\"{model}\",quantile=\"{quantile}\"}} {}",
+                nearest_rank(&totals.durations, quantile)
+            )?;
+        }
+        writeln!(
+            output,
+            "merlin_task_duration_seconds_sum{{model=\"{model}\"}} {}",
+            totals.durations.iter().sum::<f64>()
+        )?;
+        writeln!(
+            output,
+            "merlin_task_duration_seconds_count{{model=\"{model}\"}} {}",
+            totals.durations.len()
+        )?;
+    }
+    Ok(())
+}
+
+/// Answers scrapes of [`METRICS_PATH`] on `listener` until accepting fails
+///
+/// Each connection is served on its own task; a failing connection is logged
+/// and does not stop the server.
+///
+/// # Errors
+/// Returns an error if accepting a connection fails
+pub async fn serve(
+    listener: TcpListener,
+    collector: Arc<Mutex<MetricsCollector>>,
+) -> io::Result<()> {
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let collector = Arc::clone(&collector);
+        spawn(async move {
+            let service = service_fn(|request| {
+                let response = respond(&request, &collector);
+                async move { Ok::<_, Infallible>(response) }
+            });
+            if let Err(err) = Builder::new()
+                .max_buf_size(MAX_REQUEST_BYTES)
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("Metrics request from {peer} failed: {err}");
+            }
+        });
+    }
+}
+
+/// Response to `request`: the rendered metrics for `GET` [`METRICS_PATH`]
+fn respond(
+    request: &Request<Incoming>,
+    collector: &Mutex<MetricsCollector>,
+) -> Response<Full<Bytes>> {
+    if request.method() != Method::GET {
+        return text_response(
+            StatusCode::METHOD_NOT_ALLOWED,
+            "text/plain",
+            "Only GET is supported\n".to_owned(),
+        );
+    }
+    if request.uri().path() != METRICS_PATH {
+        return text_response(
+            StatusCode::NOT_FOUND,
+            "text/plain",
+            "Not found\n".to_owned(),
+        );
+    }
+
+    let body = collector
+        .lock()
+        .map_err(|err| err.to_string())
+        .and_then(|collector| render_collector(&collector).map_err(|err| err.to_string()));
+    match body {
+        Ok(body) => text_response(StatusCode::OK, CONTENT_TYPE, body),
+        Err(err) => text_response(StatusCode::INTERNAL_SERVER_ERROR, "text/plain", err),
+    }
+}
+
+/// Response with `status` and a `body` of `content_type`
+fn text_response(
+    status: StatusCode,
+    content_type: &'static str,
+    body: String,
+) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE_HEADER, HeaderValue::from_static(content_type));
+    response
+}
+
+/// Writes the `HELP` and `TYPE` lines of a metric
+///
+/// # Errors
+/// Returns an error if formatting fails
+fn write_header(output: &mut String, name: &str, help: &str, kind: &str) -> Result<(), FmtError> {
+    writeln!(output, "# HELP {name} {help}")?;
+    writeln!(output, "# TYPE {name} {kind}")
+}
+
+/// Value at `quantile` of the sorted `values` (0 when there are none)
+fn nearest_rank(values: &[f64], quantile: f64) -> f64 {
+    let rank = (quantile * values.len() as f64).ceil() as usize;
+    values
+        .get(rank.saturating_sub(1).min(values.len().saturating_sub(1)))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Escapes a label value as the text format requires
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::RequestMetricsParams;
+    use merlin_core::PhaseTimings;
+    use merlin_languages::IndexCacheStats;
+    use std::io::ErrorKind;
+    use std::net::SocketAddr;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+    use tokio::net::TcpStream;
+
+    /// Creates a recorded request of `tier`
+    fn request(tier: &str, latency_ms: u64, success: bool) -> RequestMetrics {
+        RequestMetrics::new(RequestMetricsParams {
+            query: "Add tests".to_owned(),
+            task_type: "task".to_owned(),
+            tier_used: tier.to_owned(),
+            latency_ms,
+            tokens_used: TokenUsage {
+                input: 1000,
+                output: 200,
+                cache_read: 50,
+                cache_write: 0,
+            },
+            success,
+            escalated: false,
+            timings: PhaseTimings::default(),
+        })
+    }
+
+    /// Tests that requests are rendered as counters and a summary per model.
+    ///
+    /// # Errors
+    /// Returns an error if formatting fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_render_counters_and_summary() -> Result<(), FmtError> {
+        let requests = [
+            request("claude", 1000, true),
+            request("claude", 3000, true),
+            request("claude", 2000, false),
+            request("local \"qwen\"", 500, true),
+        ];
+        let output = render(&requests)?;
+
+        assert!(output.contains("# TYPE merlin_requests_total counter\n"));
+        assert!(output.contains("merlin_requests_total{model=\"claude\",status=\"success\"} 2\n"));
+        assert!(output.contains("merlin_requests_total{model=\"claude\",status=\"failure\"} 1\n"));
+        assert!(output.contains("merlin_tokens_total{type=\"input\",model=\"claude\"} 3000\n"));
+        assert!(output.contains("merlin_tokens_total{type=\"cache_read\",model=\"claude\"} 150\n"));
+        assert!(output.contains("merlin_cost_usd_total{model=\"local \\\"qwen\\\"\"} 0\n"));
+        assert!(output.contains("# TYPE merlin_task_duration_seconds summary\n"));
+        assert!(
+            output.contains("merlin_task_duration_seconds{model=\"claude\",quantile=\"0.5\"} 2\n")
+        );
+        assert!(
+            output.contains("merlin_task_duration_seconds{model=\"claude\",quantile=\"0.99\"} 3\n")
+        );
+        assert!(output.contains("merlin_task_duration_seconds_sum{model=\"claude\"} 6\n"));
+        assert!(output.contains("merlin_task_duration_seconds_count{model=\"claude\"} 3\n"));
+        Ok(())
+    }
+
+    /// Tests that index cache counters are rendered only when the collector reports them.
+    ///
+    /// # Errors
+    /// Returns an error if formatting fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_render_collector_index_cache() -> Result<(), FmtError> {
+        let without = render_collector(&MetricsCollector::new())?;
+        assert!(!without.contains("merlin_index_cache"));
+
+        let collector = MetricsCollector::new().with_index_cache(IndexCacheStats::new());
+        let output = render_collector(&collector)?;
+        assert!(output.contains("# TYPE merlin_index_cache_hits_total counter\n"));
+        assert!(output.contains("merlin_index_cache_misses_total 0\n"));
+        assert!(output.contains("merlin_index_cache_evictions_total 0\n"));
+        Ok(())
+    }
+
+    /// Sends `request` to `address` and reads the response until the server closes
+    ///
+    /// A reset after the response is not an error: the server may close
+    /// without reading the rest of a rejected request.
+    ///
+    /// # Errors
+    /// Returns an error if connecting, writing or reading fails.
+    async fn exchange(address: SocketAddr, request: &[u8]) -> io::Result<String> {
+        let mut stream = TcpStream::connect(address).await?;
+        stream.write_all(request).await?;
+        let mut response = Vec::new();
+        let mut buffer = [0; 1024];
+        loop {
+            match stream.read(&mut buffer).await {
+                Ok(0) => break,
+                Ok(read) => response.extend_from_slice(&buffer[..read]),
+                Err(err) if err.kind() == ErrorKind::ConnectionReset && !response.is_empty() => {
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(String::from_utf8_lossy(&response).into_owned())
+    }
+
+    /// Tests that the endpoint serves metrics and rejects other paths.
+    ///
+    /// # Errors
+    /// Returns an error if the server cannot be bound or a request fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_serve_metrics_endpoint() -> io::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let mut collector = MetricsCollector::new();
+        collector.record(request("groq", 250, true));
+        let server = spawn(serve(listener, Arc::new(Mutex::new(collector))));
+
+        let get = async |path: &str| {
+            let request =
+                format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+            exchange(address, request.as_bytes()).await
+        };
+        let metrics = get(METRICS_PATH).await?;
+        let missing = get("/").await?;
+        server.abort();
+
+        assert!(metrics.starts_with("HTTP/1.1 200 OK\r\n"), "{metrics}");
+        assert!(metrics.contains("merlin_requests_total{model=\"groq\",status=\"success\"} 1\n"));
+        assert!(
+            missing.starts_with("HTTP/1.1 404 Not Found\r\n"),
+            "{missing}"
+        );
+        Ok(())
+    }
+
+    /// Tests that oversized and malformed requests are rejected instead of parsed in part.
+    ///
+    /// # Errors
+    /// Returns an error if the server cannot be bound or a request fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_serve_rejects_oversized_and_malformed_requests() -> io::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let server = spawn(serve(
+            listener,
+            Arc::new(Mutex::new(MetricsCollector::new())),
+        ));
+
+        let padding = "x".repeat(MAX_REQUEST_BYTES * 2);
+        let oversized = format!("GET {METRICS_PATH} HTTP/1.1\r\nX-Padding: {padding}\r\n\r\n");
+        let too_large = exchange(address, oversized.as_bytes()).await?;
+        let malformed = exchange(address, b"NOT HTTP\r\n\r\n").await?;
+        server.abort();
+
+        assert!(
+            too_large.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"),
+            "{too_large}"
+        );
+        assert!(
+            malformed.starts_with("HTTP/1.1 400 Bad Request\r\n"),
+            "{malformed}"
+        );
+        Ok(())
+    }
+}
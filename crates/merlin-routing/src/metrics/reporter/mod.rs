@@ -1,10 +1,24 @@
 //! Report generation for metrics analysis.
 
-use super::collector::{MetricsCollector, RequestMetrics};
+use super::collector::{MetricsCollector, RequestMetrics, phase_totals};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use merlin_core::PhaseTimings;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Error as FmtError, Write as _};
 
+/// Header of [`MetricsReport::to_csv`]
+const REQUESTS_CSV_HEADER: &str = "timestamp,model,task_type,input_tokens,output_tokens,\
+cache_read_tokens,cache_write_tokens,cost_usd,latency_ms,success";
+
+/// Requests grouped by UTC day and model
+type DayModelGroups<'req> = BTreeMap<(NaiveDate, &'req str), Vec<&'req RequestMetrics>>;
+
+/// Header of [`MetricsReport::daily_csv`]
+const DAILY_CSV_HEADER: &str = "date,model,requests,success_rate,input_tokens,output_tokens,\
+cache_read_tokens,cache_write_tokens,total_cost_usd,mean_cost_usd,mean_latency_ms";
+
 /// Breakdown of requests by tier
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TierBreakdown {
@@ -33,6 +47,9 @@ pub struct DailyReport {
     pub tier_distribution: Vec<TierBreakdown>,
     /// Escalation rate (0.0 to 1.0)
     pub escalation_rate: f64,
+    /// Time spent in each phase, added up over the requests
+    #[serde(default)]
+    pub phase_totals: PhaseTimings,
 }
 
 /// Metrics report generator
@@ -51,6 +68,7 @@ impl MetricsReport {
                 total_cost: 0.0,
                 tier_distribution: Vec::new(),
                 escalation_rate: 0.0,
+                phase_totals: PhaseTimings::default(),
             };
         }
 
@@ -75,6 +93,7 @@ impl MetricsReport {
             total_cost,
             tier_distribution,
             escalation_rate,
+            phase_totals: phase_totals(requests.iter().copied()),
         }
     }
 
@@ -90,6 +109,7 @@ impl MetricsReport {
                 total_cost: 0.0,
                 tier_distribution: Vec::new(),
                 escalation_rate: 0.0,
+                phase_totals: PhaseTimings::default(),
             };
         }
 
@@ -114,7 +134,81 @@ impl MetricsReport {
             total_cost,
             tier_distribution,
             escalation_rate,
+            phase_totals: phase_totals(requests.iter().copied()),
+        }
+    }
+
+    /// Exports every request of the collector as CSV, one row per request
+    ///
+    /// The model column holds the tier the request was routed to.
+    ///
+    /// # Errors
+    /// Returns an error if formatting fails
+    pub fn to_csv(collector: &MetricsCollector) -> Result<String, FmtError> {
+        let mut csv = String::new();
+        writeln!(csv, "{REQUESTS_CSV_HEADER}")?;
+        for request in collector.requests() {
+            let tokens = &request.tokens_used;
+            let timestamp = DateTime::<Utc>::from(request.timestamp)
+                .to_rfc3339_opts(SecondsFormat::Millis, true);
+            writeln!(
+                csv,
+                "{timestamp},{},{},{},{},{},{},{},{},{}",
+                csv_field(&request.tier_used),
+                csv_field(&request.task_type),
+                tokens.input,
+                tokens.output,
+                tokens.cache_read,
+                tokens.cache_write,
+                request.cost,
+                request.latency_ms,
+                request.success,
+            )?;
+        }
+        Ok(csv)
+    }
+
+    /// Exports the requests of the collector as CSV, one row per UTC day and model
+    ///
+    /// Rows hold the totals of the day's requests to the model, and the mean
+    /// cost and latency per request.
+    ///
+    /// # Errors
+    /// Returns an error if formatting fails
+    pub fn daily_csv(collector: &MetricsCollector) -> Result<String, FmtError> {
+        let mut groups = DayModelGroups::new();
+        for request in collector.requests() {
+            let date = DateTime::<Utc>::from(request.timestamp).date_naive();
+            groups
+                .entry((date, request.tier_used.as_str()))
+                .or_default()
+                .push(request);
+        }
+
+        let mut csv = String::new();
+        writeln!(csv, "{DAILY_CSV_HEADER}")?;
+        for ((date, model), requests) in groups {
+            let count = requests.len();
+            let successful = requests.iter().filter(|req| req.success).count();
+            let total_cost: f64 = requests.iter().map(|req| req.cost).sum();
+            let total_latency: u64 = requests.iter().map(|req| req.latency_ms).sum();
+            let tokens = |field: fn(&RequestMetrics) -> u64| -> u64 {
+                requests.iter().map(|req| field(req)).sum()
+            };
+            writeln!(
+                csv,
+                "{date},{},{count},{:.4},{},{},{},{},{total_cost},{},{}",
+                csv_field(model),
+                successful as f64 / count as f64,
+                tokens(|req| req.tokens_used.input),
+                tokens(|req| req.tokens_used.output),
+                tokens(|req| req.tokens_used.cache_read),
+                tokens(|req| req.tokens_used.cache_write),
+                total_cost / count as f64,
+                total_latency / count as u64,
+            )?;
         }
+        Ok(csv)
     }
 
     /// Calculates tier breakdown from requests
@@ -171,81 +265,47 @@ impl MetricsReport {
             )?;
         }
 
-        Ok(output)
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::metrics::collector::RequestMetricsParams;
-    use merlin_core::TokenUsage;
-
-    /// Tests daily report generation with empty collector.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_daily_report_empty() {
-        let collector = MetricsCollector::new();
-        let report = MetricsReport::daily(&collector);
-
-        assert_eq!(report.total_requests, 0);
-        assert!((report.success_rate - 0.0).abs() < f64::EPSILON);
-    }
+        Self::format_phases(&mut output, &report.phase_totals)?;
 
-    /// Tests daily report generation with recorded metrics.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_daily_report_with_data() {
-        let mut collector = MetricsCollector::new();
-
-        let metrics = RequestMetrics::new(RequestMetricsParams {
-            query: "test".to_owned(),
-            tier_used: "local".to_owned(),
-            latency_ms: 100,
-            tokens_used: TokenUsage::default(),
-            success: true,
-            escalated: false,
-        });
-
-        collector.record(metrics);
-
-        let report = MetricsReport::daily(&collector);
-
-        assert_eq!(report.total_requests, 1);
-        assert!((report.success_rate - 1.0).abs() < f64::EPSILON);
-        assert_eq!(report.avg_latency_ms, 100);
+        Ok(output)
     }
 
-    /// Tests formatting of daily report to human-readable string.
+    /// Formats where the time went, as a share of all time spent in phases
     ///
     /// # Errors
-    /// Returns an error if formatting fails.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[test]
-    fn test_format_report() -> Result<(), FmtError> {
-        let report = DailyReport {
-            total_requests: 10,
-            success_rate: 0.9,
-            avg_latency_ms: 150,
-            total_cost: 0.05,
-            tier_distribution: vec![TierBreakdown {
-                tier: "local".to_owned(),
-                count: 10,
-                percentage: 100.0,
-                total_cost: 0.0,
-            }],
-            escalation_rate: 0.1,
+    /// Returns an error if formatting fails
+    fn format_phases(output: &mut String, phases: &PhaseTimings) -> Result<(), FmtError> {
+        let total_ms = phases.total_ms();
+        if total_ms == 0 {
+            return Ok(());
+        }
+        writeln!(output, "\nTime by Phase:")?;
+        let mut phase_line = |indent: &str, phase: &str, duration_ms: u64| {
+            writeln!(
+                output,
+                "{indent}{phase}: {duration_ms}ms ({:.1}%)",
+                duration_ms as f64 / total_ms as f64 * 100.0
+            )
         };
-
-        let formatted = MetricsReport::format_report(&report)?;
-        assert!(formatted.contains("Total Requests: 10"));
-        assert!(formatted.contains("Success Rate: 90.0%"));
+        phase_line("  ", "context", phases.context_ms)?;
+        phase_line("  ", "provider", phases.provider_ms)?;
+        phase_line("  ", "tools", phases.tools_ms())?;
+        for (tool, duration_ms) in &phases.tool_ms {
+            phase_line("    ", tool, *duration_ms)?;
+        }
+        phase_line("  ", "validation", phases.validation_ms)?;
         Ok(())
     }
 }
+
+/// Quotes `value` for a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,209 @@
+//! Tests for the metrics reporter
+
+use super::*;
+use crate::metrics::collector::RequestMetricsParams;
+use merlin_core::TokenUsage;
+
+/// Tests daily report generation with empty collector.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_daily_report_empty() {
+    let collector = MetricsCollector::new();
+    let report = MetricsReport::daily(&collector);
+
+    assert_eq!(report.total_requests, 0);
+    assert!((report.success_rate - 0.0).abs() < f64::EPSILON);
+}
+
+/// Tests daily report generation with recorded metrics.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_daily_report_with_data() {
+    let mut collector = MetricsCollector::new();
+
+    let metrics = RequestMetrics::new(RequestMetricsParams {
+        query: "test".to_owned(),
+        task_type: "task".to_owned(),
+        tier_used: "local".to_owned(),
+        latency_ms: 100,
+        tokens_used: TokenUsage::default(),
+        success: true,
+        escalated: false,
+        timings: PhaseTimings::default(),
+    });
+
+    collector.record(metrics);
+
+    let report = MetricsReport::daily(&collector);
+
+    assert_eq!(report.total_requests, 1);
+    assert!((report.success_rate - 1.0).abs() < f64::EPSILON);
+    assert_eq!(report.avg_latency_ms, 100);
+}
+
+/// Records a request of `task_type` to `tier` with `tokens` on `collector`
+fn record(collector: &mut MetricsCollector, tier: &str, tokens: TokenUsage, success: bool) {
+    collector.record(RequestMetrics::new(RequestMetricsParams {
+        query: "test".to_owned(),
+        task_type: "task".to_owned(),
+        tier_used: tier.to_owned(),
+        latency_ms: 100,
+        tokens_used: tokens,
+        success,
+        escalated: false,
+        timings: PhaseTimings::default(),
+    }));
+}
+
+/// Tests that the CSV export has one row per request with its tokens, cost and outcome.
+///
+/// # Errors
+/// Returns an error if formatting fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_to_csv() -> Result<(), FmtError> {
+    let mut collector = MetricsCollector::new();
+    let tokens = TokenUsage {
+        input: 1_000_000,
+        output: 0,
+        cache_read: 20,
+        cache_write: 10,
+    };
+    record(&mut collector, "claude", tokens, true);
+    record(
+        &mut collector,
+        "Difficulty-7, retried",
+        TokenUsage::default(),
+        false,
+    );
+
+    let csv = MetricsReport::to_csv(&collector)?;
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 3, "{csv}");
+    assert_eq!(lines[0], REQUESTS_CSV_HEADER);
+    assert!(
+        lines[1].ends_with(",claude,task,1000000,0,20,10,3,100,true"),
+        "{csv}"
+    );
+    assert!(
+        lines[2].ends_with(",\"Difficulty-7, retried\",task,0,0,0,0,0,100,false"),
+        "{csv}"
+    );
+    Ok(())
+}
+
+/// Tests that the daily CSV export aggregates requests per day and model.
+///
+/// # Errors
+/// Returns an error if formatting fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_daily_csv() -> Result<(), FmtError> {
+    let mut collector = MetricsCollector::new();
+    let tokens = TokenUsage {
+        input: 500_000,
+        output: 0,
+        cache_read: 0,
+        cache_write: 0,
+    };
+    record(&mut collector, "claude", tokens.clone(), true);
+    record(&mut collector, "claude", tokens, false);
+    record(&mut collector, "local", TokenUsage::default(), true);
+
+    let csv = MetricsReport::daily_csv(&collector)?;
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(lines.len(), 3, "{csv}");
+    assert_eq!(lines[0], DAILY_CSV_HEADER);
+    let today = Utc::now().date_naive();
+    assert_eq!(
+        lines[1],
+        format!("{today},claude,2,0.5000,1000000,0,0,0,3,1.5,100")
+    );
+    assert_eq!(lines[2], format!("{today},local,1,1.0000,0,0,0,0,0,0,100"));
+    Ok(())
+}
+
+/// Tests formatting of daily report to human-readable string.
+///
+/// # Errors
+/// Returns an error if formatting fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_format_report() -> Result<(), FmtError> {
+    let report = DailyReport {
+        total_requests: 10,
+        success_rate: 0.9,
+        avg_latency_ms: 150,
+        total_cost: 0.05,
+        tier_distribution: vec![TierBreakdown {
+            tier: "local".to_owned(),
+            count: 10,
+            percentage: 100.0,
+            total_cost: 0.0,
+        }],
+        escalation_rate: 0.1,
+        phase_totals: PhaseTimings::default(),
+    };
+
+    let formatted = MetricsReport::format_report(&report)?;
+    assert!(formatted.contains("Total Requests: 10"));
+    assert!(formatted.contains("Success Rate: 90.0%"));
+    Ok(())
+}
+
+/// Tests that the report shows where time went across tasks with injected phases.
+///
+/// # Errors
+/// Returns an error if formatting fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_report_aggregates_phases() -> Result<(), FmtError> {
+    let mut collector = MetricsCollector::new();
+    for provider_ms in [500, 1_500] {
+        let mut timings = PhaseTimings {
+            context_ms: 250,
+            provider_ms,
+            validation_ms: 100,
+            ..PhaseTimings::default()
+        };
+        timings.record_tool("bash", 400);
+        collector.record(RequestMetrics::new(RequestMetricsParams {
+            query: "test".to_owned(),
+            task_type: "task".to_owned(),
+            tier_used: "local".to_owned(),
+            latency_ms: provider_ms,
+            tokens_used: TokenUsage::default(),
+            success: true,
+            escalated: false,
+            timings,
+        }));
+    }
+
+    let report = MetricsReport::daily(&collector);
+    assert_eq!(report.phase_totals.provider_ms, 2_000);
+    assert_eq!(report.phase_totals.tools_ms(), 800);
+
+    let formatted = MetricsReport::format_report(&report)?;
+    assert!(formatted.contains("Time by Phase:"), "{formatted}");
+    assert!(
+        formatted.contains("  provider: 2000ms (57.1%)"),
+        "{formatted}"
+    );
+    assert!(
+        formatted.contains("  tools: 800ms (22.9%)\n    bash: 800ms (22.9%)"),
+        "{formatted}"
+    );
+    Ok(())
+}
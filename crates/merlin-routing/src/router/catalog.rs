@@ -0,0 +1,403 @@
+//! Catalog of the models available to route to.
+//!
+//! Context windows, prices and supported features come from the `OpenRouter`
+//! model list and the models installed in Ollama rather than being compiled
+//! in, so new models and price changes need no code change. The catalog is
+//! cached in `~/.merlin/models.json` and refreshed once it is older than the
+//! configured TTL. With `offline` set, only the cached catalog is used.
+
+use super::models::Model;
+use super::provider_registry::ProviderRegistry;
+use crate::Result;
+use merlin_core::{CatalogConfig, RoutingConfig, TokenUsage};
+use merlin_local::ModelInfo;
+use merlin_providers::OpenRouterModel;
+use serde::{Deserialize, Serialize};
+use serde_json::{from_str, to_string_pretty};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// File in `~/.merlin` the catalog is cached in
+pub const CATALOG_FILE: &str = "models.json";
+
+/// Input tokens assumed for a typical request when estimating its cost
+const TYPICAL_INPUT_TOKENS: u64 = 8_000;
+
+/// Output tokens assumed for a typical request when estimating its cost
+const TYPICAL_OUTPUT_TOKENS: u64 = 2_000;
+
+/// Where a catalog entry was listed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ModelSource {
+    /// The `OpenRouter` model list
+    OpenRouter,
+    /// The models installed in Ollama
+    Ollama,
+}
+
+/// A model listed in the catalog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogModel {
+    /// Model id used in requests
+    pub id: String,
+    /// Human-readable name
+    pub name: String,
+    /// Where the model was listed
+    pub source: ModelSource,
+    /// Maximum tokens of prompt and completion, if known
+    pub context_window: Option<u64>,
+    /// USD per million input tokens, if known
+    pub input_price: Option<f64>,
+    /// USD per million output tokens, if known
+    pub output_price: Option<f64>,
+    /// Request features the model supports (e.g. `tools`, `reasoning`)
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+impl CatalogModel {
+    /// Estimated cost in USD of a request using `tokens`, if the model's prices are known
+    ///
+    /// Cached tokens are billed as input.
+    #[must_use]
+    pub fn estimate_cost(&self, tokens: &TokenUsage) -> Option<f64> {
+        let input_price = self.input_price?;
+        let output_price = self.output_price?;
+        let input_tokens = (tokens.input + tokens.cache_read + tokens.cache_write) as f64;
+        let output_tokens = tokens.output as f64;
+        Some(
+            (input_tokens / 1_000_000.0)
+                .mul_add(input_price, (output_tokens / 1_000_000.0) * output_price),
+        )
+    }
+
+    /// Whether the model supports the request feature `feature`
+    #[must_use]
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|supported| supported == feature)
+    }
+}
+
+impl From<OpenRouterModel> for CatalogModel {
+    fn from(model: OpenRouterModel) -> Self {
+        Self {
+            input_price: model.pricing.input_per_million(),
+            output_price: model.pricing.output_per_million(),
+            name: if model.name.is_empty() {
+                model.id.clone()
+            } else {
+                model.name
+            },
+            id: model.id,
+            source: ModelSource::OpenRouter,
+            context_window: model.context_length,
+            features: model.supported_parameters,
+        }
+    }
+}
+
+impl From<&ModelInfo> for CatalogModel {
+    fn from(model: &ModelInfo) -> Self {
+        Self {
+            id: model.name.clone(),
+            name: model.name.clone(),
+            source: ModelSource::Ollama,
+            context_window: None,
+            input_price: Some(0.0),
+            output_price: Some(0.0),
+            features: Vec::new(),
+        }
+    }
+}
+
+/// Models available to route to, with when they were listed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelCatalog {
+    /// When the models were fetched (`None` for an empty catalog)
+    pub fetched_at: Option<SystemTime>,
+    /// The listed models
+    pub models: Vec<CatalogModel>,
+}
+
+impl ModelCatalog {
+    /// Creates a catalog of `models` fetched at `fetched_at`
+    #[must_use]
+    pub fn new(models: Vec<CatalogModel>, fetched_at: SystemTime) -> Self {
+        Self {
+            fetched_at: Some(fetched_at),
+            models,
+        }
+    }
+
+    /// Whether the catalog lists no models
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.models.is_empty()
+    }
+
+    /// Looks up a model by id (Ollama's `:latest` tag is optional)
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&CatalogModel> {
+        let untagged = |name: &str| name.strip_suffix(":latest").unwrap_or(name).to_owned();
+        let id = untagged(id);
+        self.models.iter().find(|model| untagged(&model.id) == id)
+    }
+
+    /// Looks up the entry of a routable model
+    #[must_use]
+    pub fn model(&self, model: Model) -> Option<&CatalogModel> {
+        self.get(model.model_id())
+    }
+
+    /// Context window of `model` in tokens, if listed
+    #[must_use]
+    pub fn context_window(&self, model: Model) -> Option<u64> {
+        self.model(model)?.context_window
+    }
+
+    /// Estimated cost in USD of a request of `model` using `tokens`, if its prices are listed
+    #[must_use]
+    pub fn estimate_cost(&self, model: Model, tokens: &TokenUsage) -> Option<f64> {
+        self.model(model)?.estimate_cost(tokens)
+    }
+
+    /// Estimated cost in USD of a typical request of `model`, if its prices are listed
+    #[must_use]
+    pub fn typical_request_cost(&self, model: Model) -> Option<f64> {
+        self.estimate_cost(
+            model,
+            &TokenUsage {
+                input: TYPICAL_INPUT_TOKENS,
+                output: TYPICAL_OUTPUT_TOKENS,
+                ..TokenUsage::default()
+            },
+        )
+    }
+
+    /// Whether the catalog was fetched more than `ttl` before `now` (or never)
+    #[must_use]
+    pub fn is_stale(&self, ttl: Duration, now: SystemTime) -> bool {
+        self.fetched_at
+            .is_none_or(|fetched_at| now.duration_since(fetched_at).is_ok_and(|age| age > ttl))
+    }
+
+    /// Path of the cached catalog (`~/.merlin/models.json`)
+    ///
+    /// # Errors
+    /// Returns an error if the home directory cannot be determined
+    pub fn cache_path() -> Result<PathBuf> {
+        Ok(RoutingConfig::config_dir()?.join(CATALOG_FILE))
+    }
+
+    /// Loads a cached catalog, `None` if it is missing or unreadable
+    #[must_use]
+    pub fn load(path: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        from_str(&contents)
+            .inspect_err(|err| {
+                tracing::debug!("Ignoring model catalog {}: {err}", path.display());
+            })
+            .ok()
+    }
+
+    /// Writes the catalog to `path`
+    ///
+    /// # Errors
+    /// Returns an error if the catalog cannot be written
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Fetches the models of `OpenRouter` and Ollama
+    ///
+    /// `OpenRouter` is skipped without an API key and Ollama when it is not
+    /// running or the local tier is disabled.
+    ///
+    /// # Errors
+    /// Returns an error if the `OpenRouter` catalog cannot be fetched
+    pub async fn fetch(providers: &ProviderRegistry) -> Result<Self> {
+        let mut models = Vec::new();
+        if let Some(openrouter) = providers.openrouter_provider() {
+            models.extend(
+                openrouter
+                    .model_catalog()
+                    .await?
+                    .into_iter()
+                    .map(CatalogModel::from),
+            );
+        }
+        if let Some(local) = providers.local_provider() {
+            match local.list_available_models().await {
+                Ok(installed) => models.extend(installed.iter().map(CatalogModel::from)),
+                Err(error) => tracing::debug!("Model catalog without Ollama models: {error}"),
+            }
+        }
+        Ok(Self::new(models, SystemTime::now()))
+    }
+
+    /// Loads the cached catalog, refreshing it from the providers once it is stale
+    ///
+    /// Falls back to the stale catalog, or an empty one, if the refresh fails.
+    pub async fn load_or_refresh(config: &RoutingConfig, providers: &ProviderRegistry) -> Self {
+        match Self::cache_path() {
+            Ok(path) => {
+                Self::load_or_refresh_at(&path, &config.catalog, Self::fetch(providers)).await
+            }
+            Err(error) => {
+                tracing::warn!("Model catalog unavailable: {error}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Loads the catalog cached at `path`, replacing it with the result of `fetch` once stale
+    ///
+    /// `fetch` is not awaited while the cache is fresh or `config` pins it for
+    /// offline use. A failed refresh keeps the cached catalog, however old.
+    pub async fn load_or_refresh_at(
+        path: &Path,
+        config: &CatalogConfig,
+        fetch: impl Future<Output = Result<Self>>,
+    ) -> Self {
+        let cached = Self::load(path);
+        let ttl = Duration::from_secs(config.ttl_hours.saturating_mul(3600));
+        if config.offline
+            || cached
+                .as_ref()
+                .is_some_and(|catalog| !catalog.is_stale(ttl, SystemTime::now()))
+        {
+            return cached.unwrap_or_default();
+        }
+
+        match fetch.await {
+            Ok(catalog) => {
+                if let Err(error) = catalog.save(path) {
+                    tracing::warn!("Failed to cache model catalog: {error}");
+                }
+                catalog
+            }
+            Err(error) => {
+                tracing::warn!("Failed to refresh model catalog: {error}");
+                cached.unwrap_or_default()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RoutingError;
+    use merlin_providers::parse_model_catalog;
+    use tempfile::TempDir;
+
+    /// Reads the recorded `OpenRouter` models response from `tests/fixtures/`
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be read or parsed
+    fn recorded_catalog() -> Result<ModelCatalog> {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests")
+            .join("fixtures")
+            .join("openrouter_models.json");
+        let models = parse_model_catalog(&fs::read_to_string(path)?)?;
+        Ok(ModelCatalog::new(
+            models.into_iter().map(CatalogModel::from).collect(),
+            SystemTime::UNIX_EPOCH,
+        ))
+    }
+
+    /// Tests that the recorded response yields context windows, prices and features.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be read.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_recorded_catalog() -> Result<()> {
+        let catalog = recorded_catalog()?;
+        assert_eq!(catalog.models.len(), 4);
+
+        let deepseek = catalog
+            .model(Model::DeepSeekV3)
+            .ok_or_else(|| RoutingError::Other("DeepSeek V3 not listed".to_owned()))?;
+        assert_eq!(deepseek.name, "DeepSeek: DeepSeek V3");
+        assert_eq!(deepseek.context_window, Some(163_840));
+        assert!(deepseek.supports("tools"));
+        assert!(!deepseek.supports("reasoning"));
+        let tokens = TokenUsage {
+            input: 1_000_000,
+            output: 500_000,
+            ..TokenUsage::default()
+        };
+        let cost = catalog.estimate_cost(Model::DeepSeekV3, &tokens);
+        assert!(
+            cost.is_some_and(|cost| (cost - 0.85).abs() < 1e-9),
+            "{cost:?}"
+        );
+
+        // Variable-price routers have no known price
+        let auto = catalog
+            .get("openrouter/auto")
+            .ok_or_else(|| RoutingError::Other("auto router not listed".to_owned()))?;
+        assert_eq!(auto.estimate_cost(&tokens), None);
+
+        // Models missing from the catalog fall back to the built-in estimates
+        assert_eq!(catalog.context_window(Model::Llama318BInstant), None);
+        assert_eq!(catalog.typical_request_cost(Model::Llama318BInstant), None);
+        Ok(())
+    }
+
+    /// Loads the catalog cached at `path`, refreshing it to `fetched` once stale
+    async fn load(path: &Path, offline: bool, fetched: Result<ModelCatalog>) -> ModelCatalog {
+        let config = CatalogConfig {
+            offline,
+            ..CatalogConfig::default()
+        };
+        ModelCatalog::load_or_refresh_at(path, &config, async { fetched }).await
+    }
+
+    /// Tests that the cache is only refreshed once stale, and kept when offline or the refresh fails.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture or cache cannot be read or written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_load_or_refresh() -> Result<()> {
+        let temp = TempDir::new()?;
+        let path = temp.path().join(CATALOG_FILE);
+        let recorded = recorded_catalog()?;
+        let fresh = ModelCatalog::new(recorded.models.clone(), SystemTime::now());
+        let refreshed = ModelCatalog::new(Vec::new(), SystemTime::now());
+
+        // Offline without a cache: empty, nothing fetched
+        assert!(load(&path, true, Ok(fresh.clone())).await.is_empty());
+
+        // No cache: fetched and cached
+        assert_eq!(load(&path, false, Ok(fresh.clone())).await, fresh);
+        assert_eq!(ModelCatalog::load(&path), Some(fresh.clone()));
+
+        // Fresh cache: used without fetching
+        assert_eq!(load(&path, false, Ok(refreshed.clone())).await, fresh);
+
+        // Stale cache: kept when the refresh fails, pinned when offline
+        recorded.save(&path)?;
+        let failed = Err(RoutingError::Other("offline".to_owned()));
+        assert_eq!(load(&path, false, failed).await, recorded);
+        assert_eq!(load(&path, true, Ok(refreshed.clone())).await, recorded);
+
+        // Stale cache: replaced by the refreshed catalog
+        assert_eq!(load(&path, false, Ok(refreshed.clone())).await, refreshed);
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@
 //! This module handles intelligent routing of tasks to appropriate model tiers
 //! based on difficulty ratings (1-10).
 
+/// Catalog of model prices, context windows and features
+pub mod catalog;
 /// Model registry for difficulty-based routing
 pub mod model_registry;
 /// Model definitions and enumerations
@@ -16,6 +18,7 @@ use crate::{Result, Task};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+pub use catalog::{CATALOG_FILE, CatalogModel, ModelCatalog, ModelSource};
 pub use model_registry::ModelRegistry;
 pub use models::{Model, TierCategory};
 pub use provider_registry::ProviderRegistry;
@@ -35,6 +38,15 @@ pub struct RoutingDecision {
     /// Actual provider name (may differ from model when using overrides)
     #[serde(default)]
     pub provider_name: String,
+    /// Measured tokens of the assembled context, when routed with it
+    #[serde(default)]
+    pub context_tokens: Option<usize>,
+    /// Models left out because the context doesn't fit their context window
+    #[serde(default)]
+    pub excluded_for_capacity: Vec<Model>,
+    /// Tokens the context must be cut to, when it fits no available model
+    #[serde(default)]
+    pub truncate_context_to: Option<usize>,
 }
 
 impl Default for RoutingDecision {
@@ -56,6 +68,9 @@ impl RoutingDecision {
             estimated_latency_ms,
             reasoning,
             provider_name: String::new(),
+            context_tokens: None,
+            excluded_for_capacity: Vec::new(),
+            truncate_context_to: None,
         }
     }
 
@@ -81,6 +96,20 @@ pub trait ModelRouter: Send + Sync {
     /// Route a task to appropriate model tier
     async fn route(&self, task: &Task) -> Result<RoutingDecision>;
 
+    /// Route a task whose assembled context measures `context_tokens`
+    ///
+    /// Routers that know the context windows of their models re-route to a
+    /// model the context fits; by default the context size is only recorded.
+    async fn route_with_context(
+        &self,
+        task: &Task,
+        context_tokens: usize,
+    ) -> Result<RoutingDecision> {
+        let mut decision = self.route(task).await?;
+        decision.context_tokens = Some(context_tokens);
+        Ok(decision)
+    }
+
     /// Check if a model is available and has quota
     async fn is_available(&self, model: &Model) -> bool;
 }
@@ -2,7 +2,9 @@
 //!
 //! Maps difficulty levels (1-10) to appropriate models.
 use super::models::Model;
+use super::provider_registry::ProviderRegistry;
 use crate::{Result, RoutingError};
+use merlin_local::ModelInfo;
 use std::collections::HashMap;
 use std::ops::RangeInclusive;
 
@@ -137,6 +139,78 @@ impl ModelRegistry {
             .ok_or_else(|| RoutingError::Other("Failed to retrieve model".to_owned()))
     }
 
+    /// Routes the levels whose model `is_routable` rejects to installed local models.
+    ///
+    /// `installed` are the models Ollama lists; those matching a local [`Model`]
+    /// share the unroutable levels, larger models taking the harder ones. Levels
+    /// with a routable model keep it. Returns the local models now registered.
+    pub fn register_local_models(
+        &mut self,
+        installed: &[ModelInfo],
+        is_routable: impl Fn(Model) -> bool,
+    ) -> Vec<Model> {
+        let mut local: Vec<(u64, Model)> = Vec::new();
+        for info in installed {
+            if let Some(model) = Model::from_ollama_name(&info.name)
+                && !local.iter().any(|(_, known)| *known == model)
+            {
+                local.push((info.size_bytes, model));
+            }
+        }
+        local.sort_by_key(|(size, _)| *size);
+
+        let unroutable: Vec<DifficultyLevel> = (1..=10)
+            .filter(|level| {
+                self.models
+                    .get(level)
+                    .is_none_or(|model| !is_routable(*model))
+            })
+            .collect();
+        if local.is_empty() || unroutable.is_empty() {
+            return Vec::new();
+        }
+
+        let mut registered = Vec::new();
+        for (index, level) in unroutable.iter().enumerate() {
+            let (_, model) = local[index * local.len() / unroutable.len()];
+            self.models.insert(*level, model);
+            if !registered.contains(&model) {
+                registered.push(model);
+            }
+        }
+        registered
+    }
+
+    /// Routes the levels without an enabled provider to the models installed in Ollama.
+    ///
+    /// Does nothing if the local tier is disabled or Ollama is unreachable.
+    /// Returns the local models now registered.
+    pub async fn discover_local_models(&mut self, providers: &ProviderRegistry) -> Vec<Model> {
+        let Some(local) = providers.local_provider() else {
+            return Vec::new();
+        };
+        let installed = match local.list_available_models().await {
+            Ok(installed) => installed,
+            Err(error) => {
+                tracing::debug!("Not routing to local models: {error}");
+                return Vec::new();
+            }
+        };
+        let registered =
+            self.register_local_models(&installed, |model| providers.get_provider(model).is_ok());
+        if !registered.is_empty() {
+            tracing::info!(
+                "Routing to installed local models: {}",
+                registered
+                    .iter()
+                    .map(Model::model_id)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        registered
+    }
+
     /// Lists all registered difficulty levels.
     #[must_use]
     pub fn registered_levels(&self) -> Vec<DifficultyLevel> {
@@ -145,6 +219,19 @@ impl ModelRegistry {
         levels
     }
 
+    /// Lists the difficulty levels `model` is registered for.
+    #[must_use]
+    pub fn levels_of(&self, model: Model) -> Vec<DifficultyLevel> {
+        let mut levels: Vec<_> = self
+            .models
+            .iter()
+            .filter(|(_, registered)| **registered == model)
+            .map(|(level, _)| *level)
+            .collect();
+        levels.sort_unstable();
+        levels
+    }
+
     /// Clears all registered models.
     pub fn clear(&mut self) {
         self.models.clear();
@@ -160,6 +247,7 @@ impl Default for ModelRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::TierCategory;
 
     /// Tests model registry with default difficulty mappings.
     ///
@@ -173,6 +261,8 @@ mod tests {
         assert_eq!(levels.len(), 10);
         assert_eq!(levels[0], 1);
         assert_eq!(levels[9], 10);
+        assert_eq!(registry.levels_of(Model::Claude35Haiku), vec![7, 8]);
+        assert!(registry.levels_of(Model::Qwen25Coder7B).is_empty());
     }
 
     /// Tests exact difficulty level match.
@@ -308,6 +398,63 @@ mod tests {
         Ok(())
     }
 
+    /// Metadata Ollama lists for an installed model
+    fn ollama_model(name: &str, size_bytes: u64) -> ModelInfo {
+        ModelInfo {
+            name: name.to_owned(),
+            size_bytes,
+            parameter_count: String::new(),
+            quantization: String::new(),
+            family: String::new(),
+            modified_at: String::new(),
+        }
+    }
+
+    /// Tests that installed local models take the levels without a routable model.
+    ///
+    /// # Errors
+    /// Returns an error if model selection fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_register_local_models() -> Result<()> {
+        let installed = [
+            ollama_model("qwen2.5-coder:32b", 19_000_000_000),
+            ollama_model("nomic-embed-text:latest", 270_000_000),
+            ollama_model("qwen2.5-coder:7b", 4_700_000_000),
+        ];
+
+        // Only the premium tier is routable, so levels 1-6 go to local models
+        let mut registry = ModelRegistry::with_defaults();
+        let registered = registry.register_local_models(&installed, |model| {
+            model.tier_category() == TierCategory::Premium
+        });
+        assert_eq!(registered, [Model::Qwen25Coder7B, Model::Qwen25Coder32B]);
+        assert_eq!(registry.select_model(1)?, Model::Qwen25Coder7B);
+        assert_eq!(registry.select_model(3)?, Model::Qwen25Coder7B);
+        assert_eq!(registry.select_model(4)?, Model::Qwen25Coder32B);
+        assert_eq!(registry.select_model(6)?, Model::Qwen25Coder32B);
+        assert_eq!(registry.select_model(7)?, Model::Claude35Haiku);
+
+        // Nothing changes while every level is routable or no known model is installed
+        let mut routable = ModelRegistry::with_defaults();
+        assert!(
+            routable
+                .register_local_models(&installed, |_| true)
+                .is_empty()
+        );
+        assert_eq!(routable.select_model(1)?, Model::Llama318BInstant);
+        let mut unknown = ModelRegistry::with_defaults();
+        assert!(
+            unknown
+                .register_local_models(&[ollama_model("llama3:8b", 1)], |_| false)
+                .is_empty()
+        );
+        assert_eq!(unknown.select_model(1)?, Model::Llama318BInstant);
+        Ok(())
+    }
+
     #[test]
     #[should_panic(expected = "Difficulty level must be between 1 and 10, got 0")]
     fn test_register_range_panics_on_invalid() {
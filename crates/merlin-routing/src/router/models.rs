@@ -69,6 +69,27 @@ impl Model {
         }
     }
 
+    /// Find the model with the given provider identifier (e.g. `qwen2.5-coder:7b`).
+    #[must_use]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().into_iter().find(|model| model.model_id() == id)
+    }
+
+    /// Find the model by its identifier or its display name (e.g. `Claude 3.5 Sonnet`).
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|model| model.model_id() == name || model.to_string() == name)
+    }
+
+    /// Find the local model Ollama lists as `name`, with or without its `latest` tag.
+    #[must_use]
+    pub fn from_ollama_name(name: &str) -> Option<Self> {
+        let id = name.strip_suffix(":latest").unwrap_or(name);
+        Self::from_id(id).filter(|model| model.tier_category() == TierCategory::Local)
+    }
+
     /// Get the tier category for this model.
     #[must_use]
     pub const fn tier_category(&self) -> TierCategory {
@@ -208,7 +229,10 @@ mod tests {
             assert!(!model.model_id().is_empty());
             assert!(model.quality_score() >= 1 && model.quality_score() <= 10);
             assert!(model.cost_per_million_tokens() >= 0.0);
+            assert_eq!(Model::from_id(model.model_id()), Some(model));
+            assert_eq!(Model::from_name(&model.to_string()), Some(model));
         }
+        assert_eq!(Model::from_id("gpt-unknown"), None);
     }
 
     /// Tests that models are correctly grouped by tier category.
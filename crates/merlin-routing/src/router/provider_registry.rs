@@ -6,7 +6,8 @@
 use super::models::{Model, TierCategory};
 use merlin_core::{ModelProvider, ProviderType, Result, RoutingConfig, RoutingError};
 use merlin_local::LocalModelProvider;
-use merlin_providers::{ClaudeCodeProvider, GroqProvider, OpenRouterProvider};
+use merlin_providers::{ClaudeCodeProvider, GroqProvider, OpenRouterProvider, pooled_client};
+use reqwest::Client;
 use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
@@ -15,6 +16,7 @@ use std::sync::Arc;
 ///
 /// Providers are instantiated once during initialization and reused
 /// for all requests, avoiding the overhead of repeated provider creation.
+/// HTTP providers share one pooled client, so their connections are reused too.
 #[derive(Clone)]
 pub struct ProviderRegistry {
     /// Map from model to provider instance
@@ -23,46 +25,54 @@ pub struct ProviderRegistry {
     difficulty_overrides: HashMap<u8, Arc<dyn ModelProvider>>,
     /// Configuration for API keys and settings
     config: RoutingConfig,
+    /// HTTP client injected into every HTTP provider (clones share its connection pool)
+    http_client: Client,
 }
 
 impl ProviderRegistry {
     /// Create a new provider registry with the given configuration.
     ///
     /// # Errors
-    /// Returns an error if API keys are missing for enabled tiers.
+    /// Returns an error if API keys are missing for enabled tiers or the HTTP client cannot be built.
     pub fn new(config: RoutingConfig) -> Result<Self> {
         let mut providers = HashMap::new();
         let mut difficulty_overrides = HashMap::new();
+        let http_client = pooled_client()?;
 
         // Setup difficulty-based overrides first
-        Self::register_difficulty_overrides(&mut difficulty_overrides, &config)?;
+        Self::register_difficulty_overrides(&mut difficulty_overrides, &config, &http_client)?;
 
         // Initialize tier-based providers based on enabled flags
         // These are used for model-based routing, independent of difficulty overrides
         if config.tiers.local_enabled {
-            Self::register_local_providers(&mut providers);
+            Self::register_local_providers(&mut providers, &http_client);
         }
 
         if config.tiers.groq_enabled {
-            Self::register_groq_providers(&mut providers, &config)?;
+            Self::register_groq_providers(&mut providers, &config, &http_client)?;
         }
 
         if config.tiers.premium_enabled {
-            Self::register_premium_providers(&mut providers, &config)?;
+            Self::register_premium_providers(&mut providers, &config, &http_client)?;
         }
 
         Ok(Self {
             providers,
             difficulty_overrides,
             config,
+            http_client,
         })
     }
 
     /// Register all local model providers.
-    fn register_local_providers(providers: &mut HashMap<Model, Arc<dyn ModelProvider>>) {
+    fn register_local_providers(
+        providers: &mut HashMap<Model, Arc<dyn ModelProvider>>,
+        http_client: &Client,
+    ) {
         for model in Model::all() {
             if model.tier_category() == TierCategory::Local {
-                let provider = LocalModelProvider::new(model.model_id().to_owned());
+                let provider = LocalModelProvider::new(model.model_id().to_owned())
+                    .with_client(http_client.clone());
                 providers.insert(model, Arc::new(provider));
             }
         }
@@ -75,6 +85,7 @@ impl ProviderRegistry {
     fn register_groq_providers(
         providers: &mut HashMap<Model, Arc<dyn ModelProvider>>,
         config: &RoutingConfig,
+        http_client: &Client,
     ) -> Result<()> {
         // Get Groq API key
         let api_key = config
@@ -89,6 +100,7 @@ impl ProviderRegistry {
             if model.tier_category() == TierCategory::Groq {
                 let provider = GroqProvider::with_api_key_direct(api_key.clone())
                     .map_err(|error| RoutingError::Other(error.to_string()))?
+                    .with_client(http_client.clone())
                     .with_model(model.model_id().to_owned());
                 providers.insert(model, Arc::new(provider));
             }
@@ -104,6 +116,7 @@ impl ProviderRegistry {
     fn register_premium_providers(
         providers: &mut HashMap<Model, Arc<dyn ModelProvider>>,
         config: &RoutingConfig,
+        http_client: &Client,
     ) -> Result<()> {
         // Get OpenRouter API key
         let api_key = config
@@ -119,6 +132,7 @@ impl ProviderRegistry {
         for model in Model::all() {
             if model.tier_category() == TierCategory::Premium {
                 let provider = OpenRouterProvider::new(api_key.clone())?
+                    .with_client(http_client.clone())
                     .with_model(model.model_id().to_owned());
                 providers.insert(model, Arc::new(provider));
             }
@@ -134,10 +148,11 @@ impl ProviderRegistry {
     fn register_difficulty_overrides(
         overrides: &mut HashMap<u8, Arc<dyn ModelProvider>>,
         config: &RoutingConfig,
+        http_client: &Client,
     ) -> Result<()> {
         // Register low difficulty provider (1-3)
         if let Some(provider_type) = &config.tiers.provider_low {
-            let provider = Self::create_provider_for_type(provider_type, config)?;
+            let provider = Self::create_provider_for_type(provider_type, config, http_client)?;
             for difficulty in 1..=3 {
                 overrides.insert(difficulty, Arc::clone(&provider));
             }
@@ -145,7 +160,7 @@ impl ProviderRegistry {
 
         // Register mid difficulty provider (4-6)
         if let Some(provider_type) = &config.tiers.provider_mid {
-            let provider = Self::create_provider_for_type(provider_type, config)?;
+            let provider = Self::create_provider_for_type(provider_type, config, http_client)?;
             for difficulty in 4..=6 {
                 overrides.insert(difficulty, Arc::clone(&provider));
             }
@@ -153,7 +168,7 @@ impl ProviderRegistry {
 
         // Register high difficulty provider (7-10)
         if let Some(provider_type) = &config.tiers.provider_high {
-            let provider = Self::create_provider_for_type(provider_type, config)?;
+            let provider = Self::create_provider_for_type(provider_type, config, http_client)?;
             for difficulty in 7..=10 {
                 overrides.insert(difficulty, Arc::clone(&provider));
             }
@@ -169,11 +184,14 @@ impl ProviderRegistry {
     fn create_provider_for_type(
         provider_type: &ProviderType,
         config: &RoutingConfig,
+        http_client: &Client,
     ) -> Result<Arc<dyn ModelProvider>> {
         match provider_type {
             ProviderType::Local => {
                 let model = config.tiers.local_model.clone();
-                Ok(Arc::new(LocalModelProvider::new(model)))
+                Ok(Arc::new(
+                    LocalModelProvider::new(model).with_client(http_client.clone()),
+                ))
             }
             ProviderType::Groq => {
                 let api_key = config
@@ -187,6 +205,7 @@ impl ProviderRegistry {
                 let model = config.tiers.groq_model.clone();
                 let provider = GroqProvider::with_api_key_direct(api_key)
                     .map_err(|error| RoutingError::Other(error.to_string()))?
+                    .with_client(http_client.clone())
                     .with_model(model);
                 Ok(Arc::new(provider))
             }
@@ -199,7 +218,7 @@ impl ProviderRegistry {
                             "OPENROUTER_API_KEY not found in config or environment".to_owned(),
                         )
                     })?;
-                let provider = OpenRouterProvider::new(api_key)?;
+                let provider = OpenRouterProvider::new(api_key)?.with_client(http_client.clone());
                 Ok(Arc::new(provider))
             }
             ProviderType::ClaudeCode => {
@@ -223,6 +242,24 @@ impl ProviderRegistry {
         })
     }
 
+    /// Provider listing the models installed in Ollama, if the local tier is enabled.
+    #[must_use]
+    pub fn local_provider(&self) -> Option<LocalModelProvider> {
+        self.config.tiers.local_enabled.then(|| {
+            LocalModelProvider::new(self.config.tiers.local_model.clone())
+                .with_client(self.http_client.clone())
+        })
+    }
+
+    /// Provider for the `OpenRouter` model catalog, if an API key is configured.
+    #[must_use]
+    pub fn openrouter_provider(&self) -> Option<OpenRouterProvider> {
+        let api_key = self.config.get_api_key("openrouter")?;
+        OpenRouterProvider::new(api_key)
+            .ok()
+            .map(|provider| provider.with_client(self.http_client.clone()))
+    }
+
     /// Get the provider for a specific difficulty level, using overrides if configured.
     ///
     /// # Errors
@@ -281,6 +318,12 @@ impl ProviderRegistry {
         &self.config
     }
 
+    /// Get the pooled HTTP client shared by the registered providers.
+    #[must_use]
+    pub const fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+
     /// Register a custom provider for a specific model (useful for testing).
     ///
     /// This allows injecting mock providers or overriding default providers.
@@ -288,6 +331,22 @@ impl ProviderRegistry {
         self.providers.insert(model, provider);
     }
 
+    /// Wrap every registered provider, e.g. to record their requests.
+    #[must_use]
+    pub fn map_providers(
+        mut self,
+        wrap: impl Fn(Arc<dyn ModelProvider>) -> Arc<dyn ModelProvider>,
+    ) -> Self {
+        for provider in self
+            .providers
+            .values_mut()
+            .chain(self.difficulty_overrides.values_mut())
+        {
+            *provider = wrap(Arc::clone(provider));
+        }
+        self
+    }
+
     /// Create a registry with a single mock provider for all models (testing).
     ///
     /// # Errors
@@ -305,6 +364,7 @@ impl ProviderRegistry {
             providers,
             difficulty_overrides: HashMap::new(),
             config,
+            http_client: Client::default(),
         })
     }
 }
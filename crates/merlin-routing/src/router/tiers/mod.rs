@@ -0,0 +1,323 @@
+use super::catalog::ModelCatalog;
+use super::model_registry::ModelRegistry;
+use super::models::Model;
+use super::provider_registry::ProviderRegistry;
+use crate::{ModelRouter, Result, RoutingDecision, RoutingError, Task};
+use async_trait::async_trait;
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+/// Share of the measured context added as a safety margin (1/5), since the
+/// measurement is estimated from its length rather than tokenized
+const CONTEXT_MARGIN_DIVISOR: u64 = 5;
+
+/// Tokens of a context window left for the response
+const RESPONSE_TOKEN_RESERVE: u64 = 4_096;
+
+/// Context window needed for a request with `context_tokens` of context
+fn required_window(context_tokens: usize) -> u64 {
+    let tokens = context_tokens as u64;
+    tokens + tokens / CONTEXT_MARGIN_DIVISOR + RESPONSE_TOKEN_RESERVE
+}
+
+/// Most context tokens that fit a window of `window` tokens with the margin
+fn context_budget(window: u64) -> usize {
+    (window.saturating_sub(RESPONSE_TOKEN_RESERVE) * CONTEXT_MARGIN_DIVISOR
+        / (CONTEXT_MARGIN_DIVISOR + 1)) as usize
+}
+
+/// Availability checker for model tiers
+#[derive(Default)]
+pub struct AvailabilityChecker {
+    // In a real implementation, this would track quotas, rate limits, etc.
+}
+
+impl AvailabilityChecker {
+    /// Checks if a model is available for use.
+    ///
+    /// Currently always returns true. In production, this would check:
+    /// - API key presence
+    /// - Rate limit status
+    /// - Quota remaining
+    /// - Service health
+    pub fn check(&self, _model: Model) -> bool {
+        // For now, assume all models are available
+        // In production, this would check:
+        // - API key presence
+        // - Rate limit status
+        // - Quota remaining
+        // - Service health
+        true
+    }
+}
+
+/// Difficulty-based router implementation with model registry and provider registry
+pub struct StrategyRouter {
+    /// Model registry for difficulty-based routing
+    model_registry: Arc<ModelRegistry>,
+    /// Provider registry for accessing provider instances
+    provider_registry: Arc<ProviderRegistry>,
+    /// Availability checker
+    availability_checker: Arc<AvailabilityChecker>,
+    /// Prices and context windows of the available models
+    catalog: Arc<ModelCatalog>,
+}
+
+impl StrategyRouter {
+    /// Creates a new difficulty-based router with default model registry.
+    ///
+    /// # Errors
+    /// Returns an error if provider initialization fails (e.g., missing API keys).
+    pub fn new(provider_registry: ProviderRegistry) -> Self {
+        Self {
+            model_registry: Arc::new(ModelRegistry::with_defaults()),
+            provider_registry: Arc::new(provider_registry),
+            availability_checker: Arc::new(AvailabilityChecker::default()),
+            catalog: Arc::new(ModelCatalog::default()),
+        }
+    }
+
+    /// Creates a router with a custom model registry.
+    #[must_use]
+    pub fn with_model_registry(
+        model_registry: ModelRegistry,
+        provider_registry: ProviderRegistry,
+    ) -> Self {
+        Self {
+            model_registry: Arc::new(model_registry),
+            provider_registry: Arc::new(provider_registry),
+            availability_checker: Arc::new(AvailabilityChecker::default()),
+            catalog: Arc::new(ModelCatalog::default()),
+        }
+    }
+
+    /// Creates a router with default strategies (for backward compatibility).
+    ///
+    /// # Errors
+    /// Returns an error if provider initialization fails.
+    pub fn with_default_strategies() -> Result<Self> {
+        use merlin_core::RoutingConfig;
+        let config = RoutingConfig::load_or_create()?;
+        let provider_registry = ProviderRegistry::new(config)?;
+        Ok(Self::new(provider_registry))
+    }
+
+    /// Prices decisions and looks up context windows with `catalog`.
+    #[must_use]
+    pub fn with_catalog(mut self, catalog: Arc<ModelCatalog>) -> Self {
+        self.catalog = catalog;
+        self
+    }
+
+    /// Get the model catalog.
+    #[must_use]
+    pub fn catalog(&self) -> &Arc<ModelCatalog> {
+        &self.catalog
+    }
+
+    /// Decision for `model`, priced from the catalog when it lists the model
+    fn decision(&self, model: Model, reasoning: String) -> RoutingDecision {
+        let mut decision = RoutingDecision::new(model, reasoning);
+        if let Some(cost) = self.catalog.typical_request_cost(model) {
+            decision.estimated_cost = cost;
+        }
+        decision
+    }
+
+    /// Whether the context window of `model` holds `required` tokens
+    ///
+    /// Models the catalog lists no window for are assumed to.
+    fn fits(&self, model: Model, required: u64) -> bool {
+        self.catalog
+            .context_window(model)
+            .is_none_or(|window| window >= required)
+    }
+
+    /// Cost models that fit the context are ranked by, from the catalog if it prices them
+    fn ranking_cost(&self, model: Model) -> f64 {
+        self.catalog
+            .typical_request_cost(model)
+            .unwrap_or_else(|| model.cost_per_million_tokens())
+    }
+
+    /// Re-routes `decision` to a model whose context window fits `context_tokens`
+    ///
+    /// Keeps the routed model when it fits. Otherwise picks the cheapest available
+    /// model with a listed window that fits, and when none does, the one with
+    /// the largest window, asking for the context to be truncated to fit it.
+    async fn fit_context(
+        &self,
+        mut decision: RoutingDecision,
+        context_tokens: usize,
+    ) -> RoutingDecision {
+        decision.context_tokens = Some(context_tokens);
+        let required = required_window(context_tokens);
+        if self.fits(decision.model, required) {
+            return decision;
+        }
+
+        let (mut fitting, too_small): (Vec<_>, Vec<_>) = Model::all()
+            .into_iter()
+            .filter(|model| self.provider_registry.get_provider(*model).is_ok())
+            .filter_map(|model| Some((model, self.catalog.context_window(model)?)))
+            .partition(|(_, window)| *window >= required);
+        let mut excluded: Vec<Model> = too_small.iter().map(|(model, _)| *model).collect();
+        if !excluded.contains(&decision.model) {
+            excluded.insert(0, decision.model);
+        }
+
+        fitting.sort_by(|(left, _), (right, _)| {
+            self.ranking_cost(*left)
+                .total_cmp(&self.ranking_cost(*right))
+                .then_with(|| right.quality_score().cmp(&left.quality_score()))
+        });
+        for (model, window) in fitting {
+            if self.is_available(&model).await {
+                let reasoning = format!(
+                    "{} can't fit ~{context_tokens} tokens of context; selected {model}, \
+                     the cheapest model whose {window} token window fits",
+                    decision.model
+                );
+                let mut rerouted = self.decision(model, reasoning);
+                rerouted.context_tokens = Some(context_tokens);
+                rerouted.excluded_for_capacity = excluded;
+                return rerouted;
+            }
+        }
+
+        let mut largest = too_small;
+        largest.sort_by_key(|(_, window)| Reverse(*window));
+        for (model, window) in largest {
+            if self.is_available(&model).await {
+                let budget = context_budget(window);
+                let reasoning = format!(
+                    "No available model fits ~{context_tokens} tokens of context; selected \
+                     {model}, whose {window} token window is the largest, truncating the \
+                     context to {budget} tokens"
+                );
+                let mut truncated = self.decision(model, reasoning);
+                truncated.context_tokens = Some(context_tokens);
+                truncated.excluded_for_capacity = excluded;
+                truncated.truncate_context_to = Some(budget);
+                return truncated;
+            }
+        }
+
+        decision.excluded_for_capacity = excluded;
+        decision.truncate_context_to = self
+            .catalog
+            .context_window(decision.model)
+            .map(context_budget);
+        decision
+    }
+
+    /// Get the provider registry.
+    #[must_use]
+    pub fn provider_registry(&self) -> &Arc<ProviderRegistry> {
+        &self.provider_registry
+    }
+
+    /// Get the model registry.
+    #[must_use]
+    pub fn model_registry(&self) -> &Arc<ModelRegistry> {
+        &self.model_registry
+    }
+}
+
+#[async_trait]
+impl ModelRouter for StrategyRouter {
+    async fn route(&self, task: &Task) -> Result<RoutingDecision> {
+        // Check for difficulty-based provider override first
+        if let Ok(provider) = self
+            .provider_registry
+            .get_provider_for_difficulty(task.difficulty)
+        {
+            let reasoning = format!(
+                "Using configured provider override for difficulty level {}",
+                task.difficulty
+            );
+
+            // Use a placeholder model since we're using a direct provider
+            let model = self.model_registry.select_model(task.difficulty)?;
+            let mut decision = self.decision(model, reasoning);
+
+            // Override with actual provider name
+            provider.name().clone_into(&mut decision.provider_name);
+
+            tracing::info!(
+                "🎯 Routing decision: {} (override) | Difficulty: {} | Cost: ${:.6} | Latency: {}ms",
+                provider.name(),
+                task.difficulty,
+                decision.estimated_cost,
+                decision.estimated_latency_ms
+            );
+
+            return Ok(decision);
+        }
+
+        // Fall back to model-based routing
+        let model = self.model_registry.select_model(task.difficulty)?;
+
+        // Check if model is enabled in provider registry
+        if self.provider_registry.get_provider(model).is_err() {
+            return Err(RoutingError::Other(format!(
+                "Selected model {model} is not available. \
+                 Make sure the corresponding tier is enabled in configuration."
+            )));
+        }
+
+        if !self.is_available(&model).await {
+            return Err(RoutingError::Other(format!(
+                "Selected model {model} is not currently available"
+            )));
+        }
+
+        let reasoning = format!(
+            "Selected {} for difficulty level {}",
+            model, task.difficulty
+        );
+
+        let decision = self.decision(model, reasoning);
+
+        tracing::info!(
+            "🎯 Routing decision: {} | Difficulty: {} | Cost: ${:.6} | Latency: {}ms",
+            model,
+            task.difficulty,
+            decision.estimated_cost,
+            decision.estimated_latency_ms
+        );
+
+        Ok(decision)
+    }
+
+    async fn route_with_context(
+        &self,
+        task: &Task,
+        context_tokens: usize,
+    ) -> Result<RoutingDecision> {
+        let mut decision = self.route(task).await?;
+        // Providers configured for the difficulty are used whatever their window
+        if !decision.provider_name.is_empty() {
+            decision.context_tokens = Some(context_tokens);
+            return Ok(decision);
+        }
+
+        let decision = self.fit_context(decision, context_tokens).await;
+        if !decision.excluded_for_capacity.is_empty() {
+            tracing::info!(
+                "🎯 Re-routed to {} for ~{} tokens of context | Excluded for capacity: {:?}",
+                decision.model,
+                context_tokens,
+                decision.excluded_for_capacity
+            );
+        }
+        Ok(decision)
+    }
+
+    async fn is_available(&self, model: &Model) -> bool {
+        self.availability_checker.check(*model) && self.provider_registry.is_available(*model).await
+    }
+}
+
+#[cfg(test)]
+mod tests;
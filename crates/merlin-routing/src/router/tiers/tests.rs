@@ -0,0 +1,273 @@
+//! Tests for difficulty and context-size routing
+
+use super::*;
+use crate::router::catalog::{CatalogModel, ModelSource};
+use merlin_core::{Context, ModelProvider, Query, Response, RoutingConfig};
+use std::time::SystemTime;
+
+/// Creates a test router with cloud providers disabled.
+///
+/// # Errors
+/// Returns an error if provider registry creation fails.
+fn create_test_router() -> Result<StrategyRouter> {
+    let mut config = RoutingConfig::default();
+    // Disable cloud providers for tests
+    config.tiers.groq_enabled = false;
+    config.tiers.premium_enabled = false;
+
+    let provider_registry = ProviderRegistry::new(config)?;
+    Ok(StrategyRouter::new(provider_registry))
+}
+
+/// Tests routing for local models with low difficulty tasks.
+///
+/// # Errors
+/// Returns an error if router creation or routing fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_difficulty_based_routing_local() -> Result<()> {
+    let router = create_test_router()?;
+
+    // Test low difficulty
+    let easy_task = Task::new("Easy task".to_owned()).with_difficulty(2);
+    let result = router.route(&easy_task).await;
+
+    // Will fail because default registry uses Groq models which aren't enabled
+    if result.is_err() {
+        // Expected failure
+    }
+
+    Ok(())
+}
+
+/// Tests that availability checker returns true for all models.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_availability_checker_always_true() {
+    let checker = AvailabilityChecker::default();
+    assert!(checker.check(Model::Llama318BInstant));
+    assert!(checker.check(Model::Qwen25Coder7B));
+    assert!(checker.check(Model::Claude35Sonnet));
+}
+
+/// Tests that router accessors return valid references.
+///
+/// # Errors
+/// Returns an error if router creation fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_router_accessors() -> Result<()> {
+    use std::sync::Arc;
+
+    let router = create_test_router()?;
+    // Test that model_registry accessor works
+    let model_result = router.model_registry().select_model(5);
+    if model_result.is_err() {
+        // Expected if no models registered for difficulty 5
+    }
+    // Test that provider_registry accessor works - it returns a valid Arc reference
+    assert!(Arc::strong_count(router.provider_registry()) > 0);
+    Ok(())
+}
+
+/// Tests router creation with default strategies.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_with_default_strategies_creation() {
+    // This might fail in CI without API keys, which is expected
+    let result = StrategyRouter::with_default_strategies();
+    // Just ensure it doesn't panic
+    drop(result);
+}
+
+/// Tests availability check for local models.
+///
+/// # Errors
+/// Returns an error if router creation fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_is_available_local_model() -> Result<()> {
+    let router = create_test_router()?;
+
+    // Local models should be available if Ollama is running
+    // We don't assert true/false as it depends on environment
+    let _ = router.is_available(&Model::Qwen25Coder7B).await;
+
+    Ok(())
+}
+
+/// Tests routing error when model is not available.
+///
+/// # Errors
+/// Returns an error if router creation fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_route_with_invalid_model() -> Result<()> {
+    let router = create_test_router()?;
+
+    // Try to route a task when cloud providers are disabled
+    let task = Task::new("Test task".to_owned()).with_difficulty(2);
+    let result = router.route(&task).await;
+
+    // Should fail because default registry uses Groq models
+    if let Err(err) = result {
+        assert!(err.to_string().contains("not available"));
+    }
+
+    Ok(())
+}
+
+/// Tests creating router with custom model registry.
+///
+/// # Errors
+/// Returns an error if router creation fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_with_model_registry_constructor() -> Result<()> {
+    let mut config = RoutingConfig::default();
+    // Disable cloud providers for tests
+    config.tiers.groq_enabled = false;
+    config.tiers.premium_enabled = false;
+
+    let provider_registry = ProviderRegistry::new(config)?;
+    let model_registry = ModelRegistry::with_defaults();
+
+    let router = StrategyRouter::with_model_registry(model_registry, provider_registry);
+
+    // Verify the router was created successfully
+    let model_result = router.model_registry().select_model(5);
+    if model_result.is_err() {
+        // Expected if no models registered for difficulty 5
+    }
+    Ok(())
+}
+
+/// Provider that is always available and never called
+struct AvailableProvider;
+
+#[async_trait]
+impl ModelProvider for AvailableProvider {
+    fn name(&self) -> &'static str {
+        "available"
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    async fn generate(&self, _query: &Query, _context: &Context) -> Result<Response> {
+        Err(RoutingError::Other("Not called".to_owned()))
+    }
+
+    fn estimate_cost(&self, _context: &Context) -> f64 {
+        0.0
+    }
+}
+
+/// Catalog entry of `model` with a `window` token context window and the given prices
+fn listed(model: Model, window: u64, input_price: f64, output_price: f64) -> CatalogModel {
+    CatalogModel {
+        id: model.model_id().to_owned(),
+        name: model.to_string(),
+        source: ModelSource::OpenRouter,
+        context_window: Some(window),
+        input_price: Some(input_price),
+        output_price: Some(output_price),
+        features: Vec::new(),
+    }
+}
+
+/// Creates a router sending every difficulty to Llama 3.1 8B, with a catalog
+/// listing windows of 8k, 32k, 128k and 200k tokens
+///
+/// # Errors
+/// Returns an error if the provider registry cannot be created.
+fn create_capacity_router() -> Result<StrategyRouter> {
+    let provider: Arc<dyn ModelProvider> = Arc::new(AvailableProvider);
+    let provider_registry = ProviderRegistry::with_mock_provider(&provider)?;
+    let mut model_registry = ModelRegistry::new();
+    model_registry.register_range(1..=10, Model::Llama318BInstant);
+    let catalog = ModelCatalog::new(
+        vec![
+            listed(Model::Llama318BInstant, 8_192, 0.05, 0.08),
+            listed(Model::GroqQwen25Coder32B, 32_768, 0.1, 0.1),
+            listed(Model::DeepSeekV3, 128_000, 0.27, 1.1),
+            listed(Model::Claude35Haiku, 200_000, 0.8, 4.0),
+        ],
+        SystemTime::UNIX_EPOCH,
+    );
+    Ok(
+        StrategyRouter::with_model_registry(model_registry, provider_registry)
+            .with_catalog(Arc::new(catalog)),
+    )
+}
+
+/// Tests that contexts are routed to the cheapest model whose window fits them.
+///
+/// # Errors
+/// Returns an error if router creation or routing fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_route_with_context_fits_window() -> Result<()> {
+    let router = create_capacity_router()?;
+    let task = Task::new("Explain the codebase".to_owned()).with_difficulty(2);
+
+    // 2,000 tokens with the margin and response reserve still fit 8k
+    let small = router.route_with_context(&task, 2_000).await?;
+    assert_eq!(small.model, Model::Llama318BInstant);
+    assert_eq!(small.context_tokens, Some(2_000));
+    assert!(small.excluded_for_capacity.is_empty());
+
+    // 4,000 tokens no longer do
+    let medium = router.route_with_context(&task, 4_000).await?;
+    assert_eq!(medium.model, Model::GroqQwen25Coder32B);
+    assert_eq!(medium.excluded_for_capacity, vec![Model::Llama318BInstant]);
+    assert_eq!(medium.truncate_context_to, None);
+
+    // Past 32k, DeepSeek V3 is cheaper than Haiku
+    let large = router.route_with_context(&task, 50_000).await?;
+    assert_eq!(large.model, Model::DeepSeekV3);
+    assert_eq!(
+        large.excluded_for_capacity,
+        vec![Model::Llama318BInstant, Model::GroqQwen25Coder32B]
+    );
+    assert_eq!(large.context_tokens, Some(50_000));
+    Ok(())
+}
+
+/// Tests that a context no model fits goes to the largest window, truncated.
+///
+/// # Errors
+/// Returns an error if router creation or routing fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_route_with_context_truncates_when_nothing_fits() -> Result<()> {
+    let router = create_capacity_router()?;
+    let task = Task::new("Explain the codebase".to_owned()).with_difficulty(2);
+
+    let decision = router.route_with_context(&task, 300_000).await?;
+    assert_eq!(decision.model, Model::Claude35Haiku);
+    assert_eq!(decision.excluded_for_capacity.len(), 4);
+    let budget = decision.truncate_context_to.unwrap_or(usize::MAX);
+    assert!(required_window(budget) <= 200_000, "{budget}");
+    assert!(required_window(budget + 10) > 200_000, "{budget}");
+    Ok(())
+}
@@ -1,28 +1,93 @@
-use std::process::Command;
-
 use async_trait::async_trait;
-use serde_json::from_value;
-use tokio::task::spawn_blocking;
+use serde_json::{Value, from_value, json};
+use std::io;
+use std::process::Stdio;
+use tokio::process::Command;
 
+use crate::platform::ShellFlavor;
+use crate::shell_processes::RunningCommands;
 use crate::tool::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
 
-/// Tool that executes shell commands asynchronously using `sh`.
+/// Signature shown when commands run in `sh`
+const POSIX_SIGNATURE: &str = "/**\n\
+ * Execute a shell command using sh. \n\
+ * Usage: bash(\"command string\")\n\
+ * Example: bash(\"ls -la\") or bash(\"grep -r TODO . --exclude-dir={.git,target,node_modules}\")\n\
+ * \n\
+ * IMPORTANT for grep/search commands:\n\
+ * - Always exclude build artifacts: --exclude-dir={.git,target,node_modules,dist,build}\n\
+ * - Exclude binary files: --binary-files=without-match or -I\n\
+ * - Filter by file type using multiple --include flags (one per extension)\n\
+ * - Example: bash(\"grep -r -I 'pattern' . --include='*.rs' --exclude-dir={.git,target}\")\n\
+ * - Example: bash(\"grep -r 'TODO' . --include='*.rs' --include='*.toml' --exclude-dir={.git,target}\")\n\
+ */\n\
+declare function bash(command: string): Promise<{ stdout: string; stderr: string; exit_code: number }>;";
+
+/// Signature shown when commands run in `cmd`
+const CMD_SIGNATURE: &str = "/**\n\
+ * Execute a shell command using Windows cmd.exe. \n\
+ * Usage: bash(\"command string\")\n\
+ * Example: bash(\"dir /b\") or bash(\"findstr /s /n TODO *.rs\")\n\
+ * \n\
+ * IMPORTANT: commands run in cmd.exe, not a POSIX shell:\n\
+ * - Quote paths with double quotes; single quotes are not quoting characters\n\
+ * - Use findstr /s /i /n for searching; there is no grep or brace expansion\n\
+ * - Prefer the find and read tools over dir/type for exploring the workspace\n\
+ */\n\
+declare function bash(command: string): Promise<{ stdout: string; stderr: string; exit_code: number }>;";
+
+/// Signature shown when commands run in `PowerShell`
+const POWERSHELL_SIGNATURE: &str = "/**\n\
+ * Execute a shell command using PowerShell. \n\
+ * Usage: bash(\"command string\")\n\
+ * Example: bash(\"Get-ChildItem -Name\") or bash(\"Select-String -Path *.rs -Pattern TODO\")\n\
+ * \n\
+ * IMPORTANT: commands run in PowerShell, not a POSIX shell:\n\
+ * - Search recursively with Get-ChildItem -Recurse -Include *.rs | Select-String -Pattern 'TODO'\n\
+ * - Skip build artifacts with Where-Object { $_.FullName -notmatch '\\\\(\\.git|target|node_modules)\\\\' }\n\
+ * - Chain commands with ; and check $LASTEXITCODE instead of using &&\n\
+ */\n\
+declare function bash(command: string): Promise<{ stdout: string; stderr: string; exit_code: number }>;";
+
+/// Tool that executes shell commands asynchronously.
 ///
-/// Uses `tokio::task::spawn_blocking` with `std::process::Command` to avoid
-/// blocking the Tokio runtime. Commands are executed via `sh -c` for POSIX
-/// compliance and optimal performance across all platforms.
+/// Commands run via `sh -c` by default, or via `cmd /C` on Windows;
+/// `MERLIN_SHELL` picks another [`ShellFlavor`]. Each command is registered in
+/// the tool's [`RunningCommands`] while it runs, so it is killed with the
+/// processes it started when the call is cancelled or the registry's owner
+/// shuts down.
 ///
-/// ## Performance Note
-/// On Windows (MINGW64/Git Bash), `bash` has ~6 second startup overhead when
-/// spawned from Rust's `std::process::Command`, while `sh` has only ~55ms.
-#[derive(Debug, Clone, Copy)]
-pub struct BashTool;
+/// The tool keeps the `bash` name whatever the shell, but its TypeScript
+/// signature describes the shell the commands actually run in.
+#[derive(Debug, Clone)]
+pub struct BashTool {
+    /// Shell the commands run in
+    shell: ShellFlavor,
+    /// Commands currently running, killed when their owner shuts down
+    running: RunningCommands,
+}
 
 impl BashTool {
-    /// Execute the provided shell command using blocking I/O in `spawn_blocking`.
+    /// Create a tool running commands in `shell`
+    #[must_use]
+    pub fn with_shell(shell: ShellFlavor) -> Self {
+        Self {
+            shell,
+            running: RunningCommands::default(),
+        }
+    }
+
+    /// Register running commands in `running` instead of a registry of the tool's own
+    #[must_use]
+    pub fn with_running_commands(mut self, running: RunningCommands) -> Self {
+        self.running = running;
+        self
+    }
+
+    /// Execute the provided shell command and wait for its output.
     ///
-    /// Uses `tokio::task::spawn_blocking` with `std::process::Command` to avoid blocking
-    /// the `current_thread` runtime. The `spawn_blocking` uses Tokio's global thread pool.
+    /// On Unix the shell leads its own process group, so killing it on
+    /// cancellation also kills the pipelines and background jobs it started.
     ///
     /// # Errors
     ///
@@ -31,38 +96,31 @@ impl BashTool {
         let command_str = command;
         tracing::debug!("Executing shell command: {}", command_str);
 
-        let command = command.to_owned();
-
-        // Use spawn_blocking to run blocking I/O on Tokio's global thread pool
-        // This works even when called from a current_thread runtime
-        tracing::debug!("About to call spawn_blocking for command: {}", command);
-        let output = spawn_blocking(move || {
-            tracing::debug!("Inside spawn_blocking, about to run command");
-            // Use sh for better performance on all platforms
-            // On Windows (MINGW64/Git Bash), bash has ~6s startup overhead when spawned
-            // from Rust's std::process::Command, while sh has only ~55ms overhead
-            // sh is POSIX compliant and sufficient for all our use cases
-            let bash_cmd = "sh";
-
-            let result = Command::new(bash_cmd)
-                .arg("-c")
-                .arg(&command)
-                .env("LANG", "C.UTF-8") // Ensure consistent locale
-                .output();
-
-            tracing::debug!(
-                "Command finished in spawn_blocking with result: {:?}",
-                result.as_ref().map(|output| output.status)
-            );
-            result
-        })
-        .await
-        .map_err(|err| ToolError::ExecutionFailed(format!("Task join failed: {err}")))?
-        .map_err(|err| {
-            ToolError::ExecutionFailed(format!(
-                "Command execution failed (is sh available in PATH?): {err}"
-            ))
-        })?;
+        let spawn_failed = |err: io::Error| ToolError::CommandFailed {
+            tool_name: "bash".to_owned(),
+            exit_code: None,
+            stderr: format!(
+                "Failed to run {} (is it available in PATH?): {err}",
+                self.shell.program()
+            ),
+        };
+        let mut process = Command::from(self.shell.command(command));
+        process
+            .env("LANG", "C.UTF-8") // Ensure consistent locale
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(unix)]
+        process.process_group(0);
+
+        let child = process.spawn().map_err(spawn_failed)?;
+        let running = child.id().map(|pid| self.running.register(pid));
+        let output = child.wait_with_output().await.map_err(spawn_failed)?;
+        if let Some(running) = running {
+            running.finish();
+        }
+        tracing::debug!("Command finished with status: {:?}", output.status);
 
         let stdout = String::from_utf8_lossy(&output.stdout).to_string();
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
@@ -101,6 +159,7 @@ impl BashTool {
                 success: false,
                 message,
                 data: Some(data),
+                code: None,
             })
         }
     }
@@ -108,7 +167,7 @@ impl BashTool {
 
 impl Default for BashTool {
     fn default() -> Self {
-        Self
+        Self::with_shell(ShellFlavor::from_env())
     }
 }
 
@@ -119,19 +178,21 @@ impl Tool for BashTool {
     }
 
     fn typescript_signature(&self) -> &'static str {
-        "/**\n\
-         * Execute a shell command using bash. \n\
-         * Usage: bash(\"command string\")\n\
-         * Example: bash(\"ls -la\") or bash(\"grep -r TODO . --exclude-dir={.git,target,node_modules}\")\n\
-         * \n\
-         * IMPORTANT for grep/search commands:\n\
-         * - Always exclude build artifacts: --exclude-dir={.git,target,node_modules,dist,build}\n\
-         * - Exclude binary files: --binary-files=without-match or -I\n\
-         * - Filter by file type using multiple --include flags (one per extension)\n\
-         * - Example: bash(\"grep -r -I 'pattern' . --include='*.rs' --exclude-dir={.git,target}\")\n\
-         * - Example: bash(\"grep -r 'TODO' . --include='*.rs' --include='*.toml' --exclude-dir={.git,target}\")\n\
-         */\n\
-         declare function bash(command: string): Promise<{ stdout: string; stderr: string; exit_code: number }>;"
+        match self.shell {
+            ShellFlavor::Posix => POSIX_SIGNATURE,
+            ShellFlavor::Cmd => CMD_SIGNATURE,
+            ShellFlavor::PowerShell => POWERSHELL_SIGNATURE,
+        }
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": { "type": "string" },
+            },
+            "required": ["command"]
+        })
     }
 
     async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
@@ -139,7 +200,7 @@ impl Tool for BashTool {
         // and object parameter (from agent/routing system)
         let command: String = if input.params.is_string() {
             // Direct string from TypeScript: bash("command")
-            from_value(input.params)?
+            from_value(input.params).map_err(|err| ToolError::from_arguments(&err))?
         } else {
             // Object from agent: { "command": "..." }
             from_value(
@@ -147,10 +208,11 @@ impl Tool for BashTool {
                     .params
                     .get("command")
                     .ok_or_else(|| {
-                        ToolError::InvalidInput("Missing 'command' parameter".to_owned())
+                        ToolError::invalid_argument("command", "Missing 'command' parameter")
                     })?
                     .clone(),
-            )?
+            )
+            .map_err(|err| ToolError::invalid_argument("command", err.to_string()))?
         };
         self.execute_command(&command).await
     }
@@ -160,6 +222,10 @@ impl Tool for BashTool {
 mod tests {
     use super::*;
     use anyhow::Result;
+    #[cfg(target_os = "linux")]
+    use std::path::Path;
+    #[cfg(target_os = "linux")]
+    use tokio::task::JoinHandle;
 
     /// Tests basic bash command execution with successful output.
     ///
@@ -170,7 +236,7 @@ mod tests {
     /// Panics if assertions fail during test execution.
     #[tokio::test]
     async fn test_bash_tool_simple_command() -> Result<()> {
-        let tool = BashTool;
+        let tool = BashTool::default();
         let input = ToolInput {
             params: serde_json::json!("echo 'hello'"),
         };
@@ -199,7 +265,7 @@ mod tests {
     /// Panics if assertions fail during test execution.
     #[tokio::test]
     async fn test_bash_tool_command_failure() -> Result<()> {
-        let tool = BashTool;
+        let tool = BashTool::default();
         let input = ToolInput {
             params: serde_json::json!("exit 1"),
         };
@@ -223,7 +289,7 @@ mod tests {
     /// Panics if assertions fail during test execution.
     #[tokio::test]
     async fn test_bash_tool_with_object_params() -> Result<()> {
-        let tool = BashTool;
+        let tool = BashTool::default();
         let input = ToolInput {
             params: serde_json::json!({"command": "echo test"}),
         };
@@ -246,13 +312,16 @@ mod tests {
     /// Panics if assertions fail during test execution.
     #[tokio::test]
     async fn test_bash_tool_missing_command_param() {
-        let tool = BashTool;
+        let tool = BashTool::default();
         let input = ToolInput {
             params: serde_json::json!({"wrong": "param"}),
         };
 
         let result = tool.execute(input).await;
-        assert!(result.is_err(), "Should fail with missing command param");
+        assert!(
+            matches!(&result, Err(err) if err.field() == Some("command")),
+            "Should fail with missing command param, got {result:?}"
+        );
     }
 
     /// Tests bash tool name and TypeScript signature generation.
@@ -261,12 +330,150 @@ mod tests {
     /// Panics if assertions fail during test execution.
     #[tokio::test]
     async fn test_bash_tool_name_and_signature() {
-        let tool = BashTool;
+        let tool = BashTool::default();
         assert_eq!(tool.name(), "bash");
         assert!(!tool.typescript_signature().is_empty());
     }
 
-    // Test removed: test_git_bash_env_var was causing race conditions when run in parallel
-    // with other tests by mutating global environment state. The GIT_BASH env var behavior
-    // is already indirectly tested by the other bash tests when bash is available.
+    /// Tests that the signature describes the shell the commands run in.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_signature_matches_shell() {
+        let posix = BashTool::with_shell(ShellFlavor::Posix);
+        assert!(posix.typescript_signature().contains("using sh"));
+        assert!(posix.typescript_signature().contains("--exclude-dir={"));
+
+        for shell in [ShellFlavor::Cmd, ShellFlavor::PowerShell] {
+            let tool = BashTool::with_shell(shell);
+            let signature = tool.typescript_signature();
+            assert!(!signature.contains("--exclude-dir"), "{signature}");
+            assert!(signature.contains("declare function bash(command: string)"));
+        }
+        let cmd = BashTool::with_shell(ShellFlavor::Cmd);
+        assert!(cmd.typescript_signature().contains("cmd.exe"));
+    }
+
+    /// Returns whether process `pid` is running; exited zombies count as gone
+    #[cfg(target_os = "linux")]
+    fn process_running(pid: u32) -> bool {
+        use std::fs;
+
+        fs::read_to_string(format!("/proc/{pid}/stat")).is_ok_and(|stat| {
+            stat.rsplit_once(") ")
+                .is_some_and(|(_, fields)| !fields.starts_with('Z'))
+        })
+    }
+
+    /// Runs `sleep 30` in the background of a command of `tool`, returning
+    /// the call and the process id of `sleep` once it started
+    ///
+    /// # Errors
+    /// Returns an error if the pid file cannot be used or `sleep` never starts.
+    #[cfg(target_os = "linux")]
+    async fn start_sleep(
+        tool: BashTool,
+        pid_file: &Path,
+    ) -> Result<(JoinHandle<ToolResult<ToolOutput>>, u32)> {
+        use std::fs;
+        use std::time::Duration;
+        use tokio::spawn;
+        use tokio::time::sleep;
+
+        let command = format!("sleep 30 & echo $! > '{}'; wait", pid_file.display());
+        let call = spawn(async move {
+            tool.execute(ToolInput {
+                params: json!(command),
+            })
+            .await
+        });
+        for _ in 0..100 {
+            if let Some(pid) = fs::read_to_string(pid_file)
+                .ok()
+                .and_then(|pid| pid.trim().parse::<u32>().ok())
+            {
+                return Ok((call, pid));
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+        call.abort();
+        Err(anyhow::anyhow!("sleep never started"))
+    }
+
+    /// Waits up to five seconds for process `pid` to exit
+    #[cfg(target_os = "linux")]
+    async fn wait_for_exit(pid: u32) {
+        use std::time::Duration;
+        use tokio::time::sleep;
+
+        for _ in 0..100 {
+            if !process_running(pid) {
+                break;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Tests that cancelling a long command kills the shell's child processes.
+    ///
+    /// # Errors
+    /// Returns an error if the temporary directory or pid file cannot be used.
+    ///
+    /// # Panics
+    /// Panics if the `sleep` child outlives the cancelled call.
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_cancelled_command_kills_children() -> Result<()> {
+        use tempfile::TempDir;
+
+        let dir = TempDir::new()?;
+        let tool = BashTool::with_shell(ShellFlavor::Posix);
+        let (call, sleep_pid) = start_sleep(tool, &dir.path().join("sleep.pid")).await?;
+        assert!(process_running(sleep_pid));
+
+        call.abort();
+        assert!(call.await.is_err_and(|err| err.is_cancelled()));
+        wait_for_exit(sleep_pid).await;
+        assert!(
+            !process_running(sleep_pid),
+            "sleep {sleep_pid} still running"
+        );
+        Ok(())
+    }
+
+    /// Tests that killing one registry's commands leaves another registry's running.
+    ///
+    /// # Errors
+    /// Returns an error if the temporary directory or pid files cannot be used.
+    ///
+    /// # Panics
+    /// Panics if the wrong command is killed.
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_kill_all_only_kills_own_commands() -> Result<()> {
+        use tempfile::TempDir;
+
+        let dir = TempDir::new()?;
+        let (own, other) = (RunningCommands::default(), RunningCommands::default());
+        let tool = |running: &RunningCommands| {
+            BashTool::with_shell(ShellFlavor::Posix).with_running_commands(running.clone())
+        };
+        let (own_call, own_pid) = start_sleep(tool(&own), &dir.path().join("own.pid")).await?;
+        let (other_call, other_pid) =
+            start_sleep(tool(&other), &dir.path().join("other.pid")).await?;
+
+        assert_eq!(own.kill_all(), 1);
+        wait_for_exit(own_pid).await;
+        assert!(!process_running(own_pid), "sleep {own_pid} still running");
+        assert!(process_running(other_pid));
+        assert_eq!(other.len(), 1);
+
+        assert_eq!(other.kill_all(), 1);
+        wait_for_exit(other_pid).await;
+        assert!(!process_running(other_pid));
+        assert!(own_call.await.is_ok_and(|output| output.is_ok()));
+        assert!(other_call.await.is_ok_and(|output| output.is_ok()));
+        Ok(())
+    }
 }
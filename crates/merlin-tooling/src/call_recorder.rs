@@ -0,0 +1,142 @@
+//! Recording of the tools called by agent code.
+//!
+//! A [`ToolRegistry`](crate::ToolRegistry) built `with_call_recorder` hands
+//! out tools that record their name and parameters before executing, so tests
+//! can check which tools the agent called and in which order.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use crate::{Tool, ToolInput, ToolOutput, ToolResult};
+
+/// One recorded tool call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedToolCall {
+    /// Name of the called tool
+    pub tool: String,
+    /// Parameters the tool was called with
+    pub params: Value,
+}
+
+/// Tool calls recorded since the recorder was created
+#[derive(Debug, Default, Clone)]
+pub struct ToolCallRecorder {
+    /// Recorded calls, in call order
+    calls: Arc<Mutex<Vec<RecordedToolCall>>>,
+}
+
+impl ToolCallRecorder {
+    /// Create a new, empty recorder
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a call
+    pub async fn record(&self, tool: &str, params: Value) {
+        self.calls.lock().await.push(RecordedToolCall {
+            tool: tool.to_owned(),
+            params,
+        });
+    }
+
+    /// Get every call recorded so far, in call order
+    pub async fn calls(&self) -> Vec<RecordedToolCall> {
+        self.calls.lock().await.clone()
+    }
+}
+
+/// Tool recording its calls before delegating to the wrapped tool
+pub struct RecordingTool {
+    /// Wrapped tool
+    inner: Arc<dyn Tool>,
+    /// Recorder the calls go to
+    recorder: ToolCallRecorder,
+}
+
+impl RecordingTool {
+    /// Wrap `inner` so its calls are recorded in `recorder`
+    pub fn new(inner: Arc<dyn Tool>, recorder: ToolCallRecorder) -> Self {
+        Self { inner, recorder }
+    }
+}
+
+#[async_trait]
+impl Tool for RecordingTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn typescript_signature(&self) -> &str {
+        self.inner.typescript_signature()
+    }
+
+    fn schema(&self) -> Value {
+        self.inner.schema()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.inner.is_cacheable()
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner.is_interactive()
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        self.recorder
+            .record(self.inner.name(), input.params.clone())
+            .await;
+        self.inner.execute(input).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ReadFileTool, ToolRegistry};
+    use anyhow::{Result, anyhow};
+    use serde_json::json;
+    use std::fs::write;
+    use tempfile::TempDir;
+
+    /// Tests that tools of a recording registry record their calls in order.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be written or the tool cannot be found.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_registry_records_calls() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        write(temp_dir.path().join("lib.rs"), "pub fn one() {}\n")?;
+        let recorder = ToolCallRecorder::new();
+        let registry = ToolRegistry::with_workspace(temp_dir.path())
+            .with_tool(Arc::new(ReadFileTool::new(temp_dir.path())))
+            .with_call_recorder(recorder.clone());
+        let read = registry
+            .get_tool("readFile")
+            .ok_or_else(|| anyhow!("readFile not registered"))?;
+
+        read.execute(ToolInput {
+            params: json!("lib.rs"),
+        })
+        .await?;
+        let missing = read
+            .execute(ToolInput {
+                params: json!("missing.rs"),
+            })
+            .await;
+        assert!(missing.is_err(), "reading a missing file should fail");
+
+        let calls = recorder.calls().await;
+        let params: Vec<_> = calls.iter().map(|call| &call.params).collect();
+        assert_eq!(params, [&json!("lib.rs"), &json!("missing.rs")]);
+        assert!(calls.iter().all(|call| call.tool == "readFile"));
+        Ok(())
+    }
+}
@@ -1,28 +1,34 @@
 //! Dynamic context request tool for agents.
 //!
 //! Allows agents to request additional context files during execution
-//! when they need more information to complete a task.
+//! when they need more information to complete a task. Requests can name a
+//! symbol instead of a file pattern, returning just its definition.
+
+mod symbols;
+#[cfg(test)]
+mod tests;
 
 use async_trait::async_trait;
-use glob::glob;
+use glob::{Pattern, glob};
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, to_value};
+use serde_json::{Value, from_value, json, to_value};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-#[cfg(test)]
-use tokio::fs::{create_dir, write};
 use tokio::fs::{metadata, read_to_string};
 use tokio::sync::Mutex;
 use tracing_futures::Instrument as _;
 
-use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+use crate::symbol_lookup::SymbolResolver;
+use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult, display_path};
 
 /// Arguments for context request tool
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextRequestArgs {
-    /// File pattern to search for (glob pattern or file path)
-    pub pattern: String,
+    /// What to fetch
+    #[serde(flatten)]
+    pub target: ContextTarget,
     /// Reason for requesting this context
+    #[serde(default)]
     pub reason: String,
     /// Maximum number of files to return
     #[serde(default = "default_max_files")]
@@ -33,6 +39,22 @@ fn default_max_files() -> usize {
     5
 }
 
+/// Target of a context request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ContextTarget {
+    /// Definition of a symbol, e.g. `Config` or `ValidationPipeline::validate`
+    Symbol {
+        /// Symbol name, optionally qualified by its type
+        symbol: String,
+    },
+    /// Files matching a pattern
+    Files {
+        /// File pattern to search for (glob pattern or file path)
+        pattern: String,
+    },
+}
+
 /// Result of context request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextRequestResult {
@@ -53,6 +75,26 @@ pub struct ContextFile {
     pub content: String,
     /// Size in bytes
     pub size: usize,
+    /// Definition the content was cut from, for symbol requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<SymbolExcerpt>,
+}
+
+/// Location of a symbol definition within an excerpt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolExcerpt {
+    /// Requested symbol
+    pub name: String,
+    /// Symbol kind, e.g. `Function`, `Method` or `Trait`
+    pub kind: String,
+    /// First line of the definition (1-indexed)
+    pub start_line: usize,
+    /// Last line of the definition (1-indexed)
+    pub end_line: usize,
+    /// First line of the excerpt, including surrounding context (1-indexed)
+    pub first_line: usize,
+    /// Last line of the excerpt, including surrounding context (1-indexed)
+    pub last_line: usize,
 }
 
 /// Tracker for requested context files
@@ -60,6 +102,8 @@ pub struct ContextFile {
 pub struct ContextTracker {
     /// Files requested during conversation
     requested_files: Arc<Mutex<Vec<PathBuf>>>,
+    /// Symbols whose definitions were provided during conversation
+    requested_symbols: Arc<Mutex<Vec<String>>>,
 }
 
 impl ContextTracker {
@@ -81,9 +125,27 @@ impl ContextTracker {
         self.requested_files.lock().await.clone()
     }
 
-    /// Clear requested files
+    /// Add a symbol whose definition was provided
+    pub async fn add_requested_symbol(&self, symbol: String) {
+        let mut symbols = self.requested_symbols.lock().await;
+        if !symbols.contains(&symbol) {
+            symbols.push(symbol);
+        }
+    }
+
+    /// Whether the definition of a symbol was already provided
+    pub async fn has_requested_symbol(&self, symbol: &str) -> bool {
+        self.requested_symbols
+            .lock()
+            .await
+            .iter()
+            .any(|requested| requested == symbol)
+    }
+
+    /// Clear requested files and symbols
     pub async fn clear(&self) {
         self.requested_files.lock().await.clear();
+        self.requested_symbols.lock().await.clear();
     }
 }
 
@@ -95,6 +157,8 @@ pub struct ContextRequestTool {
     tracker: ContextTracker,
     /// Maximum file size to read (bytes)
     max_file_size: usize,
+    /// Resolver for symbol requests, searching the files if unset
+    symbol_resolver: Option<Arc<dyn SymbolResolver>>,
 }
 
 impl ContextRequestTool {
@@ -104,6 +168,7 @@ impl ContextRequestTool {
             project_root,
             tracker: ContextTracker::new(),
             max_file_size: 100_000, // 100KB default
+            symbol_resolver: None,
         }
     }
 
@@ -113,9 +178,19 @@ impl ContextRequestTool {
             project_root,
             tracker,
             max_file_size: 100_000,
+            symbol_resolver: None,
         }
     }
 
+    /// Resolve symbol requests with `resolver`, such as the language index
+    ///
+    /// Symbols it does not find are still searched for in the files.
+    #[must_use]
+    pub fn with_symbol_resolver(mut self, resolver: Arc<dyn SymbolResolver>) -> Self {
+        self.symbol_resolver = Some(resolver);
+        self
+    }
+
     /// Set maximum file size
     #[must_use]
     pub fn with_max_file_size(mut self, size: usize) -> Self {
@@ -147,13 +222,16 @@ impl ContextRequestTool {
             format!("**/{pattern}")
         };
 
-        let full_pattern = self.project_root.join(&glob_pattern);
-        let pattern_str = full_pattern.to_string_lossy();
+        // Escaped so brackets or drive prefixes in the root are matched literally
+        let pattern_str = format!(
+            "{}/{glob_pattern}",
+            Pattern::escape(&display_path(&self.project_root))
+        );
 
         let mut files = Vec::new();
 
-        for entry in
-            glob(&pattern_str).map_err(|err| ToolError::ExecutionFailed(err.to_string()))?
+        for entry in glob(&pattern_str)
+            .map_err(|err| ToolError::invalid_argument("pattern", err.to_string()))?
         {
             match entry {
                 Ok(path) if path.is_file() => {
@@ -180,7 +258,7 @@ impl ContextRequestTool {
         let file_metadata = metadata(path)
             .instrument(metadata_span)
             .await
-            .map_err(|err| ToolError::ExecutionFailed(format!("Failed to read metadata: {err}")))?;
+            .map_err(|err| ToolError::io("Failed to read metadata", &err))?;
 
         if file_metadata.len() > self.max_file_size as u64 {
             return Err(ToolError::ExecutionFailed(format!(
@@ -194,50 +272,26 @@ impl ContextRequestTool {
         read_to_string(path)
             .instrument(read_span)
             .await
-            .map_err(|err| ToolError::ExecutionFailed(format!("Failed to read file: {err}")))
+            .map_err(|err| ToolError::io("Failed to read file", &err))
     }
-}
-
-#[async_trait]
-impl Tool for ContextRequestTool {
-    fn name(&self) -> &'static str {
-        "requestContext"
-    }
-
-    fn typescript_signature(&self) -> &'static str {
-        r"/**
- * Request additional context files during task execution.
- * @param pattern - File pattern (glob or path) to search for
- * @param reason - Reason for requesting this context
- * @param max_files - Maximum number of files to return (default: 5)
- * @returns Promise<{ files: { path: string, content: string, size: number }[], success: boolean, message: string }>
- */
-declare function requestContext(pattern: string, reason: string, max_files?: number): Promise<{ files: { path: string, content: string, size: number }[], success: boolean, message: string }>"
-    }
-
-    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
-        let args: ContextRequestArgs = from_value(input.params)
-            .map_err(|err| ToolError::InvalidInput(format!("Invalid arguments: {err}")))?;
-
-        tracing::info!(
-            "Context request: pattern='{}', reason='{}'",
-            args.pattern,
-            args.reason
-        );
 
+    /// Fetches the files matching `pattern`
+    ///
+    /// # Errors
+    /// Returns an error if the pattern is invalid or the result cannot be serialized
+    async fn request_files(&self, pattern: &str, max_files: usize) -> ToolResult<ToolOutput> {
         // Find matching files
-        let file_paths = self.find_files(&args.pattern, args.max_files)?;
+        let file_paths = self.find_files(pattern, max_files)?;
 
         if file_paths.is_empty() {
             let result = ContextRequestResult {
                 files: Vec::new(),
                 success: false,
-                message: format!("No files found matching pattern: {}", args.pattern),
+                message: format!("No files found matching pattern: {pattern}"),
             };
-            let data = to_value(result)
-                .map_err(|err| ToolError::ExecutionFailed(format!("Failed to serialize: {err}")))?;
+            let data = to_value(result)?;
             return Ok(ToolOutput::success_with_data(
-                format!("No files found matching pattern: {}", args.pattern),
+                format!("No files found matching pattern: {pattern}"),
                 data,
             ));
         }
@@ -257,6 +311,7 @@ declare function requestContext(pattern: string, reason: string, max_files?: num
                         path: path.clone(),
                         content,
                         size,
+                        symbol: None,
                     });
                 }
                 Err(err) => {
@@ -282,112 +337,65 @@ declare function requestContext(pattern: string, reason: string, max_files?: num
             message: message.clone(),
         };
 
-        let data = to_value(result).map_err(|error| ToolError::Serialization(error.to_string()))?;
+        let data = to_value(result)?;
 
         // Return success even if no files were read, so tests can check success=false
         Ok(ToolOutput::success_with_data(message, data))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use anyhow::Result;
-    use tempfile::TempDir;
-
-    /// Tests context request for an exact file match.
-    ///
-    /// # Errors
-    /// Returns an error if file creation or context request fails.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[tokio::test]
-    async fn test_context_request_exact_file() -> Result<()> {
-        let temp_dir = TempDir::new()?;
-        let test_file = temp_dir.path().join("test.rs");
-
-        write(&test_file, "fn main() {}").await?;
-
-        let tool = ContextRequestTool::new(temp_dir.path().to_path_buf());
-
-        let input = ToolInput {
-            params: serde_json::json!({
-                "pattern": "test.rs",
-                "reason": "Testing exact file match"
-            }),
-        };
-
-        let output = tool.execute(input).await?;
-        assert!(output.success);
-
-        let data = output
-            .data
-            .ok_or_else(|| anyhow::anyhow!("No data in output"))?;
-        let result: ContextRequestResult = from_value(data)?;
-
-        assert!(result.success);
-        assert_eq!(result.files.len(), 1);
-        assert_eq!(result.files[0].content, "fn main() {}");
-        Ok(())
+#[async_trait]
+impl Tool for ContextRequestTool {
+    fn name(&self) -> &'static str {
+        "requestContext"
     }
 
-    /// Tests context request using glob patterns to match multiple files.
-    ///
-    /// # Errors
-    /// Returns an error if file creation or context request fails.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[tokio::test]
-    async fn test_context_request_glob_pattern() -> Result<()> {
-        let temp_dir = TempDir::new()?;
-        let src_dir = temp_dir.path().join("src");
-        create_dir(&src_dir).await?;
-
-        write(src_dir.join("lib.rs"), "pub fn foo() {}").await?;
-        write(src_dir.join("main.rs"), "fn main() {}").await?;
-
-        let tool = ContextRequestTool::new(temp_dir.path().to_path_buf());
-
-        let input = ToolInput {
-            params: serde_json::json!({
-                "pattern": "**/*.rs",
-                "reason": "Testing glob pattern",
-                "max_files": 10
-            }),
-        };
-
-        let output = tool.execute(input).await?;
-        assert!(output.success);
-
-        let data = output
-            .data
-            .ok_or_else(|| anyhow::anyhow!("No data in output"))?;
-        let result: ContextRequestResult = from_value(data)?;
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Request additional context during task execution.
+ * Pass { symbol } to get just a symbol's definition, e.g. { symbol: 'Config' } or
+ * { symbol: 'ValidationPipeline::validate' }, instead of whole files.
+ * @param target - File pattern (glob or path) to search for, or { symbol: string }
+ * @param reason - Reason for requesting this context
+ * @param max_files - Maximum number of files or definitions to return (default: 5)
+ * @returns Promise<{ files: { path: string, content: string, size: number, symbol?: { name: string, kind: string, start_line: number, end_line: number, first_line: number, last_line: number } }[], success: boolean, message: string }> - for symbols, content is the definition plus a few surrounding lines from first_line to last_line
+ */
+declare function requestContext(target: string | { symbol: string }, reason: string, max_files?: number): Promise<{ files: { path: string, content: string, size: number, symbol?: { name: string, kind: string, start_line: number, end_line: number, first_line: number, last_line: number } }[], success: boolean, message: string }>"
+    }
 
-        assert!(result.success);
-        assert!(result.files.len() >= 2);
-        Ok(())
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "anyOf": [
+                { "properties": { "pattern": { "type": "string" } }, "required": ["pattern"] },
+                { "properties": { "symbol": { "type": "string" } }, "required": ["symbol"] }
+            ],
+            "properties": {
+                "reason": { "type": "string" },
+                "max_files": { "type": "integer" }
+            }
+        })
     }
 
-    /// Tests context tracker deduplication and clearing functionality.
-    ///
-    /// # Panics
-    /// Panics if assertions fail during test execution.
-    #[tokio::test]
-    async fn test_context_tracker() {
-        let tracker = ContextTracker::new();
-
-        tracker.add_requested(PathBuf::from("file1.rs")).await;
-        tracker.add_requested(PathBuf::from("file2.rs")).await;
-        tracker.add_requested(PathBuf::from("file1.rs")).await; // Duplicate
-
-        let requested = tracker.get_requested().await;
-        assert_eq!(requested.len(), 2); // No duplicates
-
-        tracker.clear().await;
-        let requested_after_clear = tracker.get_requested().await;
-        assert_eq!(requested_after_clear.len(), 0);
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let args: ContextRequestArgs =
+            from_value(input.params).map_err(|err| ToolError::from_arguments(&err))?;
+
+        match &args.target {
+            ContextTarget::Symbol { symbol } => {
+                tracing::info!(
+                    "Context request: symbol='{symbol}', reason='{}'",
+                    args.reason
+                );
+                symbols::request_symbol(self, symbol, args.max_files).await
+            }
+            ContextTarget::Files { pattern } => {
+                tracing::info!(
+                    "Context request: pattern='{pattern}', reason='{}'",
+                    args.reason
+                );
+                self.request_files(pattern, args.max_files).await
+            }
+        }
     }
 }
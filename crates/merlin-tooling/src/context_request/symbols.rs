@@ -0,0 +1,128 @@
+//! Symbol requests, answered with a definition and a few surrounding lines.
+
+use serde_json::to_value;
+
+use super::{ContextFile, ContextRequestResult, ContextRequestTool, SymbolExcerpt};
+use crate::symbol_lookup::{GrepSymbolResolver, SymbolDefinition, SymbolName, SymbolResolver as _};
+use crate::{ToolError, ToolOutput, ToolResult};
+
+/// Lines of surrounding code included around a requested symbol's definition
+const SYMBOL_CONTEXT_LINES: usize = 3;
+
+/// Fetches the definitions of `symbol` with a few lines of surrounding code
+///
+/// # Errors
+/// Returns an error if the symbol name is empty or the result cannot be serialized
+pub(super) async fn request_symbol(
+    tool: &ContextRequestTool,
+    symbol: &str,
+    max_files: usize,
+) -> ToolResult<ToolOutput> {
+    let name = SymbolName::parse(symbol);
+    if name.name.is_empty() {
+        return Err(ToolError::invalid_argument(
+            "symbol",
+            format!("Invalid symbol name: '{symbol}'"),
+        ));
+    }
+    let key = name.to_string();
+
+    let (files, message) = if tool.tracker.has_requested_symbol(&key).await {
+        (
+            Vec::new(),
+            format!("Definition of {key} was already provided"),
+        )
+    } else {
+        let mut files = Vec::new();
+        for definition in resolve_definitions(tool, &name)
+            .await
+            .into_iter()
+            .take(max_files)
+        {
+            match symbol_excerpt(tool, &key, &definition).await {
+                Ok(file) => files.push(file),
+                Err(err) => {
+                    tracing::warn!("Failed to read {}: {}", definition.file.display(), err);
+                }
+            }
+        }
+        let message = if files.is_empty() {
+            format!("No definition found for symbol: {key}")
+        } else {
+            tool.tracker.add_requested_symbol(key.clone()).await;
+            format!("Found {} definitions of {key}", files.len())
+        };
+        (files, message)
+    };
+
+    let result = ContextRequestResult {
+        success: !files.is_empty(),
+        files,
+        message: message.clone(),
+    };
+    let data = to_value(result)?;
+    Ok(ToolOutput::success_with_data(message, data))
+}
+
+/// Definitions of `symbol`, searching the files if the resolver finds none
+async fn resolve_definitions(
+    tool: &ContextRequestTool,
+    symbol: &SymbolName,
+) -> Vec<SymbolDefinition> {
+    if let Some(resolver) = &tool.symbol_resolver {
+        match resolver.resolve_symbol(symbol).await {
+            Ok(definitions) if !definitions.is_empty() => return definitions,
+            Ok(_) => {}
+            Err(err) => tracing::warn!("Symbol resolver failed for {symbol}: {err}"),
+        }
+    }
+
+    GrepSymbolResolver::new(tool.project_root.clone())
+        .resolve_symbol(symbol)
+        .await
+        .unwrap_or_else(|err| {
+            tracing::warn!("Symbol search failed for {symbol}: {err}");
+            Vec::new()
+        })
+}
+
+/// Cuts a definition and its surrounding lines out of its file
+///
+/// # Errors
+/// Returns an error if the file cannot be read or is too large
+async fn symbol_excerpt(
+    tool: &ContextRequestTool,
+    name: &str,
+    definition: &SymbolDefinition,
+) -> ToolResult<ContextFile> {
+    let path = if definition.file.is_absolute() {
+        definition.file.clone()
+    } else {
+        tool.project_root.join(&definition.file)
+    };
+    let source = tool.read_file(&path).await?;
+    let lines: Vec<&str> = source.lines().collect();
+
+    let end_line = definition.end_line.clamp(1, lines.len().max(1));
+    let start_line = definition.start_line.clamp(1, end_line);
+    let first_line = start_line.saturating_sub(SYMBOL_CONTEXT_LINES).max(1);
+    let last_line = (end_line + SYMBOL_CONTEXT_LINES).min(lines.len());
+    let content = lines
+        .get(first_line - 1..last_line)
+        .unwrap_or_default()
+        .join("\n");
+
+    Ok(ContextFile {
+        path,
+        size: content.len(),
+        content,
+        symbol: Some(SymbolExcerpt {
+            name: name.to_owned(),
+            kind: definition.kind.clone(),
+            start_line,
+            end_line,
+            first_line,
+            last_line,
+        }),
+    })
+}
@@ -0,0 +1,278 @@
+//! Tests for the context request tool
+
+use super::*;
+use crate::symbol_lookup::{SymbolDefinition, SymbolName};
+use anyhow::Result;
+use tempfile::TempDir;
+use tokio::fs::{create_dir, write};
+
+/// Tests context request for an exact file match.
+///
+/// # Errors
+/// Returns an error if file creation or context request fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_context_request_exact_file() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let test_file = temp_dir.path().join("test.rs");
+
+    write(&test_file, "fn main() {}").await?;
+
+    let tool = ContextRequestTool::new(temp_dir.path().to_path_buf());
+
+    let input = ToolInput {
+        params: serde_json::json!({
+            "pattern": "test.rs",
+            "reason": "Testing exact file match"
+        }),
+    };
+
+    let output = tool.execute(input).await?;
+    assert!(output.success);
+
+    let data = output
+        .data
+        .ok_or_else(|| anyhow::anyhow!("No data in output"))?;
+    let result: ContextRequestResult = from_value(data)?;
+
+    assert!(result.success);
+    assert_eq!(result.files.len(), 1);
+    assert_eq!(result.files[0].content, "fn main() {}");
+    Ok(())
+}
+
+/// Tests context request using glob patterns to match multiple files.
+///
+/// # Errors
+/// Returns an error if file creation or context request fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_context_request_glob_pattern() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let src_dir = temp_dir.path().join("src");
+    create_dir(&src_dir).await?;
+
+    write(src_dir.join("lib.rs"), "pub fn foo() {}").await?;
+    write(src_dir.join("main.rs"), "fn main() {}").await?;
+
+    let tool = ContextRequestTool::new(temp_dir.path().to_path_buf());
+
+    let input = ToolInput {
+        params: serde_json::json!({
+            "pattern": "**/*.rs",
+            "reason": "Testing glob pattern",
+            "max_files": 10
+        }),
+    };
+
+    let output = tool.execute(input).await?;
+    assert!(output.success);
+
+    let data = output
+        .data
+        .ok_or_else(|| anyhow::anyhow!("No data in output"))?;
+    let result: ContextRequestResult = from_value(data)?;
+
+    assert!(result.success);
+    assert!(result.files.len() >= 2);
+    Ok(())
+}
+
+/// Tests that symbol requests return a method's definition with surrounding lines.
+///
+/// # Errors
+/// Returns an error if file creation or context request fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_context_request_symbol_method() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    write(
+        temp_dir.path().join("pipeline.rs"),
+        "use std::fmt;\n\npub struct Pipeline;\n\nimpl Pipeline {\n    /// Validates the input\n    pub fn validate(&self) -> bool {\n        true\n    }\n\n    pub fn name(&self) -> &str {\n        \"pipeline\"\n    }\n}\n",
+    )
+    .await?;
+    let tool = ContextRequestTool::new(temp_dir.path().to_path_buf());
+
+    let output = tool
+        .execute(ToolInput {
+            params: serde_json::json!({
+                "symbol": "Pipeline::validate",
+                "reason": "Testing method lookup"
+            }),
+        })
+        .await?;
+    let data = output
+        .data
+        .ok_or_else(|| anyhow::anyhow!("No data in output"))?;
+    let result: ContextRequestResult = from_value(data)?;
+
+    assert!(result.success);
+    assert_eq!(result.files.len(), 1);
+    assert_eq!(
+        result.files[0].symbol,
+        Some(SymbolExcerpt {
+            name: "Pipeline.validate".to_owned(),
+            kind: "Method".to_owned(),
+            start_line: 6,
+            end_line: 9,
+            first_line: 3,
+            last_line: 12,
+        })
+    );
+    assert!(result.files[0].content.starts_with("pub struct Pipeline;"));
+    assert!(result.files[0].content.ends_with("\"pipeline\""));
+    Ok(())
+}
+
+/// Tests that the resolver is used first and repeated symbol requests are deduplicated.
+///
+/// # Errors
+/// Returns an error if file creation or context request fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_context_request_symbol_trait_deduplicated() -> Result<()> {
+    struct FixedResolver;
+
+    #[async_trait]
+    impl SymbolResolver for FixedResolver {
+        async fn resolve_symbol(&self, symbol: &SymbolName) -> ToolResult<Vec<SymbolDefinition>> {
+            Ok((symbol.name == "Stage")
+                .then(|| SymbolDefinition {
+                    kind: "Trait".to_owned(),
+                    file: PathBuf::from("stage.ts"),
+                    start_line: 1,
+                    end_line: 3,
+                })
+                .into_iter()
+                .collect())
+        }
+    }
+
+    let temp_dir = TempDir::new()?;
+    write(
+        temp_dir.path().join("stage.ts"),
+        "interface Stage {\n  name(): string;\n}\n",
+    )
+    .await?;
+    let tool = ContextRequestTool::new(temp_dir.path().to_path_buf())
+        .with_symbol_resolver(Arc::new(FixedResolver));
+    let request = || ToolInput {
+        params: serde_json::json!({ "symbol": "Stage", "reason": "Testing traits" }),
+    };
+
+    let first: ContextRequestResult = from_value(
+        tool.execute(request())
+            .await?
+            .data
+            .ok_or_else(|| anyhow::anyhow!("No data in output"))?,
+    )?;
+    assert_eq!(first.files.len(), 1);
+    assert_eq!(first.files[0].path, temp_dir.path().join("stage.ts"));
+    assert_eq!(
+        first.files[0]
+            .symbol
+            .as_ref()
+            .map(|symbol| symbol.kind.as_str()),
+        Some("Trait")
+    );
+
+    let repeated: ContextRequestResult = from_value(
+        tool.execute(request())
+            .await?
+            .data
+            .ok_or_else(|| anyhow::anyhow!("No data in output"))?,
+    )?;
+    assert!(repeated.files.is_empty());
+    assert_eq!(repeated.message, "Definition of Stage was already provided");
+    assert!(tool.tracker().has_requested_symbol("Stage").await);
+    Ok(())
+}
+
+/// Tests that a missing symbol yields an unsuccessful result rather than an error.
+///
+/// # Errors
+/// Returns an error if file creation or context request fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_context_request_symbol_not_found() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    write(temp_dir.path().join("lib.rs"), "pub fn present() {}").await?;
+    let tool = ContextRequestTool::new(temp_dir.path().to_path_buf());
+
+    let output = tool
+        .execute(ToolInput {
+            params: serde_json::json!({ "symbol": "absent", "reason": "Testing" }),
+        })
+        .await?;
+    let data = output
+        .data
+        .ok_or_else(|| anyhow::anyhow!("No data in output"))?;
+    let result: ContextRequestResult = from_value(data)?;
+
+    assert!(!result.success);
+    assert_eq!(result.message, "No definition found for symbol: absent");
+    assert!(!tool.tracker().has_requested_symbol("absent").await);
+    Ok(())
+}
+
+/// Tests that malformed requests report invalid arguments naming the field.
+///
+/// # Errors
+/// Returns an error if the workspace cannot be created.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_context_request_invalid_arguments() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let tool = ContextRequestTool::new(temp_dir.path().to_path_buf());
+
+    for (params, field) in [
+        (
+            serde_json::json!({ "symbol": "::", "reason": "Testing" }),
+            Some("symbol"),
+        ),
+        (
+            serde_json::json!({ "pattern": "[", "reason": "Testing" }),
+            Some("pattern"),
+        ),
+        (serde_json::json!({ "reason": "Testing" }), None),
+    ] {
+        let result = tool.execute(ToolInput { params }).await;
+        assert!(
+            matches!(&result, Err(err) if err.code() == "invalid_arguments" && err.field() == field),
+            "expected invalid {field:?}, got {result:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Tests context tracker deduplication and clearing functionality.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_context_tracker() {
+    let tracker = ContextTracker::new();
+
+    tracker.add_requested(PathBuf::from("file1.rs")).await;
+    tracker.add_requested(PathBuf::from("file2.rs")).await;
+    tracker.add_requested(PathBuf::from("file1.rs")).await; // Duplicate
+
+    let requested = tracker.get_requested().await;
+    assert_eq!(requested.len(), 2); // No duplicates
+
+    tracker.clear().await;
+    let requested_after_clear = tracker.get_requested().await;
+    assert_eq!(requested_after_clear.len(), 0);
+}
@@ -3,16 +3,21 @@
 //! Provides safe file deletion for agents executing in the TypeScript runtime.
 
 use async_trait::async_trait;
-use serde_json::Value;
+use serde_json::{Value, json};
 use std::fs;
 use std::path::PathBuf;
 
-use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+use crate::{
+    FileChangeTracker, Tool, ToolError, ToolInput, ToolOutput, ToolResult, native_path,
+    resolve_existing,
+};
 
 /// Tool for deleting files from the filesystem.
 pub struct DeleteFileTool {
     /// Root directory to constrain file access (for sandboxing)
     root_dir: PathBuf,
+    /// Tracker notified of every file this tool changes
+    changes: Option<FileChangeTracker>,
 }
 
 impl DeleteFileTool {
@@ -23,39 +28,23 @@ impl DeleteFileTool {
     pub fn new(root_dir: impl Into<PathBuf>) -> Self {
         Self {
             root_dir: root_dir.into(),
+            changes: None,
         }
     }
 
+    /// Record changed files in `tracker`
+    #[must_use]
+    pub fn with_change_tracker(mut self, tracker: FileChangeTracker) -> Self {
+        self.changes = Some(tracker);
+        self
+    }
+
     /// Resolve a path relative to the root directory and validate it's within bounds.
     ///
     /// # Errors
     /// Returns error if path escapes the root directory
     fn resolve_path(&self, path: &str) -> ToolResult<PathBuf> {
-        let full_path = self.root_dir.join(path);
-
-        // Canonicalize both paths to prevent directory traversal attacks
-        let canonical_root = self
-            .root_dir
-            .canonicalize()
-            .map_err(|err| ToolError::InvalidInput(format!("Invalid root directory: {err}")))?;
-
-        if !full_path.exists() {
-            return Err(ToolError::InvalidInput(format!(
-                "File does not exist: {path}"
-            )));
-        }
-
-        let canonical_path = full_path
-            .canonicalize()
-            .map_err(|err| ToolError::InvalidInput(format!("Invalid path '{path}': {err}")))?;
-
-        if !canonical_path.starts_with(&canonical_root) {
-            return Err(ToolError::InvalidInput(format!(
-                "Path '{path}' is outside the allowed directory"
-            )));
-        }
-
-        Ok(canonical_path)
+        resolve_existing(&self.root_dir, path, "File")
     }
 }
 
@@ -74,6 +63,16 @@ impl Tool for DeleteFileTool {
 declare function deleteFile(path: string): Promise<void>;"
     }
 
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+            },
+            "required": ["path"]
+        })
+    }
+
     async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
         // Extract path parameter
         let path = input
@@ -81,7 +80,7 @@ declare function deleteFile(path: string): Promise<void>;"
             .as_str()
             .or_else(|| input.params.get("path").and_then(Value::as_str))
             .ok_or_else(|| {
-                ToolError::InvalidInput("deleteFile requires a 'path' parameter".to_owned())
+                ToolError::invalid_argument("path", "deleteFile requires a 'path' parameter")
             })?;
 
         // Resolve and validate path
@@ -89,16 +88,66 @@ declare function deleteFile(path: string): Promise<void>;"
 
         // Check if path is a directory
         if full_path.is_dir() {
-            return Err(ToolError::InvalidInput(format!(
-                "Cannot delete directory: {path}. Only files can be deleted."
-            )));
+            return Err(ToolError::invalid_argument(
+                "path",
+                format!("Cannot delete directory: {path}. Only files can be deleted."),
+            ));
         }
 
         // Delete the file
-        fs::remove_file(&full_path).map_err(|err| {
-            ToolError::ExecutionFailed(format!("Failed to delete file '{path}': {err}"))
-        })?;
+        fs::remove_file(&full_path)
+            .map_err(|err| ToolError::io(format!("Failed to delete file '{path}'"), &err))?;
+
+        if let Some(changes) = &self.changes {
+            changes.record(self.root_dir.join(native_path(path))).await;
+        }
 
         Ok(ToolOutput::success(format!("Deleted file: {path}")))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Tests that deleting removes the file and each invalid request reports its code.
+    ///
+    /// # Errors
+    /// Returns an error if file operations fail.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_delete_file_error_codes() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::create_dir(temp_dir.path().join("src"))?;
+        fs::write(temp_dir.path().join("old.txt"), "old")?;
+        let tool = DeleteFileTool::new(temp_dir.path());
+
+        let deleted = tool
+            .execute(ToolInput {
+                params: json!("old.txt"),
+            })
+            .await?;
+        assert!(deleted.success);
+        assert!(!temp_dir.path().join("old.txt").exists());
+
+        for (params, code) in [
+            (json!({}), "invalid_arguments"),
+            (json!("old.txt"), "not_found"),
+            (json!("src"), "invalid_arguments"),
+            (json!(".."), "sandbox_violation"),
+        ] {
+            let result = tool.execute(ToolInput { params }).await;
+            assert!(
+                matches!(&result, Err(err) if err.code() == code),
+                "expected {code}, got {result:?}"
+            );
+        }
+        assert!(temp_dir.path().join("src").is_dir());
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+//! Unified diffs between file versions.
+//!
+//! Lets agents show the user what changed, or check that an edit produced the
+//! expected change, without shelling out to `diff`. Either two in-memory
+//! versions of one file or two files on disk are compared.
+
+use std::fs;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_value, json, to_value};
+use similar::{ChangeTag, TextDiff};
+
+use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult, resolve_existing};
+
+/// Unchanged lines shown around each change when `context_lines` is not given
+const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Arguments for diffing
+///
+/// Either `after_content` (with `file_path`) or both `file_a` and `file_b` must be given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiffArgs {
+    /// Path labelling an in-memory diff, relative to the workspace root
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Old content (default: the current content of `file_path`)
+    #[serde(default)]
+    pub before_content: Option<String>,
+    /// New content
+    #[serde(default)]
+    pub after_content: Option<String>,
+    /// Old file on disk, relative to the workspace root
+    #[serde(default)]
+    pub file_a: Option<String>,
+    /// New file on disk, relative to the workspace root
+    #[serde(default)]
+    pub file_b: Option<String>,
+    /// Unchanged lines shown around each change (default: 3)
+    #[serde(default = "default_context_lines")]
+    pub context_lines: usize,
+}
+
+/// Default for [`DiffArgs::context_lines`]
+const fn default_context_lines() -> usize {
+    DEFAULT_CONTEXT_LINES
+}
+
+/// A unified diff and its statistics
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffSummary {
+    /// Unified diff, empty if the versions are identical
+    pub diff: String,
+    /// Number of lines only in the new version
+    pub lines_added: usize,
+    /// Number of lines only in the old version
+    pub lines_removed: usize,
+    /// Number of files that differ (0 or 1)
+    pub files_changed: usize,
+}
+
+impl DiffSummary {
+    /// Diffs two versions of a file, labelling them `old_label` and `new_label`
+    pub fn between(
+        old_label: &str,
+        new_label: &str,
+        before: &str,
+        after: &str,
+        context_lines: usize,
+    ) -> Self {
+        let text_diff = TextDiff::from_lines(before, after);
+        let mut lines_added = 0;
+        let mut lines_removed = 0;
+        for change in text_diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Insert => lines_added += 1,
+                ChangeTag::Delete => lines_removed += 1,
+                ChangeTag::Equal => {}
+            }
+        }
+        let changed = lines_added + lines_removed > 0;
+        let diff = if changed {
+            text_diff
+                .unified_diff()
+                .context_radius(context_lines)
+                .header(old_label, new_label)
+                .to_string()
+        } else {
+            String::new()
+        };
+        Self {
+            diff,
+            lines_added,
+            lines_removed,
+            files_changed: usize::from(changed),
+        }
+    }
+}
+
+/// Tool for showing the unified diff between two versions of a file.
+pub struct DiffTool {
+    /// Root directory to constrain file access (for sandboxing)
+    root_dir: PathBuf,
+}
+
+impl DiffTool {
+    /// Create a new `DiffTool` with the given root directory.
+    ///
+    /// All file paths will be resolved relative to this root directory.
+    #[must_use]
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+        }
+    }
+
+    /// Resolve a path relative to the root directory and validate it's within bounds.
+    ///
+    /// # Errors
+    /// Returns error if the file does not exist or the path escapes the root directory
+    fn resolve_path(&self, path: &str) -> ToolResult<PathBuf> {
+        resolve_existing(&self.root_dir, path, "File")
+    }
+
+    /// Reads a file within the root directory
+    ///
+    /// # Errors
+    /// Returns error if the path is invalid or the file cannot be read as text
+    fn read(&self, path: &str) -> ToolResult<String> {
+        let full_path = self.resolve_path(path)?;
+        if full_path.is_dir() {
+            return Err(ToolError::invalid_arguments(format!(
+                "Cannot diff directory: {path}"
+            )));
+        }
+        fs::read_to_string(&full_path)
+            .map_err(|err| ToolError::io(format!("Failed to read file '{path}'"), &err))
+    }
+
+    /// Computes the diff requested by `args`
+    ///
+    /// # Errors
+    /// Returns error if the arguments mix or lack both forms, or a file cannot be read
+    fn diff(&self, args: DiffArgs) -> ToolResult<DiffSummary> {
+        match (args.file_a, args.file_b, args.after_content) {
+            (Some(file_a), Some(file_b), None) => {
+                if args.file_path.is_some() || args.before_content.is_some() {
+                    return Err(ToolError::invalid_arguments(
+                        "diff takes either file_a and file_b or file_path with contents, not both",
+                    ));
+                }
+                let before = self.read(&file_a)?;
+                let after = self.read(&file_b)?;
+                Ok(DiffSummary::between(
+                    &format!("a/{file_a}"),
+                    &format!("b/{file_b}"),
+                    &before,
+                    &after,
+                    args.context_lines,
+                ))
+            }
+            (None, None, Some(after)) => {
+                let file_path = args.file_path.ok_or_else(|| {
+                    ToolError::invalid_argument(
+                        "file_path",
+                        "diff of contents requires a 'file_path'",
+                    )
+                })?;
+                let before = match args.before_content {
+                    Some(before) => before,
+                    None => self.read(&file_path)?,
+                };
+                Ok(DiffSummary::between(
+                    &format!("a/{file_path}"),
+                    &format!("b/{file_path}"),
+                    &before,
+                    &after,
+                    args.context_lines,
+                ))
+            }
+            _ => Err(ToolError::invalid_arguments(
+                "diff requires either 'file_a' and 'file_b', or 'file_path' and 'after_content'",
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for DiffTool {
+    fn name(&self) -> &'static str {
+        "diff"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Shows the unified diff between two versions of a file, or between two files.
+ * @param args - Either { file_path, before_content?, after_content } to diff contents (before_content
+ *   defaults to the file on disk), or { file_a, file_b } to diff two files relative to the workspace root.
+ *   context_lines sets the unchanged lines shown around each change (default 3)
+ * @returns The diff (empty if identical) with the number of added and removed lines and changed files
+ */
+declare function diff(args: { file_path?: string, before_content?: string, after_content?: string, file_a?: string, file_b?: string, context_lines?: number }): Promise<{ diff: string, lines_added: number, lines_removed: number, files_changed: number }>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file_path": { "type": ["string", "null"] },
+                "before_content": { "type": ["string", "null"] },
+                "after_content": { "type": ["string", "null"] },
+                "file_a": { "type": ["string", "null"] },
+                "file_b": { "type": ["string", "null"] },
+                "context_lines": { "type": ["integer", "null"] },
+            },
+            "required": []
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let args: DiffArgs =
+            from_value(input.params).map_err(|err| ToolError::from_arguments(&err))?;
+        let summary = self.diff(args)?;
+
+        let message = if summary.files_changed == 0 {
+            "No differences".to_owned()
+        } else {
+            format!(
+                "{} line(s) added, {} line(s) removed",
+                summary.lines_added, summary.lines_removed
+            )
+        };
+        Ok(ToolOutput::success_with_data(message, to_value(summary)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use serde_json::{Value, json};
+    use tempfile::TempDir;
+
+    /// Runs the tool in `root` and returns its summary
+    ///
+    /// # Errors
+    /// Returns an error if the tool fails or returns no summary.
+    async fn run(root: &TempDir, params: Value) -> Result<DiffSummary> {
+        let output = DiffTool::new(root.path())
+            .execute(ToolInput { params })
+            .await?;
+        Ok(from_value(output.data.unwrap_or(Value::Null))?)
+    }
+
+    /// Tests diffing in-memory contents with the default and a custom context.
+    ///
+    /// # Errors
+    /// Returns an error if the tool fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_diff_contents() -> Result<()> {
+        let root = TempDir::new()?;
+        let before = "one\ntwo\nthree\nfour\nfive\nsix\nseven\n";
+        let after = "one\ntwo\nthree\n4\nfive\nsix\nseven\neight\n";
+
+        let summary = run(
+            &root,
+            json!({ "file_path": "src/numbers.txt", "before_content": before, "after_content": after }),
+        )
+        .await?;
+        assert_eq!(
+            (
+                summary.lines_added,
+                summary.lines_removed,
+                summary.files_changed
+            ),
+            (2, 1, 1)
+        );
+        assert!(
+            summary
+                .diff
+                .starts_with("--- a/src/numbers.txt\n+++ b/src/numbers.txt\n")
+        );
+        assert!(summary.diff.contains("@@ -1,7 +1,8 @@\n one\n"));
+        assert!(summary.diff.contains("-four\n+4\n"));
+
+        let narrow = run(
+            &root,
+            json!({ "file_path": "src/numbers.txt", "before_content": before, "after_content": after, "context_lines": 0 }),
+        )
+        .await?;
+        assert!(
+            narrow
+                .diff
+                .contains("@@ -4 +4 @@\n-four\n+4\n@@ -7,0 +8 @@\n+eight\n")
+        );
+
+        let unchanged = run(
+            &root,
+            json!({ "file_path": "same.txt", "before_content": before, "after_content": before }),
+        )
+        .await?;
+        assert_eq!(unchanged.diff, "");
+        assert_eq!(unchanged.files_changed, 0);
+        Ok(())
+    }
+
+    /// Tests diffing files on disk and against the current content of a file.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be written or the tool fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_diff_files_on_disk() -> Result<()> {
+        let root = TempDir::new()?;
+        fs::write(root.path().join("old.rs"), "fn main() {}\n")?;
+        fs::write(root.path().join("new.rs"), "fn main() {\n    run();\n}\n")?;
+
+        let summary = run(&root, json!({ "file_a": "old.rs", "file_b": "new.rs" })).await?;
+        assert_eq!((summary.lines_added, summary.lines_removed), (3, 1));
+        assert!(summary.diff.starts_with("--- a/old.rs\n+++ b/new.rs\n"));
+
+        let proposed = run(
+            &root,
+            json!({ "file_path": "old.rs", "after_content": "fn main() { run(); }\n" }),
+        )
+        .await?;
+        assert!(
+            proposed
+                .diff
+                .contains("-fn main() {}\n+fn main() { run(); }\n")
+        );
+        Ok(())
+    }
+
+    /// Tests that incomplete, mixed and escaping arguments are rejected.
+    ///
+    /// # Errors
+    /// Returns an error if the fixture cannot be written.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_invalid_arguments() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let root = temp_dir.path().join("workspace");
+        fs::create_dir(&root)?;
+        fs::write(root.join("a.txt"), "a\n")?;
+        fs::write(temp_dir.path().join("outside.txt"), "secret\n")?;
+        let tool = DiffTool::new(&root);
+
+        for (params, code) in [
+            (json!({ "file_a": "a.txt" }), "invalid_arguments"),
+            (json!({ "after_content": "b\n" }), "invalid_arguments"),
+            (
+                json!({ "file_a": "a.txt", "file_b": "a.txt", "before_content": "b\n" }),
+                "invalid_arguments",
+            ),
+            (
+                json!({ "file_a": "a.txt", "file_b": "../outside.txt" }),
+                "sandbox_violation",
+            ),
+            (
+                json!({ "file_path": "missing.txt", "after_content": "b\n" }),
+                "not_found",
+            ),
+        ] {
+            let result = tool
+                .execute(ToolInput {
+                    params: params.clone(),
+                })
+                .await;
+            assert!(
+                matches!(&result, Err(err) if err.code() == code),
+                "{params} should be rejected with {code}, got {result:?}"
+            );
+        }
+        Ok(())
+    }
+}
@@ -4,11 +4,14 @@
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use serde_json::{Value, from_value};
+use serde_json::{Value, from_value, json};
 use std::fs;
 use std::path::PathBuf;
 
-use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+use crate::{
+    FileChangeTracker, Tool, ToolError, ToolInput, ToolOutput, ToolResult, native_path,
+    resolve_existing,
+};
 
 /// Arguments for file editing
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +31,8 @@ pub struct EditFileArgs {
 pub struct EditFileTool {
     /// Root directory to constrain file access (for sandboxing)
     root_dir: PathBuf,
+    /// Tracker notified of every file this tool changes
+    changes: Option<FileChangeTracker>,
 }
 
 impl EditFileTool {
@@ -38,39 +43,23 @@ impl EditFileTool {
     pub fn new(root_dir: impl Into<PathBuf>) -> Self {
         Self {
             root_dir: root_dir.into(),
+            changes: None,
         }
     }
 
+    /// Record changed files in `tracker`
+    #[must_use]
+    pub fn with_change_tracker(mut self, tracker: FileChangeTracker) -> Self {
+        self.changes = Some(tracker);
+        self
+    }
+
     /// Resolve a path relative to the root directory and validate it's within bounds.
     ///
     /// # Errors
     /// Returns error if path escapes the root directory or file doesn't exist
     fn resolve_path(&self, path: &str) -> ToolResult<PathBuf> {
-        let full_path = self.root_dir.join(path);
-
-        // Canonicalize both paths to prevent directory traversal attacks
-        let canonical_root = self
-            .root_dir
-            .canonicalize()
-            .map_err(|err| ToolError::InvalidInput(format!("Invalid root directory: {err}")))?;
-
-        if !full_path.exists() {
-            return Err(ToolError::InvalidInput(format!(
-                "File does not exist: {path}"
-            )));
-        }
-
-        let canonical_path = full_path
-            .canonicalize()
-            .map_err(|err| ToolError::InvalidInput(format!("Invalid path '{path}': {err}")))?;
-
-        if !canonical_path.starts_with(&canonical_root) {
-            return Err(ToolError::InvalidInput(format!(
-                "Path '{path}' is outside the allowed directory"
-            )));
-        }
-
-        Ok(canonical_path)
+        resolve_existing(&self.root_dir, path, "File")
     }
 }
 
@@ -91,62 +80,38 @@ impl Tool for EditFileTool {
 declare function editFile(path: string, old_string: string, new_string: string, options?: { replace_all?: boolean }): Promise<void>;"
     }
 
-    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
-        // Parse arguments from various input formats
-        let args: EditFileArgs = if let Some(arr) = input.params.as_array() {
-            // Handle positional arguments: [path, old_string, new_string, options?]
-            if arr.len() < 3 {
-                return Err(ToolError::InvalidInput(
-                    "editFile requires at least 3 arguments: path, old_string, new_string"
-                        .to_owned(),
-                ));
-            }
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "old_string": { "type": "string" },
+                "new_string": { "type": "string" },
+                "replace_all": { "type": ["boolean", "null"] },
+            },
+            "required": ["path", "old_string", "new_string"]
+        })
+    }
 
-            let path = arr[0].as_str().ok_or_else(|| {
-                ToolError::InvalidInput("First argument (path) must be a string".to_owned())
-            })?;
-            let old_string = arr[1].as_str().ok_or_else(|| {
-                ToolError::InvalidInput("Second argument (old_string) must be a string".to_owned())
-            })?;
-            let new_string = arr[2].as_str().ok_or_else(|| {
-                ToolError::InvalidInput("Third argument (new_string) must be a string".to_owned())
-            })?;
-
-            let replace_all = if arr.len() > 3 {
-                arr[3]
-                    .get("replace_all")
-                    .and_then(Value::as_bool)
-                    .unwrap_or(false)
-            } else {
-                false
-            };
-
-            EditFileArgs {
-                path: path.to_owned(),
-                old_string: old_string.to_owned(),
-                new_string: new_string.to_owned(),
-                replace_all,
-            }
-        } else {
-            // Handle object format
-            from_value(input.params)
-                .map_err(|err| ToolError::InvalidInput(format!("Invalid arguments: {err}")))?
-        };
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let args = parse_args(input.params)?;
 
         // Resolve and validate path
         let full_path = self.resolve_path(&args.path)?;
 
         // Read file contents
-        let content = fs::read_to_string(&full_path).map_err(|err| {
-            ToolError::ExecutionFailed(format!("Failed to read file '{}': {err}", args.path))
-        })?;
+        let content = fs::read_to_string(&full_path)
+            .map_err(|err| ToolError::io(format!("Failed to read file '{}'", args.path), &err))?;
 
         // Check if old_string exists
         if !content.contains(&args.old_string) {
-            return Err(ToolError::ExecutionFailed(format!(
-                "String '{}' not found in file '{}'",
-                args.old_string, args.path
-            )));
+            return Err(ToolError::invalid_argument(
+                "old_string",
+                format!(
+                    "String '{}' not found in file '{}'",
+                    args.old_string, args.path
+                ),
+            ));
         }
 
         // Perform replacement
@@ -156,7 +121,7 @@ declare function editFile(path: string, old_string: string, new_string: string,
             // Check for multiple occurrences
             let count = content.matches(&args.old_string).count();
             if count > 1 {
-                return Err(ToolError::ExecutionFailed(format!(
+                return Err(ToolError::Conflict(format!(
                     "String '{}' appears {} times in '{}'. Use replace_all: true to replace all occurrences",
                     args.old_string, count, args.path
                 )));
@@ -165,9 +130,14 @@ declare function editFile(path: string, old_string: string, new_string: string,
         };
 
         // Write back to file
-        fs::write(&full_path, new_content).map_err(|err| {
-            ToolError::ExecutionFailed(format!("Failed to write file '{}': {err}", args.path))
-        })?;
+        fs::write(&full_path, new_content)
+            .map_err(|err| ToolError::io(format!("Failed to write file '{}'", args.path), &err))?;
+
+        if let Some(changes) = &self.changes {
+            changes
+                .record(self.root_dir.join(native_path(&args.path)))
+                .await;
+        }
 
         let replacement_count = if args.replace_all {
             content.matches(&args.old_string).count()
@@ -182,6 +152,56 @@ declare function editFile(path: string, old_string: string, new_string: string,
     }
 }
 
+/// Parses positional `[path, old_string, new_string, options?]` or named arguments
+///
+/// # Errors
+/// Returns error if an argument is missing or has the wrong type
+fn parse_args(params: Value) -> ToolResult<EditFileArgs> {
+    if let Some(arr) = params.as_array() {
+        // Handle positional arguments: [path, old_string, new_string, options?]
+        if arr.len() < 3 {
+            return Err(ToolError::invalid_arguments(
+                "editFile requires at least 3 arguments: path, old_string, new_string",
+            ));
+        }
+
+        let path = arr[0].as_str().ok_or_else(|| {
+            ToolError::invalid_argument("path", "First argument (path) must be a string")
+        })?;
+        let old_string = arr[1].as_str().ok_or_else(|| {
+            ToolError::invalid_argument(
+                "old_string",
+                "Second argument (old_string) must be a string",
+            )
+        })?;
+        let new_string = arr[2].as_str().ok_or_else(|| {
+            ToolError::invalid_argument(
+                "new_string",
+                "Third argument (new_string) must be a string",
+            )
+        })?;
+
+        let replace_all = if arr.len() > 3 {
+            arr[3]
+                .get("replace_all")
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        } else {
+            false
+        };
+
+        Ok(EditFileArgs {
+            path: path.to_owned(),
+            old_string: old_string.to_owned(),
+            new_string: new_string.to_owned(),
+            replace_all,
+        })
+    } else {
+        // Handle object format
+        from_value(params).map_err(|err| ToolError::from_arguments(&err))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,8 +294,8 @@ mod tests {
 
         let result = tool.execute(input).await;
         assert!(
-            result.is_err(),
-            "Expected error for multiple matches without replace_all"
+            matches!(result, Err(ToolError::Conflict(_))),
+            "Expected conflict for multiple matches without replace_all, got {result:?}"
         );
 
         // Content should be unchanged
@@ -283,4 +303,53 @@ mod tests {
         assert_eq!(content, "foo bar foo baz");
         Ok(())
     }
+
+    /// Tests the error code of each way an edit can fail.
+    ///
+    /// # Errors
+    /// Returns an error if file operations fail.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_edit_file_error_codes() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::write(temp_dir.path().join("test.txt"), "foo bar")?;
+        let tool = EditFileTool::new(temp_dir.path());
+
+        for (params, code, field) in [
+            (
+                json!({ "path": "missing.txt", "old_string": "foo", "new_string": "FOO" }),
+                "not_found",
+                None,
+            ),
+            (
+                json!({ "path": "test.txt", "old_string": "qux", "new_string": "QUX" }),
+                "invalid_arguments",
+                Some("old_string"),
+            ),
+            (
+                json!({ "path": "test.txt", "new_string": "FOO" }),
+                "invalid_arguments",
+                Some("old_string"),
+            ),
+            (json!(["test.txt", "foo"]), "invalid_arguments", None),
+            (
+                json!({ "path": "..", "old_string": "foo", "new_string": "FOO" }),
+                "sandbox_violation",
+                None,
+            ),
+        ] {
+            let result = tool.execute(ToolInput { params }).await;
+            assert!(
+                matches!(&result, Err(err) if err.code() == code && err.field() == field),
+                "expected {code} ({field:?}), got {result:?}"
+            );
+        }
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("test.txt"))?,
+            "foo bar"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,88 @@
+//! Tracking of files changed by tools.
+//!
+//! The write, edit and delete tools record the files they touch so that the
+//! agent can refresh its language indexes once the agent code has finished,
+//! instead of re-indexing the whole workspace.
+
+use std::mem;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Files written, edited or deleted since the tracker was last drained
+#[derive(Debug, Default, Clone)]
+pub struct FileChangeTracker {
+    /// Changed files, in the order they were first changed
+    changed: Arc<Mutex<Vec<PathBuf>>>,
+}
+
+impl FileChangeTracker {
+    /// Create a new, empty tracker
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a changed file
+    pub async fn record(&self, path: PathBuf) {
+        let mut changed = self.changed.lock().await;
+        if !changed.contains(&path) {
+            changed.push(path);
+        }
+    }
+
+    /// Files recorded since the tracker was last drained, leaving them recorded
+    pub async fn changed(&self) -> Vec<PathBuf> {
+        self.changed.lock().await.clone()
+    }
+
+    /// Take all files recorded so far, leaving the tracker empty
+    pub async fn take(&self) -> Vec<PathBuf> {
+        mem::take(&mut *self.changed.lock().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeleteFileTool, EditFileTool, Tool as _, ToolInput, WriteFileTool};
+    use anyhow::Result;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Tests that write, edit and delete record each changed file once.
+    ///
+    /// # Errors
+    /// Returns an error if a tool fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_tools_record_changed_files() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let tracker = FileChangeTracker::new();
+        let write = WriteFileTool::new(temp_dir.path()).with_change_tracker(tracker.clone());
+        let edit = EditFileTool::new(temp_dir.path()).with_change_tracker(tracker.clone());
+        let delete = DeleteFileTool::new(temp_dir.path()).with_change_tracker(tracker.clone());
+
+        for path in ["a.py", "b.py"] {
+            let params = json!({ "path": path, "content": "x = 1\n" });
+            write.execute(ToolInput { params }).await?;
+        }
+        let params = json!({ "path": "a.py", "old_string": "1", "new_string": "2" });
+        edit.execute(ToolInput { params }).await?;
+        delete
+            .execute(ToolInput {
+                params: json!("b.py"),
+            })
+            .await?;
+
+        assert_eq!(tracker.changed().await.len(), 2);
+        assert_eq!(
+            tracker.take().await,
+            vec![temp_dir.path().join("a.py"), temp_dir.path().join("b.py")]
+        );
+        assert!(tracker.take().await.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! `listFiles` tool.
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult, resolve_existing};
+
+/// Tool for listing files in a directory.
+pub struct ListFilesTool {
+    /// Root directory to constrain file access (for sandboxing)
+    root_dir: PathBuf,
+}
+
+impl ListFilesTool {
+    /// Create a new `ListFilesTool` with the given root directory.
+    ///
+    /// All directory paths will be resolved relative to this root directory.
+    #[must_use]
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+        }
+    }
+
+    /// Resolve a path relative to the root directory and validate it's within bounds.
+    ///
+    /// # Errors
+    /// Returns error if path escapes the root directory
+    fn resolve_path(&self, path: &str) -> ToolResult<PathBuf> {
+        resolve_existing(&self.root_dir, path, "Directory")
+    }
+}
+
+#[async_trait]
+impl Tool for ListFilesTool {
+    fn name(&self) -> &'static str {
+        "listFiles"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        "/**\n * Lists all files in a directory.\n * @param path - Path to the directory relative to the workspace root (optional, defaults to \".\")\n * @returns Array of file names in the directory\n */\ndeclare function listFiles(path?: string): Promise<string[]>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": ["string", "null"] },
+            },
+            "required": []
+        })
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        // Extract path parameter (optional, defaults to ".")
+        let path = input
+            .params
+            .as_str()
+            .or_else(|| input.params.get("path").and_then(Value::as_str))
+            .unwrap_or(".");
+
+        // Resolve and validate path
+        let full_path = self.resolve_path(path)?;
+
+        // List directory contents
+        let entries = fs::read_dir(&full_path)
+            .map_err(|err| ToolError::io(format!("Failed to list directory '{path}'"), &err))?;
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let entry =
+                entry.map_err(|err| ToolError::io("Failed to read directory entry", &err))?;
+
+            let file_name = entry
+                .file_name()
+                .to_str()
+                .ok_or_else(|| ToolError::Io("Invalid UTF-8 in file name".to_owned()))?
+                .to_owned();
+
+            files.push(file_name);
+        }
+
+        files.sort();
+
+        Ok(ToolOutput::success_with_data(
+            format!("Found {} entries in {path}", files.len()),
+            json!(files),
+        ))
+    }
+}
@@ -0,0 +1,14 @@
+//! File operation tools for reading, writing, and listing files.
+//!
+//! These tools provide safe file system access for agents executing in the TypeScript runtime.
+
+mod list;
+mod read;
+mod write;
+
+pub use list::ListFilesTool;
+pub use read::ReadFileTool;
+pub use write::WriteFileTool;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,180 @@
+//! `readFile` tool, optionally limited to a line range.
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::fs::{self, File};
+use std::io::{BufRead as _, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult, resolve_existing};
+
+/// Tool for reading files from the filesystem.
+pub struct ReadFileTool {
+    /// Root directory to constrain file access (for sandboxing)
+    root_dir: PathBuf,
+}
+
+impl ReadFileTool {
+    /// Create a new `ReadFileTool` with the given root directory.
+    ///
+    /// All file paths will be resolved relative to this root directory.
+    #[must_use]
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+        }
+    }
+
+    /// Resolve a path relative to the root directory and validate it's within bounds.
+    ///
+    /// # Errors
+    /// Returns error if path escapes the root directory
+    fn resolve_path(&self, path: &str) -> ToolResult<PathBuf> {
+        resolve_existing(&self.root_dir, path, "File")
+    }
+}
+
+#[async_trait]
+impl Tool for ReadFileTool {
+    fn name(&self) -> &'static str {
+        "readFile"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Reads the contents of a file from the filesystem.
+ * Pass a line range to read only part of a large file, e.g. around a search match.
+ * @param path - Path to the file relative to the workspace root
+ * @param start_line - First line to read, 1-based (optional, defaults to the first line)
+ * @param end_line - Last line to read, inclusive (optional, defaults to the last line)
+ * @returns The contents of the file, or of the requested lines prefixed with
+ *          `// Lines start_line-end_line of path`
+ */
+declare function readFile(path: string, start_line?: number, end_line?: number): Promise<string>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "start_line": { "type": ["integer", "null"] },
+                "end_line": { "type": ["integer", "null"] },
+            },
+            "required": ["path"]
+        })
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        // Extract path parameter
+        let path = input
+            .params
+            .as_str()
+            .or_else(|| input.params.get("path").and_then(Value::as_str))
+            .ok_or_else(|| {
+                ToolError::invalid_argument("path", "readFile requires a 'path' parameter")
+            })?;
+
+        let start_line = line_param(&input.params, "start_line")?;
+        let end_line = line_param(&input.params, "end_line")?;
+
+        // Resolve and validate path
+        let full_path = self.resolve_path(path)?;
+
+        if start_line.is_some() || end_line.is_some() {
+            let content = read_line_range(
+                &full_path,
+                path,
+                start_line.unwrap_or(1),
+                end_line.unwrap_or(u64::MAX),
+            )?;
+            return Ok(ToolOutput::success_with_data(
+                format!("Read {} bytes from {path}", content.len()),
+                json!(content),
+            ));
+        }
+
+        // Read file contents
+        let content = fs::read_to_string(&full_path)
+            .map_err(|err| ToolError::io(format!("Failed to read file '{path}'"), &err))?;
+
+        Ok(ToolOutput::success_with_data(
+            format!("Read {} bytes from {path}", content.len()),
+            json!(content),
+        ))
+    }
+}
+
+/// Read an optional 1-based line number parameter
+///
+/// # Errors
+/// Returns error if the parameter is not a positive integer
+fn line_param(params: &Value, name: &str) -> ToolResult<Option<u64>> {
+    match params.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .filter(|line| *line > 0)
+            .map(Some)
+            .ok_or_else(|| {
+                ToolError::invalid_argument(
+                    name,
+                    format!("readFile '{name}' must be a positive line number, got {value}"),
+                )
+            }),
+    }
+}
+
+/// Read lines `start_line..=end_line` of a file without loading the rest of it,
+/// prefixed with the range actually read
+///
+/// # Errors
+/// Returns error if the range is empty, starts past the end of the file, or
+/// the file cannot be read
+fn read_line_range(
+    full_path: &Path,
+    path: &str,
+    start_line: u64,
+    end_line: u64,
+) -> ToolResult<String> {
+    if end_line < start_line {
+        return Err(ToolError::invalid_argument(
+            "end_line",
+            format!("readFile end_line {end_line} is before start_line {start_line}"),
+        ));
+    }
+    let read_error = |err| ToolError::io(format!("Failed to read file '{path}'"), &err);
+    let file = File::open(full_path).map_err(read_error)?;
+
+    let mut body = String::new();
+    let mut last_line = start_line - 1;
+    let mut line_number = 0;
+    for line in BufReader::new(file).lines() {
+        line_number += 1;
+        if line_number > end_line {
+            break;
+        }
+        let line = line.map_err(read_error)?;
+        if line_number >= start_line {
+            body.push_str(&line);
+            body.push('\n');
+            last_line = line_number;
+        }
+    }
+
+    if last_line < start_line {
+        return Err(ToolError::invalid_argument(
+            "start_line",
+            format!(
+                "readFile start_line {start_line} is past the end of {path} ({line_number} lines)"
+            ),
+        ));
+    }
+    Ok(format!(
+        "// Lines {start_line}-{last_line} of {path}\n{body}"
+    ))
+}
@@ -0,0 +1,272 @@
+//! Tests for the file operation tools
+
+use super::*;
+use crate::{Tool, ToolError, ToolInput};
+use anyhow::Result;
+use serde_json::{Value, json};
+use std::fs;
+use tempfile::TempDir;
+
+/// Tests successful file writing.
+///
+/// # Errors
+/// Returns an error if file operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_write_file_success() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let tool = WriteFileTool::new(temp_dir.path());
+    let input = ToolInput {
+        params: json!({
+            "path": "output.txt",
+            "content": "Test content"
+        }),
+    };
+
+    let result = tool.execute(input).await?;
+    assert!(result.success);
+
+    let written_content = fs::read_to_string(temp_dir.path().join("output.txt"))?;
+    assert_eq!(written_content, "Test content");
+    Ok(())
+}
+
+/// Tests that writing a file creates parent directories if they don't exist.
+///
+/// # Errors
+/// Returns an error if file operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_write_file_creates_directories() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let tool = WriteFileTool::new(temp_dir.path());
+    let input = ToolInput {
+        params: json!({
+            "path": "nested/dir/file.txt",
+            "content": "Nested content"
+        }),
+    };
+
+    let result = tool.execute(input).await?;
+    assert!(result.success);
+
+    let written_content = fs::read_to_string(temp_dir.path().join("nested/dir/file.txt"))?;
+    assert_eq!(written_content, "Nested content");
+    Ok(())
+}
+
+/// Tests listing files in a directory.
+///
+/// # Errors
+/// Returns an error if file operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_list_files_success() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("file1.txt"), "content1")?;
+    fs::write(temp_dir.path().join("file2.txt"), "content2")?;
+    fs::create_dir(temp_dir.path().join("subdir"))?;
+
+    let tool = ListFilesTool::new(temp_dir.path());
+    let input = ToolInput { params: json!(".") };
+
+    let result = tool.execute(input).await?;
+    assert!(result.success);
+
+    let files = result
+        .data
+        .ok_or_else(|| anyhow::anyhow!("Expected files data"))?;
+    let files_array = files
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Expected array"))?;
+    assert_eq!(files_array.len(), 3);
+    assert!(files_array.contains(&json!("file1.txt")));
+    assert!(files_array.contains(&json!("file2.txt")));
+    assert!(files_array.contains(&json!("subdir")));
+    Ok(())
+}
+
+/// Tests listing files in an empty directory.
+///
+/// # Errors
+/// Returns an error if file operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_list_files_empty_directory() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let tool = ListFilesTool::new(temp_dir.path());
+    let input = ToolInput { params: json!(".") };
+
+    let result = tool.execute(input).await?;
+    assert!(result.success);
+
+    let files = result
+        .data
+        .ok_or_else(|| anyhow::anyhow!("Expected files data"))?;
+    let files_array = files
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Expected array"))?;
+    assert_eq!(files_array.len(), 0);
+    Ok(())
+}
+
+/// Tests reading a line range of a file.
+///
+/// # Errors
+/// Returns an error if file operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_read_file_line_range() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    fs::write(temp_dir.path().join("lib.rs"), "one\ntwo\nthree\nfour\n")?;
+    let tool = ReadFileTool::new(temp_dir.path());
+
+    let middle = tool
+        .execute(ToolInput {
+            params: json!({ "path": "lib.rs", "start_line": 2, "end_line": 3 }),
+        })
+        .await?;
+    assert_eq!(
+        middle.data,
+        Some(json!("// Lines 2-3 of lib.rs\ntwo\nthree\n"))
+    );
+
+    let tail = tool
+        .execute(ToolInput {
+            params: json!({ "path": "lib.rs", "start_line": 3, "end_line": 100 }),
+        })
+        .await?;
+    assert_eq!(
+        tail.data,
+        Some(json!("// Lines 3-4 of lib.rs\nthree\nfour\n"))
+    );
+
+    let head = tool
+        .execute(ToolInput {
+            params: json!({ "path": "lib.rs", "end_line": 1 }),
+        })
+        .await?;
+    assert_eq!(head.data, Some(json!("// Lines 1-1 of lib.rs\none\n")));
+
+    for (params, field) in [
+        (json!({ "path": "lib.rs", "start_line": 5 }), "start_line"),
+        (
+            json!({ "path": "lib.rs", "start_line": 3, "end_line": 2 }),
+            "end_line",
+        ),
+        (json!({ "path": "lib.rs", "start_line": 0 }), "start_line"),
+    ] {
+        let result = tool.execute(ToolInput { params }).await;
+        assert!(
+            matches!(&result, Err(err) if err.field() == Some(field)),
+            "expected invalid {field}, got {result:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Tests path traversal attack prevention in file writing.
+///
+/// # Errors
+/// Returns an error if test setup fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_path_traversal_prevention_write() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let tool = WriteFileTool::new(temp_dir.path());
+    let input = ToolInput {
+        params: json!({
+            "path": "../../../tmp/malicious.txt",
+            "content": "bad"
+        }),
+    };
+
+    let result = tool.execute(input).await;
+    assert!(
+        matches!(result, Err(ToolError::SandboxViolation(_))),
+        "Expected error for path traversal, got {result:?}"
+    );
+    Ok(())
+}
+
+/// Tests that writing outside the root creates no directories there.
+///
+/// # Errors
+/// Returns an error if test setup fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_path_traversal_creates_no_directories() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path().join("workspace");
+    fs::create_dir(&root)?;
+    let tool = WriteFileTool::new(&root);
+    let input = ToolInput {
+        params: json!({
+            "path": "../escaped/nested/file.txt",
+            "content": "bad"
+        }),
+    };
+
+    let result = tool.execute(input).await;
+    assert!(
+        matches!(result, Err(ToolError::SandboxViolation(_))),
+        "Expected error for path traversal, got {result:?}"
+    );
+    assert!(!temp_dir.path().join("escaped").exists());
+    Ok(())
+}
+
+/// Tool, parameters, expected error code and expected field
+type ErrorCase<'tool> = (&'tool dyn Tool, Value, &'static str, Option<&'static str>);
+
+/// Tests the error code of each way reading, writing and listing can fail.
+///
+/// # Errors
+/// Returns an error if test setup fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_file_tool_error_codes() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let write = WriteFileTool::new(temp_dir.path());
+    let read = ReadFileTool::new(temp_dir.path());
+    let list = ListFilesTool::new(temp_dir.path());
+
+    let cases: [ErrorCase<'_>; 6] = [
+        (
+            &write,
+            json!({ "path": "a.txt" }),
+            "invalid_arguments",
+            Some("content"),
+        ),
+        (&write, json!("a.txt"), "invalid_arguments", None),
+        (&read, json!({}), "invalid_arguments", Some("path")),
+        (&read, json!("missing.txt"), "not_found", None),
+        (&read, json!(".."), "sandbox_violation", None),
+        (&list, json!("missing"), "not_found", None),
+    ];
+    for (tool, params, code, field) in cases {
+        let result = tool.execute(ToolInput { params }).await;
+        assert!(
+            matches!(&result, Err(err) if err.code() == code && err.field() == field),
+            "{} expected {code} ({field:?}), got {result:?}",
+            tool.name()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! `writeFile` tool.
+
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{
+    FileChangeTracker, Tool, ToolError, ToolInput, ToolOutput, ToolResult, native_path,
+    resolve_for_write,
+};
+
+/// Tool for writing files to the filesystem.
+pub struct WriteFileTool {
+    /// Root directory to constrain file access (for sandboxing)
+    root_dir: PathBuf,
+    /// Tracker notified of every file this tool changes
+    changes: Option<FileChangeTracker>,
+}
+
+impl WriteFileTool {
+    /// Create a new `WriteFileTool` with the given root directory.
+    ///
+    /// All file paths will be resolved relative to this root directory.
+    #[must_use]
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+            changes: None,
+        }
+    }
+
+    /// Record changed files in `tracker`
+    #[must_use]
+    pub fn with_change_tracker(mut self, tracker: FileChangeTracker) -> Self {
+        self.changes = Some(tracker);
+        self
+    }
+
+    /// Resolve a path relative to the root directory and validate it's within bounds.
+    ///
+    /// # Errors
+    /// Returns error if path escapes the root directory
+    fn resolve_path(&self, path: &str) -> ToolResult<PathBuf> {
+        resolve_for_write(&self.root_dir, path)
+    }
+}
+
+#[async_trait]
+impl Tool for WriteFileTool {
+    fn name(&self) -> &'static str {
+        "writeFile"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Writes content to a file in the filesystem.
+ * @param path - Path to the file relative to the workspace root
+ * @param content - Content to write to the file
+ */
+declare function writeFile(path: string, content: string): Promise<void>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": { "type": "string" },
+                "content": { "type": "string" },
+            },
+            "required": ["path", "content"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        // Extract parameters - support both object and positional arguments
+        let (path, content) = if let Some(obj) = input.params.as_object() {
+            let path = obj.get("path").and_then(Value::as_str).ok_or_else(|| {
+                ToolError::invalid_argument("path", "writeFile requires a 'path' parameter")
+            })?;
+            let content = obj.get("content").and_then(Value::as_str).ok_or_else(|| {
+                ToolError::invalid_argument("content", "writeFile requires a 'content' parameter")
+            })?;
+            (path, content)
+        } else {
+            return Err(ToolError::invalid_arguments(
+                "writeFile requires path and content parameters",
+            ));
+        };
+
+        // Resolve and validate path
+        let full_path = self.resolve_path(path)?;
+
+        tracing::info!(
+            "WriteFileTool: writing {} bytes to {:?} (resolved from '{}')",
+            content.len(),
+            full_path,
+            path
+        );
+
+        // Write file contents
+        fs::write(&full_path, content)
+            .map_err(|err| ToolError::io(format!("Failed to write file '{path}'"), &err))?;
+
+        if let Some(changes) = &self.changes {
+            changes.record(self.root_dir.join(native_path(path))).await;
+        }
+
+        tracing::info!("WriteFileTool: successfully wrote file {:?}", full_path);
+
+        Ok(ToolOutput::success(format!(
+            "Wrote {} bytes to {path}",
+            content.len()
+        )))
+    }
+}
@@ -0,0 +1,123 @@
+//! Search criteria and file previews for [`FindFilesTool`](super::FindFilesTool).
+
+use std::fs::{File, Metadata};
+use std::io::Read as _;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use glob::{MatchOptions, Pattern};
+
+use super::FindFilesArgs;
+use crate::{ToolError, ToolResult};
+
+/// Bytes read from the start of a file to build its preview
+const PREVIEW_BYTES: usize = 4096;
+
+/// Longest preview returned, in characters
+const PREVIEW_CHARS: usize = 120;
+
+/// Validated search criteria
+pub(super) struct FileFilter {
+    /// Compiled glob
+    pattern: Pattern,
+    /// Lower bound on the modification time
+    modified_after: Option<DateTime<Utc>>,
+    /// Lower bound on the size
+    min_size_bytes: Option<u64>,
+    /// Upper bound on the size
+    max_size_bytes: Option<u64>,
+}
+
+impl FileFilter {
+    /// Validates the search arguments
+    ///
+    /// # Errors
+    /// Returns an error if the pattern or timestamp is malformed or the bounds are inconsistent
+    pub(super) fn new(args: &FindFilesArgs) -> ToolResult<Self> {
+        let pattern = Pattern::new(args.pattern.trim_start_matches("./")).map_err(|err| {
+            ToolError::invalid_argument(
+                "pattern",
+                format!("Invalid glob '{}': {err}", args.pattern),
+            )
+        })?;
+        let modified_after = args
+            .modified_after
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?;
+        if let (Some(min), Some(max)) = (args.min_size_bytes, args.max_size_bytes)
+            && min > max
+        {
+            return Err(ToolError::invalid_argument(
+                "min_size_bytes",
+                format!("min_size_bytes ({min}) is larger than max_size_bytes ({max})"),
+            ));
+        }
+        Ok(Self {
+            pattern,
+            modified_after,
+            min_size_bytes: args.min_size_bytes,
+            max_size_bytes: args.max_size_bytes,
+        })
+    }
+
+    /// Returns true if a file at `relative_path` with `metadata` matches
+    pub(super) fn matches(&self, relative_path: &Path, metadata: &Metadata) -> bool {
+        // `*` stays within one directory, so `*.rs` only matches the search root
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::default()
+        };
+        if !self.pattern.matches_path_with(relative_path, options) {
+            return false;
+        }
+        let size = metadata.len();
+        if self.min_size_bytes.is_some_and(|min| size < min)
+            || self.max_size_bytes.is_some_and(|max| size > max)
+        {
+            return false;
+        }
+        self.modified_after.is_none_or(|after| {
+            metadata
+                .modified()
+                .is_ok_and(|modified| DateTime::<Utc>::from(modified) > after)
+        })
+    }
+}
+
+/// Parses an RFC 3339 timestamp, or a date taken as midnight UTC
+///
+/// # Errors
+/// Returns an error if `value` is neither
+fn parse_timestamp(value: &str) -> ToolResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+        })
+        .map_err(|_| {
+            ToolError::invalid_argument(
+                "modified_after",
+                format!(
+                    "Invalid modified_after '{value}': expected an RFC 3339 timestamp or YYYY-MM-DD"
+                ),
+            )
+        })
+}
+
+/// Returns the first non-empty line of a file, or `None` if it is binary or unreadable
+pub(super) fn preview(path: &Path) -> Option<String> {
+    let mut head = Vec::with_capacity(PREVIEW_BYTES);
+    File::open(path)
+        .ok()?
+        .take(PREVIEW_BYTES as u64)
+        .read_to_end(&mut head)
+        .ok()?;
+    if head.contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&head);
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    Some(line.chars().take(PREVIEW_CHARS).collect())
+}
@@ -0,0 +1,244 @@
+//! Recursive file search by glob pattern and file metadata.
+//!
+//! Gives agents a structured, sandboxed alternative to running `find` through
+//! `BashTool`. The project is walked the way the context indexer walks it:
+//! hidden files and anything excluded by `.gitignore` or `.merlinignore` are
+//! skipped.
+
+mod filter;
+#[cfg(test)]
+mod tests;
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_value, json, to_value};
+use tokio::task::spawn_blocking;
+
+use filter::{FileFilter, preview};
+
+use crate::{
+    Tool, ToolError, ToolInput, ToolOutput, ToolResult, canonical_root, display_path, join_error,
+    resolve_existing,
+};
+
+/// Project-specific ignore file, using `.gitignore` syntax
+pub const IGNORE_FILE: &str = ".merlinignore";
+
+/// Number of results returned when `max_results` is not given
+const DEFAULT_MAX_RESULTS: usize = 100;
+
+/// Arguments for finding files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindFilesArgs {
+    /// Glob matched against paths relative to `project_root`, like `**/*.rs`
+    pub pattern: String,
+    /// Directory to search, relative to the workspace root (default: the workspace root)
+    #[serde(default)]
+    pub project_root: Option<String>,
+    /// Only files modified after this RFC 3339 timestamp or `YYYY-MM-DD` date
+    #[serde(default)]
+    pub modified_after: Option<String>,
+    /// Only files of at least this many bytes
+    #[serde(default)]
+    pub min_size_bytes: Option<u64>,
+    /// Only files of at most this many bytes
+    #[serde(default)]
+    pub max_size_bytes: Option<u64>,
+    /// Maximum number of files returned (default: 100)
+    #[serde(default = "default_max_results")]
+    pub max_results: usize,
+}
+
+/// Default for [`FindFilesArgs::max_results`]
+const fn default_max_results() -> usize {
+    DEFAULT_MAX_RESULTS
+}
+
+/// A file matching the search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FoundFile {
+    /// Path relative to the workspace root, with `/` separators
+    pub path: String,
+    /// Size in bytes
+    pub size_bytes: u64,
+    /// Last modification time as an RFC 3339 timestamp, if the platform reports it
+    pub modified: Option<String>,
+    /// First non-empty line of the file, or `None` for binary files
+    pub preview: Option<String>,
+}
+
+/// Tool for finding files by glob pattern, size and modification time.
+pub struct FindFilesTool {
+    /// Root directory to constrain file access (for sandboxing)
+    root_dir: PathBuf,
+}
+
+impl FindFilesTool {
+    /// Create a new `FindFilesTool` with the given root directory.
+    ///
+    /// Searches are confined to this directory and results are relative to it.
+    #[must_use]
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+        }
+    }
+
+    /// Resolve the search directory and validate it's within the root directory.
+    ///
+    /// # Errors
+    /// Returns error if the directory does not exist or escapes the root directory
+    fn resolve_search_root(&self, project_root: Option<&str>) -> ToolResult<(PathBuf, PathBuf)> {
+        let canonical_root = canonical_root(&self.root_dir)?;
+        let Some(path) = project_root.filter(|path| !path.is_empty() && *path != ".") else {
+            return Ok((canonical_root.clone(), canonical_root));
+        };
+
+        let canonical_path = resolve_existing(&self.root_dir, path, "Directory")?;
+        if !canonical_path.is_dir() {
+            return Err(ToolError::invalid_argument(
+                "path",
+                format!("Not a directory: {path}"),
+            ));
+        }
+        Ok((canonical_root, canonical_path))
+    }
+
+    /// Parses the input: a pattern string or an object of [`FindFilesArgs`]
+    ///
+    /// # Errors
+    /// Returns error if the input has neither form
+    fn parse_args(params: Value) -> ToolResult<FindFilesArgs> {
+        if let Value::String(pattern) = params {
+            return Ok(FindFilesArgs {
+                pattern,
+                project_root: None,
+                modified_after: None,
+                min_size_bytes: None,
+                max_size_bytes: None,
+                max_results: DEFAULT_MAX_RESULTS,
+            });
+        }
+        from_value(params).map_err(|err| ToolError::from_arguments(&err))
+    }
+}
+
+/// Walks `search_root` and collects up to `max_results` matching files
+///
+/// Returns the files, sorted by path, and whether more files matched.
+fn find_files(
+    workspace_root: &Path,
+    search_root: &Path,
+    filter: &FileFilter,
+    max_results: usize,
+) -> (Vec<FoundFile>, bool) {
+    let walker = WalkBuilder::new(search_root)
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(false)
+        .git_exclude(false)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .sort_by_file_name(Ord::cmp)
+        .build();
+
+    let mut found = Vec::new();
+    for entry in walker.filter_map(Result::ok) {
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(relative) = path.strip_prefix(search_root) else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !filter.matches(relative, &metadata) {
+            continue;
+        }
+        if found.len() == max_results {
+            return (found, true);
+        }
+        let workspace_path = path.strip_prefix(workspace_root).unwrap_or(path);
+        found.push(FoundFile {
+            path: display_path(workspace_path),
+            size_bytes: metadata.len(),
+            modified: metadata
+                .modified()
+                .ok()
+                .map(|modified| DateTime::<Utc>::from(modified).to_rfc3339()),
+            preview: preview(path),
+        });
+    }
+    (found, false)
+}
+
+#[async_trait]
+impl Tool for FindFilesTool {
+    fn name(&self) -> &'static str {
+        "findFiles"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Recursively finds files matching a glob pattern, skipping hidden, .gitignore'd and .merlinignore'd files.
+ * @param pattern - Glob relative to the search directory, e.g. '**/*.rs' or 'src/*.ts'
+ * @param options - Optional filters: project_root (directory to search, relative to the workspace root),
+ *   modified_after (RFC 3339 timestamp or YYYY-MM-DD), min_size_bytes, max_size_bytes, max_results (default 100)
+ * @returns Matching files sorted by path, with paths relative to the workspace root
+ */
+declare function findFiles(pattern: string, options?: { project_root?: string, modified_after?: string, min_size_bytes?: number, max_size_bytes?: number, max_results?: number }): Promise<{ path: string, size_bytes: number, modified: string | null, preview: string | null }[]>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "pattern": { "type": "string" },
+                "project_root": { "type": ["string", "null"] },
+                "modified_after": { "type": ["string", "null"] },
+                "min_size_bytes": { "type": ["integer", "null"] },
+                "max_size_bytes": { "type": ["integer", "null"] },
+                "max_results": { "type": ["integer", "null"] },
+            },
+            "required": ["pattern"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let args = Self::parse_args(input.params)?;
+        if args.max_results == 0 {
+            return Err(ToolError::invalid_argument(
+                "max_results",
+                "findFiles requires max_results of at least 1",
+            ));
+        }
+        let filter = FileFilter::new(&args)?;
+        let (workspace_root, search_root) =
+            self.resolve_search_root(args.project_root.as_deref())?;
+
+        let max_results = args.max_results;
+        let (files, truncated) =
+            spawn_blocking(move || find_files(&workspace_root, &search_root, &filter, max_results))
+                .await
+                .map_err(|err| join_error("findFiles", err))?;
+
+        let message = if truncated {
+            format!(
+                "Found more than {max_results} files matching '{}', showing the first {max_results}",
+                args.pattern
+            )
+        } else {
+            format!("Found {} files matching '{}'", files.len(), args.pattern)
+        };
+        Ok(ToolOutput::success_with_data(message, to_value(files)?))
+    }
+}
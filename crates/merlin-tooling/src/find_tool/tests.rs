@@ -0,0 +1,175 @@
+//! Tests for the file finding tool
+
+use super::*;
+use anyhow::{Result, anyhow};
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+/// Creates a project with Rust sources, a text file and ignored files
+///
+/// # Errors
+/// Returns an error if the files cannot be written.
+fn project() -> Result<TempDir> {
+    let temp_dir = TempDir::new()?;
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src/nested"))?;
+    fs::create_dir_all(root.join("generated"))?;
+    fs::write(root.join("build.rs"), "fn main() {}\n")?;
+    fs::write(
+        root.join("src/lib.rs"),
+        "\n//! Library root\npub mod nested;\n",
+    )?;
+    fs::write(root.join("src/nested/mod.rs"), "pub fn helper() {}\n")?;
+    fs::write(root.join("src/notes.txt"), "x".repeat(2048))?;
+    fs::write(root.join("generated/out.rs"), "// generated\n")?;
+    fs::write(root.join(".hidden.rs"), "fn hidden() {}\n")?;
+    fs::write(root.join(IGNORE_FILE), "generated/\n")?;
+    Ok(temp_dir)
+}
+
+/// Runs the tool and returns the matching paths
+///
+/// # Errors
+/// Returns an error if the tool fails or returns no file list.
+async fn find_paths(root: &Path, params: Value) -> Result<Vec<String>> {
+    let output = FindFilesTool::new(root)
+        .execute(ToolInput { params })
+        .await?;
+    let files: Vec<FoundFile> =
+        from_value(output.data.ok_or_else(|| anyhow!("Expected file list"))?)?;
+    Ok(files.into_iter().map(|file| file.path).collect())
+}
+
+/// Tests recursive and root-only globs, skipping ignored and hidden files.
+///
+/// # Errors
+/// Returns an error if file operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_find_files_by_glob() -> Result<()> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+
+    assert_eq!(
+        find_paths(root, json!("**/*.rs")).await?,
+        ["build.rs", "src/lib.rs", "src/nested/mod.rs"]
+    );
+    assert_eq!(find_paths(root, json!("*.rs")).await?, ["build.rs"]);
+    assert_eq!(
+        find_paths(root, json!({ "pattern": "*.rs", "project_root": "src" })).await?,
+        ["src/lib.rs"]
+    );
+    assert_eq!(
+        find_paths(root, json!({ "pattern": "**/*.rs", "max_results": 1 })).await?,
+        ["build.rs"]
+    );
+
+    let output = FindFilesTool::new(root)
+        .execute(ToolInput {
+            params: json!("src/lib.rs"),
+        })
+        .await?;
+    let files: Vec<FoundFile> =
+        from_value(output.data.ok_or_else(|| anyhow!("Expected file list"))?)?;
+    let lib = files
+        .first()
+        .ok_or_else(|| anyhow!("src/lib.rs not found"))?;
+    assert_eq!(lib.preview.as_deref(), Some("//! Library root"));
+    assert_eq!(lib.size_bytes, 34);
+    assert!(lib.modified.is_some());
+    Ok(())
+}
+
+/// Tests size and modification time filters.
+///
+/// # Errors
+/// Returns an error if file operations fail.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_find_files_by_metadata() -> Result<()> {
+    let temp_dir = project()?;
+    let root = temp_dir.path();
+
+    assert_eq!(
+        find_paths(
+            root,
+            json!({ "pattern": "src/**/*", "min_size_bytes": 1024 })
+        )
+        .await?,
+        ["src/notes.txt"]
+    );
+    assert_eq!(
+        find_paths(root, json!({ "pattern": "src/**/*", "max_size_bytes": 20 })).await?,
+        ["src/nested/mod.rs"]
+    );
+    assert_eq!(
+        find_paths(
+            root,
+            json!({ "pattern": "**/*.rs", "modified_after": "2000-01-01" })
+        )
+        .await?
+        .len(),
+        3
+    );
+    assert!(
+        find_paths(
+            root,
+            json!({ "pattern": "**/*.rs", "modified_after": "2999-01-01T00:00:00Z" })
+        )
+        .await?
+        .is_empty()
+    );
+    Ok(())
+}
+
+/// Tests that invalid arguments and escaping directories are rejected.
+///
+/// # Errors
+/// Returns an error if test setup fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_find_files_invalid_input() -> Result<()> {
+    let temp_dir = project()?;
+    let tool = FindFilesTool::new(temp_dir.path().join("src"));
+    for (params, code) in [
+        (
+            json!({ "pattern": "**/*.rs", "project_root": ".." }),
+            "sandbox_violation",
+        ),
+        (
+            json!({ "pattern": "**/*.rs", "project_root": "missing" }),
+            "not_found",
+        ),
+        (
+            json!({ "pattern": "[", "project_root": "." }),
+            "invalid_arguments",
+        ),
+        (
+            json!({ "pattern": "*", "modified_after": "yesterday" }),
+            "invalid_arguments",
+        ),
+        (
+            json!({ "pattern": "*", "min_size_bytes": 10, "max_size_bytes": 1 }),
+            "invalid_arguments",
+        ),
+        (
+            json!({ "pattern": "*", "max_results": 0 }),
+            "invalid_arguments",
+        ),
+        (json!(42), "invalid_arguments"),
+    ] {
+        let result = tool.execute(ToolInput { params }).await;
+        assert!(
+            matches!(&result, Err(err) if err.code() == code),
+            "expected {code}, got {result:?}"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,215 @@
+//! Git subcommands run by [`GitTool`].
+
+use std::path::Path;
+use std::process::Command;
+
+use super::porcelain::{parse_name_status, parse_numstat, parse_status};
+use super::{GitArgs, GitBranch, GitCommit, GitDiff, GitStatus, GitTool, TOOL_NAME};
+use crate::{ToolError, ToolResult};
+
+/// Current branch and changed files
+///
+/// # Errors
+/// Returns error if git fails, e.g. outside of a repository
+pub(super) fn status(tool: &GitTool) -> ToolResult<GitStatus> {
+    let output = tool.git(&[
+        "status",
+        "--porcelain=v1",
+        "--branch",
+        "--untracked-files=all",
+        "-z",
+    ])?;
+    Ok(parse_status(&output))
+}
+
+/// Unstaged changes, or the staged ones if `staged`, limited to `paths` if any
+///
+/// # Errors
+/// Returns error if git fails
+pub(super) fn diff(tool: &GitTool, staged: bool, paths: &[String]) -> ToolResult<GitDiff> {
+    let mut args = vec!["diff"];
+    if staged {
+        args.push("--cached");
+    }
+    let mut numstat_args = args.clone();
+    numstat_args.extend(["--numstat", "--"]);
+    numstat_args.extend(paths.iter().map(String::as_str));
+    args.push("--");
+    args.extend(paths.iter().map(String::as_str));
+
+    let files = parse_numstat(&tool.git(&numstat_args)?);
+    Ok(GitDiff {
+        diff: tool.git(&args)?,
+        lines_added: files.iter().filter_map(|file| file.lines_added).sum(),
+        lines_removed: files.iter().filter_map(|file| file.lines_removed).sum(),
+        files,
+    })
+}
+
+/// Stages `paths`, returning the status afterwards
+///
+/// # Errors
+/// Returns error if no path is given or git fails
+pub(super) fn add(tool: &GitTool, paths: &[String]) -> ToolResult<GitStatus> {
+    if paths.is_empty() {
+        return Err(ToolError::invalid_argument(
+            "paths",
+            "git add requires at least one path",
+        ));
+    }
+    let mut args = vec!["add", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    tool.git(&args)?;
+    status(tool)
+}
+
+/// Commits the staged changes with `message`
+///
+/// # Errors
+/// Returns error if the message is empty, nothing is staged or git fails
+pub(super) fn commit(tool: &GitTool, message: &str) -> ToolResult<GitCommit> {
+    if message.trim().is_empty() {
+        return Err(ToolError::invalid_argument(
+            "message",
+            "git commit requires a message",
+        ));
+    }
+    tool.git(&["commit", "--quiet", "--message", message])?;
+
+    let sha = tool.git(&["rev-parse", "HEAD"])?.trim().to_owned();
+    let files = tool.git(&[
+        "diff-tree",
+        "--root",
+        "--no-commit-id",
+        "--name-status",
+        "-r",
+        "-z",
+        "HEAD",
+    ])?;
+    Ok(GitCommit {
+        sha,
+        branch: current_branch(tool),
+        message: message.to_owned(),
+        files: parse_name_status(&files),
+    })
+}
+
+/// Creates branch `name` at the current commit and switches to it
+///
+/// # Errors
+/// Returns error if the name is not a valid branch name, the branch exists or git fails
+pub(super) fn create_branch(tool: &GitTool, name: &str) -> ToolResult<GitBranch> {
+    if tool.git(&["check-ref-format", "--branch", name]).is_err() {
+        return Err(ToolError::invalid_argument(
+            "name",
+            format!("'{name}' is not a valid branch name"),
+        ));
+    }
+    let branch_ref = format!("refs/heads/{name}");
+    if tool
+        .git(&["rev-parse", "--verify", "--quiet", &branch_ref])
+        .is_ok()
+    {
+        return Err(ToolError::Conflict(format!(
+            "Branch '{name}' already exists"
+        )));
+    }
+    tool.git(&["switch", "--quiet", "--create", name])?;
+    Ok(GitBranch {
+        name: name.to_owned(),
+        commit: tool
+            .git(&["rev-parse", "--verify", "--quiet", "HEAD"])
+            .ok()
+            .map(|sha| sha.trim().to_owned()),
+    })
+}
+
+/// Commits every change in the working tree on a new branch `branch`
+///
+/// Returns `None` without creating the branch if there is nothing to commit.
+///
+/// # Errors
+/// Returns error if the branch cannot be created or git fails
+pub(super) fn commit_all_on_branch(
+    tool: &GitTool,
+    branch: &str,
+    message: &str,
+) -> ToolResult<Option<GitCommit>> {
+    if status(tool)?.is_clean() {
+        return Ok(None);
+    }
+    create_branch(tool, branch)?;
+    tool.git(&["add", "--all"])?;
+    commit(tool, message).map(Some)
+}
+
+/// Name of the checked out branch, `None` when `HEAD` is detached
+fn current_branch(tool: &GitTool) -> Option<String> {
+    // Fails when HEAD is detached
+    tool.git(&["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .ok()
+        .map(|name| name.trim().to_owned())
+}
+
+/// Runs a destructive operation, returning git's output
+///
+/// # Errors
+/// Returns error if git fails
+pub(super) fn run_destructive(tool: &GitTool, args: &GitArgs) -> ToolResult<String> {
+    match args {
+        GitArgs::Push { remote, branch } => {
+            let mut git_args = vec!["push"];
+            git_args.extend(remote.as_deref());
+            git_args.extend(branch.as_deref());
+            tool.git(&git_args)
+        }
+        GitArgs::ResetHard { target } => {
+            let mut git_args = vec!["reset", "--hard"];
+            git_args.extend(target.as_deref());
+            tool.git(&git_args)
+        }
+        GitArgs::Clean => tool.git(&["clean", "-d", "--force"]),
+        GitArgs::Status
+        | GitArgs::Diff { .. }
+        | GitArgs::Add { .. }
+        | GitArgs::Commit { .. }
+        | GitArgs::CreateBranch { .. } => Err(ToolError::invalid_arguments(format!(
+            "git {} is not a destructive operation",
+            args.command_name()
+        ))),
+    }
+}
+
+/// Runs git with `args` in `root`, returning its standard output
+///
+/// # Errors
+/// Returns error if git cannot be started or exits unsuccessfully
+pub(super) fn run_git(root: &Path, args: &[&str]) -> ToolResult<String> {
+    tracing::debug!("Running git {}", args.join(" "));
+    let output = Command::new("git")
+        .args(["-c", "core.quotepath=false"])
+        .args(args)
+        .current_dir(root)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|err| ToolError::CommandFailed {
+            tool_name: TOOL_NAME.to_owned(),
+            exit_code: None,
+            stderr: format!("Failed to run git (is it available in PATH?): {err}"),
+        })?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(ToolError::CommandFailed {
+            tool_name: TOOL_NAME.to_owned(),
+            exit_code: output.status.code(),
+            stderr: format!(
+                "git {}: {}",
+                args.first().copied().unwrap_or_default(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        })
+    }
+}
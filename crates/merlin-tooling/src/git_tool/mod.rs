@@ -0,0 +1,453 @@
+//! Git operations on behalf of agents.
+//!
+//! Exposes the subset of git an agent needs to record its work: status,
+//! diffs, staging specific paths, committing and creating branches. Commands
+//! run the git CLI in the workspace and its porcelain output is parsed into
+//! structured results, so the commit an agent made can be checked and shown
+//! without re-reading git output. Operations that can lose work (push, hard
+//! reset, clean) are rejected unless destructive operations were enabled.
+
+mod commands;
+mod porcelain;
+#[cfg(test)]
+mod tests;
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_value, json, to_value};
+use tokio::task::spawn_blocking;
+
+use crate::panic_guard::join_error;
+use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+
+/// Name of the tool, also used in its errors
+const TOOL_NAME: &str = "git";
+
+/// A git operation and its arguments
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum GitArgs {
+    /// Current branch and changed files
+    Status,
+    /// Changes not yet staged, or the staged ones
+    Diff {
+        /// Diff the staged changes instead of the unstaged ones
+        #[serde(default)]
+        staged: bool,
+        /// Only diff these paths (default: every path)
+        #[serde(default)]
+        paths: Vec<String>,
+    },
+    /// Stages the given paths
+    Add {
+        /// Paths to stage, relative to the workspace root
+        paths: Vec<String>,
+    },
+    /// Commits the staged changes
+    Commit {
+        /// Commit message
+        message: String,
+    },
+    /// Creates a branch at the current commit and switches to it
+    CreateBranch {
+        /// Name of the new branch
+        name: String,
+    },
+    /// Pushes the current branch (destructive)
+    Push {
+        /// Remote to push to (default: the branch's upstream)
+        #[serde(default)]
+        remote: Option<String>,
+        /// Branch to push (default: the current branch)
+        #[serde(default)]
+        branch: Option<String>,
+    },
+    /// Discards every uncommitted change (destructive)
+    ResetHard {
+        /// Commit to reset to (default: `HEAD`)
+        #[serde(default)]
+        target: Option<String>,
+    },
+    /// Deletes untracked files and directories (destructive)
+    Clean,
+}
+
+impl GitArgs {
+    /// Whether the operation can lose work or publish it
+    #[must_use]
+    pub const fn is_destructive(&self) -> bool {
+        matches!(
+            self,
+            Self::Push { .. } | Self::ResetHard { .. } | Self::Clean
+        )
+    }
+
+    /// Name of the operation, as given in the `command` argument
+    #[must_use]
+    pub const fn command_name(&self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Diff { .. } => "diff",
+            Self::Add { .. } => "add",
+            Self::Commit { .. } => "commit",
+            Self::CreateBranch { .. } => "create_branch",
+            Self::Push { .. } => "push",
+            Self::ResetHard { .. } => "reset_hard",
+            Self::Clean => "clean",
+        }
+    }
+}
+
+/// Kind of change made to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GitChange {
+    /// New file
+    Added,
+    /// Changed content
+    Modified,
+    /// Removed file
+    Deleted,
+    /// Moved file
+    Renamed,
+    /// Copied file
+    Copied,
+    /// Changed file type, e.g. to a symlink
+    TypeChanged,
+    /// File not known to git
+    Untracked,
+    /// Unresolved merge conflict
+    Conflicted,
+}
+
+impl GitChange {
+    /// Parses a porcelain status letter, `None` for an unchanged file
+    const fn from_code(code: char) -> Option<Self> {
+        match code {
+            'A' => Some(Self::Added),
+            'M' => Some(Self::Modified),
+            'D' => Some(Self::Deleted),
+            'R' => Some(Self::Renamed),
+            'C' => Some(Self::Copied),
+            'T' => Some(Self::TypeChanged),
+            '?' => Some(Self::Untracked),
+            'U' => Some(Self::Conflicted),
+            _ => None,
+        }
+    }
+}
+
+/// A changed file in the working tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitFileStatus {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Path before a rename or copy
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_path: Option<String>,
+    /// Change staged for the next commit
+    pub staged: Option<GitChange>,
+    /// Change not staged yet
+    pub unstaged: Option<GitChange>,
+}
+
+/// Branch and changed files of a repository
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitStatus {
+    /// Current branch, `None` when `HEAD` is detached
+    pub branch: Option<String>,
+    /// Branch the current one tracks
+    pub upstream: Option<String>,
+    /// Commits not yet on the upstream
+    pub ahead: usize,
+    /// Upstream commits not yet on the current branch
+    pub behind: usize,
+    /// Changed and untracked files
+    pub files: Vec<GitFileStatus>,
+}
+
+impl GitStatus {
+    /// Whether there is nothing to commit
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Line counts of one file in a diff
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitDiffFile {
+    /// Path relative to the repository root
+    pub path: String,
+    /// Added lines, `None` for binary files
+    pub lines_added: Option<usize>,
+    /// Removed lines, `None` for binary files
+    pub lines_removed: Option<usize>,
+}
+
+/// A diff and its statistics
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitDiff {
+    /// Unified diff, empty if nothing changed
+    pub diff: String,
+    /// Changed files
+    pub files: Vec<GitDiffFile>,
+    /// Added lines over all text files
+    pub lines_added: usize,
+    /// Removed lines over all text files
+    pub lines_removed: usize,
+}
+
+/// A file recorded in a commit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitCommittedFile {
+    /// Path relative to the repository root
+    pub path: String,
+    /// How the commit changed the file
+    pub change: GitChange,
+}
+
+/// A commit that was created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitCommit {
+    /// Full commit hash
+    pub sha: String,
+    /// Branch the commit was made on, `None` when `HEAD` is detached
+    pub branch: Option<String>,
+    /// Commit message
+    pub message: String,
+    /// Files the commit changed
+    pub files: Vec<GitCommittedFile>,
+}
+
+/// A branch that was created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitBranch {
+    /// Name of the branch
+    pub name: String,
+    /// Commit the branch starts at, `None` in a repository without commits
+    pub commit: Option<String>,
+}
+
+/// Tool running git commands in the workspace.
+#[derive(Debug, Clone)]
+pub struct GitTool {
+    /// Repository to run git in
+    root_dir: PathBuf,
+    /// Whether push, hard reset and clean are allowed
+    allow_destructive: bool,
+}
+
+impl GitTool {
+    /// Create a new `GitTool` running git in `root_dir`.
+    ///
+    /// Destructive operations are disabled.
+    #[must_use]
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+            allow_destructive: false,
+        }
+    }
+
+    /// Sets whether push, hard reset and clean are allowed
+    #[must_use]
+    pub const fn with_destructive_operations(mut self, allow: bool) -> Self {
+        self.allow_destructive = allow;
+        self
+    }
+
+    /// Runs git with `args` in the workspace, returning its standard output
+    ///
+    /// # Errors
+    /// Returns error if git cannot be started or exits unsuccessfully
+    fn git(&self, args: &[&str]) -> ToolResult<String> {
+        commands::run_git(&self.root_dir, args)
+    }
+
+    /// Current branch and changed files
+    ///
+    /// Blocks while git runs.
+    ///
+    /// # Errors
+    /// Returns error if git fails, e.g. outside of a repository
+    pub fn status(&self) -> ToolResult<GitStatus> {
+        commands::status(self)
+    }
+
+    /// Unstaged changes, or the staged ones if `staged`, limited to `paths` if any
+    ///
+    /// Blocks while git runs.
+    ///
+    /// # Errors
+    /// Returns error if git fails
+    pub fn diff(&self, staged: bool, paths: &[String]) -> ToolResult<GitDiff> {
+        commands::diff(self, staged, paths)
+    }
+
+    /// Stages `paths`, returning the status afterwards
+    ///
+    /// Blocks while git runs.
+    ///
+    /// # Errors
+    /// Returns error if no path is given or git fails
+    pub fn add(&self, paths: &[String]) -> ToolResult<GitStatus> {
+        commands::add(self, paths)
+    }
+
+    /// Commits the staged changes with `message`
+    ///
+    /// Blocks while git runs.
+    ///
+    /// # Errors
+    /// Returns error if the message is empty, nothing is staged or git fails
+    pub fn commit(&self, message: &str) -> ToolResult<GitCommit> {
+        commands::commit(self, message)
+    }
+
+    /// Creates branch `name` at the current commit and switches to it
+    ///
+    /// Uncommitted changes are kept in the working tree.
+    /// Blocks while git runs.
+    ///
+    /// # Errors
+    /// Returns error if the name is not a valid branch name, the branch exists or git fails
+    pub fn create_branch(&self, name: &str) -> ToolResult<GitBranch> {
+        commands::create_branch(self, name)
+    }
+
+    /// Commits every change in the working tree on a new branch `branch`
+    ///
+    /// Used to record the work of a completed task. Returns `None` without
+    /// creating the branch if there is nothing to commit.
+    /// Blocks while git runs.
+    ///
+    /// # Errors
+    /// Returns error if the branch cannot be created or git fails
+    pub fn commit_all_on_branch(
+        &self,
+        branch: &str,
+        message: &str,
+    ) -> ToolResult<Option<GitCommit>> {
+        commands::commit_all_on_branch(self, branch, message)
+    }
+
+    /// Runs the operation described by `args`
+    ///
+    /// # Errors
+    /// Returns error if the operation is invalid, not allowed or git fails
+    fn run(&self, args: &GitArgs) -> ToolResult<ToolOutput> {
+        let (message, data) = match args {
+            GitArgs::Status => {
+                let status = self.status()?;
+                (
+                    format!("{} changed file(s)", status.files.len()),
+                    to_value(status)?,
+                )
+            }
+            GitArgs::Diff { staged, paths } => {
+                let diff = self.diff(*staged, paths)?;
+                (
+                    format!(
+                        "{} file(s) changed, +{} -{}",
+                        diff.files.len(),
+                        diff.lines_added,
+                        diff.lines_removed
+                    ),
+                    to_value(diff)?,
+                )
+            }
+            GitArgs::Add { paths } => {
+                let status = self.add(paths)?;
+                (format!("Staged {} path(s)", paths.len()), to_value(status)?)
+            }
+            GitArgs::Commit { message } => {
+                let commit = self.commit(message)?;
+                (
+                    format!(
+                        "Committed {} file(s) as {}",
+                        commit.files.len(),
+                        short_sha(&commit.sha)
+                    ),
+                    to_value(commit)?,
+                )
+            }
+            GitArgs::CreateBranch { name } => {
+                let branch = self.create_branch(name)?;
+                (format!("Switched to new branch {name}"), to_value(branch)?)
+            }
+            GitArgs::Push { .. } | GitArgs::ResetHard { .. } | GitArgs::Clean => {
+                if !self.allow_destructive {
+                    return Err(ToolError::PermissionDenied(format!(
+                        "git {} is disabled; enable destructive git operations in the config to allow it",
+                        args.command_name()
+                    )));
+                }
+                let output = commands::run_destructive(self, args)?;
+                (
+                    format!("git {} succeeded", args.command_name()),
+                    Value::String(output),
+                )
+            }
+        };
+        Ok(ToolOutput::success_with_data(message, data))
+    }
+}
+
+/// Abbreviated commit hash for messages
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+#[async_trait]
+impl Tool for GitTool {
+    fn name(&self) -> &'static str {
+        TOOL_NAME
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r#"/**
+ * Runs a git operation in the workspace and returns its result as structured data.
+ * Commands: "status" (branch and changed files), "diff" (unified diff with per-file line
+ * counts, of the staged changes if staged is true), "add" (stage the given paths),
+ * "commit" (commit the staged changes with message), "create_branch" (create and switch to name).
+ * "push", "reset_hard" and "clean" are rejected unless destructive git operations are enabled.
+ * @returns For "status" and "add": { branch, upstream, ahead, behind, files: [{ path, original_path?, staged, unstaged }] }
+ *   where staged/unstaged are null or one of "added", "modified", "deleted", "renamed", "copied",
+ *   "type_changed", "untracked", "conflicted". For "diff": { diff, files: [{ path, lines_added, lines_removed }],
+ *   lines_added, lines_removed }. For "commit": { sha, branch, message, files: [{ path, change }] }.
+ *   For "create_branch": { name, commit }. For the others: git's output
+ */
+declare function git(args: { command: "status" | "diff" | "add" | "commit" | "create_branch" | "push" | "reset_hard" | "clean", paths?: string[], staged?: boolean, message?: string, name?: string, remote?: string, branch?: string, target?: string }): Promise<any>;"#
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "command": {
+                    "enum": ["status", "diff", "add", "commit", "create_branch", "push", "reset_hard", "clean"]
+                },
+                "paths": { "type": ["array", "null"], "items": { "type": "string" } },
+                "staged": { "type": ["boolean", "null"] },
+                "message": { "type": "string" },
+                "name": { "type": "string" },
+                "remote": { "type": ["string", "null"] },
+                "branch": { "type": ["string", "null"] },
+                "target": { "type": ["string", "null"] },
+            },
+            "required": ["command"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let args: GitArgs =
+            from_value(input.params).map_err(|err| ToolError::from_arguments(&err))?;
+        let tool = self.clone();
+        spawn_blocking(move || tool.run(&args))
+            .await
+            .map_err(|err| join_error("git command", err))?
+    }
+}
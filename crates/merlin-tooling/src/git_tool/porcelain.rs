@@ -0,0 +1,107 @@
+//! Parsing of git's machine-readable output.
+
+use super::{GitChange, GitCommittedFile, GitDiffFile, GitFileStatus, GitStatus};
+
+/// Parses `git status --porcelain=v1 --branch -z`
+pub(super) fn parse_status(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    let mut records = output.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("## ") {
+            parse_branch_header(header, &mut status);
+            continue;
+        }
+        let mut codes = record.chars();
+        let (Some(index), Some(worktree)) = (codes.next(), codes.next()) else {
+            continue;
+        };
+        let path = record.get(3..).unwrap_or_default().to_owned();
+        // Renames and copies are followed by the original path
+        let original_path = matches!(index, 'R' | 'C')
+            .then(|| records.next().map(ToOwned::to_owned))
+            .flatten();
+        let conflicted = index == 'U'
+            || worktree == 'U'
+            || (index == 'A' && worktree == 'A')
+            || (index == 'D' && worktree == 'D');
+        let (staged, unstaged) = if conflicted {
+            (Some(GitChange::Conflicted), Some(GitChange::Conflicted))
+        } else if index == '?' {
+            (None, Some(GitChange::Untracked))
+        } else {
+            (GitChange::from_code(index), GitChange::from_code(worktree))
+        };
+        status.files.push(GitFileStatus {
+            path,
+            original_path,
+            staged,
+            unstaged,
+        });
+    }
+    status
+}
+
+/// Parses the `## branch...upstream [ahead 1, behind 2]` status header
+fn parse_branch_header(header: &str, status: &mut GitStatus) {
+    let (names, counts) = header
+        .split_once(" [")
+        .map_or((header, ""), |(names, counts)| (names, counts));
+    let names = names.strip_prefix("No commits yet on ").unwrap_or(names);
+    if names.starts_with("HEAD (no branch)") {
+        return;
+    }
+    let (branch, upstream) = names
+        .split_once("...")
+        .map_or((names, None), |(branch, upstream)| (branch, Some(upstream)));
+    status.branch = Some(branch.to_owned());
+    status.upstream = upstream.map(ToOwned::to_owned);
+
+    for count in counts.trim_end_matches(']').split(", ") {
+        if let Some(ahead) = count.strip_prefix("ahead ") {
+            status.ahead = ahead.parse().unwrap_or_default();
+        } else if let Some(behind) = count.strip_prefix("behind ") {
+            status.behind = behind.parse().unwrap_or_default();
+        }
+    }
+}
+
+/// Parses `git diff --numstat`, where binary files have `-` counts
+pub(super) fn parse_numstat(output: &str) -> Vec<GitDiffFile> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let added = fields.next()?;
+            let removed = fields.next()?;
+            Some(GitDiffFile {
+                path: fields.next()?.to_owned(),
+                lines_added: added.parse().ok(),
+                lines_removed: removed.parse().ok(),
+            })
+        })
+        .collect()
+}
+
+/// Parses `git diff-tree --name-status -z`
+pub(super) fn parse_name_status(output: &str) -> Vec<GitCommittedFile> {
+    let mut files = Vec::new();
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    while let Some(code) = fields.next() {
+        let change = code
+            .chars()
+            .next()
+            .and_then(GitChange::from_code)
+            .unwrap_or(GitChange::Modified);
+        // Renames and copies list the original path before the new one
+        if matches!(change, GitChange::Renamed | GitChange::Copied) {
+            fields.next();
+        }
+        if let Some(path) = fields.next() {
+            files.push(GitCommittedFile {
+                path: path.to_owned(),
+                change,
+            });
+        }
+    }
+    files
+}
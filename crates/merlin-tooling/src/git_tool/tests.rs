@@ -0,0 +1,173 @@
+//! Tests for the git tool
+
+use super::porcelain::parse_status;
+use super::*;
+use anyhow::{Result, anyhow};
+use serde_json::json;
+use std::fs::write;
+use tempfile::TempDir;
+
+/// Creates a repository with one commit of `README.md`
+///
+/// # Errors
+/// Returns an error if the repository cannot be set up.
+fn init_repo() -> Result<(TempDir, GitTool)> {
+    let temp_dir = TempDir::new()?;
+    let tool = GitTool::new(temp_dir.path());
+    tool.git(&["init", "--quiet", "--initial-branch=main"])?;
+    tool.git(&["config", "user.name", "Test"])?;
+    tool.git(&["config", "user.email", "test@example.com"])?;
+    write(temp_dir.path().join("README.md"), "# Project\n")?;
+    tool.git(&["add", "README.md"])?;
+    tool.git(&["commit", "--quiet", "--message", "Initial commit"])?;
+    Ok((temp_dir, tool))
+}
+
+/// Tests staging and committing with the status and commit parsed.
+///
+/// # Errors
+/// Returns an error if the repository cannot be set up or git fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_add_and_commit_return_structured_results() -> Result<()> {
+    let (temp_dir, tool) = init_repo()?;
+    write(temp_dir.path().join("README.md"), "# Project\n\nMore\n")?;
+    write(temp_dir.path().join("new file.rs"), "fn main() {}\n")?;
+
+    let status = tool.status()?;
+    assert_eq!(status.branch.as_deref(), Some("main"));
+    assert_eq!(
+        status.files,
+        vec![
+            GitFileStatus {
+                path: "README.md".to_owned(),
+                original_path: None,
+                staged: None,
+                unstaged: Some(GitChange::Modified),
+            },
+            GitFileStatus {
+                path: "new file.rs".to_owned(),
+                original_path: None,
+                staged: None,
+                unstaged: Some(GitChange::Untracked),
+            },
+        ]
+    );
+
+    let diff = tool.diff(false, &[])?;
+    assert_eq!((diff.lines_added, diff.lines_removed), (2, 0));
+    assert!(diff.diff.contains("+More"));
+
+    let added = tool
+        .execute(ToolInput {
+            params: json!({ "command": "add", "paths": ["new file.rs"] }),
+        })
+        .await?;
+    let staged: GitStatus = from_value(added.data.unwrap_or_default())?;
+    assert_eq!(staged.files[1].staged, Some(GitChange::Added));
+
+    let committed = tool
+        .execute(ToolInput {
+            params: json!({ "command": "commit", "message": "Add entry point" }),
+        })
+        .await?;
+    let commit: GitCommit = from_value(committed.data.unwrap_or_default())?;
+    assert_eq!(commit.branch.as_deref(), Some("main"));
+    assert_eq!(commit.sha.len(), 40);
+    assert_eq!(
+        commit.files,
+        vec![GitCommittedFile {
+            path: "new file.rs".to_owned(),
+            change: GitChange::Added,
+        }]
+    );
+    // The unstaged README change was left out of the commit
+    assert_eq!(tool.status()?.files.len(), 1);
+    Ok(())
+}
+
+/// Tests committing all changes on a new branch, and skipping clean trees.
+///
+/// # Errors
+/// Returns an error if the repository cannot be set up or git fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_commit_all_on_branch() -> Result<()> {
+    let (temp_dir, tool) = init_repo()?;
+    assert_eq!(tool.commit_all_on_branch("merlin/1", "Nothing")?, None);
+
+    write(temp_dir.path().join("README.md"), "# Renamed project\n")?;
+    let commit = tool
+        .commit_all_on_branch("merlin/2", "Rename the project")?
+        .ok_or_else(|| anyhow!("nothing was committed"))?;
+    assert_eq!(commit.branch.as_deref(), Some("merlin/2"));
+    assert_eq!(commit.message, "Rename the project");
+    assert_eq!(commit.files[0].change, GitChange::Modified);
+    assert!(tool.status()?.is_clean());
+    assert!(matches!(
+        tool.create_branch("bad..name"),
+        Err(ToolError::InvalidArguments { .. })
+    ));
+    assert!(matches!(
+        tool.create_branch("merlin/2"),
+        Err(ToolError::Conflict(_))
+    ));
+    Ok(())
+}
+
+/// Tests that destructive operations are rejected unless enabled.
+///
+/// # Errors
+/// Returns an error if the repository cannot be set up or git fails.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_destructive_operations_require_opt_in() -> Result<()> {
+    let (temp_dir, tool) = init_repo()?;
+    write(temp_dir.path().join("scratch.txt"), "temporary")?;
+    let clean = || ToolInput {
+        params: json!({ "command": "clean" }),
+    };
+
+    let rejected = tool.execute(clean()).await;
+    assert!(matches!(rejected, Err(ToolError::PermissionDenied(_))));
+    assert!(temp_dir.path().join("scratch.txt").exists());
+
+    let allowed = tool
+        .with_destructive_operations(true)
+        .execute(clean())
+        .await;
+    assert!(matches!(allowed, Ok(ref output) if output.success));
+    assert!(!temp_dir.path().join("scratch.txt").exists());
+    Ok(())
+}
+
+/// Tests parsing of branch headers, renames and conflicts.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_parse_status() {
+    let status = parse_status(
+        "## feature...origin/feature [ahead 2, behind 1]\0R  new.rs\0old.rs\0UU merge.rs\0",
+    );
+    assert_eq!(status.branch.as_deref(), Some("feature"));
+    assert_eq!(status.upstream.as_deref(), Some("origin/feature"));
+    assert_eq!((status.ahead, status.behind), (2, 1));
+    assert_eq!(status.files[0].original_path.as_deref(), Some("old.rs"));
+    assert_eq!(status.files[0].staged, Some(GitChange::Renamed));
+    assert_eq!(status.files[1].unstaged, Some(GitChange::Conflicted));
+
+    assert_eq!(parse_status("## HEAD (no branch)\0").branch, None);
+    assert_eq!(
+        parse_status("## No commits yet on main\0")
+            .branch
+            .as_deref(),
+        Some("main")
+    );
+}
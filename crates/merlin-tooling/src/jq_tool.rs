@@ -0,0 +1,410 @@
+//! JSON queries with jq expressions.
+//!
+//! Lets agents pull nested values out of JSON configuration and data files
+//! without shelling out to `jq`. Expressions are evaluated in-process with
+//! `jaq`, only on files inside the workspace, and the result is always
+//! returned as formatted JSON.
+
+use std::fs;
+use std::iter::empty;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use jaq_core::load::{Arena, Error as LoadError, Errors as LoadErrors, File, Loader};
+use jaq_core::{Compiler, Ctx, RcIter};
+use jaq_json::{Val, defs as json_defs, funs as json_funs};
+use jaq_std::{defs as std_defs, funs as std_funs};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_str, from_value, json, to_string_pretty};
+
+use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult, resolve_existing};
+
+/// Output token budget when `max_output_tokens` is not given
+const DEFAULT_MAX_OUTPUT_TOKENS: usize = 2000;
+/// Characters per token used to size the output budget
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Arguments for a jq query
+///
+/// Exactly one of `file_path` and `json_string` must be given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JqArgs {
+    /// JSON file to query, relative to the workspace root
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Inline JSON to query
+    #[serde(default)]
+    pub json_string: Option<String>,
+    /// jq expression, e.g. `.dependencies | keys`
+    pub jq_expression: String,
+    /// Output size in tokens above which the result is truncated (default: 2000)
+    #[serde(default = "default_max_output_tokens")]
+    pub max_output_tokens: usize,
+}
+
+/// Default for [`JqArgs::max_output_tokens`]
+const fn default_max_output_tokens() -> usize {
+    DEFAULT_MAX_OUTPUT_TOKENS
+}
+
+/// Tool for querying JSON files or strings with jq expressions.
+pub struct JqTool {
+    /// Root directory to constrain file access (for sandboxing)
+    root_dir: PathBuf,
+}
+
+impl JqTool {
+    /// Create a new `JqTool` with the given root directory.
+    ///
+    /// All file paths will be resolved relative to this root directory.
+    #[must_use]
+    pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            root_dir: root_dir.into(),
+        }
+    }
+
+    /// Resolve a path relative to the root directory and validate it's within bounds.
+    ///
+    /// # Errors
+    /// Returns error if the file does not exist or the path escapes the root directory
+    fn resolve_path(&self, path: &str) -> ToolResult<PathBuf> {
+        resolve_existing(&self.root_dir, path, "File")
+    }
+
+    /// Parses the JSON document requested by `args`
+    ///
+    /// # Errors
+    /// Returns error if neither or both sources are given, the file cannot be
+    /// read or the content is not valid JSON
+    fn load_input(&self, args: &JqArgs) -> ToolResult<Value> {
+        let (source, text) = match (&args.file_path, &args.json_string) {
+            (Some(path), None) => {
+                let full_path = self.resolve_path(path)?;
+                let text = fs::read_to_string(&full_path)
+                    .map_err(|err| ToolError::io(format!("Failed to read file '{path}'"), &err))?;
+                (path.as_str(), text)
+            }
+            (None, Some(text)) => ("json_string", text.clone()),
+            _ => {
+                return Err(ToolError::invalid_arguments(
+                    "jq requires exactly one of 'file_path' and 'json_string'",
+                ));
+            }
+        };
+        from_str(&text).map_err(|err| {
+            ToolError::invalid_argument(source, format!("{source} is not valid JSON: {err}"))
+        })
+    }
+}
+
+/// Evaluates a jq expression, returning every value it outputs
+///
+/// # Errors
+/// Returns error if the expression does not parse or compile, or fails on the input
+pub fn run_jq(expression: &str, input: Value) -> ToolResult<Vec<Value>> {
+    let loader = Loader::new(std_defs().chain(json_defs()));
+    let arena = Arena::default();
+    let program = File {
+        code: expression,
+        path: (),
+    };
+    let modules = loader.load(&arena, program).map_err(|errors| {
+        ToolError::invalid_argument(
+            "jq_expression",
+            format!(
+                "Invalid jq expression '{expression}': {}",
+                describe_load_errors(errors)
+            ),
+        )
+    })?;
+    let filter = Compiler::default()
+        .with_funs(std_funs().chain(json_funs()))
+        .compile(modules)
+        .map_err(|errors| {
+            let undefined: Vec<String> = errors
+                .into_iter()
+                .flat_map(|(_, undefined)| undefined)
+                .map(|(name, kind)| format!("undefined {} '{name}'", kind.as_str()))
+                .collect();
+            ToolError::invalid_argument(
+                "jq_expression",
+                format!(
+                    "Invalid jq expression '{expression}': {}",
+                    undefined.join(", ")
+                ),
+            )
+        })?;
+
+    let inputs = RcIter::new(empty());
+    filter
+        .run((Ctx::new([], &inputs), Val::from(input)))
+        .map(|output| {
+            output.map(Value::from).map_err(|err| {
+                ToolError::ExecutionFailed(format!("jq expression '{expression}' failed: {err}"))
+            })
+        })
+        .collect()
+}
+
+/// Describes the errors of loading a jq expression
+fn describe_load_errors<P>(errors: LoadErrors<&str, P>) -> String {
+    let mut messages = Vec::new();
+    for (_, error) in errors {
+        match error {
+            LoadError::Io(failures) => messages.extend(failures.into_iter().map(|(_, err)| err)),
+            LoadError::Lex(failures) => messages.extend(
+                failures
+                    .into_iter()
+                    .map(|(expected, found)| describe_unexpected(expected.as_str(), found)),
+            ),
+            LoadError::Parse(failures) => messages.extend(
+                failures
+                    .into_iter()
+                    .map(|(expected, found)| describe_unexpected(expected.as_str(), found)),
+            ),
+        }
+    }
+    messages.join(", ")
+}
+
+/// Describes an unexpected token, given the unparsed rest of the expression
+fn describe_unexpected(expected: &str, rest: &str) -> String {
+    rest.split_whitespace().next().map_or_else(
+        || format!("expected {expected}, found end of expression"),
+        |found| format!("expected {expected}, found '{found}'"),
+    )
+}
+
+/// Formats jq outputs: a single value as itself, any other number of values as an array
+///
+/// # Errors
+/// Returns error if the outputs cannot be serialized
+fn format_outputs(mut outputs: Vec<Value>) -> ToolResult<String> {
+    let result = if outputs.len() == 1 {
+        outputs.remove(0)
+    } else {
+        Value::Array(outputs)
+    };
+    Ok(to_string_pretty(&result)?)
+}
+
+/// Cuts `text` to about `max_tokens` tokens, returning whether it was cut
+fn truncate_to_tokens(text: &mut String, max_tokens: usize) -> bool {
+    let max_chars = max_tokens.saturating_mul(CHARS_PER_TOKEN);
+    let Some((cut, _)) = text.char_indices().nth(max_chars) else {
+        return false;
+    };
+    *text = format!(
+        "{}\n... [truncated: output exceeds {max_tokens} tokens, narrow the jq expression]",
+        &text[..cut]
+    );
+    true
+}
+
+#[async_trait]
+impl Tool for JqTool {
+    fn name(&self) -> &'static str {
+        "jq"
+    }
+
+    fn typescript_signature(&self) -> &'static str {
+        r"/**
+ * Queries JSON with a jq expression, e.g. to extract nested values from configuration or data files.
+ * @param args - file_path (relative to the workspace root) or json_string to query, the jq_expression
+ *   to evaluate, and max_output_tokens above which the result is truncated (default 2000)
+ * @returns The result as formatted JSON: the value if the expression outputs one, otherwise an array
+ *   of all outputs. Truncated output ends with a `... [truncated: ...]` line and is no longer valid JSON
+ */
+declare function jq(args: { file_path?: string, json_string?: string, jq_expression: string, max_output_tokens?: number }): Promise<string>;"
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file_path": { "type": ["string", "null"] },
+                "json_string": { "type": ["string", "null"] },
+                "jq_expression": { "type": "string" },
+                "max_output_tokens": { "type": ["integer", "null"] },
+            },
+            "required": ["jq_expression"]
+        })
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let args: JqArgs =
+            from_value(input.params).map_err(|err| ToolError::from_arguments(&err))?;
+        let document = self.load_input(&args)?;
+        let outputs = run_jq(&args.jq_expression, document)?;
+        let count = outputs.len();
+
+        let mut formatted = format_outputs(outputs)?;
+        let message = if truncate_to_tokens(&mut formatted, args.max_output_tokens) {
+            tracing::warn!(
+                "jq output for '{}' truncated to {} tokens",
+                args.jq_expression,
+                args.max_output_tokens
+            );
+            format!(
+                "jq produced {count} result(s) (truncated to {} tokens)",
+                args.max_output_tokens
+            )
+        } else {
+            format!("jq produced {count} result(s)")
+        };
+        Ok(ToolOutput::success_with_data(message, json!(formatted)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use std::fs::write;
+    use tempfile::TempDir;
+
+    /// Runs the jq tool on `params` in `root`
+    ///
+    /// # Errors
+    /// Returns an error if the tool fails.
+    async fn query(root: &TempDir, params: Value) -> ToolResult<ToolOutput> {
+        JqTool::new(root.path()).execute(ToolInput { params }).await
+    }
+
+    /// Tests extracting single values and arrays from a file and an inline string.
+    ///
+    /// # Errors
+    /// Returns an error if the workspace cannot be set up or a query fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_jq_queries() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        write(
+            temp_dir.path().join("package.json"),
+            r#"{"name": "app", "dependencies": {"react": "^18.0.0", "zod": "^3.0.0"}}"#,
+        )?;
+
+        let name = query(
+            &temp_dir,
+            json!({ "file_path": "package.json", "jq_expression": ".name" }),
+        )
+        .await?;
+        assert_eq!(name.data, Some(json!("\"app\"")));
+
+        let keys = query(
+            &temp_dir,
+            json!({ "file_path": "package.json", "jq_expression": ".dependencies | keys" }),
+        )
+        .await?;
+        assert_eq!(keys.data, Some(json!("[\n  \"react\",\n  \"zod\"\n]")));
+
+        let outputs = query(
+            &temp_dir,
+            json!({ "json_string": "[1, 2, 3]", "jq_expression": ".[] | select(. > 1)" }),
+        )
+        .await?;
+        assert_eq!(outputs.data, Some(json!("[\n  2,\n  3\n]")));
+        assert_eq!(outputs.message, "jq produced 2 result(s)");
+        Ok(())
+    }
+
+    /// Tests that large outputs are truncated with a warning.
+    ///
+    /// # Errors
+    /// Returns an error if the query fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_jq_truncates_output() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let output = query(
+            &temp_dir,
+            json!({
+                "json_string": "{}",
+                "jq_expression": "[range(1000)]",
+                "max_output_tokens": 5
+            }),
+        )
+        .await?;
+        let text = output
+            .data
+            .as_ref()
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        assert!(
+            text.starts_with("[\n  0,\n  1,"),
+            "unexpected output: {text}"
+        );
+        assert!(text.ends_with("narrow the jq expression]"));
+        assert_eq!(
+            output.message,
+            "jq produced 1 result(s) (truncated to 5 tokens)"
+        );
+        Ok(())
+    }
+
+    /// Tests that bad input, expressions and paths are rejected.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_jq_rejects_invalid_input() {
+        let Ok(temp_dir) = TempDir::new() else {
+            return;
+        };
+        let invalid_expression = query(
+            &temp_dir,
+            json!({ "json_string": "{}", "jq_expression": ".a |" }),
+        )
+        .await;
+        assert!(
+            matches!(&invalid_expression, Err(ToolError::InvalidArguments { field: Some(field), message }) if field == "jq_expression" && message.contains("Invalid jq expression")),
+            "unexpected result: {invalid_expression:?}"
+        );
+
+        let undefined = query(
+            &temp_dir,
+            json!({ "json_string": "{}", "jq_expression": "nope" }),
+        )
+        .await;
+        assert!(
+            matches!(&undefined, Err(ToolError::InvalidArguments { message, .. }) if message.contains("undefined filter 'nope'")),
+            "unexpected result: {undefined:?}"
+        );
+
+        let invalid_json = query(
+            &temp_dir,
+            json!({ "json_string": "{", "jq_expression": "." }),
+        )
+        .await;
+        assert!(matches!(&invalid_json, Err(err) if err.field() == Some("json_string")));
+
+        let both = query(
+            &temp_dir,
+            json!({ "file_path": "a.json", "json_string": "{}", "jq_expression": "." }),
+        )
+        .await;
+        assert!(matches!(
+            both,
+            Err(ToolError::InvalidArguments { field: None, .. })
+        ));
+
+        let outside = query(
+            &temp_dir,
+            json!({ "file_path": "..", "jq_expression": "." }),
+        )
+        .await;
+        assert!(matches!(outside, Err(ToolError::SandboxViolation(_))));
+
+        let runtime_error = query(
+            &temp_dir,
+            json!({ "json_string": "1", "jq_expression": ".[0]" }),
+        )
+        .await;
+        assert!(matches!(runtime_error, Err(ToolError::ExecutionFailed(_))));
+    }
+}
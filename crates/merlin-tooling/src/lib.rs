@@ -6,36 +6,95 @@
 //! - TypeScript runtime with `QuickJS` for executing agent code
 //! - TypeScript signature generation from tool schemas
 
+/// Audit log of tool calls.
+mod audit_log;
 /// Shell execution tool implementation.
 mod bash;
+/// Recording of the tools called by agent code.
+mod call_recorder;
 /// Dynamic context request tool for agents.
 pub mod context_request;
 /// File deletion tool.
 mod delete_tool;
+/// Unified diffs between file versions.
+mod diff_tool;
 /// File editing tool for find-and-replace operations.
 mod edit_tool;
+/// Tracking of files changed by tools.
+mod file_changes;
 /// File operation tools (read, write, list).
 mod file_ops;
+/// Recursive file search by glob and metadata.
+mod find_tool;
+/// Git operations on behalf of agents.
+mod git_tool;
+/// JSON queries with jq expressions.
+mod jq_tool;
+/// Panic recovery for spawned tasks.
+mod panic_guard;
+/// Platform differences in shells and paths.
+mod platform;
 /// Tool registry for managing available tools.
 mod registry;
+/// Caching of the results of idempotent tools.
+mod result_cache;
 /// TypeScript/JavaScript runtime using QuickJS.
 mod runtime;
+/// Confining tool paths to the workspace root.
+mod sandbox;
+/// Shell commands started by tools, killed on shutdown.
+mod shell_processes;
 /// TypeScript signature generation from tool schemas.
 mod signatures;
+/// Symbol definition lookup for context requests.
+mod symbol_lookup;
 /// Core abstractions shared by all tools.
 mod tool;
+/// Validation of tool inputs against tool schemas.
+mod tool_schema;
+/// Time spent in tools.
+mod tool_timings;
+/// Tools loaded from WASM modules.
+#[cfg(feature = "wasm-plugins")]
+mod wasm_plugin;
 
+pub use audit_log::{
+    AuditOutcome, CONTENT_REDACTED, SECRET_REDACTED, TOOL_AUDIT_FILE, ToolAuditEntry, ToolAuditLog,
+    parse_audit_entries, redact_secrets, sanitize_input,
+};
 pub use bash::BashTool;
+pub use call_recorder::{RecordedToolCall, ToolCallRecorder};
 pub use context_request::{
-    ContextFile, ContextRequestArgs, ContextRequestResult, ContextRequestTool, ContextTracker,
+    ContextFile, ContextRequestArgs, ContextRequestResult, ContextRequestTool, ContextTarget,
+    ContextTracker, SymbolExcerpt,
 };
 pub use delete_tool::DeleteFileTool;
+pub use diff_tool::{DiffArgs, DiffSummary, DiffTool};
 pub use edit_tool::EditFileTool;
+pub use file_changes::FileChangeTracker;
 pub use file_ops::{ListFilesTool, ReadFileTool, WriteFileTool};
+pub use find_tool::{FindFilesArgs, FindFilesTool, FoundFile};
+pub use git_tool::{
+    GitArgs, GitBranch, GitChange, GitCommit, GitCommittedFile, GitDiff, GitDiffFile,
+    GitFileStatus, GitStatus, GitTool,
+};
+pub use jq_tool::{JqArgs, JqTool, run_jq};
+pub use panic_guard::{
+    ABORT_ON_PANIC_ENV, abort_on_panic, join_error, panic_message, recover_panic,
+};
+pub use platform::{SHELL_ENV, ShellFlavor, canonicalize, display_path, native_path};
 pub use registry::ToolRegistry;
+pub use result_cache::{DEFAULT_CACHE_CAPACITY, ToolResultCache};
 pub use runtime::{
     JsValueHandle as ToolingJsValueHandle, PersistentTypeScriptRuntime, TypeScriptRuntime,
     bulk_extraction,
 };
-pub use signatures::generate_typescript_signatures;
+pub use sandbox::{canonical_root, ensure_within, resolve_existing, resolve_for_write};
+pub use shell_processes::{RunningCommand, RunningCommands};
+pub use signatures::{generate_typescript_signatures, signature_from_description};
+pub use symbol_lookup::{GrepSymbolResolver, SymbolDefinition, SymbolName, SymbolResolver};
 pub use tool::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+pub use tool_schema::ToolSchemaValidator;
+pub use tool_timings::ToolTimings;
+#[cfg(feature = "wasm-plugins")]
+pub use wasm_plugin::{DEFAULT_PLUGIN_FUEL, DEFAULT_PLUGIN_MEMORY, WasmSandbox, WasmTool};
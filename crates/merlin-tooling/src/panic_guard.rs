@@ -0,0 +1,91 @@
+//! Panic recovery for spawned tasks.
+//!
+//! Tools and the TypeScript runtime run user-controlled code paths that can
+//! panic. Panics are caught at task boundaries and reported as
+//! `ToolError::Panicked` so a single bad tool call fails its task instead of
+//! taking down the process. Setting `MERLIN_ABORT_ON_PANIC=1` re-raises caught
+//! panics instead, which is useful when debugging.
+
+use std::any::Any;
+use std::env;
+use std::panic::resume_unwind;
+
+use tokio::task::JoinError;
+
+use crate::ToolError;
+
+/// Environment variable that disables panic recovery when set to `1`
+pub const ABORT_ON_PANIC_ENV: &str = "MERLIN_ABORT_ON_PANIC";
+
+/// Returns whether caught panics should be re-raised instead of reported
+#[must_use]
+pub fn abort_on_panic() -> bool {
+    env::var_os(ABORT_ON_PANIC_ENV).is_some_and(|value| value == "1")
+}
+
+/// Extracts the message from a panic payload
+#[must_use]
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| (*message).to_owned())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_owned())
+}
+
+/// Converts a caught panic into `ToolError::Panicked`
+///
+/// # Panics
+/// Re-raises the panic when `MERLIN_ABORT_ON_PANIC=1` is set.
+#[must_use]
+pub fn recover_panic(task_description: &str, payload: Box<dyn Any + Send>) -> ToolError {
+    if abort_on_panic() {
+        resume_unwind(payload);
+    }
+
+    let message = panic_message(payload.as_ref());
+    tracing::error!("{task_description} panicked: {message}");
+    ToolError::Panicked {
+        task_description: task_description.to_owned(),
+        message,
+    }
+}
+
+/// Converts the failed join of a spawned task into a `ToolError`
+///
+/// # Panics
+/// Re-raises the task's panic when `MERLIN_ABORT_ON_PANIC=1` is set.
+#[must_use]
+pub fn join_error(task_description: &str, err: JoinError) -> ToolError {
+    match err.try_into_panic() {
+        Ok(payload) => recover_panic(task_description, payload),
+        Err(join_err) => {
+            ToolError::ExecutionFailed(format!("{task_description} did not complete: {join_err}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::task::spawn_blocking;
+
+    /// Tests that a panic in a spawned task becomes a `Panicked` error.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_join_error_reports_panic() {
+        let outcome = spawn_blocking(|| -> u8 { resume_unwind(Box::new("tool blew up")) }).await;
+        let error = outcome.err().map(|err| join_error("bash command", err));
+
+        assert!(
+            matches!(
+                &error,
+                Some(ToolError::Panicked { task_description, message })
+                    if task_description == "bash command" && message == "tool blew up"
+            ),
+            "unexpected error: {error:?}"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Platform differences in shells and paths.
+//!
+//! Shell commands run through a [`ShellFlavor`], which knows how each shell is
+//! started and how it quotes arguments. Paths are shown and persisted with
+//! forward slashes by [`display_path`], turned back into native paths for IO by
+//! [`native_path`], and resolved for sandbox checks by [`canonicalize`], which
+//! drops the verbatim prefix Windows adds to drive letters and UNC shares.
+
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{MAIN_SEPARATOR, Path, PathBuf};
+use std::process::Command;
+
+/// Environment variable choosing the shell of the bash tool: `sh`, `cmd` or `powershell`
+pub const SHELL_ENV: &str = "MERLIN_SHELL";
+
+/// Prefix of verbatim paths, e.g. `\\?\C:\project`
+const VERBATIM_PREFIX: &str = r"\\?\";
+/// Prefix of verbatim UNC paths, e.g. `\\?\UNC\server\share`
+const VERBATIM_UNC_PREFIX: &str = r"\\?\UNC\";
+
+/// Shell commands are run with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellFlavor {
+    /// `sh -c`
+    Posix,
+    /// `cmd /C`
+    Cmd,
+    /// `powershell -NoProfile -NonInteractive -Command`
+    PowerShell,
+}
+
+impl ShellFlavor {
+    /// Shell set by `MERLIN_SHELL`, or the platform's default shell
+    #[must_use]
+    pub fn from_env() -> Self {
+        let Ok(name) = env::var(SHELL_ENV) else {
+            return Self::platform_default();
+        };
+        Self::from_name(&name).unwrap_or_else(|| {
+            tracing::warn!("Unknown {SHELL_ENV} value {name:?}, using the platform's shell");
+            Self::platform_default()
+        })
+    }
+
+    /// `cmd` on Windows, `sh` everywhere else
+    #[must_use]
+    pub const fn platform_default() -> Self {
+        if cfg!(windows) {
+            Self::Cmd
+        } else {
+            Self::Posix
+        }
+    }
+
+    /// Shell named `name` (`sh`, `cmd` or `powershell`, case-insensitive)
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "sh" | "posix" => Some(Self::Posix),
+            "cmd" => Some(Self::Cmd),
+            "powershell" => Some(Self::PowerShell),
+            _ => None,
+        }
+    }
+
+    /// Program started to run commands
+    #[must_use]
+    pub const fn program(self) -> &'static str {
+        match self {
+            Self::Posix => "sh",
+            Self::Cmd => "cmd",
+            Self::PowerShell => "powershell",
+        }
+    }
+
+    /// Arguments placed before the command
+    #[must_use]
+    pub const fn flags(self) -> &'static [&'static str] {
+        match self {
+            Self::Posix => &["-c"],
+            Self::Cmd => &["/C"],
+            Self::PowerShell => &["-NoProfile", "-NonInteractive", "-Command"],
+        }
+    }
+
+    /// Process running `script` in this shell
+    #[must_use]
+    pub fn command(self, script: &str) -> Command {
+        let mut command = Command::new(self.program());
+        command.args(self.flags()).arg(script);
+        command
+    }
+
+    /// `arg` quoted so this shell passes it on as a single literal argument
+    ///
+    /// `cmd` still expands `%VAR%` inside quotes, so `%` cannot be passed literally there.
+    #[must_use]
+    pub fn quote(self, arg: &str) -> String {
+        let plain = !arg.is_empty()
+            && arg.chars().all(|character| {
+                character.is_ascii_alphanumeric() || "_-./=:,+@".contains(character)
+            });
+        if plain {
+            return arg.to_owned();
+        }
+        match self {
+            Self::Posix => format!("'{}'", arg.replace('\'', r"'\''")),
+            Self::Cmd => format!("\"{}\"", arg.replace('"', "\"\"")),
+            Self::PowerShell => format!("'{}'", arg.replace('\'', "''")),
+        }
+    }
+}
+
+/// `path` with forward slashes, as it is shown and persisted
+#[must_use]
+pub fn display_path(path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if MAIN_SEPARATOR == '\\' {
+        strip_verbatim(&text).replace('\\', "/")
+    } else {
+        text.into_owned()
+    }
+}
+
+/// Path to do IO with for `path`, shown or persisted with forward slashes
+#[must_use]
+pub fn native_path(path: &str) -> PathBuf {
+    if MAIN_SEPARATOR == '\\' {
+        PathBuf::from(path.replace('/', "\\"))
+    } else {
+        PathBuf::from(path)
+    }
+}
+
+/// Absolute path of `path` with symlinks resolved, without a verbatim prefix
+///
+/// Windows canonicalizes `C:\project` to `\\?\C:\project` and `\\server\share`
+/// to `\\?\UNC\server\share`. Dropping the prefix keeps sandbox checks and
+/// displayed paths in the form users and other tools write them in.
+///
+/// # Errors
+/// Returns an error if `path` does not exist or cannot be resolved
+pub fn canonicalize(path: &Path) -> io::Result<PathBuf> {
+    let canonical = fs::canonicalize(path)?;
+    if MAIN_SEPARATOR != '\\' {
+        return Ok(canonical);
+    }
+    Ok(match strip_verbatim(&canonical.to_string_lossy()) {
+        Cow::Borrowed(_) => canonical,
+        Cow::Owned(stripped) => PathBuf::from(stripped),
+    })
+}
+
+/// `path` without the verbatim prefix of a drive letter or UNC path
+fn strip_verbatim(path: &str) -> Cow<'_, str> {
+    if let Some(share) = path.strip_prefix(VERBATIM_UNC_PREFIX) {
+        return Cow::Owned(format!(r"\\{share}"));
+    }
+    match path.strip_prefix(VERBATIM_PREFIX) {
+        Some(rest) if has_drive_letter(rest) => Cow::Owned(rest.to_owned()),
+        _ => Cow::Borrowed(path),
+    }
+}
+
+/// Whether `path` starts with a drive letter such as `C:`
+fn has_drive_letter(path: &str) -> bool {
+    let mut chars = path.chars();
+    chars
+        .next()
+        .is_some_and(|letter| letter.is_ascii_alphabetic())
+        && chars.next() == Some(':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Tests that paths are shown with forward slashes and read back natively.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_display_and_native_paths_round_trip() {
+        let native = Path::new("src").join("ui").join("lib.rs");
+        assert_eq!(display_path(&native), "src/ui/lib.rs");
+        assert_eq!(native_path("src/ui/lib.rs"), native);
+        assert_eq!(native_path(&display_path(&native)), native);
+    }
+
+    /// Tests that verbatim prefixes are dropped from drive letter and UNC paths only.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_strip_verbatim_handles_drives_and_unc_paths() {
+        assert_eq!(strip_verbatim(r"\\?\C:\project\src"), r"C:\project\src");
+        assert_eq!(
+            strip_verbatim(r"\\?\UNC\server\share\project"),
+            r"\\server\share\project"
+        );
+        assert_eq!(
+            strip_verbatim(r"\\?\Volume{1234}\project"),
+            r"\\?\Volume{1234}\project"
+        );
+        assert_eq!(strip_verbatim(r"C:\project"), r"C:\project");
+        assert_eq!(strip_verbatim("/home/user/project"), "/home/user/project");
+    }
+
+    /// Tests that canonical paths of nested directories stay inside the canonical root.
+    ///
+    /// # Errors
+    /// Returns an error if the temporary directories cannot be created.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_canonicalize_keeps_sandbox_prefix() -> io::Result<()> {
+        let root = TempDir::new()?;
+        let nested = root.path().join("src");
+        fs::create_dir(&nested)?;
+
+        let canonical_root = canonicalize(root.path())?;
+        let canonical_nested = canonicalize(&root.path().join("src").join("..").join("src"))?;
+        assert!(canonical_nested.starts_with(&canonical_root));
+        assert!(
+            !canonical_root
+                .to_string_lossy()
+                .starts_with(VERBATIM_PREFIX)
+        );
+        Ok(())
+    }
+
+    /// Tests the quoting rules of each shell.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_quote_per_shell() {
+        let cases = [
+            (ShellFlavor::Posix, "src/lib.rs", "src/lib.rs"),
+            (ShellFlavor::Posix, "it's here", r"'it'\''s here'"),
+            (ShellFlavor::Posix, "", "''"),
+            (ShellFlavor::Cmd, "C:/project", "C:/project"),
+            (
+                ShellFlavor::Cmd,
+                r#"say "hi" & exit"#,
+                r#""say ""hi"" & exit""#,
+            ),
+            (ShellFlavor::PowerShell, "it's $HOME", "'it''s $HOME'"),
+        ];
+        for (shell, arg, quoted) in cases {
+            assert_eq!(shell.quote(arg), quoted, "{shell:?} {arg}");
+        }
+    }
+
+    /// Tests how each shell is named and started.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_shell_names_and_invocation() {
+        assert_eq!(
+            ShellFlavor::from_name(" PowerShell "),
+            Some(ShellFlavor::PowerShell)
+        );
+        assert_eq!(ShellFlavor::from_name("sh"), Some(ShellFlavor::Posix));
+        assert_eq!(ShellFlavor::from_name("fish"), None);
+
+        let command = ShellFlavor::Cmd.command("dir");
+        assert_eq!(command.get_program(), "cmd");
+        assert_eq!(command.get_args().collect::<Vec<_>>(), vec!["/C", "dir"]);
+    }
+}
@@ -5,7 +5,16 @@ use std::env;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use super::Tool;
+use super::audit_log::AuditedTool;
+use super::call_recorder::RecordingTool;
+use super::result_cache::{CachedTool, DEFAULT_CACHE_CAPACITY, InvalidatingTool, ToolResultCache};
+use super::tool_schema::ValidatedTool;
+use super::tool_timings::TimedTool;
+use super::{
+    FileChangeTracker, Tool, ToolAuditLog, ToolCallRecorder, ToolSchemaValidator, ToolTimings,
+};
+#[cfg(feature = "wasm-plugins")]
+use super::{ToolError, ToolResult, WasmSandbox, WasmTool};
 
 type ToolList = Arc<Vec<Arc<dyn Tool>>>;
 
@@ -14,24 +23,34 @@ type ToolList = Arc<Vec<Arc<dyn Tool>>>;
 pub struct ToolRegistry {
     tools: ToolList,
     workspace_root: PathBuf,
+    file_changes: FileChangeTracker,
+    tool_timings: ToolTimings,
+    call_recorder: Option<ToolCallRecorder>,
+    audit_log: Option<ToolAuditLog>,
+    result_cache: ToolResultCache,
+    schema_validator: Arc<ToolSchemaValidator>,
 }
 
 impl ToolRegistry {
     /// Create a new empty tool registry
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            tools: Arc::new(Vec::new()),
-            workspace_root: env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
-        }
+        Self::with_workspace(env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
     }
 
     /// Create a new tool registry with a specific workspace root
     #[must_use]
     pub fn with_workspace(workspace_root: impl Into<PathBuf>) -> Self {
+        let workspace_root = workspace_root.into();
         Self {
             tools: Arc::new(Vec::new()),
-            workspace_root: workspace_root.into(),
+            result_cache: ToolResultCache::new(&workspace_root, DEFAULT_CACHE_CAPACITY),
+            workspace_root,
+            file_changes: FileChangeTracker::new(),
+            tool_timings: ToolTimings::new(),
+            call_recorder: None,
+            audit_log: None,
+            schema_validator: Arc::new(ToolSchemaValidator::new()),
         }
     }
 
@@ -41,20 +60,125 @@ impl ToolRegistry {
         &self.workspace_root
     }
 
+    /// Get the tracker that file-changing tools of this registry record into
+    #[must_use]
+    pub const fn file_changes(&self) -> &FileChangeTracker {
+        &self.file_changes
+    }
+
+    /// Get the tracker the tools of this registry add their call durations to
+    #[must_use]
+    pub const fn tool_timings(&self) -> &ToolTimings {
+        &self.tool_timings
+    }
+
+    /// Record every call of the tools handed out by [`Self::get_tool`]
+    #[must_use]
+    pub fn with_call_recorder(mut self, recorder: ToolCallRecorder) -> Self {
+        self.call_recorder = Some(recorder);
+        self
+    }
+
+    /// Get the recorder tool calls go to, if any
+    #[must_use]
+    pub const fn call_recorder(&self) -> Option<&ToolCallRecorder> {
+        self.call_recorder.as_ref()
+    }
+
+    /// Append every call of the tools handed out by [`Self::get_tool`] to an audit log
+    #[must_use]
+    pub fn with_audit_log(mut self, audit_log: ToolAuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+
+    /// Cache up to `capacity` results of cacheable tools (0 disables caching)
+    #[must_use]
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.result_cache = ToolResultCache::new(&self.workspace_root, capacity);
+        self
+    }
+
+    /// Get the cache of tool results shared by the clones of this registry
+    #[must_use]
+    pub const fn result_cache(&self) -> &ToolResultCache {
+        &self.result_cache
+    }
+
     /// Add a tool to the registry
     #[must_use]
     pub fn with_tool(mut self, tool: Arc<dyn Tool>) -> Self {
-        Arc::make_mut(&mut self.tools).push(tool);
+        self.register(tool);
         self
     }
 
+    /// Load the WASM plugin at `path` as a tool
+    ///
+    /// The plugin runs without access to files, the network or environment
+    /// variables; use [`Self::load_wasm_plugin_with`] to grant them.
+    ///
+    /// # Errors
+    /// Returns error if the plugin cannot be loaded or a tool of its name is already registered
+    #[cfg(feature = "wasm-plugins")]
+    pub fn load_wasm_plugin(&mut self, path: &Path) -> ToolResult<()> {
+        self.load_wasm_plugin_with(path, WasmSandbox::default())
+    }
+
+    /// Load the WASM plugin at `path` as a tool with the capabilities of `sandbox`
+    ///
+    /// # Errors
+    /// Returns error if the plugin cannot be loaded or a tool of its name is already registered
+    #[cfg(feature = "wasm-plugins")]
+    pub fn load_wasm_plugin_with(&mut self, path: &Path, sandbox: WasmSandbox) -> ToolResult<()> {
+        let tool = WasmTool::load(path, sandbox)?;
+        if self
+            .tools
+            .iter()
+            .any(|existing| existing.name() == tool.name())
+        {
+            return Err(ToolError::Conflict(format!(
+                "A tool named '{}' is already registered",
+                tool.name()
+            )));
+        }
+        self.register(Arc::new(tool));
+        Ok(())
+    }
+
+    /// Adds `tool`, checking its future calls against its schema
+    fn register(&mut self, tool: Arc<dyn Tool>) {
+        Arc::make_mut(&mut self.schema_validator).register(tool.as_ref());
+        Arc::make_mut(&mut self.tools).push(tool);
+    }
+
     /// Get a tool by name, if it exists
+    ///
+    /// This is where tool calls are dispatched from: inputs are checked
+    /// against the tool's schema before it runs, cacheable tools answer
+    /// repeated calls from the result cache and file-changing tools evict
+    /// what they make stale, every call is timed, with an audit log the tool
+    /// logs each call, and with a call recorder it records them.
     #[must_use]
     pub fn get_tool(&self, name: &str) -> Option<Arc<dyn Tool>> {
-        self.tools
+        let mut tool = self
+            .tools
             .iter()
             .find(|tool_ref| tool_ref.name() == name)
-            .cloned()
+            .cloned()?;
+        tool = Arc::new(ValidatedTool::new(tool, Arc::clone(&self.schema_validator)));
+        tool = if tool.is_cacheable() {
+            Arc::new(CachedTool::new(tool, self.result_cache.clone()))
+        } else {
+            InvalidatingTool::wrap(tool, &self.result_cache)
+        };
+        tool = Arc::new(TimedTool::new(tool, self.tool_timings.clone()));
+        if let Some(audit_log) = &self.audit_log {
+            tool = Arc::new(AuditedTool::new(tool, audit_log.clone()));
+        }
+        if let Some(recorder) = &self.call_recorder {
+            tool = Arc::new(RecordingTool::new(tool, recorder.clone()));
+        }
+        Some(tool)
     }
 
     /// List all available tools
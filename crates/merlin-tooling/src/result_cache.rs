@@ -0,0 +1,437 @@
+//! Caching of the results of idempotent tools.
+//!
+//! A [`ToolRegistry`](crate::ToolRegistry) hands out cacheable tools (those
+//! whose [`Tool::is_cacheable`] is true, such as `readFile` and `listFiles`)
+//! wrapped so repeated calls with the same input are answered from an LRU
+//! cache. Writing, editing or deleting a file evicts the results for it and
+//! its parent directories, shell commands evict everything, and results whose
+//! path was modified on disk since they were cached are not used.
+
+use std::hash::{DefaultHasher, Hash as _, Hasher as _};
+use std::num::NonZeroUsize;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use lru::LruCache;
+use serde_json::Value;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use crate::{Tool, ToolInput, ToolOutput, ToolResult};
+
+/// Number of results cached when no capacity is configured
+pub const DEFAULT_CACHE_CAPACITY: usize = 256;
+
+/// Tools changing the file named by their `path` parameter
+const FILE_CHANGING_TOOLS: [&str; 3] = ["writeFile", "editFile", "deleteFile"];
+
+/// Tools that may change any file
+const WORKSPACE_CHANGING_TOOLS: [&str; 2] = ["bash", "git"];
+
+/// Tool name and hash of the input of a cached call
+type CacheKey = (String, u64);
+
+/// Cached results, `None` if caching is disabled
+type CacheEntries = Option<LruCache<CacheKey, CachedResult>>;
+
+/// A cached successful call
+struct CachedResult {
+    /// Input of the call, compared on lookup to rule out hash collisions
+    params: Value,
+    /// Workspace-relative path the call read
+    path: PathBuf,
+    /// Modification time of the path when the call was made
+    modified: Option<SystemTime>,
+    /// Output of the call
+    output: ToolOutput,
+}
+
+/// LRU cache of tool results, shared by the clones of a registry
+#[derive(Clone)]
+pub struct ToolResultCache {
+    /// Workspace root the cached paths are relative to
+    workspace_root: PathBuf,
+    /// Cached results
+    entries: Arc<Mutex<CacheEntries>>,
+}
+
+impl ToolResultCache {
+    /// Create a cache of up to `capacity` results for tools in `workspace_root`
+    ///
+    /// A capacity of 0 disables caching.
+    #[must_use]
+    pub fn new(workspace_root: impl Into<PathBuf>, capacity: usize) -> Self {
+        Self {
+            workspace_root: workspace_root.into(),
+            entries: Arc::new(Mutex::new(NonZeroUsize::new(capacity).map(LruCache::new))),
+        }
+    }
+
+    /// Number of cached results
+    pub async fn len(&self) -> usize {
+        self.entries.lock().await.as_ref().map_or(0, LruCache::len)
+    }
+
+    /// Whether no results are cached
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Cached output of a call, if its path has not been modified since
+    async fn get(&self, tool: &str, params: &Value) -> Option<ToolOutput> {
+        let key = (tool.to_owned(), hash_params(params));
+        let (path, modified) = {
+            let mut entries = self.entries.lock().await;
+            let entry = entries.as_mut()?.get(&key)?;
+            if entry.params != *params {
+                return None;
+            }
+            (entry.path.clone(), entry.modified)
+        };
+
+        if self.modified(&path).await == modified {
+            let mut entries = self.entries.lock().await;
+            return entries
+                .as_mut()?
+                .get(&key)
+                .map(|entry| entry.output.clone());
+        }
+        if let Some(entries) = self.entries.lock().await.as_mut() {
+            entries.pop(&key);
+        }
+        None
+    }
+
+    /// Cache the output of a call reading `path`, modified at `modified` before the call
+    async fn insert(
+        &self,
+        tool: &str,
+        params: Value,
+        cached: (PathBuf, Option<SystemTime>),
+        output: ToolOutput,
+    ) {
+        let (path, modified) = cached;
+        if let Some(entries) = self.entries.lock().await.as_mut() {
+            entries.put(
+                (tool.to_owned(), hash_params(&params)),
+                CachedResult {
+                    params,
+                    path,
+                    modified,
+                    output,
+                },
+            );
+        }
+    }
+
+    /// Evict the results for `path` and for listings of its parent directories
+    pub async fn invalidate_path(&self, path: &str) {
+        let changed = normalize(path);
+        if let Some(entries) = self.entries.lock().await.as_mut() {
+            let stale: Vec<CacheKey> = entries
+                .iter()
+                .filter(|(_, entry)| changed.starts_with(&entry.path))
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in stale {
+                entries.pop(&key);
+            }
+        }
+    }
+
+    /// Evict every cached result
+    pub async fn clear(&self) {
+        if let Some(entries) = self.entries.lock().await.as_mut() {
+            entries.clear();
+        }
+    }
+
+    /// Modification time of a workspace-relative path, if it exists
+    async fn modified(&self, path: &Path) -> Option<SystemTime> {
+        fs::metadata(self.workspace_root.join(path))
+            .await
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+/// Hash of a tool input
+fn hash_params(params: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    params.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// The `path` parameter of a tool input, given as a string, a field or the first element
+fn path_param(params: &Value) -> Option<&str> {
+    params
+        .as_str()
+        .or_else(|| params.get("path").and_then(Value::as_str))
+        .or_else(|| params.get(0).and_then(Value::as_str))
+}
+
+/// Workspace-relative path without `.` components, so `./src/lib.rs` matches `src/lib.rs`
+fn normalize(path: &str) -> PathBuf {
+    Path::new(path)
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+/// Tool answering repeated calls from the cache
+pub struct CachedTool {
+    /// Wrapped cacheable tool
+    inner: Arc<dyn Tool>,
+    /// Cache the results go to
+    cache: ToolResultCache,
+}
+
+impl CachedTool {
+    /// Wrap the cacheable tool `inner` so its results are cached in `cache`
+    pub fn new(inner: Arc<dyn Tool>, cache: ToolResultCache) -> Self {
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl Tool for CachedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn typescript_signature(&self) -> &str {
+        self.inner.typescript_signature()
+    }
+
+    fn schema(&self) -> Value {
+        self.inner.schema()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let name = self.inner.name();
+        if let Some(output) = self.cache.get(name, &input.params).await {
+            tracing::debug!("{name}: answered from the tool result cache");
+            return Ok(output);
+        }
+
+        let params = input.params.clone();
+        let path = normalize(path_param(&params).unwrap_or("."));
+        let modified = self.cache.modified(&path).await;
+        let output = self.inner.execute(input).await?;
+        if output.success {
+            self.cache
+                .insert(name, params, (path, modified), output.clone())
+                .await;
+        }
+        Ok(output)
+    }
+}
+
+/// Tool evicting the cached results its calls may make stale
+pub struct InvalidatingTool {
+    /// Wrapped file-changing tool
+    inner: Arc<dyn Tool>,
+    /// Cache to evict results from
+    cache: ToolResultCache,
+}
+
+impl InvalidatingTool {
+    /// Wrap `inner` if it changes files, so its calls evict results from `cache`
+    pub fn wrap(inner: Arc<dyn Tool>, cache: &ToolResultCache) -> Arc<dyn Tool> {
+        let name = inner.name();
+        if FILE_CHANGING_TOOLS.contains(&name) || WORKSPACE_CHANGING_TOOLS.contains(&name) {
+            Arc::new(Self {
+                inner,
+                cache: cache.clone(),
+            })
+        } else {
+            inner
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for InvalidatingTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn typescript_signature(&self) -> &str {
+        self.inner.typescript_signature()
+    }
+
+    fn schema(&self) -> Value {
+        self.inner.schema()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.inner.is_cacheable()
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner.is_interactive()
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let path = path_param(&input.params).map(ToOwned::to_owned);
+        let result = self.inner.execute(input).await;
+        // Evict even after failures, which may have changed files partway
+        match path {
+            Some(path) if FILE_CHANGING_TOOLS.contains(&self.inner.name()) => {
+                self.cache.invalidate_path(&path).await;
+            }
+            _ => self.cache.clear().await,
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BashTool, ListFilesTool, ReadFileTool, ToolRegistry, WriteFileTool};
+    use anyhow::{Result, anyhow};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    /// Cacheable tool counting its executions
+    struct CountingTool {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Tool for CountingTool {
+        fn name(&self) -> &'static str {
+            "count"
+        }
+
+        fn typescript_signature(&self) -> &'static str {
+            "declare function count(path: string): Promise<number>;"
+        }
+
+        fn is_cacheable(&self) -> bool {
+            true
+        }
+
+        async fn execute(&self, _input: ToolInput) -> ToolResult<ToolOutput> {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(ToolOutput::success_with_data("counted", json!(calls)))
+        }
+    }
+
+    /// Calls a registry tool with `params`, returning its data
+    ///
+    /// # Errors
+    /// Returns an error if the tool is missing or fails.
+    async fn call(registry: &ToolRegistry, tool: &str, params: Value) -> Result<Value> {
+        let tool = registry
+            .get_tool(tool)
+            .ok_or_else(|| anyhow!("{tool} not registered"))?;
+        Ok(tool
+            .execute(ToolInput { params })
+            .await?
+            .data
+            .unwrap_or(Value::Null))
+    }
+
+    /// Tests that repeated calls are answered from the cache, up to its capacity.
+    ///
+    /// # Errors
+    /// Returns an error if a tool call fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_repeated_calls_are_cached() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let calls = Arc::new(AtomicUsize::new(0));
+        let registry = ToolRegistry::with_workspace(temp_dir.path())
+            .with_cache_capacity(2)
+            .with_tool(Arc::new(CountingTool {
+                calls: Arc::clone(&calls),
+            }));
+
+        assert_eq!(call(&registry, "count", json!("a")).await?, json!(1));
+        assert_eq!(call(&registry, "count", json!("a")).await?, json!(1));
+        assert_eq!(call(&registry, "count", json!("b")).await?, json!(2));
+        // A third input evicts the least recently used one
+        assert_eq!(call(&registry, "count", json!("c")).await?, json!(3));
+        assert_eq!(registry.result_cache().len().await, 2);
+        assert_eq!(call(&registry, "count", json!("a")).await?, json!(4));
+
+        let uncached = ToolRegistry::with_workspace(temp_dir.path())
+            .with_cache_capacity(0)
+            .with_tool(Arc::new(CountingTool {
+                calls: Arc::clone(&calls),
+            }));
+        assert_eq!(call(&uncached, "count", json!("a")).await?, json!(5));
+        assert_eq!(call(&uncached, "count", json!("a")).await?, json!(6));
+        Ok(())
+    }
+
+    /// Tests that writes, shell commands and outside modifications evict stale results.
+    ///
+    /// # Errors
+    /// Returns an error if the workspace cannot be set up or a tool call fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_changes_invalidate_cached_results() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        fs::create_dir(temp_dir.path().join("src")).await?;
+        fs::write(temp_dir.path().join("src/lib.rs"), "one").await?;
+        let registry = ToolRegistry::with_workspace(temp_dir.path())
+            .with_tool(Arc::new(ReadFileTool::new(temp_dir.path())))
+            .with_tool(Arc::new(ListFilesTool::new(temp_dir.path())))
+            .with_tool(Arc::new(WriteFileTool::new(temp_dir.path())))
+            .with_tool(Arc::new(BashTool::default()));
+
+        assert_eq!(
+            call(&registry, "readFile", json!("src/lib.rs")).await?,
+            json!("one")
+        );
+        assert_eq!(
+            call(&registry, "listFiles", json!("src")).await?,
+            json!(["lib.rs"])
+        );
+        assert_eq!(registry.result_cache().len().await, 2);
+
+        let write = json!({ "path": "./src/main.rs", "content": "fn main() {}" });
+        call(&registry, "writeFile", write).await?;
+        assert_eq!(registry.result_cache().len().await, 1);
+        assert_eq!(
+            call(&registry, "listFiles", json!("src")).await?,
+            json!(["lib.rs", "main.rs"])
+        );
+
+        let command = format!(
+            "printf two > '{}'",
+            temp_dir.path().join("src/lib.rs").display()
+        );
+        call(&registry, "bash", json!(command)).await?;
+        assert!(registry.result_cache().is_empty().await);
+        assert_eq!(
+            call(&registry, "readFile", json!("src/lib.rs")).await?,
+            json!("two")
+        );
+
+        fs::write(
+            temp_dir.path().join("src/lib.rs"),
+            "three, changed elsewhere",
+        )
+        .await?;
+        assert_eq!(
+            call(&registry, "readFile", json!("src/lib.rs")).await?,
+            json!("three, changed elsewhere")
+        );
+        Ok(())
+    }
+}
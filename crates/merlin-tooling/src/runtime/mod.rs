@@ -20,11 +20,11 @@ use serde_json::Value;
 use tokio::task::spawn_blocking;
 use tokio::time;
 
-use crate::{Tool, ToolError, ToolResult};
+use crate::{Tool, ToolError, ToolResult, join_error};
 
 // Re-export for internal use
 pub use conversion::js_value_to_json;
-use promise::extract_promise_if_needed;
+use promise::{eval_error, extract_promise_if_needed};
 use tool_registration::register_tool_functions;
 use typescript::wrap_code;
 
@@ -95,14 +95,12 @@ impl TypeScriptRuntime {
             // Run in spawn_blocking since Boa context is !Send
             spawn_blocking(move || Self::execute_sync(&wrapped_code, &tools_clone))
                 .await
-                .map_err(|err| ToolError::ExecutionFailed(format!("Task join failed: {err}")))?
+                .map_err(|err| join_error("TypeScript execution", err))?
         })
         .await
-        .map_err(|_| {
-            ToolError::ExecutionFailed(format!(
-                "Execution timed out after {} seconds",
-                timeout.as_secs()
-            ))
+        .map_err(|_| ToolError::Timeout {
+            operation: "TypeScript execution".to_owned(),
+            seconds: timeout.as_secs(),
         })?;
 
         let total_time = exec_start.elapsed();
@@ -132,7 +130,7 @@ impl TypeScriptRuntime {
 
         // Register tools as global functions
         let reg_start = Instant::now();
-        register_tool_functions(&mut context, tools)?;
+        register_tool_functions(&mut context, tools, None)?;
         let reg_time = reg_start.elapsed();
 
         tracing::debug!("Executing JavaScript code");
@@ -141,7 +139,7 @@ impl TypeScriptRuntime {
         let eval_start = Instant::now();
         let result = context
             .eval(Source::from_bytes(code))
-            .map_err(|err| ToolError::ExecutionFailed(format!("JavaScript error: {err}")))?;
+            .map_err(|err| eval_error(&err, &mut context))?;
         let eval_time = eval_start.elapsed();
 
         // Run all pending jobs (resolve Promises)
@@ -184,7 +182,10 @@ impl Default for TypeScriptRuntime {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ReadFileTool;
     use anyhow::Result;
+    use serde_json::json;
+    use tempfile::TempDir;
 
     /// Tests TypeScript runtime initialization.
     ///
@@ -223,4 +224,50 @@ mod tests {
         assert_eq!(result, serde_json::json!(84));
         Ok(())
     }
+
+    /// Tests that agent code can branch on the code of a failed tool call.
+    ///
+    /// # Errors
+    /// Returns an error if the workspace cannot be created or execution fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_tool_error_code_reaches_agent_code() -> Result<()> {
+        let workspace = TempDir::new()?;
+        let mut runtime = TypeScriptRuntime::new();
+        runtime.register_tool(Arc::new(ReadFileTool::new(workspace.path())));
+
+        let caught = runtime
+            .execute(
+                "async function agent_code() {
+                    const codes = [];
+                    for (const args of [['missing.txt'], ['a.txt', 0]]) {
+                        try {
+                            await readFile(...args);
+                        } catch (error) {
+                            codes.push({ code: error.code, field: error.field ?? null });
+                        }
+                    }
+                    return codes;
+                }",
+            )
+            .await?;
+        assert_eq!(
+            caught,
+            json!([
+                { "code": "not_found", "field": null },
+                { "code": "invalid_arguments", "field": "start_line" },
+            ])
+        );
+
+        let uncaught = runtime
+            .execute("async function agent_code() { return await readFile('../secret.txt'); }")
+            .await;
+        assert!(
+            matches!(&uncaught, Err(ToolError::NotFound(message)) if message.contains("calling tool 'readFile'")),
+            "unexpected result: {uncaught:?}"
+        );
+        Ok(())
+    }
 }
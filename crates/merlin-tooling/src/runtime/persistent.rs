@@ -1,6 +1,28 @@
 //! Persistent TypeScript runtime with long-lived Boa context using `LocalSet`
+//!
+//! # State contract
+//!
+//! Every execution shares one global object, so any global assigned by agent
+//! code persists for the lifetime of the [`PersistentTypeScriptRuntime`]
+//! instance, until [`PersistentTypeScriptRuntime::reset_state`] clears it.
+//! Globals are properties of `globalThis`, whether assigned explicitly
+//! (`globalThis.seen = []`) or implicitly by assigning an undeclared name.
+//!
+//! Code defining `agent_code`, using `await` or returning a value runs inside
+//! a function, so its `let`, `const` and function declarations are local to
+//! that execution. Only bare expressions run at the top level, where their
+//! declarations persist like globals do.
+//!
+//! # Interactive tools
+//!
+//! Calls to [interactive](crate::Tool::is_interactive) tools return a pending
+//! promise, and the runtime awaits the tool after evaluating the code. Other
+//! tools block the thread, so only interactive ones let a UI running on the
+//! same thread answer them.
 
 use std::collections::{HashMap, VecDeque};
+use std::mem::take;
+use std::panic::{AssertUnwindSafe, catch_unwind};
 use std::sync::Arc;
 
 use boa_engine::{Context, JsValue, JsValue as BoaJsValue, Source};
@@ -11,16 +33,21 @@ use uuid::Uuid;
 use super::bulk_extraction::{self, ExtractedTaskList};
 use super::conversion::js_value_to_json_static;
 use super::handle::JsValueHandle;
-use super::tool_registration::register_tool_functions;
-use crate::{Tool, ToolError, ToolResult};
+use super::tool_registration::{DeferredCalls, register_tool_functions};
+use crate::{Tool, ToolError, ToolResult, recover_panic};
 
 /// Persistent TypeScript runtime with long-lived Boa context
 ///
 /// Uses Tokio's `LocalSet` to run `!Send` Boa `Context` in async context,
-/// eliminating IPC overhead while maintaining persistent state.
+/// eliminating IPC overhead while maintaining persistent state. See the
+/// module documentation for which state outlives an execution.
 pub struct PersistentTypeScriptRuntime {
     /// The Boa JavaScript context
     context: Context,
+    /// Tools registered in the context, kept to register them again on reset
+    tools: HashMap<String, Arc<dyn Tool>>,
+    /// Interactive tool calls waiting to be awaited
+    deferred: DeferredCalls,
     /// Storage for JavaScript values referenced by handles
     value_storage: HashMap<String, JsValue>,
     /// `LocalSet` for running `!Send` futures
@@ -44,13 +71,16 @@ impl PersistentTypeScriptRuntime {
         let mut context = Context::default();
 
         // Register tools
-        register_tool_functions(&mut context, tools)?;
+        let deferred = DeferredCalls::default();
+        register_tool_functions(&mut context, tools, Some(&deferred))?;
 
         // Pre-generate UUID pool (batch of 100)
         let uuid_pool: VecDeque<String> = (0..100).map(|_| Uuid::new_v4().to_string()).collect();
 
         Ok(Self {
             context,
+            tools: tools.clone(),
+            deferred,
             value_storage: HashMap::new(),
             local_set: LocalSet::new(),
             code_cache: HashMap::new(),
@@ -58,6 +88,21 @@ impl PersistentTypeScriptRuntime {
         })
     }
 
+    /// Clear every global and stored value, keeping the registered tools
+    ///
+    /// Handles returned before the reset no longer resolve.
+    ///
+    /// # Errors
+    /// Returns error if tool registration in the fresh context fails
+    pub fn reset_state(&mut self) -> ToolResult<()> {
+        let mut context = Context::default();
+        self.deferred.borrow_mut().clear();
+        register_tool_functions(&mut context, &self.tools, Some(&self.deferred))?;
+        self.context = context;
+        self.value_storage.clear();
+        Ok(())
+    }
+
     /// Get a UUID from the pool, refilling if needed
     ///
     /// # Panics
@@ -100,16 +145,16 @@ impl PersistentTypeScriptRuntime {
         // Run in LocalSet to allow !Send Context
         self.local_set
             .run_until(async {
-                // Execute code
-                let result = self
-                    .context
-                    .eval(Source::from_bytes(&wrapped_code))
-                    .map_err(|err| {
-                        ToolError::ExecutionFailed(format!("JavaScript error: {err}"))
-                    })?;
+                // Execute code, turning a panic inside the engine into a task failure
+                let result = catch_unwind(AssertUnwindSafe(|| {
+                    self.context.eval(Source::from_bytes(&wrapped_code))
+                }))
+                .map_err(|payload| recover_panic("JavaScript evaluation", payload))?
+                .map_err(|err| super::promise::eval_error(&err, &mut self.context))?;
 
-                // Run jobs (synchronous - tools block)
+                // Run jobs (synchronous - tools block), then await interactive tools
                 drop(self.context.run_jobs());
+                settle_deferred_calls(&self.deferred, &mut self.context).await;
 
                 // Extract promise if needed
                 let final_result =
@@ -191,6 +236,7 @@ impl PersistentTypeScriptRuntime {
 
                 // Run jobs to resolve Promises
                 drop(self.context.run_jobs());
+                settle_deferred_calls(&self.deferred, &mut self.context).await;
 
                 // Extract Promise value if needed
                 let final_result =
@@ -205,3 +251,72 @@ impl PersistentTypeScriptRuntime {
             .await
     }
 }
+
+/// Awaits interactive tool calls until none are left, running the jobs each result unblocks
+async fn settle_deferred_calls(deferred: &DeferredCalls, context: &mut Context) {
+    loop {
+        let calls = take(&mut *deferred.borrow_mut());
+        if calls.is_empty() {
+            break;
+        }
+        for call in calls {
+            call.settle(context).await;
+        }
+        drop(context.run_jobs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Runs `code` and converts its result to JSON
+    ///
+    /// # Errors
+    /// Returns error if execution or conversion fails
+    async fn run(runtime: &mut PersistentTypeScriptRuntime, code: &str) -> ToolResult<Value> {
+        let handle = runtime.execute(code).await?;
+        runtime.to_json(handle).await
+    }
+
+    /// Tests that globals persist between executions and locals do not.
+    ///
+    /// # Errors
+    /// Returns an error if the runtime cannot be created or code fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_globals_persist_between_executions() -> ToolResult<()> {
+        let mut runtime = PersistentTypeScriptRuntime::new(&HashMap::new())?;
+        let increment = "async function agent_code(): Promise<number> {\n    const step = 1;\n    globalThis.counter = (globalThis.counter ?? 0) + step;\n    return counter;\n}";
+
+        assert_eq!(run(&mut runtime, increment).await?, json!(1));
+        assert_eq!(run(&mut runtime, increment).await?, json!(2));
+        assert_eq!(run(&mut runtime, "typeof step").await?, json!("undefined"));
+        Ok(())
+    }
+
+    /// Tests that resetting the state clears globals and stored values.
+    ///
+    /// # Errors
+    /// Returns an error if the runtime cannot be created or code fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_reset_state_clears_globals() -> ToolResult<()> {
+        let mut runtime = PersistentTypeScriptRuntime::new(&HashMap::new())?;
+        let stale = runtime.execute("globalThis.counter = 5").await?;
+
+        runtime.reset_state()?;
+        assert_eq!(
+            run(&mut runtime, "typeof counter").await?,
+            json!("undefined")
+        );
+        let stale_result = runtime.to_json(stale).await;
+        assert!(stale_result.is_err(), "handles should not survive a reset");
+        Ok(())
+    }
+}
@@ -1,10 +1,26 @@
 //! Promise extraction and handling utilities.
 
 use boa_engine::property::Attribute;
-use boa_engine::{Context, JsValue, Source};
+use boa_engine::{Context, JsError, JsString, JsValue, Source};
+use serde_json::{Map, Value};
 
+use super::conversion::js_value_to_json_static;
 use crate::{ToolError, ToolResult};
 
+/// Properties of a thrown tool error that [`ToolError::from_data`] reads
+const TOOL_ERROR_KEYS: [&str; 10] = [
+    "code",
+    "message",
+    "field",
+    "expected",
+    "actual",
+    "operation",
+    "seconds",
+    "tool",
+    "exit_code",
+    "task",
+];
+
 /// Extract Promise value if the result is a Promise
 ///
 /// # Errors
@@ -72,10 +88,12 @@ pub fn extract_promise_if_needed(result: JsValue, context: &mut Context) -> Tool
             ToolError::ExecutionFailed(format!("Failed to check promise error: {err}"))
         })?;
     if !error_check.is_undefined() {
-        let error_msg = extract_error_message(&error_check, context);
-        return Err(ToolError::ExecutionFailed(format!(
-            "Promise rejected: {error_msg}"
-        )));
+        return Err(
+            extract_tool_error(&error_check, context).unwrap_or_else(|| {
+                let error_msg = extract_error_message(&error_check, context);
+                ToolError::ExecutionFailed(format!("Promise rejected: {error_msg}"))
+            }),
+        );
     }
 
     // Get the result
@@ -101,3 +119,31 @@ pub fn extract_error_message(error_check: &JsValue, context: &mut Context) -> St
         },
     )
 }
+
+/// Converts an error thrown while evaluating agent code into a `ToolError`
+///
+/// Tool errors left uncaught keep their variant; anything else is reported as
+/// a JavaScript error.
+pub fn eval_error(err: &JsError, context: &mut Context) -> ToolError {
+    let thrown = err.to_opaque(context);
+    extract_tool_error(&thrown, context)
+        .unwrap_or_else(|| ToolError::ExecutionFailed(format!("JavaScript error: {err}")))
+}
+
+/// Rebuilds the `ToolError` a failed tool call threw
+///
+/// Returns None if the value is not an object with a known `code`.
+fn extract_tool_error(thrown: &JsValue, context: &mut Context) -> Option<ToolError> {
+    let object = thrown.as_object()?;
+    let mut data = Map::new();
+    for key in TOOL_ERROR_KEYS {
+        let value = object.get(JsString::from(key), context).ok()?;
+        match js_value_to_json_static(&value, context).ok()? {
+            Value::Null => {}
+            json => {
+                data.insert(key.to_owned(), json);
+            }
+        }
+    }
+    ToolError::from_data(&data)
+}
@@ -5,28 +5,76 @@
 //! data object, NOT rejected Promises. This allows TypeScript code to inspect
 //! exit codes, error messages, and other failure details without try/catch.
 
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::Arc;
 use std::thread::scope;
 
-use boa_engine::JsNativeError;
-use boa_engine::{Context, JsResult, JsValue, NativeFunction};
-use serde_json::Value;
+use boa_engine::builtins::promise::ResolvingFunctions;
+use boa_engine::object::builtins::JsPromise;
+use boa_engine::{Context, JsError, JsNativeError, JsResult, JsString, JsValue, NativeFunction};
+use serde_json::{Map, Value};
 use tokio::runtime::Builder;
+use tracing::{Instrument as _, Level, Span, span};
 
 use super::conversion::{js_value_to_json_static, json_to_js_value_static};
-use crate::{Tool, ToolInput, ToolOutput, ToolResult};
+use crate::{Tool, ToolError, ToolInput, ToolOutput, ToolResult, recover_panic};
+
+/// Calls to [interactive](Tool::is_interactive) tools waiting to be awaited by the runtime
+pub type DeferredCalls = Rc<RefCell<Vec<DeferredCall>>>;
+
+/// Interactive tool call whose promise settles once the runtime awaits it
+pub struct DeferredCall {
+    /// Tool that was called
+    tool: Arc<dyn Tool>,
+    /// Parameters of the call
+    input: ToolInput,
+    /// Functions settling the promise returned to agent code
+    resolvers: ResolvingFunctions,
+}
+
+impl DeferredCall {
+    /// Await the tool and settle the promise returned to agent code with its result
+    pub async fn settle(self, context: &mut Context) {
+        let tool_name = self.tool.name();
+        let tool_span = span!(Level::INFO, "tool_call", tool_name);
+        let result = self.tool.execute(self.input).instrument(tool_span).await;
+        let settled = match result {
+            Ok(output) => output_to_js(output, context).and_then(|value| {
+                self.resolvers
+                    .resolve
+                    .call(&JsValue::undefined(), &[value], context)
+            }),
+            Err(err) => {
+                let error = tool_error_to_js(tool_name, &err, context).to_opaque(context);
+                self.resolvers
+                    .reject
+                    .call(&JsValue::undefined(), &[error], context)
+            }
+        };
+        if let Err(err) = settled {
+            tracing::warn!("Failed to settle the call to tool '{tool_name}': {err}");
+        }
+    }
+}
 
 /// Register tool functions in the JavaScript context
 ///
+/// Calls to [interactive](Tool::is_interactive) tools are pushed to `deferred`
+/// when given, returning a pending promise, so the caller can await them
+/// without blocking its thread. All other calls block until the tool finishes.
+///
 /// # Errors
 /// Returns error if registration fails
 pub fn register_tool_functions(
     context: &mut Context,
     tools: &HashMap<String, Arc<dyn Tool>>,
+    deferred: Option<&DeferredCalls>,
 ) -> ToolResult<()> {
     for (name, tool) in tools {
         let tool_clone = Arc::clone(tool);
+        let deferred = deferred.filter(|_| tool.is_interactive()).map(Rc::clone);
 
         #[allow(
             unsafe_code,
@@ -38,60 +86,31 @@ pub fn register_tool_functions(
             // 1. The tool registry is owned by TypeScriptRuntime which outlives the Context
             // 2. Tools are immutable and thread-safe (Arc)
             // 3. The closure only captures Arc which is safe to share
+            // 4. Resolvers in the deferred calls live outside the GC heap, so they stay rooted
             unsafe {
             NativeFunction::from_closure(move |_this, args, ctx| {
                 tracing::debug!("Tool '{}' called from JavaScript", tool_clone.name());
+                let input = tool_input(&tool_clone, args, ctx)?;
+
+                if let Some(deferred) = &deferred {
+                    let (promise, resolvers) = JsPromise::new_pending(ctx);
+                    deferred.borrow_mut().push(DeferredCall {
+                        tool: Arc::clone(&tool_clone),
+                        input,
+                        resolvers,
+                    });
+                    return Ok(promise.into());
+                }
 
-                // Get parameters - handle both object and positional argument patterns
-                let params = if args.is_empty() {
-                    serde_json::json!({})
-                } else if args.len() == 1 {
-                    // Single argument - could be object or simple value
-                    js_value_to_json_static(&args[0], ctx)?
-                } else {
-                    // Multiple arguments - convert to named params based on tool
-                    convert_positional_args(&tool_clone, args, ctx)?
-                };
-
-                // Create tool input
-                let input = ToolInput { params };
-
-                // Execute tool synchronously using thread::scope
-                let tool_clone_inner = Arc::clone(&tool_clone);
-                let result = scope(|scope_ctx| {
-                    scope_ctx
-                        .spawn(move || -> Result<ToolOutput, String> {
-                            // Create a new Tokio runtime for this tool execution
-                            let runtime = Builder::new_current_thread()
-                                .enable_all()
-                                .build()
-                                .map_err(|err| format!("Failed to create runtime: {err}"))?;
-
-                            runtime.block_on(async move {
-                                tool_clone_inner
-                                    .execute(input)
-                                    .await
-                                    .map_err(|err| format!("Tool execution failed: {err}"))
-                            })
-                        })
-                        .join()
-                        .map_err(|_| "Tool execution panicked".to_owned())?
-                })
-                .map_err(|err: String| JsNativeError::error().with_message(err))?;
-
-                // Convert result to JS value
-                // Always return the data, never reject the Promise
-                // Tools that fail (success=false) still return their data object
-                // so TypeScript can inspect exit codes, error messages, etc.
-                let data = result.data.unwrap_or(Value::String(result.message));
-                json_to_js_value_static(&data, ctx)
+                let result = execute_blocking(&tool_clone, input)
+                    .map_err(|err| tool_error_to_js(tool_clone.name(), &err, ctx))?;
+                output_to_js(result, ctx)
             })
         };
 
         context
             .register_global_callable(boa_engine::js_string!(name.as_str()), 0, func)
             .map_err(|err| {
-                use crate::ToolError;
                 ToolError::ExecutionFailed(format!("Failed to register tool '{name}': {err}"))
             })?;
     }
@@ -99,6 +118,113 @@ pub fn register_tool_functions(
     Ok(())
 }
 
+/// Builds the tool input from the arguments of a JavaScript call
+///
+/// # Errors
+/// Returns error if an argument cannot be converted
+fn tool_input(tool: &Arc<dyn Tool>, args: &[JsValue], ctx: &mut Context) -> JsResult<ToolInput> {
+    // Get parameters - handle both object and positional argument patterns
+    let params = if args.is_empty() {
+        serde_json::json!({})
+    } else if args.len() == 1 {
+        // Single argument - could be object or simple value
+        js_value_to_json_static(&args[0], ctx)?
+    } else {
+        // Multiple arguments - convert to named params based on tool
+        convert_positional_args(tool, args, ctx)?
+    };
+    Ok(ToolInput { params })
+}
+
+/// Executes the tool on its own thread and runtime, blocking until it finishes
+///
+/// # Errors
+/// Returns error if the tool fails, panics or its runtime cannot be created
+fn execute_blocking(tool: &Arc<dyn Tool>, input: ToolInput) -> ToolResult<ToolOutput> {
+    // The tool thread has no current span, so parent its span explicitly
+    let parent_span = Span::current();
+    scope(|scope_ctx| {
+        scope_ctx
+            .spawn(move || -> ToolResult<ToolOutput> {
+                // Create a new Tokio runtime for this tool execution
+                let runtime =
+                    Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .map_err(|err| {
+                            ToolError::ExecutionFailed(format!("Failed to create runtime: {err}"))
+                        })?;
+
+                let tool_name = tool.name();
+                let tool_span = span!(parent: &parent_span, Level::INFO, "tool_call", tool_name);
+                runtime.block_on(tool.execute(input).instrument(tool_span))
+            })
+            .join()
+            .map_err(|payload| recover_panic(&format!("Tool '{}'", tool.name()), payload))?
+    })
+}
+
+/// Converts a tool's output to the value returned to agent code
+///
+/// Always returns the data, never rejects: tools that fail (success=false)
+/// still return their data object so TypeScript can inspect exit codes,
+/// error messages, etc.
+///
+/// # Errors
+/// Returns error if the data cannot be converted
+fn output_to_js(output: ToolOutput, ctx: &mut Context) -> JsResult<JsValue> {
+    let data = output.data.unwrap_or(Value::String(output.message));
+    json_to_js_value_static(&data, ctx)
+}
+
+/// Converts a failed tool call into the JavaScript `Error` thrown to agent code
+///
+/// The message names the tool, and the error carries the `code`, `field` and
+/// other details of [`ToolOutput::from_error`] so agent code can branch on them.
+fn tool_error_to_js(tool_name: &str, err: &ToolError, ctx: &mut Context) -> JsError {
+    let error = JsNativeError::error()
+        .with_message(format!(
+            "calling tool '{tool_name}': {}",
+            err.user_message()
+        ))
+        .to_opaque(ctx);
+    if let Some(Value::Object(details)) = ToolOutput::from_error(err).data {
+        for (key, value) in details.into_iter().filter(|(key, _)| key != "message") {
+            if let Ok(js_value) = json_to_js_value_static(&value, ctx) {
+                let _set = error.set(JsString::from(key.as_str()), js_value, false, ctx);
+            }
+        }
+    }
+    JsError::from_opaque(error.into())
+}
+
+/// Converts `requestContext(pattern | { symbol }, reason, max_files?)` arguments to named parameters
+///
+/// # Errors
+/// Returns error if conversion fails
+fn context_request_params(args: &[JsValue], ctx: &mut Context) -> JsResult<Value> {
+    let target = js_value_to_json_static(&args[0], ctx)?;
+    let reason = if args.len() > 1 {
+        js_value_to_json_static(&args[1], ctx)?
+    } else {
+        serde_json::json!("")
+    };
+    let max_files = if args.len() > 2 {
+        js_value_to_json_static(&args[2], ctx)?
+    } else {
+        serde_json::json!(5) // Default max_files
+    };
+
+    let mut params = if target.is_object() {
+        target
+    } else {
+        serde_json::json!({ "pattern": target })
+    };
+    params["reason"] = reason;
+    params["max_files"] = max_files;
+    Ok(params)
+}
+
 /// Convert positional arguments to named parameters based on tool type
 ///
 /// # Errors
@@ -109,26 +235,7 @@ fn convert_positional_args(
     ctx: &mut Context,
 ) -> JsResult<Value> {
     match tool.name() {
-        "requestContext" => {
-            // requestContext(pattern, reason, max_files?)
-            let pattern = js_value_to_json_static(&args[0], ctx)?;
-            let reason = if args.len() > 1 {
-                js_value_to_json_static(&args[1], ctx)?
-            } else {
-                serde_json::json!("")
-            };
-            let max_files = if args.len() > 2 {
-                js_value_to_json_static(&args[2], ctx)?
-            } else {
-                serde_json::json!(5) // Default max_files
-            };
-
-            Ok(serde_json::json!({
-                "pattern": pattern,
-                "reason": reason,
-                "max_files": max_files
-            }))
-        }
+        "requestContext" => context_request_params(args, ctx),
         "writeFile" => {
             // writeFile(path, content)
             if args.len() < 2 {
@@ -174,9 +281,82 @@ fn convert_positional_args(
                 "replace_all": replace_all
             }))
         }
+        // readFile(path, start_line?, end_line?) or readFile(path, { start_line, end_line })
+        "readFile" if args.get(1).is_some_and(JsValue::is_object) => {
+            with_options("path", args, ctx)
+        }
+        "readFile" => Ok(Value::Object(positional_args(
+            &["path", "start_line", "end_line"],
+            args,
+            ctx,
+        )?)),
+        // findFiles(pattern, options?)
+        "findFiles" => with_options("pattern", args, ctx),
+        // searchSymbols(symbol_name, options?)
+        "searchSymbols" => with_options("symbol_name", args, ctx),
+        // findCallers(symbol, file, line)
+        "findCallers" => named_args("findCallers", &["symbol", "file", "line"], args, ctx),
+        // askUser(question, options?)
+        "askUser" => Ok(Value::Object(positional_args(
+            &["question", "options"],
+            args,
+            ctx,
+        )?)),
         _ => {
             // For other tools, take first argument as params
             js_value_to_json_static(&args[0], ctx)
         }
     }
 }
+
+/// Adds the first argument as `name` to the options object passed second
+///
+/// # Errors
+/// Returns error if an argument cannot be converted
+fn with_options(name: &str, args: &[JsValue], ctx: &mut Context) -> JsResult<Value> {
+    let first = js_value_to_json_static(&args[0], ctx)?;
+    let mut params = match js_value_to_json_static(&args[1], ctx)? {
+        Value::Object(options) => options,
+        _ => Map::new(),
+    };
+    params.insert(name.to_owned(), first);
+    Ok(Value::Object(params))
+}
+
+/// Maps positional arguments to named parameters, requiring all of them
+///
+/// # Errors
+/// Returns error if an argument is missing or cannot be converted
+fn named_args(
+    tool_name: &str,
+    names: &[&str],
+    args: &[JsValue],
+    ctx: &mut Context,
+) -> JsResult<Value> {
+    if args.len() < names.len() {
+        return Err(JsNativeError::error()
+            .with_message(format!(
+                "{tool_name} requires {} arguments: {}",
+                names.len(),
+                names.join(", ")
+            ))
+            .into());
+    }
+    Ok(Value::Object(positional_args(names, args, ctx)?))
+}
+
+/// Maps the given positional arguments to named parameters, leaving out missing ones
+///
+/// # Errors
+/// Returns error if an argument cannot be converted
+fn positional_args(
+    names: &[&str],
+    args: &[JsValue],
+    ctx: &mut Context,
+) -> JsResult<Map<String, Value>> {
+    let mut params = Map::new();
+    for (name, arg) in names.iter().zip(args) {
+        params.insert((*name).to_owned(), js_value_to_json_static(arg, ctx)?);
+    }
+    Ok(params)
+}
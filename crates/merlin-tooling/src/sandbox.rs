@@ -0,0 +1,85 @@
+//! Confining tool paths to the workspace root.
+//!
+//! Tools take paths relative to the workspace root. The root and the resolved
+//! path are both canonicalized before they are compared, so neither `..`
+//! segments nor symlinks can reach outside the root.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{ToolError, ToolResult, canonicalize, native_path};
+
+/// Canonicalizes the workspace `root`
+///
+/// # Errors
+/// Returns an error if the root does not exist
+pub fn canonical_root(root: &Path) -> ToolResult<PathBuf> {
+    canonicalize(root).map_err(|err| ToolError::io("Invalid root directory", &err))
+}
+
+/// Resolves `path` relative to `root` to the canonical path of an existing entry inside it
+///
+/// `kind` names the entry in the error for a missing path, e.g. `File` or `Directory`.
+///
+/// # Errors
+/// Returns an error if the path does not exist or is outside the root
+pub fn resolve_existing(root: &Path, path: &str, kind: &str) -> ToolResult<PathBuf> {
+    let canonical_root = canonical_root(root)?;
+    let full_path = root.join(native_path(path));
+    if !full_path.exists() {
+        return Err(ToolError::NotFound(format!(
+            "{kind} does not exist: {path}"
+        )));
+    }
+    let canonical_path = canonicalize(&full_path)
+        .map_err(|err| ToolError::io(format!("Invalid path '{path}'"), &err))?;
+    ensure_within(&canonical_root, &canonical_path, path)?;
+    Ok(canonical_path)
+}
+
+/// Resolves `path` relative to `root` for a file that may not exist yet
+///
+/// Missing parent directories are created once the closest existing one is
+/// known to be inside the root. Returns the joined, non-canonical path.
+///
+/// # Errors
+/// Returns an error if the path has no parent, the parent cannot be created,
+/// or it is outside the root
+pub fn resolve_for_write(root: &Path, path: &str) -> ToolResult<PathBuf> {
+    let canonical_root = canonical_root(root)?;
+    let full_path = root.join(native_path(path));
+    let parent = full_path
+        .parent()
+        .ok_or_else(|| ToolError::invalid_argument("path", format!("Invalid path: {path}")))?;
+
+    if !parent.exists() {
+        let existing = parent
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .unwrap_or(root);
+        let canonical_existing = canonicalize(existing)
+            .map_err(|err| ToolError::io(format!("Invalid parent directory for '{path}'"), &err))?;
+        ensure_within(&canonical_root, &canonical_existing, path)?;
+        fs::create_dir_all(parent)
+            .map_err(|err| ToolError::io("Failed to create parent directories", &err))?;
+    }
+
+    let canonical_parent = canonicalize(parent)
+        .map_err(|err| ToolError::io(format!("Invalid parent directory for '{path}'"), &err))?;
+    ensure_within(&canonical_root, &canonical_parent, path)?;
+    Ok(full_path)
+}
+
+/// Checks that `canonical_path`, resolved from `path`, is inside `canonical_root`
+///
+/// # Errors
+/// Returns a sandbox violation if the path is outside the root
+pub fn ensure_within(canonical_root: &Path, canonical_path: &Path, path: &str) -> ToolResult<()> {
+    if canonical_path.starts_with(canonical_root) {
+        Ok(())
+    } else {
+        Err(ToolError::SandboxViolation(format!(
+            "Path '{path}' is outside the allowed directory"
+        )))
+    }
+}
@@ -0,0 +1,124 @@
+//! Shell commands started by tools, killed on shutdown.
+//!
+//! Tools run on their own thread and runtime, so dropping the task that called
+//! a tool does not stop the command it started. Every running command is
+//! registered in the [`RunningCommands`] of the bash tool that started it
+//! instead, and [`RunningCommands::kill_all`] kills each of them with the
+//! processes they started. Each owner (e.g. a shutdown coordinator) hands its
+//! own registry to its tools, so killing one owner's commands leaves the
+//! commands of other owners in the process running. On Unix commands run in
+//! their own process group so pipelines and background jobs are killed along
+//! with the shell.
+
+use std::collections::HashSet;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Registry of the shell commands a set of tools is running
+///
+/// Clones share the same registry.
+#[derive(Debug, Clone, Default)]
+pub struct RunningCommands {
+    /// Process ids of the shell commands currently running
+    pids: Arc<Mutex<HashSet<u32>>>,
+}
+
+impl RunningCommands {
+    /// Registers the shell command with process id `pid`
+    pub fn register(&self, pid: u32) -> RunningCommand {
+        self.pids
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(pid);
+        RunningCommand {
+            pid,
+            finished: false,
+            registry: self.clone(),
+        }
+    }
+
+    /// Kills every registered shell command, with the processes it started
+    ///
+    /// Returns the number of commands killed.
+    pub fn kill_all(&self) -> usize {
+        let pids: Vec<u32> = self
+            .pids
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain()
+            .collect();
+        for pid in &pids {
+            kill_process_tree(*pid);
+        }
+        if !pids.is_empty() {
+            tracing::info!("Killed {} running shell command(s)", pids.len());
+        }
+        pids.len()
+    }
+
+    /// Number of shell commands currently running
+    pub fn len(&self) -> usize {
+        self.pids
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns whether no shell command is running
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Registration of a running shell command
+///
+/// Dropped before [`RunningCommand::finish`], e.g. when the tool call is
+/// cancelled, it kills the command and the processes it started.
+#[derive(Debug)]
+pub struct RunningCommand {
+    /// Process id of the shell, which is also its process group on Unix
+    pid: u32,
+    /// Whether the command exited on its own
+    finished: bool,
+    /// Registry the command is registered in
+    registry: RunningCommands,
+}
+
+impl RunningCommand {
+    /// Unregisters the command once it has exited
+    pub fn finish(mut self) {
+        self.finished = true;
+    }
+}
+
+impl Drop for RunningCommand {
+    fn drop(&mut self) {
+        let registered = self
+            .registry
+            .pids
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.pid);
+        if registered && !self.finished {
+            kill_process_tree(self.pid);
+        }
+    }
+}
+
+/// Kills the process `pid` and every process it started
+fn kill_process_tree(pid: u32) {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("taskkill");
+        command.args(["/T", "/F", "/PID", &pid.to_string()]);
+        command
+    } else {
+        let mut command = Command::new("kill");
+        command.args(["-KILL", "--", &format!("-{pid}")]);
+        command
+    };
+    match command.stdout(Stdio::null()).stderr(Stdio::null()).status() {
+        Ok(status) if status.success() => tracing::debug!("Killed shell command {pid}"),
+        Ok(_) => tracing::debug!("Shell command {pid} already exited"),
+        Err(err) => tracing::warn!("Failed to kill shell command {pid}: {err}"),
+    }
+}
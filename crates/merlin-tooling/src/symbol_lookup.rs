@@ -0,0 +1,419 @@
+//! Symbol definition lookup for context requests.
+//!
+//! A [`SymbolResolver`] maps a symbol name to the line spans of its
+//! definitions. The language index provides one; [`GrepSymbolResolver`] is the
+//! fallback for projects or languages it does not cover, locating definitions
+//! by their keyword and finding their end by brace matching (or indentation
+//! for Python).
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use ignore::WalkBuilder;
+use regex::{Regex, escape};
+use tokio::task::spawn_blocking;
+
+use crate::find_tool::IGNORE_FILE;
+use crate::{ToolError, ToolResult, join_error};
+
+/// Files larger than this are skipped by the grep fallback (bytes)
+const MAX_GREP_FILE_SIZE: u64 = 1_000_000;
+
+/// Modifiers that may precede a definition keyword
+const MODIFIERS: &str = r#"(?:(?:pub(?:\([^)]*\))?|export|default|declare|async|unsafe|extern(?:\s+"[^"]*")?|static|abstract|public|private|protected|final)\s+)*"#;
+
+/// A symbol name, optionally qualified by the type defining it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SymbolName {
+    /// Type the symbol is a member of, e.g. `ValidationPipeline` in `ValidationPipeline.validate`
+    pub parent: Option<String>,
+    /// Name of the symbol itself
+    pub name: String,
+}
+
+impl SymbolName {
+    /// Parses `name`, `Type.member` or `Type::member`
+    ///
+    /// Only the last qualifier is kept, so `module::Type::member` names `Type::member`.
+    #[must_use]
+    pub fn parse(symbol: &str) -> Self {
+        let symbol = symbol.trim();
+        let separator = symbol
+            .rfind("::")
+            .map(|index| (index, 2))
+            .into_iter()
+            .chain(symbol.rfind('.').map(|index| (index, 1)))
+            .max_by_key(|(index, _)| *index);
+        let Some((index, length)) = separator else {
+            return Self {
+                parent: None,
+                name: symbol.to_owned(),
+            };
+        };
+
+        let qualifier = &symbol[..index];
+        let parent = qualifier
+            .rsplit("::")
+            .next()
+            .and_then(|last| last.rsplit('.').next())
+            .filter(|parent| !parent.is_empty())
+            .map(ToOwned::to_owned);
+        Self {
+            parent,
+            name: symbol[index + length..].to_owned(),
+        }
+    }
+
+    /// Returns true if a chunk identifier such as `class User` or `User.save` names this symbol
+    #[must_use]
+    pub fn matches_identifier(&self, identifier: &str) -> bool {
+        let qualified = identifier.rsplit(' ').next().unwrap_or(identifier);
+        self.parent.as_ref().map_or_else(
+            || qualified == self.name || qualified.ends_with(&format!(".{}", self.name)),
+            |parent| qualified == format!("{parent}.{}", self.name),
+        )
+    }
+}
+
+impl Display for SymbolName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.parent {
+            Some(parent) => write!(f, "{parent}.{}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// Line span of a symbol's definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolDefinition {
+    /// Symbol kind, e.g. `Function`, `Method` or `Trait`
+    pub kind: String,
+    /// File containing the definition
+    pub file: PathBuf,
+    /// First line, including leading doc comments and attributes (1-indexed)
+    pub start_line: usize,
+    /// Last line (1-indexed)
+    pub end_line: usize,
+}
+
+/// Resolves symbol names to their definitions
+#[async_trait]
+pub trait SymbolResolver: Send + Sync {
+    /// Returns the definitions of `symbol`, best matches first
+    ///
+    /// # Errors
+    /// Returns an error if the lookup cannot be performed
+    async fn resolve_symbol(&self, symbol: &SymbolName) -> ToolResult<Vec<SymbolDefinition>>;
+}
+
+/// Resolver locating definitions by searching the project's files
+#[derive(Debug, Clone)]
+pub struct GrepSymbolResolver {
+    /// Root of the searched project
+    project_root: PathBuf,
+}
+
+impl GrepSymbolResolver {
+    /// Creates a resolver searching the files under `project_root`
+    #[must_use]
+    pub const fn new(project_root: PathBuf) -> Self {
+        Self { project_root }
+    }
+}
+
+#[async_trait]
+impl SymbolResolver for GrepSymbolResolver {
+    async fn resolve_symbol(&self, symbol: &SymbolName) -> ToolResult<Vec<SymbolDefinition>> {
+        let root = self.project_root.clone();
+        let symbol = symbol.clone();
+        spawn_blocking(move || grep_definitions(&root, &symbol))
+            .await
+            .map_err(|err| join_error("symbol search", err))?
+    }
+}
+
+/// Searches the files under `root` for definitions of `symbol`
+///
+/// # Errors
+/// Returns an error if the definition patterns cannot be built
+fn grep_definitions(root: &Path, symbol: &SymbolName) -> ToolResult<Vec<SymbolDefinition>> {
+    let definition = Regex::new(&format!(
+        r"^\s*{MODIFIERS}(fn|struct|enum|union|trait|type|mod|class|interface|def|func|function|const|static|let|var)\s+(?:\([^)]*\)\s*)?{}\b",
+        escape(&symbol.name)
+    ))
+    .map_err(|err| ToolError::invalid_argument("symbol", format!("Invalid symbol name: {err}")))?;
+    let parent_block = symbol
+        .parent
+        .as_ref()
+        .map(|parent| {
+            Regex::new(&format!(
+                r"^\s*{MODIFIERS}(?:impl(?:<[^>]*>)?\s+(?:\S+\s+for\s+)?|struct\s+|enum\s+|trait\s+|class\s+|interface\s+|type\s+)[\w:]*?{}\b",
+                escape(parent)
+            ))
+        })
+        .transpose()
+        .map_err(|err| ToolError::invalid_argument("symbol", format!("Invalid symbol name: {err}")))?;
+
+    let walker = WalkBuilder::new(root)
+        .hidden(true)
+        .git_ignore(true)
+        .git_global(false)
+        .git_exclude(false)
+        .require_git(false)
+        .add_custom_ignore_filename(IGNORE_FILE)
+        .sort_by_file_name(Ord::cmp)
+        .build();
+
+    let mut definitions = Vec::new();
+    for entry in walker.filter_map(Result::ok) {
+        let path = entry.path();
+        let small_file = entry
+            .metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.len() <= MAX_GREP_FILE_SIZE);
+        if !small_file {
+            continue;
+        }
+        let Ok(source) = read_to_string(path) else {
+            continue;
+        };
+        let lines: Vec<&str> = source.lines().collect();
+        let indented = path.extension().is_some_and(|extension| extension == "py");
+        let parent_spans: Vec<(usize, usize)> = parent_block
+            .as_ref()
+            .map(|block| {
+                lines
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, line)| block.is_match(line))
+                    .map(|(index, _)| (index, definition_end(&lines, index, indented)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        for (index, line) in lines.iter().enumerate() {
+            let Some(captures) = definition.captures(line) else {
+                continue;
+            };
+            // Go methods name their type in the receiver, others are nested in it
+            let in_parent = parent_spans
+                .iter()
+                .any(|(start, end)| *start < index && index <= *end)
+                || symbol
+                    .parent
+                    .as_ref()
+                    .is_some_and(|parent| line.contains(parent.as_str()));
+            if parent_block.is_some() && !in_parent {
+                continue;
+            }
+
+            let keyword = captures.get(1).map_or("", |keyword| keyword.as_str());
+            definitions.push(SymbolDefinition {
+                kind: keyword_kind(keyword, in_parent).to_owned(),
+                file: path.to_path_buf(),
+                start_line: with_leading_comments(&lines, index) + 1,
+                end_line: definition_end(&lines, index, indented) + 1,
+            });
+        }
+    }
+    Ok(definitions)
+}
+
+/// Symbol kind named like the language index's kinds
+fn keyword_kind(keyword: &str, member: bool) -> &'static str {
+    match keyword {
+        "fn" | "def" | "func" | "function" if member => "Method",
+        "fn" | "def" | "func" | "function" => "Function",
+        "struct" | "class" | "union" => "Struct",
+        "trait" | "interface" => "Trait",
+        "enum" => "Enum",
+        "type" => "Type",
+        "mod" => "Module",
+        "const" | "static" => "Constant",
+        _ => "Variable",
+    }
+}
+
+/// Index of the first line of the comments and attributes directly above `index`
+fn with_leading_comments(lines: &[&str], index: usize) -> usize {
+    let mut start = index;
+    while start > 0 {
+        let line = lines[start - 1].trim_start();
+        let attached = ["//", "#", "/*", "*", "@"]
+            .iter()
+            .any(|prefix| line.starts_with(prefix));
+        if !attached {
+            break;
+        }
+        start -= 1;
+    }
+    start
+}
+
+/// Index of the last line of the definition starting at `index`
+///
+/// Indented definitions end before the next non-blank line indented no deeper
+/// than their first line. Others end where the braces opened after the first
+/// line are balanced, or at the first `;` if the definition has no body.
+/// Braces in strings and `//` comments are ignored.
+fn definition_end(lines: &[&str], index: usize, indented: bool) -> usize {
+    if indented {
+        let indent = indentation(lines[index]);
+        let mut end = index;
+        for (offset, line) in lines.iter().enumerate().skip(index + 1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if indentation(line) <= indent {
+                break;
+            }
+            end = offset;
+        }
+        return end;
+    }
+
+    let mut depth = 0usize;
+    let mut opened = false;
+    for (offset, line) in lines.iter().enumerate().skip(index) {
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut previous = ' ';
+        for character in line.chars() {
+            if in_string {
+                match character {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match character {
+                '/' if previous == '/' => break,
+                '"' => in_string = true,
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth = depth.saturating_sub(1),
+                ';' if !opened => return offset,
+                _ => {}
+            }
+            if opened && depth == 0 {
+                return offset;
+            }
+            previous = character;
+        }
+    }
+    lines.len().saturating_sub(1).max(index)
+}
+
+/// Number of leading whitespace characters
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use std::fs::write;
+    use tempfile::TempDir;
+
+    /// Tests parsing plain and qualified symbol names.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_symbol_name_parse() {
+        let method = SymbolName::parse("crate::validator::ValidationPipeline::validate");
+        assert_eq!(method.parent.as_deref(), Some("ValidationPipeline"));
+        assert_eq!(method.name, "validate");
+        assert_eq!(method.to_string(), "ValidationPipeline.validate");
+        assert_eq!(SymbolName::parse("User.save"), method_of("User", "save"));
+        assert_eq!(SymbolName::parse(" Config ").parent, None);
+
+        assert!(method_of("User", "save").matches_identifier("def User.save"));
+        assert!(!method_of("User", "save").matches_identifier("def Admin.save"));
+        assert!(SymbolName::parse("save").matches_identifier("Admin.save"));
+        assert!(SymbolName::parse("User").matches_identifier("class User"));
+    }
+
+    fn method_of(parent: &str, name: &str) -> SymbolName {
+        SymbolName {
+            parent: Some(parent.to_owned()),
+            name: name.to_owned(),
+        }
+    }
+
+    /// Tests that the grep fallback finds Rust methods and traits with their doc comments.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be created or the search fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_grep_resolver_finds_rust_definitions() -> Result<()> {
+        let dir = TempDir::new()?;
+        write(
+            dir.path().join("lib.rs"),
+            "/// A pipeline\npub struct Pipeline;\n\nimpl Pipeline {\n    /// Runs every stage\n    pub async fn validate(&self) -> bool {\n        let braces = \"}\";\n        true\n    }\n}\n\n/// A validation stage\npub trait Stage: Send {\n    fn name(&self) -> &str;\n}\n\nfn validate() {}\n",
+        )?;
+        let resolver = GrepSymbolResolver::new(dir.path().to_path_buf());
+
+        let method = resolver
+            .resolve_symbol(&SymbolName::parse("Pipeline::validate"))
+            .await?;
+        assert_eq!(method.len(), 1);
+        assert_eq!(method[0].kind, "Method");
+        assert_eq!((method[0].start_line, method[0].end_line), (5, 9));
+
+        let stage = resolver.resolve_symbol(&SymbolName::parse("Stage")).await?;
+        assert_eq!(stage.len(), 1);
+        assert_eq!(stage[0].kind, "Trait");
+        assert_eq!((stage[0].start_line, stage[0].end_line), (12, 15));
+
+        let unqualified = resolver
+            .resolve_symbol(&SymbolName::parse("validate"))
+            .await?;
+        assert_eq!(unqualified.len(), 2);
+        assert_eq!(
+            (unqualified[1].start_line, unqualified[1].end_line),
+            (17, 17)
+        );
+        Ok(())
+    }
+
+    /// Tests that Python definitions end with their indented body.
+    ///
+    /// # Errors
+    /// Returns an error if the project cannot be created or the search fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_grep_resolver_uses_indentation_for_python() -> Result<()> {
+        let dir = TempDir::new()?;
+        write(
+            dir.path().join("account.py"),
+            "class Account:\n    @property\n    def balance(self):\n        total = 0\n\n        return total\n\n    def close(self):\n        pass\n",
+        )?;
+        let resolver = GrepSymbolResolver::new(dir.path().to_path_buf());
+
+        let balance = resolver
+            .resolve_symbol(&SymbolName::parse("Account.balance"))
+            .await?;
+        assert_eq!(balance.len(), 1);
+        assert_eq!((balance[0].start_line, balance[0].end_line), (2, 6));
+        assert!(
+            resolver
+                .resolve_symbol(&SymbolName::parse("Missing"))
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+//! Errors returned by tools, with the stable codes agent code branches on.
+
+use std::fmt::Display;
+use std::io::{Error as IoError, ErrorKind};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Error as SerdeJsonError, Map, Value, json};
+use thiserror::Error;
+
+/// Errors that can occur during tool execution.
+///
+/// Every variant has a stable [`code`](Self::code) that agent code receives on
+/// the error thrown by a failed tool call, so it can branch on the kind of
+/// failure instead of parsing messages.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Error)]
+pub enum ToolError {
+    /// The file, directory, symbol or other target does not exist.
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// The operating system denied access to a file or directory.
+    #[error("Permission denied: {0}")]
+    PermissionDenied(String),
+
+    /// The arguments were missing, malformed or out of range.
+    #[error("Invalid arguments: {message}")]
+    InvalidArguments {
+        /// Parameter the problem is with (None if it concerns the arguments as a whole)
+        field: Option<String>,
+        /// What is wrong with the arguments
+        message: String,
+    },
+
+    /// A parameter did not match the tool's [schema](Tool::schema).
+    ///
+    /// Shares the `invalid_arguments` code with [`InvalidArguments`](Self::InvalidArguments).
+    #[error(
+        "Invalid input for {tool_name}: `{field_name}` must be {expected_type}, got {actual_value}"
+    )]
+    InvalidInput {
+        /// Name of the tool that was called
+        tool_name: String,
+        /// Path of the parameter (e.g. `options.max_results` or `paths[1]`)
+        field_name: String,
+        /// What the schema expects there (e.g. `string` or `integer or null`)
+        expected_type: String,
+        /// The value passed, as JSON (`undefined` if it was missing)
+        actual_value: String,
+    },
+
+    /// The operation clashes with the current state (e.g. an ambiguous edit or an existing branch).
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// The operation did not finish in time.
+    #[error("{operation} timed out after {seconds} seconds")]
+    Timeout {
+        /// What was running when the time ran out
+        operation: String,
+        /// Time limit that elapsed, in seconds
+        seconds: u64,
+    },
+
+    /// The operation would reach outside the workspace.
+    #[error("Sandbox violation: {0}")]
+    SandboxViolation(String),
+
+    /// An I/O operation failed.
+    #[error("IO error: {0}")]
+    Io(String),
+
+    /// The tool failed to execute its operation.
+    #[error("Tool execution failed: {0}")]
+    ExecutionFailed(String),
+
+    /// An external command run by the tool could not be started or failed.
+    #[error("{tool_name} command failed: {stderr}")]
+    CommandFailed {
+        /// Name of the tool that ran the command
+        tool_name: String,
+        /// Process exit code (None if the process never ran or was killed)
+        exit_code: Option<i32>,
+        /// Captured standard error or failure reason
+        stderr: String,
+    },
+
+    /// Code run on behalf of the tool panicked.
+    #[error("{task_description} panicked: {message}")]
+    Panicked {
+        /// What was running when the panic occurred
+        task_description: String,
+        /// Panic message
+        message: String,
+    },
+}
+
+impl From<IoError> for ToolError {
+    fn from(err: IoError) -> Self {
+        Self::from_io_kind(err.kind(), err.to_string())
+    }
+}
+
+impl From<SerdeJsonError> for ToolError {
+    fn from(err: SerdeJsonError) -> Self {
+        Self::ExecutionFailed(format!("Serialization error: {err}"))
+    }
+}
+
+impl ToolError {
+    /// Creates an [`InvalidArguments`](Self::InvalidArguments) error about the parameter `field`
+    pub fn invalid_argument(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::InvalidArguments {
+            field: Some(field.into()),
+            message: message.into(),
+        }
+    }
+
+    /// Creates an [`InvalidArguments`](Self::InvalidArguments) error about the arguments as a whole
+    pub fn invalid_arguments(message: impl Into<String>) -> Self {
+        Self::InvalidArguments {
+            field: None,
+            message: message.into(),
+        }
+    }
+
+    /// Creates the error for a failed I/O operation, prefixing its message with `context`
+    ///
+    /// Missing files become [`NotFound`](Self::NotFound), denied access
+    /// [`PermissionDenied`](Self::PermissionDenied) and existing files
+    /// [`Conflict`](Self::Conflict); everything else is [`Io`](Self::Io).
+    pub fn io(context: impl Display, err: &IoError) -> Self {
+        Self::from_io_kind(err.kind(), format!("{context}: {err}"))
+    }
+
+    /// Creates the error for arguments that could not be deserialized
+    ///
+    /// Names the field when serde reports a missing or unknown one.
+    #[must_use]
+    pub fn from_arguments(err: &SerdeJsonError) -> Self {
+        let message = err.to_string();
+        let field = ["missing field `", "unknown field `"]
+            .iter()
+            .find_map(|prefix| message.strip_prefix(prefix))
+            .and_then(|rest| rest.split_once('`'))
+            .map(|(field, _)| field.to_owned());
+        Self::InvalidArguments { field, message }
+    }
+
+    /// Variant matching an I/O error kind
+    fn from_io_kind(kind: ErrorKind, message: String) -> Self {
+        match kind {
+            ErrorKind::NotFound => Self::NotFound(message),
+            ErrorKind::PermissionDenied => Self::PermissionDenied(message),
+            ErrorKind::AlreadyExists => Self::Conflict(message),
+            _ => Self::Io(message),
+        }
+    }
+
+    /// Stable machine-readable code of the error kind
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::NotFound(_) => "not_found",
+            Self::PermissionDenied(_) => "permission_denied",
+            Self::InvalidArguments { .. } | Self::InvalidInput { .. } => "invalid_arguments",
+            Self::Conflict(_) => "conflict",
+            Self::Timeout { .. } => "timeout",
+            Self::SandboxViolation(_) => "sandbox_violation",
+            Self::Io(_) => "io",
+            Self::ExecutionFailed(_) => "execution_failed",
+            Self::CommandFailed { .. } => "command_failed",
+            Self::Panicked { .. } => "panicked",
+        }
+    }
+
+    /// Parameter an [`InvalidArguments`](Self::InvalidArguments) or
+    /// [`InvalidInput`](Self::InvalidInput) error is about
+    #[must_use]
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            Self::InvalidArguments { field, .. } => field.as_deref(),
+            Self::InvalidInput { field_name, .. } => Some(field_name),
+            _ => None,
+        }
+    }
+
+    /// Get the user-facing error message
+    ///
+    /// Returns the full error message without truncation
+    #[must_use]
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::NotFound(msg)
+            | Self::PermissionDenied(msg)
+            | Self::InvalidArguments { message: msg, .. }
+            | Self::Conflict(msg)
+            | Self::SandboxViolation(msg)
+            | Self::Io(msg)
+            | Self::ExecutionFailed(msg) => msg.clone(),
+            Self::CommandFailed { stderr, .. } => stderr.clone(),
+            Self::InvalidInput { .. } | Self::Timeout { .. } | Self::Panicked { .. } => {
+                self.to_string()
+            }
+        }
+    }
+
+    /// JSON object describing the error to agent code
+    ///
+    /// Always has `code` and `message`, plus `field` for invalid arguments
+    /// (with the `tool`, `expected` type and `actual` value when they broke the
+    /// schema) and the details of timeouts, failed commands and panics.
+    #[must_use]
+    pub fn to_data(&self) -> Value {
+        let mut data = json!({
+            "code": self.code(),
+            "message": self.user_message(),
+        });
+        match self {
+            Self::InvalidArguments {
+                field: Some(field), ..
+            } => data["field"] = json!(field),
+            Self::InvalidInput {
+                tool_name,
+                field_name,
+                expected_type,
+                actual_value,
+            } => {
+                data["field"] = json!(field_name);
+                data["tool"] = json!(tool_name);
+                data["expected"] = json!(expected_type);
+                data["actual"] = json!(actual_value);
+            }
+            Self::Timeout { operation, seconds } => {
+                data["operation"] = json!(operation);
+                data["seconds"] = json!(seconds);
+            }
+            Self::CommandFailed {
+                tool_name,
+                exit_code,
+                ..
+            } => {
+                data["tool"] = json!(tool_name);
+                data["exit_code"] = json!(exit_code);
+            }
+            Self::Panicked {
+                task_description,
+                message,
+            } => {
+                data["task"] = json!(task_description);
+                data["message"] = json!(message);
+            }
+            _ => {}
+        }
+        data
+    }
+
+    /// Rebuilds an error from the object produced by [`to_data`](Self::to_data)
+    ///
+    /// Returns None if `data` has no known `code`.
+    #[must_use]
+    pub fn from_data(data: &Map<String, Value>) -> Option<Self> {
+        let text = |key: &str| data.get(key).and_then(Value::as_str).map(str::to_owned);
+        let message = text("message").unwrap_or_default();
+        Some(match data.get("code").and_then(Value::as_str)? {
+            "not_found" => Self::NotFound(message),
+            "permission_denied" => Self::PermissionDenied(message),
+            "invalid_arguments" if data.contains_key("expected") => Self::InvalidInput {
+                tool_name: text("tool").unwrap_or_default(),
+                field_name: text("field").unwrap_or_default(),
+                expected_type: text("expected").unwrap_or_default(),
+                actual_value: text("actual").unwrap_or_default(),
+            },
+            "invalid_arguments" => Self::InvalidArguments {
+                field: text("field"),
+                message,
+            },
+            "conflict" => Self::Conflict(message),
+            "timeout" => Self::Timeout {
+                operation: text("operation").unwrap_or(message),
+                seconds: data
+                    .get("seconds")
+                    .and_then(Value::as_u64)
+                    .unwrap_or_default(),
+            },
+            "sandbox_violation" => Self::SandboxViolation(message),
+            "io" => Self::Io(message),
+            "execution_failed" => Self::ExecutionFailed(message),
+            "command_failed" => Self::CommandFailed {
+                tool_name: text("tool").unwrap_or_default(),
+                exit_code: data
+                    .get("exit_code")
+                    .and_then(Value::as_i64)
+                    .and_then(|code| i32::try_from(code).ok()),
+                stderr: message,
+            },
+            "panicked" => Self::Panicked {
+                task_description: text("task").unwrap_or_default(),
+                message,
+            },
+            _ => return None,
+        })
+    }
+}
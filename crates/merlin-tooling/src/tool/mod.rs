@@ -0,0 +1,133 @@
+mod error;
+#[cfg(test)]
+mod tests;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub use error::ToolError;
+
+/// Result type for tool operations.
+pub type ToolResult<T> = Result<T, ToolError>;
+
+/// Input parameters provided to a tool for execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolInput {
+    /// JSON value containing the tool-specific parameters.
+    pub params: Value,
+}
+
+/// Output returned by a tool after execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolOutput {
+    /// Whether the tool execution succeeded.
+    pub success: bool,
+    /// Human-readable message describing the result.
+    pub message: String,
+    /// Optional JSON data containing tool-specific output.
+    pub data: Option<Value>,
+    /// Code of the [`ToolError`] the output reports, if it reports one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl ToolOutput {
+    /// Creates a successful output with the given message and no data.
+    pub fn success<T: Into<String>>(message: T) -> Self {
+        Self {
+            success: true,
+            message: message.into(),
+            data: None,
+            code: None,
+        }
+    }
+
+    /// Creates a successful output with the given message and associated data.
+    pub fn success_with_data<T: Into<String>>(message: T, data: Value) -> Self {
+        Self {
+            success: true,
+            message: message.into(),
+            data: Some(data),
+            code: None,
+        }
+    }
+
+    /// Creates an error output with the given message.
+    pub fn error<T: Into<String>>(message: T) -> Self {
+        Self {
+            success: false,
+            message: message.into(),
+            data: None,
+            code: None,
+        }
+    }
+
+    /// Creates an error output reporting `error`, with its [`ToolError::to_data`] as data.
+    #[must_use]
+    pub fn from_error(error: &ToolError) -> Self {
+        Self {
+            success: false,
+            message: error.to_string(),
+            data: Some(error.to_data()),
+            code: Some(error.code().to_owned()),
+        }
+    }
+}
+
+/// Trait for implementing executable tools that can be invoked by the system.
+#[async_trait]
+pub trait Tool: Send + Sync {
+    /// Returns the unique identifier for this tool.
+    fn name(&self) -> &str;
+
+    /// Returns the TypeScript function signature for this tool.
+    ///
+    /// This signature is used by the TypeScript runtime to provide proper type information.
+    /// The signature should include:
+    /// - `JSDoc` comment with the tool description
+    /// - `declare function` with proper parameter and return types
+    ///
+    /// # Examples
+    ///
+    /// ```text
+    /// /**
+    ///  * Reads a file from the filesystem
+    ///  */
+    /// declare function readFile(path: string): Promise<string>;
+    /// ```
+    fn typescript_signature(&self) -> &str;
+
+    /// JSON Schema of the parameters this tool accepts.
+    ///
+    /// The [`ToolRegistry`](crate::ToolRegistry) checks every call against it
+    /// before executing, so agent code gets a [`ToolError::InvalidInput`]
+    /// naming the offending parameter. `Value::Null` (the default) skips the check.
+    fn schema(&self) -> Value {
+        Value::Null
+    }
+
+    /// Whether calls with the same input return the same output until a file changes.
+    ///
+    /// The [`ToolRegistry`](crate::ToolRegistry) caches the results of such
+    /// tools, so only read-only tools whose output depends on nothing but their
+    /// input and the file or directory at their `path` parameter should opt in.
+    fn is_cacheable(&self) -> bool {
+        false
+    }
+
+    /// Whether calls wait on someone outside the agent, such as the user.
+    ///
+    /// The persistent runtime awaits such calls without blocking its thread,
+    /// so the UI sharing that thread keeps running while the tool waits.
+    fn is_interactive(&self) -> bool {
+        false
+    }
+
+    /// Executes the tool with the provided input parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ToolError` if the input is invalid or execution fails.
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput>;
+}
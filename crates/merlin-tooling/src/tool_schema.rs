@@ -0,0 +1,481 @@
+//! Validation of tool inputs against the schemas of the tools.
+//!
+//! Agent code regularly calls tools with a missing parameter or one of the
+//! wrong type, which the tools would only report through their own parsing.
+//! Every tool handed out by the registry checks its input against
+//! [`Tool::schema`] first and fails with [`ToolError::InvalidInput`], naming
+//! the parameter, the type it needs and the value it got.
+//!
+//! Only the keywords the tool schemas use are understood: `type` (one name or
+//! a list), `properties`, `required`, `enum`, `items` and `anyOf`. Inputs that
+//! are not objects pass unchecked, since tools accept their first parameter on
+//! its own (`readFile("notes.md")`).
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+use super::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+
+/// Longest rendering of a rejected value kept in the error
+const MAX_ACTUAL_VALUE_CHARS: usize = 80;
+
+/// Rendering of a required parameter that was not passed
+const MISSING_VALUE: &str = "undefined";
+
+/// Where a value broke its schema
+struct Mismatch {
+    /// Path of the value within the input
+    field: String,
+    /// What the schema expects there
+    expected: String,
+    /// The value found, as JSON
+    actual: String,
+}
+
+/// Schemas of the registered tools, by tool name
+#[derive(Debug, Clone, Default)]
+pub struct ToolSchemaValidator {
+    /// Schema of each tool that has one
+    schemas: HashMap<String, Value>,
+}
+
+impl ToolSchemaValidator {
+    /// Create a validator without any schemas
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the schema of `tool`, if it has one
+    pub fn register(&mut self, tool: &dyn Tool) {
+        let schema = tool.schema();
+        if !schema.is_null() {
+            self.schemas.insert(tool.name().to_owned(), schema);
+        }
+    }
+
+    /// Check the input of a call of `tool_name` against its schema
+    ///
+    /// Tools without a schema and inputs that are not objects always pass.
+    ///
+    /// # Errors
+    /// Returns [`ToolError::InvalidInput`] for the first parameter that does not match
+    pub fn validate(&self, tool_name: &str, input: &ToolInput) -> ToolResult<()> {
+        let Some(schema) = self.schemas.get(tool_name) else {
+            return Ok(());
+        };
+        if !input.params.is_object() {
+            return Ok(());
+        }
+        check(schema, &input.params, "").map_err(|mismatch| ToolError::InvalidInput {
+            tool_name: tool_name.to_owned(),
+            field_name: mismatch.field,
+            expected_type: mismatch.expected,
+            actual_value: mismatch.actual,
+        })
+    }
+}
+
+/// Checks `value` at `path` against `schema`
+///
+/// # Errors
+/// Returns where the value or one of its parts first breaks the schema
+fn check(schema: &Value, value: &Value, path: &str) -> Result<(), Mismatch> {
+    let mismatch = || Mismatch {
+        field: path.to_owned(),
+        expected: describe(schema),
+        actual: render(value),
+    };
+
+    if let Some(variants) = schema.get("anyOf").and_then(Value::as_array) {
+        let mut failures = variants.iter().map(|variant| check(variant, value, path));
+        if let Some(Err(first)) = failures.next()
+            && failures.all(|failure| failure.is_err())
+        {
+            // Point at the first variant's problem, unless it is the value itself
+            return Err(if first.field == path {
+                mismatch()
+            } else {
+                first
+            });
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array)
+        && !allowed.contains(value)
+    {
+        return Err(mismatch());
+    }
+    if !schema_types(schema).is_empty()
+        && !schema_types(schema)
+            .iter()
+            .any(|name| has_type(value, name))
+    {
+        return Err(mismatch());
+    }
+
+    match value {
+        Value::Object(object) => check_properties(schema, object, path),
+        Value::Array(items) => {
+            let Some(item_schema) = schema.get("items") else {
+                return Ok(());
+            };
+            items
+                .iter()
+                .enumerate()
+                .try_for_each(|(index, item)| check(item_schema, item, &format!("{path}[{index}]")))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks the required and described properties of an object
+///
+/// # Errors
+/// Returns the first missing required property or property breaking its schema
+fn check_properties(
+    schema: &Value,
+    object: &Map<String, Value>,
+    path: &str,
+) -> Result<(), Mismatch> {
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for name in required {
+        if !object.contains_key(name) {
+            return Err(Mismatch {
+                field: join(path, name),
+                expected: properties
+                    .and_then(|properties| properties.get(name))
+                    .map_or_else(|| "a value".to_owned(), describe),
+                actual: MISSING_VALUE.to_owned(),
+            });
+        }
+    }
+
+    let Some(properties) = properties else {
+        return Ok(());
+    };
+    object.iter().try_for_each(|(name, value)| {
+        properties
+            .get(name)
+            .map_or(Ok(()), |property| check(property, value, &join(path, name)))
+    })
+}
+
+/// Names of the types a schema allows (empty if it allows any)
+fn schema_types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether `value` is of the JSON Schema type `name`
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Describes what a schema expects, for error messages
+fn describe(schema: &Value) -> String {
+    if let Some(variants) = schema.get("anyOf").and_then(Value::as_array) {
+        return variants
+            .iter()
+            .map(describe)
+            .collect::<Vec<_>>()
+            .join(" or ");
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        return format!("one of {}", allowed.join(", "));
+    }
+    let types = schema_types(schema);
+    if types.is_empty() {
+        return "any value".to_owned();
+    }
+    types
+        .iter()
+        .map(|name| match (*name, schema.get("items")) {
+            ("array", Some(items)) => format!("array of {}", describe(items)),
+            _ => (*name).to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// Renders a value as JSON, shortened to [`MAX_ACTUAL_VALUE_CHARS`]
+fn render(value: &Value) -> String {
+    let json = value.to_string();
+    if json.chars().count() <= MAX_ACTUAL_VALUE_CHARS {
+        return json;
+    }
+    let mut shortened: String = json.chars().take(MAX_ACTUAL_VALUE_CHARS).collect();
+    shortened.push_str("...");
+    shortened
+}
+
+/// Path of the property `name` of the value at `path`
+fn join(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{path}.{name}")
+    }
+}
+
+/// Tool checking its input against a [`ToolSchemaValidator`] before running
+pub struct ValidatedTool {
+    /// Wrapped tool
+    inner: Arc<dyn Tool>,
+    /// Validator holding the schema of the wrapped tool
+    validator: Arc<ToolSchemaValidator>,
+}
+
+impl ValidatedTool {
+    /// Wrap `inner` so its inputs are checked by `validator`
+    pub fn new(inner: Arc<dyn Tool>, validator: Arc<ToolSchemaValidator>) -> Self {
+        Self { inner, validator }
+    }
+}
+
+#[async_trait]
+impl Tool for ValidatedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn typescript_signature(&self) -> &str {
+        self.inner.typescript_signature()
+    }
+
+    fn schema(&self) -> Value {
+        self.inner.schema()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.inner.is_cacheable()
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner.is_interactive()
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        self.validator.validate(self.inner.name(), &input)?;
+        self.inner.execute(input).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        BashTool, ContextRequestTool, DeleteFileTool, DiffTool, EditFileTool, FindFilesTool,
+        GitTool, JqTool, ListFilesTool, ReadFileTool, ToolRegistry, WriteFileTool,
+    };
+    use anyhow::{Result, anyhow};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Tool and parameters of a call
+    type ToolCall = (Arc<dyn Tool>, Value);
+
+    /// Validator knowing the schemas of the file and search tools
+    fn validator() -> ToolSchemaValidator {
+        let mut validator = ToolSchemaValidator::new();
+        validator.register(&ReadFileTool::new("."));
+        validator.register(&FindFilesTool::new("."));
+        validator.register(&GitTool::new("."));
+        validator.register(&ContextRequestTool::new(".".into()));
+        validator
+    }
+
+    /// Validates `params` as the input of `tool_name`
+    ///
+    /// # Errors
+    /// Returns the validation error
+    fn validate(tool_name: &str, params: Value) -> ToolResult<()> {
+        validator().validate(tool_name, &ToolInput { params })
+    }
+
+    /// Tests that missing and mistyped parameters are named with the expected type.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_invalid_inputs_name_the_field() {
+        let invalid = |field: &str, expected: &str, actual: &str| {
+            Err(ToolError::InvalidInput {
+                tool_name: "findFiles".to_owned(),
+                field_name: field.to_owned(),
+                expected_type: expected.to_owned(),
+                actual_value: actual.to_owned(),
+            })
+        };
+
+        assert_eq!(
+            validate("findFiles", json!({ "max_results": 5 })),
+            invalid("pattern", "string", "undefined")
+        );
+        assert_eq!(
+            validate(
+                "findFiles",
+                json!({ "pattern": "*.rs", "max_results": "ten" })
+            ),
+            invalid("max_results", "integer or null", "\"ten\"")
+        );
+        assert_eq!(
+            validate(
+                "findFiles",
+                json!({ "pattern": "*.rs", "max_results": 2.5 })
+            ),
+            invalid("max_results", "integer or null", "2.5")
+        );
+        assert_eq!(
+            validate(
+                "findFiles",
+                json!({ "pattern": "*.rs", "modified_after": null, "extra": 1 })
+            ),
+            Ok(())
+        );
+    }
+
+    /// Tests enums, arrays and alternatives.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_enums_arrays_and_alternatives() {
+        let field = |result: ToolResult<()>| Some(result.err()?.field()?.to_owned());
+
+        assert_eq!(validate("git", json!({ "command": "status" })), Ok(()));
+        let unknown_command = validate("git", json!({ "command": "rebase" }));
+        assert!(matches!(
+            &unknown_command,
+            Err(ToolError::InvalidInput { expected_type, .. }) if expected_type.starts_with("one of \"status\"")
+        ));
+        assert_eq!(
+            field(validate(
+                "git",
+                json!({ "command": "add", "paths": ["a.rs", 3] })
+            )),
+            Some("paths[1]".to_owned())
+        );
+
+        assert_eq!(
+            validate(
+                "requestContext",
+                json!({ "symbol": "Parser::parse", "reason": "" })
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            field(validate("requestContext", json!({ "reason": "Testing" }))),
+            Some("pattern".to_owned())
+        );
+
+        // Bare first arguments and tools without a schema are not checked
+        assert_eq!(validate("readFile", json!("notes.md")), Ok(()));
+        assert_eq!(validate("unknownTool", json!({ "anything": true })), Ok(()));
+    }
+
+    /// Tests that every built-in tool has a schema that accepts a well-formed call.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[test]
+    fn test_builtin_tools_have_schemas() {
+        let calls: Vec<ToolCall> = vec![
+            (Arc::new(BashTool::default()), json!({ "command": "ls" })),
+            (
+                Arc::new(ReadFileTool::new(".")),
+                json!({ "path": "a", "start_line": 1 }),
+            ),
+            (
+                Arc::new(WriteFileTool::new(".")),
+                json!({ "path": "a", "content": "" }),
+            ),
+            (Arc::new(ListFilesTool::new(".")), json!({})),
+            (Arc::new(DeleteFileTool::new(".")), json!({ "path": "a" })),
+            (
+                Arc::new(EditFileTool::new(".")),
+                json!({ "path": "a", "old_string": "x", "new_string": "y", "replace_all": false }),
+            ),
+            (
+                Arc::new(FindFilesTool::new(".")),
+                json!({ "pattern": "*.rs" }),
+            ),
+            (
+                Arc::new(DiffTool::new(".")),
+                json!({ "file_path": "a", "context_lines": 3 }),
+            ),
+            (
+                Arc::new(JqTool::new(".")),
+                json!({ "json_string": "{}", "jq_expression": "." }),
+            ),
+            (
+                Arc::new(GitTool::new(".")),
+                json!({ "command": "commit", "message": "Fix" }),
+            ),
+            (
+                Arc::new(ContextRequestTool::new(".".into())),
+                json!({ "pattern": "src/*.rs", "reason": "", "max_files": 5 }),
+            ),
+        ];
+        for (tool, params) in calls {
+            assert!(tool.schema().is_object(), "{} has no schema", tool.name());
+            let mut validator = ToolSchemaValidator::new();
+            validator.register(tool.as_ref());
+            assert_eq!(
+                validator.validate(tool.name(), &ToolInput { params }),
+                Ok(())
+            );
+        }
+    }
+
+    /// Tests that registry tools reject invalid input before running.
+    ///
+    /// # Errors
+    /// Returns an error if the tool is not registered.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_registry_tools_validate_input() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let registry = ToolRegistry::with_workspace(temp_dir.path())
+            .with_tool(Arc::new(WriteFileTool::new(temp_dir.path())));
+        let write = registry
+            .get_tool("writeFile")
+            .ok_or_else(|| anyhow!("writeFile not registered"))?;
+
+        let result = write
+            .execute(ToolInput {
+                params: json!({ "path": "notes.md", "content": 42 }),
+            })
+            .await;
+        assert!(matches!(
+            &result,
+            Err(ToolError::InvalidInput { field_name, actual_value, .. })
+                if field_name == "content" && actual_value == "42"
+        ));
+        assert!(!temp_dir.path().join("notes.md").exists());
+        Ok(())
+    }
+}
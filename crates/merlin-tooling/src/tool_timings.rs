@@ -0,0 +1,131 @@
+//! Time spent in tools.
+//!
+//! Every tool handed out by the registry adds the duration of its calls to a
+//! shared tracker, so the agent can tell how much of a step went to tools
+//! rather than the model.
+
+use std::collections::BTreeMap;
+use std::mem;
+use std::sync::Arc;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use super::{Tool, ToolInput, ToolOutput, ToolResult};
+
+/// Summed call durations in milliseconds, by tool name
+type Durations = BTreeMap<String, u64>;
+
+/// Milliseconds spent in each tool since the tracker was last drained
+#[derive(Debug, Default, Clone)]
+pub struct ToolTimings {
+    /// Summed call durations by tool name
+    durations: Arc<Mutex<Durations>>,
+}
+
+impl ToolTimings {
+    /// Create a new, empty tracker
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a call of `tool` that took `duration_ms`
+    pub async fn record(&self, tool: &str, duration_ms: u64) {
+        *self
+            .durations
+            .lock()
+            .await
+            .entry(tool.to_owned())
+            .or_default() += duration_ms;
+    }
+
+    /// Take the durations recorded so far, leaving the tracker empty
+    pub async fn take(&self) -> Durations {
+        mem::take(&mut *self.durations.lock().await)
+    }
+}
+
+/// Tool adding the duration of its calls to a [`ToolTimings`] tracker
+pub struct TimedTool {
+    /// Wrapped tool
+    inner: Arc<dyn Tool>,
+    /// Tracker the durations go to
+    timings: ToolTimings,
+}
+
+impl TimedTool {
+    /// Wrap `inner` so its calls are timed into `timings`
+    pub fn new(inner: Arc<dyn Tool>, timings: ToolTimings) -> Self {
+        Self { inner, timings }
+    }
+}
+
+#[async_trait]
+impl Tool for TimedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn typescript_signature(&self) -> &str {
+        self.inner.typescript_signature()
+    }
+
+    fn schema(&self) -> Value {
+        self.inner.schema()
+    }
+
+    fn is_cacheable(&self) -> bool {
+        self.inner.is_cacheable()
+    }
+
+    fn is_interactive(&self) -> bool {
+        self.inner.is_interactive()
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let start = Instant::now();
+        let result = self.inner.execute(input).await;
+        let duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.timings.record(self.inner.name(), duration_ms).await;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ToolRegistry, WriteFileTool};
+    use anyhow::{Result, anyhow};
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    /// Tests that calls of registry tools are timed per tool until drained.
+    ///
+    /// # Errors
+    /// Returns an error if the tool fails.
+    ///
+    /// # Panics
+    /// Panics if assertions fail during test execution.
+    #[tokio::test]
+    async fn test_registry_tools_are_timed() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let registry = ToolRegistry::with_workspace(temp_dir.path())
+            .with_tool(Arc::new(WriteFileTool::new(temp_dir.path())));
+        let write = registry
+            .get_tool("writeFile")
+            .ok_or_else(|| anyhow!("writeFile not registered"))?;
+
+        for path in ["a.txt", "b.txt"] {
+            let params = json!({ "path": path, "content": "x" });
+            write.execute(ToolInput { params }).await?;
+        }
+
+        let timings = registry.tool_timings().take().await;
+        assert_eq!(timings.keys().collect::<Vec<_>>(), vec!["writeFile"]);
+        assert!(registry.tool_timings().take().await.is_empty());
+        Ok(())
+    }
+}
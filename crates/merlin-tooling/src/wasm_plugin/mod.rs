@@ -0,0 +1,426 @@
+//! Tools loaded from WASM modules at runtime.
+//!
+//! # Plugin interface
+//!
+//! A plugin exports its `memory` and the functions below. Strings are UTF-8
+//! in the plugin's memory, returned as an `i64` holding the pointer in its
+//! high 32 bits and the length in its low 32 bits.
+//!
+//! - `alloc(len: i32) -> i32` reserves `len` bytes the input is written to
+//! - `name() -> i64` is the name agent code calls the tool by
+//! - `description() -> i64` documents the tool in its TypeScript signature
+//! - `execute(ptr: i32, len: i32) -> i64` runs the tool on its JSON input and returns its JSON output
+//!
+//! Plugins are linked against WASI preview 1, so modules built for
+//! `wasm32-wasip1` work, but they see no files, network or environment
+//! variables unless their [`WasmSandbox`] grants them. Every call runs in a
+//! fresh instance, so no state survives between calls, and gets a fuel budget
+//! of WebAssembly instructions and a cap on its memory; a plugin that spins
+//! forever runs out of fuel and fails instead of holding its thread.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde_json::{Value, from_str};
+use tokio::task::spawn_blocking;
+use wasmtime::{
+    Config, Engine, Error as WasmError, Instance, InstancePre, Linker, Memory, Module, Store,
+    StoreLimits, StoreLimitsBuilder, Trap,
+};
+use wasmtime_wasi::p1::{WasiP1Ctx, add_to_linker_sync};
+use wasmtime_wasi::{DirPerms, FilePerms, WasiCtxBuilder};
+
+use crate::panic_guard::join_error;
+use crate::signatures::signature_from_description;
+use crate::tool::{Tool, ToolError, ToolInput, ToolOutput, ToolResult};
+
+/// WebAssembly instructions a plugin call may run by default
+pub const DEFAULT_PLUGIN_FUEL: u64 = 1_000_000_000;
+
+/// Bytes of linear memory a plugin instance may grow to by default
+pub const DEFAULT_PLUGIN_MEMORY: usize = 64 * 1024 * 1024;
+
+/// Directory of the host a plugin may access
+#[derive(Debug, Clone)]
+struct PreopenedDir {
+    /// Directory on the host
+    host: PathBuf,
+    /// Path the plugin sees the directory at
+    guest: String,
+    /// Whether the plugin may change the directory's contents
+    writable: bool,
+}
+
+/// Capabilities and limits granted to a WASM plugin
+///
+/// The default grants nothing: no files, no network and no environment
+/// variables, with [`DEFAULT_PLUGIN_FUEL`] per call and [`DEFAULT_PLUGIN_MEMORY`].
+#[derive(Debug, Clone)]
+pub struct WasmSandbox {
+    /// Directories the plugin may access
+    dirs: Vec<PreopenedDir>,
+    /// Whether the plugin may use the host's network
+    network: bool,
+    /// Environment variables the plugin sees
+    env: Vec<(String, String)>,
+    /// Instructions each call may run
+    fuel: u64,
+    /// Bytes of memory each instance may grow to
+    memory_limit: usize,
+}
+
+impl Default for WasmSandbox {
+    fn default() -> Self {
+        Self {
+            dirs: Vec::new(),
+            network: false,
+            env: Vec::new(),
+            fuel: DEFAULT_PLUGIN_FUEL,
+            memory_limit: DEFAULT_PLUGIN_MEMORY,
+        }
+    }
+}
+
+impl WasmSandbox {
+    /// Let the plugin access the host directory `host` at `guest`, read-only unless `writable`
+    #[must_use]
+    pub fn with_dir(
+        mut self,
+        host: impl Into<PathBuf>,
+        guest: impl Into<String>,
+        writable: bool,
+    ) -> Self {
+        self.dirs.push(PreopenedDir {
+            host: host.into(),
+            guest: guest.into(),
+            writable,
+        });
+        self
+    }
+
+    /// Let the plugin connect to any address and resolve host names
+    #[must_use]
+    pub const fn with_network(mut self) -> Self {
+        self.network = true;
+        self
+    }
+
+    /// Set the environment variable `key` the plugin sees
+    #[must_use]
+    pub fn with_env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    /// Let each call run up to `fuel` WebAssembly instructions
+    #[must_use]
+    pub const fn with_fuel(mut self, fuel: u64) -> Self {
+        self.fuel = fuel;
+        self
+    }
+
+    /// Let each instance grow its memory to `bytes` bytes
+    #[must_use]
+    pub const fn with_memory_limit(mut self, bytes: usize) -> Self {
+        self.memory_limit = bytes;
+        self
+    }
+
+    /// Builds the store state granting exactly these capabilities and limits
+    ///
+    /// # Errors
+    /// Returns error if a granted directory cannot be opened
+    fn plugin_state(&self) -> ToolResult<PluginState> {
+        Ok(PluginState {
+            wasi: self.wasi_context()?,
+            limits: StoreLimitsBuilder::new()
+                .memory_size(self.memory_limit)
+                .build(),
+        })
+    }
+
+    /// Builds the WASI context granting exactly these capabilities
+    ///
+    /// # Errors
+    /// Returns error if a granted directory cannot be opened
+    fn wasi_context(&self) -> ToolResult<WasiP1Ctx> {
+        let mut builder = WasiCtxBuilder::new();
+        for dir in &self.dirs {
+            let (dir_perms, file_perms) = if dir.writable {
+                (DirPerms::all(), FilePerms::all())
+            } else {
+                (DirPerms::READ, FilePerms::READ)
+            };
+            builder
+                .preopened_dir(&dir.host, &dir.guest, dir_perms, file_perms)
+                .map_err(|err| {
+                    ToolError::NotFound(format!("Plugin directory {}: {err}", dir.host.display()))
+                })?;
+        }
+        for (key, value) in &self.env {
+            builder.env(key, value);
+        }
+        if self.network {
+            builder.inherit_network().allow_ip_name_lookup(true);
+        }
+        Ok(builder.build_p1())
+    }
+}
+
+/// State of a plugin instance's store
+struct PluginState {
+    /// Capabilities of the instance
+    wasi: WasiP1Ctx,
+    /// Memory and table limits of the instance
+    limits: StoreLimits,
+}
+
+/// Tool implemented by a WASM plugin
+///
+/// See the [module documentation](self) for the functions the plugin exports.
+pub struct WasmTool {
+    /// Name reported by the plugin
+    name: String,
+    /// TypeScript signature generated from the plugin's description
+    signature: String,
+    /// Compiled and linked plugin, instantiated once per call
+    plugin: InstancePre<PluginState>,
+    /// Capabilities each instance is granted
+    sandbox: WasmSandbox,
+}
+
+impl WasmTool {
+    /// Compiles the plugin at `path` and reads its name and description
+    ///
+    /// # Errors
+    /// Returns error if the module cannot be read or compiled, does not
+    /// export the plugin interface, or its name or description is invalid
+    pub fn load(path: &Path, sandbox: WasmSandbox) -> ToolResult<Self> {
+        let engine = Engine::new(Config::new().consume_fuel(true)).map_err(|err| {
+            ToolError::ExecutionFailed(format!("Failed to create WASM engine: {err}"))
+        })?;
+        let module = Module::from_file(&engine, path).map_err(|err| {
+            ToolError::ExecutionFailed(format!(
+                "Failed to load WASM plugin {}: {err}",
+                path.display()
+            ))
+        })?;
+        let mut linker = Linker::new(&engine);
+        let plugin = add_to_linker_sync(&mut linker, |state: &mut PluginState| &mut state.wasi)
+            .and_then(|()| linker.instantiate_pre(&module))
+            .map_err(|err| {
+                ToolError::ExecutionFailed(format!(
+                    "Failed to link WASM plugin {}: {err}",
+                    path.display()
+                ))
+            })?;
+        let source = path.display().to_string();
+        let mut call = PluginCall::start(&plugin, &sandbox, &source)?;
+        let name = call.read_export("name")?;
+        let description = call.read_export("description")?;
+        drop(call);
+        Self::new(plugin, sandbox, name, &description)
+    }
+
+    /// Creates the tool once the plugin's name and description are known
+    ///
+    /// # Errors
+    /// Returns error if the name is not a valid JavaScript identifier
+    fn new(
+        plugin: InstancePre<PluginState>,
+        sandbox: WasmSandbox,
+        name: String,
+        description: &str,
+    ) -> ToolResult<Self> {
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+            && name
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '_');
+        if !valid_name {
+            return Err(ToolError::invalid_argument(
+                "name",
+                format!("WASM plugin name '{name}' is not a valid function name"),
+            ));
+        }
+        let signature = signature_from_description(&name, description);
+        Ok(Self {
+            name,
+            signature,
+            plugin,
+            sandbox,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for WasmTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn typescript_signature(&self) -> &str {
+        &self.signature
+    }
+
+    async fn execute(&self, input: ToolInput) -> ToolResult<ToolOutput> {
+        let plugin = self.plugin.clone();
+        let sandbox = self.sandbox.clone();
+        let call_name = self.name.clone();
+        let output = spawn_blocking(move || {
+            PluginCall::start(&plugin, &sandbox, &call_name)?.execute(&input.params.to_string())
+        })
+        .await
+        .map_err(|err| join_error(&format!("WASM plugin '{}'", self.name), err))??;
+
+        let name = &self.name;
+
+        let data: Value = from_str(&output).map_err(|err| {
+            ToolError::ExecutionFailed(format!("WASM plugin '{name}' returned invalid JSON: {err}"))
+        })?;
+        Ok(ToolOutput::success_with_data(
+            format!("{name} finished"),
+            data,
+        ))
+    }
+}
+
+/// Fresh instance of a plugin serving one call
+struct PluginCall<'plugin> {
+    /// Store owning the instance, its WASI context and its limits
+    store: Store<PluginState>,
+    /// The plugin instance
+    instance: Instance,
+    /// Memory exported by the plugin
+    memory: Memory,
+    /// Plugin named in errors
+    plugin: &'plugin str,
+    /// Fuel the call started with, reported when it runs out
+    fuel: u64,
+}
+
+impl<'plugin> PluginCall<'plugin> {
+    /// Instantiates the plugin with the capabilities and limits of `sandbox`
+    ///
+    /// # Errors
+    /// Returns error if instantiation fails or the plugin exports no memory
+    fn start(
+        plugin: &InstancePre<PluginState>,
+        sandbox: &WasmSandbox,
+        name: &'plugin str,
+    ) -> ToolResult<Self> {
+        let mut store = Store::new(plugin.module().engine(), sandbox.plugin_state()?);
+        store.limiter(|state| &mut state.limits);
+        store
+            .set_fuel(sandbox.fuel)
+            .map_err(|err| plugin_error(name, "could not be given fuel", &err))?;
+        let instance = plugin
+            .instantiate(&mut store)
+            .map_err(|err| call_error(name, "failed to start", &err, sandbox.fuel))?;
+        // Reactor modules initialize their runtime before any export is called
+        if let Ok(initialize) = instance.get_typed_func::<(), ()>(&mut store, "_initialize") {
+            initialize
+                .call(&mut store, ())
+                .map_err(|err| call_error(name, "failed to initialize", &err, sandbox.fuel))?;
+        }
+        let memory = instance.get_memory(&mut store, "memory").ok_or_else(|| {
+            ToolError::ExecutionFailed(format!("WASM plugin '{name}' exports no memory"))
+        })?;
+        Ok(Self {
+            store,
+            instance,
+            memory,
+            plugin: name,
+            fuel: sandbox.fuel,
+        })
+    }
+
+    /// Calls the string-returning export `export`
+    ///
+    /// # Errors
+    /// Returns error if the export is missing, traps or returns an invalid string
+    fn read_export(&mut self, export: &str) -> ToolResult<String> {
+        let packed = self
+            .instance
+            .get_typed_func::<(), i64>(&mut self.store, export)
+            .and_then(|func| func.call(&mut self.store, ()))
+            .map_err(|err| {
+                call_error(self.plugin, &format!("`{export}` failed"), &err, self.fuel)
+            })?;
+        self.read_string(packed)
+    }
+
+    /// Writes `input` to the plugin's memory and runs `execute` on it
+    ///
+    /// # Errors
+    /// Returns error if the input does not fit, the plugin traps or returns an invalid string
+    fn execute(mut self, input: &str) -> ToolResult<String> {
+        let len = i32::try_from(input.len())
+            .map_err(|_| ToolError::invalid_arguments("Input is too large for a WASM plugin"))?;
+        let ptr = self
+            .instance
+            .get_typed_func::<i32, i32>(&mut self.store, "alloc")
+            .and_then(|alloc| alloc.call(&mut self.store, len))
+            .map_err(|err| call_error(self.plugin, "`alloc` failed", &err, self.fuel))?;
+        let offset = usize::try_from(ptr).map_err(|_| {
+            ToolError::ExecutionFailed(format!("WASM plugin '{}' allocated at {ptr}", self.plugin))
+        })?;
+        self.memory
+            .write(&mut self.store, offset, input.as_bytes())
+            .map_err(|err| plugin_error(self.plugin, "allocated outside its memory", &err))?;
+        let packed = self
+            .instance
+            .get_typed_func::<(i32, i32), i64>(&mut self.store, "execute")
+            .and_then(|execute| execute.call(&mut self.store, (ptr, len)))
+            .map_err(|err| call_error(self.plugin, "`execute` failed", &err, self.fuel))?;
+        self.read_string(packed)
+    }
+
+    /// Reads the string a packed pointer and length refer to
+    ///
+    /// # Errors
+    /// Returns error if the string is outside the plugin's memory or not UTF-8
+    fn read_string(&self, packed: i64) -> ToolResult<String> {
+        let packed = u64::from_ne_bytes(packed.to_ne_bytes());
+        let start = usize::try_from(packed >> 32).unwrap_or(usize::MAX);
+        let len = usize::try_from(packed & u64::from(u32::MAX)).unwrap_or(usize::MAX);
+        let bytes = start
+            .checked_add(len)
+            .and_then(|end| self.memory.data(&self.store).get(start..end))
+            .ok_or_else(|| {
+                ToolError::ExecutionFailed(format!(
+                    "WASM plugin '{}' returned a string outside its memory",
+                    self.plugin
+                ))
+            })?;
+        String::from_utf8(bytes.to_vec()).map_err(|err| {
+            ToolError::ExecutionFailed(format!(
+                "WASM plugin '{}' returned invalid UTF-8: {err}",
+                self.plugin
+            ))
+        })
+    }
+}
+
+/// Converts an error raised by a plugin into a `ToolError`
+fn plugin_error(plugin: &str, what: &str, err: &impl Display) -> ToolError {
+    ToolError::ExecutionFailed(format!("WASM plugin '{plugin}' {what}: {err}"))
+}
+
+/// Converts an error raised while running plugin code into a `ToolError`
+///
+/// Names the budget when the call was stopped for running out of `fuel`.
+fn call_error(plugin: &str, what: &str, err: &WasmError, fuel: u64) -> ToolError {
+    if err.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+        return ToolError::ExecutionFailed(format!(
+            "WASM plugin '{plugin}' {what}: ran out of fuel after {fuel} instructions"
+        ));
+    }
+    plugin_error(plugin, what, err)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,198 @@
+//! Tests for WASM plugin tools
+
+use super::*;
+use crate::ToolRegistry;
+use anyhow::Result;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+/// Plugin echoing its input, whose `execute` reports whether it sees a preopened directory
+const PLUGIN: &str = r#"(module
+  (import "wasi_snapshot_preview1" "fd_prestat_get" (func $prestat (param i32 i32) (result i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "echo")
+  (data (i32.const 16) "Echoes its input.\nAnswers `true` for `{\"files\":true}` if it sees a directory.")
+  (data (i32.const 128) "true")
+  (data (i32.const 136) "false")
+  (global $next (mut i32) (i32.const 1024))
+  (func $pack (param $ptr i32) (param $len i32) (result i64)
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len))))
+  (func (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+  (func (export "name") (result i64) (call $pack (i32.const 0) (i32.const 4)))
+  (func (export "description") (result i64) (call $pack (i32.const 16) (i32.const 77)))
+  (func (export "execute") (param $ptr i32) (param $len i32) (result i64)
+    (if (i32.ne (local.get $len) (i32.const 14))
+      (then (return (call $pack (local.get $ptr) (local.get $len)))))
+    (if (i32.eqz (call $prestat (i32.const 3) (i32.const 512)))
+      (then (return (call $pack (i32.const 128) (i32.const 4)))))
+    (call $pack (i32.const 136) (i32.const 5))))"#;
+
+/// Plugin that spins forever on `"spin"` and otherwise reports whether it could grow its memory to 128 MiB
+const HOG_PLUGIN: &str = r#"(module
+  (memory (export "memory") 1)
+  (data (i32.const 0) "hog")
+  (data (i32.const 16) "Spins or grows its memory.")
+  (data (i32.const 64) "true")
+  (data (i32.const 72) "false")
+  (func $pack (param $ptr i32) (param $len i32) (result i64)
+    (i64.or
+      (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+      (i64.extend_i32_u (local.get $len))))
+  (func (export "alloc") (param $len i32) (result i32) (i32.const 1024))
+  (func (export "name") (result i64) (call $pack (i32.const 0) (i32.const 3)))
+  (func (export "description") (result i64) (call $pack (i32.const 16) (i32.const 26)))
+  (func (export "execute") (param $ptr i32) (param $len i32) (result i64)
+    (if (i32.eq (local.get $len) (i32.const 6))
+      (then (loop $forever (br $forever))))
+    (if (i32.eq (memory.grow (i32.const 2047)) (i32.const -1))
+      (then (return (call $pack (i32.const 72) (i32.const 5)))))
+    (call $pack (i32.const 64) (i32.const 4))))"#;
+
+/// Writes the plugin to a temporary directory
+///
+/// # Errors
+/// Returns an error if the plugin cannot be written.
+fn write_plugin() -> Result<(TempDir, PathBuf)> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("echo.wat");
+    fs::write(&path, PLUGIN)?;
+    Ok((dir, path))
+}
+
+/// Tests that a loaded plugin is named, documented and executed by its exports.
+///
+/// # Errors
+/// Returns an error if the plugin cannot be written, loaded or executed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_plugin_tool_runs_exports() -> Result<()> {
+    let (_dir, path) = write_plugin()?;
+    let mut registry = ToolRegistry::new();
+    registry.load_wasm_plugin(&path)?;
+
+    let tool = registry
+        .get_tool("echo")
+        .ok_or_else(|| anyhow::anyhow!("plugin tool not registered"))?;
+    assert!(
+        tool.typescript_signature()
+            .starts_with("/**\n * Echoes its input.\n * Answers")
+    );
+    let output = tool
+        .execute(ToolInput {
+            params: json!({ "words": ["one", "two"] }),
+        })
+        .await?;
+    assert_eq!(output.data, Some(json!({ "words": ["one", "two"] })));
+
+    let duplicate = registry.load_wasm_plugin(&path);
+    assert!(matches!(duplicate, Err(ToolError::Conflict(_))));
+    Ok(())
+}
+
+/// Tests that plugins only see the directories their sandbox grants.
+///
+/// # Errors
+/// Returns an error if the plugin cannot be written, loaded or executed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_sandbox_grants_directories() -> Result<()> {
+    let (dir, path) = write_plugin()?;
+    let probe = || ToolInput {
+        params: json!({ "files": true }),
+    };
+
+    let sandboxed = WasmTool::load(&path, WasmSandbox::default())?;
+    assert_eq!(sandboxed.execute(probe()).await?.data, Some(json!(false)));
+
+    let granted = WasmTool::load(
+        &path,
+        WasmSandbox::default().with_dir(dir.path(), "/data", false),
+    )?;
+    assert_eq!(granted.execute(probe()).await?.data, Some(json!(true)));
+    Ok(())
+}
+
+/// Tests that modules without the plugin exports fail to load.
+///
+/// # Errors
+/// Returns an error if the module cannot be written.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[test]
+fn test_missing_exports_fail_to_load() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("empty.wat");
+    fs::write(&path, "(module (memory (export \"memory\") 1))")?;
+
+    let loaded = WasmTool::load(&path, WasmSandbox::default());
+    assert!(
+        matches!(&loaded, Err(ToolError::ExecutionFailed(message)) if message.contains("`name` failed"))
+    );
+    Ok(())
+}
+
+/// Tests that a plugin spinning forever runs out of fuel instead of holding its thread.
+///
+/// # Errors
+/// Returns an error if the plugin cannot be written or loaded.
+///
+/// # Panics
+/// Panics if the call does not fail for running out of fuel.
+#[tokio::test]
+async fn test_spinning_plugin_runs_out_of_fuel() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("hog.wat");
+    fs::write(&path, HOG_PLUGIN)?;
+    let tool = WasmTool::load(&path, WasmSandbox::default().with_fuel(1_000_000))?;
+
+    let spun = tool
+        .execute(ToolInput {
+            params: json!("spin"),
+        })
+        .await;
+    assert!(
+        matches!(&spun, Err(ToolError::ExecutionFailed(message))
+            if message.contains("ran out of fuel after 1000000 instructions")),
+        "got {spun:?}"
+    );
+    Ok(())
+}
+
+/// Tests that plugins cannot grow their memory past the sandbox's limit.
+///
+/// # Errors
+/// Returns an error if the plugin cannot be written, loaded or executed.
+///
+/// # Panics
+/// Panics if assertions fail during test execution.
+#[tokio::test]
+async fn test_memory_growth_is_capped() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("hog.wat");
+    fs::write(&path, HOG_PLUGIN)?;
+    let grow = || ToolInput {
+        params: json!("grow!"),
+    };
+
+    let capped = WasmTool::load(&path, WasmSandbox::default())?;
+    assert_eq!(capped.execute(grow()).await?.data, Some(json!(false)));
+
+    let raised = WasmTool::load(
+        &path,
+        WasmSandbox::default().with_memory_limit(256 * 1024 * 1024),
+    )?;
+    assert_eq!(raised.execute(grow()).await?.data, Some(json!(true)));
+    Ok(())
+}
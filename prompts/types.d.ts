@@ -51,3 +51,33 @@ interface TaskListStep {
   result?: string;
   exit_command?: string | null;
 }
+
+// Error thrown by a failed tool call (see TOOL ERRORS in typescript_agent.md)
+type ToolErrorCode =
+  | "not_found"
+  | "permission_denied"
+  | "invalid_arguments"
+  | "conflict"
+  | "timeout"
+  | "sandbox_violation"
+  | "io"
+  | "execution_failed"
+  | "command_failed"
+  | "panicked";
+
+interface ToolError extends Error {
+  code: ToolErrorCode;
+  // Parameter at fault, for invalid_arguments
+  field?: string;
+  // Expected type and the value passed (as JSON), for invalid_arguments caused by a wrong type
+  expected?: string;
+  actual?: string;
+  // Timed out operation and its limit, for timeout
+  operation?: string;
+  seconds?: number;
+  // Failed tool and exit code, for command_failed
+  tool?: string;
+  exit_code?: number | null;
+  // What panicked, for panicked
+  task?: string;
+}